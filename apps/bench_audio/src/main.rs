@@ -0,0 +1,498 @@
+//! Dry audio pipeline benchmark
+//!
+//! Builds `--chains` copies of synthetic input → `--stages` DSP modules →
+//! output on a real `ModuleHost` and `PatchBay`, plays `--seconds` of a tone
+//! through them and reports per-module throughput and allocations, and
+//! end-to-end latency percentiles. No audio device is opened; the output
+//! only records when each frame arrives.
+//!
+//! Frames are paced in real time unless `--unpaced` is given, which sends
+//! them as fast as the graph takes them.
+
+mod alloc;
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use audio_dsp::{AudioDspProcessor, AudioDspState};
+use magnolia_core::adapters::{ProcessorAdapter, SinkAdapter, SourceAdapter};
+use magnolia_core::{
+    AudioView, DataType, ModuleHost, ModuleSchema, PatchBay, Port, PortDirection, Processor,
+    RoutedSignal, Signal, Sink, Source,
+};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+#[global_allocator]
+static ALLOCATOR: alloc::Counting = alloc::Counting;
+
+const USAGE: &str = "usage: bench_audio [--chains N] [--stages N] [--seconds S] \
+                     [--frame SAMPLES] [--sample-rate HZ] [--channels N] [--unpaced]";
+
+/// Module inbox size, as the daemon spawns them
+const INBOX: usize = 100;
+
+/// Stop waiting once nothing has been routed for this long
+const IDLE_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, PartialEq)]
+struct Config {
+    chains: usize,
+    stages: usize,
+    seconds: f64,
+    /// Samples per channel in each frame
+    frame: usize,
+    sample_rate: u32,
+    channels: u16,
+    paced: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            chains: 1,
+            stages: 4,
+            seconds: 10.0,
+            frame: 256,
+            sample_rate: 48_000,
+            channels: 2,
+            paced: true,
+        }
+    }
+}
+
+impl Config {
+    fn frames_per_chain(&self) -> u64 {
+        (self.seconds * self.sample_rate as f64 / self.frame as f64).ceil() as u64
+    }
+
+    fn frame_duration(&self) -> Duration {
+        Duration::from_secs_f64(self.frame as f64 / self.sample_rate as f64)
+    }
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Config> {
+    let mut config = Config::default();
+    while let Some(arg) = args.next() {
+        if arg == "--unpaced" {
+            config.paced = false;
+            continue;
+        }
+        let value = args
+            .next()
+            .with_context(|| format!("{} needs a value\n{}", arg, USAGE))?;
+        let invalid = || format!("invalid {} {:?}", arg, value);
+        match arg.as_str() {
+            "--chains" => config.chains = value.parse().with_context(invalid)?,
+            "--stages" => config.stages = value.parse().with_context(invalid)?,
+            "--seconds" => config.seconds = value.parse().with_context(invalid)?,
+            "--frame" => config.frame = value.parse().with_context(invalid)?,
+            "--sample-rate" => config.sample_rate = value.parse().with_context(invalid)?,
+            "--channels" => config.channels = value.parse().with_context(invalid)?,
+            _ => bail!("unknown argument {}\n{}", arg, USAGE),
+        }
+    }
+    if config.chains == 0
+        || config.frame == 0
+        || config.sample_rate == 0
+        || config.channels == 0
+        || config.seconds <= 0.0
+    {
+        bail!("chains, frame, sample rate, channels and seconds must be positive");
+    }
+    Ok(config)
+}
+
+/// What one module did during the run
+#[derive(Default)]
+struct ModuleStats {
+    frames: AtomicU64,
+    /// Interleaved samples
+    samples: AtomicU64,
+    busy_ns: AtomicU64,
+    allocations: AtomicU64,
+}
+
+impl ModuleStats {
+    fn record(&self, samples: usize, busy: Duration, allocations: u64) {
+        self.frames.fetch_add(1, Ordering::Relaxed);
+        self.samples.fetch_add(samples as u64, Ordering::Relaxed);
+        self.busy_ns
+            .fetch_add(busy.as_nanos() as u64, Ordering::Relaxed);
+        self.allocations.fetch_add(allocations, Ordering::Relaxed);
+    }
+}
+
+fn audio_port(direction: PortDirection) -> Port {
+    let (id, label) = match direction {
+        PortDirection::Input => ("audio_in", "Audio In"),
+        PortDirection::Output => ("audio_out", "Audio Out"),
+    };
+    Port {
+        id: id.to_string(),
+        label: label.to_string(),
+        data_type: DataType::Audio,
+        direction,
+    }
+}
+
+/// Synthetic input: a 440 Hz tone, timestamped against the run's epoch
+struct ToneSource {
+    id: String,
+    config: Config,
+    epoch: Instant,
+    /// When the first frame went out, for pacing
+    started: Option<Instant>,
+    sent: u64,
+    phase: f32,
+    stats: Arc<ModuleStats>,
+    enabled: bool,
+}
+
+#[async_trait]
+impl Source for ToneSource {
+    fn name(&self) -> &str {
+        "Bench Input"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "Bench Input".to_string(),
+            description: "Synthetic tone for the audio benchmark".to_string(),
+            ports: vec![audio_port(PortDirection::Output)],
+            settings_schema: None,
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    async fn poll(&mut self) -> Option<Signal> {
+        if self.sent == self.config.frames_per_chain() {
+            return None;
+        }
+        let started = *self.started.get_or_insert_with(Instant::now);
+        if self.config.paced {
+            let due = started + self.config.frame_duration().mul_f64(self.sent as f64);
+            tokio::time::sleep_until(due.into()).await;
+        }
+
+        let allocations = alloc::on_thread();
+        let began = Instant::now();
+        let channels = self.config.channels as usize;
+        let step = 440.0 * std::f32::consts::TAU / self.config.sample_rate as f32;
+        let mut data = Vec::with_capacity(self.config.frame * channels);
+        for _ in 0..self.config.frame {
+            let sample = self.phase.sin() * 0.25;
+            self.phase = (self.phase + step) % std::f32::consts::TAU;
+            data.extend(std::iter::repeat_n(sample, channels));
+        }
+        self.stats.record(
+            data.len(),
+            began.elapsed(),
+            alloc::on_thread().saturating_sub(allocations),
+        );
+        self.sent += 1;
+        Some(Signal::Audio {
+            sample_rate: self.config.sample_rate,
+            channels: self.config.channels,
+            timestamp_us: self.epoch.elapsed().as_micros() as u64,
+            data,
+        })
+    }
+}
+
+/// Times and counts the allocations of a processor's `process` calls,
+/// including the boxed future each call makes. Allocations are counted on
+/// the calling thread, which is exact for processors that don't yield part
+/// way through.
+struct Metered<P> {
+    inner: P,
+    stats: Arc<ModuleStats>,
+}
+
+#[async_trait]
+impl<P: Processor> Processor for Metered<P> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        self.inner.schema()
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.inner.is_enabled()
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.inner.set_enabled(enabled);
+    }
+
+    async fn process(&mut self, signal: Signal) -> Result<Option<Signal>> {
+        let samples = signal.audio().map_or(0, |audio| audio.data.len());
+        let allocations = alloc::on_thread();
+        let began = Instant::now();
+        let output = self.inner.process(signal).await;
+        self.stats.record(
+            samples,
+            began.elapsed(),
+            alloc::on_thread().saturating_sub(allocations),
+        );
+        output
+    }
+}
+
+/// Dry output: records how long each frame took to arrive
+struct LatencySink {
+    id: String,
+    epoch: Instant,
+    latencies_us: Arc<Mutex<Vec<u64>>>,
+    stats: Arc<ModuleStats>,
+    enabled: bool,
+}
+
+#[async_trait]
+impl Sink for LatencySink {
+    fn name(&self) -> &str {
+        "Bench Output"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "Bench Output".to_string(),
+            description: "Records end-to-end latency for the audio benchmark".to_string(),
+            ports: vec![audio_port(PortDirection::Input)],
+            settings_schema: None,
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    async fn consume(&self, signal: Signal) -> Result<Option<Signal>> {
+        let Some(AudioView {
+            timestamp_us, data, ..
+        }) = signal.audio()
+        else {
+            return Ok(None);
+        };
+        let latency = (self.epoch.elapsed().as_micros() as u64).saturating_sub(timestamp_us);
+        let allocations = alloc::on_thread();
+        let began = Instant::now();
+        if let Ok(mut latencies) = self.latencies_us.lock() {
+            latencies.push(latency);
+        }
+        self.stats.record(
+            data.len(),
+            began.elapsed(),
+            alloc::on_thread().saturating_sub(allocations),
+        );
+        Ok(None)
+    }
+}
+
+/// Nearest-rank percentile of sorted values
+fn percentile(sorted: &[u64], p: f64) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+fn main() -> Result<()> {
+    let config = parse_args(std::env::args().skip(1))?;
+    let frames_per_chain = config.frames_per_chain();
+    let expected = frames_per_chain * config.chains as u64;
+
+    let (router_tx, mut router_rx) = mpsc::channel::<RoutedSignal>(1000);
+    let mut host = ModuleHost::new(router_tx);
+    let mut patch_bay = PatchBay::new();
+    let mut modules: Vec<(String, Arc<ModuleStats>)> = Vec::new();
+    let latencies_us = Arc::new(Mutex::new(Vec::with_capacity(expected as usize)));
+    let epoch = Instant::now();
+    let mut sources = Vec::new();
+
+    // Everything downstream is running before the first frame goes out
+    for chain in 0..config.chains {
+        let stats = Arc::new(ModuleStats::default());
+        let source = ToneSource {
+            id: format!("in_{}", chain),
+            config: config.clone(),
+            epoch,
+            started: None,
+            sent: 0,
+            phase: 0.0,
+            stats: stats.clone(),
+            enabled: true,
+        };
+        patch_bay.register_module(source.schema());
+        modules.push((source.id.clone(), stats));
+        let mut upstream = source.id.clone();
+        sources.push(source);
+
+        for stage in 0..config.stages {
+            let id = format!("dsp_{}_{}", chain, stage);
+            let state = AudioDspState::new();
+            state.set_lowpass_enabled(true);
+            let stats = Arc::new(ModuleStats::default());
+            let dsp = Metered {
+                inner: AudioDspProcessor::new(&id, state),
+                stats: stats.clone(),
+            };
+            patch_bay.register_module(dsp.schema());
+            host.spawn(ProcessorAdapter::new(dsp), INBOX)
+                .map_err(anyhow::Error::msg)?;
+            patch_bay.connect(&upstream, "audio_out", &id, "audio_in")?;
+            modules.push((id.clone(), stats));
+            upstream = id;
+        }
+
+        let stats = Arc::new(ModuleStats::default());
+        let sink = LatencySink {
+            id: format!("out_{}", chain),
+            epoch,
+            latencies_us: latencies_us.clone(),
+            stats: stats.clone(),
+            enabled: true,
+        };
+        patch_bay.register_module(sink.schema());
+        patch_bay.connect(&upstream, "audio_out", &sink.id, "audio_in")?;
+        modules.push((sink.id.clone(), stats));
+        host.spawn(SinkAdapter::new(sink), INBOX)
+            .map_err(anyhow::Error::msg)?;
+    }
+
+    let allocations_before = alloc::total();
+    let run_started = Instant::now();
+    for source in sources {
+        host.spawn(SourceAdapter::new(source), INBOX)
+            .map_err(anyhow::Error::msg)?;
+    }
+
+    // Route on this thread, as the daemon does from its update loop
+    let received = || latencies_us.lock().map(|l| l.len() as u64).unwrap_or(0);
+    let mut last_routed = Instant::now();
+    while received() < expected && last_routed.elapsed() < IDLE_TIMEOUT {
+        match router_rx.try_recv() {
+            Ok(routed) => {
+                host.route_signal(&patch_bay, routed);
+                last_routed = Instant::now();
+            }
+            Err(mpsc::error::TryRecvError::Empty) => std::thread::sleep(Duration::from_micros(50)),
+            Err(mpsc::error::TryRecvError::Disconnected) => break,
+        }
+    }
+    let wall = run_started.elapsed();
+    let allocations = alloc::total() - allocations_before;
+    let routing = host.routing_metrics().snapshot();
+    host.shutdown_all();
+
+    let mut latencies = latencies_us.lock().map(|l| l.clone()).unwrap_or_default();
+    latencies.sort_unstable();
+    println!(
+        "graph chains={} stages={} modules={} frame={} sample_rate={} channels={} paced={}",
+        config.chains,
+        config.stages,
+        modules.len(),
+        config.frame,
+        config.sample_rate,
+        config.channels,
+        config.paced
+    );
+    println!(
+        "run audio_ms={} wall_ms={} frames_expected={} frames_received={} allocations={} allocs_per_frame={:.2}",
+        (config.frame_duration().mul_f64(frames_per_chain as f64)).as_millis(),
+        wall.as_millis(),
+        expected,
+        latencies.len(),
+        allocations,
+        allocations as f64 / expected as f64
+    );
+    let samples_per_sec = config.sample_rate as f64 * config.channels as f64;
+    for (id, stats) in &modules {
+        let frames = stats.frames.load(Ordering::Relaxed);
+        let samples = stats.samples.load(Ordering::Relaxed);
+        let busy = Duration::from_nanos(stats.busy_ns.load(Ordering::Relaxed));
+        let per_frame = |total: f64| {
+            if frames == 0 {
+                0.0
+            } else {
+                total / frames as f64
+            }
+        };
+        let x_realtime = if busy.is_zero() {
+            0.0
+        } else {
+            samples as f64 / samples_per_sec / busy.as_secs_f64()
+        };
+        println!(
+            "module={} frames={} busy_ms={:.2} us_per_frame={:.2} x_realtime={:.0} allocs_per_frame={:.2}",
+            id,
+            frames,
+            busy.as_secs_f64() * 1000.0,
+            per_frame(busy.as_secs_f64() * 1e6),
+            x_realtime,
+            per_frame(stats.allocations.load(Ordering::Relaxed) as f64)
+        );
+    }
+    println!(
+        "latency_us p50={} p90={} p99={} p99.9={} max={}",
+        percentile(&latencies, 50.0),
+        percentile(&latencies, 90.0),
+        percentile(&latencies, 99.0),
+        percentile(&latencies, 99.9),
+        latencies.last().copied().unwrap_or(0)
+    );
+    println!(
+        "routing delivered={} send_failures={} loss_sensitive_failures={}",
+        routing.delivered, routing.send_failures, routing.loss_sensitive_failures
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_arguments_and_percentiles() {
+        let args = [
+            "--chains",
+            "3",
+            "--stages",
+            "0",
+            "--unpaced",
+            "--frame",
+            "128",
+        ];
+        let config = parse_args(args.iter().map(|a| a.to_string())).unwrap();
+        assert_eq!((config.chains, config.stages, config.frame), (3, 0, 128));
+        assert!(!config.paced);
+        assert_eq!(config.frames_per_chain(), 3750);
+        assert!(parse_args(["--chains", "0"].iter().map(|a| a.to_string())).is_err());
+        assert!(parse_args(["--frames"].iter().map(|a| a.to_string())).is_err());
+
+        let sorted: Vec<u64> = (1..=100).collect();
+        assert_eq!(percentile(&sorted, 50.0), 50);
+        assert_eq!(percentile(&sorted, 99.0), 99);
+        assert_eq!(percentile(&sorted, 100.0), 100);
+        assert_eq!(percentile(&[], 50.0), 0);
+    }
+}
@@ -23,6 +23,7 @@ fn main() {
                 start_ms: 0,
                 end_ms: sequence * 100,
                 sequence,
+                language: None,
             }
         } else {
             SttEvent::Partial {
@@ -31,6 +32,8 @@ fn main() {
                 text: text.into(),
                 audio_end_ms: sequence * 100,
                 sequence,
+                language: None,
+                stable_len: None,
             }
         };
         state.apply(event);
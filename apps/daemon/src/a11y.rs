@@ -0,0 +1,161 @@
+//! Accessibility: announcing what changes on screen
+//!
+//! The UI is drawn straight into the window, so a screen reader finds
+//! nothing there to read. Instead the daemon describes each frame what a
+//! sighted user would notice — the mode, the selected tile, the dialog on
+//! top and its focused row — and the [`Announcer`] turns the differences
+//! into short sentences. They're spoken through the platform's speech
+//! command when `[accessibility] speak` is on, and listed on the web
+//! monitor's status page in a live region browser screen readers follow.
+
+use magnolia_core::AccessibilityConfig;
+use std::collections::VecDeque;
+use std::process::{Child, Command, Stdio};
+
+/// Announcements kept for the status page
+const RECENT: usize = 10;
+
+/// What's on screen, a phrase for each part
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Screen {
+    pub mode: String,
+    /// The selected tile, or the cursor's cell when there's none
+    pub selection: String,
+    /// The top dialog and what's focused in it
+    pub dialog: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct Announcer {
+    last: Option<Screen>,
+    recent: VecDeque<String>,
+    speaking: Option<Child>,
+    /// The speech command failed once; not retried until it's changed
+    failed: Option<Vec<String>>,
+}
+
+impl Announcer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Announce how `screen` differs from the previous one; the first is
+    /// only remembered
+    pub fn update(&mut self, screen: Screen, config: &AccessibilityConfig) {
+        let Some(last) = self.last.replace(screen.clone()) else {
+            return;
+        };
+        let text = changes(&last, &screen);
+        if text.is_empty() {
+            return;
+        }
+        if self.recent.len() == RECENT {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(text.clone());
+        if config.speak {
+            self.speak(&text, config);
+        }
+    }
+
+    /// Latest announcements, oldest first
+    pub fn recent(&self) -> Vec<String> {
+        self.recent.iter().cloned().collect()
+    }
+
+    fn speak(&mut self, text: &str, config: &AccessibilityConfig) {
+        let command = if config.speech_command.is_empty() {
+            default_speech_command()
+        } else {
+            config.speech_command.clone()
+        };
+        if self.failed.as_ref() == Some(&command) {
+            return;
+        }
+        let Some((program, args)) = command.split_first() else {
+            return;
+        };
+        // A newer announcement cuts off the one still being read
+        if let Some(mut child) = self.speaking.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        match Command::new(program)
+            .args(args)
+            .arg(text)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        {
+            Ok(child) => self.speaking = Some(child),
+            Err(e) => {
+                log::warn!("Failed to speak with {}: {}", program, e);
+                self.failed = Some(command);
+            }
+        }
+    }
+}
+
+/// `say` on macOS, Speech Dispatcher's `spd-say` elsewhere
+fn default_speech_command() -> Vec<String> {
+    let program = if cfg!(target_os = "macos") {
+        "say"
+    } else {
+        "spd-say"
+    };
+    vec![program.to_string()]
+}
+
+/// The parts of `now` that differ from `before`, as one sentence each
+fn changes(before: &Screen, now: &Screen) -> String {
+    let mut parts = Vec::new();
+    if now.dialog != before.dialog {
+        parts.push(now.dialog.as_deref().unwrap_or("Dialog closed"));
+    }
+    if now.mode != before.mode {
+        parts.push(&now.mode);
+    }
+    // Back from a dialog, say where that left you
+    if now.dialog.is_none() && (now.selection != before.selection || before.dialog.is_some()) {
+        parts.push(&now.selection);
+    }
+    parts.join(". ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_changed_parts_are_announced() {
+        let quiet = AccessibilityConfig::default();
+        let mut announcer = Announcer::new();
+        let mut screen = Screen {
+            mode: "Normal mode".to_string(),
+            selection: "Column 1, row 1".to_string(),
+            dialog: None,
+        };
+        announcer.update(screen.clone(), &quiet);
+        announcer.update(screen.clone(), &quiet);
+        assert!(announcer.recent().is_empty());
+
+        screen.mode = "Layout mode".to_string();
+        screen.selection = "captions, captions tile, column 1, row 1".to_string();
+        announcer.update(screen.clone(), &quiet);
+        screen.dialog = Some("Add tile, clock".to_string());
+        screen.selection = "Column 2, row 1".to_string();
+        announcer.update(screen.clone(), &quiet);
+        screen.dialog = None;
+        announcer.update(screen, &quiet);
+
+        assert_eq!(
+            announcer.recent(),
+            [
+                "Layout mode. captions, captions tile, column 1, row 1",
+                "Add tile, clock",
+                "Dialog closed. Column 2, row 1",
+            ]
+        );
+    }
+}
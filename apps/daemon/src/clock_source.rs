@@ -0,0 +1,440 @@
+//! Clock module backend: shared clock settings and the `clock` source.
+//!
+//! [`ClockSource`] watches the configured alarms and, when `sun_events` is
+//! on, the day's sunrise and sunset, and emits `Signal::Intent`s on its
+//! `events_out` port:
+//! - `alarm` with `[label, "HH:MM"]`
+//! - `sunrise` / `sunset` with `["HH:MM"]` (local time)
+//!
+//! The clock tile edits the same [`SharedClockConfig`], so changes made in
+//! control mode apply to the running source without a restart.
+
+use aphrodite::almanac::Almanac;
+use aphrodite::ephemeris::GeoLocation;
+use async_trait::async_trait;
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
+use magnolia_core::{
+    system_clock, DataType, ModuleSchema, Port, PortDirection, SharedClock, Signal, Source,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+/// Events further in the past than this are dropped instead of fired late
+/// (e.g. after the machine wakes from sleep).
+const MAX_CATCH_UP: Duration = Duration::from_secs(120);
+const TICK: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum TimeFormat {
+    #[default]
+    TwentyFourHour,
+    TwelveHour,
+}
+
+/// An extra clock face at a fixed UTC offset.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ZoneClock {
+    pub label: String,
+    pub utc_offset_hours: f64,
+}
+
+impl Default for ZoneClock {
+    fn default() -> Self {
+        Self {
+            label: "UTC".to_string(),
+            utc_offset_hours: 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Alarm {
+    pub label: String,
+    pub hour: u32,
+    pub minute: u32,
+    pub enabled: bool,
+}
+
+impl Default for Alarm {
+    fn default() -> Self {
+        Self {
+            label: "Alarm".to_string(),
+            hour: 7,
+            minute: 0,
+            enabled: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClockLocation {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl Default for ClockLocation {
+    fn default() -> Self {
+        // Greenwich, matching aphrodite's default
+        Self {
+            latitude: 51.48,
+            longitude: 0.0,
+        }
+    }
+}
+
+impl ClockLocation {
+    pub fn geo(&self) -> GeoLocation {
+        GeoLocation {
+            lat: self.latitude,
+            lon: self.longitude,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClockConfig {
+    pub format: TimeFormat,
+    pub show_seconds: bool,
+    pub show_date: bool,
+    pub zones: Vec<ZoneClock>,
+    pub show_almanac: bool,
+    pub location: ClockLocation,
+    /// Emit `sunrise`/`sunset` intents
+    pub sun_events: bool,
+    pub alarms: Vec<Alarm>,
+}
+
+impl Default for ClockConfig {
+    fn default() -> Self {
+        Self {
+            format: TimeFormat::TwentyFourHour,
+            show_seconds: true,
+            show_date: false,
+            zones: Vec::new(),
+            show_almanac: true,
+            location: ClockLocation::default(),
+            sun_events: false,
+            alarms: Vec::new(),
+        }
+    }
+}
+
+impl ClockConfig {
+    /// JSON Schema for the settings form
+    pub fn schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "format": {
+                    "type": "string",
+                    "title": "Format",
+                    "enum": ["TwentyFourHour", "TwelveHour"],
+                    "default": "TwentyFourHour"
+                },
+                "show_seconds": { "type": "boolean", "title": "Show seconds", "default": true },
+                "show_date": { "type": "boolean", "title": "Show date", "default": false },
+                "zones": {
+                    "type": "array",
+                    "title": "Time zones",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "label": { "type": "string", "default": "UTC" },
+                            "utc_offset_hours": {
+                                "type": "number",
+                                "title": "UTC offset",
+                                "minimum": -12.0,
+                                "maximum": 14.0,
+                                "multipleOf": 0.25,
+                                "default": 0.0
+                            }
+                        }
+                    }
+                },
+                "show_almanac": { "type": "boolean", "title": "Sun & moon", "default": true },
+                "location": {
+                    "type": "object",
+                    "title": "Location",
+                    "properties": {
+                        "latitude": {
+                            "type": "number", "minimum": -90.0, "maximum": 90.0,
+                            "multipleOf": 0.01, "default": 51.48
+                        },
+                        "longitude": {
+                            "type": "number", "minimum": -180.0, "maximum": 180.0,
+                            "multipleOf": 0.01, "default": 0.0
+                        }
+                    }
+                },
+                "sun_events": {
+                    "type": "boolean",
+                    "title": "Emit sunrise/sunset",
+                    "default": false
+                },
+                "alarms": {
+                    "type": "array",
+                    "title": "Alarms",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "label": { "type": "string", "default": "Alarm" },
+                            "hour": { "type": "integer", "minimum": 0, "maximum": 23, "default": 7 },
+                            "minute": { "type": "integer", "minimum": 0, "maximum": 59, "default": 0 },
+                            "enabled": { "type": "boolean", "default": true }
+                        }
+                    }
+                }
+            }
+        })
+    }
+}
+
+pub type SharedClockConfig = Arc<RwLock<ClockConfig>>;
+
+pub fn shared_config() -> SharedClockConfig {
+    Arc::new(RwLock::new(ClockConfig::default()))
+}
+
+/// Events that fall in `(from, to]`, in time order.
+///
+/// Alarm times are wall-clock times in `tz`; sun events come from the
+/// almanac for the configured location.
+pub fn due_events<Tz: TimeZone>(
+    config: &ClockConfig,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    tz: &Tz,
+) -> Vec<Signal>
+where
+    Tz::Offset: std::fmt::Display,
+{
+    if to <= from {
+        return Vec::new();
+    }
+    let in_window = |at: DateTime<Utc>| at > from && at <= to;
+    let hhmm = |at: DateTime<Utc>| at.with_timezone(tz).format("%H:%M").to_string();
+
+    let mut local_days: Vec<NaiveDate> = vec![
+        from.with_timezone(tz).date_naive(),
+        to.with_timezone(tz).date_naive(),
+    ];
+    local_days.dedup();
+
+    let mut events: Vec<(DateTime<Utc>, Signal)> = Vec::new();
+    for alarm in config.alarms.iter().filter(|a| a.enabled) {
+        for day in &local_days {
+            let Some(naive) = day.and_hms_opt(alarm.hour, alarm.minute, 0) else {
+                continue;
+            };
+            // Skipped by a DST jump: the alarm just doesn't ring that day.
+            let Some(at) = tz.from_local_datetime(&naive).earliest() else {
+                continue;
+            };
+            let at = at.with_timezone(&Utc);
+            if in_window(at) {
+                events.push((
+                    at,
+                    Signal::Intent {
+                        action: "alarm".to_string(),
+                        parameters: vec![alarm.label.clone(), hhmm(at)],
+                    },
+                ));
+            }
+        }
+    }
+
+    if config.sun_events {
+        let mut utc_days = vec![from.date_naive(), to.date_naive()];
+        utc_days.dedup();
+        let location = config.location.geo();
+        for day in utc_days {
+            let sun = Almanac::compute(day, &location, None).sun;
+            for (action, at) in [("sunrise", sun.sunrise), ("sunset", sun.sunset)] {
+                if let Some(at) = at.filter(|at| in_window(*at)) {
+                    events.push((
+                        at,
+                        Signal::Intent {
+                            action: action.to_string(),
+                            parameters: vec![hhmm(at)],
+                        },
+                    ));
+                }
+            }
+        }
+    }
+
+    events.sort_by_key(|(at, _)| *at);
+    events.into_iter().map(|(_, signal)| signal).collect()
+}
+
+/// Source half of the clock module.
+pub struct ClockSource {
+    id: String,
+    enabled: bool,
+    config: SharedClockConfig,
+    clock: SharedClock,
+    last_check: Option<DateTime<Utc>>,
+    pending: VecDeque<Signal>,
+}
+
+impl ClockSource {
+    pub fn new(id: &str, config: SharedClockConfig) -> Self {
+        Self {
+            id: id.to_string(),
+            enabled: true,
+            config,
+            clock: system_clock(),
+            last_check: None,
+            pending: VecDeque::new(),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    fn now(&self) -> DateTime<Utc> {
+        DateTime::from_timestamp_micros(self.clock.now_us() as i64).unwrap_or_default()
+    }
+
+    fn check(&mut self) {
+        let now = self.now();
+        let catch_up = chrono::Duration::from_std(MAX_CATCH_UP).unwrap_or_default();
+        if let Some(last) = self.last_check {
+            let from = last.max(now - catch_up);
+            if let Ok(config) = self.config.read() {
+                self.pending.extend(due_events(&config, from, now, &Local));
+            }
+        }
+        self.last_check = Some(now);
+    }
+}
+
+#[async_trait]
+impl Source for ClockSource {
+    fn name(&self) -> &str {
+        "Clock"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "Clock".to_string(),
+            description: "Alarms and sunrise/sunset events as Intent signals".to_string(),
+            ports: vec![Port {
+                id: "events_out".to_string(),
+                label: "Events".to_string(),
+                data_type: DataType::Control,
+                direction: PortDirection::Output,
+            }],
+            settings_schema: Some(ClockConfig::schema()),
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    async fn poll(&mut self) -> Option<Signal> {
+        loop {
+            if let Some(signal) = self.pending.pop_front() {
+                return Some(signal);
+            }
+            self.clock.sleep(TICK).await;
+            if self.enabled {
+                self.check();
+            } else {
+                self.last_check = None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    fn utc(h: u32, m: u32, s: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, 20, h, m, s).unwrap()
+    }
+
+    fn actions(events: &[Signal]) -> Vec<String> {
+        events
+            .iter()
+            .map(|signal| match signal {
+                Signal::Intent { action, parameters } => {
+                    format!("{} {}", action, parameters.join(" "))
+                }
+                other => format!("{:?}", other),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn alarms_fire_once_in_local_time() {
+        let config = ClockConfig {
+            alarms: vec![
+                Alarm {
+                    label: "Wake".into(),
+                    hour: 8,
+                    minute: 30,
+                    enabled: true,
+                },
+                Alarm {
+                    label: "Off".into(),
+                    hour: 8,
+                    minute: 30,
+                    enabled: false,
+                },
+            ],
+            ..ClockConfig::default()
+        };
+        let cet = FixedOffset::east_opt(3600).unwrap();
+
+        // 08:30 CET is 07:30 UTC
+        let fired = due_events(&config, utc(7, 29, 59), utc(7, 30, 0), &cet);
+        assert_eq!(actions(&fired), vec!["alarm Wake 08:30"]);
+        assert!(due_events(&config, utc(7, 30, 0), utc(7, 30, 1), &cet).is_empty());
+    }
+
+    #[test]
+    fn alarms_across_midnight_use_both_days() {
+        let config = ClockConfig {
+            alarms: vec![Alarm {
+                hour: 0,
+                minute: 0,
+                ..Alarm::default()
+            }],
+            ..ClockConfig::default()
+        };
+        let from = Utc.with_ymd_and_hms(2024, 3, 20, 23, 59, 59).unwrap();
+        let to = Utc.with_ymd_and_hms(2024, 3, 21, 0, 0, 1).unwrap();
+        assert_eq!(due_events(&config, from, to, &Utc).len(), 1);
+    }
+
+    #[test]
+    fn sun_events_only_when_enabled() {
+        let mut config = ClockConfig::default();
+        // Greenwich equinox sunrise is around 06:02 UTC
+        let (from, to) = (utc(5, 50, 0), utc(6, 15, 0));
+        assert!(due_events(&config, from, to, &Utc).is_empty());
+
+        config.sun_events = true;
+        let fired = actions(&due_events(&config, from, to, &Utc));
+        assert_eq!(fired.len(), 1);
+        assert!(fired[0].starts_with("sunrise 06:0"));
+    }
+}
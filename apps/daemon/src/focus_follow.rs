@@ -0,0 +1,162 @@
+//! Focus-follows-signal: the main-window tile whose module emitted most
+//! recently gets a highlight and, when `maximize_secs` is set, comes up
+//! full-window for a while each time focus moves to it.
+//!
+//! Activity comes from the router's [`RoutingMetrics::last_emissions`], so
+//! audio streams don't count and ignored tiles or modules never take focus.
+//!
+//! [`RoutingMetrics::last_emissions`]: magnolia_core::RoutingMetrics::last_emissions
+
+use magnolia_core::{FocusFollowConfig, LayoutConfig};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// How long the spotlight takes to grow from the tile and shrink back
+pub const SPOTLIGHT_EASE: Duration = Duration::from_millis(300);
+
+#[derive(Debug, Default)]
+pub struct FocusFollow {
+    /// Tile holding focus
+    pub tile: Option<String>,
+    /// Emission that gave it focus
+    seen: Option<Instant>,
+    /// Tile shown full-window, with when that started and ends
+    spotlight: Option<(String, Instant, Instant)>,
+}
+
+impl FocusFollow {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Move focus to the followed tile with the newest emission, if that's
+    /// newer than the one focus was given for
+    pub fn update(
+        &mut self,
+        config: &FocusFollowConfig,
+        layout: &LayoutConfig,
+        emissions: &HashMap<String, Instant>,
+        now: Instant,
+    ) {
+        if !config.enabled {
+            self.tile = None;
+            self.seen = None;
+            self.spotlight = None;
+            return;
+        }
+        if self
+            .spotlight
+            .as_ref()
+            .is_some_and(|(_, _, until)| now >= *until + SPOTLIGHT_EASE)
+        {
+            self.spotlight = None;
+        }
+
+        let newest = layout
+            .main_tiles()
+            .filter(|tile| tile.enabled && config.follows(tile))
+            .filter_map(|tile| Some((tile, *emissions.get(&tile.module)?)))
+            .max_by_key(|(_, at)| *at);
+        let Some((tile, at)) = newest else {
+            return;
+        };
+        if self.seen.is_some_and(|seen| at <= seen) {
+            return;
+        }
+        self.seen = Some(at);
+        if self.tile.as_deref() == Some(tile.id.as_str()) {
+            return;
+        }
+        self.tile = Some(tile.id.clone());
+        if config.maximize_secs > 0.0 {
+            let until = now + Duration::from_secs_f32(config.maximize_secs);
+            self.spotlight = Some((tile.id.clone(), now, until));
+        }
+    }
+
+    /// Tile shown full-window and how far it has grown, 0 to 1
+    pub fn spotlight(&self, now: Instant) -> Option<(&str, f32)> {
+        let (tile_id, started, until) = self.spotlight.as_ref()?;
+        let ease = SPOTLIGHT_EASE.as_secs_f32();
+        let grown = now.saturating_duration_since(*started).as_secs_f32() / ease;
+        let shrunk = now.saturating_duration_since(*until).as_secs_f32() / ease;
+        let factor = grown.min(1.0) - shrunk.min(1.0);
+        (factor > 0.0).then_some((tile_id.as_str(), factor))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use magnolia_core::{RoutingMetrics, Signal};
+
+    #[test]
+    fn focus_moves_to_the_newest_followed_emission() {
+        let layout: LayoutConfig = toml::from_str(
+            r#"
+            columns = ["1fr", "1fr", "1fr"]
+            rows = ["1fr"]
+            tiles = [
+                { id = "captions", col = 0, row = 0, module = "captions" },
+                { id = "kamea", col = 1, row = 0, module = "kamea" },
+                { id = "clock", col = 2, row = 0, module = "clock" },
+            ]
+            "#,
+        )
+        .unwrap();
+        let config = FocusFollowConfig {
+            enabled: true,
+            maximize_secs: 2.0,
+            ignore: vec!["clock".to_string()],
+        };
+        let t0 = Instant::now();
+        let at = |ms| t0 + Duration::from_millis(ms);
+        let mut focus = FocusFollow::new();
+
+        let mut emissions = HashMap::from([
+            ("captions".to_string(), at(0)),
+            ("clock".to_string(), at(5)),
+        ]);
+        focus.update(&config, &layout, &emissions, at(10));
+        assert_eq!(focus.tile.as_deref(), Some("captions"));
+        assert!(focus.spotlight(at(10)).is_none_or(|(_, f)| f < 0.1));
+        assert_eq!(focus.spotlight(at(500)), Some(("captions", 1.0)));
+
+        emissions.insert("kamea".to_string(), at(600));
+        focus.update(&config, &layout, &emissions, at(610));
+        assert_eq!(focus.tile.as_deref(), Some("kamea"));
+        assert_eq!(focus.spotlight(at(3000)), None);
+
+        let off = FocusFollowConfig::default();
+        focus.update(&off, &layout, &emissions, at(3000));
+        assert!(focus.tile.is_none());
+    }
+
+    #[test]
+    fn idle_pulses_dont_take_focus() {
+        let layout: LayoutConfig = toml::from_str(
+            r#"
+            columns = ["1fr", "1fr"]
+            rows = ["1fr"]
+            tiles = [
+                { id = "captions", col = 0, row = 0, module = "captions" },
+                { id = "replay", col = 1, row = 0, module = "audio_replay" },
+            ]
+            "#,
+        )
+        .unwrap();
+        let config = FocusFollowConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        let metrics = RoutingMetrics::default();
+        metrics.record_emission("captions", &Signal::Text("hello".to_string()));
+        std::thread::sleep(Duration::from_millis(2));
+        // A finished replay keeping its source alive
+        metrics.record_emission("audio_replay", &Signal::Pulse);
+
+        let mut focus = FocusFollow::new();
+        focus.update(&config, &layout, &metrics.last_emissions(), Instant::now());
+        assert_eq!(focus.tile.as_deref(), Some("captions"));
+    }
+}
@@ -4,7 +4,7 @@
 //! Arrow keys work in all modes, ESC cascades through navigation hierarchy.
 
 use crate::tiles::TileRegistry;
-use magnolia_core::{LayoutConfig, TileConfig};
+use magnolia_core::{KeyMacro, LayoutConfig, TileConfig, TileOverlay};
 use nannou::prelude::Key;
 
 /// Top-level input mode
@@ -62,6 +62,93 @@ impl Default for LayoutSubState {
     }
 }
 
+/// Macro recording, started and stopped with Ctrl+R
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum MacroState {
+    #[default]
+    Idle,
+    /// Keys pressed so far
+    Recording(Vec<String>),
+    /// Recorded; waiting for the F-key to play it
+    Binding(Vec<String>),
+}
+
+/// What the macro system made of a key press
+#[derive(Debug, Clone, PartialEq)]
+pub enum MacroInput {
+    /// Not for macros; handle it as usual (it's recorded if recording)
+    Pass,
+    /// Taken by the macro system
+    Consumed,
+    /// A macro was bound; the layout needs saving
+    Bound,
+    /// Replay these keys
+    Play(Vec<String>),
+}
+
+/// Keys a macro step can name
+#[rustfmt::skip]
+const MACRO_KEYS: [Key; 68] = [
+    Key::A, Key::B, Key::C, Key::D, Key::E, Key::F, Key::G, Key::H, Key::I, Key::J,
+    Key::K, Key::L, Key::M, Key::N, Key::O, Key::P, Key::Q, Key::R, Key::S, Key::T,
+    Key::U, Key::V, Key::W, Key::X, Key::Y, Key::Z,
+    Key::Key0, Key::Key1, Key::Key2, Key::Key3, Key::Key4,
+    Key::Key5, Key::Key6, Key::Key7, Key::Key8, Key::Key9,
+    Key::F1, Key::F2, Key::F3, Key::F4, Key::F5, Key::F6,
+    Key::F7, Key::F8, Key::F9, Key::F10, Key::F11, Key::F12,
+    Key::Up, Key::Down, Key::Left, Key::Right,
+    Key::Return, Key::Escape, Key::Space, Key::Tab, Key::Back, Key::Delete,
+    Key::Home, Key::End, Key::PageUp, Key::PageDown,
+    Key::Minus, Key::Equals, Key::LBracket, Key::RBracket, Key::Comma, Key::Period,
+];
+
+/// Keys macros can be bound to
+#[rustfmt::skip]
+const MACRO_BINDINGS: [Key; 12] = [
+    Key::F1, Key::F2, Key::F3, Key::F4, Key::F5, Key::F6,
+    Key::F7, Key::F8, Key::F9, Key::F10, Key::F11, Key::F12,
+];
+
+/// Keys accepting the patch suggestions, in rank order
+#[rustfmt::skip]
+pub const SUGGESTION_KEYS: [Key; 9] = [
+    Key::Key1, Key::Key2, Key::Key3, Key::Key4, Key::Key5,
+    Key::Key6, Key::Key7, Key::Key8, Key::Key9,
+];
+
+fn key_name(key: Key) -> String {
+    format!("{:?}", key).to_lowercase()
+}
+
+/// `"ctrl+shift+l"` for Ctrl+Shift+L
+pub fn key_step(key: Key, ctrl: bool, shift: bool) -> String {
+    let mut step = String::new();
+    if ctrl {
+        step.push_str("ctrl+");
+    }
+    if shift {
+        step.push_str("shift+");
+    }
+    step + &key_name(key)
+}
+
+/// Key and modifiers of a step written by [`key_step`]
+pub fn parse_key_step(step: &str) -> Option<(Key, bool, bool)> {
+    let step = step.trim().to_lowercase();
+    let mut parts: Vec<&str> = step.split('+').collect();
+    let name = parts.pop()?;
+    let key = MACRO_KEYS.into_iter().find(|key| key_name(*key) == name)?;
+    let (mut ctrl, mut shift) = (false, false);
+    for modifier in parts {
+        match modifier {
+            "ctrl" => ctrl = true,
+            "shift" => shift = true,
+            _ => return None,
+        }
+    }
+    Some((key, ctrl, shift))
+}
+
 /// Direction for keyboard navigation
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Direction {
@@ -91,8 +178,22 @@ pub enum AppAction {
     OpenTileSettings { tile_id: String },
     /// Open the layout manager modal
     OpenLayoutManager,
+    /// Open the community plugin index modal
+    OpenPluginIndex,
     /// Toggle maximizing the currently selected tile
     ToggleMaximize,
+    /// Toggle kiosk mode (only mapped to Ctrl+K)
+    ToggleKiosk,
+    /// Start a new session folder (only mapped to Ctrl+N)
+    StartSession,
+    /// End the running session (only mapped to Ctrl+E)
+    StopSession,
+    /// Mark the moment in the session and recordings (only mapped to Ctrl+M)
+    AddMarker,
+    /// Switch the high-contrast theme (only mapped to Ctrl+H)
+    ToggleHighContrast,
+    /// Make the selected tile's nth suggested patch (1-9 in patch mode)
+    AcceptPatchSuggestion { index: usize },
 }
 
 /// Central keyboard navigation state
@@ -111,6 +212,10 @@ pub struct KeyboardNav {
     /// Grid dimensions cache
     grid_cols: usize,
     grid_rows: usize,
+    /// Macro being recorded or bound
+    pub macro_state: MacroState,
+    /// Set while a macro plays, so its keys aren't recorded or replayed again
+    pub replaying: bool,
 }
 
 impl Default for KeyboardNav {
@@ -129,6 +234,8 @@ impl KeyboardNav {
             last_selected: None,
             grid_cols: 4,
             grid_rows: 4,
+            macro_state: MacroState::Idle,
+            replaying: false,
         }
     }
 
@@ -141,6 +248,63 @@ impl KeyboardNav {
         self.cursor.1 = self.cursor.1.min(rows.saturating_sub(1));
     }
 
+    /// Macro keys, seen before anything else handles a key press: Ctrl+R
+    /// starts and stops recording, the F-key pressed next plays the
+    /// recording back, and everything else pressed meanwhile is recorded
+    pub fn handle_macro_key(
+        &mut self,
+        key: Key,
+        ctrl: bool,
+        shift: bool,
+        layout: &mut LayoutConfig,
+    ) -> MacroInput {
+        if self.replaying {
+            return MacroInput::Pass;
+        }
+        match std::mem::take(&mut self.macro_state) {
+            MacroState::Idle => {
+                if ctrl && key == Key::R {
+                    log::info!("Recording a macro; Ctrl+R again to stop");
+                    self.macro_state = MacroState::Recording(Vec::new());
+                    return MacroInput::Consumed;
+                }
+                let name = key_name(key);
+                match layout.macros.iter().find(|m| m.key == name) {
+                    Some(recorded) if !ctrl && !shift => MacroInput::Play(recorded.steps.clone()),
+                    _ => MacroInput::Pass,
+                }
+            }
+            MacroState::Recording(mut steps) => {
+                if ctrl && key == Key::R {
+                    if steps.is_empty() {
+                        log::info!("Macro recording cancelled: nothing was pressed");
+                    } else {
+                        log::info!("Recorded {} keys; press F1-F12 to bind them", steps.len());
+                        self.macro_state = MacroState::Binding(steps);
+                    }
+                    return MacroInput::Consumed;
+                }
+                steps.push(key_step(key, ctrl, shift));
+                self.macro_state = MacroState::Recording(steps);
+                MacroInput::Pass
+            }
+            MacroState::Binding(steps) => {
+                if key == Key::Escape {
+                    log::info!("Macro discarded");
+                } else if MACRO_BINDINGS.contains(&key) && !ctrl && !shift {
+                    let name = key_name(key);
+                    layout.macros.retain(|m| m.key != name);
+                    log::info!("Bound a {}-key macro to {}", steps.len(), name);
+                    layout.macros.push(KeyMacro { key: name, steps });
+                    return MacroInput::Bound;
+                } else {
+                    self.macro_state = MacroState::Binding(steps);
+                }
+                MacroInput::Consumed
+            }
+        }
+    }
+
     /// Main entry point for processing key presses.
     /// Returns an optional AppAction for side-effects.
     pub fn handle_key(
@@ -155,6 +319,11 @@ impl KeyboardNav {
         if ctrl {
             match key {
                 Key::Q => return Some(AppAction::QuitApp),
+                Key::K => return Some(AppAction::ToggleKiosk),
+                Key::N => return Some(AppAction::StartSession),
+                Key::E => return Some(AppAction::StopSession),
+                Key::M => return Some(AppAction::AddMarker),
+                Key::H => return Some(AppAction::ToggleHighContrast),
                 Key::C => {
                     // Copy logic
                     if let Some(tile_id) = self.selected_tile_id() {
@@ -230,6 +399,12 @@ impl KeyboardNav {
                 }
             }
 
+            // Kiosk mode locks the layout and patches
+            Key::L | Key::P if layout.kiosk.enabled => {}
+
+            // === Shift+P - Plugin Index ===
+            Key::P if shift => return Some(AppAction::OpenPluginIndex),
+
             // === P - Patch Mode ===
             Key::P => match self.mode {
                 InputMode::Normal => {
@@ -363,17 +538,80 @@ impl KeyboardNav {
                 }
             }
 
+            // === O - Float the selected tile over the grid / put it back ===
+            Key::O if self.mode == InputMode::Layout => {
+                return self.edit_selected(layout, |tile| {
+                    tile.overlay = match tile.overlay {
+                        Some(_) => None,
+                        None => Some(TileOverlay::default()),
+                    };
+                    log::info!(
+                        "Tile {} is {}",
+                        tile.id,
+                        if tile.is_overlay() {
+                            "an overlay"
+                        } else {
+                            "back on the grid"
+                        }
+                    );
+                });
+            }
+
+            // === [ / ] - Lower / raise the selected tile ===
+            Key::LBracket | Key::RBracket if self.mode == InputMode::Layout => {
+                let step = if key == Key::RBracket { 1 } else { -1 };
+                return self.edit_selected(layout, |tile| tile.z += step);
+            }
+
+            // === C - Move an overlay to the next corner ===
+            Key::C if self.mode == InputMode::Layout => {
+                return self.edit_selected(layout, |tile| {
+                    if let Some(overlay) = &mut tile.overlay {
+                        overlay.anchor = overlay.anchor.next();
+                    }
+                });
+            }
+
+            // === - / = - Shrink / grow an overlay ===
+            Key::Minus | Key::Equals if self.mode == InputMode::Layout => {
+                let step = if key == Key::Equals { 0.05 } else { -0.05 };
+                return self.edit_selected(layout, |tile| {
+                    if let Some(overlay) = &mut tile.overlay {
+                        overlay.scale = (overlay.scale + step).clamp(TileOverlay::MIN_SCALE, 1.0);
+                    }
+                });
+            }
+
             // === Tab - Cycle through tiles ===
             Key::Tab => {
                 self.cycle_tile_selection(layout, true);
             }
 
+            // === 1-9 - Make one of the selected tile's suggested patches ===
+            _ if self.mode == InputMode::Patch && self.has_selection() => {
+                if let Some(index) = SUGGESTION_KEYS.iter().position(|k| *k == key) {
+                    return Some(AppAction::AcceptPatchSuggestion { index });
+                }
+            }
+
             _ => {}
         }
 
         None
     }
 
+    /// Change the selected tile in place and have the layout saved
+    fn edit_selected(
+        &self,
+        layout: &mut LayoutConfig,
+        edit: impl FnOnce(&mut TileConfig),
+    ) -> Option<AppAction> {
+        let tile_id = self.selected_tile_id()?;
+        let tile = layout.tiles.iter_mut().find(|t| t.id == tile_id)?;
+        edit(tile);
+        Some(AppAction::SaveLayout)
+    }
+
     /// Internal helper to dispatch tile-specific keybinds
     fn dispatch_tile_keybind(
         &mut self,
@@ -533,7 +771,7 @@ impl KeyboardNav {
         let mut best_tile: Option<&TileConfig> = None;
         let mut best_overlap = 0;
 
-        for tile in &layout.tiles {
+        for tile in layout.grid_tiles() {
             if tile.id == current.id {
                 continue;
             }
@@ -652,12 +890,11 @@ impl KeyboardNav {
 
     /// Cycle through tiles in row-major order (Tab navigation)
     pub fn cycle_tile_selection(&mut self, layout: &LayoutConfig, forward: bool) {
-        if layout.tiles.is_empty() {
+        // Sort tiles by row then column for consistent ordering
+        let mut sorted_tiles: Vec<&TileConfig> = layout.main_tiles().collect();
+        if sorted_tiles.is_empty() {
             return;
         }
-
-        // Sort tiles by row then column for consistent ordering
-        let mut sorted_tiles: Vec<&TileConfig> = layout.tiles.iter().collect();
         sorted_tiles.sort_by(|a, b| {
             let row_cmp = a.row.cmp(&b.row);
             if row_cmp == std::cmp::Ordering::Equal {
@@ -818,9 +1055,9 @@ impl KeyboardNav {
         }
     }
 
-    /// Get tile at a specific cell
+    /// Get the grid tile at a specific cell; overlays are reached with Tab
     pub fn get_tile_at_cell(layout: &LayoutConfig, col: usize, row: usize) -> Option<&TileConfig> {
-        for tile in &layout.tiles {
+        for tile in layout.grid_tiles() {
             let t_col = tile.col;
             let t_row = tile.row;
             let t_colspan = tile.colspan.unwrap_or(1);
@@ -891,6 +1128,38 @@ pub enum EscapeResult {
 mod tests {
     use super::*;
 
+    #[test]
+    fn key_steps_round_trip() {
+        assert_eq!(key_step(Key::L, true, true), "ctrl+shift+l");
+        assert_eq!(parse_key_step("ctrl+shift+l"), Some((Key::L, true, true)));
+        assert_eq!(parse_key_step("Return"), Some((Key::Return, false, false)));
+        assert_eq!(parse_key_step("alt+x"), None);
+        assert_eq!(parse_key_step("hyper"), None);
+    }
+
+    #[test]
+    fn macros_record_bind_and_play() {
+        let mut layout: LayoutConfig =
+            toml::from_str("columns = [\"1fr\"]\nrows = [\"1fr\"]\ntiles = []").unwrap();
+        let mut nav = KeyboardNav::new();
+        let mut press =
+            |nav: &mut KeyboardNav, key, ctrl| nav.handle_macro_key(key, ctrl, false, &mut layout);
+
+        assert_eq!(press(&mut nav, Key::R, true), MacroInput::Consumed);
+        assert_eq!(press(&mut nav, Key::P, false), MacroInput::Pass);
+        assert_eq!(press(&mut nav, Key::Down, false), MacroInput::Pass);
+        assert_eq!(press(&mut nav, Key::R, true), MacroInput::Consumed);
+        // Only F-keys take a macro
+        assert_eq!(press(&mut nav, Key::A, false), MacroInput::Consumed);
+        assert_eq!(press(&mut nav, Key::F3, false), MacroInput::Bound);
+        assert_eq!(nav.macro_state, MacroState::Idle);
+
+        let played = press(&mut nav, Key::F3, false);
+        assert_eq!(played, MacroInput::Play(vec!["p".into(), "down".into()]));
+        nav.replaying = true;
+        assert_eq!(press(&mut nav, Key::F3, false), MacroInput::Pass);
+    }
+
     #[test]
     fn test_navigation() {
         let mut nav = KeyboardNav::new();
@@ -3,51 +3,77 @@
 //! Handles layout configuration loading, track resolution (px/fr/%),
 //! and tile rect calculation.
 
-use magnolia_core::{LayoutConfig, TileConfig};
+use magnolia_core::{LayoutConfig, TileConfig, WindowConfig};
 use nannou::prelude::*;
-use std::fs;
+use std::cell::Cell;
+use std::path::Path;
 
 pub struct Layout {
     pub window_rect: Rect,
     pub config: LayoutConfig,
+    /// Copy the saved layout to layout.toml.bak before next overwriting it
+    backup_before_save: Cell<bool>,
 }
 
-impl Layout {
-    pub fn new(win_rect: Rect) -> Self {
-        // Load config from multiple potential paths
-        let paths = ["configs/layout.toml", "../../configs/layout.toml"];
-        let mut content = None;
-        for p in &paths {
-            if let Ok(c) = fs::read_to_string(p) {
-                content = Some(c);
-                break;
-            }
-        }
-
-        let content = content.unwrap_or_else(|| {
-            println!(
-                "Warning: Could not load layout.toml from {:?}, using default.",
-                paths
-            );
-            r#"
+/// A single clock tile filling the window
+fn minimal_config() -> LayoutConfig {
+    let (config, _) = magnolia_core::parse_layout(
+        r#"
             columns = ["1fr"]
             rows = ["1fr"]
-            
+
             [[tiles]]
             id = "clock"
             col = 0
             row = 0
             colspan = 1
             module = "clock"
-            "#
-            .to_string()
-        });
+            "#,
+    )
+    .expect("Default layout parses");
+    config
+}
+
+impl Layout {
+    pub fn new(win_rect: Rect) -> Self {
+        // Load config from multiple potential paths, upgrading older files
+        let paths = ["configs/layout.toml", "../../configs/layout.toml"];
+        let loaded = paths
+            .iter()
+            .map(Path::new)
+            .find(|p| p.exists())
+            .map(|p| (p, magnolia_core::load_layout(p)));
 
-        let config: LayoutConfig = toml::from_str(&content).expect("Failed to parse layout.toml");
+        let config = match loaded {
+            Some((_, Ok(config))) => config,
+            Some((path, Err(e))) => {
+                log::error!("Could not load {}: {}", path.display(), e);
+                eprintln!("Could not load {}: {}", path.display(), e);
+                std::process::exit(1);
+            }
+            None => {
+                println!(
+                    "Warning: Could not load layout.toml from {:?}, using default.",
+                    paths
+                );
+                minimal_config()
+            }
+        };
 
         Self {
             window_rect: win_rect,
             config,
+            backup_before_save: Cell::new(false),
+        }
+    }
+
+    /// The one-clock default, ignoring any saved layout (safe mode). The
+    /// first save keeps the layout it replaces as layout.toml.bak.
+    pub fn minimal(win_rect: Rect) -> Self {
+        Self {
+            window_rect: win_rect,
+            config: minimal_config(),
+            backup_before_save: Cell::new(true),
         }
     }
 
@@ -56,6 +82,12 @@ impl Layout {
     }
 
     pub fn save(&self) {
+        if self.backup_before_save.take() && Path::new("configs/layout.toml").exists() {
+            match std::fs::copy("configs/layout.toml", "configs/layout.toml.bak") {
+                Ok(_) => log::info!("Kept the previous layout as layout.toml.bak"),
+                Err(e) => log::error!("Failed to back up layout.toml: {}", e),
+            }
+        }
         let config = self.config.clone();
         std::thread::spawn(move || match toml::to_string_pretty(&config) {
             Ok(c) => {
@@ -69,8 +101,9 @@ impl Layout {
         });
     }
 
+    /// The grid tile covering a cell; overlays don't take cells
     pub fn get_tile_at(&self, col: usize, row: usize) -> Option<&TileConfig> {
-        for tile in &self.config.tiles {
+        for tile in self.config.grid_tiles() {
             let t_col = tile.col;
             let t_row = tile.row;
             let t_cols = tile.colspan.unwrap_or(1);
@@ -83,11 +116,34 @@ impl Layout {
         None
     }
 
-    /// Calculate the screen rect for a tile
+    /// Calculate the screen rect for a tile; `None` for tiles on other windows
     pub fn calculate_rect(&self, tile: &TileConfig) -> Option<Rect> {
+        if !self.config.in_main_window(tile) {
+            return None;
+        }
         let (col_tracks, row_tracks) = self.config.generate_tracks();
-        let cols = self.resolve_tracks(&col_tracks, self.window_rect.w());
-        let rows = self.resolve_tracks(&row_tracks, self.window_rect.h());
+        Some(self.grid_rect(tile, &col_tracks, &row_tracks, self.window_rect))
+    }
+
+    /// Rect for a tile on an extra window's own grid
+    pub fn calculate_window_rect(
+        &self,
+        tile: &TileConfig,
+        window: &WindowConfig,
+        window_rect: Rect,
+    ) -> Rect {
+        self.grid_rect(tile, &window.columns, &window.rows, window_rect)
+    }
+
+    fn grid_rect(
+        &self,
+        tile: &TileConfig,
+        col_tracks: &[String],
+        row_tracks: &[String],
+        window_rect: Rect,
+    ) -> Rect {
+        let cols = self.resolve_tracks(col_tracks, window_rect.w());
+        let rows = self.resolve_tracks(row_tracks, window_rect.h());
 
         let start_x = cols.iter().take(tile.col).sum::<f32>();
         let width = cols
@@ -103,11 +159,16 @@ impl Layout {
             .take(tile.rowspan.unwrap_or(1))
             .sum::<f32>();
 
+        let [start_x, start_y_from_top, width, height] = match &tile.overlay {
+            Some(overlay) => overlay.place([start_x, start_y_from_top, width, height]),
+            None => [start_x, start_y_from_top, width, height],
+        };
+
         // Nannou Coordinate Conversion (center-based, Y up)
-        let cx = self.window_rect.left() + start_x + width / 2.0;
-        let cy = self.window_rect.top() - start_y_from_top - height / 2.0;
+        let cx = window_rect.left() + start_x + width / 2.0;
+        let cy = window_rect.top() - start_y_from_top - height / 2.0;
 
-        Some(Rect::from_x_y_w_h(cx, cy, width, height))
+        Rect::from_x_y_w_h(cx, cy, width, height)
     }
 
     /// Resolve track definitions (px, %, fr) to pixel values
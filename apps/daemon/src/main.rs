@@ -1,26 +1,37 @@
 use magnolia_core::adapters::{ProcessorAdapter, SinkAdapter, SourceAdapter};
 use magnolia_core::{
-    ModuleRuntime, PatchBay, PluginManager, PluginModuleAdapter, RoutedSignal, Signal,
+    Clock, HostGpu, ModuleRuntime, PatchBay, PatchSuggestion, PluginEvent, PluginManager,
+    PluginModuleAdapter, RoutedSignal, Signal, WgpuBackend,
 };
 use magnolia_core::{Processor, Sink, Source};
 use nannou::prelude::*;
 // use nannou_egui removed
 use tokio::sync::mpsc;
 
-use audio_dsp::tile::AudioDspTile;
-use audio_dsp::{AudioDspProcessor, AudioDspState};
+use audio_dsp::tile::{AbCompareTile, AudioDspTile};
+use audio_dsp::{
+    AbCompareInputSink, AbCompareProcessor, AbCompareState, AbSide, AudioDspProcessor, AudioDspState, DuckerProcessor, DuckerSidechainSink, DuckerState,
+    SilenceProcessor, StereoToolsProcessor, StereoToolsState,
+};
 use audio_input::tile::AudioVisTile;
 use audio_input::{AudioInputSettings, AudioInputSource, AudioInputTile, AudioVizRingSink};
 use audio_output::tile::AudioOutputTile;
 use audio_output::{AudioOutputSettings, AudioOutputSink, AudioOutputState};
 use caption_state::CaptionState;
-use speech_to_text::{LocalSherpaBackend, SherpaConfig, SttEvent, SttProcessor};
+use speech_to_text::{
+    BackendFactory, LocalSherpaBackend, SherpaConfig, SttBackend, SttEvent, SttProcessor,
+};
 // use magnolia_core::ring_buffer; // Removed usage
 
 // Layout editor and visualizer modules
+mod a11y;
+mod clock_source;
+mod focus_follow;
 mod input;
 mod layout;
 mod patch_visualizer;
+mod safe_mode;
+mod sysmon;
 mod theme;
 mod tiles;
 mod ui;
@@ -31,7 +42,12 @@ use input::{AppAction, KeyboardNav};
 use layout::Layout;
 use tiles::{RenderContext, TileRegistry};
 use ui::fullscreen_modal::ModalAnim;
-use ui::modals::{ModalStack, ModalState, PatchBayModalState};
+use ui::layout_manager::LayoutManagerAction;
+use ui::modals::{
+    LayoutManagerState, ModalStack, ModalState, ModuleDocsState, PatchBayModalState,
+    PluginIndexState,
+};
+use ui::plugin_index::{PluginIndexAction, PluginIndexEvent};
 
 // --- MODEL ---
 struct Model {
@@ -61,6 +77,7 @@ struct Model {
 
     // Runtime State
     module_host: magnolia_core::ModuleHost,
+    host_gpu: std::sync::Arc<HostGpu>,
     plugin_manager: magnolia_core::PluginManager,
 
     // Tile System (Phase 6: Settings Architecture)
@@ -79,6 +96,85 @@ struct Model {
 
     // Modal animation states (for fullscreen modals)
     modal_anims: std::collections::HashMap<ModalAnimKey, ModalAnim>,
+    /// Tile previews for the Add Tile picker and Layout Manager
+    thumbnails: ui::thumbnails::TileThumbnails,
+
+    // Video share (NDI): frames read back after each view
+    video_share: Option<video_share::VideoShare>,
+    frame_capturer: wgpu::TextureCapturer,
+    main_window: WindowId,
+    /// Extra windows from `[[windows]]`, to their config ids
+    output_windows: std::collections::HashMap<WindowId, String>,
+    /// Battery and thermal readings behind `power_profile`
+    power_monitor: magnolia_core::PowerMonitor,
+    /// The chosen profile, lowered while on battery or running hot
+    power_profile: magnolia_core::PowerProfile,
+    /// Read-only status page from `[web]`, refreshed every half second
+    web_monitor: Option<web_monitor::WebMonitor>,
+    audio_output_state: std::sync::Arc<AudioOutputState>,
+    /// The running plugin index fetch or install
+    plugin_index_rx: Option<std::sync::mpsc::Receiver<PluginIndexEvent>>,
+    /// The STT model being downloaded, spawned once it's in place
+    model_download_rx: Option<std::sync::mpsc::Receiver<Result<models::ResolvedModel, String>>>,
+    sherpa_options: SherpaOptions,
+    /// Folder file-writing modules save into while a session runs
+    session: magnolia_core::SessionState,
+    /// Tile of the most recently active module, from `[focus_follow]`
+    focus_follow: focus_follow::FocusFollow,
+    /// Spoken and status-page descriptions of what changes on screen
+    announcer: a11y::Announcer,
+    /// Counts failed starts; says whether this one is in safe mode
+    startup: safe_mode::StartupGuard,
+    /// Why saved patches were left out, until the patch bay shows them
+    patch_issues: Vec<String>,
+}
+
+/// Pseudo-module announcing session starts and ends on its control port
+const SESSION_MODULE: &str = "session";
+
+/// Sherpa model files: role, the variable overriding it and its name in a
+/// model directory
+const SHERPA_FILES: [(&str, &str, &str); 4] = [
+    (
+        "encoder",
+        "MAGNOLIA_SHERPA_ENCODER",
+        "encoder-epoch-99-avg-1-chunk-16-left-128.int8.onnx",
+    ),
+    (
+        "decoder",
+        "MAGNOLIA_SHERPA_DECODER",
+        "decoder-epoch-99-avg-1-chunk-16-left-128.onnx",
+    ),
+    (
+        "joiner",
+        "MAGNOLIA_SHERPA_JOINER",
+        "joiner-epoch-99-avg-1-chunk-16-left-128.int8.onnx",
+    ),
+    ("tokens", "MAGNOLIA_SHERPA_TOKENS", "tokens.txt"),
+];
+
+/// Sherpa settings besides the model files
+#[derive(Debug, Clone)]
+struct SherpaOptions {
+    num_threads: i32,
+    endpointing: bool,
+    /// Language the configured model recognizes
+    language: String,
+}
+
+impl SherpaOptions {
+    /// Encoder, decoder, joiner and tokens, in `SHERPA_FILES` order
+    fn config(&self, [encoder, decoder, joiner, tokens]: [std::path::PathBuf; 4]) -> SherpaConfig {
+        SherpaConfig {
+            encoder,
+            decoder,
+            joiner,
+            tokens,
+            num_threads: self.num_threads,
+            endpointing: self.endpointing,
+            hotwords: None,
+        }
+    }
 }
 
 /// Key for modal animation tracking
@@ -87,6 +183,8 @@ enum ModalAnimKey {
     GlobalSettings,
     PatchBay,
     LayoutManager,
+    PluginIndex,
+    ModuleDocs,
 
     AddTilePicker,
 }
@@ -122,12 +220,27 @@ fn main() {
     // Default: warn for everything, but silence wgpu warnings, info for our crates.
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn,wgpu_core=error,wgpu_hal=error,nannou=error,daemon=info,text_tools=info,aphrodite=info,logos=info,kamea=info")).init();
 
-    nannou::app(model).update(update).run();
+    nannou::app(model).update(update).exit(exit).run();
 }
 
 fn model(app: &App) -> Model {
     app.set_exit_on_escape(false);
 
+    // Safe mode skips plugins, audio devices and the saved layout
+    let startup = safe_mode::StartupGuard::begin(std::env::args().skip(1));
+    let safe_mode = startup.is_safe_mode();
+    if let Some(description) = startup.describe() {
+        log::warn!("{}", description);
+    }
+
+    // Generative modules draw from the session seed; --reproduce pins the
+    // one an earlier session recorded
+    let seeds = magnolia_core::SharedSeed::default();
+    if let Some(seed) = reproduce_seed(std::env::args().skip(1)) {
+        seeds.pin(seed);
+    }
+    log::info!("Generative seed {}", seeds.seed());
+
     // 1. Setup Channels
     let (tx_ui, rx_ui) = std::sync::mpsc::channel::<Signal>();
     let (tx_router, rx_router) = mpsc::channel::<RoutedSignal>(1000);
@@ -157,9 +270,6 @@ fn model(app: &App) -> Model {
         .build()
         .unwrap();
 
-    let _window = app.window(_window_id).unwrap();
-    // let egui = Egui::from_window(&window); removed
-
     // 4. Init Clipboard (might fail on some systems)
     let clipboard = match arboard::Clipboard::new() {
         Ok(cb) => Some(cb),
@@ -173,7 +283,22 @@ fn model(app: &App) -> Model {
     let mut patch_bay = PatchBay::new();
 
     // Load layout config
-    let layout = Layout::new(app.window_rect());
+    let mut layout = if safe_mode {
+        Layout::minimal(app.window_rect())
+    } else {
+        Layout::new(app.window_rect())
+    };
+    if let Some(window) = app.window(_window_id) {
+        apply_kiosk(&window, &layout.config.kiosk);
+    }
+    magnolia_ui::theme::set_high_contrast(layout.config.accessibility.high_contrast);
+    let output_windows = open_output_windows(app, &layout.config);
+
+    // Startup profile: modules it marks lazy wait for a patch or a maximize
+    let requested_profile = std::env::var("MAGNOLIA_PROFILE").ok();
+    let (profile_name, startup_profile) =
+        layout.config.startup.resolve(requested_profile.as_deref());
+    module_host.begin_startup(&profile_name, startup_profile);
 
     // Apply patches from layout config (after plugins register their schemas)
     // This will be re-applied after plugin loading
@@ -182,7 +307,13 @@ fn model(app: &App) -> Model {
     let audio_input_settings = AudioInputSettings::new();
     let audio_output_settings = AudioOutputSettings::new();
     let caption_state = std::sync::Arc::new(std::sync::Mutex::new(CaptionState::default()));
-    let mut tile_registry = tiles::create_default_registry(caption_state.clone());
+    let clock_config = clock_source::shared_config();
+    let sysmon_history = sysmon::shared_history();
+    let mut tile_registry = tiles::create_default_registry(
+        caption_state.clone(),
+        clock_config.clone(),
+        sysmon_history.clone(),
+    );
     let mut stt_metrics = None;
     let mut sherpa_ready = false;
     let transcription_config = match magnolia_config::read_transcription_config() {
@@ -223,16 +354,19 @@ fn model(app: &App) -> Model {
     tile_registry.register(vis_tile);
 
     // Audio output tile (fed by AudioOutputSink)
-    let (audio_output_sink, audio_output_state) =
-        match AudioOutputSink::new("audio_output", audio_output_settings.clone()) {
-            Ok((sink, state)) => (Some(sink), state),
-            Err(e) => {
-                log::error!("Failed to initialize audio output: {}", e);
-                // Create a dummy state to keep UI stable
-                let state = std::sync::Arc::new(AudioOutputState::default());
-                (None, state)
-            }
-        };
+    let audio_output = if safe_mode {
+        log::info!("Audio output disabled in safe mode");
+        None
+    } else {
+        AudioOutputSink::new("audio_output", audio_output_settings.clone())
+            .map_err(|e| log::error!("Failed to initialize audio output: {}", e))
+            .ok()
+    };
+    let (audio_output_sink, audio_output_state) = match audio_output {
+        Some((sink, state)) => (Some(sink), state),
+        // Create a dummy state to keep UI stable
+        None => (None, std::sync::Arc::new(AudioOutputState::default())),
+    };
 
     tile_registry.register(AudioOutputTile::new(
         "audio_output",
@@ -247,8 +381,134 @@ fn model(app: &App) -> Model {
     // Astro tile (astrological chart)
     tile_registry.register(aphrodite::tile::AstroTile::new());
 
+    // System metrics; the system monitor tile draws the shared history
+    let sysmon_source = sysmon::SysmonSource::new("sysmon", sysmon_history);
+    patch_bay.register_module(sysmon_source.schema());
+    if let Err(e) = module_host.spawn(SourceAdapter::new(sysmon_source), 100) {
+        log::error!("Failed to spawn system monitor source: {}", e);
+    }
+
+    // Weather (Open-Meteo), shared between the source and its tile
+    let weather_settings = weather::WeatherSettings::new();
+    tile_registry.register(weather::WeatherTile::new("weather", weather_settings.clone()));
+    let weather_source = weather::WeatherSource::new("weather", weather_settings.clone());
+    patch_bay.register_module(weather_source.schema());
+    if let Err(e) = module_host.spawn(SourceAdapter::new(weather_source), 100) {
+        log::error!("Failed to spawn weather source: {}", e);
+    }
+
+    // Observer location; moves the clock almanac (aphrodite's GeoLocation)
+    // and the weather query along with it
+    let location_clock = clock_config.clone();
+    let location_weather = weather_settings.clone();
+    let location = location::LocationSource::new("location", location::LocationConfig::default())
+        .with_listener(move |at| {
+            if let Ok(mut config) = location_clock.write() {
+                config.location.latitude = at.latitude;
+                config.location.longitude = at.longitude;
+            }
+            let mut weather_config = location_weather.config();
+            weather_config.latitude = at.latitude;
+            weather_config.longitude = at.longitude;
+            location_weather.set_config(weather_config);
+        });
+    patch_bay.register_module(location.schema());
+    if let Err(e) = module_host.spawn(SourceAdapter::new(location), 100) {
+        log::error!("Failed to spawn location source: {}", e);
+    }
+
+    // Clock alarms and sun events, driven by the clock tile's settings
+    let clock = clock_source::ClockSource::new("clock", clock_config);
+    patch_bay.register_module(clock.schema());
+    if let Err(e) = module_host.spawn(SourceAdapter::new(clock), 100) {
+        log::error!("Failed to spawn clock source: {}", e);
+    }
+
+    // Game controllers: picks up any joystick, now or when plugged in
+    let gamepad = gamepad::GamepadSource::new("gamepad", Default::default());
+    let gamepad_schema = gamepad.schema();
+    patch_bay.register_module(gamepad_schema.clone());
+    if let Err(e) = module_host.spawn(SourceAdapter::new(gamepad), 100) {
+        log::error!("Failed to spawn gamepad source: {}", e);
+    } else if let Some(sender) = module_host.get_sender("gamepad") {
+        tile_registry.register(tiles::SchemaTile::new(
+            "gamepad",
+            &gamepad_schema.name,
+            gamepad_schema.settings_schema,
+            sender,
+        ));
+    }
+
+    // MIDI controllers: every input port, now or when plugged in
+    let midi_input = midi_input::MidiInputSource::new("midi_input", Default::default());
+    let midi_schema = midi_input.schema();
+    patch_bay.register_module(midi_schema.clone());
+    if let Err(e) = module_host.spawn(SourceAdapter::new(midi_input), 100) {
+        log::error!("Failed to spawn MIDI input: {}", e);
+    } else if let Some(sender) = module_host.get_sender("midi_input") {
+        tile_registry.register(tiles::SchemaTile::new(
+            "midi_input",
+            &midi_schema.name,
+            midi_schema.settings_schema,
+            sender,
+        ));
+    }
+
+    // Email: unseen IMAP mail as text; idle until a server is set
+    let inbox = inbox::InboxSource::new("inbox", Default::default());
+    let inbox_schema = inbox.schema();
+    patch_bay.register_module(inbox_schema.clone());
+    if let Err(e) = module_host.spawn(SourceAdapter::new(inbox), 100) {
+        log::error!("Failed to spawn inbox source: {}", e);
+    } else if let Some(sender) = module_host.get_sender("inbox") {
+        tile_registry.register(tiles::SchemaTile::new(
+            "inbox",
+            &inbox_schema.name,
+            inbox_schema.settings_schema,
+            sender,
+        ));
+    }
+
+    // Moon phase, sign ingresses and void-of-course windows
+    let lunar = aphrodite::lunar_source::LunarSource::new("lunar");
+    patch_bay.register_module(lunar.schema());
+    if let Err(e) = module_host.spawn(SourceAdapter::new(lunar), 100) {
+        log::error!("Failed to spawn lunar source: {}", e);
+    }
+
+    // Vimshottari periods from the natal Moon: timeline tile + change events
+    let dasha_timeline = aphrodite::ephemeris::SwissEphemerisAdapter::new(None)
+        .map_err(|e| e.to_string())
+        .and_then(|mut adapter| {
+            aphrodite::natal::NatalData::default()
+                .dasha_timeline(aphrodite::vedic::DashaSystem::Vimshottari, &mut adapter)
+        });
+    match &dasha_timeline {
+        Ok(timeline) => {
+            let dasha = aphrodite::dasha_source::DashaSource::new("dasha", timeline.clone());
+            patch_bay.register_module(dasha.schema());
+            if let Err(e) = module_host.spawn(SourceAdapter::new(dasha), 100) {
+                log::error!("Failed to spawn dasha source: {}", e);
+            }
+        }
+        Err(e) => log::warn!("Dasha timeline unavailable: {}", e),
+    }
+    tile_registry.register(aphrodite::dasha_tile::DashaTile::new(
+        "dasha_timeline",
+        dasha_timeline,
+    ));
+
+    // Dice / I Ching / tarot casts on trigger signals
+    let cleromancy = cleromancy::CleromancyProcessor::new("cleromancy").with_seeds(seeds.clone());
+    patch_bay.register_module(cleromancy.schema());
+    if let Err(e) = module_host.spawn(ProcessorAdapter::new(cleromancy), 100) {
+        log::error!("Failed to spawn cleromancy: {}", e);
+    }
+
     // Audio pipeline modules
-    if let Ok(audio_input_source) =
+    if safe_mode {
+        log::info!("Audio input disabled in safe mode");
+    } else if let Ok(audio_input_source) =
         AudioInputSource::new("audio_input", audio_input_settings.clone())
     {
         let schema = audio_input_source.schema();
@@ -260,39 +520,43 @@ fn model(app: &App) -> Model {
         log::error!("Audio input source failed to initialize");
     }
 
-    // Live STT is opt-in until a model is installed. The four paths should
-    // point at one compatible Sherpa streaming Zipformer model directory.
+    // Live STT is opt-in until a model is installed. The source's `model`
+    // names its files in config/transcription.toml; MAGNOLIA_SHERPA_MODEL_DIR
+    // or the per-file variables override them.
     let sherpa_source = transcription_config.source("sherpa_local");
-    let model_dir = std::env::var("MAGNOLIA_SHERPA_MODEL_DIR").ok().or_else(|| {
+    let model_store = transcription_config.model_store(".");
+    let sherpa_model = sherpa_source
+        .and_then(|source| source.model.clone())
+        .filter(|name| model_store.spec(name).is_some());
+    let resolved_model = sherpa_model
+        .as_deref()
+        .and_then(|name| model_store.resolve(name).ok());
+    let env_model_dir = std::env::var("MAGNOLIA_SHERPA_MODEL_DIR").ok();
+    let model_dir = env_model_dir.clone().or_else(|| {
         sherpa_source
             .and_then(|source| source.model_dir.as_ref())
             .map(|path| path.display().to_string())
     });
-    let model_path = |variable: &str, filename: &str| {
-        std::env::var(variable).ok().or_else(|| {
-            model_dir.as_ref().map(|dir| {
-                std::path::Path::new(dir)
-                    .join(filename)
-                    .display()
-                    .to_string()
+    let model_path = |role: &str, variable: &str, filename: &str| {
+        let in_dir = |dir: &String| {
+            std::path::Path::new(dir)
+                .join(filename)
+                .display()
+                .to_string()
+        };
+        std::env::var(variable)
+            .ok()
+            .or_else(|| env_model_dir.as_ref().map(in_dir))
+            .or_else(|| {
+                resolved_model
+                    .as_ref()
+                    .and_then(|model| model.file(role))
+                    .map(|path| path.display().to_string())
             })
-        })
+            .or_else(|| model_dir.as_ref().map(in_dir))
     };
-    let sherpa_paths = [
-        model_path(
-            "MAGNOLIA_SHERPA_ENCODER",
-            "encoder-epoch-99-avg-1-chunk-16-left-128.int8.onnx",
-        ),
-        model_path(
-            "MAGNOLIA_SHERPA_DECODER",
-            "decoder-epoch-99-avg-1-chunk-16-left-128.onnx",
-        ),
-        model_path(
-            "MAGNOLIA_SHERPA_JOINER",
-            "joiner-epoch-99-avg-1-chunk-16-left-128.int8.onnx",
-        ),
-        model_path("MAGNOLIA_SHERPA_TOKENS", "tokens.txt"),
-    ];
+    let sherpa_paths =
+        SHERPA_FILES.map(|(role, variable, filename)| model_path(role, variable, filename));
     let sherpa_enabled = std::env::var("MAGNOLIA_SHERPA_ENABLED")
         .map(|value| {
             !matches!(
@@ -301,50 +565,48 @@ fn model(app: &App) -> Model {
             )
         })
         .unwrap_or_else(|_| sherpa_source.map(|source| source.enabled).unwrap_or(true));
+    let sherpa_options = SherpaOptions {
+        num_threads: std::env::var("MAGNOLIA_SHERPA_THREADS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .or_else(|| sherpa_source.and_then(|source| source.num_threads))
+            .unwrap_or(1),
+        endpointing: sherpa_source
+            .and_then(|source| source.endpointing)
+            .unwrap_or(true),
+        language: sherpa_source
+            .map(|source| source.language.clone())
+            .unwrap_or_else(|| "en".to_string()),
+    };
     let sherpa_paths_complete = sherpa_paths.iter().all(|path| {
         path.as_deref()
             .map(std::path::Path::new)
             .is_some_and(std::path::Path::exists)
     });
+    let mut model_download_rx = None;
     if sherpa_enabled && sherpa_paths_complete {
+        let config = sherpa_options.config(sherpa_paths.map(|path| path.unwrap().into()));
+        stt_metrics = spawn_sherpa(
+            &mut module_host,
+            &mut patch_bay,
+            &caption_state,
+            config,
+            &sherpa_options.language,
+        );
+        sherpa_ready = stt_metrics.is_some();
+    } else if !sherpa_enabled {
+        log::info!("Sherpa captions disabled by MAGNOLIA_SHERPA_ENABLED");
+    } else if let Some(name) =
+        sherpa_model.filter(|name| env_model_dir.is_none() && model_store.can_download(name))
+    {
         if let Ok(mut captions) = caption_state.lock() {
             captions.apply(SttEvent::Status {
                 status: speech_to_text::SttStatus::Starting,
             });
         }
-        let config = SherpaConfig {
-            encoder: sherpa_paths[0].as_ref().unwrap().into(),
-            decoder: sherpa_paths[1].as_ref().unwrap().into(),
-            joiner: sherpa_paths[2].as_ref().unwrap().into(),
-            tokens: sherpa_paths[3].as_ref().unwrap().into(),
-            num_threads: std::env::var("MAGNOLIA_SHERPA_THREADS")
-                .ok()
-                .and_then(|value| value.parse().ok())
-                .or_else(|| sherpa_source.and_then(|source| source.num_threads))
-                .unwrap_or(1),
-            endpointing: sherpa_source
-                .and_then(|source| source.endpointing)
-                .unwrap_or(true),
-        };
-        let stt = SttProcessor::new("speech_to_text", Box::new(LocalSherpaBackend::new(config)));
-        stt_metrics = Some(stt.metrics());
-        patch_bay.register_module(stt.schema());
-        if let Err(e) = module_host.spawn(ProcessorAdapter::new(stt), 64) {
-            log::error!("Failed to spawn speech-to-text processor: {e}");
-            if let Ok(mut captions) = caption_state.lock() {
-                captions.apply(SttEvent::Status {
-                    status: speech_to_text::SttStatus::Failed,
-                });
-            }
-        } else {
-            sherpa_ready = true;
-            log::info!("Live Sherpa captions enabled");
-        }
-    } else if !sherpa_enabled {
-        log::info!("Sherpa captions disabled by MAGNOLIA_SHERPA_ENABLED");
+        model_download_rx = Some(start_model_download(model_store, name));
     } else {
-        let labels = ["encoder", "decoder", "joiner", "tokens"];
-        for (label, path) in labels.iter().zip(sherpa_paths.iter()) {
+        for ((label, ..), path) in SHERPA_FILES.iter().zip(sherpa_paths.iter()) {
             match path {
                 Some(path) if std::path::Path::new(path).exists() => {}
                 Some(path) => log::warn!("Sherpa {label} path does not exist: {path}"),
@@ -352,7 +614,7 @@ fn model(app: &App) -> Model {
             }
         }
         log::info!(
-            "Sherpa captions disabled; configure MAGNOLIA_SHERPA_MODEL_DIR or explicit model paths to enable"
+            "Sherpa captions disabled; add the model's files (or urls to fetch them from) in config/transcription.toml, or set MAGNOLIA_SHERPA_MODEL_DIR"
         );
     }
 
@@ -362,6 +624,378 @@ fn model(app: &App) -> Model {
     if let Err(e) = module_host.spawn(ProcessorAdapter::new(audio_dsp), 100) {
         log::error!("Failed to spawn audio DSP: {}", e);
     }
+    let stereo_tools = StereoToolsProcessor::new("stereo_tools", StereoToolsState::new());
+    patch_bay.register_module(stereo_tools.schema());
+    if let Err(e) = module_host.spawn(ProcessorAdapter::new(stereo_tools), 100) {
+        log::error!("Failed to spawn stereo tools: {}", e);
+    }
+    let ducker_state = DuckerState::new();
+    let ducker = DuckerProcessor::new("ducker", ducker_state.clone());
+    let ducker_sidechain = DuckerSidechainSink::new("ducker", ducker_state);
+    patch_bay.register_module(ducker.schema());
+    patch_bay.register_module(ducker_sidechain.schema());
+    if let Err(e) = module_host.spawn(ProcessorAdapter::new(ducker), 100) {
+        log::error!("Failed to spawn ducker: {}", e);
+    }
+    if let Err(e) = module_host.spawn(SinkAdapter::new(ducker_sidechain), 100) {
+        log::error!("Failed to spawn ducker sidechain: {}", e);
+    }
+
+    // Silence detector: patch its intents into speech_to_text to pause it
+    // between phrases, or into anything listening for silence_start/_end
+    let silence = SilenceProcessor::new("silence", magnolia_core::SilenceConfig::default());
+    patch_bay.register_module(silence.schema());
+    if let Err(e) = module_host.spawn(ProcessorAdapter::new(silence), 100) {
+        log::error!("Failed to spawn silence detector: {}", e);
+    }
+
+    // A/B compare: patch the tails of two alternative chains into ab_a and
+    // ab_b and ab's audio_out into the output; its tile switches them
+    let ab_state = AbCompareState::new();
+    let ab = AbCompareProcessor::new("ab", ab_state.clone());
+    patch_bay.register_module(ab.schema());
+    if let Err(e) = module_host.spawn(ProcessorAdapter::new(ab), 100) {
+        log::error!("Failed to spawn A/B compare: {}", e);
+    }
+    for side in [AbSide::A, AbSide::B] {
+        let input = AbCompareInputSink::new("ab", side, ab_state.clone());
+        patch_bay.register_module(input.schema());
+        if let Err(e) = module_host.spawn(SinkAdapter::new(input), 100) {
+            log::error!("Failed to spawn A/B compare input: {}", e);
+        }
+    }
+    tile_registry.register(AbCompareTile::new("ab", ab_state));
+
+    // Intent switchboard; routes are edited through its settings tile
+    let switchboard = switchboard::SwitchboardProcessor::new("switchboard", Default::default());
+    let switchboard_schema = switchboard.schema();
+    patch_bay.register_module(switchboard_schema.clone());
+    if let Err(e) = module_host.spawn(ProcessorAdapter::new(switchboard), 100) {
+        log::error!("Failed to spawn switchboard: {}", e);
+    } else if let Some(sender) = module_host.get_sender("switchboard") {
+        tile_registry.register(tiles::SchemaTile::new(
+            "switchboard",
+            &switchboard_schema.name,
+            switchboard_schema.settings_schema,
+            sender,
+        ));
+    }
+
+    // Rate limiter for chatty sources ahead of slow sinks
+    let rate_limit = flow_tools::RateLimitProcessor::new("rate_limit", Default::default());
+    let rate_limit_schema = rate_limit.schema();
+    patch_bay.register_module(rate_limit_schema.clone());
+    if let Err(e) = module_host.spawn(ProcessorAdapter::new(rate_limit), 100) {
+        log::error!("Failed to spawn rate limiter: {}", e);
+    } else if let Some(sender) = module_host.get_sender("rate_limit") {
+        tile_registry.register(tiles::SchemaTile::new(
+            "rate_limit",
+            &rate_limit_schema.name,
+            rate_limit_schema.settings_schema,
+            sender,
+        ));
+    }
+
+    // Drops repeats, e.g. a clipboard monitor seeing the same copy twice
+    let dedupe = flow_tools::DedupeProcessor::new("dedupe", Default::default());
+    let dedupe_schema = dedupe.schema();
+    patch_bay.register_module(dedupe_schema.clone());
+    if let Err(e) = module_host.spawn(ProcessorAdapter::new(dedupe), 100) {
+        log::error!("Failed to spawn dedupe: {}", e);
+    } else if let Some(sender) = module_host.get_sender("dedupe") {
+        tile_registry.register(tiles::SchemaTile::new(
+            "dedupe",
+            &dedupe_schema.name,
+            dedupe_schema.settings_schema,
+            sender,
+        ));
+    }
+
+    // Holds signals for later, for choreographing slow reveals
+    let delay = flow_tools::DelayProcessor::new("delay", Default::default());
+    let delay_schema = delay.schema();
+    patch_bay.register_module(delay_schema.clone());
+    if let Err(e) = module_host.spawn(ProcessorAdapter::new(delay), 100) {
+        log::error!("Failed to spawn delay: {}", e);
+    } else if let Some(sender) = module_host.get_sender("delay") {
+        tile_registry.register(tiles::SchemaTile::new(
+            "delay",
+            &delay_schema.name,
+            delay_schema.settings_schema,
+            sender,
+        ));
+    }
+
+    // Sample-and-hold: data into latch, pulses or intents into latch_trigger
+    let latch_state = flow_tools::LatchState::new();
+    let latch = flow_tools::LatchProcessor::new("latch", Default::default(), latch_state.clone());
+    let latch_trigger = flow_tools::LatchTriggerSink::new("latch", latch_state);
+    let latch_schema = latch.schema();
+    patch_bay.register_module(latch_schema.clone());
+    patch_bay.register_module(latch_trigger.schema());
+    if let Err(e) = module_host.spawn(ProcessorAdapter::new(latch), 100) {
+        log::error!("Failed to spawn latch: {}", e);
+    } else if let Some(sender) = module_host.get_sender("latch") {
+        tile_registry.register(tiles::SchemaTile::new(
+            "latch",
+            &latch_schema.name,
+            latch_schema.settings_schema,
+            sender,
+        ));
+    }
+    if let Err(e) = module_host.spawn(SinkAdapter::new(latch_trigger), 100) {
+        log::error!("Failed to spawn latch trigger: {}", e);
+    }
+
+    // N-to-1 arbitration: selector passes one input, merge interleaves all;
+    // their inputs are the selector_in_N / merge_in_N sinks
+    let selector_inputs = flow_tools::InputQueues::new(4);
+    let selector = flow_tools::SelectorProcessor::new(
+        "selector",
+        Default::default(),
+        selector_inputs.clone(),
+    );
+    let merge_inputs = flow_tools::InputQueues::new(4);
+    let merge = flow_tools::MergeProcessor::new("merge", Default::default(), merge_inputs.clone());
+    let selector_schema = selector.schema();
+    let merge_schema = merge.schema();
+    patch_bay.register_module(selector_schema.clone());
+    patch_bay.register_module(merge_schema.clone());
+    if let Err(e) = module_host.spawn(ProcessorAdapter::new(selector), 100) {
+        log::error!("Failed to spawn selector: {}", e);
+    }
+    if let Err(e) = module_host.spawn(ProcessorAdapter::new(merge), 100) {
+        log::error!("Failed to spawn merge: {}", e);
+    }
+    for (id, schema) in [("selector", selector_schema), ("merge", merge_schema)] {
+        if let Some(sender) = module_host.get_sender(id) {
+            tile_registry.register(tiles::SchemaTile::new(
+                id,
+                &schema.name,
+                schema.settings_schema,
+                sender,
+            ));
+        }
+    }
+    for input in selector_inputs
+        .sinks("selector")
+        .into_iter()
+        .chain(merge_inputs.sinks("merge"))
+    {
+        patch_bay.register_module(input.schema());
+        if let Err(e) = module_host.spawn(SinkAdapter::new(input), 100) {
+            log::error!("Failed to spawn selector/merge input: {}", e);
+        }
+    }
+
+    // Expression over numeric signals, variables bound by source
+    let math = numeric_tools::MathProcessor::new("math", Default::default());
+    let math_schema = math.schema();
+    patch_bay.register_module(math_schema.clone());
+    if let Err(e) = module_host.spawn(ProcessorAdapter::new(math), 100) {
+        log::error!("Failed to spawn math: {}", e);
+    } else if let Some(sender) = module_host.get_sender("math") {
+        tile_registry.register(tiles::SchemaTile::new(
+            "math",
+            &math_schema.name,
+            math_schema.settings_schema,
+            sender,
+        ));
+    }
+
+    // Rolling aggregates of numeric signals, e.g. to threshold a gate from
+    // the level it usually sees
+    let stats = numeric_tools::StatsProcessor::new("stats", Default::default());
+    let stats_schema = stats.schema();
+    patch_bay.register_module(stats_schema.clone());
+    if let Err(e) = module_host.spawn(ProcessorAdapter::new(stats), 100) {
+        log::error!("Failed to spawn stats: {}", e);
+    } else if let Some(sender) = module_host.get_sender("stats") {
+        tile_registry.register(tiles::SchemaTile::new(
+            "stats",
+            &stats_schema.name,
+            stats_schema.settings_schema,
+            sender,
+        ));
+    }
+
+    // Voice-command parser; patch speech_to_text's text_out into it and its
+    // intents into the switchboard or a sink
+    let intent_parser =
+        intent_parser::IntentParserProcessor::new("intent_parser", Default::default());
+    let intent_parser_schema = intent_parser.schema();
+    patch_bay.register_module(intent_parser_schema.clone());
+    if let Err(e) = module_host.spawn(ProcessorAdapter::new(intent_parser), 100) {
+        log::error!("Failed to spawn intent parser: {}", e);
+    } else if let Some(sender) = module_host.get_sender("intent_parser") {
+        tile_registry.register(tiles::SchemaTile::new(
+            "intent_parser",
+            &intent_parser_schema.name,
+            intent_parser_schema.settings_schema,
+            sender,
+        ));
+    }
+
+    // Keyword triggers: an intent per configured phrase heard, without a grammar
+    let trigger_words =
+        intent_parser::TriggerWordsProcessor::new("trigger_words", Default::default());
+    let trigger_words_schema = trigger_words.schema();
+    patch_bay.register_module(trigger_words_schema.clone());
+    if let Err(e) = module_host.spawn(ProcessorAdapter::new(trigger_words), 100) {
+        log::error!("Failed to spawn trigger words: {}", e);
+    } else if let Some(sender) = module_host.get_sender("trigger_words") {
+        tile_registry.register(tiles::SchemaTile::new(
+            "trigger_words",
+            &trigger_words_schema.name,
+            trigger_words_schema.settings_schema,
+            sender,
+        ));
+    }
+
+    // Conversation memory between user text and an LLM/oracle; its replies
+    // patched back into exchange_in are remembered too
+    let memory = memory::MemoryProcessor::new("memory", Default::default());
+    let memory_schema = memory.schema();
+    patch_bay.register_module(memory_schema.clone());
+    if let Err(e) = module_host.spawn(ProcessorAdapter::new(memory), 100) {
+        log::error!("Failed to spawn memory: {}", e);
+    } else if let Some(sender) = module_host.get_sender("memory") {
+        tile_registry.register(tiles::SchemaTile::new(
+            "memory",
+            &memory_schema.name,
+            memory_schema.settings_schema,
+            sender,
+        ));
+    }
+
+    // OBS over obs-websocket: commands in through obs_control, events out of
+    // obs; the bridge keeps retrying while OBS isn't running
+    match obs_bridge::spawn("obs_control", "obs", Default::default()) {
+        Ok((obs_sink, obs_source)) => {
+            let obs_sink_schema = obs_sink.schema();
+            patch_bay.register_module(obs_sink_schema.clone());
+            patch_bay.register_module(obs_source.schema());
+            if let Err(e) = module_host.spawn(SourceAdapter::new(obs_source), 100) {
+                log::error!("Failed to spawn OBS events: {}", e);
+            }
+            if let Err(e) = module_host.spawn(SinkAdapter::new(obs_sink), 100) {
+                log::error!("Failed to spawn OBS control: {}", e);
+            } else if let Some(sender) = module_host.get_sender("obs_control") {
+                tile_registry.register(tiles::SchemaTile::new(
+                    "obs_control",
+                    &obs_sink_schema.name,
+                    obs_sink_schema.settings_schema,
+                    sender,
+                ));
+            }
+        }
+        Err(e) => log::error!("Failed to start OBS bridge: {}", e),
+    }
+
+    // Video share: publishes the screen or one tile as an NDI source; the
+    // view reads frames back only while sharing is on
+    let video_share = match video_share::VideoShare::start(Default::default()) {
+        Ok(share) => {
+            let video_share_sink = video_share::VideoShareSink::new("video_share", share.clone());
+            let video_share_schema = video_share_sink.schema();
+            patch_bay.register_module(video_share_schema.clone());
+            if let Err(e) = module_host.spawn(SinkAdapter::new(video_share_sink), 100) {
+                log::error!("Failed to spawn video share: {}", e);
+            } else if let Some(sender) = module_host.get_sender("video_share") {
+                tile_registry.register(tiles::SchemaTile::new(
+                    "video_share",
+                    &video_share_schema.name,
+                    video_share_schema.settings_schema,
+                    sender,
+                ));
+            }
+            Some(share)
+        }
+        Err(e) => {
+            log::error!("Failed to start video share: {}", e);
+            None
+        }
+    };
+
+    // Broadcast: streams patched audio to Icecast or RTMP; the tile shows
+    // connection and buffer state
+    let broadcast_settings = broadcast::BroadcastSettings::new();
+    match broadcast::BroadcastSink::new("broadcast", broadcast_settings.clone()) {
+        Ok(broadcast_sink) => {
+            patch_bay.register_module(broadcast_sink.schema());
+            if let Err(e) = module_host.spawn(SinkAdapter::new(broadcast_sink), 100) {
+                log::error!("Failed to spawn broadcast sink: {}", e);
+            } else {
+                tile_registry.register(broadcast::BroadcastTile::new(
+                    "broadcast",
+                    broadcast_settings,
+                ));
+            }
+        }
+        Err(e) => log::error!("Failed to start broadcast sink: {}", e),
+    }
+
+    // Chat bridge: posts patched text to Matrix or Discord and feeds the
+    // room's messages back in as text
+    match chat_bridge::spawn("chat_bridge", "chat_messages", Default::default()) {
+        Ok((chat_sink, chat_source)) => {
+            let chat_sink_schema = chat_sink.schema();
+            patch_bay.register_module(chat_sink_schema.clone());
+            patch_bay.register_module(chat_source.schema());
+            if let Err(e) = module_host.spawn(SourceAdapter::new(chat_source), 100) {
+                log::error!("Failed to spawn chat messages: {}", e);
+            }
+            if let Err(e) = module_host.spawn(SinkAdapter::new(chat_sink), 100) {
+                log::error!("Failed to spawn chat bridge: {}", e);
+            } else if let Some(sender) = module_host.get_sender("chat_bridge") {
+                tile_registry.register(tiles::SchemaTile::new(
+                    "chat_bridge",
+                    &chat_sink_schema.name,
+                    chat_sink_schema.settings_schema,
+                    sender,
+                ));
+            }
+        }
+        Err(e) => log::error!("Failed to start chat bridge: {}", e),
+    }
+
+    // Signal logging to SQLite/Postgres; idle until a database is chosen
+    match db_sink::DbSink::spawn("db_sink", Default::default()) {
+        Ok(db_sink) => {
+            let db_schema = db_sink.schema();
+            patch_bay.register_module(db_schema.clone());
+            if let Err(e) = module_host.spawn(SinkAdapter::new(db_sink), 100) {
+                log::error!("Failed to spawn database sink: {}", e);
+            } else if let Some(sender) = module_host.get_sender("db_sink") {
+                tile_registry.register(tiles::SchemaTile::new(
+                    "db_sink",
+                    &db_schema.name,
+                    db_schema.settings_schema,
+                    sender,
+                ));
+            }
+        }
+        Err(e) => log::error!("Failed to start database sink: {}", e),
+    }
+
+    // Numeric export to InfluxDB/VictoriaMetrics; idle until a URL is set
+    match timeseries::TimeseriesSink::spawn("timeseries", Default::default()) {
+        Ok(timeseries) => {
+            let timeseries_schema = timeseries.schema();
+            patch_bay.register_module(timeseries_schema.clone());
+            if let Err(e) = module_host.spawn(SinkAdapter::new(timeseries), 100) {
+                log::error!("Failed to spawn time-series export: {}", e);
+            } else if let Some(sender) = module_host.get_sender("timeseries") {
+                tile_registry.register(tiles::SchemaTile::new(
+                    "timeseries",
+                    &timeseries_schema.name,
+                    timeseries_schema.settings_schema,
+                    sender,
+                ));
+            }
+        }
+        Err(e) => log::error!("Failed to start time-series export: {}", e),
+    }
     if sherpa_ready {
         if let Err(e) = patch_bay.connect("audio_dsp", "audio_out", "speech_to_text", "audio_in") {
             log::error!("Failed to connect processed audio to speech-to-text: {e}");
@@ -375,6 +1009,102 @@ fn model(app: &App) -> Model {
         log::error!("Failed to spawn audio viz sink: {}", e);
     }
 
+    // Shader canvas: WGSL on the window's device, uniforms from patched signals
+    let canvas_inputs = shader_canvas::CanvasInputs::new();
+    tile_registry.register(shader_canvas::ShaderCanvasTile::new(
+        "shader_canvas",
+        app.main_window().device_queue_pair().clone(),
+        canvas_inputs.clone(),
+    ));
+    let canvas_sink = shader_canvas::ShaderCanvasSink::new("shader_canvas", canvas_inputs);
+    patch_bay.register_module(canvas_sink.schema());
+    if let Err(e) = module_host.spawn(SinkAdapter::new(canvas_sink), 100) {
+        log::error!("Failed to spawn shader canvas sink: {}", e);
+    }
+
+    // Particle field: bursts and emission driven by patched events
+    let particle_events = particles::ParticleEvents::new();
+    tile_registry.register(
+        particles::ParticleTile::new("particles", particle_events.clone())
+            .with_seeds(seeds.clone()),
+    );
+    let particle_sink = particles::ParticleSink::new("particles", particle_events);
+    patch_bay.register_module(particle_sink.schema());
+    if let Err(e) = module_host.spawn(SinkAdapter::new(particle_sink), 100) {
+        log::error!("Failed to spawn particle sink: {}", e);
+    }
+
+    // Ticker marquee for live text (STT finals, LLM replies)
+    let ticker_state = text_tools::TickerState::new();
+    tile_registry.register(tiles::ticker::TickerTile::new(
+        "ticker",
+        ticker_state.clone(),
+    ));
+    let ticker_sink = text_tools::TickerSink::new("ticker", ticker_state);
+    patch_bay.register_module(ticker_sink.schema());
+    if let Err(e) = module_host.spawn(SinkAdapter::new(ticker_sink), 100) {
+        log::error!("Failed to spawn ticker sink: {}", e);
+    }
+
+    // Ctrl+N starts a session folder for file-writing modules, Ctrl+E ends
+    // it, Ctrl+M marks a moment in it
+    let session = magnolia_core::SessionState::with_seeds(layout.config.session.clone(), seeds);
+    patch_bay.register_module(magnolia_core::ModuleSchema {
+        id: SESSION_MODULE.to_string(),
+        name: "Session".to_string(),
+        description:
+            "Announces session starts, ends and markers as session_start / session_end / marker intents"
+                .to_string(),
+        ports: vec![magnolia_core::Port {
+            id: "control_out".to_string(),
+            label: "Boundaries & Markers".to_string(),
+            data_type: magnolia_core::DataType::Control,
+            direction: magnolia_core::PortDirection::Output,
+        }],
+        settings_schema: None,
+        docs: None,
+    });
+
+    // Session transcript with scrollback and export
+    let transcript =
+        std::sync::Arc::new(std::sync::Mutex::new(caption_state::Transcript::default()));
+    tile_registry.register(
+        tiles::transcript::TranscriptTile::new("transcript", transcript.clone())
+            .with_session(session.clone()),
+    );
+    let transcript_sink = caption_state::TranscriptSink::new("transcript", transcript);
+    patch_bay.register_module(transcript_sink.schema());
+    if let Err(e) = module_host.spawn(SinkAdapter::new(transcript_sink), 100) {
+        log::error!("Failed to spawn transcript sink: {}", e);
+    }
+
+    // Steadies partials patched through it on their way to a caption view
+    let stabilizer = caption_state::CaptionStabilizerProcessor::new("caption_stabilizer");
+    patch_bay.register_module(stabilizer.schema());
+    if let Err(e) = module_host.spawn(ProcessorAdapter::new(stabilizer), 100) {
+        log::error!("Failed to spawn caption stabilizer: {}", e);
+    }
+
+    // Recorded audio on loop, for demos and testing without a microphone
+    let replay_wav = std::env::var("MAGNOLIA_REPLAY_WAV").unwrap_or_else(|_| DEMO_WAV.to_string());
+    match audio_replay::WavReplaySource::new("wav_replay", replay_wav.clone().into(), 20, true) {
+        Ok(replay) => {
+            let replay = replay.with_mode(audio_replay::ReplayMode::Loop);
+            patch_bay.register_module(replay.schema());
+            if let Err(e) = module_host.spawn(SourceAdapter::new(replay), 100) {
+                log::error!("Failed to spawn WAV replay: {}", e);
+            }
+        }
+        Err(e) => log::info!("WAV replay unavailable ({}): {}", replay_wav, e),
+    }
+
+    // Latest text to a file
+    let save_file_sink = text_tools::SaveFileSink::default().with_session(session.clone());
+    patch_bay.register_module(save_file_sink.schema());
+    if let Err(e) = module_host.spawn(SinkAdapter::new(save_file_sink), 100) {
+        log::error!("Failed to spawn save file sink: {}", e);
+    }
+
     if let Some(output_sink) = audio_output_sink {
         let output_schema = output_sink.schema();
         patch_bay.register_module(output_schema);
@@ -387,93 +1117,78 @@ fn model(app: &App) -> Model {
 
     // Extract sleep state before moving layout into Model
     let initial_sleep_state = layout.config.is_sleeping;
+    let initial_power_profile = layout.config.power_profile;
+
+    // Host GPU services for plugins: textures stay host-owned and plugins only
+    // see opaque handles, so nothing dangles across hot-reload.
+    let host_gpu = HostGpu::new(Box::new(WgpuBackend::new(
+        app.main_window().device_queue_pair().clone(),
+        module_host.texture_map.clone(),
+        module_host.view_map.clone(),
+    )));
 
     // Load and spawn plugins
     let mut plugin_manager = PluginManager::new();
 
     // Enable hot-reload (in dev mode)
-    if let Err(e) = plugin_manager.enable_hot_reload() {
+    if safe_mode {
+        log::info!("Plugins disabled in safe mode");
+    } else if let Err(e) = plugin_manager.enable_hot_reload() {
         log::warn!("Failed to enable hot-reload: {}", e);
     }
 
     // Load existing plugins
-    log::info!("Discovering and loading plugins...");
-    {
+    let plugins = if safe_mode {
+        Vec::new()
+    } else {
+        log::info!("Discovering and loading plugins...");
         // Safe to unwrap here as we are single threaded in init
         let mut loader = plugin_manager.loader.write().unwrap();
         if let Err(e) = unsafe { loader.discover().and_then(|_| loader.load_all()) } {
             log::error!("Failed to load plugins: {}", e);
         }
+        loader.drain_loaded()
+    };
 
-        // Spawn plugins
-        for plugin in loader.drain_loaded() {
-            let adapter = PluginModuleAdapter::new(plugin);
-            let id = adapter.id().to_string();
-            let name = adapter.name().to_string();
-            let adapter_schema = adapter.schema(); // Clones ModuleSchema
-            let settings_json = adapter_schema.settings_schema.clone(); // Option<Value>
-
-            log::info!("Spawning plugin module: {}", id);
-
-            // Register module schema in PatchBay
-            patch_bay.register_module(adapter_schema);
-
-            if let Err(e) = module_host.spawn(adapter, 100) {
-                log::error!("Failed to spawn plugin: {}", e);
-            } else {
-                // Register Visual Tile wrapper to bridge settings UI
-                if let Some(sender) = module_host.get_sender(&id) {
-                    let tile = tiles::SchemaTile::new(&id, &name, settings_json, sender);
-                    tile_registry.register(tile);
-                    log::info!("Registered SchemaTile for plugin: {}", id);
-                }
-            }
-        }
-
-        // Broadcast GPU Context to all plugins
-        // We do this after spawning to ensure they receive it in their inbox.
-        let window = app.main_window();
-        let device = window.device();
-        let queue = window.queue();
-
-        let gpu_signal = Signal::GpuContext {
-            device: device as *const _ as usize,
-            queue: queue as *const _ as usize,
-        };
-
-        // We need to send this to all active modules.
-        // ModuleHost doesn't expose a list of IDs easily?
-        // We know we just spawned them from `loader.loaded()`.
-        // Let's iterate `plugin_manager.loader` again? No, drained.
-        // We should have collected IDs or use `patch_bay`?
-        // ModuleHost has internal map.
-        // Let's just broadcast to known "kamea" for now or verify ModuleHost API.
-        // magnolia_core::ModuleHost::send_signal takes id.
-        // Let's assume we can get IDs from PatchBay.
-
-        for module in patch_bay.get_modules() {
-            // Clone signal for each send
-            let sig = match gpu_signal {
-                Signal::GpuContext { device, queue } => Signal::GpuContext { device, queue },
-                _ => Signal::Pulse,
-            };
-            let _ = module_host.send_signal(&module.id, sig);
-        }
+    // Spawn plugins
+    for plugin in plugins {
+        spawn_plugin_module(
+            plugin,
+            &host_gpu,
+            &mut patch_bay,
+            &mut module_host,
+            &mut tile_registry,
+            &mut plugin_manager,
+            &mut layout,
+        );
     }
 
     // Apply saved patches from layout config
-    for patch in &layout.config.patches {
-        if let Err(e) = patch_bay.connect(
-            &patch.source_module,
-            &patch.source_port,
-            &patch.sink_module,
-            &patch.sink_port,
+    let patch_issues = apply_saved_patches(&mut patch_bay, &layout.config.patches);
+
+    module_host.activate_patched(&patch_bay);
+    log::info!("Startup: {}", module_host.finish_startup());
+
+    // Status page, e.g. to check an installation from a phone
+    let web_monitor = if layout.config.web.enabled {
+        match web_monitor::WebMonitor::start(
+            &layout.config.web.bind,
+            layout.config.access.clone(),
         ) {
-            log::warn!("Failed to apply patch {}: {}", patch.id, e);
+            Ok(monitor) => {
+                log::info!("Web monitor on {}", monitor.url());
+                Some(monitor)
+            }
+            Err(e) => {
+                log::error!("Web monitor on {}: {}", layout.config.web.bind, e);
+                None
+            }
         }
-    }
+    } else {
+        None
+    };
 
-    let model = Model {
+    let mut model = Model {
         _receiver: rx_ui,
         router_rx: rx_router,
         // egui removed
@@ -487,6 +1202,7 @@ fn model(app: &App) -> Model {
         is_sleeping: initial_sleep_state,
 
         module_host,
+        host_gpu,
         plugin_manager,
         tile_registry,
         _compositor: tiles::Compositor::new(app),
@@ -497,11 +1213,32 @@ fn model(app: &App) -> Model {
         caption_state,
         stt_metrics,
         modal_anims: std::collections::HashMap::new(),
+        thumbnails: ui::thumbnails::TileThumbnails::new(),
+        video_share,
+        frame_capturer: wgpu::TextureCapturer::default(),
+        power_monitor: magnolia_core::PowerMonitor::new(),
+        main_window: _window_id,
+        output_windows,
+        power_profile: initial_power_profile,
+        web_monitor,
+        audio_output_state,
+        plugin_index_rx: None,
+        model_download_rx,
+        sherpa_options,
+        session,
+        focus_follow: focus_follow::FocusFollow::new(),
+        announcer: a11y::Announcer::new(),
+        startup,
+        patch_issues,
     };
 
     // Apply saved tile settings from layout config
     apply_tile_settings(&model.tile_registry, &model.layout);
 
+    if model.layout.config.session.auto_start {
+        start_session(&mut model);
+    }
+
     /*
     // Connect audio stream to AudioVisTile if available
     if let Some(rx) = model.audio_stream_rx.take() {
@@ -512,6 +1249,117 @@ fn model(app: &App) -> Model {
     model
 }
 
+/// Open the layout's `[[windows]]`; each draws only the tiles assigned to it
+fn open_output_windows(
+    app: &App,
+    config: &magnolia_core::LayoutConfig,
+) -> std::collections::HashMap<WindowId, String> {
+    let monitors = app.available_monitors();
+    let mut windows = std::collections::HashMap::new();
+    for window in &config.windows {
+        let mut builder = app
+            .new_window()
+            .view(view_output)
+            .key_pressed(key_pressed)
+            .size(900, 600)
+            .title(window.title.clone().unwrap_or_else(|| window.id.clone()));
+        if window.fullscreen {
+            let monitor = match window.monitor {
+                Some(index) => monitors.get(index).cloned().or_else(|| {
+                    log::warn!(
+                        "Window {}: no monitor {}, using the primary one",
+                        window.id,
+                        index
+                    );
+                    app.primary_monitor()
+                }),
+                None => app.primary_monitor(),
+            };
+            builder = builder.fullscreen_with(Some(Fullscreen::Borderless(monitor)));
+        }
+        match builder.build() {
+            Ok(id) => {
+                windows.insert(id, window.id.clone());
+            }
+            Err(e) => log::error!("Failed to open window {}: {}", window.id, e),
+        }
+    }
+    windows
+}
+
+/// Spawn a plugin module and register a fresh SchemaTile for it.
+///
+/// If a module with the same ID is already running (hot-reload), it is shut
+/// down first and its tile replaced, so the tile never holds a sender to a
+/// dead module. Tile settings saved by an older build of the plugin are
+/// upgraded in `layout` first. Returns the module ID on success.
+fn spawn_plugin_module(
+    plugin: magnolia_core::PluginLibrary,
+    host_gpu: &std::sync::Arc<HostGpu>,
+    patch_bay: &mut PatchBay,
+    module_host: &mut magnolia_core::ModuleHost,
+    tile_registry: &mut TileRegistry,
+    plugin_manager: &mut PluginManager,
+    layout: &mut Layout,
+) -> Option<String> {
+    let path = plugin.path.clone();
+    let mut adapter = PluginModuleAdapter::new(plugin);
+    adapter.attach_gpu(host_gpu);
+    let id = adapter.id().to_string();
+    let name = adapter.name().to_string();
+    let adapter_schema = adapter.schema(); // Clones ModuleSchema
+    let settings_json = adapter_schema.settings_schema.clone(); // Option<Value>
+    let settings_version = adapter.settings_version();
+
+    let mut migrated = false;
+    for tile in layout.config.tiles.iter_mut().filter(|t| t.module == id) {
+        let old_version = tile.settings.version;
+        if tile.settings.migrate(settings_version, |old, config| {
+            adapter.migrate_settings(old, config)
+        }) {
+            log::info!(
+                "Upgraded settings of tile {} from version {} to {}",
+                tile.id,
+                old_version,
+                settings_version
+            );
+            migrated = true;
+        }
+    }
+    if migrated {
+        layout.save();
+    }
+
+    if module_host.get_sender(&id).is_some() {
+        log::info!("Replacing module: {}", id);
+        if let Err(e) = module_host.shutdown_module(&id) {
+            log::warn!("Error shutting down old module {}: {}", id, e);
+        }
+        tile_registry.unregister(&id);
+    }
+
+    log::info!("Spawning plugin module: {}", id);
+
+    // Register module schema in PatchBay
+    patch_bay.register_module(adapter_schema);
+
+    if let Err(e) = module_host.spawn(adapter, 100) {
+        log::error!("Failed to spawn plugin {}: {}", id, e);
+        tile_registry.mark_unavailable(&id, "failed to start");
+        return None;
+    }
+
+    // Register Visual Tile wrapper to bridge settings UI
+    if let Some(sender) = module_host.get_sender(&id) {
+        let tile = tiles::SchemaTile::new(&id, &name, settings_json, sender)
+            .with_settings_version(settings_version);
+        tile_registry.register(tile);
+        log::info!("Registered SchemaTile for plugin: {}", id);
+    }
+    plugin_manager.track_module(&path, &id);
+    Some(id)
+}
+
 /// Apply saved settings from layout config to all tiles in registry
 fn apply_tile_settings(registry: &tiles::TileRegistry, layout: &Layout) {
     for tile in &layout.config.tiles {
@@ -536,6 +1384,7 @@ fn save_tile_settings(registry: &tiles::TileRegistry, layout: &mut Layout, tile_
         let settings = registry.get_settings(&tile.module);
         if settings != serde_json::Value::Null {
             tile.settings.config = settings;
+            tile.settings.version = registry.settings_version(&tile.module);
             log::info!("Saved settings for tile {}", tile_id);
 
             // Save layout to disk
@@ -570,6 +1419,8 @@ fn update_modal_anims(model: &mut Model) {
     let is_global_settings = model.modal_stack.is_global_settings_open();
     let is_patch_bay = model.modal_stack.is_patch_bay_open();
     let is_layout_manager = model.modal_stack.is_layout_manager_open();
+    let is_plugin_index = model.modal_stack.is_plugin_index_open();
+    let is_module_docs = model.modal_stack.is_module_docs_open();
 
     let is_add_tile_picker = model.modal_stack.get_add_tile_picker().is_some();
 
@@ -584,6 +1435,16 @@ fn update_modal_anims(model: &mut Model) {
         ModalAnimKey::LayoutManager,
         is_layout_manager,
     );
+    sync_anim(
+        &mut model.modal_anims,
+        ModalAnimKey::PluginIndex,
+        is_plugin_index,
+    );
+    sync_anim(
+        &mut model.modal_anims,
+        ModalAnimKey::ModuleDocs,
+        is_module_docs,
+    );
 
     sync_anim(
         &mut model.modal_anims,
@@ -592,9 +1453,72 @@ fn update_modal_anims(model: &mut Model) {
     );
 }
 
+/// Draw thumbnails of the tiles the open picker or layout manager shows,
+/// and drop them once neither is open
+fn update_thumbnails(app: &App, model: &mut Model) {
+    let modules = if model.modal_stack.get_add_tile_picker().is_some() {
+        model.tile_registry.list_tiles()
+    } else if let Some(state) = model.modal_stack.get_layout_manager_state_mut() {
+        ui::layout_manager::sync_preview(state);
+        ui::layout_manager::preview_modules(state)
+    } else {
+        model.thumbnails.clear();
+        return;
+    };
+    let Some(window) = app.window(model.main_window) else {
+        return;
+    };
+    let ctx = RenderContext {
+        time: model.start_time,
+        frame_count: model.frame_count,
+        is_selected: false,
+        is_maximized: false,
+        power_profile: model.power_profile,
+        tile_settings: None,
+    };
+    model
+        .thumbnails
+        .refresh(&window, &model.tile_registry, &modules, &ctx);
+}
+
+/// Transcript lines the status page shows
+const DASHBOARD_TRANSCRIPT_LINES: usize = 20;
+
+fn publish_dashboard(model: &Model) {
+    let Some(monitor) = &model.web_monitor else {
+        return;
+    };
+    let mut dashboard = web_monitor::Dashboard::collect(&model.module_host, &model.patch_bay);
+    dashboard.uptime_secs = model.start_time.elapsed().as_secs();
+    dashboard.levels.insert(
+        "audio_output".to_string(),
+        model.audio_output_state.level_milli() as f32 / 1000.0,
+    );
+    if let Ok(captions) = model.caption_state.lock() {
+        let skip = captions
+            .committed
+            .len()
+            .saturating_sub(DASHBOARD_TRANSCRIPT_LINES);
+        dashboard.transcript = captions.committed[skip..]
+            .iter()
+            .chain(&captions.provisional)
+            .map(|segment| segment.text.clone())
+            .collect();
+    }
+    dashboard.announcements = model.announcer.recent();
+    monitor.publish(dashboard);
+}
+
+/// Closing the window is an orderly quit, like Ctrl+Q
+fn exit(_app: &App, mut model: Model) {
+    model.startup.finish();
+}
+
 fn update(_app: &App, model: &mut Model, _update: Update) {
     // Update Layout dimensions
-    model.layout.update(_app.window_rect());
+    if let Some(window) = _app.window(model.main_window) {
+        model.layout.update(window.rect());
+    }
 
     // Smooth Animation for tile maximize/minimize
     let maximized_tile = model
@@ -620,12 +1544,64 @@ fn update(_app: &App, model: &mut Model, _update: Update) {
 
     // Update modal animations for fullscreen modals
     update_modal_anims(model);
+    update_thumbnails(_app, model);
+    update_focus_follow(model);
+    let screen = describe_screen(model);
+    model
+        .announcer
+        .update(screen, &model.layout.config.accessibility);
+    poll_plugin_index(model);
+    poll_model_download(model);
+
+    // Lazy modules start once patched or maximized
+    model.module_host.activate_patched(&model.patch_bay);
+    let audio_graph = &model.layout.config.audio_graph;
+    if audio_graph.enabled {
+        model
+            .module_host
+            .compile_audio_graphs(&model.patch_bay, audio_graph.block_frames);
+    } else {
+        model.module_host.stop_audio_graphs();
+    }
+    let provenance = model.module_host.provenance();
+    if provenance.is_enabled() != model.layout.config.provenance.enabled {
+        provenance.set_enabled(model.layout.config.provenance.enabled);
+    }
+    if let Some(tile_id) = model.modal_stack.get_maximized_tile() {
+        if let Some(tile) = model.layout.config.tiles.iter().find(|t| t.id == tile_id) {
+            model.module_host.activate(&tile.module);
+        }
+    }
+
+    // Battery and heat can lower the profile chosen in settings
+    let reading = model.power_monitor.poll();
+    let profile = model
+        .layout
+        .config
+        .power
+        .profile(model.layout.config.power_profile, reading);
+    if profile != model.power_profile {
+        log::info!(
+            "Power profile {:?} -> {:?} ({:?})",
+            model.power_profile,
+            profile,
+            reading
+        );
+        model.power_profile = profile;
+    }
 
     // Update tile registry (extends to new tiles with render_monitor/render_controls)
     model
         .tile_registry
-        .update_all_with_power(model.layout.config.power_profile, model.frame_count);
+        .update_all_with_power(model.power_profile, model.frame_count, |id| {
+            model.layout.config.power.importance(id)
+        });
     model.frame_count += 1;
+    model.startup.update(model.start_time.elapsed());
+
+    if model.frame_count % 30 == 0 {
+        publish_dashboard(model);
+    }
 
     if model.frame_count % 300 == 0 {
         if let Some(metrics) = &model.stt_metrics {
@@ -644,35 +1620,54 @@ fn update(_app: &App, model: &mut Model, _update: Update) {
     // (Audio tiles update independently; module runtime handles audio pipeline)
 
     // Handle Plugin Hot-Reload
-    while let Ok(path) = model.plugin_manager.reload_rx.try_recv() {
-        log::info!("Hot-reload trigger for: {}", path.display());
-        match model.plugin_manager.reload_plugin(&path) {
-            Ok(plugin) => {
-                let adapter = PluginModuleAdapter::new(plugin);
-                let id = adapter.id().to_string(); // Copy ID
-                log::info!("Replacng module: {}", id);
-
-                // Shutdown old module
-                if let Err(e) = model.module_host.shutdown_module(&id) {
-                    log::warn!("Error shutting down old module {}: {}", id, e);
-                }
-
-                // Determine execution model (Thread pool? Dedicated?)
-                // Defaulting to dedicated for plugins.
-                // We need to re-spawn.
-                if let Err(e) = model.module_host.spawn(adapter, 100) {
-                    log::error!("Failed to respawn refreshed plugin {}: {}", id, e);
-                } else {
-                    log::info!("Successfully hot-reloaded plugin: {}", id);
+    while let Ok(event) = model.plugin_manager.events_rx.try_recv() {
+        match event {
+            PluginEvent::Changed(path) => {
+                log::info!("Hot-reload trigger for: {}", path.display());
+                match model.plugin_manager.reload_plugin(&path) {
+                    Ok(plugin) => {
+                        if let Some(id) = spawn_plugin_module(
+                            plugin,
+                            &model.host_gpu,
+                            &mut model.patch_bay,
+                            &mut model.module_host,
+                            &mut model.tile_registry,
+                            &mut model.plugin_manager,
+                            &mut model.layout,
+                        ) {
+                            log::info!("Successfully hot-reloaded plugin: {}", id);
+                            apply_tile_settings(&model.tile_registry, &model.layout);
+                        }
+                    }
+                    Err(e) => {
+                        // The previous instance (if any) keeps running with the old code.
+                        log::error!("Failed to reload plugin from {}: {}", path.display(), e);
+                    }
                 }
             }
-            Err(e) => {
-                log::error!("Failed to reload plugin from {}: {}", path.display(), e);
+            PluginEvent::Removed(path) => {
+                if let Some(id) = model.plugin_manager.untrack(&path) {
+                    log::info!("Plugin {} removed ({})", id, path.display());
+                    if let Err(e) = model.module_host.shutdown_module(&id) {
+                        log::warn!("Error shutting down removed module {}: {}", id, e);
+                    }
+                    model.patch_bay.unregister_module(&id);
+                    model.tile_registry.mark_unavailable(&id, "plugin unloaded");
+                }
             }
         }
     }
 
-    // Process Router Signals (From Plugins)
+    // The input only splits out its channels while one of them is patched
+    model.audio_input_settings.set_split_channels(
+        model
+            .patch_bay
+            .get_outgoing_patches("audio_input")
+            .iter()
+            .any(|patch| patch.source_port != "audio_out"),
+    );
+
+    // Process Router Signals (From Plugins)
     while let Ok(routed) = model.router_rx.try_recv() {
         if routed.source_id == "speech_to_text" {
             if let Signal::Computed { content, .. } = &routed.signal {
@@ -699,8 +1694,31 @@ fn update(_app: &App, model: &mut Model, _update: Update) {
             // Compositor can lookup via handle.id.
         }
 
+        // Monitoring passes the input to the output without a patch, unless
+        // one already carries it there
+        if routed.source_id == "audio_input"
+            && !model
+                .patch_bay
+                .get_outgoing_patches("audio_input")
+                .iter()
+                .any(|patch| patch.sink_module == audio_input::MONITOR_SINK)
+        {
+            if let Some(signal) =
+                audio_input::monitor_signal(&model.audio_input_settings, &routed.signal)
+            {
+                let _ = model
+                    .module_host
+                    .send_signal(audio_input::MONITOR_SINK, signal);
+            }
+        }
+
         model.module_host.route_signal(&model.patch_bay, routed);
     }
+    if model.audio_input_settings.is_monitoring() {
+        model
+            .audio_input_settings
+            .set_monitor_latency_us(model.audio_output_state.latency_us());
+    }
 
     // GUI update removed (egui removed)
 
@@ -719,26 +1737,128 @@ fn mouse_moved(_app: &App, _model: &mut Model, _pos: Point2) {
     // Mouse movement handling - keyboard-first navigation only
 }
 
-fn key_pressed(_app: &App, model: &mut Model, key: Key) {
+/// Reinitialize the module behind a tile after an error.
+///
+/// Tiles that own their backend (audio devices) rebuild it themselves; plugin
+/// modules are reloaded from disk and respawned.
+fn retry_module(model: &mut Model, module: &str) {
+    if model.tile_registry.retry(module) {
+        log::info!("Retrying module: {}", module);
+        return;
+    }
+
+    let Some(path) = model
+        .plugin_manager
+        .path_for(module)
+        .map(|p| p.to_path_buf())
+    else {
+        log::info!("Module {} has no retry action", module);
+        return;
+    };
+    match model.plugin_manager.reload_plugin(&path) {
+        Ok(plugin) => {
+            if spawn_plugin_module(
+                plugin,
+                &model.host_gpu,
+                &mut model.patch_bay,
+                &mut model.module_host,
+                &mut model.tile_registry,
+                &mut model.plugin_manager,
+                &mut model.layout,
+            )
+            .is_some()
+            {
+                log::info!("Restarted plugin module: {}", module);
+                apply_tile_settings(&model.tile_registry, &model.layout);
+            }
+        }
+        Err(e) => log::error!("Failed to restart plugin {}: {}", module, e),
+    }
+}
+
+fn key_pressed(app: &App, model: &mut Model, key: Key) {
+    let ctrl = app.keys.mods.ctrl();
+    let shift = app.keys.mods.shift();
+    handle_key_press(app, model, key, ctrl, shift);
+}
+
+/// Replay a recorded macro's keys as if typed
+fn play_macro(app: &App, model: &mut Model, steps: &[String]) {
+    model.keyboard_nav.replaying = true;
+    for step in steps {
+        match input::parse_key_step(step) {
+            Some((key, ctrl, shift)) => handle_key_press(app, model, key, ctrl, shift),
+            None => log::warn!("Skipping macro step '{}': not a key", step),
+        }
+    }
+    model.keyboard_nav.replaying = false;
+}
+
+fn handle_key_press(app: &App, model: &mut Model, key: Key, ctrl: bool, shift: bool) {
     // === INPUT ROUTING GUARD ===
     // Egui keyboard guard removed
 
-    let ctrl = _app.keys.mods.ctrl();
-    let shift = _app.keys.mods.shift();
+    // Only route to a tile if Maximized is the top modal (not covered by PatchBay etc)
+    let max_tile_id = if let Some(crate::ui::modals::ModalState::Maximized { tile_id }) =
+        model.modal_stack.top_mut()
+    {
+        Some(tile_id.clone())
+    } else {
+        None
+    };
+
+    // === RETRY (Ctrl+R on a maximized tile, unless it ends a recording) ===
+    if ctrl && key == Key::R && model.keyboard_nav.macro_state == input::MacroState::Idle {
+        if let Some(max_id) = &max_tile_id {
+            let module = model
+                .layout
+                .config
+                .tiles
+                .iter()
+                .find(|t| &t.id == max_id)
+                .map(|t| t.module.clone());
+            if let Some(module) = module {
+                retry_module(model, &module);
+                return;
+            }
+        }
+    }
+
+    // === DOCS (F1 on a maximized tile, before it can play a macro) ===
+    if key == Key::F1 && !ctrl && model.keyboard_nav.macro_state == input::MacroState::Idle {
+        if let Some(max_id) = &max_tile_id {
+            if let Some(tile) = model.layout.config.tiles.iter().find(|t| &t.id == max_id) {
+                model
+                    .modal_stack
+                    .push(ModalState::ModuleDocs(ModuleDocsState {
+                        module: tile.module.clone(),
+                        scroll: 0,
+                    }));
+                return;
+            }
+        }
+    }
+
+    // === MACROS (Ctrl+R records, F1-F12 play) ===
+    match model
+        .keyboard_nav
+        .handle_macro_key(key, ctrl, shift, &mut model.layout.config)
+    {
+        input::MacroInput::Pass => {}
+        input::MacroInput::Consumed => return,
+        input::MacroInput::Bound => {
+            model.layout.save();
+            return;
+        }
+        input::MacroInput::Play(steps) => {
+            play_macro(app, model, &steps);
+            return;
+        }
+    }
 
     // === MAXIMIZED TILE INPUT ROUTING (tile-local controls) ===
     // If a tile is maximized AND it is the top modal, give it input.
     if key != Key::Escape && !ctrl {
-        // Only route if Maximized is the top modal (not covered by PatchBay etc)
-        // We use a scope to limit the borrow of modal_stack
-        let max_tile_id = if let Some(crate::ui::modals::ModalState::Maximized { tile_id }) =
-            model.modal_stack.top_mut()
-        {
-            Some(tile_id.clone())
-        } else {
-            None
-        };
-
         if let Some(max_id) = max_tile_id {
             if let Some(tile_cfg) = model.layout.config.tiles.iter().find(|t| t.id == max_id) {
                 let handled = model
@@ -755,7 +1875,12 @@ fn key_pressed(_app: &App, model: &mut Model, key: Key) {
     // Route input to active modals (Patch Bay, Global Settings)
     // Return early if consumed.
     if let Some(mut state) = model.modal_stack.get_patch_bay_state_mut() {
-        if ui::patch_bay::handle_key(key, &mut state, &mut model.patch_bay) {
+        let traffic = model.module_host.routing_metrics().patch_traffic();
+        let trace = ui::patch_bay::Trace {
+            provenance: &model.module_host.provenance(),
+            now_us: model.module_host.clock().now_us(),
+        };
+        if ui::patch_bay::handle_key(key, &mut state, &mut model.patch_bay, &traffic, trace) {
             return;
         }
         // If Escape was not consumed (returned false), close the modal
@@ -793,9 +1918,49 @@ fn key_pressed(_app: &App, model: &mut Model, key: Key) {
         }
     }
 
+    if let Some(state) = model.modal_stack.get_layout_manager_state_mut() {
+        if key != Key::Escape {
+            if let Some(action) = ui::layout_manager::handle_key(key, state) {
+                run_layout_manager_action(model, action);
+            }
+            return;
+        }
+    }
+
+    if let Some(state) = model.modal_stack.get_module_docs_state_mut() {
+        if key == Key::F1 {
+            model.modal_stack.pop();
+            return;
+        }
+        if key != Key::Escape {
+            let schema = model.patch_bay.get_module(&state.module);
+            let len = ui::module_docs::doc_lines(&state.module, schema).len();
+            ui::module_docs::handle_key(key, state, len);
+            return;
+        }
+    }
+
+    if let Some(state) = model.modal_stack.get_plugin_index_state_mut() {
+        if key != Key::Escape {
+            match ui::plugin_index::handle_key(key, state) {
+                Some(PluginIndexAction::Refresh) => start_plugin_index_fetch(model),
+                Some(PluginIndexAction::Install(entry)) => start_plugin_install(model, entry),
+                None => {}
+            }
+            return;
+        }
+    }
+
     // === MODAL ESC HANDLING (Generic) ===
     // If we are here, no specific modal consumed Escape.
     if key == Key::Escape {
+        // A filtered add tile picker clears its filter first
+        if model
+            .modal_stack
+            .edit_add_tile_picker_query(|query| !std::mem::take(query).is_empty())
+        {
+            return;
+        }
         // Check if a tile is maximized - close it first
         if model.modal_stack.get_maximized_tile().is_some() {
             model.modal_stack.pop();
@@ -815,8 +1980,22 @@ fn key_pressed(_app: &App, model: &mut Model, key: Key) {
 
     // === ADD TILE PICKER INPUT (captures keys while open) ===
     if let Some((col, row, selected_idx)) = model.modal_stack.get_add_tile_picker() {
-        // Keyboard-only modal: Up/Down choose, Enter confirm.
-        let available = model.tile_registry.list_tiles();
+        // Keyboard-only modal: typing filters, Up/Down choose, Enter confirm.
+        if !ctrl
+            && model
+                .modal_stack
+                .edit_add_tile_picker_query(|query| ui::search::edit_query(query, key, shift))
+        {
+            return;
+        }
+        let query = model
+            .modal_stack
+            .add_tile_picker_query()
+            .unwrap_or_default();
+        let available: Vec<String> = add_tile_choices(model, query)
+            .into_iter()
+            .map(|(module, _)| module)
+            .collect();
         if available.is_empty() {
             return;
         }
@@ -847,12 +2026,16 @@ fn key_pressed(_app: &App, model: &mut Model, key: Key) {
                         module: module_id,
                         enabled: true,
                         settings: Default::default(),
+                        window: None,
+                        z: 0,
+                        overlay: None,
                     });
                     model.layout.save();
                     model.modal_stack.close(&ModalState::AddTilePicker {
                         cursor_col: col,
                         cursor_row: row,
                         selected_idx: 0,
+                        query: String::new(),
                     });
                     // Select the new tile immediately
                     model.keyboard_nav.cursor = (col, row);
@@ -897,6 +2080,8 @@ fn key_pressed(_app: &App, model: &mut Model, key: Key) {
             }
             AppAction::QuitApp => {
                 log::info!("Quit requested via Ctrl+Q");
+                stop_session(model);
+                model.startup.finish();
                 std::process::exit(0);
             }
             AppAction::Copy { text } => {
@@ -921,9 +2106,15 @@ fn key_pressed(_app: &App, model: &mut Model, key: Key) {
             }
             AppAction::OpenPatchBay => {
                 if !model.modal_stack.is_patch_bay_open() {
-                    model
-                        .modal_stack
-                        .push(ModalState::PatchBay(PatchBayModalState::default()));
+                    let mut state = PatchBayModalState::default();
+                    if !model.patch_issues.is_empty() {
+                        state.status = Some(format!(
+                            "Left out of the saved layout: {}",
+                            model.patch_issues.join("; ")
+                        ));
+                        model.patch_issues.clear();
+                    }
+                    model.modal_stack.push(ModalState::PatchBay(state));
                 }
             }
             AppAction::OpenTileSettings { tile_id } => {
@@ -947,7 +2138,58 @@ fn key_pressed(_app: &App, model: &mut Model, key: Key) {
                 }
             }
             AppAction::OpenLayoutManager => {
-                model.modal_stack.push(ModalState::LayoutManager);
+                model
+                    .modal_stack
+                    .push(ModalState::LayoutManager(LayoutManagerState {
+                        rigs: list_rigs(false),
+                        ..Default::default()
+                    }));
+            }
+            AppAction::OpenPluginIndex => {
+                model
+                    .modal_stack
+                    .push(ModalState::PluginIndex(PluginIndexState::default()));
+                start_plugin_index_fetch(model);
+            }
+            AppAction::StartSession => start_session(model),
+            AppAction::StopSession => stop_session(model),
+            AppAction::AddMarker => add_marker(model),
+            AppAction::ToggleKiosk => {
+                let kiosk = &mut model.layout.config.kiosk;
+                kiosk.enabled = !kiosk.enabled;
+                if kiosk.enabled {
+                    model.keyboard_nav.exit_layout_mode();
+                }
+                log::info!(
+                    "Kiosk mode {}",
+                    if kiosk.enabled { "on" } else { "off" }
+                );
+                if let Some(window) = app.window(model.main_window) {
+                    apply_kiosk(&window, &model.layout.config.kiosk);
+                }
+                model.layout.save();
+            }
+            AppAction::ToggleHighContrast => {
+                let accessibility = &mut model.layout.config.accessibility;
+                accessibility.high_contrast = !accessibility.high_contrast;
+                magnolia_ui::theme::set_high_contrast(accessibility.high_contrast);
+                model.layout.save();
+            }
+            AppAction::AcceptPatchSuggestion { index } => {
+                let Some((module, suggestions)) = patch_suggestions(model) else {
+                    return;
+                };
+                let module = module.to_string();
+                if let Some(suggestion) = suggestions.get(index) {
+                    if let Err(e) = model.patch_bay.connect(
+                        &module,
+                        &suggestion.source_port,
+                        &suggestion.sink_module,
+                        &suggestion.sink_port,
+                    ) {
+                        log::warn!("Failed to make suggested patch: {}", e);
+                    }
+                }
             }
         }
     }
@@ -956,52 +2198,625 @@ fn key_pressed(_app: &App, model: &mut Model, key: Key) {
     model.selected_tile = model.keyboard_nav.selected_tile_id().map(|s| s.to_string());
 }
 
-fn raw_window_event(_app: &App, _model: &mut Model, _event: &nannou::winit::event::WindowEvent) {
-    // egui event handling removed
+/// Seed recorded by the session `--reproduce` names, given its folder or
+/// its `session.json`
+fn reproduce_seed(args: impl IntoIterator<Item = String>) -> Option<u64> {
+    let mut args = args.into_iter().skip_while(|arg| arg != "--reproduce");
+    args.next()?;
+    let Some(path) = args.next() else {
+        log::warn!("--reproduce needs a session folder or its session.json");
+        return None;
+    };
+    match magnolia_core::SessionInfo::load(std::path::Path::new(&path)) {
+        Ok(info) => match info.seed {
+            Some(seed) => {
+                log::info!("Reproducing session {} with seed {}", info.id, seed);
+                Some(seed)
+            }
+            None => {
+                log::warn!("Session {} recorded no seed to reproduce", info.id);
+                None
+            }
+        },
+        Err(e) => {
+            log::warn!("Can't reproduce {}: {}", path, e);
+            None
+        }
+    }
+}
+
+/// Start a new session, ending the running one, and announce both on the
+/// session module's control port
+fn start_session(model: &mut Model) {
+    match model.session.start(None) {
+        Ok((ended, started)) => {
+            if let Some(ended) = ended {
+                log::info!("Session {} ended", ended.id);
+                announce_session(model, ended.end_signal());
+            }
+            log::info!(
+                "Session {} started in {}",
+                started.id,
+                started.dir.display()
+            );
+            announce_session(model, started.start_signal());
+        }
+        Err(e) => log::error!("Failed to start a session: {}", e),
+    }
+}
+
+fn stop_session(model: &mut Model) {
+    match model.session.stop() {
+        Ok(Some(ended)) => {
+            log::info!("Session {} ended", ended.id);
+            announce_session(model, ended.end_signal());
+        }
+        Ok(None) => {}
+        Err(e) => log::error!("Failed to end the session: {}", e),
+    }
+}
+
+/// Stamp the moment in `session.json` and send `marker [label]` to
+/// whatever is patched to the session, e.g. Save File and the transcript
+fn add_marker(model: &mut Model) {
+    let label = match model.session.current() {
+        Some(session) => format!("Marker {}", session.markers.len() + 1),
+        None => magnolia_core::session::MARKER.to_string(),
+    };
+    match model.session.mark(&label) {
+        Ok(Some(marker)) => log::info!("{} at {} ms into the session", label, marker.offset_ms),
+        Ok(None) => {}
+        Err(e) => log::warn!("Failed to record {} in the session: {}", label, e),
+    }
+    announce_session(model, magnolia_core::session::marker_signal(&label));
+}
+
+fn announce_session(model: &Model, signal: Signal) {
+    model.module_host.route_signal(
+        &model.patch_bay,
+        RoutedSignal::new(SESSION_MODULE, "control_out", signal),
+    );
+}
+
+/// Where rigs are exported to and listed from
+const RIGS_DIR: &str = "rigs";
+/// The demo rigs offered under "Load Example"
+const EXAMPLE_RIGS_DIR: &str = "examples/rigs";
+/// What `wav_replay` plays without `MAGNOLIA_REPLAY_WAV`
+const DEMO_WAV: &str = "assets/demo/tone.wav";
+
+fn list_rigs(examples: bool) -> Vec<std::path::PathBuf> {
+    rig::list_rigs(std::path::Path::new(if examples {
+        EXAMPLE_RIGS_DIR
+    } else {
+        RIGS_DIR
+    }))
+}
+
+/// Point the layout manager at the exported rigs or the examples
+fn show_rig_list(model: &mut Model, examples: bool) {
+    if let Some(state) = model.modal_stack.get_layout_manager_state_mut() {
+        *state = LayoutManagerState {
+            rigs: list_rigs(examples),
+            browsing_examples: examples,
+            ..Default::default()
+        };
+    }
+}
+
+fn run_layout_manager_action(model: &mut Model, action: LayoutManagerAction) {
+    let installed: Vec<(String, std::path::PathBuf)> = model
+        .plugin_manager
+        .plugins()
+        .map(|(module, path)| (module.to_string(), path.to_path_buf()))
+        .collect();
+
+    let status = match action {
+        LayoutManagerAction::ShowExamples => return show_rig_list(model, true),
+        LayoutManagerAction::ShowRigs => return show_rig_list(model, false),
+        LayoutManagerAction::ShowPresets => {
+            if let Some(state) = model.modal_stack.get_layout_manager_state_mut() {
+                *state = LayoutManagerState {
+                    browsing_presets: true,
+                    ..Default::default()
+                };
+            }
+            return;
+        }
+        LayoutManagerAction::ApplyPreset(preset) => {
+            // Keeps the live patches; tiles for modules this install lacks
+            // are left out
+            let mut config = model.layout.config.clone();
+            config.patches = model.patch_bay.get_patches().to_vec();
+            preset.apply(&mut config, |module| {
+                model.tile_registry.get(module).is_some()
+            });
+            let (cols, rows) = preset.grid.dimensions();
+            let placed = config.main_tiles().count();
+            apply_rig_layout(model, config);
+            log::info!("Applied the {} grid preset", preset.name());
+            format!(
+                "Laid out {} ({}x{}) with {} tiles",
+                preset.name(),
+                cols,
+                rows,
+                placed
+            )
+        }
+        LayoutManagerAction::Export => {
+            let name = format!(
+                "rig-{}",
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or_default()
+            );
+            let out =
+                std::path::Path::new(RIGS_DIR).join(format!("{}.{}", name, rig::RIG_EXTENSION));
+            // The live patches, which include any made in the patch bay
+            let mut config = model.layout.config.clone();
+            config.patches = model.patch_bay.get_patches().to_vec();
+            let result = std::fs::create_dir_all(RIGS_DIR)
+                .map_err(anyhow::Error::from)
+                .and_then(|_| rig::export_rig(&name, &config, &installed, &out));
+            match result {
+                Ok(manifest) => {
+                    log::info!("Exported rig to {}", out.display());
+                    format!(
+                        "Exported {} ({} assets, {} plugins)",
+                        out.display(),
+                        manifest.assets.len(),
+                        manifest.plugins.len()
+                    )
+                }
+                Err(e) => {
+                    log::error!("Failed to export rig: {:#}", e);
+                    format!("Export failed: {:#}", e)
+                }
+            }
+        }
+        LayoutManagerAction::Import(path) => {
+            let assets_dir = std::path::Path::new(RIGS_DIR).join("assets");
+            let result = rig::import_rig(
+                &path,
+                &assets_dir,
+                |module| {
+                    model.patch_bay.get_module(module).is_some()
+                        || model.tile_registry.get(module).is_some()
+                },
+                &installed,
+            );
+            match result {
+                Ok(import) if !import.is_complete() => {
+                    format!("Not loaded: missing {}", import.missing_modules.join(", "))
+                }
+                Ok(import) => {
+                    let mismatches = import.plugin_mismatches.len();
+                    apply_rig_layout(model, import.manifest.layout);
+                    log::info!("Loaded rig {}", import.manifest.name);
+                    if mismatches > 0 {
+                        format!(
+                            "Loaded {}; {} plugin(s) differ from the ones it was made with",
+                            import.manifest.name, mismatches
+                        )
+                    } else {
+                        format!("Loaded {}", import.manifest.name)
+                    }
+                }
+                Err(e) => {
+                    log::error!("Failed to load rig {}: {:#}", path.display(), e);
+                    format!("Load failed: {:#}", e)
+                }
+            }
+        }
+    };
+
+    if let Some(state) = model.modal_stack.get_layout_manager_state_mut() {
+        state.rigs = list_rigs(state.browsing_examples);
+        state.status = Some(status);
+    }
+}
+
+/// Make a layout's saved patches, leaving out those that would loop, don't
+/// fit their ports or name modules that aren't loaded. Returns why each was
+/// left out.
+fn apply_saved_patches(patch_bay: &mut PatchBay, patches: &[magnolia_core::Patch]) -> Vec<String> {
+    let issues = patch_bay.validate_graph(patches);
+    for issue in &issues {
+        log::warn!("Leaving out saved patch {}", issue);
+    }
+    for patch in patches
+        .iter()
+        .filter(|patch| !issues.iter().any(|issue| issue.patch_id == patch.id))
+    {
+        if let Err(e) = patch_bay.connect_patch(patch) {
+            log::warn!("Failed to apply patch {}: {}", patch.id, e);
+        }
+    }
+    issues.iter().map(ToString::to_string).collect()
+}
+
+/// Swap in a rig's layout: its patches replace the running ones and its
+/// tile settings are applied before it's saved as layout.toml
+fn apply_rig_layout(model: &mut Model, config: magnolia_core::LayoutConfig) {
+    let old: Vec<String> = model
+        .patch_bay
+        .get_patches()
+        .iter()
+        .map(|patch| patch.id.clone())
+        .collect();
+    for id in old {
+        model.patch_bay.disconnect(&id);
+    }
+
+    model.layout.config = config;
+    model.patch_issues = apply_saved_patches(&mut model.patch_bay, &model.layout.config.patches);
+    model.module_host.activate_patched(&model.patch_bay);
+    apply_tile_settings(&model.tile_registry, &model.layout);
+    model.layout.save();
+}
+
+/// Fetch the `[plugin_index]` index off the UI thread; the result is
+/// picked up by `poll_plugin_index`
+fn start_plugin_index_fetch(model: &mut Model) {
+    let Some(state) = model.modal_stack.get_plugin_index_state_mut() else {
+        return;
+    };
+    let Some(url) = model.layout.config.plugin_index.url.clone() else {
+        state.status = Some("No index configured: set [plugin_index] url".to_string());
+        return;
+    };
+    state.busy = true;
+    let (tx, rx) = std::sync::mpsc::channel();
+    model.plugin_index_rx = Some(rx);
+    std::thread::spawn(move || {
+        let verifier = magnolia_core::PluginVerifier::new();
+        let result = plugin_index::fetch_index(&url, &verifier).map_err(|e| format!("{:#}", e));
+        let _ = tx.send(PluginIndexEvent::Fetched(result));
+    });
+}
+
+/// Download and install `entry` off the UI thread. The hot-reload watcher
+/// loads it once it lands in the plugin directory.
+fn start_plugin_install(model: &mut Model, entry: plugin_index::IndexEntry) {
+    let Some(state) = model.modal_stack.get_plugin_index_state_mut() else {
+        return;
+    };
+    state.busy = true;
+    let dir = model.layout.config.plugin_index.dir.clone();
+    let (tx, rx) = std::sync::mpsc::channel();
+    model.plugin_index_rx = Some(rx);
+    std::thread::spawn(move || {
+        let verifier = magnolia_core::PluginVerifier::new();
+        let result = plugin_index::install(&entry, &dir, &verifier)
+            .map(|path| (entry.name.clone(), path))
+            .map_err(|e| format!("{:#}", e));
+        let _ = tx.send(PluginIndexEvent::Installed(result));
+    });
+}
+
+fn poll_plugin_index(model: &mut Model) {
+    let Some(event) = model
+        .plugin_index_rx
+        .as_ref()
+        .and_then(|rx| rx.try_recv().ok())
+    else {
+        return;
+    };
+    model.plugin_index_rx = None;
+    let installed = plugin_index::Installed::load(&model.layout.config.plugin_index.dir);
+    let Some(state) = model.modal_stack.get_plugin_index_state_mut() else {
+        return;
+    };
+    state.busy = false;
+    let with_status = |entry: plugin_index::IndexEntry| {
+        let status = installed.status(&entry);
+        (entry, status)
+    };
+    match event {
+        PluginIndexEvent::Fetched(Ok(index)) => {
+            state.status = Some(format!("{} plugins in the index", index.plugins.len()));
+            state.entries = index.plugins.into_iter().map(with_status).collect();
+        }
+        PluginIndexEvent::Fetched(Err(e)) => {
+            log::warn!("Failed to fetch plugin index: {}", e);
+            state.status = Some(format!("Couldn't fetch the index: {}", e));
+        }
+        PluginIndexEvent::Installed(Ok((name, path))) => {
+            state.status = Some(format!("Installed {} to {}", name, path.display()));
+            let entries = std::mem::take(&mut state.entries);
+            state.entries = entries
+                .into_iter()
+                .map(|(entry, _)| with_status(entry))
+                .collect();
+        }
+        PluginIndexEvent::Installed(Err(e)) => {
+            log::error!("Failed to install plugin: {}", e);
+            state.status = Some(format!("Install failed: {}", e));
+        }
+    }
 }
 
-fn draw_fullscreen_overlay(draw: &Draw, win_rect: Rect, title: &str) {
-    // Semi-transparent black background
-    draw.rect()
-        .xy(win_rect.xy())
-        .wh(win_rect.wh())
-        .color(rgba(0.0, 0.0, 0.0, 0.95));
-
-    // Title centered
-    draw_text(
-        draw,
-        FontId::PlexSansBold,
-        title,
-        win_rect.xy(),
-        32.0,
-        srgba(0.0, 1.0, 1.0, 1.0),
-        TextAlignment::Center,
+/// Register and spawn the Sherpa STT processor, returning its metrics
+///
+/// Other languages reuse `config` with the model files and vocabulary the
+/// processor's `languages` setting lists for them.
+fn spawn_sherpa(
+    module_host: &mut magnolia_core::ModuleHost,
+    patch_bay: &mut PatchBay,
+    caption_state: &std::sync::Mutex<CaptionState>,
+    config: SherpaConfig,
+    language: &str,
+) -> Option<std::sync::Arc<speech_to_text::SttMetrics>> {
+    if let Ok(mut captions) = caption_state.lock() {
+        captions.apply(SttEvent::Status {
+            status: speech_to_text::SttStatus::Starting,
+        });
+    }
+    let base = config.clone();
+    let factory: BackendFactory = Box::new(move |code, language| {
+        let mut config = base.clone();
+        if let Some(dir) = &language.model_dir {
+            let [encoder, decoder, joiner, tokens] =
+                SHERPA_FILES.map(|(_, _, filename)| dir.join(filename));
+            for path in [&encoder, &decoder, &joiner, &tokens] {
+                anyhow::ensure!(
+                    path.exists(),
+                    "{} model file {} does not exist",
+                    code,
+                    path.display()
+                );
+            }
+            (config.encoder, config.decoder, config.joiner, config.tokens) =
+                (encoder, decoder, joiner, tokens);
+        }
+        config.hotwords = language.vocab.clone();
+        Ok(Box::new(LocalSherpaBackend::new(config)) as Box<dyn SttBackend>)
+    });
+    let stt = SttProcessor::new("speech_to_text", Box::new(LocalSherpaBackend::new(config)))
+        .with_language(language)
+        .with_backend_factory(factory);
+    let metrics = stt.metrics();
+    patch_bay.register_module(stt.schema());
+    if let Err(e) = module_host.spawn(ProcessorAdapter::new(stt), 64) {
+        log::error!("Failed to spawn speech-to-text processor: {e}");
+        if let Ok(mut captions) = caption_state.lock() {
+            captions.apply(SttEvent::Status {
+                status: speech_to_text::SttStatus::Failed,
+            });
+        }
+        return None;
+    }
+    log::info!("Live Sherpa captions enabled");
+    Some(metrics)
+}
+
+/// Fetch the STT model's missing files off the UI thread; picked up by
+/// `poll_model_download`
+fn start_model_download(
+    store: models::ModelStore,
+    name: String,
+) -> std::sync::mpsc::Receiver<Result<models::ResolvedModel, String>> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let mut logged = std::collections::HashMap::new();
+        let result = store
+            .ensure(&name, |event| match event {
+                models::ModelEvent::Downloading {
+                    role,
+                    downloaded,
+                    total: Some(total),
+                } if total > 0 => {
+                    // Every tenth
+                    let step = downloaded * 10 / total;
+                    if logged.insert(role.clone(), step) != Some(step) {
+                        log::info!("Model {} {}: {}%", name, role, step * 10);
+                    }
+                }
+                models::ModelEvent::Installed { role, path } => {
+                    log::info!("Model {} {} installed to {}", name, role, path.display());
+                }
+                _ => {}
+            })
+            .map_err(|e| format!("{:#}", e));
+        let _ = tx.send(result);
+    });
+    rx
+}
+
+fn poll_model_download(model: &mut Model) {
+    let Some(result) = model
+        .model_download_rx
+        .as_ref()
+        .and_then(|rx| rx.try_recv().ok())
+    else {
+        return;
+    };
+    model.model_download_rx = None;
+    let resolved = match result {
+        Ok(resolved) => resolved,
+        Err(e) => {
+            log::error!("Failed to download the STT model: {}", e);
+            if let Ok(mut captions) = model.caption_state.lock() {
+                captions.apply(SttEvent::Status {
+                    status: speech_to_text::SttStatus::Failed,
+                });
+            }
+            return;
+        }
+    };
+    let files =
+        SHERPA_FILES.map(|(role, ..)| resolved.file(role).map(std::path::Path::to_path_buf));
+    if files.iter().any(Option::is_none) {
+        log::error!(
+            "Model {} lacks one of {:?}",
+            resolved.name,
+            SHERPA_FILES.map(|(role, ..)| role)
+        );
+        return;
+    }
+    let config = model.sherpa_options.config(files.map(Option::unwrap));
+    model.stt_metrics = spawn_sherpa(
+        &mut model.module_host,
+        &mut model.patch_bay,
+        &model.caption_state,
+        config,
+        &model.sherpa_options.language,
     );
+    if model.stt_metrics.is_some() {
+        if let Err(e) =
+            model
+                .patch_bay
+                .connect("audio_dsp", "audio_out", "speech_to_text", "audio_in")
+        {
+            log::error!("Failed to connect processed audio to speech-to-text: {e}");
+        }
+    }
+}
 
-    // Close hint
-    draw_text(
-        draw,
-        FontId::PlexSansRegular,
-        "[ESC] Close",
-        pt2(win_rect.x(), win_rect.y() - 40.0),
-        12.0,
-        srgba(0.5, 0.5, 0.5, 1.0),
-        TextAlignment::Center,
+/// Follow the most recently active module when `[focus_follow]` is on
+fn update_focus_follow(model: &mut Model) {
+    let config = &model.layout.config.focus_follow;
+    let emissions = if config.enabled {
+        model.module_host.routing_metrics().last_emissions()
+    } else {
+        Default::default()
+    };
+    model.focus_follow.update(
+        config,
+        &model.layout.config,
+        &emissions,
+        std::time::Instant::now(),
     );
 }
 
+/// The modules the add tile picker lists for `query`, best match first, with
+/// the positions of the matched letters
+fn add_tile_choices(model: &Model, query: &str) -> Vec<(String, Vec<usize>)> {
+    let modules = model.tile_registry.list_tiles();
+    ui::search::filter(query, modules.iter().map(String::as_str))
+        .into_iter()
+        .map(|(i, positions)| (modules[i].clone(), positions))
+        .collect()
+}
+
+/// The selected tile's module and the patches suggested from its outputs,
+/// while in patch mode
+fn patch_suggestions(model: &Model) -> Option<(&str, Vec<PatchSuggestion>)> {
+    if model.keyboard_nav.mode != input::InputMode::Patch {
+        return None;
+    }
+    let tile_id = model.keyboard_nav.selected_tile_id()?;
+    let tile = model.layout.config.tiles.iter().find(|t| t.id == tile_id)?;
+    Some((&tile.module, model.patch_bay.suggest_patches(&tile.module)))
+}
+
+/// The mode, the selection and the top dialog in words, for the announcer
+fn describe_screen(model: &Model) -> a11y::Screen {
+    let nav = &model.keyboard_nav;
+    let mode = match nav.mode {
+        input::InputMode::Normal => "Normal mode".to_string(),
+        input::InputMode::Patch => "Patch mode".to_string(),
+        input::InputMode::Layout => match &nav.layout_state {
+            input::LayoutSubState::Navigation => "Layout mode".to_string(),
+            input::LayoutSubState::Resize { tile_id, .. } => format!("Resizing {}", tile_id),
+            input::LayoutSubState::Move { tile_id, .. } => format!("Moving {}", tile_id),
+        },
+    };
+    let mode = match model.startup.safe_mode {
+        Some(_) => format!("{} in safe mode", mode),
+        None => mode,
+    };
+    let selection = match nav
+        .selected_tile_id()
+        .and_then(|id| model.layout.config.tiles.iter().find(|t| t.id == id))
+    {
+        Some(tile) => format!(
+            "{}, {} tile, column {}, row {}, {} by {}",
+            tile.id,
+            tile.module,
+            tile.col + 1,
+            tile.row + 1,
+            tile.colspan.unwrap_or(1),
+            tile.rowspan.unwrap_or(1)
+        ),
+        None => format!("Column {}, row {}", nav.cursor.0 + 1, nav.cursor.1 + 1),
+    };
+    let selection = match patch_suggestions(model) {
+        Some((_, suggestions)) => match suggestions.first() {
+            Some(best) => format!(
+                "{}, {} suggested patches, 1 is {} to {} {}",
+                selection,
+                suggestions.len(),
+                best.source_port,
+                best.sink_module,
+                best.sink_port
+            ),
+            None => format!("{}, no patches to suggest", selection),
+        },
+        None => selection,
+    };
+    let dialog = model.modal_stack.top().map(|modal| match modal {
+        ModalState::PatchBay(state) => match &state.status {
+            Some(status) => format!("Patch bay, {:?} list. {}", state.focus_pane, status),
+            None => format!("Patch bay, {:?} list", state.focus_pane),
+        },
+        ModalState::GlobalSettings(_) => "Global settings".to_string(),
+        ModalState::LayoutManager(state) => match &state.preview {
+            Some(preview) => format!("Layout manager, {}", preview.source),
+            None => "Layout manager".to_string(),
+        },
+        ModalState::PluginIndex(state) => match state.entries.get(state.focus.focused) {
+            Some((entry, _)) => format!("Plugin index, {}", entry.name),
+            None => "Plugin index".to_string(),
+        },
+        ModalState::Maximized { tile_id } => format!("{} maximized", tile_id),
+        ModalState::ModuleDocs(state) => format!("Docs for {}", state.module),
+        ModalState::AddTilePicker {
+            selected_idx,
+            query,
+            ..
+        } => match add_tile_choices(model, query).get(*selected_idx) {
+            Some((module, _)) if query.is_empty() => format!("Add tile, {}", module),
+            Some((module, _)) => format!("Add tile, filtered by {}, {}", query, module),
+            None => format!("Add tile, nothing matches {}", query),
+        },
+    });
+    a11y::Screen {
+        mode,
+        selection,
+        dialog,
+    }
+}
+
+/// Fullscreen and cursor follow kiosk mode
+fn apply_kiosk(window: &Window, kiosk: &magnolia_core::KioskConfig) {
+    window.set_fullscreen(kiosk.enabled && kiosk.fullscreen);
+    window.set_cursor_visible(!(kiosk.enabled && kiosk.hide_cursor));
+}
+
+fn raw_window_event(_app: &App, _model: &mut Model, _event: &nannou::winit::event::WindowEvent) {
+    // egui event handling removed
+}
+
 fn view(app: &App, model: &Model, frame: Frame) {
     // Color scheme (retinal burn mode removed)
-    let (bg_color, _fg_color, stroke_color) = (BLACK, CYAN, GRAY);
+    let (bg_color, _fg_color, stroke_color) = if magnolia_ui::theme::high_contrast() {
+        (BLACK, CYAN, WHITE)
+    } else {
+        (BLACK, CYAN, GRAY)
+    };
 
     let draw = app.draw();
     draw.background().color(bg_color);
 
     // Get maximized tile from modal stack
     let maximized_tile = model.modal_stack.get_maximized_tile();
+    // Kiosk mode draws the tiles' own visuals and nothing else
+    let kiosk = model.layout.config.kiosk.enabled;
 
     // Draw Empty Cell Placeholders
-    if maximized_tile.is_none() {
+    if maximized_tile.is_none() && !kiosk {
         let (cols, rows) = model.layout.config.resolve_grid();
         for c in 0..cols {
             for r in 0..rows {
@@ -1015,6 +2830,9 @@ fn view(app: &App, model: &Model, frame: Frame) {
                         module: String::new(),
                         enabled: true,
                         settings: Default::default(),
+                        window: None,
+                        z: 0,
+                        overlay: None,
                     };
                     if let Some(rect) = model.layout.calculate_rect(&temp_tile) {
                         draw.rect()
@@ -1044,7 +2862,7 @@ fn view(app: &App, model: &Model, frame: Frame) {
     }
 
     // Layout cursor highlight (supports selecting empty "+" cells)
-    if maximized_tile.is_none() && model.keyboard_nav.mode == input::InputMode::Layout {
+    if maximized_tile.is_none() && !kiosk && model.keyboard_nav.mode == input::InputMode::Layout {
         let (col, row) = model.keyboard_nav.cursor;
         let temp_tile = TileConfig {
             id: String::new(),
@@ -1055,6 +2873,9 @@ fn view(app: &App, model: &Model, frame: Frame) {
             module: String::new(),
             enabled: true,
             settings: Default::default(),
+            window: None,
+            z: 0,
+            overlay: None,
         };
         if let Some(rect) = model.layout.calculate_rect(&temp_tile) {
             draw.rect()
@@ -1066,13 +2887,18 @@ fn view(app: &App, model: &Model, frame: Frame) {
         }
     }
 
-    // Iterate over all tiles and render (MONITOR MODE - Read-only feedback)
-    for tile in &model.layout.config.tiles {
+    // Iterate over all tiles bottom to top and render (MONITOR MODE - Read-only feedback)
+    for tile in magnolia_core::draw_order(&model.layout.config.tiles) {
         if maximized_tile == Some(tile.id.as_str()) {
             continue;
         }
 
         if let Some(rect) = model.layout.calculate_rect(tile) {
+            // Overlays hide what they float over
+            if tile.is_overlay() {
+                draw.rect().xy(rect.xy()).wh(rect.wh()).color(bg_color);
+            }
+
             let bc = if model.selected_tile.as_ref() == Some(&tile.id) {
                 LinSrgba::new(0.0, 1.0, 1.0, 0.5)
             } else {
@@ -1083,19 +2909,24 @@ fn view(app: &App, model: &Model, frame: Frame) {
             if model.tile_registry.get(&tile.module).is_some() {
                 // Draw border
                 let is_selected = model.selected_tile.as_ref() == Some(&tile.id);
-                draw.rect()
-                    .xy(rect.xy())
-                    .wh(rect.wh())
-                    .color(rgba(0.0, 0.0, 0.0, 0.0))
-                    .stroke(bc)
-                    .stroke_weight(if is_selected { 2.0 } else { 1.0 });
+                if !kiosk {
+                    draw.rect()
+                        .xy(rect.xy())
+                        .wh(rect.wh())
+                        .color(rgba(0.0, 0.0, 0.0, 0.0))
+                        .stroke(bc)
+                        .stroke_weight(if is_selected { 2.0 } else { 1.0 });
+                }
 
                 let ctx = RenderContext {
                     time: model.start_time,
                     frame_count: model.frame_count,
                     is_selected,
                     is_maximized: false,
-                    power_profile: model.layout.config.power_profile,
+                    power_profile: model
+                        .tile_registry
+                        .power_profile(&tile.module)
+                        .unwrap_or(model.power_profile),
                     tile_settings: Some(&tile.settings.config),
                 };
 
@@ -1103,19 +2934,75 @@ fn view(app: &App, model: &Model, frame: Frame) {
                     .tile_registry
                     .render_monitor(&tile.module, &draw, rect.pad(5.0), &ctx);
 
+                // Focus-follows-signal highlight, kept even in kiosk mode
+                if model.focus_follow.tile.as_ref() == Some(&tile.id) {
+                    draw.rect()
+                        .xy(rect.xy())
+                        .wh(rect.pad(1.0).wh())
+                        .color(rgba(0.0, 0.0, 0.0, 0.0))
+                        .stroke(rgba(1.0, 0.8, 0.4, 0.35))
+                        .stroke_weight(3.0);
+                }
+
                 // Render error overlay if tile has an error
                 if let Some(error) = model.tile_registry.get_error(&tile.module) {
-                    tiles::render_error_overlay(&draw, rect, &error);
+                    if !kiosk {
+                        tiles::render_error_overlay(&draw, rect, &error);
+                    }
                 }
+            } else if !kiosk {
+                // Module missing (plugin unloaded, failed to start): show why instead of a gap
+                tiles::render_unavailable_placeholder(
+                    &draw,
+                    rect,
+                    &tile.module,
+                    model.tile_registry.unavailable_reason(&tile.module),
+                );
             }
         }
     }
 
+    // Focus-follows-signal spotlight: the active tile's own view, grown
+    // from its cell to the whole window for a while
+    let spotlight = model
+        .focus_follow
+        .spotlight(std::time::Instant::now())
+        .filter(|_| maximized_tile.is_none())
+        .and_then(|(id, t)| {
+            let tile = model.layout.config.tiles.iter().find(|t| t.id == id)?;
+            Some((tile, model.layout.calculate_rect(tile)?, t))
+        });
+    if let Some((tile, source_rect, t)) = spotlight {
+        let target_rect = frame.rect();
+        let t = t * t * (3.0 - 2.0 * t);
+        let rect = Rect::from_x_y_w_h(
+            source_rect.x() + (target_rect.x() - source_rect.x()) * t,
+            source_rect.y() + (target_rect.y() - source_rect.y()) * t,
+            source_rect.w() + (target_rect.w() - source_rect.w()) * t,
+            source_rect.h() + (target_rect.h() - source_rect.h()) * t,
+        );
+        draw.rect().xy(rect.xy()).wh(rect.wh()).color(bg_color);
+        let ctx = RenderContext {
+            time: model.start_time,
+            frame_count: model.frame_count,
+            is_selected: false,
+            is_maximized: false,
+            power_profile: model
+                .tile_registry
+                .power_profile(&tile.module)
+                .unwrap_or(model.power_profile),
+            tile_settings: Some(&tile.settings.config),
+        };
+        model
+            .tile_registry
+            .render_monitor(&tile.module, &draw, rect.pad(5.0), &ctx);
+    }
+
     // Draw Maximized Tile on top (CONTROL MODE - Settings UI)
     if let Some(max_id) = maximized_tile {
         if let Some(tile) = model.layout.config.tiles.iter().find(|t| t.id == max_id) {
             if let Some(source_rect) = model.layout.calculate_rect(tile) {
-                let target_rect = app.window_rect(); // Full Window maximize
+                let target_rect = frame.rect(); // Full Window maximize
 
                 let t = model.anim_factor;
                 // Cubic easing for a smoother feel
@@ -1142,7 +3029,10 @@ fn view(app: &App, model: &Model, frame: Frame) {
                     frame_count: model.frame_count,
                     is_selected: true,
                     is_maximized: true,
-                    power_profile: model.layout.config.power_profile,
+                    power_profile: model
+                        .tile_registry
+                        .power_profile(&tile.module)
+                        .unwrap_or(model.power_profile),
                     tile_settings: Some(&tile.settings.config),
                 };
 
@@ -1150,6 +3040,10 @@ fn view(app: &App, model: &Model, frame: Frame) {
                 model
                     .tile_registry
                     .render_controls(&tile.module, &draw, rect, &ctx);
+
+                if let Some(history) = model.tile_registry.error_history(&tile.module) {
+                    tiles::render_error_history(&draw, rect, &history, "Ctrl+R retry");
+                }
             }
         }
     }
@@ -1157,8 +3051,8 @@ fn view(app: &App, model: &Model, frame: Frame) {
     // Sleep visualization
     if model.is_sleeping {
         draw.rect()
-            .xy(app.window_rect().xy())
-            .wh(app.window_rect().wh())
+            .xy(frame.rect().xy())
+            .wh(frame.rect().wh())
             .color(rgba(0.0, 0.0, 0.1, 0.4));
 
         draw_text(
@@ -1166,8 +3060,8 @@ fn view(app: &App, model: &Model, frame: Frame) {
             FontId::PlexMonoRegular,
             "Zzz",
             pt2(
-                app.window_rect().right() - 30.0,
-                app.window_rect().bottom() + 30.0,
+                frame.rect().right() - 30.0,
+                frame.rect().bottom() + 30.0,
             ),
             24.0,
             srgba(0.5, 0.5, 1.0, 0.5),
@@ -1175,8 +3069,37 @@ fn view(app: &App, model: &Model, frame: Frame) {
         );
     }
 
+    // Safe mode banner (top centre)
+    if let Some(description) = model.startup.describe() {
+        draw_text(
+            &draw,
+            FontId::PlexSansBold,
+            &description,
+            pt2(frame.rect().x(), frame.rect().top() - 14.0),
+            12.0,
+            srgba(1.0, 0.3, 0.3, 0.9),
+            TextAlignment::Center,
+        );
+    }
+
+    // Saved patches left out (top centre, under the safe mode banner)
+    if !model.patch_issues.is_empty() && !kiosk {
+        draw_text(
+            &draw,
+            FontId::PlexSansBold,
+            &format!(
+                "{} saved patches left out; P shows why",
+                model.patch_issues.len()
+            ),
+            pt2(frame.rect().x(), frame.rect().top() - 30.0),
+            12.0,
+            srgba(1.0, 0.6, 0.2, 0.9),
+            TextAlignment::Center,
+        );
+    }
+
     // Mode indicator (bottom-left corner)
-    if maximized_tile.is_none() {
+    if maximized_tile.is_none() && !kiosk {
         let mode_text = match model.keyboard_nav.mode {
             input::InputMode::Normal => "NORMAL",
             input::InputMode::Layout => match model.keyboard_nav.layout_state {
@@ -1188,12 +3111,31 @@ fn view(app: &App, model: &Model, frame: Frame) {
         };
 
         let mode_color = match model.keyboard_nav.mode {
-            input::InputMode::Normal => rgba(0.5, 0.5, 0.5, 0.8),
+            input::InputMode::Normal => {
+                let level = magnolia_ui::theme::grey_level(0.5);
+                rgba(level, level, level, 0.8)
+            }
             input::InputMode::Layout => rgba(0.0, 1.0, 0.5, 0.8),
             input::InputMode::Patch => rgba(1.0, 0.5, 0.0, 0.8),
         };
 
-        let win_rect = app.window_rect();
+        let win_rect = frame.rect();
+        let macro_text = match model.keyboard_nav.macro_state {
+            input::MacroState::Idle => None,
+            input::MacroState::Recording(_) => Some("REC MACRO  [Ctrl+R] Stop"),
+            input::MacroState::Binding(_) => Some("BIND MACRO  [F1-F12] Bind [ESC] Discard"),
+        };
+        if let Some(macro_text) = macro_text {
+            draw_text(
+                &draw,
+                FontId::PlexSansBold,
+                macro_text,
+                pt2(win_rect.left() + 50.0, win_rect.bottom() + 40.0),
+                12.0,
+                srgba(1.0, 0.3, 0.3, 0.9),
+                TextAlignment::Left,
+            );
+        }
         draw_text(
             &draw,
             FontId::PlexSansBold,
@@ -1215,9 +3157,9 @@ fn view(app: &App, model: &Model, frame: Frame) {
                 "[L]ayout [P]atch [G]lobal [Tab]Cycle [Arrows]Nav [E]dit [Enter]Select"
             }
             input::InputMode::Layout => {
-                "[E]dit [A]dd [D]elete [Space]Toggle [Enter]Confirm [ESC]Cancel"
+                "[E]dit [A]dd [D]elete [O]verlay [ ]]Z [Space]Toggle [Enter]Confirm [ESC]Cancel"
             }
-            input::InputMode::Patch => "[Arrows]Select [Enter]Patch [ESC]Exit",
+            input::InputMode::Patch => "[Arrows]Select [1-9]Suggestion [Enter]Patch [ESC]Exit",
         };
 
         draw_text(
@@ -1226,13 +3168,16 @@ fn view(app: &App, model: &Model, frame: Frame) {
             hints,
             pt2(win_rect.left() + 250.0, win_rect.bottom() + 20.0),
             10.0,
-            srgba(0.4, 0.4, 0.4, 0.8),
+            {
+                let level = magnolia_ui::theme::grey_level(0.4);
+                srgba(level, level, level, 0.8)
+            },
             TextAlignment::Left,
         );
     }
 
     // Render patch cables (always visible if not maximized)
-    if maximized_tile.is_none() && !model.layout.config.patches.is_empty() {
+    if maximized_tile.is_none() && !kiosk && !model.layout.config.patches.is_empty() {
         let mut tile_rects = Vec::new();
         for tile in &model.layout.config.tiles {
             if let Some(rect) = model.layout.calculate_rect(tile) {
@@ -1242,8 +3187,14 @@ fn view(app: &App, model: &Model, frame: Frame) {
         patch_visualizer::render_patches(&draw, &model.layout.config.patches, &tile_rects);
     }
 
+    if maximized_tile.is_none() && !kiosk && model.modal_stack.is_empty() {
+        if let Some((module, suggestions)) = patch_suggestions(model) {
+            ui::patch_suggestions::render(&draw, frame.rect(), module, &suggestions);
+        }
+    }
+
     // Fullscreen Modals
-    let win_rect = app.window_rect();
+    let win_rect = frame.rect();
     if let Some(state) = model.modal_stack.get_global_settings_state() {
         // Create anim state if needed or use existing
         let anim = model
@@ -1257,11 +3208,168 @@ fn view(app: &App, model: &Model, frame: Frame) {
             factor: 1.0,
             closing: false,
         }; // TODO: Integrated animation state
-        ui::patch_bay::render(&draw, win_rect, state, &anim, &model.patch_bay);
-    } else if model.modal_stack.is_layout_manager_open() {
-        draw_fullscreen_overlay(&draw, win_rect, "LAYOUT MANAGER");
+        let traffic = model.module_host.routing_metrics().patch_traffic();
+        ui::patch_bay::render(&draw, win_rect, state, &anim, &model.patch_bay, &traffic);
+    } else if let Some(state) = model.modal_stack.get_layout_manager_state() {
+        let anim = model
+            .modal_anims
+            .get(&ModalAnimKey::LayoutManager)
+            .cloned()
+            .unwrap_or(ModalAnim::new());
+        ui::layout_manager::render(&draw, win_rect, state, &model.thumbnails, &anim);
+    } else if let Some(state) = model.modal_stack.get_plugin_index_state() {
+        let anim = model
+            .modal_anims
+            .get(&ModalAnimKey::PluginIndex)
+            .cloned()
+            .unwrap_or(ModalAnim::new());
+        ui::plugin_index::render(&draw, win_rect, state, &anim);
+    } else if let Some(state) = model.modal_stack.get_module_docs_state() {
+        let anim = model
+            .modal_anims
+            .get(&ModalAnimKey::ModuleDocs)
+            .cloned()
+            .unwrap_or(ModalAnim::new());
+        let schema = model.patch_bay.get_module(&state.module);
+        let title = schema.map_or(state.module.as_str(), |schema| schema.name.as_str());
+        let lines = ui::module_docs::doc_lines(&state.module, schema);
+        ui::module_docs::render(&draw, win_rect, title, &lines, state, &anim);
+    } else if let Some((_, _, selected)) = model.modal_stack.get_add_tile_picker() {
+        let anim = model
+            .modal_anims
+            .get(&ModalAnimKey::AddTilePicker)
+            .cloned()
+            .unwrap_or(ModalAnim::new());
+        let query = model
+            .modal_stack
+            .add_tile_picker_query()
+            .unwrap_or_default();
+        ui::add_tile_picker::render(
+            &draw,
+            win_rect,
+            query,
+            &add_tile_choices(model, query),
+            selected,
+            &model.thumbnails,
+            &anim,
+        );
     }
 
     draw.to_frame(app, &frame).unwrap();
     // egui draw removed
+
+    if let Some(share) = &model.video_share {
+        capture_video_share(model, share, &frame);
+    }
+}
+
+/// View for the extra windows: their tiles on their own grid, with no
+/// borders, placeholders or overlays
+fn view_output(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(BLACK);
+
+    let window = model
+        .output_windows
+        .get(&frame.window_id())
+        .and_then(|id| model.layout.config.window(id));
+    if let Some(window) = window {
+        for tile in magnolia_core::draw_order(model.layout.config.window_tiles(&window.id)) {
+            let rect = model
+                .layout
+                .calculate_window_rect(tile, window, frame.rect());
+            if tile.is_overlay() {
+                draw.rect().xy(rect.xy()).wh(rect.wh()).color(BLACK);
+            }
+            let ctx = RenderContext {
+                time: model.start_time,
+                frame_count: model.frame_count,
+                is_selected: false,
+                is_maximized: false,
+                power_profile: model
+                    .tile_registry
+                    .power_profile(&tile.module)
+                    .unwrap_or(model.power_profile),
+                tile_settings: Some(&tile.settings.config),
+            };
+            model
+                .tile_registry
+                .render_monitor(&tile.module, &draw, rect, &ctx);
+        }
+    }
+
+    draw.to_frame(app, &frame).unwrap();
+}
+
+/// Read the finished frame back for the video share, cropped to the shared
+/// tile if one is set. The copy is queued on this frame's encoder and mapped
+/// off-thread, so the view never waits on the GPU.
+fn capture_video_share(model: &Model, share: &video_share::VideoShare, frame: &Frame) {
+    let Some(target) = share.capture_target() else {
+        return;
+    };
+    let [width, height] = frame.texture_size();
+    let crop = match target {
+        video_share::CaptureTarget::Compositor => None,
+        video_share::CaptureTarget::Tile(id) => {
+            match model.modal_stack.get_maximized_tile() {
+                Some(maximized) if maximized == id => None,
+                // Hidden behind another maximized tile
+                Some(_) => return,
+                None => {
+                    let Some(rect) = model
+                        .layout
+                        .config
+                        .tiles
+                        .iter()
+                        .find(|tile| tile.id == id)
+                        .and_then(|tile| model.layout.calculate_rect(tile))
+                    else {
+                        return;
+                    };
+                    // Window points to texture pixels, top-left origin
+                    let win = frame.rect();
+                    let sx = width as f32 / win.w();
+                    let sy = height as f32 / win.h();
+                    Some(video_share::PixelRect {
+                        x: ((rect.left() - win.left()) * sx).max(0.0) as u32,
+                        y: ((win.top() - rect.top()) * sy).max(0.0) as u32,
+                        width: (rect.w() * sx) as u32,
+                        height: (rect.h() * sy) as u32,
+                    })
+                }
+            }
+        }
+    };
+
+    let device = frame.device_queue_pair().device();
+    let snapshot = {
+        let mut encoder = frame.command_encoder();
+        model
+            .frame_capturer
+            .capture(device, &mut encoder, frame.texture())
+    };
+    let share = share.clone();
+    let read = snapshot.read(move |result| {
+        let Ok(image) = result else {
+            return;
+        };
+        let image = image.to_owned();
+        let Some(captured) =
+            video_share::VideoFrame::new(image.width(), image.height(), image.into_raw())
+        else {
+            return;
+        };
+        match crop {
+            Some(rect) => {
+                if let Some(cropped) = captured.crop(rect) {
+                    share.submit(cropped);
+                }
+            }
+            None => share.submit(captured),
+        }
+    });
+    if read.is_err() {
+        log::warn!("Video share: frame readback timed out");
+    }
 }
@@ -0,0 +1,151 @@
+//! Safe mode: start without plugins, audio devices or the saved layout
+//!
+//! Asked for with `--safe-mode`, or entered on its own once
+//! [`CRASH_THRESHOLD`] starts in a row have ended inside [`STARTUP_GRACE`].
+//! Either way the broken plugin or layout can then be dealt with from inside
+//! the app. Starts are counted in `configs/startup_attempts`, which is
+//! removed again once a start lasts the grace period or quits in an orderly
+//! way, so only crashes add up.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Failed starts in a row that bring up safe mode
+pub const CRASH_THRESHOLD: u32 = 3;
+/// How long a start must last before it counts as good
+pub const STARTUP_GRACE: Duration = Duration::from_secs(10);
+
+const ATTEMPTS_FILE: &str = "configs/startup_attempts";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SafeModeReason {
+    /// `--safe-mode` on the command line
+    Requested,
+    /// This many starts before this one failed
+    Crashes(u32),
+}
+
+/// Counts this start until it has lasted [`STARTUP_GRACE`]
+#[derive(Debug)]
+pub struct StartupGuard {
+    /// Why this start is in safe mode, if it is
+    pub safe_mode: Option<SafeModeReason>,
+    attempts_file: PathBuf,
+    settled: bool,
+}
+
+impl StartupGuard {
+    /// Count this start and decide from `args` and the earlier ones whether
+    /// it's in safe mode
+    pub fn begin(args: impl IntoIterator<Item = String>) -> Self {
+        Self::begin_at(Path::new(ATTEMPTS_FILE), args)
+    }
+
+    fn begin_at(attempts_file: &Path, args: impl IntoIterator<Item = String>) -> Self {
+        let failed = std::fs::read_to_string(attempts_file)
+            .ok()
+            .and_then(|text| text.trim().parse::<u32>().ok())
+            .unwrap_or(0);
+        let safe_mode = if args.into_iter().any(|arg| arg == "--safe-mode") {
+            Some(SafeModeReason::Requested)
+        } else if failed >= CRASH_THRESHOLD {
+            Some(SafeModeReason::Crashes(failed))
+        } else {
+            None
+        };
+        if let Err(e) = std::fs::write(attempts_file, (failed + 1).to_string()) {
+            log::warn!("Failed to record startup attempt: {}", e);
+        }
+        Self {
+            safe_mode,
+            attempts_file: attempts_file.to_path_buf(),
+            settled: false,
+        }
+    }
+
+    pub fn is_safe_mode(&self) -> bool {
+        self.safe_mode.is_some()
+    }
+
+    /// Forget the failed starts once this one has been up for `running_for`
+    /// past the grace period
+    pub fn update(&mut self, running_for: Duration) {
+        if self.settled || running_for < STARTUP_GRACE {
+            return;
+        }
+        self.finish();
+    }
+
+    /// Forget the failed starts on an orderly quit, however soon it comes
+    pub fn finish(&mut self) {
+        self.settled = true;
+        match std::fs::remove_file(&self.attempts_file) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => log::warn!("Failed to clear startup attempts: {}", e),
+        }
+    }
+
+    /// One line for the banner and the announcer, `None` outside safe mode
+    pub fn describe(&self) -> Option<String> {
+        let why = match self.safe_mode? {
+            SafeModeReason::Requested => "started with --safe-mode".to_string(),
+            SafeModeReason::Crashes(failed) => format!("the last {} starts failed", failed),
+        };
+        Some(format!(
+            "Safe mode ({}): no plugins, no audio devices, minimal layout",
+            why
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeated_failed_starts_bring_up_safe_mode_until_one_lasts() {
+        let file =
+            std::env::temp_dir().join(format!("magnolia_startup_attempts_{}", std::process::id()));
+        let _ = std::fs::remove_file(&file);
+        let no_args = Vec::<String>::new;
+
+        for _ in 0..CRASH_THRESHOLD {
+            assert!(!StartupGuard::begin_at(&file, no_args()).is_safe_mode());
+        }
+        let mut guard = StartupGuard::begin_at(&file, no_args());
+        assert_eq!(guard.safe_mode, Some(SafeModeReason::Crashes(3)));
+        assert_eq!(
+            guard.describe().unwrap(),
+            "Safe mode (the last 3 starts failed): no plugins, no audio devices, minimal layout"
+        );
+
+        guard.update(STARTUP_GRACE / 2);
+        assert!(file.exists());
+        guard.update(STARTUP_GRACE);
+        assert!(!file.exists());
+
+        let guard = StartupGuard::begin_at(&file, no_args());
+        assert_eq!(guard.safe_mode, None);
+        let guard = StartupGuard::begin_at(&file, ["--safe-mode".to_string()]);
+        assert_eq!(guard.safe_mode, Some(SafeModeReason::Requested));
+        let _ = std::fs::remove_file(&file);
+    }
+
+    #[test]
+    fn quick_clean_exits_dont_count_as_failed_starts() {
+        let file = std::env::temp_dir().join(format!(
+            "magnolia_startup_attempts_clean_{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&file);
+
+        for _ in 0..=CRASH_THRESHOLD {
+            let mut guard = StartupGuard::begin_at(&file, Vec::<String>::new());
+            assert!(!guard.is_safe_mode());
+            guard.update(Duration::from_secs(1));
+            guard.finish();
+            assert!(!file.exists());
+        }
+    }
+}
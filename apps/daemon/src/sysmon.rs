@@ -0,0 +1,369 @@
+//! System monitor backend: metric sampling and the `sysmon` source.
+//!
+//! [`SysmonSource`] samples CPU, memory, GPU and network once per interval
+//! and emits:
+//! - the whole [`SysmonSample`] as JSON (`Computed`, source `sysmon`) on `metrics_out`
+//! - each headline metric as a number (`Computed`, source `sysmon_<metric>`)
+//!   on its own `<metric>_out` port
+//!
+//! Samples also land in a [`SharedSysmon`] history, which the system monitor
+//! tile draws its sparklines and graphs from.
+
+use async_trait::async_trait;
+use magnolia_core::{DataType, ModuleSchema, Port, PortDirection, Signal, Source};
+use nvml_wrapper::Nvml;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::fs;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use sysinfo::{Networks, System};
+
+pub const HISTORY_SIZE: usize = 60;
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(1000); // 1Hz
+const GIB: f32 = 1024.0 * 1024.0 * 1024.0;
+const MIB: f32 = 1024.0 * 1024.0;
+
+/// Headline metrics with their own numeric output port.
+pub const METRICS: [&str; 5] = ["cpu", "mem", "gpu", "net_down", "net_up"];
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SysmonSample {
+    /// Global CPU load, 0..100 %
+    pub cpu: f32,
+    pub per_core: Vec<f32>,
+    pub mem_used_gb: f32,
+    pub mem_percent: f32,
+    /// `None` when no supported GPU was found
+    pub gpu_util: Option<f32>,
+    pub gpu_temp: Option<f32>,
+    /// MB/s since the previous sample
+    pub net_down: f32,
+    pub net_up: f32,
+}
+
+impl SysmonSample {
+    pub fn metric(&self, name: &str) -> Option<f32> {
+        match name {
+            "cpu" => Some(self.cpu),
+            "mem" => Some(self.mem_percent),
+            "gpu" => self.gpu_util,
+            "net_down" => Some(self.net_down),
+            "net_up" => Some(self.net_up),
+            _ => None,
+        }
+    }
+}
+
+/// Rolling history of recent samples.
+#[derive(Debug, Default)]
+pub struct SysmonHistory {
+    pub latest: SysmonSample,
+    pub cpu: VecDeque<f32>,
+    pub per_core: Vec<VecDeque<f32>>,
+    pub mem: VecDeque<f32>,
+    pub gpu_util: VecDeque<f32>,
+    pub gpu_temp: VecDeque<f32>,
+    pub net_down: VecDeque<f32>,
+    pub net_up: VecDeque<f32>,
+}
+
+impl SysmonHistory {
+    pub fn push(&mut self, sample: SysmonSample) {
+        push_history(&mut self.cpu, sample.cpu);
+        if self.per_core.len() != sample.per_core.len() {
+            self.per_core = vec![VecDeque::with_capacity(HISTORY_SIZE); sample.per_core.len()];
+        }
+        for (history, &load) in self.per_core.iter_mut().zip(&sample.per_core) {
+            push_history(history, load);
+        }
+        push_history(&mut self.mem, sample.mem_percent);
+        push_history(&mut self.gpu_util, sample.gpu_util.unwrap_or(0.0));
+        if let Some(temp) = sample.gpu_temp {
+            push_history(&mut self.gpu_temp, temp);
+        }
+        push_history(&mut self.net_down, sample.net_down);
+        push_history(&mut self.net_up, sample.net_up);
+        self.latest = sample;
+    }
+}
+
+fn push_history(history: &mut VecDeque<f32>, value: f32) {
+    if history.len() >= HISTORY_SIZE {
+        history.pop_front();
+    }
+    history.push_back(value);
+}
+
+pub type SharedSysmon = Arc<Mutex<SysmonHistory>>;
+
+pub fn shared_history() -> SharedSysmon {
+    Arc::new(Mutex::new(SysmonHistory::default()))
+}
+
+/// Reads metrics from sysinfo, NVML and the Intel DRM sysfs counters.
+pub struct SysmonSampler {
+    sys: System,
+    networks: Networks,
+    nvml: Option<Nvml>,
+    intel_gpu_path: Option<String>,
+    last_sample: Instant,
+}
+
+impl SysmonSampler {
+    pub fn new() -> Self {
+        let mut sys = System::new_all();
+        sys.refresh_cpu_all();
+        sys.refresh_memory();
+
+        // Try to find Intel GPU busy percent path
+        let intel_gpu_path = [
+            "/sys/class/drm/card0/device/gpu_busy_percent",
+            "/sys/class/drm/card1/device/gpu_busy_percent",
+        ]
+        .into_iter()
+        .find(|path| fs::metadata(path).is_ok())
+        .map(str::to_string);
+
+        Self {
+            sys,
+            networks: Networks::new_with_refreshed_list(),
+            nvml: Nvml::init().ok(),
+            intel_gpu_path,
+            last_sample: Instant::now(),
+        }
+    }
+
+    pub fn sample(&mut self) -> SysmonSample {
+        self.sys.refresh_cpu_all();
+        self.sys.refresh_memory();
+        self.networks.refresh(true);
+        let elapsed = self.last_sample.elapsed().as_secs_f32().max(0.001);
+        self.last_sample = Instant::now();
+
+        let used_mem = self.sys.used_memory() as f32 / GIB;
+        let total_mem = self.sys.total_memory() as f32 / GIB;
+
+        let (mut gpu_util, mut gpu_temp) = (None, None);
+        if let Some(device) = self.nvml.as_ref().and_then(|n| n.device_by_index(0).ok()) {
+            gpu_util = device.utilization_rates().ok().map(|u| u.gpu as f32);
+            gpu_temp = device
+                .temperature(nvml_wrapper::enum_wrappers::device::TemperatureSensor::Gpu)
+                .ok()
+                .map(|t| t as f32);
+        }
+        // GPU (Intel fallback)
+        if gpu_util.is_none() {
+            gpu_util = self
+                .intel_gpu_path
+                .as_ref()
+                .and_then(|path| fs::read_to_string(path).ok())
+                .and_then(|content| content.trim().parse::<f32>().ok());
+        }
+
+        let (mut received, mut transmitted) = (0.0, 0.0);
+        for (_interface_name, data) in &self.networks {
+            received += data.received() as f32;
+            transmitted += data.transmitted() as f32;
+        }
+
+        SysmonSample {
+            cpu: self.sys.global_cpu_usage(),
+            per_core: self.sys.cpus().iter().map(|c| c.cpu_usage()).collect(),
+            mem_used_gb: used_mem,
+            mem_percent: if total_mem > 0.0 {
+                used_mem / total_mem * 100.0
+            } else {
+                0.0
+            },
+            gpu_util,
+            gpu_temp,
+            net_down: received / MIB / elapsed,
+            net_up: transmitted / MIB / elapsed,
+        }
+    }
+}
+
+impl Default for SysmonSampler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Signals for one sample: the JSON summary followed by one number per
+/// available headline metric.
+pub fn sample_signals(sample: &SysmonSample) -> Vec<Signal> {
+    let mut signals = Vec::with_capacity(METRICS.len() + 1);
+    match serde_json::to_string(sample) {
+        Ok(content) => signals.push(Signal::Computed {
+            source: "sysmon".to_string(),
+            content,
+        }),
+        Err(e) => log::warn!("Sysmon: failed to encode sample: {}", e),
+    }
+    for name in METRICS {
+        if let Some(value) = sample.metric(name) {
+            signals.push(Signal::Computed {
+                source: format!("sysmon_{}", name),
+                content: format!("{:.2}", value),
+            });
+        }
+    }
+    signals
+}
+
+/// Source half of the system monitor.
+pub struct SysmonSource {
+    id: String,
+    enabled: bool,
+    sampler: SysmonSampler,
+    history: SharedSysmon,
+    pending: VecDeque<Signal>,
+}
+
+impl SysmonSource {
+    pub fn new(id: &str, history: SharedSysmon) -> Self {
+        Self {
+            id: id.to_string(),
+            enabled: true,
+            sampler: SysmonSampler::new(),
+            history,
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Source for SysmonSource {
+    fn name(&self) -> &str {
+        "System Monitor"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        let mut ports = vec![Port {
+            id: "metrics_out".to_string(),
+            label: "Metrics".to_string(),
+            data_type: DataType::Text,
+            direction: PortDirection::Output,
+        }];
+        ports.extend(METRICS.iter().map(|name| {
+            Port {
+                id: format!("{}_out", name),
+                label: match *name {
+                    "cpu" => "CPU %",
+                    "mem" => "Memory %",
+                    "gpu" => "GPU %",
+                    "net_down" => "Net Down MB/s",
+                    _ => "Net Up MB/s",
+                }
+                .to_string(),
+                data_type: DataType::Numeric,
+                direction: PortDirection::Output,
+            }
+        }));
+
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "System Monitor".to_string(),
+            description: "CPU, memory, GPU and network metrics".to_string(),
+            ports,
+            settings_schema: None,
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    async fn poll(&mut self) -> Option<Signal> {
+        loop {
+            if let Some(signal) = self.pending.pop_front() {
+                return Some(signal);
+            }
+            tokio::time::sleep(SAMPLE_INTERVAL).await;
+            if !self.enabled {
+                continue;
+            }
+            let sample = self.sampler.sample();
+            self.pending.extend(sample_signals(&sample));
+            if let Ok(mut history) = self.history.lock() {
+                history.push(sample);
+            }
+        }
+    }
+
+    fn output_port(&self, signal: &Signal) -> Option<&str> {
+        let Signal::Computed { source, .. } = signal else {
+            return None;
+        };
+        match source.strip_prefix("sysmon_")? {
+            "cpu" => Some("cpu_out"),
+            "mem" => Some("mem_out"),
+            "gpu" => Some("gpu_out"),
+            "net_down" => Some("net_down_out"),
+            "net_up" => Some("net_up_out"),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(cpu: f32, gpu: Option<f32>) -> SysmonSample {
+        SysmonSample {
+            cpu,
+            per_core: vec![cpu, cpu / 2.0],
+            mem_used_gb: 4.0,
+            mem_percent: 25.0,
+            gpu_util: gpu,
+            gpu_temp: None,
+            net_down: 1.5,
+            net_up: 0.25,
+        }
+    }
+
+    #[test]
+    fn metrics_leave_from_their_own_ports() {
+        let source = SysmonSource::new("sysmon", shared_history());
+        let signals = sample_signals(&sample(42.0, Some(10.0)));
+        assert_eq!(signals.len(), METRICS.len() + 1);
+        assert_eq!(source.output_port(&signals[0]), None);
+
+        let routed: Vec<_> = signals[1..]
+            .iter()
+            .map(|signal| match signal {
+                Signal::Computed { content, .. } => {
+                    (source.output_port(signal).unwrap(), content.as_str())
+                }
+                other => panic!("unexpected signal {:?}", other),
+            })
+            .collect();
+        assert_eq!(routed[0], ("cpu_out", "42.00"));
+        assert_eq!(routed[2], ("gpu_out", "10.00"));
+        assert_eq!(routed[4], ("net_up_out", "0.25"));
+
+        // No GPU: the gpu port stays quiet
+        assert_eq!(sample_signals(&sample(1.0, None)).len(), METRICS.len());
+    }
+
+    #[test]
+    fn history_is_bounded() {
+        let mut history = SysmonHistory::default();
+        for i in 0..HISTORY_SIZE + 5 {
+            history.push(sample(i as f32, None));
+        }
+        assert_eq!(history.cpu.len(), HISTORY_SIZE);
+        assert_eq!(history.cpu.front(), Some(&5.0));
+        assert_eq!(history.per_core.len(), 2);
+        assert_eq!(history.per_core[1].len(), HISTORY_SIZE);
+        assert!(history.gpu_temp.is_empty());
+        assert_eq!(history.latest.cpu, (HISTORY_SIZE + 4) as f32);
+    }
+}
@@ -18,7 +18,7 @@ impl CaptionTile {
         }
     }
 
-    fn lines(text: &str, max_chars: usize) -> Vec<String> {
+    pub(crate) fn lines(text: &str, max_chars: usize) -> Vec<String> {
         let mut lines = Vec::new();
         let mut current = String::new();
         for word in text.split_whitespace() {
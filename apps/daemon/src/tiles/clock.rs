@@ -1,64 +1,155 @@
-//! Clock Tile - 24-hour digital clock display (HH:MM:SS)
+//! Clock Tile - local time, world clocks, sun/moon almanac and alarms
 //!
-//! Monitor mode: Shows current time
-//! Control mode: Settings for format (12/24hr), show seconds, etc.
+//! Monitor mode: Shows current time, extra time zones, sunrise/sunset and moon phase
+//! Control mode: Settings form (format, zones, location, alarms)
+//!
+//! Alarms and sun events are emitted by [`crate::clock_source::ClockSource`],
+//! which shares this tile's config.
 
 use super::{BindableAction, RenderContext, TileRenderer};
-use crate::ui::controls;
-use chrono::Local;
+use crate::clock_source::{shared_config, ClockConfig, SharedClockConfig, TimeFormat};
+use crate::ui::controls::{self, UiInput};
+use crate::ui::schema::SchemaForm;
+use crate::ui::schema_view;
+use aphrodite::almanac::Almanac;
+use aphrodite::ephemeris::SwissEphemerisAdapter;
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, TimeZone, Utc};
 use magnolia_ui::{draw_text, FontId, TextAlignment};
 use nannou::prelude::*;
-use serde::{Deserialize, Serialize};
-use std::sync::Mutex;
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-pub enum TimeFormat {
-    TwentyFourHour,
-    TwelveHour,
-}
-
-impl Default for TimeFormat {
-    fn default() -> Self {
-        Self::TwentyFourHour
-    }
-}
 
 pub struct ClockTile {
+    config: SharedClockConfig,
+    /// `config` as JSON, edited in place by the control-mode form
+    settings: serde_json::Value,
+    form: SchemaForm,
+
     current_time: String,
-    format: TimeFormat,
-    show_seconds: bool,
-    show_date: bool,
+    zone_times: Vec<(String, String)>,
+    almanac_line: Option<String>,
+    next_alarm: Option<String>,
 
-    // Control-mode UI state (keyboard focus)
-    focused_control: Mutex<usize>,
+    // Almanac is recomputed when the date or location changes
+    almanac: Option<(NaiveDate, f64, f64, Almanac)>,
+    ephemeris: Option<SwissEphemerisAdapter>,
 }
 
 impl ClockTile {
     pub fn new() -> Self {
+        Self::with_config(shared_config())
+    }
+
+    /// Tile editing the config shared with the clock source
+    pub fn with_config(config: SharedClockConfig) -> Self {
+        let settings = config
+            .read()
+            .ok()
+            .and_then(|c| serde_json::to_value(&*c).ok())
+            .unwrap_or_default();
         Self {
+            config,
+            settings,
+            form: SchemaForm::new(ClockConfig::schema()),
             current_time: String::new(),
-            format: TimeFormat::TwentyFourHour,
-            show_seconds: true,
-            show_date: false,
-            focused_control: Mutex::new(0),
+            zone_times: Vec::new(),
+            almanac_line: None,
+            next_alarm: None,
+            almanac: None,
+            // Falls back to analytic sun/moon positions when the data files are missing
+            ephemeris: SwissEphemerisAdapter::new(None).ok(),
         }
     }
 
-    fn format_time(&self) -> String {
-        let now = Local::now();
-        let time_str = match (self.format, self.show_seconds) {
-            (TimeFormat::TwentyFourHour, true) => now.format("%H:%M:%S").to_string(),
-            (TimeFormat::TwentyFourHour, false) => now.format("%H:%M").to_string(),
-            (TimeFormat::TwelveHour, true) => now.format("%I:%M:%S %p").to_string(),
-            (TimeFormat::TwelveHour, false) => now.format("%I:%M %p").to_string(),
-        };
+    fn config(&self) -> ClockConfig {
+        self.config.read().map(|c| c.clone()).unwrap_or_default()
+    }
+
+    fn set_config(&mut self, config: ClockConfig) {
+        self.settings = serde_json::to_value(&config).unwrap_or_default();
+        if let Ok(mut guard) = self.config.write() {
+            *guard = config;
+        }
+    }
+
+    fn edit_config(&mut self, edit: impl FnOnce(&mut ClockConfig)) {
+        let mut config = self.config();
+        edit(&mut config);
+        self.set_config(config);
+    }
 
-        if self.show_date {
+    fn time_pattern(config: &ClockConfig) -> &'static str {
+        match (config.format, config.show_seconds) {
+            (TimeFormat::TwentyFourHour, true) => "%H:%M:%S",
+            (TimeFormat::TwentyFourHour, false) => "%H:%M",
+            (TimeFormat::TwelveHour, true) => "%I:%M:%S %p",
+            (TimeFormat::TwelveHour, false) => "%I:%M %p",
+        }
+    }
+
+    fn format_time(config: &ClockConfig, now: DateTime<Local>) -> String {
+        let time_str = now.format(Self::time_pattern(config)).to_string();
+        if config.show_date {
             format!("{}\n{}", now.format("%Y-%m-%d"), time_str)
         } else {
             time_str
         }
     }
+
+    fn short_time(config: &ClockConfig, at: DateTime<Utc>) -> String {
+        let pattern = match config.format {
+            TimeFormat::TwentyFourHour => "%H:%M",
+            TimeFormat::TwelveHour => "%I:%M %p",
+        };
+        at.with_timezone(&Local).format(pattern).to_string()
+    }
+
+    fn refresh_almanac(&mut self, config: &ClockConfig, today: NaiveDate) {
+        let location = &config.location;
+        let stale = !matches!(
+            &self.almanac,
+            Some((date, lat, lon, _))
+                if *date == today && *lat == location.latitude && *lon == location.longitude
+        );
+        if stale {
+            let almanac = Almanac::compute(today, &location.geo(), self.ephemeris.as_ref());
+            self.almanac = Some((today, location.latitude, location.longitude, almanac));
+        }
+
+        self.almanac_line = self.almanac.as_ref().map(|(_, _, _, almanac)| {
+            let sun = match (almanac.sun.sunrise, almanac.sun.sunset) {
+                (Some(rise), Some(set)) => format!(
+                    "Sunrise {}  Sunset {}",
+                    Self::short_time(config, rise),
+                    Self::short_time(config, set)
+                ),
+                _ => "No sunrise today".to_string(),
+            };
+            format!(
+                "{}  |  {} {:.0}%",
+                sun,
+                almanac.moon.name.label(),
+                almanac.moon.illumination * 100.0
+            )
+        });
+    }
+
+    fn next_alarm(config: &ClockConfig, now: DateTime<Local>) -> Option<String> {
+        config
+            .alarms
+            .iter()
+            .filter(|alarm| alarm.enabled)
+            .filter_map(|alarm| {
+                let today = now.date_naive().and_hms_opt(alarm.hour, alarm.minute, 0)?;
+                let at = Local.from_local_datetime(&today).earliest()?;
+                let at = if at <= now {
+                    at + chrono::Duration::days(1)
+                } else {
+                    at
+                };
+                Some((at, alarm))
+            })
+            .min_by_key(|(at, _)| *at)
+            .map(|(at, alarm)| format!("{} {}", alarm.label, at.format("%H:%M")))
+    }
 }
 
 impl Default for ClockTile {
@@ -77,7 +168,29 @@ impl TileRenderer for ClockTile {
     }
 
     fn update(&mut self) {
-        self.current_time = self.format_time();
+        let config = self.config();
+        let now = Local::now();
+        self.current_time = Self::format_time(&config, now);
+
+        let pattern = Self::time_pattern(&config);
+        self.zone_times = config
+            .zones
+            .iter()
+            .filter_map(|zone| {
+                let offset = FixedOffset::east_opt((zone.utc_offset_hours * 3600.0) as i32)?;
+                Some((
+                    zone.label.clone(),
+                    now.with_timezone(&offset).format(pattern).to_string(),
+                ))
+            })
+            .collect();
+
+        if config.show_almanac {
+            self.refresh_almanac(&config, Utc::now().date_naive());
+        } else {
+            self.almanac_line = None;
+        }
+        self.next_alarm = Self::next_alarm(&config, now);
     }
 
     fn render_monitor(&self, draw: &Draw, rect: Rect, ctx: &RenderContext) {
@@ -98,16 +211,37 @@ impl TileRenderer for ClockTile {
         let pulse = (ctx.time.elapsed().as_secs_f32() * 1.5).sin() * 0.05 + 0.95;
         let color = srgba(0.0, 1.0, 1.0, pulse);
 
+        // Make room for world clocks below the main time
+        let time_y = if self.zone_times.is_empty() {
+            rect.y()
+        } else {
+            rect.y() + rect.h() * 0.1
+        };
         draw_text(
             draw,
             FontId::PlexMonoRegular,
             &self.current_time,
-            rect.xy(),
+            pt2(rect.x(), time_y),
             font_size,
             color,
             TextAlignment::Center,
         );
 
+        // World clocks
+        let zone_size = (font_size * 0.3).clamp(10.0, 16.0);
+        for (i, (label, time)) in self.zone_times.iter().take(3).enumerate() {
+            let y = time_y - font_size * 0.8 - i as f32 * (zone_size + 6.0);
+            draw_text(
+                draw,
+                FontId::PlexMonoRegular,
+                &format!("{}  {}", label.to_uppercase(), time),
+                pt2(rect.x(), y),
+                zone_size,
+                srgba(0.6, 0.8, 0.85, 1.0),
+                TextAlignment::Center,
+            );
+        }
+
         // Label
         draw_text(
             draw,
@@ -118,6 +252,30 @@ impl TileRenderer for ClockTile {
             srgba(0.5, 0.5, 0.5, 1.0),
             TextAlignment::Center,
         );
+
+        if let Some(alarm) = &self.next_alarm {
+            draw_text(
+                draw,
+                FontId::PlexSansRegular,
+                &format!("ALARM {}", alarm),
+                pt2(rect.right() - 10.0, rect.top() - 20.0),
+                10.0,
+                srgba(1.0, 0.75, 0.3, 1.0),
+                TextAlignment::Right,
+            );
+        }
+
+        if let Some(line) = &self.almanac_line {
+            draw_text(
+                draw,
+                FontId::PlexSansRegular,
+                line,
+                pt2(rect.x(), rect.bottom() + 16.0),
+                10.0,
+                srgba(0.55, 0.55, 0.65, 1.0),
+                TextAlignment::Center,
+            );
+        }
     }
 
     fn render_controls(&self, draw: &Draw, rect: Rect, _ctx: &RenderContext) -> bool {
@@ -137,159 +295,69 @@ impl TileRenderer for ClockTile {
         controls::draw_subtitle(
             draw,
             pt2(rect.x(), rect.top() - 52.0),
-            "↑/↓ focus   ←/→ change   Enter toggle",
+            "↑/↓ focus   ←/→ change   Enter toggle/open   Del remove",
             controls::UiStyle { alpha: 1.0 },
         );
 
-        // Large time preview
-        let preview_rect =
-            Rect::from_x_y_w_h(rect.x(), rect.y() + 50.0, rect.w() * 0.8, rect.h() * 0.3);
-
-        let font_size = (preview_rect.h() * 0.6).min(120.0);
+        // Time preview
+        let preview_rect = Rect::from_x_y_w_h(rect.x(), rect.top() - 120.0, rect.w() * 0.8, 80.0);
         draw_text(
             draw,
             FontId::PlexMonoRegular,
             &self.current_time,
             preview_rect.xy(),
-            font_size,
+            (preview_rect.h() * 0.6).min(64.0),
             srgb(0.0, 1.0, 1.0).into(),
             TextAlignment::Center,
         );
+        if let Some(line) = &self.almanac_line {
+            draw_text(
+                draw,
+                FontId::PlexSansRegular,
+                line,
+                pt2(rect.x(), preview_rect.bottom() - 8.0),
+                12.0,
+                srgba(0.55, 0.55, 0.65, 1.0),
+                TextAlignment::Center,
+            );
+        }
 
-        // Controls list (keyboard-only)
-        let list_rect = Rect::from_x_y_w_h(
-            rect.x(),
-            rect.y() - rect.h() * 0.15,
-            rect.w() * 0.70,
-            rect.h() * 0.35,
-        );
-        let focused = self.focused_control.lock().map(|v| *v).unwrap_or(0);
-        let rows = controls::row_stack(list_rect, 3);
-
-        // 0: format stepper
-        let fmt_label = match self.format {
-            TimeFormat::TwentyFourHour => "24 Hour",
-            TimeFormat::TwelveHour => "12 Hour",
-        };
-        controls::draw_stepper_row(
-            draw,
-            rows[0],
-            "Format",
-            fmt_label,
-            focused == 0,
-            controls::UiStyle { alpha: 1.0 },
-        );
-
-        // 1: seconds toggle
-        controls::draw_toggle_row(
-            draw,
-            rows[1],
-            "Show seconds",
-            self.show_seconds,
-            focused == 1,
-            controls::UiStyle { alpha: 1.0 },
-        );
-
-        // 2: date toggle
-        controls::draw_toggle_row(
-            draw,
-            rows[2],
-            "Show date",
-            self.show_date,
-            focused == 2,
-            controls::UiStyle { alpha: 1.0 },
+        // Settings form (keyboard-only)
+        let form_w = (rect.w() * 0.7).min(640.0);
+        let form_rect = Rect::from_corners(
+            pt2(rect.x() - form_w / 2.0, rect.bottom() + 30.0),
+            pt2(rect.x() + form_w / 2.0, preview_rect.bottom() - 30.0),
         );
+        schema_view::render_form(draw, form_rect, &self.form, &self.settings);
 
         false
     }
 
-    fn handle_key(&mut self, key: nannou::prelude::Key, _ctrl: bool, _shift: bool) -> bool {
-        // Control-mode keyboard UI:
-        // - Up/Down moves focus
-        // - Left/Right changes the focused value
-        // - Enter/Space toggles/activates
-        let mut focused = self.focused_control.lock().map(|v| *v).unwrap_or(0);
-
-        match key {
-            nannou::prelude::Key::Up => {
-                focused = focused.saturating_sub(1);
-            }
-            nannou::prelude::Key::Down => {
-                focused = (focused + 1).min(2);
+    fn handle_key(&mut self, key: nannou::prelude::Key, ctrl: bool, shift: bool) -> bool {
+        let input = UiInput::from_key(key, ctrl, shift);
+        let outcome = self.form.handle_input(&input, &mut self.settings);
+        if outcome.changed {
+            match serde_json::from_value::<ClockConfig>(self.settings.clone()) {
+                Ok(config) => self.set_config(config),
+                Err(e) => log::warn!("Clock: ignoring invalid settings edit: {}", e),
             }
-            nannou::prelude::Key::Left | nannou::prelude::Key::Right => match focused {
-                0 => {
-                    self.format = match self.format {
-                        TimeFormat::TwentyFourHour => TimeFormat::TwelveHour,
-                        TimeFormat::TwelveHour => TimeFormat::TwentyFourHour,
-                    };
-                }
-                1 => self.show_seconds = !self.show_seconds,
-                2 => self.show_date = !self.show_date,
-                _ => {}
-            },
-            nannou::prelude::Key::Return | nannou::prelude::Key::Space => match focused {
-                0 => {
-                    self.format = match self.format {
-                        TimeFormat::TwentyFourHour => TimeFormat::TwelveHour,
-                        TimeFormat::TwelveHour => TimeFormat::TwentyFourHour,
-                    };
-                }
-                1 => self.show_seconds = !self.show_seconds,
-                2 => self.show_date = !self.show_date,
-                _ => {}
-            },
-            _ => return false,
         }
-
-        if let Ok(mut guard) = self.focused_control.lock() {
-            *guard = focused;
-        }
-        true
+        outcome.consumed
     }
 
     fn settings_schema(&self) -> Option<serde_json::Value> {
-        Some(serde_json::json!({
-            "type": "object",
-            "properties": {
-                "format": {
-                    "type": "string",
-                    "enum": ["TwentyFourHour", "TwelveHour"],
-                    "default": "TwentyFourHour"
-                },
-                "show_seconds": {
-                    "type": "boolean",
-                    "default": true
-                },
-                "show_date": {
-                    "type": "boolean",
-                    "default": false
-                }
-            }
-        }))
+        Some(ClockConfig::schema())
     }
 
     fn apply_settings(&mut self, settings: &serde_json::Value) {
-        if let Some(fmt) = settings.get("format").and_then(|v| v.as_str()) {
-            self.format = match fmt {
-                "TwelveHour" => TimeFormat::TwelveHour,
-                _ => TimeFormat::TwentyFourHour,
-            };
-        }
-        if let Some(s) = settings.get("show_seconds").and_then(|v| v.as_bool()) {
-            self.show_seconds = s;
-        }
-        if let Some(d) = settings.get("show_date").and_then(|v| v.as_bool()) {
-            self.show_date = d;
+        match serde_json::from_value::<ClockConfig>(settings.clone()) {
+            Ok(config) => self.set_config(config),
+            Err(e) => log::warn!("Clock: invalid settings, keeping current: {}", e),
         }
     }
 
     fn get_settings(&self) -> serde_json::Value {
-        serde_json::json!({
-            "format": format!("{:?}", self.format),
-            "show_seconds": self.show_seconds,
-            "show_date": self.show_date
-        })
+        serde_json::to_value(self.config()).unwrap_or_default()
     }
 
     fn bindable_actions(&self) -> Vec<BindableAction> {
@@ -302,14 +370,16 @@ impl TileRenderer for ClockTile {
     fn execute_action(&mut self, action: &str) -> bool {
         match action {
             "toggle_format" => {
-                self.format = match self.format {
-                    TimeFormat::TwentyFourHour => TimeFormat::TwelveHour,
-                    TimeFormat::TwelveHour => TimeFormat::TwentyFourHour,
-                };
+                self.edit_config(|c| {
+                    c.format = match c.format {
+                        TimeFormat::TwentyFourHour => TimeFormat::TwelveHour,
+                        TimeFormat::TwelveHour => TimeFormat::TwentyFourHour,
+                    };
+                });
                 true
             }
             "toggle_seconds" => {
-                self.show_seconds = !self.show_seconds;
+                self.edit_config(|c| c.show_seconds = !c.show_seconds);
                 true
             }
             _ => false,
@@ -15,10 +15,13 @@ pub mod caption;
 pub mod clock;
 pub mod compositor;
 pub mod system_monitor;
+pub mod ticker;
+pub mod transcript;
 
 // Re-export main types from magnolia_core
 pub use magnolia_core::{
-    render_error_overlay, BindableAction, RenderContext, TileRegistry, TileRenderer,
+    render_error_history, render_error_overlay, render_unavailable_placeholder, BindableAction,
+    RenderContext, TileRegistry, TileRenderer,
 };
 
 // Re-export Compositor (daemon-specific)
@@ -28,12 +31,14 @@ pub use compositor::Compositor;
 /// External tiles must be loaded via PluginManager
 pub fn create_default_registry(
     caption_state: std::sync::Arc<std::sync::Mutex<caption_state::CaptionState>>,
+    clock_config: crate::clock_source::SharedClockConfig,
+    sysmon: crate::sysmon::SharedSysmon,
 ) -> TileRegistry {
     let mut registry = TileRegistry::new();
 
     // Register local system tiles
-    registry.register(clock::ClockTile::new());
-    registry.register(system_monitor::SystemMonitorTile::new());
+    registry.register(clock::ClockTile::with_config(clock_config));
+    registry.register(system_monitor::SystemMonitorTile::new(sysmon));
     registry.register(caption::CaptionTile::new("captions", caption_state));
 
     registry
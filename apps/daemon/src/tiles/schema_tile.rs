@@ -1,3 +1,6 @@
+use crate::ui::controls::{self, UiInput, UiStyle};
+use crate::ui::schema::SchemaForm;
+use crate::ui::schema_view;
 use magnolia_core::{ControlSignal, RenderContext, Signal, TileRenderer};
 use magnolia_ui::{draw_text, FontId, TextAlignment};
 use nannou::prelude::*;
@@ -10,6 +13,8 @@ pub struct SchemaTile {
     name: String,
     schema: Option<Value>,
     settings: Mutex<Value>,
+    settings_version: u32,
+    form: SchemaForm,
     sender: Sender<Signal>,
 }
 
@@ -18,12 +23,20 @@ impl SchemaTile {
         Self {
             id: id.to_string(),
             name: name.to_string(),
+            form: SchemaForm::new(schema.clone().unwrap_or(Value::Null)),
             schema,
             settings: Mutex::new(Value::Null),
+            settings_version: 0,
             sender,
         }
     }
 
+    /// Settings version the module reads, recorded when they're saved
+    pub fn with_settings_version(mut self, version: u32) -> Self {
+        self.settings_version = version;
+        self
+    }
+
     fn send_update(&self, settings: Value) {
         let signal = Signal::Control(ControlSignal::Settings(settings));
         let _ = self.sender.try_send(signal);
@@ -66,35 +79,49 @@ impl TileRenderer for SchemaTile {
     }
 
     fn render_controls(&self, draw: &Draw, rect: Rect, _ctx: &RenderContext) -> bool {
-        // Fullscreen placeholder
         draw.rect()
             .xy(rect.xy())
             .wh(rect.wh())
             .color(rgba(0.0, 0.0, 0.0, 0.9));
 
-        draw_text(
+        let style = UiStyle::default();
+        controls::draw_heading(
             draw,
-            FontId::PlexSansBold,
+            pt2(rect.x(), rect.top() - 30.0),
             &format!("{} - SETTINGS", self.name.to_uppercase()),
-            rect.xy(),
-            80.0,
-            srgba(0.0, 1.0, 1.0, 1.0),
-            TextAlignment::Center,
+            style,
         );
-
-        draw_text(
+        controls::draw_subtitle(
             draw,
-            FontId::PlexSansRegular,
-            "Custom Nannou controls coming soon...",
-            pt2(rect.x(), rect.y() - 40.0),
-            91.0,
-            srgba(0.5, 0.5, 0.5, 1.0),
-            TextAlignment::Center,
+            pt2(rect.x(), rect.top() - 52.0),
+            "Up/Down move  Left/Right adjust  Enter open  Del remove",
+            style,
+        );
+
+        let settings = self.settings.lock().map(|s| s.clone()).unwrap_or_default();
+        let form_w = (rect.w() * 0.7).min(640.0);
+        let form_rect = Rect::from_corners(
+            pt2(rect.x() - form_w / 2.0, rect.bottom() + 30.0),
+            pt2(rect.x() + form_w / 2.0, rect.top() - 80.0),
         );
+        schema_view::render_form(draw, form_rect, &self.form, &settings);
 
         false
     }
 
+    fn handle_key(&mut self, key: Key, ctrl: bool, shift: bool) -> bool {
+        let input = UiInput::from_key(key, ctrl, shift);
+        let Ok(settings) = self.settings.get_mut() else {
+            return false;
+        };
+        let outcome = self.form.handle_input(&input, settings);
+        if outcome.changed {
+            let updated = settings.clone();
+            self.send_update(updated);
+        }
+        outcome.consumed
+    }
+
     fn settings_schema(&self) -> Option<Value> {
         self.schema.clone()
     }
@@ -110,6 +137,10 @@ impl TileRenderer for SchemaTile {
         self.settings.lock().unwrap().clone()
     }
 
+    fn settings_version(&self) -> u32 {
+        self.settings_version
+    }
+
     fn update(&mut self) {
         // Nothing for now
     }
@@ -1,171 +1,27 @@
 //! System Monitor Tile - Real-time system metrics visualization
 //!
-//! Monitor mode: Text-based summary of CPU, RAM, and GPU
+//! Monitor mode: CPU, RAM, GPU and network with sparklines
 //! Control mode: Detailed line graphs for all metrics
+//!
+//! Sampling happens in [`crate::sysmon::SysmonSource`]; this tile draws the
+//! shared history it records.
 
 use super::{RenderContext, TileRenderer};
-use nannou::prelude::*;
-// use serde::{Deserialize, Serialize};
+use crate::sysmon::{SharedSysmon, HISTORY_SIZE};
 use magnolia_ui::{draw_text, FontId, TextAlignment};
-use nvml_wrapper::Nvml;
+use nannou::prelude::*;
 use std::collections::VecDeque;
-use std::fs;
-use std::time::{Duration, Instant};
-use sysinfo::{Disks, Networks, System};
-
-const HISTORY_SIZE: usize = 60;
-const REFRESH_INTERVAL: Duration = Duration::from_millis(1000); // 1Hz
 
 pub struct SystemMonitorTile {
-    sys: System,
-    networks: Networks,
-    _disks: Disks,
-    nvml: Option<Nvml>,
-    intel_gpu_path: Option<String>,
-
-    last_refresh: Instant,
-
-    // History buffers
-    cpu_history: VecDeque<f32>,
-    per_core_history: Vec<VecDeque<f32>>,
-    mem_history: VecDeque<f32>,
-    gpu_util_history: VecDeque<f32>,
-    gpu_temp_history: VecDeque<f32>,
-    net_up_history: VecDeque<f32>,
-    net_down_history: VecDeque<f32>,
-
-    // Current values for monitor mode
-    current_cpu: f32,
-    current_mem_gb: f32,
-    current_gpu_util: f32,
-    current_gpu_temp: f32,
+    history: SharedSysmon,
 }
 
 impl SystemMonitorTile {
-    pub fn new() -> Self {
-        let mut sys = System::new_all();
-        sys.refresh_cpu_all();
-        sys.refresh_memory();
-
-        let networks = Networks::new_with_refreshed_list();
-        let disks = Disks::new_with_refreshed_list();
-        let nvml = Nvml::init().ok();
-
-        // Try to find Intel GPU busy percent path
-        let intel_gpu_path = if fs::metadata("/sys/class/drm/card0/device/gpu_busy_percent").is_ok()
-        {
-            Some("/sys/class/drm/card0/device/gpu_busy_percent".to_string())
-        } else if fs::metadata("/sys/class/drm/card1/device/gpu_busy_percent").is_ok() {
-            Some("/sys/class/drm/card1/device/gpu_busy_percent".to_string())
-        } else {
-            None
-        };
-
-        let core_count = sys.cpus().len();
-
-        Self {
-            sys,
-            networks,
-            _disks: disks,
-            nvml,
-            intel_gpu_path,
-            last_refresh: Instant::now() - REFRESH_INTERVAL,
-            cpu_history: VecDeque::with_capacity(HISTORY_SIZE),
-            per_core_history: vec![VecDeque::with_capacity(HISTORY_SIZE); core_count],
-            mem_history: VecDeque::with_capacity(HISTORY_SIZE),
-            gpu_util_history: VecDeque::with_capacity(HISTORY_SIZE),
-            gpu_temp_history: VecDeque::with_capacity(HISTORY_SIZE),
-            net_up_history: VecDeque::with_capacity(HISTORY_SIZE),
-            net_down_history: VecDeque::with_capacity(HISTORY_SIZE),
-            current_cpu: 0.0,
-            current_mem_gb: 0.0,
-            current_gpu_util: 0.0,
-            current_gpu_temp: 0.0,
-        }
-    }
-
-    fn refresh_metrics(&mut self) {
-        self.sys.refresh_cpu_all();
-        self.sys.refresh_memory();
-        self.networks.refresh(true);
-
-        // CPU
-        self.current_cpu = self.sys.global_cpu_usage();
-        push_history(&mut self.cpu_history, self.current_cpu);
-
-        for (i, cpu) in self.sys.cpus().iter().enumerate() {
-            if i < self.per_core_history.len() {
-                push_history(&mut self.per_core_history[i], cpu.cpu_usage());
-            }
-        }
-
-        // Memory
-        let used_mem = self.sys.used_memory() as f32 / (1024.0 * 1024.0 * 1024.0);
-        self.current_mem_gb = used_mem;
-        let total_mem = self.sys.total_memory() as f32 / (1024.0 * 1024.0 * 1024.0);
-        push_history(&mut self.mem_history, (used_mem / total_mem) * 100.0);
-
-        // GPU (NVIDIA)
-        let mut gpu_found = false;
-        if let Some(ref nvml) = self.nvml {
-            if let Ok(device) = nvml.device_by_index(0) {
-                if let Ok(util) = device.utilization_rates() {
-                    self.current_gpu_util = util.gpu as f32;
-                    push_history(&mut self.gpu_util_history, self.current_gpu_util);
-                    gpu_found = true;
-                }
-                if let Ok(temp) =
-                    device.temperature(nvml_wrapper::enum_wrappers::device::TemperatureSensor::Gpu)
-                {
-                    self.current_gpu_temp = temp as f32;
-                    push_history(&mut self.gpu_temp_history, self.current_gpu_temp);
-                }
-            }
-        }
-
-        // GPU (Intel fallback)
-        if !gpu_found {
-            if let Some(ref path) = self.intel_gpu_path {
-                if let Ok(content) = fs::read_to_string(path) {
-                    if let Ok(val) = content.trim().parse::<f32>() {
-                        self.current_gpu_util = val;
-                        push_history(&mut self.gpu_util_history, val);
-                        gpu_found = true;
-                    }
-                }
-            }
-        }
-
-        if !gpu_found {
-            push_history(&mut self.gpu_util_history, 0.0);
-        }
-
-        // Network
-        let mut total_received = 0.0;
-        let mut total_transmitted = 0.0;
-        for (_interface_name, data) in &self.networks {
-            total_received += data.received() as f32;
-            total_transmitted += data.transmitted() as f32;
-        }
-        // Convert to MB/s (assuming 1s interval)
-        push_history(
-            &mut self.net_down_history,
-            total_received / (1024.0 * 1024.0),
-        );
-        push_history(
-            &mut self.net_up_history,
-            total_transmitted / (1024.0 * 1024.0),
-        );
+    pub fn new(history: SharedSysmon) -> Self {
+        Self { history }
     }
 }
 
-fn push_history(history: &mut VecDeque<f32>, value: f32) {
-    if history.len() >= HISTORY_SIZE {
-        history.pop_front();
-    }
-    history.push_back(value);
-}
-
 impl TileRenderer for SystemMonitorTile {
     fn id(&self) -> &str {
         "system_monitor"
@@ -174,12 +30,7 @@ impl TileRenderer for SystemMonitorTile {
         "System Monitor"
     }
 
-    fn update(&mut self) {
-        if self.last_refresh.elapsed() >= REFRESH_INTERVAL {
-            self.refresh_metrics();
-            self.last_refresh = Instant::now();
-        }
-    }
+    fn update(&mut self) {}
 
     fn render_monitor(&self, draw: &Draw, rect: Rect, _ctx: &RenderContext) {
         draw.rect()
@@ -187,26 +38,48 @@ impl TileRenderer for SystemMonitorTile {
             .wh(rect.wh())
             .color(srgba(0.02, 0.02, 0.05, 0.95));
 
+        let Ok(history) = self.history.lock() else {
+            return;
+        };
+        let latest = &history.latest;
+
         let font_size = (rect.h() * 0.15).min(16.0);
         let margin = 10.0;
         let x = rect.left() + margin;
         let mut y = rect.top() - margin;
 
+        // Sparklines fill the space right of the text column
+        let spark_left = rect.left() + rect.w() * 0.55;
+        let spark_w = rect.right() - margin - spark_left;
+
+        let gpu = match (latest.gpu_util, latest.gpu_temp) {
+            (Some(util), Some(temp)) => format!("GPU: {:.0}% / {:.0}°C", util, temp),
+            (Some(util), None) => format!("GPU: {:.0}%", util),
+            _ => "GPU: n/a".to_string(),
+        };
         let stats = [
-            format!("CPU: {:.1}%", self.current_cpu),
-            format!("RAM: {:.1} GB", self.current_mem_gb),
-            format!(
-                "GPU: {:.0}% / {:.0}°C",
-                self.current_gpu_util, self.current_gpu_temp
+            (
+                format!("CPU: {:.1}%", latest.cpu),
+                &history.cpu,
+                100.0,
+                srgba(0.0, 1.0, 0.5, 1.0),
+            ),
+            (
+                format!("RAM: {:.1} GB", latest.mem_used_gb),
+                &history.mem,
+                100.0,
+                srgba(0.2, 0.6, 1.0, 1.0),
             ),
-            format!(
-                "NET: ↓{:.1} ↑{:.1} MB/s",
-                self.net_down_history.back().cloned().unwrap_or(0.0),
-                self.net_up_history.back().cloned().unwrap_or(0.0)
+            (gpu, &history.gpu_util, 100.0, srgba(0.8, 0.2, 1.0, 1.0)),
+            (
+                format!("NET: ↓{:.1} ↑{:.1} MB/s", latest.net_down, latest.net_up),
+                &history.net_down,
+                10.0,
+                srgba(1.0, 0.8, 0.0, 1.0),
             ),
         ];
 
-        for stat in stats {
+        for (stat, series, max_val, color) in stats {
             draw_text(
                 draw,
                 FontId::PlexMonoRegular,
@@ -216,6 +89,13 @@ impl TileRenderer for SystemMonitorTile {
                 srgba(0.0, 1.0, 0.8, 1.0),
                 TextAlignment::Left,
             );
+            if spark_w > 20.0 {
+                let spark = Rect::from_corners(
+                    pt2(spark_left, y - font_size * 1.1),
+                    pt2(spark_left + spark_w, y - font_size * 0.1),
+                );
+                draw_sparkline(draw, spark, series, max_val, color);
+            }
             y -= font_size * 1.5;
         }
     }
@@ -226,6 +106,10 @@ impl TileRenderer for SystemMonitorTile {
             .wh(rect.wh())
             .color(srgba(0.01, 0.01, 0.02, 1.0));
 
+        let Ok(history) = self.history.lock() else {
+            return false;
+        };
+
         let padding = 20.0;
         let inner_rect = rect.pad(padding);
         let graph_spacing = 10.0;
@@ -242,7 +126,7 @@ impl TileRenderer for SystemMonitorTile {
             pt2(inner_rect.left(), current_y),
             inner_rect.w(),
             main_graph_height,
-            &self.cpu_history,
+            &history.cpu,
             "CPU Global (%)",
             srgba(0.0, 1.0, 0.5, 1.0),
             100.0,
@@ -255,7 +139,7 @@ impl TileRenderer for SystemMonitorTile {
             pt2(inner_rect.left(), current_y),
             inner_rect.w(),
             main_graph_height,
-            &self.mem_history,
+            &history.mem,
             "Memory (%)",
             srgba(0.2, 0.6, 1.0, 1.0),
             100.0,
@@ -268,7 +152,7 @@ impl TileRenderer for SystemMonitorTile {
             pt2(inner_rect.left(), current_y),
             inner_rect.w(),
             main_graph_height,
-            &self.gpu_util_history,
+            &history.gpu_util,
             "GPU Utilization (%)",
             srgba(0.8, 0.2, 1.0, 1.0),
             100.0,
@@ -281,7 +165,7 @@ impl TileRenderer for SystemMonitorTile {
             pt2(inner_rect.left(), current_y),
             inner_rect.w(),
             main_graph_height,
-            &self.net_down_history,
+            &history.net_down,
             "Network Down (MB/s)",
             srgba(1.0, 0.8, 0.0, 1.0),
             10.0,
@@ -290,11 +174,12 @@ impl TileRenderer for SystemMonitorTile {
 
         // Per-core CPU (small grid at the bottom)
         let core_cols = 8;
-        let core_rows = (self.per_core_history.len() as f32 / core_cols as f32).ceil() as usize;
+        // Empty until the first sample arrives
+        let core_rows = history.per_core.len().div_ceil(core_cols).max(1);
         let core_graph_w = (inner_rect.w() - (core_cols - 1) as f32 * 5.0) / core_cols as f32;
         let core_graph_h = (inner_rect.h() / 2.0 - (core_rows - 1) as f32 * 5.0) / core_rows as f32;
 
-        for (i, core_history) in self.per_core_history.iter().enumerate() {
+        for (i, core_history) in history.per_core.iter().enumerate() {
             let col = i % core_cols;
             let row = i / core_cols;
             let x = inner_rect.left() + col as f32 * (core_graph_w + 5.0);
@@ -316,6 +201,23 @@ impl TileRenderer for SystemMonitorTile {
     }
 }
 
+fn draw_sparkline(draw: &Draw, rect: Rect, history: &VecDeque<f32>, max_val: f32, color: Srgba) {
+    if history.len() < 2 {
+        return;
+    }
+    let step_x = rect.w() / (HISTORY_SIZE as f32 - 1.0);
+    // Right-align so the newest sample always sits at the edge
+    let offset = (HISTORY_SIZE - history.len()) as f32 * step_x;
+    let points = history.iter().enumerate().map(|(i, &val)| {
+        let norm_val = (val / max_val).clamp(0.0, 1.0);
+        pt2(
+            rect.left() + offset + i as f32 * step_x,
+            rect.bottom() + norm_val * rect.h(),
+        )
+    });
+    draw.polyline().weight(1.0).points(points).color(color);
+}
+
 fn draw_graph(
     draw: &Draw,
     top_left: Point2,
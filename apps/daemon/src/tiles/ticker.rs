@@ -0,0 +1,168 @@
+use super::{BindableAction, RenderContext, TileRenderer};
+use magnolia_ui::{draw_text, text_width, FontId, TextAlignment};
+use nannou::prelude::*;
+use std::time::Instant;
+use text_tools::ticker::{marquee_x, SharedTicker, TickerConfig, TickerFont};
+
+fn font_id(font: TickerFont) -> FontId {
+    match font {
+        TickerFont::Sans => FontId::PlexSansRegular,
+        TickerFont::SansBold => FontId::PlexSansBold,
+        TickerFont::Mono => FontId::PlexMonoRegular,
+    }
+}
+
+/// Marquee tile scrolling the ticker sink's recent lines right to left.
+pub struct TickerTile {
+    id: String,
+    state: SharedTicker,
+    config: TickerConfig,
+    /// Strip text and its width, re-measured when the state revision changes
+    strip: String,
+    strip_width: f32,
+    revision: Option<u64>,
+    /// Pixels scrolled since start
+    offset: f64,
+    last_update: Instant,
+}
+
+impl TickerTile {
+    pub fn new(id: &str, state: SharedTicker) -> Self {
+        Self {
+            id: id.to_string(),
+            state,
+            config: TickerConfig::default(),
+            strip: String::new(),
+            strip_width: 0.0,
+            revision: None,
+            offset: 0.0,
+            last_update: Instant::now(),
+        }
+    }
+}
+
+impl TileRenderer for TickerTile {
+    fn id(&self) -> &str {
+        &self.id
+    }
+    fn name(&self) -> &str {
+        "Ticker"
+    }
+
+    fn update(&mut self) {
+        let now = Instant::now();
+        let dt = (now - self.last_update).as_secs_f64();
+        self.last_update = now;
+
+        if let Ok(state) = self.state.lock() {
+            if self.revision != Some(state.revision) {
+                self.revision = Some(state.revision);
+                self.config = state.config.clone();
+                self.strip = state.strip();
+                self.strip_width = text_width(
+                    font_id(self.config.font),
+                    &self.strip,
+                    self.config.font_size,
+                );
+            }
+        }
+        if !self.strip.is_empty() {
+            self.offset += dt * self.config.speed as f64;
+        }
+    }
+
+    fn render_monitor(&self, draw: &Draw, rect: Rect, _ctx: &RenderContext) {
+        draw.rect()
+            .xy(rect.xy())
+            .wh(rect.wh())
+            .color(srgba(0.0, 0.0, 0.0, 0.96));
+
+        if self.strip.is_empty() {
+            draw_text(
+                draw,
+                FontId::PlexSansRegular,
+                "Waiting for text…",
+                rect.xy(),
+                14.0,
+                srgba(0.45, 0.48, 0.55, 1.0),
+                TextAlignment::Center,
+            );
+            return;
+        }
+
+        let size = self.config.font_size.min(rect.h() * 0.8);
+        let scale = size / self.config.font_size.max(1.0);
+        let x = marquee_x(self.offset, self.strip_width * scale, rect.w());
+        draw_text(
+            &draw.scissor(rect),
+            font_id(self.config.font),
+            &self.strip,
+            pt2(rect.left() + x, rect.y()),
+            size,
+            srgba(0.95, 0.96, 1.0, 1.0),
+            TextAlignment::Left,
+        );
+    }
+
+    fn render_controls(&self, draw: &Draw, rect: Rect, ctx: &RenderContext) -> bool {
+        self.render_monitor(draw, rect, ctx);
+        draw_text(
+            draw,
+            FontId::PlexMonoRegular,
+            &format!(
+                "{:.0} px/s  |  {} px  |  history {}",
+                self.config.speed, self.config.font_size, self.config.history
+            ),
+            pt2(rect.x(), rect.bottom() + 22.0),
+            11.0,
+            srgba(0.45, 0.48, 0.55, 1.0),
+            TextAlignment::Center,
+        );
+        false
+    }
+
+    fn settings_schema(&self) -> Option<serde_json::Value> {
+        Some(TickerConfig::schema())
+    }
+
+    fn apply_settings(&mut self, settings: &serde_json::Value) {
+        match serde_json::from_value::<TickerConfig>(settings.clone()) {
+            Ok(config) => {
+                if let Ok(mut state) = self.state.lock() {
+                    if state.config != config {
+                        state.set_config(config);
+                    }
+                }
+            }
+            Err(e) => log::warn!("Ticker: invalid settings, keeping current: {}", e),
+        }
+    }
+
+    fn get_settings(&self) -> serde_json::Value {
+        let config = match self.state.lock() {
+            Ok(state) => state.config.clone(),
+            Err(_) => self.config.clone(),
+        };
+        serde_json::to_value(config).unwrap_or_default()
+    }
+
+    fn get_display_text(&self) -> Option<String> {
+        Some(self.strip.clone())
+    }
+
+    fn bindable_actions(&self) -> Vec<BindableAction> {
+        vec![BindableAction::new("clear", "Clear Ticker", false)]
+    }
+
+    fn execute_action(&mut self, action: &str) -> bool {
+        if action != "clear" {
+            return false;
+        }
+        if let Ok(mut state) = self.state.lock() {
+            state.clear();
+            self.offset = 0.0;
+            return true;
+        }
+        false
+    }
+}
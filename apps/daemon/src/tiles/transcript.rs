@@ -0,0 +1,372 @@
+use super::caption::CaptionTile;
+use super::{BindableAction, RenderContext, TileRenderer};
+use caption_state::transcript::{format_timestamp, Transcript};
+use magnolia_ui::{draw_text, FontId, TextAlignment};
+use nannou::prelude::*;
+use std::sync::{Arc, Mutex};
+
+const LINE_HEIGHT: f32 = 18.0;
+const PAGE: usize = 10;
+
+struct Line {
+    text: String,
+    provisional: bool,
+    selected: bool,
+}
+
+/// Scrollback of the whole STT session: finals solid, the live partial
+/// dimmed. In the maximized view the arrow keys select a segment to show its
+/// timing (and word timestamps when the backend reports them); `E` exports
+/// the session to a file (into the session folder while one runs) and Ctrl+C
+/// copies it.
+pub struct TranscriptTile {
+    id: String,
+    state: Arc<Mutex<Transcript>>,
+    /// Segment pinned to the bottom of the view; `None` follows the newest
+    selected: Option<usize>,
+    show_timestamps: bool,
+    export_dir: String,
+    /// Outcome of the last export, shown in the maximized view
+    last_export: Option<String>,
+    session: Option<magnolia_core::SessionState>,
+}
+
+impl TranscriptTile {
+    pub fn new(id: &str, state: Arc<Mutex<Transcript>>) -> Self {
+        Self {
+            id: id.to_string(),
+            state,
+            selected: None,
+            show_timestamps: false,
+            export_dir: ".".to_string(),
+            last_export: None,
+            session: None,
+        }
+    }
+
+    /// Export into `session`'s folder while one runs
+    pub fn with_session(mut self, session: magnolia_core::SessionState) -> Self {
+        self.session = Some(session);
+        self
+    }
+
+    /// Wrapped lines ending at the selected (or newest) segment, oldest first
+    fn layout(&self, state: &Transcript, max_chars: usize, max_lines: usize) -> Vec<Line> {
+        let end = match self.selected {
+            Some(index) => (index + 1).min(state.segments.len()),
+            None => state.segments.len(),
+        };
+        let mut lines = Vec::new();
+        if self.selected.is_none() {
+            if let Some(partial) = &state.provisional {
+                for text in CaptionTile::lines(&partial.text, max_chars)
+                    .into_iter()
+                    .rev()
+                {
+                    lines.push(Line {
+                        text,
+                        provisional: true,
+                        selected: false,
+                    });
+                }
+            }
+        }
+        for (index, segment) in state.segments.range(..end).enumerate().rev() {
+            if lines.len() >= max_lines {
+                break;
+            }
+            let text = if self.show_timestamps {
+                format!("{} {}", format_timestamp(segment.start_ms), segment.text)
+            } else {
+                segment.text.clone()
+            };
+            for text in CaptionTile::lines(&text, max_chars).into_iter().rev() {
+                lines.push(Line {
+                    text,
+                    provisional: false,
+                    selected: self.selected == Some(index),
+                });
+            }
+        }
+        lines.truncate(max_lines);
+        lines.reverse();
+        lines
+    }
+
+    fn draw_lines(&self, draw: &Draw, rect: Rect, size: f32) {
+        let Ok(state) = self.state.lock() else { return };
+        if state.is_empty() {
+            draw_text(
+                draw,
+                FontId::PlexSansRegular,
+                "Waiting for speech…",
+                rect.xy(),
+                14.0,
+                srgba(0.45, 0.48, 0.55, 1.0),
+                TextAlignment::Center,
+            );
+            return;
+        }
+
+        let max_chars = ((rect.w() / (size * 0.6)) as usize).max(18);
+        let max_lines = ((rect.h() / LINE_HEIGHT) as usize).max(1);
+        let lines = self.layout(&state, max_chars, max_lines);
+        let mut y = rect.bottom() + LINE_HEIGHT * (lines.len() as f32 - 0.5);
+        for line in &lines {
+            if line.selected {
+                draw.rect()
+                    .x_y(rect.x(), y)
+                    .w_h(rect.w(), LINE_HEIGHT)
+                    .color(srgba(0.15, 0.25, 0.35, 0.8));
+            }
+            draw_text(
+                draw,
+                FontId::PlexSansRegular,
+                &line.text,
+                pt2(rect.left(), y),
+                size,
+                if line.provisional {
+                    srgba(0.70, 0.72, 0.80, 0.6)
+                } else {
+                    srgba(0.95, 0.96, 1.0, 1.0)
+                },
+                TextAlignment::Left,
+            );
+            y -= LINE_HEIGHT;
+        }
+    }
+
+    fn segment_count(&self) -> usize {
+        self.state.lock().map(|s| s.segments.len()).unwrap_or(0)
+    }
+
+    /// Move the selection by `delta` segments; past the newest resumes
+    /// following
+    fn select(&mut self, delta: isize) {
+        let len = self.segment_count();
+        if len == 0 {
+            self.selected = None;
+            return;
+        }
+        let current = self.selected.unwrap_or(len) as isize;
+        let next = (current + delta).max(0) as usize;
+        self.selected = (next < len).then_some(next);
+    }
+
+    fn export(&mut self) -> bool {
+        let Ok(text) = self.state.lock().map(|s| s.export_text()) else {
+            return false;
+        };
+        let stamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let dir = match &self.session {
+            Some(session) => session.resolve(&self.export_dir),
+            None => std::path::PathBuf::from(&self.export_dir),
+        };
+        let path = dir.join(format!("transcript-{stamp}.txt"));
+        let result = std::fs::write(&path, text);
+        self.last_export = Some(match &result {
+            Ok(()) => format!("exported {}", path.display()),
+            Err(e) => format!("export failed: {}: {}", path.display(), e),
+        });
+        match result {
+            Ok(()) => log::info!("Transcript exported to {}", path.display()),
+            Err(e) => log::warn!("Transcript export to {} failed: {}", path.display(), e),
+        }
+        true
+    }
+}
+
+impl TileRenderer for TranscriptTile {
+    fn id(&self) -> &str {
+        &self.id
+    }
+    fn name(&self) -> &str {
+        "Transcript"
+    }
+    fn update(&mut self) {
+        let len = self.segment_count();
+        if self.selected.is_some_and(|index| index >= len) {
+            self.selected = None;
+        }
+    }
+
+    fn render_monitor(&self, draw: &Draw, rect: Rect, _ctx: &RenderContext) {
+        draw.rect()
+            .xy(rect.xy())
+            .wh(rect.wh())
+            .color(srgba(0.02, 0.02, 0.05, 0.96));
+
+        draw_text(
+            draw,
+            FontId::PlexSansBold,
+            "TRANSCRIPT",
+            pt2(rect.x(), rect.top() - 18.0),
+            12.0,
+            srgba(0.0, 1.0, 1.0, 0.9),
+            TextAlignment::Center,
+        );
+
+        let body = Rect::from_x_y_w_h(rect.x(), rect.y() - 14.0, rect.w() - 24.0, rect.h() - 48.0);
+        self.draw_lines(&draw.scissor(body), body, 14.0);
+    }
+
+    fn render_controls(&self, draw: &Draw, rect: Rect, _ctx: &RenderContext) -> bool {
+        draw.rect()
+            .xy(rect.xy())
+            .wh(rect.wh())
+            .color(srgba(0.02, 0.02, 0.05, 0.98));
+
+        let panel_w = (rect.w() * 0.3).clamp(220.0, 360.0);
+        let body = Rect::from_x_y_w_h(
+            rect.left() + (rect.w() - panel_w) / 2.0,
+            rect.y(),
+            rect.w() - panel_w - 60.0,
+            rect.h() - 100.0,
+        );
+        self.draw_lines(&draw.scissor(body), body, 16.0);
+
+        let mut info = Vec::new();
+        if let Ok(state) = self.state.lock() {
+            info.push(format!("Status: {:?}", state.status));
+            if let Some(session) = &state.session_id {
+                info.push(format!("Session: {}", session));
+            }
+            info.push(format!("Segments: {}", state.segments.len()));
+            if !state.markers.is_empty() {
+                info.push(format!("Markers: {}", state.markers.len()));
+            }
+            info.push(String::new());
+            match self.selected.and_then(|index| state.segments.get(index)) {
+                Some(segment) => {
+                    info.push(format!("Segment #{}", segment.segment_id));
+                    info.push(format!(
+                        "{} - {}",
+                        format_timestamp(segment.start_ms),
+                        format_timestamp(segment.end_ms)
+                    ));
+                    if segment.words.is_empty() {
+                        info.push("(no word timings)".to_string());
+                    }
+                    info.extend(
+                        segment.words.iter().map(|word| {
+                            format!("{} {}", format_timestamp(word.start_ms), word.word)
+                        }),
+                    );
+                }
+                None => info.push("Following live text".to_string()),
+            }
+        }
+        if let Some(export) = &self.last_export {
+            info.push(String::new());
+            info.push(export.clone());
+        }
+
+        let x = rect.right() - panel_w - 10.0;
+        let mut y = rect.top() - 50.0;
+        for line in &info {
+            if y < rect.bottom() + 60.0 {
+                break;
+            }
+            draw_text(
+                draw,
+                FontId::PlexMonoRegular,
+                line,
+                pt2(x, y),
+                11.0,
+                srgba(0.75, 0.78, 0.85, 1.0),
+                TextAlignment::Left,
+            );
+            y -= 16.0;
+        }
+
+        draw_text(
+            draw,
+            FontId::PlexMonoRegular,
+            "Up/Down/PgUp/PgDn: select  |  End: follow  |  T: timestamps  |  E: export  |  C: clear  |  Ctrl+C: copy",
+            pt2(rect.x(), rect.bottom() + 22.0),
+            11.0,
+            srgba(0.45, 0.48, 0.55, 1.0),
+            TextAlignment::Center,
+        );
+        false
+    }
+
+    fn handle_key(&mut self, key: Key, _ctrl: bool, _shift: bool) -> bool {
+        match key {
+            Key::Up => self.select(-1),
+            Key::Down => self.select(1),
+            Key::PageUp => self.select(-(PAGE as isize)),
+            Key::PageDown => self.select(PAGE as isize),
+            Key::Home => self.selected = (self.segment_count() > 0).then_some(0),
+            Key::End => self.selected = None,
+            Key::T => self.show_timestamps = !self.show_timestamps,
+            Key::E => return self.export(),
+            Key::C => return self.execute_action("clear"),
+            _ => return false,
+        }
+        true
+    }
+
+    fn get_display_text(&self) -> Option<String> {
+        self.state.lock().ok().map(|state| state.plain_text())
+    }
+
+    fn settings_schema(&self) -> Option<serde_json::Value> {
+        Some(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "show_timestamps": {
+                    "type": "boolean",
+                    "default": false,
+                    "title": "Show Timestamps"
+                },
+                "export_dir": {
+                    "type": "string",
+                    "default": ".",
+                    "title": "Export Directory"
+                }
+            }
+        }))
+    }
+
+    fn apply_settings(&mut self, settings: &serde_json::Value) {
+        if let Some(show) = settings.get("show_timestamps").and_then(|v| v.as_bool()) {
+            self.show_timestamps = show;
+        }
+        if let Some(dir) = settings.get("export_dir").and_then(|v| v.as_str()) {
+            self.export_dir = dir.to_string();
+        }
+    }
+
+    fn get_settings(&self) -> serde_json::Value {
+        serde_json::json!({
+            "show_timestamps": self.show_timestamps,
+            "export_dir": self.export_dir
+        })
+    }
+
+    fn bindable_actions(&self) -> Vec<BindableAction> {
+        vec![
+            BindableAction::new("clear", "Clear Transcript", false),
+            BindableAction::new("export", "Export Transcript", false),
+        ]
+    }
+
+    fn execute_action(&mut self, action: &str) -> bool {
+        match action {
+            "clear" => {
+                self.selected = None;
+                if let Ok(mut state) = self.state.lock() {
+                    state.clear();
+                    return true;
+                }
+                false
+            }
+            "export" => self.export(),
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use crate::ui::controls::{FocusModel, List};
+use crate::ui::fullscreen_modal::{
+    calculate_modal_rect, draw_label_muted, draw_list_item, draw_modal_background,
+    draw_modal_header, ModalAnim,
+};
+use crate::ui::search;
+use crate::ui::thumbnails::TileThumbnails;
+use magnolia_ui::{FontId, TextAlignment};
+use nannou::prelude::*;
+
+/// Height of a row, most of it the thumbnail
+const ROW_H: f32 = 84.0;
+
+/// The registered tiles matching the typed filter, each with a live
+/// thumbnail, and a larger preview of the selected one
+pub fn render(
+    draw: &Draw,
+    rect: Rect,
+    query: &str,
+    modules: &[(String, Vec<usize>)],
+    selected: usize,
+    thumbnails: &TileThumbnails,
+    anim: &ModalAnim,
+) {
+    let modal_rect = calculate_modal_rect(rect, anim);
+    draw_modal_background(draw, modal_rect, anim);
+    let header = if query.is_empty() {
+        "ADD TILE".to_string()
+    } else {
+        format!("ADD TILE: {}", query)
+    };
+    let content_rect = draw_modal_header(draw, modal_rect, &header, anim);
+    let alpha = anim.eased();
+
+    let footer_h = 30.0;
+    let list_w = (content_rect.w() * 0.45).max(280.0).min(content_rect.w());
+    let list_rect = Rect::from_corners(
+        pt2(content_rect.left(), content_rect.bottom() + footer_h),
+        pt2(content_rect.left() + list_w, content_rect.top()),
+    );
+    let focus = FocusModel {
+        focused: selected,
+        ..Default::default()
+    };
+    List::new(&focus, list_rect, modules.len(), ROW_H)
+        .with_title("MODULES")
+        .render(draw, |i, selected, item_rect| {
+            let thumb_w = (item_rect.h() - 8.0) * 16.0 / 9.0;
+            let thumb_rect = Rect::from_x_y_w_h(
+                item_rect.left() + 12.0 + thumb_w / 2.0,
+                item_rect.y(),
+                thumb_w,
+                item_rect.h() - 8.0,
+            );
+            let (module, positions) = &modules[i];
+            draw_list_item(draw, item_rect, "", selected, alpha);
+            if !thumbnails.draw(draw, module, thumb_rect) {
+                draw.rect()
+                    .xy(thumb_rect.xy())
+                    .wh(thumb_rect.wh())
+                    .color(rgba(0.08, 0.08, 0.1, alpha));
+            }
+            search::draw_matched(
+                draw,
+                FontId::PlexSansRegular,
+                module,
+                positions,
+                pt2(thumb_rect.right() + 15.0, item_rect.y()),
+                14.0,
+                if selected {
+                    rgba(0.0, 1.0, 1.0, alpha)
+                } else {
+                    rgba(0.7, 0.7, 0.7, alpha)
+                },
+                TextAlignment::Left,
+            );
+        });
+
+    // The selected tile, as big as fits
+    let preview_rect = Rect::from_corners(
+        pt2(list_rect.right() + 20.0, list_rect.bottom()),
+        pt2(content_rect.right(), list_rect.top() - 30.0),
+    );
+    if preview_rect.w() > 40.0 {
+        if let Some((module, _)) = modules.get(selected) {
+            draw.rect()
+                .xy(preview_rect.xy())
+                .wh(preview_rect.wh())
+                .color(rgba(0.03, 0.03, 0.04, 0.8 * alpha))
+                .stroke(rgba(0.2, 0.2, 0.25, alpha))
+                .stroke_weight(1.0);
+            thumbnails.draw(draw, module, preview_rect.pad(10.0));
+        }
+    }
+
+    draw_label_muted(
+        draw,
+        content_rect.left() + 5.0,
+        content_rect.bottom() + 10.0,
+        "[TYPE] Filter   [UP/DOWN] Choose   [ENTER] Add tile   [ESC] Clear / Cancel",
+        alpha,
+    );
+}
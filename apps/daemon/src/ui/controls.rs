@@ -147,6 +147,129 @@ pub fn draw_stepper_row(
     );
 }
 
+#[allow(clippy::too_many_arguments)]
+pub fn draw_slider_row(
+    draw: &Draw,
+    rect: Rect,
+    label: &str,
+    value: f32,
+    min: f32,
+    max: f32,
+    focused: bool,
+    style: UiStyle,
+) {
+    row_bg(draw, rect, focused, style);
+
+    draw_text(
+        draw,
+        FontId::PlexSansRegular,
+        label,
+        pt2(rect.left() + LABEL_X_PAD, rect.y()),
+        14.0,
+        rgba(0.85, 0.85, 0.88, style.alpha),
+        TextAlignment::Left,
+    );
+
+    // Bar
+    let bar_w = rect.w() * 0.4;
+    let bar_h = 4.0;
+    let bar_x = rect.right() - VALUE_X_PAD - bar_w / 2.0;
+    let norm = if max > min {
+        ((value - min) / (max - min)).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    // Track
+    draw.rect()
+        .x_y(bar_x, rect.y())
+        .w_h(bar_w, bar_h)
+        .color(rgba(0.3, 0.3, 0.35, style.alpha));
+
+    // Fill
+    let fill_w = bar_w * norm;
+    draw.rect()
+        .x_y(bar_x - bar_w / 2.0 + fill_w / 2.0, rect.y())
+        .w_h(fill_w, bar_h)
+        .color(rgba(0.0, 1.0, 1.0, style.alpha));
+
+    // Knob
+    if focused {
+        draw.ellipse()
+            .x_y(bar_x - bar_w / 2.0 + fill_w, rect.y())
+            .radius(4.0)
+            .color(WHITE);
+    }
+}
+
+/// Label with a plain right-aligned value (read-only fields, pickers, dropdowns).
+pub fn draw_value_row(
+    draw: &Draw,
+    rect: Rect,
+    label: &str,
+    value_text: &str,
+    focused: bool,
+    style: UiStyle,
+) {
+    row_bg(draw, rect, focused, style);
+
+    draw_text(
+        draw,
+        FontId::PlexSansRegular,
+        label,
+        pt2(rect.left() + LABEL_X_PAD, rect.y()),
+        14.0,
+        rgba(0.85, 0.85, 0.88, style.alpha),
+        TextAlignment::Left,
+    );
+
+    draw_text(
+        draw,
+        FontId::PlexSansBold,
+        value_text,
+        pt2(rect.right() - VALUE_X_PAD, rect.y()),
+        14.0,
+        rgba(0.7, 0.75, 0.8, style.alpha),
+        TextAlignment::Right,
+    );
+}
+
+/// Collapsible section header.
+pub fn draw_section_row(
+    draw: &Draw,
+    rect: Rect,
+    label: &str,
+    expanded: bool,
+    detail: Option<&str>,
+    focused: bool,
+    style: UiStyle,
+) {
+    row_bg(draw, rect, focused, style);
+
+    let marker = if expanded { "-" } else { "+" };
+    draw_text(
+        draw,
+        FontId::PlexSansBold,
+        &format!("{} {}", marker, label.to_uppercase()),
+        pt2(rect.left() + LABEL_X_PAD, rect.y()),
+        13.0,
+        rgba(0.4, 0.75, 0.8, style.alpha),
+        TextAlignment::Left,
+    );
+
+    if let Some(detail) = detail {
+        draw_text(
+            draw,
+            FontId::PlexSansRegular,
+            detail,
+            pt2(rect.right() - VALUE_X_PAD, rect.y()),
+            12.0,
+            rgba(0.5, 0.5, 0.55, style.alpha),
+            TextAlignment::Right,
+        );
+    }
+}
+
 /// Helper: compute a vertical stack of row rects inside a container.
 pub fn row_stack(container: Rect, count: usize) -> Vec<Rect> {
     let mut rects = Vec::with_capacity(count);
@@ -298,45 +421,16 @@ impl<'a> Form<'a> {
         let (idx, rect) = self.next_row_rect();
         let focused = self.focus.focused == idx;
 
-        // Render manually since we don't have draw_slider_row yet
-        row_bg(draw, rect, focused, UiStyle::default());
-
-        draw_text(
+        draw_slider_row(
             draw,
-            FontId::PlexSansRegular,
+            rect,
             label,
-            pt2(rect.left() + LABEL_X_PAD, rect.y()),
-            14.0,
-            rgba(0.85, 0.85, 0.88, 1.0),
-            TextAlignment::Left,
+            value,
+            min,
+            max,
+            focused,
+            UiStyle::default(),
         );
-
-        // Bar
-        let bar_w = rect.w() * 0.4;
-        let bar_h = 4.0;
-        let bar_x = rect.right() - VALUE_X_PAD - bar_w / 2.0;
-        let norm = (value - min) / (max - min);
-
-        // Track
-        draw.rect()
-            .x_y(bar_x, rect.y())
-            .w_h(bar_w, bar_h)
-            .color(rgba(0.3, 0.3, 0.35, 1.0));
-
-        // Fill
-        let fill_w = bar_w * norm;
-        draw.rect()
-            .x_y(bar_x - bar_w / 2.0 + fill_w / 2.0, rect.y())
-            .w_h(fill_w, bar_h)
-            .color(rgba(0.0, 1.0, 1.0, 1.0));
-
-        // Knob
-        if focused {
-            draw.ellipse()
-                .x_y(bar_x - bar_w / 2.0 + fill_w, rect.y())
-                .radius(4.0)
-                .color(WHITE);
-        }
     }
 }
 
@@ -5,7 +5,7 @@
 
 #![allow(dead_code)] // Framework functions used progressively during migration
 
-use magnolia_ui::{draw_text, FontId, TextAlignment};
+use magnolia_ui::{draw_text, theme, FontId, TextAlignment};
 use nannou::prelude::*;
 
 /// Animation speed for modal open/close (per frame)
@@ -89,6 +89,21 @@ pub fn calculate_modal_rect(window_rect: Rect, anim: &ModalAnim) -> Rect {
     Rect::from_x_y_w_h(center.x, center.y, w, h)
 }
 
+/// Grey text or line at `level` brightness; white in high contrast
+fn grey(level: f32, alpha: f32) -> Rgba {
+    let level = theme::grey_level(level);
+    rgba(level, level, level, alpha)
+}
+
+/// How much of the tiles behind shows through; none in high contrast
+fn backdrop_alpha() -> f32 {
+    if theme::high_contrast() {
+        1.0
+    } else {
+        0.85
+    }
+}
+
 /// Draw modal background with dark theme and border
 pub fn draw_modal_background(draw: &Draw, rect: Rect, anim: &ModalAnim) {
     let alpha = anim.eased();
@@ -97,13 +112,14 @@ pub fn draw_modal_background(draw: &Draw, rect: Rect, anim: &ModalAnim) {
     draw.rect()
         .xy(rect.xy())
         .wh(rect.wh() * 1.5) // Extend beyond modal to cover screen
-        .color(rgba(0.0, 0.0, 0.0, 0.85 * alpha));
+        .color(rgba(0.0, 0.0, 0.0, backdrop_alpha() * alpha));
 
     // Modal background
+    let shade = if theme::high_contrast() { 0.0 } else { 0.04 };
     draw.rect()
         .xy(rect.xy())
         .wh(rect.wh())
-        .color(rgba(0.04, 0.04, 0.05, alpha));
+        .color(rgba(shade, shade, shade + 0.01, alpha));
 
     // Reactive cyan border
     draw.rect()
@@ -158,7 +174,7 @@ pub fn draw_modal_header(draw: &Draw, rect: Rect, title: &str, anim: &ModalAnim)
         "[ESC] Close",
         pt2(rect.right() - 60.0, rect.top() - HEADER_HEIGHT / 2.0),
         12.0,
-        rgba(0.4, 0.4, 0.4, alpha).into(),
+        grey(0.4, alpha),
         TextAlignment::Right,
     );
 
@@ -183,7 +199,7 @@ pub fn draw_section_header(draw: &Draw, y: f32, left: f32, text: &str, alpha: f3
         text,
         pt2(left + 5.0, y),
         11.0,
-        rgba(0.4, 0.4, 0.5, alpha),
+        grey(0.45, alpha),
         TextAlignment::Left,
     );
 }
@@ -196,7 +212,7 @@ pub fn draw_label(draw: &Draw, x: f32, y: f32, text: &str, alpha: f32) {
         text,
         pt2(x, y),
         14.0,
-        rgba(0.8, 0.8, 0.8, alpha),
+        grey(0.8, alpha),
         TextAlignment::Left,
     );
 }
@@ -222,7 +238,7 @@ pub fn draw_label_muted(draw: &Draw, x: f32, y: f32, text: &str, alpha: f32) {
         text,
         pt2(x, y),
         12.0,
-        rgba(0.5, 0.5, 0.5, alpha),
+        grey(0.5, alpha),
         TextAlignment::Left,
     );
 }
@@ -235,7 +251,7 @@ pub fn draw_value(draw: &Draw, x: f32, y: f32, label: &str, value: &str, alpha:
         label,
         pt2(x, y),
         13.0,
-        rgba(0.6, 0.6, 0.6, alpha),
+        grey(0.6, alpha),
         TextAlignment::Left,
     );
 
@@ -261,13 +277,13 @@ pub fn draw_button(draw: &Draw, rect: Rect, text: &str, selected: bool, alpha: f
     let border_color = if selected {
         rgba(0.0, 1.0, 1.0, 0.8 * alpha)
     } else {
-        rgba(0.3, 0.3, 0.35, alpha)
+        grey(0.3, alpha)
     };
 
     let text_color = if selected {
         rgba(0.0, 1.0, 1.0, alpha)
     } else {
-        rgba(0.7, 0.7, 0.7, alpha)
+        grey(0.7, alpha)
     };
 
     draw.rect().xy(rect.xy()).wh(rect.wh()).color(bg_color);
@@ -348,7 +364,7 @@ pub fn draw_list_item(draw: &Draw, rect: Rect, text: &str, selected: bool, alpha
     let text_color = if selected {
         rgba(0.0, 1.0, 1.0, alpha)
     } else {
-        rgba(0.7, 0.7, 0.7, alpha)
+        grey(0.7, alpha)
     };
 
     draw_text(
@@ -367,7 +383,7 @@ pub fn draw_separator(draw: &Draw, y: f32, left: f32, right: f32, alpha: f32) {
     draw.line()
         .start(pt2(left, y))
         .end(pt2(right, y))
-        .color(rgba(0.2, 0.2, 0.25, alpha))
+        .color(grey(0.2, alpha))
         .stroke_weight(1.0);
 }
 
@@ -0,0 +1,313 @@
+use crate::ui::controls::{List, UiInput, UiNav};
+use crate::ui::fullscreen_modal::{
+    calculate_modal_rect, draw_label_muted, draw_list_item, draw_modal_background,
+    draw_modal_header, ModalAnim,
+};
+use crate::ui::modals::{LayoutManagerState, RigPreview};
+use crate::ui::thumbnails::TileThumbnails;
+use magnolia_core::{LayoutPreset, TileConfig, LAYOUT_PRESETS};
+use magnolia_ui::{draw_text, FontId, TextAlignment};
+use nannou::prelude::*;
+use std::path::PathBuf;
+
+/// What the layout manager asks the app to do
+#[derive(Debug, Clone, PartialEq)]
+pub enum LayoutManagerAction {
+    /// Save the running layout as a new rig
+    Export,
+    /// Replace the running layout with a rig
+    Import(PathBuf),
+    /// List the demo rigs instead of the exported ones
+    ShowExamples,
+    /// Back to the exported rigs
+    ShowRigs,
+    /// List the kamea grid presets
+    ShowPresets,
+    /// Lay the running layout out as a preset
+    ApplyPreset(LayoutPreset),
+}
+
+/// Head the rig list; open the examples and the presets
+const LOAD_EXAMPLE: &str = "Load Example...";
+const GRID_PRESETS: &str = "Grid Presets...";
+
+/// Rows before the first rig: the two above outside the examples and presets
+fn leading_rows(state: &LayoutManagerState) -> usize {
+    if state.browsing_examples || state.browsing_presets {
+        0
+    } else {
+        2
+    }
+}
+
+/// Rows in the list
+fn row_count(state: &LayoutManagerState) -> usize {
+    leading_rows(state)
+        + if state.browsing_presets {
+            LAYOUT_PRESETS.len()
+        } else {
+            state.rigs.len()
+        }
+}
+
+/// The rig under the focus, if the focus is on one
+pub fn focused_rig(state: &LayoutManagerState) -> Option<&PathBuf> {
+    if state.browsing_presets {
+        return None;
+    }
+    state
+        .focus
+        .focused
+        .checked_sub(leading_rows(state))
+        .and_then(|i| state.rigs.get(i))
+}
+
+fn focused_preset(state: &LayoutManagerState) -> Option<&'static LayoutPreset> {
+    state
+        .browsing_presets
+        .then(|| LAYOUT_PRESETS.get(state.focus.focused))
+        .flatten()
+}
+
+fn preview_of<'a>(
+    source: String,
+    grid: (usize, usize),
+    tiles: impl Iterator<Item = &'a TileConfig>,
+) -> RigPreview {
+    RigPreview {
+        source,
+        grid,
+        tiles: tiles
+            .map(|tile| {
+                let cells = [
+                    tile.col,
+                    tile.row,
+                    tile.colspan.unwrap_or(1),
+                    tile.rowspan.unwrap_or(1),
+                ];
+                (tile.module.clone(), cells)
+            })
+            .collect(),
+        error: None,
+    }
+}
+
+/// Read the focused rig's layout, or lay out the focused preset, for the
+/// preview when the focus moved
+pub fn sync_preview(state: &mut LayoutManagerState) {
+    let focused = match (focused_preset(state), focused_rig(state)) {
+        (Some(preset), _) => Some(preset.name().to_string()),
+        (None, Some(rig)) => Some(rig.display().to_string()),
+        (None, None) => None,
+    };
+    if state.preview.as_ref().map(|p| &p.source) == focused.as_ref() {
+        return;
+    }
+    state.preview = if let Some(preset) = focused_preset(state) {
+        let tiles = preset.tiles();
+        Some(preview_of(
+            preset.name().to_string(),
+            preset.grid.dimensions(),
+            tiles.iter(),
+        ))
+    } else {
+        focused_rig(state).map(|rig| {
+            let source = rig.display().to_string();
+            match rig::read_rig(rig) {
+                Ok((manifest, _)) => preview_of(
+                    source,
+                    manifest.layout.resolve_grid(),
+                    manifest.layout.main_tiles(),
+                ),
+                Err(e) => RigPreview {
+                    source,
+                    error: Some(format!("{:#}", e)),
+                    ..Default::default()
+                },
+            }
+        })
+    };
+}
+
+/// Modules in the preview, for the thumbnails to draw
+pub fn preview_modules(state: &LayoutManagerState) -> Vec<String> {
+    state
+        .preview
+        .iter()
+        .flat_map(|preview| preview.tiles.iter().map(|(module, _)| module.clone()))
+        .collect()
+}
+
+/// The rig's grid with each tile's thumbnail in place, or its module id
+/// where this machine doesn't have it
+fn render_preview(
+    draw: &Draw,
+    rect: Rect,
+    preview: &RigPreview,
+    thumbnails: &TileThumbnails,
+    alpha: f32,
+) {
+    draw.rect()
+        .xy(rect.xy())
+        .wh(rect.wh())
+        .color(rgba(0.03, 0.03, 0.04, 0.8 * alpha))
+        .stroke(rgba(0.2, 0.2, 0.25, alpha))
+        .stroke_weight(1.0);
+    if let Some(error) = &preview.error {
+        draw_text(
+            draw,
+            FontId::PlexSansRegular,
+            error,
+            rect.xy(),
+            12.0,
+            rgba(1.0, 0.4, 0.4, alpha),
+            TextAlignment::Center,
+        );
+        return;
+    }
+    let (cols, rows) = (preview.grid.0.max(1), preview.grid.1.max(1));
+    let grid = rect.pad(10.0);
+    let (cell_w, cell_h) = (grid.w() / cols as f32, grid.h() / rows as f32);
+    for (module, [col, row, colspan, rowspan]) in &preview.tiles {
+        let tile_rect = Rect::from_corners(
+            pt2(
+                grid.left() + *col as f32 * cell_w,
+                grid.top() - (*row + *rowspan) as f32 * cell_h,
+            ),
+            pt2(
+                grid.left() + (*col + *colspan) as f32 * cell_w,
+                grid.top() - *row as f32 * cell_h,
+            ),
+        )
+        .pad(2.0);
+        draw.rect()
+            .xy(tile_rect.xy())
+            .wh(tile_rect.wh())
+            .color(rgba(0.06, 0.06, 0.08, alpha))
+            .stroke(rgba(0.0, 0.6, 0.6, 0.6 * alpha))
+            .stroke_weight(1.0);
+        if !thumbnails.draw(draw, module, tile_rect.pad(2.0)) {
+            draw_text(
+                draw,
+                FontId::PlexMonoRegular,
+                module,
+                tile_rect.xy(),
+                11.0,
+                rgba(0.6, 0.6, 0.65, alpha),
+                TextAlignment::Center,
+            );
+        }
+    }
+}
+
+pub fn render(
+    draw: &Draw,
+    rect: Rect,
+    state: &LayoutManagerState,
+    thumbnails: &TileThumbnails,
+    anim: &ModalAnim,
+) {
+    let modal_rect = calculate_modal_rect(rect, anim);
+    draw_modal_background(draw, modal_rect, anim);
+    let content_rect = draw_modal_header(draw, modal_rect, "LAYOUT MANAGER", anim);
+    let alpha = anim.eased();
+
+    // Status and hints along the bottom, the rig list above them and the
+    // focused rig's preview beside it
+    let footer_h = 60.0;
+    let list_w = (content_rect.w() * 0.4).max(280.0).min(content_rect.w());
+    let list_rect = Rect::from_corners(
+        pt2(content_rect.left(), content_rect.bottom() + footer_h),
+        pt2(content_rect.left() + list_w, content_rect.top()),
+    );
+    let preview_rect = Rect::from_corners(
+        pt2(list_rect.right() + 20.0, list_rect.bottom()),
+        pt2(content_rect.right(), list_rect.top() - 30.0),
+    );
+    if let Some(preview) = state.preview.as_ref().filter(|_| preview_rect.w() > 40.0) {
+        render_preview(draw, preview_rect, preview, thumbnails, alpha);
+    }
+
+    let leading = leading_rows(state);
+    let title = if state.browsing_presets {
+        "GRID PRESETS"
+    } else if state.browsing_examples {
+        "EXAMPLES"
+    } else {
+        "RIGS"
+    };
+    let list = List::new(&state.focus, list_rect, row_count(state), 30.0).with_title(title);
+    list.render(draw, |i, selected, item_rect| {
+        let name = match i.checked_sub(leading) {
+            None => [LOAD_EXAMPLE, GRID_PRESETS][i].to_string(),
+            Some(i) if state.browsing_presets => {
+                let preset = &LAYOUT_PRESETS[i];
+                let (cols, rows) = preset.grid.dimensions();
+                format!(
+                    "{} {}x{}: {}",
+                    preset.name(),
+                    cols,
+                    rows,
+                    preset.description
+                )
+            }
+            Some(i) => state.rigs[i]
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+        };
+        draw_list_item(draw, item_rect, &name, selected, alpha);
+    });
+
+    if let Some(status) = &state.status {
+        draw_text(
+            draw,
+            FontId::PlexSansRegular,
+            status,
+            pt2(content_rect.left() + 5.0, content_rect.bottom() + 35.0),
+            13.0,
+            rgba(0.0, 1.0, 1.0, alpha),
+            TextAlignment::Left,
+        );
+    }
+    draw_label_muted(
+        draw,
+        content_rect.left() + 5.0,
+        content_rect.bottom() + 10.0,
+        if state.browsing_presets {
+            "[ENTER] Apply preset   [LEFT] Back to rigs"
+        } else if state.browsing_examples {
+            "[ENTER] Load example   [LEFT] Back to rigs"
+        } else {
+            "[ENTER] Load rig   [E] Export current layout"
+        },
+        alpha,
+    );
+}
+
+/// Navigation is handled here; loading, exporting and listing are returned
+/// for the caller, which owns the layout and patch bay
+pub fn handle_key(key: Key, state: &mut LayoutManagerState) -> Option<LayoutManagerAction> {
+    if key == Key::E {
+        return Some(LayoutManagerAction::Export);
+    }
+    if (state.browsing_examples || state.browsing_presets) && matches!(key, Key::Left | Key::Back) {
+        return Some(LayoutManagerAction::ShowRigs);
+    }
+    let input = UiInput::from_key(key, false, false);
+    if let Some(UiNav::Escape) = input.nav {
+        return None;
+    }
+    let leading = leading_rows(state);
+    let len = row_count(state);
+    let chosen = List::handle_nav(&mut state.focus, len, &input)?;
+    match chosen.checked_sub(leading) {
+        None if chosen == 0 => Some(LayoutManagerAction::ShowExamples),
+        None => Some(LayoutManagerAction::ShowPresets),
+        Some(i) if state.browsing_presets => LAYOUT_PRESETS
+            .get(i)
+            .copied()
+            .map(LayoutManagerAction::ApplyPreset),
+        Some(i) => state.rigs.get(i).cloned().map(LayoutManagerAction::Import),
+    }
+}
@@ -1,6 +1,14 @@
+pub mod add_tile_picker;
 pub mod controls;
 pub mod fullscreen_modal;
+pub mod layout_manager;
 pub mod modals;
+pub mod module_docs;
 pub mod patch_bay;
+pub mod patch_suggestions;
+pub mod plugin_index;
 pub mod schema;
+pub mod schema_view;
+pub mod search;
 pub mod settings;
+pub mod thumbnails;
@@ -1,5 +1,7 @@
 use crate::ui::controls::FocusModel;
 use magnolia_core::PowerProfile;
+use plugin_index::{EntryStatus, IndexEntry};
+use std::path::PathBuf;
 
 pub type ModuleId = String;
 pub type PortId = String;
@@ -12,6 +14,36 @@ pub enum PatchBayPane {
     Patches,
 }
 
+/// Order of the connections list in Patch Bay
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TrafficSort {
+    /// As connected
+    #[default]
+    Connection,
+    /// Busiest by messages per second first
+    Messages,
+    /// Busiest by bytes per second first
+    Bytes,
+}
+
+impl TrafficSort {
+    pub fn next(self) -> Self {
+        match self {
+            TrafficSort::Connection => TrafficSort::Messages,
+            TrafficSort::Messages => TrafficSort::Bytes,
+            TrafficSort::Bytes => TrafficSort::Connection,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TrafficSort::Connection => "connection",
+            TrafficSort::Messages => "msg/s",
+            TrafficSort::Bytes => "bytes/s",
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct PatchBayModalState {
     pub focus_pane: PatchBayPane,
@@ -20,6 +52,25 @@ pub struct PatchBayModalState {
     pub patches_focus: FocusModel,
     pub staged_source: Option<(ModuleId, PortId)>,
     pub selected_module: usize,
+    pub traffic_sort: TrafficSort,
+    /// What the last auto-wiring did
+    pub status: Option<String>,
+    /// Filter over the module list's names
+    pub search: String,
+    /// Typing goes to `search` rather than the lists
+    pub searching: bool,
+    /// A text replace being typed for a connection
+    pub replace_edit: Option<ReplaceEdit>,
+}
+
+/// Pattern and replacement being typed for a connection's text replace
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ReplaceEdit {
+    pub patch_id: String,
+    pub pattern: String,
+    pub replacement: String,
+    /// Typing goes to `replacement` rather than `pattern`
+    pub on_replacement: bool,
 }
 
 impl Default for PatchBayModalState {
@@ -31,6 +82,11 @@ impl Default for PatchBayModalState {
             patches_focus: FocusModel::default(),
             staged_source: None,
             selected_module: 0,
+            traffic_sort: TrafficSort::default(),
+            status: None,
+            search: String::new(),
+            searching: false,
+            replace_edit: None,
         }
     }
 }
@@ -59,6 +115,50 @@ impl Default for GlobalSettingsState {
     }
 }
 
+/// Layout manager: the rigs on disk and the outcome of the last action
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LayoutManagerState {
+    pub focus: FocusModel,
+    /// Exported rigs, or the examples while `browsing_examples`
+    pub rigs: Vec<PathBuf>,
+    pub browsing_examples: bool,
+    /// Listing the kamea grid presets instead of rigs
+    pub browsing_presets: bool,
+    pub status: Option<String>,
+    /// The focused rig's grid, shown beside the list
+    pub preview: Option<RigPreview>,
+}
+
+/// Where a rig's or preset's tiles sit, read when it's focused
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RigPreview {
+    /// The rig's path or the preset's name
+    pub source: String,
+    /// Columns and rows
+    pub grid: (usize, usize),
+    /// Module of each tile and its cells: column, row, columns, rows
+    pub tiles: Vec<(String, [usize; 4])>,
+    /// Why the rig couldn't be read
+    pub error: Option<String>,
+}
+
+/// Plugin index browser: the index's plugins against what's installed
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PluginIndexState {
+    pub focus: FocusModel,
+    pub entries: Vec<(IndexEntry, EntryStatus)>,
+    /// A fetch or install is running
+    pub busy: bool,
+    pub status: Option<String>,
+}
+
+/// Docs pane: a module's manual, scrolled by line
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ModuleDocsState {
+    pub module: ModuleId,
+    pub scroll: usize,
+}
+
 /// Modal types for the unified modal stack
 #[derive(Debug, Clone, PartialEq)]
 pub enum ModalState {
@@ -67,14 +167,21 @@ pub enum ModalState {
     /// Global settings modal
     GlobalSettings(GlobalSettingsState),
     /// Layout manager modal
-    LayoutManager,
+    LayoutManager(LayoutManagerState),
+    /// Plugin index modal
+    PluginIndex(PluginIndexState),
     /// Tile maximized/control view (tile_id)
     Maximized { tile_id: String },
+    /// Docs for the maximized tile's module
+    ModuleDocs(ModuleDocsState),
     /// Add tile picker (in layout mode)
     AddTilePicker {
         cursor_col: usize,
         cursor_row: usize,
+        /// Index into the modules matching `query`
         selected_idx: usize,
+        /// Typed filter over the module names
+        query: String,
     },
 }
 
@@ -103,6 +210,11 @@ impl ModalStack {
         self.stack.pop()
     }
 
+    /// Get reference to the top modal
+    pub fn top(&self) -> Option<&ModalState> {
+        self.stack.last()
+    }
+
     /// Get mutable reference to the top modal
     pub fn top_mut(&mut self) -> Option<&mut ModalState> {
         self.stack.last_mut()
@@ -171,7 +283,81 @@ impl ModalStack {
     pub fn is_layout_manager_open(&self) -> bool {
         self.stack
             .iter()
-            .any(|m| matches!(m, ModalState::LayoutManager))
+            .any(|m| matches!(m, ModalState::LayoutManager(_)))
+    }
+
+    /// Get mutable reference to active layout manager state
+    pub fn get_layout_manager_state_mut(&mut self) -> Option<&mut LayoutManagerState> {
+        for modal in self.stack.iter_mut().rev() {
+            if let ModalState::LayoutManager(state) = modal {
+                return Some(state);
+            }
+        }
+        None
+    }
+
+    /// Get immutable reference to active layout manager state
+    pub fn get_layout_manager_state(&self) -> Option<&LayoutManagerState> {
+        for modal in self.stack.iter().rev() {
+            if let ModalState::LayoutManager(state) = modal {
+                return Some(state);
+            }
+        }
+        None
+    }
+
+    /// Check if the plugin index is open
+    pub fn is_plugin_index_open(&self) -> bool {
+        self.stack
+            .iter()
+            .any(|m| matches!(m, ModalState::PluginIndex(_)))
+    }
+
+    /// Get mutable reference to active plugin index state
+    pub fn get_plugin_index_state_mut(&mut self) -> Option<&mut PluginIndexState> {
+        for modal in self.stack.iter_mut().rev() {
+            if let ModalState::PluginIndex(state) = modal {
+                return Some(state);
+            }
+        }
+        None
+    }
+
+    /// Get immutable reference to active plugin index state
+    pub fn get_plugin_index_state(&self) -> Option<&PluginIndexState> {
+        for modal in self.stack.iter().rev() {
+            if let ModalState::PluginIndex(state) = modal {
+                return Some(state);
+            }
+        }
+        None
+    }
+
+    /// Check if the docs pane is open
+    pub fn is_module_docs_open(&self) -> bool {
+        self.stack
+            .iter()
+            .any(|m| matches!(m, ModalState::ModuleDocs(_)))
+    }
+
+    /// Get mutable reference to active docs pane state
+    pub fn get_module_docs_state_mut(&mut self) -> Option<&mut ModuleDocsState> {
+        for modal in self.stack.iter_mut().rev() {
+            if let ModalState::ModuleDocs(state) = modal {
+                return Some(state);
+            }
+        }
+        None
+    }
+
+    /// Get immutable reference to active docs pane state
+    pub fn get_module_docs_state(&self) -> Option<&ModuleDocsState> {
+        for modal in self.stack.iter().rev() {
+            if let ModalState::ModuleDocs(state) = modal {
+                return Some(state);
+            }
+        }
+        None
     }
 
     /// Check if a tile is maximized
@@ -204,9 +390,36 @@ impl ModalStack {
             cursor_col: col,
             cursor_row: row,
             selected_idx: 0,
+            query: String::new(),
         });
     }
 
+    /// The add tile picker's filter, if it's open
+    pub fn add_tile_picker_query(&self) -> Option<&str> {
+        self.stack.iter().rev().find_map(|modal| match modal {
+            ModalState::AddTilePicker { query, .. } => Some(query.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Change the add tile picker's filter with `edit`, which says whether
+    /// it did; the selection goes back to the best match
+    pub fn edit_add_tile_picker_query(&mut self, edit: impl FnOnce(&mut String) -> bool) -> bool {
+        let Some(ModalState::AddTilePicker {
+            selected_idx,
+            query,
+            ..
+        }) = self.stack.last_mut()
+        else {
+            return false;
+        };
+        let edited = edit(query);
+        if edited {
+            *selected_idx = 0;
+        }
+        edited
+    }
+
     pub fn move_add_tile_picker_selection(&mut self, delta: i32, len: usize) {
         if len == 0 {
             return;
@@ -0,0 +1,185 @@
+//! Docs pane: a module's manual over its maximized tile (F1)
+//!
+//! Shows the schema's description and the overview from its docs, then what
+//! each port carries and what each setting changes. Settings the docs don't
+//! cover fall back to the descriptions in the settings schema.
+
+use crate::ui::controls::{UiInput, UiNav};
+use crate::ui::fullscreen_modal::{
+    calculate_modal_rect, draw_label, draw_label_highlight, draw_label_muted,
+    draw_modal_background, draw_modal_header, draw_section_header, ModalAnim,
+};
+use crate::ui::modals::ModuleDocsState;
+use magnolia_core::ModuleSchema;
+use magnolia_ui::{text_width, FontId};
+use nannou::prelude::*;
+
+const TEXT_SIZE: f32 = 14.0;
+const LINE_H: f32 = 20.0;
+/// Width of the port and setting names column
+const NAME_W: f32 = 220.0;
+/// Lines a page key scrolls
+const PAGE: usize = 10;
+
+/// One entry of the manual, before wrapping
+#[derive(Debug, Clone, PartialEq)]
+pub enum DocLine {
+    Heading(String),
+    Paragraph(String),
+    /// A port or setting and what it does
+    Item {
+        name: String,
+        text: String,
+    },
+}
+
+/// The manual for `module`, or a note that there isn't one
+pub fn doc_lines(module: &str, schema: Option<&ModuleSchema>) -> Vec<DocLine> {
+    let Some(schema) = schema else {
+        return vec![DocLine::Paragraph(format!(
+            "{} isn't a registered module, so there's no manual for it.",
+            module
+        ))];
+    };
+    let mut lines = vec![DocLine::Paragraph(schema.description.clone())];
+    let docs = schema.docs.clone().unwrap_or_default();
+    lines.extend(
+        docs.overview
+            .split("\n\n")
+            .map(str::trim)
+            .filter(|paragraph| !paragraph.is_empty())
+            .map(|paragraph| DocLine::Paragraph(paragraph.to_string())),
+    );
+
+    let mut ports: Vec<DocLine> = schema
+        .ports
+        .iter()
+        .map(|port| DocLine::Item {
+            name: format!("{} ({:?} {:?})", port.label, port.direction, port.data_type),
+            text: docs.ports.get(&port.id).cloned().unwrap_or_default(),
+        })
+        .collect();
+    // Plugins document ports their schema doesn't list yet
+    ports.extend(
+        docs.ports
+            .iter()
+            .filter(|(id, _)| !schema.ports.iter().any(|port| &port.id == *id))
+            .map(|(id, text)| DocLine::Item {
+                name: id.clone(),
+                text: text.clone(),
+            }),
+    );
+    if !ports.is_empty() {
+        lines.push(DocLine::Heading("PORTS".to_string()));
+        lines.extend(ports);
+    }
+
+    let settings = schema.setting_docs();
+    if !settings.is_empty() {
+        lines.push(DocLine::Heading("SETTINGS".to_string()));
+        lines.extend(
+            settings
+                .into_iter()
+                .map(|(name, text)| DocLine::Item { name, text }),
+        );
+    }
+    if schema.docs.is_none() {
+        lines.push(DocLine::Paragraph(
+            "This module ships no manual beyond the above.".to_string(),
+        ));
+    }
+    lines
+}
+
+/// `text` broken into lines no wider than `width`
+fn wrap(text: &str, width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let candidate = if line.is_empty() {
+            word.to_string()
+        } else {
+            format!("{} {}", line, word)
+        };
+        if !line.is_empty() && text_width(FontId::PlexSansRegular, &candidate, TEXT_SIZE) > width {
+            lines.push(std::mem::replace(&mut line, word.to_string()));
+        } else {
+            line = candidate;
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+pub fn render(
+    draw: &Draw,
+    rect: Rect,
+    title: &str,
+    lines: &[DocLine],
+    state: &ModuleDocsState,
+    anim: &ModalAnim,
+) {
+    let modal_rect = calculate_modal_rect(rect, anim);
+    draw_modal_background(draw, modal_rect, anim);
+    let header = format!("DOCS: {}", title.to_uppercase());
+    let content_rect = draw_modal_header(draw, modal_rect, &header, anim);
+    let alpha = anim.eased();
+
+    let bottom = content_rect.bottom() + 30.0;
+    let left = content_rect.left() + 5.0;
+    let mut y = content_rect.top() - 10.0;
+    let first = state.scroll.min(lines.len().saturating_sub(1));
+    for line in &lines[first..] {
+        if y < bottom {
+            break;
+        }
+        match line {
+            DocLine::Heading(text) => {
+                y -= 8.0;
+                draw_section_header(draw, y, content_rect.left(), text, alpha);
+                y -= LINE_H + 4.0;
+            }
+            DocLine::Paragraph(text) => {
+                for row in wrap(text, content_rect.w() - 10.0) {
+                    draw_label(draw, left, y, &row, alpha);
+                    y -= LINE_H;
+                }
+                y -= 8.0;
+            }
+            DocLine::Item { name, text } => {
+                draw_label_highlight(draw, left, y, name, alpha);
+                let rows = wrap(text, content_rect.w() - NAME_W - 10.0);
+                for row in &rows {
+                    draw_label(draw, left + NAME_W, y, row, alpha);
+                    y -= LINE_H;
+                }
+                if rows.is_empty() {
+                    y -= LINE_H;
+                }
+                y -= 4.0;
+            }
+        }
+    }
+
+    draw_label_muted(
+        draw,
+        content_rect.left() + 5.0,
+        content_rect.bottom() + 10.0,
+        "[UP/DOWN] Scroll   [PGUP/PGDN] Page   [F1/ESC] Close",
+        alpha,
+    );
+}
+
+/// Scroll through the `len` lines of the manual
+pub fn handle_key(key: Key, state: &mut ModuleDocsState, len: usize) {
+    let last = len.saturating_sub(1);
+    match UiInput::from_key(key, false, false).nav {
+        Some(UiNav::Up) => state.scroll = state.scroll.saturating_sub(1),
+        Some(UiNav::Down) => state.scroll = (state.scroll + 1).min(last),
+        Some(UiNav::PageUp) => state.scroll = state.scroll.saturating_sub(PAGE),
+        Some(UiNav::PageDown) => state.scroll = (state.scroll + PAGE).min(last),
+        _ => {}
+    }
+}
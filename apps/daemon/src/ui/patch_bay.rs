@@ -2,10 +2,15 @@ use crate::ui::controls::{List, UiInput, UiNav};
 use crate::ui::fullscreen_modal::{
     calculate_modal_rect, draw_modal_background, draw_modal_header, ModalAnim,
 };
-use crate::ui::modals::{PatchBayModalState, PatchBayPane};
-use magnolia_core::{PatchBay, PortDirection};
+use crate::ui::modals::{PatchBayModalState, PatchBayPane, ReplaceEdit, TrafficSort};
+use crate::ui::search;
+use magnolia_core::{
+    ModuleSchema, Patch, PatchBay, PatchTemplate, PatchTraffic, PatchTransform, PortDirection,
+    ProvenanceTracker,
+};
 use magnolia_ui::{draw_text, FontId, TextAlignment};
 use nannou::prelude::*;
+use std::collections::HashMap;
 
 pub fn render(
     draw: &Draw,
@@ -13,6 +18,7 @@ pub fn render(
     state: &PatchBayModalState, // Immutable state
     anim: &ModalAnim,
     patch_bay: &PatchBay,
+    traffic: &HashMap<String, PatchTraffic>,
 ) {
     // Calculate animated modal rect
     let modal_rect = calculate_modal_rect(rect, anim);
@@ -55,8 +61,8 @@ pub fn render(
     let mut target_port_pos = None;
 
     // 3. Render Modules List
-    // Filter active modules
-    let modules = patch_bay.get_modules();
+    // Filtered by the search, best match first
+    let modules = listed_modules(patch_bay, &state.search);
 
     // Highlight focused pane
     let modules_focused = state.focus_pane == PatchBayPane::Modules;
@@ -65,15 +71,21 @@ pub fn render(
 
     // -- Modules Pane --
     // List::new takes immutable reference now
+    let modules_title = match (state.searching, state.search.is_empty()) {
+        (true, _) => format!("/{}_", state.search),
+        (false, true) => "MODULES".to_string(),
+        (false, false) => format!("MODULES /{}", state.search),
+    };
+    let modules_title = if modules_focused {
+        format!("> {} <", modules_title)
+    } else {
+        modules_title
+    };
     let module_list = List::new(&state.modules_focus, modules_rect, modules.len(), 30.0)
-        .with_title(if modules_focused {
-            "> MODULES <"
-        } else {
-            "MODULES"
-        });
+        .with_title(&modules_title);
 
     module_list.render(draw, |i, selected, rect| {
-        let module = &modules[i];
+        let (module, positions) = &modules[i];
         let color = if selected { CYAN } else { GREY };
         let name = &module.name;
 
@@ -107,10 +119,11 @@ pub fn render(
                 .color(CYAN);
         }
 
-        draw_text(
+        search::draw_matched(
             draw,
             FontId::PlexSansRegular,
             name,
+            positions,
             rect.xy(),
             14.0,
             srgba(
@@ -121,12 +134,61 @@ pub fn render(
             ),
             TextAlignment::Center,
         );
+
+        // Solo / mute badges; a source silenced by someone else's solo is dimmed
+        let mut badge_x = rect.right() - 12.0;
+        if patch_bay.is_module_muted(&module.id) {
+            draw_text(
+                draw,
+                FontId::PlexMonoRegular,
+                "M",
+                pt2(badge_x, rect.y()),
+                11.0,
+                srgba(1.0, 0.3, 0.3, 1.0),
+                TextAlignment::Center,
+            );
+            badge_x -= 14.0;
+        }
+        if patch_bay.is_module_bypassed(&module.id) {
+            draw_text(
+                draw,
+                FontId::PlexMonoRegular,
+                "B",
+                pt2(badge_x, rect.y()),
+                11.0,
+                srgba(0.4, 0.6, 1.0, 1.0),
+                TextAlignment::Center,
+            );
+            badge_x -= 14.0;
+        }
+        if patch_bay.is_module_soloed(&module.id) {
+            draw_text(
+                draw,
+                FontId::PlexMonoRegular,
+                "S",
+                pt2(badge_x, rect.y()),
+                11.0,
+                srgba(1.0, 0.85, 0.0, 1.0),
+                TextAlignment::Center,
+            );
+        } else if patch_bay.is_module_silenced(&module.id) && !patch_bay.is_module_muted(&module.id)
+        {
+            draw_text(
+                draw,
+                FontId::PlexMonoRegular,
+                "-",
+                pt2(badge_x, rect.y()),
+                11.0,
+                srgba(0.5, 0.5, 0.5, 0.8),
+                TextAlignment::Center,
+            );
+        }
     });
 
     // -- Ports Pane --
     let selected_mod_idx = state.selected_module;
     let mut ports = Vec::new();
-    if let Some(module) = modules.get(selected_mod_idx) {
+    if let Some((module, _)) = modules.get(selected_mod_idx) {
         ports = module.ports.clone();
     }
 
@@ -145,7 +207,7 @@ pub fn render(
         }
 
         // Also check if this port is the staged source (loopback scenario)
-        if let Some(module) = modules.get(selected_mod_idx) {
+        if let Some((module, _)) = modules.get(selected_mod_idx) {
             if let Some((src_mod_id, src_port_id)) = &state.staged_source {
                 if &module.id == src_mod_id && &port.id == src_port_id {
                     // Using port position as source is better if visible
@@ -210,18 +272,39 @@ pub fn render(
     });
 
     // -- Patches Pane --
-    let patches = patch_bay.get_patches();
-    let patch_list = List::new(&state.patches_focus, patches_rect, patches.len(), 30.0).with_title(
-        if patches_focused {
-            "> CONNECTIONS <"
-        } else {
-            "CONNECTIONS"
-        },
-    );
+    let patches = sorted_patches(patch_bay, state.traffic_sort, traffic);
+    // Heat is relative to the busiest connection, on a log scale so a
+    // trickle of text still shows next to an audio stream
+    let max_bytes = patches
+        .iter()
+        .filter_map(|patch| traffic.get(&patch.id))
+        .map(|t| t.bytes_per_sec)
+        .fold(0.0, f64::max);
+    let patches_title = if patches_focused {
+        format!("> CONNECTIONS ({}) <", state.traffic_sort.label())
+    } else {
+        format!("CONNECTIONS ({})", state.traffic_sort.label())
+    };
+    let patch_list = List::new(&state.patches_focus, patches_rect, patches.len(), 30.0)
+        .with_title(&patches_title);
 
     patch_list.render(draw, |i, selected, rect| {
-        let patch = &patches[i];
+        let patch = patches[i];
         let color = if selected { CYAN } else { GREY };
+        let flow = traffic.get(&patch.id).copied().unwrap_or_default();
+        let heat = if max_bytes > 0.0 {
+            ((1.0 + flow.bytes_per_sec).ln() / (1.0 + max_bytes).ln()) as f32
+        } else {
+            0.0
+        };
+        if heat > 0.01 {
+            draw.rect().xy(rect.xy()).wh(rect.wh()).color(rgba(
+                heat,
+                0.35 * (1.0 - heat),
+                0.6 * (1.0 - heat),
+                0.1 + 0.25 * heat,
+            ));
+        }
         if selected {
             draw.rect()
                 .xy(rect.xy())
@@ -259,6 +342,32 @@ pub fn render(
             rgba(0.5, 0.5, 0.5, 0.8),
             TextAlignment::Center,
         );
+
+        draw_text(
+            draw,
+            FontId::PlexMonoRegular,
+            &format!(
+                "{:.1}/s {}",
+                flow.messages_per_sec,
+                format_bytes_rate(flow.bytes_per_sec)
+            ),
+            pt2(rect.right() - 6.0, rect.y() + 6.0),
+            9.0,
+            srgba(0.9, 0.7 + 0.3 * (1.0 - heat), 0.4, 0.9),
+            TextAlignment::Right,
+        );
+
+        if let Some(transform) = &patch.transform {
+            draw_text(
+                draw,
+                FontId::PlexMonoRegular,
+                &transform.label(),
+                pt2(rect.left() + 6.0, rect.y() + 6.0),
+                9.0,
+                srgba(1.0, 0.85, 0.0, 0.9),
+                TextAlignment::Left,
+            );
+        }
     });
 
     // Draw Staged Connection Line
@@ -295,7 +404,9 @@ pub fn render(
 
     // 4. Helper Text
     let hint = match state.focus_pane {
-        PatchBayPane::Modules => "Select Module [Space/Enter] to Browse Ports",
+        PatchBayPane::Modules => {
+            "Select Module [Space/Enter] to Browse Ports, [/] Search, [M] Mute, [S] Solo, [B] Bypass"
+        }
         PatchBayPane::Ports => {
             if state.staged_source.is_some() {
                 "Select Sink Port [Enter] to Connect, [Esc] Cancel"
@@ -303,7 +414,9 @@ pub fn render(
                 "Select Source Port [Enter] to Stage Connection"
             }
         }
-        PatchBayPane::Patches => "[Del/Back] to Disconnect, [Arrows] Navigate",
+        PatchBayPane::Patches => {
+            "[Del/Back] to Disconnect, [-/=] Gain, [R] Replace Text, [I] Trace, [O] Sort by Traffic"
+        }
     };
 
     draw_text(
@@ -315,13 +428,173 @@ pub fn render(
         srgba(0.0, 1.0, 1.0, 1.0),
         TextAlignment::Center,
     );
+    let editing = state.replace_edit.as_ref().map(|edit| {
+        let (pattern_cursor, replacement_cursor) = if edit.on_replacement {
+            ("", "_")
+        } else {
+            ("_", "")
+        };
+        format!(
+            "Replace /{}{}/ with /{}{}/ on {}: [Tab] Switch, [Enter] Apply, [Esc] Cancel",
+            edit.pattern, pattern_cursor, edit.replacement, replacement_cursor, edit.patch_id
+        )
+    });
+    let status = editing
+        .as_deref()
+        .or(state.status.as_deref())
+        .unwrap_or("[A] Auto-wire the audio chain, [T] the transcription chain");
+    draw_text(
+        draw,
+        FontId::PlexSansRegular,
+        status,
+        pt2(rect.x(), rect.bottom() + 55.0),
+        13.0,
+        srgba(0.6, 0.6, 0.6, 1.0),
+        TextAlignment::Center,
+    );
+}
+
+/// The modules the list shows for `query`, best match first, with the
+/// positions of the matched letters in their names
+fn listed_modules<'a>(patch_bay: &'a PatchBay, query: &str) -> Vec<(&'a ModuleSchema, Vec<usize>)> {
+    let modules = patch_bay.get_modules();
+    search::filter(query, modules.iter().map(|module| module.name.as_str()))
+        .into_iter()
+        .map(|(i, positions)| (modules[i], positions))
+        .collect()
+}
+
+/// Patches in the order the connections list shows them
+fn sorted_patches<'a>(
+    patch_bay: &'a PatchBay,
+    sort: TrafficSort,
+    traffic: &HashMap<String, PatchTraffic>,
+) -> Vec<&'a Patch> {
+    let mut patches: Vec<&Patch> = patch_bay.get_patches().iter().collect();
+    let rate = |patch: &Patch| {
+        let flow = traffic.get(&patch.id).copied().unwrap_or_default();
+        match sort {
+            TrafficSort::Connection => 0.0,
+            TrafficSort::Messages => flow.messages_per_sec,
+            TrafficSort::Bytes => flow.bytes_per_sec,
+        }
+    };
+    if sort != TrafficSort::Connection {
+        patches.sort_by(|a, b| rate(b).total_cmp(&rate(a)));
+    }
+    patches
+}
+
+/// Put `transform` on the patch, or take its transform away, saying how
+/// that went
+fn set_transform(
+    patch_bay: &mut PatchBay,
+    patch_id: &str,
+    transform: Option<PatchTransform>,
+) -> String {
+    let label = transform.as_ref().map(PatchTransform::label);
+    match patch_bay.set_patch_transform(patch_id, transform) {
+        Ok(()) => match label {
+            Some(label) => format!("{} now {}", patch_id, label),
+            None => format!("{} passes signals unchanged", patch_id),
+        },
+        Err(e) => e.to_string(),
+    }
+}
+
+/// `512 B/s`, `1.2 MB/s`
+fn format_bytes_rate(bytes_per_sec: f64) -> String {
+    if bytes_per_sec >= 1_000_000.0 {
+        format!("{:.1} MB/s", bytes_per_sec / 1_000_000.0)
+    } else if bytes_per_sec >= 1_000.0 {
+        format!("{:.1} kB/s", bytes_per_sec / 1_000.0)
+    } else {
+        format!("{:.0} B/s", bytes_per_sec)
+    }
+}
+
+/// Where `I` looks up the last signal down a connection
+pub struct Trace<'a> {
+    pub provenance: &'a ProvenanceTracker,
+    pub now_us: u64,
+}
+
+impl Trace<'_> {
+    fn describe(&self, patch: &Patch) -> String {
+        let route = format!("{} ➔ {}", patch.source_module, patch.sink_module);
+        if !self.provenance.is_enabled() {
+            return format!("{}: tracing is off ([provenance] enabled = false)", route);
+        }
+        match self.provenance.on_patch(&patch.id) {
+            Some(provenance) => format!("{}: {}", route, provenance.describe(self.now_us)),
+            None => format!("{}: nothing has come through yet", route),
+        }
+    }
 }
 
 /// Handle key input. Returns true if the key event was consumed by the modal.
 /// Returns false if it should be handled by the parent (e.g. global close).
-pub fn handle_key(key: Key, state: &mut PatchBayModalState, patch_bay: &mut PatchBay) -> bool {
+pub fn handle_key(
+    key: Key,
+    state: &mut PatchBayModalState,
+    patch_bay: &mut PatchBay,
+    traffic: &HashMap<String, PatchTraffic>,
+    trace: Trace<'_>,
+) -> bool {
     let input = UiInput::from_key(key, false, false);
 
+    // Typing a text replace for a connection
+    if let Some(edit) = &mut state.replace_edit {
+        match key {
+            Key::Escape => state.replace_edit = None,
+            Key::Tab => edit.on_replacement = !edit.on_replacement,
+            Key::Return => {
+                let ReplaceEdit {
+                    patch_id,
+                    pattern,
+                    replacement,
+                    ..
+                } = edit.clone();
+                state.replace_edit = None;
+                // An empty pattern takes the replace away
+                let transform = (!pattern.is_empty()).then_some(PatchTransform::Replace {
+                    pattern,
+                    replacement,
+                });
+                state.status = Some(set_transform(patch_bay, &patch_id, transform));
+            }
+            _ => {
+                let field = if edit.on_replacement {
+                    &mut edit.replacement
+                } else {
+                    &mut edit.pattern
+                };
+                search::edit_query(field, key, false);
+            }
+        }
+        return true;
+    }
+
+    // Typing into the module search; arrows and Enter still work the list
+    if state.searching {
+        if key == Key::Escape {
+            state.searching = false;
+            state.search.clear();
+            return true;
+        }
+        if key == Key::Return {
+            state.searching = false;
+        } else if key != Key::Space && search::edit_query(&mut state.search, key, false) {
+            state.modules_focus.focused = 0;
+            state.selected_module = 0;
+            return true;
+        }
+    } else if key == Key::Slash {
+        state.searching = true;
+        state.focus_pane = PatchBayPane::Modules;
+        return true;
+    }
+
     // Escape Handling
     if let Some(UiNav::Escape) = input.nav {
         // If staging a connection, cancel it
@@ -330,6 +603,13 @@ pub fn handle_key(key: Key, state: &mut PatchBayModalState, patch_bay: &mut Patc
             state.focus_pane = PatchBayPane::Ports; // Or Modules?
             return true;
         }
+        // Then drop the search
+        if !state.search.is_empty() {
+            state.search.clear();
+            state.modules_focus.focused = 0;
+            state.selected_module = 0;
+            return true;
+        }
         // Otherwise, allow parent to close modal
         return false;
     }
@@ -362,9 +642,39 @@ pub fn handle_key(key: Key, state: &mut PatchBayModalState, patch_bay: &mut Patc
         return true;
     }
 
+    // Standard chains, wired in one go
+    let template = match key {
+        Key::A => PatchTemplate::find("audio"),
+        Key::T => PatchTemplate::find("transcription"),
+        _ => None,
+    };
+    if let Some(template) = template {
+        let report = template.apply(patch_bay);
+        for patch in &report.failed {
+            log::warn!("Auto-wiring couldn't make {}", patch);
+        }
+        let summary = report.summary(template);
+        log::info!("{}", summary);
+        state.status = Some(summary);
+        return true;
+    }
+
     match state.focus_pane {
         PatchBayPane::Modules => {
-            let module_count = patch_bay.get_modules().len();
+            if matches!(key, Key::M | Key::S | Key::B) {
+                let focused = listed_modules(patch_bay, &state.search)
+                    .get(state.modules_focus.focused)
+                    .map(|(module, _)| module.id.clone());
+                if let Some(module_id) = focused {
+                    match key {
+                        Key::M => patch_bay.toggle_module_muted(&module_id),
+                        Key::S => patch_bay.toggle_module_soloed(&module_id),
+                        _ => patch_bay.toggle_module_bypassed(&module_id),
+                    };
+                }
+                return true;
+            }
+            let module_count = listed_modules(patch_bay, &state.search).len();
             // Use static List::handle_nav
             if let Some(idx) = List::handle_nav(&mut state.modules_focus, module_count, &input) {
                 state.selected_module = idx;
@@ -378,8 +688,8 @@ pub fn handle_key(key: Key, state: &mut PatchBayModalState, patch_bay: &mut Patc
             let mut action_stage = None;
 
             {
-                let modules = patch_bay.get_modules();
-                if let Some(module) = modules.get(state.selected_module) {
+                let modules = listed_modules(patch_bay, &state.search);
+                if let Some((module, _)) = modules.get(state.selected_module) {
                     let ports = &module.ports;
                     if let Some(idx) = List::handle_nav(&mut state.ports_focus, ports.len(), &input)
                     {
@@ -416,9 +726,55 @@ pub fn handle_key(key: Key, state: &mut PatchBayModalState, patch_bay: &mut Patc
             }
         }
         PatchBayPane::Patches => {
+            if key == Key::O {
+                state.traffic_sort = state.traffic_sort.next();
+                return true;
+            }
+            if key == Key::I {
+                // Where the last signal down the connection started
+                if let Some(patch) = sorted_patches(patch_bay, state.traffic_sort, traffic)
+                    .get(state.patches_focus.focused)
+                {
+                    state.status = Some(trace.describe(patch));
+                }
+                return true;
+            }
+            if matches!(key, Key::Minus | Key::Equals | Key::R) {
+                let focused = sorted_patches(patch_bay, state.traffic_sort, traffic)
+                    .get(state.patches_focus.focused)
+                    .map(|patch| (patch.id.clone(), patch.transform.clone()));
+                let Some((patch_id, transform)) = focused else {
+                    return true;
+                };
+                if key == Key::R {
+                    let mut edit = ReplaceEdit {
+                        patch_id,
+                        ..Default::default()
+                    };
+                    if let Some(PatchTransform::Replace {
+                        pattern,
+                        replacement,
+                    }) = transform
+                    {
+                        edit.pattern = pattern;
+                        edit.replacement = replacement;
+                    }
+                    state.replace_edit = Some(edit);
+                    return true;
+                }
+                // Gain moves in 1 dB steps; back at 0 dB it goes away
+                let db = match transform {
+                    Some(PatchTransform::Gain { gain }) => 20.0 * gain.log10(),
+                    _ => 0.0,
+                };
+                let db = (db + if key == Key::Minus { -1.0 } else { 1.0 }).round();
+                let transform = (db != 0.0).then(|| PatchTransform::gain_db(db));
+                state.status = Some(set_transform(patch_bay, &patch_id, transform));
+                return true;
+            }
             let mut disconnect_id = None;
             {
-                let patches = patch_bay.get_patches();
+                let patches = sorted_patches(patch_bay, state.traffic_sort, traffic);
                 let nav = input.nav.as_ref();
 
                 if matches!(nav, Some(UiNav::Delete)) {
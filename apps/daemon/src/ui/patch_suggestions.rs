@@ -0,0 +1,57 @@
+//! Patch suggestions: where the selected tile's outputs could go
+//!
+//! Shown in patch mode while a tile is selected, ranked by
+//! [`PatchBay::suggest_patches`]. The number beside each one makes it.
+//!
+//! [`PatchBay::suggest_patches`]: magnolia_core::PatchBay::suggest_patches
+
+use crate::input::SUGGESTION_KEYS;
+use crate::ui::fullscreen_modal::{draw_label, draw_label_highlight, draw_label_muted};
+use magnolia_core::PatchSuggestion;
+use magnolia_ui::{text_width, FontId};
+use nannou::prelude::*;
+
+const ROW_H: f32 = 20.0;
+const PANEL_W: f32 = 420.0;
+const PADDING: f32 = 12.0;
+
+/// The suggestions for `module`, in a panel at the bottom right of `rect`
+pub fn render(draw: &Draw, rect: Rect, module: &str, suggestions: &[PatchSuggestion]) {
+    let shown = suggestions.len().min(SUGGESTION_KEYS.len());
+    let rows = shown.max(1) + 1;
+    let h = rows as f32 * ROW_H + 2.0 * PADDING;
+    let panel = Rect::from_corners(
+        pt2(rect.right() - PANEL_W - 20.0, rect.bottom() + 40.0),
+        pt2(rect.right() - 20.0, rect.bottom() + 40.0 + h),
+    );
+    let level = magnolia_ui::theme::grey_level(0.05);
+    draw.rect()
+        .xy(panel.xy())
+        .wh(panel.wh())
+        .color(rgba(level, level, level, 0.9))
+        .stroke(rgba(1.0, 0.5, 0.0, 0.8))
+        .stroke_weight(1.0);
+
+    let left = panel.left() + PADDING;
+    let mut y = panel.top() - PADDING - ROW_H / 2.0;
+    draw_label_highlight(draw, left, y, &format!("PATCH FROM {}", module), 1.0);
+    y -= ROW_H;
+    if shown == 0 {
+        draw_label_muted(draw, left, y, "No compatible inputs left to patch", 1.0);
+        return;
+    }
+    for (i, suggestion) in suggestions[..shown].iter().enumerate() {
+        let number = format!("{}", i + 1);
+        draw_label_highlight(draw, left, y, &number, 1.0);
+        let text = format!(
+            "{} -> {}:{}",
+            suggestion.source_port, suggestion.sink_module, suggestion.sink_port
+        );
+        draw_label(draw, left + 24.0, y, &text, 1.0);
+        if !suggestion.exact {
+            let x = left + 32.0 + text_width(FontId::PlexSansRegular, &text, 14.0);
+            draw_label_muted(draw, x, y, "(any)", 1.0);
+        }
+        y -= ROW_H;
+    }
+}
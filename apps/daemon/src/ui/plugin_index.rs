@@ -0,0 +1,129 @@
+use crate::ui::controls::{List, UiInput, UiNav};
+use crate::ui::fullscreen_modal::{
+    calculate_modal_rect, draw_label_muted, draw_list_item, draw_modal_background,
+    draw_modal_header, ModalAnim,
+};
+use crate::ui::modals::PluginIndexState;
+use magnolia_ui::{draw_text, FontId, TextAlignment};
+use nannou::prelude::*;
+use plugin_index::{EntryStatus, IndexEntry, PluginIndex};
+use std::path::PathBuf;
+
+/// What the plugin index asks the app to do
+#[derive(Debug, Clone, PartialEq)]
+pub enum PluginIndexAction {
+    /// Fetch the index again
+    Refresh,
+    /// Install or update a plugin
+    Install(IndexEntry),
+}
+
+/// Results of the background fetches and installs
+#[derive(Debug)]
+pub enum PluginIndexEvent {
+    Fetched(Result<PluginIndex, String>),
+    Installed(Result<(String, PathBuf), String>),
+}
+
+fn status_label(status: &EntryStatus) -> String {
+    match status {
+        EntryStatus::Available => "available".to_string(),
+        EntryStatus::Installed => "installed".to_string(),
+        EntryStatus::UpdateAvailable { installed } => format!("update from {}", installed),
+        EntryStatus::Incompatible { abi } => format!("needs ABI {}", abi),
+    }
+}
+
+pub fn render(draw: &Draw, rect: Rect, state: &PluginIndexState, anim: &ModalAnim) {
+    let modal_rect = calculate_modal_rect(rect, anim);
+    draw_modal_background(draw, modal_rect, anim);
+    let content_rect = draw_modal_header(draw, modal_rect, "PLUGIN INDEX", anim);
+    let alpha = anim.eased();
+
+    // Status and hints along the bottom, the plugins above them
+    let footer_h = 60.0;
+    let list_rect = Rect::from_corners(
+        pt2(content_rect.left(), content_rect.bottom() + footer_h),
+        content_rect.top_right(),
+    );
+
+    let list = List::new(&state.focus, list_rect, state.entries.len(), 30.0).with_title("PLUGINS");
+    list.render(draw, |i, selected, item_rect| {
+        let (entry, status) = &state.entries[i];
+        let label = format!("{} {}  {}", entry.name, entry.version, entry.description);
+        draw_list_item(draw, item_rect, &label, selected, alpha);
+        draw_text(
+            draw,
+            FontId::PlexMonoRegular,
+            &status_label(status),
+            pt2(item_rect.right() - 10.0, item_rect.y()),
+            12.0,
+            if matches!(status, EntryStatus::Incompatible { .. }) {
+                rgba(1.0, 0.4, 0.4, alpha)
+            } else {
+                rgba(0.5, 0.5, 0.5, alpha)
+            },
+            TextAlignment::Right,
+        );
+    });
+
+    let status = if state.busy {
+        Some("Working...")
+    } else {
+        state.status.as_deref()
+    };
+    if let Some(status) = status {
+        draw_text(
+            draw,
+            FontId::PlexSansRegular,
+            status,
+            pt2(content_rect.left() + 5.0, content_rect.bottom() + 35.0),
+            13.0,
+            rgba(0.0, 1.0, 1.0, alpha),
+            TextAlignment::Left,
+        );
+    }
+    draw_label_muted(
+        draw,
+        content_rect.left() + 5.0,
+        content_rect.bottom() + 10.0,
+        "[ENTER] Install / update   [R] Refresh",
+        alpha,
+    );
+}
+
+/// Navigation is handled here; fetching and installing are returned for
+/// the caller, which runs them off the UI thread
+pub fn handle_key(key: Key, state: &mut PluginIndexState) -> Option<PluginIndexAction> {
+    if state.busy {
+        return None;
+    }
+    if key == Key::R {
+        return Some(PluginIndexAction::Refresh);
+    }
+    let input = UiInput::from_key(key, false, false);
+    if let Some(UiNav::Escape) = input.nav {
+        return None;
+    }
+    let chosen = List::handle_nav(&mut state.focus, state.entries.len(), &input)?;
+    let (entry, status) = state.entries.get(chosen)?;
+    match status {
+        EntryStatus::Installed => {
+            state.status = Some(format!("{} {} is up to date", entry.name, entry.version));
+            None
+        }
+        EntryStatus::Incompatible { abi } => {
+            state.status = Some(format!(
+                "{} {} is built for plugin ABI {}, this build loads {}",
+                entry.name,
+                entry.version,
+                abi,
+                plugin_index::ABI_VERSION
+            ));
+            None
+        }
+        EntryStatus::Available | EntryStatus::UpdateAvailable { .. } => {
+            Some(PluginIndexAction::Install(entry.clone()))
+        }
+    }
+}
@@ -1,11 +1,893 @@
-// use nannou_egui::egui; removed
-#![allow(dead_code)] // Stubbed during egui removal; will be replaced by nannou controls-driven schema UI.
-use serde_json::Value;
-
-/// Render controls for a settings object based on a JSON schema
-/// (Stubbed - Egui removed)
-pub fn render_schema_ui(_ui: &mut (), _settings: &mut Value, _schema: &Value) -> bool {
-    // let mut changed = false;
-    // placeholder for future Nannou implementation
-    false
+//! Settings forms generated from a plugin's JSON Schema.
+//!
+//! [`SchemaForm`] flattens a `settings_schema` plus the current settings into
+//! keyboard-navigable [`SchemaRow`]s and applies [`UiInput`] to the settings
+//! value. Drawing stays with the tile; this module only decides what each row
+//! is and how keys edit it:
+//!
+//! - `boolean` → toggle
+//! - `enum` → dropdown (Left/Right cycles, Enter opens the list)
+//! - `number`/`integer` → slider when `minimum` and `maximum` are set, stepper otherwise
+//! - `string` with `format: "path"`, `"file-path"` or `"directory"` → file picker
+//! - `array` → collapsible list with a "+ Add" row; Delete removes the focused item
+//! - `object` → collapsible section of its `properties`
+use crate::ui::controls::{FocusModel, UiInput, UiNav};
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// One step into a settings value.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSeg {
+    Key(String),
+    Index(usize),
+}
+
+pub type FieldPath = Vec<PathSeg>;
+
+/// JSON pointer for a field path, used to remember collapsed sections.
+pub fn pointer(path: &[PathSeg]) -> String {
+    path.iter()
+        .map(|seg| match seg {
+            PathSeg::Key(key) => format!("/{}", key.replace('~', "~0").replace('/', "~1")),
+            PathSeg::Index(i) => format!("/{}", i),
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RowKind {
+    Toggle(bool),
+    Number {
+        value: f64,
+        min: Option<f64>,
+        max: Option<f64>,
+        step: f64,
+        integer: bool,
+    },
+    Dropdown {
+        options: Vec<Value>,
+        selected: Option<usize>,
+    },
+    Text(String),
+    FilePath {
+        value: String,
+        directory: bool,
+    },
+    /// Header of a nested object, or of an array when `len` is set.
+    Section {
+        expanded: bool,
+        len: Option<usize>,
+    },
+    /// Appends a default item to the array at the row's path.
+    AddItem,
+}
+
+impl RowKind {
+    /// Bounded numbers render as sliders.
+    pub fn slider_range(&self) -> Option<(f64, f64)> {
+        match self {
+            RowKind::Number {
+                min: Some(min),
+                max: Some(max),
+                ..
+            } if max > min => Some((*min, *max)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaRow {
+    pub path: FieldPath,
+    pub label: String,
+    pub depth: usize,
+    pub kind: RowKind,
+    /// Array items can be removed with Delete.
+    pub removable: bool,
+}
+
+/// Display text for an enum option or free-form value.
+pub fn value_label(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => "-".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Display text for a number, trimming float noise.
+pub fn number_label(value: f64, integer: bool) -> String {
+    if integer {
+        format!("{}", value.round() as i64)
+    } else {
+        let text = format!("{:.3}", value);
+        text.trim_end_matches('0').trim_end_matches('.').to_string()
+    }
+}
+
+fn schema_type(schema: &Value) -> Option<&str> {
+    match schema.get("type") {
+        Some(Value::String(t)) => Some(t.as_str()),
+        Some(Value::Array(types)) => types
+            .iter()
+            .filter_map(Value::as_str)
+            .find(|t| *t != "null"),
+        _ if schema.get("properties").is_some() => Some("object"),
+        _ if schema.get("items").is_some() => Some("array"),
+        _ => None,
+    }
+}
+
+/// `Some(true)` for directory pickers, `Some(false)` for file pickers.
+fn path_format(schema: &Value) -> Option<bool> {
+    match schema.get("format").and_then(Value::as_str)? {
+        "path" | "file-path" | "file" => Some(false),
+        "directory" | "dir-path" => Some(true),
+        _ => None,
+    }
+}
+
+fn number_bound(schema: &Value, inclusive: &str, exclusive: &str) -> Option<f64> {
+    schema
+        .get(inclusive)
+        .and_then(Value::as_f64)
+        .or_else(|| schema.get(exclusive).and_then(Value::as_f64))
+}
+
+/// The schema's `default`, or an empty value of its type.
+pub fn default_for(schema: &Value) -> Value {
+    if let Some(default) = schema.get("default") {
+        return default.clone();
+    }
+    if let Some(first) = schema
+        .get("enum")
+        .and_then(Value::as_array)
+        .and_then(|options| options.first())
+    {
+        return first.clone();
+    }
+    match schema_type(schema) {
+        Some("boolean") => Value::Bool(false),
+        Some("integer") => {
+            Value::from(number_bound(schema, "minimum", "exclusiveMinimum").unwrap_or(0.0) as i64)
+        }
+        Some("number") => {
+            Value::from(number_bound(schema, "minimum", "exclusiveMinimum").unwrap_or(0.0))
+        }
+        Some("string") => Value::String(String::new()),
+        Some("array") => Value::Array(Vec::new()),
+        Some("object") => {
+            let mut map = Map::new();
+            if let Some(props) = schema.get("properties").and_then(Value::as_object) {
+                for (key, prop) in props {
+                    map.insert(key.clone(), default_for(prop));
+                }
+            }
+            Value::Object(map)
+        }
+        _ => Value::Null,
+    }
+}
+
+fn schema_at<'a>(schema: &'a Value, path: &[PathSeg]) -> Option<&'a Value> {
+    path.iter().try_fold(schema, |node, seg| match seg {
+        PathSeg::Key(key) => node.get("properties")?.get(key),
+        PathSeg::Index(_) => node.get("items"),
+    })
+}
+
+fn ensure_object(value: &mut Value) -> &mut Map<String, Value> {
+    if !value.is_object() {
+        *value = Value::Object(Map::new());
+    }
+    match value {
+        Value::Object(map) => map,
+        _ => unreachable!(),
+    }
+}
+
+fn ensure_array(value: &mut Value) -> &mut Vec<Value> {
+    if !value.is_array() {
+        *value = Value::Array(Vec::new());
+    }
+    match value {
+        Value::Array(items) => items,
+        _ => unreachable!(),
+    }
+}
+
+/// Walk to `path`, creating intermediate objects and array slots as needed.
+fn value_at_mut<'a>(root: &'a mut Value, path: &[PathSeg]) -> &'a mut Value {
+    path.iter().fold(root, |node, seg| match seg {
+        PathSeg::Key(key) => ensure_object(node)
+            .entry(key.clone())
+            .or_insert(Value::Null),
+        PathSeg::Index(i) => {
+            let items = ensure_array(node);
+            if items.len() <= *i {
+                items.resize(*i + 1, Value::Null);
+            }
+            &mut items[*i]
+        }
+    })
+}
+
+fn remove_at(root: &mut Value, path: &[PathSeg]) -> bool {
+    let Some((PathSeg::Index(i), parent)) = path.split_last() else {
+        return false;
+    };
+    match value_at_mut(root, parent) {
+        Value::Array(items) if *i < items.len() => {
+            items.remove(*i);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Key handling result for a [`SchemaForm`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FormOutcome {
+    pub consumed: bool,
+    /// The settings value was edited and should be sent to the module.
+    pub changed: bool,
+}
+
+impl FormOutcome {
+    const IGNORED: Self = Self {
+        consumed: false,
+        changed: false,
+    };
+    const CONSUMED: Self = Self {
+        consumed: true,
+        changed: false,
+    };
+    const CHANGED: Self = Self {
+        consumed: true,
+        changed: true,
+    };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    Parent,
+    /// Picks the directory being browsed (directory pickers only).
+    UseCurrent,
+    Dir,
+    File,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PickerEntry {
+    pub name: String,
+    pub path: PathBuf,
+    pub kind: EntryKind,
+}
+
+/// Keyboard file browser opened from a path field.
+#[derive(Debug, Clone)]
+pub struct FilePicker {
+    pub path: FieldPath,
+    pub label: String,
+    pub directory: bool,
+    pub dir: PathBuf,
+    pub entries: Vec<PickerEntry>,
+    pub focus: FocusModel,
+}
+
+impl FilePicker {
+    fn open(path: FieldPath, label: String, current: &str, directory: bool) -> Self {
+        let current = Path::new(current);
+        let dir = if directory && current.is_dir() {
+            current.to_path_buf()
+        } else {
+            current
+                .parent()
+                .filter(|parent| parent.is_dir())
+                .map(Path::to_path_buf)
+                .or_else(|| std::env::current_dir().ok())
+                .unwrap_or_else(|| PathBuf::from("/"))
+        };
+        let mut picker = Self {
+            path,
+            label,
+            directory,
+            dir: dir.clone(),
+            entries: Vec::new(),
+            focus: FocusModel::new(),
+        };
+        picker.browse(dir);
+        picker
+    }
+
+    fn browse(&mut self, dir: PathBuf) {
+        let mut entries = Vec::new();
+        if let Some(parent) = dir.parent() {
+            entries.push(PickerEntry {
+                name: "..".to_string(),
+                path: parent.to_path_buf(),
+                kind: EntryKind::Parent,
+            });
+        }
+        if self.directory {
+            entries.push(PickerEntry {
+                name: "[ use this folder ]".to_string(),
+                path: dir.clone(),
+                kind: EntryKind::UseCurrent,
+            });
+        }
+
+        let mut listing: Vec<PickerEntry> = match std::fs::read_dir(&dir) {
+            Ok(read) => read
+                .flatten()
+                .filter_map(|entry| {
+                    let name = entry.file_name().to_string_lossy().into_owned();
+                    if name.starts_with('.') {
+                        return None;
+                    }
+                    let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+                    if self.directory && !is_dir {
+                        return None;
+                    }
+                    Some(PickerEntry {
+                        name,
+                        path: entry.path(),
+                        kind: if is_dir {
+                            EntryKind::Dir
+                        } else {
+                            EntryKind::File
+                        },
+                    })
+                })
+                .collect(),
+            Err(e) => {
+                log::warn!("File picker cannot read {:?}: {}", dir, e);
+                Vec::new()
+            }
+        };
+        listing.sort_by(|a, b| {
+            (a.kind != EntryKind::Dir, a.name.to_lowercase())
+                .cmp(&(b.kind != EntryKind::Dir, b.name.to_lowercase()))
+        });
+        entries.extend(listing);
+
+        self.dir = dir;
+        self.entries = entries;
+        self.focus = FocusModel::new();
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DropdownState {
+    pub path: FieldPath,
+    pub label: String,
+    pub options: Vec<Value>,
+    pub focus: FocusModel,
+}
+
+/// Popup currently capturing input on top of the form.
+#[derive(Debug, Clone)]
+pub enum Overlay {
+    Dropdown(DropdownState),
+    Picker(FilePicker),
+}
+
+/// Form state for one schema: focus, collapsed sections and any open popup.
+#[derive(Debug, Clone)]
+pub struct SchemaForm {
+    schema: Value,
+    collapsed: HashSet<String>,
+    pub focus: FocusModel,
+    overlay: Option<Overlay>,
+}
+
+impl SchemaForm {
+    pub fn new(schema: Value) -> Self {
+        Self {
+            schema,
+            collapsed: HashSet::new(),
+            focus: FocusModel::new(),
+            overlay: None,
+        }
+    }
+
+    pub fn overlay(&self) -> Option<&Overlay> {
+        self.overlay.as_ref()
+    }
+
+    /// Flatten the schema against `settings`; missing values show their defaults.
+    pub fn rows(&self, settings: &Value) -> Vec<SchemaRow> {
+        let mut rows = Vec::new();
+        let mut path = Vec::new();
+        if schema_type(&self.schema) == Some("object") {
+            self.push_properties(&self.schema, settings, &mut path, 0, &mut rows);
+        } else if !self.schema.is_null() {
+            self.push_rows(
+                &self.schema,
+                Some(settings),
+                &mut path,
+                "Value".to_string(),
+                0,
+                false,
+                &mut rows,
+            );
+        }
+        rows
+    }
+
+    fn push_properties(
+        &self,
+        schema: &Value,
+        value: &Value,
+        path: &mut FieldPath,
+        depth: usize,
+        rows: &mut Vec<SchemaRow>,
+    ) {
+        let Some(props) = schema.get("properties").and_then(Value::as_object) else {
+            return;
+        };
+        for (key, prop) in props {
+            path.push(PathSeg::Key(key.clone()));
+            let label = prop
+                .get("title")
+                .and_then(Value::as_str)
+                .unwrap_or(key)
+                .to_string();
+            self.push_rows(prop, value.get(key), path, label, depth, false, rows);
+            path.pop();
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn push_rows(
+        &self,
+        schema: &Value,
+        value: Option<&Value>,
+        path: &mut FieldPath,
+        label: String,
+        depth: usize,
+        removable: bool,
+        rows: &mut Vec<SchemaRow>,
+    ) {
+        let default = default_for(schema);
+        let value = value.filter(|v| !v.is_null()).unwrap_or(&default);
+        let row = |kind| SchemaRow {
+            path: path.clone(),
+            label: label.clone(),
+            depth,
+            kind,
+            removable,
+        };
+
+        if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+            let selected = options.iter().position(|option| option == value);
+            rows.push(row(RowKind::Dropdown {
+                options: options.clone(),
+                selected,
+            }));
+            return;
+        }
+
+        match schema_type(schema) {
+            Some("boolean") => rows.push(row(RowKind::Toggle(value.as_bool().unwrap_or(false)))),
+            Some(t @ ("number" | "integer")) => {
+                let integer = t == "integer";
+                let min = number_bound(schema, "minimum", "exclusiveMinimum");
+                let max = number_bound(schema, "maximum", "exclusiveMaximum");
+                let step = schema
+                    .get("multipleOf")
+                    .and_then(Value::as_f64)
+                    .filter(|step| *step > 0.0)
+                    .unwrap_or(match (min, max) {
+                        _ if integer => 1.0,
+                        (Some(min), Some(max)) if max > min => (max - min) / 100.0,
+                        _ => 0.1,
+                    });
+                rows.push(row(RowKind::Number {
+                    value: value.as_f64().unwrap_or(0.0),
+                    min,
+                    max,
+                    step,
+                    integer,
+                }));
+            }
+            Some("string") => {
+                let text = value.as_str().unwrap_or_default().to_string();
+                rows.push(row(match path_format(schema) {
+                    Some(directory) => RowKind::FilePath {
+                        value: text,
+                        directory,
+                    },
+                    None => RowKind::Text(text),
+                }));
+            }
+            Some("array") => {
+                let items = value.as_array().map(Vec::as_slice).unwrap_or_default();
+                let expanded = !self.collapsed.contains(&pointer(path));
+                rows.push(row(RowKind::Section {
+                    expanded,
+                    len: Some(items.len()),
+                }));
+                if !expanded {
+                    return;
+                }
+                let item_schema = schema.get("items").unwrap_or(&Value::Null);
+                for (i, item) in items.iter().enumerate() {
+                    path.push(PathSeg::Index(i));
+                    let item_label = format!("#{}", i + 1);
+                    self.push_rows(
+                        item_schema,
+                        Some(item),
+                        path,
+                        item_label,
+                        depth + 1,
+                        true,
+                        rows,
+                    );
+                    path.pop();
+                }
+                rows.push(SchemaRow {
+                    path: path.clone(),
+                    label: "+ Add".to_string(),
+                    depth: depth + 1,
+                    kind: RowKind::AddItem,
+                    removable: false,
+                });
+            }
+            Some("object") => {
+                let expanded = !self.collapsed.contains(&pointer(path));
+                rows.push(row(RowKind::Section {
+                    expanded,
+                    len: None,
+                }));
+                if expanded {
+                    self.push_properties(schema, value, path, depth + 1, rows);
+                }
+            }
+            _ => rows.push(row(RowKind::Text(value_label(value)))),
+        }
+    }
+
+    /// Apply a key to the form, editing `settings` in place.
+    pub fn handle_input(&mut self, input: &UiInput, settings: &mut Value) -> FormOutcome {
+        if self.overlay.is_some() {
+            return self.handle_overlay(input, settings);
+        }
+
+        let rows = self.rows(settings);
+        self.focus.clamp(rows.len());
+        let Some(nav) = &input.nav else {
+            return FormOutcome::IGNORED;
+        };
+        let last = rows.len().saturating_sub(1);
+        match nav {
+            UiNav::Up => self.focus.focused = self.focus.focused.saturating_sub(1),
+            UiNav::Down => self.focus.focused = (self.focus.focused + 1).min(last),
+            UiNav::PageUp => self.focus.focused = self.focus.focused.saturating_sub(10),
+            UiNav::PageDown => self.focus.focused = (self.focus.focused + 10).min(last),
+            // Escape closes the tile's control view.
+            UiNav::Escape => return FormOutcome::IGNORED,
+            _ => {
+                return match rows.get(self.focus.focused) {
+                    Some(row) => self.edit_row(row, nav, input.shift, settings),
+                    None => FormOutcome::IGNORED,
+                };
+            }
+        }
+        FormOutcome::CONSUMED
+    }
+
+    fn edit_row(
+        &mut self,
+        row: &SchemaRow,
+        nav: &UiNav,
+        shift: bool,
+        settings: &mut Value,
+    ) -> FormOutcome {
+        if *nav == UiNav::Delete && row.removable {
+            if remove_at(settings, &row.path) {
+                return FormOutcome::CHANGED;
+            }
+            return FormOutcome::CONSUMED;
+        }
+
+        match (&row.kind, nav) {
+            (RowKind::Toggle(value), UiNav::Left | UiNav::Right | UiNav::Enter) => {
+                *value_at_mut(settings, &row.path) = Value::Bool(!value);
+                FormOutcome::CHANGED
+            }
+            (
+                RowKind::Number {
+                    value,
+                    min,
+                    max,
+                    step,
+                    integer,
+                },
+                UiNav::Left | UiNav::Right,
+            ) => {
+                let scale = if shift { 10.0 } else { 1.0 };
+                let delta = (if *nav == UiNav::Left { -step } else { *step }) * scale;
+                let mut next = value + delta;
+                if let Some(min) = min {
+                    next = next.max(*min);
+                }
+                if let Some(max) = max {
+                    next = next.min(*max);
+                }
+                let next = if *integer {
+                    Value::from(next.round() as i64)
+                } else {
+                    // Trim accumulated float error so saved settings stay readable.
+                    Value::from((next * 1e6).round() / 1e6)
+                };
+                *value_at_mut(settings, &row.path) = next;
+                FormOutcome::CHANGED
+            }
+            (RowKind::Dropdown { options, selected }, UiNav::Left | UiNav::Right) => {
+                if options.is_empty() {
+                    return FormOutcome::CONSUMED;
+                }
+                let len = options.len();
+                let next = match (selected, nav) {
+                    (None, _) => 0,
+                    (Some(i), UiNav::Left) => (i + len - 1) % len,
+                    (Some(i), _) => (i + 1) % len,
+                };
+                *value_at_mut(settings, &row.path) = options[next].clone();
+                FormOutcome::CHANGED
+            }
+            (RowKind::Dropdown { options, selected }, UiNav::Enter) => {
+                let mut focus = FocusModel::new();
+                focus.focused = selected.unwrap_or(0);
+                self.overlay = Some(Overlay::Dropdown(DropdownState {
+                    path: row.path.clone(),
+                    label: row.label.clone(),
+                    options: options.clone(),
+                    focus,
+                }));
+                FormOutcome::CONSUMED
+            }
+            (RowKind::FilePath { value, directory }, UiNav::Enter) => {
+                self.overlay = Some(Overlay::Picker(FilePicker::open(
+                    row.path.clone(),
+                    row.label.clone(),
+                    value,
+                    *directory,
+                )));
+                FormOutcome::CONSUMED
+            }
+            (RowKind::FilePath { .. }, UiNav::Delete) => {
+                *value_at_mut(settings, &row.path) = Value::String(String::new());
+                FormOutcome::CHANGED
+            }
+            (RowKind::Section { expanded, .. }, UiNav::Enter | UiNav::Left | UiNav::Right) => {
+                let expand = match nav {
+                    UiNav::Left => false,
+                    UiNav::Right => true,
+                    _ => !expanded,
+                };
+                let key = pointer(&row.path);
+                if expand {
+                    self.collapsed.remove(&key);
+                } else {
+                    self.collapsed.insert(key);
+                }
+                FormOutcome::CONSUMED
+            }
+            (RowKind::AddItem, UiNav::Enter) => {
+                let item = schema_at(&self.schema, &row.path)
+                    .and_then(|schema| schema.get("items"))
+                    .map(default_for)
+                    .unwrap_or(Value::Null);
+                ensure_array(value_at_mut(settings, &row.path)).push(item);
+                FormOutcome::CHANGED
+            }
+            _ => FormOutcome::IGNORED,
+        }
+    }
+
+    fn handle_overlay(&mut self, input: &UiInput, settings: &mut Value) -> FormOutcome {
+        let Some(nav) = &input.nav else {
+            return FormOutcome::CONSUMED;
+        };
+        if *nav == UiNav::Escape {
+            self.overlay = None;
+            return FormOutcome::CONSUMED;
+        }
+
+        match self.overlay.as_mut() {
+            Some(Overlay::Dropdown(dropdown)) => {
+                let last = dropdown.options.len().saturating_sub(1);
+                match nav {
+                    UiNav::Up => dropdown.focus.focused = dropdown.focus.focused.saturating_sub(1),
+                    UiNav::Down => dropdown.focus.focused = (dropdown.focus.focused + 1).min(last),
+                    UiNav::Enter => {
+                        let choice = dropdown.options.get(dropdown.focus.focused).cloned();
+                        let path = dropdown.path.clone();
+                        self.overlay = None;
+                        if let Some(choice) = choice {
+                            *value_at_mut(settings, &path) = choice;
+                            return FormOutcome::CHANGED;
+                        }
+                    }
+                    _ => {}
+                }
+                FormOutcome::CONSUMED
+            }
+            Some(Overlay::Picker(picker)) => {
+                let last = picker.entries.len().saturating_sub(1);
+                match nav {
+                    UiNav::Up => picker.focus.focused = picker.focus.focused.saturating_sub(1),
+                    UiNav::Down => picker.focus.focused = (picker.focus.focused + 1).min(last),
+                    UiNav::PageUp => picker.focus.focused = picker.focus.focused.saturating_sub(10),
+                    UiNav::PageDown => picker.focus.focused = (picker.focus.focused + 10).min(last),
+                    UiNav::Delete => {
+                        if let Some(parent) = picker.dir.parent().map(Path::to_path_buf) {
+                            picker.browse(parent);
+                        }
+                    }
+                    UiNav::Enter => {
+                        let Some(entry) = picker.entries.get(picker.focus.focused).cloned() else {
+                            return FormOutcome::CONSUMED;
+                        };
+                        match entry.kind {
+                            EntryKind::Parent | EntryKind::Dir => picker.browse(entry.path),
+                            EntryKind::UseCurrent | EntryKind::File => {
+                                let path = picker.path.clone();
+                                self.overlay = None;
+                                *value_at_mut(settings, &path) =
+                                    Value::String(entry.path.to_string_lossy().into_owned());
+                                return FormOutcome::CHANGED;
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+                FormOutcome::CONSUMED
+            }
+            None => FormOutcome::IGNORED,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn nav(nav: UiNav) -> UiInput {
+        UiInput {
+            nav: Some(nav),
+            shift: false,
+            ctrl: false,
+        }
+    }
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "enabled": { "type": "boolean", "default": true },
+                "gain": { "type": "number", "minimum": 0.0, "maximum": 2.0, "multipleOf": 0.5 },
+                "mode": { "type": "string", "enum": ["fast", "slow"] },
+                "model": { "type": "string", "format": "file-path" },
+                "tags": { "type": "array", "items": { "type": "string", "default": "new" } },
+                "network": {
+                    "type": "object",
+                    "properties": { "port": { "type": "integer", "default": 8080 } }
+                }
+            }
+        })
+    }
+
+    fn focus_on(form: &mut SchemaForm, settings: &Value, label: &str) {
+        form.focus.focused = form
+            .rows(settings)
+            .iter()
+            .position(|row| row.label == label)
+            .expect("row exists");
+    }
+
+    #[test]
+    fn flattens_schema_into_widget_rows() {
+        let form = SchemaForm::new(schema());
+        let rows = form.rows(&json!({ "tags": ["a"] }));
+        let kind = |label: &str| {
+            rows.iter()
+                .find(|row| row.label == label)
+                .map(|row| row.kind.clone())
+                .unwrap()
+        };
+
+        assert_eq!(kind("enabled"), RowKind::Toggle(true));
+        assert_eq!(kind("gain").slider_range(), Some((0.0, 2.0)));
+        assert!(matches!(
+            kind("mode"),
+            RowKind::Dropdown {
+                selected: Some(0),
+                ..
+            }
+        ));
+        assert!(matches!(
+            kind("model"),
+            RowKind::FilePath {
+                directory: false,
+                ..
+            }
+        ));
+        assert_eq!(
+            kind("tags"),
+            RowKind::Section {
+                expanded: true,
+                len: Some(1)
+            }
+        );
+        assert_eq!(kind("#1"), RowKind::Text("a".into()));
+        assert_eq!(kind("+ Add"), RowKind::AddItem);
+        let port = rows.iter().find(|row| row.label == "port").unwrap();
+        assert_eq!(port.depth, 1);
+        assert_eq!(
+            port.path,
+            vec![PathSeg::Key("network".into()), PathSeg::Key("port".into())]
+        );
+    }
+
+    #[test]
+    fn keys_edit_settings_in_place() {
+        let mut form = SchemaForm::new(schema());
+        let mut settings = Value::Null;
+
+        focus_on(&mut form, &settings, "gain");
+        assert!(form.handle_input(&nav(UiNav::Right), &mut settings).changed);
+        form.handle_input(&nav(UiNav::Right), &mut settings);
+        form.handle_input(&nav(UiNav::Right), &mut settings);
+        form.handle_input(&nav(UiNav::Right), &mut settings);
+        form.handle_input(&nav(UiNav::Right), &mut settings);
+        assert_eq!(settings["gain"], json!(2.0));
+
+        focus_on(&mut form, &settings, "mode");
+        form.handle_input(&nav(UiNav::Left), &mut settings);
+        assert_eq!(settings["mode"], json!("slow"));
+
+        focus_on(&mut form, &settings, "port");
+        form.handle_input(&nav(UiNav::Right), &mut settings);
+        assert_eq!(settings["network"]["port"], json!(8081));
+    }
+
+    #[test]
+    fn arrays_add_and_remove_items() {
+        let mut form = SchemaForm::new(schema());
+        let mut settings = json!({ "tags": ["a", "b"] });
+
+        focus_on(&mut form, &settings, "+ Add");
+        assert!(form.handle_input(&nav(UiNav::Enter), &mut settings).changed);
+        assert_eq!(settings["tags"], json!(["a", "b", "new"]));
+
+        focus_on(&mut form, &settings, "#1");
+        assert!(
+            form.handle_input(&nav(UiNav::Delete), &mut settings)
+                .changed
+        );
+        assert_eq!(settings["tags"], json!(["b", "new"]));
+    }
+
+    #[test]
+    fn sections_collapse_and_dropdowns_pick_from_list() {
+        let mut form = SchemaForm::new(schema());
+        let mut settings = json!({});
+
+        focus_on(&mut form, &settings, "network");
+        form.handle_input(&nav(UiNav::Enter), &mut settings);
+        assert!(form.rows(&settings).iter().all(|row| row.label != "port"));
+
+        focus_on(&mut form, &settings, "mode");
+        form.handle_input(&nav(UiNav::Enter), &mut settings);
+        assert!(matches!(form.overlay(), Some(Overlay::Dropdown(_))));
+        form.handle_input(&nav(UiNav::Down), &mut settings);
+        assert!(form.handle_input(&nav(UiNav::Enter), &mut settings).changed);
+        assert_eq!(settings["mode"], json!("slow"));
+        assert!(form.overlay().is_none());
+    }
 }
@@ -0,0 +1,156 @@
+//! Drawing for [`SchemaForm`]s: one row per field, plus the dropdown and
+//! file-picker popups. Shared by every tile whose control mode is a form.
+use crate::ui::controls::{self, List, UiStyle, ROW_GAP, ROW_H};
+use crate::ui::schema::{
+    number_label, value_label, EntryKind, Overlay, RowKind, SchemaForm, SchemaRow,
+};
+use magnolia_ui::{draw_text, FontId, TextAlignment};
+use nannou::prelude::*;
+use serde_json::Value;
+
+/// Horizontal indent per nesting level.
+const INDENT: f32 = 22.0;
+
+/// Render `form` for `settings` inside `rect`, scrolled to keep focus visible.
+pub fn render_form(draw: &Draw, rect: Rect, form: &SchemaForm, settings: &Value) {
+    let rows = form.rows(settings);
+    if rows.is_empty() {
+        draw_text(
+            draw,
+            FontId::PlexSansRegular,
+            "No configurable settings",
+            rect.xy(),
+            14.0,
+            srgba(0.5, 0.5, 0.5, 1.0),
+            TextAlignment::Center,
+        );
+        return;
+    }
+
+    let visible = (((rect.h() + ROW_GAP) / (ROW_H + ROW_GAP)).floor() as usize).max(1);
+    let focused = form.focus.focused.min(rows.len() - 1);
+    let start = (focused + 1).saturating_sub(visible);
+
+    for (slot, (i, row)) in rows
+        .iter()
+        .enumerate()
+        .skip(start)
+        .take(visible)
+        .enumerate()
+    {
+        let indent = row.depth as f32 * INDENT;
+        let y = rect.top() - ROW_H / 2.0 - slot as f32 * (ROW_H + ROW_GAP);
+        let row_rect = Rect::from_x_y_w_h(rect.x() + indent / 2.0, y, rect.w() - indent, ROW_H);
+        draw_row(draw, row_rect, row, i == focused);
+    }
+
+    if let Some(overlay) = form.overlay() {
+        draw_overlay(draw, rect, overlay);
+    }
+}
+
+fn draw_row(draw: &Draw, rect: Rect, row: &SchemaRow, focused: bool) {
+    let style = UiStyle::default();
+    match &row.kind {
+        RowKind::Toggle(value) => {
+            controls::draw_toggle_row(draw, rect, &row.label, *value, focused, style)
+        }
+        kind @ RowKind::Number { value, integer, .. } => match kind.slider_range() {
+            Some((min, max)) => {
+                let label = format!("{}  {}", row.label, number_label(*value, *integer));
+                controls::draw_slider_row(
+                    draw,
+                    rect,
+                    &label,
+                    *value as f32,
+                    min as f32,
+                    max as f32,
+                    focused,
+                    style,
+                );
+            }
+            None => controls::draw_stepper_row(
+                draw,
+                rect,
+                &row.label,
+                &number_label(*value, *integer),
+                focused,
+                style,
+            ),
+        },
+        RowKind::Dropdown { options, selected } => {
+            let current = selected
+                .and_then(|i| options.get(i))
+                .map(value_label)
+                .unwrap_or_else(|| "-".to_string());
+            controls::draw_stepper_row(draw, rect, &row.label, &current, focused, style);
+        }
+        RowKind::Text(text) => {
+            controls::draw_value_row(draw, rect, &row.label, text, focused, style)
+        }
+        RowKind::FilePath { value, .. } => {
+            let shown = if value.is_empty() {
+                "Browse...".to_string()
+            } else {
+                // Keep the end of long paths, where the file name is.
+                let chars: Vec<char> = value.chars().collect();
+                if chars.len() > 40 {
+                    format!(
+                        "...{}",
+                        chars[chars.len() - 37..].iter().collect::<String>()
+                    )
+                } else {
+                    value.clone()
+                }
+            };
+            controls::draw_value_row(draw, rect, &row.label, &shown, focused, style);
+        }
+        RowKind::Section { expanded, len } => {
+            let detail = len.map(|n| format!("{} items", n));
+            controls::draw_section_row(
+                draw,
+                rect,
+                &row.label,
+                *expanded,
+                detail.as_deref(),
+                focused,
+                style,
+            );
+        }
+        RowKind::AddItem => controls::draw_value_row(draw, rect, &row.label, "", focused, style),
+    }
+}
+
+fn draw_overlay(draw: &Draw, rect: Rect, overlay: &Overlay) {
+    let panel = Rect::from_x_y_w_h(rect.x(), rect.y(), rect.w() * 0.8, rect.h() * 0.8);
+    draw.rect()
+        .xy(panel.xy())
+        .wh(panel.wh())
+        .color(rgba(0.02, 0.02, 0.03, 0.97));
+
+    let style = UiStyle::default();
+    match overlay {
+        Overlay::Dropdown(dropdown) => {
+            List::new(&dropdown.focus, panel, dropdown.options.len(), ROW_H)
+                .with_title(&dropdown.label)
+                .render(draw, |i, focused, item_rect| {
+                    let text = value_label(&dropdown.options[i]);
+                    controls::draw_value_row(draw, item_rect, &text, "", focused, style);
+                });
+        }
+        Overlay::Picker(picker) => {
+            let title = picker.dir.to_string_lossy();
+            List::new(&picker.focus, panel, picker.entries.len(), ROW_H)
+                .with_title(&title)
+                .render(draw, |i, focused, item_rect| {
+                    let entry = &picker.entries[i];
+                    let kind = match entry.kind {
+                        EntryKind::Parent | EntryKind::Dir => "DIR",
+                        EntryKind::UseCurrent => "",
+                        EntryKind::File => "FILE",
+                    };
+                    controls::draw_value_row(draw, item_rect, &entry.name, kind, focused, style);
+                });
+        }
+    }
+}
@@ -0,0 +1,167 @@
+//! Type-to-filter for the module lists
+//!
+//! A query matches a name when its characters appear there in order,
+//! ignoring case, so `stt` finds `speech_to_text`. Runs of consecutive
+//! letters and letters starting a word score higher, and lists show their
+//! best matches first with the matched letters picked out.
+
+use magnolia_ui::{draw_text, text_width, FontId, TextAlignment};
+use nannou::prelude::*;
+
+/// Colour of the matched letters
+const MATCH_COLOR: (f32, f32, f32) = (1.0, 0.85, 0.0);
+
+/// Score and matched character positions of `query` in `text`, or `None`
+/// if its characters don't all appear there in order
+pub fn fuzzy_match(query: &str, text: &str) -> Option<(i32, Vec<usize>)> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut positions = Vec::new();
+    let mut score = 0;
+    let mut from = 0;
+    for wanted in query.chars().filter(|c| !c.is_whitespace()) {
+        let found = (from..chars.len()).find(|&i| chars[i].eq_ignore_ascii_case(&wanted))?;
+        score += 1;
+        if positions.last() == Some(&(found.wrapping_sub(1))) {
+            score += 5;
+        }
+        let word_start = found == 0
+            || !chars[found - 1].is_alphanumeric()
+            || (chars[found - 1].is_lowercase() && chars[found].is_uppercase());
+        if word_start {
+            score += 3;
+        }
+        positions.push(found);
+        from = found + 1;
+    }
+    // Between equal matches, the one found earlier in the shorter name
+    let spread = positions.first().copied().unwrap_or(0) + chars.len() / 8;
+    Some((score * 16 - spread as i32, positions))
+}
+
+/// Indices of the `items` matching `query`, best first, each with its
+/// matched positions; every item in order while the query is empty
+pub fn filter<'a>(
+    query: &str,
+    items: impl IntoIterator<Item = &'a str>,
+) -> Vec<(usize, Vec<usize>)> {
+    let mut matches: Vec<(i32, usize, Vec<usize>)> = items
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, item)| {
+            let (score, positions) = fuzzy_match(query, item)?;
+            Some((score, i, positions))
+        })
+        .collect();
+    if !query.trim().is_empty() {
+        matches.sort_by_key(|(score, i, _)| (std::cmp::Reverse(*score), *i));
+    }
+    matches
+        .into_iter()
+        .map(|(_, i, positions)| (i, positions))
+        .collect()
+}
+
+/// Type `key` into `query`, or delete its last character on Backspace;
+/// false if the key isn't one for typing
+pub fn edit_query(query: &mut String, key: Key, shift: bool) -> bool {
+    if key == Key::Back {
+        query.pop();
+        return true;
+    }
+    let typed = match key {
+        Key::Minus if shift => Some('_'),
+        Key::Minus => Some('-'),
+        Key::Space => Some(' '),
+        _ => {
+            // Letters are named `A` to `Z`, digits `Key0` to `Key9`
+            let name = format!("{:?}", key);
+            match name.as_str() {
+                letter if letter.len() == 1 => letter.chars().next(),
+                digit if digit.len() == 4 && digit.starts_with("Key") => digit.chars().last(),
+                _ => None,
+            }
+            .filter(char::is_ascii_alphanumeric)
+            .map(|c| c.to_ascii_lowercase())
+        }
+    };
+    match typed {
+        Some(c) => {
+            query.push(c);
+            true
+        }
+        None => false,
+    }
+}
+
+/// `text` as `draw_text` would draw it, with the characters at `positions`
+/// drawn over in the match colour
+#[allow(clippy::too_many_arguments)]
+pub fn draw_matched(
+    draw: &Draw,
+    font: FontId,
+    text: &str,
+    positions: &[usize],
+    pos: Point2,
+    size: f32,
+    color: Srgba,
+    align: TextAlignment,
+) {
+    draw_text(draw, font, text, pos, size, color, align);
+    if positions.is_empty() {
+        return;
+    }
+    let left = match align {
+        TextAlignment::Left => pos.x,
+        TextAlignment::Center => pos.x - text_width(font, text, size) / 2.0,
+        TextAlignment::Right => pos.x - text_width(font, text, size),
+    };
+    let (r, g, b) = MATCH_COLOR;
+    let highlight = srgba(r, g, b, color.alpha);
+    for (i, c) in text.chars().enumerate() {
+        if positions.contains(&i) {
+            let prefix: String = text.chars().take(i).collect();
+            let x = left + text_width(font, &prefix, size);
+            let mut buf = [0; 4];
+            draw_text(
+                draw,
+                font,
+                c.encode_utf8(&mut buf),
+                pt2(x, pos.y),
+                size,
+                highlight,
+                TextAlignment::Left,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_rank_word_starts_and_runs_first() {
+        assert_eq!(
+            fuzzy_match("stt", "speech_to_text").map(|(_, p)| p),
+            Some(vec![0, 7, 10])
+        );
+        assert!(fuzzy_match("xyz", "speech_to_text").is_none());
+
+        let modules = ["audio_output", "speech_to_text", "stats", "silence"];
+        let order: Vec<&str> = filter("st", modules)
+            .into_iter()
+            .map(|(i, _)| modules[i])
+            .collect();
+        assert_eq!(order, ["stats", "speech_to_text"]);
+        assert_eq!(filter("", modules).len(), 4);
+
+        let mut query = String::new();
+        assert!(edit_query(&mut query, Key::S, false));
+        assert!(edit_query(&mut query, Key::Key2, false));
+        assert!(edit_query(&mut query, Key::Minus, true));
+        assert!(!edit_query(&mut query, Key::Up, false));
+        assert_eq!(query, "s2_");
+        edit_query(&mut query, Key::Back, false);
+        assert_eq!(query, "s2");
+    }
+}
@@ -0,0 +1,119 @@
+//! Tile thumbnails for the Add Tile picker and the Layout Manager
+//!
+//! Each tile's monitor view is drawn offscreen into a small texture of its
+//! own, refreshed every couple of seconds while a list showing it is open,
+//! so choosing a module shows what it looks like rather than just its id.
+
+use crate::tiles::{RenderContext, TileRegistry};
+use nannou::prelude::*;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Pixel size of every thumbnail
+pub const THUMBNAIL_SIZE: [u32; 2] = [320, 180];
+/// Age at which a thumbnail is drawn again
+const REFRESH: Duration = Duration::from_secs(2);
+/// Thumbnails drawn per frame, so opening a long list doesn't stall one
+const PER_FRAME: usize = 4;
+
+struct Thumbnail {
+    texture: wgpu::Texture,
+    drawn_at: Instant,
+}
+
+/// Offscreen snapshots of tiles by module id
+#[derive(Default)]
+pub struct TileThumbnails {
+    /// Built with the first thumbnail; they all share a format and size
+    renderer: Option<nannou::draw::Renderer>,
+    thumbnails: HashMap<String, Thumbnail>,
+}
+
+impl TileThumbnails {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Draw the missing and stale thumbnails among `modules`, a few at a time
+    pub fn refresh(
+        &mut self,
+        window: &Window,
+        registry: &TileRegistry,
+        modules: &[String],
+        ctx: &RenderContext,
+    ) {
+        let now = Instant::now();
+        let due: Vec<&String> = modules
+            .iter()
+            .filter(|module| registry.get(module).is_some())
+            .filter(|module| {
+                self.thumbnails
+                    .get(module.as_str())
+                    .is_none_or(|thumbnail| now - thumbnail.drawn_at >= REFRESH)
+            })
+            .take(PER_FRAME)
+            .collect();
+        if due.is_empty() {
+            return;
+        }
+
+        let device = window.device();
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("tile thumbnails"),
+        });
+        let [width, height] = THUMBNAIL_SIZE;
+        for module in due {
+            let texture = match self.thumbnails.remove(module.as_str()) {
+                Some(thumbnail) => thumbnail.texture,
+                None => wgpu::TextureBuilder::new()
+                    .size(THUMBNAIL_SIZE)
+                    .usage(
+                        wgpu::TextureUsages::RENDER_ATTACHMENT
+                            | wgpu::TextureUsages::TEXTURE_BINDING,
+                    )
+                    .sample_count(1)
+                    .format(Frame::TEXTURE_FORMAT)
+                    .build(device),
+            };
+            let renderer = self.renderer.get_or_insert_with(|| {
+                nannou::draw::RendererBuilder::new()
+                    .build_from_texture_descriptor(device, texture.descriptor())
+            });
+            let draw = Draw::new();
+            draw.background().color(BLACK);
+            registry.render_monitor(
+                module,
+                &draw,
+                Rect::from_w_h(width as f32, height as f32),
+                ctx,
+            );
+            renderer.render_to_texture(device, &mut encoder, &draw, &texture);
+            self.thumbnails.insert(
+                module.clone(),
+                Thumbnail {
+                    texture,
+                    drawn_at: now,
+                },
+            );
+        }
+        window.queue().submit(Some(encoder.finish()));
+    }
+
+    /// Draw `module`'s thumbnail fitted into `rect`; false until it has one
+    pub fn draw(&self, draw: &Draw, module: &str, rect: Rect) -> bool {
+        let Some(thumbnail) = self.thumbnails.get(module) else {
+            return false;
+        };
+        let [width, height] = THUMBNAIL_SIZE;
+        let scale = (rect.w() / width as f32).min(rect.h() / height as f32);
+        draw.texture(&thumbnail.texture)
+            .xy(rect.xy())
+            .w_h(width as f32 * scale, height as f32 * scale);
+        true
+    }
+
+    /// Let go of the textures once nothing shows them
+    pub fn clear(&mut self) {
+        self.thumbnails.clear();
+    }
+}
@@ -0,0 +1,407 @@
+//! Headless end-to-end check of the daemon's routing
+//!
+//! Builds WAV replay → audio DSP → capture on a real `ModuleHost` and
+//! `PatchBay`, with a second capture straight off the replay, plays the file
+//! through in real time and checks that each capture got every chunk, in
+//! order, at the level expected: the replay's own for the straight capture,
+//! the DSP's gain times that for the other. Exits non-zero if anything is
+//! missing or off, or hasn't arrived within `--timeout` seconds.
+//!
+//! Without `--wav` it plays a generated second of 440 Hz tone.
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use audio_dsp::{AudioDspProcessor, AudioDspState};
+use audio_replay::WavReplaySource;
+use magnolia_core::adapters::{ProcessorAdapter, SinkAdapter, SourceAdapter};
+use magnolia_core::{
+    AudioView, DataType, ModuleHost, ModuleSchema, PatchBay, Port, PortDirection, Processor,
+    RoutedSignal, Signal, Sink, Source,
+};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+const USAGE: &str = "usage: daemon_smoke [--wav PATH] [--timeout SECONDS]";
+
+/// Module inbox size, as the daemon spawns them
+const INBOX: usize = 100;
+
+const CHUNK_MS: u32 = 20;
+
+/// Gain the DSP applies, with its AGC and lowpass off
+const GAIN: f32 = 0.5;
+
+/// Largest relative difference allowed between a capture's RMS level and
+/// the expected one
+const LEVEL_TOLERANCE: f32 = 0.01;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Config {
+    /// File to replay; a generated tone if `None`
+    wav: Option<PathBuf>,
+    timeout: Duration,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            wav: None,
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Config> {
+    let mut config = Config::default();
+    while let Some(arg) = args.next() {
+        let value = args
+            .next()
+            .with_context(|| format!("{} needs a value\n{}", arg, USAGE))?;
+        match arg.as_str() {
+            "--wav" => config.wav = Some(PathBuf::from(value)),
+            "--timeout" => {
+                let seconds: f64 = value
+                    .parse()
+                    .with_context(|| format!("invalid {} {:?}", arg, value))?;
+                if !seconds.is_finite() || seconds <= 0.0 {
+                    bail!("timeout must be positive");
+                }
+                config.timeout = Duration::from_secs_f64(seconds);
+            }
+            _ => bail!("unknown argument {}\n{}", arg, USAGE),
+        }
+    }
+    Ok(config)
+}
+
+/// The audio a capture has heard
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Heard {
+    frames: u64,
+    /// Interleaved samples
+    samples: u64,
+    sum_squares: f64,
+    peak: f32,
+    /// Frames timestamped before the one ahead of them
+    out_of_order: u64,
+    last_timestamp_us: Option<u64>,
+}
+
+impl Heard {
+    fn record(&mut self, audio: &AudioView<'_>) {
+        self.frames += 1;
+        self.samples += audio.data.len() as u64;
+        for sample in audio.data {
+            self.sum_squares += (*sample as f64).powi(2);
+            self.peak = self.peak.max(sample.abs());
+        }
+        if self
+            .last_timestamp_us
+            .is_some_and(|last| audio.timestamp_us < last)
+        {
+            self.out_of_order += 1;
+        }
+        self.last_timestamp_us = Some(audio.timestamp_us);
+    }
+
+    fn rms(&self) -> f32 {
+        if self.samples == 0 {
+            return 0.0;
+        }
+        (self.sum_squares / self.samples as f64).sqrt() as f32
+    }
+}
+
+/// What a capture should have heard by the end of the run
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Expected {
+    frames: u64,
+    samples: u64,
+    rms: f32,
+}
+
+impl Expected {
+    /// Why `heard` falls short, one line each; empty if it doesn't
+    fn check(&self, capture: &str, heard: &Heard) -> Vec<String> {
+        let mut failures = Vec::new();
+        if heard.frames != self.frames || heard.samples != self.samples {
+            failures.push(format!(
+                "{} got {} frames ({} samples), expected {} ({})",
+                capture, heard.frames, heard.samples, self.frames, self.samples
+            ));
+        }
+        if heard.out_of_order > 0 {
+            failures.push(format!(
+                "{} got {} frames out of order",
+                capture, heard.out_of_order
+            ));
+        }
+        let rms = heard.rms();
+        if (rms - self.rms).abs() > self.rms * LEVEL_TOLERANCE {
+            failures.push(format!(
+                "{} level is {:.4} RMS, expected {:.4}",
+                capture, rms, self.rms
+            ));
+        }
+        failures
+    }
+}
+
+/// Records the audio reaching it
+struct CaptureSink {
+    id: String,
+    heard: Arc<Mutex<Heard>>,
+    enabled: bool,
+}
+
+#[async_trait]
+impl Sink for CaptureSink {
+    fn name(&self) -> &str {
+        "Smoke Capture"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "Smoke Capture".to_string(),
+            description: "Records the audio reaching it for the smoke test".to_string(),
+            ports: vec![Port {
+                id: "audio_in".to_string(),
+                label: "Audio In".to_string(),
+                data_type: DataType::Audio,
+                direction: PortDirection::Input,
+            }],
+            settings_schema: None,
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    async fn consume(&self, signal: Signal) -> Result<Option<Signal>> {
+        // The replay's end-of-file intents and idle pulses come down the
+        // same patch
+        if let Some(audio) = signal.audio() {
+            if let Ok(mut heard) = self.heard.lock() {
+                heard.record(&audio);
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// A second of 440 Hz tone at half scale, in stereo
+fn write_tone(path: &Path) -> Result<()> {
+    let spec = hound::WavSpec {
+        channels: 2,
+        sample_rate: 48_000,
+        bits_per_sample: 32,
+        sample_format: hound::SampleFormat::Float,
+    };
+    let mut writer = hound::WavWriter::create(path, spec)?;
+    let step = 440.0 * std::f32::consts::TAU / spec.sample_rate as f32;
+    for i in 0..spec.sample_rate {
+        let sample = (i as f32 * step).sin() * 0.5;
+        writer.write_sample(sample)?;
+        writer.write_sample(sample)?;
+    }
+    writer.finalize()?;
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    let config = parse_args(std::env::args().skip(1))?;
+    match &config.wav {
+        Some(wav) => run(&config, wav),
+        None => {
+            let wav =
+                std::env::temp_dir().join(format!("magnolia_smoke_{}.wav", std::process::id()));
+            write_tone(&wav)?;
+            let result = run(&config, &wav);
+            let _ = std::fs::remove_file(&wav);
+            result
+        }
+    }
+}
+
+fn run(config: &Config, wav: &Path) -> Result<()> {
+    let (sample_rate, channels, audio) = audio_replay::load_wav_f32(wav)
+        .with_context(|| format!("failed to read {}", wav.display()))?;
+    if audio.is_empty() {
+        bail!("{} has no audio", wav.display());
+    }
+    let raw = Expected {
+        frames: audio_replay::chunk_audio_signals(sample_rate, channels, &audio, CHUNK_MS).len()
+            as u64,
+        samples: audio.len() as u64,
+        rms: (audio.iter().map(|s| (*s as f64).powi(2)).sum::<f64>() / audio.len() as f64).sqrt()
+            as f32,
+    };
+    let processed = Expected {
+        rms: raw.rms * GAIN,
+        ..raw
+    };
+
+    let (router_tx, mut router_rx) = mpsc::channel::<RoutedSignal>(1000);
+    let mut host = ModuleHost::new(router_tx);
+    let mut patch_bay = PatchBay::new();
+
+    let replay = WavReplaySource::new("replay", wav.to_path_buf(), CHUNK_MS, true)?;
+    let state = AudioDspState::new();
+    state.set_gain(GAIN);
+    state.set_agc_enabled(false);
+    let dsp = AudioDspProcessor::new("dsp", state);
+    let raw_heard = Arc::new(Mutex::new(Heard::default()));
+    let processed_heard = Arc::new(Mutex::new(Heard::default()));
+    let captures = [
+        ("raw_capture", raw_heard.clone()),
+        ("dsp_capture", processed_heard.clone()),
+    ]
+    .map(|(id, heard)| CaptureSink {
+        id: id.to_string(),
+        heard,
+        enabled: true,
+    });
+
+    patch_bay.register_module(replay.schema());
+    patch_bay.register_module(dsp.schema());
+    for capture in &captures {
+        patch_bay.register_module(capture.schema());
+    }
+    patch_bay.connect("replay", "audio_out", "dsp", "audio_in")?;
+    patch_bay.connect("dsp", "audio_out", "dsp_capture", "audio_in")?;
+    patch_bay.connect("replay", "audio_out", "raw_capture", "audio_in")?;
+
+    // Everything downstream is running before the first chunk goes out
+    host.spawn(ProcessorAdapter::new(dsp), INBOX)
+        .map_err(anyhow::Error::msg)?;
+    for capture in captures {
+        host.spawn(SinkAdapter::new(capture), INBOX)
+            .map_err(anyhow::Error::msg)?;
+    }
+    let started = Instant::now();
+    host.spawn(SourceAdapter::new(replay), INBOX)
+        .map_err(anyhow::Error::msg)?;
+
+    // Route on this thread, as the daemon does from its update loop
+    let frames = |heard: &Arc<Mutex<Heard>>| heard.lock().map(|h| h.frames).unwrap_or(0);
+    while started.elapsed() < config.timeout
+        && (frames(&raw_heard) < raw.frames || frames(&processed_heard) < processed.frames)
+    {
+        match router_rx.try_recv() {
+            Ok(routed) => {
+                host.route_signal(&patch_bay, routed);
+            }
+            Err(mpsc::error::TryRecvError::Empty) => std::thread::sleep(Duration::from_micros(200)),
+            Err(mpsc::error::TryRecvError::Disconnected) => break,
+        }
+    }
+    let wall = started.elapsed();
+    let routing = host.routing_metrics().snapshot();
+    host.shutdown_all();
+
+    let raw_heard = raw_heard.lock().map(|h| h.clone()).unwrap_or_default();
+    let processed_heard = processed_heard
+        .lock()
+        .map(|h| h.clone())
+        .unwrap_or_default();
+    println!(
+        "input wav={} sample_rate={} channels={} frames={} rms={:.4}",
+        wav.display(),
+        sample_rate,
+        channels,
+        raw.frames,
+        raw.rms
+    );
+    for (capture, heard) in [
+        ("raw_capture", &raw_heard),
+        ("dsp_capture", &processed_heard),
+    ] {
+        println!(
+            "capture={} frames={} samples={} rms={:.4} peak={:.4} out_of_order={}",
+            capture,
+            heard.frames,
+            heard.samples,
+            heard.rms(),
+            heard.peak,
+            heard.out_of_order
+        );
+    }
+    println!(
+        "routing wall_ms={} delivered={} send_failures={} loss_sensitive_failures={}",
+        wall.as_millis(),
+        routing.delivered,
+        routing.send_failures,
+        routing.loss_sensitive_failures
+    );
+
+    let mut failures = raw.check("raw_capture", &raw_heard);
+    failures.extend(processed.check("dsp_capture", &processed_heard));
+    if wall >= config.timeout {
+        failures.push(format!(
+            "timed out after {:.1}s",
+            config.timeout.as_secs_f64()
+        ));
+    }
+    if !failures.is_empty() {
+        for failure in &failures {
+            eprintln!("FAIL {}", failure);
+        }
+        bail!("smoke test failed {} checks", failures.len());
+    }
+    println!("ok");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_arguments_and_checks_captures() {
+        let config = parse_args(["--timeout", "2.5"].iter().map(|a| a.to_string())).unwrap();
+        assert_eq!(config.timeout, Duration::from_millis(2500));
+        assert_eq!(config.wav, None);
+        assert!(parse_args(["--timeout", "0"].iter().map(|a| a.to_string())).is_err());
+        assert!(parse_args(["--wav"].iter().map(|a| a.to_string())).is_err());
+
+        let mut heard = Heard::default();
+        for (timestamp_us, data) in [(0, [0.5, -0.5]), (20_000, [0.5, 0.5])] {
+            heard.record(&AudioView {
+                sample_rate: 48_000,
+                channels: 2,
+                timestamp_us,
+                data: &data,
+            });
+        }
+        let expected = Expected {
+            frames: 2,
+            samples: 4,
+            rms: 0.5,
+        };
+        assert!(expected.check("capture", &heard).is_empty());
+
+        heard.record(&AudioView {
+            sample_rate: 48_000,
+            channels: 2,
+            timestamp_us: 10_000,
+            data: &[0.0, 0.0],
+        });
+        assert_eq!(
+            expected.check("capture", &heard),
+            [
+                "capture got 3 frames (6 samples), expected 2 (4)",
+                "capture got 1 frames out of order",
+                "capture level is 0.4082 RMS, expected 0.5000",
+            ]
+        );
+    }
+}
@@ -48,6 +48,7 @@ fn main() -> Result<()> {
             .and_then(|v| v.parse().ok())
             .unwrap_or(2),
         endpointing: true,
+        hotwords: None,
     };
 
     let audio_duration = Duration::from_secs_f64(samples.len() as f64 / 16_000.0);
@@ -0,0 +1,227 @@
+use async_trait::async_trait;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use magnolia_core::{
+    DataType, ModuleHost, ModuleSchema, PatchBay, Port, PortDirection, RoutedSignal, Signal, Sink,
+    SinkAdapter,
+};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+/// Sink inboxes hold a whole batch, so timed sends never see a full queue
+const INBOX: usize = 1024;
+
+fn schema(id: &str) -> ModuleSchema {
+    let port = |id: &str, direction| Port {
+        id: id.to_string(),
+        label: id.to_string(),
+        data_type: DataType::Audio,
+        direction,
+    };
+    ModuleSchema {
+        id: id.to_string(),
+        name: id.to_string(),
+        description: String::new(),
+        ports: vec![
+            port("in", PortDirection::Input),
+            port("out", PortDirection::Output),
+        ],
+        settings_schema: None,
+        docs: None,
+    }
+}
+
+fn audio(samples: usize) -> Signal {
+    Signal::Audio {
+        sample_rate: 48_000,
+        channels: 2,
+        timestamp_us: 0,
+        data: vec![0.25; samples],
+    }
+}
+
+fn bench_signal_clone(c: &mut Criterion) {
+    let mut group = c.benchmark_group("signal_clone");
+    // One 256-frame stereo buffer up to a second of stereo audio
+    for samples in [512, 8_192, 96_000] {
+        group.throughput(Throughput::Bytes((samples * 4) as u64));
+        let owned = audio(samples);
+        group.bench_with_input(BenchmarkId::new("audio", samples), &owned, |b, signal| {
+            b.iter(|| black_box(signal).clone())
+        });
+        let shared = audio(samples).into_shared_audio();
+        group.bench_with_input(
+            BenchmarkId::new("shared_audio", samples),
+            &shared,
+            |b, signal| b.iter(|| black_box(signal).clone()),
+        );
+
+        let bytes = samples * 4;
+        let blob = Signal::Blob {
+            mime_type: "application/octet-stream".to_string(),
+            bytes: vec![7; bytes],
+        };
+        group.bench_with_input(BenchmarkId::new("blob", bytes), &blob, |b, signal| {
+            b.iter(|| black_box(signal).clone())
+        });
+        let shared = blob.clone().into_shared_blob();
+        group.bench_with_input(
+            BenchmarkId::new("shared_blob", bytes),
+            &shared,
+            |b, signal| b.iter(|| black_box(signal).clone()),
+        );
+    }
+    group.finish();
+}
+
+fn bench_patch_lookup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("patch_bay_outgoing");
+    for modules in [16, 128, 1024] {
+        // A chain, so every module has one outgoing patch
+        let mut patch_bay = PatchBay::new();
+        for i in 0..modules {
+            patch_bay.register_module(schema(&format!("m{}", i)));
+        }
+        for i in 1..modules {
+            patch_bay
+                .connect(&format!("m{}", i - 1), "out", &format!("m{}", i), "in")
+                .unwrap();
+        }
+        let middle = format!("m{}", modules / 2);
+        group.bench_with_input(BenchmarkId::from_parameter(modules), &middle, |b, id| {
+            b.iter(|| patch_bay.get_outgoing_patches(black_box(id)).len())
+        });
+    }
+    group.finish();
+}
+
+/// Counts what reaches it, so a batch can be waited out untimed
+struct CountingSink {
+    id: String,
+    received: Arc<AtomicU64>,
+}
+
+#[async_trait]
+impl Sink for CountingSink {
+    fn name(&self) -> &str {
+        &self.id
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        schema(&self.id)
+    }
+
+    fn set_enabled(&mut self, _enabled: bool) {}
+
+    async fn consume(&self, _signal: Signal) -> anyhow::Result<Option<Signal>> {
+        self.received.fetch_add(1, Ordering::Relaxed);
+        Ok(None)
+    }
+}
+
+/// Sources `fanout_N` patched to the first N of four sinks
+struct Graph {
+    host: ModuleHost,
+    patch_bay: PatchBay,
+    received: Arc<AtomicU64>,
+}
+
+impl Graph {
+    fn new() -> Self {
+        let (router_tx, _router_rx) = mpsc::channel(16);
+        let mut host = ModuleHost::new(router_tx);
+        let mut patch_bay = PatchBay::new();
+        let received = Arc::new(AtomicU64::new(0));
+        for sink in 0..4 {
+            let sink = CountingSink {
+                id: format!("sink{}", sink),
+                received: received.clone(),
+            };
+            patch_bay.register_module(sink.schema());
+            host.spawn(SinkAdapter::new(sink), INBOX).unwrap();
+        }
+        for fanout in [1, 4] {
+            let source = format!("fanout_{}", fanout);
+            patch_bay.register_module(schema(&source));
+            for sink in 0..fanout {
+                patch_bay
+                    .connect(&source, "out", &format!("sink{}", sink), "in")
+                    .unwrap();
+            }
+        }
+        Self {
+            host,
+            patch_bay,
+            received,
+        }
+    }
+
+    /// Time `send` over `iters` signals from `make`, in inbox-sized
+    /// batches, waiting for the sinks to drain between them
+    fn run(
+        &self,
+        iters: u64,
+        deliveries: u64,
+        make: impl Fn() -> Signal,
+        mut send: impl FnMut(&Self, Signal),
+    ) -> Duration {
+        let mut total = Duration::ZERO;
+        let mut left = iters;
+        while left > 0 {
+            let batch = left.min(INBOX as u64);
+            let signals = (0..batch).map(|_| make()).collect::<Vec<_>>();
+            let expected = self.received.load(Ordering::Relaxed) + batch * deliveries;
+            let started = Instant::now();
+            for signal in signals {
+                send(self, signal);
+            }
+            total += started.elapsed();
+            let deadline = Instant::now() + Duration::from_secs(1);
+            while self.received.load(Ordering::Relaxed) < expected && Instant::now() < deadline {
+                std::thread::yield_now();
+            }
+            left -= batch;
+        }
+        total
+    }
+}
+
+fn bench_module_host(c: &mut Criterion) {
+    let graph = Graph::new();
+    let mut group = c.benchmark_group("module_host");
+    group.bench_function("send_signal", |b| {
+        b.iter_custom(|iters| {
+            graph.run(
+                iters,
+                1,
+                || Signal::Pulse,
+                |graph, signal| graph.host.send_signal("sink0", signal).unwrap(),
+            )
+        })
+    });
+    for fanout in [1u64, 4] {
+        let source = format!("fanout_{}", fanout);
+        let route = |graph: &Graph, signal| {
+            graph
+                .host
+                .route_signal(&graph.patch_bay, RoutedSignal::new(&source, "out", signal));
+        };
+        group.bench_function(BenchmarkId::new("route_audio", fanout), |b| {
+            b.iter_custom(|iters| graph.run(iters, fanout, || audio(512), route))
+        });
+        group.bench_function(BenchmarkId::new("route_shared_audio", fanout), |b| {
+            let shared = audio(512).into_shared_audio();
+            b.iter_custom(|iters| graph.run(iters, fanout, || shared.clone(), route))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_signal_clone,
+    bench_patch_lookup,
+    bench_module_host
+);
+criterion_main!(benches);
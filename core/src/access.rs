@@ -0,0 +1,158 @@
+//! Access control for the remote surfaces
+//!
+//! `[access]` in layout.toml lists bearer tokens, each with a [`Scope`], and
+//! optionally a certificate to serve TLS with. With no tokens every request
+//! is let through, which is only meant for surfaces bound to localhost.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// What a token may do; each scope includes the ones before it
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema, Default,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Scope {
+    /// Status, metrics and the patch graph
+    #[default]
+    Read,
+    /// Also patching, settings and module control
+    Control,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ApiToken {
+    /// For logs; the token itself never is
+    pub name: String,
+    pub token: String,
+    #[serde(default)]
+    pub scope: Scope,
+}
+
+/// PEM files to serve TLS with
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TlsConfig {
+    /// Certificate chain, leaf first
+    pub cert: PathBuf,
+    pub key: PathBuf,
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum AccessError {
+    #[error("no token presented")]
+    Missing,
+
+    #[error("unknown token")]
+    Unknown,
+
+    #[error("token '{name}' is limited to {scope:?}")]
+    Forbidden { name: String, scope: Scope },
+}
+
+/// `[access]` in layout.toml
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct AccessConfig {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tokens: Vec<ApiToken>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls: Option<TlsConfig>,
+}
+
+/// Compares every byte whatever the first mismatch, so response timing
+/// doesn't give a token away
+fn same_secret(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0u8, |diff, (x, y)| diff | (x ^ y))
+            == 0
+}
+
+impl AccessConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// No tokens configured: anyone reaching the port gets in
+    pub fn is_open(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// The token behind `presented` if it allows `needed`; `None` when
+    /// access is open
+    pub fn authorize(
+        &self,
+        presented: Option<&str>,
+        needed: Scope,
+    ) -> Result<Option<&ApiToken>, AccessError> {
+        if self.is_open() {
+            return Ok(None);
+        }
+        let presented = presented.ok_or(AccessError::Missing)?;
+        // Checked against every token so timing doesn't tell how many match
+        let matches: Vec<&ApiToken> = self
+            .tokens
+            .iter()
+            .filter(|token| same_secret(&token.token, presented))
+            .collect();
+        let token = *matches.first().ok_or(AccessError::Unknown)?;
+        if token.scope < needed {
+            return Err(AccessError::Forbidden {
+                name: token.name.clone(),
+                scope: token.scope,
+            });
+        }
+        Ok(Some(token))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_are_checked_against_their_scope() {
+        assert_eq!(
+            AccessConfig::default().authorize(None, Scope::Control),
+            Ok(None)
+        );
+
+        let token = |name: &str, scope| ApiToken {
+            name: name.to_string(),
+            token: format!("{}-secret", name),
+            scope,
+        };
+        let access = AccessConfig {
+            tokens: vec![token("phone", Scope::Read), token("desk", Scope::Control)],
+            tls: None,
+        };
+        assert_eq!(
+            access.authorize(None, Scope::Read),
+            Err(AccessError::Missing)
+        );
+        assert_eq!(
+            access.authorize(Some("phone-secre"), Scope::Read),
+            Err(AccessError::Unknown)
+        );
+        assert_eq!(
+            access
+                .authorize(Some("phone-secret"), Scope::Read)
+                .unwrap()
+                .map(|t| t.name.as_str()),
+            Some("phone")
+        );
+        assert_eq!(
+            access.authorize(Some("phone-secret"), Scope::Control),
+            Err(AccessError::Forbidden {
+                name: "phone".to_string(),
+                scope: Scope::Read
+            })
+        );
+        assert!(access
+            .authorize(Some("desk-secret"), Scope::Control)
+            .is_ok());
+        assert!(access.authorize(Some("desk-secret"), Scope::Read).is_ok());
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     default_output_port, ExecutionModel, ModuleRuntime, ModuleSchema, Priority, Processor,
-    RoutedSignal, Signal, Sink, Source,
+    RoutedSignal, RtAudioNode, Signal, Sink, Source,
 };
 use async_trait::async_trait;
 use tokio::sync::mpsc;
@@ -48,17 +48,38 @@ impl<S: Source + 'static> ModuleRuntime for SourceAdapter<S> {
         self.source.set_enabled(enabled);
     }
 
-    async fn run(&mut self, _inbox: mpsc::Receiver<Signal>, outbox: mpsc::Sender<RoutedSignal>) {
-        // Sources don't receive signals, they only emit
-        // Clean async/await now that run() is async!
+    async fn run(&mut self, mut inbox: mpsc::Receiver<Signal>, outbox: mpsc::Sender<RoutedSignal>) {
+        // Sources mostly emit; anything patched into them is handed over between polls
+        // so a slow poll never loses buffered output to cancellation.
         loop {
+            while let Ok(signal) = inbox.try_recv() {
+                self.source.handle_signal(signal);
+            }
+            if self.source.is_idle() {
+                match inbox.recv().await {
+                    Some(signal) => self.source.handle_signal(signal),
+                    None => {
+                        log::info!(
+                            "Source {} inbox closed while idle, shutting down",
+                            self.name()
+                        );
+                        break;
+                    }
+                }
+                continue;
+            }
             match self.source.poll().await {
                 Some(signal) => {
+                    let source_port = match self.source.output_port(&signal) {
+                        Some(port) => port.to_string(),
+                        None => default_output_port(&self.schema),
+                    };
                     let routed = RoutedSignal {
                         source_id: self.schema.id.clone(),
-                        source_port: default_output_port(&self.schema),
+                        source_port,
                         schema_version: RoutedSignal::SCHEMA_VERSION,
                         signal,
+                        provenance: None,
                     };
                     if outbox.send(routed).await.is_err() {
                         log::warn!("Source {} outbox closed, shutting down", self.name());
@@ -117,6 +138,10 @@ impl<S: Sink + 'static> ModuleRuntime for SinkAdapter<S> {
         self.sink.set_enabled(enabled);
     }
 
+    fn rt_audio_node(&mut self) -> Option<Box<dyn RtAudioNode>> {
+        self.sink.rt_audio_node()
+    }
+
     async fn run(
         &mut self,
         mut inbox: mpsc::Receiver<Signal>,
@@ -180,19 +205,39 @@ impl<P: Processor + 'static> ModuleRuntime for ProcessorAdapter<P> {
         self.processor.set_enabled(enabled);
     }
 
+    fn rt_audio_node(&mut self) -> Option<Box<dyn RtAudioNode>> {
+        self.processor.rt_audio_node()
+    }
+
     async fn run(&mut self, mut inbox: mpsc::Receiver<Signal>, outbox: mpsc::Sender<RoutedSignal>) {
-        while let Some(signal) = inbox.recv().await {
-            if !self.is_enabled() {
-                continue;
-            }
+        loop {
+            let received = tokio::select! {
+                signal = inbox.recv() => Some(signal),
+                _ = self.processor.next_wake() => None,
+            };
+            let result = match received {
+                Some(Some(signal)) => {
+                    if !self.is_enabled() {
+                        continue;
+                    }
+                    self.processor.process(signal).await
+                }
+                Some(None) => break,
+                None => self.processor.wake().await,
+            };
 
-            match self.processor.process(signal).await {
+            match result {
                 Ok(Some(output)) => {
+                    let source_port = match self.processor.output_port(&output) {
+                        Some(port) => port.to_string(),
+                        None => default_output_port(&self.schema),
+                    };
                     let routed = RoutedSignal {
                         source_id: self.schema.id.clone(),
-                        source_port: default_output_port(&self.schema),
+                        source_port,
                         schema_version: RoutedSignal::SCHEMA_VERSION,
                         signal: output,
+                        provenance: None,
                     };
                     if outbox.send(routed).await.is_err() {
                         log::warn!("Processor {} outbox closed, shutting down", self.name());
@@ -208,3 +253,127 @@ impl<P: Processor + 'static> ModuleRuntime for ProcessorAdapter<P> {
         log::info!("Processor {} inbox closed, shutting down", self.name());
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DataType, Port, PortDirection};
+
+    /// Holds each signal until the next wake
+    struct Holder {
+        held: Option<Signal>,
+    }
+
+    #[async_trait]
+    impl Processor for Holder {
+        fn name(&self) -> &str {
+            "holder"
+        }
+
+        fn schema(&self) -> ModuleSchema {
+            ModuleSchema {
+                id: "holder".to_string(),
+                name: "Holder".to_string(),
+                description: "Test processor".to_string(),
+                ports: vec![Port {
+                    id: "out".to_string(),
+                    label: "Out".to_string(),
+                    data_type: DataType::Any,
+                    direction: PortDirection::Output,
+                }],
+                settings_schema: None,
+                docs: None,
+            }
+        }
+
+        fn set_enabled(&mut self, _enabled: bool) {}
+
+        async fn process(&mut self, signal: Signal) -> anyhow::Result<Option<Signal>> {
+            self.held = Some(signal);
+            Ok(None)
+        }
+
+        async fn next_wake(&self) {
+            if self.held.is_none() {
+                std::future::pending::<()>().await;
+            }
+        }
+
+        async fn wake(&mut self) -> anyhow::Result<Option<Signal>> {
+            Ok(self.held.take())
+        }
+    }
+
+    #[tokio::test]
+    async fn processor_emits_on_wake_without_input() {
+        let mut adapter = ProcessorAdapter::new(Holder { held: None });
+        let (in_tx, in_rx) = mpsc::channel(4);
+        let (out_tx, mut out_rx) = mpsc::channel(4);
+        let task = tokio::spawn(async move { adapter.run(in_rx, out_tx).await });
+
+        in_tx.send(Signal::Text("held".into())).await.unwrap();
+        let routed = out_rx.recv().await.unwrap();
+        assert_eq!(routed.source_port, "out");
+        assert!(matches!(routed.signal, Signal::Text(ref t) if t == "held"));
+
+        drop(in_tx);
+        task.await.unwrap();
+    }
+
+    /// Sends one signal per `again` it's handed, then sits idle
+    struct OneShot {
+        armed: bool,
+    }
+
+    #[async_trait]
+    impl Source for OneShot {
+        fn name(&self) -> &str {
+            "one_shot"
+        }
+
+        fn schema(&self) -> ModuleSchema {
+            ModuleSchema {
+                id: "one_shot".to_string(),
+                name: "One Shot".to_string(),
+                description: "Test source".to_string(),
+                ports: Vec::new(),
+                settings_schema: None,
+                docs: None,
+            }
+        }
+
+        fn set_enabled(&mut self, _enabled: bool) {}
+
+        fn handle_signal(&mut self, _signal: Signal) {
+            self.armed = true;
+        }
+
+        fn is_idle(&self) -> bool {
+            !self.armed
+        }
+
+        async fn poll(&mut self) -> Option<Signal> {
+            assert!(self.armed, "polled while idle");
+            self.armed = false;
+            Some(Signal::Text("shot".into()))
+        }
+    }
+
+    #[tokio::test]
+    async fn idle_source_waits_for_input_instead_of_polling() {
+        let mut adapter = SourceAdapter::new(OneShot { armed: true });
+        let (in_tx, in_rx) = mpsc::channel(4);
+        let (out_tx, mut out_rx) = mpsc::channel(4);
+        let task = tokio::spawn(async move { adapter.run(in_rx, out_tx).await });
+
+        assert!(out_rx.recv().await.is_some());
+        let quiet = tokio::time::timeout(std::time::Duration::from_millis(50), out_rx.recv()).await;
+        assert!(quiet.is_err());
+
+        in_tx.send(Signal::Pulse).await.unwrap();
+        assert!(out_rx.recv().await.is_some());
+
+        drop(in_tx);
+        task.await.unwrap();
+    }
+}
@@ -0,0 +1,152 @@
+//! Time source abstraction for the runtime.
+//!
+//! Modules that pace themselves or stamp signals should read time through a
+//! [`Clock`] instead of calling `SystemTime::now()` / `tokio::time::sleep`
+//! directly, so tests can swap in a [`VirtualClock`] and run pipelines faster
+//! than real time with reproducible timestamps.
+
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::watch;
+
+/// Shared handle to a clock, cheap to clone into modules.
+pub type SharedClock = Arc<dyn Clock>;
+
+#[async_trait]
+pub trait Clock: Send + Sync {
+    /// Current time in microseconds.
+    fn now_us(&self) -> u64;
+
+    /// Wait until `duration` has elapsed on this clock.
+    async fn sleep(&self, duration: Duration);
+}
+
+/// Wall-clock time backed by `SystemTime` and the tokio timer.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+#[async_trait]
+impl Clock for SystemClock {
+    fn now_us(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_micros() as u64
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        tokio::time::sleep(duration).await;
+    }
+}
+
+/// The default clock used when none is configured.
+pub fn system_clock() -> SharedClock {
+    Arc::new(SystemClock)
+}
+
+/// Deterministic clock that only moves when told to.
+///
+/// In auto-advance mode (the default) a `sleep` jumps the clock forward to its
+/// deadline and yields, so paced sources run as fast as the CPU allows while
+/// still producing the timestamps they would have in real time. With
+/// auto-advance off, sleepers wait until [`VirtualClock::advance`] moves time
+/// past their deadline, which lets a test step the whole graph explicitly.
+pub struct VirtualClock {
+    now_tx: watch::Sender<u64>,
+    auto_advance: bool,
+}
+
+impl VirtualClock {
+    pub fn new() -> Arc<Self> {
+        Self::starting_at(0, true)
+    }
+
+    /// Manually stepped clock; see [`VirtualClock::advance`].
+    pub fn manual() -> Arc<Self> {
+        Self::starting_at(0, false)
+    }
+
+    pub fn starting_at(start_us: u64, auto_advance: bool) -> Arc<Self> {
+        let (now_tx, _) = watch::channel(start_us);
+        Arc::new(Self {
+            now_tx,
+            auto_advance,
+        })
+    }
+
+    /// Move time forward, waking any sleepers whose deadline has passed.
+    pub fn advance(&self, duration: Duration) {
+        let step = duration.as_micros() as u64;
+        self.now_tx
+            .send_modify(|now| *now = now.saturating_add(step));
+    }
+
+    /// Move time forward to `us`; earlier values are ignored so time never runs backwards.
+    pub fn advance_to(&self, us: u64) {
+        self.now_tx.send_if_modified(|now| {
+            if us > *now {
+                *now = us;
+                true
+            } else {
+                false
+            }
+        });
+    }
+}
+
+#[async_trait]
+impl Clock for VirtualClock {
+    fn now_us(&self) -> u64 {
+        *self.now_tx.borrow()
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        let deadline = self.now_us().saturating_add(duration.as_micros() as u64);
+        if self.auto_advance {
+            self.advance_to(deadline);
+            tokio::task::yield_now().await;
+            return;
+        }
+        let mut now_rx = self.now_tx.subscribe();
+        while *now_rx.borrow_and_update() < deadline {
+            if now_rx.changed().await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn auto_advance_sleep_moves_time_without_waiting() {
+        let clock = VirtualClock::new();
+        let wall = std::time::Instant::now();
+
+        clock.sleep(Duration::from_secs(3600)).await;
+
+        assert_eq!(clock.now_us(), 3_600_000_000);
+        assert!(wall.elapsed() < Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn manual_clock_wakes_sleepers_on_advance() {
+        let clock = VirtualClock::manual();
+        let sleeper = clock.clone();
+        let task = tokio::spawn(async move {
+            sleeper.sleep(Duration::from_millis(50)).await;
+            sleeper.now_us()
+        });
+
+        tokio::task::yield_now().await;
+        clock.advance(Duration::from_millis(20));
+        tokio::task::yield_now().await;
+        assert!(!task.is_finished());
+
+        clock.advance(Duration::from_millis(40));
+        assert_eq!(task.await.unwrap(), 60_000);
+    }
+}
@@ -0,0 +1,221 @@
+//! Audio device errors, sorted by what can be done about them
+//!
+//! Backends report failures as text (cpal and PipeWire errors arrive
+//! wrapped in `anyhow`), so the cause is read from the message. Each kind
+//! comes with advice for the tile and, where the OS has one, the settings
+//! page that fixes it.
+
+use std::fmt;
+
+/// Which way the device carries audio, for wording the advice
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceDirection {
+    Input,
+    Output,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceErrorKind {
+    /// Another program holds the device
+    Busy,
+    /// The OS refused access (microphone privacy on macOS, sandbox rules)
+    PermissionDenied,
+    /// No such device, or no device at all
+    NotFound,
+    /// The device went away while in use
+    Disconnected,
+    /// The device offers no format the backend can use
+    UnsupportedFormat,
+    /// Anything else the backend reported
+    Backend,
+}
+
+/// A device failure with its cause
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceError {
+    pub kind: DeviceErrorKind,
+    pub direction: DeviceDirection,
+    /// The backend's own message
+    pub message: String,
+}
+
+impl DeviceError {
+    pub fn new(kind: DeviceErrorKind, direction: DeviceDirection, message: &str) -> Self {
+        Self {
+            kind,
+            direction,
+            message: message.to_string(),
+        }
+    }
+
+    /// Sort a backend error by its message and those of its causes
+    pub fn classify(direction: DeviceDirection, error: &anyhow::Error) -> Self {
+        Self::from_message(direction, &format!("{:#}", error))
+    }
+
+    pub fn from_message(direction: DeviceDirection, message: &str) -> Self {
+        let lower = message.to_ascii_lowercase();
+        let has = |words: &[&str]| words.iter().any(|word| lower.contains(word));
+        let kind = if has(&[
+            "permission",
+            "not permitted",
+            "access denied",
+            "eacces",
+            "not authorized",
+            "unauthorized",
+        ]) {
+            DeviceErrorKind::PermissionDenied
+        } else if has(&["busy", "in use", "exclusive", "temporarily unavailable"]) {
+            DeviceErrorKind::Busy
+        } else if has(&[
+            "no longer available",
+            "unplugged",
+            "disconnected",
+            "removed",
+        ]) {
+            DeviceErrorKind::Disconnected
+        } else if has(&[
+            "not found",
+            "no input device",
+            "no output device",
+            "no such device",
+        ]) {
+            DeviceErrorKind::NotFound
+        } else if has(&["unsupported", "not supported", "only f32"]) {
+            DeviceErrorKind::UnsupportedFormat
+        } else {
+            DeviceErrorKind::Backend
+        };
+        Self::new(kind, direction, message)
+    }
+
+    fn device(&self) -> &'static str {
+        match self.direction {
+            DeviceDirection::Input => "microphone",
+            DeviceDirection::Output => "audio output",
+        }
+    }
+
+    /// Short line for the tile's error banner
+    pub fn title(&self) -> String {
+        match self.kind {
+            DeviceErrorKind::Busy => format!("The {} is in use", self.device()),
+            DeviceErrorKind::PermissionDenied => {
+                format!("No permission to use the {}", self.device())
+            }
+            DeviceErrorKind::NotFound => format!("No {} found", self.device()),
+            DeviceErrorKind::Disconnected => format!("The {} was disconnected", self.device()),
+            DeviceErrorKind::UnsupportedFormat => {
+                format!("The {} has no usable format", self.device())
+            }
+            DeviceErrorKind::Backend => format!("The {} failed to start", self.device()),
+        }
+    }
+
+    /// What to do about it, ending with the keys that help
+    pub fn advice(&self) -> &'static str {
+        match (self.kind, self.direction) {
+            (DeviceErrorKind::Busy, _) => {
+                "Close the other program using it, then press Ctrl+R to retry."
+            }
+            (DeviceErrorKind::PermissionDenied, DeviceDirection::Input) => {
+                "Allow microphone access for Magnolia (S opens the settings), then press Ctrl+R."
+            }
+            (DeviceErrorKind::PermissionDenied, DeviceDirection::Output) => {
+                "Allow audio access for Magnolia (S opens the settings), then press Ctrl+R."
+            }
+            (DeviceErrorKind::NotFound | DeviceErrorKind::Disconnected, _) => {
+                "Plug the device in or pick another from the list, then press Ctrl+R."
+            }
+            (DeviceErrorKind::UnsupportedFormat, _) => {
+                "Pick another device, or change its format in the sound settings (S)."
+            }
+            (DeviceErrorKind::Backend, _) => "Press Ctrl+R to retry; S opens the sound settings.",
+        }
+    }
+
+    /// The program and arguments opening the OS settings page for this
+    /// error, if the OS has one
+    pub fn settings_command(&self) -> Option<(&'static str, Vec<&'static str>)> {
+        let privacy = self.kind == DeviceErrorKind::PermissionDenied
+            && self.direction == DeviceDirection::Input;
+        if cfg!(target_os = "macos") {
+            let page = if privacy {
+                "x-apple.systempreferences:com.apple.preference.security?Privacy_Microphone"
+            } else {
+                "x-apple.systempreferences:com.apple.preference.sound"
+            };
+            Some(("open", vec![page]))
+        } else if cfg!(target_os = "windows") {
+            let page = if privacy {
+                "ms-settings:privacy-microphone"
+            } else {
+                "ms-settings:sound"
+            };
+            Some(("cmd", vec!["/C", "start", page]))
+        } else if cfg!(target_os = "linux") {
+            Some(("pavucontrol", Vec::new()))
+        } else {
+            None
+        }
+    }
+
+    /// Open the settings page from [`settings_command`](Self::settings_command)
+    pub fn open_settings(&self) -> std::io::Result<()> {
+        let Some((program, args)) = self.settings_command() else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "no sound settings page on this OS",
+            ));
+        };
+        std::process::Command::new(program).args(args).spawn()?;
+        Ok(())
+    }
+}
+
+impl fmt::Display for DeviceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.title(), self.message)
+    }
+}
+
+impl std::error::Error for DeviceError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backend_messages_sort_into_kinds() {
+        let kind = |message: &str| DeviceError::from_message(DeviceDirection::Input, message).kind;
+        assert_eq!(
+            kind("Device or resource busy (os error 16)"),
+            DeviceErrorKind::Busy
+        );
+        assert_eq!(
+            kind("stream.connect failed: Permission denied"),
+            DeviceErrorKind::PermissionDenied
+        );
+        assert_eq!(kind("No input device"), DeviceErrorKind::NotFound);
+        assert_eq!(
+            kind(
+                "The requested device is no longer available. For example, it has been unplugged."
+            ),
+            DeviceErrorKind::Disconnected
+        );
+        assert_eq!(
+            kind("Only F32 supported for now"),
+            DeviceErrorKind::UnsupportedFormat
+        );
+        assert_eq!(kind("failed to create mainloop"), DeviceErrorKind::Backend);
+
+        let error = anyhow::anyhow!("EACCES").context("starting capture");
+        let denied = DeviceError::classify(DeviceDirection::Input, &error);
+        assert_eq!(denied.kind, DeviceErrorKind::PermissionDenied);
+        assert_eq!(
+            denied.to_string(),
+            "No permission to use the microphone: starting capture: EACCES"
+        );
+        assert!(denied.advice().contains("microphone access"));
+    }
+}
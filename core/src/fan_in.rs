@@ -0,0 +1,146 @@
+//! Fan-in: several patches into one input port
+//!
+//! A port that [`MergePolicy::Sum`]s its inputs gets one mixed buffer per
+//! round instead of every source's buffers interleaved. A round ends once
+//! each patch feeding the port has sent a buffer, or early when one sends a
+//! second before the others caught up, so a stalled source holds the mix
+//! back by one buffer at most. Compiled audio graphs sum their inputs
+//! themselves (see [`RtAudioNode`](crate::RtAudioNode)).
+//!
+//! [`MergePolicy::Sum`]: crate::MergePolicy::Sum
+
+use crate::{AudioView, Signal};
+use std::collections::HashMap;
+
+/// The buffers heard so far in one port's round
+#[derive(Debug)]
+struct Round {
+    sample_rate: u32,
+    channels: u16,
+    timestamp_us: u64,
+    data: Vec<f32>,
+    /// Patches that sent a buffer this round
+    heard: Vec<String>,
+}
+
+impl Round {
+    fn start(patch_id: &str, audio: &AudioView<'_>) -> Self {
+        Self {
+            sample_rate: audio.sample_rate,
+            channels: audio.channels,
+            timestamp_us: audio.timestamp_us,
+            data: audio.data.to_vec(),
+            heard: vec![patch_id.to_string()],
+        }
+    }
+
+    fn add(&mut self, patch_id: &str, audio: &AudioView<'_>) {
+        if self.data.len() < audio.data.len() {
+            self.data.resize(audio.data.len(), 0.0);
+        }
+        for (mixed, sample) in self.data.iter_mut().zip(audio.data) {
+            *mixed += sample;
+        }
+        self.timestamp_us = self.timestamp_us.min(audio.timestamp_us);
+        self.heard.push(patch_id.to_string());
+    }
+
+    fn into_signal(self) -> Signal {
+        Signal::Audio {
+            sample_rate: self.sample_rate,
+            channels: self.channels,
+            timestamp_us: self.timestamp_us,
+            data: self.data,
+        }
+    }
+}
+
+/// Mixes audio arriving at summing ports, one round per port
+#[derive(Debug, Default)]
+pub struct AudioMixer {
+    /// Rounds in progress by sink module and port
+    rounds: HashMap<(String, String), Round>,
+}
+
+impl AudioMixer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `audio` sent down `patch_id` to the round for `port` of `module`,
+    /// which `feeders` patches feed. Returns the mix when the round is over.
+    pub fn mix(
+        &mut self,
+        module: &str,
+        port: &str,
+        patch_id: &str,
+        feeders: usize,
+        audio: AudioView<'_>,
+    ) -> Option<Signal> {
+        let key = (module.to_string(), port.to_string());
+        let mut finished = None;
+        match self.rounds.remove(&key) {
+            // A source that got ahead, or audio in another format, starts
+            // the next round
+            Some(round)
+                if round.heard.iter().any(|heard| heard == patch_id)
+                    || round.sample_rate != audio.sample_rate
+                    || round.channels != audio.channels =>
+            {
+                finished = Some(round.into_signal());
+                self.rounds.insert(key, Round::start(patch_id, &audio));
+            }
+            Some(mut round) => {
+                round.add(patch_id, &audio);
+                if round.heard.len() >= feeders {
+                    return Some(round.into_signal());
+                }
+                self.rounds.insert(key, round);
+            }
+            None => {
+                self.rounds.insert(key, Round::start(patch_id, &audio));
+            }
+        }
+        finished
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn audio(timestamp_us: u64, data: &[f32]) -> AudioView<'_> {
+        AudioView {
+            sample_rate: 48_000,
+            channels: 1,
+            timestamp_us,
+            data,
+        }
+    }
+
+    #[test]
+    fn rounds_sum_every_feeder_or_end_when_one_gets_ahead() {
+        let mut mixer = AudioMixer::new();
+        assert!(mixer
+            .mix("out", "audio_in", "a", 2, audio(10, &[0.25, 0.5]))
+            .is_none());
+        let mixed = mixer
+            .mix("out", "audio_in", "b", 2, audio(5, &[0.25, 0.25, 1.0]))
+            .unwrap();
+        let view = mixed.audio().unwrap();
+        assert_eq!(view.data, [0.5, 0.75, 1.0]);
+        assert_eq!(view.timestamp_us, 5);
+
+        assert!(mixer
+            .mix("out", "audio_in", "a", 2, audio(20, &[1.0]))
+            .is_none());
+        let early = mixer
+            .mix("out", "audio_in", "a", 2, audio(30, &[2.0]))
+            .unwrap();
+        assert_eq!(early.audio().unwrap().data, [1.0]);
+        let mixed = mixer
+            .mix("out", "audio_in", "b", 2, audio(30, &[0.5]))
+            .unwrap();
+        assert_eq!(mixed.audio().unwrap().data, [2.5]);
+    }
+}
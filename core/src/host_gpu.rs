@@ -0,0 +1,589 @@
+//! Host side of the plugin GPU API.
+//!
+//! Plugins never touch `wgpu` objects. Each plugin adapter gets a
+//! [`GpuSession`] that hands out opaque texture handles through the C
+//! [`HostGpuApi`] table; draw commands are rasterised into a host-side RGBA
+//! canvas and uploaded by a [`GpuBackend`]. Everything a plugin created is
+//! released with its session, so a hot-reloaded library cannot leave stale
+//! device pointers or textures behind.
+
+use magnolia_plugin_abi::{GpuDrawCommand, GpuDrawKind, HostGpuApi};
+use std::collections::HashMap;
+use std::os::raw::c_void;
+use std::sync::{Arc, Mutex};
+
+/// Largest texture edge a plugin may request, in pixels.
+pub const MAX_TEXTURE_DIM: u32 = 4096;
+/// Most textures a single plugin may hold at once.
+pub const MAX_TEXTURES_PER_PLUGIN: usize = 16;
+/// Most draw commands accepted in one `submit_draw` call.
+pub const MAX_DRAW_COMMANDS: usize = 65_536;
+
+/// Pack a host texture slot into the opaque handle given to plugins (never 0).
+pub fn pack_texture_handle(id: u64, generation: u32) -> u64 {
+    ((generation as u64) << 32) | ((id & 0xFFFF_FFFF) + 1)
+}
+
+/// Inverse of [`pack_texture_handle`]; `None` for the reserved 0 handle.
+pub fn unpack_texture_handle(handle: u64) -> Option<(u64, u32)> {
+    let slot = handle & 0xFFFF_FFFF;
+    if slot == 0 {
+        return None;
+    }
+    Some((slot - 1, (handle >> 32) as u32))
+}
+
+/// Storage for plugin textures (a GPU device in the app, memory in tests).
+pub trait GpuBackend: Send {
+    /// Allocate an RGBA8 texture and return its slot id and generation.
+    fn create_texture(&mut self, width: u32, height: u32) -> Option<(u64, u32)>;
+
+    /// Replace the contents of a texture with tightly packed RGBA8 pixels.
+    fn upload_rgba(&mut self, id: u64, generation: u32, pixels: &[u8]) -> bool;
+
+    fn destroy_texture(&mut self, id: u64, generation: u32);
+}
+
+/// Shared entry point for plugin GPU access; one per host device.
+pub struct HostGpu {
+    backend: Mutex<Box<dyn GpuBackend>>,
+}
+
+impl HostGpu {
+    pub fn new(backend: Box<dyn GpuBackend>) -> Arc<Self> {
+        Arc::new(Self {
+            backend: Mutex::new(backend),
+        })
+    }
+
+    /// Open a session for one plugin instance.
+    pub fn session(self: &Arc<Self>, owner: &str) -> Arc<GpuSession> {
+        Arc::new(GpuSession {
+            gpu: self.clone(),
+            owner: owner.to_string(),
+            textures: Mutex::new(HashMap::new()),
+        })
+    }
+}
+
+/// Textures owned by a single plugin instance.
+pub struct GpuSession {
+    gpu: Arc<HostGpu>,
+    owner: String,
+    textures: Mutex<HashMap<u64, Canvas>>,
+}
+
+impl GpuSession {
+    /// C table for `attach_gpu`. The session must outlive the plugin's use of it.
+    pub fn api(self: &Arc<Self>) -> HostGpuApi {
+        HostGpuApi {
+            host: Arc::as_ptr(self) as *mut c_void,
+            create_texture: host_create_texture,
+            upload_texture: host_upload_texture,
+            submit_draw: host_submit_draw,
+            destroy_texture: host_destroy_texture,
+        }
+    }
+
+    /// Dimensions of a texture this session owns.
+    pub fn texture_size(&self, handle: u64) -> Option<(u32, u32)> {
+        let textures = self.textures.lock().ok()?;
+        textures.get(&handle).map(|c| (c.width, c.height))
+    }
+
+    pub fn texture_count(&self) -> usize {
+        self.textures.lock().map(|t| t.len()).unwrap_or(0)
+    }
+
+    pub fn create_texture(&self, width: u32, height: u32) -> Option<u64> {
+        if width == 0 || height == 0 || width > MAX_TEXTURE_DIM || height > MAX_TEXTURE_DIM {
+            log::warn!(
+                "Plugin {} requested invalid texture size {}x{}",
+                self.owner,
+                width,
+                height
+            );
+            return None;
+        }
+        let mut textures = self.textures.lock().ok()?;
+        if textures.len() >= MAX_TEXTURES_PER_PLUGIN {
+            log::warn!(
+                "Plugin {} exceeded {} textures",
+                self.owner,
+                MAX_TEXTURES_PER_PLUGIN
+            );
+            return None;
+        }
+        let (id, generation) = self
+            .gpu
+            .backend
+            .lock()
+            .ok()?
+            .create_texture(width, height)?;
+        let handle = pack_texture_handle(id, generation);
+        textures.insert(handle, Canvas::new(width, height));
+        Some(handle)
+    }
+
+    pub fn upload(&self, handle: u64, pixels: &[u8]) -> bool {
+        let Ok(mut textures) = self.textures.lock() else {
+            return false;
+        };
+        let Some(canvas) = textures.get_mut(&handle) else {
+            return false;
+        };
+        if pixels.len() != canvas.pixels.len() {
+            log::warn!(
+                "Plugin {} uploaded {} bytes to a {}x{} texture",
+                self.owner,
+                pixels.len(),
+                canvas.width,
+                canvas.height
+            );
+            return false;
+        }
+        canvas.pixels.copy_from_slice(pixels);
+        self.present(handle, canvas)
+    }
+
+    pub fn draw(&self, handle: u64, commands: &[GpuDrawCommand]) -> bool {
+        let Ok(mut textures) = self.textures.lock() else {
+            return false;
+        };
+        let Some(canvas) = textures.get_mut(&handle) else {
+            return false;
+        };
+        for command in commands {
+            canvas.apply(command);
+        }
+        self.present(handle, canvas)
+    }
+
+    pub fn destroy(&self, handle: u64) {
+        let removed = self
+            .textures
+            .lock()
+            .ok()
+            .and_then(|mut t| t.remove(&handle));
+        if removed.is_some() {
+            self.release(handle);
+        }
+    }
+
+    fn present(&self, handle: u64, canvas: &Canvas) -> bool {
+        let Some((id, generation)) = unpack_texture_handle(handle) else {
+            return false;
+        };
+        match self.gpu.backend.lock() {
+            Ok(mut backend) => backend.upload_rgba(id, generation, &canvas.pixels),
+            Err(_) => false,
+        }
+    }
+
+    fn release(&self, handle: u64) {
+        if let (Some((id, generation)), Ok(mut backend)) =
+            (unpack_texture_handle(handle), self.gpu.backend.lock())
+        {
+            backend.destroy_texture(id, generation);
+        }
+    }
+}
+
+impl Drop for GpuSession {
+    fn drop(&mut self) {
+        let handles: Vec<u64> = match self.textures.get_mut() {
+            Ok(textures) => textures.drain().map(|(h, _)| h).collect(),
+            Err(_) => return,
+        };
+        for handle in handles {
+            self.release(handle);
+        }
+    }
+}
+
+// --- C trampolines handed to plugins ---
+
+unsafe extern "C" fn host_create_texture(host: *mut c_void, width: u32, height: u32) -> u64 {
+    let session = &*(host as *const GpuSession);
+    session.create_texture(width, height).unwrap_or(0)
+}
+
+unsafe extern "C" fn host_upload_texture(
+    host: *mut c_void,
+    handle: u64,
+    data: *const u8,
+    len: usize,
+) -> bool {
+    let session = &*(host as *const GpuSession);
+    if data.is_null() || len > (MAX_TEXTURE_DIM as usize).pow(2) * 4 {
+        return false;
+    }
+    session.upload(handle, std::slice::from_raw_parts(data, len))
+}
+
+unsafe extern "C" fn host_submit_draw(
+    host: *mut c_void,
+    handle: u64,
+    commands: *const GpuDrawCommand,
+    len: usize,
+) -> bool {
+    let session = &*(host as *const GpuSession);
+    if len == 0 {
+        return session.draw(handle, &[]);
+    }
+    if commands.is_null() || !commands.is_aligned() || len > MAX_DRAW_COMMANDS {
+        log::warn!("Rejecting malformed draw list ({} commands)", len);
+        return false;
+    }
+    session.draw(handle, std::slice::from_raw_parts(commands, len))
+}
+
+unsafe extern "C" fn host_destroy_texture(host: *mut c_void, handle: u64) {
+    let session = &*(host as *const GpuSession);
+    session.destroy(handle);
+}
+
+/// CPU-side RGBA8 image that draw commands are rasterised into.
+struct Canvas {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![0; width as usize * height as usize * 4],
+        }
+    }
+
+    fn apply(&mut self, command: &GpuDrawCommand) {
+        let color = command.color.map(|c| {
+            if c.is_finite() {
+                c.clamp(0.0, 1.0)
+            } else {
+                0.0
+            }
+        });
+        let finite = [command.x, command.y, command.w, command.h, command.stroke]
+            .iter()
+            .all(|v| v.is_finite());
+        if !finite {
+            return;
+        }
+        match command.kind {
+            k if k == GpuDrawKind::Clear as u32 => {
+                let rgba = color.map(|c| (c * 255.0).round() as u8);
+                for px in self.pixels.chunks_exact_mut(4) {
+                    px.copy_from_slice(&rgba);
+                }
+            }
+            k if k == GpuDrawKind::Rect as u32 => {
+                let (x0, y0) = (command.x, command.y);
+                let (x1, y1) = (x0 + command.w, y0 + command.h);
+                self.fill(x0, y0, x1, y1, color, |px, py| {
+                    px >= x0 && px < x1 && py >= y0 && py < y1
+                });
+            }
+            k if k == GpuDrawKind::Line as u32 => {
+                let (ax, ay, bx, by) = (command.x, command.y, command.w, command.h);
+                let half = command.stroke.max(1.0) * 0.5;
+                self.fill(
+                    ax.min(bx) - half,
+                    ay.min(by) - half,
+                    ax.max(bx) + half,
+                    ay.max(by) + half,
+                    color,
+                    |px, py| segment_distance(px, py, ax, ay, bx, by) <= half,
+                );
+            }
+            k if k == GpuDrawKind::Ellipse as u32 => {
+                let (cx, cy) = (command.x, command.y);
+                let (rx, ry) = (command.w.abs().max(0.5), command.h.abs().max(0.5));
+                let stroke = command.stroke.max(0.0);
+                self.fill(cx - rx, cy - ry, cx + rx, cy + ry, color, |px, py| {
+                    let d = ((px - cx) / rx).powi(2) + ((py - cy) / ry).powi(2);
+                    if stroke == 0.0 {
+                        d <= 1.0
+                    } else {
+                        let inner_x = (rx - stroke).max(0.0);
+                        let inner_y = (ry - stroke).max(0.0);
+                        let inside_inner = inner_x > 0.0
+                            && inner_y > 0.0
+                            && ((px - cx) / inner_x).powi(2) + ((py - cy) / inner_y).powi(2) < 1.0;
+                        d <= 1.0 && !inside_inner
+                    }
+                });
+            }
+            other => log::debug!("Ignoring unknown draw command kind {}", other),
+        }
+    }
+
+    /// Blend `color` over every pixel in the bounding box whose centre passes `inside`.
+    fn fill(
+        &mut self,
+        x0: f32,
+        y0: f32,
+        x1: f32,
+        y1: f32,
+        color: [f32; 4],
+        inside: impl Fn(f32, f32) -> bool,
+    ) {
+        let clamp_x = |v: f32| v.floor().clamp(0.0, self.width as f32) as usize;
+        let clamp_y = |v: f32| v.floor().clamp(0.0, self.height as f32) as usize;
+        let (min_x, max_x) = (clamp_x(x0), clamp_x(x1 + 1.0));
+        let (min_y, max_y) = (clamp_y(y0), clamp_y(y1 + 1.0));
+        let alpha = color[3];
+        for y in min_y..max_y {
+            for x in min_x..max_x {
+                if !inside(x as f32 + 0.5, y as f32 + 0.5) {
+                    continue;
+                }
+                let i = (y * self.width as usize + x) * 4;
+                let px = &mut self.pixels[i..i + 4];
+                for c in 0..3 {
+                    let dst = px[c] as f32 / 255.0;
+                    px[c] = ((color[c] * alpha + dst * (1.0 - alpha)) * 255.0).round() as u8;
+                }
+                let dst_a = px[3] as f32 / 255.0;
+                px[3] = ((alpha + dst_a * (1.0 - alpha)) * 255.0).round() as u8;
+            }
+        }
+    }
+}
+
+fn segment_distance(px: f32, py: f32, ax: f32, ay: f32, bx: f32, by: f32) -> f32 {
+    let (dx, dy) = (bx - ax, by - ay);
+    let len_sq = dx * dx + dy * dy;
+    let t = if len_sq > 0.0 {
+        (((px - ax) * dx + (py - ay) * dy) / len_sq).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let (cx, cy) = (ax + t * dx, ay + t * dy);
+    ((px - cx).powi(2) + (py - cy).powi(2)).sqrt()
+}
+
+/// `wgpu` device access for [`WgpuBackend`].
+#[cfg(feature = "gpu-resources")]
+pub trait GpuDeviceSource: Send + Sync {
+    fn device(&self) -> &wgpu::Device;
+    fn queue(&self) -> &wgpu::Queue;
+}
+
+#[cfg(feature = "tile-rendering")]
+impl GpuDeviceSource for nannou::wgpu::DeviceQueuePair {
+    fn device(&self) -> &wgpu::Device {
+        nannou::wgpu::DeviceQueuePair::device(self)
+    }
+
+    fn queue(&self) -> &wgpu::Queue {
+        nannou::wgpu::DeviceQueuePair::queue(self)
+    }
+}
+
+/// Backend that keeps plugin textures in the host's GPU resource maps, where
+/// the compositor can look their views up by slot id.
+#[cfg(feature = "gpu-resources")]
+pub struct WgpuBackend {
+    device: Arc<dyn GpuDeviceSource>,
+    textures: Arc<crate::GpuTextureMap>,
+    views: Arc<crate::GpuTextureViewMap>,
+    sizes: HashMap<u64, (u32, u32)>,
+}
+
+#[cfg(feature = "gpu-resources")]
+impl WgpuBackend {
+    pub fn new(
+        device: Arc<dyn GpuDeviceSource>,
+        textures: Arc<crate::GpuTextureMap>,
+        views: Arc<crate::GpuTextureViewMap>,
+    ) -> Self {
+        Self {
+            device,
+            textures,
+            views,
+            sizes: HashMap::new(),
+        }
+    }
+}
+
+#[cfg(feature = "gpu-resources")]
+impl GpuBackend for WgpuBackend {
+    fn create_texture(&mut self, width: u32, height: u32) -> Option<(u64, u32)> {
+        let texture = self
+            .device
+            .device()
+            .create_texture(&wgpu::TextureDescriptor {
+                label: Some("plugin_texture"),
+                size: wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Rgba8UnormSrgb,
+                usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+                view_formats: &[],
+            });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let (id, generation) = self.textures.insert(texture);
+        // Keep view slots aligned with texture slots so one id addresses both.
+        let (view_id, _) = self.views.insert(view);
+        debug_assert_eq!(id, view_id);
+        self.sizes.insert(id, (width, height));
+        Some((id, generation))
+    }
+
+    fn upload_rgba(&mut self, id: u64, generation: u32, pixels: &[u8]) -> bool {
+        let Some(&(width, height)) = self.sizes.get(&id) else {
+            return false;
+        };
+        let queue = self.device.queue();
+        self.textures
+            .get_with(id, generation, |texture| {
+                queue.write_texture(
+                    wgpu::ImageCopyTexture {
+                        texture,
+                        mip_level: 0,
+                        origin: wgpu::Origin3d::ZERO,
+                        aspect: wgpu::TextureAspect::All,
+                    },
+                    pixels,
+                    wgpu::ImageDataLayout {
+                        offset: 0,
+                        bytes_per_row: Some(width * 4),
+                        rows_per_image: Some(height),
+                    },
+                    wgpu::Extent3d {
+                        width,
+                        height,
+                        depth_or_array_layers: 1,
+                    },
+                );
+            })
+            .is_some()
+    }
+
+    fn destroy_texture(&mut self, id: u64, _generation: u32) {
+        self.sizes.remove(&id);
+        self.views.remove(id);
+        if let Some(texture) = self.textures.remove(id) {
+            texture.destroy();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct MemoryBackend {
+        next: u64,
+        live: Arc<Mutex<HashMap<u64, Vec<u8>>>>,
+    }
+
+    impl GpuBackend for MemoryBackend {
+        fn create_texture(&mut self, _width: u32, _height: u32) -> Option<(u64, u32)> {
+            let id = self.next;
+            self.next += 1;
+            self.live.lock().unwrap().insert(id, Vec::new());
+            Some((id, 0))
+        }
+
+        fn upload_rgba(&mut self, id: u64, _generation: u32, pixels: &[u8]) -> bool {
+            match self.live.lock().unwrap().get_mut(&id) {
+                Some(slot) => {
+                    *slot = pixels.to_vec();
+                    true
+                }
+                None => false,
+            }
+        }
+
+        fn destroy_texture(&mut self, id: u64, _generation: u32) {
+            self.live.lock().unwrap().remove(&id);
+        }
+    }
+
+    fn command(
+        kind: GpuDrawKind,
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        color: [f32; 4],
+    ) -> GpuDrawCommand {
+        GpuDrawCommand {
+            kind: kind as u32,
+            x,
+            y,
+            w,
+            h,
+            stroke: 0.0,
+            color,
+        }
+    }
+
+    #[test]
+    fn handles_round_trip_and_zero_is_reserved() {
+        let handle = pack_texture_handle(0, 0);
+        assert_ne!(handle, 0);
+        assert_eq!(unpack_texture_handle(handle), Some((0, 0)));
+        assert_eq!(
+            unpack_texture_handle(pack_texture_handle(41, 7)),
+            Some((41, 7))
+        );
+        assert_eq!(unpack_texture_handle(0), None);
+    }
+
+    #[test]
+    fn api_draws_through_trampolines_and_session_drop_releases_textures() {
+        let backend = MemoryBackend::default();
+        let live = backend.live.clone();
+        let gpu = HostGpu::new(Box::new(backend));
+        let session = gpu.session("test_plugin");
+        let api = session.api();
+
+        let handle = unsafe { (api.create_texture)(api.host, 4, 4) };
+        assert_ne!(handle, 0);
+        assert_eq!(session.texture_size(handle), Some((4, 4)));
+
+        let commands = [
+            command(GpuDrawKind::Clear, 0.0, 0.0, 0.0, 0.0, [0.0, 0.0, 0.0, 1.0]),
+            command(GpuDrawKind::Rect, 0.0, 0.0, 2.0, 2.0, [1.0, 0.0, 0.0, 1.0]),
+        ];
+        assert!(unsafe { (api.submit_draw)(api.host, handle, commands.as_ptr(), commands.len()) });
+
+        let pixels = live.lock().unwrap().get(&0).cloned().unwrap();
+        assert_eq!(&pixels[0..4], &[255, 0, 0, 255]);
+        // (3, 3) is outside the rect and keeps the clear colour
+        assert_eq!(&pixels[60..64], &[0, 0, 0, 255]);
+
+        drop(session);
+        assert!(live.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_foreign_handles_bad_sizes_and_short_uploads() {
+        let gpu = HostGpu::new(Box::new(MemoryBackend::default()));
+        let owner = gpu.session("owner");
+        let other = gpu.session("other");
+
+        assert_eq!(owner.create_texture(0, 16), None);
+        assert_eq!(owner.create_texture(MAX_TEXTURE_DIM + 1, 16), None);
+
+        let handle = owner.create_texture(2, 2).unwrap();
+        assert!(!other.upload(handle, &[0; 16]));
+        assert!(!owner.upload(handle, &[0; 3]));
+        assert!(owner.upload(handle, &[0; 16]));
+
+        other.destroy(handle);
+        assert_eq!(owner.texture_count(), 1);
+    }
+}
@@ -0,0 +1,222 @@
+//! layout.toml schema versions
+//!
+//! Files carry a `version`; older ones are brought up to [`LAYOUT_VERSION`]
+//! one step at a time before they're parsed, and [`load_layout`] keeps the
+//! original next to the upgraded file. Files from a newer build are refused
+//! rather than half-read.
+
+use crate::{KameaGrid, LayoutConfig};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+/// The version this build writes
+pub const LAYOUT_VERSION: u32 = 1;
+
+#[derive(thiserror::Error, Debug)]
+pub enum LayoutLoadError {
+    #[error("{path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("invalid layout: {0}")]
+    Parse(String),
+
+    #[error("invalid track_overrides: {0}")]
+    InvalidTracks(String),
+
+    #[error("layout `version` must be a whole number, found {0}")]
+    InvalidVersion(String),
+
+    #[error("layout is version {found} but this build only reads up to {supported}; update Magnolia or restore an older layout")]
+    TooNew { found: u32, supported: u32 },
+}
+
+/// Step `i` takes a file from version `i` to `i + 1`
+const MIGRATIONS: [fn(&mut Table); LAYOUT_VERSION as usize] = [nest_tile_settings];
+
+/// 0 → 1: tiles kept `config` and `keybinds` at their top level before
+/// `settings` existed, and grids could be given as track counts
+/// (`columns = 4`) or by kamea name alone
+fn nest_tile_settings(layout: &mut Table) {
+    if let Some(Value::Array(tiles)) = layout.get_mut("tiles") {
+        for tile in tiles.iter_mut().filter_map(Value::as_table_mut) {
+            for key in ["config", "keybinds"] {
+                let Some(value) = tile.remove(key) else {
+                    continue;
+                };
+                let settings = tile
+                    .entry("settings")
+                    .or_insert_with(|| Value::Table(Table::new()));
+                if let Value::Table(settings) = settings {
+                    settings.entry(key).or_insert(value);
+                }
+            }
+        }
+    }
+
+    let kamea = layout
+        .get("grid")
+        .and_then(Value::as_str)
+        .and_then(KameaGrid::from_str)
+        .map(|kamea| kamea.dimensions());
+    for (key, kamea_count) in [
+        ("columns", kamea.map(|d| d.0)),
+        ("rows", kamea.map(|d| d.1)),
+    ] {
+        let count = match layout.get(key) {
+            Some(Value::Integer(count)) => Some((*count).max(1) as usize),
+            None => kamea_count.or(Some(1)),
+            Some(_) => None,
+        };
+        if let Some(count) = count {
+            let tracks = vec![Value::String("1fr".to_string()); count];
+            layout.insert(key.to_string(), Value::Array(tracks));
+        }
+    }
+}
+
+/// Upgrade `layout` in place; the version it was at before
+pub fn migrate(layout: &mut Table) -> Result<u32, LayoutLoadError> {
+    let found = match layout.get("version") {
+        None => 0,
+        Some(Value::Integer(v)) => {
+            u32::try_from(*v).map_err(|_| LayoutLoadError::InvalidVersion(v.to_string()))?
+        }
+        Some(other) => return Err(LayoutLoadError::InvalidVersion(other.to_string())),
+    };
+    if found > LAYOUT_VERSION {
+        return Err(LayoutLoadError::TooNew {
+            found,
+            supported: LAYOUT_VERSION,
+        });
+    }
+    for step in &MIGRATIONS[found as usize..] {
+        step(layout);
+    }
+    layout.insert("version".to_string(), Value::Integer(LAYOUT_VERSION.into()));
+    Ok(found)
+}
+
+/// Parse layout.toml contents of any supported version, with the version
+/// they were at
+pub fn parse_layout(content: &str) -> Result<(LayoutConfig, u32), LayoutLoadError> {
+    let mut layout: Table = content
+        .parse()
+        .map_err(|e: toml::de::Error| LayoutLoadError::Parse(e.to_string()))?;
+    let found = migrate(&mut layout)?;
+    let config =
+        LayoutConfig::deserialize(layout).map_err(|e| LayoutLoadError::Parse(e.to_string()))?;
+    config
+        .validate_tracks()
+        .map_err(LayoutLoadError::InvalidTracks)?;
+    Ok((config, found))
+}
+
+/// `layout.toml` → `layout.toml.v0.bak`
+pub fn backup_path(path: &Path, version: u32) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".v{}.bak", version));
+    path.with_file_name(name)
+}
+
+/// Read `path`, upgrading it on disk if it's from an older version: the
+/// original is kept at [`backup_path`] and the upgraded layout written over it
+pub fn load_layout(path: &Path) -> Result<LayoutConfig, LayoutLoadError> {
+    let io = |source| LayoutLoadError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    let content = std::fs::read_to_string(path).map_err(io)?;
+    let (config, found) = parse_layout(&content)?;
+    if found < LAYOUT_VERSION {
+        let backup = backup_path(path, found);
+        std::fs::copy(path, &backup).map_err(io)?;
+        let upgraded =
+            toml::to_string_pretty(&config).map_err(|e| LayoutLoadError::Parse(e.to_string()))?;
+        std::fs::write(path, upgraded).map_err(io)?;
+        log::info!(
+            "Upgraded {} from layout version {} to {}; the original is at {}",
+            path.display(),
+            found,
+            LAYOUT_VERSION,
+            backup.display()
+        );
+    }
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UNVERSIONED: &str = r#"
+grid = "saturn"
+
+[[tiles]]
+id = "viz"
+col = 0
+row = 0
+module = "audio_viz"
+config = { vis_type = "Oscilloscope" }
+keybinds = { mute = "m" }
+"#;
+
+    #[test]
+    fn unversioned_layouts_are_upgraded() {
+        let (config, found) = parse_layout(UNVERSIONED).unwrap();
+        assert_eq!(found, 0);
+        assert_eq!(config.version, LAYOUT_VERSION);
+        assert_eq!((config.columns.len(), config.rows.len()), (3, 3));
+        let tile = &config.tiles[0];
+        assert_eq!(tile.settings.config["vis_type"], "Oscilloscope");
+        assert_eq!(tile.settings.keybinds["mute"], "m");
+
+        let (config, _) =
+            parse_layout("columns = 2\nrows = [\"30%\", \"1fr\"]\ntiles = []").unwrap();
+        assert_eq!(config.columns, ["1fr", "1fr"]);
+        assert_eq!(config.rows, ["30%", "1fr"]);
+    }
+
+    #[test]
+    fn newer_layouts_are_refused() {
+        let newer = format!(
+            "version = {}\ncolumns = []\nrows = []\ntiles = []",
+            LAYOUT_VERSION + 1
+        );
+        assert!(matches!(
+            parse_layout(&newer),
+            Err(LayoutLoadError::TooNew { found, supported: LAYOUT_VERSION })
+                if found == LAYOUT_VERSION + 1
+        ));
+        assert!(matches!(
+            parse_layout("version = \"one\""),
+            Err(LayoutLoadError::InvalidVersion(_))
+        ));
+    }
+
+    #[test]
+    fn loading_keeps_a_backup_of_the_original() {
+        let dir = std::env::temp_dir().join(format!("magnolia_layout_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("layout.toml");
+        std::fs::write(&path, UNVERSIONED).unwrap();
+
+        load_layout(&path).unwrap();
+        let backup = backup_path(&path, 0);
+        assert_eq!(backup, dir.join("layout.toml.v0.bak"));
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), UNVERSIONED);
+        let (config, found) = parse_layout(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(found, LAYOUT_VERSION);
+        assert_eq!(config.tiles[0].settings.keybinds["mute"], "m");
+
+        // Already current: left alone
+        std::fs::remove_file(&backup).unwrap();
+        load_layout(&path).unwrap();
+        assert!(!backup.exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
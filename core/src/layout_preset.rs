@@ -0,0 +1,198 @@
+//! Ritual layouts: a preset for each planetary square
+//!
+//! Every preset lays its grid out the same way around the square's centre:
+//! the kamea in the middle, a clock in each corner, and the chart, the
+//! canvas, the captions and the scope on the four sides between them. The
+//! corners grow with the square, so the larger squares keep a wide border.
+
+use crate::{KameaGrid, LayoutConfig, TileConfig, TileSettings, TrackOverrides};
+
+/// Module of the centre, the corners and the top, right, bottom and left
+/// sides
+const CENTRE: &str = "kamea";
+const CORNER: &str = "clock";
+const SIDES: [&str; 4] = ["astro", "shader_canvas", "captions", "audio_viz"];
+
+/// A kamea grid with tiles in their traditional places
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LayoutPreset {
+    pub grid: KameaGrid,
+    /// Cells along a corner's side
+    corner: usize,
+    pub description: &'static str,
+}
+
+pub const LAYOUT_PRESETS: [LayoutPreset; 7] = [
+    LayoutPreset {
+        grid: KameaGrid::Saturn,
+        corner: 1,
+        description: "Kamea at the centre cell, the hours at the corners",
+    },
+    LayoutPreset {
+        grid: KameaGrid::Jupiter,
+        corner: 1,
+        description: "Kamea across the four centre cells",
+    },
+    LayoutPreset {
+        grid: KameaGrid::Mars,
+        corner: 1,
+        description: "Kamea over the inner nine, a one-cell border",
+    },
+    LayoutPreset {
+        grid: KameaGrid::Sun,
+        corner: 1,
+        description: "Kamea over the inner sixteen, a one-cell border",
+    },
+    LayoutPreset {
+        grid: KameaGrid::Venus,
+        corner: 2,
+        description: "Kamea over the inner nine, a two-cell border",
+    },
+    LayoutPreset {
+        grid: KameaGrid::Mercury,
+        corner: 2,
+        description: "Kamea over the inner sixteen, a two-cell border",
+    },
+    LayoutPreset {
+        grid: KameaGrid::Moon,
+        corner: 3,
+        description: "Nine equal squares, the kamea in the middle one",
+    },
+];
+
+impl LayoutPreset {
+    /// The preset for a grid name such as `"saturn"` or `"3x3"`
+    pub fn find(name: &str) -> Option<&'static LayoutPreset> {
+        let grid = KameaGrid::from_str(name)?;
+        LAYOUT_PRESETS.iter().find(|preset| preset.grid == grid)
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.grid.name()
+    }
+
+    /// The preset's tiles, named after their module and numbered from the
+    /// second of a kind
+    pub fn tiles(&self) -> Vec<TileConfig> {
+        let (n, _) = self.grid.dimensions();
+        let c = self.corner;
+        let inner = n - 2 * c;
+        let far = n - c;
+        let mut tiles = vec![(CENTRE, [c, c, inner, inner])];
+        for (col, row) in [(0, 0), (far, 0), (0, far), (far, far)] {
+            tiles.push((CORNER, [col, row, c, c]));
+        }
+        let [top, right, bottom, left] = SIDES;
+        tiles.push((top, [c, 0, inner, c]));
+        tiles.push((right, [far, c, c, inner]));
+        tiles.push((bottom, [c, far, inner, c]));
+        tiles.push((left, [0, c, c, inner]));
+
+        let mut ids = std::collections::HashMap::<&str, usize>::new();
+        tiles
+            .into_iter()
+            .map(|(module, [col, row, colspan, rowspan])| {
+                let count = ids.entry(module).or_default();
+                *count += 1;
+                TileConfig {
+                    id: if *count == 1 {
+                        module.to_string()
+                    } else {
+                        format!("{}_{}", module, count)
+                    },
+                    col,
+                    row,
+                    colspan: Some(colspan),
+                    rowspan: Some(rowspan),
+                    module: module.to_string(),
+                    enabled: true,
+                    settings: TileSettings::default(),
+                    window: None,
+                    z: 0,
+                    overlay: None,
+                }
+            })
+            .collect()
+    }
+
+    /// Switch `layout` to the preset's grid and replace the main window's
+    /// tiles with its own, leaving out modules `is_available` doesn't know.
+    /// Patches, other windows and the rest of the config stay; track
+    /// overrides go, since the preset's tracks are all equal.
+    pub fn apply(&self, layout: &mut LayoutConfig, is_available: impl Fn(&str) -> bool) {
+        layout.grid = Some(self.name().to_string());
+        layout.track_overrides = TrackOverrides::default();
+        let (columns, rows) = layout.generate_tracks();
+        layout.columns = columns;
+        layout.rows = rows;
+        let kept: Vec<TileConfig> = layout
+            .tiles
+            .drain(..)
+            .filter(|tile| tile.window.is_some())
+            .collect();
+        layout.tiles = self
+            .tiles()
+            .into_iter()
+            .filter(|tile| is_available(&tile.module))
+            .chain(kept)
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_fill_their_grid_without_overlaps() {
+        for preset in &LAYOUT_PRESETS {
+            let (n, _) = preset.grid.dimensions();
+            let mut cells = vec![0; n * n];
+            for tile in preset.tiles() {
+                let (colspan, rowspan) = (tile.colspan.unwrap(), tile.rowspan.unwrap());
+                assert!(tile.col + colspan <= n && tile.row + rowspan <= n);
+                for row in tile.row..tile.row + rowspan {
+                    for col in tile.col..tile.col + colspan {
+                        cells[row * n + col] += 1;
+                    }
+                }
+            }
+            assert!(cells.iter().all(|&count| count == 1), "{}", preset.name());
+        }
+
+        let saturn = LayoutPreset::find("3x3").unwrap().tiles();
+        let centre = saturn.iter().find(|tile| tile.module == "kamea").unwrap();
+        assert_eq!((centre.col, centre.row), (1, 1));
+        let corners: Vec<_> = saturn.iter().filter(|t| t.module == "clock").collect();
+        assert_eq!(corners.len(), 4);
+        assert_eq!(corners[3].id, "clock_4");
+    }
+
+    #[test]
+    fn applying_keeps_other_windows_and_skips_missing_modules() {
+        let mut layout: LayoutConfig = toml::from_str(
+            r#"
+            columns = ["1fr", "1fr"]
+            rows = ["1fr"]
+            tiles = [
+                { id = "old", col = 0, row = 0, module = "ticker" },
+                { id = "stage", col = 0, row = 0, module = "clock", window = "stage" },
+            ]
+            "#,
+        )
+        .unwrap();
+        LayoutPreset::find("mars")
+            .unwrap()
+            .apply(&mut layout, |module| module != "kamea");
+
+        assert_eq!(layout.grid.as_deref(), Some("mars"));
+        assert_eq!(layout.resolve_grid(), (5, 5));
+        assert_eq!(layout.columns.len(), 5);
+        assert!(layout
+            .tiles
+            .iter()
+            .all(|t| t.module != "kamea" && t.id != "old"));
+        assert!(layout.tiles.iter().any(|t| t.id == "stage"));
+        assert_eq!(layout.tiles.len(), 9);
+    }
+}
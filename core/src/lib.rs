@@ -1,49 +1,100 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 // Feature-gated tile rendering system
 #[cfg(feature = "tile-rendering")]
 pub mod tile;
 #[cfg(feature = "tile-rendering")]
 pub use tile::{
-    render_error_overlay, BindableAction, ErrorSeverity, RenderContext, TileError, TileRegistry,
-    TileRenderer,
+    render_device_error, render_error_history, render_error_overlay,
+    render_unavailable_placeholder, BindableAction, ErrorHistory, ErrorRecord, ErrorSeverity,
+    RenderContext, TileError, TileRegistry, TileRenderer,
 };
 
+pub mod device_error;
+pub use device_error::{DeviceDirection, DeviceError, DeviceErrorKind};
+pub mod fan_in;
 pub mod patch_bay;
-pub use patch_bay::{PatchBay, PatchBayError};
+pub mod patch_transform;
+pub use patch_bay::{PatchBay, PatchBayError, PatchIssue, PatchSuggestion};
+pub use patch_transform::PatchTransformer;
+pub mod provenance;
+pub use provenance::{Provenance, ProvenanceTracker};
+pub mod request;
+pub use request::{PendingReply, Request, RequestError, Requester};
 
 pub mod host;
 pub use host::{ModuleHandle, ModuleImpl};
 
 pub mod runtime;
 pub use runtime::{
-    default_output_port, RoutedSignal, RoutedSignalError, RoutingMetrics, RoutingMetricsSnapshot,
-    RoutingResult,
+    default_output_port, PatchTraffic, RoutedSignal, RoutedSignalError, RoutingMetrics,
+    RoutingMetricsSnapshot, RoutingResult, StartupReport,
 };
 pub use runtime::{ExecutionModel, ModuleHost, ModuleRuntime, ModuleState, Priority};
 
 pub mod adapters;
 pub use adapters::{SinkAdapter, SourceAdapter};
 
+pub mod rt_graph;
+pub use rt_graph::{AudioGraphInfo, BlockContext, RtAudioNode};
+
+pub mod power;
+pub use power::{Importance, PowerConfig, PowerMonitor, PowerReading};
+
+pub mod layout_migration;
+pub use layout_migration::{load_layout, parse_layout, LayoutLoadError, LAYOUT_VERSION};
+
+pub mod access;
+pub use access::{AccessConfig, AccessError, ApiToken, Scope, TlsConfig};
+
+pub mod clock;
+pub use clock::{system_clock, Clock, SharedClock, SystemClock, VirtualClock};
+
 pub mod ring_buffer;
 pub use ring_buffer::{RingBufferReceiver, RingBufferSender, SPSCRingBuffer};
 
 pub mod audio_frame;
 pub use audio_frame::AudioFrame;
 
+pub mod silence;
+pub use silence::{SilenceConfig, SilenceDetector, SilenceEvent};
+
+pub mod layout_preset;
+pub use layout_preset::{LayoutPreset, LAYOUT_PRESETS};
+pub mod patch_template;
+pub use patch_template::{AutoWireReport, PatchTemplate, PATCH_TEMPLATES};
+
+pub mod seed;
+pub use seed::{ModuleSeed, SeedSource, SharedSeed};
+
+pub mod session;
+pub use session::{SessionConfig, SessionInfo, SessionMarker, SessionState};
+
 pub mod shared_data;
 pub use shared_data::{AudioData, BlobData};
+pub mod worker_settings;
+pub use worker_settings::WorkerSettings;
 
+pub mod native_library;
+pub use native_library::LibraryError;
 pub mod plugin_loader;
 pub use plugin_loader::{PluginLibrary, PluginLoader};
 
+pub mod host_gpu;
+pub use host_gpu::{GpuBackend, GpuSession, HostGpu};
+#[cfg(feature = "gpu-resources")]
+pub use host_gpu::{GpuDeviceSource, WgpuBackend};
+
 pub mod plugin_adapter;
-pub use plugin_adapter::PluginModuleAdapter;
+pub mod plugin_codec;
+pub mod plugin_quota;
+pub use plugin_adapter::{PluginBufferLedger, PluginModuleAdapter};
+pub use plugin_quota::{PluginQuota, QuotaMetrics, QuotaMetricsSnapshot};
 
 pub mod plugin_manager;
-pub use plugin_manager::PluginManager;
+pub use plugin_manager::{PluginEvent, PluginManager};
 
 pub mod sandbox;
 pub use sandbox::{apply_sandbox, create_plugin_sandbox};
@@ -87,6 +138,19 @@ impl KameaGrid {
         }
     }
 
+    /// Name as written in the layout's `grid`
+    pub fn name(&self) -> &'static str {
+        match self {
+            KameaGrid::Saturn => "saturn",
+            KameaGrid::Jupiter => "jupiter",
+            KameaGrid::Mars => "mars",
+            KameaGrid::Sun => "sun",
+            KameaGrid::Venus => "venus",
+            KameaGrid::Mercury => "mercury",
+            KameaGrid::Moon => "moon",
+        }
+    }
+
     /// Parse from string
     pub fn from_str(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
@@ -102,7 +166,10 @@ impl KameaGrid {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
+/// Ordered by thrift: `Normal < LowPower < BatteryBackground`
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema, Default,
+)]
 pub enum PowerProfile {
     #[default]
     Normal,
@@ -110,8 +177,108 @@ pub enum PowerProfile {
     BatteryBackground,
 }
 
+/// When a module's task starts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SpawnMode {
+    /// At launch
+    #[default]
+    Eager,
+    /// On first patch activation or when its tile is maximized
+    Lazy,
+}
+
+/// Which modules start at launch
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct StartupProfile {
+    /// Mode for modules not listed below
+    #[serde(default)]
+    pub default: SpawnMode,
+    #[serde(default)]
+    pub eager: Vec<String>,
+    #[serde(default)]
+    pub lazy: Vec<String>,
+}
+
+impl StartupProfile {
+    pub fn mode(&self, module_id: &str) -> SpawnMode {
+        if self.eager.iter().any(|id| id == module_id) {
+            SpawnMode::Eager
+        } else if self.lazy.iter().any(|id| id == module_id) {
+            SpawnMode::Lazy
+        } else {
+            self.default
+        }
+    }
+}
+
+/// Named startup profiles, e.g. `[startup.profiles.minimal]`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct StartupConfig {
+    /// Profile used when none is asked for; everything is eager without one
+    #[serde(default)]
+    pub profile: Option<String>,
+    #[serde(default)]
+    pub profiles: BTreeMap<String, StartupProfile>,
+}
+
+impl StartupConfig {
+    pub fn is_empty(&self) -> bool {
+        self.profile.is_none() && self.profiles.is_empty()
+    }
+
+    /// `requested` (e.g. from the command line) or the configured profile,
+    /// with its name. An unknown name falls back to all-eager.
+    pub fn resolve(&self, requested: Option<&str>) -> (String, StartupProfile) {
+        let name = requested
+            .or(self.profile.as_deref())
+            .unwrap_or("default")
+            .to_string();
+        match self.profiles.get(&name) {
+            Some(profile) => (name, profile.clone()),
+            None => {
+                if requested.is_some() || self.profile.is_some() {
+                    log::warn!("Unknown startup profile '{}', spawning everything", name);
+                }
+                (name, StartupProfile::default())
+            }
+        }
+    }
+}
+
+/// `[track_overrides]`: a symbolic grid with some tracks sized explicitly,
+/// e.g. `columns = { 3 = "2fr" }` for a wider centre column on venus.
+/// Keys are 0-based track indices.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct TrackOverrides {
+    pub columns: BTreeMap<String, String>,
+    pub rows: BTreeMap<String, String>,
+}
+
+impl TrackOverrides {
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty() && self.rows.is_empty()
+    }
+}
+
+/// Whether `size` is a track size the grid understands: a non-negative
+/// number followed by `px`, `%` or `fr`
+pub fn is_valid_track_size(size: &str) -> bool {
+    let size = size.trim();
+    ["px", "%", "fr"].iter().any(|unit| {
+        size.strip_suffix(unit)
+            .and_then(|value| value.trim().parse::<f32>().ok())
+            .is_some_and(|value| value.is_finite() && value >= 0.0)
+    })
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct LayoutConfig {
+    /// Schema version (see [`layout_migration`]); 0 for files from before
+    /// it was recorded
+    #[serde(default)]
+    pub version: u32,
     /// Symbolic Kamea grid size (optional, overrides columns/rows when set)
     /// Values: "saturn" (3×3), "jupiter" (4×4), "mars" (5×5),
     /// "sun" (6×6), "venus" (7×7), "mercury" (8×8), "moon" (9×9)
@@ -119,6 +286,9 @@ pub struct LayoutConfig {
     pub grid: Option<String>,
     pub columns: Vec<String>, // e.g. "30%", "1fr", "200px"
     pub rows: Vec<String>,
+    /// Sizes for single tracks of the symbolic grid, the rest staying 1fr
+    #[serde(default, skip_serializing_if = "TrackOverrides::is_empty")]
+    pub track_overrides: TrackOverrides,
     pub tiles: Vec<TileConfig>,
     #[serde(default)]
     pub patches: Vec<Patch>,
@@ -126,9 +296,251 @@ pub struct LayoutConfig {
     pub is_sleeping: bool,
     #[serde(default)]
     pub power_profile: PowerProfile,
+    #[serde(default, skip_serializing_if = "PowerConfig::is_default")]
+    pub power: PowerConfig,
+    #[serde(default, skip_serializing_if = "StartupConfig::is_empty")]
+    pub startup: StartupConfig,
+    /// Windows besides the main one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub windows: Vec<WindowConfig>,
+    #[serde(default, skip_serializing_if = "KioskConfig::is_default")]
+    pub kiosk: KioskConfig,
+    #[serde(default, skip_serializing_if = "WebConfig::is_default")]
+    pub web: WebConfig,
+    /// Tokens and TLS for the web monitor and other remote surfaces
+    #[serde(default, skip_serializing_if = "AccessConfig::is_default")]
+    pub access: AccessConfig,
+    #[serde(default, skip_serializing_if = "PluginIndexConfig::is_default")]
+    pub plugin_index: PluginIndexConfig,
+    #[serde(default, skip_serializing_if = "AudioGraphConfig::is_default")]
+    pub audio_graph: AudioGraphConfig,
+    #[serde(default, skip_serializing_if = "ProvenanceConfig::is_default")]
+    pub provenance: ProvenanceConfig,
+    #[serde(default, skip_serializing_if = "SessionConfig::is_default")]
+    pub session: SessionConfig,
+    #[serde(default, skip_serializing_if = "FocusFollowConfig::is_default")]
+    pub focus_follow: FocusFollowConfig,
+    /// Recorded key sequences, each replayed by one key
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub macros: Vec<KeyMacro>,
+    #[serde(default, skip_serializing_if = "AccessibilityConfig::is_default")]
+    pub accessibility: AccessibilityConfig,
+}
+
+/// `[accessibility]`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct AccessibilityConfig {
+    /// White text and lines on black, opaque dialogs
+    pub high_contrast: bool,
+    /// Speak mode, selection and dialog changes aloud
+    pub speak: bool,
+    /// Program and arguments the announcement is appended to; the
+    /// platform's own (`spd-say`, `say`) when empty
+    pub speech_command: Vec<String>,
+}
+
+impl AccessibilityConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// `[[macros]]`: keys pressed in order, e.g. `steps = ["p", "down", "return"]`,
+/// with modifiers as `"ctrl+shift+l"`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct KeyMacro {
+    /// Key that plays it, `"f1"` to `"f12"`
+    pub key: String,
+    pub steps: Vec<String>,
+}
+
+/// Performance mode: only the tiles' own visuals are drawn and the layout
+/// can't be edited
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct KioskConfig {
+    pub enabled: bool,
+    /// Go fullscreen while enabled
+    pub fullscreen: bool,
+    /// Hide the cursor while enabled
+    pub hide_cursor: bool,
+}
+
+impl KioskConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// `[focus_follow]`: draw attention to the tile whose module emitted most
+/// recently
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct FocusFollowConfig {
+    pub enabled: bool,
+    /// Bring a tile up full-window for this many seconds when focus moves
+    /// to it; 0 only highlights it
+    pub maximize_secs: f32,
+    /// Tile or module ids never followed, e.g. a clock that ticks every second
+    pub ignore: Vec<String>,
+}
+
+impl FocusFollowConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn follows(&self, tile: &TileConfig) -> bool {
+        !self
+            .ignore
+            .iter()
+            .any(|id| *id == tile.id || *id == tile.module)
+    }
+}
+
+/// `[web]`: the read-only status page the daemon serves
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct WebConfig {
+    pub enabled: bool,
+    /// `0.0.0.0:8420` to reach it from other machines on the network
+    pub bind: String,
+}
+
+impl Default for WebConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind: "127.0.0.1:8420".to_string(),
+        }
+    }
+}
+
+impl WebConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// `[plugin_index]`: where community plugins are listed and installed to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct PluginIndexConfig {
+    /// URL of the signed `index.json`; its signature is fetched from
+    /// the same URL with `.sig` appended. Unset: no index
+    pub url: Option<String>,
+    /// Plugin directory installs go to
+    pub dir: std::path::PathBuf,
+}
+
+impl Default for PluginIndexConfig {
+    fn default() -> Self {
+        Self {
+            url: None,
+            dir: std::path::PathBuf::from("plugins"),
+        }
+    }
+}
+
+impl PluginIndexConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// `[audio_graph]`: running connected real-time audio modules on their own
+/// thread (see [`rt_graph`]) instead of through the router
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct AudioGraphConfig {
+    pub enabled: bool,
+    /// Frames per block
+    pub block_frames: usize,
+}
+
+impl Default for AudioGraphConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            block_frames: 256,
+        }
+    }
+}
+
+impl AudioGraphConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// `[provenance]`: envelopes on routed signals (see [`provenance`]), for
+/// the patch bay's inspector and sinks tracing a chain. Off unless enabled.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ProvenanceConfig {
+    pub enabled: bool,
+}
+
+impl ProvenanceConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+fn default_tracks() -> Vec<String> {
+    vec!["1fr".to_string()]
+}
+
+/// An extra window, e.g. a clean fullscreen output on a second monitor.
+/// Its tiles sit on its own grid and are drawn without chrome.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct WindowConfig {
+    pub id: String,
+    /// Defaults to the id
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub fullscreen: bool,
+    /// Monitor to go fullscreen on, by index; the primary one when unset
+    #[serde(default)]
+    pub monitor: Option<usize>,
+    #[serde(default = "default_tracks")]
+    pub columns: Vec<String>,
+    #[serde(default = "default_tracks")]
+    pub rows: Vec<String>,
 }
 
 impl LayoutConfig {
+    pub fn window(&self, id: &str) -> Option<&WindowConfig> {
+        self.windows.iter().find(|w| w.id == id)
+    }
+
+    /// Whether `tile` belongs to the main window; tiles naming a window
+    /// that isn't configured fall back to it
+    pub fn in_main_window(&self, tile: &TileConfig) -> bool {
+        tile.window
+            .as_deref()
+            .is_none_or(|id| self.window(id).is_none())
+    }
+
+    /// Tiles on the main window's grid
+    pub fn main_tiles(&self) -> impl Iterator<Item = &TileConfig> {
+        self.tiles.iter().filter(|tile| self.in_main_window(tile))
+    }
+
+    /// Main-window tiles that take grid cells, i.e. not overlays
+    pub fn grid_tiles(&self) -> impl Iterator<Item = &TileConfig> {
+        self.main_tiles().filter(|tile| !tile.is_overlay())
+    }
+
+    /// Tiles assigned to window `id`
+    pub fn window_tiles<'a>(&'a self, id: &'a str) -> impl Iterator<Item = &'a TileConfig> {
+        self.tiles
+            .iter()
+            .filter(move |tile| tile.window.as_deref() == Some(id))
+    }
+
     /// Resolve grid to column/row counts
     /// Returns (cols, rows) tuple
     pub fn resolve_grid(&self) -> (usize, usize) {
@@ -141,19 +553,71 @@ impl LayoutConfig {
         (self.columns.len().max(1), self.rows.len().max(1))
     }
 
-    /// Generate equal-sized track definitions for symbolic grid
+    /// Track definitions: 1fr tracks for a symbolic grid with any
+    /// `track_overrides` applied, otherwise the explicit columns and rows
     pub fn generate_tracks(&self) -> (Vec<String>, Vec<String>) {
         let (cols, rows) = self.resolve_grid();
         if self.grid.is_some() {
-            // Generate 1fr tracks for symbolic grid
-            let col_tracks: Vec<String> = (0..cols).map(|_| "1fr".to_string()).collect();
-            let row_tracks: Vec<String> = (0..rows).map(|_| "1fr".to_string()).collect();
-            (col_tracks, row_tracks)
+            let tracks = |count: usize, overrides: &BTreeMap<String, String>| {
+                let mut tracks = vec!["1fr".to_string(); count];
+                for (index, size) in overrides {
+                    match index.trim().parse::<usize>() {
+                        Ok(i) if i < count && is_valid_track_size(size) => {
+                            tracks[i] = size.trim().to_string()
+                        }
+                        _ => {}
+                    }
+                }
+                tracks
+            };
+            (
+                tracks(cols, &self.track_overrides.columns),
+                tracks(rows, &self.track_overrides.rows),
+            )
         } else {
             (self.columns.clone(), self.rows.clone())
         }
     }
 
+    /// Check `track_overrides` against the grid: they need a symbolic grid,
+    /// an index inside it and a size such as `"2fr"`, `"25%"` or `"120px"`
+    pub fn validate_tracks(&self) -> std::result::Result<(), String> {
+        if self.track_overrides.is_empty() {
+            return Ok(());
+        }
+        if self.grid.is_none() {
+            return Err(
+                "track_overrides only apply to a symbolic grid; size columns and rows directly"
+                    .to_string(),
+            );
+        }
+        let (cols, rows) = self.resolve_grid();
+        for (kind, count, overrides) in [
+            ("column", cols, &self.track_overrides.columns),
+            ("row", rows, &self.track_overrides.rows),
+        ] {
+            for (index, size) in overrides {
+                match index.trim().parse::<usize>() {
+                    Ok(i) if i < count => {}
+                    Ok(_) => {
+                        return Err(format!(
+                            "{} {} is outside the {}×{} grid (tracks count from 0)",
+                            kind, index, cols, rows
+                        ))
+                    }
+                    Err(_) => return Err(format!("`{}` is not a {} index", index, kind)),
+                }
+                if !is_valid_track_size(size) {
+                    return Err(format!(
+                        "{} {}: `{}` is not a size; use px, % or fr",
+                        kind, index, size
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Resolve tile overlaps by re-packing tiles onto the grid.
     ///
     /// Goals:
@@ -174,11 +638,37 @@ impl LayoutConfig {
         self.resolve_conflicts_within(cols, rows, preferred_tile_id)
     }
 
+    /// Only main-window grid tiles are packed; the others keep their places
+    /// on their own windows' grids, and overlays may cover anything, so
+    /// they're only kept inside the grid
     pub fn resolve_conflicts_within(
         &mut self,
         cols: usize,
         rows: usize,
         preferred_tile_id: Option<&str>,
+    ) -> std::result::Result<(), LayoutResolveError> {
+        let (main, mut elsewhere): (Vec<_>, Vec<_>) = std::mem::take(&mut self.tiles)
+            .into_iter()
+            .partition(|tile| self.in_main_window(tile) && !tile.is_overlay());
+        for tile in elsewhere.iter_mut().filter(|tile| tile.is_overlay()) {
+            if tile.window.is_none() && cols > 0 && rows > 0 {
+                tile.col = tile.col.min(cols - 1);
+                tile.row = tile.row.min(rows - 1);
+                tile.colspan = Some(tile.colspan.unwrap_or(1).clamp(1, cols - tile.col));
+                tile.rowspan = Some(tile.rowspan.unwrap_or(1).clamp(1, rows - tile.row));
+            }
+        }
+        self.tiles = main;
+        let result = self.resolve_main_conflicts(cols, rows, preferred_tile_id);
+        self.tiles.extend(elsewhere);
+        result
+    }
+
+    fn resolve_main_conflicts(
+        &mut self,
+        cols: usize,
+        rows: usize,
+        preferred_tile_id: Option<&str>,
     ) -> std::result::Result<(), LayoutResolveError> {
         if cols == 0 || rows == 0 {
             return Err(LayoutResolveError::InvalidGrid { cols, rows });
@@ -434,6 +924,50 @@ pub struct TileSettings {
     /// Keybindings: action name -> key (e.g., "mute" -> "m")
     #[serde(default)]
     pub keybinds: HashMap<String, String>,
+
+    /// Module settings version `config` was saved at
+    #[serde(default, skip_serializing_if = "is_unversioned")]
+    pub version: u32,
+}
+
+fn is_unversioned(version: &u32) -> bool {
+    *version == 0
+}
+
+impl TileSettings {
+    /// Bring `config`, saved at `version`, up to `current` through
+    /// `migrate(old_version, config)`. Settings it can't upgrade are dropped
+    /// so the module starts from its defaults; ones from a newer build are
+    /// left alone. Returns whether anything changed.
+    pub fn migrate(
+        &mut self,
+        current: u32,
+        migrate: impl FnOnce(u32, serde_json::Value) -> Option<serde_json::Value>,
+    ) -> bool {
+        if self.version > current {
+            log::warn!(
+                "Settings saved at version {} by a newer build; this one reads {}",
+                self.version,
+                current
+            );
+        }
+        if self.version >= current {
+            return false;
+        }
+        let old = std::mem::replace(&mut self.version, current);
+        if !self.config.is_null() {
+            let config = std::mem::take(&mut self.config);
+            self.config = migrate(old, config).unwrap_or_else(|| {
+                log::warn!(
+                    "Settings at version {} can't be upgraded to {}; using defaults",
+                    old,
+                    current
+                );
+                serde_json::Value::Null
+            });
+        }
+        true
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -449,20 +983,120 @@ pub struct TileConfig {
     /// Per-tile instance settings (module interprets these)
     #[serde(default)]
     pub settings: TileSettings,
+    /// Extra window (`[[windows]]` id) showing this tile; the main window when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window: Option<String>,
+    /// Stacking order: higher draws on top. Grid tiles never overlap, so
+    /// this matters for overlays and what they float over.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub z: i32,
+    /// Float over the grid (picture-in-picture) instead of taking cells
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overlay: Option<TileOverlay>,
 }
 
 fn default_enabled() -> bool {
     true
 }
 
+fn is_zero(z: &i32) -> bool {
+    *z == 0
+}
+
+impl TileConfig {
+    pub fn is_overlay(&self) -> bool {
+        self.overlay.is_some()
+    }
+}
+
+/// Corner (or centre) of its cells an overlay sits in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlayAnchor {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+    Center,
+}
+
+impl OverlayAnchor {
+    pub const ALL: [OverlayAnchor; 5] = [
+        OverlayAnchor::TopLeft,
+        OverlayAnchor::TopRight,
+        OverlayAnchor::BottomLeft,
+        OverlayAnchor::BottomRight,
+        OverlayAnchor::Center,
+    ];
+
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|a| *a == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+}
+
+/// `overlay = { anchor = "top_right", scale = 0.25 }` on a tile: drawn
+/// over whatever shares its cells, at a fraction of their size
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct TileOverlay {
+    pub anchor: OverlayAnchor,
+    /// Share of its cells' width and height, 0.1 to 1
+    pub scale: f32,
+    /// Gap to the cells' edges at a corner anchor, in pixels
+    pub margin: f32,
+}
+
+impl Default for TileOverlay {
+    fn default() -> Self {
+        Self {
+            anchor: OverlayAnchor::default(),
+            scale: 0.33,
+            margin: 12.0,
+        }
+    }
+}
+
+impl TileOverlay {
+    pub const MIN_SCALE: f32 = 0.1;
+
+    /// The overlay's box inside its cells' box, both as
+    /// `[left, top, width, height]` with y growing downwards
+    pub fn place(&self, cells: [f32; 4]) -> [f32; 4] {
+        let [left, top, width, height] = cells;
+        let scale = self.scale.clamp(Self::MIN_SCALE, 1.0);
+        let (w, h) = (width * scale, height * scale);
+        let margin = self.margin.max(0.0).min((width - w).min(height - h) / 2.0);
+        let (x, y) = match self.anchor {
+            OverlayAnchor::TopLeft => (left + margin, top + margin),
+            OverlayAnchor::TopRight => (left + width - w - margin, top + margin),
+            OverlayAnchor::BottomLeft => (left + margin, top + height - h - margin),
+            OverlayAnchor::BottomRight => (left + width - w - margin, top + height - h - margin),
+            OverlayAnchor::Center => (left + (width - w) / 2.0, top + (height - h) / 2.0),
+        };
+        [x, y, w, h]
+    }
+}
+
+/// `tiles` bottom to top: by `z`, overlays above grid tiles of the same `z`,
+/// otherwise in layout order
+pub fn draw_order<'a>(tiles: impl IntoIterator<Item = &'a TileConfig>) -> Vec<&'a TileConfig> {
+    let mut tiles: Vec<&TileConfig> = tiles.into_iter().collect();
+    tiles.sort_by_key(|tile| (tile.z, tile.is_overlay()));
+    tiles
+}
+
 use schemars::JsonSchema;
 use std::fmt::Debug;
 
 pub type Result<T> = std::result::Result<T, anyhow::Error>;
 
 // Re-export core types from signals
+pub use magnolia_signals::midi;
 pub use magnolia_signals::{
-    AstrologyData, ControlSignal, DataType, OverflowPolicy, PortDirection, Signal,
+    AstrologyData, AudioView, BlobView, ControlSignal, DataType, MidiMessage, OverflowPolicy,
+    PortDirection, Signal,
 };
 pub use magnolia_signals::{AudioBufferHandle, BlobHandle, GpuBufferHandle, GpuTextureHandle};
 
@@ -492,6 +1126,44 @@ pub struct ModuleSchema {
     pub ports: Vec<Port>,
     /// Optional JSON Schema for settings UI
     pub settings_schema: Option<serde_json::Value>,
+    /// Long-form documentation for the docs pane
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub docs: Option<ModuleDocs>,
+}
+
+impl ModuleSchema {
+    /// What each setting does: the module's docs where they say, the
+    /// settings schema's descriptions otherwise
+    pub fn setting_docs(&self) -> BTreeMap<String, String> {
+        let mut notes: BTreeMap<String, String> = self
+            .settings_schema
+            .as_ref()
+            .and_then(|schema| schema.get("properties")?.as_object())
+            .into_iter()
+            .flatten()
+            .filter_map(|(key, property)| {
+                let description = property.get("description")?.as_str()?;
+                Some((key.clone(), description.to_string()))
+            })
+            .collect();
+        if let Some(docs) = &self.docs {
+            notes.extend(docs.settings.clone());
+        }
+        notes
+    }
+}
+
+/// A module's manual: what it's for and what its ports and settings do.
+/// Plugins hand it over as JSON from `magnolia_plugin_get_docs`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ModuleDocs {
+    /// Paragraphs separated by blank lines
+    pub overview: String,
+    /// Port id to what arrives or leaves there
+    pub ports: BTreeMap<String, String>,
+    /// Setting to what it changes
+    pub settings: BTreeMap<String, String>,
 }
 
 /// A connection between two ports on different modules
@@ -507,6 +1179,68 @@ pub struct Patch {
     pub sink_module: String,
     /// Sink port ID (must be Input direction)
     pub sink_port: String,
+    /// How the sink port combines this with its other patches; `None` for
+    /// the default, [`MergePolicy::LastWrite`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merge: Option<MergePolicy>,
+    /// What the router does to signals crossing this patch, if anything
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transform: Option<PatchTransform>,
+}
+
+/// How an input port fed by several patches combines what they send
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MergePolicy {
+    /// Each signal is delivered as it arrives, so the latest one wins. The
+    /// default for every port, audio too
+    #[default]
+    LastWrite,
+    /// Audio from every patch is mixed into one buffer, sample by sample
+    Sum,
+}
+
+/// A change the router makes to what crosses one patch, in place of a
+/// module between its ends
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum PatchTransform {
+    /// Audio samples are scaled by `gain`
+    Gain { gain: f32 },
+    /// Text matching the regex `pattern` is replaced by `replacement`,
+    /// which can refer to groups as `$1`
+    Replace {
+        pattern: String,
+        replacement: String,
+    },
+}
+
+impl PatchTransform {
+    /// Scale audio by `db` decibels
+    pub fn gain_db(db: f32) -> Self {
+        Self::Gain {
+            gain: 10f32.powf(db / 20.0),
+        }
+    }
+
+    /// Whether it changes signals from a port carrying `data_type`
+    pub fn applies_to(&self, data_type: &DataType) -> bool {
+        match self {
+            Self::Gain { .. } => matches!(data_type, DataType::Audio | DataType::Any),
+            Self::Replace { .. } => matches!(data_type, DataType::Text | DataType::Any),
+        }
+    }
+
+    /// Short form for patch lists: `-6.0 dB`, `s/um//`
+    pub fn label(&self) -> String {
+        match self {
+            Self::Gain { gain } => format!("{:+.1} dB", 20.0 * gain.log10()),
+            Self::Replace {
+                pattern,
+                replacement,
+            } => format!("s/{}/{}/", pattern, replacement),
+        }
+    }
 }
 
 // Signal types replaced by magnolia_signals re-export
@@ -537,6 +1271,23 @@ pub trait Source: Send + Sync {
     /// Wait for the next signal from this source.
     /// Returns `None` if the source is exhausted/closed.
     async fn poll(&mut self) -> Option<Signal>;
+
+    /// Handle a signal patched into one of this source's input ports
+    /// (e.g. transport control). Called between polls; ignored by default.
+    fn handle_signal(&mut self, _signal: Signal) {}
+
+    /// Whether there's nothing to poll for until a signal patched into this
+    /// source changes that, as with a finished replay waiting for a seek.
+    /// While it is, the source isn't polled, only handed its inputs.
+    fn is_idle(&self) -> bool {
+        false
+    }
+
+    /// Output port a polled signal leaves from, for sources with several
+    /// output ports. `None` routes it from the first output port.
+    fn output_port(&self, _signal: &Signal) -> Option<&str> {
+        None
+    }
 }
 
 /// A Sink consumes Signals from the Patch Bay.
@@ -563,6 +1314,12 @@ pub trait Sink: Send + Sync {
         None
     }
 
+    /// This sink's half for compiled audio graphs, if it has one; taken
+    /// once, when it's spawned
+    fn rt_audio_node(&mut self) -> Option<Box<dyn RtAudioNode>> {
+        None
+    }
+
     /// Consume a signal and optionally produce an output signal.
     ///
     /// Returns:
@@ -596,6 +1353,32 @@ pub trait Processor: Send + Sync {
 
     /// Process an input signal and optionally emit an output signal
     async fn process(&mut self, signal: Signal) -> Result<Option<Signal>>;
+
+    /// Output port the signal just returned by `process` leaves from, for
+    /// processors with several output ports. `None` routes it from the first
+    /// output port.
+    fn output_port(&self, _signal: &Signal) -> Option<&str> {
+        None
+    }
+
+    /// Resolves when a processor holding signals back (debounce, delay)
+    /// wants [`Processor::wake`] called without new input. Dropped and
+    /// re-awaited whenever a signal arrives first; never resolves by default.
+    async fn next_wake(&self) {
+        std::future::pending::<()>().await
+    }
+
+    /// Emit one signal that has come due; `next_wake` should resolve again
+    /// at once while more are waiting.
+    async fn wake(&mut self) -> Result<Option<Signal>> {
+        Ok(None)
+    }
+
+    /// This processor's half for compiled audio graphs, if it has one;
+    /// taken once, when it's spawned
+    fn rt_audio_node(&mut self) -> Option<Box<dyn RtAudioNode>> {
+        None
+    }
 }
 
 /// A Transform modifies a Signal in flight (synchronous version).
@@ -604,3 +1387,208 @@ pub trait Processor: Send + Sync {
 pub trait Transform: Send + Sync {
     async fn apply(&self, signal: Signal) -> Result<Signal>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tile(id: &str, col: usize, row: usize, window: Option<&str>) -> TileConfig {
+        TileConfig {
+            id: id.to_string(),
+            col,
+            row,
+            colspan: Some(1),
+            rowspan: Some(1),
+            module: id.to_string(),
+            enabled: true,
+            settings: TileSettings::default(),
+            window: window.map(str::to_string),
+            z: 0,
+            overlay: None,
+        }
+    }
+
+    #[test]
+    fn tiles_on_other_windows_stay_off_the_main_grid() {
+        let mut layout = LayoutConfig {
+            version: LAYOUT_VERSION,
+            grid: None,
+            columns: vec!["1fr".to_string()],
+            rows: vec!["1fr".to_string()],
+            track_overrides: TrackOverrides::default(),
+            tiles: vec![
+                tile("main", 0, 0, None),
+                tile("output", 0, 0, Some("stage")),
+                tile("stray", 0, 0, Some("missing")),
+            ],
+            patches: Vec::new(),
+            is_sleeping: false,
+            power_profile: PowerProfile::Normal,
+            power: PowerConfig::default(),
+            startup: StartupConfig::default(),
+            kiosk: KioskConfig::default(),
+            web: WebConfig::default(),
+            access: AccessConfig::default(),
+            plugin_index: PluginIndexConfig::default(),
+            audio_graph: AudioGraphConfig::default(),
+            provenance: ProvenanceConfig::default(),
+            session: SessionConfig::default(),
+            focus_follow: FocusFollowConfig::default(),
+            macros: Vec::new(),
+            accessibility: AccessibilityConfig::default(),
+            windows: vec![WindowConfig {
+                id: "stage".to_string(),
+                title: None,
+                fullscreen: true,
+                monitor: Some(1),
+                columns: vec!["1fr".to_string()],
+                rows: vec!["1fr".to_string()],
+            }],
+        };
+        let ids = |tiles: Vec<&TileConfig>| tiles.iter().map(|t| t.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(layout.main_tiles().collect()), ["main", "stray"]);
+        assert_eq!(ids(layout.window_tiles("stage").collect()), ["output"]);
+
+        // A window-less tile naming a missing window shares the 1x1 main grid
+        assert!(layout.resolve_conflicts(None).is_err());
+        layout.tiles.retain(|t| t.id != "stray");
+        layout.resolve_conflicts(None).unwrap();
+        let output = layout.tiles.iter().find(|t| t.id == "output").unwrap();
+        assert_eq!((output.col, output.row), (0, 0));
+    }
+
+    #[test]
+    fn overlays_float_over_the_grid() {
+        let mut layout: LayoutConfig = toml::from_str(
+            r#"
+            columns = ["1fr", "1fr"]
+            rows = ["1fr"]
+            tiles = [
+                { id = "pip", col = 1, row = 0, colspan = 3, module = "viz", z = 1, overlay = { anchor = "top_right" } },
+                { id = "kamea", col = 0, row = 0, colspan = 2, module = "kamea" },
+            ]
+            "#,
+        )
+        .unwrap();
+        // Overlapping an overlay isn't a conflict; it's only kept on the grid
+        layout.resolve_conflicts(None).unwrap();
+        let pip = layout.tiles.iter().find(|t| t.id == "pip").unwrap();
+        assert_eq!((pip.col, pip.colspan), (1, Some(1)));
+        let kamea = layout.tiles.iter().find(|t| t.id == "kamea").unwrap();
+        assert_eq!((kamea.col, kamea.colspan), (0, Some(2)));
+        assert_eq!(layout.grid_tiles().count(), 1);
+
+        let order: Vec<_> = draw_order(&layout.tiles).iter().map(|t| &t.id).collect();
+        assert_eq!(order, ["kamea", "pip"]);
+
+        let overlay = pip.overlay.clone().unwrap();
+        assert_eq!(overlay.scale, 0.33);
+        let [x, y, w, h] = TileOverlay {
+            scale: 0.25,
+            ..overlay
+        }
+        .place([0.0, 0.0, 400.0, 200.0]);
+        assert_eq!([x, y, w, h], [288.0, 12.0, 100.0, 50.0]);
+
+        let saved = |tile: &TileConfig| serde_json::to_value(tile).unwrap();
+        assert_eq!(saved(pip)["overlay"]["anchor"], "top_right");
+        assert!(saved(kamea).get("z").is_none());
+    }
+
+    #[test]
+    fn symbolic_grids_take_track_overrides() {
+        let mut layout: LayoutConfig = toml::from_str(
+            r#"
+            grid = "venus"
+            columns = []
+            rows = []
+            tiles = []
+
+            [track_overrides]
+            columns = { 3 = "2fr" }
+            rows = { 0 = "80px", 6 = "10%" }
+            "#,
+        )
+        .unwrap();
+        layout.validate_tracks().unwrap();
+        let (columns, rows) = layout.generate_tracks();
+        assert_eq!(columns.len(), 7);
+        assert_eq!(columns[3], "2fr");
+        assert!(columns.iter().filter(|c| *c == "1fr").count() == 6);
+        assert_eq!((rows[0].as_str(), rows[6].as_str()), ("80px", "10%"));
+
+        layout.grid = Some("saturn".to_string());
+        assert!(layout.validate_tracks().unwrap_err().contains("column 3"));
+        assert_eq!(
+            layout.generate_tracks(),
+            (
+                vec!["1fr".to_string(); 3],
+                vec!["80px".to_string(), "1fr".to_string(), "1fr".to_string()]
+            )
+        );
+
+        layout.track_overrides.rows.clear();
+        layout
+            .track_overrides
+            .columns
+            .insert("1".to_string(), "wide".to_string());
+        assert!(layout.validate_tracks().is_err());
+        layout.grid = None;
+        assert!(layout.validate_tracks().is_err());
+    }
+
+    #[test]
+    fn setting_docs_prefer_the_manual_to_the_schema() {
+        let schema = ModuleSchema {
+            id: "delay".to_string(),
+            name: "Delay".to_string(),
+            description: String::new(),
+            ports: vec![],
+            settings_schema: Some(serde_json::json!({
+                "properties": {
+                    "delay_ms": { "type": "integer", "description": "Hold time" },
+                    "at": { "type": "string" },
+                }
+            })),
+            docs: Some(ModuleDocs {
+                settings: [("at".to_string(), "Wall-clock release".to_string())].into(),
+                ..ModuleDocs::default()
+            }),
+        };
+        let notes = schema.setting_docs();
+        assert_eq!(notes["delay_ms"], "Hold time");
+        assert_eq!(notes["at"], "Wall-clock release");
+        assert_eq!(notes.len(), 2);
+    }
+
+    #[test]
+    fn tile_settings_migrate_once_to_the_current_version() {
+        // v1 stored `gain` in percent, v2 as a ratio
+        let upgrade = |old: u32, mut config: serde_json::Value| {
+            assert_eq!(old, 1);
+            let percent = config["gain"].as_f64()?;
+            config["gain"] = serde_json::json!(percent / 100.0);
+            Some(config)
+        };
+        let mut settings: TileSettings =
+            toml::from_str("version = 1\nconfig = { gain = 50.0 }").unwrap();
+        assert!(settings.migrate(2, upgrade));
+        assert_eq!(
+            (settings.version, &settings.config["gain"]),
+            (2, &0.5.into())
+        );
+        assert!(!settings.migrate(2, upgrade));
+        assert_eq!(settings.config["gain"], 0.5);
+
+        // Can't be upgraded: dropped rather than misapplied
+        let mut settings: TileSettings =
+            toml::from_str("version = 1\nconfig = { gain = \"loud\" }").unwrap();
+        assert!(settings.migrate(2, upgrade));
+        assert!(settings.config.is_null());
+
+        // Newer than this build reads: kept for that build
+        let mut settings: TileSettings = toml::from_str("version = 3\nconfig = {}").unwrap();
+        assert!(!settings.migrate(2, |_, _| unreachable!()));
+        assert_eq!(settings.version, 3);
+    }
+}
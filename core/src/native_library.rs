@@ -0,0 +1,72 @@
+//! Shared libraries loaded at run time
+//!
+//! Modules wrapping a codec, database client or SDK bind it with
+//! [`load_library`] and [`symbol`] rather than linking it, so the daemon
+//! builds and runs without it and only that module needs it installed.
+//! Each maps [`LibraryError`] into its own error type.
+
+use libloading::Library;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("could not load {library}: {message}")]
+pub struct LibraryError {
+    /// The library, or the symbol missing from it
+    pub library: String,
+    pub message: String,
+}
+
+/// Load the first of `candidates` (file names for the loader's search path,
+/// or full paths) that opens; `what` names the library in logs and errors
+pub fn load_library<P: AsRef<Path>>(what: &str, candidates: &[P]) -> Result<Library, LibraryError> {
+    let mut last_error = String::from("no candidates");
+    for candidate in candidates {
+        let candidate = candidate.as_ref();
+        // Safety: loading runs the library's initialisers; the codecs,
+        // clients and runtimes loaded this way have no unusual ones
+        match unsafe { Library::new(candidate) } {
+            Ok(library) => {
+                log::info!("Loaded {} from {}", what, candidate.display());
+                return Ok(library);
+            }
+            Err(e) => last_error = e.to_string(),
+        }
+    }
+    Err(LibraryError {
+        library: what.to_string(),
+        message: last_error,
+    })
+}
+
+/// Copy a function pointer out of `library`; the caller keeps the library
+/// alive for as long as it's used
+///
+/// # Safety
+/// `T` must match the symbol's real signature.
+pub unsafe fn symbol<T: Copy>(library: &Library, name: &[u8]) -> Result<T, LibraryError> {
+    library
+        .get::<T>(name)
+        .map(|symbol| *symbol)
+        .map_err(|e| LibraryError {
+            library: String::from_utf8_lossy(name.strip_suffix(b"\0").unwrap_or(name)).into_owned(),
+            message: e.to_string(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_libraries_report_the_last_failure() {
+        let error = load_library("libnothing", &["libmagnolia_missing_test.so"]).unwrap_err();
+        assert_eq!(error.library, "libnothing");
+        assert!(error.message.contains("libmagnolia_missing_test"));
+
+        let none: &[&str] = &[];
+        assert_eq!(
+            load_library("libnothing", none).unwrap_err().message,
+            "no candidates"
+        );
+    }
+}
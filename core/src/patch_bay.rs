@@ -1,4 +1,8 @@
-use crate::{DataType, ModuleSchema, Patch, Port, PortDirection};
+use crate::request::REPLY_IN;
+use crate::{
+    DataType, MergePolicy, ModuleSchema, Patch, PatchTransform, PatchTransformer, Port,
+    PortDirection,
+};
 use std::collections::{HashMap, HashSet};
 
 /// PatchBay manages module connections and validates type compatibility.
@@ -12,6 +16,16 @@ pub struct PatchBay {
     patches: Vec<Patch>,
     /// Modules in pass-thru mode (disabled but still routing signals)
     disabled_modules: HashSet<String>,
+    /// Modules whose outgoing signals are dropped
+    muted_modules: HashSet<String>,
+    /// While any are set, sources not feeding one of these are silenced
+    soloed_modules: HashSet<String>,
+    /// Processors skipped by the router; their input goes straight to
+    /// their output patches
+    bypassed_modules: HashSet<String>,
+    /// Input ports merging their patches other than by their default, by
+    /// module and port
+    merge_policies: HashMap<(String, String), MergePolicy>,
     /// Counter for generating patch IDs
     next_patch_id: u64,
 }
@@ -28,6 +42,10 @@ impl PatchBay {
             modules: HashMap::new(),
             patches: Vec::new(),
             disabled_modules: HashSet::new(),
+            muted_modules: HashSet::new(),
+            soloed_modules: HashSet::new(),
+            bypassed_modules: HashSet::new(),
+            merge_policies: HashMap::new(),
             next_patch_id: 1,
         }
     }
@@ -48,6 +66,11 @@ impl PatchBay {
     /// Unregister a module and remove all its connections
     pub fn unregister_module(&mut self, module_id: &str) {
         self.modules.remove(module_id);
+        self.muted_modules.remove(module_id);
+        self.soloed_modules.remove(module_id);
+        self.bypassed_modules.remove(module_id);
+        self.merge_policies
+            .retain(|(module, _), _| module != module_id);
         self.patches
             .retain(|p| p.source_module != module_id && p.sink_module != module_id);
     }
@@ -100,6 +123,53 @@ impl PatchBay {
         sink_module: &str,
         sink_port: &str,
     ) -> Result<String, PatchBayError> {
+        self.check_patch(
+            &self.patches,
+            source_module,
+            source_port,
+            sink_module,
+            sink_port,
+        )?;
+
+        // Create patch
+        let patch_id = format!("patch_{}", self.next_patch_id);
+        self.next_patch_id += 1;
+
+        let patch = Patch {
+            id: patch_id.clone(),
+            source_module: source_module.to_string(),
+            source_port: source_port.to_string(),
+            sink_module: sink_module.to_string(),
+            sink_port: sink_port.to_string(),
+            merge: self
+                .merge_policies
+                .get(&(sink_module.to_string(), sink_port.to_string()))
+                .copied(),
+            transform: None,
+        };
+
+        log::info!(
+            "PatchBay: Connected {}:{} -> {}:{}",
+            source_module,
+            source_port,
+            sink_module,
+            sink_port
+        );
+
+        self.patches.push(patch);
+        Ok(patch_id)
+    }
+
+    /// Why a patch couldn't join `patches`: a missing module or port, ports
+    /// that don't fit or a copy of one already there
+    fn check_patch(
+        &self,
+        patches: &[Patch],
+        source_module: &str,
+        source_port: &str,
+        sink_module: &str,
+        sink_port: &str,
+    ) -> Result<(), PatchBayError> {
         // Validate modules exist
         let source_schema = self
             .modules
@@ -135,7 +205,7 @@ impl PatchBay {
         }
 
         // Check for duplicate connection
-        let already_exists = self.patches.iter().any(|p| {
+        let already_exists = patches.iter().any(|p| {
             p.source_module == source_module
                 && p.source_port == source_port
                 && p.sink_module == sink_module
@@ -144,29 +214,196 @@ impl PatchBay {
         if already_exists {
             return Err(PatchBayError::DuplicateConnection);
         }
+        Ok(())
+    }
 
-        // Create patch
-        let patch_id = format!("patch_{}", self.next_patch_id);
-        self.next_patch_id += 1;
+    /// Check `patches`, as saved in a layout, before making them. Each is
+    /// checked against the modules registered and the patches already made
+    /// or accepted before it, the way [`connect_patch`](Self::connect_patch)
+    /// would take them in order, and also refused if it would close a loop
+    /// back to its source: the router would pass that signal round it
+    /// forever. Patches into a `reply_in` port carry replies back to the
+    /// module that asked and are let through. Returns what's wrong with
+    /// each refused patch.
+    pub fn validate_graph(&self, patches: &[Patch]) -> Vec<PatchIssue> {
+        let mut accepted = self.patches.clone();
+        let mut issues = Vec::new();
+        for patch in patches {
+            let checked = match patch.merge {
+                Some(merge) => self.check_merge(&patch.sink_module, &patch.sink_port, merge),
+                None => Ok(()),
+            }
+            .and_then(|()| match &patch.transform {
+                Some(transform) => {
+                    self.check_transform(&patch.source_module, &patch.source_port, transform)
+                }
+                None => Ok(()),
+            })
+            .and_then(|()| {
+                self.check_patch(
+                    &accepted,
+                    &patch.source_module,
+                    &patch.source_port,
+                    &patch.sink_module,
+                    &patch.sink_port,
+                )
+            })
+            .and_then(|()| {
+                if patch.sink_port == REPLY_IN {
+                    return Ok(());
+                }
+                match Self::find_path(&accepted, &patch.sink_module, &patch.source_module) {
+                    Some(path) => {
+                        let mut modules = vec![patch.source_module.clone()];
+                        modules.extend(path);
+                        Err(PatchBayError::FeedbackLoop(modules))
+                    }
+                    None => Ok(()),
+                }
+            });
+            match checked {
+                Ok(()) => accepted.push(patch.clone()),
+                Err(error) => issues.push(PatchIssue {
+                    patch_id: patch.id.clone(),
+                    error,
+                }),
+            }
+        }
+        issues
+    }
 
-        let patch = Patch {
-            id: patch_id.clone(),
-            source_module: source_module.to_string(),
-            source_port: source_port.to_string(),
-            sink_module: sink_module.to_string(),
-            sink_port: sink_port.to_string(),
+    /// Make a patch as saved in a layout, with the merge policy it records
+    /// for its sink port and its transform
+    pub fn connect_patch(&mut self, patch: &Patch) -> Result<String, PatchBayError> {
+        if let Some(transform) = &patch.transform {
+            self.check_transform(&patch.source_module, &patch.source_port, transform)?;
+        }
+        if let Some(merge) = patch.merge {
+            self.set_merge_policy(&patch.sink_module, &patch.sink_port, merge)?;
+        }
+        let id = self.connect(
+            &patch.source_module,
+            &patch.source_port,
+            &patch.sink_module,
+            &patch.sink_port,
+        )?;
+        if let Some(made) = self.patches.iter_mut().find(|p| p.id == id) {
+            made.transform = patch.transform.clone();
+        }
+        Ok(id)
+    }
+
+    /// Have the router transform what crosses a patch, or stop with `None`
+    pub fn set_patch_transform(
+        &mut self,
+        patch_id: &str,
+        transform: Option<PatchTransform>,
+    ) -> Result<(), PatchBayError> {
+        let patch = self
+            .patches
+            .iter()
+            .find(|p| p.id == patch_id)
+            .ok_or_else(|| PatchBayError::PatchNotFound(patch_id.to_string()))?;
+        if let Some(transform) = &transform {
+            self.check_transform(&patch.source_module, &patch.source_port, transform)?;
+        }
+        if let Some(patch) = self.patches.iter_mut().find(|p| p.id == patch_id) {
+            patch.transform = transform;
+        }
+        Ok(())
+    }
+
+    /// Why `transform` can't go on a patch from `port` of `module`
+    fn check_transform(
+        &self,
+        module_id: &str,
+        port_id: &str,
+        transform: &PatchTransform,
+    ) -> Result<(), PatchBayError> {
+        let port = self.find_port(module_id, port_id).ok_or_else(|| {
+            PatchBayError::PortNotFound(module_id.to_string(), port_id.to_string())
+        })?;
+        let unsupported = |reason: String| PatchBayError::UnsupportedTransform {
+            port: format!("{}:{}", module_id, port_id),
+            reason,
         };
+        if !transform.applies_to(&port.data_type) {
+            return Err(unsupported(format!(
+                "{} can't change {:?}",
+                transform.label(),
+                port.data_type
+            )));
+        }
+        if let PatchTransform::Replace { pattern, .. } = transform {
+            PatchTransformer::check_pattern(pattern).map_err(unsupported)?;
+        }
+        Ok(())
+    }
 
-        log::info!(
-            "PatchBay: Connected {}:{} -> {}:{}",
-            source_module,
-            source_port,
-            sink_module,
-            sink_port
-        );
+    /// How `port` of `module` combines the patches into it; last write
+    /// unless set otherwise, audio included
+    pub fn merge_policy(&self, module_id: &str, port_id: &str) -> MergePolicy {
+        if let Some(policy) = self
+            .merge_policies
+            .get(&(module_id.to_string(), port_id.to_string()))
+        {
+            return *policy;
+        }
+        MergePolicy::default()
+    }
 
-        self.patches.push(patch);
-        Ok(patch_id)
+    /// Declare how an input port combines the patches into it. Only ports
+    /// that take audio can sum.
+    pub fn set_merge_policy(
+        &mut self,
+        module_id: &str,
+        port_id: &str,
+        policy: MergePolicy,
+    ) -> Result<(), PatchBayError> {
+        self.check_merge(module_id, port_id, policy)?;
+        self.merge_policies
+            .insert((module_id.to_string(), port_id.to_string()), policy);
+        for patch in &mut self.patches {
+            if patch.sink_module == module_id && patch.sink_port == port_id {
+                patch.merge = Some(policy);
+            }
+        }
+        Ok(())
+    }
+
+    fn check_merge(
+        &self,
+        module_id: &str,
+        port_id: &str,
+        policy: MergePolicy,
+    ) -> Result<(), PatchBayError> {
+        let port = self.find_port(module_id, port_id).ok_or_else(|| {
+            PatchBayError::PortNotFound(module_id.to_string(), port_id.to_string())
+        })?;
+        let summable = matches!(port.data_type, DataType::Audio | DataType::Any);
+        if port.direction != PortDirection::Input || (policy == MergePolicy::Sum && !summable) {
+            return Err(PatchBayError::UnsupportedMerge {
+                port: format!("{}:{}", module_id, port_id),
+                policy,
+            });
+        }
+        Ok(())
+    }
+
+    /// Patches into `port` of `module`
+    pub fn get_port_feeds(&self, module_id: &str, port_id: &str) -> Vec<&Patch> {
+        self.patches
+            .iter()
+            .filter(|p| p.sink_module == module_id && p.sink_port == port_id)
+            .collect()
+    }
+
+    fn find_port(&self, module_id: &str, port_id: &str) -> Option<&Port> {
+        self.modules
+            .get(module_id)?
+            .ports
+            .iter()
+            .find(|port| port.id == port_id)
     }
 
     /// Remove a connection by patch ID
@@ -223,6 +460,140 @@ impl PatchBay {
         &self.disabled_modules
     }
 
+    /// Check if a module is bypassed (input forwarded unprocessed)
+    pub fn is_module_bypassed(&self, module_id: &str) -> bool {
+        self.bypassed_modules.contains(module_id)
+    }
+
+    /// Bypass a module or put it back in the chain. Unlike disabling, the
+    /// signals keep flowing past it to its first output port's patches.
+    pub fn set_module_bypassed(&mut self, module_id: &str, bypassed: bool) {
+        if bypassed {
+            self.bypassed_modules.insert(module_id.to_string());
+        } else {
+            self.bypassed_modules.remove(module_id);
+        }
+        log::info!(
+            "PatchBay: Module '{}' {}",
+            module_id,
+            if bypassed { "bypassed" } else { "in circuit" }
+        );
+    }
+
+    /// Toggle bypass; returns whether the module is now bypassed
+    pub fn toggle_module_bypassed(&mut self, module_id: &str) -> bool {
+        let bypassed = !self.is_module_bypassed(module_id);
+        self.set_module_bypassed(module_id, bypassed);
+        bypassed
+    }
+
+    /// Get all bypassed modules
+    pub fn get_bypassed_modules(&self) -> &HashSet<String> {
+        &self.bypassed_modules
+    }
+
+    /// Check if a module is muted (outgoing signals dropped)
+    pub fn is_module_muted(&self, module_id: &str) -> bool {
+        self.muted_modules.contains(module_id)
+    }
+
+    /// Mute or unmute a module's outgoing signals
+    pub fn set_module_muted(&mut self, module_id: &str, muted: bool) {
+        if muted {
+            self.muted_modules.insert(module_id.to_string());
+        } else {
+            self.muted_modules.remove(module_id);
+        }
+        log::info!(
+            "PatchBay: Module '{}' {}",
+            module_id,
+            if muted { "muted" } else { "unmuted" }
+        );
+    }
+
+    /// Toggle mute; returns whether the module is now muted
+    pub fn toggle_module_muted(&mut self, module_id: &str) -> bool {
+        let muted = !self.is_module_muted(module_id);
+        self.set_module_muted(module_id, muted);
+        muted
+    }
+
+    /// Get all muted modules
+    pub fn get_muted_modules(&self) -> &HashSet<String> {
+        &self.muted_modules
+    }
+
+    /// Check if a module is soloed
+    pub fn is_module_soloed(&self, module_id: &str) -> bool {
+        self.soloed_modules.contains(module_id)
+    }
+
+    /// Solo or unsolo a module
+    pub fn set_module_soloed(&mut self, module_id: &str, soloed: bool) {
+        if soloed {
+            self.soloed_modules.insert(module_id.to_string());
+        } else {
+            self.soloed_modules.remove(module_id);
+        }
+        log::info!(
+            "PatchBay: Module '{}' {}",
+            module_id,
+            if soloed { "soloed" } else { "unsoloed" }
+        );
+    }
+
+    /// Toggle solo; returns whether the module is now soloed
+    pub fn toggle_module_soloed(&mut self, module_id: &str) -> bool {
+        let soloed = !self.is_module_soloed(module_id);
+        self.set_module_soloed(module_id, soloed);
+        soloed
+    }
+
+    /// Get all soloed modules
+    pub fn get_soloed_modules(&self) -> &HashSet<String> {
+        &self.soloed_modules
+    }
+
+    /// Check if the router should drop a module's outgoing signals: it's
+    /// muted, or something is soloed and it's a source (no input ports)
+    /// that is neither soloed nor upstream of a soloed module
+    pub fn is_module_silenced(&self, module_id: &str) -> bool {
+        if self.is_module_muted(module_id) {
+            return true;
+        }
+        if self.soloed_modules.is_empty() {
+            return false;
+        }
+        let is_source = self.modules.get(module_id).is_some_and(|schema| {
+            !schema
+                .ports
+                .iter()
+                .any(|port| port.direction == PortDirection::Input)
+        });
+        is_source && !self.feeds_soloed(module_id)
+    }
+
+    /// Whether `module_id` is soloed or reaches a soloed module through patches
+    fn feeds_soloed(&self, module_id: &str) -> bool {
+        let mut seen = HashSet::new();
+        let mut pending = vec![module_id];
+        while let Some(current) = pending.pop() {
+            if self.soloed_modules.contains(current) {
+                return true;
+            }
+            if !seen.insert(current) {
+                continue;
+            }
+            pending.extend(
+                self.patches
+                    .iter()
+                    .filter(|p| p.source_module == current)
+                    .map(|p| p.sink_module.as_str()),
+            );
+        }
+        false
+    }
+
     /// Check if two modules have any compatible port pairs for connection
     /// Returns all possible (source_port, sink_port) pairs
     pub fn get_compatible_ports(
@@ -264,6 +635,122 @@ impl PatchBay {
             .get_compatible_ports(source_module, sink_module)
             .is_empty()
     }
+
+    /// Patches that could carry `module_id`'s outputs to other modules'
+    /// inputs, best first.
+    ///
+    /// Leaves out pairs already patched and any but replies that would close
+    /// a loop back to `module_id`. Exact type matches rank above `Any`, and inputs
+    /// nothing feeds yet above those already fed.
+    pub fn suggest_patches(&self, module_id: &str) -> Vec<PatchSuggestion> {
+        let Some(source) = self.modules.get(module_id) else {
+            return Vec::new();
+        };
+        let mut ranked = Vec::new();
+        for sink in self.modules.values() {
+            let loops = Self::find_path(&self.patches, &sink.id, module_id).is_some();
+            for (source_port, sink_port) in self.get_compatible_ports(module_id, &sink.id) {
+                if loops && sink_port != REPLY_IN {
+                    continue;
+                }
+                let patched = self.patches.iter().any(|p| {
+                    p.source_module == module_id
+                        && p.source_port == source_port
+                        && p.sink_module == sink.id
+                        && p.sink_port == sink_port
+                });
+                if patched {
+                    continue;
+                }
+                let port_type = |schema: &ModuleSchema, port: &str| {
+                    schema
+                        .ports
+                        .iter()
+                        .find(|p| p.id == port)
+                        .map(|p| p.data_type.clone())
+                };
+                let exact = port_type(source, &source_port) == port_type(sink, &sink_port);
+                let fed = self
+                    .patches
+                    .iter()
+                    .any(|p| p.sink_module == sink.id && p.sink_port == sink_port);
+                ranked.push((
+                    (!exact, fed),
+                    PatchSuggestion {
+                        source_port,
+                        sink_module: sink.id.clone(),
+                        sink_port,
+                        exact,
+                    },
+                ));
+            }
+        }
+        ranked.sort_by(|(a_rank, a), (b_rank, b)| {
+            a_rank
+                .cmp(b_rank)
+                .then_with(|| a.sink_module.cmp(&b.sink_module))
+                .then_with(|| a.sink_port.cmp(&b.sink_port))
+                .then_with(|| a.source_port.cmp(&b.source_port))
+        });
+        ranked
+            .into_iter()
+            .map(|(_, suggestion)| suggestion)
+            .collect()
+    }
+
+    /// The modules from `from` to `to` through `patches`, both included,
+    /// or `None` if `to` isn't `from` or downstream of it. Replies going
+    /// back to `reply_in` ports don't count.
+    fn find_path(patches: &[Patch], from: &str, to: &str) -> Option<Vec<String>> {
+        // Each module reached, to the one it was reached from
+        let mut reached_from: HashMap<&str, &str> = HashMap::from([(from, from)]);
+        let mut pending = vec![from];
+        while let Some(current) = pending.pop() {
+            if current == to {
+                let mut path = vec![to.to_string()];
+                let mut at = to;
+                while at != from {
+                    at = reached_from[at];
+                    path.push(at.to_string());
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for patch in patches
+                .iter()
+                .filter(|p| p.source_module == current && p.sink_port != REPLY_IN)
+            {
+                if !reached_from.contains_key(patch.sink_module.as_str()) {
+                    reached_from.insert(&patch.sink_module, current);
+                    pending.push(&patch.sink_module);
+                }
+            }
+        }
+        None
+    }
+}
+
+/// A patch [`PatchBay::suggest_patches`] offers from a module's output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchSuggestion {
+    pub source_port: String,
+    pub sink_module: String,
+    pub sink_port: String,
+    /// Both ports carry the same type, rather than one taking `Any`
+    pub exact: bool,
+}
+
+/// A saved patch [`PatchBay::validate_graph`] would refuse, and why
+#[derive(Debug, Clone)]
+pub struct PatchIssue {
+    pub patch_id: String,
+    pub error: PatchBayError,
+}
+
+impl std::fmt::Display for PatchIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.patch_id, self.error)
+    }
 }
 
 /// Errors that can occur during patch bay operations
@@ -276,6 +763,21 @@ pub enum PatchBayError {
         sink_type: DataType,
     },
     DuplicateConnection,
+    /// The port can't merge its patches that way
+    UnsupportedMerge {
+        port: String,
+        policy: MergePolicy,
+    },
+    /// No patch has this id
+    PatchNotFound(String),
+    /// The transform can't go on patches from this port
+    UnsupportedTransform {
+        port: String,
+        reason: String,
+    },
+    /// The patch would close a loop through these modules, which start and
+    /// end with its source
+    FeedbackLoop(Vec<String>),
 }
 
 impl std::fmt::Display for PatchBayError {
@@ -294,6 +796,14 @@ impl std::fmt::Display for PatchBayError {
                 )
             }
             Self::DuplicateConnection => write!(f, "Connection already exists"),
+            Self::UnsupportedMerge { port, policy } => {
+                write!(f, "{} can't merge its inputs by {:?}", port, policy)
+            }
+            Self::PatchNotFound(id) => write!(f, "Patch not found: {}", id),
+            Self::UnsupportedTransform { port, reason } => {
+                write!(f, "Can't transform patches from {}: {}", port, reason)
+            }
+            Self::FeedbackLoop(modules) => write!(f, "Feedback loop: {}", modules.join(" -> ")),
         }
     }
 }
@@ -320,6 +830,7 @@ mod tests {
             description: "Test module".to_string(),
             ports,
             settings_schema: None,
+            docs: None,
         }
     }
 
@@ -445,4 +956,228 @@ mod tests {
         assert!(pb.disconnect(&patch_id));
         assert_eq!(pb.get_patches().len(), 0);
     }
+
+    #[test]
+    fn test_mute_and_solo_silence_sources() {
+        let mut pb = PatchBay::new();
+        let out = || make_port("out", DataType::Audio, PortDirection::Output);
+        let input = || make_port("in", DataType::Audio, PortDirection::Input);
+        pb.register_module(make_schema("mic", vec![out()]));
+        pb.register_module(make_schema("replay", vec![out()]));
+        pb.register_module(make_schema("dsp", vec![input(), out()]));
+        pb.register_module(make_schema("speaker", vec![input()]));
+        pb.connect("mic", "out", "dsp", "in").unwrap();
+        pb.connect("dsp", "out", "speaker", "in").unwrap();
+        pb.connect("replay", "out", "speaker", "in").unwrap();
+
+        assert!(pb.toggle_module_muted("dsp"));
+        assert!(pb.is_module_silenced("dsp"));
+        assert!(!pb.is_module_silenced("mic"));
+        assert!(!pb.toggle_module_muted("dsp"));
+
+        // Soloing the DSP keeps the mic feeding it and silences the replay
+        pb.set_module_soloed("dsp", true);
+        assert!(!pb.is_module_silenced("mic"));
+        assert!(!pb.is_module_silenced("dsp"));
+        assert!(pb.is_module_silenced("replay"));
+
+        pb.toggle_module_soloed("replay");
+        assert!(!pb.is_module_silenced("replay"));
+
+        pb.unregister_module("replay");
+        pb.set_module_soloed("dsp", false);
+        assert!(pb.get_soloed_modules().is_empty());
+    }
+
+    #[test]
+    fn suggestions_rank_exact_unfed_inputs_and_skip_loops() {
+        let mut pb = PatchBay::new();
+        let audio_in = || make_port("in", DataType::Audio, PortDirection::Input);
+        let audio_out = || make_port("out", DataType::Audio, PortDirection::Output);
+        pb.register_module(make_schema("mic", vec![audio_out()]));
+        pb.register_module(make_schema("dsp", vec![audio_in(), audio_out()]));
+        pb.register_module(make_schema("speaker", vec![audio_in()]));
+        pb.register_module(make_schema("recorder", vec![audio_in()]));
+        pb.register_module(make_schema(
+            "logger",
+            vec![make_port("in", DataType::Any, PortDirection::Input)],
+        ));
+        pb.register_module(make_schema(
+            "captions",
+            vec![make_port("in", DataType::Text, PortDirection::Input)],
+        ));
+        pb.register_module(make_schema("fx", vec![audio_in(), audio_out()]));
+        pb.connect("mic", "out", "dsp", "in").unwrap();
+        pb.connect("mic", "out", "recorder", "in").unwrap();
+        pb.connect("dsp", "out", "speaker", "in").unwrap();
+        pb.connect("dsp", "out", "fx", "in").unwrap();
+
+        let targets = |module: &str| -> Vec<String> {
+            pb.suggest_patches(module)
+                .into_iter()
+                .map(|s| s.sink_module)
+                .collect()
+        };
+        // dsp and the recorder are patched already and captions want text;
+        // the logger only takes Any, so it comes after the fed inputs
+        assert_eq!(targets("mic"), ["fx", "speaker", "logger"]);
+        // fx back into dsp would loop
+        assert_eq!(targets("fx"), ["recorder", "speaker", "logger"]);
+        assert!(!pb.suggest_patches("fx")[2].exact);
+        assert!(targets("missing").is_empty());
+    }
+
+    #[test]
+    fn input_ports_declare_how_their_patches_merge() {
+        let mut pb = PatchBay::new();
+        for id in ["mic", "replay"] {
+            pb.register_module(make_schema(
+                id,
+                vec![make_port(
+                    "audio_out",
+                    DataType::Audio,
+                    PortDirection::Output,
+                )],
+            ));
+        }
+        pb.register_module(make_schema(
+            "mixer",
+            vec![
+                make_port("audio_in", DataType::Audio, PortDirection::Input),
+                make_port("text_in", DataType::Text, PortDirection::Input),
+            ],
+        ));
+        pb.connect("mic", "audio_out", "mixer", "audio_in").unwrap();
+        pb.connect("replay", "audio_out", "mixer", "audio_in")
+            .unwrap();
+        assert_eq!(pb.get_port_feeds("mixer", "audio_in").len(), 2);
+        // Audio fanning in interleaves, as layouts from before merge
+        // policies expect; summing is asked for
+        assert_eq!(pb.merge_policy("mixer", "audio_in"), MergePolicy::LastWrite);
+        assert_eq!(pb.merge_policy("mixer", "text_in"), MergePolicy::LastWrite);
+        assert!(pb.get_patches().iter().all(|patch| patch.merge.is_none()));
+
+        assert!(matches!(
+            pb.set_merge_policy("mixer", "text_in", MergePolicy::Sum),
+            Err(PatchBayError::UnsupportedMerge { .. })
+        ));
+        pb.set_merge_policy("mixer", "audio_in", MergePolicy::Sum)
+            .unwrap();
+        let saved = pb.get_patches().to_vec();
+        assert!(saved
+            .iter()
+            .all(|patch| patch.merge == Some(MergePolicy::Sum)));
+
+        // A layout's patches bring their port's policy back with them
+        let mut restored = PatchBay::new();
+        for id in ["mic", "replay", "mixer"] {
+            restored.register_module(pb.get_module(id).unwrap().clone());
+        }
+        for patch in &saved {
+            restored.connect_patch(patch).unwrap();
+        }
+        assert_eq!(restored.merge_policy("mixer", "audio_in"), MergePolicy::Sum);
+    }
+
+    #[test]
+    fn validation_finds_loops_mismatches_and_missing_modules() {
+        let mut pb = PatchBay::new();
+        for id in ["a", "b", "c"] {
+            pb.register_module(make_schema(
+                id,
+                vec![
+                    make_port("in", DataType::Text, PortDirection::Input),
+                    make_port("out", DataType::Text, PortDirection::Output),
+                    make_port("audio_out", DataType::Audio, PortDirection::Output),
+                ],
+            ));
+        }
+        pb.connect("a", "out", "b", "in").unwrap();
+
+        let patch = |id: &str, source: &str, source_port: &str, sink: &str| Patch {
+            id: id.to_string(),
+            source_module: source.to_string(),
+            source_port: source_port.to_string(),
+            sink_module: sink.to_string(),
+            sink_port: "in".to_string(),
+            merge: None,
+            transform: None,
+        };
+        let saved = [
+            patch("p1", "b", "out", "c"),
+            patch("p2", "c", "out", "a"),
+            patch("p3", "a", "audio_out", "c"),
+            patch("p4", "gone", "out", "a"),
+            patch("p5", "c", "out", "c"),
+            Patch {
+                transform: Some(PatchTransform::Replace {
+                    pattern: "(".to_string(),
+                    replacement: String::new(),
+                }),
+                ..patch("p6", "a", "out", "c")
+            },
+        ];
+        let issues = pb.validate_graph(&saved);
+        let ids: Vec<&str> = issues.iter().map(|issue| issue.patch_id.as_str()).collect();
+        assert_eq!(ids, ["p2", "p3", "p4", "p5", "p6"]);
+        assert_eq!(issues[0].to_string(), "p2: Feedback loop: c -> a -> b -> c");
+        assert!(matches!(
+            issues[1].error,
+            PatchBayError::IncompatibleTypes { .. }
+        ));
+        assert!(matches!(&issues[2].error, PatchBayError::ModuleNotFound(id) if id == "gone"));
+        assert_eq!(issues[3].to_string(), "p5: Feedback loop: c -> c");
+        assert!(matches!(
+            issues[4].error,
+            PatchBayError::UnsupportedTransform { .. }
+        ));
+        // Validation only looks
+        assert_eq!(pb.get_patches().len(), 1);
+
+        let id = pb.get_patches()[0].id.clone();
+        assert!(matches!(
+            pb.set_patch_transform(&id, Some(PatchTransform::gain_db(-6.0))),
+            Err(PatchBayError::UnsupportedTransform { .. })
+        ));
+        let lowercase = PatchTransform::Replace {
+            pattern: "[A-Z]".to_string(),
+            replacement: "x".to_string(),
+        };
+        pb.set_patch_transform(&id, Some(lowercase.clone()))
+            .unwrap();
+        assert_eq!(pb.get_patches()[0].transform, Some(lowercase));
+        assert!(matches!(
+            pb.set_patch_transform("missing", None),
+            Err(PatchBayError::PatchNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn replies_may_go_back_to_the_module_that_asked() {
+        use crate::request::{requester_ports, responder_ports, REPLY_OUT, REQUEST_IN};
+        let mut pb = PatchBay::new();
+        pb.register_module(make_schema("script", requester_ports()));
+        pb.register_module(make_schema("llm", responder_ports()));
+        let patch =
+            |id: &str, source: &str, source_port: &str, sink: &str, sink_port: &str| Patch {
+                id: id.to_string(),
+                source_module: source.to_string(),
+                source_port: source_port.to_string(),
+                sink_module: sink.to_string(),
+                sink_port: sink_port.to_string(),
+                merge: None,
+                transform: None,
+            };
+        // Either way round
+        let saved = [
+            patch("reply", "llm", REPLY_OUT, "script", REPLY_IN),
+            patch("ask", "script", "request_out", "llm", REQUEST_IN),
+        ];
+        assert!(pb.validate_graph(&saved).is_empty());
+
+        pb.connect_patch(&saved[1]).unwrap();
+        let suggestions = pb.suggest_patches("llm");
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].sink_port, REPLY_IN);
+    }
 }
@@ -0,0 +1,200 @@
+//! Connection templates: the standard chains, wired in one go
+//!
+//! Applying a template makes whichever of its patches are missing and
+//! leaves the rest of the patch bay alone, so it also repairs a rig whose
+//! chain was half unpatched. It wires nothing unless every module the chain
+//! needs is registered.
+
+use crate::PatchBay;
+
+/// A chain of patches between the built-in modules
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PatchTemplate {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Source module and port, then sink module and port
+    links: &'static [[&'static str; 4]],
+}
+
+pub const PATCH_TEMPLATES: [PatchTemplate; 2] = [
+    PatchTemplate {
+        name: "audio",
+        description: "Microphone through the DSP to the scope and the speakers",
+        links: &[
+            ["audio_input", "audio_out", "audio_dsp", "audio_in"],
+            ["audio_dsp", "audio_out", "audio_viz", "audio_in"],
+            ["audio_dsp", "audio_out", "audio_output", "audio_in"],
+        ],
+    },
+    PatchTemplate {
+        name: "transcription",
+        description: "Microphone through speech-to-text, told of silences, to the transcript",
+        links: &[
+            ["audio_input", "audio_out", "silence", "audio_in"],
+            ["audio_input", "audio_out", "speech_to_text", "audio_in"],
+            ["silence", "intent_out", "speech_to_text", "silence_in"],
+            ["speech_to_text", "text_out", "transcript", "stt_in"],
+        ],
+    },
+];
+
+/// What applying a template did, each patch as `module:port -> module:port`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AutoWireReport {
+    pub made: Vec<String>,
+    pub existing: Vec<String>,
+    /// Needed modules that aren't registered; nothing is made while any are
+    pub missing: Vec<String>,
+    /// Patches the patch bay refused, with why
+    pub failed: Vec<String>,
+}
+
+impl AutoWireReport {
+    /// One line for a status bar
+    pub fn summary(&self, template: &PatchTemplate) -> String {
+        if !self.missing.is_empty() {
+            return format!(
+                "Can't wire the {} chain without {}",
+                template.name,
+                self.missing.join(", ")
+            );
+        }
+        let mut summary = format!(
+            "Wired the {} chain: {} made, {} already there",
+            template.name,
+            self.made.len(),
+            self.existing.len()
+        );
+        if !self.failed.is_empty() {
+            summary.push_str(&format!(", {} failed", self.failed.len()));
+        }
+        summary
+    }
+}
+
+impl PatchTemplate {
+    pub fn find(name: &str) -> Option<&'static PatchTemplate> {
+        PATCH_TEMPLATES
+            .iter()
+            .find(|template| template.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Modules the chain runs through, in order
+    pub fn modules(&self) -> Vec<&'static str> {
+        let mut modules = Vec::new();
+        for [source, _, sink, _] in self.links {
+            for module in [*source, *sink] {
+                if !modules.contains(&module) {
+                    modules.push(module);
+                }
+            }
+        }
+        modules
+    }
+
+    /// Make the chain's missing patches in `patch_bay`
+    pub fn apply(&self, patch_bay: &mut PatchBay) -> AutoWireReport {
+        let mut report = AutoWireReport {
+            missing: self
+                .modules()
+                .into_iter()
+                .filter(|module| patch_bay.get_module(module).is_none())
+                .map(str::to_string)
+                .collect(),
+            ..Default::default()
+        };
+        if !report.missing.is_empty() {
+            return report;
+        }
+        for &[source, source_port, sink, sink_port] in self.links {
+            let patch = format!("{}:{} -> {}:{}", source, source_port, sink, sink_port);
+            let exists = patch_bay.get_patches().iter().any(|p| {
+                p.source_module == source
+                    && p.source_port == source_port
+                    && p.sink_module == sink
+                    && p.sink_port == sink_port
+            });
+            if exists {
+                report.existing.push(patch);
+                continue;
+            }
+            match patch_bay.connect(source, source_port, sink, sink_port) {
+                Ok(_) => report.made.push(patch),
+                Err(e) => report.failed.push(format!("{} ({})", patch, e)),
+            }
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DataType, ModuleSchema, Port, PortDirection};
+
+    fn register(patch_bay: &mut PatchBay, id: &str, ports: &[(&str, DataType, PortDirection)]) {
+        patch_bay.register_module(ModuleSchema {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            ports: ports
+                .iter()
+                .map(|(port, data_type, direction)| Port {
+                    id: port.to_string(),
+                    label: port.to_string(),
+                    data_type: data_type.clone(),
+                    direction: direction.clone(),
+                })
+                .collect(),
+            settings_schema: None,
+            docs: None,
+        });
+    }
+
+    #[test]
+    fn applying_fills_in_missing_patches_once_every_module_is_there() {
+        use DataType::Audio;
+        use PortDirection::{Input, Output};
+        let audio = PatchTemplate::find("Audio").unwrap();
+        let mut patch_bay = PatchBay::new();
+        register(
+            &mut patch_bay,
+            "audio_input",
+            &[("audio_out", Audio, Output)],
+        );
+        register(
+            &mut patch_bay,
+            "audio_dsp",
+            &[("audio_in", Audio, Input), ("audio_out", Audio, Output)],
+        );
+        register(&mut patch_bay, "audio_viz", &[("audio_in", Audio, Input)]);
+
+        let report = audio.apply(&mut patch_bay);
+        assert_eq!(report.missing, ["audio_output"]);
+        assert!(patch_bay.get_patches().is_empty());
+        assert_eq!(
+            report.summary(audio),
+            "Can't wire the audio chain without audio_output"
+        );
+
+        register(
+            &mut patch_bay,
+            "audio_output",
+            &[("audio_in", Audio, Input)],
+        );
+        patch_bay
+            .connect("audio_dsp", "audio_out", "audio_viz", "audio_in")
+            .unwrap();
+        let report = audio.apply(&mut patch_bay);
+        assert_eq!(report.made.len(), 2);
+        assert_eq!(
+            report.existing,
+            ["audio_dsp:audio_out -> audio_viz:audio_in"]
+        );
+        assert_eq!(patch_bay.get_patches().len(), 3);
+        assert_eq!(
+            report.summary(audio),
+            "Wired the audio chain: 2 made, 1 already there"
+        );
+    }
+}
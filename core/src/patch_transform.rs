@@ -0,0 +1,119 @@
+//! Inline transforms on single patches
+//!
+//! A patch can scale the audio or rewrite the text crossing it (see
+//! [`PatchTransform`]), so one cable can be turned down or cleaned up without
+//! a module in between. The router applies them after fan-out, so other
+//! patches from the same port get the signal as it was sent.
+
+use crate::{PatchTransform, Signal};
+use regex::Regex;
+use std::collections::HashMap;
+
+/// Applies [`PatchTransform`]s, keeping each replace pattern compiled
+#[derive(Debug, Default)]
+pub struct PatchTransformer {
+    patterns: HashMap<String, Regex>,
+}
+
+impl PatchTransformer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Why `pattern` can't be used for [`PatchTransform::Replace`]
+    pub fn check_pattern(pattern: &str) -> Result<(), String> {
+        Regex::new(pattern).map(|_| ()).map_err(|e| e.to_string())
+    }
+
+    /// `signal` as `transform` leaves it; signals of other kinds, and text
+    /// when the pattern doesn't compile, pass through unchanged
+    pub fn apply(&mut self, transform: &PatchTransform, signal: Signal) -> Signal {
+        match transform {
+            PatchTransform::Gain { gain } => match signal.into_owned_audio() {
+                Signal::Audio {
+                    sample_rate,
+                    channels,
+                    timestamp_us,
+                    mut data,
+                } => {
+                    for sample in &mut data {
+                        *sample *= gain;
+                    }
+                    Signal::Audio {
+                        sample_rate,
+                        channels,
+                        timestamp_us,
+                        data,
+                    }
+                }
+                other => other,
+            },
+            PatchTransform::Replace {
+                pattern,
+                replacement,
+            } => {
+                let Signal::Text(text) = signal else {
+                    return signal;
+                };
+                let regex = match self.patterns.get(pattern) {
+                    Some(regex) => regex,
+                    None => match Regex::new(pattern) {
+                        Ok(regex) => self.patterns.entry(pattern.clone()).or_insert(regex),
+                        Err(e) => {
+                            log::warn!("Patch transform pattern {:?}: {}", pattern, e);
+                            return Signal::Text(text);
+                        }
+                    },
+                };
+                Signal::Text(regex.replace_all(&text, replacement.as_str()).into_owned())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gain_scales_audio_and_replace_rewrites_text() {
+        let mut transformer = PatchTransformer::new();
+        let half = PatchTransform::Gain { gain: 0.5 };
+        let audio = transformer.apply(
+            &half,
+            Signal::Audio {
+                sample_rate: 48_000,
+                channels: 1,
+                timestamp_us: 7,
+                data: vec![1.0, -0.5],
+            },
+        );
+        assert_eq!(audio.audio().unwrap().data, [0.5, -0.25]);
+        assert_eq!(audio.audio().unwrap().timestamp_us, 7);
+        assert!(matches!(
+            transformer.apply(&half, Signal::Text("hi".into())),
+            Signal::Text(text) if text == "hi"
+        ));
+
+        let fillers = PatchTransform::Replace {
+            pattern: r"\b(um|uh),? ".to_string(),
+            replacement: String::new(),
+        };
+        assert!(matches!(
+            transformer.apply(&fillers, Signal::Text("um, so uh we go".into())),
+            Signal::Text(text) if text == "so we go"
+        ));
+        let swap = PatchTransform::Replace {
+            pattern: r"(\w+) (\w+)".to_string(),
+            replacement: "$2 $1".to_string(),
+        };
+        assert!(matches!(
+            transformer.apply(&swap, Signal::Text("hello world".into())),
+            Signal::Text(text) if text == "world hello"
+        ));
+        assert!(PatchTransformer::check_pattern("(").is_err());
+
+        assert_eq!(PatchTransform::gain_db(-6.0).label(), "-6.0 dB");
+        assert_eq!(fillers.label(), r"s/\b(um|uh),? //");
+    }
+}
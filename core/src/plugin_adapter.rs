@@ -1,13 +1,51 @@
-use crate::{ControlSignal, ModuleRuntime, ModuleSchema, PluginLibrary, RoutedSignal, Signal};
+use crate::host_gpu::{pack_texture_handle, GpuSession, HostGpu};
+use crate::plugin_codec;
+use crate::plugin_quota::{PluginQuota, QuotaEnforcer, QuotaMetrics, QuotaViolation};
+use crate::{
+    ControlSignal, GpuTextureHandle, ModuleRuntime, ModuleSchema, PluginLibrary, RoutedSignal,
+    Signal,
+};
 use async_trait::async_trait;
 use magnolia_plugin_abi::*;
 use std::ffi::CStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
 use tokio::sync::mpsc;
 
+/// Host-side accounting of plugin-allocated payloads.
+///
+/// Every non-null payload the host receives from `poll_signal` or
+/// `consume_signal` must go back through the plugin's `free_buffer`; a
+/// non-zero [`outstanding`](Self::outstanding) count means memory was lost.
+#[derive(Debug, Default)]
+pub struct PluginBufferLedger {
+    received: AtomicU64,
+    returned: AtomicU64,
+}
+
+impl PluginBufferLedger {
+    pub fn received(&self) -> u64 {
+        self.received.load(Ordering::Relaxed)
+    }
+
+    pub fn returned(&self) -> u64 {
+        self.returned.load(Ordering::Relaxed)
+    }
+
+    pub fn outstanding(&self) -> u64 {
+        self.received().saturating_sub(self.returned())
+    }
+}
+
 pub struct PluginModuleAdapter {
+    // Declared before `gpu_session` so the instance is destroyed before its textures.
     plugin: PluginLibrary,
     id_cache: String,
     name_cache: String,
+    ledger: Arc<PluginBufferLedger>,
+    gpu_session: Option<Arc<GpuSession>>,
+    quota: QuotaEnforcer,
 }
 
 impl PluginModuleAdapter {
@@ -26,197 +64,119 @@ impl PluginModuleAdapter {
             plugin,
             id_cache,
             name_cache,
+            ledger: Arc::new(PluginBufferLedger::default()),
+            gpu_session: None,
+            quota: QuotaEnforcer::new(PluginQuota::default()),
         }
     }
 
-    fn encode_signal(&self, signal: &Signal) -> SignalBuffer {
-        // Convert Rust Signal to C SignalBuffer
-        match signal {
-            Signal::Text(text) => {
-                let cstring = std::ffi::CString::new(text.as_str()).unwrap_or_default();
-                SignalBuffer {
-                    signal_type: SignalType::Text as u32,
-                    value: SignalValue {
-                        ptr: cstring.into_raw() as *mut _,
-                    },
-                    size: 0, // null-terminated
-                    param: 0,
-                }
-            }
-            Signal::Audio {
-                sample_rate,
-                channels,
-                timestamp_us: _,
-                data,
-            } => {
-                // Transfer ownership of data to the buffer
-                // We clone the data here because we can't easily take ownership from &Signal
-                // But in a real hot-path, efficient movement is key.
-                // For now, adapter clones.
-                let mut data_clone = data.clone();
-                let ptr = data_clone.as_mut_ptr();
-                let len = data_clone.len();
-                std::mem::forget(data_clone); // Leak it (ownership passed to buffer)
-
-                // Pack metadata into param: High 32 = Sample Rate, Low 32 = Channels
-                let param = ((*sample_rate as u64) << 32) | (*channels as u64);
-
-                SignalBuffer {
-                    signal_type: SignalType::Audio as u32,
-                    value: SignalValue { ptr: ptr as *mut _ },
-                    size: len as u64,
-                    param,
-                }
-            }
-            Signal::GpuContext { device, queue } => SignalBuffer {
-                signal_type: SignalType::GpuContext as u32,
-                value: SignalValue {
-                    ptr: *device as *mut _,
-                },
-                size: 0,
-                param: *queue as u64,
-            },
-            Signal::Texture {
-                handle,
-                start_time: _,
-            } => {
-                // We only support sending Handles derived from local pointers back to plugins
-                // which is rare (usually host sends Handles to consumers).
-                // Plugins generating textures (kamea) send TO host.
-                // Sending Texture TO plugin?
-                // If a plugin consumes textures, it expects a Handle ID.
-                // The ABI SignalValue has `gpu_id`.
-                SignalBuffer {
-                    signal_type: SignalType::Texture as u32,
-                    value: SignalValue { gpu_id: handle.id },
-                    size: 0,
-                    param: 0,
-                }
-            }
-            Signal::Pulse => SignalBuffer::empty(),
-            // TODO: extensive signal mapping
-            _ => SignalBuffer::empty(),
+    /// Replace the default resource limits for this plugin.
+    pub fn with_quota(mut self, quota: PluginQuota) -> Self {
+        self.quota = QuotaEnforcer::new(quota);
+        self
+    }
+
+    /// Shared view of this plugin's quota violations.
+    pub fn quota_metrics(&self) -> Arc<QuotaMetrics> {
+        self.quota.metrics()
+    }
+
+    fn watchdog(&mut self, call: &'static str, started: Instant) {
+        let now = Instant::now();
+        if let Err(violation) = self.quota.record_call(call, now - started, now) {
+            self.report(&violation);
         }
     }
 
-    unsafe fn decode_signal(&self, buffer: &SignalBuffer) -> Option<Signal> {
-        match buffer.signal_type {
-            t if t == SignalType::Text as u32 => {
-                if buffer.value.ptr.is_null() {
-                    return None;
-                }
-                let cstr = CStr::from_ptr(buffer.value.ptr as *const i8);
-                Some(Signal::Text(cstr.to_string_lossy().into_owned()))
-            }
-            t if t == SignalType::Audio as u32 => {
-                if buffer.value.ptr.is_null() {
-                    return None;
-                }
-                // Unpack metadata
-                let sample_rate = (buffer.param >> 32) as u32;
-                let channels = (buffer.param & 0xFFFFFFFF) as u16;
-                let size = buffer.size as usize;
-
-                // Reconstruct Vec from raw parts
-                // Note: We copy here to be safe and own the data in the Signal
-                // effectively treating the input buffer as borrowed.
-                // BUT, to avoid double-free or leak, we need to know who owns it.
-                // The contract is: Consumer takes ownership if it returns ptr?
-                // Or Consumer copies?
-                // The current adapter implementation assumes it TAKES ownership for freeing
-                // but decoding usually returns a new struct.
-
-                let slice = std::slice::from_raw_parts(buffer.value.ptr as *const f32, size);
-                let data = slice.to_vec(); // Copy
-
-                Some(Signal::Audio {
-                    sample_rate,
-                    channels,
-                    timestamp_us: 0,
-                    data,
-                })
+    /// Meter an outgoing signal, dropping it if it breaks the quota.
+    fn admit(&mut self, signal: Signal) -> Option<Signal> {
+        match self.quota.admit_signal(&signal, Instant::now()) {
+            Ok(()) => Some(signal),
+            Err(violation) => {
+                self.report(&violation);
+                None
             }
-            t if t == SignalType::Astrology as u32 => {
-                // Pointer to JSON string
-                if buffer.value.ptr.is_null() {
-                    return Some(Signal::Pulse);
-                }
+        }
+    }
 
-                let json_str = unsafe {
-                    // We take ownership of the string to drop it
-                    let len = buffer.size as usize;
-                    let vec = Vec::from_raw_parts(buffer.value.ptr as *mut u8, len, len);
-                    String::from_utf8_lossy(&vec).to_string()
-                };
+    fn report(&self, violation: &QuotaViolation) {
+        if let QuotaViolation::OversizedSignal { .. } = violation {
+            log::warn!("Plugin {} {}; signal dropped", self.id_cache, violation);
+        } else {
+            log::warn!(
+                "Plugin {} {}; throttling for {:?}",
+                self.id_cache,
+                violation,
+                self.quota.quota().throttle_for
+            );
+        }
+    }
 
-                // Parse JSON back to AstrologyData
-                use magnolia_signals::AstrologyData;
-                if let Ok(data) = serde_json::from_str::<AstrologyData>(&json_str) {
-                    Some(Signal::Astrology(data))
-                } else if let Ok(_val) = serde_json::from_str::<serde_json::Value>(&json_str) {
-                    // Legacy fallback if plugin sends flat structure?
-                    // Current plugin sends: {"sun_sign": ..., "planetary_positions": ...}
-                    // This matches AstrologyData struct!
-                    // So direct deserialization should work if fields match.
-                    // Just in case, manual extract if needed, but strict serde is better.
-                    // Let's assume strict for now.
-                    log::warn!("Failed to strict parse AstrologyData - plugin mismatch?");
-                    Some(Signal::Pulse)
-                } else {
-                    log::warn!("Failed to parse astrology JSON from plugin");
-                    Some(Signal::Pulse)
-                }
-            }
-            t if t == SignalType::GpuContext as u32 => {
-                let device = buffer.value.ptr as usize;
-                let queue = buffer.param as usize;
-                Some(Signal::GpuContext { device, queue })
-            }
-            t if t == SignalType::Texture as u32 => {
-                // Plugin sends:
-                // value.ptr = *wgpu::TextureView
+    /// Give the plugin access to host GPU services through a fresh session.
+    pub fn attach_gpu(&mut self, gpu: &Arc<HostGpu>) {
+        let session = gpu.session(&self.id_cache);
+        let api = session.api();
+        unsafe { (self.plugin.vtable.attach_gpu)(self.plugin.instance, &api) };
+        self.gpu_session = Some(session);
+    }
+
+    /// Revoke GPU access; the plugin's textures are released with the session.
+    pub fn detach_gpu(&mut self) {
+        if self.gpu_session.take().is_some() {
+            unsafe { (self.plugin.vtable.attach_gpu)(self.plugin.instance, std::ptr::null()) };
+        }
+    }
 
-                // TODO: Re-enable texture sharing when wgpu::TextureView Clone issue is resolved.
-                // Current ambiguity: TextureView (alias TextureViewHandle) is !Clone !Copy.
-                log::warn!("Signal::Texture received but texture sharing is temporarily disabled due to wgpu type issues.");
+    /// Only pass on texture handles this plugin actually owns, with host-known dimensions.
+    fn vet_texture(&self, signal: Signal) -> Option<Signal> {
+        let Signal::Texture { handle, start_time } = signal else {
+            return Some(signal);
+        };
+        let packed = pack_texture_handle(handle.id, handle.generation);
+        match self
+            .gpu_session
+            .as_ref()
+            .and_then(|s| s.texture_size(packed))
+        {
+            Some((width, height)) => Some(Signal::Texture {
+                handle: GpuTextureHandle {
+                    width,
+                    height,
+                    ..handle
+                },
+                start_time,
+            }),
+            None => {
+                log::warn!(
+                    "Plugin {} announced texture {} it does not own",
+                    self.id_cache,
+                    packed
+                );
                 None
-                /*
-                use wgpu as wgpu_crate;
-                unsafe {
-                    let view_ptr = buffer.value.ptr as *const wgpu_crate::TextureView;
-                    if !view_ptr.is_null() {
-                        // Clone the view (Arc incr)
-                        // ERROR: TextureView not Clone or Copy
-                        // let view = (*view_ptr).clone();
-
-                        // Register in our map
-                        // let (id, generation) = self.view_map.insert(view);
-
-                        let width = (buffer.param >> 32) as u32;
-                        let height = (buffer.param & 0xFFFFFFFF) as u32;
-
-                        let handle = GpuTextureHandle {
-                            id,
-                            generation,
-                            width,
-                            height,
-                        };
-
-                        Some(Signal::Texture {
-                            handle,
-                            start_time: 0.0,
-                        })
-                    } else {
-                        None
-                    }
-                }
-                */
             }
-            t if t == SignalType::Pulse as u32 => Some(Signal::Pulse),
-            _ => None,
         }
     }
 
+    /// Shared view of this plugin's buffer accounting.
+    pub fn buffer_ledger(&self) -> Arc<PluginBufferLedger> {
+        self.ledger.clone()
+    }
+
+    /// Copy a plugin-owned buffer into a host signal and hand the payload back.
+    ///
+    /// # Safety
+    /// `buffer` must have just been filled by this plugin's `poll_signal` or
+    /// returned from its `consume_signal`, and not handed back already.
+    unsafe fn take_plugin_buffer(&self, buffer: &mut SignalBuffer) -> Option<Signal> {
+        let signal = plugin_codec::decode_signal(buffer).and_then(|s| self.vet_texture(s));
+        if !buffer.value.ptr.is_null() {
+            self.ledger.received.fetch_add(1, Ordering::Relaxed);
+            (self.plugin.vtable.free_buffer)(self.plugin.instance, buffer);
+            self.ledger.returned.fetch_add(1, Ordering::Relaxed);
+        }
+        signal
+    }
+
     // === HOT-RELOAD LIFECYCLE HOOKS ===
 
     /// Called before the plugin is unloaded during hot-reload.
@@ -226,6 +186,16 @@ impl PluginModuleAdapter {
 
         // Disable the plugin to stop it from processing
         self.set_enabled(false);
+        self.detach_gpu();
+
+        let outstanding = self.ledger.outstanding();
+        if outstanding > 0 {
+            log::warn!(
+                "Plugin {} unloading with {} buffers not returned to its allocator",
+                self.id_cache,
+                outstanding
+            );
+        }
 
         // Give it a moment to finish any pending work
         // In a real implementation, you might want to:
@@ -290,6 +260,7 @@ impl ModuleRuntime for PluginModuleAdapter {
             description: format!("Plugin: {}", self.name_cache),
             ports: vec![], // TODO: Extend ABI to support port definitions
             settings_schema,
+            docs: self.plugin.docs.clone(),
         }
     }
 
@@ -301,35 +272,56 @@ impl ModuleRuntime for PluginModuleAdapter {
         unsafe { (self.plugin.vtable.set_enabled)(self.plugin.instance, enabled) }
     }
 
+    fn settings_version(&self) -> u32 {
+        match self.plugin.schema {
+            Some(schema_ptr) if !schema_ptr.is_null() => unsafe { (*schema_ptr).settings_version },
+            _ => 0,
+        }
+    }
+
+    fn migrate_settings(
+        &self,
+        old_version: u32,
+        settings: serde_json::Value,
+    ) -> Option<serde_json::Value> {
+        let migrate = self.plugin.vtable.migrate_settings?;
+        let c_str = std::ffi::CString::new(settings.to_string()).ok()?;
+        let migrated = unsafe {
+            let out = migrate(self.plugin.instance, old_version, c_str.as_ptr());
+            if out.is_null() {
+                return None;
+            }
+            // Copied out before anything else calls into the instance
+            CStr::from_ptr(out).to_string_lossy().into_owned()
+        };
+        serde_json::from_str(&migrated).ok()
+    }
+
     async fn run(&mut self, mut inbox: mpsc::Receiver<Signal>, outbox: mpsc::Sender<RoutedSignal>) {
         let mut interval = tokio::time::interval(tokio::time::Duration::from_millis(10));
 
         loop {
             interval.tick().await;
+            let throttled = self.quota.is_throttled(Instant::now());
 
             // Poll plugin for outgoing signals
             // We must process and free the buffer BEFORE awaiting anything,
             // because SignalBuffer is !Send (contains raw pointers)
-            let maybe_signal = unsafe {
-                let mut signal_buf = SignalBuffer::empty();
-                let mut result = None;
-
-                if (self.plugin.vtable.poll_signal)(self.plugin.instance, &mut signal_buf) {
-                    result = self.decode_signal(&signal_buf);
-
-                    // Free the buffer data if allocated by the plugin
-                    if !signal_buf.value.ptr.is_null() {
-                        if signal_buf.signal_type == SignalType::Text as u32 {
-                            let _ = std::ffi::CString::from_raw(signal_buf.value.ptr as *mut i8);
-                        } else if signal_buf.signal_type == SignalType::Audio as u32 {
-                            let size = signal_buf.size as usize;
-                            // Assume capacity == length (plugin must ensure this)
-                            let _ =
-                                Vec::from_raw_parts(signal_buf.value.ptr as *mut f32, size, size);
-                        }
+            let maybe_signal = if throttled {
+                None
+            } else {
+                let started = Instant::now();
+                let result = unsafe {
+                    let mut signal_buf = SignalBuffer::empty();
+                    let mut result = None;
+
+                    if (self.plugin.vtable.poll_signal)(self.plugin.instance, &mut signal_buf) {
+                        result = self.take_plugin_buffer(&mut signal_buf);
                     }
-                }
-                result
+                    result
+                };
+                self.watchdog("poll_signal", started);
+                result.and_then(|signal| self.admit(signal))
             };
 
             if let Some(signal) = maybe_signal {
@@ -349,41 +341,21 @@ impl ModuleRuntime for PluginModuleAdapter {
                     continue; // Skip consume_signal for this special control message
                 }
 
+                if self.quota.is_throttled(Instant::now()) {
+                    self.quota.record_drop();
+                    continue;
+                }
+
+                let started = Instant::now();
                 let maybe_output = unsafe {
-                    let signal_buf = self.encode_signal(&signal);
+                    let signal_buf = plugin_codec::encode_signal(&signal);
                     let output_ptr =
                         (self.plugin.vtable.consume_signal)(self.plugin.instance, &signal_buf);
-                    // We allocated signal_buf.data in encode_signal, we must free it
-                    if !signal_buf.value.ptr.is_null() {
-                        if signal_buf.signal_type == SignalType::Text as u32 {
-                            let _ = std::ffi::CString::from_raw(signal_buf.value.ptr as *mut i8);
-                        } else if signal_buf.signal_type == SignalType::Audio as u32 {
-                            let size = signal_buf.size as usize;
-                            std::mem::drop(Vec::from_raw_parts(
-                                signal_buf.value.ptr as *mut f32,
-                                size,
-                                size,
-                            ));
-                        }
-                    }
+                    plugin_codec::release_host_buffer(&signal_buf);
 
                     // Check if plugin returned an output signal
                     if !output_ptr.is_null() {
-                        let output_signal = self.decode_signal(&*output_ptr);
-                        // Free the output buffer that the plugin allocated
-                        if !(*output_ptr).value.ptr.is_null() {
-                            if (*output_ptr).signal_type == SignalType::Text as u32 {
-                                let _ =
-                                    std::ffi::CString::from_raw((*output_ptr).value.ptr as *mut i8);
-                            } else if (*output_ptr).signal_type == SignalType::Audio as u32 {
-                                let size = (*output_ptr).size as usize;
-                                let _ = Vec::from_raw_parts(
-                                    (*output_ptr).value.ptr as *mut f32,
-                                    size,
-                                    size,
-                                );
-                            }
-                        }
+                        let output_signal = self.take_plugin_buffer(&mut *output_ptr);
                         // Free the SignalBuffer struct itself (plugin allocated it)
                         let _ = Box::from_raw(output_ptr);
                         output_signal
@@ -391,9 +363,10 @@ impl ModuleRuntime for PluginModuleAdapter {
                         None
                     }
                 };
+                self.watchdog("consume_signal", started);
 
                 // Send any output signal from consume_signal
-                if let Some(output) = maybe_output {
+                if let Some(output) = maybe_output.and_then(|signal| self.admit(signal)) {
                     let routed = RoutedSignal::new(self.id_cache.clone(), "default", output);
                     let _ = outbox.send(routed).await;
                 }
@@ -0,0 +1,201 @@
+//! Conversion between host `Signal`s and the plugin ABI `SignalBuffer`.
+//!
+//! Everything arriving from a plugin is treated as untrusted: pointers may be
+//! null or misaligned, sizes may be absurd, and the type tag may not match the
+//! payload. Decoding never takes ownership: plugin payloads are handed back to
+//! the plugin's own `free_buffer` entry afterwards, valid or not, since only the
+//! plugin knows how it allocated them.
+
+use crate::host_gpu::{pack_texture_handle, unpack_texture_handle};
+use crate::{GpuTextureHandle, Signal};
+use magnolia_plugin_abi::{SignalBuffer, SignalType, SignalValue};
+use std::ffi::{c_char, CString};
+
+/// Largest text payload accepted from a plugin, in bytes.
+pub const MAX_TEXT_BYTES: usize = 16 * 1024 * 1024;
+/// Largest audio payload accepted from a plugin, in samples.
+pub const MAX_AUDIO_SAMPLES: usize = 16 * 1024 * 1024;
+/// Largest JSON payload (e.g. astrology) accepted from a plugin, in bytes.
+pub const MAX_JSON_BYTES: usize = 4 * 1024 * 1024;
+
+/// Encode a host signal for a plugin. The buffer owns host memory and must be
+/// passed to [`release_host_buffer`] once the plugin call returns.
+pub fn encode_signal(signal: &Signal) -> SignalBuffer {
+    match signal {
+        Signal::Text(text) => {
+            let cstring = CString::new(text.as_str()).unwrap_or_default();
+            SignalBuffer {
+                signal_type: SignalType::Text as u32,
+                value: SignalValue {
+                    ptr: cstring.into_raw() as *mut _,
+                },
+                size: 0, // null-terminated
+                param: 0,
+            }
+        }
+        Signal::Audio { .. } | Signal::SharedAudio { .. } => {
+            let audio = signal.audio().expect("audio signal");
+            // boxed slice guarantees capacity == length for the release path
+            let samples = audio.data.to_vec().into_boxed_slice();
+            let len = samples.len();
+            let ptr = Box::into_raw(samples) as *mut f32;
+
+            // Pack metadata into param: High 32 = Sample Rate, Low 32 = Channels
+            let param = ((audio.sample_rate as u64) << 32) | (audio.channels as u64);
+
+            SignalBuffer {
+                signal_type: SignalType::Audio as u32,
+                value: SignalValue { ptr: ptr as *mut _ },
+                size: len as u64,
+                param,
+            }
+        }
+        Signal::Texture {
+            handle,
+            start_time: _,
+        } => SignalBuffer {
+            signal_type: SignalType::Texture as u32,
+            value: SignalValue {
+                gpu_id: pack_texture_handle(handle.id, handle.generation),
+            },
+            size: 0,
+            param: ((handle.width as u64) << 32) | (handle.height as u64),
+        },
+        Signal::Pulse => SignalBuffer::empty(),
+        // TODO: extensive signal mapping
+        _ => SignalBuffer::empty(),
+    }
+}
+
+/// Free the host allocation behind a buffer produced by [`encode_signal`].
+///
+/// # Safety
+/// `buffer` must come from [`encode_signal`] and not have been released already.
+pub unsafe fn release_host_buffer(buffer: &SignalBuffer) {
+    let ptr = buffer.value.ptr;
+    if ptr.is_null() {
+        return;
+    }
+    if buffer.signal_type == SignalType::Text as u32 {
+        drop(CString::from_raw(ptr as *mut c_char));
+    } else if buffer.signal_type == SignalType::Audio as u32 {
+        let samples = std::ptr::slice_from_raw_parts_mut(ptr as *mut f32, buffer.size as usize);
+        drop(Box::from_raw(samples));
+    }
+}
+
+/// Read a NUL-terminated string of at most `limit` bytes without reading past the terminator.
+unsafe fn bounded_c_str<'a>(ptr: *const u8, limit: usize) -> Option<&'a [u8]> {
+    let mut len = 0;
+    while len < limit {
+        if *ptr.add(len) == 0 {
+            return Some(std::slice::from_raw_parts(ptr, len));
+        }
+        len += 1;
+    }
+    None
+}
+
+/// Decode a plugin-provided buffer into a host signal, copying the payload.
+///
+/// Returns `None` for malformed buffers instead of trusting them. The buffer
+/// is only borrowed; the caller returns it through the plugin's `free_buffer`.
+///
+/// # Safety
+/// A non-null `value.ptr` that passes the checks here must point to memory
+/// valid for the declared payload: `size` bytes (or up to the NUL terminator
+/// when `size == 0`) for text and JSON, `size` samples for audio.
+pub unsafe fn decode_signal(buffer: &SignalBuffer) -> Option<Signal> {
+    match buffer.signal_type {
+        t if t == SignalType::Text as u32 => {
+            let ptr = buffer.value.ptr as *const u8;
+            if ptr.is_null() {
+                return None;
+            }
+            // `size` is an optional upper bound; legacy plugins send 0 and rely on the NUL.
+            let limit = match buffer.size as usize {
+                0 => MAX_TEXT_BYTES,
+                size => size.min(MAX_TEXT_BYTES),
+            };
+            let Some(bytes) = bounded_c_str(ptr, limit) else {
+                log::warn!(
+                    "Plugin text signal exceeds {} bytes or is unterminated",
+                    limit
+                );
+                return None;
+            };
+            Some(Signal::Text(String::from_utf8_lossy(bytes).into_owned()))
+        }
+        t if t == SignalType::Audio as u32 => {
+            let ptr = buffer.value.ptr as *const f32;
+            if ptr.is_null() {
+                return None;
+            }
+            // Unpack metadata
+            let sample_rate = (buffer.param >> 32) as u32;
+            let channels = (buffer.param & 0xFFFFFFFF) as u16;
+            let size = buffer.size as usize;
+            if !ptr.is_aligned()
+                || buffer.size > MAX_AUDIO_SAMPLES as u64
+                || sample_rate == 0
+                || channels == 0
+                || !size.is_multiple_of(channels as usize)
+            {
+                log::warn!(
+                    "Rejecting malformed plugin audio: {} samples, {} Hz, {} ch",
+                    buffer.size,
+                    sample_rate,
+                    channels
+                );
+                return None;
+            }
+            let data = std::slice::from_raw_parts(ptr, size).to_vec();
+
+            Some(Signal::Audio {
+                sample_rate,
+                channels,
+                timestamp_us: 0,
+                data,
+            })
+        }
+        t if t == SignalType::Astrology as u32 => {
+            // Pointer to JSON string
+            let ptr = buffer.value.ptr as *const u8;
+            if ptr.is_null() {
+                return Some(Signal::Pulse);
+            }
+            if buffer.size > MAX_JSON_BYTES as u64 {
+                log::warn!(
+                    "Rejecting {} byte astrology payload from plugin",
+                    buffer.size
+                );
+                return None;
+            }
+            let bytes = std::slice::from_raw_parts(ptr, buffer.size as usize);
+
+            use magnolia_signals::AstrologyData;
+            match serde_json::from_slice::<AstrologyData>(bytes) {
+                Ok(data) => Some(Signal::Astrology(data)),
+                Err(_) => {
+                    log::warn!("Failed to parse astrology JSON from plugin");
+                    Some(Signal::Pulse)
+                }
+            }
+        }
+        t if t == SignalType::Texture as u32 => {
+            // Opaque HostGpuApi handle; ownership is checked against the plugin's session.
+            let (id, generation) = unpack_texture_handle(buffer.value.gpu_id)?;
+            Some(Signal::Texture {
+                handle: GpuTextureHandle {
+                    id,
+                    generation,
+                    width: (buffer.param >> 32) as u32,
+                    height: (buffer.param & 0xFFFFFFFF) as u32,
+                },
+                start_time: 0.0,
+            })
+        }
+        t if t == SignalType::Pulse as u32 => Some(Signal::Pulse),
+        _ => None,
+    }
+}
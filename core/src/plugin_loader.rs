@@ -8,10 +8,14 @@ use std::path::{Path, PathBuf};
 /// Loaded plugin library with manifest and vtable
 pub struct PluginLibrary {
     _lib: Library,
+    /// File the library was loaded from
+    pub path: PathBuf,
     pub manifest: PluginManifest,
     pub vtable: &'static ModuleRuntimeVTable,
     pub instance: *mut c_void,
     pub schema: Option<*const ModuleSchemaAbi>,
+    /// From the optional docs export
+    pub docs: Option<crate::ModuleDocs>,
 }
 
 // Safety: The plugin instance must be thread-safe for the operations called on it.
@@ -66,6 +70,22 @@ impl PluginLibrary {
             None
         };
 
+        // Get docs (optional)
+        let docs = match lib.get::<PluginGetDocsFn>(PLUGIN_DOCS_SYMBOL) {
+            Ok(docs_fn) => {
+                let docs_ptr = docs_fn();
+                if docs_ptr.is_null() {
+                    None
+                } else {
+                    let json = CStr::from_ptr(docs_ptr).to_string_lossy();
+                    serde_json::from_str(&json)
+                        .map_err(|e| log::warn!("Ignoring plugin docs: {}", e))
+                        .ok()
+                }
+            }
+            Err(_) => None,
+        };
+
         // Create instance
         let create_fn: Symbol<PluginCreateFn> = lib
             .get(PLUGIN_CREATE_SYMBOL)
@@ -82,10 +102,12 @@ impl PluginLibrary {
 
         Ok(Self {
             _lib: lib,
+            path: path.to_path_buf(),
             manifest,
             vtable,
             instance,
             schema,
+            docs,
         })
     }
 
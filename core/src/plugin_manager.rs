@@ -1,11 +1,21 @@
 use anyhow::Result;
 use log::{error, info};
 use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::{mpsc, Arc, RwLock};
 
 use crate::plugin_loader::{PluginLibrary, PluginLoader};
 
+/// Plugin file changes observed by the hot-reload watcher
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PluginEvent {
+    /// Library was written or replaced and should be reloaded
+    Changed(PathBuf),
+    /// Library was deleted; its module and tile should be torn down
+    Removed(PathBuf),
+}
+
 pub struct PluginManager {
     // Shared loader state
     pub loader: Arc<RwLock<PluginLoader>>,
@@ -13,38 +23,80 @@ pub struct PluginManager {
     // File watcher
     watcher: Option<RecommendedWatcher>,
 
-    // Channel to notify about reload events (path of reloaded plugin)
-    reload_tx: mpsc::Sender<PathBuf>,
-    pub reload_rx: mpsc::Receiver<PathBuf>,
+    // Channel to notify about plugin file events
+    events_tx: mpsc::Sender<PluginEvent>,
+    pub events_rx: mpsc::Receiver<PluginEvent>,
+
+    // Module ID spawned from each plugin file, so events can be mapped back to tiles
+    modules: HashMap<PathBuf, String>,
 }
 
 impl PluginManager {
     pub fn new() -> Self {
-        let (reload_tx, reload_rx) = mpsc::channel();
+        let (events_tx, events_rx) = mpsc::channel();
 
         Self {
             loader: Arc::new(RwLock::new(PluginLoader::new())),
             watcher: None,
-            reload_tx,
-            reload_rx,
+            events_tx,
+            events_rx,
+            modules: HashMap::new(),
         }
     }
 
+    /// Remember which module a plugin file provides
+    pub fn track_module(&mut self, path: &Path, module_id: &str) {
+        self.modules
+            .insert(path.to_path_buf(), module_id.to_string());
+    }
+
+    /// Module previously spawned from `path`, if any
+    pub fn module_for(&self, path: &Path) -> Option<&str> {
+        self.modules.get(path).map(String::as_str)
+    }
+
+    /// Plugin file a module was spawned from, if it came from a plugin
+    pub fn path_for(&self, module_id: &str) -> Option<&Path> {
+        self.modules
+            .iter()
+            .find(|(_, id)| id.as_str() == module_id)
+            .map(|(path, _)| path.as_path())
+    }
+
+    /// Modules spawned from plugins, with their library files
+    pub fn plugins(&self) -> impl Iterator<Item = (&str, &Path)> {
+        self.modules
+            .iter()
+            .map(|(path, id)| (id.as_str(), path.as_path()))
+    }
+
+    /// Forget a plugin file, returning the module it provided
+    pub fn untrack(&mut self, path: &Path) -> Option<String> {
+        self.modules.remove(path)
+    }
+
     /// Enable hot-reloading by watching plugin directories
     pub fn enable_hot_reload(&mut self) -> Result<()> {
-        let reload_tx = self.reload_tx.clone();
+        let events_tx = self.events_tx.clone();
 
         // Create watcher logic wrapped in sync API of notify 6.0
         let mut watcher =
             notify::recommended_watcher(move |res: Result<Event, notify::Error>| match res {
                 Ok(event) => {
-                    if event.kind.is_modify() {
+                    let removed = event.kind.is_remove();
+                    if removed || event.kind.is_modify() || event.kind.is_create() {
                         for path in event.paths {
                             if let Some(ext) = path.extension() {
                                 let ext_str = ext.to_string_lossy();
                                 if ext_str == "so" || ext_str == "dll" || ext_str == "dylib" {
-                                    info!("Plugin changed: {}", path.display());
-                                    let _ = reload_tx.send(path.clone());
+                                    let plugin_event = if removed {
+                                        info!("Plugin removed: {}", path.display());
+                                        PluginEvent::Removed(path.clone())
+                                    } else {
+                                        info!("Plugin changed: {}", path.display());
+                                        PluginEvent::Changed(path.clone())
+                                    };
+                                    let _ = events_tx.send(plugin_event);
                                 }
                             }
                         }
@@ -73,7 +125,7 @@ impl PluginManager {
     }
 
     /// Handle the reload of a plugin by path
-    /// This should be called when a path is received from reload_rx
+    /// This should be called when a `PluginEvent::Changed` is received from events_rx
     pub fn reload_plugin(&self, path: &Path) -> Result<PluginLibrary> {
         // Since we are creating a fresh library instance, we don't strictly need the write lock
         // on the loader unless we are updating the loader's internal list.
@@ -86,3 +138,21 @@ impl PluginManager {
         unsafe { PluginLibrary::load(path) }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracks_modules_by_plugin_path() {
+        let mut manager = PluginManager::new();
+        let path = Path::new("./plugins/libkamea.so");
+
+        manager.track_module(path, "kamea");
+        assert_eq!(manager.module_for(path), Some("kamea"));
+        assert_eq!(manager.path_for("kamea"), Some(path));
+
+        assert_eq!(manager.untrack(path).as_deref(), Some("kamea"));
+        assert_eq!(manager.module_for(path), None);
+    }
+}
@@ -0,0 +1,300 @@
+//! Per-plugin resource limits enforced by `PluginModuleAdapter`.
+//!
+//! Plugin calls are synchronous FFI, so they can't be pre-empted; instead the
+//! adapter times every `poll_signal`/`consume_signal` call and meters what
+//! comes out. A plugin that exceeds its quota is throttled (not polled, inputs
+//! dropped) for a cool-down period, and each violation is counted and logged
+//! so one misbehaving plugin can no longer flood the router.
+
+use crate::Signal;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Limits applied to a single plugin instance.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PluginQuota {
+    /// Signals the plugin may emit per one-second window.
+    pub max_signals_per_sec: u32,
+    /// Largest payload accepted in a single signal, in bytes.
+    pub max_signal_bytes: usize,
+    /// Longest a single poll/consume call may take before the watchdog trips.
+    pub max_call_duration: Duration,
+    /// How long a plugin stays throttled after a violation.
+    pub throttle_for: Duration,
+}
+
+impl Default for PluginQuota {
+    fn default() -> Self {
+        Self {
+            max_signals_per_sec: 500,
+            max_signal_bytes: 8 * 1024 * 1024,
+            max_call_duration: Duration::from_millis(50),
+            throttle_for: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Why a plugin was throttled.
+#[derive(Debug, Clone, PartialEq)]
+pub enum QuotaViolation {
+    SignalRate {
+        limit: u32,
+    },
+    OversizedSignal {
+        bytes: usize,
+        limit: usize,
+    },
+    SlowCall {
+        call: &'static str,
+        elapsed: Duration,
+    },
+}
+
+impl std::fmt::Display for QuotaViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QuotaViolation::SignalRate { limit } => {
+                write!(f, "emitted more than {} signals/s", limit)
+            }
+            QuotaViolation::OversizedSignal { bytes, limit } => {
+                write!(f, "emitted a {} byte signal (limit {})", bytes, limit)
+            }
+            QuotaViolation::SlowCall { call, elapsed } => {
+                write!(f, "{} took {:?}", call, elapsed)
+            }
+        }
+    }
+}
+
+/// Violation counters, shared so the UI can show them.
+#[derive(Debug, Default)]
+pub struct QuotaMetrics {
+    pub rate_violations: AtomicU64,
+    pub oversized_signals: AtomicU64,
+    pub slow_calls: AtomicU64,
+    /// Signals or inputs discarded while throttled or for breaking a limit.
+    pub dropped: AtomicU64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QuotaMetricsSnapshot {
+    pub rate_violations: u64,
+    pub oversized_signals: u64,
+    pub slow_calls: u64,
+    pub dropped: u64,
+}
+
+impl QuotaMetrics {
+    pub fn snapshot(&self) -> QuotaMetricsSnapshot {
+        let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+        QuotaMetricsSnapshot {
+            rate_violations: load(&self.rate_violations),
+            oversized_signals: load(&self.oversized_signals),
+            slow_calls: load(&self.slow_calls),
+            dropped: load(&self.dropped),
+        }
+    }
+}
+
+/// Approximate payload size of a signal, for the per-signal byte limit.
+pub fn signal_payload_bytes(signal: &Signal) -> usize {
+    match signal {
+        Signal::Text(text) => text.len(),
+        Signal::Intent { action, parameters } => {
+            action.len() + parameters.iter().map(String::len).sum::<usize>()
+        }
+        Signal::Blob { bytes, .. } => bytes.len(),
+        Signal::SharedBlob { bytes, .. } => bytes.len(),
+        Signal::Audio { data, .. } => std::mem::size_of_val(data.as_slice()),
+        Signal::SharedAudio { data, .. } => std::mem::size_of_val(data.as_slice()),
+        Signal::Computed { source, content } => source.len() + content.len(),
+        Signal::Astrology(data) => data
+            .planetary_positions
+            .iter()
+            .map(|(name, _)| name.len() + std::mem::size_of::<f64>())
+            .sum(),
+        _ => 0,
+    }
+}
+
+/// Tracks one plugin's usage against its [`PluginQuota`].
+#[derive(Debug)]
+pub struct QuotaEnforcer {
+    quota: PluginQuota,
+    window_start: Option<Instant>,
+    window_count: u32,
+    throttled_until: Option<Instant>,
+    metrics: std::sync::Arc<QuotaMetrics>,
+}
+
+impl QuotaEnforcer {
+    pub fn new(quota: PluginQuota) -> Self {
+        Self {
+            quota,
+            window_start: None,
+            window_count: 0,
+            throttled_until: None,
+            metrics: std::sync::Arc::new(QuotaMetrics::default()),
+        }
+    }
+
+    pub fn quota(&self) -> PluginQuota {
+        self.quota
+    }
+
+    pub fn metrics(&self) -> std::sync::Arc<QuotaMetrics> {
+        self.metrics.clone()
+    }
+
+    /// Whether the plugin is currently serving a throttle period.
+    pub fn is_throttled(&mut self, now: Instant) -> bool {
+        match self.throttled_until {
+            Some(until) if now < until => true,
+            Some(_) => {
+                self.throttled_until = None;
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// Count a discarded signal or input.
+    pub fn record_drop(&self) {
+        self.metrics.dropped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Check the watchdog after a plugin call returns.
+    pub fn record_call(
+        &mut self,
+        call: &'static str,
+        elapsed: Duration,
+        now: Instant,
+    ) -> Result<(), QuotaViolation> {
+        if elapsed <= self.quota.max_call_duration {
+            return Ok(());
+        }
+        self.metrics.slow_calls.fetch_add(1, Ordering::Relaxed);
+        self.throttle(now);
+        Err(QuotaViolation::SlowCall { call, elapsed })
+    }
+
+    /// Meter a signal the plugin wants to emit. On `Err` the signal must be dropped.
+    pub fn admit_signal(&mut self, signal: &Signal, now: Instant) -> Result<(), QuotaViolation> {
+        let bytes = signal_payload_bytes(signal);
+        if bytes > self.quota.max_signal_bytes {
+            self.metrics
+                .oversized_signals
+                .fetch_add(1, Ordering::Relaxed);
+            self.record_drop();
+            return Err(QuotaViolation::OversizedSignal {
+                bytes,
+                limit: self.quota.max_signal_bytes,
+            });
+        }
+
+        let window_expired = self
+            .window_start
+            .is_none_or(|start| now.duration_since(start) >= Duration::from_secs(1));
+        if window_expired {
+            self.window_start = Some(now);
+            self.window_count = 0;
+        }
+        self.window_count += 1;
+        if self.window_count > self.quota.max_signals_per_sec {
+            self.metrics.rate_violations.fetch_add(1, Ordering::Relaxed);
+            self.record_drop();
+            self.throttle(now);
+            return Err(QuotaViolation::SignalRate {
+                limit: self.quota.max_signals_per_sec,
+            });
+        }
+        Ok(())
+    }
+
+    fn throttle(&mut self, now: Instant) {
+        self.throttled_until = Some(now + self.quota.throttle_for);
+        self.window_start = None;
+        self.window_count = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quota() -> PluginQuota {
+        PluginQuota {
+            max_signals_per_sec: 3,
+            max_signal_bytes: 8,
+            max_call_duration: Duration::from_millis(10),
+            throttle_for: Duration::from_millis(500),
+        }
+    }
+
+    #[test]
+    fn rate_limit_throttles_until_cool_down_expires() {
+        let mut enforcer = QuotaEnforcer::new(quota());
+        let t0 = Instant::now();
+        let signal = Signal::Pulse;
+
+        for _ in 0..3 {
+            assert!(enforcer.admit_signal(&signal, t0).is_ok());
+        }
+        assert_eq!(
+            enforcer.admit_signal(&signal, t0),
+            Err(QuotaViolation::SignalRate { limit: 3 })
+        );
+        assert!(enforcer.is_throttled(t0 + Duration::from_millis(100)));
+        assert!(!enforcer.is_throttled(t0 + Duration::from_millis(600)));
+        assert!(enforcer
+            .admit_signal(&signal, t0 + Duration::from_millis(600))
+            .is_ok());
+
+        let metrics = enforcer.metrics().snapshot();
+        assert_eq!(metrics.rate_violations, 1);
+        assert_eq!(metrics.dropped, 1);
+    }
+
+    #[test]
+    fn rate_window_resets_each_second() {
+        let mut enforcer = QuotaEnforcer::new(quota());
+        let t0 = Instant::now();
+        for second in 0..5 {
+            let now = t0 + Duration::from_secs(second);
+            for _ in 0..3 {
+                assert!(enforcer.admit_signal(&Signal::Pulse, now).is_ok());
+            }
+        }
+    }
+
+    #[test]
+    fn oversized_signals_are_dropped_without_throttling() {
+        let mut enforcer = QuotaEnforcer::new(quota());
+        let now = Instant::now();
+        let big = Signal::Text("this is far too long".to_string());
+
+        assert!(matches!(
+            enforcer.admit_signal(&big, now),
+            Err(QuotaViolation::OversizedSignal { limit: 8, .. })
+        ));
+        assert!(!enforcer.is_throttled(now));
+        assert!(enforcer
+            .admit_signal(&Signal::Text("ok".into()), now)
+            .is_ok());
+    }
+
+    #[test]
+    fn slow_calls_trip_the_watchdog() {
+        let mut enforcer = QuotaEnforcer::new(quota());
+        let now = Instant::now();
+
+        assert!(enforcer
+            .record_call("poll_signal", Duration::from_millis(5), now)
+            .is_ok());
+        assert!(enforcer
+            .record_call("consume_signal", Duration::from_millis(40), now)
+            .is_err());
+        assert!(enforcer.is_throttled(now));
+        assert_eq!(enforcer.metrics().snapshot().slow_calls, 1);
+    }
+}
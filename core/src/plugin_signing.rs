@@ -15,6 +15,27 @@ impl PluginVerifier {
         }
     }
 
+    /// Check against `trusted_keys` instead of the user's key file
+    pub fn with_keys(trusted_keys: Vec<VerifyingKey>) -> Self {
+        Self { trusted_keys }
+    }
+
+    /// Whether any key is trusted; with none, nothing verifies
+    pub fn has_keys(&self) -> bool {
+        !self.trusted_keys.is_empty()
+    }
+
+    /// Whether `signature` is a trusted key's signature of `message`
+    pub fn verify_bytes(&self, message: &[u8], signature: &[u8]) -> bool {
+        let Ok(signature) = <[u8; 64]>::try_from(signature) else {
+            return false;
+        };
+        let signature = Signature::from_bytes(&signature);
+        self.trusted_keys
+            .iter()
+            .any(|key| key.verify(message, &signature).is_ok())
+    }
+
     fn load_trusted_keys() -> Vec<VerifyingKey> {
         let mut keys = Vec::new();
 
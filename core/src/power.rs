@@ -0,0 +1,311 @@
+//! Battery and thermal awareness
+//!
+//! A [`PowerMonitor`] samples `/sys/class/power_supply` and
+//! `/sys/class/thermal` every few seconds; [`PowerConfig`] turns the reading
+//! into the profile the daemon runs at, and each module's [`Importance`]
+//! decides how hard that profile hits it.
+
+use crate::PowerProfile;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// How much a module matters when power or heat is short
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Importance {
+    /// Never throttled
+    Essential,
+    #[default]
+    Normal,
+    /// Throttled a step further than the rest, paused at the bottom
+    Background,
+}
+
+impl PowerProfile {
+    /// One step more frugal
+    pub fn downgraded(self) -> Self {
+        match self {
+            PowerProfile::Normal => PowerProfile::LowPower,
+            PowerProfile::LowPower | PowerProfile::BatteryBackground => {
+                PowerProfile::BatteryBackground
+            }
+        }
+    }
+
+    /// The profile a module of `importance` runs at under this one
+    pub fn for_importance(self, importance: Importance) -> Self {
+        match importance {
+            Importance::Essential => PowerProfile::Normal,
+            Importance::Normal => self,
+            Importance::Background if self == PowerProfile::Normal => self,
+            Importance::Background => self.downgraded(),
+        }
+    }
+
+    /// Background modules stop updating altogether at the bottom profile
+    pub fn pauses(self, importance: Importance) -> bool {
+        importance == Importance::Background && self == PowerProfile::BatteryBackground
+    }
+}
+
+/// What the machine's power supply and sensors say
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PowerReading {
+    pub on_battery: bool,
+    /// Lowest charge over all batteries
+    pub battery_percent: Option<u8>,
+    /// Hottest thermal zone
+    pub temperature_c: Option<f32>,
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    std::fs::read_to_string(path)
+        .ok()
+        .map(|s| s.trim().to_string())
+}
+
+fn entries(dir: &Path) -> Vec<PathBuf> {
+    std::fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+        .unwrap_or_default()
+}
+
+impl PowerReading {
+    /// Read from a sysfs class root (`/sys/class` outside tests). Anything
+    /// missing reads as mains power at an unknown temperature.
+    pub fn from_sysfs(class_root: &Path) -> Self {
+        let mut mains_online = false;
+        let mut discharging = false;
+        let mut battery_percent: Option<u8> = None;
+        for supply in entries(&class_root.join("power_supply")) {
+            match read_trimmed(&supply.join("type")).as_deref() {
+                Some("Mains") | Some("USB") => {
+                    mains_online |= read_trimmed(&supply.join("online")).as_deref() == Some("1");
+                }
+                Some("Battery") => {
+                    discharging |=
+                        read_trimmed(&supply.join("status")).as_deref() == Some("Discharging");
+                    if let Some(capacity) =
+                        read_trimmed(&supply.join("capacity")).and_then(|c| c.parse::<u8>().ok())
+                    {
+                        battery_percent =
+                            Some(battery_percent.map_or(capacity, |p| p.min(capacity)));
+                    }
+                }
+                _ => {}
+            }
+        }
+        let temperature_c = entries(&class_root.join("thermal"))
+            .iter()
+            .filter(|zone| {
+                zone.file_name()
+                    .is_some_and(|name| name.to_string_lossy().starts_with("thermal_zone"))
+            })
+            .filter_map(|zone| read_trimmed(&zone.join("temp"))?.parse::<f32>().ok())
+            // Millidegrees; zones that report nonsense are ignored
+            .map(|milli| milli / 1000.0)
+            .filter(|c| (0.0..150.0).contains(c))
+            .reduce(f32::max);
+        Self {
+            on_battery: discharging && !mains_online,
+            battery_percent,
+            temperature_c,
+        }
+    }
+}
+
+/// `[power]` in layout.toml
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct PowerConfig {
+    /// Follow battery and temperature; off keeps the chosen profile
+    pub auto: bool,
+    /// At least this on battery
+    pub battery_profile: PowerProfile,
+    /// At or below this charge, the most frugal profile
+    pub low_battery_percent: u8,
+    /// From this temperature, a step down
+    pub thermal_warn_c: f32,
+    /// From this temperature, the most frugal profile
+    pub thermal_critical_c: f32,
+    /// Module id to importance; unlisted modules are normal
+    pub importance: BTreeMap<String, Importance>,
+}
+
+impl Default for PowerConfig {
+    fn default() -> Self {
+        Self {
+            auto: true,
+            battery_profile: PowerProfile::LowPower,
+            low_battery_percent: 20,
+            thermal_warn_c: 80.0,
+            thermal_critical_c: 90.0,
+            importance: BTreeMap::new(),
+        }
+    }
+}
+
+impl PowerConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn importance(&self, module_id: &str) -> Importance {
+        self.importance.get(module_id).copied().unwrap_or_default()
+    }
+
+    /// The profile to run at: `chosen`, or lower if the reading calls for it
+    pub fn profile(&self, chosen: PowerProfile, reading: &PowerReading) -> PowerProfile {
+        if !self.auto {
+            return chosen;
+        }
+        let mut profile = chosen;
+        if reading.on_battery {
+            profile = profile.max(self.battery_profile);
+            if reading
+                .battery_percent
+                .is_some_and(|p| p <= self.low_battery_percent)
+            {
+                profile = PowerProfile::BatteryBackground;
+            }
+        }
+        match reading.temperature_c {
+            Some(c) if c >= self.thermal_critical_c => PowerProfile::BatteryBackground,
+            Some(c) if c >= self.thermal_warn_c => profile.downgraded(),
+            _ => profile,
+        }
+    }
+}
+
+/// How often sysfs is read
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Samples power and thermal state at most every few seconds
+pub struct PowerMonitor {
+    class_root: PathBuf,
+    last_poll: Option<Instant>,
+    reading: PowerReading,
+}
+
+impl Default for PowerMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PowerMonitor {
+    pub fn new() -> Self {
+        Self::with_root("/sys/class")
+    }
+
+    pub fn with_root(class_root: impl Into<PathBuf>) -> Self {
+        Self {
+            class_root: class_root.into(),
+            last_poll: None,
+            reading: PowerReading::default(),
+        }
+    }
+
+    /// The latest reading, refreshed if it's stale
+    pub fn poll(&mut self) -> &PowerReading {
+        if self
+            .last_poll
+            .is_none_or(|at| at.elapsed() >= POLL_INTERVAL)
+        {
+            self.last_poll = Some(Instant::now());
+            self.reading = PowerReading::from_sysfs(&self.class_root);
+        }
+        &self.reading
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(root: &Path, path: &str, contents: &str) {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn reads_battery_and_hottest_zone_from_sysfs() {
+        let root = std::env::temp_dir().join(format!("magnolia_power_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        write(&root, "power_supply/AC/type", "Mains\n");
+        write(&root, "power_supply/AC/online", "0\n");
+        write(&root, "power_supply/BAT0/type", "Battery\n");
+        write(&root, "power_supply/BAT0/status", "Discharging\n");
+        write(&root, "power_supply/BAT0/capacity", "42\n");
+        write(&root, "thermal/thermal_zone0/temp", "51000\n");
+        write(&root, "thermal/thermal_zone1/temp", "83500\n");
+        write(&root, "thermal/cooling_device0/temp", "99000\n");
+
+        let reading = PowerReading::from_sysfs(&root);
+        assert!(reading.on_battery);
+        assert_eq!(reading.battery_percent, Some(42));
+        assert_eq!(reading.temperature_c, Some(83.5));
+
+        write(&root, "power_supply/AC/online", "1\n");
+        assert!(!PowerReading::from_sysfs(&root).on_battery);
+        assert_eq!(
+            PowerReading::from_sysfs(&root.join("missing")),
+            PowerReading::default()
+        );
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn profile_follows_battery_heat_and_importance() {
+        let config = PowerConfig::default();
+        let reading = |on_battery, battery_percent, temperature_c| PowerReading {
+            on_battery,
+            battery_percent,
+            temperature_c,
+        };
+        let normal = PowerProfile::Normal;
+        assert_eq!(
+            config.profile(normal, &reading(false, None, Some(60.0))),
+            normal
+        );
+        assert_eq!(
+            config.profile(normal, &reading(true, Some(80), None)),
+            PowerProfile::LowPower
+        );
+        assert_eq!(
+            config.profile(normal, &reading(true, Some(15), None)),
+            PowerProfile::BatteryBackground
+        );
+        assert_eq!(
+            config.profile(normal, &reading(false, None, Some(85.0))),
+            PowerProfile::LowPower
+        );
+        assert_eq!(
+            config.profile(normal, &reading(false, None, Some(95.0))),
+            PowerProfile::BatteryBackground
+        );
+        let manual = PowerConfig {
+            auto: false,
+            ..PowerConfig::default()
+        };
+        assert_eq!(
+            manual.profile(normal, &reading(true, Some(5), Some(95.0))),
+            normal
+        );
+
+        let low = PowerProfile::LowPower;
+        assert_eq!(low.for_importance(Importance::Essential), normal);
+        assert_eq!(low.for_importance(Importance::Normal), low);
+        assert_eq!(
+            low.for_importance(Importance::Background),
+            PowerProfile::BatteryBackground
+        );
+        assert_eq!(normal.for_importance(Importance::Background), normal);
+        assert!(PowerProfile::BatteryBackground.pauses(Importance::Background));
+        assert!(!PowerProfile::BatteryBackground.pauses(Importance::Normal));
+    }
+}
@@ -0,0 +1,160 @@
+//! Provenance: where a routed signal came from
+//!
+//! With tracking on, the router gives every signal it routes an envelope:
+//! the module it started from, the modules it has passed through, when it
+//! started and a correlation id shared by everything derived from it. The
+//! router can't see inside modules, so a module's output is taken to carry
+//! on from the last signal delivered to it. That is exact for one-in,
+//! one-out chains like STT → parser → LLM → TTS and a best guess for
+//! modules merging several inputs.
+//!
+//! Module inboxes carry bare signals, so sinks read the envelope of what
+//! reached them from the [`ProvenanceTracker`] the host shares.
+
+use crate::Patch;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Modules kept in a chain; a loop drops the oldest after the origin
+pub const MAX_HOPS: usize = 32;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Provenance {
+    /// Shared by every signal derived from the same first one
+    pub correlation_id: u64,
+    /// The module that emitted the first signal
+    pub origin: String,
+    /// Modules the chain has come through, origin first
+    pub hops: Vec<String>,
+    /// When the origin emitted, on the host clock
+    pub created_us: u64,
+}
+
+impl Provenance {
+    pub fn new(correlation_id: u64, origin: &str, created_us: u64) -> Self {
+        Self {
+            correlation_id,
+            origin: origin.to_string(),
+            hops: vec![origin.to_string()],
+            created_us,
+        }
+    }
+
+    /// The chain carried on by what `module` emits
+    pub fn hop(&self, module: &str) -> Self {
+        let mut next = self.clone();
+        if next.hops.len() >= MAX_HOPS {
+            next.hops.remove(1);
+        }
+        next.hops.push(module.to_string());
+        next
+    }
+
+    /// `#12 stt → parser → llm, 340 ms ago`
+    pub fn describe(&self, now_us: u64) -> String {
+        format!(
+            "#{} {}, {} ms ago",
+            self.correlation_id,
+            self.hops.join(" → "),
+            now_us.saturating_sub(self.created_us) / 1000
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+struct Seen {
+    /// Last envelope delivered to each module
+    received: HashMap<String, Arc<Provenance>>,
+    /// Last envelope across each patch
+    patches: HashMap<String, Arc<Provenance>>,
+}
+
+/// What the router remembers of the envelopes it handed out, for sinks and
+/// the patch bay's inspector. Off until [`set_enabled`](Self::set_enabled).
+#[derive(Debug, Default)]
+pub struct ProvenanceTracker {
+    enabled: AtomicBool,
+    next_id: AtomicU64,
+    seen: Mutex<Seen>,
+}
+
+impl ProvenanceTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Turning it off forgets what was seen
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+        if !enabled {
+            if let Ok(mut seen) = self.seen.lock() {
+                *seen = Seen::default();
+            }
+        }
+    }
+
+    /// Envelope for a signal `module_id` emits at `now_us`: carrying on from
+    /// the last one delivered to it, or starting a chain
+    pub fn stamp(&self, module_id: &str, now_us: u64) -> Provenance {
+        let last = self
+            .seen
+            .lock()
+            .ok()
+            .and_then(|seen| seen.received.get(module_id).cloned());
+        match last {
+            Some(last) => last.hop(module_id),
+            None => {
+                let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+                Provenance::new(id, module_id, now_us)
+            }
+        }
+    }
+
+    /// Note `provenance` went down `patch`
+    pub fn delivered(&self, patch: &Patch, provenance: &Arc<Provenance>) {
+        if let Ok(mut seen) = self.seen.lock() {
+            seen.received
+                .insert(patch.sink_module.clone(), provenance.clone());
+            seen.patches.insert(patch.id.clone(), provenance.clone());
+        }
+    }
+
+    /// Envelope of the last signal delivered to `module_id`
+    pub fn received(&self, module_id: &str) -> Option<Provenance> {
+        let seen = self.seen.lock().ok()?;
+        seen.received.get(module_id).map(|p| p.as_ref().clone())
+    }
+
+    /// Envelope of the last signal down `patch_id`
+    pub fn on_patch(&self, patch_id: &str) -> Option<Provenance> {
+        let seen = self.seen.lock().ok()?;
+        seen.patches.get(patch_id).map(|p| p.as_ref().clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hops_stop_growing_past_the_limit() {
+        let mut chain = Provenance::new(7, "loop", 1_000);
+        for _ in 0..MAX_HOPS + 5 {
+            chain = chain.hop("loop");
+        }
+        assert_eq!(chain.hops.len(), MAX_HOPS);
+        assert_eq!(chain.hops[0], "loop");
+        assert_eq!(
+            Provenance::new(7, "stt", 1_000)
+                .hop("parser")
+                .describe(341_000),
+            "#7 stt → parser, 340 ms ago"
+        );
+    }
+}
@@ -0,0 +1,275 @@
+//! Request/response between modules
+//!
+//! A module asks a question by emitting an `Intent` from its `request_out`
+//! port whose first parameter is a correlation id (`req:<id>`). The module
+//! answering takes it on `request_in` and sends a `Computed` whose source
+//! is `reply:<id>` from `reply_out`, patched back to the asker's `reply_in`.
+//! That return patch closes a loop, so the patch bay lets patches into
+//! `reply_in` ports through its feedback-loop check.
+//!
+//! A responder serving several askers sends each reply to all of them;
+//! ids start with the asking module's id, so each picks out its own.
+
+use crate::{DataType, Port, PortDirection, Signal};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+pub const REQUEST_OUT: &str = "request_out";
+pub const REQUEST_IN: &str = "request_in";
+pub const REPLY_OUT: &str = "reply_out";
+pub const REPLY_IN: &str = "reply_in";
+
+const REQUEST_PREFIX: &str = "req:";
+const REPLY_PREFIX: &str = "reply:";
+
+fn port(id: &str, label: &str, data_type: DataType, direction: PortDirection) -> Port {
+    Port {
+        id: id.to_string(),
+        label: label.to_string(),
+        data_type,
+        direction,
+    }
+}
+
+/// Ports a module asking questions adds to its schema
+pub fn requester_ports() -> Vec<Port> {
+    vec![
+        port(
+            REQUEST_OUT,
+            "Requests",
+            DataType::Control,
+            PortDirection::Output,
+        ),
+        port(REPLY_IN, "Replies", DataType::Text, PortDirection::Input),
+    ]
+}
+
+/// Ports a module answering them adds to its schema
+pub fn responder_ports() -> Vec<Port> {
+    vec![
+        port(
+            REQUEST_IN,
+            "Requests",
+            DataType::Control,
+            PortDirection::Input,
+        ),
+        port(REPLY_OUT, "Replies", DataType::Text, PortDirection::Output),
+    ]
+}
+
+/// The port a request or reply leaves from, for `output_port`
+pub fn output_port(signal: &Signal) -> Option<&'static str> {
+    if Request::parse(signal).is_some() {
+        Some(REQUEST_OUT)
+    } else if reply_of(signal).is_some() {
+        Some(REPLY_OUT)
+    } else {
+        None
+    }
+}
+
+/// An `Intent` carrying a correlation id
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub correlation_id: String,
+    pub action: String,
+    pub parameters: Vec<String>,
+}
+
+impl Request {
+    /// `None` unless `signal` is an intent whose first parameter is `req:<id>`
+    pub fn parse(signal: &Signal) -> Option<Self> {
+        let Signal::Intent { action, parameters } = signal else {
+            return None;
+        };
+        let (first, rest) = parameters.split_first()?;
+        let correlation_id = first.strip_prefix(REQUEST_PREFIX)?;
+        Some(Self {
+            correlation_id: correlation_id.to_string(),
+            action: action.clone(),
+            parameters: rest.to_vec(),
+        })
+    }
+
+    pub fn signal(&self) -> Signal {
+        let mut parameters = vec![format!("{}{}", REQUEST_PREFIX, self.correlation_id)];
+        parameters.extend(self.parameters.iter().cloned());
+        Signal::Intent {
+            action: self.action.clone(),
+            parameters,
+        }
+    }
+
+    /// The answer to send from `reply_out`
+    pub fn reply(&self, content: impl Into<String>) -> Signal {
+        Signal::Computed {
+            source: format!("{}{}", REPLY_PREFIX, self.correlation_id),
+            content: content.into(),
+        }
+    }
+}
+
+/// Correlation id and content of a reply
+pub fn reply_of(signal: &Signal) -> Option<(&str, &str)> {
+    match signal {
+        Signal::Computed { source, content } => source
+            .strip_prefix(REPLY_PREFIX)
+            .map(|id| (id, content.as_str())),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum RequestError {
+    #[error("no reply to {0} within {1:?}")]
+    TimedOut(String, Duration),
+    #[error("request {0} was dropped before its reply came")]
+    Dropped(String),
+}
+
+type Slots = Mutex<HashMap<String, oneshot::Sender<String>>>;
+
+/// The asking side: hands out request signals with fresh ids and matches
+/// replies fed back from `reply_in` to whoever awaits them. Requests
+/// still waiting fail with [`RequestError::Dropped`] when it's dropped.
+#[derive(Debug)]
+pub struct Requester {
+    module_id: String,
+    next_id: u64,
+    waiting: Arc<Slots>,
+}
+
+impl Requester {
+    pub fn new(module_id: &str) -> Self {
+        Self {
+            module_id: module_id.to_string(),
+            next_id: 0,
+            waiting: Arc::default(),
+        }
+    }
+
+    /// The signal to emit from `request_out` and its reply, which fails if
+    /// nothing answers within `timeout`
+    pub fn ask(
+        &mut self,
+        action: &str,
+        parameters: Vec<String>,
+        timeout: Duration,
+    ) -> (Signal, PendingReply) {
+        self.next_id += 1;
+        let request = Request {
+            correlation_id: format!("{}/{}", self.module_id, self.next_id),
+            action: action.to_string(),
+            parameters,
+        };
+        let (tx, rx) = oneshot::channel();
+        if let Ok(mut waiting) = self.waiting.lock() {
+            waiting.insert(request.correlation_id.clone(), tx);
+        }
+        let pending = PendingReply {
+            correlation_id: request.correlation_id.clone(),
+            timeout,
+            rx,
+            waiting: Arc::downgrade(&self.waiting),
+        };
+        (request.signal(), pending)
+    }
+
+    /// Pass on a signal that arrived on `reply_in`; true if it answered one
+    /// of this module's requests still waiting
+    pub fn resolve(&self, signal: &Signal) -> bool {
+        let Some((id, content)) = reply_of(signal) else {
+            return false;
+        };
+        let Some(tx) = self.waiting.lock().ok().and_then(|mut w| w.remove(id)) else {
+            return false;
+        };
+        tx.send(content.to_string()).is_ok()
+    }
+
+    /// Requests sent and not yet answered or given up on
+    pub fn waiting(&self) -> usize {
+        self.waiting.lock().map(|w| w.len()).unwrap_or(0)
+    }
+}
+
+/// A reply on its way; await [`wait`](Self::wait) from a task of its own,
+/// since the reply comes in through the module's input
+#[derive(Debug)]
+pub struct PendingReply {
+    correlation_id: String,
+    timeout: Duration,
+    rx: oneshot::Receiver<String>,
+    waiting: Weak<Slots>,
+}
+
+impl PendingReply {
+    pub fn correlation_id(&self) -> &str {
+        &self.correlation_id
+    }
+
+    pub async fn wait(self) -> Result<String, RequestError> {
+        let result = tokio::time::timeout(self.timeout, self.rx).await;
+        // A late reply then finds nothing waiting
+        if let Some(waiting) = self.waiting.upgrade() {
+            if let Ok(mut waiting) = waiting.lock() {
+                waiting.remove(&self.correlation_id);
+            }
+        }
+        match result {
+            Ok(Ok(content)) => Ok(content),
+            Ok(Err(_)) => Err(RequestError::Dropped(self.correlation_id)),
+            Err(_) => Err(RequestError::TimedOut(self.correlation_id, self.timeout)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn replies_match_requests_and_time_out() {
+        let mut requester = Requester::new("script");
+        let (signal, pending) = requester.ask(
+            "ask",
+            vec!["what time is it".to_string()],
+            Duration::from_secs(5),
+        );
+        assert_eq!(output_port(&signal), Some(REQUEST_OUT));
+
+        // The responder's side
+        let request = Request::parse(&signal).unwrap();
+        assert_eq!(request.action, "ask");
+        assert_eq!(request.parameters, ["what time is it"]);
+        let reply = request.reply("noon");
+        assert_eq!(output_port(&reply), Some(REPLY_OUT));
+
+        // Another asker's reply
+        assert!(!requester.resolve(&Signal::Computed {
+            source: "reply:other/1".to_string(),
+            content: "midnight".to_string(),
+        }));
+        assert!(requester.resolve(&reply));
+        assert_eq!(pending.wait().await, Ok("noon".to_string()));
+        // Already answered
+        assert!(!requester.resolve(&reply));
+
+        let (_, pending) = requester.ask("ask", Vec::new(), Duration::from_millis(10));
+        let id = pending.correlation_id().to_string();
+        assert_eq!(
+            pending.wait().await,
+            Err(RequestError::TimedOut(id, Duration::from_millis(10)))
+        );
+        assert_eq!(requester.waiting(), 0);
+
+        let (_, pending) = requester.ask("ask", Vec::new(), Duration::from_secs(5));
+        drop(requester);
+        assert!(matches!(
+            pending.wait().await,
+            Err(RequestError::Dropped(_))
+        ));
+    }
+}
@@ -0,0 +1,622 @@
+//! Compiled audio graphs
+//!
+//! Through the router every audio buffer is a queue hop and an await per
+//! module, timed by whatever else the async runtime is busy with. Modules
+//! that can also run as an [`RtAudioNode`] skip that: the host finds
+//! connected sets of them in the patch bay and runs each set in topological
+//! order on one thread, a fixed-size block at a time (see
+//! `ModuleHost::compile_audio_graphs`). Audio patched into a set from outside
+//! is handed to its thread instead of to module inboxes, and audio leaving
+//! the set goes back on the router as ordinary signals. Everything else
+//! (settings, control) still reaches the modules themselves.
+
+use crate::{DataType, Patch, PatchBay, PortDirection, RoutedSignal, Signal};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc as std_mpsc;
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+/// Buffers queued for a graph before the router starts dropping them
+const INPUT_QUEUE: usize = 64;
+
+/// Format and timing of the block being processed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BlockContext {
+    pub sample_rate: u32,
+    pub channels: u16,
+    /// Frames in the block, the same for every block of a graph
+    pub frames: usize,
+    /// Capture time of the block's first frame
+    pub timestamp_us: u64,
+}
+
+impl BlockContext {
+    /// How long the block lasts when played
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs_f64(self.frames as f64 / self.sample_rate.max(1) as f64)
+    }
+}
+
+/// The real-time half of a module that can run inside a compiled audio
+/// graph. It shares whatever settings state the module keeps, so settings
+/// still arrive through the module's inbox.
+pub trait RtAudioNode: Send {
+    /// Fill `output` from `input`, both `frames * channels` interleaved
+    /// samples; `input` is everything patched in, summed. Called on the
+    /// graph's thread once per block: no allocating, locking or blocking.
+    fn process_block(&mut self, context: &BlockContext, input: &[f32], output: &mut [f32]);
+}
+
+/// One connected set of RT-capable modules, as it will be run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphPlan {
+    /// Module ids in processing order
+    pub members: Vec<String>,
+    /// Ids of the patches between members
+    pub internal: BTreeSet<String>,
+    /// Patches into the graph from outside, by id, with the member they feed
+    pub entries: BTreeMap<String, usize>,
+    /// Members whose audio also leaves the graph, with the port it leaves from
+    pub exits: Vec<(usize, String)>,
+    /// The members feeding each member
+    upstream: Vec<Vec<usize>>,
+}
+
+fn is_audio_port(patch_bay: &PatchBay, module: &str, port: &str, direction: PortDirection) -> bool {
+    patch_bay.get_module(module).is_some_and(|schema| {
+        schema.ports.iter().any(|candidate| {
+            candidate.id == port
+                && candidate.direction == direction
+                && candidate.data_type == DataType::Audio
+        })
+    })
+}
+
+fn root<'a>(parent: &BTreeMap<&'a str, &'a str>, mut id: &'a str) -> &'a str {
+    while let Some(&up) = parent.get(id) {
+        if up == id {
+            break;
+        }
+        id = up;
+    }
+    id
+}
+
+/// The graphs `patch_bay` compiles to, given which modules have a node.
+/// Modules that are disabled, bypassed or silenced stay with the router,
+/// which knows what to do with them; so do sets with a cycle and sets
+/// nothing outside feeds. Patches with a transform are left to the router
+/// too, which applies it.
+pub fn plan_audio_graphs(patch_bay: &PatchBay, has_node: impl Fn(&str) -> bool) -> Vec<GraphPlan> {
+    let eligible = |id: &str| {
+        has_node(id)
+            && !patch_bay.is_module_disabled(id)
+            && !patch_bay.is_module_bypassed(id)
+            && !patch_bay.is_module_silenced(id)
+    };
+    let audio = patch_bay
+        .get_patches()
+        .iter()
+        .filter(|patch| {
+            is_audio_port(
+                patch_bay,
+                &patch.source_module,
+                &patch.source_port,
+                PortDirection::Output,
+            ) && is_audio_port(
+                patch_bay,
+                &patch.sink_module,
+                &patch.sink_port,
+                PortDirection::Input,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    // Connected sets, joining the ends of every patch between eligible modules
+    let mut parent = BTreeMap::new();
+    for patch in &audio {
+        let (source, sink) = (patch.source_module.as_str(), patch.sink_module.as_str());
+        if source == sink || !eligible(source) || !eligible(sink) || patch.transform.is_some() {
+            continue;
+        }
+        parent.entry(source).or_insert(source);
+        parent.entry(sink).or_insert(sink);
+        let (a, b) = (root(&parent, source), root(&parent, sink));
+        if a != b {
+            parent.insert(a.max(b), a.min(b));
+        }
+    }
+    let mut sets = BTreeMap::<&str, BTreeSet<&str>>::new();
+    for &id in parent.keys() {
+        sets.entry(root(&parent, id)).or_default().insert(id);
+    }
+    sets.into_values()
+        .filter_map(|set| plan_set(&audio, &set))
+        .collect()
+}
+
+fn plan_set(audio: &[&Patch], set: &BTreeSet<&str>) -> Option<GraphPlan> {
+    let inside = |id: &String| set.contains(id.as_str());
+    let internal = audio
+        .iter()
+        .filter(|patch| inside(&patch.source_module) && inside(&patch.sink_module))
+        .collect::<Vec<_>>();
+    if internal.iter().any(|patch| patch.transform.is_some()) {
+        log::debug!(
+            "A patch between audio modules {:?} has a transform; leaving them to the router",
+            set
+        );
+        return None;
+    }
+
+    // Kahn's algorithm, taking ready modules by id so the order is stable
+    let mut incoming = set.iter().map(|id| (*id, 0)).collect::<BTreeMap<_, _>>();
+    for patch in &internal {
+        *incoming.get_mut(patch.sink_module.as_str())? += 1;
+    }
+    let mut ready = incoming
+        .iter()
+        .filter(|(_, count)| **count == 0)
+        .map(|(id, _)| *id)
+        .collect::<BTreeSet<_>>();
+    let mut members = Vec::new();
+    while let Some(id) = ready.pop_first() {
+        members.push(id.to_string());
+        for patch in internal.iter().filter(|patch| patch.source_module == id) {
+            let count = incoming.get_mut(patch.sink_module.as_str())?;
+            *count -= 1;
+            if *count == 0 {
+                ready.insert(patch.sink_module.as_str());
+            }
+        }
+    }
+    if members.len() != set.len() {
+        log::warn!(
+            "Audio modules {:?} form a cycle; leaving them to the router",
+            set
+        );
+        return None;
+    }
+
+    let index = |id: &str| members.iter().position(|member| member == id);
+    let entries = audio
+        .iter()
+        .filter(|patch| !inside(&patch.source_module) && inside(&patch.sink_module))
+        .map(|patch| Some((patch.id.clone(), index(&patch.sink_module)?)))
+        .collect::<Option<BTreeMap<_, _>>>()?;
+    if entries.is_empty() {
+        log::debug!("Nothing feeds audio modules {:?}; not compiling them", set);
+        return None;
+    }
+    let mut exits = audio
+        .iter()
+        .filter(|patch| inside(&patch.source_module) && !inside(&patch.sink_module))
+        .map(|patch| Some((index(&patch.source_module)?, patch.source_port.clone())))
+        .collect::<Option<Vec<_>>>()?;
+    exits.sort();
+    exits.dedup();
+    let mut upstream = vec![Vec::new(); members.len()];
+    for patch in &internal {
+        upstream[index(&patch.sink_module)?].push(index(&patch.source_module)?);
+    }
+    Some(GraphPlan {
+        members,
+        internal: internal.iter().map(|patch| patch.id.clone()).collect(),
+        entries,
+        exits,
+        upstream,
+    })
+}
+
+#[derive(Debug, Default)]
+struct GraphStats {
+    blocks: AtomicU64,
+    late_blocks: AtomicU64,
+    dropped: AtomicU64,
+}
+
+/// A running graph, for status displays
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AudioGraphInfo {
+    /// Module ids in processing order
+    pub members: Vec<String>,
+    pub block_frames: usize,
+    pub blocks: u64,
+    /// Blocks that took longer to process than they last
+    pub late_blocks: u64,
+    /// Buffers dropped on the way in or out, with the graph's queue or the
+    /// router's full
+    pub dropped: u64,
+}
+
+/// What the router does with a patch a graph runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum GraphPatch {
+    /// Between members: the graph carries its audio
+    Internal,
+    /// Into the graph: audio goes to the graph's thread
+    Entry { graph: usize, member: usize },
+}
+
+pub(crate) struct RunningGraph {
+    pub(crate) plan: GraphPlan,
+    pub(crate) block_frames: usize,
+    input: std_mpsc::SyncSender<(usize, Signal)>,
+    stats: Arc<GraphStats>,
+    thread: JoinHandle<Vec<Box<dyn RtAudioNode>>>,
+}
+
+impl RunningGraph {
+    /// Run `plan` with `nodes`, one per member in order
+    pub(crate) fn start(
+        plan: GraphPlan,
+        nodes: Vec<Box<dyn RtAudioNode>>,
+        block_frames: usize,
+        router_tx: mpsc::Sender<RoutedSignal>,
+    ) -> Self {
+        let (input, inbox) = std_mpsc::sync_channel(INPUT_QUEUE);
+        let stats = Arc::new(GraphStats::default());
+        let runner = Runner {
+            pending: (0..nodes.len()).map(|_| Pending::default()).collect(),
+            plan: plan.clone(),
+            nodes,
+            block_frames: block_frames.max(1),
+            router_tx,
+            stats: stats.clone(),
+            format: None,
+            input: Vec::new(),
+            outputs: Vec::new(),
+        };
+        log::info!(
+            "Compiled audio graph {:?}, {} frame blocks",
+            plan.members,
+            runner.block_frames
+        );
+        Self {
+            plan,
+            block_frames: runner.block_frames,
+            input,
+            stats,
+            thread: thread::spawn(move || runner.run(inbox)),
+        }
+    }
+
+    /// Hand `signal` to `member`; false when the graph's queue is full
+    pub(crate) fn feed(&self, member: usize, signal: Signal) -> bool {
+        if self.input.try_send((member, signal)).is_ok() {
+            return true;
+        }
+        self.stats.dropped.fetch_add(1, Ordering::Relaxed);
+        false
+    }
+
+    /// Stop the thread and take the nodes back, by module id
+    pub(crate) fn stop(self) -> Vec<(String, Box<dyn RtAudioNode>)> {
+        drop(self.input);
+        match self.thread.join() {
+            Ok(nodes) => self.plan.members.into_iter().zip(nodes).collect(),
+            Err(_) => {
+                log::error!(
+                    "Audio graph {:?} panicked; its modules go back to the router",
+                    self.plan.members
+                );
+                Vec::new()
+            }
+        }
+    }
+
+    pub(crate) fn info(&self) -> AudioGraphInfo {
+        let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+        AudioGraphInfo {
+            members: self.plan.members.clone(),
+            block_frames: self.block_frames,
+            blocks: load(&self.stats.blocks),
+            late_blocks: load(&self.stats.late_blocks),
+            dropped: load(&self.stats.dropped),
+        }
+    }
+}
+
+/// Samples at one member waiting to fill a block
+#[derive(Default)]
+struct Pending {
+    samples: Vec<f32>,
+    timestamp_us: u64,
+}
+
+/// A graph's thread
+struct Runner {
+    plan: GraphPlan,
+    nodes: Vec<Box<dyn RtAudioNode>>,
+    block_frames: usize,
+    router_tx: mpsc::Sender<RoutedSignal>,
+    stats: Arc<GraphStats>,
+    /// Taken from the first buffer in; another format starts over
+    format: Option<(u32, u16)>,
+    pending: Vec<Pending>,
+    input: Vec<f32>,
+    outputs: Vec<Vec<f32>>,
+}
+
+impl Runner {
+    fn run(mut self, inbox: std_mpsc::Receiver<(usize, Signal)>) -> Vec<Box<dyn RtAudioNode>> {
+        if !raise_priority() {
+            log::debug!(
+                "Audio graph {:?} runs at normal priority",
+                self.plan.members
+            );
+        }
+        while let Ok((member, signal)) = inbox.recv() {
+            self.push(member, signal);
+            while self.ready() {
+                self.run_block();
+            }
+        }
+        self.nodes
+    }
+
+    fn push(&mut self, member: usize, signal: Signal) {
+        let Some(audio) = signal.audio() else {
+            return;
+        };
+        if audio.sample_rate == 0 || audio.channels == 0 || member >= self.pending.len() {
+            return;
+        }
+        let format = (audio.sample_rate, audio.channels);
+        if self.format != Some(format) {
+            // The only place buffers are sized, so blocks themselves don't allocate
+            let len = self.block_frames * audio.channels as usize;
+            self.format = Some(format);
+            for pending in &mut self.pending {
+                pending.samples.clear();
+                pending.samples.reserve(len * 2);
+            }
+            self.input = vec![0.0; len];
+            self.outputs = vec![vec![0.0; len]; self.nodes.len()];
+        }
+        let pending = &mut self.pending[member];
+        if pending.samples.is_empty() {
+            pending.timestamp_us = audio.timestamp_us;
+        }
+        pending.samples.extend_from_slice(audio.data);
+    }
+
+    /// Some entry has a block's worth; the others add what they have
+    fn ready(&self) -> bool {
+        self.format.is_some()
+            && self
+                .pending
+                .iter()
+                .any(|pending| pending.samples.len() >= self.input.len())
+    }
+
+    fn run_block(&mut self) {
+        let Some((sample_rate, channels)) = self.format else {
+            return;
+        };
+        let len = self.input.len();
+        let context = BlockContext {
+            sample_rate,
+            channels,
+            frames: self.block_frames,
+            timestamp_us: self
+                .pending
+                .iter()
+                .find(|pending| pending.samples.len() >= len)
+                .map_or(0, |pending| pending.timestamp_us),
+        };
+        let started = Instant::now();
+        for member in 0..self.nodes.len() {
+            self.input.fill(0.0);
+            let pending = &mut self.pending[member];
+            let take = pending.samples.len().min(len);
+            if take > 0 {
+                for (sum, sample) in self.input.iter_mut().zip(pending.samples.drain(..take)) {
+                    *sum += sample;
+                }
+                pending.timestamp_us +=
+                    (take / channels as usize) as u64 * 1_000_000 / sample_rate as u64;
+            }
+            for &upstream in &self.plan.upstream[member] {
+                for (sum, sample) in self.input.iter_mut().zip(&self.outputs[upstream]) {
+                    *sum += *sample;
+                }
+            }
+            let output = &mut self.outputs[member];
+            output.fill(0.0);
+            self.nodes[member].process_block(&context, &self.input, output);
+        }
+        if started.elapsed() > context.duration() {
+            self.stats.late_blocks.fetch_add(1, Ordering::Relaxed);
+        }
+        self.stats.blocks.fetch_add(1, Ordering::Relaxed);
+
+        // Leaving the graph is an ordinary signal again
+        for (member, port) in &self.plan.exits {
+            let signal = Signal::Audio {
+                sample_rate,
+                channels,
+                timestamp_us: context.timestamp_us,
+                data: self.outputs[*member].clone(),
+            };
+            let routed =
+                RoutedSignal::new(self.plan.members[*member].clone(), port.clone(), signal);
+            if self.router_tx.try_send(routed).is_err() {
+                self.stats.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+/// Ask for real-time scheduling; without the privilege for it the graph
+/// runs at normal priority
+#[cfg(target_os = "linux")]
+fn raise_priority() -> bool {
+    let param = libc::sched_param { sched_priority: 10 };
+    // SAFETY: sets the calling thread's own policy from a valid param
+    unsafe { libc::pthread_setschedparam(libc::pthread_self(), libc::SCHED_FIFO, &param) == 0 }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn raise_priority() -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ModuleSchema, Port};
+
+    fn module(id: &str, rt: bool) -> ModuleSchema {
+        let port = |id: &str, direction| Port {
+            id: id.to_string(),
+            label: id.to_string(),
+            data_type: DataType::Audio,
+            direction,
+        };
+        ModuleSchema {
+            id: id.to_string(),
+            name: if rt { "rt" } else { "plain" }.to_string(),
+            description: String::new(),
+            ports: vec![
+                port("audio_in", PortDirection::Input),
+                port("audio_out", PortDirection::Output),
+            ],
+            settings_schema: None,
+            docs: None,
+        }
+    }
+
+    fn patch_bay(modules: &[(&str, bool)], patches: &[(&str, &str)]) -> PatchBay {
+        let mut patch_bay = PatchBay::new();
+        for (id, rt) in modules {
+            patch_bay.register_module(module(id, *rt));
+        }
+        for (source, sink) in patches {
+            patch_bay
+                .connect(source, "audio_out", sink, "audio_in")
+                .unwrap();
+        }
+        patch_bay
+    }
+
+    fn plan(patch_bay: &PatchBay) -> Vec<GraphPlan> {
+        plan_audio_graphs(patch_bay, |id| {
+            patch_bay
+                .get_module(id)
+                .is_some_and(|schema| schema.name == "rt")
+        })
+    }
+
+    #[test]
+    fn connected_rt_modules_compile_in_order() {
+        // mic -> gate -> (eq, comp) -> mix -> speaker
+        let patch_bay = patch_bay(
+            &[
+                ("mic", false),
+                ("gate", true),
+                ("eq", true),
+                ("comp", true),
+                ("mix", true),
+                ("speaker", false),
+            ],
+            &[
+                ("mic", "gate"),
+                ("gate", "eq"),
+                ("gate", "comp"),
+                ("eq", "mix"),
+                ("comp", "mix"),
+                ("mix", "speaker"),
+            ],
+        );
+        let plans = plan(&patch_bay);
+        assert_eq!(plans.len(), 1);
+        let plan = &plans[0];
+        assert_eq!(plan.members, vec!["gate", "comp", "eq", "mix"]);
+        assert_eq!(plan.internal.len(), 4);
+        assert_eq!(plan.entries.values().copied().collect::<Vec<_>>(), vec![0]);
+        assert_eq!(plan.exits, vec![(3, "audio_out".to_string())]);
+        assert_eq!(plan.upstream[3], vec![2, 1]);
+    }
+
+    #[test]
+    fn cycles_unfed_sets_and_silenced_modules_stay_with_the_router() {
+        let looped = patch_bay(
+            &[("mic", false), ("a", true), ("b", true)],
+            &[("mic", "a"), ("a", "b"), ("b", "a")],
+        );
+        assert!(plan(&looped).is_empty());
+
+        let unfed = patch_bay(&[("a", true), ("b", true)], &[("a", "b")]);
+        assert!(plan(&unfed).is_empty());
+
+        let mut muted = patch_bay(
+            &[("mic", false), ("a", true), ("b", true), ("c", true)],
+            &[("mic", "a"), ("a", "b"), ("b", "c")],
+        );
+        assert_eq!(plan(&muted)[0].members.len(), 3);
+        muted.set_module_muted("c", true);
+        assert_eq!(plan(&muted)[0].members, vec!["a", "b"]);
+    }
+
+    /// Passes on what comes in, plus a constant
+    struct Offset(f32);
+
+    impl RtAudioNode for Offset {
+        fn process_block(&mut self, _context: &BlockContext, input: &[f32], output: &mut [f32]) {
+            for (out, sample) in output.iter_mut().zip(input) {
+                *out = sample + self.0;
+            }
+        }
+    }
+
+    #[test]
+    fn blocks_are_fixed_size_and_leave_as_signals() {
+        let patch_bay = patch_bay(
+            &[("mic", false), ("a", true), ("b", true), ("out", false)],
+            &[("mic", "a"), ("a", "b"), ("b", "out")],
+        );
+        let plan = plan(&patch_bay).remove(0);
+        let entry = *plan.entries.values().next().unwrap();
+        let (router_tx, mut router_rx) = mpsc::channel(16);
+        let graph = RunningGraph::start(
+            plan,
+            vec![Box::new(Offset(1.0)), Box::new(Offset(0.5))],
+            4,
+            router_tx,
+        );
+
+        let audio = |timestamp_us, data: Vec<f32>| Signal::Audio {
+            sample_rate: 1_000,
+            channels: 1,
+            timestamp_us,
+            data,
+        };
+        // Six frames make one block, with two left over for the next
+        assert!(graph.feed(entry, audio(100, vec![0.0; 6])));
+        assert!(graph.feed(entry, audio(6_100, vec![0.0; 2])));
+        let mut blocks = Vec::new();
+        while blocks.len() < 2 {
+            if let Ok(routed) = router_rx.try_recv() {
+                blocks.push(routed);
+            }
+        }
+        assert_eq!(blocks[0].source_id, "b");
+        assert_eq!(blocks[0].source_port, "audio_out");
+        let view = blocks[0].signal.audio().unwrap();
+        assert_eq!(view.data, &[1.5; 4]);
+        assert_eq!(view.timestamp_us, 100);
+        assert_eq!(blocks[1].signal.audio().unwrap().timestamp_us, 4_100);
+
+        let info = graph.info();
+        assert_eq!((info.blocks, info.block_frames), (2, 4));
+        let nodes = graph.stop();
+        assert_eq!(
+            nodes.iter().map(|(id, _)| id.as_str()).collect::<Vec<_>>(),
+            vec!["a", "b"]
+        );
+    }
+}
@@ -1,12 +1,17 @@
-use crate::{ModuleSchema, OverflowPolicy, Signal};
+use crate::clock::{system_clock, SharedClock};
+use crate::fan_in::AudioMixer;
+use crate::patch_transform::PatchTransformer;
+use crate::provenance::{Provenance, ProvenanceTracker};
+use crate::rt_graph::{self, AudioGraphInfo, GraphPatch, RtAudioNode, RunningGraph};
+use crate::{MergePolicy, ModuleSchema, OverflowPolicy, Signal, SpawnMode, StartupProfile};
 use async_trait::async_trait;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
 use std::sync::mpsc as std_mpsc;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::thread::{self, JoinHandle};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 
 /// Execution model for a module - determines how it runs
@@ -90,6 +95,29 @@ pub trait ModuleRuntime: Send + Sync {
     /// Enable or disable this module
     fn set_enabled(&mut self, enabled: bool);
 
+    /// Version of the settings this module reads; bump it when their shape
+    /// changes and upgrade older ones in `migrate_settings`
+    fn settings_version(&self) -> u32 {
+        0
+    }
+
+    /// Upgrade settings saved at `old_version` to `settings_version`.
+    /// `None` when they can't be, so they're dropped rather than misapplied.
+    fn migrate_settings(
+        &self,
+        _old_version: u32,
+        _settings: serde_json::Value,
+    ) -> Option<serde_json::Value> {
+        None
+    }
+
+    /// The module's half for compiled audio graphs, if it has one. Taken
+    /// once, at spawn; the host runs it instead of routing audio through
+    /// the module while a graph holds it.
+    fn rt_audio_node(&mut self) -> Option<Box<dyn RtAudioNode>> {
+        None
+    }
+
     /// Run the module's main loop (async)
     /// This will be called in a separate thread/task with a tokio runtime
     async fn run(&mut self, inbox: mpsc::Receiver<Signal>, outbox: mpsc::Sender<RoutedSignal>);
@@ -102,6 +130,9 @@ pub struct RoutedSignal {
     pub source_port: String,
     pub schema_version: u32,
     pub signal: Signal,
+    /// Filled in by the router when it tracks provenance; set by the
+    /// sender only to carry on a chain the router can't see
+    pub provenance: Option<Provenance>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -124,9 +155,15 @@ impl RoutedSignal {
             source_port: source_port.into(),
             schema_version: Self::SCHEMA_VERSION,
             signal,
+            provenance: None,
         }
     }
 
+    pub fn with_provenance(mut self, provenance: Provenance) -> Self {
+        self.provenance = Some(provenance);
+        self
+    }
+
     /// Validate metadata before a signal enters the patch graph.
     pub fn validate(&self) -> Result<(), RoutedSignalError> {
         if self.schema_version != Self::SCHEMA_VERSION {
@@ -169,19 +206,52 @@ pub struct RoutingMetrics {
     pub invalid_dropped: AtomicU64,
     pub unroutable: AtomicU64,
     pub disabled: AtomicU64,
+    pub muted: AtomicU64,
+    pub bypassed: AtomicU64,
     pub delivered: AtomicU64,
     pub send_failures: AtomicU64,
     pub fanout_clones: AtomicU64,
     pub replaceable_drops: AtomicU64,
     pub loss_sensitive_failures: AtomicU64,
+    /// Per-patch traffic with when it was last brought up to date
+    patch_traffic: Mutex<HashMap<String, (PatchTraffic, Instant)>>,
+    /// When each module last emitted something other than audio or a pulse
+    last_emitted: Mutex<HashMap<String, Instant>>,
 }
 
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// How quickly [`PatchTraffic`] rates follow changes, in seconds.
+const TRAFFIC_RATE_WINDOW_SECS: f64 = 2.0;
+
+/// Signals delivered through one patch, for the patch bay heatmap.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PatchTraffic {
+    pub messages: u64,
+    /// Approximate payload bytes (see [`crate::plugin_quota::signal_payload_bytes`])
+    pub bytes: u64,
+    /// Recent messages per second, averaged over a couple of seconds
+    pub messages_per_sec: f64,
+    pub bytes_per_sec: f64,
+}
+
+impl PatchTraffic {
+    /// Rates decayed from `since` to `now`, as if nothing arrived in between
+    fn decayed(mut self, since: Instant, now: Instant) -> Self {
+        let elapsed = now.saturating_duration_since(since).as_secs_f64();
+        let decay = (-elapsed / TRAFFIC_RATE_WINDOW_SECS).exp();
+        self.messages_per_sec *= decay;
+        self.bytes_per_sec *= decay;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize)]
 pub struct RoutingMetricsSnapshot {
     pub received: u64,
     pub invalid_dropped: u64,
     pub unroutable: u64,
     pub disabled: u64,
+    pub muted: u64,
+    pub bypassed: u64,
     pub delivered: u64,
     pub send_failures: u64,
     pub fanout_clones: u64,
@@ -203,6 +273,8 @@ impl RoutingMetrics {
             invalid_dropped: load(&self.invalid_dropped),
             unroutable: load(&self.unroutable),
             disabled: load(&self.disabled),
+            muted: load(&self.muted),
+            bypassed: load(&self.bypassed),
             delivered: load(&self.delivered),
             send_failures: load(&self.send_failures),
             fanout_clones: load(&self.fanout_clones),
@@ -210,6 +282,115 @@ impl RoutingMetrics {
             loss_sensitive_failures: load(&self.loss_sensitive_failures),
         }
     }
+
+    /// Count one signal of `bytes` through `patch_id`
+    pub fn record_patch(&self, patch_id: &str, bytes: usize) {
+        let now = Instant::now();
+        let Ok(mut traffic) = self.patch_traffic.lock() else {
+            return;
+        };
+        let (entry, updated) = traffic
+            .entry(patch_id.to_string())
+            .or_insert_with(|| (PatchTraffic::default(), now));
+        let mut current = entry.decayed(*updated, now);
+        current.messages += 1;
+        current.bytes += bytes as u64;
+        current.messages_per_sec += 1.0 / TRAFFIC_RATE_WINDOW_SECS;
+        current.bytes_per_sec += bytes as f64 / TRAFFIC_RATE_WINDOW_SECS;
+        *entry = current;
+        *updated = now;
+    }
+
+    /// Note that `module_id` emitted `signal` just now. Audio and the
+    /// `Pulse` keep-alives idle sources send don't count.
+    pub fn record_emission(&self, module_id: &str, signal: &Signal) {
+        if signal.audio().is_some() || matches!(signal, Signal::Pulse) {
+            return;
+        }
+        if let Ok(mut last) = self.last_emitted.lock() {
+            last.insert(module_id.to_string(), Instant::now());
+        }
+    }
+
+    /// When each module last emitted a signal. Audio and pulses don't
+    /// count: sources stream them all the time, so they say nothing about
+    /// what's active.
+    pub fn last_emissions(&self) -> HashMap<String, Instant> {
+        self.last_emitted
+            .lock()
+            .map(|last| last.clone())
+            .unwrap_or_default()
+    }
+
+    /// Traffic by patch id, rates as of now; patches never used are absent
+    pub fn patch_traffic(&self) -> HashMap<String, PatchTraffic> {
+        let now = Instant::now();
+        self.patch_traffic
+            .lock()
+            .map(|traffic| {
+                traffic
+                    .iter()
+                    .map(|(id, (entry, updated))| (id.clone(), entry.decayed(*updated, now)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// How launch went: what was spawned, how long each took, what waits
+#[derive(Debug, Clone, Default)]
+pub struct StartupReport {
+    pub profile: String,
+    pub total: Duration,
+    /// In spawn order
+    pub spawned: Vec<(String, Duration)>,
+    pub deferred: Vec<String>,
+}
+
+impl StartupReport {
+    /// The `n` slowest spawns, slowest first
+    pub fn slowest(&self, n: usize) -> Vec<(String, Duration)> {
+        let mut spawned = self.spawned.clone();
+        spawned.sort_by_key(|(_, took)| std::cmp::Reverse(*took));
+        spawned.truncate(n);
+        spawned
+    }
+}
+
+impl std::fmt::Display for StartupReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "profile '{}': {} modules spawned, {} deferred in {:.1?}",
+            self.profile,
+            self.spawned.len(),
+            self.deferred.len(),
+            self.total
+        )?;
+        let slowest = self.slowest(5);
+        if !slowest.is_empty() {
+            let list = slowest
+                .iter()
+                .map(|(id, took)| format!("{id} {took:.1?}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            write!(f, "; slowest: {list}")?;
+        }
+        Ok(())
+    }
+}
+
+struct StartupPhase {
+    name: String,
+    profile: StartupProfile,
+    began: Instant,
+    spawned: Vec<(String, Duration)>,
+}
+
+/// A lazy module whose inbox already takes signals
+struct DeferredModule {
+    inbox: mpsc::Sender<Signal>,
+    start: Box<dyn FnOnce(&mut ModuleHost) + Send>,
 }
 
 /// Handle to a running module instance
@@ -267,9 +448,24 @@ use crate::resources::gpu_map::{GpuBufferMap, GpuTextureMap, GpuTextureViewMap};
 /// Manages the lifecycle of all module runtimes
 pub struct ModuleHost {
     modules: HashMap<String, ModuleHandle>,
+    /// Lazy modules waiting for `activate`
+    deferred: HashMap<String, DeferredModule>,
+    /// Set between `begin_startup` and `finish_startup`
+    startup: Option<StartupPhase>,
     router_tx: mpsc::Sender<RoutedSignal>,
     runtime: Arc<tokio::runtime::Runtime>,
     routing_metrics: Arc<RoutingMetrics>,
+    clock: SharedClock,
+    /// Real-time halves of spawned modules that no graph is running
+    rt_nodes: HashMap<String, Box<dyn RtAudioNode>>,
+    audio_graphs: Vec<RunningGraph>,
+    /// Patches the running graphs take over, by id
+    graph_patches: HashMap<String, GraphPatch>,
+    /// Rounds of audio for ports summing several patches
+    audio_mixer: Mutex<AudioMixer>,
+    /// Applies the transforms patches carry
+    patch_transformer: Mutex<PatchTransformer>,
+    provenance: Arc<ProvenanceTracker>,
     pub audio_pool: Arc<AudioBufferPool>,
     pub blob_pool: Arc<BlobBufferPool>,
     #[cfg(feature = "gpu-resources")]
@@ -283,13 +479,27 @@ pub struct ModuleHost {
 impl ModuleHost {
     /// Create a new module host
     pub fn new(router_tx: mpsc::Sender<RoutedSignal>) -> Self {
+        Self::with_clock(router_tx, system_clock())
+    }
+
+    /// Create a module host whose modules share `clock` (e.g. a `VirtualClock` in tests)
+    pub fn with_clock(router_tx: mpsc::Sender<RoutedSignal>, clock: SharedClock) -> Self {
         Self {
             modules: HashMap::new(),
+            deferred: HashMap::new(),
+            startup: None,
             router_tx,
             runtime: Arc::new(
                 tokio::runtime::Runtime::new().expect("Failed to create Magnolia runtime"),
             ),
             routing_metrics: Arc::new(RoutingMetrics::default()),
+            clock,
+            rt_nodes: HashMap::new(),
+            audio_graphs: Vec::new(),
+            graph_patches: HashMap::new(),
+            audio_mixer: Mutex::new(AudioMixer::new()),
+            patch_transformer: Mutex::new(PatchTransformer::new()),
+            provenance: Arc::new(ProvenanceTracker::new()),
             audio_pool: Arc::new(AudioBufferPool::new()),
             blob_pool: Arc::new(BlobBufferPool::new()),
             #[cfg(feature = "gpu-resources")]
@@ -301,20 +511,131 @@ impl ModuleHost {
         }
     }
 
-    /// Spawn a module in its own isolated thread with panic catching
+    /// Mark the start of launch: modules the profile calls lazy are deferred
+    /// by `spawn` until `finish_startup`
+    pub fn begin_startup(&mut self, name: &str, profile: StartupProfile) {
+        self.startup = Some(StartupPhase {
+            name: name.to_string(),
+            profile,
+            began: Instant::now(),
+            spawned: Vec::new(),
+        });
+    }
+
+    /// End launch; later spawns are eager again
+    pub fn finish_startup(&mut self) -> StartupReport {
+        let Some(phase) = self.startup.take() else {
+            return StartupReport::default();
+        };
+        let mut deferred = self.deferred_modules();
+        deferred.sort();
+        StartupReport {
+            profile: phase.name,
+            total: phase.began.elapsed(),
+            spawned: phase.spawned,
+            deferred,
+        }
+    }
+
+    /// Spawn a module in its own isolated thread with panic catching.
+    /// During startup, modules the profile marks lazy only get their inbox:
+    /// signals queue up until `activate` starts them.
     pub fn spawn<M>(&mut self, mut module: M, buffer_size: usize) -> Result<(), String>
     where
         M: ModuleRuntime + 'static,
     {
         let module_id = module.id().to_string();
-        let module_name = module.name().to_string();
-
-        if self.modules.contains_key(&module_id) {
+        if self.modules.contains_key(&module_id) || self.deferred.contains_key(&module_id) {
             return Err(format!("Module {} already spawned", module_id));
         }
+        if let Some(node) = module.rt_audio_node() {
+            self.rt_nodes.insert(module_id.clone(), node);
+        }
 
-        // Create channels for this module
         let (inbox_tx, inbox_rx) = mpsc::channel::<Signal>(buffer_size);
+        let lazy = self
+            .startup
+            .as_ref()
+            .is_some_and(|phase| phase.profile.mode(&module_id) == SpawnMode::Lazy);
+        if lazy {
+            log::debug!("Deferring module {}", module_id);
+            let inbox = inbox_tx.clone();
+            self.deferred.insert(
+                module_id,
+                DeferredModule {
+                    inbox,
+                    start: Box::new(move |host: &mut ModuleHost| {
+                        host.start_module(module, inbox_tx, inbox_rx)
+                    }),
+                },
+            );
+            return Ok(());
+        }
+
+        let started = Instant::now();
+        self.start_module(module, inbox_tx, inbox_rx);
+        if let Some(phase) = self.startup.as_mut() {
+            phase.spawned.push((module_id, started.elapsed()));
+        }
+        Ok(())
+    }
+
+    /// Start a deferred module; false if it wasn't deferred
+    pub fn activate(&mut self, module_id: &str) -> bool {
+        let Some(deferred) = self.deferred.remove(module_id) else {
+            return false;
+        };
+        let started = Instant::now();
+        (deferred.start)(self);
+        let took = started.elapsed();
+        log::info!("Started lazy module {} in {:.1?}", module_id, took);
+        if let Some(phase) = self.startup.as_mut() {
+            phase.spawned.push((module_id.to_string(), took));
+        }
+        true
+    }
+
+    /// Start every deferred module that a patch touches; returns their ids
+    pub fn activate_patched(&mut self, patch_bay: &crate::PatchBay) -> Vec<String> {
+        if self.deferred.is_empty() {
+            return Vec::new();
+        }
+        let patched = self
+            .deferred
+            .keys()
+            .filter(|id| {
+                patch_bay
+                    .get_patches()
+                    .iter()
+                    .any(|patch| &patch.source_module == *id || &patch.sink_module == *id)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        for id in &patched {
+            self.activate(id);
+        }
+        patched
+    }
+
+    pub fn is_deferred(&self, module_id: &str) -> bool {
+        self.deferred.contains_key(module_id)
+    }
+
+    pub fn deferred_modules(&self) -> Vec<String> {
+        self.deferred.keys().cloned().collect()
+    }
+
+    fn start_module<M>(
+        &mut self,
+        mut module: M,
+        inbox_tx: mpsc::Sender<Signal>,
+        inbox_rx: mpsc::Receiver<Signal>,
+    ) where
+        M: ModuleRuntime + 'static,
+    {
+        let module_id = module.id().to_string();
+        let module_name = module.name().to_string();
+
         let (shutdown_tx, mut shutdown_rx) = mpsc::channel::<()>(1);
         let outbox = self.router_tx.clone();
         let state = Arc::new(AtomicU8::new(ModuleState::Starting.as_u8()));
@@ -412,7 +733,6 @@ impl ModuleHost {
         };
 
         self.modules.insert(module_id, module_handle);
-        Ok(())
     }
 
     /// Get a handle to a module by ID
@@ -448,6 +768,11 @@ impl ModuleHost {
         module_id: &str,
         timeout: Duration,
     ) -> Result<ShutdownReport, String> {
+        if self.in_audio_graph(module_id) {
+            // Rebuilt without it on the next compile
+            self.stop_audio_graphs();
+        }
+        self.rt_nodes.remove(module_id);
         if let Some(mut handle) = self.modules.remove(module_id) {
             handle.shutdown();
             let mut report = ShutdownReport::default();
@@ -459,6 +784,8 @@ impl ModuleHost {
                 }
             }
             Ok(report)
+        } else if self.deferred.remove(module_id).is_some() {
+            Ok(ShutdownReport::default())
         } else {
             Err(format!("Module {} not found", module_id))
         }
@@ -475,6 +802,9 @@ impl ModuleHost {
     /// Shutdown all modules, bounding each join by `timeout`.
     pub fn shutdown_all_with_timeout(&mut self, timeout: Duration) -> ShutdownReport {
         log::info!("Shutting down {} modules", self.modules.len());
+        self.deferred.clear();
+        self.stop_audio_graphs();
+        self.rt_nodes.clear();
         let mut report = ShutdownReport::default();
 
         // Send shutdown signals
@@ -536,6 +866,8 @@ impl ModuleHost {
     pub fn send_signal(&self, module_id: &str, signal: Signal) -> Result<(), String> {
         if let Some(handle) = self.modules.get(module_id) {
             handle.try_send(signal).map_err(|e| e.to_string())
+        } else if let Some(deferred) = self.deferred.get(module_id) {
+            deferred.inbox.try_send(signal).map_err(|e| e.to_string())
         } else {
             Err(format!("Module {} not found", module_id))
         }
@@ -543,13 +875,111 @@ impl ModuleHost {
 
     /// Get a direct sender to a module's inbox (for UI/Tiles)
     pub fn get_sender(&self, module_id: &str) -> Option<mpsc::Sender<Signal>> {
-        self.modules.get(module_id).map(|h| h.inbox.clone())
+        self.modules
+            .get(module_id)
+            .map(|h| h.inbox.clone())
+            .or_else(|| self.deferred.get(module_id).map(|d| d.inbox.clone()))
+    }
+
+    /// Clock handed to modules spawned on this host
+    pub fn clock(&self) -> SharedClock {
+        self.clock.clone()
+    }
+
+    /// Envelopes of routed signals, once enabled
+    pub fn provenance(&self) -> Arc<ProvenanceTracker> {
+        self.provenance.clone()
     }
 
     pub fn routing_metrics(&self) -> Arc<RoutingMetrics> {
         self.routing_metrics.clone()
     }
 
+    fn in_audio_graph(&self, module_id: &str) -> bool {
+        self.audio_graphs
+            .iter()
+            .any(|graph| graph.plan.members.iter().any(|member| member == module_id))
+    }
+
+    /// Run each connected set of real-time audio modules in `patch_bay` as
+    /// a compiled graph with `block_frames` blocks (see [`rt_graph`]).
+    /// Nothing happens when the graphs would come out as they are, so this
+    /// can follow every patch bay change.
+    pub fn compile_audio_graphs(&mut self, patch_bay: &crate::PatchBay, block_frames: usize) {
+        let block_frames = block_frames.max(1);
+        let plans = rt_graph::plan_audio_graphs(patch_bay, |id| {
+            self.rt_nodes.contains_key(id) || self.in_audio_graph(id)
+        });
+        let unchanged = plans.len() == self.audio_graphs.len()
+            && self
+                .audio_graphs
+                .iter()
+                .zip(&plans)
+                .all(|(graph, plan)| graph.plan == *plan && graph.block_frames == block_frames);
+        if unchanged {
+            return;
+        }
+
+        self.stop_audio_graphs();
+        // Again, now every node is back (or lost to a panic)
+        let plans = rt_graph::plan_audio_graphs(patch_bay, |id| self.rt_nodes.contains_key(id));
+        for plan in plans {
+            let graph = self.audio_graphs.len();
+            let nodes = plan
+                .members
+                .iter()
+                .filter_map(|id| self.rt_nodes.remove(id))
+                .collect::<Vec<_>>();
+            for patch in &plan.internal {
+                self.graph_patches
+                    .insert(patch.clone(), GraphPatch::Internal);
+            }
+            for (patch, member) in &plan.entries {
+                self.graph_patches.insert(
+                    patch.clone(),
+                    GraphPatch::Entry {
+                        graph,
+                        member: *member,
+                    },
+                );
+            }
+            self.audio_graphs.push(RunningGraph::start(
+                plan,
+                nodes,
+                block_frames,
+                self.router_tx.clone(),
+            ));
+        }
+    }
+
+    /// Stop the compiled graphs; their audio goes through the router again
+    pub fn stop_audio_graphs(&mut self) {
+        self.graph_patches.clear();
+        for graph in std::mem::take(&mut self.audio_graphs) {
+            self.rt_nodes.extend(graph.stop());
+        }
+    }
+
+    /// The graphs running now
+    pub fn audio_graphs(&self) -> Vec<AudioGraphInfo> {
+        self.audio_graphs.iter().map(RunningGraph::info).collect()
+    }
+
+    /// Audio into a compiled graph goes to its thread, everything else to
+    /// the sink's inbox
+    fn deliver(&self, patch: &crate::Patch, signal: Signal) -> Result<(), String> {
+        if let Some(GraphPatch::Entry { graph, member }) = self.graph_patches.get(&patch.id) {
+            if signal.audio().is_some() {
+                return if self.audio_graphs[*graph].feed(*member, signal) {
+                    Ok(())
+                } else {
+                    Err(format!("Audio graph into {} is full", patch.sink_module))
+                };
+            }
+        }
+        self.send_signal(&patch.sink_module, signal)
+    }
+
     /// Route an envelope through the patch graph and deliver it to module inboxes.
     pub fn route_signal(&self, patch_bay: &crate::PatchBay, routed: RoutedSignal) -> RoutingResult {
         self.routing_metrics
@@ -569,6 +999,15 @@ impl ModuleHost {
                 ..Default::default()
             };
         }
+        if patch_bay.is_module_silenced(&routed.source_id) {
+            self.routing_metrics.muted.fetch_add(1, Ordering::Relaxed);
+            return RoutingResult {
+                dropped: true,
+                ..Default::default()
+            };
+        }
+        self.routing_metrics
+            .record_emission(&routed.source_id, &routed.signal);
         let outgoing = patch_bay
             .get_outgoing_patches(&routed.source_id)
             .into_iter()
@@ -585,25 +1024,34 @@ impl ModuleHost {
                 ..Default::default()
             };
         }
-        let active_sinks = outgoing
-            .into_iter()
-            .filter(|patch| {
-                if patch_bay.is_module_disabled(&patch.sink_module) {
-                    self.routing_metrics
-                        .disabled
-                        .fetch_add(1, Ordering::Relaxed);
-                    false
-                } else {
-                    true
-                }
-            })
-            .collect::<Vec<_>>();
+        // Audio buffers go untracked: they arrive too often to take the
+        // tracker's lock for each, and chains worth tracing are made of
+        // what's derived from them
+        let tracked = self.provenance.is_enabled() && routed.signal.audio().is_none();
+        let provenance = tracked.then(|| {
+            Arc::new(routed.provenance.unwrap_or_else(|| {
+                self.provenance
+                    .stamp(&routed.source_id, self.clock.now_us())
+            }))
+        });
+        let mut active_sinks = self.delivery_targets(patch_bay, outgoing);
+        if !self.graph_patches.is_empty() && routed.signal.audio().is_some() {
+            // Compiled graphs carry audio between their own modules
+            active_sinks
+                .retain(|patch| self.graph_patches.get(&patch.id) != Some(&GraphPatch::Internal));
+        }
         let delivery_count = if matches!(&routed.signal, Signal::AudioStream { .. }) {
             active_sinks.len().min(1)
         } else {
             active_sinks.len()
         };
-        let mut signal = Some(routed.signal);
+        // Sinks past the first share the samples or bytes rather than each
+        // getting a copy
+        let mut signal = Some(if delivery_count > 1 {
+            routed.signal.into_shared_audio().into_shared_blob()
+        } else {
+            routed.signal
+        });
         let mut delivered = 0;
         for (index, patch) in active_sinks.into_iter().take(delivery_count).enumerate() {
             let payload = if index + 1 == delivery_count {
@@ -615,8 +1063,26 @@ impl ModuleHost {
                 signal.as_ref().expect("signal payload missing").clone()
             };
             let overflow_policy = payload.overflow_policy();
-            if self.send_signal(&patch.sink_module, payload).is_ok() {
+            let bytes = crate::plugin_quota::signal_payload_bytes(&payload);
+            let payload = self.transform(patch, payload);
+            let Some(payload) = self.fan_in(patch_bay, patch, payload) else {
+                // Held for the port's mix
                 delivered += 1;
+                if let Some(provenance) = &provenance {
+                    self.provenance.delivered(patch, provenance);
+                }
+                self.routing_metrics.record_patch(&patch.id, bytes);
+                self.routing_metrics
+                    .delivered
+                    .fetch_add(1, Ordering::Relaxed);
+                continue;
+            };
+            if self.deliver(patch, payload).is_ok() {
+                delivered += 1;
+                if let Some(provenance) = &provenance {
+                    self.provenance.delivered(patch, provenance);
+                }
+                self.routing_metrics.record_patch(&patch.id, bytes);
                 self.routing_metrics
                     .delivered
                     .fetch_add(1, Ordering::Relaxed);
@@ -642,6 +1108,97 @@ impl ModuleHost {
         }
     }
 
+    /// `signal` as the patch's transform leaves it
+    fn transform(&self, patch: &crate::Patch, signal: Signal) -> Signal {
+        let Some(transform) = &patch.transform else {
+            return signal;
+        };
+        match self.patch_transformer.lock() {
+            Ok(mut transformer) => transformer.apply(transform, signal),
+            Err(_) => signal,
+        }
+    }
+
+    /// Audio down one of several patches into a summing port goes into the
+    /// port's mix, which takes its place once the round is over; `None`
+    /// while the round goes on. Anything else passes straight through.
+    fn fan_in(
+        &self,
+        patch_bay: &crate::PatchBay,
+        patch: &crate::Patch,
+        signal: Signal,
+    ) -> Option<Signal> {
+        let Some(audio) = signal.audio() else {
+            return Some(signal);
+        };
+        // Compiled graphs sum what's patched into them
+        if self.graph_patches.contains_key(&patch.id)
+            || patch_bay.merge_policy(&patch.sink_module, &patch.sink_port) != MergePolicy::Sum
+        {
+            return Some(signal);
+        }
+        let feeders = patch_bay
+            .get_port_feeds(&patch.sink_module, &patch.sink_port)
+            .len();
+        if feeders < 2 {
+            return Some(signal);
+        }
+        let Ok(mut mixer) = self.audio_mixer.lock() else {
+            return Some(signal);
+        };
+        mixer.mix(
+            &patch.sink_module,
+            &patch.sink_port,
+            &patch.id,
+            feeders,
+            audio,
+        )
+    }
+
+    /// Patches that deliver to a sink. Disabled sinks are skipped;
+    /// bypassed ones pass the signal on to whatever their first output
+    /// port feeds, as if they had emitted it unchanged.
+    fn delivery_targets<'a>(
+        &self,
+        patch_bay: &'a crate::PatchBay,
+        outgoing: Vec<&'a crate::Patch>,
+    ) -> Vec<&'a crate::Patch> {
+        let mut targets = Vec::new();
+        let mut bypassed = HashSet::new();
+        let mut pending = VecDeque::from(outgoing);
+        while let Some(patch) = pending.pop_front() {
+            let sink = patch.sink_module.as_str();
+            if patch_bay.is_module_disabled(sink) {
+                self.routing_metrics
+                    .disabled
+                    .fetch_add(1, Ordering::Relaxed);
+                continue;
+            }
+            if !patch_bay.is_module_bypassed(sink) {
+                targets.push(patch);
+                continue;
+            }
+            // Each bypassed module forwards once, which also ends cycles
+            if !bypassed.insert(sink) || patch_bay.is_module_silenced(sink) {
+                continue;
+            }
+            self.routing_metrics
+                .bypassed
+                .fetch_add(1, Ordering::Relaxed);
+            self.routing_metrics.record_patch(&patch.id, 0);
+            let Some(port) = patch_bay.get_module(sink).map(default_output_port) else {
+                continue;
+            };
+            pending.extend(
+                patch_bay
+                    .get_outgoing_patches(sink)
+                    .into_iter()
+                    .filter(|next| next.source_port == port),
+            );
+        }
+        targets
+    }
+
     /// Return the lifecycle state of a registered module.
     pub fn module_state(&self, module_id: &str) -> Option<ModuleState> {
         self.modules.get(module_id).map(ModuleHandle::state)
@@ -744,6 +1301,7 @@ mod tests {
                 description: "Test module".to_string(),
                 ports: self.ports.clone(),
                 settings_schema: None,
+                docs: None,
             }
         }
         fn is_enabled(&self) -> bool {
@@ -818,6 +1376,62 @@ mod tests {
         assert_eq!(report.timed_out, vec!["slow_module".to_string()]);
     }
 
+    #[test]
+    fn lazy_modules_queue_signals_until_activated() {
+        let (router_tx, _router_rx) = mpsc::channel(10);
+        let mut host = ModuleHost::new(router_tx);
+        host.begin_startup(
+            "minimal",
+            StartupProfile {
+                default: SpawnMode::Lazy,
+                eager: vec!["eager".to_string()],
+                lazy: Vec::new(),
+            },
+        );
+        let (eager, eager_ran) = TestModule::new("eager");
+        let (lazy, lazy_ran) = TestModule::new("lazy");
+        host.spawn(eager, 10).unwrap();
+        host.spawn(lazy, 10).unwrap();
+        assert!(host.spawn(TestModule::new("lazy").0, 10).is_err());
+
+        // Deferred modules take signals but don't run
+        host.send_signal("lazy", Signal::Text("early".into()))
+            .unwrap();
+        let report = host.finish_startup();
+        assert_eq!(report.profile, "minimal");
+        assert_eq!(report.spawned.len(), 1);
+        assert_eq!(report.spawned[0].0, "eager");
+        assert_eq!(report.deferred, vec!["lazy".to_string()]);
+
+        thread::sleep(Duration::from_millis(50));
+        assert!(eager_ran.load(Ordering::SeqCst));
+        assert!(!lazy_ran.load(Ordering::SeqCst));
+        assert!(host.is_deferred("lazy"));
+        assert_eq!(host.module_state("lazy"), None);
+
+        let port = |id: &str, direction| crate::Port {
+            id: id.to_string(),
+            label: id.to_string(),
+            data_type: crate::DataType::Any,
+            direction,
+        };
+        let mut patch_bay = crate::PatchBay::new();
+        patch_bay.register_module(
+            TestModule::with_ports("eager", vec![port("out", crate::PortDirection::Output)])
+                .schema(),
+        );
+        patch_bay.register_module(
+            TestModule::with_ports("lazy", vec![port("in", crate::PortDirection::Input)]).schema(),
+        );
+        patch_bay.connect("eager", "out", "lazy", "in").unwrap();
+        assert_eq!(host.activate_patched(&patch_bay), vec!["lazy".to_string()]);
+        assert!(!host.activate("lazy"));
+
+        thread::sleep(Duration::from_millis(50));
+        assert!(lazy_ran.load(Ordering::SeqCst));
+        assert_eq!(host.module_state("lazy"), Some(ModuleState::Running));
+    }
+
     #[test]
     fn route_signal_fanout_is_delivered_and_counted() {
         let (router_tx, _router_rx) = mpsc::channel(10);
@@ -858,6 +1472,501 @@ mod tests {
         assert_eq!(result.delivered, 2);
         assert!(!result.dropped);
         assert_eq!(host.routing_metrics().snapshot().fanout_clones, 1);
+
+        host.route_signal(
+            &patch_bay,
+            RoutedSignal::new("source", "out", Signal::Text("hello".to_string())),
+        );
+        let traffic = host.routing_metrics().patch_traffic();
+        let first = traffic[&patch_bay.get_patches()[0].id];
+        assert_eq!((first.messages, first.bytes), (2, 5));
+        assert!(first.messages_per_sec > 0.0 && first.bytes_per_sec > 0.0);
+        assert!(host
+            .routing_metrics()
+            .last_emissions()
+            .contains_key("source"));
+    }
+
+    #[test]
+    fn route_signal_tracks_provenance_along_a_chain() {
+        use crate::Clock;
+        let (router_tx, _router_rx) = mpsc::channel(10);
+        let clock = crate::VirtualClock::new();
+        let mut host = ModuleHost::with_clock(router_tx, clock.clone());
+        let mut patch_bay = crate::PatchBay::new();
+        let port = |id: &str, direction| crate::Port {
+            id: id.to_string(),
+            label: id.to_string(),
+            data_type: crate::DataType::Any,
+            direction,
+        };
+        for (id, ports) in [
+            ("stt", vec![port("out", crate::PortDirection::Output)]),
+            (
+                "parser",
+                vec![
+                    port("in", crate::PortDirection::Input),
+                    port("out", crate::PortDirection::Output),
+                ],
+            ),
+            ("tts", vec![port("in", crate::PortDirection::Input)]),
+        ] {
+            let module = TestModule::with_ports(id, ports);
+            patch_bay.register_module(module.schema());
+            host.spawn(module, 10).unwrap();
+        }
+        patch_bay.connect("stt", "out", "parser", "in").unwrap();
+        patch_bay.connect("parser", "out", "tts", "in").unwrap();
+        let text = |source: &str| RoutedSignal::new(source, "out", Signal::Text("hi".into()));
+
+        // Off by default
+        host.route_signal(&patch_bay, text("stt"));
+        assert!(host.provenance().received("parser").is_none());
+
+        host.provenance().set_enabled(true);
+        clock.advance(Duration::from_millis(5));
+        host.route_signal(&patch_bay, text("stt"));
+        let first = host.provenance().received("parser").unwrap();
+        assert_eq!(first.hops, ["stt"]);
+        assert_eq!(first.created_us, 5_000);
+
+        clock.advance(Duration::from_millis(300));
+        host.route_signal(&patch_bay, text("parser"));
+        let relayed = host.provenance().received("tts").unwrap();
+        assert_eq!(relayed.hops, ["stt", "parser"]);
+        assert_eq!(relayed.correlation_id, first.correlation_id);
+        assert_eq!(
+            relayed.describe(clock.now_us()),
+            format!("#{} stt → parser, 300 ms ago", first.correlation_id)
+        );
+        let patch_id = &patch_bay.get_outgoing_patches("parser")[0].id;
+        assert_eq!(host.provenance().on_patch(patch_id), Some(relayed));
+
+        host.route_signal(&patch_bay, text("stt"));
+        let next = host.provenance().received("parser").unwrap();
+        assert_ne!(next.correlation_id, first.correlation_id);
+
+        // Audio isn't stamped
+        let audio = Signal::Audio {
+            sample_rate: 48_000,
+            channels: 1,
+            timestamp_us: 0,
+            data: vec![0.0; 4],
+        };
+        host.route_signal(&patch_bay, RoutedSignal::new("stt", "out", audio));
+        assert_eq!(host.provenance().received("parser"), Some(next));
+    }
+
+    /// Keeps what reaches it
+    struct RecordingSink {
+        id: String,
+        received: Arc<std::sync::Mutex<Vec<Signal>>>,
+    }
+
+    #[async_trait]
+    impl crate::Sink for RecordingSink {
+        fn name(&self) -> &str {
+            &self.id
+        }
+        fn schema(&self) -> ModuleSchema {
+            ModuleSchema {
+                id: self.id.clone(),
+                name: self.id.clone(),
+                description: "Recording sink".to_string(),
+                ports: vec![crate::Port {
+                    id: "in".to_string(),
+                    label: "In".to_string(),
+                    data_type: crate::DataType::Audio,
+                    direction: crate::PortDirection::Input,
+                }],
+                settings_schema: None,
+                docs: None,
+            }
+        }
+        fn set_enabled(&mut self, _enabled: bool) {}
+        async fn consume(&self, signal: Signal) -> anyhow::Result<Option<Signal>> {
+            self.received.lock().unwrap().push(signal);
+            Ok(None)
+        }
+    }
+
+    #[test]
+    fn route_signal_shares_audio_and_blobs_across_fanout() {
+        let (router_tx, _router_rx) = mpsc::channel(10);
+        let mut host = ModuleHost::new(router_tx);
+        let mut patch_bay = crate::PatchBay::new();
+        let source = TestModule::with_ports(
+            "source",
+            vec![crate::Port {
+                id: "out".to_string(),
+                label: "Out".to_string(),
+                data_type: crate::DataType::Audio,
+                direction: crate::PortDirection::Output,
+            }],
+        );
+        patch_bay.register_module(source.schema());
+        host.spawn(source, 10).unwrap();
+        let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+        for id in ["sink_one", "sink_two", "solo"] {
+            let sink = RecordingSink {
+                id: id.to_string(),
+                received: received.clone(),
+            };
+            patch_bay.register_module(crate::Sink::schema(&sink));
+            host.spawn(crate::SinkAdapter::new(sink), 10).unwrap();
+        }
+        patch_bay
+            .connect("source", "out", "sink_one", "in")
+            .unwrap();
+        patch_bay
+            .connect("source", "out", "sink_two", "in")
+            .unwrap();
+        let audio = || Signal::Audio {
+            sample_rate: 48_000,
+            channels: 2,
+            timestamp_us: 9,
+            data: vec![0.5; 64],
+        };
+
+        let result = host.route_signal(&patch_bay, RoutedSignal::new("source", "out", audio()));
+        assert_eq!(result.delivered, 2);
+        thread::sleep(Duration::from_millis(50));
+        {
+            let received = received.lock().unwrap();
+            assert_eq!(received.len(), 2);
+            let [Signal::SharedAudio { data: first, .. }, Signal::SharedAudio { data: second, .. }] =
+                received.as_slice()
+            else {
+                panic!("expected shared audio, got {:?}", received);
+            };
+            assert!(Arc::ptr_eq(first, second));
+            let view = received[0].audio().unwrap();
+            assert_eq!(
+                (view.sample_rate, view.channels, view.timestamp_us),
+                (48_000, 2, 9)
+            );
+        }
+        // Per-patch byte counts are unchanged by sharing
+        let traffic = host.routing_metrics().patch_traffic();
+        assert_eq!(traffic[&patch_bay.get_patches()[0].id].bytes, 64 * 4);
+
+        // Blobs keep their type when shared
+        received.lock().unwrap().clear();
+        let blob = Signal::Blob {
+            mime_type: "image/png".to_string(),
+            bytes: vec![7; 1024],
+        };
+        host.route_signal(&patch_bay, RoutedSignal::new("source", "out", blob));
+        thread::sleep(Duration::from_millis(50));
+        {
+            let received = received.lock().unwrap();
+            let [Signal::SharedBlob { bytes: first, .. }, Signal::SharedBlob { bytes: second, .. }] =
+                received.as_slice()
+            else {
+                panic!("expected shared blobs, got {:?}", received);
+            };
+            assert!(Arc::ptr_eq(first, second));
+            assert_eq!(received[1].blob().unwrap().mime_type, "image/png");
+        }
+
+        // A single sink still gets the buffer itself
+        for patch in patch_bay.get_patches().to_vec() {
+            patch_bay.disconnect(&patch.id);
+        }
+        patch_bay.connect("source", "out", "solo", "in").unwrap();
+        received.lock().unwrap().clear();
+        host.route_signal(&patch_bay, RoutedSignal::new("source", "out", audio()));
+        thread::sleep(Duration::from_millis(50));
+        assert!(matches!(
+            received.lock().unwrap().as_slice(),
+            [Signal::Audio { .. }]
+        ));
+    }
+
+    #[test]
+    fn route_signal_mixes_audio_only_into_ports_set_to_sum() {
+        let (router_tx, _router_rx) = mpsc::channel(10);
+        let mut host = ModuleHost::new(router_tx);
+        let mut patch_bay = crate::PatchBay::new();
+        for id in ["mic", "replay"] {
+            let source = TestModule::with_ports(
+                id,
+                vec![crate::Port {
+                    id: "out".to_string(),
+                    label: "Out".to_string(),
+                    data_type: crate::DataType::Audio,
+                    direction: crate::PortDirection::Output,
+                }],
+            );
+            patch_bay.register_module(source.schema());
+            host.spawn(source, 10).unwrap();
+        }
+        let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = RecordingSink {
+            id: "speaker".to_string(),
+            received: received.clone(),
+        };
+        patch_bay.register_module(crate::Sink::schema(&sink));
+        host.spawn(crate::SinkAdapter::new(sink), 10).unwrap();
+        patch_bay.connect("mic", "out", "speaker", "in").unwrap();
+        patch_bay.connect("replay", "out", "speaker", "in").unwrap();
+        let audio = |level: f32| Signal::Audio {
+            sample_rate: 48_000,
+            channels: 1,
+            timestamp_us: 0,
+            data: vec![level; 4],
+        };
+
+        // Unless told to sum, each buffer passes on as it comes, as before
+        // ports could merge
+        host.route_signal(&patch_bay, RoutedSignal::new("mic", "out", audio(0.25)));
+        host.route_signal(&patch_bay, RoutedSignal::new("replay", "out", audio(0.5)));
+        thread::sleep(Duration::from_millis(50));
+        assert_eq!(received.lock().unwrap().len(), 2);
+
+        // A patch's transform changes only what crosses that patch
+        received.lock().unwrap().clear();
+        let mic_patch = patch_bay.get_incoming_patches("speaker")[0].id.clone();
+        patch_bay
+            .set_patch_transform(&mic_patch, Some(crate::PatchTransform::Gain { gain: 0.5 }))
+            .unwrap();
+        host.route_signal(&patch_bay, RoutedSignal::new("mic", "out", audio(0.25)));
+        host.route_signal(&patch_bay, RoutedSignal::new("replay", "out", audio(0.5)));
+        thread::sleep(Duration::from_millis(50));
+        {
+            let received = received.lock().unwrap();
+            assert_eq!(received[0].audio().unwrap().data, [0.125; 4]);
+            assert_eq!(received[1].audio().unwrap().data, [0.5; 4]);
+        }
+
+        received.lock().unwrap().clear();
+        patch_bay.set_patch_transform(&mic_patch, None).unwrap();
+        patch_bay
+            .set_merge_policy("speaker", "in", crate::MergePolicy::Sum)
+            .unwrap();
+        host.route_signal(&patch_bay, RoutedSignal::new("mic", "out", audio(0.25)));
+        host.route_signal(&patch_bay, RoutedSignal::new("replay", "out", audio(0.5)));
+        thread::sleep(Duration::from_millis(50));
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].audio().unwrap().data, [0.75; 4]);
+    }
+
+    /// Scales audio, in the router or in a compiled graph
+    struct Gain {
+        id: String,
+        gain: f32,
+    }
+
+    struct GainNode(f32);
+
+    impl RtAudioNode for GainNode {
+        fn process_block(
+            &mut self,
+            _context: &crate::BlockContext,
+            input: &[f32],
+            output: &mut [f32],
+        ) {
+            for (out, sample) in output.iter_mut().zip(input) {
+                *out = sample * self.0;
+            }
+        }
+    }
+
+    #[async_trait]
+    impl crate::Processor for Gain {
+        fn name(&self) -> &str {
+            &self.id
+        }
+        fn schema(&self) -> ModuleSchema {
+            let port = |id: &str, direction| crate::Port {
+                id: id.to_string(),
+                label: id.to_string(),
+                data_type: crate::DataType::Audio,
+                direction,
+            };
+            ModuleSchema {
+                id: self.id.clone(),
+                name: self.id.clone(),
+                description: "Gain".to_string(),
+                ports: vec![
+                    port("audio_in", crate::PortDirection::Input),
+                    port("audio_out", crate::PortDirection::Output),
+                ],
+                settings_schema: None,
+                docs: None,
+            }
+        }
+        fn set_enabled(&mut self, _enabled: bool) {}
+        async fn process(&mut self, signal: Signal) -> anyhow::Result<Option<Signal>> {
+            Ok(Some(signal))
+        }
+        fn rt_audio_node(&mut self) -> Option<Box<dyn RtAudioNode>> {
+            Some(Box::new(GainNode(self.gain)))
+        }
+    }
+
+    #[test]
+    fn compiled_audio_graphs_take_over_their_patches() {
+        let (router_tx, mut router_rx) = mpsc::channel(10);
+        let mut host = ModuleHost::new(router_tx);
+        let mut patch_bay = crate::PatchBay::new();
+        let mic = TestModule::with_ports(
+            "mic",
+            vec![crate::Port {
+                id: "out".to_string(),
+                label: "Out".to_string(),
+                data_type: crate::DataType::Audio,
+                direction: crate::PortDirection::Output,
+            }],
+        );
+        patch_bay.register_module(mic.schema());
+        host.spawn(mic, 10).unwrap();
+        for (id, gain) in [("a", 2.0), ("b", 0.25)] {
+            let gain = Gain {
+                id: id.to_string(),
+                gain,
+            };
+            patch_bay.register_module(crate::Processor::schema(&gain));
+            host.spawn(crate::adapters::ProcessorAdapter::new(gain), 10)
+                .unwrap();
+        }
+        let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let speaker = RecordingSink {
+            id: "speaker".to_string(),
+            received: received.clone(),
+        };
+        patch_bay.register_module(crate::Sink::schema(&speaker));
+        host.spawn(crate::SinkAdapter::new(speaker), 10).unwrap();
+        patch_bay.connect("mic", "out", "a", "audio_in").unwrap();
+        patch_bay
+            .connect("a", "audio_out", "b", "audio_in")
+            .unwrap();
+        patch_bay
+            .connect("b", "audio_out", "speaker", "in")
+            .unwrap();
+
+        host.compile_audio_graphs(&patch_bay, 4);
+        assert_eq!(host.audio_graphs()[0].members, vec!["a", "b"]);
+        let audio = Signal::Audio {
+            sample_rate: 1_000,
+            channels: 1,
+            timestamp_us: 0,
+            data: vec![1.0; 4],
+        };
+        let result = host.route_signal(&patch_bay, RoutedSignal::new("mic", "out", audio));
+        assert_eq!(result.delivered, 1);
+
+        // The graph's output comes back through the router from its last module
+        let routed = router_rx.blocking_recv().unwrap();
+        assert_eq!(
+            (routed.source_id.as_str(), routed.source_port.as_str()),
+            ("b", "audio_out")
+        );
+        assert_eq!(routed.signal.audio().unwrap().data, &[0.5; 4]);
+        assert_eq!(host.route_signal(&patch_bay, routed).delivered, 1);
+        thread::sleep(Duration::from_millis(50));
+        assert_eq!(received.lock().unwrap().len(), 1);
+
+        // Unchanged, nothing is rebuilt; bypassing a member leaves too little
+        host.compile_audio_graphs(&patch_bay, 4);
+        assert_eq!(host.audio_graphs()[0].blocks, 1);
+        patch_bay.set_module_bypassed("b", true);
+        host.compile_audio_graphs(&patch_bay, 4);
+        assert!(host.audio_graphs().is_empty());
+        patch_bay.set_module_bypassed("b", false);
+        host.compile_audio_graphs(&patch_bay, 4);
+        assert_eq!(host.audio_graphs().len(), 1);
+    }
+
+    #[test]
+    fn route_signal_drops_muted_sources() {
+        let (router_tx, _router_rx) = mpsc::channel(10);
+        let mut host = ModuleHost::new(router_tx);
+        let mut patch_bay = crate::PatchBay::new();
+        let output = crate::Port {
+            id: "out".to_string(),
+            label: "Out".to_string(),
+            data_type: crate::DataType::Any,
+            direction: crate::PortDirection::Output,
+        };
+        let input = crate::Port {
+            id: "in".to_string(),
+            label: "In".to_string(),
+            data_type: crate::DataType::Any,
+            direction: crate::PortDirection::Input,
+        };
+        let source = TestModule::with_ports("source", vec![output]);
+        let sink = TestModule::with_ports("sink", vec![input]);
+        patch_bay.register_module(source.schema());
+        patch_bay.register_module(sink.schema());
+        patch_bay.connect("source", "out", "sink", "in").unwrap();
+        host.spawn(source, 10).unwrap();
+        host.spawn(sink, 10).unwrap();
+
+        patch_bay.set_module_muted("source", true);
+        let muted = host.route_signal(
+            &patch_bay,
+            RoutedSignal::new("source", "out", Signal::Pulse),
+        );
+        assert_eq!(muted.delivered, 0);
+        assert!(muted.dropped);
+        assert_eq!(host.routing_metrics().snapshot().muted, 1);
+
+        patch_bay.set_module_muted("source", false);
+        let unmuted = host.route_signal(
+            &patch_bay,
+            RoutedSignal::new("source", "out", Signal::Pulse),
+        );
+        assert_eq!(unmuted.delivered, 1);
+    }
+
+    #[test]
+    fn route_signal_passes_through_bypassed_processors() {
+        let (router_tx, _router_rx) = mpsc::channel(10);
+        let mut host = ModuleHost::new(router_tx);
+        let mut patch_bay = crate::PatchBay::new();
+        let output = crate::Port {
+            id: "out".to_string(),
+            label: "Out".to_string(),
+            data_type: crate::DataType::Any,
+            direction: crate::PortDirection::Output,
+        };
+        let input = crate::Port {
+            id: "in".to_string(),
+            label: "In".to_string(),
+            data_type: crate::DataType::Any,
+            direction: crate::PortDirection::Input,
+        };
+        let source = TestModule::with_ports("source", vec![output.clone()]);
+        let effect = TestModule::with_ports("effect", vec![input.clone(), output]);
+        let sink = TestModule::with_ports("sink", vec![input]);
+        patch_bay.register_module(source.schema());
+        patch_bay.register_module(effect.schema());
+        patch_bay.register_module(sink.schema());
+        patch_bay.connect("source", "out", "effect", "in").unwrap();
+        patch_bay.connect("effect", "out", "sink", "in").unwrap();
+        host.spawn(source, 10).unwrap();
+        host.spawn(effect, 10).unwrap();
+        host.spawn(sink, 10).unwrap();
+
+        patch_bay.set_module_bypassed("effect", true);
+        let sinks = |host: &ModuleHost, patch_bay: &crate::PatchBay| {
+            host.delivery_targets(patch_bay, patch_bay.get_outgoing_patches("source"))
+                .iter()
+                .map(|patch| patch.sink_module.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(sinks(&host, &patch_bay), vec!["sink"]);
+        let result = host.route_signal(
+            &patch_bay,
+            RoutedSignal::new("source", "out", Signal::Pulse),
+        );
+        assert_eq!(result.delivered, 1);
+        assert_eq!(host.routing_metrics().snapshot().bypassed, 2);
+
+        patch_bay.set_module_bypassed("effect", false);
+        assert_eq!(sinks(&host, &patch_bay), vec!["effect"]);
     }
 
     #[test]
@@ -0,0 +1,148 @@
+//! Seeds for generative modules
+//!
+//! Modules that draw random numbers take their seed from a [`SeedSource`]
+//! instead of the OS or the time of day, the way paced modules read time
+//! through a [`Clock`](crate::Clock). The daemon's source gets a new seed
+//! per session (recorded in its `session.json`), so a take can be run again
+//! with the same numbers by pinning that seed.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Shared handle to a seed source, cheap to clone into modules
+pub type SharedSeed = Arc<SeedSource>;
+
+/// A seed nobody chose, for when no session pins one
+pub fn random_seed() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+/// The seed generative modules derive theirs from
+#[derive(Debug)]
+pub struct SeedSource {
+    seed: AtomicU64,
+    /// Bumped on every change, so modules know to reseed
+    generation: AtomicU64,
+    /// Set when replaying a session: new sessions keep the seed
+    pinned: AtomicBool,
+}
+
+impl Default for SeedSource {
+    fn default() -> Self {
+        Self {
+            seed: AtomicU64::new(random_seed()),
+            generation: AtomicU64::new(0),
+            pinned: AtomicBool::new(false),
+        }
+    }
+}
+
+impl SeedSource {
+    pub fn new(seed: u64) -> SharedSeed {
+        let source = Self::default();
+        source.seed.store(seed, Ordering::Release);
+        Arc::new(source)
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed.load(Ordering::Acquire)
+    }
+
+    pub fn set(&self, seed: u64) {
+        self.seed.store(seed, Ordering::Release);
+        self.generation.fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// Use `seed` from now on, whatever later sessions ask for
+    pub fn pin(&self, seed: u64) {
+        self.pinned.store(true, Ordering::Release);
+        self.set(seed);
+    }
+
+    /// The seed, when [`pin`](Self::pin)ned
+    pub fn pinned(&self) -> Option<u64> {
+        self.pinned.load(Ordering::Acquire).then(|| self.seed())
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    /// `module_id`'s own seed: the same for the same id and source seed,
+    /// different between modules
+    pub fn derive(&self, module_id: &str) -> u64 {
+        derive(self.seed(), module_id)
+    }
+}
+
+/// Mix `module_id` into `seed` (FNV-1a, then splitmix64)
+pub fn derive(seed: u64, module_id: &str) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in module_id.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    let mut z = (seed ^ hash).wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// One module's view of a [`SeedSource`], noticing when it changes
+#[derive(Debug, Clone)]
+pub struct ModuleSeed {
+    source: SharedSeed,
+    module_id: String,
+    generation: u64,
+}
+
+impl ModuleSeed {
+    pub fn new(source: SharedSeed, module_id: &str) -> Self {
+        Self {
+            generation: source.generation(),
+            source,
+            module_id: module_id.to_string(),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.source.derive(&self.module_id)
+    }
+
+    /// The new seed if the source changed since the last call
+    pub fn changed(&mut self) -> Option<u64> {
+        let generation = self.source.generation();
+        if generation == self.generation {
+            return None;
+        }
+        self.generation = generation;
+        Some(self.seed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derived_seeds_follow_the_source_and_differ_per_module() {
+        let source = SeedSource::new(42);
+        assert_eq!(source.derive("dice"), derive(42, "dice"));
+        assert_ne!(source.derive("dice"), source.derive("particles"));
+
+        let mut dice = ModuleSeed::new(source.clone(), "dice");
+        let first = dice.seed();
+        assert_eq!(dice.changed(), None);
+        source.set(7);
+        assert_eq!(dice.changed(), Some(derive(7, "dice")));
+        assert_eq!(dice.changed(), None);
+        source.set(42);
+        assert_eq!(dice.changed(), Some(first));
+
+        assert_eq!(source.pinned(), None);
+        source.pin(99);
+        assert_eq!(source.pinned(), Some(99));
+    }
+}
@@ -0,0 +1,380 @@
+//! Sessions: one timestamped folder per take
+//!
+//! While a session runs, modules that write files (Save File recordings and
+//! transcript exports) resolve their relative paths into its folder, so everything from one take ends up together next to a
+//! `session.json` describing it. Each session also gets the seed the
+//! generative modules draw from (see [`crate::seed`]), kept in that file so
+//! the take can be reproduced.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::seed::{random_seed, SharedSeed};
+use crate::Signal;
+
+/// Intent announcing a new session: `session_start [id, dir]`
+pub const SESSION_START: &str = "session_start";
+/// Intent announcing the end of one: `session_end [id, dir]`
+pub const SESSION_END: &str = "session_end";
+/// Intent stamping the moment it arrives with a label: `marker [label]`
+pub const MARKER: &str = "marker";
+/// Metadata file written into every session folder
+pub const SESSION_FILE: &str = "session.json";
+
+/// `[session]` in the layout
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct SessionConfig {
+    /// Folder the session folders are created in
+    pub root: PathBuf,
+    /// Start a session when the daemon starts
+    pub auto_start: bool,
+    /// Seed every session uses, for repeatable runs; a fresh one per
+    /// session when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            root: PathBuf::from("sessions"),
+            auto_start: false,
+            seed: None,
+        }
+    }
+}
+
+impl SessionConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// What `session.json` holds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionInfo {
+    /// Folder name: start time, plus the project name when given
+    pub id: String,
+    pub name: Option<String>,
+    pub dir: PathBuf,
+    /// RFC 3339 local times
+    pub started_at: String,
+    pub ended_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub markers: Vec<SessionMarker>,
+    /// Seed the generative modules drew from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+}
+
+/// A labelled moment on a session's timeline
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionMarker {
+    pub label: String,
+    /// Since the session started
+    pub offset_ms: u64,
+}
+
+/// `marker [label]`
+pub fn marker_signal(label: &str) -> Signal {
+    Signal::Intent {
+        action: MARKER.to_string(),
+        parameters: vec![label.to_string()],
+    }
+}
+
+/// Label of a `marker` intent, `"marker"` when it has none; the first
+/// parameter is used, so a trigger word with the `marker` action labels the
+/// moment with what was heard
+pub fn marker_label(signal: &Signal) -> Option<String> {
+    match signal {
+        Signal::Intent { action, parameters } if action == MARKER => Some(
+            parameters
+                .first()
+                .map(|label| label.trim())
+                .filter(|label| !label.is_empty())
+                .unwrap_or(MARKER)
+                .to_string(),
+        ),
+        _ => None,
+    }
+}
+
+impl SessionInfo {
+    fn signal(&self, action: &str) -> Signal {
+        Signal::Intent {
+            action: action.to_string(),
+            parameters: vec![self.id.clone(), self.dir.display().to_string()],
+        }
+    }
+
+    pub fn start_signal(&self) -> Signal {
+        self.signal(SESSION_START)
+    }
+
+    pub fn end_signal(&self) -> Signal {
+        self.signal(SESSION_END)
+    }
+
+    /// Read a `session.json`, given it or the session folder holding it
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        let file = if path.is_dir() {
+            path.join(SESSION_FILE)
+        } else {
+            path.to_path_buf()
+        };
+        let json = std::fs::read_to_string(file)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    fn write(&self) -> std::io::Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(self.dir.join(SESSION_FILE), json)
+    }
+}
+
+/// The running session, shared between the daemon and the modules writing
+/// into it; clones share state
+#[derive(Debug, Clone, Default)]
+pub struct SessionState {
+    inner: Arc<SessionInner>,
+}
+
+#[derive(Debug, Default)]
+struct SessionInner {
+    config: Mutex<SessionConfig>,
+    current: Mutex<Option<SessionInfo>>,
+    generation: AtomicU64,
+    seeds: SharedSeed,
+}
+
+impl SessionState {
+    pub fn new(config: SessionConfig) -> Self {
+        Self::with_seeds(config, SharedSeed::default())
+    }
+
+    /// A session state reseeding `seeds` at every start
+    pub fn with_seeds(config: SessionConfig, seeds: SharedSeed) -> Self {
+        Self {
+            inner: Arc::new(SessionInner {
+                config: Mutex::new(config),
+                seeds,
+                ..SessionInner::default()
+            }),
+        }
+    }
+
+    /// Seed source for generative modules, reseeded at every session start
+    pub fn seeds(&self) -> SharedSeed {
+        self.inner.seeds.clone()
+    }
+
+    pub fn config(&self) -> SessionConfig {
+        self.inner.config.lock().unwrap().clone()
+    }
+
+    /// Takes effect from the next session
+    pub fn set_config(&self, config: SessionConfig) {
+        *self.inner.config.lock().unwrap() = config;
+    }
+
+    pub fn current(&self) -> Option<SessionInfo> {
+        self.inner.current.lock().unwrap().clone()
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.inner.current.lock().unwrap().is_some()
+    }
+
+    /// Folder of the running session
+    pub fn dir(&self) -> Option<PathBuf> {
+        self.inner
+            .current
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|session| session.dir.clone())
+    }
+
+    /// Changes on every start and stop, so writers holding a file open can
+    /// tell they've crossed a boundary
+    pub fn generation(&self) -> u64 {
+        self.inner.generation.load(Ordering::Acquire)
+    }
+
+    /// `path` inside the running session's folder when it's relative;
+    /// unchanged when it's absolute or no session is running
+    pub fn resolve(&self, path: impl AsRef<Path>) -> PathBuf {
+        let path = path.as_ref();
+        match self.dir() {
+            Some(dir) if path.is_relative() => dir.join(path),
+            _ => path.to_path_buf(),
+        }
+    }
+
+    /// Start a session, ending the running one first; returns the ended
+    /// session, if any, and the new one
+    pub fn start(&self, name: Option<&str>) -> std::io::Result<(Option<SessionInfo>, SessionInfo)> {
+        let ended = self.stop()?;
+        let now = chrono::Local::now();
+        let name = name.map(str::trim).filter(|name| !name.is_empty());
+        let mut id = now.format("%Y-%m-%d_%H-%M-%S").to_string();
+        if let Some(name) = name {
+            let safe: String = name
+                .chars()
+                .map(|c| {
+                    if c.is_alphanumeric() || c == '-' {
+                        c
+                    } else {
+                        '_'
+                    }
+                })
+                .collect();
+            id = format!("{}_{}", id, safe);
+        }
+        let root = self.config().root;
+        std::fs::create_dir_all(&root)?;
+        // Two sessions in the same second get -2, -3, ...
+        let mut dir = root.join(&id);
+        let mut suffix = 1;
+        while dir.exists() {
+            suffix += 1;
+            dir = root.join(format!("{}-{}", id, suffix));
+        }
+        std::fs::create_dir(&dir)?;
+        let seeds = &self.inner.seeds;
+        let seed = seeds
+            .pinned()
+            .or(self.config().seed)
+            .unwrap_or_else(random_seed);
+        seeds.set(seed);
+        let session = SessionInfo {
+            id: dir
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or(id),
+            name: name.map(str::to_string),
+            dir,
+            started_at: now.to_rfc3339(),
+            ended_at: None,
+            markers: Vec::new(),
+            seed: Some(seed),
+        };
+        session.write()?;
+        *self.inner.current.lock().unwrap() = Some(session.clone());
+        self.inner.generation.fetch_add(1, Ordering::AcqRel);
+        Ok((ended, session))
+    }
+
+    /// Add a marker to the running session's `session.json`; `None` when
+    /// no session is running
+    pub fn mark(&self, label: &str) -> std::io::Result<Option<SessionMarker>> {
+        let mut current = self.inner.current.lock().unwrap();
+        let Some(session) = current.as_mut() else {
+            return Ok(None);
+        };
+        let offset_ms = chrono::DateTime::parse_from_rfc3339(&session.started_at)
+            .ok()
+            .and_then(|started| {
+                (chrono::Local::now() - started.with_timezone(&chrono::Local))
+                    .to_std()
+                    .ok()
+            })
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or(0);
+        let marker = SessionMarker {
+            label: label.to_string(),
+            offset_ms,
+        };
+        session.markers.push(marker.clone());
+        session.write()?;
+        Ok(Some(marker))
+    }
+
+    /// End the running session, recording when in its `session.json`
+    pub fn stop(&self) -> std::io::Result<Option<SessionInfo>> {
+        let Some(mut session) = self.inner.current.lock().unwrap().take() else {
+            return Ok(None);
+        };
+        self.inner.generation.fetch_add(1, Ordering::AcqRel);
+        session.ended_at = Some(chrono::Local::now().to_rfc3339());
+        session.write()?;
+        Ok(Some(session))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn marker_intents_carry_a_label() {
+        assert_eq!(
+            marker_label(&marker_signal("intro")).as_deref(),
+            Some("intro")
+        );
+        let bare = Signal::Intent {
+            action: MARKER.to_string(),
+            parameters: vec![],
+        };
+        assert_eq!(marker_label(&bare).as_deref(), Some("marker"));
+        assert!(marker_label(&Signal::Text("marker".into())).is_none());
+    }
+
+    #[test]
+    fn sessions_get_their_own_folder_and_metadata() {
+        let root = std::env::temp_dir().join(format!("magnolia_session_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let sessions = SessionState::new(SessionConfig {
+            root: root.clone(),
+            auto_start: false,
+            seed: Some(7),
+        });
+        assert_eq!(sessions.resolve("take.wav"), PathBuf::from("take.wav"));
+
+        let (ended, first) = sessions.start(Some("demo take")).unwrap();
+        assert!(ended.is_none());
+        assert!(first.id.ends_with("_demo_take"));
+        assert_eq!(first.seed, Some(7));
+        assert_eq!(sessions.seeds().seed(), 7);
+        assert_eq!(sessions.resolve("take.wav"), first.dir.join("take.wav"));
+        assert_eq!(sessions.resolve("/tmp/x.wav"), PathBuf::from("/tmp/x.wav"));
+
+        // Starting again in the same second ends the first and doesn't reuse it
+        let generation = sessions.generation();
+        let (ended, second) = sessions.start(Some("demo take")).unwrap();
+        assert_eq!(ended.unwrap().id, first.id);
+        assert_ne!(second.dir, first.dir);
+        assert!(sessions.generation() > generation);
+
+        let written = SessionInfo::load(&first.dir).unwrap();
+        assert!(written.ended_at.is_some());
+        assert_eq!(written.seed, Some(7));
+        assert_eq!(
+            sessions.mark("applause").unwrap().unwrap().label,
+            "applause"
+        );
+        assert!(matches!(
+            second.start_signal(),
+            Signal::Intent { action, parameters }
+                if action == SESSION_START && parameters[0] == second.id
+        ));
+
+        let ended = sessions.stop().unwrap().unwrap();
+        assert_eq!(ended.id, second.id);
+        assert_eq!(ended.markers.len(), 1);
+        assert!(sessions.mark("too late").unwrap().is_none());
+        assert!(!sessions.is_active());
+
+        // A pinned seed outlasts the configured one
+        sessions.seeds().pin(123);
+        let (_, replay) = sessions.start(None).unwrap();
+        assert_eq!(replay.seed, Some(123));
+        sessions.stop().unwrap();
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,182 @@
+//! Silence detection over audio buffers
+//!
+//! Shared by the recorder, which splits files at silence, and the silence
+//! processor, which turns the transitions into intents.
+
+use serde::{Deserialize, Serialize};
+
+/// When a stream counts as silent
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SilenceConfig {
+    /// RMS level (dBFS) below which a buffer is quiet
+    pub threshold_db: f32,
+    /// How long the stream must stay quiet before it is silent
+    pub hold_ms: u32,
+}
+
+impl Default for SilenceConfig {
+    fn default() -> Self {
+        Self {
+            threshold_db: -50.0,
+            hold_ms: 1500,
+        }
+    }
+}
+
+impl SilenceConfig {
+    /// Reads `silence_threshold_db` / `silence_hold_ms` (or the bare
+    /// `threshold_db` / `hold_ms`) from module settings, keeping the rest
+    pub fn apply_settings(&mut self, settings: &serde_json::Value) {
+        let get = |prefixed: &str, bare: &str| {
+            settings
+                .get(prefixed)
+                .or_else(|| settings.get(bare))
+                .and_then(|v| v.as_f64())
+        };
+        if let Some(db) = get("silence_threshold_db", "threshold_db") {
+            self.threshold_db = (db as f32).clamp(-96.0, 0.0);
+        }
+        if let Some(ms) = get("silence_hold_ms", "hold_ms") {
+            self.hold_ms = ms.clamp(0.0, 600_000.0) as u32;
+        }
+    }
+}
+
+/// A change between sound and silence
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SilenceEvent {
+    /// The stream has been quiet for the hold time
+    Start,
+    /// Sound above the threshold after a silence
+    End,
+}
+
+impl SilenceEvent {
+    /// Intent action for the event
+    pub fn action(&self) -> &'static str {
+        match self {
+            SilenceEvent::Start => "silence_start",
+            SilenceEvent::End => "silence_end",
+        }
+    }
+}
+
+/// Tracks whether a stream is silent, one buffer at a time
+///
+/// A stream starts out sounding, so one that opens quiet reports
+/// [`SilenceEvent::Start`] once the hold time has passed.
+#[derive(Debug, Clone)]
+pub struct SilenceDetector {
+    config: SilenceConfig,
+    silent: bool,
+    quiet_us: u64,
+}
+
+impl SilenceDetector {
+    pub fn new(config: SilenceConfig) -> Self {
+        Self {
+            config,
+            silent: false,
+            quiet_us: 0,
+        }
+    }
+
+    pub fn config(&self) -> SilenceConfig {
+        self.config
+    }
+
+    pub fn set_config(&mut self, config: SilenceConfig) {
+        self.config = config;
+    }
+
+    pub fn is_silent(&self) -> bool {
+        self.silent
+    }
+
+    /// Forget the current run, as if the stream had just started
+    pub fn reset(&mut self) {
+        self.silent = false;
+        self.quiet_us = 0;
+    }
+
+    /// Feed one interleaved buffer, returning the transition it caused
+    pub fn process(
+        &mut self,
+        sample_rate: u32,
+        channels: u16,
+        data: &[f32],
+    ) -> Option<SilenceEvent> {
+        if data.is_empty() || sample_rate == 0 {
+            return None;
+        }
+        let rms =
+            (data.iter().map(|sample| sample * sample).sum::<f32>() / data.len() as f32).sqrt();
+        let level_db = 20.0 * rms.max(1e-9).log10();
+
+        if level_db >= self.config.threshold_db {
+            self.quiet_us = 0;
+            if self.silent {
+                self.silent = false;
+                return Some(SilenceEvent::End);
+            }
+            return None;
+        }
+
+        let frames = (data.len() / channels.max(1) as usize) as u64;
+        self.quiet_us += frames * 1_000_000 / sample_rate as u64;
+        if !self.silent && self.quiet_us >= self.config.hold_ms as u64 * 1_000 {
+            self.silent = true;
+            return Some(SilenceEvent::Start);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_silence_after_the_hold_and_sound_right_away() {
+        let mut detector = SilenceDetector::new(SilenceConfig {
+            threshold_db: -40.0,
+            hold_ms: 300,
+        });
+        let quiet = vec![0.001; 100];
+        let loud = vec![0.5; 100];
+
+        // 100 ms buffers at 1 kHz
+        assert_eq!(detector.process(1_000, 1, &loud), None);
+        assert_eq!(detector.process(1_000, 1, &quiet), None);
+        assert_eq!(detector.process(1_000, 1, &quiet), None);
+        assert_eq!(
+            detector.process(1_000, 1, &quiet),
+            Some(SilenceEvent::Start)
+        );
+        assert!(detector.is_silent());
+        assert_eq!(detector.process(1_000, 1, &quiet), None);
+        assert_eq!(detector.process(1_000, 1, &loud), Some(SilenceEvent::End));
+
+        // A blip of sound restarts the hold
+        assert_eq!(detector.process(1_000, 1, &quiet), None);
+        assert_eq!(detector.process(1_000, 1, &quiet), None);
+        assert_eq!(detector.process(1_000, 1, &loud), None);
+        assert_eq!(detector.process(1_000, 1, &quiet), None);
+        assert!(!detector.is_silent());
+    }
+
+    #[test]
+    fn settings_accept_either_key_form() {
+        let mut config = SilenceConfig::default();
+        config.apply_settings(&serde_json::json!({ "silence_threshold_db": -30.0 }));
+        config.apply_settings(&serde_json::json!({ "hold_ms": 500 }));
+        assert_eq!(
+            config,
+            SilenceConfig {
+                threshold_db: -30.0,
+                hold_ms: 500,
+            }
+        );
+    }
+}
@@ -21,7 +21,8 @@
 use magnolia_ui::{draw_text, FontId, TextAlignment};
 use nannou::prelude::*;
 use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
 
 /// Describes an action that can be bound to a key
 #[derive(Debug, Clone)]
@@ -128,6 +129,91 @@ pub enum ErrorSeverity {
     Error,   // Red - something is wrong
 }
 
+/// Distinct errors kept per tile; older ones are dropped first
+pub const ERROR_HISTORY_LEN: usize = 32;
+
+/// One distinct error a tile has reported
+#[derive(Debug, Clone)]
+pub struct ErrorRecord {
+    pub message: String,
+    pub details: Option<String>,
+    pub severity: ErrorSeverity,
+    pub first_seen: Instant,
+    pub last_seen: Instant,
+    /// Separate occurrences (the error cleared or changed in between)
+    pub count: u32,
+}
+
+/// Errors reported by one tile, most recent first
+///
+/// Tiles only expose their current error, so the registry samples it every
+/// frame; an error that stays up counts once, one that clears and comes back
+/// counts again. Info messages are status, not failures, and are not kept.
+#[derive(Debug, Clone, Default)]
+pub struct ErrorHistory {
+    records: Vec<ErrorRecord>,
+    active: bool,
+}
+
+impl ErrorHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sample a tile's current error
+    pub fn observe(&mut self, error: Option<&TileError>, now: Instant) {
+        let Some(error) = error.filter(|e| e.severity != ErrorSeverity::Info) else {
+            self.active = false;
+            return;
+        };
+
+        let existing = self
+            .records
+            .iter()
+            .position(|r| r.message == error.message && r.details == error.details);
+        let record = match existing {
+            Some(pos) => {
+                let mut record = self.records.remove(pos);
+                if !(self.active && pos == 0) {
+                    record.count += 1;
+                }
+                record.severity = error.severity;
+                record.last_seen = now;
+                record
+            }
+            None => ErrorRecord {
+                message: error.message.clone(),
+                details: error.details.clone(),
+                severity: error.severity,
+                first_seen: now,
+                last_seen: now,
+                count: 1,
+            },
+        };
+        self.records.insert(0, record);
+        self.records.truncate(ERROR_HISTORY_LEN);
+        self.active = true;
+    }
+
+    pub fn records(&self) -> &[ErrorRecord] {
+        &self.records
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Total occurrences across all recorded errors
+    pub fn total(&self) -> u32 {
+        self.records.iter().map(|r| r.count).sum()
+    }
+
+    /// Whether the most recent error is still being reported
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+}
+
 /// Core trait for all renderable tiles
 ///
 /// Tiles have two rendering modes:
@@ -178,6 +264,10 @@ pub trait TileRenderer: Send + Sync {
         false
     }
 
+    /// The power profile this tile now runs at changed; slow down any
+    /// polling of its own to match
+    fn set_power_profile(&mut self, _profile: crate::PowerProfile) {}
+
     // === ERROR HANDLING ===
 
     /// Get current error state, if any
@@ -188,6 +278,12 @@ pub trait TileRenderer: Send + Sync {
     /// Clear the current error
     fn clear_error(&mut self) {}
 
+    /// Reinitialize the backing module after an error (e.g. rebuild the
+    /// audio stream). Returns true if the tile handled the retry itself.
+    fn retry(&mut self) -> bool {
+        false
+    }
+
     // === LIFECYCLE ===
 
     /// Update tile state (called each frame before render)
@@ -208,6 +304,11 @@ pub trait TileRenderer: Send + Sync {
         serde_json::Value::Null
     }
 
+    /// Version `get_settings` is persisted as (see `ModuleRuntime::settings_version`)
+    fn settings_version(&self) -> u32 {
+        0
+    }
+
     // === KEYBINDS ===
 
     /// List of action names that can be bound to keys
@@ -229,21 +330,47 @@ pub trait TileRenderer: Send + Sync {
 /// Central registry for tile instances
 pub struct TileRegistry {
     tiles: HashMap<String, Arc<RwLock<Box<dyn TileRenderer>>>>,
+    /// Modules whose tile went away (plugin unloaded, failed reload), with the reason
+    unavailable: HashMap<String, String>,
+    /// Error history per module, sampled on every update
+    errors: Mutex<HashMap<String, ErrorHistory>>,
+    /// Profile each tile was last told it runs at
+    power: Mutex<HashMap<String, crate::PowerProfile>>,
 }
 
 impl TileRegistry {
     pub fn new() -> Self {
         Self {
             tiles: HashMap::new(),
+            unavailable: HashMap::new(),
+            errors: Mutex::new(HashMap::new()),
+            power: Mutex::new(HashMap::new()),
         }
     }
 
-    /// Register a new tile instance
+    /// Register a new tile instance, replacing any tile with the same ID
     pub fn register<T: TileRenderer + 'static>(&mut self, tile: T) {
         let id = tile.id().to_string();
+        self.unavailable.remove(&id);
         self.tiles.insert(id, Arc::new(RwLock::new(Box::new(tile))));
     }
 
+    /// Remove a tile; returns false if none was registered under `id`
+    pub fn unregister(&mut self, id: &str) -> bool {
+        self.tiles.remove(id).is_some()
+    }
+
+    /// Remove a tile and remember why, so layouts can show a placeholder
+    pub fn mark_unavailable(&mut self, id: &str, reason: &str) {
+        self.tiles.remove(id);
+        self.unavailable.insert(id.to_string(), reason.to_string());
+    }
+
+    /// Why a module's tile is missing, if it was marked unavailable
+    pub fn unavailable_reason(&self, id: &str) -> Option<&str> {
+        self.unavailable.get(id).map(String::as_str)
+    }
+
     /// Get a tile by ID
     pub fn get(&self, id: &str) -> Option<Arc<RwLock<Box<dyn TileRenderer>>>> {
         self.tiles.get(id).cloned()
@@ -258,42 +385,67 @@ impl TileRegistry {
 
     /// Update all tiles (call each frame)
     pub fn update_all(&self) {
-        for tile in self.tiles.values() {
+        for (id, tile) in &self.tiles {
             if let Ok(mut t) = tile.write() {
                 t.update();
+                self.observe_error(id, t.get_error());
             }
         }
     }
 
-    /// Update all tiles with power-aware throttling
-    pub fn update_all_with_power(&self, profile: crate::PowerProfile, frame_count: u64) {
-        for tile in self.tiles.values() {
+    /// Update all tiles with power-aware throttling. Each tile runs at
+    /// `profile` adjusted for its importance; background tiles pause at the
+    /// bottom profile.
+    pub fn update_all_with_power(
+        &self,
+        profile: crate::PowerProfile,
+        frame_count: u64,
+        importance: impl Fn(&str) -> crate::Importance,
+    ) {
+        let mut applied = self.power.lock().unwrap_or_else(|e| e.into_inner());
+        for (id, tile) in &self.tiles {
             if let Ok(mut t) = tile.write() {
-                let should_update = match profile {
-                    crate::PowerProfile::Normal => true,
-                    crate::PowerProfile::LowPower => {
-                        if t.prefers_gpu() {
-                            frame_count % 2 == 0
-                        } else {
-                            true
+                let importance = importance(id);
+                let effective = profile.for_importance(importance);
+                if applied.insert(id.clone(), effective) != Some(effective) {
+                    t.set_power_profile(effective);
+                }
+                let should_update = !profile.pauses(importance)
+                    && match effective {
+                        crate::PowerProfile::Normal => true,
+                        crate::PowerProfile::LowPower => {
+                            if t.prefers_gpu() {
+                                frame_count % 2 == 0
+                            } else {
+                                true
+                            }
                         }
-                    }
-                    crate::PowerProfile::BatteryBackground => {
-                        if t.prefers_gpu() {
-                            frame_count % 8 == 0
-                        } else {
-                            true
+                        crate::PowerProfile::BatteryBackground => {
+                            if t.prefers_gpu() {
+                                frame_count % 8 == 0
+                            } else {
+                                true
+                            }
                         }
-                    }
-                };
+                    };
 
                 if should_update {
                     t.update();
                 }
+                self.observe_error(id, t.get_error());
             }
         }
     }
 
+    /// Profile a tile was last updated at, adjusted for its importance
+    pub fn power_profile(&self, module: &str) -> Option<crate::PowerProfile> {
+        self.power
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(module)
+            .copied()
+    }
+
     /// Render a tile in monitor mode by module name
     pub fn render_monitor(&self, module: &str, draw: &Draw, rect: Rect, ctx: &RenderContext) {
         if let Some(tile) = self.tiles.get(module) {
@@ -349,6 +501,14 @@ impl TileRegistry {
         serde_json::Value::Null
     }
 
+    /// Version of a tile's settings
+    pub fn settings_version(&self, module: &str) -> u32 {
+        self.tiles
+            .get(module)
+            .and_then(|tile| tile.read().ok().map(|t| t.settings_version()))
+            .unwrap_or(0)
+    }
+
     /// Execute an action on a tile
     pub fn execute_action(&self, module: &str, action: &str) -> bool {
         if let Some(tile) = self.tiles.get(module) {
@@ -394,6 +554,46 @@ impl TileRegistry {
             }
         }
     }
+
+    /// Ask a tile to reinitialize its module; returns false if the tile has no retry
+    pub fn retry(&self, module: &str) -> bool {
+        if let Some(tile) = self.tiles.get(module) {
+            if let Ok(mut t) = tile.write() {
+                if t.retry() {
+                    t.clear_error();
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    fn observe_error(&self, module: &str, error: Option<TileError>) {
+        if let Ok(mut errors) = self.errors.lock() {
+            if error.is_none() && !errors.contains_key(module) {
+                return;
+            }
+            errors
+                .entry(module.to_string())
+                .or_default()
+                .observe(error.as_ref(), Instant::now());
+        }
+    }
+
+    /// Errors a tile has reported, most recent first
+    pub fn error_history(&self, module: &str) -> Option<ErrorHistory> {
+        self.errors
+            .lock()
+            .ok()
+            .and_then(|errors| errors.get(module).cloned())
+    }
+
+    /// Forget a tile's error history
+    pub fn clear_error_history(&self, module: &str) {
+        if let Ok(mut errors) = self.errors.lock() {
+            errors.remove(module);
+        }
+    }
 }
 
 impl Default for TileRegistry {
@@ -402,6 +602,36 @@ impl Default for TileRegistry {
     }
 }
 
+/// Render a placeholder for a layout tile whose module is not registered
+pub fn render_unavailable_placeholder(draw: &Draw, rect: Rect, module: &str, reason: Option<&str>) {
+    draw.rect()
+        .xy(rect.xy())
+        .wh(rect.wh())
+        .color(srgba(0.05, 0.05, 0.06, 0.9))
+        .stroke(srgba(0.4, 0.4, 0.4, 0.6))
+        .stroke_weight(1.0);
+
+    draw_text(
+        draw,
+        FontId::PlexSansBold,
+        &module.to_uppercase(),
+        pt2(rect.x(), rect.y() + 10.0),
+        12.0,
+        srgba(0.6, 0.6, 0.6, 1.0),
+        TextAlignment::Center,
+    );
+
+    draw_text(
+        draw,
+        FontId::PlexSansRegular,
+        reason.unwrap_or("unavailable"),
+        pt2(rect.x(), rect.y() - 10.0),
+        10.0,
+        srgba(0.45, 0.45, 0.45, 1.0),
+        TextAlignment::Center,
+    );
+}
+
 /// Render an error overlay on a tile
 pub fn render_error_overlay(draw: &Draw, rect: Rect, error: &TileError) {
     let (bg_color, fg_color, icon) = match error.severity {
@@ -449,3 +679,240 @@ pub fn render_error_overlay(draw: &Draw, rect: Rect, error: &TileError) {
         TextAlignment::Center,
     );
 }
+
+fn format_age(age: Duration) -> String {
+    match age.as_secs() {
+        s if s < 60 => format!("{}s ago", s),
+        s if s < 3600 => format!("{}m ago", s / 60),
+        s => format!("{}h ago", s / 3600),
+    }
+}
+
+/// Render a tile's error history as a panel along the bottom of `rect`
+///
+/// Meant for control mode; shows the most recent errors with their counts.
+pub fn render_error_history(draw: &Draw, rect: Rect, history: &ErrorHistory, retry_hint: &str) {
+    const MAX_ROWS: usize = 5;
+    const ROW_H: f32 = 18.0;
+
+    let shown = history.records().len().min(MAX_ROWS);
+    if shown == 0 {
+        return;
+    }
+    let panel_h = 28.0 + shown as f32 * ROW_H;
+    let panel = Rect::from_x_y_w_h(
+        rect.x(),
+        rect.bottom() + panel_h / 2.0 + 8.0,
+        (rect.w() - 16.0).max(0.0),
+        panel_h,
+    );
+
+    draw.rect()
+        .xy(panel.xy())
+        .wh(panel.wh())
+        .color(srgba(0.08, 0.03, 0.03, 0.92))
+        .stroke(srgba(0.5, 0.2, 0.2, 0.8))
+        .stroke_weight(1.0);
+
+    draw_text(
+        draw,
+        FontId::PlexSansBold,
+        &format!("ERRORS ({})", history.total()),
+        pt2(panel.left() + 10.0, panel.top() - 12.0),
+        11.0,
+        srgba(1.0, 0.45, 0.45, 1.0),
+        TextAlignment::Left,
+    );
+    draw_text(
+        draw,
+        FontId::PlexSansRegular,
+        retry_hint,
+        pt2(panel.right() - 10.0, panel.top() - 12.0),
+        10.0,
+        srgba(0.6, 0.6, 0.6, 1.0),
+        TextAlignment::Right,
+    );
+
+    let now = Instant::now();
+    for (i, record) in history.records().iter().take(MAX_ROWS).enumerate() {
+        let y = panel.top() - 30.0 - i as f32 * ROW_H;
+        let color = match record.severity {
+            ErrorSeverity::Error => srgba(1.0, 0.35, 0.35, 1.0),
+            _ => srgba(1.0, 0.8, 0.3, 1.0),
+        };
+        let text = match &record.details {
+            Some(details) => format!("x{}  {}: {}", record.count, record.message, details),
+            None => format!("x{}  {}", record.count, record.message),
+        };
+        let text: String = if text.chars().count() > 90 {
+            text.chars().take(87).chain("...".chars()).collect()
+        } else {
+            text
+        };
+        draw_text(
+            draw,
+            FontId::PlexSansRegular,
+            &text,
+            pt2(panel.left() + 10.0, y),
+            10.0,
+            color,
+            TextAlignment::Left,
+        );
+        draw_text(
+            draw,
+            FontId::PlexMonoRegular,
+            &format_age(now.saturating_duration_since(record.last_seen)),
+            pt2(panel.right() - 10.0, y),
+            10.0,
+            srgba(0.55, 0.55, 0.55, 1.0),
+            TextAlignment::Right,
+        );
+    }
+}
+
+/// Render an audio device failure as its cause over what to do about it,
+/// left-aligned at `pos`
+pub fn render_device_error(draw: &Draw, pos: Point2, error: &crate::DeviceError) {
+    draw_text(
+        draw,
+        FontId::PlexSansBold,
+        &error.title(),
+        pos,
+        12.0,
+        srgba(1.0, 0.35, 0.35, 1.0),
+        TextAlignment::Left,
+    );
+    draw_text(
+        draw,
+        FontId::PlexSansRegular,
+        error.advice(),
+        pt2(pos.x, pos.y - 15.0),
+        11.0,
+        srgba(0.85, 0.75, 0.6, 1.0),
+        TextAlignment::Left,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct StubTile(&'static str);
+
+    impl TileRenderer for StubTile {
+        fn id(&self) -> &str {
+            self.0
+        }
+        fn name(&self) -> &str {
+            self.0
+        }
+        fn render_monitor(&self, _draw: &Draw, _rect: Rect, _ctx: &RenderContext) {}
+        fn update(&mut self) {}
+    }
+
+    #[test]
+    fn unregistered_tiles_report_unavailable_until_reregistered() {
+        let mut registry = TileRegistry::new();
+        registry.register(StubTile("plugin"));
+        assert!(registry.get("plugin").is_some());
+
+        registry.mark_unavailable("plugin", "plugin unloaded");
+        assert!(registry.get("plugin").is_none());
+        assert_eq!(
+            registry.unavailable_reason("plugin"),
+            Some("plugin unloaded")
+        );
+        assert!(registry.list_tiles().is_empty());
+
+        registry.register(StubTile("plugin"));
+        assert!(registry.get("plugin").is_some());
+        assert_eq!(registry.unavailable_reason("plugin"), None);
+
+        assert!(registry.unregister("plugin"));
+        assert!(!registry.unregister("plugin"));
+    }
+
+    #[derive(Default)]
+    struct PowerTile {
+        updates: Arc<Mutex<(usize, Option<crate::PowerProfile>)>>,
+    }
+
+    impl TileRenderer for PowerTile {
+        fn id(&self) -> &str {
+            "viz"
+        }
+        fn name(&self) -> &str {
+            "viz"
+        }
+        fn render_monitor(&self, _draw: &Draw, _rect: Rect, _ctx: &RenderContext) {}
+        fn update(&mut self) {
+            self.updates.lock().unwrap().0 += 1;
+        }
+        fn set_power_profile(&mut self, profile: crate::PowerProfile) {
+            self.updates.lock().unwrap().1 = Some(profile);
+        }
+    }
+
+    #[test]
+    fn background_tiles_step_down_and_pause() {
+        use crate::{Importance, PowerProfile};
+        let mut registry = TileRegistry::new();
+        let tile = PowerTile::default();
+        let updates = tile.updates.clone();
+        registry.register(tile);
+        let background = |_: &str| Importance::Background;
+
+        registry.update_all_with_power(PowerProfile::LowPower, 0, background);
+        assert_eq!(
+            *updates.lock().unwrap(),
+            (1, Some(PowerProfile::BatteryBackground))
+        );
+        assert_eq!(
+            registry.power_profile("viz"),
+            Some(PowerProfile::BatteryBackground)
+        );
+
+        for frame in 0..4 {
+            registry.update_all_with_power(PowerProfile::BatteryBackground, frame, background);
+        }
+        assert_eq!(updates.lock().unwrap().0, 1);
+
+        registry.update_all_with_power(PowerProfile::BatteryBackground, 0, |_| {
+            Importance::Essential
+        });
+        assert_eq!(*updates.lock().unwrap(), (2, Some(PowerProfile::Normal)));
+    }
+
+    #[test]
+    fn error_history_counts_recurrences_not_frames() {
+        let mut history = ErrorHistory::new();
+        let t0 = Instant::now();
+        let stream_lost = TileError::new("Stream lost");
+
+        for frame in 0..10 {
+            history.observe(Some(&stream_lost), t0 + Duration::from_millis(frame));
+        }
+        assert_eq!(history.records().len(), 1);
+        assert_eq!(history.records()[0].count, 1);
+
+        history.observe(None, t0 + Duration::from_secs(1));
+        history.observe(
+            Some(&TileError::info("Reconnecting")),
+            t0 + Duration::from_secs(2),
+        );
+        assert!(!history.is_active());
+        history.observe(Some(&stream_lost), t0 + Duration::from_secs(3));
+        history.observe(
+            Some(&TileError::warning("Buffer underrun")),
+            t0 + Duration::from_secs(4),
+        );
+
+        let records = history.records();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].message, "Buffer underrun");
+        assert_eq!(records[1].count, 2);
+        assert_eq!(records[1].first_seen, t0);
+        assert_eq!(records[1].last_seen, t0 + Duration::from_secs(3));
+        assert_eq!(history.total(), 3);
+    }
+}
@@ -0,0 +1,104 @@
+//! Settings shared with a module's background thread
+//!
+//! Sinks that keep a connection or device open on a thread of their own
+//! (OBS, chat, broadcast, databases) hand that thread their config through a
+//! [`WorkerSettings`]. Each change bumps a generation: the thread notes the
+//! one its session started with and reconnects once it moves on, and its
+//! waits between retries end as soon as a change comes in.
+
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+#[derive(Debug, Default)]
+pub struct WorkerSettings<T> {
+    /// The config and how many times it has changed
+    state: Mutex<(u64, T)>,
+    changed: Condvar,
+}
+
+impl<T: Clone> WorkerSettings<T> {
+    pub fn new(config: T) -> Self {
+        Self {
+            state: Mutex::new((0, config)),
+            changed: Condvar::new(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, (u64, T)> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn get(&self) -> T {
+        self.lock().1.clone()
+    }
+
+    /// Read part of the config without cloning all of it
+    pub fn read<R>(&self, read: impl FnOnce(&T) -> R) -> R {
+        read(&self.lock().1)
+    }
+
+    /// The config with the generation it belongs to, for a session to start
+    /// from
+    pub fn current(&self) -> (u64, T) {
+        let state = self.lock();
+        (state.0, state.1.clone())
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.lock().0
+    }
+
+    pub fn changed_since(&self, generation: u64) -> bool {
+        self.generation() != generation
+    }
+
+    /// Replace the config, waking the thread
+    pub fn set(&self, config: T) {
+        let mut state = self.lock();
+        state.0 += 1;
+        state.1 = config;
+        self.changed.notify_all();
+    }
+
+    /// Sleep up to `duration`; `false` if the settings changed from
+    /// `generation` first
+    pub fn wait(&self, generation: u64, duration: Duration) -> bool {
+        let state = self.lock();
+        let (state, _) = self
+            .changed
+            .wait_timeout_while(state, duration, |state| state.0 == generation)
+            .unwrap_or_else(PoisonError::into_inner);
+        state.0 == generation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Instant;
+
+    #[test]
+    fn waits_end_early_on_a_change() {
+        let settings = Arc::new(WorkerSettings::new("a".to_string()));
+        let (generation, config) = settings.current();
+        assert_eq!((generation, config.as_str()), (0, "a"));
+        assert!(settings.wait(generation, Duration::from_millis(1)));
+
+        let setter = settings.clone();
+        let started = Instant::now();
+        let thread = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            setter.set("b".to_string());
+        });
+        assert!(!settings.wait(generation, Duration::from_secs(10)));
+        assert!(started.elapsed() < Duration::from_secs(5));
+        thread.join().unwrap();
+
+        assert!(settings.changed_since(generation));
+        assert_eq!(settings.read(|config| config.len()), 1);
+        assert_eq!(settings.current(), (1, "b".to_string()));
+        // Already moved on: no sleep at all
+        assert!(!settings.wait(generation, Duration::from_secs(10)));
+    }
+}
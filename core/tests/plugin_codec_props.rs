@@ -0,0 +1,158 @@
+//! Property tests for the plugin `SignalBuffer` boundary: malformed buffers
+//! must be rejected or decoded, never crash the host.
+
+use magnolia_core::host_gpu::unpack_texture_handle;
+use magnolia_core::plugin_codec::{
+    decode_signal, encode_signal, release_host_buffer, MAX_TEXT_BYTES,
+};
+use magnolia_core::Signal;
+use magnolia_plugin_abi::{SignalBuffer, SignalType, SignalValue};
+use proptest::prelude::*;
+
+fn buffer(signal_type: u32, ptr: *mut u8, size: u64, param: u64) -> SignalBuffer {
+    SignalBuffer {
+        signal_type,
+        value: SignalValue { ptr: ptr as *mut _ },
+        size,
+        param,
+    }
+}
+
+proptest! {
+    #[test]
+    fn null_pointers_never_panic(signal_type in 0u32..16, size in any::<u64>(), param in any::<u64>()) {
+        let buf = buffer(signal_type, std::ptr::null_mut(), size, param);
+        let decoded = unsafe { decode_signal(&buf) };
+        let accepted = matches!(
+            decoded,
+            None | Some(Signal::Pulse)
+        );
+        prop_assert!(accepted);
+    }
+
+    #[test]
+    fn unknown_and_retired_types_are_rejected(
+        signal_type in prop_oneof![Just(8u32), 10u32..],
+        handle in any::<u64>(),
+    ) {
+        let buf = SignalBuffer {
+            signal_type,
+            value: SignalValue { gpu_id: handle },
+            size: 0,
+            param: 0,
+        };
+        let decoded = unsafe { decode_signal(&buf) };
+        prop_assert!(decoded.is_none());
+    }
+
+    #[test]
+    fn texture_handles_decode_opaquely(handle in any::<u64>(), param in any::<u64>()) {
+        // Ownership of the handle is checked by the adapter's GPU session, not here.
+        let texture = SignalBuffer {
+            signal_type: SignalType::Texture as u32,
+            value: SignalValue { gpu_id: handle },
+            size: 0,
+            param,
+        };
+        let decoded = unsafe { decode_signal(&texture) };
+        let expected = unpack_texture_handle(handle);
+        let actual = match decoded {
+            Some(Signal::Texture { handle, .. }) => Some((handle.id, handle.generation)),
+            _ => None,
+        };
+        prop_assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn arbitrary_bytes_under_any_text_or_json_tag(
+        mut bytes in proptest::collection::vec(any::<u8>(), 0..256),
+        tag in prop_oneof![Just(SignalType::Text as u32), Just(SignalType::Astrology as u32)],
+        param in any::<u64>(),
+        size_kind in 0u8..3,
+        fraction in 0.0f64..=1.0,
+    ) {
+        // Terminate so the legacy size == 0 path has a NUL to stop at.
+        bytes.push(0);
+        let len = bytes.len();
+        // Sizes are either legacy (0), honest about the allocation, or far past any limit.
+        let size = match size_kind {
+            0 => 0,
+            1 => (fraction * len as f64) as u64,
+            _ => MAX_TEXT_BYTES as u64 + 1 + (fraction * u32::MAX as f64) as u64,
+        };
+        let buf = buffer(tag, bytes.as_mut_ptr(), size, param);
+        let decoded = unsafe { decode_signal(&buf) };
+        if let Some(Signal::Text(text)) = decoded {
+            prop_assert_eq!(tag, SignalType::Text as u32);
+            // Lossy UTF-8 replacement can grow each byte to at most three.
+            prop_assert!(text.len() <= len * 3);
+        }
+    }
+
+    #[test]
+    fn audio_with_bad_metadata_or_alignment_is_rejected(
+        samples in proptest::collection::vec(any::<f32>(), 2..256),
+        sample_rate in any::<u32>(),
+        channels in any::<u16>(),
+        misalign in 1usize..4,
+        oversize in any::<bool>(),
+    ) {
+        let mut samples = samples;
+        let param = ((sample_rate as u64) << 32) | channels as u64;
+        let base = samples.as_mut_ptr() as *mut u8;
+
+        // Misaligned pointer: still inside the allocation, one sample short.
+        let misaligned = buffer(
+            SignalType::Audio as u32,
+            unsafe { base.add(misalign) },
+            (samples.len() - 1) as u64,
+            param,
+        );
+        let decoded = unsafe { decode_signal(&misaligned) };
+        prop_assert!(decoded.is_none());
+
+        let size = if oversize { u64::MAX } else { samples.len() as u64 };
+        let aligned = buffer(SignalType::Audio as u32, base, size, param);
+        let decoded = unsafe { decode_signal(&aligned) };
+        match decoded {
+            Some(Signal::Audio { sample_rate: sr, channels: ch, data, .. }) => {
+                prop_assert!(!oversize);
+                prop_assert!(sr != 0 && ch != 0);
+                prop_assert_eq!(data.len() % ch as usize, 0);
+                prop_assert_eq!(data.len(), samples.len());
+            }
+            Some(other) => prop_assert!(false, "unexpected {:?}", other),
+            None => prop_assert!(
+                oversize || sample_rate == 0 || channels == 0 || samples.len() % channels as usize != 0
+            ),
+        }
+    }
+
+    #[test]
+    fn text_round_trips(text in "[^\u{0}]{0,200}") {
+        let buf = encode_signal(&Signal::Text(text.clone()));
+        let decoded = unsafe { decode_signal(&buf) };
+        unsafe { release_host_buffer(&buf) };
+        let round_tripped = matches!(decoded, Some(Signal::Text(ref t)) if *t == text);
+        prop_assert!(round_tripped);
+    }
+
+    #[test]
+    fn audio_round_trips(
+        frames in 0usize..128,
+        channels in 1u16..8,
+        sample_rate in 1u32..192_000,
+    ) {
+        let data: Vec<f32> = (0..frames * channels as usize).map(|i| i as f32 * 0.001).collect();
+        let buf = encode_signal(&Signal::Audio { sample_rate, channels, timestamp_us: 0, data: data.clone() });
+        let decoded = unsafe { decode_signal(&buf) };
+        unsafe { release_host_buffer(&buf) };
+        match decoded {
+            Some(Signal::Audio { sample_rate: sr, channels: ch, data: out, .. }) => {
+                prop_assert_eq!((sr, ch), (sample_rate, channels));
+                prop_assert_eq!(out, data);
+            }
+            other => prop_assert!(false, "unexpected {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,260 @@
+//! Daily almanac: sunrise/sunset and moon phase for a location.
+//!
+//! Sun and moon longitudes come from the Swiss Ephemeris when its data files
+//! are installed, and from low-precision analytic series otherwise. The
+//! fallback is good to a couple of minutes for rise/set times and well within
+//! a day for phases, which is plenty for a clock face.
+
+use crate::ephemeris::{GeoLocation, SwissEphemerisAdapter};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+const J2000: f64 = 2_451_545.0;
+const UNIX_EPOCH_JD: f64 = 2_440_587.5;
+const OBLIQUITY_DEG: f64 = 23.4393;
+/// Apparent altitude of the sun's upper limb at rise/set (refraction + semi-diameter).
+const SUNRISE_ALTITUDE_DEG: f64 = -0.833;
+/// FLG_SWIEPH
+const SWISS_FLAGS: i32 = 2;
+
+pub fn julian_day(dt: DateTime<Utc>) -> f64 {
+    dt.timestamp_millis() as f64 / 86_400_000.0 + UNIX_EPOCH_JD
+}
+
+pub(crate) fn from_julian_day(jd: f64) -> DateTime<Utc> {
+    let millis = ((jd - UNIX_EPOCH_JD) * 86_400_000.0).round() as i64;
+    DateTime::from_timestamp_millis(millis).unwrap_or_default()
+}
+
+fn sin_deg(deg: f64) -> f64 {
+    deg.to_radians().sin()
+}
+
+fn cos_deg(deg: f64) -> f64 {
+    deg.to_radians().cos()
+}
+
+/// Geocentric ecliptic longitude of the sun, low precision (~0.01°).
+pub fn approx_sun_longitude(jd: f64) -> f64 {
+    let n = jd - J2000;
+    let mean_lon = 280.460 + 0.985_647_4 * n;
+    let anomaly = 357.528 + 0.985_600_3 * n;
+    (mean_lon + 1.915 * sin_deg(anomaly) + 0.020 * sin_deg(2.0 * anomaly)).rem_euclid(360.0)
+}
+
+/// Geocentric ecliptic longitude of the moon, low precision (~0.3°).
+pub fn approx_moon_longitude(jd: f64) -> f64 {
+    let n = jd - J2000;
+    let mean_lon = 218.316 + 13.176_396 * n;
+    let moon_anomaly = 134.963 + 13.064_993 * n;
+    let sun_anomaly = 357.529 + 0.985_600_28 * n;
+    let elongation = 297.850 + 12.190_749 * n;
+    (mean_lon
+        + 6.289 * sin_deg(moon_anomaly)
+        + 1.274 * sin_deg(2.0 * elongation - moon_anomaly)
+        + 0.658 * sin_deg(2.0 * elongation)
+        + 0.214 * sin_deg(2.0 * moon_anomaly)
+        - 0.186 * sin_deg(sun_anomaly))
+    .rem_euclid(360.0)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MoonPhaseName {
+    New,
+    WaxingCrescent,
+    FirstQuarter,
+    WaxingGibbous,
+    Full,
+    WaningGibbous,
+    LastQuarter,
+    WaningCrescent,
+}
+
+impl MoonPhaseName {
+    /// Phase for a sun-moon elongation in degrees (0 = new, 180 = full).
+    pub fn from_elongation(elongation: f64) -> Self {
+        const PHASES: [MoonPhaseName; 8] = [
+            MoonPhaseName::New,
+            MoonPhaseName::WaxingCrescent,
+            MoonPhaseName::FirstQuarter,
+            MoonPhaseName::WaxingGibbous,
+            MoonPhaseName::Full,
+            MoonPhaseName::WaningGibbous,
+            MoonPhaseName::LastQuarter,
+            MoonPhaseName::WaningCrescent,
+        ];
+        let octant = ((elongation.rem_euclid(360.0) + 22.5) / 45.0).floor() as usize % 8;
+        PHASES[octant]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            MoonPhaseName::New => "New Moon",
+            MoonPhaseName::WaxingCrescent => "Waxing Crescent",
+            MoonPhaseName::FirstQuarter => "First Quarter",
+            MoonPhaseName::WaxingGibbous => "Waxing Gibbous",
+            MoonPhaseName::Full => "Full Moon",
+            MoonPhaseName::WaningGibbous => "Waning Gibbous",
+            MoonPhaseName::LastQuarter => "Last Quarter",
+            MoonPhaseName::WaningCrescent => "Waning Crescent",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MoonPhase {
+    /// Moon longitude minus sun longitude, 0..360°
+    pub elongation: f64,
+    /// Illuminated fraction of the disc, 0..1
+    pub illumination: f64,
+    pub name: MoonPhaseName,
+}
+
+pub fn moon_phase(sun_lon: f64, moon_lon: f64) -> MoonPhase {
+    let elongation = (moon_lon - sun_lon).rem_euclid(360.0);
+    MoonPhase {
+        elongation,
+        illumination: (1.0 - cos_deg(elongation)) / 2.0,
+        name: MoonPhaseName::from_elongation(elongation),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SunTimes {
+    pub solar_noon: DateTime<Utc>,
+    /// `None` when the sun does not rise or set that day (polar day/night)
+    pub sunrise: Option<DateTime<Utc>>,
+    pub sunset: Option<DateTime<Utc>>,
+}
+
+/// Sunrise, solar noon and sunset on `date` (UTC calendar day) at `location`.
+///
+/// `sun_longitude` maps a Julian day to the sun's ecliptic longitude.
+pub fn sun_times(
+    date: NaiveDate,
+    location: &GeoLocation,
+    sun_longitude: impl Fn(f64) -> f64,
+) -> SunTimes {
+    let midnight = julian_day(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc());
+    // Local mean noon; east longitudes are positive.
+    let noon = midnight + 0.5 - location.lon / 360.0;
+
+    let lambda = sun_longitude(noon);
+    let declination = (sin_deg(OBLIQUITY_DEG) * sin_deg(lambda))
+        .asin()
+        .to_degrees();
+    let right_ascension = (cos_deg(OBLIQUITY_DEG) * sin_deg(lambda))
+        .atan2(cos_deg(lambda))
+        .to_degrees()
+        .rem_euclid(360.0);
+
+    // Equation of time: mean sun minus apparent sun, in degrees of hour angle.
+    let centuries = (noon - J2000) / 36_525.0;
+    let mean_lon = (280.466_46 + 36_000.769_83 * centuries).rem_euclid(360.0);
+    let equation_of_time = (mean_lon - right_ascension + 180.0).rem_euclid(360.0) - 180.0;
+    let solar_noon = noon - equation_of_time / 360.0;
+
+    let cos_hour_angle = (sin_deg(SUNRISE_ALTITUDE_DEG)
+        - sin_deg(location.lat) * sin_deg(declination))
+        / (cos_deg(location.lat) * cos_deg(declination));
+
+    let (sunrise, sunset) = if (-1.0..=1.0).contains(&cos_hour_angle) {
+        let half_day = cos_hour_angle.acos().to_degrees() / 360.0;
+        (
+            Some(from_julian_day(solar_noon - half_day)),
+            Some(from_julian_day(solar_noon + half_day)),
+        )
+    } else {
+        (None, None)
+    };
+
+    SunTimes {
+        solar_noon: from_julian_day(solar_noon),
+        sunrise,
+        sunset,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Almanac {
+    pub date: NaiveDate,
+    pub sun: SunTimes,
+    pub moon: MoonPhase,
+}
+
+impl Almanac {
+    /// Compute the almanac for `date`, using the ephemeris when available.
+    pub fn compute(
+        date: NaiveDate,
+        location: &GeoLocation,
+        ephemeris: Option<&SwissEphemerisAdapter>,
+    ) -> Self {
+        let longitude = |body: &str, jd: f64| {
+            ephemeris
+                .and_then(|eph| eph.calc_planet_position(body, jd, SWISS_FLAGS).ok())
+                .map(|pos| pos.lon)
+                .unwrap_or_else(|| match body {
+                    "moon" => approx_moon_longitude(jd),
+                    _ => approx_sun_longitude(jd),
+                })
+        };
+
+        let sun = sun_times(date, location, |jd| longitude("sun", jd));
+        let noon = julian_day(sun.solar_noon);
+        let moon = moon_phase(longitude("sun", noon), longitude("moon", noon));
+        Self { date, sun, moon }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Timelike};
+
+    fn minutes(dt: DateTime<Utc>) -> i64 {
+        (dt.hour() * 60 + dt.minute()) as i64
+    }
+
+    #[test]
+    fn moon_phase_names_follow_elongation() {
+        assert_eq!(moon_phase(10.0, 10.0).name, MoonPhaseName::New);
+        assert_eq!(moon_phase(0.0, 90.0).name, MoonPhaseName::FirstQuarter);
+        assert_eq!(moon_phase(300.0, 120.0).name, MoonPhaseName::Full);
+        assert_eq!(moon_phase(0.0, 315.0).name, MoonPhaseName::WaningCrescent);
+        assert!((moon_phase(0.0, 180.0).illumination - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn known_full_moon_is_detected_without_ephemeris() {
+        // Full moon of 2024-01-25 17:54 UTC
+        let jd = julian_day(Utc.with_ymd_and_hms(2024, 1, 25, 18, 0, 0).unwrap());
+        let phase = moon_phase(approx_sun_longitude(jd), approx_moon_longitude(jd));
+        assert_eq!(phase.name, MoonPhaseName::Full);
+        assert!(phase.illumination > 0.99);
+    }
+
+    #[test]
+    fn greenwich_equinox_sun_times() {
+        let greenwich = GeoLocation {
+            lat: 51.48,
+            lon: 0.0,
+        };
+        let date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let times = sun_times(date, &greenwich, approx_sun_longitude);
+
+        // Published: sunrise 06:02 UTC, sunset 18:14 UTC
+        assert!((minutes(times.sunrise.unwrap()) - (6 * 60 + 2)).abs() <= 3);
+        assert!((minutes(times.sunset.unwrap()) - (18 * 60 + 14)).abs() <= 3);
+    }
+
+    #[test]
+    fn polar_night_has_no_sunrise() {
+        let svalbard = GeoLocation {
+            lat: 78.2,
+            lon: 15.6,
+        };
+        let date = NaiveDate::from_ymd_opt(2024, 12, 21).unwrap();
+        let times = sun_times(date, &svalbard, approx_sun_longitude);
+        assert!(times.sunrise.is_none() && times.sunset.is_none());
+    }
+}
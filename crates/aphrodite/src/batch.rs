@@ -0,0 +1,283 @@
+//! Batch chart computation for research workloads.
+//!
+//! Computes positions (and optionally intra-layer aspects) over a regular
+//! time grid, e.g. every hour of a year, and streams the rows as JSONL.
+//! Instants are stepped in Julian days from the range start, and the adapter
+//! resolves zodiac flags and the house system once per chunk rather than per
+//! timestamp.
+//!
+//! ```no_run
+//! use aphrodite::batch::{BatchChart, TimeRange};
+//! use aphrodite::ephemeris::{EphemerisSettings, SwissEphemerisAdapter};
+//! use chrono::{TimeZone, Utc};
+//!
+//! let mut adapter = SwissEphemerisAdapter::new(None).unwrap();
+//! let settings = EphemerisSettings {
+//!     zodiac_type: "tropical".to_string(),
+//!     ayanamsa: None,
+//!     house_system: "placidus".to_string(),
+//!     include_objects: vec!["sun".to_string(), "moon".to_string()],
+//! };
+//! let range = TimeRange::hourly(
+//!     Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+//!     Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+//! );
+//! let out = std::fs::File::create("2024.jsonl").unwrap();
+//! BatchChart::new(settings)
+//!     .write_jsonl(&mut adapter, &range, std::io::BufWriter::new(out))
+//!     .unwrap();
+//! ```
+
+use crate::aspects::{AspectCalculator, AspectPair, AspectSettings};
+use crate::ephemeris::adapter::{datetime_to_julian_day, EphemerisError};
+use crate::ephemeris::{EphemerisSettings, GeoLocation, LayerPositions, SwissEphemerisAdapter};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use thiserror::Error;
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// Default number of instants computed per adapter call
+const DEFAULT_CHUNK_SIZE: usize = 256;
+
+#[derive(Error, Debug)]
+pub enum BatchError {
+    #[error("Ephemeris error: {0}")]
+    Ephemeris(#[from] EphemerisError),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Regular time grid: `start`, `start + step`, ... up to (excluding) `end`
+#[derive(Debug, Clone)]
+pub struct TimeRange {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub step: chrono::Duration,
+}
+
+impl TimeRange {
+    pub fn new(start: DateTime<Utc>, end: DateTime<Utc>, step: chrono::Duration) -> Self {
+        Self { start, end, step }
+    }
+
+    pub fn hourly(start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        Self::new(start, end, chrono::Duration::hours(1))
+    }
+
+    pub fn len(&self) -> usize {
+        let step = self.step.num_milliseconds();
+        let span = (self.end - self.start).num_milliseconds();
+        if step <= 0 || span <= 0 {
+            return 0;
+        }
+        ((span + step - 1) / step) as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The `index`th instant of the grid
+    pub fn instant(&self, index: usize) -> DateTime<Utc> {
+        self.start + self.step * index as i32
+    }
+
+    /// Julian days for `indices`, stepped from the start's Julian day
+    pub fn julian_days(&self, indices: std::ops::Range<usize>) -> Vec<f64> {
+        let jd0 = datetime_to_julian_day(self.start);
+        let step_days = self.step.num_milliseconds() as f64 / 1000.0 / SECONDS_PER_DAY;
+        indices.map(|i| jd0 + i as f64 * step_days).collect()
+    }
+}
+
+/// One computed instant
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchRow {
+    pub datetime: DateTime<Utc>,
+    pub positions: LayerPositions,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aspects: Vec<AspectPair>,
+}
+
+/// What to compute at every instant of a [`TimeRange`]
+pub struct BatchChart {
+    settings: EphemerisSettings,
+    location: Option<GeoLocation>,
+    aspects: Option<AspectSettings>,
+    chunk_size: usize,
+}
+
+impl BatchChart {
+    pub fn new(settings: EphemerisSettings) -> Self {
+        Self {
+            settings,
+            location: None,
+            aspects: None,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        }
+    }
+
+    /// Compute houses for this location as well
+    pub fn with_location(mut self, location: GeoLocation) -> Self {
+        self.location = Some(location);
+        self
+    }
+
+    /// Compute intra-layer aspects for every row
+    pub fn with_aspects(mut self, aspects: AspectSettings) -> Self {
+        self.aspects = Some(aspects);
+        self
+    }
+
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Rows for the whole range, computed a chunk at a time
+    pub fn rows<'a>(
+        &'a self,
+        adapter: &'a mut SwissEphemerisAdapter,
+        range: &'a TimeRange,
+    ) -> impl Iterator<Item = Result<BatchRow, EphemerisError>> + 'a {
+        let calculator = AspectCalculator::new();
+        let len = range.len();
+        (0..len)
+            .step_by(self.chunk_size)
+            .flat_map(move |chunk_start| {
+                let indices = chunk_start..(chunk_start + self.chunk_size).min(len);
+                let jds = range.julian_days(indices.clone());
+                match adapter.calc_positions_series(&jds, self.location.as_ref(), &self.settings) {
+                    Ok(positions) => indices
+                        .zip(positions)
+                        .map(|(index, positions)| {
+                            Ok(self.row(&calculator, range, index, positions))
+                        })
+                        .collect::<Vec<_>>(),
+                    Err(e) => vec![Err(e)],
+                }
+            })
+    }
+
+    /// All rows in memory
+    pub fn compute(
+        &self,
+        adapter: &mut SwissEphemerisAdapter,
+        range: &TimeRange,
+    ) -> Result<Vec<BatchRow>, EphemerisError> {
+        self.rows(adapter, range).collect()
+    }
+
+    /// Stream every row as one JSON line; returns the number of rows written
+    pub fn write_jsonl<W: Write>(
+        &self,
+        adapter: &mut SwissEphemerisAdapter,
+        range: &TimeRange,
+        writer: W,
+    ) -> Result<usize, BatchError> {
+        write_jsonl(self.rows(adapter, range), writer)
+    }
+
+    fn row(
+        &self,
+        calculator: &AspectCalculator,
+        range: &TimeRange,
+        index: usize,
+        positions: LayerPositions,
+    ) -> BatchRow {
+        let aspects = self
+            .aspects
+            .as_ref()
+            .map(|settings| {
+                calculator
+                    .compute_intra_layer_aspects("batch", &positions, settings)
+                    .pairs
+            })
+            .unwrap_or_default();
+        BatchRow {
+            datetime: range.instant(index),
+            positions,
+            aspects,
+        }
+    }
+}
+
+/// Write rows as JSONL, stopping at the first error
+pub fn write_jsonl<W, E>(
+    rows: impl IntoIterator<Item = Result<BatchRow, E>>,
+    mut writer: W,
+) -> Result<usize, BatchError>
+where
+    W: Write,
+    BatchError: From<E>,
+{
+    let mut written = 0;
+    for row in rows {
+        serde_json::to_writer(&mut writer, &row?)?;
+        writer.write_all(b"\n")?;
+        written += 1;
+    }
+    writer.flush()?;
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ephemeris::PlanetPosition;
+    use chrono::TimeZone;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_time_range_steps_in_julian_days() {
+        let start = Utc.with_ymd_and_hms(2000, 1, 1, 12, 0, 0).unwrap();
+        let range = TimeRange::hourly(start, start + chrono::Duration::days(365));
+        assert_eq!(range.len(), 365 * 24);
+        assert_eq!(range.instant(25), start + chrono::Duration::hours(25));
+
+        let jds = range.julian_days(0..49);
+        // J2000.0
+        assert!((jds[0] - 2_451_545.0).abs() < 1e-9);
+        assert!((jds[48] - 2_451_547.0).abs() < 1e-9);
+
+        let partial = TimeRange::new(start, start + chrono::Duration::minutes(90), range.step);
+        assert_eq!(partial.len(), 2);
+        assert!(TimeRange::new(start, start, range.step).is_empty());
+    }
+
+    #[test]
+    fn test_write_jsonl_one_row_per_line() {
+        let start = Utc.with_ymd_and_hms(2000, 1, 1, 0, 0, 0).unwrap();
+        let rows = (0..3).map(|i| {
+            Ok::<_, EphemerisError>(BatchRow {
+                datetime: start + chrono::Duration::hours(i),
+                positions: LayerPositions {
+                    planets: HashMap::from([(
+                        "sun".to_string(),
+                        PlanetPosition {
+                            lon: 280.0 + i as f64 * 0.04,
+                            lat: 0.0,
+                            speed_lon: 1.0,
+                            retrograde: false,
+                        },
+                    )]),
+                    houses: None,
+                },
+                aspects: Vec::new(),
+            })
+        });
+
+        let mut out = Vec::new();
+        assert_eq!(write_jsonl(rows, &mut out).unwrap(), 3);
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 3);
+        let last: BatchRow = serde_json::from_str(lines[2]).unwrap();
+        assert_eq!(last.datetime, start + chrono::Duration::hours(2));
+        assert!(!lines[0].contains("aspects"));
+    }
+}
@@ -3,12 +3,14 @@ pub mod data;
 pub mod radix;
 pub mod settings;
 pub mod transit;
+pub mod wheel;
 
 pub use animation::ChartAnimation;
 pub use data::ChartData;
 pub use radix::RadixChart;
 pub use settings::ChartSettings;
 pub use transit::TransitChart;
+pub use wheel::WheelChart;
 
 use nannou::prelude::*;
 
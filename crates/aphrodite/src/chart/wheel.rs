@@ -0,0 +1,158 @@
+//! Renders an [`AssembledWheel`] from a JSON wheel definition.
+//!
+//! Ring radii in the definition are fractions of the chart radius.
+
+use std::str::FromStr;
+
+use magnolia_ui::{draw_text, FontId, TextAlignment};
+use nannou::prelude::*;
+
+use super::settings::ChartSettings;
+use super::{get_point_position, parse_hex_color};
+use crate::layout::rings::RingItem;
+use crate::layout::AssembledWheel;
+use crate::rendering::glyphs::{draw_glyph, Glyph};
+
+const SIGN_GLYPHS: [Glyph; 12] = [
+    Glyph::Aries,
+    Glyph::Taurus,
+    Glyph::Gemini,
+    Glyph::Cancer,
+    Glyph::Leo,
+    Glyph::Virgo,
+    Glyph::Libra,
+    Glyph::Scorpio,
+    Glyph::Sagittarius,
+    Glyph::Capricorn,
+    Glyph::Aquarius,
+    Glyph::Pisces,
+];
+
+pub struct WheelChart<'a> {
+    settings: &'a ChartSettings,
+    wheel: &'a AssembledWheel,
+    cx: f32,
+    cy: f32,
+    radius: f32,
+    shift: f32,
+}
+
+impl<'a> WheelChart<'a> {
+    pub fn new(
+        cx: f32,
+        cy: f32,
+        radius: f32,
+        shift: f32,
+        wheel: &'a AssembledWheel,
+        settings: &'a ChartSettings,
+    ) -> Self {
+        Self {
+            settings,
+            wheel,
+            cx,
+            cy,
+            radius,
+            shift,
+        }
+    }
+
+    fn color(hex: &str) -> Srgba {
+        let c = parse_hex_color(hex);
+        srgba(c.red, c.green, c.blue, 1.0)
+    }
+
+    pub fn draw(&self, draw: &Draw) {
+        let circles = Self::color(&self.settings.color_circles);
+        let lines = Self::color(&self.settings.color_lines);
+        let points = Self::color(&self.settings.color_points);
+        let signs = Self::color(&self.settings.color_signs);
+        let stroke = self.settings.stroke_circles;
+
+        for ring in &self.wheel.rings {
+            let r_in = self.radius * ring.radius_inner;
+            let r_out = self.radius * ring.radius_outer;
+            let r_mid = (r_in + r_out) / 2.0;
+            let band = r_out - r_in;
+
+            for r in [r_in, r_out] {
+                draw.ellipse()
+                    .x_y(self.cx, self.cy)
+                    .radius(r)
+                    .no_fill()
+                    .stroke(circles)
+                    .stroke_weight(stroke);
+            }
+
+            for item in &ring.items {
+                match item {
+                    RingItem::Sign(sign) => {
+                        self.radial(draw, r_in, r_out, sign.start_lon as f32, lines, stroke);
+                        let mid = ((sign.start_lon + sign.end_lon) / 2.0) as f32;
+                        let pos = get_point_position(self.cx, self.cy, r_mid, mid, self.shift);
+                        let glyph = SIGN_GLYPHS[sign.index as usize % 12];
+                        draw_glyph(draw, glyph, pos, (band * 0.6).min(20.0), signs);
+                    }
+                    RingItem::House(house) => {
+                        let lon = house.lon as f32;
+                        self.radial(draw, r_in, r_out, lon, lines, self.settings.stroke_cusps);
+                        let pos =
+                            get_point_position(self.cx, self.cy, r_mid, lon + 5.0, self.shift);
+                        draw_text(
+                            draw,
+                            FontId::PlexSansRegular,
+                            &(house.house_index + 1).to_string(),
+                            pos,
+                            (band * 0.5).clamp(8.0, 14.0),
+                            lines,
+                            TextAlignment::Center,
+                        );
+                    }
+                    RingItem::Planet(planet) => {
+                        let lon = planet.lon as f32;
+                        let pos = get_point_position(self.cx, self.cy, r_mid, lon, self.shift);
+                        let mut name = planet.planet_id.clone();
+                        if let Some(first) = name.get_mut(0..1) {
+                            first.make_ascii_uppercase();
+                        }
+                        match Glyph::from_str(&name) {
+                            Ok(Glyph::Unknown) | Err(_) => {
+                                draw.ellipse().xy(pos).radius(2.5).color(points);
+                            }
+                            Ok(glyph) => {
+                                draw_glyph(draw, glyph, pos, (band * 0.6).min(16.0), points);
+                            }
+                        }
+                        self.radial(draw, r_in, r_in + band * 0.15, lon, points, stroke);
+                    }
+                    RingItem::Aspect(aspect) => {
+                        let from = get_point_position(
+                            self.cx,
+                            self.cy,
+                            r_out,
+                            aspect.from_lon as f32,
+                            self.shift,
+                        );
+                        let to = get_point_position(
+                            self.cx,
+                            self.cy,
+                            r_out,
+                            aspect.to_lon as f32,
+                            self.shift,
+                        );
+                        draw.line().start(from).end(to).color(lines).weight(stroke);
+                    }
+                }
+            }
+        }
+    }
+
+    fn radial(&self, draw: &Draw, r_in: f32, r_out: f32, lon: f32, color: Srgba, weight: f32) {
+        let start = get_point_position(self.cx, self.cy, r_in, lon, self.shift);
+        let end = get_point_position(self.cx, self.cy, r_out, lon, self.shift);
+        draw.line()
+            .start(start)
+            .end(end)
+            .color(color)
+            .weight(weight);
+    }
+}
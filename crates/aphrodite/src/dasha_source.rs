@@ -0,0 +1,221 @@
+//! `dasha` source: the running dasha periods and their changes.
+//!
+//! Emits on two ports:
+//! - `dasha_out`: the active periods as JSON (`Computed`, source `dasha`) at
+//!   startup and after every change
+//! - `events_out`: `Intent`s from [`DashaChange::intent`]
+//!   - `dasha_change` / `bhukti_change` when a period begins
+//!   - `dasha_upcoming` / `bhukti_upcoming` the configured lead time before
+
+use crate::vedic::timeline::{DashaChange, DashaTimeline};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use magnolia_core::{
+    system_clock, DataType, ModuleSchema, Port, PortDirection, SharedClock, Signal, Source,
+};
+use std::collections::VecDeque;
+use std::time::Duration;
+
+const TICK: Duration = Duration::from_secs(60);
+
+/// JSON summary of the periods running at `at`
+pub fn active_summary(timeline: &DashaTimeline, at: DateTime<Utc>) -> serde_json::Value {
+    let active: Vec<serde_json::Value> = timeline
+        .active(at)
+        .into_iter()
+        .map(|p| {
+            serde_json::json!({
+                "level": p.level,
+                "planet": p.planet,
+                "start": p.start,
+                "end": p.end,
+            })
+        })
+        .collect();
+    serde_json::json!({
+        "system": timeline.system,
+        "at": at,
+        "active": active,
+        "next": timeline.next_change(at),
+    })
+}
+
+pub struct DashaSource {
+    id: String,
+    enabled: bool,
+    timeline: DashaTimeline,
+    clock: SharedClock,
+    lead: chrono::Duration,
+    last_check: Option<DateTime<Utc>>,
+    pending: VecDeque<Signal>,
+}
+
+impl DashaSource {
+    pub fn new(id: &str, timeline: DashaTimeline) -> Self {
+        Self {
+            id: id.to_string(),
+            enabled: true,
+            timeline,
+            clock: system_clock(),
+            lead: chrono::Duration::days(7),
+            last_check: None,
+            pending: VecDeque::new(),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// How far ahead `*_upcoming` intents fire; zero disables them
+    pub fn with_lead_time(mut self, lead: chrono::Duration) -> Self {
+        self.lead = lead;
+        self
+    }
+
+    fn now(&self) -> DateTime<Utc> {
+        DateTime::from_timestamp_micros(self.clock.now_us() as i64).unwrap_or_default()
+    }
+
+    fn check(&mut self) {
+        let now = self.now();
+        let Some(last) = self.last_check.replace(now) else {
+            self.push_summary(now);
+            return;
+        };
+        if now <= last {
+            return;
+        }
+
+        if self.lead > chrono::Duration::zero() {
+            let upcoming = self
+                .timeline
+                .changes_between(last + self.lead, now + self.lead);
+            self.pending
+                .extend(upcoming.iter().map(|change| change.intent(true)));
+        }
+        let changes: Vec<DashaChange> = self.timeline.changes_between(last, now);
+        if !changes.is_empty() {
+            self.pending
+                .extend(changes.iter().map(|change| change.intent(false)));
+            self.push_summary(now);
+        }
+    }
+
+    fn push_summary(&mut self, at: DateTime<Utc>) {
+        self.pending.push_back(Signal::Computed {
+            source: "dasha".to_string(),
+            content: active_summary(&self.timeline, at).to_string(),
+        });
+    }
+}
+
+#[async_trait]
+impl Source for DashaSource {
+    fn name(&self) -> &str {
+        "Dasha"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "Dasha Periods".to_string(),
+            description: format!(
+                "Running {} dasha and bhukti, with period change events",
+                self.timeline.system.label()
+            ),
+            ports: vec![
+                Port {
+                    id: "dasha_out".to_string(),
+                    label: "Active Periods".to_string(),
+                    data_type: DataType::Astrology,
+                    direction: PortDirection::Output,
+                },
+                Port {
+                    id: "events_out".to_string(),
+                    label: "Period Events".to_string(),
+                    data_type: DataType::Control,
+                    direction: PortDirection::Output,
+                },
+            ],
+            settings_schema: None,
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    async fn poll(&mut self) -> Option<Signal> {
+        loop {
+            if let Some(signal) = self.pending.pop_front() {
+                return Some(signal);
+            }
+            if self.enabled {
+                self.check();
+                if !self.pending.is_empty() {
+                    continue;
+                }
+            } else {
+                self.last_check = None;
+            }
+            self.clock.sleep(TICK).await;
+        }
+    }
+
+    fn output_port(&self, signal: &Signal) -> Option<&str> {
+        match signal {
+            Signal::Intent { .. } => Some("events_out"),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vedic::timeline::DashaSystem;
+    use chrono::TimeZone;
+    use magnolia_core::VirtualClock;
+
+    #[tokio::test]
+    async fn announces_then_reports_period_changes() {
+        let birth = Utc.with_ymd_and_hms(1990, 1, 1, 0, 0, 0).unwrap();
+        let timeline = DashaTimeline::from_moon(DashaSystem::Vimshottari, birth, 6.665).unwrap();
+        let venus = timeline.periods[1].start;
+        let begin = venus - chrono::Duration::days(2) - chrono::Duration::minutes(3);
+
+        let clock = VirtualClock::starting_at(begin.timestamp_micros() as u64, true);
+        let mut source = DashaSource::new("dasha", timeline)
+            .with_clock(clock)
+            .with_lead_time(chrono::Duration::days(2));
+
+        let first = source.poll().await.unwrap();
+        let Signal::Computed {
+            source: name,
+            content,
+        } = &first
+        else {
+            panic!("expected the active summary first");
+        };
+        assert_eq!(name, "dasha");
+        let summary: serde_json::Value = serde_json::from_str(content).unwrap();
+        assert_eq!(summary["active"][0]["planet"], "ketu");
+        assert_eq!(source.output_port(&first), None);
+
+        let mut actions = Vec::new();
+        while actions.len() < 2 {
+            if let Signal::Intent { action, .. } = source.poll().await.unwrap() {
+                actions.push(action);
+            }
+        }
+        assert_eq!(actions, ["dasha_upcoming", "bhukti_upcoming"]);
+        assert!(source.now() < venus);
+    }
+}
@@ -0,0 +1,283 @@
+//! Dasha Tile - Vedic period timeline
+//!
+//! Monitor mode: running mahadasha / bhukti and the next change
+//! Control mode: mahadasha timeline from birth, with the bhuktis of the
+//! running mahadasha below it; the current periods are highlighted
+
+use chrono::{DateTime, Utc};
+use magnolia_core::{RenderContext, TileError, TileRenderer};
+use magnolia_ui::{draw_text, FontId, TextAlignment};
+use nannou::prelude::*;
+
+use crate::vedic::dashas::DashaPeriod;
+use crate::vedic::timeline::DashaTimeline;
+
+fn planet_color(planet: &str) -> Srgb {
+    match planet {
+        "sun" => srgb(0.95, 0.65, 0.15),
+        "moon" => srgb(0.8, 0.82, 0.9),
+        "mars" => srgb(0.85, 0.25, 0.2),
+        "mercury" => srgb(0.3, 0.75, 0.4),
+        "jupiter" => srgb(0.95, 0.85, 0.3),
+        "venus" => srgb(0.9, 0.5, 0.75),
+        "saturn" => srgb(0.35, 0.4, 0.7),
+        "rahu" => srgb(0.45, 0.35, 0.55),
+        "ketu" => srgb(0.6, 0.45, 0.35),
+        _ => srgb(0.5, 0.5, 0.5),
+    }
+}
+
+fn title_case(planet: &str) -> String {
+    let mut chars = planet.chars();
+    chars
+        .next()
+        .map(|c| c.to_uppercase().collect::<String>() + chars.as_str())
+        .unwrap_or_default()
+}
+
+pub struct DashaTile {
+    id: String,
+    timeline: Result<DashaTimeline, String>,
+    now: DateTime<Utc>,
+}
+
+impl DashaTile {
+    pub fn new(id: &str, timeline: Result<DashaTimeline, String>) -> Self {
+        Self {
+            id: id.to_string(),
+            timeline,
+            now: Utc::now(),
+        }
+    }
+
+    fn running(&self) -> Vec<&DashaPeriod> {
+        self.timeline
+            .as_ref()
+            .map(|t| t.active(self.now))
+            .unwrap_or_default()
+    }
+
+    fn next_text(&self) -> Option<String> {
+        let next = self.timeline.as_ref().ok()?.next_change(self.now)?;
+        Some(format!(
+            "Next: {} {:?} in {} days ({})",
+            title_case(&next.planet),
+            next.level,
+            (next.start - self.now).num_days(),
+            next.start.format("%Y-%m-%d")
+        ))
+    }
+
+    /// One row of period bars spanning `start..end`
+    fn draw_row(
+        &self,
+        draw: &Draw,
+        row: Rect,
+        periods: &[DashaPeriod],
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) {
+        let span = (end - start).num_seconds().max(1) as f32;
+        let x_at =
+            |t: DateTime<Utc>| row.left() + row.w() * ((t - start).num_seconds() as f32 / span);
+
+        for period in periods {
+            let (x0, x1) = (x_at(period.start), x_at(period.end));
+            let active = period.start <= self.now && self.now < period.end;
+            let c = planet_color(&period.planet);
+            let alpha = if active { 0.95 } else { 0.35 };
+            draw.rect()
+                .x_y((x0 + x1) / 2.0, row.y())
+                .w_h((x1 - x0 - 1.0).max(1.0), row.h())
+                .color(srgba(c.red, c.green, c.blue, alpha));
+            if active {
+                draw.rect()
+                    .x_y((x0 + x1) / 2.0, row.y())
+                    .w_h(x1 - x0, row.h())
+                    .no_fill()
+                    .stroke(WHITE)
+                    .stroke_weight(1.5);
+            }
+            if x1 - x0 > 28.0 {
+                let label: String = title_case(&period.planet).chars().take(3).collect();
+                draw_text(
+                    draw,
+                    FontId::PlexMonoRegular,
+                    &label,
+                    pt2((x0 + x1) / 2.0, row.y()),
+                    11.0,
+                    srgba(0.0, 0.0, 0.0, 0.9),
+                    TextAlignment::Center,
+                );
+            }
+        }
+
+        if start <= self.now && self.now < end {
+            let x = x_at(self.now);
+            draw.line()
+                .start(pt2(x, row.bottom() - 4.0))
+                .end(pt2(x, row.top() + 4.0))
+                .color(srgba(1.0, 1.0, 1.0, 0.9))
+                .weight(2.0);
+        }
+    }
+}
+
+impl TileRenderer for DashaTile {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn name(&self) -> &str {
+        "Dasha Timeline"
+    }
+
+    fn update(&mut self) {
+        self.now = Utc::now();
+    }
+
+    fn render_monitor(&self, draw: &Draw, rect: Rect, _ctx: &RenderContext) {
+        draw.rect()
+            .xy(rect.xy())
+            .wh(rect.wh())
+            .color(srgba(0.04, 0.02, 0.06, 0.9));
+
+        draw_text(
+            draw,
+            FontId::PlexSansBold,
+            "DASHA",
+            pt2(rect.x(), rect.top() - 20.0),
+            12.0,
+            srgba(0.5, 0.5, 0.5, 1.0),
+            TextAlignment::Center,
+        );
+
+        let running: Vec<String> = self
+            .running()
+            .iter()
+            .map(|p| title_case(&p.planet))
+            .collect();
+        let headline = if running.is_empty() {
+            "—".to_string()
+        } else {
+            running.join(" / ")
+        };
+        draw_text(
+            draw,
+            FontId::PlexSansBold,
+            &headline,
+            rect.xy(),
+            (rect.h() * 0.15).clamp(12.0, 24.0),
+            srgba(0.95, 0.85, 0.6, 1.0),
+            TextAlignment::Center,
+        );
+        if let Some(next) = self.next_text() {
+            draw_text(
+                draw,
+                FontId::PlexMonoRegular,
+                &next,
+                pt2(rect.x(), rect.y() - 24.0),
+                11.0,
+                srgba(0.6, 0.6, 0.6, 1.0),
+                TextAlignment::Center,
+            );
+        }
+    }
+
+    fn render_controls(&self, draw: &Draw, rect: Rect, _ctx: &RenderContext) -> bool {
+        draw.rect()
+            .xy(rect.xy())
+            .wh(rect.wh())
+            .color(srgba(0.03, 0.02, 0.05, 0.95));
+
+        let timeline = match &self.timeline {
+            Ok(timeline) => timeline,
+            Err(e) => {
+                draw_text(
+                    draw,
+                    FontId::PlexSansBold,
+                    e,
+                    rect.xy(),
+                    14.0,
+                    srgba(1.0, 0.4, 0.4, 1.0),
+                    TextAlignment::Center,
+                );
+                return false;
+            }
+        };
+
+        let pad = 24.0;
+        let row_h = (rect.h() * 0.12).clamp(18.0, 40.0);
+        let width = rect.w() - pad * 2.0;
+
+        draw_text(
+            draw,
+            FontId::PlexSansBold,
+            &format!("{} dasha", title_case(timeline.system.label())),
+            pt2(rect.x(), rect.top() - pad),
+            16.0,
+            srgba(0.9, 0.9, 0.9, 1.0),
+            TextAlignment::Center,
+        );
+
+        let maha_row = Rect::from_x_y_w_h(rect.x(), rect.top() - pad * 2.5, width, row_h);
+        self.draw_row(
+            draw,
+            maha_row,
+            &timeline.periods,
+            timeline.start(),
+            timeline.end(),
+        );
+
+        let running = self.running();
+        let mut lines = Vec::new();
+        if let Some(maha) = running.first() {
+            let bhukti_row = Rect::from_x_y_w_h(
+                rect.x(),
+                maha_row.bottom() - pad - row_h / 2.0,
+                width,
+                row_h,
+            );
+            self.draw_row(draw, bhukti_row, &maha.children, maha.start, maha.end);
+        }
+        for period in &running {
+            lines.push(format!(
+                "{:?}: {}  {} → {}",
+                period.level,
+                title_case(&period.planet),
+                period.start.format("%Y-%m-%d"),
+                period.end.format("%Y-%m-%d")
+            ));
+        }
+        lines.extend(self.next_text());
+
+        let mut y = maha_row.bottom() - pad * 2.0 - row_h * 1.5;
+        for line in &lines {
+            draw_text(
+                draw,
+                FontId::PlexMonoRegular,
+                line,
+                pt2(rect.x(), y),
+                13.0,
+                srgba(0.8, 0.8, 0.8, 1.0),
+                TextAlignment::Center,
+            );
+            y -= 20.0;
+        }
+        false
+    }
+
+    fn get_error(&self) -> Option<TileError> {
+        let error = self.timeline.as_ref().err()?;
+        Some(TileError::new("Dasha timeline unavailable").with_details(error))
+    }
+
+    fn get_display_text(&self) -> Option<String> {
+        let running: Vec<String> = self
+            .running()
+            .iter()
+            .map(|p| title_case(&p.planet))
+            .collect();
+        Some(format!("Dasha: {}", running.join(" / ")))
+    }
+}
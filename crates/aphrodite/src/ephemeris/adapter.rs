@@ -126,7 +126,32 @@ impl SwissEphemerisAdapter {
         let jd = datetime_to_julian_day(dt_utc);
         let house_system_byte = get_house_system_byte(&settings.house_system)?;
         let flags = self.configure_flags(settings)?;
+        self.positions_at_jd(jd, location.as_ref(), settings, flags, house_system_byte)
+    }
+
+    /// Calculate positions at a series of Julian days, resolving the zodiac
+    /// flags and house system once for the whole series
+    pub fn calc_positions_series(
+        &mut self,
+        jds: &[f64],
+        location: Option<&GeoLocation>,
+        settings: &EphemerisSettings,
+    ) -> Result<Vec<LayerPositions>, EphemerisError> {
+        let house_system_byte = get_house_system_byte(&settings.house_system)?;
+        let flags = self.configure_flags(settings)?;
+        jds.iter()
+            .map(|jd| self.positions_at_jd(*jd, location, settings, flags, house_system_byte))
+            .collect()
+    }
 
+    fn positions_at_jd(
+        &self,
+        jd: f64,
+        location: Option<&GeoLocation>,
+        settings: &EphemerisSettings,
+        flags: i32,
+        house_system_byte: u8,
+    ) -> Result<LayerPositions, EphemerisError> {
         // Calculate planets
         let mut planets = HashMap::new();
         for obj_id in &settings.include_objects {
@@ -314,7 +339,7 @@ impl SwissEphemerisAdapter {
 }
 
 /// Convert UTC datetime to Julian Day
-fn datetime_to_julian_day(dt: DateTime<Utc>) -> f64 {
+pub(crate) fn datetime_to_julian_day(dt: DateTime<Utc>) -> f64 {
     let year = dt.year();
     let month = dt.month();
     let day = dt.day();
@@ -1,9 +1,12 @@
 use crate::aspects::types::AspectSet;
 use crate::ephemeris::types::LayerPositions;
 use crate::layout::rings::{
-    build_house_items, build_planet_items, build_static_zodiac_items, RingItem,
+    build_house_items, build_midpoint_items, build_planet_items, build_static_zodiac_items,
+    RingItem,
 };
 use crate::layout::types::{RingDefinition, WheelDefinition};
+use crate::western::harmonics::harmonic_positions;
+use crate::western::midpoints::URANIAN_DIAL;
 use std::collections::HashMap;
 
 /// Assembled wheel with resolved ring items
@@ -110,6 +113,21 @@ impl WheelAssembler {
                     items.extend(planet_items.into_iter().map(RingItem::Planet));
                 }
             }
+            crate::layout::types::RingDataSource::LayerHarmonicPlanets { layer_id, harmonic } => {
+                if let Some(positions) = positions_by_layer.get(layer_id) {
+                    let harmonic_chart = harmonic_positions(positions, *harmonic);
+                    let planet_items =
+                        build_planet_items(slug, layer_id, &harmonic_chart, include_objects);
+                    items.extend(planet_items.into_iter().map(RingItem::Planet));
+                }
+            }
+            crate::layout::types::RingDataSource::LayerMidpoints { layer_id, dial } => {
+                if let Some(positions) = positions_by_layer.get(layer_id) {
+                    let dial = dial.unwrap_or(URANIAN_DIAL);
+                    let midpoint_items = build_midpoint_items(slug, layer_id, positions, dial);
+                    items.extend(midpoint_items.into_iter().map(RingItem::Planet));
+                }
+            }
             crate::layout::types::RingDataSource::LayerVargaPlanets { .. } => {
                 // Vedic varga planets - deferred to Phase 6
                 // For now, leave items empty
@@ -1,5 +1,6 @@
-use crate::layout::types::WheelDefinitionWithPresets;
+use crate::layout::types::{RingDefinition, WheelDefinitionWithPresets};
 use serde_json;
+use std::path::Path;
 use thiserror::Error;
 
 /// Errors that can occur when loading wheel definitions
@@ -13,6 +14,8 @@ pub enum WheelDefinitionError {
     MissingField(String),
     #[error("Invalid field value: {0}")]
     InvalidFieldValue(String),
+    #[error("Cannot read wheel definition: {0}")]
+    Io(String),
 }
 
 /// Load a wheel definition from JSON string
@@ -24,7 +27,29 @@ pub fn load_wheel_definition_from_json(
 
     validate_wheel_definition(&parsed)?;
 
-    serde_json::from_value(parsed).map_err(|e| WheelDefinitionError::ValidationError(e.to_string()))
+    serde_json::from_value(parsed.clone()).map_err(|e| {
+        // Point at the ring that failed to deserialize rather than the whole document
+        let rings = parsed["rings"].as_array().cloned().unwrap_or_default();
+        rings
+            .into_iter()
+            .enumerate()
+            .find_map(|(index, ring)| {
+                serde_json::from_value::<RingDefinition>(ring)
+                    .err()
+                    .map(|ring_err| format!("rings[{}]: {}", index, ring_err))
+            })
+            .map(WheelDefinitionError::ValidationError)
+            .unwrap_or_else(|| WheelDefinitionError::ValidationError(e.to_string()))
+    })
+}
+
+/// Load a wheel definition from a JSON file
+pub fn load_wheel_definition_from_file(
+    path: &Path,
+) -> Result<WheelDefinitionWithPresets, WheelDefinitionError> {
+    let json = std::fs::read_to_string(path)
+        .map_err(|e| WheelDefinitionError::Io(format!("{}: {}", path.display(), e)))?;
+    load_wheel_definition_from_json(&json)
 }
 
 /// Validate a wheel definition
@@ -73,6 +98,7 @@ fn validate_wheel_definition(definition: &serde_json::Value) -> Result<(), Wheel
     for (index, ring) in rings_array.iter().enumerate() {
         validate_ring_definition(ring, index)?;
     }
+    validate_ring_layout(rings_array)?;
 
     // Validate optional fields
     if let Some(description) = obj.get("description") {
@@ -131,6 +157,36 @@ fn validate_wheel_definition(definition: &serde_json::Value) -> Result<(), Wheel
     Ok(())
 }
 
+/// Validate slugs and radii across rings (each ring already validated on its own)
+fn validate_ring_layout(rings: &[serde_json::Value]) -> Result<(), WheelDefinitionError> {
+    for (index, ring) in rings.iter().enumerate() {
+        for (other_index, other) in rings.iter().enumerate().take(index) {
+            if ring["slug"] == other["slug"] {
+                return Err(WheelDefinitionError::InvalidFieldValue(format!(
+                    "rings[{}].slug {} duplicates rings[{}].slug",
+                    index, ring["slug"], other_index
+                )));
+            }
+
+            let (inner, outer) = (ring["radiusInner"].as_f64(), ring["radiusOuter"].as_f64());
+            let (other_inner, other_outer) =
+                (other["radiusInner"].as_f64(), other["radiusOuter"].as_f64());
+            if let (Some(inner), Some(outer), Some(other_inner), Some(other_outer)) =
+                (inner, outer, other_inner, other_outer)
+            {
+                // Rings may share a boundary but not cover the same band
+                if inner < other_outer && other_inner < outer {
+                    return Err(WheelDefinitionError::InvalidFieldValue(format!(
+                        "rings[{}] ({}..{}) overlaps rings[{}] ({}..{})",
+                        index, inner, outer, other_index, other_inner, other_outer
+                    )));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Validate a ring definition
 fn validate_ring_definition(
     ring: &serde_json::Value,
@@ -313,6 +369,8 @@ fn validate_ring_definition(
             "layer_houses",
             "layer_planets",
             "layer_varga_planets",
+            "layer_harmonic_planets",
+            "layer_midpoints",
             "aspect_set",
         ];
         if !valid_kinds.contains(&kind_str) {
@@ -324,7 +382,10 @@ fn validate_ring_definition(
         }
 
         // Validate layer-specific requirements
-        if kind_str == "layer_houses" || kind_str == "layer_planets" {
+        if matches!(
+            kind_str,
+            "layer_houses" | "layer_planets" | "layer_harmonic_planets" | "layer_midpoints"
+        ) {
             if !data_source_obj.contains_key("layerId") {
                 return Err(WheelDefinitionError::MissingField(format!(
                     "rings[{}].dataSource.layerId (required for {})",
@@ -351,6 +412,32 @@ fn validate_ring_definition(
             }
         }
 
+        if kind_str == "layer_harmonic_planets" {
+            let harmonic = data_source_obj.get("harmonic").ok_or_else(|| {
+                WheelDefinitionError::MissingField(format!(
+                    "rings[{}].dataSource.harmonic (required for layer_harmonic_planets)",
+                    index
+                ))
+            })?;
+            if harmonic.as_u64().is_none_or(|n| n == 0) {
+                return Err(WheelDefinitionError::InvalidFieldValue(format!(
+                    "rings[{}].dataSource.harmonic must be a positive integer",
+                    index
+                )));
+            }
+        }
+
+        if kind_str == "layer_midpoints" {
+            if let Some(dial) = data_source_obj.get("dial").filter(|d| !d.is_null()) {
+                if !dial.as_f64().is_some_and(|d| d > 0.0 && d <= 360.0) {
+                    return Err(WheelDefinitionError::InvalidFieldValue(format!(
+                        "rings[{}].dataSource.dial must be a number of degrees in (0, 360]",
+                        index
+                    )));
+                }
+            }
+        }
+
         if kind_str == "aspect_set" {
             if !data_source_obj.contains_key("aspectSetId") {
                 return Err(WheelDefinitionError::MissingField(format!(
@@ -2,10 +2,14 @@ pub mod assembler;
 pub mod loader;
 pub mod rings;
 pub mod types;
+pub mod watcher;
 
 pub use assembler::{AssembledRing, AssembledWheel, WheelAssembler};
-pub use loader::{load_wheel_definition_from_json, WheelDefinitionError};
+pub use loader::{
+    load_wheel_definition_from_file, load_wheel_definition_from_json, WheelDefinitionError,
+};
 pub use types::{
     AspectSetFilter, RingDataSource, RingDefinition, RingType, WheelDefinition,
     WheelDefinitionWithPresets,
 };
+pub use watcher::WheelDefinitionWatcher;
@@ -1,4 +1,5 @@
 use crate::ephemeris::types::LayerPositions;
+use crate::western::midpoints::{dial_longitude, midpoints};
 use std::collections::HashMap;
 
 /// Sign names and glyphs
@@ -247,3 +248,32 @@ pub fn build_planet_items(
 
     items
 }
+
+/// Build midpoint items, with dial positions scaled onto the full circle
+pub fn build_midpoint_items(
+    slug: &str,
+    layer_id: &str,
+    positions: &LayerPositions,
+    dial: f64,
+) -> Vec<PlanetRingItem> {
+    let scale = 360.0 / dial;
+    midpoints(positions)
+        .into_iter()
+        .map(|midpoint| {
+            let lon = dial_longitude(midpoint.lon, dial) * scale;
+            PlanetRingItem {
+                id: format!("{}_{}_{}", slug, midpoint.a, midpoint.b),
+                kind: "midpoint".to_string(),
+                planet_id: format!("{}/{}", midpoint.a, midpoint.b),
+                layer_id: layer_id.to_string(),
+                lon,
+                lat: None,
+                speed_lon: None,
+                retrograde: None,
+                sign_index: get_sign_index(lon),
+                sign_degree: get_sign_degree(lon),
+                house_index: None,
+            }
+        })
+        .collect()
+}
@@ -18,16 +18,35 @@ pub enum RingDataSource {
     StaticZodiac,
     StaticNakshatras,
     LayerHouses {
+        #[serde(rename = "layerId")]
         layer_id: String,
     },
     LayerPlanets {
+        #[serde(rename = "layerId")]
         layer_id: String,
     },
     LayerVargaPlanets {
+        #[serde(rename = "layerId")]
         layer_id: String,
+        #[serde(rename = "vargaId")]
         varga_id: String,
     },
+    /// Planets of a layer projected into the Nth harmonic chart
+    LayerHarmonicPlanets {
+        #[serde(rename = "layerId")]
+        layer_id: String,
+        harmonic: u32,
+    },
+    /// Pairwise midpoints of a layer, on a dial (90° unless set) spread
+    /// around the full ring
+    LayerMidpoints {
+        #[serde(rename = "layerId")]
+        layer_id: String,
+        #[serde(default)]
+        dial: Option<f64>,
+    },
     AspectSet {
+        #[serde(rename = "aspectSetId")]
         aspect_set_id: String,
         filter: Option<AspectSetFilter>,
     },
@@ -0,0 +1,92 @@
+use crate::layout::loader::{load_wheel_definition_from_json, WheelDefinitionError};
+use crate::layout::types::WheelDefinitionWithPresets;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Polls a wheel definition file and reloads it when its contents change.
+///
+/// The last valid definition is kept while the file is invalid, so a half
+/// edited layout never blanks the wheel.
+pub struct WheelDefinitionWatcher {
+    path: PathBuf,
+    modified: Option<(SystemTime, u64)>,
+    contents: Option<String>,
+    definition: Option<WheelDefinitionWithPresets>,
+    error: Option<WheelDefinitionError>,
+}
+
+impl WheelDefinitionWatcher {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            modified: None,
+            contents: None,
+            definition: None,
+            error: None,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Last definition that passed validation
+    pub fn definition(&self) -> Option<&WheelDefinitionWithPresets> {
+        self.definition.as_ref()
+    }
+
+    /// Error from the latest load, cleared once the file validates again
+    pub fn error(&self) -> Option<&WheelDefinitionError> {
+        self.error.as_ref()
+    }
+
+    /// Force the next `check` to reload even if the file looks unchanged
+    pub fn invalidate(&mut self) {
+        self.modified = None;
+        self.contents = None;
+    }
+
+    /// Reload if the file changed. Returns true when the definition or the
+    /// error changed, i.e. when the wheel should be re-assembled or redrawn.
+    pub fn check(&mut self) -> bool {
+        let stamp = std::fs::metadata(&self.path)
+            .ok()
+            .and_then(|meta| Some((meta.modified().ok()?, meta.len())));
+        if stamp.is_some() && stamp == self.modified {
+            return false;
+        }
+        self.modified = stamp;
+
+        let contents = match std::fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) => {
+                self.contents = None;
+                return self.set_error(WheelDefinitionError::Io(format!(
+                    "{}: {}",
+                    self.path.display(),
+                    e
+                )));
+            }
+        };
+        if self.contents.as_ref() == Some(&contents) {
+            return false;
+        }
+
+        let result = load_wheel_definition_from_json(&contents);
+        self.contents = Some(contents);
+        match result {
+            Ok(definition) => {
+                self.definition = Some(definition);
+                self.error = None;
+                true
+            }
+            Err(e) => self.set_error(e),
+        }
+    }
+
+    fn set_error(&mut self, error: WheelDefinitionError) -> bool {
+        let changed = self.error.as_ref().map(|e| e.to_string()) != Some(error.to_string());
+        self.error = Some(error);
+        changed
+    }
+}
@@ -1,13 +1,21 @@
 use magnolia_plugin_helper::{
-    export_plugin, MagnoliaPlugin, SignalBuffer, SignalType, SignalValue,
+    bytes_payload, export_plugin, MagnoliaPlugin, SignalBuffer, SignalType,
 };
 use magnolia_signals::AstrologyData;
 
+pub mod almanac;
 pub mod aspects;
+pub mod batch;
 #[cfg(feature = "tile-rendering")]
 pub mod chart;
+pub mod dasha_source;
+#[cfg(feature = "tile-rendering")]
+pub mod dasha_tile;
 pub mod ephemeris;
 pub mod layout;
+pub mod lunar;
+pub mod lunar_source;
+pub mod natal;
 pub mod rendering;
 pub mod vedic;
 pub mod western;
@@ -154,15 +162,9 @@ impl MagnoliaPlugin for AphroditePlugin {
                 };
 
                 // Serialize to JSON (Host expects serialized data)
-                let json_str = serde_json::to_string(&data).unwrap_or_default();
-                let mut bytes = json_str.into_bytes();
-                let len = bytes.len();
-                let ptr = bytes.as_mut_ptr();
-                std::mem::forget(bytes); // Leak to host
-
-                buffer.signal_type = SignalType::Astrology as u32;
-                buffer.value = SignalValue { ptr: ptr as *mut _ };
-                buffer.size = len as u64;
+                // The host hands the payload back through `free_buffer` once copied.
+                let json = serde_json::to_vec(&data).unwrap_or_default();
+                bytes_payload(buffer, SignalType::Astrology, json);
 
                 return true;
             }
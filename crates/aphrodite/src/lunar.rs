@@ -0,0 +1,375 @@
+//! Lunar timing: phase, sign ingresses and void-of-course windows.
+//!
+//! The Moon is void of course from its last major aspect (conjunction,
+//! sextile, square, trine or opposition) to another body until it enters the
+//! next sign. Without Swiss Ephemeris data only the Sun is available as an
+//! aspect partner and ingress times come from the analytic series in
+//! [`crate::almanac`], so windows are marked `approximate`.
+
+use crate::almanac::{
+    approx_moon_longitude, approx_sun_longitude, from_julian_day, julian_day, moon_phase, MoonPhase,
+};
+use crate::ephemeris::SwissEphemerisAdapter;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// FLG_SWIEPH
+const SWISS_FLAGS: i32 = 2;
+/// Coarse search step; the Moon moves ~0.5° per hour.
+const STEP_DAYS: f64 = 1.0 / 24.0;
+/// Bisection stops below this (~5 s).
+const PRECISION_DAYS: f64 = 1.0 / 17_280.0;
+/// The Moon never spends more than ~2.7 days in a sign.
+const MAX_SIGN_DAYS: f64 = 3.0;
+
+pub const SIGNS: [&str; 12] = [
+    "Aries",
+    "Taurus",
+    "Gemini",
+    "Cancer",
+    "Leo",
+    "Virgo",
+    "Libra",
+    "Scorpio",
+    "Sagittarius",
+    "Capricorn",
+    "Aquarius",
+    "Pisces",
+];
+
+/// Aspect partners for void-of-course, traditional planets first.
+pub const VOC_BODIES: [&str; 9] = [
+    "sun", "mercury", "venus", "mars", "jupiter", "saturn", "uranus", "neptune", "pluto",
+];
+
+const ASPECTS: [(f64, &str); 5] = [
+    (0.0, "conjunction"),
+    (60.0, "sextile"),
+    (90.0, "square"),
+    (120.0, "trine"),
+    (180.0, "opposition"),
+];
+
+pub fn sign_index(lon: f64) -> usize {
+    (lon.rem_euclid(360.0) / 30.0).floor() as usize % 12
+}
+
+pub fn sign_name(lon: f64) -> &'static str {
+    SIGNS[sign_index(lon)]
+}
+
+/// Angle wrapped to -180..180°.
+fn wrap180(deg: f64) -> f64 {
+    (deg + 180.0).rem_euclid(360.0) - 180.0
+}
+
+/// Ecliptic longitudes by body name and Julian day.
+pub trait LongitudeSource {
+    fn longitude(&self, body: &str, jd: f64) -> Option<f64>;
+
+    /// Whether positions come from the analytic fallback.
+    fn approximate(&self) -> bool;
+}
+
+/// Swiss Ephemeris when available, analytic Sun/Moon otherwise.
+pub struct EphemerisLongitudes<'a> {
+    pub ephemeris: Option<&'a SwissEphemerisAdapter>,
+}
+
+impl LongitudeSource for EphemerisLongitudes<'_> {
+    fn longitude(&self, body: &str, jd: f64) -> Option<f64> {
+        if let Some(pos) = self
+            .ephemeris
+            .and_then(|eph| eph.calc_planet_position(body, jd, SWISS_FLAGS).ok())
+        {
+            return Some(pos.lon);
+        }
+        match body {
+            "sun" => Some(approx_sun_longitude(jd)),
+            "moon" => Some(approx_moon_longitude(jd)),
+            _ => None,
+        }
+    }
+
+    fn approximate(&self) -> bool {
+        self.ephemeris.is_none()
+    }
+}
+
+fn moon_lon(longitudes: &dyn LongitudeSource, jd: f64) -> f64 {
+    longitudes
+        .longitude("moon", jd)
+        .unwrap_or_else(|| approx_moon_longitude(jd))
+}
+
+/// Bisect `[lo, hi]` for the first time `f` is true, given `f(lo)` false and `f(hi)` true.
+fn bisect(mut lo: f64, mut hi: f64, f: impl Fn(f64) -> bool) -> f64 {
+    while hi - lo > PRECISION_DAYS {
+        let mid = (lo + hi) / 2.0;
+        if f(mid) {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+    hi
+}
+
+/// Julian day the Moon next leaves its sign at `jd`.
+pub fn next_ingress(longitudes: &dyn LongitudeSource, jd: f64) -> f64 {
+    let sign = sign_index(moon_lon(longitudes, jd));
+    let left = |t: f64| sign_index(moon_lon(longitudes, t)) != sign;
+    let mut t = jd;
+    while t - jd < MAX_SIGN_DAYS {
+        let next = t + STEP_DAYS;
+        if left(next) {
+            return bisect(t, next, left);
+        }
+        t = next;
+    }
+    t
+}
+
+/// Julian day the Moon entered its sign at `jd`.
+pub fn previous_ingress(longitudes: &dyn LongitudeSource, jd: f64) -> f64 {
+    let sign = sign_index(moon_lon(longitudes, jd));
+    let inside = |t: f64| sign_index(moon_lon(longitudes, t)) == sign;
+    let mut t = jd;
+    while jd - t < MAX_SIGN_DAYS {
+        let prev = t - STEP_DAYS;
+        if !inside(prev) {
+            return bisect(prev, t, inside);
+        }
+        t = prev;
+    }
+    t
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LunarAspect {
+    pub body: String,
+    pub aspect: String,
+    pub at: DateTime<Utc>,
+}
+
+/// Exact Moon aspects to `body` between `start` and `end`, in time order.
+fn aspects_to(
+    longitudes: &dyn LongitudeSource,
+    body: &str,
+    start: f64,
+    end: f64,
+) -> Vec<(f64, &'static str)> {
+    let mut found = Vec::new();
+    for (angle, name) in ASPECTS {
+        // Aspects other than conjunction/opposition occur on both sides
+        let targets: &[f64] = if angle == 0.0 || angle == 180.0 {
+            &[angle]
+        } else {
+            &[angle, -angle]
+        };
+        for &target in targets {
+            let offset = |t: f64| {
+                let body_lon = longitudes.longitude(body, t)?;
+                Some(wrap180(moon_lon(longitudes, t) - body_lon - target))
+            };
+            let mut t = start;
+            let Some(mut prev) = offset(t) else {
+                return found;
+            };
+            while t < end {
+                let next_t = (t + STEP_DAYS).min(end);
+                let Some(next) = offset(next_t) else {
+                    break;
+                };
+                // A real crossing, not the ±180° wrap
+                if prev < 0.0 && next >= 0.0 && next - prev < 90.0 {
+                    let at = bisect(t, next_t, |x| offset(x).is_some_and(|v| v >= 0.0));
+                    found.push((at, name));
+                }
+                t = next_t;
+                prev = next;
+            }
+        }
+    }
+    found.sort_by(|a, b| a.0.total_cmp(&b.0));
+    found
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VoidOfCourse {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// Aspect that starts the void; `None` if the Moon made none in this sign
+    pub last_aspect: Option<LunarAspect>,
+}
+
+impl VoidOfCourse {
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        self.start <= at && at < self.end
+    }
+}
+
+/// Void-of-course window for the Moon's stay in the sign from `sign_start` to `ingress`.
+pub fn void_of_course(
+    longitudes: &dyn LongitudeSource,
+    sign_start: f64,
+    ingress: f64,
+) -> VoidOfCourse {
+    let last_aspect = VOC_BODIES
+        .iter()
+        .filter_map(|body| {
+            aspects_to(longitudes, body, sign_start, ingress)
+                .last()
+                .map(|&(at, aspect)| (at, body, aspect))
+        })
+        .max_by(|a, b| a.0.total_cmp(&b.0));
+
+    let (start, last_aspect) = match last_aspect {
+        Some((at, body, aspect)) => (
+            at,
+            Some(LunarAspect {
+                body: body.to_string(),
+                aspect: aspect.to_string(),
+                at: from_julian_day(at),
+            }),
+        ),
+        None => (sign_start, None),
+    };
+    VoidOfCourse {
+        start: from_julian_day(start),
+        end: from_julian_day(ingress),
+        last_aspect,
+    }
+}
+
+/// Moon phase, sign and void-of-course status at one moment.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LunarReport {
+    pub at: DateTime<Utc>,
+    pub phase: MoonPhase,
+    pub moon_longitude: f64,
+    pub sign: String,
+    pub sign_entered: DateTime<Utc>,
+    pub next_ingress: DateTime<Utc>,
+    pub next_sign: String,
+    pub void_of_course: VoidOfCourse,
+    pub is_void: bool,
+    pub approximate: bool,
+}
+
+impl LunarReport {
+    pub fn compute(at: DateTime<Utc>, longitudes: &dyn LongitudeSource) -> Self {
+        let jd = julian_day(at);
+        let moon = moon_lon(longitudes, jd);
+        let sun = longitudes
+            .longitude("sun", jd)
+            .unwrap_or_else(|| approx_sun_longitude(jd));
+        let entered = previous_ingress(longitudes, jd);
+        let ingress = next_ingress(longitudes, jd);
+        let void_of_course = void_of_course(longitudes, entered, ingress);
+        Self {
+            at,
+            phase: moon_phase(sun, moon),
+            moon_longitude: moon,
+            sign: sign_name(moon).to_string(),
+            sign_entered: from_julian_day(entered),
+            next_ingress: from_julian_day(ingress),
+            next_sign: SIGNS[(sign_index(moon) + 1) % 12].to_string(),
+            is_void: void_of_course.contains(at),
+            void_of_course,
+            approximate: longitudes.approximate(),
+        }
+    }
+
+    /// Same sign window at a later moment: refreshes the phase and void flag
+    /// without searching for ingresses and aspects again.
+    pub fn advance(&self, at: DateTime<Utc>, longitudes: &dyn LongitudeSource) -> Option<Self> {
+        if at < self.sign_entered || at >= self.next_ingress {
+            return None;
+        }
+        let jd = julian_day(at);
+        let moon = moon_lon(longitudes, jd);
+        let sun = longitudes
+            .longitude("sun", jd)
+            .unwrap_or_else(|| approx_sun_longitude(jd));
+        Some(Self {
+            at,
+            phase: moon_phase(sun, moon),
+            moon_longitude: moon,
+            is_void: self.void_of_course.contains(at),
+            ..self.clone()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    /// Analytic Sun/Moon plus a fixed body, for aspect searches.
+    struct Fixture {
+        fixed: f64,
+    }
+
+    impl LongitudeSource for Fixture {
+        fn longitude(&self, body: &str, jd: f64) -> Option<f64> {
+            match body {
+                "sun" => Some(approx_sun_longitude(jd)),
+                "moon" => Some(approx_moon_longitude(jd)),
+                "saturn" => Some(self.fixed),
+                _ => None,
+            }
+        }
+
+        fn approximate(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn ingresses_bracket_the_current_sign() {
+        let longitudes = EphemerisLongitudes { ephemeris: None };
+        let at = Utc.with_ymd_and_hms(2024, 1, 25, 18, 0, 0).unwrap();
+        let report = LunarReport::compute(at, &longitudes);
+
+        // Full moon in Leo; it entered ~2024-01-24 and left ~2024-01-27
+        assert_eq!(report.sign, "Leo");
+        assert_eq!(report.next_sign, "Virgo");
+        assert!(report.sign_entered < at && at < report.next_ingress);
+        let stay = report.next_ingress - report.sign_entered;
+        assert!(stay > chrono::Duration::hours(40) && stay < chrono::Duration::hours(72));
+        assert!((report.phase.illumination - 1.0).abs() < 0.01);
+        assert!(report.approximate);
+
+        let entered = julian_day(report.sign_entered);
+        assert_eq!(sign_name(approx_moon_longitude(entered + 0.001)), "Leo");
+        assert_eq!(sign_name(approx_moon_longitude(entered - 0.001)), "Cancer");
+    }
+
+    #[test]
+    fn void_starts_at_the_last_aspect_in_the_sign() {
+        let at = Utc.with_ymd_and_hms(2024, 1, 25, 18, 0, 0).unwrap();
+        let jd = julian_day(at);
+        let base = EphemerisLongitudes { ephemeris: None };
+        let (entered, ingress) = (previous_ingress(&base, jd), next_ingress(&base, jd));
+
+        // Put a body 120° behind the Moon's longitude 6h before ingress:
+        // the trine perfects then and nothing follows it.
+        let perfect = ingress - 0.25;
+        let fixture = Fixture {
+            fixed: (approx_moon_longitude(perfect) - 120.0).rem_euclid(360.0),
+        };
+        let voc = void_of_course(&fixture, entered, ingress);
+
+        let aspect = voc.last_aspect.as_ref().expect("trine should be found");
+        assert_eq!(
+            (aspect.body.as_str(), aspect.aspect.as_str()),
+            ("saturn", "trine")
+        );
+        assert!((julian_day(voc.start) - perfect).abs() < 0.001);
+        assert_eq!(voc.end, from_julian_day(ingress));
+        assert!(voc.contains(from_julian_day(ingress - 0.1)));
+        assert!(!voc.contains(from_julian_day(perfect - 0.1)));
+    }
+}
@@ -0,0 +1,254 @@
+//! `lunar` source: periodic Moon reports and transition events.
+//!
+//! Emits on two ports:
+//! - `lunar_out`: a [`LunarReport`] as JSON (`Computed`, source `lunar`) every report interval
+//! - `events_out`: `Intent`s at transitions
+//!   - `moon_phase` `[phase, illumination %]`
+//!   - `moon_ingress` `[sign]`
+//!   - `void_of_course_start` `[until (RFC 3339), last aspect]`
+//!   - `void_of_course_end` `[sign]`
+
+use crate::ephemeris::SwissEphemerisAdapter;
+use crate::lunar::{EphemerisLongitudes, LunarReport};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use magnolia_core::{
+    system_clock, DataType, ModuleSchema, Port, PortDirection, SharedClock, Signal, Source,
+};
+use std::collections::VecDeque;
+use std::time::Duration;
+
+const TICK: Duration = Duration::from_secs(10);
+const DEFAULT_REPORT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Intents for what changed between two consecutive reports.
+pub fn transition_events(previous: &LunarReport, current: &LunarReport) -> Vec<Signal> {
+    let intent = |action: &str, parameters: Vec<String>| Signal::Intent {
+        action: action.to_string(),
+        parameters,
+    };
+    let mut events = Vec::new();
+
+    if current.sign != previous.sign {
+        if previous.is_void {
+            events.push(intent("void_of_course_end", vec![current.sign.clone()]));
+        }
+        events.push(intent("moon_ingress", vec![current.sign.clone()]));
+    } else if previous.is_void && !current.is_void {
+        events.push(intent("void_of_course_end", vec![current.sign.clone()]));
+    }
+
+    if current.is_void && (!previous.is_void || current.sign != previous.sign) {
+        let aspect = current
+            .void_of_course
+            .last_aspect
+            .as_ref()
+            .map(|a| format!("{} {}", a.aspect, a.body))
+            .unwrap_or_default();
+        events.push(intent(
+            "void_of_course_start",
+            vec![current.void_of_course.end.to_rfc3339(), aspect],
+        ));
+    }
+
+    if current.phase.name != previous.phase.name {
+        events.push(intent(
+            "moon_phase",
+            vec![
+                current.phase.name.label().to_string(),
+                format!("{:.0}", current.phase.illumination * 100.0),
+            ],
+        ));
+    }
+    events
+}
+
+pub struct LunarSource {
+    id: String,
+    enabled: bool,
+    ephemeris: Option<SwissEphemerisAdapter>,
+    clock: SharedClock,
+    report_interval: Duration,
+    report: Option<LunarReport>,
+    last_emit: Option<DateTime<Utc>>,
+    pending: VecDeque<Signal>,
+}
+
+impl LunarSource {
+    pub fn new(id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            enabled: true,
+            // Falls back to analytic Sun/Moon positions when the data files are missing
+            ephemeris: SwissEphemerisAdapter::new(None).ok(),
+            clock: system_clock(),
+            report_interval: DEFAULT_REPORT_INTERVAL,
+            report: None,
+            last_emit: None,
+            pending: VecDeque::new(),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn with_report_interval(mut self, interval: Duration) -> Self {
+        self.report_interval = interval.max(TICK);
+        self
+    }
+
+    fn now(&self) -> DateTime<Utc> {
+        DateTime::from_timestamp_micros(self.clock.now_us() as i64).unwrap_or_default()
+    }
+
+    fn check(&mut self) {
+        let now = self.now();
+        let longitudes = EphemerisLongitudes {
+            ephemeris: self.ephemeris.as_ref(),
+        };
+        let current = self
+            .report
+            .as_ref()
+            .and_then(|report| report.advance(now, &longitudes))
+            .unwrap_or_else(|| LunarReport::compute(now, &longitudes));
+
+        if let Some(previous) = &self.report {
+            self.pending.extend(transition_events(previous, &current));
+        }
+
+        let due = self
+            .last_emit
+            .is_none_or(|last| (now - last).to_std().unwrap_or_default() >= self.report_interval);
+        if due {
+            match serde_json::to_string(&current) {
+                Ok(content) => self.pending.push_back(Signal::Computed {
+                    source: "lunar".to_string(),
+                    content,
+                }),
+                Err(e) => eprintln!("Lunar: failed to encode report: {}", e),
+            }
+            self.last_emit = Some(now);
+        }
+        self.report = Some(current);
+    }
+}
+
+#[async_trait]
+impl Source for LunarSource {
+    fn name(&self) -> &str {
+        "Lunar"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "Lunar Timing".to_string(),
+            description: "Moon phase, sign ingresses and void-of-course windows".to_string(),
+            ports: vec![
+                Port {
+                    id: "lunar_out".to_string(),
+                    label: "Lunar Report".to_string(),
+                    data_type: DataType::Astrology,
+                    direction: PortDirection::Output,
+                },
+                Port {
+                    id: "events_out".to_string(),
+                    label: "Lunar Events".to_string(),
+                    data_type: DataType::Control,
+                    direction: PortDirection::Output,
+                },
+            ],
+            settings_schema: None,
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    async fn poll(&mut self) -> Option<Signal> {
+        loop {
+            if let Some(signal) = self.pending.pop_front() {
+                return Some(signal);
+            }
+            if self.enabled {
+                self.check();
+                if !self.pending.is_empty() {
+                    continue;
+                }
+            } else {
+                // Re-baseline on enable so no stale transitions fire
+                self.report = None;
+                self.last_emit = None;
+            }
+            self.clock.sleep(TICK).await;
+        }
+    }
+
+    fn output_port(&self, signal: &Signal) -> Option<&str> {
+        match signal {
+            Signal::Intent { .. } => Some("events_out"),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::almanac::julian_day;
+    use crate::lunar::next_ingress;
+    use chrono::TimeZone;
+    use magnolia_core::VirtualClock;
+
+    fn at_us(at: DateTime<Utc>) -> u64 {
+        at.timestamp_micros() as u64
+    }
+
+    #[tokio::test]
+    async fn emits_report_then_ingress_event() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 25, 18, 0, 0).unwrap();
+        let longitudes = EphemerisLongitudes { ephemeris: None };
+        let ingress = crate::almanac::from_julian_day(next_ingress(&longitudes, julian_day(start)));
+        let begin = ingress - chrono::Duration::minutes(5);
+
+        let mut source = LunarSource::new("lunar")
+            .with_clock(VirtualClock::starting_at(at_us(begin), true))
+            .with_report_interval(Duration::from_secs(3600));
+        source.ephemeris = None;
+
+        // First poll: the baseline report, from the default port
+        let first = source.poll().await.unwrap();
+        assert!(matches!(&first, Signal::Computed { source, .. } if source == "lunar"));
+        assert_eq!(source.output_port(&first), None);
+
+        let mut actions = Vec::new();
+        while actions.len() < 2 {
+            match source.poll().await.unwrap() {
+                signal @ Signal::Intent { .. } => {
+                    assert_eq!(source.output_port(&signal), Some("events_out"));
+                    if let Signal::Intent { action, parameters } = signal {
+                        actions.push((action, parameters));
+                    }
+                }
+                Signal::Computed { .. } => panic!("report interval not elapsed yet"),
+                other => panic!("unexpected signal {:?}", other),
+            }
+        }
+
+        // Leo -> Virgo; the Moon is always void right before it changes sign
+        assert_eq!(actions[0].0, "void_of_course_end");
+        assert_eq!(
+            actions[1],
+            ("moon_ingress".to_string(), vec!["Virgo".to_string()])
+        );
+        assert!(source.now() >= ingress);
+    }
+}
@@ -0,0 +1,57 @@
+//! Birth data for the radix chart and the dasha timeline.
+
+use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+
+use crate::ephemeris::adapter::EphemerisError;
+use crate::ephemeris::{EphemerisSettings, GeoLocation, SwissEphemerisAdapter};
+use crate::vedic::timeline::{DashaSystem, DashaTimeline};
+
+#[derive(Debug, Clone)]
+pub struct NatalData {
+    pub datetime: DateTime<Utc>,
+    pub location: GeoLocation,
+}
+
+impl Default for NatalData {
+    fn default() -> Self {
+        // Hardcoded: Emmy - 11/21/1985 03:09am Columbia SC
+        // Columbia SC: lat 34.0007, lon -81.0348
+        // EST = UTC-5
+        let est = FixedOffset::west_opt(5 * 3600).unwrap();
+        let local = est.with_ymd_and_hms(1985, 11, 21, 3, 9, 0).unwrap();
+        Self {
+            datetime: local.with_timezone(&Utc),
+            location: GeoLocation {
+                lat: 34.0007,
+                lon: -81.0348,
+            },
+        }
+    }
+}
+
+impl NatalData {
+    /// Sidereal (Lahiri) Moon longitude, which seeds the dasha sequence
+    pub fn sidereal_moon(
+        &self,
+        adapter: &mut SwissEphemerisAdapter,
+    ) -> Result<f64, EphemerisError> {
+        let settings = EphemerisSettings {
+            zodiac_type: "sidereal".to_string(),
+            ayanamsa: Some("lahiri".to_string()),
+            house_system: "whole_sign".to_string(),
+            include_objects: vec!["moon".to_string()],
+        };
+        let positions =
+            adapter.calc_positions(self.datetime, Some(self.location.clone()), &settings)?;
+        Ok(positions.planets.get("moon").map(|p| p.lon).unwrap_or(0.0))
+    }
+
+    pub fn dasha_timeline(
+        &self,
+        system: DashaSystem,
+        adapter: &mut SwissEphemerisAdapter,
+    ) -> Result<DashaTimeline, String> {
+        let moon = self.sidereal_moon(adapter).map_err(|e| e.to_string())?;
+        DashaTimeline::from_moon(system, self.datetime, moon)
+    }
+}
@@ -68,6 +68,7 @@ impl Source for AphroditeSource {
                 },
             ],
             settings_schema: None, // TODO: Location/timezone settings
+            docs: None,
         }
     }
     
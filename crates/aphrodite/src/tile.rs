@@ -2,11 +2,18 @@
 //!
 //! Monitor mode: Shows current sun/moon positions
 //! Control mode: Full astrological wheel chart with planets, houses, and signs
+//!
+//! Setting `wheel_definition` to a JSON wheel definition file replaces the
+//! built-in chart with that ring layout. The file is watched and the wheel
+//! re-assembled on save; validation errors are reported as the tile error
+//! while the last valid wheel stays on screen.
 
-use chrono::{FixedOffset, TimeZone, Utc};
-use magnolia_core::{BindableAction, RenderContext, TileRenderer};
+use chrono::Utc;
+use magnolia_core::{BindableAction, PowerProfile, RenderContext, TileError, TileRenderer};
 use magnolia_ui::{draw_text, FontId, TextAlignment};
 use nannou::prelude::*;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
 /// Transit mode for bi-wheel chart
 #[derive(Debug, Clone, Default)]
@@ -19,8 +26,24 @@ pub enum TransitMode {
     // Future: Fixed(DateTime<Utc>) for user-specified transit time
 }
 
-use crate::chart::{ChartAnimation, ChartData, ChartSettings, RadixChart, TransitChart};
+use crate::chart::{
+    ChartAnimation, ChartData, ChartSettings, RadixChart, TransitChart, WheelChart,
+};
 use crate::ephemeris::{EphemerisSettings, GeoLocation, LayerPositions, SwissEphemerisAdapter};
+use crate::layout::{AssembledWheel, WheelAssembler, WheelDefinitionWatcher};
+use crate::natal::NatalData;
+
+/// How often the wheel definition file is checked for edits
+const WHEEL_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How often positions are recalculated, by power profile
+fn ephemeris_interval(profile: PowerProfile) -> Duration {
+    match profile {
+        PowerProfile::Normal => Duration::from_secs(10),
+        PowerProfile::LowPower => Duration::from_secs(60),
+        PowerProfile::BatteryBackground => Duration::from_secs(300),
+    }
+}
 
 pub struct AstroTile {
     // Ephemeris state
@@ -43,8 +66,14 @@ pub struct AstroTile {
     sun_sign: String,
     moon_sign: String,
     last_update: std::time::Instant,
+    ephemeris_interval: Duration,
     show_degrees: bool,
     show_moon: bool,
+
+    // Custom ring layout (hot-reloaded JSON wheel definition)
+    wheel_watcher: Option<WheelDefinitionWatcher>,
+    wheel: Option<AssembledWheel>,
+    last_wheel_check: Instant,
 }
 
 impl AstroTile {
@@ -69,16 +98,9 @@ impl AstroTile {
             ],
         };
 
-        // Hardcoded: Emmy - 11/21/1985 03:09am Columbia SC
-        // Columbia SC: lat 34.0007, lon -81.0348
-        // EST = UTC-5
-        let natal_location = GeoLocation {
-            lat: 34.0007,
-            lon: -81.0348,
-        };
-        let est = FixedOffset::west_opt(5 * 3600).unwrap();
-        let natal_local = est.with_ymd_and_hms(1985, 11, 21, 3, 9, 0).unwrap();
-        let natal_datetime = natal_local.with_timezone(&Utc);
+        let natal = NatalData::default();
+        let natal_location = natal.location;
+        let natal_datetime = natal.datetime;
 
         let mut tile = Self {
             adapter,
@@ -94,8 +116,12 @@ impl AstroTile {
             sun_sign: String::new(),
             moon_sign: String::new(),
             last_update: std::time::Instant::now(),
+            ephemeris_interval: ephemeris_interval(PowerProfile::Normal),
             show_degrees: true,
             show_moon: true,
+            wheel_watcher: None,
+            wheel: None,
+            last_wheel_check: Instant::now(),
         };
 
         tile.refresh_ephemeris();
@@ -147,6 +173,49 @@ impl AstroTile {
         }
     }
 
+    /// Point the wheel at a definition file; an empty path restores the built-in chart
+    fn set_wheel_definition(&mut self, path: &str) {
+        let path = path.trim();
+        if path.is_empty() {
+            self.wheel_watcher = None;
+            self.wheel = None;
+            return;
+        }
+        if self
+            .wheel_watcher
+            .as_ref()
+            .is_some_and(|w| w.path() == std::path::Path::new(path))
+        {
+            return;
+        }
+        let mut watcher = WheelDefinitionWatcher::new(path);
+        watcher.check();
+        self.wheel_watcher = Some(watcher);
+        self.assemble_wheel();
+    }
+
+    /// Rebuild the wheel from the last valid definition and current positions.
+    /// Layers are `natal` (radix) and `transit`.
+    fn assemble_wheel(&mut self) {
+        let Some(definition) = self.wheel_watcher.as_ref().and_then(|w| w.definition()) else {
+            self.wheel = None;
+            return;
+        };
+        let mut layers = HashMap::new();
+        if let Some(radix) = &self.radix_positions {
+            layers.insert("natal".to_string(), radix.clone());
+        }
+        if let Some(transit) = &self.transit_positions {
+            layers.insert("transit".to_string(), transit.clone());
+        }
+        self.wheel = Some(WheelAssembler::build_wheel(
+            &definition.wheel,
+            &layers,
+            &HashMap::new(),
+            Some(&self.eph_settings.include_objects),
+        ));
+    }
+
     fn longitude_to_sign(longitude: f64) -> String {
         let signs = [
             "Aries ♈",
@@ -171,6 +240,27 @@ impl AstroTile {
         signs[index].to_string()
     }
 
+    /// Wheel name, or the validation error while the file is invalid
+    fn draw_wheel_status(&self, draw: &Draw, rect: Rect) {
+        let Some(watcher) = &self.wheel_watcher else {
+            return;
+        };
+        let (text, color) = match (watcher.error(), &self.wheel) {
+            (Some(error), _) => (error.to_string(), srgba(1.0, 0.4, 0.4, 1.0)),
+            (None, Some(wheel)) => (wheel.name.clone(), srgba(0.5, 0.5, 0.5, 1.0)),
+            (None, None) => return,
+        };
+        draw_text(
+            draw,
+            FontId::PlexMonoRegular,
+            &text,
+            pt2(rect.x(), rect.bottom() + 16.0),
+            12.0,
+            color,
+            TextAlignment::Center,
+        );
+    }
+
     // fn build_spec removed
 }
 
@@ -193,8 +283,8 @@ impl TileRenderer for AstroTile {
         // Tick transit animation if active
         self.transit_animation.update();
 
-        // Update ephemeris every 10 seconds
-        if self.last_update.elapsed().as_secs() >= 10 {
+        // Update ephemeris every 10 seconds, less often when saving power
+        if self.last_update.elapsed() >= self.ephemeris_interval {
             self.refresh_ephemeris();
             self.last_update = std::time::Instant::now();
 
@@ -203,6 +293,14 @@ impl TileRenderer for AstroTile {
                 let t_data: ChartData = t_pos.into();
                 self.transit_animation.animate_to(&t_data);
             }
+            self.assemble_wheel();
+        }
+
+        if self.last_wheel_check.elapsed() >= WHEEL_CHECK_INTERVAL {
+            self.last_wheel_check = Instant::now();
+            if self.wheel_watcher.as_mut().is_some_and(|w| w.check()) {
+                self.assemble_wheel();
+            }
         }
     }
 
@@ -295,6 +393,12 @@ impl TileRenderer for AstroTile {
             0.0
         };
 
+        if let Some(wheel) = &self.wheel {
+            WheelChart::new(rect.x(), rect.y(), radius, shift, wheel, &settings).draw(draw);
+            self.draw_wheel_status(draw, rect);
+            return false;
+        }
+
         // Create Radix Chart
         let radix = RadixChart::new(rect.x(), rect.y(), radius, &data, &settings);
 
@@ -319,6 +423,7 @@ impl TileRenderer for AstroTile {
             transit.draw_points(draw);
         }
 
+        self.draw_wheel_status(draw, rect);
         false
     }
 
@@ -333,11 +438,20 @@ impl TileRenderer for AstroTile {
                 "show_moon": {
                     "type": "boolean",
                     "default": true
+                },
+                "wheel_definition": {
+                    "type": "string",
+                    "title": "Wheel Definition (JSON path)",
+                    "default": ""
                 }
             }
         }))
     }
 
+    fn set_power_profile(&mut self, profile: PowerProfile) {
+        self.ephemeris_interval = ephemeris_interval(profile);
+    }
+
     fn apply_settings(&mut self, settings: &serde_json::Value) {
         if let Some(d) = settings.get("show_degrees").and_then(|v| v.as_bool()) {
             self.show_degrees = d;
@@ -345,12 +459,20 @@ impl TileRenderer for AstroTile {
         if let Some(m) = settings.get("show_moon").and_then(|v| v.as_bool()) {
             self.show_moon = m;
         }
+        if let Some(path) = settings.get("wheel_definition").and_then(|v| v.as_str()) {
+            self.set_wheel_definition(path);
+        }
     }
 
     fn get_settings(&self) -> serde_json::Value {
         serde_json::json!({
             "show_degrees": self.show_degrees,
-            "show_moon": self.show_moon
+            "show_moon": self.show_moon,
+            "wheel_definition": self
+                .wheel_watcher
+                .as_ref()
+                .map(|w| w.path().display().to_string())
+                .unwrap_or_default()
         })
     }
 
@@ -359,6 +481,7 @@ impl TileRenderer for AstroTile {
             BindableAction::new("toggle_degrees", "Toggle Degrees", true),
             BindableAction::new("toggle_moon", "Toggle Moon", true),
             BindableAction::new("refresh", "Refresh Positions", false),
+            BindableAction::new("reload_wheel", "Reload Wheel Definition", false),
         ]
     }
 
@@ -375,12 +498,31 @@ impl TileRenderer for AstroTile {
             "refresh" => {
                 self.refresh_ephemeris();
                 self.last_update = std::time::Instant::now();
+                self.assemble_wheel();
+                true
+            }
+            "reload_wheel" => {
+                let Some(watcher) = self.wheel_watcher.as_mut() else {
+                    return false;
+                };
+                watcher.invalidate();
+                watcher.check();
+                self.assemble_wheel();
                 true
             }
             _ => false,
         }
     }
 
+    fn get_error(&self) -> Option<TileError> {
+        let error = self.wheel_watcher.as_ref()?.error()?;
+        Some(TileError::new("Invalid wheel definition").with_details(&error.to_string()))
+    }
+
+    fn retry(&mut self) -> bool {
+        self.execute_action("reload_wheel")
+    }
+
     fn get_display_text(&self) -> Option<String> {
         Some(format!(
             "Sun: {:.1}° {} | Moon: {:.1}° {}",
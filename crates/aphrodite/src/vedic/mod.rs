@@ -1,5 +1,6 @@
 pub mod dashas;
 pub mod nakshatra;
+pub mod timeline;
 pub mod types;
 pub mod vargas;
 pub mod yogas;
@@ -9,6 +10,7 @@ pub use dashas::{
     compute_yogini_dasha, DashaLevel, DashaPeriod, VimshottariResponse,
 };
 pub use nakshatra::{annotate_layer_nakshatras, NakshatraPlacement};
+pub use timeline::{DashaChange, DashaSystem, DashaTimeline};
 pub use types::{NakshatraLayer, VedicLayerData, VedicPayload};
 pub use vargas::{build_varga_layers, VargaLayer, VargaPlanetPosition};
 pub use yogas::{identify_yogas, Yoga};
@@ -0,0 +1,269 @@
+//! Dasha timelines: the periods running at a given moment and the period
+//! changes between two moments.
+
+use crate::ephemeris::types::{LayerPositions, PlanetPosition};
+use crate::vedic::dashas::{
+    compute_vimshottari_dasha, compute_yogini_dasha, DashaLevel, DashaPeriod,
+};
+use chrono::{DateTime, Utc};
+use magnolia_core::Signal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DashaSystem {
+    #[default]
+    Vimshottari,
+    Yogini,
+}
+
+impl DashaSystem {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DashaSystem::Vimshottari => "vimshottari",
+            DashaSystem::Yogini => "yogini",
+        }
+    }
+
+    pub fn compute(
+        &self,
+        birth: DateTime<Utc>,
+        positions: &LayerPositions,
+        depth: DashaLevel,
+    ) -> Result<Vec<DashaPeriod>, String> {
+        match self {
+            DashaSystem::Vimshottari => compute_vimshottari_dasha(birth, positions, depth),
+            DashaSystem::Yogini => compute_yogini_dasha(birth, positions, depth),
+        }
+    }
+}
+
+/// A period beginning: a new mahadasha, or a new antardasha (bhukti)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DashaChange {
+    pub system: DashaSystem,
+    pub level: DashaLevel,
+    pub planet: String,
+    /// Ruling planet of the enclosing mahadasha, for bhuktis
+    pub parent: Option<String>,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl DashaChange {
+    /// `dasha_change` / `bhukti_change` `[system, planet, start, end, parent]`
+    /// (or `dasha_upcoming` / `bhukti_upcoming` ahead of time)
+    pub fn intent(&self, upcoming: bool) -> Signal {
+        let kind = match self.level {
+            DashaLevel::Mahadasha => "dasha",
+            DashaLevel::Antardasha => "bhukti",
+            DashaLevel::Pratyantardasha => "pratyantar",
+        };
+        let suffix = if upcoming { "upcoming" } else { "change" };
+        Signal::Intent {
+            action: format!("{}_{}", kind, suffix),
+            parameters: vec![
+                self.system.label().to_string(),
+                self.planet.clone(),
+                self.start.to_rfc3339(),
+                self.end.to_rfc3339(),
+                self.parent.clone().unwrap_or_default(),
+            ],
+        }
+    }
+}
+
+/// Mahadashas and their antardashas from birth.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DashaTimeline {
+    pub system: DashaSystem,
+    pub birth: DateTime<Utc>,
+    pub periods: Vec<DashaPeriod>,
+}
+
+impl DashaTimeline {
+    pub fn compute(
+        system: DashaSystem,
+        birth: DateTime<Utc>,
+        positions: &LayerPositions,
+    ) -> Result<Self, String> {
+        Ok(Self {
+            system,
+            birth,
+            periods: system.compute(birth, positions, DashaLevel::Antardasha)?,
+        })
+    }
+
+    /// Timeline from the natal Moon's sidereal longitude alone
+    pub fn from_moon(
+        system: DashaSystem,
+        birth: DateTime<Utc>,
+        sidereal_moon_lon: f64,
+    ) -> Result<Self, String> {
+        let planets = HashMap::from([(
+            "moon".to_string(),
+            PlanetPosition {
+                lon: sidereal_moon_lon,
+                lat: 0.0,
+                speed_lon: 0.0,
+                retrograde: false,
+            },
+        )]);
+        Self::compute(
+            system,
+            birth,
+            &LayerPositions {
+                planets,
+                houses: None,
+            },
+        )
+    }
+
+    pub fn start(&self) -> DateTime<Utc> {
+        self.periods.first().map_or(self.birth, |p| p.start)
+    }
+
+    pub fn end(&self) -> DateTime<Utc> {
+        self.periods.last().map_or(self.birth, |p| p.end)
+    }
+
+    /// Running periods, mahadasha first
+    pub fn active(&self, at: DateTime<Utc>) -> Vec<&DashaPeriod> {
+        let mut path = Vec::new();
+        let mut level = &self.periods;
+        while let Some(period) = level.iter().find(|p| p.start <= at && at < p.end) {
+            path.push(period);
+            level = &period.children;
+        }
+        path
+    }
+
+    /// Periods starting in `(from, to]`, in time order with a mahadasha
+    /// ahead of its first bhukti.
+    pub fn changes_between(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Vec<DashaChange> {
+        let mut changes = Vec::new();
+        for period in &self.periods {
+            if period.end <= from || period.start > to {
+                continue;
+            }
+            self.collect_changes(period, None, from, to, &mut changes);
+        }
+        changes
+    }
+
+    fn collect_changes(
+        &self,
+        period: &DashaPeriod,
+        parent: Option<&str>,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        changes: &mut Vec<DashaChange>,
+    ) {
+        if from < period.start && period.start <= to {
+            changes.push(DashaChange {
+                system: self.system,
+                level: period.level,
+                planet: period.planet.clone(),
+                parent: parent.map(str::to_string),
+                start: period.start,
+                end: period.end,
+            });
+        }
+        for child in &period.children {
+            if child.end > from && child.start <= to {
+                self.collect_changes(child, Some(&period.planet), from, to, changes);
+            }
+        }
+    }
+
+    /// The next period to begin after `at`, at the finest level
+    pub fn next_change(&self, at: DateTime<Utc>) -> Option<DashaChange> {
+        let next = self
+            .active(at)
+            .last()
+            .map_or_else(|| self.start(), |period| period.end);
+        if next <= at {
+            return None;
+        }
+        self.changes_between(at, next).pop()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn timeline() -> DashaTimeline {
+        // 6.665° sidereal: halfway through Ashwini, so half of Ketu's 7 years remain
+        let birth = Utc.with_ymd_and_hms(1990, 1, 1, 0, 0, 0).unwrap();
+        DashaTimeline::from_moon(DashaSystem::Vimshottari, birth, 6.665).unwrap()
+    }
+
+    #[test]
+    fn active_periods_follow_the_sequence() {
+        let timeline = timeline();
+        assert_eq!(timeline.periods.len(), 9);
+        assert_eq!(timeline.periods[0].children.len(), 9);
+
+        let at_birth = timeline.active(timeline.birth);
+        let planets: Vec<&str> = at_birth.iter().map(|p| p.planet.as_str()).collect();
+        assert_eq!(planets, ["ketu", "ketu"]);
+
+        // Venus mahadasha starts ~3.5 years after birth, with its own bhukti
+        let venus = &timeline.periods[1];
+        assert_eq!(venus.planet, "venus");
+        assert!((venus.start - timeline.birth).num_days() > 365 * 3);
+        let active = timeline.active(venus.start + Duration::days(1));
+        assert_eq!(active[0].planet, "venus");
+        assert_eq!(active[1].planet, "venus");
+
+        assert!(timeline.active(timeline.end()).is_empty());
+    }
+
+    #[test]
+    fn changes_report_mahadasha_before_its_first_bhukti() {
+        let timeline = timeline();
+        let venus_start = timeline.periods[1].start;
+        let changes = timeline.changes_between(venus_start - Duration::hours(1), venus_start);
+        let summary: Vec<(DashaLevel, &str)> = changes
+            .iter()
+            .map(|c| (c.level, c.planet.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (DashaLevel::Mahadasha, "venus"),
+                (DashaLevel::Antardasha, "venus")
+            ]
+        );
+        assert_eq!(changes[1].parent.as_deref(), Some("venus"));
+
+        // An interval ending just before the start reports nothing
+        assert!(timeline
+            .changes_between(
+                venus_start - Duration::hours(1),
+                venus_start - Duration::seconds(1)
+            )
+            .is_empty());
+
+        let next = timeline
+            .next_change(venus_start - Duration::days(1))
+            .unwrap();
+        assert_eq!(
+            (next.level, next.start),
+            (DashaLevel::Antardasha, venus_start)
+        );
+
+        let Signal::Intent { action, parameters } = changes[0].intent(false) else {
+            panic!("expected an intent");
+        };
+        assert_eq!(action, "dasha_change");
+        assert_eq!(parameters[..2], ["vimshottari", "venus"]);
+        assert!(matches!(
+            changes[1].intent(true),
+            Signal::Intent { action, .. } if action == "bhukti_upcoming"
+        ));
+    }
+}
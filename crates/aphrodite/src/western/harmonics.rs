@@ -0,0 +1,84 @@
+//! Harmonic charts.
+//!
+//! The Nth harmonic multiplies every longitude by N (mod 360), so points
+//! 360/N apart in the radix become conjunct.
+
+use crate::ephemeris::types::{HousePositions, LayerPositions, PlanetPosition};
+
+/// Longitude in the `harmonic`th harmonic chart
+pub fn harmonic_longitude(longitude: f64, harmonic: u32) -> f64 {
+    (longitude * harmonic as f64).rem_euclid(360.0)
+}
+
+/// Harmonic chart of a layer.
+///
+/// Planets and angles are projected; house cusps are dropped since they have
+/// no harmonic meaning. Speeds scale with the harmonic.
+pub fn harmonic_positions(positions: &LayerPositions, harmonic: u32) -> LayerPositions {
+    let planets = positions
+        .planets
+        .iter()
+        .map(|(id, pos)| {
+            (
+                id.clone(),
+                PlanetPosition {
+                    lon: harmonic_longitude(pos.lon, harmonic),
+                    lat: pos.lat,
+                    speed_lon: pos.speed_lon * harmonic as f64,
+                    retrograde: pos.retrograde,
+                },
+            )
+        })
+        .collect();
+    let houses = positions.houses.as_ref().map(|houses| HousePositions {
+        system: houses.system.clone(),
+        cusps: Default::default(),
+        angles: houses
+            .angles
+            .iter()
+            .map(|(id, lon)| (id.clone(), harmonic_longitude(*lon, harmonic)))
+            .collect(),
+    });
+    LayerPositions { planets, houses }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_harmonic_longitude() {
+        assert!((harmonic_longitude(100.0, 1) - 100.0).abs() < 1e-9);
+        assert!((harmonic_longitude(100.0, 4) - 40.0).abs() < 1e-9);
+        // Squares become conjunctions in the 4th harmonic
+        assert!((harmonic_longitude(10.0, 4) - harmonic_longitude(100.0, 4)).abs() < 1e-9);
+        assert!((harmonic_longitude(350.0, 5) - 310.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_harmonic_positions_drop_cusps() {
+        let positions = LayerPositions {
+            planets: HashMap::from([(
+                "sun".to_string(),
+                PlanetPosition {
+                    lon: 200.0,
+                    lat: 0.0,
+                    speed_lon: 1.0,
+                    retrograde: false,
+                },
+            )]),
+            houses: Some(HousePositions {
+                system: "placidus".to_string(),
+                cusps: HashMap::from([("1".to_string(), 15.0)]),
+                angles: HashMap::from([("asc".to_string(), 15.0)]),
+            }),
+        };
+        let h7 = harmonic_positions(&positions, 7);
+        assert!((h7.planets["sun"].lon - 320.0).abs() < 1e-9);
+        assert!((h7.planets["sun"].speed_lon - 7.0).abs() < 1e-9);
+        let houses = h7.houses.unwrap();
+        assert!(houses.cusps.is_empty());
+        assert!((houses.angles["asc"] - 105.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,183 @@
+//! Midpoints and midpoint trees on a modulus dial.
+//!
+//! A midpoint tree lists, for every focus point, the pairs whose midpoint
+//! falls on it within orb on the dial (90° for Uranian work, so midpoints
+//! and focus in hard aspect count as contacts).
+
+use crate::ephemeris::types::LayerPositions;
+use serde::{Deserialize, Serialize};
+
+/// The Uranian 90° dial
+pub const URANIAN_DIAL: f64 = 90.0;
+
+/// Angles included as midpoint factors when the layer has houses
+const ANGLES: &[&str] = &["asc", "mc"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Midpoint {
+    pub a: String,
+    pub b: String,
+    /// Near midpoint (on the shorter arc), 0-360
+    pub lon: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MidpointBranch {
+    pub a: String,
+    pub b: String,
+    pub lon: f64,
+    /// Distance from the focus on the dial
+    pub orb: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MidpointTree {
+    pub focus: String,
+    pub lon: f64,
+    #[serde(rename = "dialLon")]
+    pub dial_lon: f64,
+    pub branches: Vec<MidpointBranch>,
+}
+
+/// Midpoint of `a` and `b` on the shorter arc
+pub fn midpoint_longitude(a: f64, b: f64) -> f64 {
+    let diff = (b - a).rem_euclid(360.0);
+    if diff <= 180.0 {
+        (a + diff / 2.0).rem_euclid(360.0)
+    } else {
+        (b + (360.0 - diff) / 2.0).rem_euclid(360.0)
+    }
+}
+
+/// Position on a dial of `dial` degrees
+pub fn dial_longitude(longitude: f64, dial: f64) -> f64 {
+    longitude.rem_euclid(dial)
+}
+
+/// Shortest distance between two longitudes on the dial
+pub fn dial_distance(a: f64, b: f64, dial: f64) -> f64 {
+    let diff = (a - b).rem_euclid(dial);
+    diff.min(dial - diff)
+}
+
+/// Planets and angles of a layer, sorted by id
+fn factors(positions: &LayerPositions) -> Vec<(String, f64)> {
+    let mut points: Vec<(String, f64)> = positions
+        .planets
+        .iter()
+        .map(|(id, pos)| (id.clone(), pos.lon))
+        .collect();
+    if let Some(houses) = &positions.houses {
+        for angle in ANGLES {
+            if let Some(lon) = houses.angles.get(*angle) {
+                points.push((angle.to_string(), *lon));
+            }
+        }
+    }
+    points.sort_by(|a, b| a.0.cmp(&b.0));
+    points
+}
+
+/// All pairwise midpoints of a layer's planets and angles
+pub fn midpoints(positions: &LayerPositions) -> Vec<Midpoint> {
+    let points = factors(positions);
+    let mut result = Vec::new();
+    for (i, (a, a_lon)) in points.iter().enumerate() {
+        for (b, b_lon) in &points[i + 1..] {
+            result.push(Midpoint {
+                a: a.clone(),
+                b: b.clone(),
+                lon: midpoint_longitude(*a_lon, *b_lon),
+            });
+        }
+    }
+    result
+}
+
+/// Midpoint trees for every factor, ordered around the dial.
+///
+/// Branches are the midpoints within `orb` of the focus on the dial,
+/// closest first; pairs containing the focus itself are skipped.
+pub fn midpoint_trees(positions: &LayerPositions, dial: f64, orb: f64) -> Vec<MidpointTree> {
+    let all = midpoints(positions);
+    let mut trees: Vec<MidpointTree> = factors(positions)
+        .into_iter()
+        .map(|(focus, lon)| {
+            let mut branches: Vec<MidpointBranch> = all
+                .iter()
+                .filter(|m| m.a != focus && m.b != focus)
+                .filter_map(|m| {
+                    let distance = dial_distance(m.lon, lon, dial);
+                    (distance <= orb).then(|| MidpointBranch {
+                        a: m.a.clone(),
+                        b: m.b.clone(),
+                        lon: m.lon,
+                        orb: distance,
+                    })
+                })
+                .collect();
+            branches.sort_by(|a, b| a.orb.total_cmp(&b.orb));
+            MidpointTree {
+                dial_lon: dial_longitude(lon, dial),
+                focus,
+                lon,
+                branches,
+            }
+        })
+        .collect();
+    trees.sort_by(|a, b| a.dial_lon.total_cmp(&b.dial_lon));
+    trees
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ephemeris::types::PlanetPosition;
+    use std::collections::HashMap;
+
+    fn layer(points: &[(&str, f64)]) -> LayerPositions {
+        LayerPositions {
+            planets: points
+                .iter()
+                .map(|(id, lon)| {
+                    (
+                        id.to_string(),
+                        PlanetPosition {
+                            lon: *lon,
+                            lat: 0.0,
+                            speed_lon: 0.0,
+                            retrograde: false,
+                        },
+                    )
+                })
+                .collect::<HashMap<_, _>>(),
+            houses: None,
+        }
+    }
+
+    #[test]
+    fn test_midpoint_longitude_takes_shorter_arc() {
+        assert!((midpoint_longitude(10.0, 50.0) - 30.0).abs() < 1e-9);
+        assert!((midpoint_longitude(350.0, 30.0) - 10.0).abs() < 1e-9);
+        assert!((midpoint_longitude(30.0, 350.0) - 10.0).abs() < 1e-9);
+        assert!((dial_distance(1.0, 89.0, URANIAN_DIAL) - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_midpoint_tree_uses_dial_contacts() {
+        // sun/moon = 30°, mars at 120° squares it: a contact on the 90° dial
+        let positions = layer(&[("sun", 10.0), ("moon", 50.0), ("mars", 120.5)]);
+        let trees = midpoint_trees(&positions, URANIAN_DIAL, 1.0);
+        let mars = trees.iter().find(|t| t.focus == "mars").unwrap();
+        assert_eq!(mars.branches.len(), 1);
+        assert_eq!(
+            (mars.branches[0].a.as_str(), mars.branches[0].b.as_str()),
+            ("moon", "sun")
+        );
+        assert!((mars.branches[0].orb - 0.5).abs() < 1e-9);
+
+        // The full circle has no contact within orb
+        let wide = midpoint_trees(&positions, 360.0, 1.0);
+        assert!(wide.iter().all(|t| t.branches.is_empty()));
+    }
+}
@@ -1,5 +1,7 @@
 pub mod decans;
 pub mod dignities;
+pub mod harmonics;
+pub mod midpoints;
 pub mod rulers;
 pub mod types;
 
@@ -8,5 +10,10 @@ pub use decans::{
     Element,
 };
 pub use dignities::{DignitiesService, DignityResult, DignityType, ExactExaltation};
+pub use harmonics::{harmonic_longitude, harmonic_positions};
+pub use midpoints::{
+    dial_longitude, midpoint_longitude, midpoint_trees, midpoints, Midpoint, MidpointBranch,
+    MidpointTree, URANIAN_DIAL,
+};
 pub use rulers::{get_sign_index, get_sign_ruler, get_sign_ruler_from_longitude};
 pub use types::WesternLayerData;
@@ -1,5 +1,6 @@
 use aphrodite::ephemeris::{LayerPositions, PlanetPosition};
-use aphrodite::layout::{load_wheel_definition_from_json, WheelAssembler};
+use aphrodite::layout::rings::RingItem;
+use aphrodite::layout::{load_wheel_definition_from_json, WheelAssembler, WheelDefinitionWatcher};
 use std::collections::HashMap;
 
 #[test]
@@ -214,3 +215,197 @@ fn test_wheel_assembler_build_with_planets() {
     // Should have 2 planet items
     assert_eq!(wheel.rings[0].items.len(), 2);
 }
+
+#[test]
+fn test_wheel_assembler_build_harmonic_and_midpoint_rings() {
+    let json = r#"
+    {
+      "name": "Uranian Wheel",
+      "rings": [
+        {
+          "slug": "ring_h4",
+          "type": "planets",
+          "label": "4th Harmonic",
+          "orderIndex": 0,
+          "radiusInner": 0.85,
+          "radiusOuter": 1.0,
+          "dataSource": { "kind": "layer_harmonic_planets", "layerId": "natal", "harmonic": 4 }
+        },
+        {
+          "slug": "ring_midpoints",
+          "type": "planets",
+          "label": "Midpoints",
+          "orderIndex": 1,
+          "radiusInner": 0.6,
+          "radiusOuter": 0.85,
+          "dataSource": { "kind": "layer_midpoints", "layerId": "natal" }
+        }
+      ]
+    }
+    "#;
+
+    let wheel_def = load_wheel_definition_from_json(json).unwrap();
+
+    let planets = [("sun", 100.0), ("moon", 200.0), ("mars", 10.0)]
+        .into_iter()
+        .map(|(id, lon)| {
+            (
+                id.to_string(),
+                PlanetPosition {
+                    lon,
+                    lat: 0.0,
+                    speed_lon: 0.0,
+                    retrograde: false,
+                },
+            )
+        })
+        .collect();
+    let positions_by_layer = HashMap::from([(
+        "natal".to_string(),
+        LayerPositions {
+            planets,
+            houses: None,
+        },
+    )]);
+    let aspect_sets: HashMap<String, aphrodite::aspects::types::AspectSet> = HashMap::new();
+
+    let wheel =
+        WheelAssembler::build_wheel(&wheel_def.wheel, &positions_by_layer, &aspect_sets, None);
+
+    let lon_of = |ring: usize, id: &str| {
+        wheel.rings[ring]
+            .items
+            .iter()
+            .find_map(|item| match item {
+                RingItem::Planet(p) if p.planet_id == id => Some(p.lon),
+                _ => None,
+            })
+            .unwrap()
+    };
+    // Sun (100°) and Mars (10°) are square: conjunct at 40° in the 4th harmonic
+    assert!((lon_of(0, "sun") - 40.0).abs() < 1e-9);
+    assert!((lon_of(0, "mars") - 40.0).abs() < 1e-9);
+
+    // Three factors give three midpoints; mars/sun = 55°, 55° on the 90° dial
+    assert_eq!(wheel.rings[1].items.len(), 3);
+    assert!((lon_of(1, "mars/sun") - 220.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_load_wheel_definition_rejects_bad_harmonic() {
+    let json = r#"
+    {
+      "name": "Harmonic Wheel",
+      "rings": [
+        {
+          "slug": "ring_h0",
+          "type": "planets",
+          "label": "Harmonic",
+          "orderIndex": 0,
+          "radiusInner": 0.7,
+          "radiusOuter": 0.85,
+          "dataSource": { "kind": "layer_harmonic_planets", "layerId": "natal", "harmonic": 0 }
+        }
+      ]
+    }
+    "#;
+    let error = load_wheel_definition_from_json(json)
+        .unwrap_err()
+        .to_string();
+    assert_eq!(
+        error,
+        "Invalid field value: rings[0].dataSource.harmonic must be a positive integer"
+    );
+}
+
+fn two_ring_wheel(planets_outer: f32, planets_slug: &str) -> String {
+    format!(
+        r#"
+    {{
+      "name": "Edited Wheel",
+      "rings": [
+        {{
+          "slug": "ring_signs",
+          "type": "signs",
+          "label": "Zodiac Signs",
+          "orderIndex": 0,
+          "radiusInner": 0.85,
+          "radiusOuter": 1.0,
+          "dataSource": {{ "kind": "static_zodiac" }}
+        }},
+        {{
+          "slug": "{}",
+          "type": "planets",
+          "label": "Planets",
+          "orderIndex": 1,
+          "radiusInner": 0.7,
+          "radiusOuter": {},
+          "dataSource": {{ "kind": "layer_planets", "layerId": "natal" }}
+        }}
+      ]
+    }}
+    "#,
+        planets_slug, planets_outer
+    )
+}
+
+#[test]
+fn test_load_wheel_definition_reports_ring_layout_errors() {
+    assert!(load_wheel_definition_from_json(&two_ring_wheel(0.85, "ring_planets")).is_ok());
+
+    let overlap = load_wheel_definition_from_json(&two_ring_wheel(0.9, "ring_planets"))
+        .unwrap_err()
+        .to_string();
+    assert_eq!(
+        overlap,
+        "Invalid field value: rings[1] (0.7..0.9) overlaps rings[0] (0.85..1)"
+    );
+
+    let duplicate = load_wheel_definition_from_json(&two_ring_wheel(0.85, "ring_signs"))
+        .unwrap_err()
+        .to_string();
+    assert_eq!(
+        duplicate,
+        "Invalid field value: rings[1].slug \"ring_signs\" duplicates rings[0].slug"
+    );
+}
+
+#[test]
+fn test_wheel_definition_watcher_keeps_last_valid_definition() {
+    let path = std::env::temp_dir().join(format!("aphrodite_wheel_{}.json", std::process::id()));
+    std::fs::write(&path, two_ring_wheel(0.85, "ring_planets")).unwrap();
+
+    let mut watcher = WheelDefinitionWatcher::new(&path);
+    assert!(watcher.check());
+    assert_eq!(watcher.definition().unwrap().wheel.rings.len(), 2);
+    assert!(!watcher.check(), "unchanged file should not reload");
+
+    // A broken edit reports the error but keeps the wheel
+    std::fs::write(&path, "{ \"name\": \"Edited Wheel\", \"rings\": [").unwrap();
+    watcher.invalidate();
+    assert!(watcher.check());
+    let error = watcher.error().unwrap().to_string();
+    assert!(
+        error.starts_with("Invalid JSON:") && error.contains("line 1"),
+        "{}",
+        error
+    );
+    assert!(watcher.definition().is_some());
+
+    std::fs::write(&path, two_ring_wheel(0.8, "ring_planets")).unwrap();
+    watcher.invalidate();
+    assert!(watcher.check());
+    assert!(watcher.error().is_none());
+    assert_eq!(
+        watcher.definition().unwrap().wheel.rings[1].radius_outer,
+        0.8
+    );
+
+    std::fs::remove_file(&path).unwrap();
+    watcher.invalidate();
+    assert!(watcher.check());
+    assert!(matches!(
+        watcher.error(),
+        Some(aphrodite::layout::WheelDefinitionError::Io(_))
+    ));
+}
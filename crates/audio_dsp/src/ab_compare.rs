@@ -0,0 +1,490 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU32, AtomicU8, Ordering};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use tokio::sync::Notify;
+
+use magnolia_core::{
+    ControlSignal, DataType, ModuleSchema, Port, PortDirection, Processor, Signal, Sink,
+};
+
+use crate::{load_f32, store_f32};
+
+/// Buffers held per side before the oldest are dropped.
+const QUEUE: usize = 32;
+
+/// One of the two chains being compared.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AbSide {
+    A,
+    B,
+}
+
+impl AbSide {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AbSide::A => "a",
+            AbSide::B => "b",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "a" => Some(AbSide::A),
+            "b" => Some(AbSide::B),
+            _ => None,
+        }
+    }
+
+    pub fn other(self) -> Self {
+        match self {
+            AbSide::A => AbSide::B,
+            AbSide::B => AbSide::A,
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            AbSide::A => 0,
+            AbSide::B => 1,
+        }
+    }
+}
+
+struct AudioBuffer {
+    sample_rate: u32,
+    channels: u16,
+    timestamp_us: u64,
+    data: Vec<f32>,
+}
+
+#[derive(Default)]
+struct SideQueue {
+    audio: VecDeque<AudioBuffer>,
+    /// Non-audio signals; only kept while the side is active.
+    other: VecDeque<Signal>,
+}
+
+/// Shared state between the A/B processor and its two inputs.
+///
+/// The runtime delivers signals to a module without the destination port, so
+/// each chain's tail patches into its own sink module (`<id>_a`, `<id>_b`).
+pub struct AbCompareState {
+    active: AtomicU8,
+    crossfade_ms: AtomicU32,
+    sides: Mutex<[SideQueue; 2]>,
+    notify: Notify,
+}
+
+impl AbCompareState {
+    pub fn new() -> Arc<Self> {
+        let state = Arc::new(Self {
+            active: AtomicU8::new(0),
+            crossfade_ms: AtomicU32::new(0),
+            sides: Mutex::new(Default::default()),
+            notify: Notify::new(),
+        });
+        store_f32(&state.crossfade_ms, 50.0);
+        state
+    }
+
+    /// The chain feeding the output (or being faded to).
+    pub fn active(&self) -> AbSide {
+        match self.active.load(Ordering::Relaxed) {
+            1 => AbSide::B,
+            _ => AbSide::A,
+        }
+    }
+
+    pub fn set_active(&self, side: AbSide) {
+        self.active.store(side.index() as u8, Ordering::Relaxed);
+        if let Ok(mut sides) = self.sides.lock() {
+            sides[side.other().index()].other.clear();
+        }
+        self.notify.notify_one();
+    }
+
+    pub fn toggle(&self) -> AbSide {
+        let side = self.active().other();
+        self.set_active(side);
+        side
+    }
+
+    /// Length of the audio crossfade on a switch.
+    pub fn crossfade_ms(&self) -> f32 {
+        load_f32(&self.crossfade_ms)
+    }
+
+    pub fn set_crossfade_ms(&self, ms: f32) {
+        store_f32(&self.crossfade_ms, ms.clamp(0.0, 2000.0));
+    }
+
+    pub fn apply_settings(&self, settings: &serde_json::Value) {
+        if let Some(ms) = settings.get("crossfade_ms").and_then(|v| v.as_f64()) {
+            self.set_crossfade_ms(ms as f32);
+        }
+        if let Some(side) = settings
+            .get("active")
+            .and_then(|v| v.as_str())
+            .and_then(AbSide::parse)
+        {
+            self.set_active(side);
+        }
+    }
+
+    fn push(&self, side: AbSide, signal: Signal) {
+        let Ok(mut sides) = self.sides.lock() else {
+            return;
+        };
+        let queue = &mut sides[side.index()];
+        match signal.into_owned_audio() {
+            Signal::Audio {
+                sample_rate,
+                channels,
+                timestamp_us,
+                data,
+            } => {
+                if queue.audio.len() >= QUEUE {
+                    queue.audio.pop_front();
+                }
+                queue.audio.push_back(AudioBuffer {
+                    sample_rate,
+                    channels,
+                    timestamp_us,
+                    data,
+                });
+            }
+            // A stream can't be interleaved with the other side's buffers
+            Signal::AudioStream { .. } => return,
+            signal if side == self.active() => {
+                if queue.other.len() >= QUEUE {
+                    queue.other.pop_front();
+                }
+                queue.other.push_back(signal);
+            }
+            _ => return,
+        }
+        drop(sides);
+        self.notify.notify_one();
+    }
+
+    fn has_output(&self) -> bool {
+        let active = self.active().index();
+        self.sides
+            .lock()
+            .map(|sides| !sides[active].audio.is_empty() || !sides[active].other.is_empty())
+            .unwrap_or(false)
+    }
+}
+
+/// A/B comparison: passes one of two alternative chains (`<id>_a`, `<id>_b`)
+/// and switches between them with a crossfade, by the `ab` intent, a pulse,
+/// settings or the tile's keybind.
+///
+/// Output is paced by the active chain; the other is consumed in lockstep so
+/// it's current at the next switch. Both are expected to be fed from the same
+/// source with the same buffer size.
+pub struct AbCompareProcessor {
+    id: String,
+    enabled: bool,
+    state: Arc<AbCompareState>,
+    /// Mix position, 0 all A to 1 all B
+    position: f32,
+    last_port: &'static str,
+}
+
+impl AbCompareProcessor {
+    pub fn new(id: &str, state: Arc<AbCompareState>) -> Self {
+        let position = match state.active() {
+            AbSide::A => 0.0,
+            AbSide::B => 1.0,
+        };
+        Self {
+            id: id.to_string(),
+            enabled: true,
+            state,
+            position,
+            last_port: "audio_out",
+        }
+    }
+
+    fn command(&self, signal: &Signal) -> Option<AbSide> {
+        match signal {
+            Signal::Pulse => Some(self.state.active().other()),
+            Signal::Intent { action, parameters } if action.trim().eq_ignore_ascii_case("ab") => {
+                match parameters.first() {
+                    Some(arg) => AbSide::parse(arg).or_else(|| {
+                        arg.trim()
+                            .eq_ignore_ascii_case("toggle")
+                            .then(|| self.state.active().other())
+                    }),
+                    None => Some(self.state.active().other()),
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Mixes `active` with the other side's buffer, moving the crossfade
+    /// position towards the active side frame by frame.
+    fn mix(&mut self, mut active: AudioBuffer, other: Option<AudioBuffer>) -> AudioBuffer {
+        let target = match self.state.active() {
+            AbSide::A => 0.0,
+            AbSide::B => 1.0,
+        };
+        if self.position == target {
+            return active;
+        }
+        let channels = usize::from(active.channels.max(1));
+        let fade_frames = self.state.crossfade_ms() / 1000.0 * active.sample_rate.max(1) as f32;
+        let step = if fade_frames < 1.0 {
+            1.0
+        } else {
+            1.0 / fade_frames
+        };
+        let other = other.map(|buffer| buffer.data).unwrap_or_default();
+        for (index, frame) in active.data.chunks_exact_mut(channels).enumerate() {
+            self.position = if self.position < target {
+                (self.position + step).min(target)
+            } else {
+                (self.position - step).max(target)
+            };
+            // Both chains carry the same source, so a linear fade keeps the level
+            let active_gain = 1.0 - (self.position - target).abs();
+            for (channel, sample) in frame.iter_mut().enumerate() {
+                let other_sample = other.get(index * channels + channel).copied();
+                *sample = *sample * active_gain + other_sample.unwrap_or(0.0) * (1.0 - active_gain);
+            }
+        }
+        active
+    }
+}
+
+#[async_trait]
+impl Processor for AbCompareProcessor {
+    fn name(&self) -> &str {
+        "A/B Compare"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "A/B Compare".to_string(),
+            description: format!("Switches between {0}_a and {0}_b with a crossfade", self.id),
+            ports: vec![
+                Port {
+                    id: "control_in".to_string(),
+                    label: "Switch".to_string(),
+                    data_type: DataType::Control,
+                    direction: PortDirection::Input,
+                },
+                Port {
+                    id: "audio_out".to_string(),
+                    label: "Audio Out".to_string(),
+                    data_type: DataType::Audio,
+                    direction: PortDirection::Output,
+                },
+                Port {
+                    id: "signal_out".to_string(),
+                    label: "Other Out".to_string(),
+                    data_type: DataType::Any,
+                    direction: PortDirection::Output,
+                },
+            ],
+            settings_schema: Some(serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "active": { "type": "string", "enum": ["a", "b"], "default": "a" },
+                    "crossfade_ms": { "type": "number", "default": 50.0, "minimum": 0.0, "maximum": 2000.0 }
+                }
+            })),
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    fn output_port(&self, _signal: &Signal) -> Option<&str> {
+        Some(self.last_port)
+    }
+
+    async fn process(&mut self, signal: Signal) -> anyhow::Result<Option<Signal>> {
+        if let Signal::Control(ControlSignal::Settings(settings)) = &signal {
+            self.state.apply_settings(settings);
+        } else if let Some(side) = self.command(&signal) {
+            self.state.set_active(side);
+        }
+        Ok(None)
+    }
+
+    async fn next_wake(&self) {
+        loop {
+            // Registered before the check, so a push in between isn't missed
+            let notified = self.state.notify.notified();
+            if self.state.has_output() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    async fn wake(&mut self) -> anyhow::Result<Option<Signal>> {
+        let active = self.state.active();
+        let (buffer, other) = {
+            let Ok(mut sides) = self.state.sides.lock() else {
+                return Ok(None);
+            };
+            if let Some(signal) = sides[active.index()].other.pop_front() {
+                self.last_port = "signal_out";
+                return Ok(Some(signal));
+            }
+            let Some(buffer) = sides[active.index()].audio.pop_front() else {
+                return Ok(None);
+            };
+            (buffer, sides[active.other().index()].audio.pop_front())
+        };
+        let buffer = self.mix(buffer, other);
+        self.last_port = "audio_out";
+        Ok(Some(Signal::Audio {
+            sample_rate: buffer.sample_rate,
+            channels: buffer.channels,
+            timestamp_us: buffer.timestamp_us,
+            data: buffer.data,
+        }))
+    }
+}
+
+/// One side of [`AbCompareProcessor`]; patch the tail of a chain into it.
+pub struct AbCompareInputSink {
+    id: String,
+    side: AbSide,
+    enabled: bool,
+    state: Arc<AbCompareState>,
+}
+
+impl AbCompareInputSink {
+    pub fn new(compare_id: &str, side: AbSide, state: Arc<AbCompareState>) -> Self {
+        Self {
+            id: format!("{compare_id}_{}", side.as_str()),
+            side,
+            enabled: true,
+            state,
+        }
+    }
+}
+
+#[async_trait]
+impl Sink for AbCompareInputSink {
+    fn name(&self) -> &str {
+        &self.id
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: format!("A/B Input {}", self.side.as_str().to_uppercase()),
+            description: "Feeds one side of the paired A/B compare".to_string(),
+            ports: vec![Port {
+                id: "signal_in".to_string(),
+                label: "In".to_string(),
+                data_type: DataType::Any,
+                direction: PortDirection::Input,
+            }],
+            settings_schema: None,
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    async fn consume(&self, signal: Signal) -> anyhow::Result<Option<Signal>> {
+        self.state.push(self.side, signal);
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn audio(value: f32, frames: usize) -> Signal {
+        Signal::Audio {
+            sample_rate: 1_000,
+            channels: 1,
+            timestamp_us: 0,
+            data: vec![value; frames],
+        }
+    }
+
+    async fn next_audio(compare: &mut AbCompareProcessor) -> Vec<f32> {
+        compare.next_wake().await;
+        match compare.wake().await.unwrap() {
+            Some(Signal::Audio { data, .. }) => data,
+            other => panic!("expected audio, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn passes_the_active_side_and_crossfades_on_switch() {
+        let state = AbCompareState::new();
+        state.set_crossfade_ms(10.0);
+        let mut compare = AbCompareProcessor::new("ab", state.clone());
+        let a = AbCompareInputSink::new("ab", AbSide::A, state.clone());
+        let b = AbCompareInputSink::new("ab", AbSide::B, state.clone());
+        assert_eq!(b.schema().id, "ab_b");
+
+        a.consume(audio(1.0, 20)).await.unwrap();
+        b.consume(audio(-1.0, 20)).await.unwrap();
+        assert_eq!(next_audio(&mut compare).await, vec![1.0; 20]);
+
+        compare
+            .process(Signal::Intent {
+                action: "ab".to_string(),
+                parameters: vec!["b".to_string()],
+            })
+            .await
+            .unwrap();
+        assert_eq!(state.active(), AbSide::B);
+        a.consume(audio(1.0, 20)).await.unwrap();
+        b.consume(audio(-1.0, 20)).await.unwrap();
+        let faded = next_audio(&mut compare).await;
+        // Ten frames from A to B, then all B
+        assert!((faded[4] - 0.0).abs() < 1e-5);
+        assert!(faded.windows(2).all(|w| w[1] <= w[0]));
+        assert_eq!(faded[10..], [-1.0; 10]);
+
+        compare.process(Signal::Pulse).await.unwrap();
+        assert_eq!(state.active(), AbSide::A);
+    }
+
+    #[tokio::test]
+    async fn only_the_active_side_passes_other_signals() {
+        let state = AbCompareState::new();
+        let mut compare = AbCompareProcessor::new("ab", state.clone());
+        let a = AbCompareInputSink::new("ab", AbSide::A, state.clone());
+        let b = AbCompareInputSink::new("ab", AbSide::B, state.clone());
+
+        b.consume(Signal::Text("from b".to_string())).await.unwrap();
+        a.consume(Signal::Text("from a".to_string())).await.unwrap();
+        compare.next_wake().await;
+        match compare.wake().await.unwrap() {
+            Some(Signal::Text(text)) => assert_eq!(text, "from a"),
+            other => panic!("unexpected {other:?}"),
+        }
+        assert_eq!(compare.output_port(&Signal::Pulse), Some("signal_out"));
+        assert!(compare.wake().await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,399 @@
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+use async_trait::async_trait;
+
+use magnolia_core::{
+    AudioView, ControlSignal, DataType, ModuleSchema, Port, PortDirection, Processor, Signal, Sink,
+};
+
+use crate::{load_f32, store_f32};
+
+fn db_to_gain(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+fn gain_to_db(gain: f32) -> f32 {
+    20.0 * gain.max(1e-9).log10()
+}
+
+/// Shared state between the ducker's main processor and its sidechain sink.
+///
+/// The runtime delivers signals to a module without the destination port, so the
+/// sidechain input is a separate module (`<id>_sidechain`) feeding this state.
+pub struct DuckerState {
+    threshold_db: AtomicU32,
+    depth_db: AtomicU32,
+    attack_ms: AtomicU32,
+    release_ms: AtomicU32,
+    hold_ms: AtomicU32,
+    sidechain_db: AtomicU32,
+    gain_reduction_db: AtomicU32,
+    /// Micros since `epoch` of the last sidechain buffer; 0 means never.
+    sidechain_seen_us: AtomicU64,
+    epoch: Instant,
+}
+
+impl Default for DuckerState {
+    fn default() -> Self {
+        Self {
+            threshold_db: AtomicU32::new(0),
+            depth_db: AtomicU32::new(0),
+            attack_ms: AtomicU32::new(0),
+            release_ms: AtomicU32::new(0),
+            hold_ms: AtomicU32::new(0),
+            sidechain_db: AtomicU32::new(0),
+            gain_reduction_db: AtomicU32::new(0),
+            sidechain_seen_us: AtomicU64::new(0),
+            epoch: Instant::now(),
+        }
+    }
+}
+
+impl DuckerState {
+    pub fn new() -> Arc<Self> {
+        let state = Arc::new(Self::default());
+        store_f32(&state.threshold_db, -40.0);
+        store_f32(&state.depth_db, -18.0);
+        store_f32(&state.attack_ms, 20.0);
+        store_f32(&state.release_ms, 400.0);
+        store_f32(&state.hold_ms, 250.0);
+        store_f32(&state.sidechain_db, -120.0);
+        store_f32(&state.gain_reduction_db, 0.0);
+        state
+    }
+
+    /// Sidechain level (dBFS RMS) above which the main input is ducked.
+    pub fn threshold_db(&self) -> f32 {
+        load_f32(&self.threshold_db)
+    }
+
+    pub fn set_threshold_db(&self, db: f32) {
+        store_f32(&self.threshold_db, db.clamp(-96.0, 0.0));
+    }
+
+    /// Attenuation applied to the main input while the sidechain is active.
+    pub fn depth_db(&self) -> f32 {
+        load_f32(&self.depth_db)
+    }
+
+    pub fn set_depth_db(&self, db: f32) {
+        store_f32(&self.depth_db, db.clamp(-96.0, 0.0));
+    }
+
+    pub fn attack_ms(&self) -> f32 {
+        load_f32(&self.attack_ms)
+    }
+
+    pub fn set_attack_ms(&self, ms: f32) {
+        store_f32(&self.attack_ms, ms.clamp(0.0, 5000.0));
+    }
+
+    pub fn release_ms(&self) -> f32 {
+        load_f32(&self.release_ms)
+    }
+
+    pub fn set_release_ms(&self, ms: f32) {
+        store_f32(&self.release_ms, ms.clamp(0.0, 10000.0));
+    }
+
+    /// How long a sidechain reading stays valid once the sidechain stops sending audio.
+    pub fn hold_ms(&self) -> f32 {
+        load_f32(&self.hold_ms)
+    }
+
+    pub fn set_hold_ms(&self, ms: f32) {
+        store_f32(&self.hold_ms, ms.clamp(0.0, 10000.0));
+    }
+
+    /// Last measured sidechain level in dBFS.
+    pub fn sidechain_db(&self) -> f32 {
+        load_f32(&self.sidechain_db)
+    }
+
+    /// Current attenuation of the main input in dB (0 = no ducking).
+    pub fn gain_reduction_db(&self) -> f32 {
+        load_f32(&self.gain_reduction_db)
+    }
+
+    fn now_us(&self) -> u64 {
+        (self.epoch.elapsed().as_micros() as u64).max(1)
+    }
+
+    pub fn record_sidechain_level(&self, level_db: f32) {
+        store_f32(&self.sidechain_db, level_db);
+        self.sidechain_seen_us
+            .store(self.now_us(), Ordering::Relaxed);
+    }
+
+    /// Whether the sidechain is currently above threshold and not stale.
+    pub fn sidechain_active(&self) -> bool {
+        let seen = self.sidechain_seen_us.load(Ordering::Relaxed);
+        if seen == 0 {
+            return false;
+        }
+        let age_ms = self.now_us().saturating_sub(seen) as f32 / 1000.0;
+        age_ms <= self.hold_ms() && self.sidechain_db() >= self.threshold_db()
+    }
+
+    pub fn apply_settings(&self, settings: &serde_json::Value) {
+        if let Some(db) = settings.get("threshold_db").and_then(|v| v.as_f64()) {
+            self.set_threshold_db(db as f32);
+        }
+        if let Some(db) = settings.get("depth_db").and_then(|v| v.as_f64()) {
+            self.set_depth_db(db as f32);
+        }
+        if let Some(ms) = settings.get("attack_ms").and_then(|v| v.as_f64()) {
+            self.set_attack_ms(ms as f32);
+        }
+        if let Some(ms) = settings.get("release_ms").and_then(|v| v.as_f64()) {
+            self.set_release_ms(ms as f32);
+        }
+        if let Some(ms) = settings.get("hold_ms").and_then(|v| v.as_f64()) {
+            self.set_hold_ms(ms as f32);
+        }
+    }
+}
+
+/// Ducking processor: attenuates the main input while the sidechain is active.
+pub struct DuckerProcessor {
+    id: String,
+    enabled: bool,
+    state: Arc<DuckerState>,
+    gain: f32,
+}
+
+impl DuckerProcessor {
+    pub fn new(id: &str, state: Arc<DuckerState>) -> Self {
+        Self {
+            id: id.to_string(),
+            enabled: true,
+            state,
+            gain: 1.0,
+        }
+    }
+
+    fn process_frames(&mut self, sample_rate: u32, channels: usize, data: &mut [f32]) {
+        let target = if self.state.sidechain_active() {
+            db_to_gain(self.state.depth_db())
+        } else {
+            1.0
+        };
+        let coefficient = |ms: f32| {
+            let samples = ms / 1000.0 * sample_rate.max(1) as f32;
+            if samples < 1.0 {
+                1.0
+            } else {
+                1.0 - (-1.0 / samples).exp()
+            }
+        };
+        // Moving down in gain is the attack; recovering towards unity is the release.
+        let smoothing = if target < self.gain {
+            coefficient(self.state.attack_ms())
+        } else {
+            coefficient(self.state.release_ms())
+        };
+
+        for frame in data.chunks_exact_mut(channels) {
+            self.gain += (target - self.gain) * smoothing;
+            for sample in frame.iter_mut() {
+                *sample *= self.gain;
+            }
+        }
+        store_f32(&self.state.gain_reduction_db, gain_to_db(self.gain));
+    }
+}
+
+#[async_trait]
+impl Processor for DuckerProcessor {
+    fn name(&self) -> &str {
+        "Ducker"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "Ducker".to_string(),
+            description: format!(
+                "Attenuates the main input while {}_sidechain is above threshold",
+                self.id
+            ),
+            ports: vec![
+                Port {
+                    id: "audio_in".to_string(),
+                    label: "Main In".to_string(),
+                    data_type: DataType::Audio,
+                    direction: PortDirection::Input,
+                },
+                Port {
+                    id: "audio_out".to_string(),
+                    label: "Audio Out".to_string(),
+                    data_type: DataType::Audio,
+                    direction: PortDirection::Output,
+                },
+            ],
+            settings_schema: Some(serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "threshold_db": { "type": "number", "default": -40.0, "minimum": -96.0, "maximum": 0.0 },
+                    "depth_db": { "type": "number", "default": -18.0, "minimum": -96.0, "maximum": 0.0 },
+                    "attack_ms": { "type": "number", "default": 20.0, "minimum": 0.0, "maximum": 5000.0 },
+                    "release_ms": { "type": "number", "default": 400.0, "minimum": 0.0, "maximum": 10000.0 },
+                    "hold_ms": { "type": "number", "default": 250.0, "minimum": 0.0, "maximum": 10000.0 }
+                }
+            })),
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    async fn process(&mut self, signal: Signal) -> anyhow::Result<Option<Signal>> {
+        match signal.into_owned_audio() {
+            Signal::Audio {
+                sample_rate,
+                channels,
+                timestamp_us,
+                mut data,
+            } => {
+                if channels > 0 {
+                    self.process_frames(sample_rate, channels as usize, &mut data);
+                }
+                Ok(Some(Signal::Audio {
+                    sample_rate,
+                    channels,
+                    timestamp_us,
+                    data,
+                }))
+            }
+            Signal::Control(ControlSignal::Settings(settings)) => {
+                self.state.apply_settings(&settings);
+                Ok(None)
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Sidechain input for [`DuckerProcessor`]; measures level into the shared state.
+pub struct DuckerSidechainSink {
+    id: String,
+    enabled: bool,
+    state: Arc<DuckerState>,
+}
+
+impl DuckerSidechainSink {
+    pub fn new(ducker_id: &str, state: Arc<DuckerState>) -> Self {
+        Self {
+            id: format!("{ducker_id}_sidechain"),
+            enabled: true,
+            state,
+        }
+    }
+}
+
+#[async_trait]
+impl Sink for DuckerSidechainSink {
+    fn name(&self) -> &str {
+        "Ducker Sidechain"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "Ducker Sidechain".to_string(),
+            description: "Sidechain level detector that drives the paired ducker".to_string(),
+            ports: vec![Port {
+                id: "sidechain_in".to_string(),
+                label: "Sidechain In".to_string(),
+                data_type: DataType::Audio,
+                direction: PortDirection::Input,
+            }],
+            settings_schema: None,
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    async fn consume(&self, signal: Signal) -> anyhow::Result<Option<Signal>> {
+        if let Some(AudioView { data, .. }) = signal.audio() {
+            if !data.is_empty() {
+                let rms = (data.iter().map(|sample| sample * sample).sum::<f32>()
+                    / data.len() as f32)
+                    .sqrt();
+                self.state.record_sidechain_level(gain_to_db(rms));
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn audio(value: f32, frames: usize) -> Signal {
+        Signal::Audio {
+            sample_rate: 1_000,
+            channels: 1,
+            timestamp_us: 0,
+            data: vec![value; frames],
+        }
+    }
+
+    async fn last_sample(processor: &mut DuckerProcessor, signal: Signal) -> f32 {
+        match processor.process(signal).await.unwrap() {
+            Some(Signal::Audio { data, .. }) => *data.last().unwrap(),
+            other => panic!("expected audio, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn main_passes_through_without_sidechain() {
+        let state = DuckerState::new();
+        let mut ducker = DuckerProcessor::new("ducker", state.clone());
+
+        let out = last_sample(&mut ducker, audio(0.5, 100)).await;
+        assert!((out - 0.5).abs() < 1e-6);
+        assert!(!state.sidechain_active());
+    }
+
+    #[tokio::test]
+    async fn active_sidechain_ducks_main_by_depth() {
+        let state = DuckerState::new();
+        state.set_attack_ms(0.0);
+        state.set_depth_db(-20.0);
+        let mut ducker = DuckerProcessor::new("ducker", state.clone());
+        let sidechain = DuckerSidechainSink::new("ducker", state.clone());
+
+        sidechain.consume(audio(0.5, 64)).await.unwrap();
+        assert!(state.sidechain_active());
+
+        let out = last_sample(&mut ducker, audio(1.0, 10)).await;
+        assert!((out - 0.1).abs() < 1e-4);
+        assert!((state.gain_reduction_db() + 20.0).abs() < 1e-2);
+    }
+
+    #[tokio::test]
+    async fn quiet_sidechain_stays_below_threshold() {
+        let state = DuckerState::new();
+        let sidechain = DuckerSidechainSink::new("ducker", state.clone());
+
+        sidechain.consume(audio(0.001, 64)).await.unwrap();
+        assert!(!state.sidechain_active());
+    }
+}
@@ -3,11 +3,22 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 
-use magnolia_core::{DataType, ModuleSchema, Port, PortDirection, Processor, Signal};
-
+use magnolia_core::{
+    BlockContext, DataType, ModuleSchema, Port, PortDirection, Processor, RtAudioNode, Signal,
+};
+
+pub mod ab_compare;
+pub mod ducker;
+pub mod silence;
+pub mod stereo_tools;
 #[cfg(feature = "tile-rendering")]
 pub mod tile;
 
+pub use ab_compare::{AbCompareInputSink, AbCompareProcessor, AbCompareState, AbSide};
+pub use ducker::{DuckerProcessor, DuckerSidechainSink, DuckerState};
+pub use silence::SilenceProcessor;
+pub use stereo_tools::{StereoMode, StereoToolsProcessor, StereoToolsState};
+
 fn load_f32(atom: &AtomicU32) -> f32 {
     f32::from_bits(atom.load(Ordering::Relaxed))
 }
@@ -76,90 +87,32 @@ impl AudioDspState {
     }
 }
 
-/// Simple DSP processor that applies gain and optional lowpass.
-pub struct AudioDspProcessor {
-    id: String,
-    enabled: bool,
+/// Gain, automatic gain and lowpass over interleaved frames, shared by the
+/// processor and its node in compiled audio graphs
+struct DspFilter {
     state: Arc<AudioDspState>,
     last_samples: Vec<f32>,
     agc_gain: f32,
 }
 
-impl AudioDspProcessor {
-    pub fn new(id: &str, state: Arc<AudioDspState>) -> Self {
+impl DspFilter {
+    fn new(state: Arc<AudioDspState>) -> Self {
         Self {
-            id: id.to_string(),
-            enabled: true,
             state,
             last_samples: Vec::new(),
             agc_gain: 1.0,
         }
     }
-}
-
-#[async_trait]
-impl Processor for AudioDspProcessor {
-    fn name(&self) -> &str {
-        "Audio DSP"
-    }
-
-    fn schema(&self) -> ModuleSchema {
-        ModuleSchema {
-            id: self.id.clone(),
-            name: "Audio DSP".to_string(),
-            description: "Applies gain and lowpass to audio buffers".to_string(),
-            ports: vec![
-                Port {
-                    id: "audio_in".to_string(),
-                    label: "Audio In".to_string(),
-                    data_type: DataType::Audio,
-                    direction: PortDirection::Input,
-                },
-                Port {
-                    id: "audio_out".to_string(),
-                    label: "Audio Out".to_string(),
-                    data_type: DataType::Audio,
-                    direction: PortDirection::Output,
-                },
-            ],
-            settings_schema: None,
-        }
-    }
-
-    fn is_enabled(&self) -> bool {
-        self.enabled
-    }
-
-    fn set_enabled(&mut self, enabled: bool) {
-        self.enabled = enabled;
-    }
-
-    async fn process(&mut self, signal: Signal) -> anyhow::Result<Option<Signal>> {
-        let Signal::Audio {
-            sample_rate,
-            channels,
-            timestamp_us,
-            mut data,
-        } = signal
-        else {
-            return Ok(None);
-        };
 
+    fn apply(&mut self, sample_rate: u32, channels: u16, data: &mut [f32]) {
         let gain = self.state.gain();
         let agc_enabled = self.state.agc_enabled();
         let lowpass_enabled = self.state.lowpass_enabled();
         let lowpass_hz = self.state.lowpass_hz().max(10.0);
 
         if self.state.is_muted() {
-            for sample in data.iter_mut() {
-                *sample = 0.0;
-            }
-            return Ok(Some(Signal::Audio {
-                sample_rate,
-                channels,
-                timestamp_us,
-                data,
-            }));
+            data.fill(0.0);
+            return;
         }
 
         if self.last_samples.len() != channels as usize {
@@ -210,7 +163,85 @@ impl Processor for AudioDspProcessor {
                 *sample = x.clamp(-1.0, 1.0);
             }
         }
+    }
+}
+
+/// Simple DSP processor that applies gain and optional lowpass.
+pub struct AudioDspProcessor {
+    id: String,
+    enabled: bool,
+    filter: DspFilter,
+}
+
+impl AudioDspProcessor {
+    pub fn new(id: &str, state: Arc<AudioDspState>) -> Self {
+        Self {
+            id: id.to_string(),
+            enabled: true,
+            filter: DspFilter::new(state),
+        }
+    }
+}
+
+/// [`AudioDspProcessor`] in a compiled audio graph
+struct AudioDspNode(DspFilter);
+
+impl RtAudioNode for AudioDspNode {
+    fn process_block(&mut self, context: &BlockContext, input: &[f32], output: &mut [f32]) {
+        output.copy_from_slice(input);
+        self.0.apply(context.sample_rate, context.channels, output);
+    }
+}
+
+#[async_trait]
+impl Processor for AudioDspProcessor {
+    fn name(&self) -> &str {
+        "Audio DSP"
+    }
 
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "Audio DSP".to_string(),
+            description: "Applies gain and lowpass to audio buffers".to_string(),
+            ports: vec![
+                Port {
+                    id: "audio_in".to_string(),
+                    label: "Audio In".to_string(),
+                    data_type: DataType::Audio,
+                    direction: PortDirection::Input,
+                },
+                Port {
+                    id: "audio_out".to_string(),
+                    label: "Audio Out".to_string(),
+                    data_type: DataType::Audio,
+                    direction: PortDirection::Output,
+                },
+            ],
+            settings_schema: None,
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    async fn process(&mut self, signal: Signal) -> anyhow::Result<Option<Signal>> {
+        let Signal::Audio {
+            sample_rate,
+            channels,
+            timestamp_us,
+            mut data,
+        } = signal.into_owned_audio()
+        else {
+            return Ok(None);
+        };
+        self.filter.apply(sample_rate, channels, &mut data);
         Ok(Some(Signal::Audio {
             sample_rate,
             channels,
@@ -218,11 +249,17 @@ impl Processor for AudioDspProcessor {
             data,
         }))
     }
+
+    fn rt_audio_node(&mut self) -> Option<Box<dyn RtAudioNode>> {
+        Some(Box::new(AudioDspNode(DspFilter::new(
+            self.filter.state.clone(),
+        ))))
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::AudioDspState;
+    use super::*;
 
     #[test]
     fn automatic_gain_control_is_enabled_by_default_and_toggleable() {
@@ -232,4 +269,38 @@ mod tests {
         state.set_agc_enabled(false);
         assert!(!state.agc_enabled());
     }
+
+    #[tokio::test]
+    async fn compiled_node_matches_the_processor() {
+        let state = AudioDspState::new();
+        state.set_gain(0.5);
+        state.set_lowpass_enabled(true);
+        let mut processor = AudioDspProcessor::new("dsp", state.clone());
+        let mut node = processor.rt_audio_node().unwrap();
+        let input = (0..64)
+            .map(|i| (i as f32 * 0.3).sin() * 0.4)
+            .collect::<Vec<_>>();
+
+        let Some(Signal::Audio { data, .. }) = processor
+            .process(Signal::Audio {
+                sample_rate: 48_000,
+                channels: 2,
+                timestamp_us: 0,
+                data: input.clone(),
+            })
+            .await
+            .unwrap()
+        else {
+            panic!("expected audio");
+        };
+        let mut output = vec![0.0; input.len()];
+        let context = BlockContext {
+            sample_rate: 48_000,
+            channels: 2,
+            frames: 32,
+            timestamp_us: 0,
+        };
+        node.process_block(&context, &input, &mut output);
+        assert_eq!(output, data);
+    }
 }
@@ -0,0 +1,144 @@
+use async_trait::async_trait;
+
+use magnolia_core::{
+    AudioView, ControlSignal, DataType, ModuleSchema, Port, PortDirection, Processor, Signal,
+    SilenceConfig, SilenceDetector,
+};
+
+/// Emits `silence_start` / `silence_end` intents as its audio input goes
+/// quiet and comes back, e.g. to pause transcription between phrases.
+pub struct SilenceProcessor {
+    id: String,
+    enabled: bool,
+    detector: SilenceDetector,
+}
+
+impl SilenceProcessor {
+    pub fn new(id: &str, config: SilenceConfig) -> Self {
+        Self {
+            id: id.to_string(),
+            enabled: true,
+            detector: SilenceDetector::new(config),
+        }
+    }
+}
+
+#[async_trait]
+impl Processor for SilenceProcessor {
+    fn name(&self) -> &str {
+        "Silence Detector"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        let defaults = SilenceConfig::default();
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "Silence Detector".to_string(),
+            description: "Emits silence_start / silence_end intents when the input goes quiet"
+                .to_string(),
+            ports: vec![
+                Port {
+                    id: "audio_in".to_string(),
+                    label: "Audio In".to_string(),
+                    data_type: DataType::Audio,
+                    direction: PortDirection::Input,
+                },
+                Port {
+                    id: "intent_out".to_string(),
+                    label: "Silence".to_string(),
+                    data_type: DataType::Control,
+                    direction: PortDirection::Output,
+                },
+            ],
+            settings_schema: Some(serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "threshold_db": { "type": "number", "default": defaults.threshold_db, "minimum": -96.0, "maximum": 0.0 },
+                    "hold_ms": { "type": "number", "default": defaults.hold_ms, "minimum": 0.0, "maximum": 600000.0 }
+                }
+            })),
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.detector.reset();
+        }
+    }
+
+    async fn process(&mut self, signal: Signal) -> anyhow::Result<Option<Signal>> {
+        if let Signal::Control(ControlSignal::Settings(settings)) = &signal {
+            let mut config = self.detector.config();
+            config.apply_settings(settings);
+            self.detector.set_config(config);
+            return Ok(None);
+        }
+        let Some(AudioView {
+            sample_rate,
+            channels,
+            data,
+            ..
+        }) = signal.audio()
+        else {
+            return Ok(None);
+        };
+        Ok(self
+            .detector
+            .process(sample_rate, channels, data)
+            .map(|event| Signal::Intent {
+                action: event.action().to_string(),
+                parameters: Vec::new(),
+            }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn audio(value: f32) -> Signal {
+        Signal::Audio {
+            sample_rate: 1_000,
+            channels: 1,
+            timestamp_us: 0,
+            data: vec![value; 250],
+        }
+    }
+
+    async fn action(processor: &mut SilenceProcessor, signal: Signal) -> Option<String> {
+        match processor.process(signal).await.unwrap() {
+            Some(Signal::Intent { action, .. }) => Some(action),
+            None => None,
+            other => panic!("expected an intent, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn emits_intents_on_each_transition() {
+        let mut processor = SilenceProcessor::new("silence", SilenceConfig::default());
+        processor
+            .process(Signal::Control(ControlSignal::Settings(
+                serde_json::json!({ "hold_ms": 500 }),
+            )))
+            .await
+            .unwrap();
+
+        assert_eq!(action(&mut processor, audio(0.5)).await, None);
+        assert_eq!(action(&mut processor, audio(0.0)).await, None);
+        assert_eq!(
+            action(&mut processor, audio(0.0)).await.as_deref(),
+            Some("silence_start")
+        );
+        assert_eq!(action(&mut processor, audio(0.0)).await, None);
+        assert_eq!(
+            action(&mut processor, audio(0.5)).await.as_deref(),
+            Some("silence_end")
+        );
+    }
+}
@@ -0,0 +1,385 @@
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use magnolia_core::{
+    ControlSignal, DataType, ModuleSchema, Port, PortDirection, Processor, Signal,
+};
+
+use crate::{load_f32, store_f32};
+
+/// Channel layout transform applied before width and auto-pan.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StereoMode {
+    /// Input and output are left/right.
+    LeftRight,
+    /// Input is left/right, output is mid (ch 0) and side (ch 1).
+    EncodeMidSide,
+    /// Input is mid (ch 0) and side (ch 1), output is left/right.
+    DecodeMidSide,
+}
+
+impl StereoMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StereoMode::LeftRight => "left_right",
+            StereoMode::EncodeMidSide => "encode_ms",
+            StereoMode::DecodeMidSide => "decode_ms",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "left_right" => Some(StereoMode::LeftRight),
+            "encode_ms" => Some(StereoMode::EncodeMidSide),
+            "decode_ms" => Some(StereoMode::DecodeMidSide),
+            _ => None,
+        }
+    }
+
+    fn to_u32(self) -> u32 {
+        match self {
+            StereoMode::LeftRight => 0,
+            StereoMode::EncodeMidSide => 1,
+            StereoMode::DecodeMidSide => 2,
+        }
+    }
+
+    fn from_u32(value: u32) -> Self {
+        match value {
+            1 => StereoMode::EncodeMidSide,
+            2 => StereoMode::DecodeMidSide,
+            _ => StereoMode::LeftRight,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct StereoToolsState {
+    mode: AtomicU32,
+    width: AtomicU32,
+    autopan_enabled: AtomicBool,
+    autopan_bpm: AtomicU32,
+    autopan_beats: AtomicU32,
+    autopan_depth: AtomicU32,
+}
+
+impl StereoToolsState {
+    pub fn new() -> Arc<Self> {
+        let state = Arc::new(Self::default());
+        state.set_mode(StereoMode::LeftRight);
+        store_f32(&state.width, 1.0);
+        state.autopan_enabled.store(false, Ordering::Relaxed);
+        store_f32(&state.autopan_bpm, 120.0);
+        store_f32(&state.autopan_beats, 4.0);
+        store_f32(&state.autopan_depth, 1.0);
+        state
+    }
+
+    pub fn mode(&self) -> StereoMode {
+        StereoMode::from_u32(self.mode.load(Ordering::Relaxed))
+    }
+
+    pub fn set_mode(&self, mode: StereoMode) {
+        self.mode.store(mode.to_u32(), Ordering::Relaxed);
+    }
+
+    /// Stereo width: 0.0 collapses to mono, 1.0 is unchanged, up to 2.0 widens.
+    pub fn width(&self) -> f32 {
+        load_f32(&self.width)
+    }
+
+    pub fn set_width(&self, width: f32) {
+        store_f32(&self.width, width.clamp(0.0, 2.0));
+    }
+
+    pub fn autopan_enabled(&self) -> bool {
+        self.autopan_enabled.load(Ordering::Relaxed)
+    }
+
+    pub fn set_autopan_enabled(&self, enabled: bool) {
+        self.autopan_enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    pub fn autopan_bpm(&self) -> f32 {
+        load_f32(&self.autopan_bpm)
+    }
+
+    pub fn set_autopan_bpm(&self, bpm: f32) {
+        store_f32(&self.autopan_bpm, bpm.clamp(1.0, 999.0));
+    }
+
+    /// Length of one full left-right-left sweep, in beats.
+    pub fn autopan_beats(&self) -> f32 {
+        load_f32(&self.autopan_beats)
+    }
+
+    pub fn set_autopan_beats(&self, beats: f32) {
+        store_f32(&self.autopan_beats, beats.clamp(0.25, 64.0));
+    }
+
+    pub fn autopan_depth(&self) -> f32 {
+        load_f32(&self.autopan_depth)
+    }
+
+    pub fn set_autopan_depth(&self, depth: f32) {
+        store_f32(&self.autopan_depth, depth.clamp(0.0, 1.0));
+    }
+
+    pub fn apply_settings(&self, settings: &serde_json::Value) {
+        if let Some(mode) = settings
+            .get("mode")
+            .and_then(|v| v.as_str())
+            .and_then(StereoMode::parse)
+        {
+            self.set_mode(mode);
+        }
+        if let Some(width) = settings.get("width").and_then(|v| v.as_f64()) {
+            self.set_width(width as f32);
+        }
+        if let Some(enabled) = settings.get("autopan_enabled").and_then(|v| v.as_bool()) {
+            self.set_autopan_enabled(enabled);
+        }
+        if let Some(bpm) = settings.get("autopan_bpm").and_then(|v| v.as_f64()) {
+            self.set_autopan_bpm(bpm as f32);
+        }
+        if let Some(beats) = settings.get("autopan_beats").and_then(|v| v.as_f64()) {
+            self.set_autopan_beats(beats as f32);
+        }
+        if let Some(depth) = settings.get("autopan_depth").and_then(|v| v.as_f64()) {
+            self.set_autopan_depth(depth as f32);
+        }
+    }
+}
+
+/// Encode a left/right pair into mid/side.
+pub fn encode_mid_side(left: f32, right: f32) -> (f32, f32) {
+    ((left + right) * 0.5, (left - right) * 0.5)
+}
+
+/// Decode a mid/side pair back into left/right.
+pub fn decode_mid_side(mid: f32, side: f32) -> (f32, f32) {
+    (mid + side, mid - side)
+}
+
+/// Stereo processor: mid/side encode-decode, width, and tempo-synced auto-pan.
+///
+/// Only interleaved two-channel audio is transformed; other layouts pass through.
+pub struct StereoToolsProcessor {
+    id: String,
+    enabled: bool,
+    state: Arc<StereoToolsState>,
+    /// Auto-pan LFO phase in cycles, kept in `[0, 1)`.
+    pan_phase: f64,
+}
+
+impl StereoToolsProcessor {
+    pub fn new(id: &str, state: Arc<StereoToolsState>) -> Self {
+        Self {
+            id: id.to_string(),
+            enabled: true,
+            state,
+            pan_phase: 0.0,
+        }
+    }
+
+    fn process_frames(&mut self, sample_rate: u32, data: &mut [f32]) {
+        let mode = self.state.mode();
+        let width = self.state.width();
+        let autopan = self.state.autopan_enabled();
+        let depth = self.state.autopan_depth();
+        let cycle_secs = self.state.autopan_beats() as f64 * 60.0 / self.state.autopan_bpm() as f64;
+        let phase_step = 1.0 / (cycle_secs * sample_rate.max(1) as f64);
+
+        for frame in data.chunks_exact_mut(2) {
+            let (mid, mut side) = match mode {
+                StereoMode::DecodeMidSide => (frame[0], frame[1]),
+                _ => encode_mid_side(frame[0], frame[1]),
+            };
+            side *= width;
+
+            if mode == StereoMode::EncodeMidSide {
+                frame[0] = mid.clamp(-1.0, 1.0);
+                frame[1] = side.clamp(-1.0, 1.0);
+                continue;
+            }
+
+            let (mut left, mut right) = decode_mid_side(mid, side);
+            if autopan {
+                // Equal-power pan: position sweeps -depth..depth over one cycle.
+                let position = depth * (self.pan_phase * std::f64::consts::TAU).sin() as f32;
+                let angle = (position + 1.0) * std::f32::consts::FRAC_PI_4;
+                let (gain_r, gain_l) = angle.sin_cos();
+                let norm = std::f32::consts::SQRT_2;
+                left *= gain_l * norm;
+                right *= gain_r * norm;
+                self.pan_phase = (self.pan_phase + phase_step).fract();
+            }
+            frame[0] = left.clamp(-1.0, 1.0);
+            frame[1] = right.clamp(-1.0, 1.0);
+        }
+    }
+}
+
+#[async_trait]
+impl Processor for StereoToolsProcessor {
+    fn name(&self) -> &str {
+        "Stereo Tools"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "Stereo Tools".to_string(),
+            description: "Mid/side encode-decode, stereo width, and tempo-synced auto-pan"
+                .to_string(),
+            ports: vec![
+                Port {
+                    id: "audio_in".to_string(),
+                    label: "Audio In".to_string(),
+                    data_type: DataType::Audio,
+                    direction: PortDirection::Input,
+                },
+                Port {
+                    id: "audio_out".to_string(),
+                    label: "Audio Out".to_string(),
+                    data_type: DataType::Audio,
+                    direction: PortDirection::Output,
+                },
+            ],
+            settings_schema: Some(serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "mode": {
+                        "type": "string",
+                        "enum": ["left_right", "encode_ms", "decode_ms"],
+                        "default": "left_right"
+                    },
+                    "width": { "type": "number", "default": 1.0, "minimum": 0.0, "maximum": 2.0 },
+                    "autopan_enabled": { "type": "boolean", "default": false },
+                    "autopan_bpm": { "type": "number", "default": 120.0, "minimum": 1.0, "maximum": 999.0 },
+                    "autopan_beats": { "type": "number", "default": 4.0, "minimum": 0.25, "maximum": 64.0 },
+                    "autopan_depth": { "type": "number", "default": 1.0, "minimum": 0.0, "maximum": 1.0 }
+                }
+            })),
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    async fn process(&mut self, signal: Signal) -> anyhow::Result<Option<Signal>> {
+        match signal.into_owned_audio() {
+            Signal::Audio {
+                sample_rate,
+                channels,
+                timestamp_us,
+                mut data,
+            } => {
+                if channels == 2 {
+                    self.process_frames(sample_rate, &mut data);
+                }
+                Ok(Some(Signal::Audio {
+                    sample_rate,
+                    channels,
+                    timestamp_us,
+                    data,
+                }))
+            }
+            Signal::Control(ControlSignal::Settings(settings)) => {
+                self.state.apply_settings(&settings);
+                Ok(None)
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stereo(data: Vec<f32>) -> Signal {
+        Signal::Audio {
+            sample_rate: 48_000,
+            channels: 2,
+            timestamp_us: 0,
+            data,
+        }
+    }
+
+    async fn run(processor: &mut StereoToolsProcessor, data: Vec<f32>) -> Vec<f32> {
+        match processor.process(stereo(data)).await.unwrap() {
+            Some(Signal::Audio { data, .. }) => data,
+            other => panic!("expected audio, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn zero_width_collapses_to_mono() {
+        let state = StereoToolsState::new();
+        state.set_width(0.0);
+        let mut processor = StereoToolsProcessor::new("stereo", state);
+
+        let out = run(&mut processor, vec![0.8, 0.2]).await;
+        assert!((out[0] - 0.5).abs() < 1e-6);
+        assert!((out[1] - 0.5).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn mid_side_round_trips() {
+        let encode_state = StereoToolsState::new();
+        encode_state.set_mode(StereoMode::EncodeMidSide);
+        let mut encoder = StereoToolsProcessor::new("encode", encode_state);
+        let decode_state = StereoToolsState::new();
+        decode_state.set_mode(StereoMode::DecodeMidSide);
+        let mut decoder = StereoToolsProcessor::new("decode", decode_state);
+
+        let encoded = run(&mut encoder, vec![0.6, -0.2]).await;
+        assert!((encoded[0] - 0.2).abs() < 1e-6);
+        assert!((encoded[1] - 0.4).abs() < 1e-6);
+
+        let decoded = run(&mut decoder, encoded).await;
+        assert!((decoded[0] - 0.6).abs() < 1e-6);
+        assert!((decoded[1] + 0.2).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn shared_audio_is_processed_without_touching_other_holders() {
+        let state = StereoToolsState::new();
+        state.set_width(0.0);
+        let mut processor = StereoToolsProcessor::new("stereo", state);
+
+        let shared = stereo(vec![0.8, 0.2]).into_shared_audio();
+        let kept = shared.clone();
+        match processor.process(shared).await.unwrap() {
+            Some(Signal::Audio { data, .. }) => assert!((data[0] - 0.5).abs() < 1e-6),
+            other => panic!("expected audio, got {other:?}"),
+        }
+        assert_eq!(kept.audio().unwrap().data, &[0.8, 0.2]);
+    }
+
+    #[tokio::test]
+    async fn settings_control_updates_state() {
+        let state = StereoToolsState::new();
+        let mut processor = StereoToolsProcessor::new("stereo", state.clone());
+
+        let settings =
+            serde_json::json!({ "mode": "encode_ms", "width": 1.5, "autopan_enabled": true });
+        let out = processor
+            .process(Signal::Control(ControlSignal::Settings(settings)))
+            .await
+            .unwrap();
+        assert!(out.is_none());
+        assert_eq!(state.mode(), StereoMode::EncodeMidSide);
+        assert!((state.width() - 1.5).abs() < 1e-6);
+        assert!(state.autopan_enabled());
+    }
+}
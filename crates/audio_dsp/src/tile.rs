@@ -4,7 +4,7 @@ use magnolia_core::{BindableAction, RenderContext, TileRenderer};
 use magnolia_ui::{draw_text, FontId, TextAlignment};
 use nannou::prelude::*;
 
-use crate::AudioDspState;
+use crate::{AbCompareState, AbSide, AudioDspState};
 
 pub struct AudioDspTile {
     id: String,
@@ -309,3 +309,115 @@ impl TileRenderer for AudioDspTile {
         })
     }
 }
+
+/// Shows which chain of an A/B compare is live; `toggle` (bindable, or Space
+/// while selected) switches it.
+pub struct AbCompareTile {
+    id: String,
+    state: Arc<AbCompareState>,
+}
+
+impl AbCompareTile {
+    pub fn new(id: &str, state: Arc<AbCompareState>) -> Self {
+        Self {
+            id: id.to_string(),
+            state,
+        }
+    }
+}
+
+impl TileRenderer for AbCompareTile {
+    fn id(&self) -> &str {
+        &self.id
+    }
+    fn name(&self) -> &str {
+        "A/B Compare"
+    }
+    fn update(&mut self) {}
+
+    fn render_monitor(&self, draw: &Draw, rect: Rect, _ctx: &RenderContext) {
+        draw.rect()
+            .xy(rect.xy())
+            .wh(rect.wh())
+            .color(srgba(0.03, 0.03, 0.06, 0.95));
+
+        draw_text(
+            draw,
+            FontId::PlexSansBold,
+            "A/B COMPARE",
+            pt2(rect.x(), rect.top() - 18.0),
+            12.0,
+            srgba(0.6, 0.8, 0.9, 1.0),
+            TextAlignment::Center,
+        );
+
+        let active = self.state.active();
+        for (side, offset) in [(AbSide::A, -30.0), (AbSide::B, 30.0)] {
+            let color = if side == active {
+                srgba(0.0, 1.0, 0.6, 1.0)
+            } else {
+                srgba(0.3, 0.3, 0.35, 1.0)
+            };
+            draw_text(
+                draw,
+                FontId::PlexSansBold,
+                &side.as_str().to_uppercase(),
+                pt2(rect.x() + offset, rect.y()),
+                28.0,
+                color,
+                TextAlignment::Center,
+            );
+        }
+
+        draw_text(
+            draw,
+            FontId::PlexMonoRegular,
+            &format!("Crossfade: {:.0} ms", self.state.crossfade_ms()),
+            pt2(rect.x(), rect.bottom() + 16.0),
+            11.0,
+            srgba(0.5, 0.7, 0.9, 1.0),
+            TextAlignment::Center,
+        );
+    }
+
+    fn bindable_actions(&self) -> Vec<BindableAction> {
+        vec![BindableAction::new("toggle", "Switch A/B", true)]
+    }
+
+    fn execute_action(&mut self, action: &str) -> bool {
+        if action == "toggle" {
+            self.state.toggle();
+            return true;
+        }
+        false
+    }
+
+    fn handle_key(&mut self, key: Key, _ctrl: bool, _shift: bool) -> bool {
+        if key == Key::Space {
+            self.state.toggle();
+            return true;
+        }
+        false
+    }
+
+    fn settings_schema(&self) -> Option<serde_json::Value> {
+        Some(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "active": { "type": "string", "enum": ["a", "b"], "default": "a" },
+                "crossfade_ms": { "type": "number", "default": 50.0, "minimum": 0.0, "maximum": 2000.0 }
+            }
+        }))
+    }
+
+    fn apply_settings(&mut self, settings: &serde_json::Value) {
+        self.state.apply_settings(settings);
+    }
+
+    fn get_settings(&self) -> serde_json::Value {
+        serde_json::json!({
+            "active": self.state.active().as_str(),
+            "crossfade_ms": self.state.crossfade_ms(),
+        })
+    }
+}
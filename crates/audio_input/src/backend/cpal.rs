@@ -5,6 +5,7 @@ use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, Sample, SizedSample};
 use log::error;
 
 use magnolia_signals::ring_buffer::RingBufferSender;
@@ -24,6 +25,30 @@ fn now_micros() -> u64 {
         .as_micros() as u64
 }
 
+/// Capture in the device's own sample format, converting to f32 as it arrives
+fn build_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    tx: RingBufferSender<f32>,
+    capture_us: Arc<AtomicU64>,
+) -> Result<cpal::Stream, cpal::BuildStreamError>
+where
+    T: SizedSample,
+    f32: FromSample<T>,
+{
+    device.build_input_stream(
+        config,
+        move |data: &[T], _| {
+            capture_us.store(now_micros(), std::sync::atomic::Ordering::Relaxed);
+            for &sample in data {
+                let _ = tx.try_send(sample.to_sample::<f32>());
+            }
+        },
+        |err| error!("cpal input error: {}", err),
+        None,
+    )
+}
+
 pub struct CpalInputBackend;
 
 impl CpalInputBackend {
@@ -61,12 +86,12 @@ impl AudioInputBackend for CpalInputBackend {
 
         let resolved_device = if device_id == "Default" {
             host.default_input_device()
+                .ok_or_else(|| anyhow::anyhow!("No input device"))?
         } else {
-            host.input_devices().ok().and_then(|mut devices| {
-                devices.find(|d| d.name().ok().as_deref() == Some(device_id))
-            })
-        }
-        .ok_or_else(|| anyhow::anyhow!("No input device"))?;
+            host.input_devices()?
+                .find(|d| d.name().ok().as_deref() == Some(device_id))
+                .ok_or_else(|| anyhow::anyhow!("Input device '{}' not found", device_id))?
+        };
 
         let resolved_name = resolved_device
             .name()
@@ -75,21 +100,28 @@ impl AudioInputBackend for CpalInputBackend {
         let config = resolved_device.default_input_config()?;
         let sample_rate = config.sample_rate().0;
         let channels = config.channels();
-
-        let err_fn = |err| error!("cpal input error: {}", err);
-        let stream = match config.sample_format() {
-            cpal::SampleFormat::F32 => resolved_device.build_input_stream(
-                &config.into(),
-                move |data: &[f32], _| {
-                    capture_us.store(now_micros(), std::sync::atomic::Ordering::Relaxed);
-                    for &sample in data {
-                        let _ = tx.try_send(sample);
-                    }
-                },
-                err_fn,
-                None,
-            )?,
-            _ => return Err(anyhow::anyhow!("Only F32 supported for now")),
+        let sample_format = config.sample_format();
+        let config: cpal::StreamConfig = config.into();
+
+        let stream = match sample_format {
+            cpal::SampleFormat::F32 => {
+                build_stream::<f32>(&resolved_device, &config, tx, capture_us)?
+            }
+            cpal::SampleFormat::I16 => {
+                build_stream::<i16>(&resolved_device, &config, tx, capture_us)?
+            }
+            cpal::SampleFormat::U16 => {
+                build_stream::<u16>(&resolved_device, &config, tx, capture_us)?
+            }
+            cpal::SampleFormat::I32 => {
+                build_stream::<i32>(&resolved_device, &config, tx, capture_us)?
+            }
+            other => {
+                return Err(anyhow::anyhow!(
+                    "Unsupported input sample format {:?}",
+                    other
+                ))
+            }
         };
 
         stream.play()?;
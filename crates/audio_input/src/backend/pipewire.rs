@@ -43,7 +43,8 @@ impl Drop for PipeWireStreamHandle {
 #[derive(Default)]
 struct UserData {
     format: spa::param::audio::AudioInfoRaw,
-    fmt_tx: Option<mpsc::Sender<NegotiatedFormat>>,
+    /// Takes the negotiated format, or why the stream failed
+    fmt_tx: Option<mpsc::Sender<Result<NegotiatedFormat, String>>>,
 }
 
 /// Native PipeWire input backend (Linux).
@@ -162,29 +163,36 @@ impl AudioInputBackend for PipeWireInputBackend {
         };
 
         let (stop_tx, stop_rx) = mpsc::channel::<()>();
-        let (fmt_tx, fmt_rx) = mpsc::channel::<NegotiatedFormat>();
+        let (fmt_tx, fmt_rx) = mpsc::channel::<Result<NegotiatedFormat, String>>();
 
         let join = thread::spawn(move || {
+            // Failures are handed back to `start` as well as logged
+            let fail_tx = fmt_tx.clone();
+            let fail = |what: String| {
+                log::error!("PipeWire input: {what}");
+                let _ = fail_tx.send(Err(what));
+            };
+
             pw::init();
 
             let mainloop = match pw::main_loop::MainLoopRc::new(None) {
                 Ok(v) => v,
                 Err(e) => {
-                    log::error!("PipeWire input: failed to create mainloop: {e}");
+                    fail(format!("failed to create mainloop: {e}"));
                     return;
                 }
             };
             let context = match pw::context::ContextRc::new(&mainloop, None) {
                 Ok(v) => v,
                 Err(e) => {
-                    log::error!("PipeWire input: failed to create context: {e}");
+                    fail(format!("failed to create context: {e}"));
                     return;
                 }
             };
             let core = match context.connect_rc(None) {
                 Ok(v) => v,
                 Err(e) => {
-                    log::error!("PipeWire input: failed to connect: {e}");
+                    fail(format!("failed to connect: {e}"));
                     return;
                 }
             };
@@ -198,7 +206,7 @@ impl AudioInputBackend for PipeWireInputBackend {
             let stream = match pw::stream::StreamBox::new(&core, "magnolia-audio-input", props) {
                 Ok(v) => v,
                 Err(e) => {
-                    log::error!("PipeWire input: failed to create stream: {e}");
+                    fail(format!("failed to create stream: {e}"));
                     return;
                 }
             };
@@ -210,6 +218,13 @@ impl AudioInputBackend for PipeWireInputBackend {
 
             let _listener = stream
                 .add_local_listener_with_user_data(data)
+                .state_changed(|_, user_data, _old, new| {
+                    if let pw::stream::StreamState::Error(message) = new {
+                        if let Some(tx) = user_data.fmt_tx.take() {
+                            let _ = tx.send(Err(message));
+                        }
+                    }
+                })
                 .param_changed(|_, user_data, id, param| {
                     let Some(param) = param else {
                         return;
@@ -228,10 +243,10 @@ impl AudioInputBackend for PipeWireInputBackend {
 
                     if user_data.format.parse(param).is_ok() {
                         if let Some(tx) = user_data.fmt_tx.take() {
-                            let _ = tx.send(NegotiatedFormat {
+                            let _ = tx.send(Ok(NegotiatedFormat {
                                 sample_rate: user_data.format.rate(),
                                 channels: user_data.format.channels() as u16,
-                            });
+                            }));
                         }
                     }
                 })
@@ -285,7 +300,7 @@ impl AudioInputBackend for PipeWireInputBackend {
                     | pw::stream::StreamFlags::RT_PROCESS,
                 &mut params,
             ) {
-                log::error!("PipeWire input: stream.connect failed: {e}");
+                fail(format!("stream.connect failed: {e}"));
                 return;
             }
 
@@ -302,12 +317,19 @@ impl AudioInputBackend for PipeWireInputBackend {
             mainloop.run();
         });
 
-        let fmt = fmt_rx
-            .recv_timeout(Duration::from_secs(2))
-            .unwrap_or(NegotiatedFormat {
+        let fmt = match fmt_rx.recv_timeout(Duration::from_secs(2)) {
+            Ok(Ok(fmt)) => fmt,
+            Ok(Err(e)) => {
+                let _ = stop_tx.send(());
+                let _ = join.join();
+                anyhow::bail!("PipeWire input: {e}");
+            }
+            // Nothing negotiated yet; assume the usual format
+            Err(_) => NegotiatedFormat {
                 sample_rate: 48000,
                 channels: 2,
-            });
+            },
+        };
 
         let handle = PipeWireStreamHandle {
             stop_tx,
@@ -0,0 +1,264 @@
+//! Scrolling history for the audio visualizer.
+//!
+//! Samples are folded into fixed-size min/max columns so a long stretch of
+//! audio can be drawn as an envelope; spectrogram columns are pushed by the
+//! tile at a slower, sample-counted hop so both lanes share one time axis.
+
+use std::collections::VecDeque;
+
+/// Samples folded into one waveform column
+pub const WAVE_COLUMN_SAMPLES: usize = 256;
+/// Samples between spectrogram columns (a multiple of the waveform column)
+pub const SPECTRUM_COLUMN_SAMPLES: usize = 1024;
+/// Frequency rows per spectrogram column
+pub const SPECTROGRAM_BINS: usize = 64;
+/// Total audio kept, in samples (~43s at 48kHz)
+const HISTORY_SAMPLES: usize = 1 << 21;
+/// Floor of the spectrogram's dB scale; quieter bins draw black
+const SPECTROGRAM_FLOOR_DB: f32 = -70.0;
+/// Peaks below this count as silence (dropout markers)
+const SILENCE: f32 = 1e-4;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct WaveColumn {
+    pub min: f32,
+    pub max: f32,
+}
+
+impl WaveColumn {
+    fn merge(self, other: WaveColumn) -> WaveColumn {
+        WaveColumn {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+
+    pub fn is_silent(&self) -> bool {
+        self.min.abs().max(self.max.abs()) < SILENCE
+    }
+}
+
+#[derive(Debug)]
+pub struct AudioHistory {
+    wave: VecDeque<WaveColumn>,
+    spectrum: VecDeque<Vec<f32>>,
+    /// Column being filled and how many samples it holds
+    current: WaveColumn,
+    current_len: usize,
+    spectrogram: bool,
+    /// Samples seen since the last spectrogram column
+    spectrum_pending: usize,
+}
+
+impl Default for AudioHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AudioHistory {
+    pub const WAVE_CAPACITY: usize = HISTORY_SAMPLES / WAVE_COLUMN_SAMPLES;
+    pub const SPECTRUM_CAPACITY: usize = HISTORY_SAMPLES / SPECTRUM_COLUMN_SAMPLES;
+
+    pub fn new() -> Self {
+        Self {
+            wave: VecDeque::with_capacity(Self::WAVE_CAPACITY),
+            spectrum: VecDeque::new(),
+            current: WaveColumn::default(),
+            current_len: 0,
+            spectrogram: false,
+            spectrum_pending: 0,
+        }
+    }
+
+    pub fn push_samples(&mut self, samples: impl IntoIterator<Item = f32>) {
+        for s in samples {
+            let s = if s.is_finite() { s } else { 0.0 };
+            self.current = if self.current_len == 0 {
+                WaveColumn { min: s, max: s }
+            } else {
+                self.current.merge(WaveColumn { min: s, max: s })
+            };
+            self.current_len += 1;
+            if self.current_len == WAVE_COLUMN_SAMPLES {
+                if self.wave.len() == Self::WAVE_CAPACITY {
+                    self.wave.pop_front();
+                }
+                self.wave.push_back(self.current);
+                self.current_len = 0;
+            }
+            if self.spectrogram {
+                self.spectrum_pending += 1;
+            }
+        }
+    }
+
+    pub fn spectrogram_enabled(&self) -> bool {
+        self.spectrogram
+    }
+
+    /// Turning the spectrogram off drops its columns; it restarts empty
+    pub fn set_spectrogram(&mut self, enabled: bool) {
+        if enabled != self.spectrogram {
+            self.spectrogram = enabled;
+            self.spectrum.clear();
+            self.spectrum_pending = 0;
+        }
+    }
+
+    /// Spectrogram columns owed since the last call. The tile runs one FFT
+    /// per frame at most, so catch-up columns repeat the latest spectrum.
+    pub fn take_spectrum_due(&mut self) -> usize {
+        let due = self.spectrum_pending / SPECTRUM_COLUMN_SAMPLES;
+        self.spectrum_pending %= SPECTRUM_COLUMN_SAMPLES;
+        due.min(Self::SPECTRUM_CAPACITY)
+    }
+
+    /// Append `repeat` copies of the column built from FFT `magnitudes`
+    /// (the positive half of the spectrum)
+    pub fn push_spectrum(&mut self, magnitudes: &[f32], repeat: usize) {
+        if repeat == 0 || !self.spectrogram {
+            return;
+        }
+        let column = spectrogram_column(magnitudes);
+        for _ in 0..repeat {
+            if self.spectrum.len() == Self::SPECTRUM_CAPACITY {
+                self.spectrum.pop_front();
+            }
+            self.spectrum.push_back(column.clone());
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.wave.clear();
+        self.spectrum.clear();
+        self.current_len = 0;
+        self.spectrum_pending = 0;
+    }
+
+    /// Number of completed waveform columns
+    pub fn wave_len(&self) -> usize {
+        self.wave.len()
+    }
+
+    /// `count` waveform columns ending `offset` columns before the newest,
+    /// oldest first; shorter when the history doesn't reach that far back
+    pub fn wave_window(&self, count: usize, offset: usize) -> Vec<WaveColumn> {
+        let end = self.wave.len().saturating_sub(offset);
+        let start = end.saturating_sub(count);
+        self.wave.range(start..end).copied().collect()
+    }
+
+    /// Spectrogram columns covering the same span as
+    /// [`wave_window`](Self::wave_window) with the same arguments
+    pub fn spectrum_window(&self, count: usize, offset: usize) -> Vec<&[f32]> {
+        let per = SPECTRUM_COLUMN_SAMPLES / WAVE_COLUMN_SAMPLES;
+        let end = self.spectrum.len().saturating_sub(offset / per);
+        let start = end.saturating_sub(count.div_ceil(per));
+        self.spectrum.range(start..end).map(Vec::as_slice).collect()
+    }
+}
+
+/// Fold `columns` into at most `max` envelope columns
+pub fn decimate(columns: &[WaveColumn], max: usize) -> Vec<WaveColumn> {
+    if columns.len() <= max || max == 0 {
+        return columns.to_vec();
+    }
+    let step = columns.len().div_ceil(max);
+    columns
+        .chunks(step)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .copied()
+                .reduce(WaveColumn::merge)
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// FFT magnitudes to [`SPECTROGRAM_BINS`] rows of 0..1 intensity, low
+/// frequencies first. Rows follow the same quadratic frequency curve as the
+/// spectrum views; levels are dB relative to a full-scale sine.
+pub fn spectrogram_column(magnitudes: &[f32]) -> Vec<f32> {
+    let n = magnitudes.len();
+    if n == 0 {
+        return vec![0.0; SPECTROGRAM_BINS];
+    }
+    // A Hann-windowed full-scale sine peaks at a quarter of the FFT size,
+    // which is half the number of positive bins
+    let full_scale = (n as f32 / 2.0).max(1.0);
+    (0..SPECTROGRAM_BINS)
+        .map(|i| {
+            let f1 = (i as f32 / SPECTROGRAM_BINS as f32).powi(2);
+            let f2 = ((i + 1) as f32 / SPECTROGRAM_BINS as f32).powi(2);
+            let start = ((f1 * n as f32) as usize).min(n - 1);
+            let end = ((f2 * n as f32) as usize).clamp(start + 1, n);
+            let mag = magnitudes[start..end]
+                .iter()
+                .copied()
+                .fold(0.0_f32, f32::max);
+            let db = 20.0 * (mag / full_scale).max(1e-9).log10();
+            ((db - SPECTROGRAM_FLOOR_DB) / -SPECTROGRAM_FLOOR_DB).clamp(0.0, 1.0)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn folds_samples_into_columns_and_windows() {
+        let mut history = AudioHistory::new();
+        let mut samples = vec![0.0; WAVE_COLUMN_SAMPLES * 3 + 10];
+        samples[5] = 0.5;
+        samples[WAVE_COLUMN_SAMPLES + 7] = -0.25;
+        history.push_samples(samples);
+
+        // The trailing partial column isn't visible yet
+        assert_eq!(history.wave_len(), 3);
+        let all = history.wave_window(10, 0);
+        assert_eq!(all[0], WaveColumn { min: 0.0, max: 0.5 });
+        assert_eq!(
+            all[1],
+            WaveColumn {
+                min: -0.25,
+                max: 0.0
+            }
+        );
+        assert!(all[2].is_silent());
+
+        assert_eq!(history.wave_window(1, 1), vec![all[1]]);
+        assert_eq!(
+            decimate(&all, 2),
+            vec![
+                WaveColumn {
+                    min: -0.25,
+                    max: 0.5
+                },
+                all[2]
+            ]
+        );
+    }
+
+    #[test]
+    fn spectrogram_columns_follow_the_sample_count() {
+        let mut history = AudioHistory::new();
+        history.push_samples(vec![0.0; SPECTRUM_COLUMN_SAMPLES * 2]);
+        assert_eq!(history.take_spectrum_due(), 0, "off by default");
+
+        history.set_spectrogram(true);
+        history.push_samples(vec![0.0; SPECTRUM_COLUMN_SAMPLES * 2 + 1]);
+        let due = history.take_spectrum_due();
+        assert_eq!(due, 2);
+
+        let mut magnitudes = vec![0.0; 1024];
+        magnitudes[1023] = 512.0;
+        history.push_spectrum(&magnitudes, due);
+        let columns = history.spectrum_window(16, 0);
+        assert_eq!(columns.len(), 2);
+        assert_eq!(columns[0][SPECTROGRAM_BINS - 1], 1.0);
+        assert_eq!(columns[0][0], 0.0);
+    }
+}
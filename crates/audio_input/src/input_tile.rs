@@ -1,6 +1,9 @@
 use std::sync::{Arc, Mutex};
 
-use magnolia_core::{BindableAction, RenderContext, TileError, TileRenderer};
+use magnolia_core::{
+    render_device_error, BindableAction, DeviceDirection, DeviceError, DeviceErrorKind,
+    RenderContext, TileError, TileRenderer,
+};
 use magnolia_ui::{draw_text, FontId, TextAlignment};
 use nannou::prelude::*;
 
@@ -25,6 +28,16 @@ impl AudioInputTile {
             is_muted: Mutex::new(false),
         }
     }
+
+    /// The OS page that fixes the current error, or the sound settings
+    fn open_sound_settings(&self) {
+        let error = self.settings.last_error().unwrap_or_else(|| {
+            DeviceError::new(DeviceErrorKind::Backend, DeviceDirection::Input, "")
+        });
+        if let Err(e) = error.open_settings() {
+            log::warn!("Couldn't open the sound settings: {}", e);
+        }
+    }
 }
 
 impl TileRenderer for AudioInputTile {
@@ -80,6 +93,17 @@ impl TileRenderer for AudioInputTile {
                 TextAlignment::Right,
             );
         }
+        if self.settings.is_monitoring() {
+            draw_text(
+                draw,
+                FontId::PlexSansBold,
+                "MON",
+                pt2(rect.left() + 25.0, rect.top() - 18.0),
+                10.0,
+                srgba(1.0, 0.8, 0.2, 1.0),
+                TextAlignment::Left,
+            );
+        }
     }
 
     fn render_controls(&self, draw: &Draw, rect: Rect, ctx: &RenderContext) -> bool {
@@ -102,7 +126,7 @@ impl TileRenderer for AudioInputTile {
         draw_text(
             draw,
             FontId::PlexSansRegular,
-            "[Up/Down] Select  [Enter] Apply  [R] Refresh",
+            "[Up/Down] Select  [Enter] Apply  [R] Refresh  [O] Monitor  [Left/Right] Monitor gain  [S] Sound settings",
             pt2(rect.x(), rect.top() - 55.0),
             12.0,
             srgba(0.5, 0.5, 0.55, 1.0),
@@ -173,16 +197,32 @@ impl TileRenderer for AudioInputTile {
             TextAlignment::Right,
         );
 
+        let monitoring = self.settings.is_monitoring();
+        let monitor = if monitoring {
+            format!(
+                "Monitor: on  gain {:.1}  latency {:.1} ms",
+                self.settings.monitor_gain(),
+                self.settings.monitor_latency_us() as f64 / 1000.0
+            )
+        } else {
+            format!("Monitor: off  gain {:.1}", self.settings.monitor_gain())
+        };
+        draw_text(
+            draw,
+            FontId::PlexMonoRegular,
+            &monitor,
+            pt2(rect.right() - 100.0, rect.top() - 130.0),
+            12.0,
+            if monitoring {
+                srgba(1.0, 0.8, 0.2, 1.0)
+            } else {
+                srgba(0.5, 0.5, 0.5, 1.0)
+            },
+            TextAlignment::Right,
+        );
+
         if let Some(err) = self.settings.last_error() {
-            draw_text(
-                draw,
-                FontId::PlexMonoRegular,
-                &format!("Error: {}", err),
-                pt2(rect.left() + 20.0, rect.top() - 155.0),
-                11.0,
-                srgba(1.0, 0.3, 0.3, 0.9),
-                TextAlignment::Left,
-            );
+            render_device_error(draw, pt2(rect.left() + 20.0, rect.top() - 150.0), &err);
         }
 
         // Device list (Default + discovered)
@@ -265,12 +305,26 @@ impl TileRenderer for AudioInputTile {
                 let cur = self.settings.selected();
                 self.settings.set_selected(cur);
             }
+            Key::S => {
+                self.open_sound_settings();
+                return true;
+            }
             Key::M => {
                 let mut muted = self.is_muted.lock().unwrap();
                 *muted = !*muted;
                 self.settings.set_muted(*muted);
                 return true;
             }
+            Key::O => {
+                self.settings.set_monitoring(!self.settings.is_monitoring());
+                return true;
+            }
+            Key::Left | Key::Right => {
+                let step = if key == Key::Left { -0.1 } else { 0.1 };
+                self.settings
+                    .set_monitor_gain(self.settings.monitor_gain() + step);
+                return true;
+            }
             _ => return false,
         }
 
@@ -283,7 +337,13 @@ impl TileRenderer for AudioInputTile {
     fn get_error(&self) -> Option<TileError> {
         self.settings
             .last_error()
-            .map(|e| TileError::new("Audio input backend error").with_details(&e))
+            .map(|e| TileError::new(&e.title()).with_details(&e.message))
+    }
+
+    fn retry(&mut self) -> bool {
+        self.settings.set_last_error(None);
+        self.settings.request_rebuild();
+        true
     }
 
     fn settings_schema(&self) -> Option<serde_json::Value> {
@@ -298,6 +358,18 @@ impl TileRenderer for AudioInputTile {
                 "is_muted": {
                     "type": "boolean",
                     "default": false
+                },
+                "monitor": {
+                    "type": "boolean",
+                    "default": false,
+                    "title": "Monitor to Output"
+                },
+                "monitor_gain": {
+                    "type": "number",
+                    "default": 1.0,
+                    "minimum": 0.0,
+                    "maximum": 2.0,
+                    "title": "Monitor Gain"
                 }
             }
         }))
@@ -316,6 +388,12 @@ impl TileRenderer for AudioInputTile {
             }
             self.settings.set_muted(muted);
         }
+        if let Some(monitor) = settings.get("monitor").and_then(|v| v.as_bool()) {
+            self.settings.set_monitoring(monitor);
+        }
+        if let Some(gain) = settings.get("monitor_gain").and_then(|v| v.as_f64()) {
+            self.settings.set_monitor_gain(gain as f32);
+        }
     }
 
     fn get_settings(&self) -> serde_json::Value {
@@ -325,11 +403,19 @@ impl TileRenderer for AudioInputTile {
             .map(|s| s.clone())
             .unwrap_or_else(|_| "Default".to_string());
         let is_muted = self.is_muted.lock().map(|v| *v).unwrap_or(true);
-        serde_json::json!({ "device": device, "is_muted": is_muted })
+        serde_json::json!({
+            "device": device,
+            "is_muted": is_muted,
+            "monitor": self.settings.is_monitoring(),
+            "monitor_gain": self.settings.monitor_gain(),
+        })
     }
 
     fn bindable_actions(&self) -> Vec<BindableAction> {
-        vec![BindableAction::new("mute", "Toggle Mute", true)]
+        vec![
+            BindableAction::new("mute", "Toggle Mute", true),
+            BindableAction::new("monitor", "Toggle Monitor", true),
+        ]
     }
 
     fn execute_action(&mut self, action: &str) -> bool {
@@ -340,6 +426,10 @@ impl TileRenderer for AudioInputTile {
                 self.settings.set_muted(*muted);
                 true
             }
+            "monitor" => {
+                self.settings.set_monitoring(!self.settings.is_monitoring());
+                true
+            }
             _ => false,
         }
     }
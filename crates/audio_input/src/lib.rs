@@ -1,14 +1,18 @@
 mod backend;
+mod history;
 #[cfg(feature = "tile-rendering")]
 mod input_tile;
+mod monitor;
 mod settings;
 mod source;
 #[cfg(feature = "tile-rendering")]
 pub mod tile;
 mod viz_sink;
 
+pub use history::{AudioHistory, WaveColumn};
 #[cfg(feature = "tile-rendering")]
 pub use input_tile::AudioInputTile;
+pub use monitor::{monitor_signal, MONITOR_SINK};
 pub use settings::AudioInputSettings;
 pub use source::AudioInputSource;
 pub use viz_sink::{AudioVizRingSink, AudioVizSink};
@@ -0,0 +1,65 @@
+//! Local passthrough from the input to the output sink, so a mic can be
+//! checked without patching anything
+
+use magnolia_core::{AudioView, Signal};
+
+use crate::AudioInputSettings;
+
+/// The output sink monitored audio is sent to
+pub const MONITOR_SINK: &str = "audio_output";
+
+/// Captured audio at the monitor gain, or `None` while monitoring is off
+pub fn monitor_signal(settings: &AudioInputSettings, signal: &Signal) -> Option<Signal> {
+    if !settings.is_monitoring() {
+        return None;
+    }
+    let AudioView {
+        sample_rate,
+        channels,
+        timestamp_us,
+        data,
+    } = signal.audio()?;
+    let gain = settings.monitor_gain();
+    Some(Signal::Audio {
+        sample_rate,
+        channels,
+        timestamp_us,
+        data: data.iter().map(|sample| sample * gain).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn audio() -> Signal {
+        Signal::Audio {
+            sample_rate: 48_000,
+            channels: 1,
+            timestamp_us: 7,
+            data: vec![0.5, -0.25],
+        }
+    }
+
+    #[test]
+    fn passes_audio_through_at_the_monitor_gain() {
+        let settings = AudioInputSettings::new();
+        assert!(monitor_signal(&settings, &audio()).is_none());
+
+        settings.set_monitoring(true);
+        settings.set_monitor_gain(0.5);
+        match monitor_signal(&settings, &audio()) {
+            Some(Signal::Audio {
+                timestamp_us, data, ..
+            }) => {
+                assert_eq!(timestamp_us, 7);
+                assert_eq!(data, vec![0.25, -0.125]);
+            }
+            other => panic!("expected audio, got {:?}", other),
+        }
+        assert!(monitor_signal(&settings, &Signal::Pulse).is_none());
+
+        settings.set_monitor_gain(9.0);
+        assert_eq!(settings.monitor_gain(), 2.0);
+    }
+}
@@ -1,4 +1,5 @@
-use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use magnolia_core::DeviceError;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 
 #[derive(Default)]
@@ -6,13 +7,17 @@ pub struct AudioInputSettings {
     devices: Mutex<Vec<AudioDeviceEntry>>,
     selected: Mutex<String>,
     pending: AtomicBool,
-    last_error: Mutex<Option<String>>,
+    last_error: Mutex<Option<DeviceError>>,
     active_device: Mutex<Option<String>>,
     sample_rate: AtomicU32,
     channels: AtomicU32,
     is_muted: AtomicBool,
     frame_samples: AtomicU32,
     max_batch_wait_ms: AtomicU32,
+    monitor: AtomicBool,
+    monitor_gain_bits: AtomicU32,
+    monitor_latency_us: AtomicU64,
+    split_channels: AtomicBool,
 }
 
 #[derive(Clone, Debug)]
@@ -34,6 +39,10 @@ impl AudioInputSettings {
             is_muted: AtomicBool::new(false),
             frame_samples: AtomicU32::new(256),
             max_batch_wait_ms: AtomicU32::new(3),
+            monitor: AtomicBool::new(false),
+            monitor_gain_bits: AtomicU32::new(1.0f32.to_bits()),
+            monitor_latency_us: AtomicU64::new(0),
+            split_channels: AtomicBool::new(false),
         })
     }
 
@@ -61,17 +70,23 @@ impl AudioInputSettings {
             .unwrap_or_else(|_| "Default".to_string())
     }
 
+    /// Rebuild the stream with the current selection (retry after an error).
+    pub fn request_rebuild(&self) {
+        self.pending.store(true, Ordering::Relaxed);
+    }
+
     pub fn take_pending(&self) -> bool {
         self.pending.swap(false, Ordering::Relaxed)
     }
 
-    pub fn set_last_error(&self, err: Option<String>) {
+    /// The device failure the tile shows, with its cause
+    pub fn set_last_error(&self, err: Option<DeviceError>) {
         if let Ok(mut e) = self.last_error.lock() {
             *e = err;
         }
     }
 
-    pub fn last_error(&self) -> Option<String> {
+    pub fn last_error(&self) -> Option<DeviceError> {
         self.last_error.lock().ok().and_then(|e| e.clone())
     }
 
@@ -121,4 +136,51 @@ impl AudioInputSettings {
     pub fn max_batch_wait_ms(&self) -> u32 {
         self.max_batch_wait_ms.load(Ordering::Relaxed)
     }
+
+    /// Whether captured audio is passed straight to the output sink
+    pub fn is_monitoring(&self) -> bool {
+        self.monitor.load(Ordering::Relaxed)
+    }
+
+    pub fn set_monitoring(&self, monitor: bool) {
+        self.monitor.store(monitor, Ordering::Relaxed);
+        if !monitor {
+            self.monitor_latency_us.store(0, Ordering::Relaxed);
+        }
+    }
+
+    pub fn monitor_gain(&self) -> f32 {
+        f32::from_bits(self.monitor_gain_bits.load(Ordering::Relaxed))
+    }
+
+    /// Linear gain for the monitor path, clamped to 0..=2
+    pub fn set_monitor_gain(&self, gain: f32) {
+        let gain = if gain.is_finite() {
+            gain.clamp(0.0, 2.0)
+        } else {
+            1.0
+        };
+        self.monitor_gain_bits
+            .store(gain.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Capture to playback latency of the monitor path, as the output last saw it
+    pub fn monitor_latency_us(&self) -> u64 {
+        self.monitor_latency_us.load(Ordering::Relaxed)
+    }
+
+    pub fn set_monitor_latency_us(&self, latency_us: u64) {
+        self.monitor_latency_us.store(latency_us, Ordering::Relaxed);
+    }
+
+    /// Whether each capture channel is also emitted on its own port
+    pub fn split_channels(&self) -> bool {
+        self.split_channels.load(Ordering::Relaxed)
+    }
+
+    /// Set by the host while any per-channel port is patched, so unpatched
+    /// inputs don't pay for the copies
+    pub fn set_split_channels(&self, split: bool) {
+        self.split_channels.store(split, Ordering::Relaxed);
+    }
 }
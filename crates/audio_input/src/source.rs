@@ -1,3 +1,4 @@
+use std::collections::VecDeque;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::sync::Mutex;
@@ -10,11 +11,29 @@ use tokio::time::Instant;
 use crate::backend::{default_backend, AudioInputBackend, BackendStream};
 use crate::settings::AudioDeviceEntry;
 use crate::AudioInputSettings;
-use magnolia_core::{DataType, ModuleSchema, Port, PortDirection, Signal, Source};
+use magnolia_core::{
+    DataType, DeviceDirection, DeviceError, ModuleSchema, Port, PortDirection, Signal, Source,
+};
 use magnolia_signals::ring_buffer::{self, RingBufferReceiver};
 
 const DEFAULT_CAPACITY: usize = 16384;
 
+/// Capture channels with a port of their own
+const MAX_CHANNEL_PORTS: usize = 8;
+
+const CHANNEL_PORTS: [&str; MAX_CHANNEL_PORTS] = [
+    "ch_1_out", "ch_2_out", "ch_3_out", "ch_4_out", "ch_5_out", "ch_6_out", "ch_7_out", "ch_8_out",
+];
+
+/// One channel of interleaved samples
+fn deinterleave(data: &[f32], channels: usize, channel: usize) -> Vec<f32> {
+    data.iter()
+        .skip(channel)
+        .step_by(channels)
+        .copied()
+        .collect()
+}
+
 /// Audio input source using CPAL, emitting buffered Audio signals.
 pub struct AudioInputSource {
     id: String,
@@ -26,6 +45,9 @@ pub struct AudioInputSource {
     last_capture_us: Arc<AtomicU64>,
     settings: Arc<AudioInputSettings>,
     backend: Mutex<Box<dyn AudioInputBackend>>,
+    /// Per-channel copies of the last buffer, emitted before the next capture
+    split: VecDeque<(&'static str, Signal)>,
+    last_port: &'static str,
 }
 
 impl AudioInputSource {
@@ -43,11 +65,15 @@ impl AudioInputSource {
             last_capture_us,
             settings,
             backend: Mutex::new(backend),
+            split: VecDeque::new(),
+            last_port: "audio_out",
         };
 
         if let Err(e) = source.initialize() {
             // Keep the module alive so the user can retry via settings UI.
-            source.settings.set_last_error(Some(e.to_string()));
+            source
+                .settings
+                .set_last_error(Some(DeviceError::classify(DeviceDirection::Input, &e)));
         }
         Ok(source)
     }
@@ -120,13 +146,21 @@ impl Source for AudioInputSource {
             description:
                 "Captures audio from the system input device (PipeWire on Linux, CPAL elsewhere)"
                     .to_string(),
-            ports: vec![Port {
+            ports: std::iter::once(Port {
                 id: "audio_out".to_string(),
                 label: "Audio Out".to_string(),
                 data_type: DataType::Audio,
                 direction: PortDirection::Output,
-            }],
+            })
+            .chain(CHANNEL_PORTS.iter().enumerate().map(|(i, port)| Port {
+                id: port.to_string(),
+                label: format!("Channel {}", i + 1),
+                data_type: DataType::Audio,
+                direction: PortDirection::Output,
+            }))
+            .collect(),
             settings_schema: None,
+            docs: None,
         }
     }
 
@@ -139,9 +173,19 @@ impl Source for AudioInputSource {
     }
 
     async fn poll(&mut self) -> Option<Signal> {
+        if let Some((port, signal)) = self.split.pop_front() {
+            self.last_port = port;
+            return Some(signal);
+        }
+        self.last_port = "audio_out";
+
         if self.settings.take_pending() {
             self.stream = None;
-            let _ = self.initialize();
+            if let Err(e) = self.initialize() {
+                warn!("AudioInputSource: failed to start capture: {e:#}");
+                self.settings
+                    .set_last_error(Some(DeviceError::classify(DeviceDirection::Input, &e)));
+            }
         }
 
         if !self.enabled || self.settings.is_muted() {
@@ -185,6 +229,20 @@ impl Source for AudioInputSource {
         }
 
         let timestamp_us = self.last_capture_us.load(Ordering::Relaxed);
+        let channels = self.channels as usize;
+        if self.settings.split_channels() && channels > 1 {
+            for (channel, port) in CHANNEL_PORTS.iter().enumerate().take(channels) {
+                self.split.push_back((
+                    *port,
+                    Signal::Audio {
+                        sample_rate: self.sample_rate,
+                        channels: 1,
+                        timestamp_us,
+                        data: deinterleave(&data, channels, channel),
+                    },
+                ));
+            }
+        }
         Some(Signal::Audio {
             sample_rate: self.sample_rate,
             channels: self.channels,
@@ -192,4 +250,20 @@ impl Source for AudioInputSource {
             data,
         })
     }
+
+    fn output_port(&self, _signal: &Signal) -> Option<&str> {
+        Some(self.last_port)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deinterleaves_one_channel() {
+        let data = [0.0, 1.0, 2.0, 10.0, 11.0, 12.0];
+        assert_eq!(deinterleave(&data, 3, 0), vec![0.0, 10.0]);
+        assert_eq!(deinterleave(&data, 3, 2), vec![2.0, 12.0]);
+    }
 }
@@ -5,6 +5,10 @@
 //! and color schemes via the settings modal (maximized view).
 //!
 //! Uses SPSC ring buffer for minimal latency audio streaming.
+//!
+//! Incoming audio is also kept as a scrolling waveform (and, optionally, a
+//! spectrogram) for the Waveform mode and the maximized history panel, where
+//! `[`/`]` zoom and, while frozen, the arrow keys pan back through it.
 
 #[cfg(feature = "tile-rendering")]
 use magnolia_core::{BindableAction, RenderContext, TileError, TileRenderer};
@@ -22,6 +26,10 @@ use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
 use std::sync::Arc as StdArc;
 use std::sync::{Arc, Mutex};
 
+#[cfg(feature = "tile-rendering")]
+use crate::history::{decimate, SPECTROGRAM_BINS, SPECTRUM_COLUMN_SAMPLES};
+use crate::history::{AudioHistory, WAVE_COLUMN_SAMPLES};
+
 /// Available visualization types
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum VisualizationType {
@@ -30,6 +38,7 @@ pub enum VisualizationType {
     SpectrumLine,
     VuMeter,
     Lissajous,
+    Waveform,
 }
 
 impl Default for VisualizationType {
@@ -46,6 +55,7 @@ impl VisualizationType {
             VisualizationType::SpectrumLine,
             VisualizationType::VuMeter,
             VisualizationType::Lissajous,
+            VisualizationType::Waveform,
         ]
     }
 
@@ -56,6 +66,7 @@ impl VisualizationType {
             VisualizationType::SpectrumLine => "Spectrum Line",
             VisualizationType::VuMeter => "VU Meter",
             VisualizationType::Lissajous => "Lissajous",
+            VisualizationType::Waveform => "Waveform History",
         }
     }
 
@@ -65,7 +76,8 @@ impl VisualizationType {
             VisualizationType::SpectrumBars => VisualizationType::SpectrumLine,
             VisualizationType::SpectrumLine => VisualizationType::VuMeter,
             VisualizationType::VuMeter => VisualizationType::Lissajous,
-            VisualizationType::Lissajous => VisualizationType::Oscilloscope,
+            VisualizationType::Lissajous => VisualizationType::Waveform,
+            VisualizationType::Waveform => VisualizationType::Oscilloscope,
         }
     }
 }
@@ -194,6 +206,15 @@ pub struct AudioVisTile {
     /// FFT throttling (update spectrum every N frames)
     #[cfg(feature = "tile-rendering")]
     fft_tick: u32,
+
+    /// Scrolling waveform / spectrogram history
+    history: AudioHistory,
+
+    /// Seconds of history shown across the tile (zoom)
+    history_secs: f32,
+
+    /// Pan position in waveform columns back from the newest (frozen only)
+    history_offset: usize,
 }
 
 const BUFFER_SIZE: usize = 2048;
@@ -202,6 +223,11 @@ const MAX_SCOPE_POINTS: usize = 512;
 const MAX_LISSAJOUS_POINTS: usize = 512;
 #[cfg(feature = "tile-rendering")]
 const FFT_EVERY_N_FRAMES: u32 = 2;
+const MIN_HISTORY_SECS: f32 = 0.25;
+const MAX_HISTORY_SECS: f32 = 20.0;
+// Spectrogram cells drawn per frame at most; columns are merged beyond this.
+#[cfg(feature = "tile-rendering")]
+const MAX_SPECTROGRAM_CELLS: usize = 6144;
 
 impl AudioVisTile {
     pub fn new(id: &str) -> Self {
@@ -239,6 +265,9 @@ impl AudioVisTile {
             right_pos: 0,
             #[cfg(feature = "tile-rendering")]
             fft_tick: 0,
+            history: AudioHistory::new(),
+            history_secs: 5.0,
+            history_offset: 0,
         }
     }
 
@@ -323,6 +352,30 @@ impl AudioVisTile {
         self.right_pos % self.right_buffer.len().max(1)
     }
 
+    /// Waveform columns spanning `history_secs` at the current sample rate
+    fn history_columns(&self) -> usize {
+        let sr = self.sample_rate_hz.load(Ordering::Relaxed).max(1) as f32;
+        ((self.history_secs * sr / WAVE_COLUMN_SAMPLES as f32) as usize)
+            .clamp(16, AudioHistory::WAVE_CAPACITY)
+    }
+
+    fn zoom_history(&mut self, factor: f32) {
+        self.history_secs = (self.history_secs * factor).clamp(MIN_HISTORY_SECS, MAX_HISTORY_SECS);
+        self.pan_history(0);
+    }
+
+    /// Move the view `delta` columns back in time (negative: forward)
+    fn pan_history(&mut self, delta: isize) {
+        let max_offset = self
+            .history
+            .wave_len()
+            .saturating_sub(self.history_columns());
+        self.history_offset = self
+            .history_offset
+            .saturating_add_signed(delta)
+            .min(max_offset);
+    }
+
     fn poll_audio(&mut self) {
         // Allow the sink to update channels dynamically (e.g. device switch).
         let desired_ch = self.channels_count.load(Ordering::Relaxed) as u16;
@@ -417,6 +470,15 @@ impl AudioVisTile {
                     }
                 }
             }
+
+            // The newest `frames_processed` mono samples end at the cursor
+            let n = self.buffer.len();
+            if n > 0 {
+                let first = self.mono_pos + n - frames_processed.min(n);
+                self.history.push_samples(
+                    (first..first + frames_processed.min(n)).map(|i| self.buffer[i % n]),
+                );
+            }
         } else {
             if let Ok(buf) = self.legacy_buffer.lock() {
                 let len = buf.len().min(BUFFER_SIZE);
@@ -492,6 +554,117 @@ impl AudioVisTile {
             self.spectrum[i] = mag;
         }
     }
+
+    /// Scrolling waveform, newest at the right edge, with the spectrogram
+    /// below it when enabled. Silent stretches between audio are tinted red
+    /// so dropouts stand out.
+    #[cfg(feature = "tile-rendering")]
+    fn draw_history(&self, draw: &Draw, rect: Rect, ctx: &RenderContext) {
+        use magnolia_core::PowerProfile;
+        let budget = match ctx.power_profile {
+            PowerProfile::Normal => 1,
+            PowerProfile::LowPower => 2,
+            PowerProfile::BatteryBackground => 4,
+        };
+
+        let visible = self.history_columns();
+        let (wave_rect, spec_rect) = if self.history.spectrogram_enabled() {
+            let h = rect.h() / 2.0;
+            (
+                Rect::from_x_y_w_h(rect.x(), rect.top() - h / 2.0, rect.w(), h),
+                Some(Rect::from_x_y_w_h(
+                    rect.x(),
+                    rect.bottom() + h / 2.0,
+                    rect.w(),
+                    h,
+                )),
+            )
+        } else {
+            (rect, None)
+        };
+        let col_w = rect.w() / visible as f32;
+
+        let columns = self.history.wave_window(visible, self.history_offset);
+        let envelope = decimate(&columns, MAX_SCOPE_POINTS / budget);
+        if !envelope.is_empty() {
+            let env_w = columns.len() as f32 * col_w / envelope.len() as f32;
+            let left = wave_rect.right() - envelope.len() as f32 * env_w;
+            let half_h = wave_rect.h() * 0.5;
+            let y_of = |v: f32| wave_rect.y() + (v * self.sensitivity).clamp(-1.0, 1.0) * half_h;
+
+            // Dropout markers: silent runs with audio on both sides
+            let mut run_start: Option<usize> = None;
+            let mut seen_audio = false;
+            for (i, col) in envelope.iter().enumerate() {
+                if col.is_silent() {
+                    if seen_audio && run_start.is_none() {
+                        run_start = Some(i);
+                    }
+                } else {
+                    if let Some(start) = run_start.take() {
+                        let w = (i - start) as f32 * env_w;
+                        draw.rect()
+                            .x_y(left + start as f32 * env_w + w / 2.0, wave_rect.y())
+                            .w_h(w.max(1.0), wave_rect.h())
+                            .color(srgba(1.0, 0.2, 0.2, 0.25));
+                    }
+                    seen_audio = true;
+                }
+            }
+
+            let x_of = |i: usize| left + (i as f32 + 0.5) * env_w;
+            let top = envelope
+                .iter()
+                .enumerate()
+                .map(|(i, c)| pt2(x_of(i), y_of(c.max)));
+            let bottom = envelope
+                .iter()
+                .enumerate()
+                .rev()
+                .map(|(i, c)| pt2(x_of(i), y_of(c.min)));
+            let avg_amp =
+                envelope.iter().map(|c| c.max - c.min).sum::<f32>() / (2.0 * envelope.len() as f32);
+            draw.polygon()
+                .points(top.chain(bottom))
+                .color(self.get_color(avg_amp));
+        }
+        draw.line()
+            .start(pt2(wave_rect.left(), wave_rect.y()))
+            .end(pt2(wave_rect.right(), wave_rect.y()))
+            .weight(1.0)
+            .color(srgba(0.3, 0.3, 0.3, 0.5));
+
+        let Some(spec_rect) = spec_rect else {
+            return;
+        };
+        let spectra = self.history.spectrum_window(visible, self.history_offset);
+        if spectra.is_empty() {
+            return;
+        }
+        let spec_col_w = col_w * (SPECTRUM_COLUMN_SAMPLES / WAVE_COLUMN_SAMPLES) as f32;
+        let max_cells = MAX_SPECTROGRAM_CELLS / budget;
+        let group = (spectra.len() * SPECTROGRAM_BINS)
+            .div_ceil(max_cells)
+            .max(1);
+        let cell_w = spec_col_w * group as f32;
+        let cell_h = spec_rect.h() / SPECTROGRAM_BINS as f32;
+        let right = spec_rect.right();
+        // Group from the newest column back so the right edge stays aligned
+        for (slot, chunk) in spectra.rchunks(group).enumerate() {
+            let x = right - (slot as f32 + 0.5) * cell_w;
+            for row in 0..SPECTROGRAM_BINS {
+                let v = chunk.iter().map(|c| c[row]).fold(0.0_f32, f32::max);
+                if v < 0.02 {
+                    continue;
+                }
+                let (r, g, b) = hsv_to_rgb(0.66 * (1.0 - v), 1.0, v);
+                draw.rect()
+                    .x_y(x, spec_rect.bottom() + (row as f32 + 0.5) * cell_h)
+                    .w_h(cell_w, cell_h)
+                    .color(srgba(r, g, b, 1.0));
+            }
+        }
+    }
 }
 
 impl Default for AudioVisTile {
@@ -701,6 +874,9 @@ impl TileRenderer for AudioVisTile {
                     draw.polyline().weight(2.0).points(points).color(color);
                 }
             }
+            VisualizationType::Waveform => {
+                self.draw_history(draw, content_rect, ctx);
+            }
         }
 
         // Status indicators
@@ -820,18 +996,67 @@ impl TileRenderer for AudioVisTile {
             );
         }
 
-        // Main Visualization Area (Centered)
-        let preview_rect =
-            Rect::from_x_y_w_h(rect.x(), rect.y() - 20.0, rect.w() * 0.85, rect.h() * 0.6);
+        // Main Visualization Area (Centered), with the history panel below
+        // unless the mode already shows it
+        let in_preview = self.vis_type == VisualizationType::Waveform;
+        let (preview_rect, history_rect) = if in_preview {
+            let preview =
+                Rect::from_x_y_w_h(rect.x(), rect.y() - 20.0, rect.w() * 0.85, rect.h() * 0.6);
+            (preview, preview)
+        } else {
+            (
+                Rect::from_x_y_w_h(
+                    rect.x(),
+                    rect.y() + rect.h() * 0.15 - 20.0,
+                    rect.w() * 0.85,
+                    rect.h() * 0.28,
+                ),
+                Rect::from_x_y_w_h(
+                    rect.x(),
+                    rect.y() - rect.h() * 0.17 - 20.0,
+                    rect.w() * 0.85,
+                    rect.h() * 0.28,
+                ),
+            )
+        };
 
-        draw.rect()
-            .xy(preview_rect.xy())
-            .wh(preview_rect.wh())
-            .no_fill()
-            .stroke(srgba(0.1, 0.2, 0.2, 0.8))
-            .stroke_weight(1.0);
+        let frames: &[Rect] = if in_preview {
+            &[preview_rect]
+        } else {
+            &[preview_rect, history_rect]
+        };
+        for frame in frames {
+            draw.rect()
+                .xy(frame.xy())
+                .wh(frame.wh())
+                .no_fill()
+                .stroke(srgba(0.1, 0.2, 0.2, 0.8))
+                .stroke_weight(1.0);
+        }
 
         self.render_monitor(draw, preview_rect.pad(2.0), ctx);
+        if !in_preview {
+            self.draw_history(draw, history_rect.pad(2.0), ctx);
+        }
+
+        let sr = sr.max(1) as f32;
+        let mut history_label = format!("HISTORY: {:.2}s", self.history_secs);
+        if self.history_offset > 0 {
+            let back = (self.history_offset * WAVE_COLUMN_SAMPLES) as f32 / sr;
+            history_label.push_str(&format!("   |   -{:.2}s", back));
+        }
+        if self.history.spectrogram_enabled() {
+            history_label.push_str("   |   SPECTROGRAM");
+        }
+        draw_text(
+            draw,
+            FontId::PlexMonoRegular,
+            &history_label,
+            pt2(history_rect.left(), history_rect.top() + 10.0),
+            10.0,
+            srgba(0.4, 0.5, 0.5, 1.0),
+            TextAlignment::Left,
+        );
 
         // Controls hint at bottom
         draw_text(
@@ -843,6 +1068,15 @@ impl TileRenderer for AudioVisTile {
             srgba(0.3, 0.3, 0.3, 1.0),
             TextAlignment::Center,
         );
+        draw_text(
+            draw,
+            FontId::PlexSansRegular,
+            "[ / ] Zoom History   [S] Spectrogram   [Left/Right] Pan (frozen)   [End] Newest",
+            pt2(rect.x(), rect.bottom() + 24.0),
+            10.0,
+            srgba(0.3, 0.3, 0.3, 1.0),
+            TextAlignment::Center,
+        );
 
         false
     }
@@ -854,18 +1088,43 @@ impl TileRenderer for AudioVisTile {
 
         #[cfg(feature = "tile-rendering")]
         {
+            // Spectrogram columns are paced by samples received, not frames
+            let due = self.history.take_spectrum_due();
+            if due > 0 {
+                self.update_spectrum();
+                self.history.push_spectrum(&self.spectrum, due);
+            }
+
             if matches!(
                 self.vis_type,
                 VisualizationType::SpectrumBars | VisualizationType::SpectrumLine
             ) {
                 self.fft_tick = self.fft_tick.wrapping_add(1);
-                if self.fft_tick % FFT_EVERY_N_FRAMES == 0 {
+                if self.fft_tick % FFT_EVERY_N_FRAMES == 0 && due == 0 {
                     self.update_spectrum();
                 }
             }
         }
     }
 
+    fn handle_key(&mut self, key: Key, _ctrl: bool, _shift: bool) -> bool {
+        match key {
+            Key::LBracket => self.zoom_history(0.5),
+            Key::RBracket => self.zoom_history(2.0),
+            Key::S => {
+                let enabled = !self.history.spectrogram_enabled();
+                self.history.set_spectrogram(enabled);
+            }
+            Key::Left | Key::Right if self.is_frozen => {
+                let step = (self.history_columns() / 4).max(1) as isize;
+                self.pan_history(if key == Key::Left { step } else { -step });
+            }
+            Key::End => self.history_offset = 0,
+            _ => return false,
+        }
+        true
+    }
+
     fn get_error(&self) -> Option<TileError> {
         #[cfg(feature = "tile-rendering")]
         {
@@ -890,7 +1149,7 @@ impl TileRenderer for AudioVisTile {
             "properties": {
                 "vis_type": {
                     "type": "string",
-                    "enum": ["Oscilloscope", "SpectrumBars", "SpectrumLine", "VuMeter", "Lissajous"],
+                    "enum": ["Oscilloscope", "SpectrumBars", "SpectrumLine", "VuMeter", "Lissajous", "Waveform"],
                     "default": "Oscilloscope",
                     "title": "Visualization Type"
                 },
@@ -906,6 +1165,18 @@ impl TileRenderer for AudioVisTile {
                     "maximum": 5.0,
                     "default": 1.0,
                     "title": "Sensitivity"
+                },
+                "history_secs": {
+                    "type": "number",
+                    "minimum": MIN_HISTORY_SECS,
+                    "maximum": MAX_HISTORY_SECS,
+                    "default": 5.0,
+                    "title": "History Window (s)"
+                },
+                "spectrogram": {
+                    "type": "boolean",
+                    "default": false,
+                    "title": "Spectrogram History"
                 }
             }
         }))
@@ -919,6 +1190,7 @@ impl TileRenderer for AudioVisTile {
                 "SpectrumLine" => VisualizationType::SpectrumLine,
                 "VuMeter" => VisualizationType::VuMeter,
                 "Lissajous" => VisualizationType::Lissajous,
+                "Waveform" => VisualizationType::Waveform,
                 _ => VisualizationType::Oscilloscope,
             };
         }
@@ -934,13 +1206,22 @@ impl TileRenderer for AudioVisTile {
         if let Some(s) = settings.get("sensitivity").and_then(|v| v.as_f64()) {
             self.sensitivity = (s as f32).clamp(0.1, 5.0);
         }
+        if let Some(secs) = settings.get("history_secs").and_then(|v| v.as_f64()) {
+            self.history_secs = (secs as f32).clamp(MIN_HISTORY_SECS, MAX_HISTORY_SECS);
+            self.pan_history(0);
+        }
+        if let Some(enabled) = settings.get("spectrogram").and_then(|v| v.as_bool()) {
+            self.history.set_spectrogram(enabled);
+        }
     }
 
     fn get_settings(&self) -> serde_json::Value {
         serde_json::json!({
             "vis_type": format!("{:?}", self.vis_type),
             "color_scheme": format!("{:?}", self.color_scheme),
-            "sensitivity": self.sensitivity
+            "sensitivity": self.sensitivity,
+            "history_secs": self.history_secs,
+            "spectrogram": self.history.spectrogram_enabled()
         })
     }
 
@@ -952,6 +1233,9 @@ impl TileRenderer for AudioVisTile {
             BindableAction::new("next_theme", "Next Color Scheme", false),
             BindableAction::new("inc_sens", "Increase Sensitivity", false),
             BindableAction::new("dec_sens", "Decrease Sensitivity", false),
+            BindableAction::new("spectrogram", "Spectrogram History", true),
+            BindableAction::new("zoom_in", "Zoom History In", false),
+            BindableAction::new("zoom_out", "Zoom History Out", false),
         ]
     }
 
@@ -967,6 +1251,9 @@ impl TileRenderer for AudioVisTile {
                     self.frozen_buffer = self.buffer.clone();
                 }
                 self.is_frozen = !self.is_frozen;
+                if !self.is_frozen {
+                    self.history_offset = 0;
+                }
                 log::info!("Audio vis freeze: {}", self.is_frozen);
                 true
             }
@@ -990,6 +1277,20 @@ impl TileRenderer for AudioVisTile {
                 log::info!("Audio vis sensitivity: {:.1}", self.sensitivity);
                 true
             }
+            "spectrogram" => {
+                let enabled = !self.history.spectrogram_enabled();
+                self.history.set_spectrogram(enabled);
+                log::info!("Audio vis spectrogram: {}", enabled);
+                true
+            }
+            "zoom_in" => {
+                self.zoom_history(0.5);
+                true
+            }
+            "zoom_out" => {
+                self.zoom_history(2.0);
+                true
+            }
             _ => false,
         }
     }
@@ -4,7 +4,7 @@ use std::time::{SystemTime, UNIX_EPOCH};
 
 use async_trait::async_trait;
 
-use magnolia_core::{DataType, ModuleSchema, Port, PortDirection, Signal, Sink};
+use magnolia_core::{AudioView, DataType, ModuleSchema, Port, PortDirection, Signal, Sink};
 use magnolia_signals::ring_buffer::RingBufferSender;
 
 fn now_micros() -> u64 {
@@ -89,6 +89,7 @@ impl Sink for AudioVizSink {
                 direction: PortDirection::Input,
             }],
             settings_schema: None,
+            docs: None,
         }
     }
 
@@ -105,12 +106,12 @@ impl Sink for AudioVizSink {
             return Ok(None);
         }
 
-        let Signal::Audio {
+        let Some(AudioView {
             timestamp_us,
             data,
             sample_rate,
             ..
-        } = signal
+        }) = signal.audio()
         else {
             return Ok(None);
         };
@@ -135,7 +136,7 @@ impl Sink for AudioVizSink {
                 buf.rotate_left(n);
                 // Copy new samples to the end
                 let start = buf_len - n;
-                buf[start..].copy_from_slice(&data);
+                buf[start..].copy_from_slice(data);
             }
         }
 
@@ -161,6 +162,7 @@ impl Sink for AudioVizRingSink {
                 direction: PortDirection::Input,
             }],
             settings_schema: None,
+            docs: None,
         }
     }
 
@@ -177,13 +179,12 @@ impl Sink for AudioVizRingSink {
             return Ok(None);
         }
 
-        let Signal::Audio {
+        let Some(AudioView {
             timestamp_us,
             data,
             sample_rate,
             channels,
-            ..
-        } = signal
+        }) = signal.audio()
         else {
             return Ok(None);
         };
@@ -201,7 +202,7 @@ impl Sink for AudioVizRingSink {
 
         // Best-effort: if the UI can't keep up, we drop samples rather than blocking.
         for s in data {
-            let _ = self.tx.try_send(s);
+            let _ = self.tx.try_send(*s);
         }
 
         Ok(None)
@@ -14,7 +14,10 @@ use async_trait::async_trait;
 use log::{info, warn};
 
 use crate::backend::{default_backend, AudioOutputBackend, BackendStream};
-use magnolia_core::{DataType, ModuleSchema, Port, PortDirection, Signal, Sink};
+use magnolia_core::{
+    AudioView, DataType, DeviceDirection, DeviceError, DeviceErrorKind, ModuleSchema, Port,
+    PortDirection, Signal, Sink,
+};
 use magnolia_signals::ring_buffer::{self, RingBufferSender};
 
 use settings::AudioDeviceEntry;
@@ -80,7 +83,7 @@ impl AudioOutputSink {
             Ok(v) => v,
             Err(e) => {
                 // Keep the module alive so the user can fix devices / backend and retry.
-                settings.set_last_error(Some(e.to_string()));
+                settings.set_last_error(Some(DeviceError::classify(DeviceDirection::Output, &e)));
                 (
                     AudioOutputInner {
                         _stream: None,
@@ -169,8 +172,11 @@ impl AudioOutputSink {
                 let mut backend_guard = match backend.lock() {
                     Ok(g) => g,
                     Err(_) => {
-                        settings
-                            .set_last_error(Some("AudioOutput backend lock poisoned".to_string()));
+                        settings.set_last_error(Some(DeviceError::new(
+                            DeviceErrorKind::Backend,
+                            DeviceDirection::Output,
+                            "AudioOutput backend lock poisoned",
+                        )));
                         thread::sleep(Duration::from_millis(200));
                         continue;
                     }
@@ -184,7 +190,10 @@ impl AudioOutputSink {
                         settings.set_devices(devices);
                     }
                     Err(e) => {
-                        settings.set_last_error(Some(e.to_string()));
+                        settings.set_last_error(Some(DeviceError::classify(
+                            DeviceDirection::Output,
+                            &e,
+                        )));
                     }
                 }
             }
@@ -232,6 +241,7 @@ impl Sink for AudioOutputSink {
                 direction: PortDirection::Input,
             }],
             settings_schema: None,
+            docs: None,
         }
     }
 
@@ -251,12 +261,12 @@ impl Sink for AudioOutputSink {
 
         // Stream rebuilds are handled by a background thread to avoid needing incoming audio.
 
-        let Signal::Audio {
+        let Some(AudioView {
             sample_rate,
             channels,
             timestamp_us,
             data,
-        } = signal
+        }) = signal.audio()
         else {
             return Ok(None);
         };
@@ -282,7 +292,7 @@ impl Sink for AudioOutputSink {
         }
 
         let mut sum = 0.0f64;
-        for sample in &data {
+        for sample in data {
             sum += (*sample as f64) * (*sample as f64);
             let _ = inner.sender.try_send(*sample);
         }
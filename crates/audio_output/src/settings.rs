@@ -1,3 +1,4 @@
+use magnolia_core::DeviceError;
 use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::{Arc, Mutex};
 
@@ -6,7 +7,7 @@ pub struct AudioOutputSettings {
     devices: Mutex<Vec<AudioDeviceEntry>>,
     selected: Mutex<String>,
     pending: AtomicBool,
-    last_error: Mutex<Option<String>>,
+    last_error: Mutex<Option<DeviceError>>,
     active_device: Mutex<Option<String>>,
     sample_rate: AtomicU32,
     channels: AtomicU32,
@@ -57,17 +58,23 @@ impl AudioOutputSettings {
             .unwrap_or_else(|_| "Default".to_string())
     }
 
+    /// Rebuild the stream with the current selection (retry after an error).
+    pub fn request_rebuild(&self) {
+        self.pending.store(true, Ordering::Relaxed);
+    }
+
     pub fn take_pending(&self) -> bool {
         self.pending.swap(false, Ordering::Relaxed)
     }
 
-    pub fn set_last_error(&self, err: Option<String>) {
+    /// The device failure the tile shows, with its cause
+    pub fn set_last_error(&self, err: Option<DeviceError>) {
         if let Ok(mut e) = self.last_error.lock() {
             *e = err;
         }
     }
 
-    pub fn last_error(&self) -> Option<String> {
+    pub fn last_error(&self) -> Option<DeviceError> {
         self.last_error.lock().ok().and_then(|e| e.clone())
     }
 
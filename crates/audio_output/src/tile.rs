@@ -1,6 +1,9 @@
 use std::sync::{Arc, Mutex};
 
-use magnolia_core::{BindableAction, RenderContext, TileError, TileRenderer};
+use magnolia_core::{
+    render_device_error, BindableAction, DeviceDirection, DeviceError, DeviceErrorKind,
+    RenderContext, TileError, TileRenderer,
+};
 use magnolia_ui::{draw_text, FontId, TextAlignment};
 use nannou::prelude::*;
 
@@ -27,6 +30,16 @@ impl AudioOutputTile {
             is_muted: Mutex::new(false),
         }
     }
+
+    /// The OS page that fixes the current error, or the sound settings
+    fn open_sound_settings(&self) {
+        let error = self.settings.last_error().unwrap_or_else(|| {
+            DeviceError::new(DeviceErrorKind::Backend, DeviceDirection::Output, "")
+        });
+        if let Err(e) = error.open_settings() {
+            log::warn!("Couldn't open the sound settings: {}", e);
+        }
+    }
 }
 
 impl TileRenderer for AudioOutputTile {
@@ -125,7 +138,7 @@ impl TileRenderer for AudioOutputTile {
         draw_text(
             draw,
             FontId::PlexSansRegular,
-            "[Up/Down] Select  [Enter] Apply  [R] Refresh",
+            "[Up/Down] Select  [Enter] Apply  [R] Refresh  [S] Sound settings",
             pt2(rect.x(), rect.top() - 55.0),
             12.0,
             srgba(0.5, 0.5, 0.55, 1.0),
@@ -197,15 +210,7 @@ impl TileRenderer for AudioOutputTile {
         );
 
         if let Some(err) = self.settings.last_error() {
-            draw_text(
-                draw,
-                FontId::PlexMonoRegular,
-                &format!("Error: {}", err),
-                pt2(rect.left() + 20.0, rect.top() - 155.0),
-                11.0,
-                srgba(1.0, 0.3, 0.3, 0.9),
-                TextAlignment::Left,
-            );
+            render_device_error(draw, pt2(rect.left() + 20.0, rect.top() - 150.0), &err);
         }
 
         let mut devices: Vec<(String, String)> =
@@ -285,6 +290,10 @@ impl TileRenderer for AudioOutputTile {
                 let cur = self.settings.selected();
                 self.settings.set_selected(cur);
             }
+            Key::S => {
+                self.open_sound_settings();
+                return true;
+            }
             Key::M => {
                 let mut muted = self.is_muted.lock().unwrap();
                 *muted = !*muted;
@@ -303,7 +312,13 @@ impl TileRenderer for AudioOutputTile {
     fn get_error(&self) -> Option<TileError> {
         self.settings
             .last_error()
-            .map(|e| TileError::new("Audio output backend error").with_details(&e))
+            .map(|e| TileError::new(&e.title()).with_details(&e.message))
+    }
+
+    fn retry(&mut self) -> bool {
+        self.settings.set_last_error(None);
+        self.settings.request_rebuild();
+        true
     }
 
     fn settings_schema(&self) -> Option<serde_json::Value> {
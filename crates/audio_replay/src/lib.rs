@@ -2,44 +2,199 @@ use async_trait::async_trait;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 
-use magnolia_core::{DataType, ModuleSchema, Port, PortDirection, Signal, Source};
+use magnolia_core::{
+    system_clock, ControlSignal, DataType, ModuleSchema, Port, PortDirection, SharedClock, Signal,
+    Source,
+};
+
+/// What happens when the end of the playlist is reached.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReplayMode {
+    /// Play the playlist once, then idle until seeked or restarted.
+    OneShot,
+    /// Wrap around to the first track.
+    Loop,
+}
+
+impl ReplayMode {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "one_shot" => Some(ReplayMode::OneShot),
+            "loop" => Some(ReplayMode::Loop),
+            _ => None,
+        }
+    }
+}
 
 /// Deterministic WAV replay source for demos/tests.
 ///
 /// Emits `Signal::Audio` chunks with the WAV's sample rate/channels.
 /// Downstream modules are responsible for any required resampling.
+///
+/// Plays a playlist of files in order. At the end of every track it emits
+/// `Signal::Intent { action: "eof", parameters: [track_index, path] }`. Transport is
+/// driven through the `control_in` port:
+/// - `Intent` actions `seek` (`[ms]`), `next`, `previous`, `restart`
+/// - `Control(Settings)` keys `mode` (`"one_shot"`/`"loop"`), `track`, `seek_ms`
+///
+/// Timestamps stay monotonic across track changes, loops and seeks.
 pub struct WavReplaySource {
     id: String,
     enabled: bool,
-    wav_path: PathBuf,
+    playlist: Vec<PathBuf>,
     chunk_ms: u32,
     realtime: bool,
+    mode: ReplayMode,
+    clock: SharedClock,
 
-    started: bool,
+    track: usize,
+    finished: bool,
     pos: usize,
     sample_rate: u32,
     channels: u16,
     audio: Vec<f32>,
-    t0_us: u64,
+    /// Timestamp offset of the current track relative to its first sample.
+    t0_us: i64,
+    /// Timestamp where the next chunk starts.
+    next_ts_us: u64,
 }
 
 impl WavReplaySource {
     pub fn new(id: &str, wav_path: PathBuf, chunk_ms: u32, realtime: bool) -> anyhow::Result<Self> {
-        let (sample_rate, channels, audio) = load_wav_f32(&wav_path)?;
+        Self::with_playlist(id, vec![wav_path], chunk_ms, realtime)
+    }
+
+    /// Replay several files back to back. The first track is loaded eagerly so that
+    /// a bad path fails construction; later tracks are loaded when reached.
+    pub fn with_playlist(
+        id: &str,
+        playlist: Vec<PathBuf>,
+        chunk_ms: u32,
+        realtime: bool,
+    ) -> anyhow::Result<Self> {
+        let first = playlist
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("WAV replay playlist is empty"))?;
+        let (sample_rate, channels, audio) = load_wav_f32(first)?;
         Ok(Self {
             id: id.to_string(),
             enabled: true,
-            wav_path,
+            playlist,
             chunk_ms: chunk_ms.max(10),
             realtime,
-            started: false,
+            mode: ReplayMode::OneShot,
+            clock: system_clock(),
+            track: 0,
+            finished: false,
             pos: 0,
             sample_rate,
             channels,
             audio,
             t0_us: 0,
+            next_ts_us: 0,
         })
     }
+
+    pub fn with_mode(mut self, mode: ReplayMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Pace realtime playback and idle waits on `clock` instead of wall time.
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn mode(&self) -> ReplayMode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: ReplayMode) {
+        self.mode = mode;
+    }
+
+    /// Index of the track currently playing.
+    pub fn current_track(&self) -> usize {
+        self.track
+    }
+
+    /// Playback position within the current track, in milliseconds.
+    pub fn position_ms(&self) -> u64 {
+        self.pos_to_us(self.pos) / 1000
+    }
+
+    fn pos_to_us(&self, pos: usize) -> u64 {
+        (pos as u64 / self.channels.max(1) as u64) * 1_000_000u64 / self.sample_rate.max(1) as u64
+    }
+
+    /// Re-anchor the track timeline so the sample at `pos` lands on `next_ts_us`.
+    fn anchor(&mut self) {
+        self.t0_us = self.next_ts_us as i64 - self.pos_to_us(self.pos) as i64;
+    }
+
+    /// Seek within the current track. Positions past the end clamp to the end.
+    pub fn seek_ms(&mut self, ms: u64) {
+        let frame = ms * self.sample_rate as u64 / 1000;
+        self.pos = ((frame * self.channels as u64) as usize).min(self.audio.len());
+        self.finished = false;
+        self.anchor();
+    }
+
+    /// Jump to the start of `index` in the playlist. On error the current track is kept.
+    pub fn select_track(&mut self, index: usize) -> anyhow::Result<()> {
+        let path = self
+            .playlist
+            .get(index)
+            .ok_or_else(|| anyhow::anyhow!("Track {} out of range", index))?;
+        let (sample_rate, channels, audio) = load_wav_f32(path)?;
+        self.track = index;
+        self.sample_rate = sample_rate;
+        self.channels = channels;
+        self.audio = audio;
+        self.pos = 0;
+        self.finished = false;
+        self.anchor();
+        Ok(())
+    }
+
+    /// Move on from a finished track according to the replay mode.
+    fn advance(&mut self) {
+        let next = self.track + 1;
+        let target = if next < self.playlist.len() {
+            next
+        } else if self.mode == ReplayMode::Loop {
+            0
+        } else {
+            self.finished = true;
+            return;
+        };
+        if target == self.track {
+            self.pos = 0;
+            self.anchor();
+            return;
+        }
+        if let Err(e) = self.select_track(target) {
+            log::warn!("WavReplaySource {}: stopping playback: {}", self.id, e);
+            self.finished = true;
+        }
+    }
+
+    fn eof_signal(&self) -> Signal {
+        Signal::Intent {
+            action: "eof".to_string(),
+            parameters: vec![
+                self.track.to_string(),
+                self.playlist[self.track].display().to_string(),
+            ],
+        }
+    }
+
+    fn log_error(&self, result: anyhow::Result<()>) {
+        if let Err(e) = result {
+            log::warn!("WavReplaySource {}: {}", self.id, e);
+        }
+    }
 }
 
 /// Load a WAV into interleaved f32 samples (normalized to [-1,1] for PCM int input).
@@ -113,17 +268,30 @@ impl Source for WavReplaySource {
     }
 
     fn schema(&self) -> ModuleSchema {
+        let description = match self.playlist.as_slice() {
+            [path] => format!("Replays WAV audio from {}", path.display()),
+            paths => format!("Replays a playlist of {} WAV files", paths.len()),
+        };
         ModuleSchema {
             id: self.id.clone(),
             name: "WAV Replay".to_string(),
-            description: format!("Replays WAV audio from {}", self.wav_path.display()),
-            ports: vec![Port {
-                id: "audio_out".to_string(),
-                label: "Audio Out".to_string(),
-                data_type: DataType::Audio,
-                direction: PortDirection::Output,
-            }],
+            description,
+            ports: vec![
+                Port {
+                    id: "audio_out".to_string(),
+                    label: "Audio Out".to_string(),
+                    data_type: DataType::Audio,
+                    direction: PortDirection::Output,
+                },
+                Port {
+                    id: "control_in".to_string(),
+                    label: "Transport".to_string(),
+                    data_type: DataType::Control,
+                    direction: PortDirection::Input,
+                },
+            ],
             settings_schema: None,
+            docs: None,
         }
     }
 
@@ -135,33 +303,80 @@ impl Source for WavReplaySource {
         self.enabled = enabled;
     }
 
-    async fn poll(&mut self) -> Option<Signal> {
-        if !self.enabled {
-            tokio::time::sleep(Duration::from_millis(10)).await;
-            return Some(Signal::Pulse);
+    fn handle_signal(&mut self, signal: Signal) {
+        match signal {
+            Signal::Intent { action, parameters } => match action.as_str() {
+                "seek" => {
+                    if let Some(ms) = parameters.first().and_then(|p| p.parse::<f64>().ok()) {
+                        self.seek_ms(ms.max(0.0) as u64);
+                    }
+                }
+                "next" => {
+                    let next = (self.track + 1) % self.playlist.len();
+                    let result = self.select_track(next);
+                    self.log_error(result);
+                }
+                "previous" => {
+                    let previous = self.track.checked_sub(1).unwrap_or(self.playlist.len() - 1);
+                    let result = self.select_track(previous);
+                    self.log_error(result);
+                }
+                "restart" => {
+                    let result = self.select_track(0);
+                    self.log_error(result);
+                }
+                _ => {}
+            },
+            Signal::Control(ControlSignal::Settings(settings)) => {
+                if let Some(mode) = settings
+                    .get("mode")
+                    .and_then(|v| v.as_str())
+                    .and_then(ReplayMode::parse)
+                {
+                    self.mode = mode;
+                }
+                if let Some(track) = settings.get("track").and_then(|v| v.as_u64()) {
+                    let result = self.select_track(track as usize);
+                    self.log_error(result);
+                }
+                if let Some(ms) = settings.get("seek_ms").and_then(|v| v.as_u64()) {
+                    self.seek_ms(ms);
+                }
+            }
+            _ => {}
         }
+    }
+
+    /// Finished or disabled: nothing to send until a seek, restart or
+    /// settings change comes in
+    fn is_idle(&self) -> bool {
+        !self.enabled || self.finished
+    }
 
-        if !self.started {
-            self.started = true;
-            self.t0_us = 0;
+    async fn poll(&mut self) -> Option<Signal> {
+        if self.is_idle() {
+            // The adapter doesn't poll an idle source
+            std::future::pending::<()>().await;
         }
 
         if self.pos >= self.audio.len() {
-            // End: keep emitting pulses.
-            tokio::time::sleep(Duration::from_millis(10)).await;
-            return Some(Signal::Pulse);
+            let eof = self.eof_signal();
+            self.advance();
+            return Some(eof);
         }
 
         let samples_per_chunk = (self.sample_rate as u64 * self.chunk_ms as u64 / 1000) as usize;
         let take = (samples_per_chunk * self.channels as usize).max(1);
         let end = (self.pos + take).min(self.audio.len());
         let data = self.audio[self.pos..end].to_vec();
-        let ts_us = self.t0_us
-            + ((self.pos as u64 / self.channels as u64) * 1_000_000u64 / self.sample_rate as u64);
+        let ts_us = (self.t0_us + self.pos_to_us(self.pos) as i64).max(0) as u64;
         self.pos = end;
+        self.next_ts_us = (self.t0_us + self.pos_to_us(self.pos) as i64).max(0) as u64;
 
         if self.realtime {
-            tokio::time::sleep(Duration::from_millis(self.chunk_ms as u64)).await;
+            self.clock
+                .sleep(Duration::from_millis(self.chunk_ms as u64))
+                .await;
         } else {
             tokio::task::yield_now().await;
         }
@@ -174,3 +389,91 @@ impl Source for WavReplaySource {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_wav(name: &str, frames: usize) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("magnolia_replay_{name}.wav"));
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 1_000,
+            bits_per_sample: 32,
+            sample_format: hound::SampleFormat::Float,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for i in 0..frames {
+            writer.write_sample(i as f32 / frames as f32).unwrap();
+        }
+        writer.finalize().unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn playlist_emits_eof_per_track_and_keeps_timestamps_monotonic() {
+        let a = write_wav("playlist_a", 20);
+        let b = write_wav("playlist_b", 20);
+        let mut source = WavReplaySource::with_playlist("replay", vec![a, b], 10, false).unwrap();
+
+        let mut last_ts = None;
+        let mut eofs = Vec::new();
+        while eofs.len() < 2 {
+            match source.poll().await.unwrap() {
+                Signal::Audio { timestamp_us, .. } => {
+                    assert!(last_ts.is_none_or(|last| timestamp_us > last));
+                    last_ts = Some(timestamp_us);
+                }
+                Signal::Intent { action, parameters } => {
+                    assert_eq!(action, "eof");
+                    eofs.push(parameters[0].clone());
+                }
+                other => panic!("unexpected signal {other:?}"),
+            }
+        }
+        assert_eq!(eofs, vec!["0", "1"]);
+        assert_eq!(last_ts, Some(30_000));
+        assert!(source.is_idle());
+    }
+
+    #[tokio::test]
+    async fn realtime_replay_on_virtual_clock_runs_without_waiting() {
+        let path = write_wav("virtual", 2_000);
+        let clock = magnolia_core::VirtualClock::new();
+        let mut source = WavReplaySource::new("replay", path, 100, true)
+            .unwrap()
+            .with_clock(clock.clone());
+        let wall = std::time::Instant::now();
+
+        let mut chunks = 0;
+        while let Signal::Audio { .. } = source.poll().await.unwrap() {
+            chunks += 1;
+        }
+
+        assert_eq!(chunks, 20);
+        assert_eq!(magnolia_core::Clock::now_us(clock.as_ref()), 2_000_000);
+        assert!(wall.elapsed() < std::time::Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn loop_mode_wraps_and_seek_moves_position() {
+        let path = write_wav("loop", 40);
+        let mut source = WavReplaySource::new("replay", path, 10, false)
+            .unwrap()
+            .with_mode(ReplayMode::Loop);
+
+        source.handle_signal(Signal::Intent {
+            action: "seek".to_string(),
+            parameters: vec!["30".to_string()],
+        });
+        assert_eq!(source.position_ms(), 30);
+
+        assert!(matches!(source.poll().await.unwrap(), Signal::Audio { .. }));
+        assert!(matches!(
+            source.poll().await.unwrap(),
+            Signal::Intent { .. }
+        ));
+        assert_eq!(source.position_ms(), 0);
+        assert!(matches!(source.poll().await.unwrap(), Signal::Audio { .. }));
+    }
+}
@@ -0,0 +1,93 @@
+use magnolia_core::LibraryError;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::lame::Mp3Encoder;
+use crate::opus::OggOpusEncoder;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Codec {
+    Opus,
+    Mp3,
+}
+
+impl Codec {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Codec::Opus => "audio/ogg",
+            Codec::Mp3 => "audio/mpeg",
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum EncodeError {
+    #[error("could not load {library}: {message}")]
+    Library { library: String, message: String },
+    #[error("{0}")]
+    Encoder(String),
+}
+
+impl From<LibraryError> for EncodeError {
+    fn from(e: LibraryError) -> Self {
+        Self::Library {
+            library: e.library,
+            message: e.message,
+        }
+    }
+}
+
+/// Interleaved f32 in, stream bytes out
+pub trait Encoder: Send {
+    /// Bytes a new listener needs before any audio (Ogg headers)
+    fn header(&mut self) -> Vec<u8> {
+        Vec::new()
+    }
+
+    fn encode(&mut self, samples: &[f32]) -> Result<Vec<u8>, EncodeError>;
+
+    /// Whatever the encoder still holds, at the end of a stream
+    fn finish(&mut self) -> Vec<u8> {
+        Vec::new()
+    }
+}
+
+pub fn open(
+    codec: Codec,
+    sample_rate: u32,
+    channels: u16,
+    bitrate_kbps: u32,
+) -> Result<Box<dyn Encoder>, EncodeError> {
+    Ok(match codec {
+        Codec::Opus => Box::new(OggOpusEncoder::new(sample_rate, channels, bitrate_kbps)?),
+        Codec::Mp3 => Box::new(Mp3Encoder::new(sample_rate, channels, bitrate_kbps)?),
+    })
+}
+
+/// The first two channels of interleaved audio (or the only one): the
+/// encoders take mono or stereo
+pub fn fit_channels(samples: &[f32], channels: u16) -> (Vec<f32>, u16) {
+    match channels {
+        0 | 1 => (samples.to_vec(), 1),
+        2 => (samples.to_vec(), 2),
+        n => (
+            samples
+                .chunks_exact(n as usize)
+                .flat_map(|frame| [frame[0], frame[1]])
+                .collect(),
+            2,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_front_pair() {
+        let (stereo, channels) = fit_channels(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0], 3);
+        assert_eq!((stereo, channels), (vec![1.0, 2.0, 4.0, 5.0], 2));
+        assert_eq!(fit_channels(&[0.5], 1).1, 1);
+    }
+}
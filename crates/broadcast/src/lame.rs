@@ -0,0 +1,147 @@
+//! MP3 on libmp3lame. LAME resamples rates MP3 can't carry itself.
+
+use libloading::Library;
+use magnolia_core::native_library::{load_library, symbol};
+use std::ffi::{c_int, c_void};
+
+use crate::encoder::{fit_channels, EncodeError, Encoder};
+
+type InitFn = unsafe extern "C" fn() -> *mut c_void;
+type SetIntFn = unsafe extern "C" fn(*mut c_void, c_int) -> c_int;
+type InitParamsFn = unsafe extern "C" fn(*mut c_void) -> c_int;
+type EncodeFloatFn =
+    unsafe extern "C" fn(*mut c_void, *const f32, *const f32, c_int, *mut u8, c_int) -> c_int;
+type FlushFn = unsafe extern "C" fn(*mut c_void, *mut u8, c_int) -> c_int;
+type CloseFn = unsafe extern "C" fn(*mut c_void) -> c_int;
+
+const LIBRARY_NAMES: &[&str] = if cfg!(windows) {
+    &["libmp3lame.dll", "mp3lame.dll"]
+} else if cfg!(target_os = "macos") {
+    &[
+        "libmp3lame.0.dylib",
+        "/opt/homebrew/lib/libmp3lame.0.dylib",
+        "/usr/local/lib/libmp3lame.0.dylib",
+    ]
+} else {
+    &["libmp3lame.so.0", "libmp3lame.so"]
+};
+
+/// `lame_set_quality`: 0 best .. 9 fastest; 5 is LAME's "good"
+const QUALITY: c_int = 5;
+
+pub struct Mp3Encoder {
+    state: *mut c_void,
+    encode_float: EncodeFloatFn,
+    flush: FlushFn,
+    close: CloseFn,
+    _library: Library,
+    channels: u16,
+}
+
+// The LAME state is only touched through `&mut self`
+unsafe impl Send for Mp3Encoder {}
+
+/// Worst case from lame.h: 1.25 * samples + 7200
+fn output_capacity(frames: usize) -> usize {
+    frames + frames / 4 + 7200
+}
+
+impl Mp3Encoder {
+    pub fn new(sample_rate: u32, channels: u16, bitrate_kbps: u32) -> Result<Self, EncodeError> {
+        let library = load_library("libmp3lame", LIBRARY_NAMES)?;
+        // Safety: the signatures match lame.h
+        let (init, set_rate, set_channels, set_bitrate, set_quality, init_params) = unsafe {
+            (
+                symbol::<InitFn>(&library, b"lame_init\0")?,
+                symbol::<SetIntFn>(&library, b"lame_set_in_samplerate\0")?,
+                symbol::<SetIntFn>(&library, b"lame_set_num_channels\0")?,
+                symbol::<SetIntFn>(&library, b"lame_set_brate\0")?,
+                symbol::<SetIntFn>(&library, b"lame_set_quality\0")?,
+                symbol::<InitParamsFn>(&library, b"lame_init_params\0")?,
+            )
+        };
+        let (encode_float, flush, close) = unsafe {
+            (
+                symbol::<EncodeFloatFn>(&library, b"lame_encode_buffer_ieee_float\0")?,
+                symbol::<FlushFn>(&library, b"lame_encode_flush\0")?,
+                symbol::<CloseFn>(&library, b"lame_close\0")?,
+            )
+        };
+        let channels = channels.clamp(1, 2);
+        // Safety: lame_init has no preconditions; the setters take the state
+        // it returned
+        unsafe {
+            let state = init();
+            if state.is_null() {
+                return Err(EncodeError::Encoder("lame_init failed".to_string()));
+            }
+            set_rate(state, sample_rate as c_int);
+            set_channels(state, channels as c_int);
+            set_bitrate(state, bitrate_kbps.clamp(32, 320) as c_int);
+            set_quality(state, QUALITY);
+            let result = init_params(state);
+            if result < 0 {
+                close(state);
+                return Err(EncodeError::Encoder(format!(
+                    "LAME rejected {} Hz / {} kbps ({})",
+                    sample_rate, bitrate_kbps, result
+                )));
+            }
+            Ok(Self {
+                state,
+                encode_float,
+                flush,
+                close,
+                _library: library,
+                channels,
+            })
+        }
+    }
+}
+
+impl Encoder for Mp3Encoder {
+    fn encode(&mut self, samples: &[f32]) -> Result<Vec<u8>, EncodeError> {
+        let (samples, channels) = fit_channels(samples, self.channels);
+        let (left, right): (Vec<f32>, Vec<f32>) = if channels == 2 {
+            samples.chunks_exact(2).map(|f| (f[0], f[1])).unzip()
+        } else {
+            (samples.clone(), samples)
+        };
+        let mut out = vec![0u8; output_capacity(left.len())];
+        // Safety: both channel buffers hold `left.len()` samples and `out`
+        // is at least LAME's documented worst case
+        let len = unsafe {
+            (self.encode_float)(
+                self.state,
+                left.as_ptr(),
+                right.as_ptr(),
+                left.len() as c_int,
+                out.as_mut_ptr(),
+                out.len() as c_int,
+            )
+        };
+        if len < 0 {
+            return Err(EncodeError::Encoder(format!(
+                "lame_encode_buffer_ieee_float failed ({})",
+                len
+            )));
+        }
+        out.truncate(len as usize);
+        Ok(out)
+    }
+
+    fn finish(&mut self) -> Vec<u8> {
+        let mut out = vec![0u8; 7200];
+        // Safety: `out` is the size passed in
+        let len = unsafe { (self.flush)(self.state, out.as_mut_ptr(), out.len() as c_int) };
+        out.truncate(len.max(0) as usize);
+        out
+    }
+}
+
+impl Drop for Mp3Encoder {
+    fn drop(&mut self) {
+        // Safety: created in `new`, closed exactly once
+        unsafe { (self.close)(self.state) };
+    }
+}
@@ -0,0 +1,30 @@
+//! Broadcast sink: streams incoming audio to an Icecast mount or an RTMP
+//! endpoint for radio-style installations.
+//!
+//! Audio is queued by the sink and encoded on a worker thread: Ogg Opus or
+//! MP3 for Icecast, MP3 in FLV for RTMP. libopus and libmp3lame are loaded
+//! at run time, so a missing codec shows up as a broadcast error rather
+//! than a build dependency. The worker reconnects with a backoff and keeps
+//! the stats the tile shows.
+
+mod encoder;
+mod icecast;
+mod lame;
+mod ogg;
+mod opus;
+mod queue;
+mod resample;
+mod rtmp;
+mod settings;
+mod sink;
+mod target;
+#[cfg(feature = "tile-rendering")]
+mod tile;
+mod worker;
+
+pub use encoder::{Codec, EncodeError, Encoder};
+pub use settings::{BroadcastConfig, BroadcastSettings, BroadcastState, BroadcastStats};
+pub use sink::BroadcastSink;
+pub use target::Target;
+#[cfg(feature = "tile-rendering")]
+pub use tile::BroadcastTile;
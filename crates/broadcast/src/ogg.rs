@@ -0,0 +1,131 @@
+//! Ogg pages (RFC 3533), one logical stream per connection.
+
+const HEADER_BOS: u8 = 0x02;
+
+/// Lacing values a single page can hold
+const MAX_SEGMENTS: usize = 255;
+
+const CRC_TABLE: [u32; 256] = crc_table();
+
+const fn crc_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut r = (i as u32) << 24;
+        let mut bit = 0;
+        while bit < 8 {
+            r = if r & 0x8000_0000 != 0 {
+                (r << 1) ^ 0x04C1_1DB7
+            } else {
+                r << 1
+            };
+            bit += 1;
+        }
+        table[i] = r;
+        i += 1;
+    }
+    table
+}
+
+/// Ogg's CRC-32: polynomial 0x04C11DB7, unreflected, no pre/post inversion
+pub fn crc(data: &[u8]) -> u32 {
+    data.iter().fold(0u32, |crc, &byte| {
+        (crc << 8) ^ CRC_TABLE[((crc >> 24) as u8 ^ byte) as usize]
+    })
+}
+
+fn lacing(len: usize) -> impl Iterator<Item = u8> {
+    std::iter::repeat_n(255, len / 255).chain(std::iter::once((len % 255) as u8))
+}
+
+pub struct OggStream {
+    serial: u32,
+    sequence: u32,
+}
+
+impl OggStream {
+    pub fn new(serial: u32) -> Self {
+        Self {
+            serial,
+            sequence: 0,
+        }
+    }
+
+    /// Pages holding whole `(packet, granule at its end)`s; the stream's
+    /// first page is marked beginning-of-stream
+    pub fn pages(&mut self, packets: &[(Vec<u8>, u64)]) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut start = 0;
+        while start < packets.len() {
+            let mut end = start;
+            let mut segments = 0;
+            while end < packets.len() {
+                let needed = packets[end].0.len() / 255 + 1;
+                if segments + needed > MAX_SEGMENTS && end > start {
+                    break;
+                }
+                segments += needed;
+                end += 1;
+            }
+            self.page(&packets[start..end], &mut out);
+            start = end;
+        }
+        out
+    }
+
+    fn page(&mut self, packets: &[(Vec<u8>, u64)], out: &mut Vec<u8>) {
+        let granule = packets.last().map_or(0, |(_, granule)| *granule);
+        let start = out.len();
+        out.extend_from_slice(b"OggS");
+        out.push(0);
+        out.push(if self.sequence == 0 { HEADER_BOS } else { 0 });
+        out.extend_from_slice(&granule.to_le_bytes());
+        out.extend_from_slice(&self.serial.to_le_bytes());
+        out.extend_from_slice(&self.sequence.to_le_bytes());
+        let crc_at = out.len();
+        out.extend_from_slice(&[0; 4]);
+        let lacing: Vec<u8> = packets.iter().flat_map(|(p, _)| lacing(p.len())).collect();
+        out.push(lacing.len() as u8);
+        out.extend_from_slice(&lacing);
+        for (packet, _) in packets {
+            out.extend_from_slice(packet);
+        }
+        let checksum = crc(&out[start..]);
+        out[crc_at..crc_at + 4].copy_from_slice(&checksum.to_le_bytes());
+        self.sequence += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pages_are_laced_and_checksummed() {
+        assert_eq!(crc(b"123456789"), 0x89A1_897F);
+
+        let mut stream = OggStream::new(7);
+        let page = stream.pages(&[(vec![1; 300], 480), (vec![2; 255], 960)]);
+        assert_eq!(&page[..4], b"OggS");
+        assert_eq!(page[5], HEADER_BOS);
+        assert_eq!(u64::from_le_bytes(page[6..14].try_into().unwrap()), 960);
+        // 300 = 255 + 45; 255 = 255 + 0
+        assert_eq!(&page[26..31], &[4, 255, 45, 255, 0]);
+        assert_eq!(page.len(), 27 + 4 + 555);
+
+        let stored = u32::from_le_bytes(page[22..26].try_into().unwrap());
+        let mut zeroed = page.clone();
+        zeroed[22..26].copy_from_slice(&[0; 4]);
+        assert_eq!(crc(&zeroed), stored);
+
+        // Too many segments for one page: split between packets
+        let packets: Vec<_> = (1..=300).map(|i| (vec![0; 10], i)).collect();
+        let pages = stream.pages(&packets);
+        assert_eq!(pages[5], 0);
+        assert_eq!(pages[26], 255);
+        assert_eq!(u64::from_le_bytes(pages[6..14].try_into().unwrap()), 255);
+        let second = 27 + 255 + 2550;
+        assert_eq!(&pages[second..second + 4], b"OggS");
+        assert_eq!(pages[second + 26], 45);
+    }
+}
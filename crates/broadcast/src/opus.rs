@@ -0,0 +1,210 @@
+//! Ogg Opus (RFC 7845) on libopus. Opus always runs at 48 kHz here; other
+//! input rates are resampled first.
+
+use libloading::Library;
+use magnolia_core::native_library::{load_library, symbol};
+use std::ffi::{c_int, c_void};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::encoder::{fit_channels, EncodeError, Encoder};
+use crate::ogg::OggStream;
+use crate::resample::Resampler;
+
+const OPUS_RATE: u32 = 48_000;
+/// 20 ms
+const FRAME: usize = 960;
+const MAX_PACKET: usize = 4000;
+
+const OPUS_APPLICATION_AUDIO: c_int = 2049;
+const OPUS_SET_BITRATE_REQUEST: c_int = 4002;
+const OPUS_GET_LOOKAHEAD_REQUEST: c_int = 4027;
+
+type CreateFn = unsafe extern "C" fn(i32, c_int, c_int, *mut c_int) -> *mut c_void;
+type EncodeFloatFn = unsafe extern "C" fn(*mut c_void, *const f32, c_int, *mut u8, i32) -> i32;
+type CtlFn = unsafe extern "C" fn(*mut c_void, c_int, ...) -> c_int;
+type DestroyFn = unsafe extern "C" fn(*mut c_void);
+
+const LIBRARY_NAMES: &[&str] = if cfg!(windows) {
+    &["opus.dll", "libopus-0.dll"]
+} else if cfg!(target_os = "macos") {
+    &[
+        "libopus.0.dylib",
+        "/opt/homebrew/lib/libopus.0.dylib",
+        "/usr/local/lib/libopus.0.dylib",
+    ]
+} else {
+    &["libopus.so.0", "libopus.so"]
+};
+
+pub struct OggOpusEncoder {
+    state: *mut c_void,
+    encode_float: EncodeFloatFn,
+    destroy: DestroyFn,
+    _library: Library,
+    channels: u16,
+    input_rate: u32,
+    pre_skip: u16,
+    resampler: Option<Resampler>,
+    /// Samples short of a whole frame, carried to the next call
+    pending: Vec<f32>,
+    granule: u64,
+    ogg: OggStream,
+}
+
+// The encoder state is only touched through `&mut self`
+unsafe impl Send for OggOpusEncoder {}
+
+impl OggOpusEncoder {
+    pub fn new(sample_rate: u32, channels: u16, bitrate_kbps: u32) -> Result<Self, EncodeError> {
+        let library = load_library("libopus", LIBRARY_NAMES)?;
+        // Safety: the signatures match opus.h
+        let (create, encode_float, ctl, destroy) = unsafe {
+            (
+                symbol::<CreateFn>(&library, b"opus_encoder_create\0")?,
+                symbol::<EncodeFloatFn>(&library, b"opus_encode_float\0")?,
+                symbol::<CtlFn>(&library, b"opus_encoder_ctl\0")?,
+                symbol::<DestroyFn>(&library, b"opus_encoder_destroy\0")?,
+            )
+        };
+        let channels = channels.clamp(1, 2);
+        let mut error: c_int = 0;
+        // Safety: valid rate, channel count and out pointer
+        let state = unsafe {
+            create(
+                OPUS_RATE as i32,
+                channels as c_int,
+                OPUS_APPLICATION_AUDIO,
+                &mut error,
+            )
+        };
+        if state.is_null() || error != 0 {
+            return Err(EncodeError::Encoder(format!(
+                "opus_encoder_create failed ({})",
+                error
+            )));
+        }
+        let mut lookahead: i32 = 312;
+        // Safety: both requests take one argument of the documented type
+        unsafe {
+            ctl(
+                state,
+                OPUS_SET_BITRATE_REQUEST,
+                (bitrate_kbps.clamp(6, 510) * 1000) as i32,
+            );
+            ctl(
+                state,
+                OPUS_GET_LOOKAHEAD_REQUEST,
+                &mut lookahead as *mut i32,
+            );
+        }
+        let serial = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.subsec_nanos())
+            .unwrap_or(1);
+        Ok(Self {
+            state,
+            encode_float,
+            destroy,
+            _library: library,
+            channels,
+            input_rate: sample_rate,
+            pre_skip: lookahead.clamp(0, u16::MAX as i32) as u16,
+            resampler: (sample_rate != OPUS_RATE)
+                .then(|| Resampler::new(sample_rate, OPUS_RATE, channels)),
+            pending: Vec::new(),
+            granule: 0,
+            ogg: OggStream::new(serial),
+        })
+    }
+}
+
+/// RFC 7845 §5.1, channel mapping family 0
+fn opus_head(channels: u16, pre_skip: u16, input_rate: u32) -> Vec<u8> {
+    let mut head = b"OpusHead".to_vec();
+    head.push(1);
+    head.push(channels as u8);
+    head.extend_from_slice(&pre_skip.to_le_bytes());
+    head.extend_from_slice(&input_rate.to_le_bytes());
+    head.extend_from_slice(&0i16.to_le_bytes());
+    head.push(0);
+    head
+}
+
+fn opus_tags() -> Vec<u8> {
+    let vendor = b"magnolia";
+    let mut tags = b"OpusTags".to_vec();
+    tags.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+    tags.extend_from_slice(vendor);
+    tags.extend_from_slice(&0u32.to_le_bytes());
+    tags
+}
+
+impl Encoder for OggOpusEncoder {
+    fn header(&mut self) -> Vec<u8> {
+        // Each header packet gets a page of its own
+        let mut out = self
+            .ogg
+            .pages(&[(opus_head(self.channels, self.pre_skip, self.input_rate), 0)]);
+        out.extend(self.ogg.pages(&[(opus_tags(), 0)]));
+        out
+    }
+
+    fn encode(&mut self, samples: &[f32]) -> Result<Vec<u8>, EncodeError> {
+        let (samples, _) = fit_channels(samples, self.channels);
+        match &mut self.resampler {
+            Some(resampler) => self.pending.extend(resampler.process(&samples)),
+            None => self.pending.extend_from_slice(&samples),
+        }
+        let frame_len = FRAME * self.channels as usize;
+        let mut packets = Vec::new();
+        let mut packet = [0u8; MAX_PACKET];
+        let mut offset = 0;
+        while self.pending.len() - offset >= frame_len {
+            // Safety: the input holds a whole frame and the output buffer is
+            // the size passed in
+            let len = unsafe {
+                (self.encode_float)(
+                    self.state,
+                    self.pending[offset..].as_ptr(),
+                    FRAME as c_int,
+                    packet.as_mut_ptr(),
+                    MAX_PACKET as i32,
+                )
+            };
+            if len < 0 {
+                return Err(EncodeError::Encoder(format!(
+                    "opus_encode_float failed ({})",
+                    len
+                )));
+            }
+            offset += frame_len;
+            self.granule += FRAME as u64;
+            packets.push((packet[..len as usize].to_vec(), self.granule));
+        }
+        self.pending.drain(..offset);
+        Ok(self.ogg.pages(&packets))
+    }
+}
+
+impl Drop for OggOpusEncoder {
+    fn drop(&mut self) {
+        // Safety: created in `new`, destroyed exactly once
+        unsafe { (self.destroy)(self.state) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identification_header_layout() {
+        let head = opus_head(2, 312, 44_100);
+        assert_eq!(head.len(), 19);
+        assert_eq!(&head[..8], b"OpusHead");
+        assert_eq!(head[9], 2);
+        assert_eq!(u16::from_le_bytes([head[10], head[11]]), 312);
+        assert_eq!(u32::from_le_bytes(head[12..16].try_into().unwrap()), 44_100);
+        assert_eq!(&opus_tags()[..8], b"OpusTags");
+    }
+}
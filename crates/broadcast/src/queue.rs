@@ -0,0 +1,170 @@
+use std::collections::VecDeque;
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+pub struct AudioChunk {
+    pub sample_rate: u32,
+    pub channels: u16,
+    /// Interleaved
+    pub samples: Vec<f32>,
+}
+
+impl AudioChunk {
+    pub fn frames(&self) -> usize {
+        self.samples.len() / self.channels.max(1) as usize
+    }
+}
+
+#[derive(Default)]
+struct QueueState {
+    sample_rate: u32,
+    channels: u16,
+    samples: VecDeque<f32>,
+    capacity_secs: f32,
+    dropped_frames: u64,
+}
+
+impl QueueState {
+    fn samples_per_sec(&self) -> f32 {
+        (self.sample_rate.max(1) as f32) * self.channels.max(1) as f32
+    }
+}
+
+/// Audio waiting for the network: rides out slow sends and reconnects up to
+/// its capacity, then drops the oldest audio so the stream stays near live
+pub struct AudioQueue {
+    state: Mutex<QueueState>,
+    ready: Condvar,
+}
+
+impl AudioQueue {
+    pub fn new(capacity_secs: f32) -> Self {
+        Self {
+            state: Mutex::new(QueueState {
+                capacity_secs,
+                ..QueueState::default()
+            }),
+            ready: Condvar::new(),
+        }
+    }
+
+    pub fn set_capacity_secs(&self, capacity_secs: f32) {
+        if let Ok(mut state) = self.state.lock() {
+            state.capacity_secs = capacity_secs;
+        }
+    }
+
+    /// Queue interleaved samples; a change of format starts over
+    pub fn push(&self, sample_rate: u32, channels: u16, data: &[f32]) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        if (state.sample_rate, state.channels) != (sample_rate, channels) {
+            state.samples.clear();
+            state.sample_rate = sample_rate;
+            state.channels = channels;
+        }
+        state.samples.extend(data);
+        let channels = channels.max(1) as usize;
+        let capacity = (state.capacity_secs.max(0.1) * state.samples_per_sec()) as usize;
+        let capacity = capacity - capacity % channels;
+        if state.samples.len() > capacity {
+            let excess = state.samples.len() - capacity;
+            state.samples.drain(..excess);
+            state.dropped_frames += (excess / channels) as u64;
+        }
+        self.ready.notify_all();
+    }
+
+    /// Format of the queued audio, waiting up to `wait` for some to arrive
+    pub fn wait_format(&self, wait: Duration) -> Option<(u32, u16)> {
+        let state = self.state.lock().ok()?;
+        let (state, _) = self
+            .ready
+            .wait_timeout_while(state, wait, |state| state.samples.is_empty())
+            .ok()?;
+        (!state.samples.is_empty()).then_some((state.sample_rate, state.channels))
+    }
+
+    /// Up to `duration` of audio, once that much is queued or `wait` has
+    /// passed with something queued
+    pub fn pop(&self, duration: Duration, wait: Duration) -> Option<AudioChunk> {
+        let mut state = self.state.lock().ok()?;
+        let deadline = Instant::now() + wait;
+        loop {
+            let wanted = (duration.as_secs_f32() * state.samples_per_sec()) as usize;
+            let wanted = wanted.max(1);
+            let now = Instant::now();
+            if state.samples.len() >= wanted || (now >= deadline && !state.samples.is_empty()) {
+                let channels = state.channels.max(1) as usize;
+                let take = state.samples.len().min(wanted);
+                let take = take - take % channels;
+                if take == 0 {
+                    return None;
+                }
+                return Some(AudioChunk {
+                    sample_rate: state.sample_rate,
+                    channels: state.channels,
+                    samples: state.samples.drain(..take).collect(),
+                });
+            }
+            if now >= deadline {
+                return None;
+            }
+            state = self.ready.wait_timeout(state, deadline - now).ok()?.0;
+        }
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.samples.clear();
+        }
+    }
+
+    pub fn buffered_secs(&self) -> f32 {
+        self.state
+            .lock()
+            .map(|s| s.samples.len() as f32 / s.samples_per_sec())
+            .unwrap_or(0.0)
+    }
+
+    pub fn dropped_secs(&self) -> f32 {
+        self.state
+            .lock()
+            .map(|s| s.dropped_frames as f32 / s.sample_rate.max(1) as f32)
+            .unwrap_or(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounded_and_chunked() {
+        // 1 s at 100 Hz stereo
+        let queue = AudioQueue::new(1.0);
+        assert_eq!(queue.wait_format(Duration::ZERO), None);
+        queue.push(100, 2, &[0.5; 300]);
+        assert_eq!(queue.wait_format(Duration::ZERO), Some((100, 2)));
+        // 150 frames into 100: the oldest 50 go
+        assert!((queue.buffered_secs() - 1.0).abs() < 1e-6);
+        assert!((queue.dropped_secs() - 0.5).abs() < 1e-6);
+
+        let chunk = queue
+            .pop(Duration::from_millis(300), Duration::ZERO)
+            .unwrap();
+        assert_eq!(
+            (chunk.sample_rate, chunk.channels, chunk.frames()),
+            (100, 2, 30)
+        );
+
+        // A new format drops what was queued
+        queue.push(50, 1, &[0.1; 10]);
+        let chunk = queue.pop(Duration::from_secs(1), Duration::ZERO).unwrap();
+        assert_eq!((chunk.sample_rate, chunk.frames()), (50, 10));
+        assert!(queue
+            .pop(Duration::from_secs(1), Duration::from_millis(10))
+            .is_none());
+    }
+}
@@ -0,0 +1,70 @@
+/// Streaming linear resampler over interleaved frames. Plenty for feeding
+/// an encoder that only takes certain rates; not for mastering.
+pub struct Resampler {
+    channels: usize,
+    /// Input frames per output frame
+    step: f64,
+    /// Position of the next output frame within `pending`
+    position: f64,
+    pending: Vec<f32>,
+}
+
+impl Resampler {
+    pub fn new(from_rate: u32, to_rate: u32, channels: u16) -> Self {
+        Self {
+            channels: channels.max(1) as usize,
+            step: from_rate.max(1) as f64 / to_rate.max(1) as f64,
+            position: 0.0,
+            pending: Vec::new(),
+        }
+    }
+
+    pub fn process(&mut self, input: &[f32]) -> Vec<f32> {
+        let channels = self.channels;
+        self.pending.extend_from_slice(input);
+        let frames = self.pending.len() / channels;
+        let mut out = Vec::with_capacity((input.len() as f64 / self.step) as usize + channels);
+        // Interpolating needs the frame after the position too
+        while self.position + 1.0 < frames as f64 {
+            let index = self.position as usize;
+            let t = (self.position - index as f64) as f32;
+            for c in 0..channels {
+                let a = self.pending[index * channels + c];
+                let b = self.pending[(index + 1) * channels + c];
+                out.push(a + (b - a) * t);
+            }
+            self.position += self.step;
+        }
+        let consumed = (self.position as usize).min(frames);
+        self.pending.drain(..consumed * channels);
+        self.position -= consumed as f64;
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resamples_across_chunks() {
+        // Stereo ramp, 1 → 2x rate
+        let mut up = Resampler::new(24_000, 48_000, 2);
+        let input: Vec<f32> = (0..8).flat_map(|i| [i as f32, -(i as f32)]).collect();
+        let mut out = up.process(&input[..6]);
+        out.extend(up.process(&input[6..]));
+        let left: Vec<f32> = out.chunks(2).map(|f| f[0]).collect();
+        assert_eq!(
+            left,
+            vec![0.0, 0.5, 1.0, 1.5, 2.0, 2.5, 3.0, 3.5, 4.0, 4.5, 5.0, 5.5, 6.0, 6.5]
+        );
+        assert!(out.chunks(2).all(|f| f[1] == -f[0]));
+
+        // 44.1k → 48k keeps roughly the duration
+        let mut resampler = Resampler::new(44_100, 48_000, 1);
+        let produced: usize = (0..10)
+            .map(|_| resampler.process(&[0.25; 4_410]).len())
+            .sum();
+        assert!((47_990..=48_000).contains(&produced), "{}", produced);
+    }
+}
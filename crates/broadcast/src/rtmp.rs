@@ -0,0 +1,518 @@
+//! Minimal RTMP publisher: handshake, `connect` / `createStream` / `publish`
+//! and MP3 audio messages (FLV sound format 2). Audio-only streams suit
+//! media servers (nginx-rtmp, MediaMTX, Owncast); most video platforms
+//! insist on a video track too.
+
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use crate::worker::Output;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+const HANDSHAKE_SIZE: usize = 1536;
+const OUR_CHUNK_SIZE: u32 = 4096;
+
+const TYPE_SET_CHUNK_SIZE: u8 = 1;
+const TYPE_USER_CONTROL: u8 = 4;
+const TYPE_AUDIO: u8 = 8;
+const TYPE_DATA_AMF0: u8 = 18;
+const TYPE_COMMAND_AMF0: u8 = 20;
+
+const CSID_CONTROL: u32 = 2;
+const CSID_COMMAND: u32 = 3;
+const CSID_AUDIO: u32 = 4;
+
+/// FLV audio tag header: MP3, "44 kHz" (the decoder reads the real rate
+/// from the frames), 16-bit
+const FLV_MP3: u8 = (2 << 4) | (3 << 2) | (1 << 1);
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Amf {
+    Number(f64),
+    Bool(bool),
+    String(String),
+    Object(Vec<(String, Amf)>),
+    Null,
+}
+
+impl Amf {
+    fn str(value: &str) -> Self {
+        Amf::String(value.to_string())
+    }
+
+    fn get(&self, key: &str) -> Option<&Amf> {
+        match self {
+            Amf::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Amf::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+fn encode_key(out: &mut Vec<u8>, key: &str) {
+    out.extend_from_slice(&(key.len() as u16).to_be_bytes());
+    out.extend_from_slice(key.as_bytes());
+}
+
+pub(crate) fn encode_amf(values: &[Amf]) -> Vec<u8> {
+    fn encode(out: &mut Vec<u8>, value: &Amf) {
+        match value {
+            Amf::Number(n) => {
+                out.push(0x00);
+                out.extend_from_slice(&n.to_be_bytes());
+            }
+            Amf::Bool(b) => out.extend_from_slice(&[0x01, *b as u8]),
+            Amf::String(s) => {
+                out.push(0x02);
+                encode_key(out, s);
+            }
+            Amf::Object(fields) => {
+                out.push(0x03);
+                for (key, value) in fields {
+                    encode_key(out, key);
+                    encode(out, value);
+                }
+                out.extend_from_slice(&[0, 0, 0x09]);
+            }
+            Amf::Null => out.push(0x05),
+        }
+    }
+    let mut out = Vec::new();
+    for value in values {
+        encode(&mut out, value);
+    }
+    out
+}
+
+/// Every value in an AMF0 message; types a publisher never needs to read
+/// stop decoding there
+pub(crate) fn decode_amf(mut data: &[u8]) -> Vec<Amf> {
+    fn take<'a>(data: &mut &'a [u8], n: usize) -> Option<&'a [u8]> {
+        let (head, rest) = (data.get(..n)?, data.get(n..)?);
+        *data = rest;
+        Some(head)
+    }
+    fn key(data: &mut &[u8]) -> Option<String> {
+        let len = u16::from_be_bytes(take(data, 2)?.try_into().ok()?) as usize;
+        Some(String::from_utf8_lossy(take(data, len)?).into_owned())
+    }
+    fn fields(data: &mut &[u8]) -> Option<Vec<(String, Amf)>> {
+        let mut fields = Vec::new();
+        loop {
+            let name = key(data)?;
+            if name.is_empty() && data.first() == Some(&0x09) {
+                take(data, 1)?;
+                return Some(fields);
+            }
+            fields.push((name, value(data)?));
+        }
+    }
+    fn value(data: &mut &[u8]) -> Option<Amf> {
+        Some(match take(data, 1)?[0] {
+            0x00 => Amf::Number(f64::from_be_bytes(take(data, 8)?.try_into().ok()?)),
+            0x01 => Amf::Bool(take(data, 1)?[0] != 0),
+            0x02 => Amf::String(key(data)?),
+            0x03 => Amf::Object(fields(data)?),
+            0x05 | 0x06 => Amf::Null,
+            0x08 => {
+                take(data, 4)?;
+                Amf::Object(fields(data)?)
+            }
+            _ => return None,
+        })
+    }
+    let mut values = Vec::new();
+    while let Some(v) = value(&mut data) {
+        values.push(v);
+    }
+    values
+}
+
+/// One message split into chunks of `chunk_size`: a full (type 0) header,
+/// then continuation (type 3) headers
+pub(crate) fn encode_chunks(
+    csid: u32,
+    type_id: u8,
+    stream_id: u32,
+    timestamp: u32,
+    payload: &[u8],
+    chunk_size: usize,
+) -> Vec<u8> {
+    let extended = timestamp >= 0xFF_FFFF;
+    let mut out = Vec::with_capacity(payload.len() + 16);
+    out.push(csid as u8 & 0x3F);
+    let field = if extended { 0xFF_FFFF } else { timestamp };
+    out.extend_from_slice(&field.to_be_bytes()[1..]);
+    out.extend_from_slice(&(payload.len() as u32).to_be_bytes()[1..]);
+    out.push(type_id);
+    out.extend_from_slice(&stream_id.to_le_bytes());
+    if extended {
+        out.extend_from_slice(&timestamp.to_be_bytes());
+    }
+    for (i, chunk) in payload.chunks(chunk_size.max(1)).enumerate() {
+        if i > 0 {
+            out.push(0xC0 | (csid as u8 & 0x3F));
+            if extended {
+                out.extend_from_slice(&timestamp.to_be_bytes());
+            }
+        }
+        out.extend_from_slice(chunk);
+    }
+    out
+}
+
+struct Message {
+    type_id: u8,
+    payload: Vec<u8>,
+}
+
+#[derive(Default, Clone)]
+struct InHeader {
+    length: usize,
+    type_id: u8,
+    extended: bool,
+}
+
+/// Reassembles the server's chunked messages
+struct ChunkReader {
+    chunk_size: usize,
+    headers: HashMap<u32, InHeader>,
+    partial: HashMap<u32, Vec<u8>>,
+}
+
+impl ChunkReader {
+    fn new() -> Self {
+        Self {
+            chunk_size: 128,
+            headers: HashMap::new(),
+            partial: HashMap::new(),
+        }
+    }
+
+    fn read(&mut self, r: &mut impl Read) -> io::Result<Message> {
+        loop {
+            let mut byte = [0u8; 1];
+            r.read_exact(&mut byte)?;
+            let fmt = byte[0] >> 6;
+            let csid = match byte[0] & 0x3F {
+                0 => {
+                    r.read_exact(&mut byte)?;
+                    64 + byte[0] as u32
+                }
+                1 => {
+                    let mut two = [0u8; 2];
+                    r.read_exact(&mut two)?;
+                    64 + two[0] as u32 + two[1] as u32 * 256
+                }
+                id => id as u32,
+            };
+            let mut header = self.headers.get(&csid).cloned().unwrap_or_default();
+            let header_len = [11, 7, 3, 0][fmt as usize];
+            let mut fields = [0u8; 11];
+            r.read_exact(&mut fields[..header_len])?;
+            if header_len >= 3 {
+                header.extended = fields[..3] == [0xFF; 3];
+            }
+            if header_len >= 7 {
+                header.length = u32::from_be_bytes([0, fields[3], fields[4], fields[5]]) as usize;
+                header.type_id = fields[6];
+            }
+            if header.extended {
+                let mut ts = [0u8; 4];
+                r.read_exact(&mut ts)?;
+            }
+            self.headers.insert(csid, header.clone());
+
+            let partial = self.partial.entry(csid).or_default();
+            let take = (header.length - partial.len().min(header.length)).min(self.chunk_size);
+            let start = partial.len();
+            partial.resize(start + take, 0);
+            r.read_exact(&mut partial[start..])?;
+            if partial.len() >= header.length {
+                let payload = std::mem::take(partial);
+                if header.type_id == TYPE_SET_CHUNK_SIZE && payload.len() >= 4 {
+                    let size = u32::from_be_bytes([payload[0], payload[1], payload[2], payload[3]]);
+                    self.chunk_size = (size & 0x7FFF_FFFF).max(1) as usize;
+                }
+                return Ok(Message {
+                    type_id: header.type_id,
+                    payload,
+                });
+            }
+        }
+    }
+}
+
+pub struct RtmpOutput {
+    stream: TcpStream,
+    stream_id: u32,
+    stereo: bool,
+}
+
+impl RtmpOutput {
+    pub fn publish(
+        host: &str,
+        port: u16,
+        app: &str,
+        key: &str,
+        sample_rate: u32,
+        channels: u16,
+        bitrate_kbps: u32,
+    ) -> io::Result<Self> {
+        let addr = (host, port)
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "host not found"))?;
+        let mut stream = TcpStream::connect_timeout(&addr, TIMEOUT)?;
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        stream.set_write_timeout(Some(TIMEOUT))?;
+        stream.set_nodelay(true)?;
+        handshake(&mut stream)?;
+
+        let mut reader = ChunkReader::new();
+        let chunk = OUR_CHUNK_SIZE as usize;
+        let send = |stream: &mut TcpStream, csid, type_id, stream_id, payload: &[u8]| {
+            stream.write_all(&encode_chunks(csid, type_id, stream_id, 0, payload, chunk))
+        };
+        // Set Chunk Size goes out under the default 128-byte chunking
+        stream.write_all(&encode_chunks(
+            CSID_CONTROL,
+            TYPE_SET_CHUNK_SIZE,
+            0,
+            0,
+            &OUR_CHUNK_SIZE.to_be_bytes(),
+            128,
+        ))?;
+
+        let tc_url = format!("rtmp://{}:{}/{}", host, port, app);
+        let connect = encode_amf(&[
+            Amf::str("connect"),
+            Amf::Number(1.0),
+            Amf::Object(vec![
+                ("app".into(), Amf::str(app)),
+                ("type".into(), Amf::str("nonprivate")),
+                (
+                    "flashVer".into(),
+                    Amf::str("FMLE/3.0 (compatible; magnolia)"),
+                ),
+                ("tcUrl".into(), Amf::str(&tc_url)),
+            ]),
+        ]);
+        send(&mut stream, CSID_COMMAND, TYPE_COMMAND_AMF0, 0, &connect)?;
+        expect_result(&mut stream, &mut reader, 1.0)?;
+
+        for (txn, command) in [(2.0, "releaseStream"), (3.0, "FCPublish")] {
+            let body = encode_amf(&[
+                Amf::str(command),
+                Amf::Number(txn),
+                Amf::Null,
+                Amf::str(key),
+            ]);
+            send(&mut stream, CSID_COMMAND, TYPE_COMMAND_AMF0, 0, &body)?;
+        }
+        let create = encode_amf(&[Amf::str("createStream"), Amf::Number(4.0), Amf::Null]);
+        send(&mut stream, CSID_COMMAND, TYPE_COMMAND_AMF0, 0, &create)?;
+        let stream_id = match expect_result(&mut stream, &mut reader, 4.0)?.get(3) {
+            Some(Amf::Number(id)) => *id as u32,
+            _ => return Err(protocol_error("createStream returned no stream id")),
+        };
+
+        let publish = encode_amf(&[
+            Amf::str("publish"),
+            Amf::Number(5.0),
+            Amf::Null,
+            Amf::str(key),
+            Amf::str("live"),
+        ]);
+        send(
+            &mut stream,
+            CSID_COMMAND,
+            TYPE_COMMAND_AMF0,
+            stream_id,
+            &publish,
+        )?;
+        loop {
+            let message = reader.read(&mut stream)?;
+            if message.type_id != TYPE_COMMAND_AMF0 {
+                continue;
+            }
+            let values = decode_amf(&message.payload);
+            if values.first().and_then(Amf::as_str) != Some("onStatus") {
+                continue;
+            }
+            let code = values
+                .get(3)
+                .and_then(|info| info.get("code"))
+                .and_then(Amf::as_str)
+                .unwrap_or_default();
+            if code == "NetStream.Publish.Start" {
+                break;
+            }
+            if code.contains("Publish") || code.contains("Failed") || code.contains("Rejected") {
+                return Err(protocol_error(&format!("publish refused: {}", code)));
+            }
+        }
+
+        let metadata = encode_amf(&[
+            Amf::str("@setDataFrame"),
+            Amf::str("onMetaData"),
+            Amf::Object(vec![
+                ("audiocodecid".into(), Amf::Number(2.0)),
+                ("audiodatarate".into(), Amf::Number(bitrate_kbps as f64)),
+                ("audiosamplerate".into(), Amf::Number(sample_rate as f64)),
+                ("stereo".into(), Amf::Bool(channels >= 2)),
+            ]),
+        ]);
+        send(
+            &mut stream,
+            CSID_AUDIO,
+            TYPE_DATA_AMF0,
+            stream_id,
+            &metadata,
+        )?;
+        Ok(Self {
+            stream,
+            stream_id,
+            stereo: channels >= 2,
+        })
+    }
+}
+
+fn protocol_error(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("RTMP: {}", message))
+}
+
+fn handshake(stream: &mut TcpStream) -> io::Result<()> {
+    let mut c0c1 = vec![0u8; 1 + HANDSHAKE_SIZE];
+    c0c1[0] = 3;
+    // time and zero fields stay 0; the rest only has to be echoed back
+    for (i, byte) in c0c1[9..].iter_mut().enumerate() {
+        *byte = (i as u32).wrapping_mul(2_654_435_761).to_be_bytes()[0];
+    }
+    stream.write_all(&c0c1)?;
+    let mut s0s1 = vec![0u8; 1 + HANDSHAKE_SIZE];
+    stream.read_exact(&mut s0s1)?;
+    if s0s1[0] != 3 {
+        return Err(protocol_error(&format!("unsupported version {}", s0s1[0])));
+    }
+    // C2 echoes S1
+    stream.write_all(&s0s1[1..])?;
+    let mut s2 = vec![0u8; HANDSHAKE_SIZE];
+    stream.read_exact(&mut s2)
+}
+
+/// Wait for `_result` (or `_error`) to transaction `txn`
+fn expect_result(
+    stream: &mut TcpStream,
+    reader: &mut ChunkReader,
+    txn: f64,
+) -> io::Result<Vec<Amf>> {
+    loop {
+        let message = reader.read(stream)?;
+        match message.type_id {
+            TYPE_COMMAND_AMF0 => {}
+            // Answer pings so the server keeps the connection
+            TYPE_USER_CONTROL if message.payload.starts_with(&[0, 6]) => {
+                let mut pong = vec![0, 7];
+                pong.extend_from_slice(&message.payload[2..]);
+                stream.write_all(&encode_chunks(
+                    CSID_CONTROL,
+                    TYPE_USER_CONTROL,
+                    0,
+                    0,
+                    &pong,
+                    OUR_CHUNK_SIZE as usize,
+                ))?;
+                continue;
+            }
+            _ => continue,
+        }
+        let values = decode_amf(&message.payload);
+        if values.get(1) != Some(&Amf::Number(txn)) {
+            continue;
+        }
+        return match values.first().and_then(Amf::as_str) {
+            Some("_result") => Ok(values),
+            _ => {
+                let description = values
+                    .get(3)
+                    .and_then(|info| info.get("description").or_else(|| info.get("code")))
+                    .and_then(Amf::as_str)
+                    .unwrap_or("command failed")
+                    .to_string();
+                Err(protocol_error(&description))
+            }
+        };
+    }
+}
+
+impl Output for RtmpOutput {
+    fn send(&mut self, data: &[u8], media_ms: u64) -> io::Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        let mut payload = Vec::with_capacity(data.len() + 1);
+        payload.push(FLV_MP3 | self.stereo as u8);
+        payload.extend_from_slice(data);
+        self.stream.write_all(&encode_chunks(
+            CSID_AUDIO,
+            TYPE_AUDIO,
+            self.stream_id,
+            media_ms as u32,
+            &payload,
+            OUR_CHUNK_SIZE as usize,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn amf_and_chunks_round_trip() {
+        let values = vec![
+            Amf::str("_result"),
+            Amf::Number(4.0),
+            Amf::Null,
+            Amf::Object(vec![
+                ("code".into(), Amf::str("NetStream.Publish.Start")),
+                ("live".into(), Amf::Bool(true)),
+            ]),
+        ];
+        let encoded = encode_amf(&values);
+        assert_eq!(&encoded[..3], &[0x02, 0x00, 0x07]);
+        assert_eq!(decode_amf(&encoded), values);
+        assert_eq!(
+            values[3].get("code").and_then(Amf::as_str),
+            Some("NetStream.Publish.Start")
+        );
+
+        // 300 bytes at 128-byte chunks: type-0 header, then two type-3 continuations
+        let payload: Vec<u8> = (0..300).map(|i| i as u8).collect();
+        let chunks = encode_chunks(CSID_AUDIO, TYPE_AUDIO, 1, 40, &payload, 128);
+        assert_eq!(chunks.len(), 12 + 300 + 2);
+        assert_eq!(chunks[0], 0x04);
+        assert_eq!(&chunks[1..4], &[0, 0, 40]);
+        assert_eq!(&chunks[4..7], &[0, 1, 44]);
+        assert_eq!(chunks[12 + 128], 0xC4);
+
+        let mut reader = ChunkReader::new();
+        let message = reader.read(&mut chunks.as_slice()).unwrap();
+        assert_eq!(message.type_id, TYPE_AUDIO);
+        assert_eq!(message.payload, payload);
+
+        let extended = encode_chunks(CSID_AUDIO, TYPE_AUDIO, 1, 0x0100_0000, &payload, 128);
+        assert_eq!(&extended[1..4], &[0xFF; 3]);
+        let message = ChunkReader::new().read(&mut extended.as_slice()).unwrap();
+        assert_eq!(message.payload.len(), 300);
+    }
+}
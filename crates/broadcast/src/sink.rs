@@ -0,0 +1,227 @@
+use async_trait::async_trait;
+use magnolia_core::{ControlSignal, DataType, ModuleSchema, Port, PortDirection, Signal, Sink};
+use std::io;
+use std::sync::Arc;
+
+use crate::settings::{BroadcastConfig, BroadcastSettings};
+use crate::worker::{self, Backends};
+
+/// Streams the audio it receives to an Icecast mount or RTMP endpoint
+pub struct BroadcastSink {
+    id: String,
+    enabled: bool,
+    settings: Arc<BroadcastSettings>,
+}
+
+impl BroadcastSink {
+    pub fn new(id: &str, settings: Arc<BroadcastSettings>) -> io::Result<Self> {
+        Self::with_backends(id, settings, Backends::native())
+    }
+
+    pub(crate) fn with_backends(
+        id: &str,
+        settings: Arc<BroadcastSettings>,
+        backends: Backends,
+    ) -> io::Result<Self> {
+        worker::spawn(&settings, backends)?;
+        Ok(Self {
+            id: id.to_string(),
+            enabled: true,
+            settings,
+        })
+    }
+
+    fn command(&self, action: &str, parameters: &[String]) {
+        if !action.eq_ignore_ascii_case("broadcast") {
+            return;
+        }
+        let enabled = match parameters.first().map(String::as_str) {
+            Some("start") | Some("on") => true,
+            Some("stop") | Some("off") => false,
+            Some("toggle") | None => !self.settings.config().enabled,
+            Some(other) => {
+                log::debug!("Broadcast: unknown command {:?}", other);
+                return;
+            }
+        };
+        self.settings.set_enabled(enabled);
+    }
+}
+
+#[async_trait]
+impl Sink for BroadcastSink {
+    fn name(&self) -> &str {
+        "Broadcast"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "Broadcast".to_string(),
+            description: "Streams audio to an Icecast mount or RTMP endpoint".to_string(),
+            ports: vec![
+                Port {
+                    id: "audio_in".to_string(),
+                    label: "Audio".to_string(),
+                    data_type: DataType::Audio,
+                    direction: PortDirection::Input,
+                },
+                Port {
+                    id: "control_in".to_string(),
+                    label: "Control".to_string(),
+                    data_type: DataType::Control,
+                    direction: PortDirection::Input,
+                },
+            ],
+            settings_schema: Some(BroadcastConfig::schema()),
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    fn render_output(&self) -> Option<String> {
+        Some(format!("Broadcast: {}", self.settings.stats().summary()))
+    }
+
+    async fn consume(&self, signal: Signal) -> magnolia_core::Result<Option<Signal>> {
+        match signal {
+            Signal::Control(ControlSignal::Settings(settings)) => {
+                match serde_json::from_value::<BroadcastConfig>(settings) {
+                    Ok(config) => self.settings.set_config(config),
+                    Err(e) => log::warn!("Broadcast: invalid settings, keeping current: {}", e),
+                }
+            }
+            _ if !self.enabled => {}
+            Signal::Audio { .. } | Signal::SharedAudio { .. } => {
+                if let Some(audio) = signal.audio() {
+                    self.settings
+                        .push_audio(audio.sample_rate, audio.channels, audio.data);
+                }
+            }
+            Signal::Intent { action, parameters } => self.command(&action, &parameters),
+            Signal::Text(text) => {
+                let words: Vec<String> = text.split_whitespace().map(str::to_string).collect();
+                if let Some((action, parameters)) = words.split_first() {
+                    self.command(action, parameters);
+                }
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoder::{Codec, EncodeError, Encoder};
+    use crate::settings::BroadcastState;
+    use crate::target::Target;
+    use crate::worker::Output;
+    use std::sync::Mutex;
+    use std::time::{Duration, Instant};
+
+    /// One byte per frame, so byte counts are frame counts
+    struct FakeEncoder(u16);
+
+    impl Encoder for FakeEncoder {
+        fn header(&mut self) -> Vec<u8> {
+            b"HEAD".to_vec()
+        }
+
+        fn encode(&mut self, samples: &[f32]) -> Result<Vec<u8>, EncodeError> {
+            Ok(vec![0; samples.len() / self.0 as usize])
+        }
+    }
+
+    type Sent = Arc<Mutex<Vec<(usize, u64)>>>;
+
+    struct FakeOutput(Sent);
+
+    impl Output for FakeOutput {
+        fn send(&mut self, data: &[u8], media_ms: u64) -> io::Result<()> {
+            self.0.lock().unwrap().push((data.len(), media_ms));
+            Ok(())
+        }
+    }
+
+    fn wait_for(mut condition: impl FnMut() -> bool) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !condition() {
+            assert!(Instant::now() < deadline, "timed out");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[tokio::test]
+    async fn streams_queued_audio_once_on_air() {
+        let sent: Sent = Arc::default();
+        let connections = Arc::new(Mutex::new(Vec::new()));
+        let (output, seen) = (sent.clone(), connections.clone());
+        let backends = Backends {
+            connect: Box::new(move |target, info| {
+                seen.lock().unwrap().push((target.clone(), info.codec));
+                Ok(Box::new(FakeOutput(output.clone())) as Box<dyn Output>)
+            }),
+            encoder: Box::new(|_, _, channels, _| {
+                Ok(Box::new(FakeEncoder(channels)) as Box<dyn Encoder>)
+            }),
+        };
+        let settings = BroadcastSettings::new();
+        let sink = BroadcastSink::with_backends("broadcast", settings.clone(), backends).unwrap();
+        let audio = || Signal::Audio {
+            sample_rate: 1000,
+            channels: 2,
+            timestamp_us: 0,
+            data: vec![0.25; 200],
+        };
+
+        // Off air: audio isn't queued
+        sink.consume(audio()).await.unwrap();
+        assert_eq!(settings.stats().buffered_secs, 0.0);
+
+        let config = serde_json::json!({
+            "enabled": true,
+            "url": "rtmp://live.example/app/key",
+            "codec": "Opus"
+        });
+        sink.consume(Signal::Control(ControlSignal::Settings(config)))
+            .await
+            .unwrap();
+        wait_for(|| settings.stats().state == BroadcastState::WaitingForAudio);
+        sink.consume(audio()).await.unwrap();
+        wait_for(|| settings.stats().state == BroadcastState::Live);
+        wait_for(|| sent.lock().unwrap().len() == 2);
+
+        // RTMP always gets MP3; header first, then 100 ms of audio
+        assert_eq!(
+            connections.lock().unwrap()[0],
+            (
+                Target::parse("rtmp://live.example/app/key").unwrap(),
+                Codec::Mp3
+            )
+        );
+        assert_eq!(*sent.lock().unwrap(), vec![(4, 0), (100, 0)]);
+        sink.consume(audio()).await.unwrap();
+        wait_for(|| sent.lock().unwrap().len() == 3);
+        assert_eq!(sent.lock().unwrap()[2], (100, 100));
+        assert_eq!(settings.stats().bytes_sent, 204);
+        assert!(sink
+            .render_output()
+            .unwrap()
+            .starts_with("Broadcast: LIVE rtmp"));
+
+        sink.consume(Signal::Text("broadcast stop".into()))
+            .await
+            .unwrap();
+        wait_for(|| settings.stats().state == BroadcastState::Off);
+        assert!(!settings.config().enabled);
+    }
+}
@@ -0,0 +1,289 @@
+//! Broadcast Tile
+//!
+//! Monitor mode shows whether the stream is on air, for how long, and how
+//! full the buffer is; the maximized view adds the target, format and
+//! reconnect and drop counts.
+
+use std::sync::Arc;
+
+use magnolia_core::{BindableAction, RenderContext, TileError, TileRenderer};
+use magnolia_ui::{draw_text, FontId, TextAlignment};
+use nannou::prelude::*;
+
+use crate::settings::format_bytes;
+use crate::{BroadcastConfig, BroadcastSettings, BroadcastState, BroadcastStats};
+
+pub struct BroadcastTile {
+    id: String,
+    settings: Arc<BroadcastSettings>,
+    stats: BroadcastStats,
+    buffer_secs: f32,
+}
+
+impl BroadcastTile {
+    pub fn new(id: &str, settings: Arc<BroadcastSettings>) -> Self {
+        Self {
+            id: id.to_string(),
+            settings,
+            stats: BroadcastStats::default(),
+            buffer_secs: BroadcastConfig::default().buffer_secs,
+        }
+    }
+
+    fn toggle(&self) {
+        self.settings.set_enabled(!self.settings.config().enabled);
+    }
+
+    fn state_color(state: BroadcastState) -> Srgba {
+        match state {
+            BroadcastState::Live => srgba(1.0, 0.25, 0.25, 1.0),
+            BroadcastState::Connecting | BroadcastState::WaitingForAudio => {
+                srgba(1.0, 0.8, 0.3, 1.0)
+            }
+            BroadcastState::Retrying => srgba(1.0, 0.5, 0.2, 1.0),
+            BroadcastState::Off => srgba(0.5, 0.5, 0.55, 1.0),
+        }
+    }
+
+    fn uptime_text(&self) -> String {
+        let up = self.stats.uptime_secs();
+        format!("{:02}:{:02}:{:02}", up / 3600, up / 60 % 60, up % 60)
+    }
+
+    fn draw_buffer_bar(&self, draw: &Draw, center: Point2, width: f32) {
+        let fill = (self.stats.buffered_secs / self.buffer_secs.max(0.1)).clamp(0.0, 1.0);
+        draw.rect()
+            .xy(center)
+            .w_h(width, 6.0)
+            .color(srgba(0.15, 0.15, 0.2, 1.0));
+        draw.rect()
+            .x_y(center.x - width * (1.0 - fill) / 2.0, center.y)
+            .w_h(width * fill, 6.0)
+            .color(if fill > 0.8 {
+                srgba(1.0, 0.4, 0.3, 1.0)
+            } else {
+                srgba(0.4, 0.8, 0.9, 1.0)
+            });
+    }
+}
+
+impl TileRenderer for BroadcastTile {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn name(&self) -> &str {
+        "Broadcast"
+    }
+
+    fn update(&mut self) {
+        self.stats = self.settings.stats();
+        self.buffer_secs = self.settings.config().buffer_secs;
+    }
+
+    fn render_monitor(&self, draw: &Draw, rect: Rect, _ctx: &RenderContext) {
+        draw.rect()
+            .xy(rect.xy())
+            .wh(rect.wh())
+            .color(srgba(0.03, 0.04, 0.07, 0.95));
+
+        draw_text(
+            draw,
+            FontId::PlexSansBold,
+            "BROADCAST",
+            pt2(rect.x(), rect.top() - 18.0),
+            12.0,
+            srgba(0.6, 0.8, 0.9, 1.0),
+            TextAlignment::Center,
+        );
+
+        let state = self.stats.state;
+        let font_size = (rect.h() * 0.2).min(rect.w() / 6.0).min(48.0);
+        draw_text(
+            draw,
+            FontId::PlexSansBold,
+            state.label(),
+            pt2(rect.x(), rect.y() + font_size * 0.4),
+            font_size,
+            Self::state_color(state),
+            TextAlignment::Center,
+        );
+        if state == BroadcastState::Live {
+            draw_text(
+                draw,
+                FontId::PlexMonoRegular,
+                &format!(
+                    "{}  {}",
+                    self.uptime_text(),
+                    format_bytes(self.stats.bytes_sent)
+                ),
+                pt2(rect.x(), rect.y() - font_size * 0.4),
+                13.0,
+                srgba(0.8, 0.8, 0.85, 1.0),
+                TextAlignment::Center,
+            );
+        }
+
+        self.draw_buffer_bar(draw, pt2(rect.x(), rect.bottom() + 30.0), rect.w() * 0.7);
+        draw_text(
+            draw,
+            FontId::PlexSansRegular,
+            &format!(
+                "Buffer {:.1} s  Dropped {:.1} s  Reconnects {}",
+                self.stats.buffered_secs, self.stats.dropped_secs, self.stats.reconnects
+            ),
+            pt2(rect.x(), rect.bottom() + 14.0),
+            10.0,
+            srgba(0.55, 0.55, 0.65, 1.0),
+            TextAlignment::Center,
+        );
+    }
+
+    fn render_controls(&self, draw: &Draw, rect: Rect, _ctx: &RenderContext) -> bool {
+        draw.rect()
+            .xy(rect.xy())
+            .wh(rect.wh())
+            .color(srgba(0.02, 0.02, 0.05, 0.98));
+
+        draw_text(
+            draw,
+            FontId::PlexSansBold,
+            "BROADCAST",
+            pt2(rect.x(), rect.top() - 30.0),
+            18.0,
+            srgba(0.0, 1.0, 1.0, 1.0),
+            TextAlignment::Center,
+        );
+        let config = self.settings.config();
+        draw_text(
+            draw,
+            FontId::PlexSansRegular,
+            if config.enabled {
+                "[B] Go off air"
+            } else {
+                "[B] Go on air"
+            },
+            pt2(rect.x(), rect.top() - 55.0),
+            12.0,
+            srgba(0.5, 0.5, 0.55, 1.0),
+            TextAlignment::Center,
+        );
+
+        let stats = &self.stats;
+        let mut lines = vec![
+            format!("State: {}", stats.state.label()),
+            format!(
+                "Target: {}",
+                if stats.target.is_empty() {
+                    "-"
+                } else {
+                    &stats.target
+                }
+            ),
+            format!("Bitrate: {} kbps", config.bitrate_kbps),
+        ];
+        if stats.sample_rate > 0 {
+            lines.push(format!(
+                "Input: {} Hz, {} ch",
+                stats.sample_rate, stats.channels
+            ));
+        }
+        if stats.state == BroadcastState::Live {
+            lines.push(format!("On air: {}", self.uptime_text()));
+        }
+        lines.extend([
+            format!("Sent: {}", format_bytes(stats.bytes_sent)),
+            format!(
+                "Buffer: {:.1} / {:.1} s",
+                stats.buffered_secs, self.buffer_secs
+            ),
+            format!("Dropped: {:.1} s", stats.dropped_secs),
+            format!("Reconnects: {}", stats.reconnects),
+        ]);
+        if let Some(e) = self.settings.last_error() {
+            lines.push(format!("Last error: {}", e));
+        }
+
+        for (i, line) in lines.iter().enumerate() {
+            draw_text(
+                draw,
+                FontId::PlexMonoRegular,
+                line,
+                pt2(rect.left() + 20.0, rect.top() - 90.0 - i as f32 * 20.0),
+                12.0,
+                srgba(0.6, 0.7, 0.9, 1.0),
+                TextAlignment::Left,
+            );
+        }
+        self.draw_buffer_bar(
+            draw,
+            pt2(rect.x(), rect.top() - 100.0 - lines.len() as f32 * 20.0),
+            rect.w() - 40.0,
+        );
+
+        false
+    }
+
+    fn handle_key(&mut self, key: nannou::prelude::Key, _ctrl: bool, _shift: bool) -> bool {
+        match key {
+            Key::B => {
+                self.toggle();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn get_error(&self) -> Option<TileError> {
+        if self.stats.state == BroadcastState::Live {
+            return None;
+        }
+        self.settings
+            .last_error()
+            .map(|e| TileError::new("Broadcast connection error").with_details(&e))
+    }
+
+    fn retry(&mut self) -> bool {
+        // A fresh config generation skips the remaining backoff
+        self.settings.set_last_error(None);
+        self.settings.set_config(self.settings.config());
+        true
+    }
+
+    fn settings_schema(&self) -> Option<serde_json::Value> {
+        Some(BroadcastConfig::schema())
+    }
+
+    fn apply_settings(&mut self, settings: &serde_json::Value) {
+        match serde_json::from_value::<BroadcastConfig>(settings.clone()) {
+            Ok(config) => {
+                if config != self.settings.config() {
+                    self.settings.set_config(config);
+                }
+            }
+            Err(e) => log::warn!("Broadcast: invalid settings, keeping current: {}", e),
+        }
+    }
+
+    fn get_settings(&self) -> serde_json::Value {
+        serde_json::to_value(self.settings.config()).unwrap_or_default()
+    }
+
+    fn bindable_actions(&self) -> Vec<BindableAction> {
+        vec![BindableAction::new("toggle", "Toggle On Air", true)]
+    }
+
+    fn execute_action(&mut self, action: &str) -> bool {
+        match action {
+            "toggle" => {
+                self.toggle();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn get_display_text(&self) -> Option<String> {
+        Some(self.stats.summary())
+    }
+}
@@ -0,0 +1,242 @@
+use std::io;
+use std::sync::{Arc, Weak};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::encoder::{self, Codec, EncodeError, Encoder};
+use crate::icecast::{IcecastOutput, SourceInfo};
+use crate::rtmp::RtmpOutput;
+use crate::settings::{BroadcastConfig, BroadcastSettings, BroadcastState};
+use crate::target::Target;
+
+/// Audio handed to the encoder at a time
+const CHUNK: Duration = Duration::from_millis(100);
+const IDLE: Duration = Duration::from_millis(250);
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Where encoded bytes go once connected
+pub(crate) trait Output: Send {
+    /// `media_ms` is the stream time of the first sample in `data`
+    fn send(&mut self, data: &[u8], media_ms: u64) -> io::Result<()>;
+}
+
+/// What a connection needs to announce the stream
+pub(crate) struct SessionInfo {
+    pub codec: Codec,
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub bitrate_kbps: u32,
+    pub name: String,
+    pub description: String,
+}
+
+type ConnectFn = Box<dyn Fn(&Target, &SessionInfo) -> io::Result<Box<dyn Output>> + Send>;
+type EncoderFn = Box<dyn Fn(Codec, u32, u16, u32) -> Result<Box<dyn Encoder>, EncodeError> + Send>;
+
+/// Network and codec hooks, replaced in tests
+pub(crate) struct Backends {
+    pub connect: ConnectFn,
+    pub encoder: EncoderFn,
+}
+
+impl Backends {
+    pub fn native() -> Self {
+        Self {
+            connect: Box::new(connect),
+            encoder: Box::new(encoder::open),
+        }
+    }
+}
+
+fn connect(target: &Target, info: &SessionInfo) -> io::Result<Box<dyn Output>> {
+    Ok(match target {
+        Target::Icecast {
+            host,
+            port,
+            mount,
+            user,
+            password,
+        } => {
+            let source = SourceInfo {
+                content_type: info.codec.content_type(),
+                name: &info.name,
+                description: &info.description,
+                bitrate_kbps: info.bitrate_kbps,
+            };
+            Box::new(IcecastOutput::connect(
+                host, *port, mount, user, password, &source,
+            )?)
+        }
+        Target::Rtmp {
+            host,
+            port,
+            app,
+            key,
+        } => Box::new(RtmpOutput::publish(
+            host,
+            *port,
+            app,
+            key,
+            info.sample_rate,
+            info.channels.clamp(1, 2),
+            info.bitrate_kbps,
+        )?),
+    })
+}
+
+pub(crate) fn spawn(settings: &Arc<BroadcastSettings>, backends: Backends) -> io::Result<()> {
+    let settings = Arc::downgrade(settings);
+    thread::Builder::new()
+        .name("broadcast".into())
+        .spawn(move || run(settings, backends))
+        .map(|_| ())
+}
+
+/// Sleeps up to `duration`; false once the settings changed or everyone
+/// else let go of them
+fn wait(settings: &Arc<BroadcastSettings>, generation: u64, duration: Duration) -> bool {
+    let deadline = Instant::now() + duration;
+    loop {
+        if Arc::strong_count(settings) == 1 {
+            return false;
+        }
+        let left = deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return true;
+        }
+        // Letting go wakes no one, so look again every so often
+        if !settings.wait(generation, left.min(Duration::from_millis(50))) {
+            return false;
+        }
+    }
+}
+
+fn set_state(settings: &BroadcastSettings, state: BroadcastState) {
+    settings.update_stats(|stats| {
+        stats.state = state;
+        if state != BroadcastState::Live {
+            stats.live_since = None;
+        }
+    });
+}
+
+fn run(settings: Weak<BroadcastSettings>, backends: Backends) {
+    let mut backoff = MIN_BACKOFF;
+    let mut last_generation = None;
+    while let Some(settings) = settings.upgrade() {
+        if Arc::strong_count(&settings) == 1 {
+            return;
+        }
+        let generation = settings.generation();
+        if last_generation != Some(generation) {
+            last_generation = Some(generation);
+            backoff = MIN_BACKOFF;
+        }
+        let config = settings.config();
+        if !config.enabled {
+            settings.queue.clear();
+            set_state(&settings, BroadcastState::Off);
+            settings.set_last_error(None);
+            wait(&settings, generation, IDLE);
+            continue;
+        }
+        let target = match Target::parse(&config.url) {
+            Ok(target) => target,
+            Err(e) => {
+                settings.set_last_error(Some(e));
+                set_state(&settings, BroadcastState::Off);
+                wait(&settings, generation, IDLE);
+                continue;
+            }
+        };
+        settings.update_stats(|stats| stats.target = target.display());
+
+        let Some((sample_rate, channels)) = settings.queue.wait_format(IDLE) else {
+            set_state(&settings, BroadcastState::WaitingForAudio);
+            continue;
+        };
+        set_state(&settings, BroadcastState::Connecting);
+        match session(
+            &settings,
+            &backends,
+            &config,
+            &target,
+            generation,
+            sample_rate,
+            channels,
+        ) {
+            Ok(()) => backoff = MIN_BACKOFF,
+            Err(e) => {
+                log::warn!("Broadcast: {}", e);
+                settings.set_last_error(Some(e));
+                set_state(&settings, BroadcastState::Retrying);
+                settings.update_stats(|stats| stats.reconnects += 1);
+                if wait(&settings, generation, backoff) {
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                }
+            }
+        }
+    }
+}
+
+/// One connection, until it fails, the audio format changes or the
+/// settings do
+fn session(
+    settings: &Arc<BroadcastSettings>,
+    backends: &Backends,
+    config: &BroadcastConfig,
+    target: &Target,
+    generation: u64,
+    sample_rate: u32,
+    channels: u16,
+) -> Result<(), String> {
+    let codec = config.codec_for(target);
+    let mut encoder = (backends.encoder)(codec, sample_rate, channels, config.bitrate_kbps)
+        .map_err(|e| e.to_string())?;
+    let info = SessionInfo {
+        codec,
+        sample_rate,
+        channels,
+        bitrate_kbps: config.bitrate_kbps,
+        name: config.name.clone(),
+        description: config.description.clone(),
+    };
+    let io_error = |e: io::Error| format!("{}: {}", target.display(), e);
+    let mut output = (backends.connect)(target, &info).map_err(io_error)?;
+
+    let header = encoder.header();
+    output.send(&header, 0).map_err(io_error)?;
+    settings.set_last_error(None);
+    settings.update_stats(|stats| {
+        stats.state = BroadcastState::Live;
+        stats.live_since = Some(Instant::now());
+        stats.bytes_sent += header.len() as u64;
+        stats.sample_rate = sample_rate;
+        stats.channels = channels;
+    });
+    log::info!("Broadcast: live on {}", target.display());
+
+    let mut frames_sent = 0u64;
+    let media_ms = |frames: u64| frames * 1000 / sample_rate.max(1) as u64;
+    loop {
+        if settings.generation() != generation || Arc::strong_count(settings) == 1 {
+            let tail = encoder.finish();
+            let _ = output.send(&tail, media_ms(frames_sent));
+            return Ok(());
+        }
+        let Some(chunk) = settings.queue.pop(CHUNK, CHUNK * 2) else {
+            continue;
+        };
+        if (chunk.sample_rate, chunk.channels) != (sample_rate, channels) {
+            // Reconnect so the stream headers match the new format
+            return Ok(());
+        }
+        let data = encoder.encode(&chunk.samples).map_err(|e| e.to_string())?;
+        output
+            .send(&data, media_ms(frames_sent))
+            .map_err(io_error)?;
+        frames_sent += chunk.frames() as u64;
+        settings.update_stats(|stats| stats.bytes_sent += data.len() as u64);
+    }
+}
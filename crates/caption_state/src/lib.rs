@@ -3,6 +3,16 @@
 use serde::{Deserialize, Serialize};
 use speech_to_text::{SttEvent, SttStatus};
 
+pub mod stabilizer;
+pub mod transcript;
+
+pub use stabilizer::CaptionStabilizer;
+#[cfg(feature = "magnolia")]
+pub use stabilizer::CaptionStabilizerProcessor;
+#[cfg(feature = "magnolia")]
+pub use transcript::TranscriptSink;
+pub use transcript::{Transcript, TranscriptMarker, TranscriptSegment, WordTiming};
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct CaptionSegment {
     pub segment_id: u64,
@@ -110,6 +120,8 @@ mod tests {
             text: "the planets".into(),
             audio_end_ms: 500,
             sequence: 1,
+            language: None,
+            stable_len: None,
         });
         state.apply(SttEvent::Partial {
             session_id: "s".into(),
@@ -117,6 +129,8 @@ mod tests {
             text: "the planets are".into(),
             audio_end_ms: 700,
             sequence: 2,
+            language: None,
+            stable_len: None,
         });
         assert_eq!(state.display_text(), "the planets are");
         state.apply(SttEvent::Final {
@@ -126,6 +140,7 @@ mod tests {
             start_ms: 0,
             end_ms: 900,
             sequence: 3,
+            language: None,
         });
         assert_eq!(state.display_text(), "The planets are");
         assert!(state.provisional.is_none());
@@ -140,6 +155,8 @@ mod tests {
             text: "new".into(),
             audio_end_ms: 1,
             sequence: 2,
+            language: None,
+            stable_len: None,
         });
         state.apply(SttEvent::Partial {
             session_id: "s".into(),
@@ -147,6 +164,8 @@ mod tests {
             text: "old".into(),
             audio_end_ms: 1,
             sequence: 1,
+            language: None,
+            stable_len: None,
         });
         assert_eq!(state.display_text(), "new");
     }
@@ -160,6 +179,8 @@ mod tests {
             text: "visible".into(),
             audio_end_ms: 1,
             sequence: 4,
+            language: None,
+            stable_len: None,
         });
         state.clear();
         state.apply(SttEvent::Partial {
@@ -168,6 +189,8 @@ mod tests {
             text: "stale".into(),
             audio_end_ms: 1,
             sequence: 3,
+            language: None,
+            stable_len: None,
         });
         assert!(state.display_text().is_empty());
     }
@@ -0,0 +1,281 @@
+//! Steadies partial hypotheses before they reach a caption view.
+//!
+//! Streaming recognizers revise the words at the end of a partial as more
+//! audio arrives, and now and then the words before them too. Redrawing
+//! every revision makes captions flicker. The stabilizer settles a word once
+//! it has stayed put for `min_repeats` partials in a row and never takes a
+//! settled word back. Each partial it re-emits carries the settled prefix
+//! with the newest tail after it, and `stable_len` marks where the tail
+//! starts.
+
+use speech_to_text::SttEvent;
+
+/// Partials a word has to survive unchanged before it settles
+pub const DEFAULT_MIN_REPEATS: u32 = 2;
+
+#[derive(Debug, Clone)]
+pub struct CaptionStabilizer {
+    min_repeats: u32,
+    session_id: Option<String>,
+    segment_id: Option<u64>,
+    stable: Vec<String>,
+    /// Words of the last partial after the settled ones, with how many
+    /// partials in a row each has held its place
+    tail: Vec<(String, u32)>,
+}
+
+impl Default for CaptionStabilizer {
+    fn default() -> Self {
+        Self::new(DEFAULT_MIN_REPEATS)
+    }
+}
+
+impl CaptionStabilizer {
+    pub fn new(min_repeats: u32) -> Self {
+        Self {
+            min_repeats: min_repeats.max(1),
+            session_id: None,
+            segment_id: None,
+            stable: Vec::new(),
+            tail: Vec::new(),
+        }
+    }
+
+    pub fn min_repeats(&self) -> u32 {
+        self.min_repeats
+    }
+
+    pub fn set_min_repeats(&mut self, min_repeats: u32) {
+        self.min_repeats = min_repeats.max(1);
+    }
+
+    /// Forget the current segment
+    pub fn reset(&mut self) {
+        self.segment_id = None;
+        self.stable.clear();
+        self.tail.clear();
+    }
+
+    /// Stabilize a partial; every other event passes through, a final
+    /// closing the segment
+    pub fn apply(&mut self, event: SttEvent) -> SttEvent {
+        let SttEvent::Partial {
+            session_id,
+            segment_id,
+            text,
+            audio_end_ms,
+            sequence,
+            language,
+            ..
+        } = event
+        else {
+            if matches!(event, SttEvent::Final { .. }) {
+                self.reset();
+            }
+            return event;
+        };
+        if self.session_id.as_ref() != Some(&session_id) || self.segment_id != Some(segment_id) {
+            self.reset();
+            self.session_id = Some(session_id.clone());
+            self.segment_id = Some(segment_id);
+        }
+
+        // The settled words stay even when the backend revises them
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let mut tail = Vec::new();
+        let mut agreeing = true;
+        for (index, word) in words.iter().skip(self.stable.len()).enumerate() {
+            let held = match self.tail.get(index) {
+                Some((previous, count)) if agreeing && previous == word => count + 1,
+                _ => {
+                    agreeing = false;
+                    1
+                }
+            };
+            tail.push((word.to_string(), held));
+        }
+        let settled = tail
+            .iter()
+            .take_while(|(_, held)| *held >= self.min_repeats)
+            .count();
+        self.stable
+            .extend(tail.drain(..settled).map(|(word, _)| word));
+        self.tail = tail;
+
+        let stable_text = self.stable.join(" ");
+        let stable_len = stable_text.len();
+        let mut text = stable_text;
+        for (word, _) in &self.tail {
+            if !text.is_empty() {
+                text.push(' ');
+            }
+            text.push_str(word);
+        }
+        SttEvent::Partial {
+            session_id,
+            segment_id,
+            text,
+            audio_end_ms,
+            sequence,
+            language,
+            stable_len: Some(stable_len),
+        }
+    }
+}
+
+#[cfg(feature = "magnolia")]
+mod processor {
+    use super::CaptionStabilizer;
+    use async_trait::async_trait;
+    use magnolia_core::{
+        ControlSignal, DataType, ModuleSchema, Port, PortDirection, Processor, Result, Signal,
+    };
+    use speech_to_text::SttEvent;
+
+    /// Re-emits speech-to-text events with steadied partials, for patching
+    /// between the recognizer and a caption or transcript view
+    pub struct CaptionStabilizerProcessor {
+        id: String,
+        enabled: bool,
+        stabilizer: CaptionStabilizer,
+    }
+
+    impl CaptionStabilizerProcessor {
+        pub fn new(id: &str) -> Self {
+            Self {
+                id: id.to_string(),
+                enabled: true,
+                stabilizer: CaptionStabilizer::default(),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Processor for CaptionStabilizerProcessor {
+        fn name(&self) -> &str {
+            "Caption Stabilizer"
+        }
+
+        fn schema(&self) -> ModuleSchema {
+            ModuleSchema {
+                id: self.id.clone(),
+                name: "Caption Stabilizer".to_string(),
+                description: "Holds back partial caption words until they stop changing"
+                    .to_string(),
+                ports: vec![
+                    Port {
+                        id: "stt_in".to_string(),
+                        label: "Text Events".to_string(),
+                        data_type: DataType::Text,
+                        direction: PortDirection::Input,
+                    },
+                    Port {
+                        id: "text_out".to_string(),
+                        label: "Stable Events".to_string(),
+                        data_type: DataType::Text,
+                        direction: PortDirection::Output,
+                    },
+                ],
+                settings_schema: Some(serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "min_repeats": { "type": "integer", "default": super::DEFAULT_MIN_REPEATS, "minimum": 1, "maximum": 10 }
+                    }
+                })),
+                docs: None,
+            }
+        }
+
+        fn is_enabled(&self) -> bool {
+            self.enabled
+        }
+
+        fn set_enabled(&mut self, enabled: bool) {
+            self.enabled = enabled;
+            self.stabilizer.reset();
+        }
+
+        async fn process(&mut self, signal: Signal) -> Result<Option<Signal>> {
+            match signal {
+                Signal::Control(ControlSignal::Settings(settings)) => {
+                    if let Some(repeats) = settings.get("min_repeats").and_then(|v| v.as_u64()) {
+                        self.stabilizer.set_min_repeats(repeats.min(10) as u32);
+                    }
+                    Ok(None)
+                }
+                Signal::Computed { source, content } => {
+                    let Ok(event) = serde_json::from_str::<SttEvent>(&content) else {
+                        return Ok(Some(Signal::Computed { source, content }));
+                    };
+                    let content = serde_json::to_string(&self.stabilizer.apply(event))?;
+                    Ok(Some(Signal::Computed { source, content }))
+                }
+                other => Ok(Some(other)),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "magnolia")]
+pub use processor::CaptionStabilizerProcessor;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn partial(segment_id: u64, text: &str, sequence: u64) -> SttEvent {
+        SttEvent::Partial {
+            session_id: "s".into(),
+            segment_id,
+            text: text.into(),
+            audio_end_ms: sequence * 100,
+            sequence,
+            language: None,
+            stable_len: None,
+        }
+    }
+
+    fn split(event: &SttEvent) -> (&str, &str) {
+        let SttEvent::Partial {
+            text, stable_len, ..
+        } = event
+        else {
+            panic!("expected a partial");
+        };
+        let (stable, tail) = text.split_at(stable_len.unwrap());
+        (stable, tail.trim_start())
+    }
+
+    #[test]
+    fn settles_words_that_hold_and_keeps_them() {
+        let mut stabilizer = CaptionStabilizer::new(2);
+        let first = stabilizer.apply(partial(1, "the plan", 1));
+        assert_eq!(split(&first), ("", "the plan"));
+
+        let second = stabilizer.apply(partial(1, "the planets are", 2));
+        assert_eq!(split(&second), ("the", "planets are"));
+
+        // A revision of a settled word doesn't reach the output
+        let third = stabilizer.apply(partial(1, "a planets are aligned", 3));
+        assert_eq!(split(&third), ("the planets are", "aligned"));
+    }
+
+    #[test]
+    fn finals_and_new_segments_start_over() {
+        let mut stabilizer = CaptionStabilizer::new(1);
+        stabilizer.apply(partial(1, "hello there", 1));
+        let final_ = SttEvent::Final {
+            session_id: "s".into(),
+            segment_id: 1,
+            text: "Hello there.".into(),
+            start_ms: 0,
+            end_ms: 200,
+            sequence: 2,
+            language: None,
+        };
+        assert_eq!(stabilizer.apply(final_.clone()), final_);
+
+        let next = stabilizer.apply(partial(2, "general", 3));
+        assert_eq!(split(&next), ("general", ""));
+    }
+}
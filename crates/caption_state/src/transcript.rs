@@ -0,0 +1,431 @@
+//! Session transcript: every committed segment plus the live partial.
+//!
+//! Unlike [`CaptionState`](crate::CaptionState), which only needs enough text
+//! for a caption line, the transcript keeps timing per segment so it can be
+//! scrolled back through and exported when the session ends.
+
+use serde::{Deserialize, Serialize};
+use speech_to_text::{SttEvent, SttStatus};
+use std::collections::VecDeque;
+
+/// Segments kept before the oldest are dropped
+pub const DEFAULT_MAX_SEGMENTS: usize = 5000;
+
+/// Word-level timing, for backends that report it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WordTiming {
+    pub word: String,
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TranscriptSegment {
+    pub segment_id: u64,
+    pub text: String,
+    pub start_ms: u64,
+    pub end_ms: u64,
+    /// Empty until a backend provides word timestamps
+    #[serde(default)]
+    pub words: Vec<WordTiming>,
+}
+
+/// A labelled moment, placed at the audio time heard when it arrived
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TranscriptMarker {
+    pub label: String,
+    pub at_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Transcript {
+    pub session_id: Option<String>,
+    pub segments: VecDeque<TranscriptSegment>,
+    pub provisional: Option<TranscriptSegment>,
+    pub last_sequence: u64,
+    pub status: SttStatus,
+    pub max_segments: usize,
+    #[serde(default)]
+    pub markers: Vec<TranscriptMarker>,
+    /// Bumped on every visible change so renderers can cache layout
+    pub revision: u64,
+}
+
+impl Default for Transcript {
+    fn default() -> Self {
+        Self {
+            session_id: None,
+            segments: VecDeque::new(),
+            provisional: None,
+            last_sequence: 0,
+            status: SttStatus::Stopped,
+            max_segments: DEFAULT_MAX_SEGMENTS,
+            markers: Vec::new(),
+            revision: 0,
+        }
+    }
+}
+
+impl Transcript {
+    /// Same sequence guard as [`CaptionState::apply`](crate::CaptionState::apply);
+    /// a new session id starts the guard over.
+    pub fn apply(&mut self, event: SttEvent) {
+        let (session_id, sequence) = match &event {
+            SttEvent::Partial {
+                session_id,
+                sequence,
+                ..
+            }
+            | SttEvent::Final {
+                session_id,
+                sequence,
+                ..
+            } => (session_id, *sequence),
+            SttEvent::Status { status } => {
+                self.status = status.clone();
+                self.revision += 1;
+                return;
+            }
+            SttEvent::Error { .. } => {
+                self.status = SttStatus::Failed;
+                self.revision += 1;
+                return;
+            }
+        };
+        if self.session_id.as_ref() != Some(session_id) {
+            self.session_id = Some(session_id.clone());
+            self.last_sequence = 0;
+        } else if sequence <= self.last_sequence {
+            return;
+        }
+        self.last_sequence = sequence;
+        match event {
+            SttEvent::Partial {
+                segment_id,
+                text,
+                audio_end_ms,
+                ..
+            } => {
+                let start_ms = self
+                    .provisional
+                    .as_ref()
+                    .filter(|p| p.segment_id == segment_id)
+                    .map(|p| p.start_ms)
+                    .or_else(|| self.segments.back().map(|s| s.end_ms))
+                    .unwrap_or(0);
+                self.provisional = Some(TranscriptSegment {
+                    segment_id,
+                    text,
+                    start_ms,
+                    end_ms: audio_end_ms,
+                    words: Vec::new(),
+                });
+            }
+            SttEvent::Final {
+                segment_id,
+                text,
+                start_ms,
+                end_ms,
+                ..
+            } => {
+                self.push_final(TranscriptSegment {
+                    segment_id,
+                    text,
+                    start_ms,
+                    end_ms,
+                    words: Vec::new(),
+                });
+            }
+            _ => {}
+        }
+        self.revision += 1;
+    }
+
+    /// Plain text from sources that don't speak [`SttEvent`]: each line is
+    /// committed as-is, without timing
+    pub fn push_text(&mut self, text: &str) {
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+        let at = self.segments.back().map(|s| s.end_ms).unwrap_or(0);
+        self.push_final(TranscriptSegment {
+            segment_id: self.segments.back().map(|s| s.segment_id + 1).unwrap_or(0),
+            text: text.to_string(),
+            start_ms: at,
+            end_ms: at,
+            words: Vec::new(),
+        });
+        self.revision += 1;
+    }
+
+    /// Mark the latest audio heard, the live partial's end if there is one
+    pub fn add_marker(&mut self, label: &str) {
+        let at_ms = self
+            .provisional
+            .as_ref()
+            .or(self.segments.back())
+            .map(|s| s.end_ms)
+            .unwrap_or(0);
+        self.markers.push(TranscriptMarker {
+            label: label.to_string(),
+            at_ms,
+        });
+        self.revision += 1;
+    }
+
+    fn push_final(&mut self, segment: TranscriptSegment) {
+        if self
+            .provisional
+            .as_ref()
+            .is_some_and(|p| p.segment_id == segment.segment_id)
+        {
+            self.provisional = None;
+        }
+        if segment.text.trim().is_empty() {
+            return;
+        }
+        self.segments.push_back(segment);
+        while self.segments.len() > self.max_segments.max(1) {
+            self.segments.pop_front();
+        }
+    }
+
+    /// Drop the text but keep the session and sequence guard, like
+    /// [`CaptionState::clear`](crate::CaptionState::clear)
+    pub fn clear(&mut self) {
+        self.segments.clear();
+        self.provisional = None;
+        self.markers.clear();
+        self.revision += 1;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty() && self.provisional.is_none()
+    }
+
+    /// Committed text only, one segment per line
+    pub fn plain_text(&self) -> String {
+        self.segments
+            .iter()
+            .map(|s| s.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Committed segments with their audio timestamps, for saving a session;
+    /// each marker follows the segments that started before it
+    pub fn export_text(&self) -> String {
+        let mut out = String::new();
+        if let Some(session) = &self.session_id {
+            out.push_str(&format!("# session {}\n", session));
+        }
+        let mut markers: Vec<&TranscriptMarker> = self.markers.iter().collect();
+        markers.sort_by_key(|marker| marker.at_ms);
+        let mut markers = markers.into_iter().peekable();
+        let mut push_markers = |out: &mut String, before: Option<u64>| {
+            while let Some(marker) = markers.next_if(|m| before.is_none_or(|at| m.at_ms < at)) {
+                out.push_str(&format!(
+                    "[{}] ** {}\n",
+                    format_timestamp(marker.at_ms),
+                    marker.label
+                ));
+            }
+        };
+        for segment in &self.segments {
+            push_markers(&mut out, Some(segment.start_ms));
+            out.push_str(&format!(
+                "[{} - {}] {}\n",
+                format_timestamp(segment.start_ms),
+                format_timestamp(segment.end_ms),
+                segment.text
+            ));
+        }
+        push_markers(&mut out, None);
+        out
+    }
+}
+
+/// `h:mm:ss.mmm`, or `mm:ss.mmm` under an hour
+pub fn format_timestamp(ms: u64) -> String {
+    let (h, m, s, ms) = (ms / 3_600_000, ms / 60_000 % 60, ms / 1000 % 60, ms % 1000);
+    if h > 0 {
+        format!("{}:{:02}:{:02}.{:03}", h, m, s, ms)
+    } else {
+        format!("{:02}:{:02}.{:03}", m, s, ms)
+    }
+}
+
+#[cfg(feature = "magnolia")]
+mod sink {
+    use super::Transcript;
+    use async_trait::async_trait;
+    use magnolia_core::session::marker_label;
+    use magnolia_core::{DataType, ModuleSchema, Port, PortDirection, Result, Signal, Sink};
+    use speech_to_text::SttEvent;
+    use std::sync::{Arc, Mutex};
+
+    /// Feeds a shared [`Transcript`] from the speech-to-text processor's
+    /// events; plain `Text` signals are committed as untimed segments and
+    /// `marker` intents annotate it
+    pub struct TranscriptSink {
+        id: String,
+        enabled: bool,
+        state: Arc<Mutex<Transcript>>,
+    }
+
+    impl TranscriptSink {
+        pub fn new(id: &str, state: Arc<Mutex<Transcript>>) -> Self {
+            Self {
+                id: id.to_string(),
+                enabled: true,
+                state,
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Sink for TranscriptSink {
+        fn name(&self) -> &str {
+            "transcript"
+        }
+
+        fn schema(&self) -> ModuleSchema {
+            ModuleSchema {
+                id: self.id.clone(),
+                name: "Transcript".to_string(),
+                description: "Accumulates the session transcript from speech-to-text events"
+                    .to_string(),
+                ports: vec![
+                    Port {
+                        id: "stt_in".to_string(),
+                        label: "Text Events".to_string(),
+                        data_type: DataType::Text,
+                        direction: PortDirection::Input,
+                    },
+                    Port {
+                        id: "control_in".to_string(),
+                        label: "Markers".to_string(),
+                        data_type: DataType::Control,
+                        direction: PortDirection::Input,
+                    },
+                ],
+                settings_schema: None,
+                docs: None,
+            }
+        }
+
+        fn is_enabled(&self) -> bool {
+            self.enabled
+        }
+
+        fn set_enabled(&mut self, enabled: bool) {
+            self.enabled = enabled;
+        }
+
+        fn render_output(&self) -> Option<String> {
+            self.state.lock().ok().map(|state| state.export_text())
+        }
+
+        async fn consume(&self, signal: Signal) -> Result<Option<Signal>> {
+            if !self.enabled {
+                return Ok(None);
+            }
+            let Ok(mut state) = self.state.lock() else {
+                return Ok(None);
+            };
+            if let Some(label) = marker_label(&signal) {
+                state.add_marker(&label);
+                return Ok(None);
+            }
+            match signal {
+                Signal::Computed { content, .. } => {
+                    if let Ok(event) = serde_json::from_str::<SttEvent>(&content) {
+                        state.apply(event);
+                    }
+                }
+                Signal::Text(text) => state.push_text(&text),
+                _ => {}
+            }
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(feature = "magnolia")]
+pub use sink::TranscriptSink;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn partial(segment_id: u64, text: &str, audio_end_ms: u64, sequence: u64) -> SttEvent {
+        SttEvent::Partial {
+            session_id: "s".into(),
+            segment_id,
+            text: text.into(),
+            audio_end_ms,
+            sequence,
+            language: None,
+            stable_len: None,
+        }
+    }
+
+    fn final_(segment_id: u64, text: &str, start_ms: u64, end_ms: u64, sequence: u64) -> SttEvent {
+        SttEvent::Final {
+            session_id: "s".into(),
+            segment_id,
+            text: text.into(),
+            start_ms,
+            end_ms,
+            sequence,
+            language: None,
+        }
+    }
+
+    #[test]
+    fn keeps_finals_with_timing_and_exports() {
+        let mut transcript = Transcript::default();
+        transcript.apply(partial(1, "hello", 400, 1));
+        transcript.apply(final_(1, "Hello.", 0, 900, 2));
+        transcript.apply(partial(2, "and good", 1500, 3));
+        assert_eq!(transcript.segments.len(), 1);
+        assert_eq!(transcript.provisional.as_ref().unwrap().start_ms, 900);
+
+        transcript.add_marker("good bit");
+        transcript.apply(final_(2, "And goodbye.", 1000, 61_250, 4));
+        transcript.apply(partial(3, "stale", 1, 2));
+        assert!(transcript.provisional.is_none());
+        transcript.add_marker("end");
+        assert_eq!(transcript.plain_text(), "Hello.\nAnd goodbye.");
+        assert_eq!(
+            transcript.export_text(),
+            "# session s\n[00:00.000 - 00:00.900] Hello.\n[00:01.000 - 01:01.250] And goodbye.\n\
+             [00:01.500] ** good bit\n[01:01.250] ** end\n"
+        );
+    }
+
+    #[test]
+    fn caps_scrollback_and_restarts_guard_per_session() {
+        let mut transcript = Transcript {
+            max_segments: 2,
+            ..Transcript::default()
+        };
+        for seq in 1..=3 {
+            transcript.apply(final_(seq, &format!("line {seq}"), 0, 0, seq));
+        }
+        assert_eq!(transcript.plain_text(), "line 2\nline 3");
+
+        transcript.apply(SttEvent::Final {
+            session_id: "next".into(),
+            segment_id: 1,
+            text: "new session".into(),
+            start_ms: 0,
+            end_ms: 10,
+            sequence: 1,
+            language: None,
+        });
+        assert_eq!(transcript.segments.back().unwrap().text, "new session");
+        assert_eq!(format_timestamp(3_723_004), "1:02:03.004");
+    }
+}
@@ -0,0 +1,571 @@
+//! The bridge thread and the sink/source pair sharing it.
+
+use async_trait::async_trait;
+use magnolia_core::{
+    ControlSignal, DataType, ModuleSchema, Port, PortDirection, Signal, Sink, Source,
+    WorkerSettings,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc as std_mpsc, Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+use crate::discord::Discord;
+use crate::matrix::Matrix;
+use crate::service::{ChatMessage, ChatService};
+
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// Posts waiting on the service; more are dropped rather than piling up
+const OUTGOING_QUEUE: usize = 64;
+const INCOMING_QUEUE: usize = 64;
+const TICK: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChatServiceKind {
+    #[default]
+    Off,
+    Matrix,
+    Discord,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChatConfig {
+    pub service: ChatServiceKind,
+    pub matrix_homeserver: String,
+    pub matrix_access_token: String,
+    /// Room id (`!abc:server`) or alias (`#name:server`)
+    pub matrix_room: String,
+    /// Where posts go; reading needs the bot token and channel
+    pub discord_webhook_url: String,
+    pub discord_bot_token: String,
+    pub discord_channel_id: String,
+    /// Name posts appear under where the service allows it
+    pub display_name: String,
+    /// Incoming messages must start with this to be ingested (stripped);
+    /// empty ingests everything
+    pub command_prefix: String,
+    /// Comma-separated `Computed` sources to forward; empty forwards all
+    pub computed_sources: String,
+    pub poll_secs: u32,
+    /// Posts closer together than this are batched into one
+    pub min_post_interval_secs: f32,
+}
+
+impl Default for ChatConfig {
+    fn default() -> Self {
+        Self {
+            service: ChatServiceKind::Off,
+            matrix_homeserver: "https://matrix.org".to_string(),
+            matrix_access_token: String::new(),
+            matrix_room: String::new(),
+            discord_webhook_url: String::new(),
+            discord_bot_token: String::new(),
+            discord_channel_id: String::new(),
+            display_name: "Magnolia".to_string(),
+            command_prefix: "!".to_string(),
+            computed_sources: String::new(),
+            poll_secs: 3,
+            min_post_interval_secs: 2.0,
+        }
+    }
+}
+
+impl ChatConfig {
+    pub fn schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "service": {
+                    "type": "string",
+                    "title": "Service",
+                    "enum": ["off", "matrix", "discord"],
+                    "default": "off"
+                },
+                "matrix_homeserver": {
+                    "type": "string",
+                    "title": "Matrix Homeserver",
+                    "default": "https://matrix.org"
+                },
+                "matrix_access_token": { "type": "string", "title": "Matrix Access Token", "default": "" },
+                "matrix_room": { "type": "string", "title": "Matrix Room", "default": "" },
+                "discord_webhook_url": { "type": "string", "title": "Discord Webhook URL", "default": "" },
+                "discord_bot_token": { "type": "string", "title": "Discord Bot Token", "default": "" },
+                "discord_channel_id": { "type": "string", "title": "Discord Channel ID", "default": "" },
+                "display_name": { "type": "string", "title": "Display Name", "default": "Magnolia" },
+                "command_prefix": { "type": "string", "title": "Command Prefix", "default": "!" },
+                "computed_sources": { "type": "string", "title": "Forwarded Sources", "default": "" },
+                "poll_secs": {
+                    "type": "integer",
+                    "title": "Poll Interval (s)",
+                    "minimum": 1,
+                    "maximum": 300,
+                    "default": 3
+                },
+                "min_post_interval_secs": {
+                    "type": "number",
+                    "title": "Min Post Interval (s)",
+                    "minimum": 0.0,
+                    "maximum": 60.0,
+                    "default": 2.0
+                }
+            }
+        })
+    }
+
+    fn poll_interval(&self) -> Duration {
+        Duration::from_secs(self.poll_secs.max(1) as u64)
+    }
+
+    fn post_interval(&self) -> Duration {
+        Duration::from_secs_f32(self.min_post_interval_secs.clamp(0.0, 60.0))
+    }
+
+    /// Whether a `Computed` signal from `source` is forwarded
+    fn forwards(&self, source: &str) -> bool {
+        let mut sources = self
+            .computed_sources
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .peekable();
+        sources.peek().is_none() || sources.any(|s| s == source)
+    }
+
+    /// The text an incoming message feeds in, if it has the prefix
+    fn ingest(&self, message: &ChatMessage) -> Option<String> {
+        let body = message.body.trim();
+        let text = match self.command_prefix.trim() {
+            "" => body,
+            prefix => body.strip_prefix(prefix)?.trim_start(),
+        };
+        (!text.is_empty()).then(|| text.to_string())
+    }
+
+    fn open(&self) -> anyhow::Result<Box<dyn ChatService>> {
+        Ok(match self.service {
+            ChatServiceKind::Matrix => Box::new(Matrix::connect(
+                &self.matrix_homeserver,
+                &self.matrix_access_token,
+                &self.matrix_room,
+            )?),
+            ChatServiceKind::Discord => Box::new(Discord::connect(
+                &self.discord_webhook_url,
+                &self.discord_bot_token,
+                &self.discord_channel_id,
+                &self.display_name,
+            )?),
+            ChatServiceKind::Off => anyhow::bail!("no chat service selected"),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChatStatus {
+    Disabled,
+    Connecting,
+    Connected,
+    /// Last error; retried with backoff
+    Failed(String),
+}
+
+struct Shared {
+    config: WorkerSettings<ChatConfig>,
+    status: Mutex<ChatStatus>,
+    posted: AtomicU64,
+    received: AtomicU64,
+}
+
+impl Shared {
+    fn set_status(&self, status: ChatStatus) {
+        if let Ok(mut s) = self.status.lock() {
+            *s = status;
+        }
+    }
+
+    fn status(&self) -> ChatStatus {
+        self.status
+            .lock()
+            .map(|s| s.clone())
+            .unwrap_or(ChatStatus::Disabled)
+    }
+}
+
+/// Start the bridge thread; `sink_id` takes text to post and settings,
+/// `source_id` emits incoming messages
+pub fn spawn(
+    sink_id: &str,
+    source_id: &str,
+    config: ChatConfig,
+) -> std::io::Result<(ChatSink, ChatSource)> {
+    let shared = new_shared(config);
+    let (outgoing_tx, outgoing_rx) = std_mpsc::sync_channel(OUTGOING_QUEUE);
+    let (incoming_tx, incoming_rx) = mpsc::channel(INCOMING_QUEUE);
+    let thread_shared = shared.clone();
+    std::thread::Builder::new()
+        .name("chat-bridge".to_string())
+        .spawn(move || run(thread_shared, outgoing_rx, incoming_tx))?;
+    Ok((
+        ChatSink::new(sink_id, outgoing_tx, shared),
+        ChatSource::new(source_id, incoming_rx),
+    ))
+}
+
+fn new_shared(config: ChatConfig) -> Arc<Shared> {
+    Arc::new(Shared {
+        config: WorkerSettings::new(config),
+        status: Mutex::new(ChatStatus::Connecting),
+        posted: AtomicU64::new(0),
+        received: AtomicU64::new(0),
+    })
+}
+
+fn run(shared: Arc<Shared>, outgoing: std_mpsc::Receiver<String>, incoming: mpsc::Sender<Signal>) {
+    let mut backoff = Duration::from_secs(1);
+    while !incoming.is_closed() {
+        let (generation, config) = shared.config.current();
+        // Text sent while offline is dropped rather than posted late
+        while outgoing.try_recv().is_ok() {}
+        if config.service == ChatServiceKind::Off {
+            shared.set_status(ChatStatus::Disabled);
+            shared.config.wait(generation, Duration::from_secs(1));
+            continue;
+        }
+
+        shared.set_status(ChatStatus::Connecting);
+        match session(&shared, &config, generation, &outgoing, &incoming) {
+            Ok(connected) => {
+                shared.set_status(ChatStatus::Connecting);
+                if connected {
+                    backoff = Duration::from_secs(1);
+                }
+            }
+            Err(e) => {
+                if !matches!(shared.status(), ChatStatus::Failed(_)) {
+                    log::warn!("Chat bridge: {:?}: {}", config.service, e);
+                }
+                shared.set_status(ChatStatus::Failed(e.to_string()));
+            }
+        }
+        shared.config.wait(generation, backoff);
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
+
+/// Queued texts joined into as few posts as fit `max_len` characters
+fn batch_posts(texts: &[String], max_len: usize) -> Vec<String> {
+    let mut posts: Vec<String> = Vec::new();
+    for text in texts {
+        let text: String = text.chars().take(max_len).collect();
+        match posts.last_mut() {
+            Some(post) if post.chars().count() + 1 + text.chars().count() <= max_len => {
+                post.push('\n');
+                post.push_str(&text);
+            }
+            _ => posts.push(text),
+        }
+    }
+    posts
+}
+
+/// One connection, until it fails or the settings change. `Ok(true)` if it
+/// connected.
+fn session(
+    shared: &Shared,
+    config: &ChatConfig,
+    generation: u64,
+    outgoing: &std_mpsc::Receiver<String>,
+    incoming: &mpsc::Sender<Signal>,
+) -> anyhow::Result<bool> {
+    let mut service = config.open()?;
+    log::info!("Chat bridge connected to {:?}", config.service);
+    shared.set_status(ChatStatus::Connected);
+
+    let mut next_poll = Instant::now() + config.poll_interval();
+    let mut next_post = Instant::now();
+    loop {
+        if shared.config.changed_since(generation) || incoming.is_closed() {
+            return Ok(true);
+        }
+        let now = Instant::now();
+        if now >= next_post {
+            let wait = next_poll.saturating_duration_since(now).min(TICK);
+            match outgoing.recv_timeout(wait) {
+                Ok(text) => {
+                    let mut texts = vec![text];
+                    texts.extend(outgoing.try_iter());
+                    for post in batch_posts(&texts, service.max_len()) {
+                        service.post(&post)?;
+                        shared.posted.fetch_add(1, Ordering::Relaxed);
+                    }
+                    next_post = Instant::now() + config.post_interval();
+                }
+                Err(std_mpsc::RecvTimeoutError::Timeout) => {}
+                Err(std_mpsc::RecvTimeoutError::Disconnected) => return Ok(true),
+            }
+        } else {
+            shared.config.wait(generation, (next_post - now).min(TICK));
+        }
+
+        if Instant::now() >= next_poll {
+            for message in service.poll()? {
+                let Some(text) = config.ingest(&message) else {
+                    continue;
+                };
+                log::debug!("Chat bridge: {} says {:?}", message.sender, text);
+                shared.received.fetch_add(1, Ordering::Relaxed);
+                if incoming.blocking_send(Signal::Text(text)).is_err() {
+                    return Ok(true);
+                }
+            }
+            next_poll = Instant::now() + config.poll_interval();
+        }
+    }
+}
+
+/// Posts the Text and Computed signals patched into it to the room or
+/// channel
+pub struct ChatSink {
+    id: String,
+    enabled: bool,
+    outgoing: std_mpsc::SyncSender<String>,
+    shared: Arc<Shared>,
+}
+
+impl ChatSink {
+    fn new(id: &str, outgoing: std_mpsc::SyncSender<String>, shared: Arc<Shared>) -> Self {
+        Self {
+            id: id.to_string(),
+            enabled: true,
+            outgoing,
+            shared,
+        }
+    }
+
+    pub fn status(&self) -> ChatStatus {
+        self.shared.status()
+    }
+
+    pub fn set_config(&self, config: ChatConfig) {
+        self.shared.config.set(config);
+    }
+
+    fn queue(&self, text: String) {
+        if self.status() != ChatStatus::Connected {
+            return;
+        }
+        if self.outgoing.try_send(text).is_err() {
+            log::debug!("Chat bridge: post queue full, dropping message");
+        }
+    }
+}
+
+#[async_trait]
+impl Sink for ChatSink {
+    fn name(&self) -> &str {
+        "Chat Bridge"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "Chat Bridge".to_string(),
+            description: "Posts text to a Matrix room or Discord channel".to_string(),
+            ports: vec![
+                Port {
+                    id: "text_in".to_string(),
+                    label: "Text".to_string(),
+                    data_type: DataType::Text,
+                    direction: PortDirection::Input,
+                },
+                Port {
+                    id: "values_in".to_string(),
+                    label: "Values".to_string(),
+                    data_type: DataType::Numeric,
+                    direction: PortDirection::Input,
+                },
+            ],
+            settings_schema: Some(ChatConfig::schema()),
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    fn render_output(&self) -> Option<String> {
+        let service = self.shared.config.read(|c| format!("{:?}", c.service));
+        Some(match self.status() {
+            ChatStatus::Disabled => "Chat: off".to_string(),
+            ChatStatus::Connecting => format!("Chat: connecting to {}", service),
+            ChatStatus::Connected => format!(
+                "Chat: {} · {} posted · {} received",
+                service,
+                self.shared.posted.load(Ordering::Relaxed),
+                self.shared.received.load(Ordering::Relaxed)
+            ),
+            ChatStatus::Failed(e) => format!("Chat: {}", e),
+        })
+    }
+
+    async fn consume(&self, signal: Signal) -> magnolia_core::Result<Option<Signal>> {
+        match signal {
+            Signal::Control(ControlSignal::Settings(settings)) => {
+                match serde_json::from_value::<ChatConfig>(settings) {
+                    Ok(config) => self.set_config(config),
+                    Err(e) => log::warn!("Chat bridge: invalid settings, keeping current: {}", e),
+                }
+            }
+            _ if !self.enabled => {}
+            Signal::Text(text) if !text.trim().is_empty() => self.queue(text),
+            Signal::Computed { source, content }
+                if self.shared.config.read(|c| c.forwards(&source)) =>
+            {
+                self.queue(format!("{}: {}", source, content))
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+}
+
+/// Messages from the room or channel as Text, for the intent parser and
+/// friends
+pub struct ChatSource {
+    id: String,
+    enabled: bool,
+    incoming: mpsc::Receiver<Signal>,
+}
+
+impl ChatSource {
+    fn new(id: &str, incoming: mpsc::Receiver<Signal>) -> Self {
+        Self {
+            id: id.to_string(),
+            enabled: true,
+            incoming,
+        }
+    }
+}
+
+#[async_trait]
+impl Source for ChatSource {
+    fn name(&self) -> &str {
+        "Chat Messages"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "Chat Messages".to_string(),
+            description: "Messages from the chat bridge's room or channel".to_string(),
+            ports: vec![Port {
+                id: "text_out".to_string(),
+                label: "Messages".to_string(),
+                data_type: DataType::Text,
+                direction: PortDirection::Output,
+            }],
+            settings_schema: None,
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    async fn poll(&mut self) -> Option<Signal> {
+        loop {
+            let signal = self.incoming.recv().await?;
+            if self.enabled {
+                return Some(signal);
+            }
+        }
+    }
+
+    fn output_port(&self, _signal: &Signal) -> Option<&str> {
+        Some("text_out")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn sink_filters_and_batches_posts() {
+        let shared = new_shared(ChatConfig {
+            computed_sources: "sysmon, weather".into(),
+            ..ChatConfig::default()
+        });
+        let (tx, rx) = std_mpsc::sync_channel(OUTGOING_QUEUE);
+        let sink = ChatSink::new("chat_bridge", tx, shared.clone());
+
+        // Nothing queues until connected
+        sink.consume(Signal::Text("early".into())).await.unwrap();
+        shared.set_status(ChatStatus::Connected);
+        sink.consume(Signal::Text("hello room".into()))
+            .await
+            .unwrap();
+        sink.consume(Signal::Text("  ".into())).await.unwrap();
+        for source in ["weather", "clock"] {
+            sink.consume(Signal::Computed {
+                source: source.into(),
+                content: "12°".into(),
+            })
+            .await
+            .unwrap();
+        }
+        let queued: Vec<String> = rx.try_iter().collect();
+        assert_eq!(queued, vec!["hello room", "weather: 12°"]);
+        assert_eq!(batch_posts(&queued, 30), vec!["hello room\nweather: 12°"]);
+        assert_eq!(batch_posts(&queued, 12), vec!["hello room", "weather: 12°"]);
+
+        let config = shared.config.get();
+        let message = |body: &str| ChatMessage {
+            sender: "@ana:example.org".into(),
+            body: body.into(),
+        };
+        assert_eq!(
+            config.ingest(&message("! scene Intro")).as_deref(),
+            Some("scene Intro")
+        );
+        assert_eq!(config.ingest(&message("just chatting")), None);
+        assert_eq!(config.ingest(&message("!")), None);
+
+        let settings = serde_json::json!({ "service": "discord", "command_prefix": "" });
+        sink.consume(Signal::Control(ControlSignal::Settings(settings)))
+            .await
+            .unwrap();
+        let config = shared.config.get();
+        assert_eq!(config.service, ChatServiceKind::Discord);
+        assert!(config.forwards("anything"));
+        assert_eq!(
+            config.ingest(&message("just chatting")).as_deref(),
+            Some("just chatting")
+        );
+        assert_eq!(shared.config.generation(), 1);
+
+        let (incoming_tx, incoming_rx) = mpsc::channel(4);
+        let mut source = ChatSource::new("chat_messages", incoming_rx);
+        incoming_tx
+            .send(Signal::Text("scene Intro".into()))
+            .await
+            .unwrap();
+        let signal = source.poll().await.unwrap();
+        assert!(matches!(&signal, Signal::Text(text) if text == "scene Intro"));
+        assert_eq!(source.output_port(&signal), Some("text_out"));
+        drop(incoming_tx);
+        assert!(source.poll().await.is_none());
+    }
+}
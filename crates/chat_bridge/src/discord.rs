@@ -0,0 +1,172 @@
+//! Discord: posts through a channel webhook and, given a bot token, reads the
+//! channel by polling its messages. Reading needs the bot's Message Content
+//! intent enabled in the developer portal.
+
+use anyhow::anyhow;
+use serde_json::{json, Value};
+
+use crate::service::{self, ChatMessage, ChatService};
+
+const API: &str = "https://discord.com/api/v10";
+const MAX_LEN: usize = 2000;
+
+pub(crate) struct Discord {
+    agent: ureq::Agent,
+    webhook_url: String,
+    bot_token: String,
+    channel_id: String,
+    username: String,
+    last_id: Option<u64>,
+}
+
+impl Discord {
+    pub fn connect(
+        webhook_url: &str,
+        bot_token: &str,
+        channel_id: &str,
+        username: &str,
+    ) -> anyhow::Result<Self> {
+        let reads = !bot_token.trim().is_empty() && !channel_id.trim().is_empty();
+        if webhook_url.trim().is_empty() && !reads {
+            return Err(anyhow!(
+                "Discord needs a webhook URL, or a bot token and channel id"
+            ));
+        }
+        let mut discord = Self {
+            agent: service::agent(),
+            webhook_url: webhook_url.trim().to_string(),
+            bot_token: bot_token.trim().to_string(),
+            channel_id: channel_id.trim().to_string(),
+            username: username.to_string(),
+            last_id: None,
+        };
+        if reads {
+            // Start after the newest message rather than replaying history
+            let latest = discord.messages(&[("limit", "1")])?;
+            discord.last_id = Some(newest_id(&latest).unwrap_or(0));
+        }
+        Ok(discord)
+    }
+
+    fn reads(&self) -> bool {
+        !self.bot_token.is_empty() && !self.channel_id.is_empty()
+    }
+
+    fn messages(&self, query: &[(&str, &str)]) -> anyhow::Result<Value> {
+        let url = format!("{}/channels/{}/messages", API, self.channel_id);
+        let mut request = self
+            .agent
+            .get(&url)
+            .set("Authorization", &format!("Bot {}", self.bot_token));
+        for (key, value) in query {
+            request = request.query(key, value);
+        }
+        service::json(request.call())
+    }
+}
+
+impl ChatService for Discord {
+    fn max_len(&self) -> usize {
+        MAX_LEN
+    }
+
+    fn post(&mut self, text: &str) -> anyhow::Result<()> {
+        let response = if !self.webhook_url.is_empty() {
+            self.agent
+                .post(&self.webhook_url)
+                .set("Content-Type", "application/json")
+                .send_string(&webhook_body(text, &self.username).to_string())
+        } else {
+            let url = format!("{}/channels/{}/messages", API, self.channel_id);
+            self.agent
+                .post(&url)
+                .set("Authorization", &format!("Bot {}", self.bot_token))
+                .set("Content-Type", "application/json")
+                .send_string(&webhook_body(text, "").to_string())
+        };
+        service::json(response)?;
+        Ok(())
+    }
+
+    fn poll(&mut self) -> anyhow::Result<Vec<ChatMessage>> {
+        if !self.reads() {
+            return Ok(Vec::new());
+        }
+        let after = self.last_id.unwrap_or(0).to_string();
+        let body = self.messages(&[("limit", "50"), ("after", &after)])?;
+        if let Some(newest) = newest_id(&body) {
+            self.last_id = Some(newest.max(self.last_id.unwrap_or(0)));
+        }
+        Ok(parse_messages(&body))
+    }
+}
+
+/// Webhook payload; mentions are never expanded, so forwarded text can't
+/// ping `@everyone`
+pub(crate) fn webhook_body(text: &str, username: &str) -> Value {
+    let mut body = json!({ "content": text, "allowed_mentions": { "parse": [] } });
+    if !username.is_empty() {
+        body["username"] = json!(username);
+    }
+    body
+}
+
+fn id(message: &Value) -> Option<u64> {
+    message["id"].as_str()?.parse().ok()
+}
+
+fn newest_id(messages: &Value) -> Option<u64> {
+    messages.as_array()?.iter().filter_map(id).max()
+}
+
+/// People's messages, oldest first; bots and webhooks (including this
+/// bridge's own posts) are skipped
+pub(crate) fn parse_messages(messages: &Value) -> Vec<ChatMessage> {
+    let mut messages: Vec<&Value> = messages
+        .as_array()
+        .map(|m| m.iter().collect())
+        .unwrap_or_default();
+    messages.sort_by_key(|message| id(message).unwrap_or(0));
+    messages
+        .into_iter()
+        .filter(|message| message.get("webhook_id").is_none())
+        .filter(|message| !message["author"]["bot"].as_bool().unwrap_or(false))
+        .filter_map(|message| {
+            let body = message["content"].as_str()?;
+            (!body.is_empty()).then(|| ChatMessage {
+                sender: message["author"]["username"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+                body: body.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_oldest_first_without_bots() {
+        let body = json!([
+            { "id": "1200000000000000003", "content": "!lights on",
+              "author": { "username": "ana" } },
+            { "id": "1200000000000000002", "content": "from the bridge",
+              "webhook_id": "99", "author": { "username": "Magnolia", "bot": true } },
+            { "id": "1200000000000000001", "content": "hello",
+              "author": { "username": "ben" } },
+            { "id": "1200000000000000004", "content": "",
+              "author": { "username": "ana" } }
+        ]);
+        let bodies: Vec<String> = parse_messages(&body).into_iter().map(|m| m.body).collect();
+        assert_eq!(bodies, vec!["hello", "!lights on"]);
+        assert_eq!(newest_id(&body), Some(1200000000000000004));
+
+        let hook = webhook_body("@everyone hi", "Magnolia");
+        assert_eq!(hook["username"], "Magnolia");
+        assert_eq!(hook["allowed_mentions"]["parse"], json!([]));
+        assert!(webhook_body("hi", "").get("username").is_none());
+    }
+}
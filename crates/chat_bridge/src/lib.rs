@@ -0,0 +1,14 @@
+//! Chat bridge: a Matrix room or Discord channel in both directions.
+//!
+//! `Chat Bridge` (a sink) posts the Text and Computed signals patched into
+//! it; `Chat Messages` (a source) emits what remote participants write, as
+//! Text, so the intent parser can turn `!scene Intro` into an intent. Both
+//! share one thread that polls the service and reconnects with backoff.
+
+mod bridge;
+mod discord;
+mod matrix;
+mod service;
+
+pub use bridge::{spawn, ChatConfig, ChatServiceKind, ChatSink, ChatSource, ChatStatus};
+pub use service::ChatMessage;
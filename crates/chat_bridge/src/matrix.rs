@@ -0,0 +1,201 @@
+//! Matrix client-server API: posts `m.text` messages to one room and reads
+//! its timeline through `/sync`.
+
+use anyhow::anyhow;
+use serde_json::{json, Value};
+use std::time::{SystemTime, UNIX_EPOCH};
+use url::Url;
+
+use crate::service::{self, ChatMessage, ChatService};
+
+const MAX_LEN: usize = 4000;
+
+pub(crate) struct Matrix {
+    agent: ureq::Agent,
+    homeserver: Url,
+    token: String,
+    room_id: String,
+    user_id: String,
+    since: Option<String>,
+    txn_prefix: u128,
+    txn: u64,
+}
+
+impl Matrix {
+    /// Log in with an access token and join the timeline where it is now;
+    /// `room` is a room id (`!abc:server`) or alias (`#name:server`)
+    pub fn connect(homeserver: &str, token: &str, room: &str) -> anyhow::Result<Self> {
+        let homeserver =
+            Url::parse(homeserver.trim()).map_err(|e| anyhow!("invalid homeserver URL: {}", e))?;
+        if homeserver.cannot_be_a_base() {
+            return Err(anyhow!("invalid homeserver URL"));
+        }
+        if token.trim().is_empty() || room.trim().is_empty() {
+            return Err(anyhow!("Matrix needs an access token and a room"));
+        }
+        let mut matrix = Self {
+            agent: service::agent(),
+            homeserver,
+            token: token.trim().to_string(),
+            room_id: room.trim().to_string(),
+            user_id: String::new(),
+            since: None,
+            txn_prefix: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis())
+                .unwrap_or_default(),
+            txn: 0,
+        };
+        let whoami = matrix.get(&["account", "whoami"], &[])?;
+        matrix.user_id = whoami["user_id"].as_str().unwrap_or_default().to_string();
+        if matrix.room_id.starts_with('#') {
+            let alias = matrix.room_id.clone();
+            let resolved = matrix.get(&["directory", "room", &alias], &[])?;
+            matrix.room_id = resolved["room_id"]
+                .as_str()
+                .ok_or_else(|| anyhow!("no room for {}", alias))?
+                .to_string();
+        }
+        // History before connecting isn't replayed
+        matrix.poll()?;
+        Ok(matrix)
+    }
+
+    fn url(&self, path: &[&str], query: &[(&str, &str)]) -> Url {
+        let mut url = self.homeserver.clone();
+        if let Ok(mut segments) = url.path_segments_mut() {
+            segments
+                .pop_if_empty()
+                .extend(["_matrix", "client", "v3"])
+                .extend(path);
+        }
+        if !query.is_empty() {
+            url.query_pairs_mut().extend_pairs(query);
+        }
+        url
+    }
+
+    fn get(&self, path: &[&str], query: &[(&str, &str)]) -> anyhow::Result<Value> {
+        let url = self.url(path, query);
+        service::json(
+            self.agent
+                .get(url.as_str())
+                .set("Authorization", &format!("Bearer {}", self.token))
+                .call(),
+        )
+    }
+}
+
+impl ChatService for Matrix {
+    fn max_len(&self) -> usize {
+        MAX_LEN
+    }
+
+    fn post(&mut self, text: &str) -> anyhow::Result<()> {
+        self.txn += 1;
+        let txn = format!("magnolia-{}-{}", self.txn_prefix, self.txn);
+        let room_id = self.room_id.clone();
+        let url = self.url(&["rooms", &room_id, "send", "m.room.message", &txn], &[]);
+        let body = json!({ "msgtype": "m.text", "body": text });
+        service::json(
+            self.agent
+                .put(url.as_str())
+                .set("Authorization", &format!("Bearer {}", self.token))
+                .set("Content-Type", "application/json")
+                .send_string(&body.to_string()),
+        )?;
+        Ok(())
+    }
+
+    fn poll(&mut self) -> anyhow::Result<Vec<ChatMessage>> {
+        let filter = sync_filter(&self.room_id);
+        let mut query = vec![("timeout", "0"), ("filter", filter.as_str())];
+        let since = self.since.clone();
+        if let Some(since) = &since {
+            query.push(("since", since));
+        }
+        let body = self.get(&["sync"], &query)?;
+        let (next_batch, messages) = parse_sync(&body, &self.room_id, &self.user_id);
+        let first = self.since.is_none();
+        if next_batch.is_some() {
+            self.since = next_batch;
+        }
+        Ok(if first { Vec::new() } else { messages })
+    }
+}
+
+/// Only the room's timeline; no presence, account data or other rooms
+pub(crate) fn sync_filter(room_id: &str) -> String {
+    json!({
+        "presence": { "types": [] },
+        "account_data": { "types": [] },
+        "room": {
+            "rooms": [room_id],
+            "timeline": { "limit": 50, "types": ["m.room.message"] },
+            "state": { "types": [] },
+            "ephemeral": { "types": [] },
+            "account_data": { "types": [] }
+        }
+    })
+    .to_string()
+}
+
+/// `next_batch` and the room's text messages not sent by `own_user`
+pub(crate) fn parse_sync(
+    body: &Value,
+    room_id: &str,
+    own_user: &str,
+) -> (Option<String>, Vec<ChatMessage>) {
+    let next_batch = body["next_batch"].as_str().map(str::to_string);
+    let events = body["rooms"]["join"][room_id]["timeline"]["events"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    let messages = events
+        .iter()
+        .filter(|event| event["type"] == "m.room.message")
+        .filter(|event| event["sender"] != own_user)
+        .filter(|event| matches!(event["content"]["msgtype"].as_str(), Some("m.text")))
+        .filter_map(|event| {
+            Some(ChatMessage {
+                sender: event["sender"].as_str()?.to_string(),
+                body: event["content"]["body"].as_str()?.to_string(),
+            })
+        })
+        .collect();
+    (next_batch, messages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sync_keeps_other_peoples_text_messages() {
+        let room = "!stage:example.org";
+        let body = json!({
+            "next_batch": "s72595_4483",
+            "rooms": { "join": { room: { "timeline": { "events": [
+                { "type": "m.room.message", "sender": "@ana:example.org",
+                  "content": { "msgtype": "m.text", "body": "!scene Intro" } },
+                { "type": "m.room.message", "sender": "@magnolia:example.org",
+                  "content": { "msgtype": "m.text", "body": "echo" } },
+                { "type": "m.room.message", "sender": "@ana:example.org",
+                  "content": { "msgtype": "m.image", "body": "cat.png" } },
+                { "type": "m.reaction", "sender": "@ana:example.org", "content": {} }
+            ] } } } }
+        });
+        let (next, messages) = parse_sync(&body, room, "@magnolia:example.org");
+        assert_eq!(next.as_deref(), Some("s72595_4483"));
+        assert_eq!(
+            messages,
+            vec![ChatMessage {
+                sender: "@ana:example.org".into(),
+                body: "!scene Intro".into(),
+            }]
+        );
+
+        let filter: Value = serde_json::from_str(&sync_filter(room)).unwrap();
+        assert_eq!(filter["room"]["rooms"][0], room);
+    }
+}
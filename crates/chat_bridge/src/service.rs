@@ -0,0 +1,57 @@
+//! What the bridge needs from a chat service, and the HTTP plumbing the
+//! Matrix and Discord clients share.
+
+use anyhow::{anyhow, Context};
+use serde_json::Value;
+use std::time::Duration;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChatMessage {
+    pub sender: String,
+    pub body: String,
+}
+
+/// A connected room or channel. Blocking; called from the bridge thread.
+pub(crate) trait ChatService: Send {
+    /// Longest message the service takes, in characters
+    fn max_len(&self) -> usize;
+
+    fn post(&mut self, text: &str) -> anyhow::Result<()>;
+
+    /// Messages from other people since the last poll
+    fn poll(&mut self) -> anyhow::Result<Vec<ChatMessage>>;
+}
+
+pub(crate) fn agent() -> ureq::Agent {
+    ureq::AgentBuilder::new().timeout(REQUEST_TIMEOUT).build()
+}
+
+/// The JSON body of a response; error statuses carry the service's own
+/// error message where it gives one
+pub(crate) fn json(response: Result<ureq::Response, ureq::Error>) -> anyhow::Result<Value> {
+    match response {
+        Ok(response) => {
+            let body = response.into_string().context("failed to read response")?;
+            if body.trim().is_empty() {
+                return Ok(Value::Null);
+            }
+            serde_json::from_str(&body).context("response isn't JSON")
+        }
+        Err(ureq::Error::Status(code, response)) => {
+            let body: Value = response
+                .into_string()
+                .ok()
+                .and_then(|body| serde_json::from_str(&body).ok())
+                .unwrap_or_default();
+            // Matrix says `error`, Discord `message`
+            let message = body["error"]
+                .as_str()
+                .or_else(|| body["message"].as_str())
+                .unwrap_or("request failed");
+            Err(anyhow!("HTTP {}: {}", code, message))
+        }
+        Err(e) => Err(anyhow!("{}", e)),
+    }
+}
@@ -0,0 +1,10 @@
+//! Cleromancy: seeded random divination (dice, I Ching, tarot) as a processor.
+//!
+//! Each trigger signal produces one cast, emitted as a Text reading or as
+//! JSON in a Computed signal. Readings can feed kamea sigils or the LLM.
+
+pub mod oracle;
+mod processor;
+
+pub use oracle::{Cast, Hexagram, Oracle, TarotCard};
+pub use processor::{CleromancyConfig, CleromancyProcessor, OutputFormat};
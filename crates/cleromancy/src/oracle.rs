@@ -0,0 +1,423 @@
+//! Casting methods: dice, I Ching (three-coin method) and tarot.
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+pub const MAX_DICE: u32 = 100;
+pub const MAX_SIDES: u32 = 1000;
+pub const TAROT_DECK_SIZE: usize = 78;
+
+/// What to cast.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(tag = "oracle", rename_all = "snake_case")]
+pub enum Oracle {
+    Dice {
+        count: u32,
+        sides: u32,
+        modifier: i32,
+    },
+    #[default]
+    IChing,
+    Tarot {
+        count: usize,
+        reversals: bool,
+    },
+}
+
+impl Oracle {
+    /// Parse dice notation such as `d20`, `2d6` or `3d6+2`.
+    pub fn parse_dice(notation: &str) -> Option<Self> {
+        let notation = notation.trim().to_ascii_lowercase();
+        let (count, rest) = notation.split_once('d')?;
+        let count = if count.is_empty() {
+            1
+        } else {
+            count.parse().ok()?
+        };
+        let (sides, modifier) = match rest.find(['+', '-']) {
+            Some(at) => (&rest[..at], rest[at..].parse().ok()?),
+            None => (rest, 0),
+        };
+        let sides = sides.parse().ok()?;
+        if !(1..=MAX_DICE).contains(&count) || !(2..=MAX_SIDES).contains(&sides) {
+            return None;
+        }
+        Some(Oracle::Dice {
+            count,
+            sides,
+            modifier,
+        })
+    }
+
+    pub fn cast(&self, rng: &mut impl Rng) -> Cast {
+        match *self {
+            Oracle::Dice {
+                count,
+                sides,
+                modifier,
+            } => {
+                let rolls: Vec<u32> = (0..count).map(|_| rng.gen_range(1..=sides)).collect();
+                let total = rolls.iter().map(|&r| r as i64).sum::<i64>() + modifier as i64;
+                Cast::Dice {
+                    notation: dice_notation(count, sides, modifier),
+                    rolls,
+                    total,
+                }
+            }
+            Oracle::IChing => {
+                let lines = std::array::from_fn(|_| (0..3).map(|_| rng.gen_range(2..=3)).sum());
+                Cast::Hexagram(Hexagram::from_lines(lines))
+            }
+            Oracle::Tarot { count, reversals } => {
+                let mut deck: Vec<usize> = (0..TAROT_DECK_SIZE).collect();
+                let count = count.clamp(1, TAROT_DECK_SIZE);
+                let (drawn, _) = deck.partial_shuffle(rng, count);
+                let cards = drawn
+                    .iter()
+                    .map(|&index| TarotCard {
+                        name: tarot_card_name(index),
+                        reversed: reversals && rng.gen_bool(0.5),
+                    })
+                    .collect();
+                Cast::Tarot { cards }
+            }
+        }
+    }
+}
+
+fn dice_notation(count: u32, sides: u32, modifier: i32) -> String {
+    match modifier {
+        0 => format!("{}d{}", count, sides),
+        m if m > 0 => format!("{}d{}+{}", count, sides, m),
+        m => format!("{}d{}{}", count, sides, m),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TarotCard {
+    pub name: String,
+    pub reversed: bool,
+}
+
+/// A cast hexagram. Lines run bottom to top and use the coin values:
+/// 6 old yin, 7 young yang, 8 young yin, 9 old yang. Old lines change.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hexagram {
+    pub lines: [u8; 6],
+    /// King Wen number, 1..=64
+    pub number: u8,
+    pub name: String,
+    /// 1-based positions of the changing lines
+    pub changing: Vec<usize>,
+    /// Hexagram after the changing lines flip, if any changed
+    pub relating: Option<(u8, String)>,
+}
+
+impl Hexagram {
+    pub fn from_lines(lines: [u8; 6]) -> Self {
+        let yang = lines.map(|v| v == 7 || v == 9);
+        let number = king_wen(yang);
+        let changing: Vec<usize> = lines
+            .iter()
+            .enumerate()
+            .filter(|(_, &v)| v == 6 || v == 9)
+            .map(|(i, _)| i + 1)
+            .collect();
+        let relating = (!changing.is_empty()).then(|| {
+            let flipped = std::array::from_fn(|i| yang[i] != (lines[i] == 6 || lines[i] == 9));
+            let n = king_wen(flipped);
+            (n, hexagram_name(n).to_string())
+        });
+        Self {
+            lines,
+            number,
+            name: hexagram_name(number).to_string(),
+            changing,
+            relating,
+        }
+    }
+}
+
+// King Wen numbers indexed [upper][lower]. Trigram index is the yang lines
+// read bottom-up as bits: 0 Kun, 1 Zhen, 2 Kan, 3 Dui, 4 Gen, 5 Li, 6 Xun, 7 Qian.
+const KING_WEN: [[u8; 8]; 8] = [
+    [2, 24, 7, 19, 15, 36, 46, 11],
+    [16, 51, 40, 54, 62, 55, 32, 34],
+    [8, 3, 29, 60, 39, 63, 48, 5],
+    [45, 17, 47, 58, 31, 49, 28, 43],
+    [23, 27, 4, 41, 52, 22, 18, 26],
+    [35, 21, 64, 38, 56, 30, 50, 14],
+    [20, 42, 59, 61, 53, 37, 57, 9],
+    [12, 25, 6, 10, 33, 13, 44, 1],
+];
+
+fn king_wen(yang: [bool; 6]) -> u8 {
+    let trigram = |lines: &[bool]| {
+        lines
+            .iter()
+            .enumerate()
+            .map(|(i, &y)| (y as usize) << i)
+            .sum::<usize>()
+    };
+    KING_WEN[trigram(&yang[3..])][trigram(&yang[..3])]
+}
+
+const HEXAGRAM_NAMES: [&str; 64] = [
+    "The Creative",
+    "The Receptive",
+    "Difficulty at the Beginning",
+    "Youthful Folly",
+    "Waiting",
+    "Conflict",
+    "The Army",
+    "Holding Together",
+    "Small Taming",
+    "Treading",
+    "Peace",
+    "Standstill",
+    "Fellowship",
+    "Great Possession",
+    "Modesty",
+    "Enthusiasm",
+    "Following",
+    "Work on the Decayed",
+    "Approach",
+    "Contemplation",
+    "Biting Through",
+    "Grace",
+    "Splitting Apart",
+    "Return",
+    "Innocence",
+    "Great Taming",
+    "Nourishment",
+    "Great Exceeding",
+    "The Abysmal",
+    "The Clinging",
+    "Influence",
+    "Duration",
+    "Retreat",
+    "Great Power",
+    "Progress",
+    "Darkening of the Light",
+    "The Family",
+    "Opposition",
+    "Obstruction",
+    "Deliverance",
+    "Decrease",
+    "Increase",
+    "Breakthrough",
+    "Coming to Meet",
+    "Gathering Together",
+    "Pushing Upward",
+    "Oppression",
+    "The Well",
+    "Revolution",
+    "The Cauldron",
+    "The Arousing",
+    "Keeping Still",
+    "Development",
+    "The Marrying Maiden",
+    "Abundance",
+    "The Wanderer",
+    "The Gentle",
+    "The Joyous",
+    "Dispersion",
+    "Limitation",
+    "Inner Truth",
+    "Small Exceeding",
+    "After Completion",
+    "Before Completion",
+];
+
+pub fn hexagram_name(number: u8) -> &'static str {
+    HEXAGRAM_NAMES
+        .get((number as usize).wrapping_sub(1))
+        .copied()
+        .unwrap_or("Unknown")
+}
+
+const MAJOR_ARCANA: [&str; 22] = [
+    "The Fool",
+    "The Magician",
+    "The High Priestess",
+    "The Empress",
+    "The Emperor",
+    "The Hierophant",
+    "The Lovers",
+    "The Chariot",
+    "Strength",
+    "The Hermit",
+    "Wheel of Fortune",
+    "Justice",
+    "The Hanged Man",
+    "Death",
+    "Temperance",
+    "The Devil",
+    "The Tower",
+    "The Star",
+    "The Moon",
+    "The Sun",
+    "Judgement",
+    "The World",
+];
+const SUITS: [&str; 4] = ["Wands", "Cups", "Swords", "Pentacles"];
+const RANKS: [&str; 14] = [
+    "Ace", "Two", "Three", "Four", "Five", "Six", "Seven", "Eight", "Nine", "Ten", "Page",
+    "Knight", "Queen", "King",
+];
+
+/// Card name for a deck index: majors first, then each suit Ace..King.
+pub fn tarot_card_name(index: usize) -> String {
+    match index.checked_sub(MAJOR_ARCANA.len()) {
+        None => MAJOR_ARCANA[index].to_string(),
+        Some(minor) => format!(
+            "{} of {}",
+            RANKS[minor % RANKS.len()],
+            SUITS[(minor / RANKS.len()) % SUITS.len()]
+        ),
+    }
+}
+
+/// Result of one cast.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "oracle", rename_all = "snake_case")]
+pub enum Cast {
+    Dice {
+        notation: String,
+        rolls: Vec<u32>,
+        total: i64,
+    },
+    Hexagram(Hexagram),
+    Tarot {
+        cards: Vec<TarotCard>,
+    },
+}
+
+impl Cast {
+    /// One-line human reading, suitable for Text outputs.
+    pub fn describe(&self) -> String {
+        match self {
+            Cast::Dice {
+                notation,
+                rolls,
+                total,
+            } => {
+                let rolls: Vec<String> = rolls.iter().map(|r| r.to_string()).collect();
+                format!("{}: [{}] = {}", notation, rolls.join(", "), total)
+            }
+            Cast::Hexagram(hexagram) => {
+                let mut text = format!("Hexagram {}: {}", hexagram.number, hexagram.name);
+                if let Some((number, name)) = &hexagram.relating {
+                    let lines: Vec<String> =
+                        hexagram.changing.iter().map(|l| l.to_string()).collect();
+                    text.push_str(&format!(
+                        " (changing lines {}) -> {}: {}",
+                        lines.join(", "),
+                        number,
+                        name
+                    ));
+                }
+                text
+            }
+            Cast::Tarot { cards } => cards
+                .iter()
+                .map(|card| {
+                    if card.reversed {
+                        format!("{} (reversed)", card.name)
+                    } else {
+                        card.name.clone()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(", "),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    #[test]
+    fn dice_notation_round_trips() {
+        assert_eq!(
+            Oracle::parse_dice("3d6+2"),
+            Some(Oracle::Dice {
+                count: 3,
+                sides: 6,
+                modifier: 2
+            })
+        );
+        assert_eq!(
+            Oracle::parse_dice("D20"),
+            Some(Oracle::Dice {
+                count: 1,
+                sides: 20,
+                modifier: 0
+            })
+        );
+        assert_eq!(Oracle::parse_dice("2d1"), None);
+        assert_eq!(Oracle::parse_dice("six"), None);
+
+        let mut rng = ChaCha20Rng::seed_from_u64(7);
+        match Oracle::parse_dice("4d6-1").unwrap().cast(&mut rng) {
+            Cast::Dice {
+                notation,
+                rolls,
+                total,
+            } => {
+                assert_eq!(notation, "4d6-1");
+                assert!(rolls.iter().all(|r| (1..=6).contains(r)));
+                assert_eq!(total, rolls.iter().sum::<u32>() as i64 - 1);
+            }
+            other => panic!("unexpected cast {:?}", other),
+        }
+    }
+
+    #[test]
+    fn hexagram_lookup_and_changing_lines() {
+        assert_eq!(Hexagram::from_lines([7; 6]).number, 1);
+        assert_eq!(Hexagram::from_lines([8; 6]).number, 2);
+        // Water over fire
+        let after = Hexagram::from_lines([7, 8, 7, 8, 7, 8]);
+        assert_eq!(
+            (after.number, after.name.as_str()),
+            (63, "After Completion")
+        );
+        assert!(after.relating.is_none());
+
+        // Old yang at the bottom of The Creative turns it into Coming to Meet
+        let cast = Hexagram::from_lines([9, 7, 7, 7, 7, 7]);
+        assert_eq!(cast.changing, vec![1]);
+        assert_eq!(cast.relating, Some((44, "Coming to Meet".to_string())));
+
+        let mut numbers: Vec<u8> = KING_WEN.iter().flatten().copied().collect();
+        numbers.sort_unstable();
+        assert_eq!(numbers, (1..=64).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn tarot_draws_are_distinct_and_seeded() {
+        let oracle = Oracle::Tarot {
+            count: 10,
+            reversals: true,
+        };
+        let draw = |seed| oracle.cast(&mut ChaCha20Rng::seed_from_u64(seed));
+        assert_eq!(draw(42), draw(42));
+
+        let Cast::Tarot { cards } = draw(42) else {
+            panic!("expected tarot cast");
+        };
+        let mut names: Vec<_> = cards.iter().map(|c| c.name.clone()).collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), 10);
+
+        assert_eq!(tarot_card_name(0), "The Fool");
+        assert_eq!(tarot_card_name(22), "Ace of Wands");
+        assert_eq!(tarot_card_name(77), "King of Pentacles");
+    }
+}
@@ -0,0 +1,293 @@
+use async_trait::async_trait;
+use magnolia_core::{
+    DataType, ModuleSchema, ModuleSeed, Port, PortDirection, Processor, Result, SharedSeed, Signal,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::oracle::{Cast, Oracle};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum OutputFormat {
+    /// Human-readable reading as `Signal::Text`
+    #[default]
+    Text,
+    /// Full cast as JSON in `Signal::Computed`
+    Computed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CleromancyConfig {
+    /// Oracle used by bare triggers (`Pulse`, `cast`)
+    pub oracle: Oracle,
+    /// Fixed seed for reproducible sequences; `None` follows the session
+    /// seed, or entropy without one
+    pub seed: Option<u64>,
+    pub output: OutputFormat,
+}
+
+/// Casts a divination on each trigger signal.
+///
+/// Triggers are `Pulse`, or an `Intent`/`Text` command:
+/// - `cast` uses the configured oracle
+/// - `roll [NdM+K]` (default `1d6`)
+/// - `iching`
+/// - `tarot [count]` (default 1)
+/// - `seed <n>` reseeds without casting
+pub struct CleromancyProcessor {
+    id: String,
+    enabled: bool,
+    config: CleromancyConfig,
+    rng: ChaCha20Rng,
+    session_seed: Option<ModuleSeed>,
+}
+
+impl CleromancyProcessor {
+    pub fn new(id: &str) -> Self {
+        Self::with_config(id, CleromancyConfig::default())
+    }
+
+    pub fn with_config(id: &str, config: CleromancyConfig) -> Self {
+        Self {
+            id: id.to_string(),
+            enabled: true,
+            rng: seeded_rng(config.seed),
+            config,
+            session_seed: None,
+        }
+    }
+
+    /// Draw from `seeds` when no fixed seed is configured, starting over
+    /// whenever it changes
+    pub fn with_seeds(mut self, seeds: SharedSeed) -> Self {
+        self.session_seed = Some(ModuleSeed::new(seeds, &self.id));
+        self.reseed(self.config.seed);
+        self
+    }
+
+    pub fn reseed(&mut self, seed: Option<u64>) {
+        self.config.seed = seed;
+        let seed = seed.or_else(|| self.session_seed.as_ref().map(ModuleSeed::seed));
+        self.rng = seeded_rng(seed);
+    }
+
+    /// Oracle requested by a command, or `None` if it is not a cast command.
+    fn oracle_for(&mut self, action: &str, args: &[&str]) -> Option<Oracle> {
+        match action.to_ascii_lowercase().as_str() {
+            "cast" | "divine" => Some(self.config.oracle.clone()),
+            "roll" | "dice" => Oracle::parse_dice(args.first().copied().unwrap_or("1d6")),
+            "iching" | "hexagram" => Some(Oracle::IChing),
+            "tarot" | "draw" => Some(Oracle::Tarot {
+                count: args.first().and_then(|n| n.parse().ok()).unwrap_or(1),
+                reversals: match self.config.oracle {
+                    Oracle::Tarot { reversals, .. } => reversals,
+                    _ => true,
+                },
+            }),
+            "seed" => {
+                let seed = args.first().and_then(|n| n.parse().ok());
+                self.reseed(seed);
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn render(&self, cast: &Cast) -> Signal {
+        match self.config.output {
+            OutputFormat::Text => Signal::Text(cast.describe()),
+            OutputFormat::Computed => {
+                let mut content = serde_json::to_value(cast).unwrap_or_default();
+                if let Some(fields) = content.as_object_mut() {
+                    fields.insert("text".to_string(), cast.describe().into());
+                }
+                Signal::Computed {
+                    source: "cleromancy".to_string(),
+                    content: content.to_string(),
+                }
+            }
+        }
+    }
+}
+
+fn seeded_rng(seed: Option<u64>) -> ChaCha20Rng {
+    match seed {
+        Some(seed) => ChaCha20Rng::seed_from_u64(seed),
+        None => ChaCha20Rng::from_entropy(),
+    }
+}
+
+#[async_trait]
+impl Processor for CleromancyProcessor {
+    fn name(&self) -> &str {
+        "Cleromancy"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "Cleromancy".to_string(),
+            description: "Dice, I Ching and tarot casts on trigger".to_string(),
+            ports: vec![
+                Port {
+                    id: "trigger_in".to_string(),
+                    label: "Trigger".to_string(),
+                    data_type: DataType::Any,
+                    direction: PortDirection::Input,
+                },
+                Port {
+                    id: "reading_out".to_string(),
+                    label: "Reading".to_string(),
+                    data_type: DataType::Text,
+                    direction: PortDirection::Output,
+                },
+            ],
+            settings_schema: None,
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    async fn process(&mut self, signal: Signal) -> Result<Option<Signal>> {
+        let oracle = match &signal {
+            Signal::Pulse => Some(self.config.oracle.clone()),
+            Signal::Intent { action, parameters } => {
+                let args: Vec<&str> = parameters.iter().map(String::as_str).collect();
+                self.oracle_for(action, &args)
+            }
+            Signal::Text(command) => {
+                let mut words = command.split_whitespace();
+                let action = words.next().unwrap_or_default().to_string();
+                let args: Vec<&str> = words.collect();
+                self.oracle_for(&action, &args)
+            }
+            _ => None,
+        };
+
+        if self.config.seed.is_none() {
+            if let Some(seed) = self.session_seed.as_mut().and_then(ModuleSeed::changed) {
+                self.rng = seeded_rng(Some(seed));
+            }
+        }
+        Ok(oracle.map(|oracle| {
+            let cast = oracle.cast(&mut self.rng);
+            log::debug!("[CLEROMANCY] {}", cast.describe());
+            self.render(&cast)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use magnolia_core::SeedSource;
+
+    fn seeded(output: OutputFormat) -> CleromancyProcessor {
+        CleromancyProcessor::with_config(
+            "cleromancy",
+            CleromancyConfig {
+                seed: Some(1234),
+                output,
+                ..CleromancyConfig::default()
+            },
+        )
+    }
+
+    fn text(signal: Option<Signal>) -> String {
+        match signal {
+            Some(Signal::Text(text)) => text,
+            other => panic!("expected text, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn seeded_sequences_repeat() {
+        let mut a = seeded(OutputFormat::Text);
+        let mut b = seeded(OutputFormat::Text);
+        for _ in 0..3 {
+            let first = text(a.process(Signal::Pulse).await.unwrap());
+            assert!(first.starts_with("Hexagram "));
+            assert_eq!(first, text(b.process(Signal::Pulse).await.unwrap()));
+        }
+
+        // Reseeding restarts the sequence
+        let replay = text(a.process(Signal::Text("roll 2d6".into())).await.unwrap());
+        assert!(a
+            .process(Signal::Text("seed 1234".into()))
+            .await
+            .unwrap()
+            .is_none());
+        let mut fresh = seeded(OutputFormat::Text);
+        let first = text(
+            fresh
+                .process(Signal::Text("roll 2d6".into()))
+                .await
+                .unwrap(),
+        );
+        let again = text(a.process(Signal::Text("roll 2d6".into())).await.unwrap());
+        assert_eq!(first, again);
+        assert!(replay.starts_with("2d6: ["));
+    }
+
+    #[tokio::test]
+    async fn session_seeds_replay_unless_a_seed_is_fixed() {
+        let seeds = SeedSource::new(99);
+        let mut a = CleromancyProcessor::new("cleromancy").with_seeds(seeds.clone());
+        let mut b = CleromancyProcessor::new("cleromancy").with_seeds(SeedSource::new(99));
+        let first = text(a.process(Signal::Pulse).await.unwrap());
+        assert_eq!(first, text(b.process(Signal::Pulse).await.unwrap()));
+
+        // A new session seed starts a new sequence, and the old one replays
+        seeds.set(5);
+        text(a.process(Signal::Pulse).await.unwrap());
+        seeds.set(99);
+        assert_eq!(first, text(a.process(Signal::Pulse).await.unwrap()));
+
+        let mut fixed = seeded(OutputFormat::Text).with_seeds(seeds.clone());
+        let fixed_first = text(fixed.process(Signal::Pulse).await.unwrap());
+        seeds.set(5);
+        let mut again = seeded(OutputFormat::Text);
+        text(again.process(Signal::Pulse).await.unwrap());
+        assert_eq!(
+            text(fixed.process(Signal::Pulse).await.unwrap()),
+            text(again.process(Signal::Pulse).await.unwrap())
+        );
+        assert!(fixed_first.starts_with("Hexagram "));
+    }
+
+    #[tokio::test]
+    async fn intents_pick_the_oracle_and_json_output() {
+        let mut processor = seeded(OutputFormat::Computed);
+        let signal = processor
+            .process(Signal::Intent {
+                action: "tarot".to_string(),
+                parameters: vec!["3".to_string()],
+            })
+            .await
+            .unwrap();
+        let Some(Signal::Computed { source, content }) = signal else {
+            panic!("expected computed output");
+        };
+        assert_eq!(source, "cleromancy");
+        let json: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(json["oracle"], "tarot");
+        assert_eq!(json["cards"].as_array().unwrap().len(), 3);
+        assert!(json["text"].is_string());
+
+        // Unrelated text is not a trigger
+        assert!(processor
+            .process(Signal::Text("hello there".into()))
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
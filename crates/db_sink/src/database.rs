@@ -0,0 +1,80 @@
+//! What the sink needs from a backend, and the schema migrations it runs
+//! on connect.
+
+use magnolia_core::LibraryError;
+use thiserror::Error;
+
+use crate::row::Row;
+
+#[derive(Debug, Error)]
+pub enum DbError {
+    #[error("could not load {library}: {message}")]
+    Library { library: String, message: String },
+    #[error("{0}")]
+    Database(String),
+}
+
+impl From<LibraryError> for DbError {
+    fn from(e: LibraryError) -> Self {
+        Self::Library {
+            library: e.library,
+            message: e.message,
+        }
+    }
+}
+
+/// One schema version; its statements run in a single transaction
+pub struct Migration {
+    pub version: u32,
+    pub statements: &'static [&'static str],
+}
+
+pub trait Database: Send {
+    /// Ascending by version
+    fn migrations(&self) -> &'static [Migration];
+
+    /// A statement that returns no rows
+    fn execute(&mut self, sql: &str) -> Result<(), DbError>;
+
+    /// Highest version recorded in `schema_version`; 0 when it's empty
+    fn schema_version(&mut self) -> Result<u32, DbError>;
+
+    /// All of `rows` or none of them
+    fn insert(&mut self, rows: &[Row]) -> Result<(), DbError>;
+}
+
+/// Brings the schema up to date; returns the version it's at
+pub fn migrate(db: &mut dyn Database) -> Result<u32, DbError> {
+    db.execute("CREATE TABLE IF NOT EXISTS schema_version (version INTEGER NOT NULL)")?;
+    let mut version = db.schema_version()?;
+    let latest = db.migrations().last().map_or(0, |m| m.version);
+    if version > latest {
+        return Err(DbError::Database(format!(
+            "database schema v{} is newer than this build's v{}",
+            version, latest
+        )));
+    }
+
+    let current = version;
+    for migration in db.migrations().iter().filter(|m| m.version > current) {
+        db.execute("BEGIN")?;
+        let applied = migration
+            .statements
+            .iter()
+            .try_for_each(|sql| db.execute(sql))
+            .and_then(|_| {
+                db.execute(&format!(
+                    "INSERT INTO schema_version (version) VALUES ({})",
+                    migration.version
+                ))
+            });
+        if let Err(e) = applied {
+            let _ = db.execute("ROLLBACK");
+            return Err(e);
+        }
+        db.execute("COMMIT")?;
+        log::info!("Database sink: migrated schema to v{}", migration.version);
+        version = migration.version;
+    }
+    Ok(version)
+}
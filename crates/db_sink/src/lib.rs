@@ -0,0 +1,17 @@
+//! Database sink: routed signals as rows in SQLite or Postgres.
+//!
+//! Each signal becomes one row in a `signals` table (time, type, source,
+//! JSON payload), written in batches from a thread of its own so the
+//! router never waits on disk or network. The schema is versioned and
+//! migrated on connect. Both client libraries are loaded at run time;
+//! only the configured backend has to be installed.
+
+mod database;
+mod native;
+mod postgres;
+mod row;
+mod sink;
+mod sqlite;
+
+pub use database::DbError;
+pub use sink::{DbBackend, DbConfig, DbSink, DbStatus};
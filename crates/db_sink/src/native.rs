@@ -0,0 +1,20 @@
+//! C strings across the client libraries, which are loaded at run time
+//! (see [`magnolia_core::native_library`]) so only logging to a database
+//! needs one installed.
+
+use std::ffi::{c_char, CStr, CString};
+
+use crate::database::DbError;
+
+pub fn c_string(value: &str) -> Result<CString, DbError> {
+    CString::new(value).map_err(|_| DbError::Database("unexpected NUL byte".to_string()))
+}
+
+/// # Safety
+/// `ptr` is null or a NUL-terminated string that outlives the call.
+pub unsafe fn read_string(ptr: *const c_char) -> String {
+    if ptr.is_null() {
+        return String::new();
+    }
+    CStr::from_ptr(ptr).to_string_lossy().trim().to_string()
+}
@@ -0,0 +1,290 @@
+//! Postgres through the system libpq.
+
+use libloading::Library;
+use magnolia_core::native_library::{load_library, symbol};
+use std::ffi::{c_char, c_int, c_void, CString};
+
+use crate::database::{Database, DbError, Migration};
+use crate::native;
+use crate::row::Row;
+
+type ConnectFn = unsafe extern "C" fn(*const c_char) -> *mut c_void;
+type StatusFn = unsafe extern "C" fn(*const c_void) -> c_int;
+type MessageFn = unsafe extern "C" fn(*const c_void) -> *const c_char;
+type FinishFn = unsafe extern "C" fn(*mut c_void);
+type NoticeProcessor = unsafe extern "C" fn(*mut c_void, *const c_char);
+type SetNoticeProcessorFn =
+    unsafe extern "C" fn(*mut c_void, NoticeProcessor, *mut c_void) -> Option<NoticeProcessor>;
+type ExecFn = unsafe extern "C" fn(*mut c_void, *const c_char) -> *mut c_void;
+type ExecParamsFn = unsafe extern "C" fn(
+    *mut c_void,
+    *const c_char,
+    c_int,
+    *const u32,
+    *const *const c_char,
+    *const c_int,
+    *const c_int,
+    c_int,
+) -> *mut c_void;
+type GetValueFn = unsafe extern "C" fn(*const c_void, c_int, c_int) -> *const c_char;
+type ClearFn = unsafe extern "C" fn(*mut c_void);
+
+const LIBRARY_NAMES: &[&str] = if cfg!(windows) {
+    &["libpq.dll"]
+} else if cfg!(target_os = "macos") {
+    &[
+        "libpq.5.dylib",
+        "/opt/homebrew/opt/libpq/lib/libpq.5.dylib",
+        "/usr/local/opt/libpq/lib/libpq.5.dylib",
+    ]
+} else {
+    &["libpq.so.5", "libpq.so"]
+};
+
+const CONNECTION_OK: c_int = 0;
+const PGRES_COMMAND_OK: c_int = 1;
+const PGRES_TUPLES_OK: c_int = 2;
+/// Rows per INSERT; four parameters each, well under the 65535 limit
+const ROWS_PER_STATEMENT: usize = 1000;
+
+const MIGRATIONS: &[Migration] = &[Migration {
+    version: 1,
+    statements: &[
+        "CREATE TABLE signals (
+            id BIGSERIAL PRIMARY KEY,
+            timestamp_us BIGINT NOT NULL,
+            signal_type TEXT NOT NULL,
+            source TEXT NOT NULL,
+            payload JSONB NOT NULL
+        )",
+        "CREATE INDEX signals_timestamp ON signals (timestamp_us)",
+        "CREATE INDEX signals_type_timestamp ON signals (signal_type, timestamp_us)",
+    ],
+}];
+
+struct Api {
+    error_message: MessageFn,
+    finish: FinishFn,
+    exec: ExecFn,
+    exec_params: ExecParamsFn,
+    result_status: StatusFn,
+    result_error_message: MessageFn,
+    get_value: GetValueFn,
+    clear: ClearFn,
+}
+
+pub struct Postgres {
+    conn: *mut c_void,
+    api: Api,
+    _library: Library,
+}
+
+// The connection is only touched through `&mut self`
+unsafe impl Send for Postgres {}
+
+/// A `PGresult`, cleared on drop
+struct QueryResult<'a> {
+    result: *mut c_void,
+    postgres: &'a Postgres,
+}
+
+impl Drop for QueryResult<'_> {
+    fn drop(&mut self) {
+        // Safety: `result` came from exec and is cleared once
+        unsafe { (self.postgres.api.clear)(self.result) };
+    }
+}
+
+/// libpq writes notices (`already exists, skipping`) to stderr otherwise
+unsafe extern "C" fn log_notice(_arg: *mut c_void, message: *const c_char) {
+    log::debug!("Database sink: {}", native::read_string(message));
+}
+
+/// Postgres text can't hold NUL, so it's dropped rather than failing the
+/// batch
+fn param(value: &str) -> CString {
+    CString::new(value.replace('\0', "")).unwrap_or_default()
+}
+
+/// `INSERT ... VALUES ($1, $2, $3, $4::jsonb), ($5, ...)` for `rows` rows
+fn insert_sql(rows: usize) -> String {
+    let values: Vec<String> = (0..rows)
+        .map(|row| {
+            let n = row * 4;
+            format!("(${}, ${}, ${}, ${}::jsonb)", n + 1, n + 2, n + 3, n + 4)
+        })
+        .collect();
+    format!(
+        "INSERT INTO signals (timestamp_us, signal_type, source, payload) VALUES {}",
+        values.join(", ")
+    )
+}
+
+impl Postgres {
+    /// `conninfo` is a `postgres://` URL or `host=... dbname=...` pairs
+    pub fn connect(conninfo: &str) -> Result<Self, DbError> {
+        let library = load_library("libpq", LIBRARY_NAMES)?;
+        // Safety: the signatures match libpq-fe.h
+        let (connect, status, set_notice_processor, api) = unsafe {
+            (
+                symbol::<ConnectFn>(&library, b"PQconnectdb\0")?,
+                symbol::<StatusFn>(&library, b"PQstatus\0")?,
+                symbol::<SetNoticeProcessorFn>(&library, b"PQsetNoticeProcessor\0")?,
+                Api {
+                    error_message: symbol(&library, b"PQerrorMessage\0")?,
+                    finish: symbol(&library, b"PQfinish\0")?,
+                    exec: symbol(&library, b"PQexec\0")?,
+                    exec_params: symbol(&library, b"PQexecParams\0")?,
+                    result_status: symbol(&library, b"PQresultStatus\0")?,
+                    result_error_message: symbol(&library, b"PQresultErrorMessage\0")?,
+                    get_value: symbol(&library, b"PQgetvalue\0")?,
+                    clear: symbol(&library, b"PQclear\0")?,
+                },
+            )
+        };
+
+        let c_conninfo = native::c_string(conninfo)?;
+        // Safety: `c_conninfo` is NUL-terminated; a failed connection still
+        // returns a handle to read the error from and finish
+        let conn = unsafe { connect(c_conninfo.as_ptr()) };
+        if conn.is_null() {
+            return Err(DbError::Database("libpq: out of memory".to_string()));
+        }
+        let postgres = Self {
+            conn,
+            api,
+            _library: library,
+        };
+        // Safety: `conn` is a live handle
+        unsafe {
+            if status(conn) != CONNECTION_OK {
+                return Err(postgres.error("connect"));
+            }
+            set_notice_processor(conn, log_notice, std::ptr::null_mut());
+        }
+        Ok(postgres)
+    }
+
+    fn error(&self, what: &str) -> DbError {
+        // Safety: `conn` is live and the message outlives the copy
+        let message = unsafe { native::read_string((self.api.error_message)(self.conn)) };
+        DbError::Database(format!("{}: {}", what, message))
+    }
+
+    /// Wraps `result`, failing unless its status is one of `ok`
+    fn checked(&self, result: *mut c_void, ok: c_int) -> Result<QueryResult<'_>, DbError> {
+        if result.is_null() {
+            return Err(self.error("query"));
+        }
+        let result = QueryResult {
+            result,
+            postgres: self,
+        };
+        // Safety: `result` is live until the wrapper drops
+        unsafe {
+            if (self.api.result_status)(result.result) != ok {
+                let message = native::read_string((self.api.result_error_message)(result.result));
+                return Err(DbError::Database(message));
+            }
+        }
+        Ok(result)
+    }
+
+    fn query(&self, sql: &str, ok: c_int) -> Result<QueryResult<'_>, DbError> {
+        let c_sql = native::c_string(sql)?;
+        // Safety: `conn` is live and `c_sql` is NUL-terminated
+        let result = unsafe { (self.api.exec)(self.conn, c_sql.as_ptr()) };
+        self.checked(result, ok)
+    }
+
+    fn insert_rows(&self, rows: &[Row]) -> Result<(), DbError> {
+        for chunk in rows.chunks(ROWS_PER_STATEMENT) {
+            let values: Vec<CString> = chunk
+                .iter()
+                .flat_map(|row| {
+                    [
+                        param(&row.timestamp_us.to_string()),
+                        param(row.signal_type),
+                        param(&row.source),
+                        param(&row.payload),
+                    ]
+                })
+                .collect();
+            let pointers: Vec<*const c_char> = values.iter().map(|v| v.as_ptr()).collect();
+            let sql = native::c_string(&insert_sql(chunk.len()))?;
+            // Safety: `pointers` has one NUL-terminated text parameter per
+            // placeholder, alive for the call
+            let result = unsafe {
+                (self.api.exec_params)(
+                    self.conn,
+                    sql.as_ptr(),
+                    pointers.len() as c_int,
+                    std::ptr::null(),
+                    pointers.as_ptr(),
+                    std::ptr::null(),
+                    std::ptr::null(),
+                    0,
+                )
+            };
+            self.checked(result, PGRES_COMMAND_OK)?;
+        }
+        Ok(())
+    }
+}
+
+impl Database for Postgres {
+    fn migrations(&self) -> &'static [Migration] {
+        MIGRATIONS
+    }
+
+    fn execute(&mut self, sql: &str) -> Result<(), DbError> {
+        self.query(sql, PGRES_COMMAND_OK).map(|_| ())
+    }
+
+    fn schema_version(&mut self) -> Result<u32, DbError> {
+        let result = self.query(
+            "SELECT COALESCE(MAX(version), 0) FROM schema_version",
+            PGRES_TUPLES_OK,
+        )?;
+        // Safety: the query returns one row with one column
+        let value = unsafe { native::read_string((self.api.get_value)(result.result, 0, 0)) };
+        value
+            .parse()
+            .map_err(|_| DbError::Database(format!("bad schema version {:?}", value)))
+    }
+
+    fn insert(&mut self, rows: &[Row]) -> Result<(), DbError> {
+        self.execute("BEGIN")?;
+        match self.insert_rows(rows) {
+            Ok(()) => self.execute("COMMIT"),
+            Err(e) => {
+                let _ = self.execute("ROLLBACK");
+                Err(e)
+            }
+        }
+    }
+}
+
+impl Drop for Postgres {
+    fn drop(&mut self) {
+        // Safety: `conn` came from connect and is finished once
+        unsafe { (self.api.finish)(self.conn) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batched_insert_placeholders() {
+        assert_eq!(
+            insert_sql(2),
+            "INSERT INTO signals (timestamp_us, signal_type, source, payload) \
+             VALUES ($1, $2, $3, $4::jsonb), ($5, $6, $7, $8::jsonb)"
+        );
+        assert_eq!(param("a\0b").to_str().unwrap(), "ab");
+        let versions: Vec<u32> = MIGRATIONS.iter().map(|m| m.version).collect();
+        assert!(versions.windows(2).all(|w| w[0] < w[1]));
+    }
+}
@@ -0,0 +1,124 @@
+//! A signal as a table row.
+
+use magnolia_core::Signal;
+use serde_json::json;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+    /// When the sink received it, in microseconds since the Unix epoch
+    pub timestamp_us: i64,
+    /// The variant name, as in the signal's serialized `type` tag
+    pub signal_type: &'static str,
+    /// The producing module where the signal names it (`Computed`), else
+    /// empty
+    pub source: String,
+    /// JSON; media is summarised rather than stored
+    pub payload: String,
+}
+
+pub fn now_us() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_micros() as i64)
+}
+
+/// Audio, blobs and frames arrive many times a second and mostly aren't
+/// worth a row each
+fn is_media(signal: &Signal) -> bool {
+    matches!(
+        signal,
+        Signal::Blob { .. }
+            | Signal::BlobHandle { .. }
+            | Signal::Audio { .. }
+            | Signal::AudioHandle { .. }
+            | Signal::SharedAudio { .. }
+            | Signal::SharedBlob { .. }
+            | Signal::Texture { .. }
+    )
+}
+
+impl Row {
+    /// `None` for streams, which can't be logged, and for media unless
+    /// `log_media` is set
+    pub fn from_signal(signal: &Signal, timestamp_us: i64, log_media: bool) -> Option<Self> {
+        if is_media(signal) && !log_media {
+            return None;
+        }
+        let mut source = String::new();
+        let (signal_type, payload) = match signal {
+            Signal::Text(text) => ("Text", json!(text)),
+            Signal::Intent { action, parameters } => (
+                "Intent",
+                json!({ "action": action, "parameters": parameters }),
+            ),
+            Signal::Astrology(data) => ("Astrology", serde_json::to_value(data).ok()?),
+            Signal::Blob { .. } | Signal::SharedBlob { .. } => {
+                let blob = signal.blob()?;
+                (
+                    "Blob",
+                    json!({ "mime_type": blob.mime_type, "size": blob.bytes.len() }),
+                )
+            }
+            Signal::BlobHandle { handle, mime_type } => (
+                "BlobHandle",
+                json!({ "mime_type": mime_type, "size": handle.size }),
+            ),
+            // Shared or not depends only on how many sinks it went to
+            Signal::Audio { .. } | Signal::SharedAudio { .. } => {
+                let audio = signal.audio()?;
+                (
+                    "Audio",
+                    json!({
+                        "sample_rate": audio.sample_rate,
+                        "channels": audio.channels,
+                        "timestamp_us": audio.timestamp_us,
+                        "samples": audio.data.len(),
+                    }),
+                )
+            }
+            Signal::AudioHandle {
+                handle,
+                sample_rate,
+                channels,
+            } => (
+                "AudioHandle",
+                json!({
+                    "sample_rate": sample_rate,
+                    "channels": channels,
+                    "samples": handle.length,
+                }),
+            ),
+            Signal::Texture { handle, .. } => (
+                "Texture",
+                json!({ "width": handle.width, "height": handle.height }),
+            ),
+            Signal::AudioStream { .. } => return None,
+            Signal::Control(control) => ("Control", serde_json::to_value(control).ok()?),
+            Signal::Computed {
+                source: name,
+                content,
+            } => {
+                source = name.clone();
+                // Most computed content is JSON already; keep it queryable
+                let content = serde_json::from_str(content).unwrap_or_else(|_| json!(content));
+                ("Computed", content)
+            }
+            Signal::Midi {
+                timestamp_us,
+                status,
+                data,
+            } => (
+                "Midi",
+                json!({ "timestamp_us": timestamp_us, "status": status, "data": data }),
+            ),
+            Signal::Pulse => ("Pulse", serde_json::Value::Null),
+        };
+        Some(Self {
+            timestamp_us,
+            signal_type,
+            source,
+            payload: payload.to_string(),
+        })
+    }
+}
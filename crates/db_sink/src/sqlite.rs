@@ -0,0 +1,307 @@
+//! SQLite through the system libsqlite3.
+
+use libloading::Library;
+use magnolia_core::native_library::{load_library, symbol};
+use std::ffi::{c_char, c_int, c_void};
+use std::path::Path;
+
+use crate::database::{Database, DbError, Migration};
+use crate::native;
+use crate::row::Row;
+
+type OpenFn = unsafe extern "C" fn(*const c_char, *mut *mut c_void, c_int, *const c_char) -> c_int;
+type CloseFn = unsafe extern "C" fn(*mut c_void) -> c_int;
+type ErrmsgFn = unsafe extern "C" fn(*mut c_void) -> *const c_char;
+type BusyTimeoutFn = unsafe extern "C" fn(*mut c_void, c_int) -> c_int;
+type ExecFn = unsafe extern "C" fn(
+    *mut c_void,
+    *const c_char,
+    *const c_void,
+    *mut c_void,
+    *mut *mut c_char,
+) -> c_int;
+type PrepareFn = unsafe extern "C" fn(
+    *mut c_void,
+    *const c_char,
+    c_int,
+    *mut *mut c_void,
+    *mut *const c_char,
+) -> c_int;
+type BindInt64Fn = unsafe extern "C" fn(*mut c_void, c_int, i64) -> c_int;
+type BindTextFn =
+    unsafe extern "C" fn(*mut c_void, c_int, *const c_char, c_int, *const c_void) -> c_int;
+type StatementFn = unsafe extern "C" fn(*mut c_void) -> c_int;
+type ColumnInt64Fn = unsafe extern "C" fn(*mut c_void, c_int) -> i64;
+
+const LIBRARY_NAMES: &[&str] = if cfg!(windows) {
+    &["sqlite3.dll"]
+} else if cfg!(target_os = "macos") {
+    &["libsqlite3.dylib", "/usr/lib/libsqlite3.dylib"]
+} else {
+    &["libsqlite3.so.0", "libsqlite3.so"]
+};
+
+const SQLITE_OK: c_int = 0;
+const SQLITE_ROW: c_int = 100;
+const SQLITE_DONE: c_int = 101;
+const SQLITE_OPEN_READWRITE: c_int = 0x02;
+const SQLITE_OPEN_CREATE: c_int = 0x04;
+/// Analytics queries holding a lock get this long before a batch fails
+const BUSY_TIMEOUT_MS: c_int = 5000;
+
+const MIGRATIONS: &[Migration] = &[Migration {
+    version: 1,
+    statements: &[
+        "CREATE TABLE signals (
+            id INTEGER PRIMARY KEY,
+            timestamp_us INTEGER NOT NULL,
+            signal_type TEXT NOT NULL,
+            source TEXT NOT NULL,
+            payload TEXT NOT NULL
+        )",
+        "CREATE INDEX signals_timestamp ON signals (timestamp_us)",
+        "CREATE INDEX signals_type_timestamp ON signals (signal_type, timestamp_us)",
+    ],
+}];
+
+const INSERT: &str =
+    "INSERT INTO signals (timestamp_us, signal_type, source, payload) VALUES (?1, ?2, ?3, ?4)";
+
+struct Api {
+    close: CloseFn,
+    errmsg: ErrmsgFn,
+    exec: ExecFn,
+    prepare: PrepareFn,
+    bind_int64: BindInt64Fn,
+    bind_text: BindTextFn,
+    step: StatementFn,
+    reset: StatementFn,
+    finalize: StatementFn,
+    column_int64: ColumnInt64Fn,
+}
+
+pub struct Sqlite {
+    db: *mut c_void,
+    api: Api,
+    _library: Library,
+}
+
+// The connection is only touched through `&mut self`
+unsafe impl Send for Sqlite {}
+
+/// A prepared statement, finalized on drop
+struct Statement<'a> {
+    stmt: *mut c_void,
+    sqlite: &'a Sqlite,
+}
+
+impl Drop for Statement<'_> {
+    fn drop(&mut self) {
+        // Safety: `stmt` came from prepare and is finalized once
+        unsafe { (self.sqlite.api.finalize)(self.stmt) };
+    }
+}
+
+impl Sqlite {
+    pub fn open(path: &str) -> Result<Self, DbError> {
+        if let Some(parent) = Path::new(path).parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| DbError::Database(format!("{}: {}", parent.display(), e)))?;
+            }
+        }
+        let library = load_library("libsqlite3", LIBRARY_NAMES)?;
+        // Safety: the signatures match sqlite3.h
+        let (open, busy_timeout, api) = unsafe {
+            (
+                symbol::<OpenFn>(&library, b"sqlite3_open_v2\0")?,
+                symbol::<BusyTimeoutFn>(&library, b"sqlite3_busy_timeout\0")?,
+                Api {
+                    close: symbol(&library, b"sqlite3_close_v2\0")?,
+                    errmsg: symbol(&library, b"sqlite3_errmsg\0")?,
+                    exec: symbol(&library, b"sqlite3_exec\0")?,
+                    prepare: symbol(&library, b"sqlite3_prepare_v2\0")?,
+                    bind_int64: symbol(&library, b"sqlite3_bind_int64\0")?,
+                    bind_text: symbol(&library, b"sqlite3_bind_text\0")?,
+                    step: symbol(&library, b"sqlite3_step\0")?,
+                    reset: symbol(&library, b"sqlite3_reset\0")?,
+                    finalize: symbol(&library, b"sqlite3_finalize\0")?,
+                    column_int64: symbol(&library, b"sqlite3_column_int64\0")?,
+                },
+            )
+        };
+
+        let c_path = native::c_string(path)?;
+        let mut db = std::ptr::null_mut();
+        // Safety: `db` is written before open returns, even on failure
+        let rc = unsafe {
+            open(
+                c_path.as_ptr(),
+                &mut db,
+                SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE,
+                std::ptr::null(),
+            )
+        };
+        let sqlite = Self {
+            db,
+            api,
+            _library: library,
+        };
+        if db.is_null() {
+            return Err(DbError::Database(format!("{}: out of memory", path)));
+        }
+        sqlite.check(rc, path)?;
+        // Safety: `db` is open
+        unsafe { busy_timeout(db, BUSY_TIMEOUT_MS) };
+        Ok(sqlite)
+    }
+
+    fn error(&self, what: &str) -> DbError {
+        // Safety: `db` is open and the message outlives the copy
+        let message = unsafe { native::read_string((self.api.errmsg)(self.db)) };
+        DbError::Database(format!("{}: {}", what, message))
+    }
+
+    fn check(&self, rc: c_int, what: &str) -> Result<(), DbError> {
+        if rc == SQLITE_OK {
+            Ok(())
+        } else {
+            Err(self.error(what))
+        }
+    }
+
+    fn prepare(&self, sql: &str) -> Result<Statement<'_>, DbError> {
+        let c_sql = native::c_string(sql)?;
+        let mut stmt = std::ptr::null_mut();
+        // Safety: `db` is open and `c_sql` is NUL-terminated
+        let rc = unsafe {
+            (self.api.prepare)(self.db, c_sql.as_ptr(), -1, &mut stmt, std::ptr::null_mut())
+        };
+        self.check(rc, "prepare")?;
+        Ok(Statement { stmt, sqlite: self })
+    }
+
+    fn insert_rows(&self, rows: &[Row]) -> Result<(), DbError> {
+        let statement = self.prepare(INSERT)?;
+        let stmt = statement.stmt;
+        for row in rows {
+            let texts = [row.signal_type, row.source.as_str(), row.payload.as_str()];
+            // Safety: the statement is live, and the texts outlive the step
+            // that reads them (a null destructor is SQLITE_STATIC)
+            unsafe {
+                self.check((self.api.bind_int64)(stmt, 1, row.timestamp_us), "bind")?;
+                for (index, text) in texts.iter().enumerate() {
+                    let rc = (self.api.bind_text)(
+                        stmt,
+                        index as c_int + 2,
+                        text.as_ptr().cast(),
+                        text.len() as c_int,
+                        std::ptr::null(),
+                    );
+                    self.check(rc, "bind")?;
+                }
+                if (self.api.step)(stmt) != SQLITE_DONE {
+                    return Err(self.error("insert"));
+                }
+                (self.api.reset)(stmt);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Database for Sqlite {
+    fn migrations(&self) -> &'static [Migration] {
+        MIGRATIONS
+    }
+
+    fn execute(&mut self, sql: &str) -> Result<(), DbError> {
+        let c_sql = native::c_string(sql)?;
+        // Safety: `db` is open; no callback, no error string
+        let rc = unsafe {
+            (self.api.exec)(
+                self.db,
+                c_sql.as_ptr(),
+                std::ptr::null(),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            )
+        };
+        self.check(rc, "execute")
+    }
+
+    fn schema_version(&mut self) -> Result<u32, DbError> {
+        let statement = self.prepare("SELECT COALESCE(MAX(version), 0) FROM schema_version")?;
+        // Safety: the statement is live
+        unsafe {
+            if (self.api.step)(statement.stmt) != SQLITE_ROW {
+                return Err(self.error("schema version"));
+            }
+            Ok((self.api.column_int64)(statement.stmt, 0) as u32)
+        }
+    }
+
+    fn insert(&mut self, rows: &[Row]) -> Result<(), DbError> {
+        self.execute("BEGIN")?;
+        match self.insert_rows(rows) {
+            Ok(()) => self.execute("COMMIT"),
+            Err(e) => {
+                let _ = self.execute("ROLLBACK");
+                Err(e)
+            }
+        }
+    }
+}
+
+impl Drop for Sqlite {
+    fn drop(&mut self) {
+        // Safety: `db` came from open and is closed once
+        unsafe { (self.api.close)(self.db) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::migrate;
+
+    #[test]
+    fn migrates_and_inserts() {
+        let path = std::env::temp_dir().join(format!("magnolia-db-sink-{}.db", std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+        let _ = std::fs::remove_file(&path);
+        let mut db = match Sqlite::open(&path) {
+            Ok(db) => db,
+            Err(DbError::Library { .. }) => return, // libsqlite3 isn't installed
+            Err(e) => panic!("{}", e),
+        };
+        assert_eq!(migrate(&mut db).unwrap(), 1);
+        // Already current: nothing runs twice
+        assert_eq!(migrate(&mut db).unwrap(), 1);
+
+        let row = |signal_type, payload: &str| Row {
+            timestamp_us: 1_700_000_000_000_000,
+            signal_type,
+            source: "weather".into(),
+            payload: payload.into(),
+        };
+        db.insert(&[
+            row("Computed", r#"{"temp":12}"#),
+            row("Text", r#""it's 'quoted'""#),
+        ])
+        .unwrap();
+        let count = db
+            .prepare("SELECT COUNT(*) FROM signals WHERE source = 'weather'")
+            .unwrap();
+        // Safety: the statement is live
+        unsafe {
+            assert_eq!((db.api.step)(count.stmt), SQLITE_ROW);
+            assert_eq!((db.api.column_int64)(count.stmt, 0), 2);
+        }
+        drop(count);
+
+        assert!(db.execute("INSERT INTO nowhere VALUES (1)").is_err());
+        drop(db);
+        let _ = std::fs::remove_file(&path);
+    }
+}
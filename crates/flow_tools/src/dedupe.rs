@@ -0,0 +1,288 @@
+use async_trait::async_trait;
+use magnolia_core::{
+    system_clock, ControlSignal, DataType, ModuleSchema, Port, PortDirection, Processor,
+    SharedClock, Signal,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+use crate::key::{payload_hash, signal_key};
+
+/// Key for all signals when they share one history
+const SHARED_KEY: &str = "*";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DedupeConfig {
+    /// How many recent signals a new one is compared against
+    pub history: usize,
+    /// Older signals are forgotten; 0 remembers them until pushed out
+    pub window_secs: f32,
+    /// A history per signal type (and `Computed` source); otherwise a
+    /// repeat of anything recent is dropped
+    pub per_type: bool,
+    /// Types deduplicated (`Text`, `Computed:clipboard`); the rest pass
+    /// untouched. Empty deduplicates everything.
+    pub types: Vec<String>,
+}
+
+impl Default for DedupeConfig {
+    fn default() -> Self {
+        Self {
+            history: 1,
+            window_secs: 0.0,
+            per_type: true,
+            types: Vec::new(),
+        }
+    }
+}
+
+impl DedupeConfig {
+    pub fn schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "history": {
+                    "type": "integer",
+                    "title": "Compare With Last",
+                    "minimum": 1,
+                    "maximum": 1000,
+                    "default": 1
+                },
+                "window_secs": {
+                    "type": "number",
+                    "title": "Window (s, 0: any age)",
+                    "minimum": 0.0,
+                    "maximum": 86400.0,
+                    "default": 0.0
+                },
+                "per_type": { "type": "boolean", "title": "Per Signal Type", "default": true },
+                "types": {
+                    "type": "array",
+                    "title": "Types (empty: all)",
+                    "items": { "type": "string" },
+                    "default": []
+                }
+            }
+        })
+    }
+
+    fn applies_to(&self, key: &str) -> bool {
+        let signal_type = key.split(':').next().unwrap_or(key);
+        self.types.is_empty()
+            || self
+                .types
+                .iter()
+                .any(|t| t.trim() == key || t.trim() == signal_type)
+    }
+}
+
+/// Drops signals whose payload matches one of the last few passed.
+/// Control signals always pass.
+pub struct DedupeProcessor {
+    id: String,
+    enabled: bool,
+    config: DedupeConfig,
+    clock: SharedClock,
+    /// Hashes of recent signals with when they passed, oldest first
+    seen: HashMap<String, VecDeque<(u64, u64)>>,
+    dropped: u64,
+}
+
+impl DedupeProcessor {
+    pub fn new(id: &str, config: DedupeConfig) -> Self {
+        Self {
+            id: id.to_string(),
+            enabled: true,
+            config,
+            clock: system_clock(),
+            seen: HashMap::new(),
+            dropped: 0,
+        }
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn config(&self) -> &DedupeConfig {
+        &self.config
+    }
+
+    /// Histories start over
+    pub fn set_config(&mut self, config: DedupeConfig) {
+        self.seen.clear();
+        self.config = config;
+    }
+
+    /// Signals dropped since start
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Whether `signal` repeats a recent one; remembers it if not
+    fn is_duplicate(&mut self, signal: &Signal) -> bool {
+        let Some(hash) = payload_hash(signal) else {
+            return false;
+        };
+        let key = signal_key(signal);
+        if !self.config.applies_to(&key) {
+            return false;
+        }
+        let key = if self.config.per_type {
+            key
+        } else {
+            SHARED_KEY.to_string()
+        };
+
+        let now = self.clock.now_us();
+        let window_us = (f64::from(self.config.window_secs.max(0.0)) * 1e6) as u64;
+        let history = self.config.history.max(1);
+        let seen = self.seen.entry(key).or_default();
+        if window_us > 0 {
+            while seen
+                .front()
+                .is_some_and(|&(at, _)| now.saturating_sub(at) >= window_us)
+            {
+                seen.pop_front();
+            }
+        }
+        if seen.iter().any(|&(_, h)| h == hash) {
+            return true;
+        }
+        seen.push_back((now, hash));
+        while seen.len() > history {
+            seen.pop_front();
+        }
+        false
+    }
+}
+
+#[async_trait]
+impl Processor for DedupeProcessor {
+    fn name(&self) -> &str {
+        "Dedupe"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "Dedupe".to_string(),
+            description: "Drops signals identical to a recent one".to_string(),
+            ports: vec![
+                Port {
+                    id: "signal_in".to_string(),
+                    label: "In".to_string(),
+                    data_type: DataType::Any,
+                    direction: PortDirection::Input,
+                },
+                Port {
+                    id: "signal_out".to_string(),
+                    label: "Out".to_string(),
+                    data_type: DataType::Any,
+                    direction: PortDirection::Output,
+                },
+            ],
+            settings_schema: Some(DedupeConfig::schema()),
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    async fn process(&mut self, signal: Signal) -> anyhow::Result<Option<Signal>> {
+        if let Signal::Control(ControlSignal::Settings(settings)) = signal {
+            match serde_json::from_value::<DedupeConfig>(settings) {
+                Ok(config) => self.set_config(config),
+                Err(e) => log::warn!("Dedupe: invalid settings, keeping current: {}", e),
+            }
+            return Ok(None);
+        }
+        if self.is_duplicate(&signal) {
+            self.dropped += 1;
+            return Ok(None);
+        }
+        Ok(Some(signal))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use magnolia_core::VirtualClock;
+    use std::time::Duration;
+
+    fn text(s: &str) -> Signal {
+        Signal::Text(s.to_string())
+    }
+
+    fn clipboard(s: &str) -> Signal {
+        Signal::Computed {
+            source: "clipboard".to_string(),
+            content: s.to_string(),
+        }
+    }
+
+    async fn passes(dedupe: &mut DedupeProcessor, signal: Signal) -> bool {
+        dedupe.process(signal).await.unwrap().is_some()
+    }
+
+    #[tokio::test]
+    async fn drops_repeats_of_recent_signals() {
+        let config = DedupeConfig {
+            history: 2,
+            ..DedupeConfig::default()
+        };
+        let mut dedupe = DedupeProcessor::new("dedupe", config);
+
+        assert!(passes(&mut dedupe, text("a")).await);
+        assert!(!passes(&mut dedupe, text("a")).await);
+        assert!(passes(&mut dedupe, text("b")).await);
+        assert!(!passes(&mut dedupe, text("a")).await);
+        assert!(passes(&mut dedupe, text("c")).await);
+        // `a` has been pushed out of the last two
+        assert!(passes(&mut dedupe, text("a")).await);
+        // Same content, different type
+        assert!(passes(&mut dedupe, clipboard("a")).await);
+        assert!(passes(&mut dedupe, Signal::Control(ControlSignal::Shutdown)).await);
+        assert!(passes(&mut dedupe, Signal::Control(ControlSignal::Shutdown)).await);
+        assert_eq!(dedupe.dropped(), 2);
+    }
+
+    #[tokio::test]
+    async fn pulses_all_pass() {
+        let mut dedupe = DedupeProcessor::new("dedupe", DedupeConfig::default());
+        assert!(passes(&mut dedupe, Signal::Pulse).await);
+        assert!(passes(&mut dedupe, Signal::Pulse).await);
+        assert_eq!(dedupe.dropped(), 0);
+    }
+
+    #[tokio::test]
+    async fn forgets_after_the_window_and_filters_types() {
+        let clock = VirtualClock::manual();
+        let config = DedupeConfig {
+            history: 10,
+            window_secs: 5.0,
+            types: vec!["Computed:clipboard".to_string()],
+            ..DedupeConfig::default()
+        };
+        let mut dedupe = DedupeProcessor::new("dedupe", config).with_clock(clock.clone());
+
+        assert!(passes(&mut dedupe, clipboard("x")).await);
+        clock.advance(Duration::from_secs(4));
+        assert!(!passes(&mut dedupe, clipboard("x")).await);
+        clock.advance(Duration::from_secs(1));
+        assert!(passes(&mut dedupe, clipboard("x")).await);
+
+        // Not in `types`
+        assert!(passes(&mut dedupe, text("x")).await);
+        assert!(passes(&mut dedupe, text("x")).await);
+    }
+}
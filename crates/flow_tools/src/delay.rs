@@ -0,0 +1,427 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Local, NaiveTime, TimeZone};
+use magnolia_core::{
+    system_clock, ControlSignal, DataType, ModuleDocs, ModuleSchema, Port, PortDirection,
+    Processor, SharedClock, Signal,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::Duration;
+
+pub const DEPTH_PORT: &str = "depth_out";
+const SIGNAL_PORT: &str = "signal_out";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DelayMode {
+    /// Each signal `delay_secs` after it arrived
+    #[default]
+    After,
+    /// Everything at the time in `at`
+    At,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DelayConfig {
+    pub mode: DelayMode,
+    pub delay_secs: f32,
+    /// `HH:MM[:SS]` local time (the next one to come) or an RFC 3339
+    /// timestamp
+    pub at: String,
+    /// Signals held at most; the oldest are dropped beyond it
+    pub max_queued: usize,
+    /// Intent action that drops everything held
+    pub cancel_action: String,
+}
+
+impl Default for DelayConfig {
+    fn default() -> Self {
+        Self {
+            mode: DelayMode::After,
+            delay_secs: 5.0,
+            at: String::new(),
+            max_queued: 100,
+            cancel_action: "cancel".to_string(),
+        }
+    }
+}
+
+impl DelayConfig {
+    pub fn schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "mode": {
+                    "type": "string",
+                    "title": "Mode",
+                    "enum": ["after", "at"],
+                    "default": "after"
+                },
+                "delay_secs": {
+                    "type": "number",
+                    "title": "Delay (s)",
+                    "minimum": 0.0,
+                    "maximum": 86400.0,
+                    "default": 5.0
+                },
+                "at": { "type": "string", "title": "At (HH:MM or RFC 3339)", "default": "" },
+                "max_queued": {
+                    "type": "integer",
+                    "title": "Max Queued",
+                    "minimum": 1,
+                    "maximum": 10000,
+                    "default": 100
+                },
+                "cancel_action": { "type": "string", "title": "Cancel Action", "default": "cancel" }
+            }
+        })
+    }
+
+    pub fn docs() -> ModuleDocs {
+        let notes = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(key, text)| (key.to_string(), text.to_string()))
+                .collect()
+        };
+        ModuleDocs {
+            overview: "Holds every signal it's given and lets it go later, in the order \
+                they arrived: a fixed time after each one, or all together at a time of day.\n\n\
+                Control signals pass straight through. An intent named by the cancel action \
+                drops everything still held, e.g. to call off a scheduled announcement."
+                .to_string(),
+            ports: notes(&[
+                ("signal_in", "Anything to hold back"),
+                (SIGNAL_PORT, "The held signals, once due"),
+                (DEPTH_PORT, "How many are waiting, whenever that changes"),
+            ]),
+            settings: notes(&[
+                (
+                    "mode",
+                    "`after` holds each signal for the delay; `at` until the time set",
+                ),
+                ("delay_secs", "How long each signal is held in `after` mode"),
+                (
+                    "at",
+                    "A local time such as `21:30` (the next one to come) or an RFC 3339 \
+                    timestamp, for `at` mode",
+                ),
+                (
+                    "max_queued",
+                    "Signals held at most; the oldest are dropped beyond it",
+                ),
+                ("cancel_action", "Intent action that drops everything held"),
+            ]),
+        }
+    }
+
+    fn is_cancel(&self, signal: &Signal) -> bool {
+        matches!(signal, Signal::Intent { action, .. }
+            if !self.cancel_action.trim().is_empty()
+                && action.trim().eq_ignore_ascii_case(self.cancel_action.trim()))
+    }
+
+    /// When a signal arriving at `now_us` goes out; `None` if `at` doesn't
+    /// parse
+    fn due_us(&self, now_us: u64) -> Option<u64> {
+        match self.mode {
+            DelayMode::After => Some(now_us + (f64::from(self.delay_secs.max(0.0)) * 1e6) as u64),
+            DelayMode::At => parse_at(self.at.trim(), now_us),
+        }
+    }
+}
+
+/// Microseconds since the Unix epoch for `at`; a bare time of day is its
+/// next occurrence in local time
+pub fn parse_at(at: &str, now_us: u64) -> Option<u64> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(at) {
+        return u64::try_from(time.timestamp_micros()).ok();
+    }
+    let time = NaiveTime::parse_from_str(at, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(at, "%H:%M"))
+        .ok()?;
+    let now = DateTime::from_timestamp_micros(now_us as i64)?.with_timezone(&Local);
+    let mut date = now.date_naive();
+    loop {
+        // Skip times a DST change leaves out
+        if let Some(due) = Local.from_local_datetime(&date.and_time(time)).earliest() {
+            if due > now {
+                return u64::try_from(due.timestamp_micros()).ok();
+            }
+        }
+        date = date.succ_opt()?;
+    }
+}
+
+/// Holds signals back and lets them go later, in order; control signals
+/// aren't held. Reports how many are waiting on `depth_out` whenever that
+/// changes.
+pub struct DelayProcessor {
+    id: String,
+    enabled: bool,
+    config: DelayConfig,
+    clock: SharedClock,
+    /// Held signals with when they're due, earliest first
+    queue: VecDeque<(u64, Signal)>,
+    /// The depth changed since it was last reported
+    depth_changed: bool,
+    /// Port for the signal last returned
+    last_port: &'static str,
+    dropped: u64,
+}
+
+impl DelayProcessor {
+    pub fn new(id: &str, config: DelayConfig) -> Self {
+        Self {
+            id: id.to_string(),
+            enabled: true,
+            config,
+            clock: system_clock(),
+            queue: VecDeque::new(),
+            depth_changed: false,
+            last_port: SIGNAL_PORT,
+            dropped: 0,
+        }
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn config(&self) -> &DelayConfig {
+        &self.config
+    }
+
+    /// Signals already held keep the time they were given
+    pub fn set_config(&mut self, config: DelayConfig) {
+        if config.mode == DelayMode::At && parse_at(config.at.trim(), 0).is_none() {
+            log::warn!("Delay {}: can't read time {:?}", self.id, config.at);
+        }
+        self.config = config;
+    }
+
+    pub fn depth(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Signals dropped since start, for a full queue
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Drop everything held
+    pub fn cancel(&mut self) {
+        self.queue.clear();
+    }
+
+    fn depth_signal(&mut self) -> Signal {
+        self.depth_changed = false;
+        self.last_port = DEPTH_PORT;
+        Signal::Computed {
+            source: format!("{}_depth", self.id),
+            content: self.queue.len().to_string(),
+        }
+    }
+
+    fn hold(&mut self, signal: Signal) {
+        let now = self.clock.now_us();
+        let Some(due) = self.config.due_us(now) else {
+            log::warn!("Delay {}: no valid time set, dropping signal", self.id);
+            self.dropped += 1;
+            return;
+        };
+        while self.queue.len() >= self.config.max_queued.max(1) {
+            self.queue.pop_front();
+            self.dropped += 1;
+        }
+        // Equal times keep arrival order
+        let index = self.queue.partition_point(|(d, _)| *d <= due);
+        self.queue.insert(index, (due, signal));
+    }
+}
+
+#[async_trait]
+impl Processor for DelayProcessor {
+    fn name(&self) -> &str {
+        "Delay"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "Delay".to_string(),
+            description: "Re-emits signals after a delay or at a set time".to_string(),
+            ports: vec![
+                Port {
+                    id: "signal_in".to_string(),
+                    label: "In".to_string(),
+                    data_type: DataType::Any,
+                    direction: PortDirection::Input,
+                },
+                Port {
+                    id: SIGNAL_PORT.to_string(),
+                    label: "Out".to_string(),
+                    data_type: DataType::Any,
+                    direction: PortDirection::Output,
+                },
+                Port {
+                    id: DEPTH_PORT.to_string(),
+                    label: "Queue Depth".to_string(),
+                    data_type: DataType::Numeric,
+                    direction: PortDirection::Output,
+                },
+            ],
+            settings_schema: Some(DelayConfig::schema()),
+            docs: Some(DelayConfig::docs()),
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    async fn process(&mut self, signal: Signal) -> anyhow::Result<Option<Signal>> {
+        match signal {
+            Signal::Control(ControlSignal::Settings(settings)) => {
+                match serde_json::from_value::<DelayConfig>(settings) {
+                    Ok(config) => self.set_config(config),
+                    Err(e) => log::warn!("Delay: invalid settings, keeping current: {}", e),
+                }
+                Ok(None)
+            }
+            signal @ Signal::Control(_) => {
+                self.last_port = SIGNAL_PORT;
+                Ok(Some(signal))
+            }
+            signal if self.config.is_cancel(&signal) => {
+                self.cancel();
+                Ok(Some(self.depth_signal()))
+            }
+            signal => {
+                self.hold(signal);
+                Ok(Some(self.depth_signal()))
+            }
+        }
+    }
+
+    fn output_port(&self, _signal: &Signal) -> Option<&str> {
+        Some(self.last_port)
+    }
+
+    async fn next_wake(&self) {
+        if self.depth_changed {
+            return;
+        }
+        match self.queue.front() {
+            Some((due, _)) => {
+                let wait = due.saturating_sub(self.clock.now_us());
+                self.clock.sleep(Duration::from_micros(wait)).await;
+            }
+            None => std::future::pending().await,
+        }
+    }
+
+    async fn wake(&mut self) -> anyhow::Result<Option<Signal>> {
+        let now = self.clock.now_us();
+        if self.queue.front().is_some_and(|(due, _)| *due <= now) {
+            self.depth_changed = true;
+            self.last_port = SIGNAL_PORT;
+            return Ok(self.queue.pop_front().map(|(_, signal)| signal));
+        }
+        if self.depth_changed {
+            return Ok(Some(self.depth_signal()));
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use magnolia_core::VirtualClock;
+
+    fn text(s: &str) -> Signal {
+        Signal::Text(s.to_string())
+    }
+
+    fn depth(signal: Option<Signal>) -> Option<usize> {
+        match signal? {
+            Signal::Computed { source, content } if source == "delay_depth" => content.parse().ok(),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn releases_in_order_after_the_delay() {
+        let clock = VirtualClock::manual();
+        let config = DelayConfig {
+            delay_secs: 2.0,
+            ..DelayConfig::default()
+        };
+        let mut delay = DelayProcessor::new("delay", config).with_clock(clock.clone());
+
+        assert_eq!(depth(delay.process(text("a")).await.unwrap()), Some(1));
+        assert_eq!(delay.output_port(&text("")), Some(DEPTH_PORT));
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(depth(delay.process(text("b")).await.unwrap()), Some(2));
+        assert!(delay.wake().await.unwrap().is_none());
+
+        clock.advance(Duration::from_secs(1));
+        let out = delay.wake().await.unwrap();
+        assert!(matches!(out, Some(Signal::Text(ref t)) if t == "a"));
+        assert_eq!(delay.output_port(&text("")), Some(SIGNAL_PORT));
+        // The new depth follows straight away
+        delay.next_wake().await;
+        assert_eq!(depth(delay.wake().await.unwrap()), Some(1));
+
+        clock.advance(Duration::from_secs(1));
+        let out = delay.wake().await.unwrap();
+        assert!(matches!(out, Some(Signal::Text(ref t)) if t == "b"));
+        assert_eq!(depth(delay.wake().await.unwrap()), Some(0));
+        assert!(delay.wake().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn cancel_drops_everything_held() {
+        let clock = VirtualClock::manual();
+        let mut delay =
+            DelayProcessor::new("delay", DelayConfig::default()).with_clock(clock.clone());
+        delay.process(text("a")).await.unwrap();
+        delay.process(Signal::Pulse).await.unwrap();
+        let cancel = Signal::Intent {
+            action: "Cancel".to_string(),
+            parameters: vec![],
+        };
+        assert_eq!(depth(delay.process(cancel).await.unwrap()), Some(0));
+        clock.advance(Duration::from_secs(10));
+        assert!(delay.wake().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn holds_until_an_absolute_time() {
+        let clock = VirtualClock::starting_at(1_700_000_000_000_000, false);
+        let config = DelayConfig {
+            mode: DelayMode::At,
+            at: "2023-11-14T22:13:30Z".to_string(),
+            ..DelayConfig::default()
+        };
+        let mut delay = DelayProcessor::new("delay", config).with_clock(clock.clone());
+        delay.process(text("a")).await.unwrap();
+
+        clock.advance(Duration::from_secs(9));
+        assert!(delay.wake().await.unwrap().is_none());
+        clock.advance(Duration::from_secs(1));
+        assert!(matches!(delay.wake().await.unwrap(), Some(Signal::Text(_))));
+
+        assert_eq!(parse_at("not a time", 0), None);
+        let next = parse_at("12:00", 1_700_000_000_000_000).unwrap();
+        assert!(next > 1_700_000_000_000_000);
+        assert!(next - 1_700_000_000_000_000 <= 86_400_000_000 + 3_600_000_000);
+    }
+}
@@ -0,0 +1,164 @@
+//! Numbered inputs for processors that take several streams.
+//!
+//! The runtime delivers signals to a module without the destination port, so
+//! each input is a separate sink module (`<id>_in_1`, `<id>_in_2`, ..)
+//! queueing into state shared with the processor, which wakes to drain it.
+
+use async_trait::async_trait;
+use magnolia_core::{DataType, ModuleSchema, Port, PortDirection, Signal, Sink};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+
+/// Inputs a selector or merge can have
+pub const MAX_INPUTS: usize = 8;
+/// Signals queued per input before the oldest are dropped
+const QUEUE: usize = 256;
+
+pub struct InputQueues {
+    queues: Mutex<Vec<VecDeque<Signal>>>,
+    /// Bit `n` set: input `n` drops what arrives
+    closed: AtomicU64,
+    dropped: AtomicU64,
+    notify: Notify,
+}
+
+impl InputQueues {
+    /// `count` is clamped to 1..=[`MAX_INPUTS`]
+    pub fn new(count: usize) -> Arc<Self> {
+        Arc::new(Self {
+            queues: Mutex::new(vec![VecDeque::new(); count.clamp(1, MAX_INPUTS)]),
+            closed: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+            notify: Notify::new(),
+        })
+    }
+
+    pub fn count(&self) -> usize {
+        self.queues.lock().map(|q| q.len()).unwrap_or(0)
+    }
+
+    /// Sink modules for every input, `<owner_id>_in_1` ..
+    pub fn sinks(self: &Arc<Self>, owner_id: &str) -> Vec<InputSink> {
+        (0..self.count())
+            .map(|index| InputSink {
+                id: format!("{}_in_{}", owner_id, index + 1),
+                index,
+                enabled: true,
+                queues: self.clone(),
+            })
+            .collect()
+    }
+
+    /// Let only `open` through (zero-based); `None` opens all
+    pub fn open_only(&self, open: Option<usize>) {
+        let mask = match open {
+            Some(index) => !(1u64 << index.min(63)),
+            None => 0,
+        };
+        self.closed.store(mask, Ordering::Release);
+        if let (Some(index), Ok(mut queues)) = (open, self.queues.lock()) {
+            for (i, queue) in queues.iter_mut().enumerate() {
+                if i != index {
+                    queue.clear();
+                }
+            }
+        }
+    }
+
+    pub fn push(&self, index: usize, signal: Signal) {
+        if self.closed.load(Ordering::Acquire) & (1u64 << index.min(63)) != 0 {
+            return;
+        }
+        let Ok(mut queues) = self.queues.lock() else {
+            return;
+        };
+        let Some(queue) = queues.get_mut(index) else {
+            return;
+        };
+        if queue.len() >= QUEUE {
+            queue.pop_front();
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+        queue.push_back(signal);
+        drop(queues);
+        self.notify.notify_one();
+    }
+
+    /// Signals dropped from full queues since start
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Lengths of every queue, in input order
+    pub fn lengths(&self) -> Vec<usize> {
+        self.queues
+            .lock()
+            .map(|q| q.iter().map(VecDeque::len).collect())
+            .unwrap_or_default()
+    }
+
+    /// The next signal from input `index`
+    pub fn pop(&self, index: usize) -> Option<Signal> {
+        self.queues.lock().ok()?.get_mut(index)?.pop_front()
+    }
+
+    /// Resolves once any input has something queued
+    pub async fn wait(&self) {
+        loop {
+            // Registered before the check, so a push in between isn't missed
+            let notified = self.notify.notified();
+            if self.lengths().iter().any(|&len| len > 0) {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// One numbered input of a selector or merge
+pub struct InputSink {
+    id: String,
+    index: usize,
+    enabled: bool,
+    queues: Arc<InputQueues>,
+}
+
+#[async_trait]
+impl Sink for InputSink {
+    fn name(&self) -> &str {
+        &self.id
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: format!("Input {}", self.index + 1),
+            description: "Feeds one input of the paired selector or merge".to_string(),
+            ports: vec![Port {
+                id: "signal_in".to_string(),
+                label: "In".to_string(),
+                data_type: DataType::Any,
+                direction: PortDirection::Input,
+            }],
+            settings_schema: None,
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    async fn consume(&self, signal: Signal) -> anyhow::Result<Option<Signal>> {
+        if !matches!(signal, Signal::AudioStream { .. }) {
+            self.queues.push(self.index, signal);
+        }
+        Ok(None)
+    }
+}
@@ -0,0 +1,111 @@
+//! Naming and fingerprinting signals, for per-type state.
+
+use magnolia_core::Signal;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// The variant name, as in the signal's serialized `type` tag. Shared
+/// audio and blobs are named `Audio` and `Blob`: the router shares what
+/// it fans out, and that shouldn't change which state it lands in
+pub fn signal_type(signal: &Signal) -> &'static str {
+    match signal {
+        Signal::Text(_) => "Text",
+        Signal::Intent { .. } => "Intent",
+        Signal::Astrology(_) => "Astrology",
+        Signal::Blob { .. } | Signal::SharedBlob { .. } => "Blob",
+        Signal::BlobHandle { .. } => "BlobHandle",
+        Signal::Audio { .. } | Signal::SharedAudio { .. } => "Audio",
+        Signal::AudioHandle { .. } => "AudioHandle",
+        Signal::AudioStream { .. } => "AudioStream",
+        Signal::Control(_) => "Control",
+        Signal::Computed { .. } => "Computed",
+        Signal::Texture { .. } => "Texture",
+        Signal::Midi { .. } => "Midi",
+        Signal::Pulse => "Pulse",
+    }
+}
+
+/// The type, with the source for `Computed` (`Computed:stt_partial`), so
+/// each producer sharing a port gets its own state
+pub fn signal_key(signal: &Signal) -> String {
+    match signal {
+        Signal::Computed { source, .. } => format!("Computed:{}", source),
+        signal => signal_type(signal).to_string(),
+    }
+}
+
+fn hash_samples(hasher: &mut DefaultHasher, samples: &[f32]) {
+    samples.len().hash(hasher);
+    for sample in samples {
+        sample.to_bits().hash(hasher);
+    }
+}
+
+/// Hash of the type and everything the signal carries; `None` for streams,
+/// control signals and pulses, which have no payload to compare
+pub fn payload_hash(signal: &Signal) -> Option<u64> {
+    let mut hasher = DefaultHasher::new();
+    signal_type(signal).hash(&mut hasher);
+    match signal {
+        Signal::Text(text) => text.hash(&mut hasher),
+        Signal::Intent { action, parameters } => {
+            action.hash(&mut hasher);
+            parameters.hash(&mut hasher);
+        }
+        Signal::Astrology(data) => {
+            data.sun_sign.hash(&mut hasher);
+            data.moon_sign.hash(&mut hasher);
+            data.rising_sign.hash(&mut hasher);
+            for (planet, longitude) in &data.planetary_positions {
+                planet.hash(&mut hasher);
+                longitude.to_bits().hash(&mut hasher);
+            }
+        }
+        Signal::Blob { .. } | Signal::SharedBlob { .. } => {
+            let blob = signal.blob()?;
+            blob.mime_type.hash(&mut hasher);
+            blob.bytes.hash(&mut hasher);
+        }
+        Signal::BlobHandle { handle, mime_type } => {
+            handle.hash(&mut hasher);
+            mime_type.hash(&mut hasher);
+        }
+        Signal::Audio { .. } | Signal::SharedAudio { .. } => {
+            let audio = signal.audio()?;
+            // Stamped, so a run of silent buffers isn't one repeated buffer
+            audio.timestamp_us.hash(&mut hasher);
+            audio.sample_rate.hash(&mut hasher);
+            audio.channels.hash(&mut hasher);
+            hash_samples(&mut hasher, audio.data);
+        }
+        Signal::AudioHandle {
+            handle,
+            sample_rate,
+            channels,
+        } => {
+            handle.hash(&mut hasher);
+            sample_rate.hash(&mut hasher);
+            channels.hash(&mut hasher);
+        }
+        Signal::Computed { source, content } => {
+            source.hash(&mut hasher);
+            content.hash(&mut hasher);
+        }
+        Signal::Texture { handle, start_time } => {
+            handle.hash(&mut hasher);
+            start_time.to_bits().hash(&mut hasher);
+        }
+        Signal::Midi {
+            timestamp_us,
+            status,
+            data,
+        } => {
+            // Stamped, so a repeated note is a new message
+            timestamp_us.hash(&mut hasher);
+            status.hash(&mut hasher);
+            data.hash(&mut hasher);
+        }
+        Signal::AudioStream { .. } | Signal::Control(_) | Signal::Pulse => return None,
+    }
+    Some(hasher.finish())
+}
@@ -0,0 +1,317 @@
+use async_trait::async_trait;
+use magnolia_core::{
+    ControlSignal, DataType, ModuleSchema, Port, PortDirection, Processor, Signal, Sink,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LatchConfig {
+    /// Only intents with this action trigger; empty takes any intent.
+    /// Pulses always trigger.
+    pub trigger_action: String,
+    /// Forget the held signal once emitted, so each trigger needs fresh
+    /// data (otherwise it's sample-and-hold: re-emitted on every trigger)
+    pub clear_on_emit: bool,
+}
+
+impl LatchConfig {
+    pub fn schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "trigger_action": {
+                    "type": "string",
+                    "title": "Trigger Action (empty: any)",
+                    "default": ""
+                },
+                "clear_on_emit": { "type": "boolean", "title": "Clear on Emit", "default": false }
+            }
+        })
+    }
+}
+
+/// Shared between the latch and its trigger sink.
+///
+/// The runtime delivers signals to a module without the destination port, so
+/// the trigger input is a separate module (`<id>_trigger`) counting into this
+/// state.
+#[derive(Default)]
+pub struct LatchState {
+    trigger_action: Mutex<String>,
+    triggers: AtomicU64,
+    notify: Notify,
+}
+
+impl LatchState {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    pub fn trigger(&self) {
+        self.triggers.fetch_add(1, Ordering::AcqRel);
+        self.notify.notify_one();
+    }
+
+    fn is_trigger(&self, signal: &Signal) -> bool {
+        match signal {
+            Signal::Pulse => true,
+            Signal::Intent { action, .. } => {
+                let wanted = self
+                    .trigger_action
+                    .lock()
+                    .map(|a| a.clone())
+                    .unwrap_or_default();
+                wanted.trim().is_empty() || action.trim().eq_ignore_ascii_case(wanted.trim())
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Stores the latest data signal and emits it only when triggered through
+/// `<id>_trigger`
+pub struct LatchProcessor {
+    id: String,
+    enabled: bool,
+    config: LatchConfig,
+    state: Arc<LatchState>,
+    held: Option<Signal>,
+    /// Triggers already answered
+    seen_triggers: u64,
+}
+
+impl LatchProcessor {
+    pub fn new(id: &str, config: LatchConfig, state: Arc<LatchState>) -> Self {
+        let mut latch = Self {
+            id: id.to_string(),
+            enabled: true,
+            config: LatchConfig::default(),
+            seen_triggers: state.triggers.load(Ordering::Acquire),
+            state,
+            held: None,
+        };
+        latch.set_config(config);
+        latch
+    }
+
+    pub fn config(&self) -> &LatchConfig {
+        &self.config
+    }
+
+    pub fn set_config(&mut self, config: LatchConfig) {
+        if let Ok(mut action) = self.state.trigger_action.lock() {
+            action.clone_from(&config.trigger_action);
+        }
+        self.config = config;
+    }
+
+    pub fn held(&self) -> Option<&Signal> {
+        self.held.as_ref()
+    }
+
+    fn pending_trigger(&self) -> bool {
+        self.state.triggers.load(Ordering::Acquire) != self.seen_triggers
+    }
+}
+
+#[async_trait]
+impl Processor for LatchProcessor {
+    fn name(&self) -> &str {
+        "Latch"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "Latch".to_string(),
+            description: format!(
+                "Holds the latest signal and emits it when {}_trigger fires",
+                self.id
+            ),
+            ports: vec![
+                Port {
+                    id: "data_in".to_string(),
+                    label: "Data".to_string(),
+                    data_type: DataType::Any,
+                    direction: PortDirection::Input,
+                },
+                Port {
+                    id: "signal_out".to_string(),
+                    label: "Out".to_string(),
+                    data_type: DataType::Any,
+                    direction: PortDirection::Output,
+                },
+            ],
+            settings_schema: Some(LatchConfig::schema()),
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    async fn process(&mut self, signal: Signal) -> anyhow::Result<Option<Signal>> {
+        match signal {
+            Signal::Control(ControlSignal::Settings(settings)) => {
+                match serde_json::from_value::<LatchConfig>(settings) {
+                    Ok(config) => self.set_config(config),
+                    Err(e) => log::warn!("Latch: invalid settings, keeping current: {}", e),
+                }
+            }
+            Signal::Control(_) | Signal::AudioStream { .. } => {}
+            signal => self.held = Some(signal),
+        }
+        Ok(None)
+    }
+
+    async fn next_wake(&self) {
+        loop {
+            // Registered before the check, so a trigger in between isn't missed
+            let notified = self.state.notify.notified();
+            if self.pending_trigger() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    async fn wake(&mut self) -> anyhow::Result<Option<Signal>> {
+        // Triggers arriving together emit once
+        self.seen_triggers = self.state.triggers.load(Ordering::Acquire);
+        if self.config.clear_on_emit {
+            Ok(self.held.take())
+        } else {
+            Ok(self.held.clone())
+        }
+    }
+}
+
+/// Trigger input for [`LatchProcessor`]; Pulses and matching intents fire
+/// the paired latch
+pub struct LatchTriggerSink {
+    id: String,
+    enabled: bool,
+    state: Arc<LatchState>,
+}
+
+impl LatchTriggerSink {
+    pub fn new(latch_id: &str, state: Arc<LatchState>) -> Self {
+        Self {
+            id: format!("{latch_id}_trigger"),
+            enabled: true,
+            state,
+        }
+    }
+}
+
+#[async_trait]
+impl Sink for LatchTriggerSink {
+    fn name(&self) -> &str {
+        "Latch Trigger"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "Latch Trigger".to_string(),
+            description: "Fires the paired latch on a pulse or intent".to_string(),
+            ports: vec![Port {
+                id: "trigger_in".to_string(),
+                label: "Trigger".to_string(),
+                data_type: DataType::Control,
+                direction: PortDirection::Input,
+            }],
+            settings_schema: None,
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    async fn consume(&self, signal: Signal) -> anyhow::Result<Option<Signal>> {
+        if self.state.is_trigger(&signal) {
+            self.state.trigger();
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(s: &str) -> Signal {
+        Signal::Text(s.to_string())
+    }
+
+    fn intent(action: &str) -> Signal {
+        Signal::Intent {
+            action: action.to_string(),
+            parameters: vec![],
+        }
+    }
+
+    async fn fire(latch: &mut LatchProcessor) -> Option<String> {
+        latch.next_wake().await;
+        match latch.wake().await.unwrap()? {
+            Signal::Text(t) => Some(t),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn emits_the_latest_data_on_trigger() {
+        let state = LatchState::new();
+        let mut latch = LatchProcessor::new("latch", LatchConfig::default(), state.clone());
+        let trigger = LatchTriggerSink::new("latch", state);
+        assert_eq!(trigger.schema().id, "latch_trigger");
+
+        assert!(latch.process(text("a")).await.unwrap().is_none());
+        assert!(latch.process(text("b")).await.unwrap().is_none());
+        trigger.consume(Signal::Pulse).await.unwrap();
+        assert_eq!(fire(&mut latch).await, Some("b".into()));
+
+        // Held until replaced
+        trigger.consume(intent("anything")).await.unwrap();
+        assert_eq!(fire(&mut latch).await, Some("b".into()));
+
+        // Data on the trigger input doesn't fire
+        trigger.consume(text("c")).await.unwrap();
+        assert!(!latch.pending_trigger());
+    }
+
+    #[tokio::test]
+    async fn clears_and_filters_trigger_actions() {
+        let state = LatchState::new();
+        let config = LatchConfig {
+            trigger_action: "speak".into(),
+            clear_on_emit: true,
+        };
+        let mut latch = LatchProcessor::new("latch", config, state.clone());
+        let trigger = LatchTriggerSink::new("latch", state);
+
+        latch.process(text("answer")).await.unwrap();
+        trigger.consume(intent("draw")).await.unwrap();
+        assert!(!latch.pending_trigger());
+        trigger.consume(intent("Speak")).await.unwrap();
+        assert_eq!(fire(&mut latch).await, Some("answer".into()));
+
+        trigger.consume(Signal::Pulse).await.unwrap();
+        assert_eq!(fire(&mut latch).await, None);
+    }
+}
@@ -0,0 +1,24 @@
+//! Flow tools: processors that shape when, how often and from where
+//! signals pass.
+//!
+//! They sit in a patch between sources and sinks and don't care what the
+//! signals carry. Timing reads the runtime clock, so they run
+//! under virtual time like everything else.
+
+mod dedupe;
+mod delay;
+mod inputs;
+mod key;
+mod latch;
+mod merge;
+mod rate_limit;
+mod selector;
+
+pub use dedupe::{DedupeConfig, DedupeProcessor};
+pub use delay::{parse_at, DelayConfig, DelayMode, DelayProcessor, DEPTH_PORT};
+pub use inputs::{InputQueues, InputSink, MAX_INPUTS};
+pub use key::{payload_hash, signal_key, signal_type};
+pub use latch::{LatchConfig, LatchProcessor, LatchState, LatchTriggerSink};
+pub use merge::{MergeConfig, MergeMode, MergeProcessor};
+pub use rate_limit::{RateLimitConfig, RateLimitMode, RateLimitProcessor};
+pub use selector::{SelectorConfig, SelectorProcessor};
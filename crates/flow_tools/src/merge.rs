@@ -0,0 +1,183 @@
+use async_trait::async_trait;
+use magnolia_core::{
+    ControlSignal, DataType, ModuleSchema, Port, PortDirection, Processor, Signal,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::inputs::InputQueues;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeMode {
+    /// Round-robin between inputs with something waiting
+    #[default]
+    Fair,
+    /// Always the lowest-numbered input with something waiting
+    Priority,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MergeConfig {
+    pub mode: MergeMode,
+}
+
+impl MergeConfig {
+    pub fn schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "mode": {
+                    "type": "string",
+                    "title": "Mode",
+                    "enum": ["fair", "priority"],
+                    "default": "fair"
+                }
+            }
+        })
+    }
+}
+
+/// Interleaves its numbered inputs (`<id>_in_N`) into one output
+pub struct MergeProcessor {
+    id: String,
+    enabled: bool,
+    config: MergeConfig,
+    inputs: Arc<InputQueues>,
+    /// Where the fair round-robin looks first
+    next_input: usize,
+}
+
+impl MergeProcessor {
+    pub fn new(id: &str, config: MergeConfig, inputs: Arc<InputQueues>) -> Self {
+        inputs.open_only(None);
+        Self {
+            id: id.to_string(),
+            enabled: true,
+            config,
+            inputs,
+            next_input: 0,
+        }
+    }
+
+    pub fn config(&self) -> &MergeConfig {
+        &self.config
+    }
+
+    pub fn set_config(&mut self, config: MergeConfig) {
+        self.config = config;
+    }
+
+    /// Input the next signal comes from (zero-based)
+    fn pick(&self) -> Option<usize> {
+        let lengths = self.inputs.lengths();
+        let start = match self.config.mode {
+            MergeMode::Fair => self.next_input,
+            MergeMode::Priority => 0,
+        };
+        (0..lengths.len())
+            .map(|offset| (start + offset) % lengths.len())
+            .find(|&index| lengths[index] > 0)
+    }
+}
+
+#[async_trait]
+impl Processor for MergeProcessor {
+    fn name(&self) -> &str {
+        "Merge"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "Merge".to_string(),
+            description: format!(
+                "Interleaves {}_in_1..{} into one output",
+                self.id,
+                self.inputs.count()
+            ),
+            ports: vec![Port {
+                id: "signal_out".to_string(),
+                label: "Out".to_string(),
+                data_type: DataType::Any,
+                direction: PortDirection::Output,
+            }],
+            settings_schema: Some(MergeConfig::schema()),
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    async fn process(&mut self, signal: Signal) -> anyhow::Result<Option<Signal>> {
+        if let Signal::Control(ControlSignal::Settings(settings)) = signal {
+            match serde_json::from_value::<MergeConfig>(settings) {
+                Ok(config) => self.set_config(config),
+                Err(e) => log::warn!("Merge: invalid settings, keeping current: {}", e),
+            }
+        }
+        Ok(None)
+    }
+
+    async fn next_wake(&self) {
+        self.inputs.wait().await;
+    }
+
+    async fn wake(&mut self) -> anyhow::Result<Option<Signal>> {
+        let Some(index) = self.pick() else {
+            return Ok(None);
+        };
+        self.next_input = (index + 1) % self.inputs.count().max(1);
+        Ok(self.inputs.pop(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use magnolia_core::Sink;
+
+    fn text(s: &str) -> Signal {
+        Signal::Text(s.to_string())
+    }
+
+    async fn drain(merge: &mut MergeProcessor) -> Vec<String> {
+        let mut out = Vec::new();
+        while let Some(signal) = merge.wake().await.unwrap() {
+            match signal {
+                Signal::Text(t) => out.push(t),
+                other => panic!("unexpected {:?}", other),
+            }
+        }
+        out
+    }
+
+    #[tokio::test]
+    async fn interleaves_fairly_or_by_priority() {
+        let inputs = InputQueues::new(2);
+        let sinks = inputs.sinks("merge");
+        let mut merge = MergeProcessor::new("merge", MergeConfig::default(), inputs);
+
+        for s in ["a1", "a2", "a3"] {
+            sinks[0].consume(text(s)).await.unwrap();
+        }
+        sinks[1].consume(text("b1")).await.unwrap();
+        merge.next_wake().await;
+        assert_eq!(drain(&mut merge).await, ["a1", "b1", "a2", "a3"]);
+
+        merge.set_config(MergeConfig {
+            mode: MergeMode::Priority,
+        });
+        sinks[1].consume(text("b2")).await.unwrap();
+        sinks[0].consume(text("a4")).await.unwrap();
+        sinks[1].consume(text("b3")).await.unwrap();
+        assert_eq!(drain(&mut merge).await, ["a4", "b2", "b3"]);
+    }
+}
@@ -0,0 +1,403 @@
+use async_trait::async_trait;
+use magnolia_core::{
+    system_clock, ControlSignal, DataType, ModuleSchema, Port, PortDirection, Processor,
+    SharedClock, Signal,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::key::signal_key;
+
+/// Key for all signals when they share one limit
+const SHARED_KEY: &str = "*";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitMode {
+    /// Bursts up to `burst`, refilled at `rate_per_sec`; the rest dropped
+    #[default]
+    TokenBucket,
+    /// At most one per `min_interval_secs`; the rest dropped
+    MinInterval,
+    /// Only the last of a run, once `debounce_secs` pass without another
+    Debounce,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    pub mode: RateLimitMode,
+    pub rate_per_sec: f32,
+    pub burst: u32,
+    pub min_interval_secs: f32,
+    pub debounce_secs: f32,
+    /// Separate limits per signal type (and `Computed` source), so a flood
+    /// of partials doesn't starve the finals behind it
+    pub per_type: bool,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            mode: RateLimitMode::TokenBucket,
+            rate_per_sec: 2.0,
+            burst: 5,
+            min_interval_secs: 1.0,
+            debounce_secs: 0.5,
+            per_type: true,
+        }
+    }
+}
+
+impl RateLimitConfig {
+    pub fn schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "mode": {
+                    "type": "string",
+                    "title": "Mode",
+                    "enum": ["token_bucket", "min_interval", "debounce"],
+                    "default": "token_bucket"
+                },
+                "rate_per_sec": {
+                    "type": "number",
+                    "title": "Rate (per s)",
+                    "minimum": 0.01,
+                    "maximum": 1000.0,
+                    "default": 2.0
+                },
+                "burst": {
+                    "type": "integer",
+                    "title": "Burst",
+                    "minimum": 1,
+                    "maximum": 1000,
+                    "default": 5
+                },
+                "min_interval_secs": {
+                    "type": "number",
+                    "title": "Min Interval (s)",
+                    "minimum": 0.0,
+                    "maximum": 3600.0,
+                    "default": 1.0
+                },
+                "debounce_secs": {
+                    "type": "number",
+                    "title": "Debounce (s)",
+                    "minimum": 0.0,
+                    "maximum": 60.0,
+                    "default": 0.5
+                },
+                "per_type": { "type": "boolean", "title": "Per Signal Type", "default": true }
+            }
+        })
+    }
+
+    fn secs_to_us(secs: f32) -> u64 {
+        (f64::from(secs.max(0.0)) * 1e6) as u64
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    refilled_us: u64,
+}
+
+/// Drops or holds back signals so a slow sink sees at most a set rate.
+/// Control signals are never limited.
+pub struct RateLimitProcessor {
+    id: String,
+    enabled: bool,
+    config: RateLimitConfig,
+    clock: SharedClock,
+    buckets: HashMap<String, Bucket>,
+    last_sent_us: HashMap<String, u64>,
+    /// Debounced signals waiting for quiet, with when they're due
+    held: HashMap<String, (u64, Signal)>,
+    dropped: u64,
+}
+
+impl RateLimitProcessor {
+    pub fn new(id: &str, config: RateLimitConfig) -> Self {
+        Self {
+            id: id.to_string(),
+            enabled: true,
+            config,
+            clock: system_clock(),
+            buckets: HashMap::new(),
+            last_sent_us: HashMap::new(),
+            held: HashMap::new(),
+            dropped: 0,
+        }
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn config(&self) -> &RateLimitConfig {
+        &self.config
+    }
+
+    /// Limits start over; debounced signals are let go on the next wake
+    pub fn set_config(&mut self, config: RateLimitConfig) {
+        self.buckets.clear();
+        self.last_sent_us.clear();
+        if config.mode != RateLimitMode::Debounce {
+            let now = self.clock.now_us();
+            for (due, _) in self.held.values_mut() {
+                *due = now;
+            }
+        }
+        self.config = config;
+    }
+
+    /// Signals dropped since start
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    fn key(&self, signal: &Signal) -> String {
+        if self.config.per_type {
+            signal_key(signal)
+        } else {
+            SHARED_KEY.to_string()
+        }
+    }
+
+    fn take_token(&mut self, key: String, now: u64) -> bool {
+        let burst = f64::from(self.config.burst.max(1));
+        let rate = f64::from(self.config.rate_per_sec.max(0.01));
+        let bucket = self.buckets.entry(key).or_insert(Bucket {
+            tokens: burst,
+            refilled_us: now,
+        });
+        let elapsed = now.saturating_sub(bucket.refilled_us) as f64 / 1e6;
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(burst);
+        bucket.refilled_us = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn interval_passed(&mut self, key: String, now: u64) -> bool {
+        let interval = RateLimitConfig::secs_to_us(self.config.min_interval_secs);
+        match self.last_sent_us.get(&key) {
+            Some(&last) if now.saturating_sub(last) < interval => false,
+            _ => {
+                self.last_sent_us.insert(key, now);
+                true
+            }
+        }
+    }
+
+    /// The held signal due first
+    fn next_due(&self) -> Option<(&String, u64)> {
+        self.held
+            .iter()
+            .map(|(key, (due, _))| (key, *due))
+            .min_by_key(|(_, due)| *due)
+    }
+}
+
+#[async_trait]
+impl Processor for RateLimitProcessor {
+    fn name(&self) -> &str {
+        "Rate Limit"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "Rate Limit".to_string(),
+            description: "Caps how often signals pass: token bucket, interval or debounce"
+                .to_string(),
+            ports: vec![
+                Port {
+                    id: "signal_in".to_string(),
+                    label: "In".to_string(),
+                    data_type: DataType::Any,
+                    direction: PortDirection::Input,
+                },
+                Port {
+                    id: "signal_out".to_string(),
+                    label: "Out".to_string(),
+                    data_type: DataType::Any,
+                    direction: PortDirection::Output,
+                },
+            ],
+            settings_schema: Some(RateLimitConfig::schema()),
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    async fn process(&mut self, signal: Signal) -> anyhow::Result<Option<Signal>> {
+        match signal {
+            Signal::Control(ControlSignal::Settings(settings)) => {
+                match serde_json::from_value::<RateLimitConfig>(settings) {
+                    Ok(config) => self.set_config(config),
+                    Err(e) => log::warn!("Rate limit: invalid settings, keeping current: {}", e),
+                }
+                Ok(None)
+            }
+            signal @ Signal::Control(_) => Ok(Some(signal)),
+            signal => {
+                let now = self.clock.now_us();
+                let key = self.key(&signal);
+                let pass = match self.config.mode {
+                    RateLimitMode::TokenBucket => self.take_token(key, now),
+                    RateLimitMode::MinInterval => self.interval_passed(key, now),
+                    RateLimitMode::Debounce => {
+                        let due = now + RateLimitConfig::secs_to_us(self.config.debounce_secs);
+                        // The one it replaces never goes out
+                        if self.held.insert(key, (due, signal)).is_some() {
+                            self.dropped += 1;
+                        }
+                        return Ok(None);
+                    }
+                };
+                if pass {
+                    Ok(Some(signal))
+                } else {
+                    self.dropped += 1;
+                    Ok(None)
+                }
+            }
+        }
+    }
+
+    async fn next_wake(&self) {
+        match self.next_due() {
+            Some((_, due)) => {
+                let wait = due.saturating_sub(self.clock.now_us());
+                self.clock.sleep(Duration::from_micros(wait)).await;
+            }
+            None => std::future::pending().await,
+        }
+    }
+
+    async fn wake(&mut self) -> anyhow::Result<Option<Signal>> {
+        let now = self.clock.now_us();
+        let key = match self.next_due() {
+            Some((key, due)) if due <= now => key.clone(),
+            _ => return Ok(None),
+        };
+        Ok(self.held.remove(&key).map(|(_, signal)| signal))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use magnolia_core::VirtualClock;
+
+    fn text(s: &str) -> Signal {
+        Signal::Text(s.to_string())
+    }
+
+    fn partial(s: &str) -> Signal {
+        Signal::Computed {
+            source: "stt_partial".to_string(),
+            content: s.to_string(),
+        }
+    }
+
+    fn passed(signal: Option<Signal>) -> Option<String> {
+        match signal? {
+            Signal::Text(s) => Some(s),
+            Signal::Computed { content, .. } => Some(content),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn token_bucket_allows_bursts_then_refills() {
+        let clock = VirtualClock::manual();
+        let config = RateLimitConfig {
+            rate_per_sec: 2.0,
+            burst: 2,
+            ..RateLimitConfig::default()
+        };
+        let mut limiter = RateLimitProcessor::new("rate_limit", config).with_clock(clock.clone());
+
+        assert!(limiter.process(text("a")).await.unwrap().is_some());
+        assert!(limiter.process(text("b")).await.unwrap().is_some());
+        assert!(limiter.process(text("c")).await.unwrap().is_none());
+        // Other types have their own bucket
+        assert!(limiter.process(partial("p")).await.unwrap().is_some());
+
+        clock.advance(Duration::from_millis(500));
+        assert!(limiter.process(text("y")).await.unwrap().is_some());
+        assert!(limiter.process(text("z")).await.unwrap().is_none());
+        assert_eq!(limiter.dropped(), 2);
+    }
+
+    #[tokio::test]
+    async fn min_interval_drops_between() {
+        let clock = VirtualClock::manual();
+        let config = RateLimitConfig {
+            mode: RateLimitMode::MinInterval,
+            min_interval_secs: 1.0,
+            per_type: false,
+            ..RateLimitConfig::default()
+        };
+        let mut limiter = RateLimitProcessor::new("rate_limit", config).with_clock(clock.clone());
+
+        assert_eq!(
+            passed(limiter.process(text("a")).await.unwrap()),
+            Some("a".into())
+        );
+        clock.advance(Duration::from_millis(400));
+        assert_eq!(passed(limiter.process(partial("b")).await.unwrap()), None);
+        clock.advance(Duration::from_millis(600));
+        assert_eq!(
+            passed(limiter.process(text("c")).await.unwrap()),
+            Some("c".into())
+        );
+        // Control signals are never held up
+        let shutdown = Signal::Control(ControlSignal::Shutdown);
+        assert!(limiter.process(shutdown).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn debounce_emits_last_after_quiet() {
+        let clock = VirtualClock::new();
+        let config = RateLimitConfig {
+            mode: RateLimitMode::Debounce,
+            debounce_secs: 0.5,
+            ..RateLimitConfig::default()
+        };
+        let mut limiter = RateLimitProcessor::new("rate_limit", config).with_clock(clock.clone());
+
+        for word in ["h", "he", "hel"] {
+            assert!(limiter.process(partial(word)).await.unwrap().is_none());
+        }
+        assert!(limiter.process(text("final")).await.unwrap().is_none());
+        assert_eq!(passed(limiter.wake().await.unwrap()), None);
+
+        // Auto-advancing clock: the wait jumps straight to the deadline
+        limiter.next_wake().await;
+        let first = passed(limiter.wake().await.unwrap()).unwrap();
+        limiter.next_wake().await;
+        let second = passed(limiter.wake().await.unwrap()).unwrap();
+        let mut out = [first, second];
+        out.sort();
+        assert_eq!(out, ["final".to_string(), "hel".to_string()]);
+        assert_eq!(limiter.dropped(), 2);
+        assert_eq!(passed(limiter.wake().await.unwrap()), None);
+    }
+}
@@ -0,0 +1,238 @@
+use async_trait::async_trait;
+use magnolia_core::{
+    ControlSignal, DataType, ModuleSchema, Port, PortDirection, Processor, Signal,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::inputs::InputQueues;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SelectorConfig {
+    /// The input passed through, from 1
+    pub selected: usize,
+    /// Intent action switching input: `select 2`, `select next`,
+    /// `select previous`
+    pub select_action: String,
+}
+
+impl Default for SelectorConfig {
+    fn default() -> Self {
+        Self {
+            selected: 1,
+            select_action: "select".to_string(),
+        }
+    }
+}
+
+impl SelectorConfig {
+    pub fn schema(inputs: usize) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "selected": {
+                    "type": "integer",
+                    "title": "Selected Input",
+                    "minimum": 1,
+                    "maximum": inputs,
+                    "default": 1
+                },
+                "select_action": { "type": "string", "title": "Select Action", "default": "select" }
+            }
+        })
+    }
+}
+
+/// Passes one of its numbered inputs (`<id>_in_N`) and drops the rest.
+/// Switched by `select` intents, a bare number (Text or `Computed`) or
+/// settings on `select_in`; emits nothing itself on a switch.
+pub struct SelectorProcessor {
+    id: String,
+    enabled: bool,
+    config: SelectorConfig,
+    inputs: Arc<InputQueues>,
+}
+
+impl SelectorProcessor {
+    pub fn new(id: &str, config: SelectorConfig, inputs: Arc<InputQueues>) -> Self {
+        let mut selector = Self {
+            id: id.to_string(),
+            enabled: true,
+            config: SelectorConfig::default(),
+            inputs,
+        };
+        selector.set_config(config);
+        selector
+    }
+
+    pub fn config(&self) -> &SelectorConfig {
+        &self.config
+    }
+
+    pub fn set_config(&mut self, config: SelectorConfig) {
+        self.config = config;
+        self.select(self.config.selected);
+    }
+
+    /// The input passing, from 1
+    pub fn selected(&self) -> usize {
+        self.config.selected
+    }
+
+    /// Switch to input `input` (from 1, clamped); whatever the others had
+    /// queued is dropped
+    pub fn select(&mut self, input: usize) {
+        let count = self.inputs.count();
+        self.config.selected = input.clamp(1, count.max(1));
+        self.inputs.open_only(Some(self.config.selected - 1));
+    }
+
+    /// The input a command names, if it's one
+    fn requested(&self, signal: &Signal) -> Option<usize> {
+        let count = self.inputs.count();
+        let current = self.config.selected;
+        let arg = match signal {
+            Signal::Intent { action, parameters }
+                if action
+                    .trim()
+                    .eq_ignore_ascii_case(self.config.select_action.trim()) =>
+            {
+                parameters.first()?.trim().to_lowercase()
+            }
+            Signal::Text(text) => text.trim().to_string(),
+            Signal::Computed { content, .. } => content.trim().to_string(),
+            _ => return None,
+        };
+        match arg.as_str() {
+            "next" => Some(current % count + 1),
+            "previous" | "prev" => Some((current + count - 2) % count + 1),
+            // Numeric signals may arrive as `2.0`
+            number => number
+                .parse::<f64>()
+                .ok()
+                .filter(|n| n.is_finite() && *n >= 1.0)
+                .map(|n| n.round() as usize),
+        }
+    }
+}
+
+#[async_trait]
+impl Processor for SelectorProcessor {
+    fn name(&self) -> &str {
+        "Selector"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "Selector".to_string(),
+            description: format!(
+                "Passes one of {}_in_1..{} and drops the rest",
+                self.id,
+                self.inputs.count()
+            ),
+            ports: vec![
+                Port {
+                    id: "select_in".to_string(),
+                    label: "Select".to_string(),
+                    data_type: DataType::Control,
+                    direction: PortDirection::Input,
+                },
+                Port {
+                    id: "signal_out".to_string(),
+                    label: "Out".to_string(),
+                    data_type: DataType::Any,
+                    direction: PortDirection::Output,
+                },
+            ],
+            settings_schema: Some(SelectorConfig::schema(self.inputs.count())),
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    async fn process(&mut self, signal: Signal) -> anyhow::Result<Option<Signal>> {
+        if let Signal::Control(ControlSignal::Settings(settings)) = signal {
+            match serde_json::from_value::<SelectorConfig>(settings) {
+                Ok(config) => self.set_config(config),
+                Err(e) => log::warn!("Selector: invalid settings, keeping current: {}", e),
+            }
+        } else if let Some(input) = self.requested(&signal) {
+            self.select(input);
+        }
+        Ok(None)
+    }
+
+    async fn next_wake(&self) {
+        self.inputs.wait().await;
+    }
+
+    async fn wake(&mut self) -> anyhow::Result<Option<Signal>> {
+        Ok(self.inputs.pop(self.config.selected - 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use magnolia_core::Sink;
+
+    fn text(s: &str) -> Signal {
+        Signal::Text(s.to_string())
+    }
+
+    fn select(arg: &str) -> Signal {
+        Signal::Intent {
+            action: "select".to_string(),
+            parameters: vec![arg.to_string()],
+        }
+    }
+
+    async fn next(selector: &mut SelectorProcessor) -> String {
+        selector.next_wake().await;
+        match selector.wake().await.unwrap() {
+            Some(Signal::Text(t)) => t,
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn passes_only_the_selected_input() {
+        let inputs = InputQueues::new(3);
+        let sinks = inputs.sinks("selector");
+        assert_eq!(sinks[2].schema().id, "selector_in_3");
+        let mut selector = SelectorProcessor::new("selector", SelectorConfig::default(), inputs);
+
+        sinks[0].consume(text("a1")).await.unwrap();
+        sinks[1].consume(text("b1")).await.unwrap();
+        assert_eq!(next(&mut selector).await, "a1");
+        assert!(selector.wake().await.unwrap().is_none());
+
+        selector.process(select("2")).await.unwrap();
+        sinks[0].consume(text("a2")).await.unwrap();
+        sinks[1].consume(text("b2")).await.unwrap();
+        assert_eq!(next(&mut selector).await, "b2");
+
+        selector.process(select("next")).await.unwrap();
+        assert_eq!(selector.selected(), 3);
+        selector.process(select("next")).await.unwrap();
+        assert_eq!(selector.selected(), 1);
+        selector.process(select("previous")).await.unwrap();
+        assert_eq!(selector.selected(), 3);
+        selector.process(text("2.0")).await.unwrap();
+        assert_eq!(selector.selected(), 2);
+        selector.process(select("9")).await.unwrap();
+        assert_eq!(selector.selected(), 3);
+        // Not a select command
+        selector.process(text("hello")).await.unwrap();
+        assert_eq!(selector.selected(), 3);
+    }
+}
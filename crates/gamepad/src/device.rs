@@ -0,0 +1,231 @@
+//! Controller input from the Linux joystick interface (`/dev/input/js*`).
+//!
+//! Each device file yields fixed 8-byte records; the kernel already exposes
+//! every pad it has a driver for there, so no userspace library is needed.
+//! Devices are rescanned periodically so pads can be plugged in later.
+//!
+//! That interface is Linux only. Elsewhere no pads are found and
+//! [`spawn_reader`] fails with [`std::io::ErrorKind::Unsupported`]; the
+//! source warns once and stays idle.
+
+#[cfg(target_os = "linux")]
+use std::io::Read;
+#[cfg(target_os = "linux")]
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+const JS_EVENT_BUTTON: u8 = 0x01;
+const JS_EVENT_AXIS: u8 = 0x02;
+/// Set on the synthetic events describing the state at open
+const JS_EVENT_INIT: u8 = 0x80;
+
+pub const RESCAN_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Whether this platform has a joystick interface to read
+pub const SUPPORTED: bool = cfg!(target_os = "linux");
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GamepadEvent {
+    Connected {
+        pad: usize,
+        path: String,
+    },
+    Disconnected {
+        pad: usize,
+    },
+    /// `value` in -1..=1
+    Axis {
+        pad: usize,
+        axis: u8,
+        value: f32,
+    },
+    Button {
+        pad: usize,
+        button: u8,
+        pressed: bool,
+        /// Reported state at open rather than a press
+        initial: bool,
+    },
+}
+
+/// Decode one `struct js_event` (time, value, type, number; native endian)
+pub fn parse_js_event(pad: usize, record: &[u8; 8]) -> Option<GamepadEvent> {
+    let value = i16::from_ne_bytes([record[4], record[5]]);
+    let kind = record[6];
+    let number = record[7];
+    let initial = kind & JS_EVENT_INIT != 0;
+    match kind & !JS_EVENT_INIT {
+        JS_EVENT_BUTTON => Some(GamepadEvent::Button {
+            pad,
+            button: number,
+            pressed: value != 0,
+            initial,
+        }),
+        JS_EVENT_AXIS => Some(GamepadEvent::Axis {
+            pad,
+            axis: number,
+            value: (value as f32 / i16::MAX as f32).clamp(-1.0, 1.0),
+        }),
+        _ => None,
+    }
+}
+
+/// Pad index of a `jsN` device path
+#[cfg(target_os = "linux")]
+pub fn pad_index(path: &Path) -> Option<usize> {
+    path.file_name()?.to_str()?.strip_prefix("js")?.parse().ok()
+}
+
+/// Joystick device files, sorted by pad index
+#[cfg(target_os = "linux")]
+pub fn list_devices() -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir("/dev/input") else {
+        return Vec::new();
+    };
+    let mut devices: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| pad_index(path).is_some())
+        .collect();
+    devices.sort_by_key(|path| pad_index(path));
+    devices
+}
+
+/// No joystick interface to list
+#[cfg(not(target_os = "linux"))]
+pub fn list_devices() -> Vec<PathBuf> {
+    Vec::new()
+}
+
+/// Background thread that opens `device` (or every joystick when empty) and
+/// forwards its events until `tx` closes
+#[cfg(target_os = "linux")]
+pub fn spawn_reader(device: String, tx: mpsc::Sender<GamepadEvent>) -> std::io::Result<()> {
+    std::thread::Builder::new()
+        .name("gamepad-scan".to_string())
+        .spawn(move || {
+            let mut open: Vec<PathBuf> = Vec::new();
+            let (closed_tx, closed_rx) = std::sync::mpsc::channel::<PathBuf>();
+            while !tx.is_closed() {
+                while let Ok(path) = closed_rx.try_recv() {
+                    open.retain(|p| *p != path);
+                }
+                let candidates = if device.is_empty() {
+                    list_devices()
+                } else {
+                    vec![PathBuf::from(&device)]
+                };
+                for path in candidates {
+                    if open.contains(&path) {
+                        continue;
+                    }
+                    match std::fs::File::open(&path) {
+                        Ok(file) => {
+                            open.push(path.clone());
+                            let (tx, closed_tx) = (tx.clone(), closed_tx.clone());
+                            let spawned = std::thread::Builder::new()
+                                .name(format!("gamepad-{}", path.display()))
+                                .spawn(move || {
+                                    read_device(&path, file, &tx);
+                                    let _ = closed_tx.send(path);
+                                });
+                            if let Err(e) = spawned {
+                                log::warn!("Gamepad: failed to start reader: {}", e);
+                            }
+                        }
+                        // Unreadable devices are retried on the next scan
+                        Err(e) => log::debug!("Gamepad: cannot open {}: {}", path.display(), e),
+                    }
+                }
+                std::thread::sleep(RESCAN_INTERVAL);
+            }
+        })
+        .map(|_| ())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn spawn_reader(_device: String, _tx: mpsc::Sender<GamepadEvent>) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "gamepads are only read through the Linux joystick interface",
+    ))
+}
+
+#[cfg(target_os = "linux")]
+fn read_device(path: &Path, mut file: std::fs::File, tx: &mpsc::Sender<GamepadEvent>) {
+    let pad = pad_index(path).unwrap_or(0);
+    log::info!("Gamepad {} connected: {}", pad, path.display());
+    let connected = GamepadEvent::Connected {
+        pad,
+        path: path.display().to_string(),
+    };
+    if tx.blocking_send(connected).is_err() {
+        return;
+    }
+    let mut record = [0u8; 8];
+    loop {
+        if let Err(e) = file.read_exact(&mut record) {
+            log::info!("Gamepad {} disconnected: {}", pad, e);
+            let _ = tx.blocking_send(GamepadEvent::Disconnected { pad });
+            return;
+        }
+        if let Some(event) = parse_js_event(pad, &record) {
+            if tx.blocking_send(event).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(value: i16, kind: u8, number: u8) -> [u8; 8] {
+        let mut record = [0u8; 8];
+        record[..4].copy_from_slice(&1234u32.to_ne_bytes());
+        record[4..6].copy_from_slice(&value.to_ne_bytes());
+        record[6] = kind;
+        record[7] = number;
+        record
+    }
+
+    #[test]
+    fn decodes_joystick_records() {
+        assert_eq!(
+            parse_js_event(0, &record(1, JS_EVENT_BUTTON, 3)),
+            Some(GamepadEvent::Button {
+                pad: 0,
+                button: 3,
+                pressed: true,
+                initial: false
+            })
+        );
+        assert_eq!(
+            parse_js_event(1, &record(0, JS_EVENT_BUTTON | JS_EVENT_INIT, 0)),
+            Some(GamepadEvent::Button {
+                pad: 1,
+                button: 0,
+                pressed: false,
+                initial: true
+            })
+        );
+        assert_eq!(
+            parse_js_event(0, &record(i16::MIN, JS_EVENT_AXIS, 2)),
+            Some(GamepadEvent::Axis {
+                pad: 0,
+                axis: 2,
+                value: -1.0
+            })
+        );
+        assert_eq!(parse_js_event(0, &record(5, 0x04, 0)), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn reads_pad_index_from_device_path() {
+        assert_eq!(pad_index(Path::new("/dev/input/js2")), Some(2));
+        assert_eq!(pad_index(Path::new("/dev/input/event2")), None);
+    }
+}
@@ -0,0 +1,15 @@
+//! Gamepad: game controllers and joysticks as a Magnolia source.
+//!
+//! Axes leave as numeric `Computed` signals (one port per axis) so a stick
+//! can sweep a DSP parameter, and button presses as intents so pads can
+//! drive navigation or a switchboard without MIDI hardware. Input comes from
+//! the Linux joystick interface, so on other platforms the source finds no
+//! pads: it logs an "unsupported platform" warning and emits nothing.
+
+mod device;
+mod mapping;
+mod source;
+
+pub use device::{list_devices, parse_js_event, GamepadEvent};
+pub use mapping::{apply_dead_zone, axis_source, GamepadConfig, GamepadMapper, MAX_AXES};
+pub use source::GamepadSource;
@@ -0,0 +1,227 @@
+use magnolia_core::Signal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::device::GamepadEvent;
+
+/// Axes with their own output port (`axis_0_out` ..)
+pub const MAX_AXES: usize = 8;
+
+/// Source name of an axis' numeric signals
+pub fn axis_source(axis: u8) -> String {
+    format!("gamepad_axis_{}", axis)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GamepadConfig {
+    /// Joystick device (`/dev/input/js0`); empty uses every pad
+    pub device: String,
+    /// Axis values within this of centre read as 0; the rest is rescaled
+    pub dead_zone: f32,
+    /// Smallest change worth a new numeric signal
+    pub resolution: f32,
+    /// Intent action per button index; buttons past the end use `button_N`
+    pub button_actions: Vec<String>,
+    /// Also emit an intent when a button is let go
+    pub emit_releases: bool,
+}
+
+impl Default for GamepadConfig {
+    fn default() -> Self {
+        Self {
+            device: String::new(),
+            dead_zone: 0.08,
+            resolution: 0.01,
+            button_actions: Vec::new(),
+            emit_releases: false,
+        }
+    }
+}
+
+impl GamepadConfig {
+    pub fn schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "device": {
+                    "type": "string",
+                    "title": "Device (empty: all)",
+                    "default": ""
+                },
+                "dead_zone": {
+                    "type": "number",
+                    "title": "Dead Zone",
+                    "minimum": 0.0,
+                    "maximum": 0.5,
+                    "default": 0.08
+                },
+                "resolution": {
+                    "type": "number",
+                    "title": "Axis Resolution",
+                    "minimum": 0.001,
+                    "maximum": 0.25,
+                    "default": 0.01
+                },
+                "button_actions": {
+                    "type": "array",
+                    "title": "Button Actions",
+                    "items": { "type": "string" },
+                    "default": []
+                },
+                "emit_releases": {
+                    "type": "boolean",
+                    "title": "Emit Releases",
+                    "default": false
+                }
+            }
+        })
+    }
+
+    pub fn button_action(&self, button: u8) -> String {
+        match self.button_actions.get(button as usize) {
+            Some(action) if !action.trim().is_empty() => action.trim().to_string(),
+            _ => format!("button_{}", button),
+        }
+    }
+}
+
+/// Apply the dead zone, rescaling the remainder back to -1..=1
+pub fn apply_dead_zone(value: f32, dead_zone: f32) -> f32 {
+    let dead_zone = dead_zone.clamp(0.0, 0.99);
+    let magnitude = value.abs();
+    if magnitude <= dead_zone {
+        0.0
+    } else {
+        value.signum() * ((magnitude - dead_zone) / (1.0 - dead_zone)).min(1.0)
+    }
+}
+
+/// Turns device events into signals: axes as numeric `Computed` values,
+/// button presses as `Intent { action, ["pressed" | "released"] }`.
+/// Pads are merged, so any controller drives the same ports.
+#[derive(Debug, Default)]
+pub struct GamepadMapper {
+    /// Last value sent per (pad, axis)
+    axes: HashMap<(usize, u8), f32>,
+}
+
+impl GamepadMapper {
+    pub fn map(&mut self, config: &GamepadConfig, event: GamepadEvent) -> Option<Signal> {
+        match event {
+            GamepadEvent::Axis { pad, axis, value } => {
+                let value = apply_dead_zone(value, config.dead_zone);
+                let last = self.axes.get(&(pad, axis)).copied();
+                // Always send the rest position and full deflection, so small
+                // steps near either end aren't swallowed
+                let settled = value == 0.0 || value.abs() == 1.0;
+                let changed = match last {
+                    None => true,
+                    Some(last) => {
+                        (value - last).abs() >= config.resolution.max(1e-4)
+                            || (settled && value != last)
+                    }
+                };
+                if !changed {
+                    return None;
+                }
+                self.axes.insert((pad, axis), value);
+                Some(Signal::Computed {
+                    source: axis_source(axis),
+                    content: format!("{:.3}", value),
+                })
+            }
+            GamepadEvent::Button {
+                button,
+                pressed,
+                initial,
+                ..
+            } => {
+                if initial || (!pressed && !config.emit_releases) {
+                    return None;
+                }
+                Some(Signal::Intent {
+                    action: config.button_action(button),
+                    parameters: vec![if pressed { "pressed" } else { "released" }.to_string()],
+                })
+            }
+            GamepadEvent::Disconnected { pad } => {
+                self.axes.retain(|(p, _), _| *p != pad);
+                None
+            }
+            GamepadEvent::Connected { .. } => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn axis(axis: u8, value: f32) -> GamepadEvent {
+        GamepadEvent::Axis {
+            pad: 0,
+            axis,
+            value,
+        }
+    }
+
+    fn numeric(signal: Option<Signal>) -> Option<(String, String)> {
+        match signal? {
+            Signal::Computed { source, content } => Some((source, content)),
+            other => panic!("expected numeric signal, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn maps_axes_and_buttons() {
+        let config = GamepadConfig {
+            button_actions: vec!["select".into(), " ".into()],
+            ..GamepadConfig::default()
+        };
+        let mut mapper = GamepadMapper::default();
+
+        assert_eq!(
+            numeric(mapper.map(&config, axis(1, 0.05))),
+            Some(("gamepad_axis_1".into(), "0.000".into()))
+        );
+        // Inside the dead zone again: nothing new
+        assert_eq!(numeric(mapper.map(&config, axis(1, -0.07))), None);
+        assert_eq!(
+            numeric(mapper.map(&config, axis(1, 0.54))),
+            Some(("gamepad_axis_1".into(), "0.500".into()))
+        );
+        assert_eq!(numeric(mapper.map(&config, axis(1, 0.545))), None);
+        assert_eq!(
+            numeric(mapper.map(&config, axis(1, 1.0))).unwrap().1,
+            "1.000"
+        );
+
+        let button = |button, pressed, initial| GamepadEvent::Button {
+            pad: 0,
+            button,
+            pressed,
+            initial,
+        };
+        let pressed = mapper.map(&config, button(0, true, false));
+        assert!(matches!(
+            &pressed,
+            Some(Signal::Intent { action, parameters }) if action == "select" && parameters == &["pressed"]
+        ));
+        let unnamed = mapper.map(&config, button(1, true, false));
+        assert!(matches!(&unnamed, Some(Signal::Intent { action, .. }) if action == "button_1"));
+        assert!(mapper.map(&config, button(0, false, false)).is_none());
+        assert!(mapper.map(&config, button(0, true, true)).is_none());
+
+        let releases = GamepadConfig {
+            emit_releases: true,
+            ..config
+        };
+        let released = mapper.map(&releases, button(4, false, false));
+        assert!(matches!(
+            &released,
+            Some(Signal::Intent { action, parameters }) if action == "button_4" && parameters == &["released"]
+        ));
+        assert!(apply_dead_zone(-1.2, 0.1) == -1.0);
+    }
+}
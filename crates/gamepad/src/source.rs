@@ -0,0 +1,191 @@
+use async_trait::async_trait;
+use magnolia_core::{ControlSignal, DataType, ModuleSchema, Port, PortDirection, Signal, Source};
+use tokio::sync::mpsc;
+
+use crate::device::{spawn_reader, GamepadEvent, RESCAN_INTERVAL, SUPPORTED};
+use crate::mapping::{GamepadConfig, GamepadMapper, MAX_AXES};
+
+const EVENT_QUEUE: usize = 1024;
+
+/// Game controllers as a source: `buttons_out` carries button intents and
+/// `axis_N_out` each axis as a numeric signal. Settings arrive on
+/// `settings_in`; changing the device reopens it.
+pub struct GamepadSource {
+    id: String,
+    enabled: bool,
+    config: GamepadConfig,
+    mapper: GamepadMapper,
+    events: Option<mpsc::Receiver<GamepadEvent>>,
+}
+
+impl GamepadSource {
+    pub fn new(id: &str, config: GamepadConfig) -> Self {
+        if !SUPPORTED {
+            log::warn!(
+                "Gamepad {}: unsupported platform, pads are only read on Linux",
+                id
+            );
+        }
+        Self {
+            id: id.to_string(),
+            enabled: true,
+            config,
+            mapper: GamepadMapper::default(),
+            events: None,
+        }
+    }
+
+    pub fn config(&self) -> &GamepadConfig {
+        &self.config
+    }
+
+    pub fn set_config(&mut self, config: GamepadConfig) {
+        if config.device != self.config.device {
+            // Dropping the receiver stops the old readers
+            self.events = None;
+            self.mapper = GamepadMapper::default();
+        }
+        self.config = config;
+    }
+
+    fn start(&mut self) {
+        let (tx, rx) = mpsc::channel(EVENT_QUEUE);
+        match spawn_reader(self.config.device.clone(), tx) {
+            Ok(()) => self.events = Some(rx),
+            Err(e) => log::error!("Gamepad: failed to start device scan: {}", e),
+        }
+    }
+}
+
+#[async_trait]
+impl Source for GamepadSource {
+    fn name(&self) -> &str {
+        "Gamepad"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        let mut ports = vec![Port {
+            id: "buttons_out".to_string(),
+            label: "Buttons".to_string(),
+            data_type: DataType::Control,
+            direction: PortDirection::Output,
+        }];
+        ports.extend((0..MAX_AXES).map(|axis| Port {
+            id: format!("axis_{}_out", axis),
+            label: format!("Axis {}", axis),
+            data_type: DataType::Numeric,
+            direction: PortDirection::Output,
+        }));
+        ports.push(Port {
+            id: "settings_in".to_string(),
+            label: "Settings".to_string(),
+            data_type: DataType::Control,
+            direction: PortDirection::Input,
+        });
+
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "Gamepad".to_string(),
+            description: "Game controller axes and buttons".to_string(),
+            ports,
+            settings_schema: Some(GamepadConfig::schema()),
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Nothing to read on platforms without the joystick interface
+    fn is_idle(&self) -> bool {
+        !SUPPORTED
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    async fn poll(&mut self) -> Option<Signal> {
+        loop {
+            let Some(events) = self.events.as_mut() else {
+                self.start();
+                if self.events.is_none() {
+                    tokio::time::sleep(RESCAN_INTERVAL).await;
+                }
+                continue;
+            };
+            let Some(event) = events.recv().await else {
+                // Scan thread gone; start over
+                self.events = None;
+                tokio::time::sleep(RESCAN_INTERVAL).await;
+                continue;
+            };
+            if !self.enabled {
+                continue;
+            }
+            if matches!(event, GamepadEvent::Axis { axis, .. } if axis as usize >= MAX_AXES) {
+                continue;
+            }
+            if let Some(signal) = self.mapper.map(&self.config, event) {
+                return Some(signal);
+            }
+        }
+    }
+
+    fn handle_signal(&mut self, signal: Signal) {
+        if let Signal::Control(ControlSignal::Settings(settings)) = signal {
+            match serde_json::from_value::<GamepadConfig>(settings) {
+                Ok(config) => self.set_config(config),
+                Err(e) => log::warn!("Gamepad: invalid settings, keeping current: {}", e),
+            }
+        }
+    }
+
+    fn output_port(&self, signal: &Signal) -> Option<&str> {
+        let Signal::Computed { source, .. } = signal else {
+            return Some("buttons_out");
+        };
+        const AXIS_PORTS: [&str; MAX_AXES] = [
+            "axis_0_out",
+            "axis_1_out",
+            "axis_2_out",
+            "axis_3_out",
+            "axis_4_out",
+            "axis_5_out",
+            "axis_6_out",
+            "axis_7_out",
+        ];
+        let axis: usize = source.strip_prefix("gamepad_axis_")?.parse().ok()?;
+        AXIS_PORTS.get(axis).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn routes_axes_to_their_ports() {
+        let mut source = GamepadSource::new("gamepad", GamepadConfig::default());
+        let schema = source.schema();
+        assert_eq!(schema.ports.len(), 1 + MAX_AXES + 1);
+
+        let axis = Signal::Computed {
+            source: "gamepad_axis_3".into(),
+            content: "0.250".into(),
+        };
+        assert_eq!(source.output_port(&axis), Some("axis_3_out"));
+        let button = Signal::Intent {
+            action: "button_0".into(),
+            parameters: vec!["pressed".into()],
+        };
+        assert_eq!(source.output_port(&button), Some("buttons_out"));
+
+        source.handle_signal(Signal::Control(ControlSignal::Settings(
+            serde_json::json!({ "dead_zone": 0.2, "button_actions": ["select"] }),
+        )));
+        assert_eq!(source.config().dead_zone, 0.2);
+        assert_eq!(source.config().button_action(0), "select");
+    }
+}
@@ -0,0 +1,301 @@
+//! Just enough IMAP4rev1 to read a mailbox: log in, find unseen messages
+//! by UID, fetch them and flag them `\Seen`.
+
+use anyhow::{anyhow, bail, Context};
+use rustls::pki_types::ServerName;
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::Arc;
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(20);
+/// Longest response line or literal accepted
+const MAX_LITERAL: usize = 1 << 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Security {
+    /// Implicit TLS, usually port 993
+    #[default]
+    Tls,
+    /// Plain connection upgraded with STARTTLS, usually port 143
+    StartTls,
+    /// No encryption; only for a server on the same machine
+    None,
+}
+
+pub(crate) trait Stream: Read + Write + Send {}
+
+impl<T: Read + Write + Send> Stream for T {}
+
+/// One response line; literals (`{n}` followed by n bytes) are lifted out
+/// in order, leaving the `{n}` marker in `text`
+#[derive(Debug, Default)]
+pub(crate) struct Line {
+    pub text: String,
+    pub literals: Vec<Vec<u8>>,
+}
+
+pub(crate) struct ImapClient {
+    reader: BufReader<Box<dyn Stream>>,
+    tag: u32,
+}
+
+fn tls(host: &str, stream: Box<dyn Stream>) -> anyhow::Result<Box<dyn Stream>> {
+    let roots = rustls::RootCertStore {
+        roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+    };
+    let config = rustls::ClientConfig::builder_with_provider(
+        rustls::crypto::ring::default_provider().into(),
+    )
+    .with_protocol_versions(&[&rustls::version::TLS12, &rustls::version::TLS13])?
+    .with_root_certificates(roots)
+    .with_no_client_auth();
+    let name = ServerName::try_from(host.to_string()).context("invalid server name")?;
+    let connection = rustls::ClientConnection::new(Arc::new(config), name)?;
+    Ok(Box::new(rustls::StreamOwned::new(connection, stream)))
+}
+
+/// `"..."` with quotes and backslashes escaped
+pub(crate) fn quote(value: &str) -> anyhow::Result<String> {
+    if value.contains(['\r', '\n']) {
+        bail!("line breaks aren't allowed here");
+    }
+    Ok(format!(
+        "\"{}\"",
+        value.replace('\\', "\\\\").replace('"', "\\\"")
+    ))
+}
+
+impl ImapClient {
+    pub fn connect(host: &str, port: u16, security: Security) -> anyhow::Result<Self> {
+        let addr = (host, port)
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| anyhow!("{} not found", host))?;
+        let tcp = TcpStream::connect_timeout(&addr, TIMEOUT)?;
+        tcp.set_read_timeout(Some(TIMEOUT))?;
+        tcp.set_write_timeout(Some(TIMEOUT))?;
+        let stream: Box<dyn Stream> = match security {
+            Security::Tls => tls(host, Box::new(tcp))?,
+            Security::StartTls | Security::None => Box::new(tcp),
+        };
+        let mut client = Self::from_stream(stream)?;
+        if security == Security::StartTls {
+            client.command("STARTTLS")?;
+            let stream = client.reader.into_inner();
+            client = Self {
+                reader: BufReader::new(tls(host, stream)?),
+                tag: client.tag,
+            };
+        }
+        Ok(client)
+    }
+
+    /// Wraps a connected stream and reads the greeting
+    pub fn from_stream(stream: Box<dyn Stream>) -> anyhow::Result<Self> {
+        let mut client = Self {
+            reader: BufReader::new(stream),
+            tag: 0,
+        };
+        let greeting = client.read_line()?;
+        if !greeting.text.starts_with("* OK") && !greeting.text.starts_with("* PREAUTH") {
+            bail!("server refused: {}", greeting.text);
+        }
+        Ok(client)
+    }
+
+    fn read_raw_line(&mut self) -> io::Result<Vec<u8>> {
+        let mut line = Vec::new();
+        (&mut self.reader)
+            .take(MAX_LITERAL as u64)
+            .read_until(b'\n', &mut line)?;
+        if !line.ends_with(b"\n") {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        while matches!(line.last(), Some(b'\r' | b'\n')) {
+            line.pop();
+        }
+        Ok(line)
+    }
+
+    pub(crate) fn read_line(&mut self) -> anyhow::Result<Line> {
+        let mut line = Line::default();
+        loop {
+            let raw = self.read_raw_line()?;
+            let text = String::from_utf8_lossy(&raw);
+            line.text.push_str(&text);
+            let Some(size) = literal_size(&text) else {
+                return Ok(line);
+            };
+            if size > MAX_LITERAL {
+                bail!("literal of {} bytes is too large", size);
+            }
+            let mut literal = vec![0; size];
+            self.reader.read_exact(&mut literal)?;
+            line.literals.push(literal);
+        }
+    }
+
+    /// Sends a command and returns the untagged lines before its `OK`
+    pub fn command(&mut self, command: &str) -> anyhow::Result<Vec<Line>> {
+        self.tag += 1;
+        let tag = format!("a{}", self.tag);
+        let stream = self.reader.get_mut();
+        stream.write_all(format!("{} {}\r\n", tag, command).as_bytes())?;
+        stream.flush()?;
+
+        let mut untagged = Vec::new();
+        loop {
+            let line = self.read_line()?;
+            let Some(status) = line.text.strip_prefix(&tag) else {
+                if line.text.starts_with("* BYE") {
+                    bail!("server closed the connection: {}", line.text);
+                }
+                untagged.push(line);
+                continue;
+            };
+            let status = status.trim_start();
+            if status.starts_with("OK") {
+                return Ok(untagged);
+            }
+            let verb = command.split_whitespace().next().unwrap_or_default();
+            bail!("{} failed: {}", verb, status);
+        }
+    }
+
+    pub fn login(&mut self, user: &str, password: &str) -> anyhow::Result<()> {
+        self.command(&format!("LOGIN {} {}", quote(user)?, quote(password)?))
+            .map(|_| ())
+            .map_err(|e| anyhow!("login failed: {}", e))
+    }
+
+    /// Opens the mailbox read-write; returns its UIDVALIDITY
+    pub fn select(&mut self, mailbox: &str) -> anyhow::Result<u32> {
+        let lines = self.command(&format!("SELECT {}", quote(mailbox)?))?;
+        lines
+            .iter()
+            .find_map(|line| {
+                let rest = line.text.split("[UIDVALIDITY ").nth(1)?;
+                rest.split(']').next()?.trim().parse().ok()
+            })
+            .ok_or_else(|| anyhow!("no UIDVALIDITY for {}", mailbox))
+    }
+
+    /// UIDs of unseen messages above `after`, ascending
+    pub fn unseen_after(&mut self, after: u32) -> anyhow::Result<Vec<u32>> {
+        let lines = self.command(&format!("UID SEARCH UNSEEN UID {}:*", after + 1))?;
+        let mut uids: Vec<u32> = lines
+            .iter()
+            .filter_map(|line| line.text.strip_prefix("* SEARCH"))
+            .flat_map(|rest| rest.split_whitespace().filter_map(|uid| uid.parse().ok()))
+            // `n:*` always matches the highest UID, even below n
+            .filter(|&uid| uid > after)
+            .collect();
+        uids.sort_unstable();
+        Ok(uids)
+    }
+
+    /// Up to `max_bytes` of a message, without setting `\Seen`
+    pub fn fetch(&mut self, uid: u32, max_bytes: usize) -> anyhow::Result<Vec<u8>> {
+        let lines = self.command(&format!("UID FETCH {} (BODY.PEEK[]<0.{}>)", uid, max_bytes))?;
+        lines
+            .into_iter()
+            .find(|line| line.text.contains("FETCH") && line.text.contains("BODY["))
+            .and_then(|line| line.literals.into_iter().next())
+            .ok_or_else(|| anyhow!("message {} vanished", uid))
+    }
+
+    pub fn mark_seen(&mut self, uid: u32) -> anyhow::Result<()> {
+        self.command(&format!("UID STORE {} +FLAGS.SILENT (\\Seen)", uid))
+            .map(|_| ())
+    }
+
+    pub fn logout(mut self) {
+        let _ = self.command("LOGOUT");
+    }
+}
+
+/// `n` when the line ends with a `{n}` literal marker
+fn literal_size(text: &str) -> Option<usize> {
+    let open = text.strip_suffix('}')?.rfind('{')?;
+    text[open + 1..text.len() - 1].parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use std::sync::Mutex;
+
+    /// Replays a server script and records what the client wrote
+    struct Scripted {
+        input: Cursor<Vec<u8>>,
+        written: Arc<Mutex<Vec<u8>>>,
+    }
+
+    impl Scripted {
+        fn new(script: &str) -> Self {
+            Self {
+                input: Cursor::new(script.as_bytes().to_vec()),
+                written: Arc::default(),
+            }
+        }
+    }
+
+    impl Read for Scripted {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.input.read(buf)
+        }
+    }
+
+    impl Write for Scripted {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.written.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn session_with_literals() {
+        let script = "* OK IMAP4rev1 ready\r\n\
+            a1 OK LOGIN completed\r\n\
+            * 3 EXISTS\r\n\
+            * OK [UIDVALIDITY 3857529045] UIDs valid\r\n\
+            a2 OK [READ-WRITE] SELECT completed\r\n\
+            * SEARCH 7 12\r\n\
+            a3 OK SEARCH completed\r\n\
+            * 2 FETCH (UID 12 BODY[]<0> {11}\r\nSubject: hi)\r\n\
+            a4 OK FETCH completed\r\n\
+            a5 NO [CANNOT] read-only\r\n";
+        let stream = Scripted::new(script);
+        let written = stream.written.clone();
+        let mut client = ImapClient::from_stream(Box::new(stream)).unwrap();
+
+        client.login("ana@example.org", "p\"w").unwrap();
+        assert_eq!(client.select("INBOX").unwrap(), 3857529045);
+        assert_eq!(client.unseen_after(7).unwrap(), vec![12]);
+        assert_eq!(client.fetch(12, 65536).unwrap(), b"Subject: hi");
+        let err = client.mark_seen(12).unwrap_err();
+        assert_eq!(err.to_string(), "UID failed: NO [CANNOT] read-only");
+
+        let written = String::from_utf8(written.lock().unwrap().clone()).unwrap();
+        let commands: Vec<&str> = written.lines().collect();
+        assert_eq!(
+            commands,
+            vec![
+                r#"a1 LOGIN "ana@example.org" "p\"w""#,
+                r#"a2 SELECT "INBOX""#,
+                "a3 UID SEARCH UNSEEN UID 8:*",
+                "a4 UID FETCH 12 (BODY.PEEK[]<0.65536>)",
+                r"a5 UID STORE 12 +FLAGS.SILENT (\Seen)",
+            ]
+        );
+        assert!(quote("a\r\nb").is_err());
+    }
+}
@@ -0,0 +1,16 @@
+//! Inbox source: polls an IMAP mailbox for unseen mail.
+//!
+//! Each new message leaves as Text on `text_out` (its subject, then its
+//! plain-text body) and as JSON with the sender, date and message id on
+//! `messages_out`. Mail is flagged `\Seen` once read, so a slow email
+//! channel can feed intents into a long-running installation.
+
+mod imap;
+pub mod mime;
+mod source;
+
+pub use imap::Security;
+pub use mime::Email;
+pub use source::{
+    check, email_signals, Cursor, InboxConfig, InboxSource, MESSAGE_SOURCE, MIN_POLL_INTERVAL_SECS,
+};
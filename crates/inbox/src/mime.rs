@@ -0,0 +1,335 @@
+//! Reading an RFC 5322 message: the headers people see and a plain-text
+//! body, through MIME multiparts, transfer encodings and encoded words.
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Email {
+    /// The From header as written, e.g. `Ana <ana@example.org>`
+    pub from: String,
+    /// Just the address, lowercased
+    pub address: String,
+    pub subject: String,
+    pub date: String,
+    pub message_id: String,
+    pub body: String,
+}
+
+/// Unfolded `(lowercased name, value)` pairs and the body after them
+fn split_headers(raw: &[u8]) -> (Vec<(String, String)>, &[u8]) {
+    let end = raw
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .map(|i| (i, i + 4))
+        .or_else(|| {
+            raw.windows(2)
+                .position(|w| w == b"\n\n")
+                .map(|i| (i, i + 2))
+        });
+    let (head, body) = match end {
+        Some((head_end, body_start)) => (&raw[..head_end], &raw[body_start..]),
+        None => (raw, &raw[raw.len()..]),
+    };
+
+    let mut headers: Vec<(String, String)> = Vec::new();
+    for line in String::from_utf8_lossy(head).lines() {
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+        }
+    }
+    (headers, body)
+}
+
+fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, v)| v.as_str())
+}
+
+/// `text/plain; charset="utf-8"` as the lowercased type and a parameter
+/// lookup
+fn content_type(value: &str) -> (String, Vec<(String, String)>) {
+    let mut parts = value.split(';');
+    let mime = parts.next().unwrap_or_default().trim().to_ascii_lowercase();
+    let params = parts
+        .filter_map(|param| {
+            let (name, value) = param.split_once('=')?;
+            Some((
+                name.trim().to_ascii_lowercase(),
+                value.trim().trim_matches('"').to_string(),
+            ))
+        })
+        .collect();
+    (mime, params)
+}
+
+fn param<'a>(params: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    params
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, v)| v.as_str())
+}
+
+fn decode_charset(bytes: &[u8], charset: &str) -> String {
+    match charset.to_ascii_lowercase().as_str() {
+        // Close enough for the Western European mail this sees
+        "iso-8859-1" | "iso-8859-15" | "latin1" | "windows-1252" | "cp1252" => {
+            bytes.iter().map(|&b| b as char).collect()
+        }
+        _ => String::from_utf8_lossy(bytes).into_owned(),
+    }
+}
+
+/// Quoted-printable; `header` also reads `_` as a space (RFC 2047 Q)
+fn quoted_printable(data: &[u8], header: bool) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len());
+    let mut i = 0;
+    while i < data.len() {
+        match data[i] {
+            b'=' => {
+                let hex = data
+                    .get(i + 1..i + 3)
+                    .and_then(|hex| std::str::from_utf8(hex).ok())
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+                if let Some(byte) = hex {
+                    out.push(byte);
+                    i += 3;
+                } else if data[i + 1..].starts_with(b"\r\n") {
+                    // Soft line break
+                    i += 3;
+                } else if data[i + 1..].starts_with(b"\n") {
+                    i += 2;
+                } else {
+                    out.push(b'=');
+                    i += 1;
+                }
+            }
+            b'_' if header => {
+                out.push(b' ');
+                i += 1;
+            }
+            byte => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+fn base64_lenient(data: &[u8]) -> Vec<u8> {
+    let clean: Vec<u8> = data
+        .iter()
+        .copied()
+        .filter(|b| !b.is_ascii_whitespace())
+        .collect();
+    base64::engine::general_purpose::STANDARD
+        .decode(&clean)
+        .or_else(|_| base64::engine::general_purpose::STANDARD_NO_PAD.decode(&clean))
+        .unwrap_or_default()
+}
+
+/// One `=?charset?B|Q?text?=` word, if that's what it is
+fn decode_word(word: &str) -> Option<String> {
+    let inner = word.strip_prefix("=?")?.strip_suffix("?=")?;
+    let mut parts = inner.splitn(3, '?');
+    let charset = parts.next()?;
+    // RFC 2231 language suffix: utf-8*en
+    let charset = charset.split('*').next().unwrap_or(charset);
+    let encoding = parts.next()?;
+    let text = parts.next()?;
+    let bytes = match encoding {
+        "B" | "b" => base64_lenient(text.as_bytes()),
+        "Q" | "q" => quoted_printable(text.as_bytes(), true),
+        _ => return None,
+    };
+    Some(decode_charset(&bytes, charset))
+}
+
+/// Header value with RFC 2047 encoded words decoded; whitespace between
+/// two encoded words is dropped
+pub fn decode_words(value: &str) -> String {
+    let mut out = String::new();
+    let mut pending_space = String::new();
+    let mut last_encoded = false;
+    let mut rest = value;
+    while !rest.is_empty() {
+        let split = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let (word, tail) = rest.split_at(split);
+        let spaces = tail.len() - tail.trim_start().len();
+        match decode_word(word) {
+            Some(decoded) => {
+                if !last_encoded {
+                    out.push_str(&pending_space);
+                }
+                out.push_str(&decoded);
+                last_encoded = true;
+            }
+            None => {
+                out.push_str(&pending_space);
+                out.push_str(word);
+                last_encoded = false;
+            }
+        }
+        pending_space = tail[..spaces].to_string();
+        rest = &tail[spaces..];
+    }
+    out
+}
+
+fn address(from: &str) -> String {
+    let address = match (from.rfind('<'), from.rfind('>')) {
+        (Some(open), Some(close)) if open < close => &from[open + 1..close],
+        _ => from,
+    };
+    address.trim().to_ascii_lowercase()
+}
+
+/// Tags dropped and common entities undone; enough to read an HTML-only
+/// message as text
+fn strip_html(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                text.push(' ');
+            }
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    let text = text
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// The text of one MIME entity: plain text preferred, HTML as a fallback
+fn entity_text(headers: &[(String, String)], body: &[u8]) -> Option<(bool, String)> {
+    let (mime, params) = content_type(header(headers, "content-type").unwrap_or("text/plain"));
+    if mime.starts_with("multipart/") {
+        let boundary = format!("--{}", param(&params, "boundary")?);
+        let mut html = None;
+        for part in split_parts(body, boundary.as_bytes()) {
+            let (part_headers, part_body) = split_headers(part);
+            match entity_text(&part_headers, part_body) {
+                Some((true, text)) => return Some((true, text)),
+                Some((false, text)) => html = html.or(Some(text)),
+                None => {}
+            }
+        }
+        return html.map(|text| (false, text));
+    }
+    let disposition = header(headers, "content-disposition").unwrap_or_default();
+    if disposition.to_ascii_lowercase().starts_with("attachment") {
+        return None;
+    }
+    let plain = match mime.as_str() {
+        "text/plain" => true,
+        "text/html" => false,
+        _ => return None,
+    };
+    let encoding = header(headers, "content-transfer-encoding")
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let bytes = match encoding.as_str() {
+        "quoted-printable" => quoted_printable(body, false),
+        "base64" => base64_lenient(body),
+        _ => body.to_vec(),
+    };
+    let text = decode_charset(&bytes, param(&params, "charset").unwrap_or("utf-8"));
+    Some(if plain {
+        (true, text)
+    } else {
+        (false, strip_html(&text))
+    })
+}
+
+/// Bodies between `boundary` lines; a truncated message just ends early
+fn split_parts<'a>(body: &'a [u8], boundary: &[u8]) -> Vec<&'a [u8]> {
+    let mut parts = Vec::new();
+    let mut start = None;
+    let mut offset = 0;
+    for line in body.split_inclusive(|&b| b == b'\n') {
+        let trimmed = line.trim_ascii_end();
+        if trimmed.starts_with(boundary) {
+            if let Some(start) = start {
+                parts.push(&body[start..offset]);
+            }
+            if trimmed[boundary.len()..].starts_with(b"--") {
+                return parts;
+            }
+            start = Some(offset + line.len());
+        }
+        offset += line.len();
+    }
+    if let Some(start) = start {
+        parts.push(&body[start..]);
+    }
+    parts
+}
+
+pub fn parse(raw: &[u8]) -> Email {
+    let (headers, body) = split_headers(raw);
+    let get = |name| decode_words(header(&headers, name).unwrap_or_default());
+    let from = get("from");
+    Email {
+        address: address(&from),
+        from,
+        subject: get("subject"),
+        date: get("date"),
+        message_id: get("message-id"),
+        body: entity_text(&headers, body)
+            .map(|(_, text)| text.trim().to_string())
+            .unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multipart_with_encoded_headers() {
+        let raw = "From: =?UTF-8?Q?Ana_Mar=C3=ADa?= <Ana@Example.org>\r\n\
+            Subject: =?utf-8?B?TGljaHQ=?= =?utf-8?B?IGFu?= please\r\n\
+            Message-ID: <1@example.org>\r\n\
+            Content-Type: multipart/alternative;\r\n boundary=\"b1\"\r\n\
+            \r\n\
+            preamble\r\n\
+            --b1\r\n\
+            Content-Type: text/html\r\n\
+            \r\n\
+            <p>lights&nbsp;on</p>\r\n\
+            --b1\r\n\
+            Content-Type: text/plain; charset=iso-8859-1\r\n\
+            Content-Transfer-Encoding: quoted-printable\r\n\
+            \r\n\
+            scene caf=E9 =\r\n\
+            now\r\n\
+            --b1--\r\n";
+        let email = parse(raw.as_bytes());
+        assert_eq!(email.from, "Ana María <Ana@Example.org>");
+        assert_eq!(email.address, "ana@example.org");
+        assert_eq!(email.subject, "Licht an please");
+        assert_eq!(email.message_id, "<1@example.org>");
+        assert_eq!(email.body, "scene café now");
+
+        let html_only = "Content-Type: text/html\n\n<b>Hi</b> &amp; bye";
+        assert_eq!(parse(html_only.as_bytes()).body, "Hi & bye");
+    }
+}
@@ -0,0 +1,385 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use magnolia_core::{ControlSignal, DataType, ModuleSchema, Port, PortDirection, Signal, Source};
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+use crate::imap::{ImapClient, Security};
+use crate::mime::{self, Email};
+
+/// How often poll() wakes to check for settings changes.
+const TICK: Duration = Duration::from_millis(500);
+/// Servers throttle clients that log in too often.
+pub const MIN_POLL_INTERVAL_SECS: u64 = 15;
+/// Messages read per poll; the rest wait for the next one.
+const MAX_PER_POLL: usize = 20;
+/// Enough of a message for its text, without its attachments.
+const FETCH_BYTES: usize = 64 * 1024;
+
+pub const MESSAGE_SOURCE: &str = "inbox";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InboxConfig {
+    /// Empty leaves the inbox idle
+    pub host: String,
+    pub port: u16,
+    pub security: Security,
+    pub username: String,
+    pub password: String,
+    pub mailbox: String,
+    pub poll_interval_secs: u64,
+    /// Comma-separated addresses or `@domain`s; empty accepts anyone.
+    /// Other mail is left unread.
+    pub allowed_senders: String,
+    pub mark_read: bool,
+    /// Text signals carry the body after the subject, not just the subject
+    pub include_body: bool,
+    pub max_body_chars: usize,
+}
+
+impl Default for InboxConfig {
+    fn default() -> Self {
+        Self {
+            host: String::new(),
+            port: 993,
+            security: Security::Tls,
+            username: String::new(),
+            password: String::new(),
+            mailbox: "INBOX".to_string(),
+            poll_interval_secs: 60,
+            allowed_senders: String::new(),
+            mark_read: true,
+            include_body: true,
+            max_body_chars: 2000,
+        }
+    }
+}
+
+impl InboxConfig {
+    pub fn poll_interval_secs(&self) -> u64 {
+        self.poll_interval_secs.max(MIN_POLL_INTERVAL_SECS)
+    }
+
+    pub fn schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "host": { "type": "string", "title": "IMAP Server", "default": "" },
+                "port": {
+                    "type": "integer",
+                    "title": "Port",
+                    "minimum": 1,
+                    "maximum": 65535,
+                    "default": 993
+                },
+                "security": {
+                    "type": "string",
+                    "title": "Security",
+                    "enum": ["tls", "starttls", "none"],
+                    "default": "tls"
+                },
+                "username": { "type": "string", "title": "Username", "default": "" },
+                "password": { "type": "string", "title": "Password", "default": "" },
+                "mailbox": { "type": "string", "title": "Mailbox", "default": "INBOX" },
+                "poll_interval_secs": {
+                    "type": "integer",
+                    "title": "Poll Interval (s)",
+                    "minimum": MIN_POLL_INTERVAL_SECS,
+                    "maximum": 86400,
+                    "default": 60
+                },
+                "allowed_senders": { "type": "string", "title": "Allowed Senders", "default": "" },
+                "mark_read": { "type": "boolean", "title": "Mark As Read", "default": true },
+                "include_body": { "type": "boolean", "title": "Include Body", "default": true },
+                "max_body_chars": {
+                    "type": "integer",
+                    "title": "Max Body Length",
+                    "minimum": 0,
+                    "maximum": 100000,
+                    "default": 2000
+                }
+            }
+        })
+    }
+
+    /// Whether mail from `address` is read
+    pub fn allows(&self, address: &str) -> bool {
+        let mut allowed = self
+            .allowed_senders
+            .split(',')
+            .map(|s| s.trim().to_ascii_lowercase())
+            .filter(|s| !s.is_empty())
+            .peekable();
+        allowed.peek().is_none()
+            || allowed.any(|allowed| match allowed.strip_prefix('@') {
+                Some(domain) => address.rsplit_once('@').is_some_and(|(_, d)| d == domain),
+                None => address == allowed,
+            })
+    }
+}
+
+/// Where the last poll got to. UIDs only mean something within one
+/// UIDVALIDITY; the server changing it starts over.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Cursor {
+    pub uid_validity: u32,
+    pub last_uid: u32,
+}
+
+/// Log in, read the new mail and log out. Blocking; run it off the async
+/// executor.
+pub fn check(config: &InboxConfig, cursor: Cursor) -> anyhow::Result<(Cursor, Vec<Email>)> {
+    let mut client = ImapClient::connect(config.host.trim(), config.port, config.security)?;
+    client.login(&config.username, &config.password)?;
+    let uid_validity = client.select(&config.mailbox)?;
+    let mut cursor = if cursor.uid_validity == uid_validity {
+        cursor
+    } else {
+        Cursor {
+            uid_validity,
+            last_uid: 0,
+        }
+    };
+
+    let mut emails = Vec::new();
+    for uid in client
+        .unseen_after(cursor.last_uid)?
+        .into_iter()
+        .take(MAX_PER_POLL)
+    {
+        let email = mime::parse(&client.fetch(uid, FETCH_BYTES)?);
+        cursor.last_uid = uid;
+        if !config.allows(&email.address) {
+            log::debug!("Inbox: leaving mail from {} unread", email.address);
+            continue;
+        }
+        if config.mark_read {
+            client.mark_seen(uid)?;
+        }
+        emails.push(email);
+    }
+    client.logout();
+    Ok((cursor, emails))
+}
+
+/// The Text signal on `text_out` and the full message as JSON on
+/// `messages_out`
+pub fn email_signals(email: &Email, config: &InboxConfig) -> Vec<Signal> {
+    let body: String = email.body.chars().take(config.max_body_chars).collect();
+    let text = if config.include_body && !body.is_empty() {
+        format!("{}\n{}", email.subject, body)
+    } else {
+        email.subject.clone()
+    };
+    let mut signals = vec![Signal::Text(text)];
+    let message = Email {
+        body,
+        ..email.clone()
+    };
+    match serde_json::to_string(&message) {
+        Ok(content) => signals.push(Signal::Computed {
+            source: MESSAGE_SOURCE.to_string(),
+            content,
+        }),
+        Err(e) => log::warn!("Inbox: failed to encode message: {}", e),
+    }
+    signals
+}
+
+/// Polls an IMAP mailbox for unseen mail.
+pub struct InboxSource {
+    id: String,
+    enabled: bool,
+    config: InboxConfig,
+    cursor: Cursor,
+    pending: VecDeque<Signal>,
+    next_check: Option<Instant>,
+}
+
+impl InboxSource {
+    pub fn new(id: &str, config: InboxConfig) -> Self {
+        Self {
+            id: id.to_string(),
+            enabled: true,
+            config,
+            cursor: Cursor::default(),
+            pending: VecDeque::new(),
+            next_check: None,
+        }
+    }
+
+    pub fn config(&self) -> &InboxConfig {
+        &self.config
+    }
+
+    pub fn set_config(&mut self, config: InboxConfig) {
+        let mailbox = (&config.host, &config.username, &config.mailbox);
+        if mailbox
+            != (
+                &self.config.host,
+                &self.config.username,
+                &self.config.mailbox,
+            )
+        {
+            self.cursor = Cursor::default();
+        }
+        self.config = config;
+        self.next_check = None;
+    }
+
+    async fn refresh(&mut self) {
+        let config = self.config.clone();
+        let cursor = self.cursor;
+        let interval = Duration::from_secs(config.poll_interval_secs());
+        let result = tokio::task::spawn_blocking(move || check(&config, cursor)).await;
+
+        self.next_check = Some(Instant::now() + interval);
+        match result {
+            Ok(Ok((cursor, emails))) => {
+                self.cursor = cursor;
+                for email in &emails {
+                    log::info!("Inbox: mail from {}: {}", email.address, email.subject);
+                    self.pending.extend(email_signals(email, &self.config));
+                }
+            }
+            Ok(Err(e)) => log::warn!("Inbox: {}: {}", self.config.host, e),
+            Err(e) => log::error!("Inbox: check task failed: {}", e),
+        }
+    }
+}
+
+#[async_trait]
+impl Source for InboxSource {
+    fn name(&self) -> &str {
+        "Inbox"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "Inbox".to_string(),
+            description: "New mail from an IMAP mailbox".to_string(),
+            ports: vec![
+                Port {
+                    id: "text_out".to_string(),
+                    label: "Text".to_string(),
+                    data_type: DataType::Text,
+                    direction: PortDirection::Output,
+                },
+                Port {
+                    id: "messages_out".to_string(),
+                    label: "Messages".to_string(),
+                    data_type: DataType::Text,
+                    direction: PortDirection::Output,
+                },
+                Port {
+                    id: "settings_in".to_string(),
+                    label: "Settings".to_string(),
+                    data_type: DataType::Control,
+                    direction: PortDirection::Input,
+                },
+            ],
+            settings_schema: Some(InboxConfig::schema()),
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    async fn poll(&mut self) -> Option<Signal> {
+        loop {
+            if let Some(signal) = self.pending.pop_front() {
+                return Some(signal);
+            }
+
+            let due = self.next_check.map_or(true, |next| Instant::now() >= next);
+            if self.enabled && due && !self.config.host.trim().is_empty() {
+                self.refresh().await;
+                continue;
+            }
+
+            tokio::time::sleep(TICK).await;
+        }
+    }
+
+    fn handle_signal(&mut self, signal: Signal) {
+        if let Signal::Control(ControlSignal::Settings(settings)) = signal {
+            match serde_json::from_value::<InboxConfig>(settings) {
+                Ok(config) => self.set_config(config),
+                Err(e) => log::warn!("Inbox: invalid settings, keeping current: {}", e),
+            }
+        }
+    }
+
+    fn output_port(&self, signal: &Signal) -> Option<&str> {
+        match signal {
+            Signal::Computed { .. } => Some("messages_out"),
+            _ => Some("text_out"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mail_becomes_text_and_metadata() {
+        let mut source = InboxSource::new("inbox", InboxConfig::default());
+        source.handle_signal(Signal::Control(ControlSignal::Settings(
+            serde_json::json!({
+                "host": "imap.example.org",
+                "allowed_senders": "ana@example.org, @studio.example",
+                "max_body_chars": 5
+            }),
+        )));
+        let config = source.config().clone();
+        assert_eq!(config.port, 993);
+        assert!(config.allows("ana@example.org"));
+        assert!(config.allows("ben@studio.example"));
+        assert!(!config.allows("eve@example.net"));
+        assert!(!config.allows("eve@notstudio.example"));
+
+        let email = Email {
+            from: "Ana <ana@example.org>".into(),
+            address: "ana@example.org".into(),
+            subject: "scene Intro".into(),
+            body: "please, now".into(),
+            ..Email::default()
+        };
+        let signals = email_signals(&email, &config);
+        assert!(matches!(&signals[0], Signal::Text(text) if text == "scene Intro\npleas"));
+        assert_eq!(source.output_port(&signals[0]), Some("text_out"));
+        match &signals[1] {
+            Signal::Computed {
+                source: name,
+                content,
+            } => {
+                assert_eq!(name, MESSAGE_SOURCE);
+                let decoded: Email = serde_json::from_str(content).unwrap();
+                assert_eq!(decoded.from, email.from);
+                assert_eq!(decoded.body, "pleas");
+            }
+            other => panic!("expected Computed, got {:?}", other),
+        }
+        assert_eq!(source.output_port(&signals[1]), Some("messages_out"));
+
+        let subject_only = InboxConfig {
+            include_body: false,
+            ..config
+        };
+        assert!(matches!(
+            &email_signals(&email, &subject_only)[0],
+            Signal::Text(text) if text == "scene Intro"
+        ));
+    }
+}
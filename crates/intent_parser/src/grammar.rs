@@ -0,0 +1,157 @@
+//! Grammar configuration and its pattern syntax.
+//!
+//! A pattern is a run of words matched in order against the utterance:
+//!
+//! * `word` must appear as written (after synonyms are applied)
+//! * `[the]`, `[for me]` may be left out
+//! * `{slot}` captures a parameter: `{number}` a spoken or written number,
+//!   `{text}` one or more words, or a slot declared under `[slots]` one of
+//!   its values
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Grammar shipped with the crate, used when no file is configured
+pub const BUILTIN_GRAMMAR: &str = include_str!("../grammar/default.toml");
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CommandRule {
+    pub action: String,
+    /// Tried in order; the first that matches the whole utterance wins
+    pub patterns: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Grammar {
+    /// Words a command must open with (`hey magnolia`); empty accepts any
+    /// utterance
+    pub wake_word: String,
+    /// Canonical word → spoken alternatives, substituted before matching
+    pub synonyms: BTreeMap<String, Vec<String>>,
+    /// Slot name → accepted values; `number` and `text` are built in
+    pub slots: BTreeMap<String, Vec<String>>,
+    /// Checked in order
+    pub commands: Vec<CommandRule>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum GrammarError {
+    #[error("failed to read grammar {path}: {source}")]
+    Io {
+        path: String,
+        source: std::io::Error,
+    },
+    #[error("invalid grammar: {0}")]
+    Toml(#[from] toml::de::Error),
+    #[error("command {action:?}: pattern {pattern:?}: {reason}")]
+    Pattern {
+        action: String,
+        pattern: String,
+        reason: String,
+    },
+    #[error("command {action:?}: unknown slot {{{slot}}}")]
+    UnknownSlot { action: String, slot: String },
+}
+
+impl Grammar {
+    pub fn builtin() -> Self {
+        toml::from_str(BUILTIN_GRAMMAR).expect("builtin grammar is valid TOML")
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self, GrammarError> {
+        Ok(toml::from_str(text)?)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, GrammarError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|source| GrammarError::Io {
+            path: path.display().to_string(),
+            source,
+        })?;
+        Self::from_toml(&text)
+    }
+}
+
+/// One piece of a pattern, before slots are resolved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Element {
+    Word(String),
+    Optional(Vec<String>),
+    Slot(String),
+}
+
+/// Split a pattern into elements; words are normalized the same way as
+/// utterances so `Start,` and `start` are one word
+pub(crate) fn parse_pattern(pattern: &str) -> Result<Vec<Element>, String> {
+    let mut elements = Vec::new();
+    let mut rest = pattern.trim();
+    while !rest.is_empty() {
+        if let Some(inner) = rest.strip_prefix('[') {
+            let end = inner.find(']').ok_or("unclosed `[`")?;
+            if inner[..end].contains(['[', '{']) {
+                return Err("optional groups may only hold words".to_string());
+            }
+            let words = crate::parser::normalize(&inner[..end]);
+            if words.is_empty() {
+                return Err("empty `[]`".to_string());
+            }
+            elements.push(Element::Optional(words));
+            rest = inner[end + 1..].trim_start();
+        } else if let Some(inner) = rest.strip_prefix('{') {
+            let end = inner.find('}').ok_or("unclosed `{`")?;
+            let name = inner[..end].trim();
+            if name.is_empty() || name.contains(char::is_whitespace) {
+                return Err(format!("bad slot name {:?}", name));
+            }
+            elements.push(Element::Slot(name.to_lowercase()));
+            rest = inner[end + 1..].trim_start();
+        } else {
+            let end = rest.find([' ', '\t', '[', '{']).unwrap_or(rest.len());
+            let word = &rest[..end];
+            if word.contains([']', '}']) {
+                return Err(format!("unexpected bracket in {:?}", word));
+            }
+            elements.extend(
+                crate::parser::normalize(word)
+                    .into_iter()
+                    .map(Element::Word),
+            );
+            rest = rest[end..].trim_start();
+        }
+    }
+    if elements.is_empty() {
+        return Err("empty pattern".to_string());
+    }
+    Ok(elements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_patterns_and_the_builtin_grammar() {
+        use Element::*;
+        assert_eq!(
+            parse_pattern("Set [the] volume [up to] {number}").unwrap(),
+            vec![
+                Word("set".into()),
+                Optional(vec!["the".into()]),
+                Word("volume".into()),
+                Optional(vec!["up".into(), "to".into()]),
+                Slot("number".into()),
+            ]
+        );
+        assert!(parse_pattern("[the").is_err());
+        assert!(parse_pattern("play {}").is_err());
+        assert!(parse_pattern("[{number}]").is_err());
+        assert!(parse_pattern("  ").is_err());
+
+        let grammar = Grammar::builtin();
+        assert!(!grammar.commands.is_empty());
+        assert!(Grammar::from_toml("commands = 3").is_err());
+    }
+}
@@ -0,0 +1,24 @@
+//! Intent parser: turns final speech-to-text into `Signal::Intent`.
+//!
+//! Commands come from a TOML [`Grammar`] of patterns with slots, synonyms
+//! and spoken numbers, so `"kick off the recording"` arrives downstream as
+//! `record [start]` and sinks no longer need their own string matching. The
+//! crate's `grammar/default.toml` is used until a grammar file is set.
+//!
+//! [`TriggerWordsProcessor`] is the lighter option: no grammar, just an
+//! intent per keyword or phrase heard.
+
+mod grammar;
+mod number;
+mod parser;
+mod processor;
+mod trigger;
+
+pub use grammar::{CommandRule, Grammar, GrammarError, BUILTIN_GRAMMAR};
+pub use number::{format_number, parse_number};
+pub use parser::{normalize, IntentParser, ParsedIntent};
+pub use processor::{IntentParserConfig, IntentParserProcessor, UNRECOGNIZED_ACTION};
+pub use trigger::{
+    edit_distance, find_triggers, TriggerMatch, TriggerWord, TriggerWordsConfig,
+    TriggerWordsProcessor,
+};
@@ -0,0 +1,153 @@
+//! Spoken numbers: digits as transcribed (`42`, `3.5`) or English number
+//! words (`twenty five`, `one hundred and six`, `minus three`).
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Start,
+    Unit,
+    Teen,
+    Ten,
+    Hundred,
+    Scale,
+}
+
+fn word_value(word: &str) -> Option<(u64, Kind)> {
+    const UNITS: [&str; 10] = [
+        "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    ];
+    const TEENS: [&str; 10] = [
+        "ten",
+        "eleven",
+        "twelve",
+        "thirteen",
+        "fourteen",
+        "fifteen",
+        "sixteen",
+        "seventeen",
+        "eighteen",
+        "nineteen",
+    ];
+    const TENS: [&str; 8] = [
+        "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+    ];
+    if let Some(i) = UNITS.iter().position(|w| *w == word) {
+        return Some((i as u64, Kind::Unit));
+    }
+    if let Some(i) = TEENS.iter().position(|w| *w == word) {
+        return Some((10 + i as u64, Kind::Teen));
+    }
+    if let Some(i) = TENS.iter().position(|w| *w == word) {
+        return Some((20 + 10 * i as u64, Kind::Ten));
+    }
+    None
+}
+
+/// Value of `words` if they spell exactly one number, all words consumed.
+/// Runs that read as several numbers (`five five`) are rejected rather than
+/// summed.
+pub fn parse_number(words: &[&str]) -> Option<f64> {
+    if let [word] = words {
+        if let Ok(value) = word.parse::<f64>() {
+            return value.is_finite().then_some(value);
+        }
+    }
+    let (negative, words) = match words.split_first() {
+        Some((&("minus" | "negative"), rest)) => (true, rest),
+        _ => (false, words),
+    };
+    if words.is_empty() {
+        return None;
+    }
+
+    let (mut total, mut current) = (0u64, 0u64);
+    let mut last = Kind::Start;
+    for (index, &word) in words.iter().enumerate() {
+        if word == "and" {
+            // Only between parts: "one hundred and six"
+            if !matches!(last, Kind::Hundred | Kind::Scale) || index + 1 == words.len() {
+                return None;
+            }
+            continue;
+        }
+        let kind = match word {
+            "hundred" => {
+                if !matches!(last, Kind::Unit | Kind::Teen) || current % 100 == 0 {
+                    return None;
+                }
+                current *= 100;
+                Kind::Hundred
+            }
+            "thousand" | "million" => {
+                if last == Kind::Start || last == Kind::Scale || current == 0 {
+                    return None;
+                }
+                let scale = if word == "thousand" { 1_000 } else { 1_000_000 };
+                if total != 0 && total < current * scale {
+                    return None;
+                }
+                total += current * scale;
+                current = 0;
+                Kind::Scale
+            }
+            _ => {
+                let (value, kind) = word_value(word)?;
+                let allowed = match kind {
+                    Kind::Unit => {
+                        matches!(last, Kind::Start | Kind::Ten | Kind::Hundred | Kind::Scale)
+                    }
+                    _ => matches!(last, Kind::Start | Kind::Hundred | Kind::Scale),
+                };
+                // "zero" only stands alone
+                if !allowed || (value == 0 && words.len() > 1) {
+                    return None;
+                }
+                current += value;
+                kind
+            }
+        };
+        last = kind;
+    }
+    let value = (total + current) as f64;
+    Some(if negative { -value } else { value })
+}
+
+/// Slot text for a parsed number: integral values without a decimal point
+pub fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Option<f64> {
+        parse_number(&text.split_whitespace().collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn parses_digits_and_number_words() {
+        assert_eq!(parse("42"), Some(42.0));
+        assert_eq!(parse("-3.5"), Some(-3.5));
+        assert_eq!(parse("zero"), Some(0.0));
+        assert_eq!(parse("twenty five"), Some(25.0));
+        assert_eq!(parse("one hundred and six"), Some(106.0));
+        assert_eq!(parse("twelve hundred"), Some(1200.0));
+        assert_eq!(parse("two thousand three hundred forty"), Some(2340.0));
+        assert_eq!(parse("minus seven"), Some(-7.0));
+
+        assert_eq!(parse("five five"), None);
+        assert_eq!(parse("twenty twenty"), None);
+        assert_eq!(parse("fifteen three"), None);
+        assert_eq!(parse("hundred"), None);
+        assert_eq!(parse("one and"), None);
+        assert_eq!(parse("seven seconds"), None);
+        assert_eq!(parse(""), None);
+
+        assert_eq!(format_number(25.0), "25");
+        assert_eq!(format_number(-3.5), "-3.5");
+    }
+}
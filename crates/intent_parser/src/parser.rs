@@ -0,0 +1,353 @@
+use crate::grammar::{parse_pattern, Element, Grammar, GrammarError};
+use crate::number::{format_number, parse_number};
+use std::ops::Range;
+
+/// Words of `text`, lowercased with surrounding punctuation dropped.
+/// Hyphenated words are split (`twenty-five` → `twenty five`) unless they
+/// read as a number (`-3`).
+pub fn normalize(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    for raw in text.split_whitespace() {
+        let word = raw
+            .trim_start_matches(|c: char| !c.is_alphanumeric() && c != '-')
+            .trim_end_matches(|c: char| !c.is_alphanumeric())
+            .to_lowercase();
+        if word.parse::<f64>().is_ok() {
+            words.push(word);
+        } else {
+            words.extend(
+                word.split('-')
+                    .filter(|part| !part.is_empty())
+                    .map(str::to_string),
+            );
+        }
+    }
+    words
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedIntent {
+    pub action: String,
+    /// Slot values in pattern order
+    pub parameters: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+enum Slot {
+    Number,
+    Text,
+    /// Normalized words of each accepted value, with the value as reported
+    Values(Vec<(Vec<String>, String)>),
+}
+
+#[derive(Debug, Clone)]
+enum Step {
+    Words(Vec<String>),
+    Optional(Vec<String>),
+    Slot(Slot),
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    action: String,
+    steps: Vec<Step>,
+}
+
+/// A [`Grammar`] compiled for matching
+#[derive(Debug, Clone)]
+pub struct IntentParser {
+    wake_word: Vec<String>,
+    /// Alternative → canonical words, longest alternative first
+    synonyms: Vec<(Vec<String>, Vec<String>)>,
+    rules: Vec<Rule>,
+}
+
+impl IntentParser {
+    pub fn new(grammar: &Grammar) -> Result<Self, GrammarError> {
+        let mut synonyms: Vec<(Vec<String>, Vec<String>)> = grammar
+            .synonyms
+            .iter()
+            .flat_map(|(canonical, alternatives)| {
+                let canonical = normalize(canonical);
+                alternatives
+                    .iter()
+                    .map(move |alt| (normalize(alt), canonical.clone()))
+            })
+            .filter(|(alt, _)| !alt.is_empty())
+            .collect();
+        synonyms.sort_by_key(|(alternative, _)| std::cmp::Reverse(alternative.len()));
+
+        let mut parser = Self {
+            wake_word: normalize(&grammar.wake_word),
+            synonyms,
+            rules: Vec::new(),
+        };
+        for command in &grammar.commands {
+            for pattern in &command.patterns {
+                let elements = parse_pattern(pattern).map_err(|reason| GrammarError::Pattern {
+                    action: command.action.clone(),
+                    pattern: pattern.clone(),
+                    reason,
+                })?;
+                let steps = parser.steps(grammar, &command.action, elements)?;
+                parser.rules.push(Rule {
+                    action: command.action.clone(),
+                    steps,
+                });
+            }
+        }
+        Ok(parser)
+    }
+
+    /// Steps for a parsed pattern; literal words go through the synonym
+    /// table too, so patterns may use any spelling
+    fn steps(
+        &self,
+        grammar: &Grammar,
+        action: &str,
+        elements: Vec<Element>,
+    ) -> Result<Vec<Step>, GrammarError> {
+        let mut steps = Vec::new();
+        let mut run = Vec::new();
+        for element in elements {
+            if let Element::Word(word) = element {
+                run.push(word);
+                continue;
+            }
+            if !run.is_empty() {
+                steps.push(Step::Words(self.canonical(&std::mem::take(&mut run))));
+            }
+            steps.push(match element {
+                Element::Optional(words) => Step::Optional(self.canonical(&words)),
+                Element::Slot(name) => Step::Slot(self.slot(grammar, action, name)?),
+                Element::Word(_) => unreachable!(),
+            });
+        }
+        if !run.is_empty() {
+            steps.push(Step::Words(self.canonical(&run)));
+        }
+        Ok(steps)
+    }
+
+    fn slot(&self, grammar: &Grammar, action: &str, name: String) -> Result<Slot, GrammarError> {
+        if let Some(values) = grammar.slots.get(&name) {
+            return Ok(Slot::Values(
+                values
+                    .iter()
+                    .map(|value| (self.canonical(&normalize(value)), value.trim().to_string()))
+                    .filter(|(words, _)| !words.is_empty())
+                    .collect(),
+            ));
+        }
+        match name.as_str() {
+            "number" => Ok(Slot::Number),
+            "text" => Ok(Slot::Text),
+            _ => Err(GrammarError::UnknownSlot {
+                action: action.to_string(),
+                slot: name,
+            }),
+        }
+    }
+
+    /// `words` with every synonym replaced by its canonical form
+    fn canonical(&self, words: &[String]) -> Vec<String> {
+        self.substitute(words).into_iter().map(|t| t.word).collect()
+    }
+
+    fn substitute(&self, words: &[String]) -> Vec<Token> {
+        let mut out = Vec::with_capacity(words.len());
+        let mut index = 0;
+        'words: while index < words.len() {
+            for (alternative, canonical) in &self.synonyms {
+                if words[index..].starts_with(alternative) {
+                    let span = index..index + alternative.len();
+                    out.extend(canonical.iter().map(|word| Token {
+                        word: word.clone(),
+                        span: span.clone(),
+                    }));
+                    index = span.end;
+                    continue 'words;
+                }
+            }
+            out.push(Token {
+                word: words[index].clone(),
+                span: index..index + 1,
+            });
+            index += 1;
+        }
+        out
+    }
+
+    /// The first command whose pattern covers the whole utterance
+    pub fn parse(&self, text: &str) -> Option<ParsedIntent> {
+        let words = normalize(text);
+        let spoken = words.strip_prefix(self.wake_word.as_slice())?;
+        let tokens = self.substitute(spoken);
+        self.rules.iter().find_map(|rule| {
+            let mut parameters = Vec::new();
+            matches(&rule.steps, &tokens, spoken, &mut parameters).then(|| ParsedIntent {
+                action: rule.action.clone(),
+                parameters,
+            })
+        })
+    }
+}
+
+/// Utterance word after synonym substitution, with the range of spoken
+/// words it replaced
+#[derive(Debug, Clone)]
+struct Token {
+    word: String,
+    span: Range<usize>,
+}
+
+fn starts_with(tokens: &[Token], prefix: &[String]) -> bool {
+    tokens.len() >= prefix.len() && tokens.iter().zip(prefix).all(|(t, p)| t.word == *p)
+}
+
+/// Backtracking match of `steps` against all of `tokens`, pushing slot values
+/// onto `out`; `out` is left as it was when there is no match. `{text}` is
+/// captured from the `spoken` words, so synonyms don't rewrite free text.
+fn matches(steps: &[Step], tokens: &[Token], spoken: &[String], out: &mut Vec<String>) -> bool {
+    let Some((step, rest)) = steps.split_first() else {
+        return tokens.is_empty();
+    };
+    match step {
+        Step::Words(run) => {
+            starts_with(tokens, run) && matches(rest, &tokens[run.len()..], spoken, out)
+        }
+        Step::Optional(group) => {
+            (starts_with(tokens, group) && matches(rest, &tokens[group.len()..], spoken, out))
+                || matches(rest, tokens, spoken, out)
+        }
+        Step::Slot(slot) => {
+            let mut try_value = |len: usize, value: String| {
+                out.push(value);
+                if matches(rest, &tokens[len..], spoken, out) {
+                    return true;
+                }
+                out.pop();
+                false
+            };
+            match slot {
+                Slot::Number => (1..=tokens.len()).rev().any(|len| {
+                    let words: Vec<&str> = tokens[..len].iter().map(|t| t.word.as_str()).collect();
+                    parse_number(&words).is_some_and(|n| try_value(len, format_number(n)))
+                }),
+                // Longest first, so a trailing `{text}` takes everything left
+                Slot::Text => (1..=tokens.len()).rev().any(|len| {
+                    let span = tokens[0].span.start..tokens[len - 1].span.end;
+                    try_value(len, spoken[span].join(" "))
+                }),
+                Slot::Values(values) => values.iter().any(|(value_words, value)| {
+                    starts_with(tokens, value_words) && try_value(value_words.len(), value.clone())
+                }),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parser(toml: &str) -> IntentParser {
+        IntentParser::new(&Grammar::from_toml(toml).unwrap()).unwrap()
+    }
+
+    fn parse(parser: &IntentParser, text: &str) -> Option<(String, Vec<String>)> {
+        parser.parse(text).map(|p| (p.action, p.parameters))
+    }
+
+    fn intent(action: &str, parameters: &[&str]) -> Option<(String, Vec<String>)> {
+        Some((
+            action.to_string(),
+            parameters.iter().map(|p| p.to_string()).collect(),
+        ))
+    }
+
+    #[test]
+    fn matches_slots_synonyms_and_numbers() {
+        let parser = parser(
+            r#"
+            [synonyms]
+            start = ["begin", "kick off"]
+            volume = ["level"]
+            for = ["four"]
+
+            [slots]
+            transport = ["start", "stop"]
+            color = ["deep blue", "red"]
+
+            [[commands]]
+            action = "record"
+            patterns = ["{transport} [the] recording"]
+
+            [[commands]]
+            action = "volume"
+            patterns = ["set [the] volume [to] {number} [percent]"]
+
+            [[commands]]
+            action = "color"
+            patterns = ["make it {color}"]
+
+            [[commands]]
+            action = "sigil"
+            patterns = ["[draw] [a] sigil for {text}"]
+            "#,
+        );
+        assert_eq!(
+            parse(&parser, "Kick off the recording."),
+            intent("record", &["start"])
+        );
+        assert_eq!(
+            parse(&parser, "set level to twenty-five percent"),
+            intent("volume", &["25"])
+        );
+        assert_eq!(
+            parse(&parser, "Set the volume 7.5"),
+            intent("volume", &["7.5"])
+        );
+        assert_eq!(
+            parse(&parser, "make it deep blue"),
+            intent("color", &["deep blue"])
+        );
+        assert_eq!(
+            parse(&parser, "draw a sigil four love and begin"),
+            intent("sigil", &["love and begin"])
+        );
+        assert_eq!(parse(&parser, "set the volume to loud"), None);
+        assert_eq!(parse(&parser, "pause the recording"), None);
+    }
+
+    #[test]
+    fn wake_word_and_grammar_errors() {
+        let parser = parser(
+            r#"
+            wake_word = "Hey Magnolia"
+            [[commands]]
+            action = "next"
+            patterns = ["next [track]"]
+            "#,
+        );
+        assert_eq!(
+            parse(&parser, "hey magnolia, next track"),
+            intent("next", &[])
+        );
+        assert_eq!(parse(&parser, "next track"), None);
+
+        let unknown = Grammar::from_toml(
+            r#"
+            [[commands]]
+            action = "play"
+            patterns = ["play {song}"]
+            "#,
+        )
+        .unwrap();
+        assert!(matches!(
+            IntentParser::new(&unknown),
+            Err(GrammarError::UnknownSlot { slot, .. }) if slot == "song"
+        ));
+        assert!(IntentParser::new(&Grammar::builtin()).is_ok());
+    }
+}
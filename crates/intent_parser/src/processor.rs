@@ -0,0 +1,244 @@
+use async_trait::async_trait;
+use magnolia_core::{
+    ControlSignal, DataType, ModuleSchema, Port, PortDirection, Processor, Signal,
+};
+use serde::{Deserialize, Serialize};
+use speech_to_text::SttEvent;
+
+use crate::grammar::{Grammar, GrammarError};
+use crate::parser::IntentParser;
+
+/// Action of the intent emitted for unmatched text when `emit_unmatched` is
+/// set; its one parameter is the text
+pub const UNRECOGNIZED_ACTION: &str = "unrecognized";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IntentParserConfig {
+    /// TOML grammar file; empty uses the built-in grammar
+    pub grammar_path: String,
+    pub emit_unmatched: bool,
+}
+
+impl IntentParserConfig {
+    pub fn schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "grammar_path": {
+                    "type": "string",
+                    "title": "Grammar File",
+                    "default": ""
+                },
+                "emit_unmatched": {
+                    "type": "boolean",
+                    "title": "Emit Unrecognized",
+                    "default": false
+                }
+            }
+        })
+    }
+
+    pub fn load_grammar(&self) -> Result<Grammar, GrammarError> {
+        if self.grammar_path.trim().is_empty() {
+            Ok(Grammar::builtin())
+        } else {
+            Grammar::load(self.grammar_path.trim())
+        }
+    }
+}
+
+/// Text a command is parsed from: final STT segments or plain text.
+/// Partials are skipped so a command fires once, when the segment commits.
+pub(crate) fn utterance(signal: &Signal) -> Option<String> {
+    match signal {
+        Signal::Computed { content, .. } => match serde_json::from_str(content).ok()? {
+            SttEvent::Final { text, .. } => Some(text),
+            _ => None,
+        },
+        Signal::Text(text) => Some(text.clone()),
+        _ => None,
+    }
+}
+
+pub struct IntentParserProcessor {
+    id: String,
+    enabled: bool,
+    config: IntentParserConfig,
+    parser: IntentParser,
+}
+
+impl IntentParserProcessor {
+    /// Falls back to the built-in grammar if `config`'s doesn't load
+    pub fn new(id: &str, config: IntentParserConfig) -> Self {
+        let mut processor = Self {
+            id: id.to_string(),
+            enabled: true,
+            config: IntentParserConfig::default(),
+            parser: IntentParser::new(&Grammar::builtin()).expect("builtin grammar compiles"),
+        };
+        if let Err(e) = processor.set_config(config.clone()) {
+            log::warn!("Intent parser {}: {}; using the built-in grammar", id, e);
+            processor.config = config;
+        }
+        processor
+    }
+
+    pub fn config(&self) -> &IntentParserConfig {
+        &self.config
+    }
+
+    /// Loads and compiles the configured grammar; on error nothing changes
+    pub fn set_config(&mut self, config: IntentParserConfig) -> Result<(), GrammarError> {
+        self.parser = IntentParser::new(&config.load_grammar()?)?;
+        self.config = config;
+        Ok(())
+    }
+
+    pub fn parse(&self, text: &str) -> Option<Signal> {
+        match self.parser.parse(text) {
+            Some(intent) => Some(Signal::Intent {
+                action: intent.action,
+                parameters: intent.parameters,
+            }),
+            None if self.config.emit_unmatched && !text.trim().is_empty() => Some(Signal::Intent {
+                action: UNRECOGNIZED_ACTION.to_string(),
+                parameters: vec![text.trim().to_string()],
+            }),
+            None => None,
+        }
+    }
+}
+
+#[async_trait]
+impl Processor for IntentParserProcessor {
+    fn name(&self) -> &str {
+        "Intent Parser"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "Intent Parser".to_string(),
+            description: "Parses spoken commands into intents with a configurable grammar"
+                .to_string(),
+            ports: vec![
+                Port {
+                    id: "text_in".to_string(),
+                    label: "Text In".to_string(),
+                    data_type: DataType::Text,
+                    direction: PortDirection::Input,
+                },
+                Port {
+                    id: "intent_out".to_string(),
+                    label: "Intents Out".to_string(),
+                    data_type: DataType::Control,
+                    direction: PortDirection::Output,
+                },
+            ],
+            settings_schema: Some(IntentParserConfig::schema()),
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    async fn process(&mut self, signal: Signal) -> anyhow::Result<Option<Signal>> {
+        if let Signal::Control(ControlSignal::Settings(settings)) = &signal {
+            match serde_json::from_value::<IntentParserConfig>(settings.clone()) {
+                Ok(config) => {
+                    if let Err(e) = self.set_config(config) {
+                        log::warn!("Intent parser: {}, keeping current grammar", e);
+                    }
+                }
+                Err(e) => log::warn!("Intent parser: invalid settings, keeping current: {}", e),
+            }
+            return Ok(None);
+        }
+        Ok(utterance(&signal).and_then(|text| self.parse(&text)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stt(event: SttEvent) -> Signal {
+        Signal::Computed {
+            source: "speech_to_text".to_string(),
+            content: serde_json::to_string(&event).unwrap(),
+        }
+    }
+
+    #[tokio::test]
+    async fn turns_final_text_into_intents() {
+        let mut parser = IntentParserProcessor::new("intent_parser", Default::default());
+
+        let partial = stt(SttEvent::Partial {
+            session_id: "s".into(),
+            segment_id: 1,
+            text: "begin the recording".into(),
+            audio_end_ms: 500,
+            sequence: 1,
+            language: None,
+            stable_len: None,
+        });
+        assert!(parser.process(partial).await.unwrap().is_none());
+
+        let final_ = stt(SttEvent::Final {
+            session_id: "s".into(),
+            segment_id: 1,
+            text: "Begin the recording.".into(),
+            start_ms: 0,
+            end_ms: 900,
+            sequence: 2,
+            language: None,
+        });
+        let out = parser.process(final_).await.unwrap();
+        assert!(matches!(
+            &out,
+            Some(Signal::Intent { action, parameters }) if action == "record" && parameters == &["start"]
+        ));
+
+        let out = parser
+            .process(Signal::Text("set the volume to forty two".into()))
+            .await
+            .unwrap();
+        assert!(matches!(
+            &out,
+            Some(Signal::Intent { action, parameters }) if action == "volume" && parameters == &["42"]
+        ));
+        assert!(parser
+            .process(Signal::Text("what a lovely day".into()))
+            .await
+            .unwrap()
+            .is_none());
+
+        // A missing grammar file keeps the current grammar; the flag still applies
+        let settings = serde_json::json!({ "grammar_path": "/nonexistent/grammar.toml" });
+        parser
+            .process(Signal::Control(ControlSignal::Settings(settings)))
+            .await
+            .unwrap();
+        assert_eq!(parser.config().grammar_path, "");
+        let settings = serde_json::json!({ "emit_unmatched": true });
+        parser
+            .process(Signal::Control(ControlSignal::Settings(settings)))
+            .await
+            .unwrap();
+        let out = parser
+            .process(Signal::Text("what a lovely day".into()))
+            .await
+            .unwrap();
+        assert!(matches!(
+            &out,
+            Some(Signal::Intent { action, .. }) if action == UNRECOGNIZED_ACTION
+        ));
+    }
+}
@@ -0,0 +1,396 @@
+//! Keyword triggers: an intent per configured word or phrase heard, for
+//! when a full grammar is more than a patch needs.
+//!
+//! Matching tolerates a few misrecognized letters, so a trigger on
+//! `"magnolia"` still fires on `"magnolias"` or `"magnola"`.
+
+use async_trait::async_trait;
+use magnolia_core::{
+    ControlSignal, DataType, ModuleSchema, Port, PortDirection, Processor, Signal,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use crate::processor::utterance;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TriggerWord {
+    /// Word or phrase to listen for
+    pub phrase: String,
+    /// Intent action; the phrase's words joined with `_` when empty
+    pub action: String,
+    /// Overrides the config's `max_distance` for this phrase
+    pub max_distance: Option<usize>,
+}
+
+impl TriggerWord {
+    pub fn new(phrase: &str) -> Self {
+        Self {
+            phrase: phrase.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn action(&self) -> String {
+        if self.action.trim().is_empty() {
+            words(&self.phrase)
+                .into_iter()
+                .map(|(word, _)| word)
+                .collect::<Vec<_>>()
+                .join("_")
+        } else {
+            self.action.trim().to_string()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TriggerWordsConfig {
+    pub triggers: Vec<TriggerWord>,
+    /// Letters that may differ, be missing or be extra; never more than a
+    /// quarter of the phrase's letters, so short words must match exactly
+    pub max_distance: usize,
+}
+
+impl Default for TriggerWordsConfig {
+    fn default() -> Self {
+        Self {
+            triggers: Vec::new(),
+            max_distance: 1,
+        }
+    }
+}
+
+impl TriggerWordsConfig {
+    pub fn schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "triggers": {
+                    "type": "array",
+                    "title": "Triggers",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "phrase": { "type": "string" },
+                            "action": { "type": "string" },
+                            "max_distance": { "type": "integer", "minimum": 0 }
+                        }
+                    }
+                },
+                "max_distance": {
+                    "type": "integer",
+                    "title": "Max Edit Distance",
+                    "default": 1,
+                    "minimum": 0,
+                    "maximum": 4
+                }
+            }
+        })
+    }
+}
+
+/// A trigger heard in some text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TriggerMatch {
+    pub action: String,
+    /// The text as heard
+    pub text: String,
+    /// Character offsets of `text` in the input
+    pub start: usize,
+    pub end: usize,
+    pub distance: usize,
+}
+
+impl TriggerMatch {
+    /// `action [text, start, end]`
+    pub fn into_signal(self) -> Signal {
+        Signal::Intent {
+            action: self.action,
+            parameters: vec![self.text, self.start.to_string(), self.end.to_string()],
+        }
+    }
+}
+
+/// Lowercased words of `text` without surrounding punctuation, with their
+/// character ranges in `text`
+fn words(text: &str) -> Vec<(String, (usize, usize))> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i].is_whitespace() {
+            i += 1;
+            continue;
+        }
+        let mut end = i;
+        while end < chars.len() && !chars[end].is_whitespace() {
+            end += 1;
+        }
+        let (mut start, mut stop) = (i, end);
+        while start < stop && !chars[start].is_alphanumeric() {
+            start += 1;
+        }
+        while stop > start && !chars[stop - 1].is_alphanumeric() {
+            stop -= 1;
+        }
+        if start < stop {
+            let word: String = chars[start..stop].iter().collect();
+            out.push((word.to_lowercase(), (start, stop)));
+        }
+        i = end;
+    }
+    out
+}
+
+/// Levenshtein distance over characters
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                diagonal
+            } else {
+                1 + diagonal.min(above).min(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+/// Triggers found in `text`, in order; a stretch of text fires at most one
+pub fn find_triggers(config: &TriggerWordsConfig, text: &str) -> Vec<TriggerMatch> {
+    let heard = words(text);
+    let phrases: Vec<(String, usize, &TriggerWord)> = config
+        .triggers
+        .iter()
+        .filter_map(|trigger| {
+            let phrase_words = words(&trigger.phrase);
+            if phrase_words.is_empty() {
+                return None;
+            }
+            let phrase = phrase_words
+                .iter()
+                .map(|(word, _)| word.as_str())
+                .collect::<Vec<_>>()
+                .join(" ");
+            let letters = phrase.chars().filter(|c| !c.is_whitespace()).count();
+            let tolerance = trigger
+                .max_distance
+                .unwrap_or(config.max_distance)
+                .min(letters / 4);
+            Some((phrase, tolerance, trigger))
+        })
+        .collect();
+
+    let chars: Vec<char> = text.chars().collect();
+    let mut matches = Vec::new();
+    let mut i = 0;
+    while i < heard.len() {
+        let mut best: Option<(usize, usize, &TriggerWord)> = None;
+        for (phrase, tolerance, trigger) in &phrases {
+            let len = phrase.split(' ').count();
+            if i + len > heard.len() {
+                continue;
+            }
+            let window = heard[i..i + len]
+                .iter()
+                .map(|(word, _)| word.as_str())
+                .collect::<Vec<_>>()
+                .join(" ");
+            let distance = edit_distance(&window, phrase);
+            if distance <= *tolerance && best.is_none_or(|(d, ..)| distance < d) {
+                best = Some((distance, len, trigger));
+            }
+        }
+        match best {
+            Some((distance, len, trigger)) => {
+                let (start, end) = (heard[i].1 .0, heard[i + len - 1].1 .1);
+                matches.push(TriggerMatch {
+                    action: trigger.action(),
+                    text: chars[start..end].iter().collect(),
+                    start,
+                    end,
+                    distance,
+                });
+                i += len;
+            }
+            None => i += 1,
+        }
+    }
+    matches
+}
+
+/// Emits an intent for every configured keyword or phrase in final
+/// speech-to-text segments and plain text: `action [heard text, start, end]`
+pub struct TriggerWordsProcessor {
+    id: String,
+    enabled: bool,
+    config: TriggerWordsConfig,
+    pending: VecDeque<Signal>,
+}
+
+impl TriggerWordsProcessor {
+    pub fn new(id: &str, config: TriggerWordsConfig) -> Self {
+        Self {
+            id: id.to_string(),
+            enabled: true,
+            config,
+            pending: VecDeque::new(),
+        }
+    }
+
+    pub fn config(&self) -> &TriggerWordsConfig {
+        &self.config
+    }
+
+    pub fn set_config(&mut self, config: TriggerWordsConfig) {
+        self.config = config;
+    }
+}
+
+#[async_trait]
+impl Processor for TriggerWordsProcessor {
+    fn name(&self) -> &str {
+        "Trigger Words"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "Trigger Words".to_string(),
+            description: "Emits an intent for each keyword or phrase heard, tolerating near misses"
+                .to_string(),
+            ports: vec![
+                Port {
+                    id: "text_in".to_string(),
+                    label: "Text In".to_string(),
+                    data_type: DataType::Text,
+                    direction: PortDirection::Input,
+                },
+                Port {
+                    id: "intent_out".to_string(),
+                    label: "Intents Out".to_string(),
+                    data_type: DataType::Control,
+                    direction: PortDirection::Output,
+                },
+            ],
+            settings_schema: Some(TriggerWordsConfig::schema()),
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.pending.clear();
+        }
+    }
+
+    async fn process(&mut self, signal: Signal) -> anyhow::Result<Option<Signal>> {
+        if let Signal::Control(ControlSignal::Settings(settings)) = &signal {
+            match serde_json::from_value::<TriggerWordsConfig>(settings.clone()) {
+                Ok(config) => self.set_config(config),
+                Err(e) => log::warn!("Trigger words: invalid settings, keeping current: {}", e),
+            }
+            return Ok(None);
+        }
+        let Some(text) = utterance(&signal) else {
+            return Ok(None);
+        };
+        // The first goes out now, the rest through `wake`
+        self.pending.extend(
+            find_triggers(&self.config, &text)
+                .into_iter()
+                .map(TriggerMatch::into_signal),
+        );
+        Ok(self.pending.pop_front())
+    }
+
+    async fn next_wake(&self) {
+        if self.pending.is_empty() {
+            std::future::pending::<()>().await;
+        }
+    }
+
+    async fn wake(&mut self) -> anyhow::Result<Option<Signal>> {
+        Ok(self.pending.pop_front())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(phrases: &[&str]) -> TriggerWordsConfig {
+        TriggerWordsConfig {
+            triggers: phrases.iter().map(|p| TriggerWord::new(p)).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn edit_distance_counts_single_letter_edits() {
+        assert_eq!(edit_distance("magnolia", "magnolia"), 0);
+        assert_eq!(edit_distance("magnolia", "magnola"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn finds_phrases_with_their_spans_and_tolerates_near_misses() {
+        let config = config(&["next slide", "magnolia", "go"]);
+        let text = "Okay, Magnola: next slyde please, then go!";
+        let found = find_triggers(&config, text);
+        let summary: Vec<_> = found
+            .iter()
+            .map(|m| (m.action.as_str(), m.text.as_str(), m.distance))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("magnolia", "Magnola", 1),
+                ("next_slide", "next slyde", 1),
+                ("go", "go", 0),
+            ]
+        );
+        let slide = &found[1];
+        let span: String = text
+            .chars()
+            .skip(slide.start)
+            .take(slide.end - slide.start)
+            .collect();
+        assert_eq!(span, "next slyde");
+
+        // Short words don't get fuzzy matches
+        assert!(find_triggers(&config, "so").is_empty());
+    }
+
+    #[tokio::test]
+    async fn emits_one_intent_per_match() {
+        let mut processor = TriggerWordsProcessor::new("trigger_words", config(&["stop", "start"]));
+        let first = processor
+            .process(Signal::Text("start and then stop".into()))
+            .await
+            .unwrap();
+        assert!(matches!(first, Some(Signal::Intent { ref action, .. }) if action == "start"));
+        processor.next_wake().await;
+        let Some(Signal::Intent { action, parameters }) = processor.wake().await.unwrap() else {
+            panic!("expected a second intent");
+        };
+        assert_eq!(action, "stop");
+        assert_eq!(parameters, vec!["stop", "15", "19"]);
+        assert!(processor.wake().await.unwrap().is_none());
+    }
+}
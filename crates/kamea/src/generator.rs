@@ -2,58 +2,266 @@
 use rand::{Rng, SeedableRng};
 #[cfg(feature = "tile-rendering")]
 use rand_chacha::ChaCha20Rng;
+#[cfg(feature = "tile-rendering")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "tile-rendering")]
+use sha2::{Digest, Sha256};
+#[cfg(feature = "tile-rendering")]
+use std::collections::{HashSet, VecDeque};
+
+/// Grid cell as (column, row), row 0 at the bottom
+#[cfg(feature = "tile-rendering")]
+pub type Cell = (usize, usize);
+
+/// Walk moves (adjacent and diagonal)
+#[cfg(feature = "tile-rendering")]
+const MOVES: [(i32, i32); 8] = [
+    (0, 1),
+    (0, -1),
+    (1, 0),
+    (-1, 0),
+    (1, 1),
+    (1, -1),
+    (-1, 1),
+    (-1, -1),
+];
+
+/// How a multi-word intent is composed into one sigil
+#[cfg(feature = "tile-rendering")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LayerMode {
+    /// One walk for the whole phrase
+    #[default]
+    Single,
+    /// One walk per word, drawn on top of each other
+    Overlay,
+    /// The set union of every word's grid edges, as one layer
+    Union,
+    /// One walk per word, word `i` of `n` rotated by `i * 360 / n` degrees
+    Rotate,
+}
+
+#[cfg(feature = "tile-rendering")]
+impl LayerMode {
+    pub const ALL: [LayerMode; 4] = [
+        LayerMode::Single,
+        LayerMode::Overlay,
+        LayerMode::Union,
+        LayerMode::Rotate,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LayerMode::Single => "single",
+            LayerMode::Overlay => "overlay",
+            LayerMode::Union => "union",
+            LayerMode::Rotate => "rotate",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|m| m.as_str() == s)
+    }
+}
+
+/// Stroke styling for one layer
+#[cfg(feature = "tile-rendering")]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LayerStyle {
+    pub color: (f32, f32, f32),
+    pub stroke_weight: f32,
+}
 
 #[cfg(feature = "tile-rendering")]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct SigilConfig {
     pub spacing: f32,
     pub stroke_weight: f32,
     pub grid_rows: usize,
     pub grid_cols: usize,
+    pub layer_mode: LayerMode,
+    /// Per-layer styles, cycled; empty means every layer uses the base style
+    pub layer_styles: Vec<LayerStyle>,
+    /// Waypoints every walk passes through, in order
+    pub pinned: Vec<Cell>,
+    /// Cells no walk may enter
+    pub excluded: Vec<Cell>,
+}
+
+#[cfg(feature = "tile-rendering")]
+impl SigilConfig {
+    /// Style of layer `index`, falling back to `base`
+    pub fn style_for(&self, index: usize, base: LayerStyle) -> LayerStyle {
+        if self.layer_styles.is_empty() {
+            base
+        } else {
+            self.layer_styles[index % self.layer_styles.len()]
+        }
+    }
+
+    pub fn contains(&self, cell: Cell) -> bool {
+        cell.0 < self.grid_cols && cell.1 < self.grid_rows
+    }
+
+    /// Whether a walk may step onto `cell`
+    pub fn is_open(&self, cell: Cell) -> bool {
+        self.contains(cell) && !self.excluded.contains(&cell)
+    }
+
+    /// Drop constraints that fall outside the grid (after a resize)
+    pub fn retain_in_grid(&mut self) {
+        let (cols, rows) = (self.grid_cols, self.grid_rows);
+        let inside = |c: &Cell| c.0 < cols && c.1 < rows;
+        self.pinned.retain(inside);
+        self.excluded.retain(inside);
+    }
+
+    fn step(&self, from: Cell, (dx, dy): (i32, i32)) -> Option<Cell> {
+        let x = from.0 as i32 + dx;
+        let y = from.1 as i32 + dy;
+        if x < 0 || y < 0 {
+            return None;
+        }
+        let cell = (x as usize, y as usize);
+        self.is_open(cell).then_some(cell)
+    }
+}
+
+/// One layer of a composed sigil: the word it came from and its polylines
+#[cfg(feature = "tile-rendering")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SigilLayer {
+    pub word: String,
+    pub strokes: Vec<Vec<(f32, f32)>>,
+}
+
+#[cfg(feature = "tile-rendering")]
+pub fn text_seed(text: &str) -> [u8; 32] {
+    let mut seed = [0u8; 32];
+    seed.copy_from_slice(&Sha256::digest(text.as_bytes()));
+    seed
+}
+
+/// Compose `text` into sigil layers according to `config.layer_mode`.
+///
+/// Phrases of a single word always produce one walk, so every mode agrees
+/// with [`LayerMode::Single`] for them.
+#[cfg(feature = "tile-rendering")]
+pub fn generate_layers(text: &str, config: &SigilConfig) -> Vec<SigilLayer> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    if config.layer_mode == LayerMode::Single || words.len() < 2 {
+        return vec![SigilLayer {
+            word: text.to_string(),
+            strokes: vec![generate_path(text_seed(text), config)],
+        }];
+    }
+
+    match config.layer_mode {
+        LayerMode::Single | LayerMode::Overlay => words
+            .iter()
+            .map(|word| SigilLayer {
+                word: word.to_string(),
+                strokes: vec![generate_path(text_seed(word), config)],
+            })
+            .collect(),
+        LayerMode::Rotate => {
+            let step = std::f32::consts::TAU / words.len() as f32;
+            words
+                .iter()
+                .enumerate()
+                .map(|(i, word)| {
+                    let (sin, cos) = (step * i as f32).sin_cos();
+                    let path = generate_path(text_seed(word), config)
+                        .into_iter()
+                        .map(|(x, y)| (x * cos - y * sin, x * sin + y * cos))
+                        .collect();
+                    SigilLayer {
+                        word: word.to_string(),
+                        strokes: vec![path],
+                    }
+                })
+                .collect()
+        }
+        LayerMode::Union => {
+            let mut seen = HashSet::new();
+            let mut strokes = Vec::new();
+            for word in &words {
+                for edge in walk(text_seed(word), config).windows(2) {
+                    let key = if edge[0] <= edge[1] {
+                        (edge[0], edge[1])
+                    } else {
+                        (edge[1], edge[0])
+                    };
+                    if seen.insert(key) {
+                        strokes.push(vec![
+                            grid_to_world(edge[0], config),
+                            grid_to_world(edge[1], config),
+                        ]);
+                    }
+                }
+            }
+            vec![SigilLayer {
+                word: text.to_string(),
+                strokes,
+            }]
+        }
+    }
 }
 
 #[cfg(feature = "tile-rendering")]
-pub fn generate_path(seed: [u8; 32], config: SigilConfig) -> Vec<(f32, f32)> {
+pub fn generate_path(seed: [u8; 32], config: &SigilConfig) -> Vec<(f32, f32)> {
+    walk(seed, config)
+        .into_iter()
+        .map(|node| grid_to_world(node, config))
+        .collect()
+}
+
+/// The seeded random walk, as grid nodes.
+///
+/// The walk never enters excluded cells. Pinned waypoints are reached in
+/// order along randomly chosen shortest routes (unreachable ones are
+/// skipped) before the walk wanders freely for the rest of its length.
+#[cfg(feature = "tile-rendering")]
+fn walk(seed: [u8; 32], config: &SigilConfig) -> Vec<Cell> {
     let mut rng = ChaCha20Rng::from_seed(seed);
     let mut points = Vec::new();
 
     let cols = config.grid_cols;
     let rows = config.grid_rows;
 
-    // Start at a random node
+    // Start at a random node (the next open one if it is excluded)
     let start_x = rng.gen_range(0..cols);
     let start_y = rng.gen_range(0..rows);
-    let mut curr = (start_x, start_y);
+    let start = start_y * cols + start_x;
+    let Some(mut curr) = (0..cols * rows)
+        .map(|i| ((start + i) % cols, (start + i) / cols % rows))
+        .find(|cell| config.is_open(*cell))
+    else {
+        return points;
+    };
 
-    points.push(grid_to_world(curr, config));
+    points.push(curr);
 
     // Path length between 5 and max nodes
     let len = rng.gen_range(5..=(cols * rows));
 
-    for _ in 0..len {
-        // Defined moves (adjacent and diagonal)
-        let moves = vec![
-            (0, 1),
-            (0, -1),
-            (1, 0),
-            (-1, 0),
-            (1, 1),
-            (1, -1),
-            (-1, 1),
-            (-1, -1),
-        ];
+    for &pin in &config.pinned {
+        if let Some(leg) = route(&mut rng, curr, pin, config) {
+            points.extend(leg);
+            curr = pin;
+        }
+    }
 
+    for _ in 0..len.saturating_sub(points.len() - 1) {
         let mut attempts = 0;
         let mut found = false;
 
         while attempts < 8 {
-            let (dx, dy) = moves[rng.gen_range(0..moves.len())];
-            let next_x = curr.0 as i32 + dx;
-            let next_y = curr.1 as i32 + dy;
-
-            if next_x >= 0 && next_x < cols as i32 && next_y >= 0 && next_y < rows as i32 {
-                curr = (next_x as usize, next_y as usize);
-                points.push(grid_to_world(curr, config));
+            let mv = MOVES[rng.gen_range(0..MOVES.len())];
+            if let Some(next) = config.step(curr, mv) {
+                curr = next;
+                points.push(curr);
                 found = true;
                 break;
             }
@@ -68,8 +276,49 @@ pub fn generate_path(seed: [u8; 32], config: SigilConfig) -> Vec<(f32, f32)> {
     points
 }
 
+/// A random shortest route from `from` to `to` through open cells,
+/// excluding `from`; `None` when `to` is closed or unreachable
+#[cfg(feature = "tile-rendering")]
+fn route(rng: &mut ChaCha20Rng, from: Cell, to: Cell, config: &SigilConfig) -> Option<Vec<Cell>> {
+    if !config.is_open(to) {
+        return None;
+    }
+
+    // Distances to the target, breadth first
+    let index = |c: Cell| c.1 * config.grid_cols + c.0;
+    let mut distance = vec![usize::MAX; config.grid_cols * config.grid_rows];
+    distance[index(to)] = 0;
+    let mut queue = VecDeque::from([to]);
+    while let Some(cell) = queue.pop_front() {
+        for mv in MOVES {
+            if let Some(next) = config.step(cell, mv) {
+                if distance[index(next)] == usize::MAX {
+                    distance[index(next)] = distance[index(cell)] + 1;
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+    if distance[index(from)] == usize::MAX {
+        return None;
+    }
+
+    let mut leg = Vec::new();
+    let mut curr = from;
+    while curr != to {
+        let closer: Vec<Cell> = MOVES
+            .iter()
+            .filter_map(|mv| config.step(curr, *mv))
+            .filter(|next| distance[index(*next)] + 1 == distance[index(curr)])
+            .collect();
+        curr = closer[rng.gen_range(0..closer.len())];
+        leg.push(curr);
+    }
+    Some(leg)
+}
+
 #[cfg(feature = "tile-rendering")]
-fn grid_to_world(grid_pos: (usize, usize), config: SigilConfig) -> (f32, f32) {
+pub fn grid_to_world(grid_pos: Cell, config: &SigilConfig) -> (f32, f32) {
     // Centering the grid
     let output_x = (grid_pos.0 as f32 - (config.grid_cols as f32 - 1.0) / 2.0) * config.spacing;
     let output_y = (grid_pos.1 as f32 - (config.grid_rows as f32 - 1.0) / 2.0) * config.spacing;
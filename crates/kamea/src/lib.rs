@@ -1,32 +1,23 @@
 pub use magnolia_core::KameaGrid;
-#[cfg(feature = "tile-rendering")]
-use magnolia_plugin_helper::SignalValue;
 use magnolia_plugin_helper::{export_plugin, MagnoliaPlugin, SignalBuffer, SignalType};
 #[cfg(feature = "tile-rendering")]
-use nannou::prelude::*;
-
-#[cfg(feature = "tile-rendering")]
-use nannou::wgpu; // Access nannou's re-exported wgpu
+use magnolia_plugin_helper::{texture_payload, GpuApi};
 
 mod generator;
+pub mod plot;
 mod tile;
 use tile::KameaTile;
 
+/// Edge length of the sigil texture rendered through the host GPU API.
 #[cfg(feature = "tile-rendering")]
-struct GpuState {
-    device: *const wgpu::Device,
-    queue: *const wgpu::Queue,
-    renderer: nannou::draw::Renderer,
-    nannou_texture: wgpu::Texture,
-    view: wgpu::TextureView, // Store the view to keep it alive
-    width: u32,
-    height: u32,
-}
+const TEXTURE_SIZE: u32 = 512;
 
+/// Host GPU access and the texture the sigil is drawn into.
 #[cfg(feature = "tile-rendering")]
-unsafe impl Send for GpuState {}
-#[cfg(feature = "tile-rendering")]
-unsafe impl Sync for GpuState {}
+struct GpuState {
+    api: GpuApi,
+    texture: u64,
+}
 
 struct KameaPlugin {
     tile: Option<KameaTile>,
@@ -94,55 +85,18 @@ impl MagnoliaPlugin for KameaPlugin {
 
         #[cfg(feature = "tile-rendering")]
         {
-            if let Some(gpu) = &mut self.gpu_state {
+            if let Some(gpu) = &self.gpu_state {
                 let tile = self.tile.as_mut().unwrap();
                 use magnolia_core::TileRenderer;
                 tile.update();
 
-                let draw = Draw::new();
-                let rect = Rect::from_w_h(gpu.width as f32, gpu.height as f32);
-
-                let ctx = magnolia_core::RenderContext {
-                    time: std::time::Instant::now(),
-                    frame_count: 0,
-                    is_selected: false,
-                    is_maximized: false,
-                    tile_settings: None,
-                };
-
-                tile.render_monitor(&draw, rect, &ctx);
-
-                unsafe {
-                    let device = &*gpu.device;
-                    let queue = &*gpu.queue;
-
-                    let mut encoder =
-                        device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
-                            label: Some("kamea_encoder"),
-                        });
-
-                    gpu.renderer.render_to_texture(
-                        device,
-                        &mut encoder,
-                        &draw,
-                        &gpu.nannou_texture,
-                    );
-
-                    queue.submit(Some(encoder.finish()));
+                let commands = tile.sigil_draw_commands(TEXTURE_SIZE, TEXTURE_SIZE);
+                if !gpu.api.draw(gpu.texture, &commands) {
+                    log::warn!("kamea: host rejected sigil draw");
                 }
 
                 if !self.sent_texture {
-                    let id = 0xCAFE_BABE;
-
-                    buffer.signal_type = SignalType::Texture as u32;
-                    let raw_view = gpu.view.inner();
-
-                    buffer.value = SignalValue {
-                        ptr: raw_view as *const _ as *mut _,
-                    };
-                    buffer.size = id;
-                    buffer.param = ((gpu.width as u64) << 32) | (gpu.height as u64);
-
+                    texture_payload(buffer, gpu.texture);
                     self.sent_texture = true;
                     return true;
                 }
@@ -152,18 +106,18 @@ impl MagnoliaPlugin for KameaPlugin {
         false
     }
 
-    fn consume_signal(&mut self, input: &SignalBuffer) -> Option<SignalBuffer> {
-        if input.signal_type == SignalType::GpuContext as u32 {
-            #[cfg(feature = "tile-rendering")]
-            unsafe {
-                let device_ptr = input.value.ptr as *const wgpu::Device;
-                let queue_ptr = input.param as *const wgpu::Queue;
+    #[cfg(feature = "tile-rendering")]
+    fn attach_gpu(&mut self, gpu: Option<GpuApi>) {
+        // Textures are host-owned and die with a revoked session, so start over either way.
+        self.gpu_state = gpu.and_then(|api| {
+            let texture = api.create_texture(TEXTURE_SIZE, TEXTURE_SIZE)?;
+            Some(GpuState { api, texture })
+        });
+        self.sent_texture = false;
+    }
 
-                if !device_ptr.is_null() && !queue_ptr.is_null() {
-                    self.init_gpu(device_ptr, queue_ptr);
-                }
-            }
-        } else if input.signal_type == SignalType::Text as u32 {
+    fn consume_signal(&mut self, input: &SignalBuffer) -> Option<SignalBuffer> {
+        if input.signal_type == SignalType::Text as u32 {
             unsafe {
                 if !input.value.ptr.is_null() {
                     use std::ffi::CStr;
@@ -184,43 +138,4 @@ impl MagnoliaPlugin for KameaPlugin {
     }
 }
 
-#[cfg(feature = "tile-rendering")]
-impl KameaPlugin {
-    unsafe fn init_gpu(&mut self, device_ptr: *const wgpu::Device, queue_ptr: *const wgpu::Queue) {
-        let device = &*device_ptr;
-
-        let width = 512;
-        let height = 512;
-        let format = wgpu::TextureFormat::Rgba8Unorm;
-        let sample_count = 1;
-
-        let nannou_texture = wgpu::TextureBuilder::new()
-            .size([width, height])
-            .format(format)
-            .sample_count(sample_count)
-            .usage(wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING)
-            .build(device);
-
-        let view = nannou_texture.view().build();
-
-        let renderer = nannou::draw::RendererBuilder::new().build(
-            device,
-            [width, height],
-            1.0,
-            sample_count,
-            format,
-        );
-
-        self.gpu_state = Some(GpuState {
-            device: device_ptr,
-            queue: queue_ptr,
-            renderer,
-            nannou_texture,
-            view,
-            width,
-            height,
-        });
-    }
-}
-
 export_plugin!(KameaPlugin);
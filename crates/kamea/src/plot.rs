@@ -0,0 +1,273 @@
+//! Pen-plotter export: sigil polylines as G-code, HPGL or SVG.
+//!
+//! Polylines are in sigil units (centred, y-up) and are fitted into the
+//! configured drawing area with a uniform scale. With travel optimization on,
+//! strokes are reordered (and reversed where that helps) nearest-neighbour
+//! style, and strokes that start where the previous one ended are joined so
+//! the pen stays down.
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+pub type Polyline = Vec<(f32, f32)>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlotFormat {
+    /// GRBL-style G-code with the pen on a servo (`M3 S..`)
+    #[default]
+    Gcode,
+    /// HPGL, 40 plotter units per millimetre
+    Hpgl,
+    /// Plain SVG polylines, in millimetres
+    Svg,
+}
+
+impl PlotFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            PlotFormat::Gcode => "gcode",
+            PlotFormat::Hpgl => "hpgl",
+            PlotFormat::Svg => "svg",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "gcode" => Some(PlotFormat::Gcode),
+            "hpgl" => Some(PlotFormat::Hpgl),
+            "svg" => Some(PlotFormat::Svg),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlotConfig {
+    /// Drawing area (AxiDraw V3: 300 x 218 mm)
+    pub width_mm: f32,
+    pub height_mm: f32,
+    pub margin_mm: f32,
+    pub optimize_travel: bool,
+    /// Drawing and pen-up travel feed rates, mm/min
+    pub draw_feed: f32,
+    pub travel_feed: f32,
+    /// Servo values for the pen (G-code `M3 S<value>`)
+    pub pen_up: u32,
+    pub pen_down: u32,
+}
+
+impl Default for PlotConfig {
+    fn default() -> Self {
+        Self {
+            width_mm: 150.0,
+            height_mm: 150.0,
+            margin_mm: 10.0,
+            optimize_travel: true,
+            draw_feed: 1500.0,
+            travel_feed: 4000.0,
+            pen_up: 30,
+            pen_down: 60,
+        }
+    }
+}
+
+fn distance(a: (f32, f32), b: (f32, f32)) -> f32 {
+    ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+}
+
+/// Pen-up travel when drawing `polylines` in order, starting from the origin
+pub fn travel_distance(polylines: &[Polyline]) -> f32 {
+    let mut pen = (0.0, 0.0);
+    let mut travel = 0.0;
+    for line in polylines.iter().filter(|l| !l.is_empty()) {
+        travel += distance(pen, line[0]);
+        pen = line[line.len() - 1];
+    }
+    travel
+}
+
+/// Greedy nearest-neighbour ordering from `start`; strokes may be reversed,
+/// and strokes continuing from the previous end are joined
+pub fn optimize_order(polylines: &[Polyline], start: (f32, f32)) -> Vec<Polyline> {
+    let mut remaining: Vec<&Polyline> = polylines.iter().filter(|l| !l.is_empty()).collect();
+    let mut ordered: Vec<Polyline> = Vec::new();
+    let mut pen = start;
+
+    while !remaining.is_empty() {
+        let (index, reversed, _) = remaining
+            .iter()
+            .enumerate()
+            .flat_map(|(i, line)| {
+                [
+                    (i, false, distance(pen, line[0])),
+                    (i, true, distance(pen, line[line.len() - 1])),
+                ]
+            })
+            .fold((0, false, f32::INFINITY), |best, candidate| {
+                if candidate.2 < best.2 {
+                    candidate
+                } else {
+                    best
+                }
+            });
+        let mut line = remaining.swap_remove(index).clone();
+        if reversed {
+            line.reverse();
+        }
+        pen = line[line.len() - 1];
+
+        match ordered.last_mut() {
+            Some(previous) if previous.last() == line.first() => {
+                previous.extend_from_slice(&line[1..]);
+            }
+            _ => ordered.push(line),
+        }
+    }
+    ordered
+}
+
+/// Fit polylines into the drawing area in millimetres (origin bottom-left,
+/// y-up), then order them for travel if configured
+pub fn fit(polylines: &[Polyline], config: &PlotConfig) -> Vec<Polyline> {
+    let points = polylines.iter().flatten();
+    let (mut min_x, mut min_y) = (f32::INFINITY, f32::INFINITY);
+    let (mut max_x, mut max_y) = (f32::NEG_INFINITY, f32::NEG_INFINITY);
+    for &(x, y) in points {
+        min_x = min_x.min(x);
+        min_y = min_y.min(y);
+        max_x = max_x.max(x);
+        max_y = max_y.max(y);
+    }
+    if !min_x.is_finite() {
+        return Vec::new();
+    }
+
+    let area_w = (config.width_mm - 2.0 * config.margin_mm).max(1.0);
+    let area_h = (config.height_mm - 2.0 * config.margin_mm).max(1.0);
+    let span = (max_x - min_x).max(max_y - min_y).max(f32::EPSILON);
+    let scale = area_w.min(area_h) / span;
+    let centre = ((min_x + max_x) / 2.0, (min_y + max_y) / 2.0);
+    let to_mm = |(x, y): (f32, f32)| {
+        (
+            config.width_mm / 2.0 + (x - centre.0) * scale,
+            config.height_mm / 2.0 + (y - centre.1) * scale,
+        )
+    };
+
+    let fitted: Vec<Polyline> = polylines
+        .iter()
+        .map(|line| line.iter().copied().map(to_mm).collect())
+        .collect();
+    if config.optimize_travel {
+        optimize_order(&fitted, (0.0, 0.0))
+    } else {
+        fitted.into_iter().filter(|l| !l.is_empty()).collect()
+    }
+}
+
+pub fn to_gcode(polylines: &[Polyline], config: &PlotConfig) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "; kamea sigil, {} strokes", polylines.len());
+    out.push_str("G21 ; mm\nG90 ; absolute\n");
+    let _ = writeln!(out, "M3 S{}", config.pen_up);
+    for line in fit(polylines, config) {
+        let (x, y) = line[0];
+        let _ = writeln!(out, "G0 X{:.3} Y{:.3} F{:.0}", x, y, config.travel_feed);
+        let _ = writeln!(out, "M3 S{}", config.pen_down);
+        for &(x, y) in &line[1..] {
+            let _ = writeln!(out, "G1 X{:.3} Y{:.3} F{:.0}", x, y, config.draw_feed);
+        }
+        let _ = writeln!(out, "M3 S{}", config.pen_up);
+    }
+    out.push_str("G0 X0 Y0\nM5\n");
+    out
+}
+
+pub fn to_hpgl(polylines: &[Polyline], config: &PlotConfig) -> String {
+    let unit = |v: f32| (v * 40.0).round() as i32;
+    let mut out = String::from("IN;SP1;");
+    for line in fit(polylines, config) {
+        let (x, y) = line[0];
+        let _ = write!(out, "PU{},{};", unit(x), unit(y));
+        let coords: Vec<String> = line[1..]
+            .iter()
+            .map(|&(x, y)| format!("{},{}", unit(x), unit(y)))
+            .collect();
+        if !coords.is_empty() {
+            let _ = write!(out, "PD{};", coords.join(","));
+        }
+    }
+    out.push_str("PU;SP0;\n");
+    out
+}
+
+pub fn to_svg(polylines: &[Polyline], config: &PlotConfig) -> String {
+    let (w, h) = (config.width_mm, config.height_mm);
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}mm" height="{h}mm" viewBox="0 0 {w} {h}">"#
+    );
+    out.push_str(
+        r#"<g fill="none" stroke="black" stroke-width="0.5" stroke-linecap="round" stroke-linejoin="round">"#,
+    );
+    out.push('\n');
+    for line in fit(polylines, config) {
+        // SVG is y-down
+        let points: Vec<String> = line
+            .iter()
+            .map(|&(x, y)| format!("{:.3},{:.3}", x, h - y))
+            .collect();
+        let _ = writeln!(out, r#"<polyline points="{}"/>"#, points.join(" "));
+    }
+    out.push_str("</g>\n</svg>\n");
+    out
+}
+
+pub fn export(polylines: &[Polyline], format: PlotFormat, config: &PlotConfig) -> String {
+    match format {
+        PlotFormat::Gcode => to_gcode(polylines, config),
+        PlotFormat::Hpgl => to_hpgl(polylines, config),
+        PlotFormat::Svg => to_svg(polylines, config),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn optimize_order_reverses_and_joins_strokes() {
+        let strokes = vec![
+            vec![(10.0, 0.0), (20.0, 0.0)],
+            vec![(10.0, 0.0), (1.0, 0.0)],
+            vec![(50.0, 50.0), (60.0, 60.0)],
+        ];
+        let ordered = optimize_order(&strokes, (0.0, 0.0));
+        // The second stroke is drawn backwards and the first continues it
+        assert_eq!(ordered.len(), 2);
+        assert_eq!(ordered[0], vec![(1.0, 0.0), (10.0, 0.0), (20.0, 0.0)]);
+        assert!(travel_distance(&ordered) < travel_distance(&strokes));
+    }
+
+    #[test]
+    fn exports_fit_the_drawing_area() {
+        let strokes = vec![vec![(-60.0, -60.0), (60.0, 60.0)], vec![(-60.0, 60.0)]];
+        let config = PlotConfig {
+            optimize_travel: false,
+            ..PlotConfig::default()
+        };
+
+        let fitted = fit(&strokes, &config);
+        assert_eq!(fitted[0], vec![(10.0, 10.0), (140.0, 140.0)]);
+
+        let gcode = to_gcode(&strokes, &config);
+        assert!(gcode.contains("G0 X10.000 Y10.000 F4000\nM3 S60\nG1 X140.000 Y140.000 F1500\n"));
+        let hpgl = to_hpgl(&strokes, &config);
+        assert!(hpgl.starts_with("IN;SP1;PU400,400;PD5600,5600;PU400,5600;"));
+        let svg = to_svg(&strokes, &config);
+        assert!(svg.contains(r#"<polyline points="10.000,140.000 140.000,10.000"/>"#));
+    }
+}
@@ -41,6 +41,7 @@ impl Sink for KameaSink {
                 },
             ],
             settings_schema: None,
+            docs: None,
         }
     }
     
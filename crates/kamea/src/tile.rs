@@ -3,27 +3,39 @@
 //! Creates visual sigils from text input using SHA256 hash
 //! and random walk on a grid (Digital Kamea method).
 //!
+//! Long phrases can be split into words, one walk per word, composed by
+//! overlaying, unioning or rotating the walks (see [`LayerMode`]).
+//!
+//! Cells can be pinned as waypoints or excluded from the walk: when the
+//! tile is maximized, the arrow keys move a cursor over the grid, `P` pins
+//! or unpins the cell, `X` excludes it and `C` clears every constraint.
+//!
+//! The `export_plot` action writes the sigil as G-code, HPGL or SVG for a
+//! pen plotter (see [`crate::plot`]).
+//!
 //! Monitor mode: Displays the current sigil
-//! Control mode: Settings for grid size, colors, stroke weight
+//! Control mode: Settings for grid size, colors, stroke weight, layering
 
 #[cfg(feature = "tile-rendering")]
-use crate::generator::generate_path;
+use crate::generator::{
+    generate_layers, grid_to_world, text_seed, Cell, LayerMode, LayerStyle, SigilConfig, SigilLayer,
+};
 #[cfg(feature = "tile-rendering")]
-use crate::generator::SigilConfig;
+use crate::plot::{self, PlotConfig, PlotFormat, Polyline};
 #[cfg(feature = "tile-rendering")]
 use magnolia_core::{BindableAction, RenderContext, TileRenderer};
 #[cfg(feature = "tile-rendering")]
+use magnolia_plugin_helper::{GpuDrawCommand, GpuDrawKind};
+#[cfg(feature = "tile-rendering")]
 use magnolia_ui::{draw_text, FontId, TextAlignment};
 #[cfg(feature = "tile-rendering")]
 use nannou::prelude::*;
-#[cfg(feature = "tile-rendering")]
-use sha2::{Digest, Sha256};
 use std::sync::{Arc, Mutex};
 
 pub struct KameaTile {
     current_text: Arc<Mutex<String>>,
     #[cfg(feature = "tile-rendering")]
-    path_points: Vec<Point2>,
+    layers: Vec<SigilLayer>,
     #[cfg(feature = "tile-rendering")]
     config: SigilConfig,
     #[cfg(feature = "tile-rendering")]
@@ -34,6 +46,16 @@ pub struct KameaTile {
     glow_intensity: f32,
     #[cfg(feature = "tile-rendering")]
     path_color: (f32, f32, f32), // RGB 0-1
+    #[cfg(feature = "tile-rendering")]
+    cursor: Cell,
+    #[cfg(feature = "tile-rendering")]
+    plot_format: PlotFormat,
+    #[cfg(feature = "tile-rendering")]
+    plot_config: PlotConfig,
+    #[cfg(feature = "tile-rendering")]
+    plot_dir: String,
+    #[cfg(feature = "tile-rendering")]
+    plot_status: Option<String>,
 }
 
 impl KameaTile {
@@ -41,13 +63,17 @@ impl KameaTile {
         Self {
             current_text: Arc::new(Mutex::new(String::new())),
             #[cfg(feature = "tile-rendering")]
-            path_points: Vec::new(),
+            layers: Vec::new(),
             #[cfg(feature = "tile-rendering")]
             config: SigilConfig {
                 spacing: 40.0,
                 stroke_weight: 2.0,
                 grid_rows: 4,
                 grid_cols: 4,
+                layer_mode: LayerMode::Single,
+                layer_styles: Vec::new(),
+                pinned: Vec::new(),
+                excluded: Vec::new(),
             },
             #[cfg(feature = "tile-rendering")]
             last_text_hash: [0u8; 32],
@@ -57,6 +83,16 @@ impl KameaTile {
             glow_intensity: 0.2,
             #[cfg(feature = "tile-rendering")]
             path_color: (0.0, 1.0, 1.0), // Cyan default
+            #[cfg(feature = "tile-rendering")]
+            cursor: (0, 0),
+            #[cfg(feature = "tile-rendering")]
+            plot_format: PlotFormat::default(),
+            #[cfg(feature = "tile-rendering")]
+            plot_config: PlotConfig::default(),
+            #[cfg(feature = "tile-rendering")]
+            plot_dir: String::new(),
+            #[cfg(feature = "tile-rendering")]
+            plot_status: None,
         }
     }
 
@@ -69,24 +105,117 @@ impl KameaTile {
 
     #[cfg(feature = "tile-rendering")]
     fn regenerate_path(&mut self, text: &str) {
-        // Hash the text
-        let mut hasher = Sha256::new();
-        hasher.update(text.as_bytes());
-        let result = hasher.finalize();
-        let mut seed = [0u8; 32];
-        seed.copy_from_slice(&result);
+        let seed = text_seed(text);
 
         // Check if we need to regenerate
-        if seed == self.last_text_hash && !self.path_points.is_empty() {
+        if seed == self.last_text_hash && !self.layers.is_empty() {
             return;
         }
         self.last_text_hash = seed;
 
-        // Generate the path with current config
-        self.path_points = generate_path(seed, self.config)
-            .into_iter()
-            .map(|(x, y)| pt2(x, y))
+        // Generate the layers with current config
+        self.layers = generate_layers(text, &self.config);
+    }
+
+    /// Layer strokes (in unscaled grid units) with their resolved styles
+    #[cfg(feature = "tile-rendering")]
+    fn styled_strokes(&self) -> impl Iterator<Item = (Vec<Point2>, LayerStyle)> + '_ {
+        let base = LayerStyle {
+            color: self.path_color,
+            stroke_weight: self.config.stroke_weight,
+        };
+        self.layers.iter().enumerate().flat_map(move |(i, layer)| {
+            let style = self.config.style_for(i, base);
+            layer.strokes.iter().map(move |stroke| {
+                let points = stroke.iter().map(|&(x, y)| pt2(x, y)).collect();
+                (points, style)
+            })
+        })
+    }
+
+    /// Cell centre in unscaled grid units
+    #[cfg(feature = "tile-rendering")]
+    fn cell_point(&self, cell: Cell) -> Point2 {
+        let (x, y) = grid_to_world(cell, &self.config);
+        pt2(x, y)
+    }
+
+    /// Pin the cursor cell as the next waypoint, or unpin it
+    #[cfg(feature = "tile-rendering")]
+    fn toggle_pin(&mut self) {
+        let cell = self.cursor;
+        if let Some(i) = self.config.pinned.iter().position(|c| *c == cell) {
+            self.config.pinned.remove(i);
+        } else {
+            self.config.excluded.retain(|c| *c != cell);
+            self.config.pinned.push(cell);
+        }
+        self.last_text_hash = [0u8; 32]; // Force regeneration
+    }
+
+    /// Exclude the cursor cell from the walk, or allow it again
+    #[cfg(feature = "tile-rendering")]
+    fn toggle_exclude(&mut self) {
+        let cell = self.cursor;
+        if let Some(i) = self.config.excluded.iter().position(|c| *c == cell) {
+            self.config.excluded.remove(i);
+        } else {
+            self.config.pinned.retain(|c| *c != cell);
+            self.config.excluded.push(cell);
+        }
+        self.last_text_hash = [0u8; 32]; // Force regeneration
+    }
+
+    #[cfg(feature = "tile-rendering")]
+    fn clear_constraints(&mut self) -> bool {
+        if self.config.pinned.is_empty() && self.config.excluded.is_empty() {
+            return false;
+        }
+        self.config.pinned.clear();
+        self.config.excluded.clear();
+        self.last_text_hash = [0u8; 32]; // Force regeneration
+        true
+    }
+
+    #[cfg(feature = "tile-rendering")]
+    fn set_grid_size(&mut self, size: usize) {
+        self.config.grid_rows = size;
+        self.config.grid_cols = size;
+        self.config.retain_in_grid();
+        self.cursor = (self.cursor.0.min(size - 1), self.cursor.1.min(size - 1));
+        self.last_text_hash = [0u8; 32]; // Force regeneration
+    }
+
+    /// Write the sigil for a pen plotter into `plot_dir` (the working
+    /// directory when empty), named after the intent hash
+    #[cfg(feature = "tile-rendering")]
+    fn export_plot(&self) -> Result<std::path::PathBuf, String> {
+        let strokes: Vec<Polyline> = self
+            .layers
+            .iter()
+            .flat_map(|layer| layer.strokes.iter().cloned())
             .collect();
+        if strokes.is_empty() {
+            return Err("no sigil to export".to_string());
+        }
+
+        let hash: String = self.last_text_hash[..4]
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        let name = format!("sigil-{}.{}", hash, self.plot_format.extension());
+        let path = std::path::Path::new(&self.plot_dir).join(name);
+        let content = plot::export(&strokes, self.plot_format, &self.plot_config);
+        std::fs::write(&path, content).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok(path)
+    }
+
+    /// Start of the first walk and end of the last one
+    #[cfg(feature = "tile-rendering")]
+    fn sigil_endpoints(&self) -> Option<(Point2, Point2)> {
+        let first = self.layers.first()?.strokes.first()?.first()?;
+        let last = self.layers.last()?.strokes.last()?.last()?;
+        Some((pt2(first.0, first.1), pt2(last.0, last.1)))
     }
 
     #[cfg(feature = "tile-rendering")]
@@ -120,13 +249,44 @@ impl KameaTile {
             }
         }
 
-        // Draw sigil path
-        if self.path_points.len() >= 2 {
-            let (r, g, b) = self.path_color;
-            let path_color = srgb(r, g, b);
+        // Excluded cells (×) and pinned waypoints (●, numbered in order)
+        for cell in &self.config.excluded {
+            let pos = rect.xy() + self.cell_point(*cell) * scale;
+            let size = 4.0;
+            let color = srgba(0.8, 0.2, 0.2, 0.6);
+            draw.line()
+                .start(pos + vec2(-size, -size))
+                .end(pos + vec2(size, size))
+                .weight(1.5)
+                .color(color);
+            draw.line()
+                .start(pos + vec2(size, -size))
+                .end(pos + vec2(-size, size))
+                .weight(1.5)
+                .color(color);
+        }
+        for (i, cell) in self.config.pinned.iter().enumerate() {
+            let pos = rect.xy() + self.cell_point(*cell) * scale;
+            draw.ellipse()
+                .xy(pos)
+                .radius(4.5)
+                .color(srgba(1.0, 0.75, 0.2, 0.9));
+            draw_text(
+                draw,
+                FontId::PlexMonoRegular,
+                &(i + 1).to_string(),
+                pos + vec2(9.0, 9.0),
+                9.0,
+                srgba(1.0, 0.75, 0.2, 0.9),
+                TextAlignment::Center,
+            );
+        }
 
-            for window in self.path_points.windows(2) {
-                let offset = vec2(rect.x(), rect.y());
+        // Draw sigil layers
+        let offset = vec2(rect.x(), rect.y());
+        for (points, style) in self.styled_strokes() {
+            let (r, g, b) = style.color;
+            for window in points.windows(2) {
                 let p0 = window[0] * scale + offset;
                 let p1 = window[1] * scale + offset;
 
@@ -135,7 +295,7 @@ impl KameaTile {
                     draw.line()
                         .start(p0)
                         .end(p1)
-                        .weight(self.config.stroke_weight * 3.0)
+                        .weight(style.stroke_weight * 3.0)
                         .color(srgba(r, g, b, self.glow_intensity));
                 }
 
@@ -143,39 +303,172 @@ impl KameaTile {
                 draw.line()
                     .start(p0)
                     .end(p1)
-                    .weight(self.config.stroke_weight)
-                    .color(path_color);
+                    .weight(style.stroke_weight)
+                    .color(srgb(r, g, b));
             }
+        }
 
+        if let Some((start, end)) = self.sigil_endpoints() {
             // Start marker - Circle ○
-            if let Some(start) = self.path_points.first() {
-                let offset = vec2(rect.x(), rect.y());
-                let pos = *start * scale + offset;
-                draw.ellipse()
-                    .xy(pos)
-                    .radius(8.0)
-                    .no_fill()
-                    .stroke_weight(2.0)
-                    .stroke(srgb(0.0, 1.0, 0.5));
-            }
+            draw.ellipse()
+                .xy(start * scale + offset)
+                .radius(8.0)
+                .no_fill()
+                .stroke_weight(2.0)
+                .stroke(srgb(0.0, 1.0, 0.5));
 
             // End marker - Cross ×
-            if let Some(end) = self.path_points.last() {
-                let offset = vec2(rect.x(), rect.y());
-                let pos = *end * scale + offset;
-                let size = 6.0;
-                draw.line()
-                    .start(pos + vec2(-size, -size))
-                    .end(pos + vec2(size, size))
-                    .weight(2.0)
-                    .color(srgb(1.0, 0.3, 0.3));
-                draw.line()
-                    .start(pos + vec2(size, -size))
-                    .end(pos + vec2(-size, size))
-                    .weight(2.0)
-                    .color(srgb(1.0, 0.3, 0.3));
+            let pos = end * scale + offset;
+            let size = 6.0;
+            draw.line()
+                .start(pos + vec2(-size, -size))
+                .end(pos + vec2(size, size))
+                .weight(2.0)
+                .color(srgb(1.0, 0.3, 0.3));
+            draw.line()
+                .start(pos + vec2(size, -size))
+                .end(pos + vec2(-size, size))
+                .weight(2.0)
+                .color(srgb(1.0, 0.3, 0.3));
+        }
+    }
+    /// The sigil as host GPU draw commands for a `width` x `height` texture.
+    ///
+    /// Mirrors [`Self::render_sigil`], converting from centred, y-up tile
+    /// coordinates to top-left texture pixels.
+    #[cfg(feature = "tile-rendering")]
+    pub fn sigil_draw_commands(&self, width: u32, height: u32) -> Vec<GpuDrawCommand> {
+        let (w, h) = (width as f32, height as f32);
+        let to_px = |p: Point2| (w * 0.5 + p.x, h * 0.5 - p.y);
+        let cmd = |kind: GpuDrawKind, x: f32, y: f32, cw: f32, ch: f32, stroke: f32, color| {
+            GpuDrawCommand {
+                kind: kind as u32,
+                x,
+                y,
+                w: cw,
+                h: ch,
+                stroke,
+                color,
             }
+        };
+        let line = |a: Point2, b: Point2, weight: f32, color| {
+            let ((ax, ay), (bx, by)) = (to_px(a), to_px(b));
+            cmd(GpuDrawKind::Line, ax, ay, bx, by, weight, color)
+        };
+
+        let mut commands = vec![cmd(
+            GpuDrawKind::Clear,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            [0.02, 0.02, 0.05, 0.95],
+        )];
+
+        let grid_size = self.config.grid_cols.max(self.config.grid_rows) as f32;
+        let scale = (w.min(h) * 0.8) / (grid_size * self.config.spacing);
+
+        if self.show_grid_dots {
+            for row in 0..self.config.grid_rows {
+                for col in 0..self.config.grid_cols {
+                    let x = (col as f32 - (self.config.grid_cols as f32 - 1.0) / 2.0)
+                        * self.config.spacing
+                        * scale;
+                    let y = (row as f32 - (self.config.grid_rows as f32 - 1.0) / 2.0)
+                        * self.config.spacing
+                        * scale;
+                    let (px, py) = to_px(pt2(x, y));
+                    commands.push(cmd(
+                        GpuDrawKind::Ellipse,
+                        px,
+                        py,
+                        3.0,
+                        3.0,
+                        0.0,
+                        [0.2, 0.3, 0.4, 0.5],
+                    ));
+                }
+            }
+        }
+
+        for cell in &self.config.excluded {
+            let pos = self.cell_point(*cell) * scale;
+            let size = 4.0;
+            let color = [0.8, 0.2, 0.2, 0.6];
+            commands.push(line(
+                pos + vec2(-size, -size),
+                pos + vec2(size, size),
+                1.5,
+                color,
+            ));
+            commands.push(line(
+                pos + vec2(size, -size),
+                pos + vec2(-size, size),
+                1.5,
+                color,
+            ));
+        }
+        for cell in &self.config.pinned {
+            let (px, py) = to_px(self.cell_point(*cell) * scale);
+            commands.push(cmd(
+                GpuDrawKind::Ellipse,
+                px,
+                py,
+                4.5,
+                4.5,
+                0.0,
+                [1.0, 0.75, 0.2, 0.9],
+            ));
         }
+
+        for (points, style) in self.styled_strokes() {
+            let (r, g, b) = style.color;
+            for window in points.windows(2) {
+                let p0 = window[0] * scale;
+                let p1 = window[1] * scale;
+                if self.glow_intensity > 0.0 {
+                    commands.push(line(
+                        p0,
+                        p1,
+                        style.stroke_weight * 3.0,
+                        [r, g, b, self.glow_intensity],
+                    ));
+                }
+                commands.push(line(p0, p1, style.stroke_weight, [r, g, b, 1.0]));
+            }
+        }
+
+        if let Some((start, end)) = self.sigil_endpoints() {
+            let (px, py) = to_px(start * scale);
+            commands.push(cmd(
+                GpuDrawKind::Ellipse,
+                px,
+                py,
+                8.0,
+                8.0,
+                2.0,
+                [0.0, 1.0, 0.5, 1.0],
+            ));
+
+            let pos = end * scale;
+            let size = 6.0;
+            let red = [1.0, 0.3, 0.3, 1.0];
+            commands.push(line(
+                pos + vec2(-size, -size),
+                pos + vec2(size, size),
+                2.0,
+                red,
+            ));
+            commands.push(line(
+                pos + vec2(size, -size),
+                pos + vec2(-size, size),
+                2.0,
+                red,
+            ));
+        }
+
+        commands
     }
 }
 
@@ -274,6 +567,33 @@ impl TileRenderer for KameaTile {
             TextAlignment::Center,
         );
 
+        draw_text(
+            draw,
+            FontId::PlexMonoRegular,
+            &format!(
+                "Layering: {} ({} layer{})",
+                self.config.layer_mode.as_str(),
+                self.layers.len(),
+                if self.layers.len() == 1 { "" } else { "s" }
+            ),
+            pt2(rect.x(), rect.top() - 72.0),
+            11.0,
+            srgba(0.4, 0.4, 0.4, 1.0),
+            TextAlignment::Center,
+        );
+
+        if let Some(status) = &self.plot_status {
+            draw_text(
+                draw,
+                FontId::PlexMonoRegular,
+                status,
+                pt2(rect.x(), rect.top() - 89.0),
+                11.0,
+                srgba(0.4, 0.6, 0.4, 1.0),
+                TextAlignment::Center,
+            );
+        }
+
         // Large sigil preview
         let preview_rect = Rect::from_x_y_w_h(
             rect.x() + rect.w() * 0.15,
@@ -290,13 +610,56 @@ impl TileRenderer for KameaTile {
             .stroke(srgba(0.2, 0.3, 0.3, 1.0))
             .stroke_weight(1.0);
 
-        self.render_sigil(draw, preview_rect.pad(5.0));
+        let sigil_rect = preview_rect.pad(5.0);
+        self.render_sigil(draw, sigil_rect);
+
+        // Edit cursor
+        let grid_size = self.config.grid_cols.max(self.config.grid_rows) as f32;
+        let scale = (sigil_rect.w().min(sigil_rect.h()) * 0.8) / (grid_size * self.config.spacing);
+        let cell = self.config.spacing * scale;
+        draw.rect()
+            .xy(sigil_rect.xy() + self.cell_point(self.cursor) * scale)
+            .wh(vec2(cell * 0.6, cell * 0.6))
+            .no_fill()
+            .stroke(srgba(1.0, 1.0, 1.0, 0.8))
+            .stroke_weight(1.0);
+        draw_text(
+            draw,
+            FontId::PlexMonoRegular,
+            &format!(
+                "Cursor {},{}  ·  {} pinned, {} excluded  ·  arrows move, P pin, X exclude, C clear",
+                self.cursor.0 + 1,
+                self.cursor.1 + 1,
+                self.config.pinned.len(),
+                self.config.excluded.len()
+            ),
+            pt2(rect.x(), rect.bottom() + 20.0),
+            10.0,
+            srgba(0.4, 0.4, 0.4, 1.0),
+            TextAlignment::Center,
+        );
 
         // Egui controls removed - migrated to Settings Modal
 
         false
     }
 
+    fn handle_key(&mut self, key: Key, _ctrl: bool, _shift: bool) -> bool {
+        let (cols, rows) = (self.config.grid_cols, self.config.grid_rows);
+        let (col, row) = self.cursor;
+        match key {
+            Key::Left => self.cursor = (col.saturating_sub(1), row),
+            Key::Right => self.cursor = ((col + 1).min(cols - 1), row),
+            Key::Up => self.cursor = (col, (row + 1).min(rows - 1)),
+            Key::Down => self.cursor = (col, row.saturating_sub(1)),
+            Key::P => self.toggle_pin(),
+            Key::X => self.toggle_exclude(),
+            Key::C => return self.clear_constraints(),
+            _ => return false,
+        }
+        true
+    }
+
     fn settings_schema(&self) -> Option<serde_json::Value> {
         Some(serde_json::json!({
             "type": "object",
@@ -332,18 +695,82 @@ impl TileRenderer for KameaTile {
                     "items": { "type": "number" },
                     "default": [0.0, 1.0, 1.0],
                     "title": "Path Color (RGB)"
+                },
+                "layer_mode": {
+                    "type": "string",
+                    "enum": ["single", "overlay", "union", "rotate"],
+                    "default": "single",
+                    "title": "Multi-word Layering"
+                },
+                "layer_styles": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "color": { "type": "array", "items": { "type": "number" } },
+                            "stroke_weight": { "type": "number", "minimum": 0.5, "maximum": 8.0 }
+                        }
+                    },
+                    "default": [],
+                    "title": "Per-word Styles (cycled; empty uses the path style)"
+                },
+                "pinned": {
+                    "type": "array",
+                    "items": { "type": "array", "items": { "type": "integer" } },
+                    "default": [],
+                    "title": "Pinned Waypoints ([col, row], in order)"
+                },
+                "excluded": {
+                    "type": "array",
+                    "items": { "type": "array", "items": { "type": "integer" } },
+                    "default": [],
+                    "title": "Excluded Cells ([col, row])"
+                },
+                "plot_format": {
+                    "type": "string",
+                    "enum": ["gcode", "hpgl", "svg"],
+                    "default": "gcode",
+                    "title": "Plot Export Format"
+                },
+                "plot_dir": {
+                    "type": "string",
+                    "default": "",
+                    "title": "Plot Export Directory"
+                },
+                "plot": {
+                    "type": "object",
+                    "title": "Plotter",
+                    "properties": {
+                        "width_mm": { "type": "number", "default": 150.0, "title": "Width (mm)" },
+                        "height_mm": { "type": "number", "default": 150.0, "title": "Height (mm)" },
+                        "margin_mm": { "type": "number", "default": 10.0, "title": "Margin (mm)" },
+                        "optimize_travel": { "type": "boolean", "default": true, "title": "Optimize Pen-up Travel" },
+                        "draw_feed": { "type": "number", "default": 1500.0, "title": "Draw Feed (mm/min)" },
+                        "travel_feed": { "type": "number", "default": 4000.0, "title": "Travel Feed (mm/min)" },
+                        "pen_up": { "type": "integer", "default": 30, "title": "Pen Up Servo (M3 S)" },
+                        "pen_down": { "type": "integer", "default": 60, "title": "Pen Down Servo (M3 S)" }
+                    }
                 }
             }
         }))
     }
 
     fn apply_settings(&mut self, settings: &serde_json::Value) {
+        for (key, cells) in [
+            ("pinned", &mut self.config.pinned),
+            ("excluded", &mut self.config.excluded),
+        ] {
+            if let Some(value) = settings.get(key) {
+                if let Ok(parsed) = serde_json::from_value::<Vec<Cell>>(value.clone()) {
+                    if *cells != parsed {
+                        *cells = parsed;
+                        self.last_text_hash = [0u8; 32];
+                    }
+                }
+            }
+        }
         if let Some(size) = settings.get("grid_size").and_then(|v| v.as_i64()) {
-            let size = (size as usize).clamp(3, 9);
-            self.config.grid_rows = size;
-            self.config.grid_cols = size;
-            // Force regeneration
-            self.last_text_hash = [0u8; 32];
+            self.set_grid_size((size as usize).clamp(3, 9));
         }
         if let Some(weight) = settings.get("stroke_weight").and_then(|v| v.as_f64()) {
             self.config.stroke_weight = (weight as f32).clamp(0.5, 8.0);
@@ -364,6 +791,43 @@ impl TileRenderer for KameaTile {
                 );
             }
         }
+        if let Some(mode) = settings
+            .get("layer_mode")
+            .and_then(|v| v.as_str())
+            .and_then(LayerMode::parse)
+        {
+            if mode != self.config.layer_mode {
+                self.config.layer_mode = mode;
+                self.last_text_hash = [0u8; 32];
+            }
+        }
+        if let Some(styles) = settings.get("layer_styles") {
+            if let Ok(styles) = serde_json::from_value::<Vec<LayerStyle>>(styles.clone()) {
+                self.config.layer_styles = styles
+                    .into_iter()
+                    .map(|style| LayerStyle {
+                        stroke_weight: style.stroke_weight.clamp(0.5, 8.0),
+                        ..style
+                    })
+                    .collect();
+            }
+        }
+        if let Some(format) = settings
+            .get("plot_format")
+            .and_then(|v| v.as_str())
+            .and_then(PlotFormat::parse)
+        {
+            self.plot_format = format;
+        }
+        if let Some(dir) = settings.get("plot_dir").and_then(|v| v.as_str()) {
+            self.plot_dir = dir.to_string();
+        }
+        if let Some(config) = settings.get("plot") {
+            match serde_json::from_value::<PlotConfig>(config.clone()) {
+                Ok(config) => self.plot_config = config,
+                Err(e) => log::warn!("Kamea: invalid plot settings, keeping current: {}", e),
+            }
+        }
     }
 
     fn get_settings(&self) -> serde_json::Value {
@@ -372,7 +836,14 @@ impl TileRenderer for KameaTile {
             "stroke_weight": self.config.stroke_weight,
             "glow_intensity": self.glow_intensity,
             "show_grid_dots": self.show_grid_dots,
-            "path_color": [self.path_color.0, self.path_color.1, self.path_color.2]
+            "path_color": [self.path_color.0, self.path_color.1, self.path_color.2],
+            "layer_mode": self.config.layer_mode,
+            "layer_styles": self.config.layer_styles,
+            "pinned": self.config.pinned,
+            "excluded": self.config.excluded,
+            "plot_format": self.plot_format,
+            "plot_dir": self.plot_dir,
+            "plot": self.plot_config
         })
     }
 
@@ -382,6 +853,9 @@ impl TileRenderer for KameaTile {
             BindableAction::new("increase_grid", "Increase Grid Size", false),
             BindableAction::new("decrease_grid", "Decrease Grid Size", false),
             BindableAction::new("regenerate", "Regenerate Sigil", false),
+            BindableAction::new("cycle_layer_mode", "Cycle Word Layering", false),
+            BindableAction::new("clear_constraints", "Clear Pins and Exclusions", false),
+            BindableAction::new("export_plot", "Export for Plotter", false),
         ]
     }
 
@@ -396,9 +870,7 @@ impl TileRenderer for KameaTile {
             }
             "increase_grid" => {
                 if self.config.grid_rows < 9 {
-                    self.config.grid_rows += 1;
-                    self.config.grid_cols += 1;
-                    self.last_text_hash = [0u8; 32]; // Force regeneration
+                    self.set_grid_size(self.config.grid_rows + 1);
                     true
                 } else {
                     false
@@ -406,9 +878,7 @@ impl TileRenderer for KameaTile {
             }
             "decrease_grid" => {
                 if self.config.grid_rows > 3 {
-                    self.config.grid_rows -= 1;
-                    self.config.grid_cols -= 1;
-                    self.last_text_hash = [0u8; 32]; // Force regeneration
+                    self.set_grid_size(self.config.grid_rows - 1);
                     true
                 } else {
                     false
@@ -418,6 +888,24 @@ impl TileRenderer for KameaTile {
                 self.last_text_hash = [0u8; 32]; // Force regeneration
                 true
             }
+            "clear_constraints" => self.clear_constraints(),
+            "export_plot" => {
+                self.plot_status = Some(match self.export_plot() {
+                    Ok(path) => format!("Exported {}", path.display()),
+                    Err(e) => format!("Export failed: {}", e),
+                });
+                true
+            }
+            "cycle_layer_mode" => {
+                let modes = LayerMode::ALL;
+                let current = modes
+                    .iter()
+                    .position(|m| *m == self.config.layer_mode)
+                    .unwrap_or(0);
+                self.config.layer_mode = modes[(current + 1) % modes.len()];
+                self.last_text_hash = [0u8; 32]; // Force regeneration
+                true
+            }
             _ => false,
         }
     }
@@ -0,0 +1,189 @@
+//! Offline geocoding against a GeoNames-style gazetteer.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+const BUILTIN: &str = include_str!("../data/cities.tsv");
+
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Place {
+    pub name: String,
+    /// ISO 3166-1 alpha-2 country code
+    pub country: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub population: u64,
+}
+
+impl Place {
+    pub fn label(&self) -> String {
+        format!("{}, {}", self.name, self.country)
+    }
+}
+
+/// Great-circle distance between two points, in kilometres.
+pub fn distance_km(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let dphi = (lat2 - lat1).to_radians();
+    let dlambda = (lon2 - lon1).to_radians();
+    let a = (dphi / 2.0).sin().powi(2) + phi1.cos() * phi2.cos() * (dlambda / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+}
+
+/// Parse a literal `lat, lon` (or `lat lon`) pair.
+pub fn parse_coordinates(query: &str) -> Option<(f64, f64)> {
+    let mut parts = query
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|p| !p.is_empty());
+    let lat: f64 = parts.next()?.parse().ok()?;
+    let lon: f64 = parts.next()?.parse().ok()?;
+    if parts.next().is_some() || !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon)
+    {
+        return None;
+    }
+    Some((lat, lon))
+}
+
+/// Place name index. Ships with a small built-in city list; a full GeoNames
+/// dump (`cities500.txt` etc.) can be loaded for wider coverage.
+#[derive(Debug, Clone, Default)]
+pub struct Gazetteer {
+    places: Vec<Place>,
+}
+
+impl Gazetteer {
+    pub fn builtin() -> Self {
+        let places = BUILTIN
+            .lines()
+            .filter(|line| !line.starts_with('#') && !line.trim().is_empty())
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split('\t').collect();
+                Some(Place {
+                    name: fields.first()?.to_string(),
+                    country: fields.get(1)?.to_string(),
+                    latitude: fields.get(2)?.parse().ok()?,
+                    longitude: fields.get(3)?.parse().ok()?,
+                    population: fields.get(4)?.parse().ok()?,
+                })
+            })
+            .collect();
+        Self { places }
+    }
+
+    /// Load a GeoNames `cities*.txt` dump (tab-separated, 19 columns).
+    pub fn load_geonames(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("reading gazetteer {}", path.display()))?;
+        let places: Vec<Place> = text.lines().filter_map(parse_geonames_line).collect();
+        anyhow::ensure!(!places.is_empty(), "no places in {}", path.display());
+        Ok(Self { places })
+    }
+
+    pub fn len(&self) -> usize {
+        self.places.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.places.is_empty()
+    }
+
+    /// Resolve `Name` or `Name, CC` to the most populous match. Literal
+    /// coordinates resolve to the nearest known place, keeping the coordinates.
+    pub fn resolve(&self, query: &str) -> Option<Place> {
+        if let Some((latitude, longitude)) = parse_coordinates(query) {
+            let name = self
+                .nearest(latitude, longitude)
+                .map(|p| p.name.clone())
+                .unwrap_or_else(|| format!("{:.4}, {:.4}", latitude, longitude));
+            return Some(Place {
+                name,
+                country: String::new(),
+                latitude,
+                longitude,
+                population: 0,
+            });
+        }
+
+        let (name, country) = match query.rsplit_once(',') {
+            Some((name, cc)) if cc.trim().len() == 2 => (name.trim(), Some(cc.trim())),
+            _ => (query.trim(), None),
+        };
+        self.places
+            .iter()
+            .filter(|p| p.name.eq_ignore_ascii_case(name))
+            .filter(|p| country.is_none_or(|cc| p.country.eq_ignore_ascii_case(cc)))
+            .max_by_key(|p| p.population)
+            .cloned()
+    }
+
+    pub fn nearest(&self, latitude: f64, longitude: f64) -> Option<&Place> {
+        self.places.iter().min_by(|a, b| {
+            let da = distance_km(latitude, longitude, a.latitude, a.longitude);
+            let db = distance_km(latitude, longitude, b.latitude, b.longitude);
+            da.total_cmp(&db)
+        })
+    }
+}
+
+fn parse_geonames_line(line: &str) -> Option<Place> {
+    let fields: Vec<&str> = line.split('\t').collect();
+    if fields.len() < 15 {
+        return None;
+    }
+    // Prefer the ASCII name so queries work without diacritics
+    let name = if fields[2].is_empty() {
+        fields[1]
+    } else {
+        fields[2]
+    };
+    Some(Place {
+        name: name.to_string(),
+        country: fields[8].to_string(),
+        latitude: fields[4].parse().ok()?,
+        longitude: fields[5].parse().ok()?,
+        population: fields[14].parse().unwrap_or(0),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_names_countries_and_coordinates() {
+        let gazetteer = Gazetteer::builtin();
+        assert!(gazetteer.len() > 50);
+
+        assert_eq!(gazetteer.resolve("london").unwrap().country, "GB");
+        assert_eq!(gazetteer.resolve("London, CA").unwrap().country, "CA");
+        assert!(gazetteer.resolve("Atlantis").is_none());
+
+        let place = gazetteer.resolve("48.86, 2.35").unwrap();
+        assert_eq!(place.name, "Paris");
+        assert_eq!((place.latitude, place.longitude), (48.86, 2.35));
+        assert!(parse_coordinates("95, 0").is_none());
+    }
+
+    #[test]
+    fn parses_geonames_rows() {
+        let row = "2643743\tLondon\tLondon\tLondres,Londra\t51.50853\t-0.12574\tP\tPPLC\tGB\t\tENG\tGLA\t\t\t8961989\t\t25\tEurope/London\t2023-01-12";
+        let place = parse_geonames_line(row).unwrap();
+        assert_eq!(place.label(), "London, GB");
+        assert_eq!(place.population, 8961989);
+        assert!(parse_geonames_line("too\tshort").is_none());
+
+        let london = Gazetteer::builtin().resolve("London").unwrap();
+        let paris = Gazetteer::builtin().resolve("Paris").unwrap();
+        let d = distance_km(
+            london.latitude,
+            london.longitude,
+            paris.latitude,
+            paris.longitude,
+        );
+        assert!((d - 344.0).abs() < 5.0, "{}", d);
+    }
+}
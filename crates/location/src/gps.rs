@@ -0,0 +1,84 @@
+//! GPS fixes from a gpsd daemon (JSON protocol over TCP).
+//!
+//! Only gpsd is supported; CoreLocation has no binding here, so macOS users
+//! need gpsd (or a fixed place in the settings).
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_GPSD_ADDR: &str = "127.0.0.1:2947";
+
+const WATCH: &[u8] = b"?WATCH={\"enable\":true,\"json\":true};\n";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GpsFix {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub altitude: Option<f64>,
+    /// UTC time of the fix as reported by gpsd
+    pub time: Option<String>,
+}
+
+/// Parse one gpsd report; only `TPV` reports with a 2D or 3D fix count.
+pub fn parse_tpv(line: &str) -> Option<GpsFix> {
+    let report: serde_json::Value = serde_json::from_str(line).ok()?;
+    if report["class"] != "TPV" || report["mode"].as_u64().unwrap_or(0) < 2 {
+        return None;
+    }
+    Some(GpsFix {
+        latitude: report["lat"].as_f64()?,
+        longitude: report["lon"].as_f64()?,
+        altitude: report["altMSL"].as_f64().or_else(|| report["alt"].as_f64()),
+        time: report["time"].as_str().map(str::to_string),
+    })
+}
+
+/// Blocking one-shot read of the current fix.
+pub fn read_fix(addr: &str, timeout: Duration) -> Result<GpsFix> {
+    let socket = addr
+        .to_socket_addrs()
+        .with_context(|| format!("resolving gpsd address {}", addr))?
+        .next()
+        .ok_or_else(|| anyhow!("no address for {}", addr))?;
+    let mut stream = TcpStream::connect_timeout(&socket, timeout)
+        .with_context(|| format!("connecting to gpsd at {}", addr))?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.write_all(WATCH)?;
+
+    let deadline = Instant::now() + timeout;
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    while Instant::now() < deadline {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        if let Some(fix) = parse_tpv(&line) {
+            return Ok(fix);
+        }
+    }
+    Err(anyhow!("no GPS fix from {} within {:?}", addr, timeout))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_tpv_reports_with_a_fix_parse() {
+        let fix = parse_tpv(
+            r#"{"class":"TPV","device":"/dev/ttyACM0","mode":3,"time":"2024-03-20T12:00:00.000Z","lat":51.4779,"lon":-0.0015,"altMSL":46.2}"#,
+        )
+        .unwrap();
+        assert_eq!((fix.latitude, fix.longitude), (51.4779, -0.0015));
+        assert_eq!(fix.altitude, Some(46.2));
+
+        assert!(parse_tpv(r#"{"class":"TPV","mode":1}"#).is_none());
+        assert!(parse_tpv(r#"{"class":"SKY","satellites":[]}"#).is_none());
+        assert!(parse_tpv(r#"{"class":"VERSION","release":"3.25"}"#).is_none());
+    }
+}
@@ -0,0 +1,14 @@
+//! Location services: the observer location for ephemeris and weather.
+//!
+//! Resolves place names against an offline GeoNames gazetteer and optionally
+//! follows a gpsd fix, emitting `location_changed` intents as it moves.
+
+pub mod geocode;
+pub mod gps;
+mod source;
+
+pub use geocode::{Gazetteer, Place};
+pub use gps::GpsFix;
+pub use source::{
+    location_intent, Location, LocationConfig, LocationOrigin, LocationSource, LOCATION_CHANGED,
+};
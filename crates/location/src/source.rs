@@ -0,0 +1,390 @@
+use std::collections::VecDeque;
+use std::path::Path;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+use crate::geocode::{distance_km, Gazetteer};
+use crate::gps::{self, DEFAULT_GPSD_ADDR};
+use magnolia_core::{DataType, ModuleSchema, Port, PortDirection, Signal, Source};
+
+const TICK: Duration = Duration::from_secs(1);
+const GPS_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub const LOCATION_CHANGED: &str = "location_changed";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LocationConfig {
+    /// Place name (`Name` or `Name, CC`) or `lat, lon`; overrides the coordinates
+    pub place: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// GeoNames dump to use instead of the built-in city list
+    pub gazetteer_path: Option<String>,
+    /// Follow the fix from gpsd
+    pub gps: bool,
+    pub gpsd_addr: String,
+    pub gps_interval_secs: u64,
+    /// Ignore GPS movement smaller than this
+    pub min_change_km: f64,
+}
+
+impl Default for LocationConfig {
+    fn default() -> Self {
+        Self {
+            place: String::new(),
+            // Greenwich, matching the clock and astro defaults
+            latitude: 51.48,
+            longitude: 0.0,
+            gazetteer_path: None,
+            gps: false,
+            gpsd_addr: DEFAULT_GPSD_ADDR.to_string(),
+            gps_interval_secs: 60,
+            min_change_km: 1.0,
+        }
+    }
+}
+
+impl LocationConfig {
+    pub fn schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "place": {
+                    "type": "string",
+                    "title": "Place",
+                    "default": ""
+                },
+                "latitude": {
+                    "type": "number",
+                    "title": "Latitude",
+                    "minimum": -90.0,
+                    "maximum": 90.0,
+                    "default": 51.48
+                },
+                "longitude": {
+                    "type": "number",
+                    "title": "Longitude",
+                    "minimum": -180.0,
+                    "maximum": 180.0,
+                    "default": 0.0
+                },
+                "gps": {
+                    "type": "boolean",
+                    "title": "Use GPS (gpsd)",
+                    "default": false
+                },
+                "gpsd_addr": {
+                    "type": "string",
+                    "title": "gpsd Address",
+                    "default": DEFAULT_GPSD_ADDR
+                },
+                "gps_interval_secs": {
+                    "type": "integer",
+                    "title": "GPS Interval (s)",
+                    "minimum": 5,
+                    "maximum": 3600,
+                    "default": 60
+                },
+                "min_change_km": {
+                    "type": "number",
+                    "title": "Minimum Change (km)",
+                    "minimum": 0.0,
+                    "default": 1.0
+                }
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LocationOrigin {
+    Settings,
+    Geocoded,
+    Gps,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Location {
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub origin: LocationOrigin,
+}
+
+/// `location_changed [lat, lon, name]`
+pub fn location_intent(location: &Location) -> Signal {
+    Signal::Intent {
+        action: LOCATION_CHANGED.to_string(),
+        parameters: vec![
+            format!("{:.5}", location.latitude),
+            format!("{:.5}", location.longitude),
+            location.name.clone(),
+        ],
+    }
+}
+
+type Listener = Box<dyn Fn(&Location) + Send + Sync>;
+
+/// Current observer location from settings, place-name queries or GPS.
+///
+/// Emits `location_changed` on `location_out` whenever the location moves,
+/// and calls the listener so ephemeris consumers can follow it directly.
+/// `query_in` takes a place name or `lat, lon` as `Text`, or as the
+/// parameters of a `set_location` intent.
+pub struct LocationSource {
+    id: String,
+    enabled: bool,
+    config: LocationConfig,
+    gazetteer: Gazetteer,
+    current: Option<Location>,
+    listener: Option<Listener>,
+    pending: VecDeque<Signal>,
+    next_gps: Option<Instant>,
+}
+
+impl LocationSource {
+    pub fn new(id: &str, config: LocationConfig) -> Self {
+        let gazetteer = config
+            .gazetteer_path
+            .as_deref()
+            .and_then(|path| match Gazetteer::load_geonames(Path::new(path)) {
+                Ok(gazetteer) => Some(gazetteer),
+                Err(e) => {
+                    log::warn!("LocationSource: {:#}; using built-in places", e);
+                    None
+                }
+            })
+            .unwrap_or_else(Gazetteer::builtin);
+        Self {
+            id: id.to_string(),
+            enabled: true,
+            config,
+            gazetteer,
+            current: None,
+            listener: None,
+            pending: VecDeque::new(),
+            next_gps: None,
+        }
+    }
+
+    pub fn with_listener(mut self, listener: impl Fn(&Location) + Send + Sync + 'static) -> Self {
+        self.listener = Some(Box::new(listener));
+        self
+    }
+
+    pub fn current(&self) -> Option<&Location> {
+        self.current.as_ref()
+    }
+
+    /// Resolve a place query and move there. Returns false if it is unknown.
+    pub fn set_place(&mut self, query: &str) -> bool {
+        match self.gazetteer.resolve(query) {
+            Some(place) => {
+                self.update(Location {
+                    name: place.name,
+                    latitude: place.latitude,
+                    longitude: place.longitude,
+                    origin: LocationOrigin::Geocoded,
+                });
+                true
+            }
+            None => {
+                log::warn!("LocationSource: unknown place {:?}", query);
+                false
+            }
+        }
+    }
+
+    fn update(&mut self, location: Location) {
+        if self.current.as_ref() == Some(&location) {
+            return;
+        }
+        if let Some(listener) = &self.listener {
+            listener(&location);
+        }
+        self.pending.push_back(location_intent(&location));
+        self.current = Some(location);
+    }
+
+    fn initialize(&mut self) {
+        let place = self.config.place.trim().to_string();
+        if place.is_empty() || !self.set_place(&place) {
+            let (latitude, longitude) = (self.config.latitude, self.config.longitude);
+            let name = self
+                .gazetteer
+                .nearest(latitude, longitude)
+                .map(|p| p.name.clone())
+                .unwrap_or_default();
+            self.update(Location {
+                name,
+                latitude,
+                longitude,
+                origin: LocationOrigin::Settings,
+            });
+        }
+    }
+
+    fn apply_fix(&mut self, fix: gps::GpsFix) {
+        let moved = self.current.as_ref().is_none_or(|current| {
+            distance_km(
+                current.latitude,
+                current.longitude,
+                fix.latitude,
+                fix.longitude,
+            ) >= self.config.min_change_km
+        });
+        if moved {
+            let name = self
+                .gazetteer
+                .nearest(fix.latitude, fix.longitude)
+                .map(|p| p.name.clone())
+                .unwrap_or_default();
+            self.update(Location {
+                name,
+                latitude: fix.latitude,
+                longitude: fix.longitude,
+                origin: LocationOrigin::Gps,
+            });
+        }
+    }
+
+    async fn poll_gps(&mut self) {
+        let addr = self.config.gpsd_addr.clone();
+        let result = tokio::task::spawn_blocking(move || gps::read_fix(&addr, GPS_TIMEOUT)).await;
+        match result {
+            Ok(Ok(fix)) => self.apply_fix(fix),
+            Ok(Err(e)) => log::debug!("LocationSource: {:#}", e),
+            Err(e) => log::error!("LocationSource: gpsd task failed: {}", e),
+        }
+        let interval = Duration::from_secs(self.config.gps_interval_secs.max(5));
+        self.next_gps = Some(Instant::now() + interval);
+    }
+}
+
+#[async_trait]
+impl Source for LocationSource {
+    fn name(&self) -> &str {
+        "Location"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "Location".to_string(),
+            description: "Observer location from place names or GPS".to_string(),
+            ports: vec![
+                Port {
+                    id: "location_out".to_string(),
+                    label: "Location".to_string(),
+                    data_type: DataType::Control,
+                    direction: PortDirection::Output,
+                },
+                Port {
+                    id: "query_in".to_string(),
+                    label: "Place Query".to_string(),
+                    data_type: DataType::Text,
+                    direction: PortDirection::Input,
+                },
+            ],
+            settings_schema: Some(LocationConfig::schema()),
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    async fn poll(&mut self) -> Option<Signal> {
+        loop {
+            if let Some(signal) = self.pending.pop_front() {
+                return Some(signal);
+            }
+            if self.enabled {
+                if self.current.is_none() {
+                    self.initialize();
+                    continue;
+                }
+                let due = self.next_gps.is_none_or(|next| Instant::now() >= next);
+                if self.config.gps && due {
+                    self.poll_gps().await;
+                    continue;
+                }
+            }
+            tokio::time::sleep(TICK).await;
+        }
+    }
+
+    fn handle_signal(&mut self, signal: Signal) {
+        match signal {
+            Signal::Text(query) => {
+                self.set_place(&query);
+            }
+            Signal::Intent { action, parameters } if action == "set_location" => {
+                self.set_place(&parameters.join(", "));
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test]
+    async fn starts_from_settings_then_follows_queries() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let log = seen.clone();
+        let mut source = LocationSource::new("location", LocationConfig::default())
+            .with_listener(move |location| log.lock().unwrap().push(location.name.clone()));
+
+        let Some(Signal::Intent { action, parameters }) = source.poll().await else {
+            panic!("expected the initial location");
+        };
+        assert_eq!(action, LOCATION_CHANGED);
+        assert_eq!(parameters, ["51.48000", "0.00000", "Greenwich"]);
+
+        source.handle_signal(Signal::Text("Kathmandu".to_string()));
+        source.handle_signal(Signal::Text("Atlantis".to_string()));
+        source.handle_signal(Signal::Intent {
+            action: "set_location".to_string(),
+            parameters: vec!["Kathmandu".to_string()],
+        });
+        let Some(Signal::Intent { parameters, .. }) = source.poll().await else {
+            panic!("expected a location change");
+        };
+        assert_eq!(parameters[2], "Kathmandu");
+        assert!(source.pending.is_empty(), "repeat query should not re-emit");
+        assert_eq!(source.current().unwrap().origin, LocationOrigin::Geocoded);
+        assert_eq!(*seen.lock().unwrap(), ["Greenwich", "Kathmandu"]);
+    }
+
+    #[test]
+    fn small_gps_moves_are_ignored() {
+        let mut source = LocationSource::new("location", LocationConfig::default());
+        let fix = |latitude, longitude| gps::GpsFix {
+            latitude,
+            longitude,
+            altitude: None,
+            time: None,
+        };
+        source.apply_fix(fix(51.5, -0.12));
+        source.apply_fix(fix(51.501, -0.121));
+        assert_eq!(source.pending.len(), 1);
+        assert_eq!(source.current().unwrap().latitude, 51.5);
+
+        source.apply_fix(fix(48.85, 2.35));
+        assert_eq!(source.current().unwrap().name, "Paris");
+        assert_eq!(source.current().unwrap().origin, LocationOrigin::Gps);
+    }
+}
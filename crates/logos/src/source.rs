@@ -40,6 +40,7 @@ impl Source for LogosSource {
                 },
             ],
             settings_schema: None,
+            docs: None,
         }
     }
     
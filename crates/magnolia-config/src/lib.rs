@@ -1,6 +1,7 @@
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use models::{ModelSpec, ModelStore};
 use serde::Deserialize;
 
 /// Matches the daemon behavior: try common relative paths for `configs/layout.toml`.
@@ -21,6 +22,8 @@ pub struct TranscriptionConfig {
     pub sources: Vec<TranscriptionSourceConfig>,
     pub reconciliation: ReconciliationConfig,
     pub context: TranscriptionContextConfig,
+    /// Model files sources name with `model`
+    pub models: Vec<ModelSpec>,
 }
 
 impl Default for TranscriptionConfig {
@@ -30,6 +33,7 @@ impl Default for TranscriptionConfig {
             sources: Vec::new(),
             reconciliation: ReconciliationConfig::default(),
             context: TranscriptionContextConfig::default(),
+            models: Vec::new(),
         }
     }
 }
@@ -39,6 +43,11 @@ impl TranscriptionConfig {
         self.sources.iter().find(|source| source.id == id)
     }
 
+    /// The configured models, with directories relative to `root`
+    pub fn model_store(&self, root: impl Into<PathBuf>) -> ModelStore {
+        ModelStore::new(root, self.models.clone())
+    }
+
     pub fn validate(&self) -> anyhow::Result<()> {
         anyhow::ensure!(
             self.version == 1,
@@ -61,6 +70,15 @@ impl TranscriptionConfig {
                 source.id
             );
         }
+        let mut names = std::collections::HashSet::new();
+        for model in &self.models {
+            model.validate()?;
+            anyhow::ensure!(
+                names.insert(&model.name),
+                "duplicate model name: {}",
+                model.name
+            );
+        }
         Ok(())
     }
 }
@@ -178,5 +196,13 @@ mod tests {
         let config = read_transcription_config_from(&path).unwrap();
         assert!(config.source("sherpa_local").unwrap().enabled);
         assert!(!config.source("openai_realtime").unwrap().enabled);
+
+        // The local source's model is one the store knows
+        let model = config.source("sherpa_local").unwrap().model.as_deref();
+        let store = config.model_store(".");
+        let spec = store.spec(model.unwrap()).unwrap();
+        assert!(["encoder", "decoder", "joiner", "tokens"]
+            .iter()
+            .all(|role| spec.files.contains_key(*role)));
     }
 }
@@ -1,7 +1,7 @@
 use std::os::raw::{c_char, c_void};
 
 /// Current ABI version - increment when making breaking changes
-pub const ABI_VERSION: u32 = 4;
+pub const ABI_VERSION: u32 = 7;
 
 /// Plugin manifest - describes the plugin's capabilities
 #[repr(C)]
@@ -56,6 +56,9 @@ pub struct ModuleSchemaAbi {
     pub ports_len: usize,
     /// Optional JSON Schema for settings (null if none)
     pub settings_schema: *const c_char,
+    /// Version of the settings `settings_schema` describes (ABI v7); bump it
+    /// when their shape changes and upgrade old ones in `migrate_settings`
+    pub settings_version: u32,
 }
 
 /// VTable for module runtime callbacks
@@ -80,6 +83,7 @@ pub struct ModuleRuntimeVTable {
 
     /// Consume incoming signal (sink behavior)
     /// Returns: 0 = no output, pointer = output signal buffer (caller must free)
+    /// The input buffer and its payload stay owned by the host.
     pub consume_signal: unsafe extern "C" fn(*mut c_void, *const SignalBuffer) -> *mut SignalBuffer,
 
     /// Apply settings as JSON string
@@ -87,6 +91,87 @@ pub struct ModuleRuntimeVTable {
 
     /// Destroy the module instance
     pub destroy: unsafe extern "C" fn(*mut c_void),
+
+    /// Release the payload of a buffer the plugin handed to the host (ABI v5)
+    ///
+    /// Called exactly once for every buffer written by `poll_signal` or returned
+    /// by `consume_signal`, after the host has copied the data out. Only the
+    /// payload behind `value.ptr` is released: the `SignalBuffer` from
+    /// `poll_signal` lives on the host stack, and the one from `consume_signal`
+    /// is freed by the host as a `Box<SignalBuffer>` afterwards.
+    pub free_buffer: unsafe extern "C" fn(*mut c_void, *mut SignalBuffer),
+
+    /// Hand the plugin the host GPU API (ABI v6), or null to revoke it
+    ///
+    /// The table is only valid for the duration of the call; plugins copy it.
+    /// The functions inside stay callable until `attach_gpu` is called with
+    /// null or the instance is destroyed.
+    pub attach_gpu: unsafe extern "C" fn(*mut c_void, *const HostGpuApi),
+
+    /// Upgrade settings saved at an older `settings_version` (ABI v7)
+    ///
+    /// Called with the version they were saved at and their JSON, before
+    /// they are applied. Returns the upgraded JSON, owned by the plugin and
+    /// valid until the next call into the instance, or null if they can't be
+    /// upgraded, in which case the host drops them and the plugin starts from
+    /// its defaults. `None` for plugins whose settings never changed shape.
+    pub migrate_settings:
+        Option<unsafe extern "C" fn(*mut c_void, u32, *const c_char) -> *const c_char>,
+}
+
+/// Draw primitive kinds for `HostGpuApi::submit_draw`
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GpuDrawKind {
+    /// Fill the whole texture with `color`
+    Clear = 0,
+    /// Filled rectangle at (`x`, `y`) with size (`w`, `h`)
+    Rect = 1,
+    /// Line from (`x`, `y`) to (`w`, `h`), `stroke` pixels wide
+    Line = 2,
+    /// Ellipse centred on (`x`, `y`) with radii (`w`, `h`); filled when `stroke` is 0
+    Ellipse = 3,
+}
+
+/// One draw primitive, in texture pixels with the origin at the top-left
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GpuDrawCommand {
+    pub kind: u32,
+    pub x: f32,
+    pub y: f32,
+    pub w: f32,
+    pub h: f32,
+    pub stroke: f32,
+    /// Straight (non-premultiplied) sRGB colour, 0.0 - 1.0
+    pub color: [f32; 4],
+}
+
+/// Host-owned GPU services offered to plugins (ABI v6)
+///
+/// Replaces passing raw `wgpu::Device`/`Queue` pointers: textures live on the
+/// host and plugins only ever see opaque handles, so nothing dangles when a
+/// plugin library is hot-reloaded. A handle of 0 is never valid. Textures are
+/// RGBA8 and are released automatically when the plugin instance goes away.
+/// To show a texture, emit a `SignalType::Texture` buffer with `value.gpu_id`
+/// set to the handle.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct HostGpuApi {
+    /// Opaque host context, passed back as the first argument
+    pub host: *mut c_void,
+
+    /// Create a `width` x `height` texture; returns 0 on failure
+    pub create_texture: unsafe extern "C" fn(*mut c_void, u32, u32) -> u64,
+
+    /// Replace the texture contents with `len` bytes of tightly packed RGBA8
+    pub upload_texture: unsafe extern "C" fn(*mut c_void, u64, *const u8, usize) -> bool,
+
+    /// Draw `len` commands into the texture and present the result
+    pub submit_draw: unsafe extern "C" fn(*mut c_void, u64, *const GpuDrawCommand, usize) -> bool,
+
+    /// Release a texture early
+    pub destroy_texture: unsafe extern "C" fn(*mut c_void, u64),
 }
 
 /// Signal types (matches core Signal enum)
@@ -101,7 +186,7 @@ pub enum SignalType {
     Control = 5,
     Computed = 6,
     Pulse = 7,
-    GpuContext = 8,
+    // 8 was GpuContext (raw device/queue pointers), removed in ABI v6 in favour of HostGpuApi
     Texture = 9,
 }
 
@@ -160,9 +245,18 @@ pub type PluginGetVTableFn = unsafe extern "C" fn() -> *const ModuleRuntimeVTabl
 /// Returns null if not supported
 pub type PluginGetSchemaFn = unsafe extern "C" fn() -> *const ModuleSchemaAbi;
 
+/// Get the module's documentation (optional, for the docs pane)
+///
+/// Returns NUL-terminated JSON with an `overview` string and `ports` and
+/// `settings` objects mapping port ids and setting names to text, valid for
+/// as long as the library stays loaded, or null if there is none
+pub type PluginGetDocsFn = unsafe extern "C" fn() -> *const c_char;
+
 /// Symbol names that plugins must export
 pub const PLUGIN_MANIFEST_SYMBOL: &[u8] = b"magnolia_plugin_manifest\0";
 pub const PLUGIN_CREATE_SYMBOL: &[u8] = b"magnolia_plugin_create\0";
 pub const PLUGIN_VTABLE_SYMBOL: &[u8] = b"magnolia_plugin_get_vtable\0";
 /// Optional schema export symbol
 pub const PLUGIN_SCHEMA_SYMBOL: &[u8] = b"magnolia_plugin_get_schema\0";
+/// Optional documentation export symbol
+pub const PLUGIN_DOCS_SYMBOL: &[u8] = b"magnolia_plugin_get_docs\0";
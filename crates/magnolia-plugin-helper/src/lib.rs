@@ -1,9 +1,11 @@
 pub use magnolia_plugin_abi;
 // Re-export common types for convenience
 pub use magnolia_plugin_abi::{
-    ABI_VERSION, ModuleRuntimeVTable, PluginManifest, SignalBuffer, SignalType, SignalValue,
+    ABI_VERSION, GpuDrawCommand, GpuDrawKind, HostGpuApi, ModuleRuntimeVTable, PluginManifest,
+    SignalBuffer, SignalType, SignalValue,
 };
 
+use std::ffi::CString;
 use std::os::raw::c_char;
 
 /// Macro to export the necessary C-ABI symbols for a Magnolia plugin.
@@ -47,6 +49,9 @@ macro_rules! export_plugin {
             consume_signal: _plugin_consume_signal,
             apply_settings: _plugin_apply_settings,
             destroy: _plugin_destroy,
+            free_buffer: _plugin_free_buffer,
+            attach_gpu: _plugin_attach_gpu,
+            migrate_settings: Some(_plugin_migrate_settings),
         };
 
         #[unsafe(no_mangle)]
@@ -87,6 +92,7 @@ macro_rules! export_plugin {
                         ports: std::ptr::null(), // Ports not supported via basic macro yet
                         ports_len: 0,
                         settings_schema: settings,
+                        settings_version: <$plugin_type>::settings_version(),
                     });
                 });
 
@@ -94,6 +100,18 @@ macro_rules! export_plugin {
             }
         }
 
+        // --- DOCS ---
+        #[unsafe(no_mangle)]
+        pub unsafe extern "C" fn magnolia_plugin_get_docs() -> *const std::os::raw::c_char {
+            static DOCS: std::sync::OnceLock<Option<std::ffi::CString>> =
+                std::sync::OnceLock::new();
+            DOCS.get_or_init(|| {
+                <$plugin_type>::docs().and_then(|docs| std::ffi::CString::new(docs).ok())
+            })
+            .as_ref()
+            .map_or(std::ptr::null(), |docs| docs.as_ptr())
+        }
+
         // --- TRAMPOLINES ---
 
         unsafe extern "C" fn _plugin_get_id(
@@ -157,6 +175,52 @@ macro_rules! export_plugin {
         unsafe extern "C" fn _plugin_destroy(instance: *mut std::os::raw::c_void) {
             let _ = Box::from_raw(instance as *mut $plugin_type);
         }
+
+        unsafe extern "C" fn _plugin_free_buffer(
+            instance: *mut std::os::raw::c_void,
+            buffer: *mut $crate::SignalBuffer,
+        ) {
+            if buffer.is_null() {
+                return;
+            }
+            let plugin = &mut *(instance as *mut $plugin_type);
+            plugin.free_buffer(&mut *buffer);
+        }
+
+        unsafe extern "C" fn _plugin_attach_gpu(
+            instance: *mut std::os::raw::c_void,
+            api: *const $crate::HostGpuApi,
+        ) {
+            let plugin = &mut *(instance as *mut $plugin_type);
+            plugin.attach_gpu($crate::GpuApi::from_raw(api));
+        }
+
+        unsafe extern "C" fn _plugin_migrate_settings(
+            _instance: *mut std::os::raw::c_void,
+            old_version: u32,
+            json: *const std::os::raw::c_char,
+        ) -> *const std::os::raw::c_char {
+            // Kept until the next migration on this thread; the host copies it out first
+            thread_local! {
+                static MIGRATED: std::cell::RefCell<std::ffi::CString> =
+                    std::cell::RefCell::new(std::ffi::CString::default());
+            }
+            if json.is_null() {
+                return std::ptr::null();
+            }
+            let Ok(json) = std::ffi::CStr::from_ptr(json).to_str() else {
+                return std::ptr::null();
+            };
+            let Some(migrated) = <$plugin_type>::migrate_settings(old_version, json)
+                .and_then(|migrated| std::ffi::CString::new(migrated).ok())
+            else {
+                return std::ptr::null();
+            };
+            MIGRATED.with(|slot| {
+                *slot.borrow_mut() = migrated;
+                slot.borrow().as_ptr()
+            })
+        }
     };
 }
 
@@ -177,9 +241,188 @@ pub trait MagnoliaPlugin: Default {
     fn poll_signal(&mut self, buffer: &mut SignalBuffer) -> bool;
     fn consume_signal(&mut self, input: &SignalBuffer) -> Option<SignalBuffer>;
 
+    /// Release a payload previously handed to the host. The default matches the
+    /// layouts produced by [`text_payload`], [`audio_payload`] and [`bytes_payload`];
+    /// override it if the plugin allocates payloads some other way.
+    fn free_buffer(&mut self, buffer: &mut SignalBuffer) {
+        unsafe { free_payload(buffer) }
+    }
+
+    /// Receive (or lose, on `None`) access to host GPU services.
+    fn attach_gpu(&mut self, _gpu: Option<GpuApi>) {}
+
+    /// JSON for the host's docs pane: an `overview`, plus `ports` and
+    /// `settings` objects mapping ids to what they do
+    fn docs() -> Option<String> {
+        None
+    }
+
     // Settings
     fn settings_schema() -> Option<String> {
         None
     }
     fn apply_settings(&mut self, _json: &str) {}
+
+    /// Bump when the settings change shape, and upgrade older ones in
+    /// [`migrate_settings`](Self::migrate_settings)
+    fn settings_version() -> u32 {
+        0
+    }
+
+    /// Upgrade settings saved at `old_version` to [`settings_version`](Self::settings_version).
+    /// `None` drops them and the plugin starts from its defaults.
+    fn migrate_settings(_old_version: u32, _json: &str) -> Option<String> {
+        None
+    }
+}
+
+// --- HOST GPU ---
+
+/// Plugin-side wrapper around the [`HostGpuApi`] table handed over by `attach_gpu`.
+///
+/// Textures are owned by the host; the handles returned here are opaque and
+/// are released automatically when the plugin instance is destroyed.
+#[derive(Clone, Copy)]
+pub struct GpuApi {
+    raw: HostGpuApi,
+}
+
+// The host context is internally synchronised.
+unsafe impl Send for GpuApi {}
+unsafe impl Sync for GpuApi {}
+
+impl GpuApi {
+    /// Copy the table out of the pointer passed to `attach_gpu`.
+    ///
+    /// # Safety
+    /// `api` must be null or point to a valid table for the duration of the call.
+    pub unsafe fn from_raw(api: *const HostGpuApi) -> Option<Self> {
+        if api.is_null() {
+            None
+        } else {
+            Some(Self {
+                raw: unsafe { *api },
+            })
+        }
+    }
+
+    pub fn create_texture(&self, width: u32, height: u32) -> Option<u64> {
+        match unsafe { (self.raw.create_texture)(self.raw.host, width, height) } {
+            0 => None,
+            handle => Some(handle),
+        }
+    }
+
+    /// Replace the texture contents with tightly packed RGBA8 pixels.
+    pub fn upload(&self, texture: u64, rgba: &[u8]) -> bool {
+        unsafe { (self.raw.upload_texture)(self.raw.host, texture, rgba.as_ptr(), rgba.len()) }
+    }
+
+    pub fn draw(&self, texture: u64, commands: &[GpuDrawCommand]) -> bool {
+        unsafe { (self.raw.submit_draw)(self.raw.host, texture, commands.as_ptr(), commands.len()) }
+    }
+
+    pub fn destroy_texture(&self, texture: u64) {
+        unsafe { (self.raw.destroy_texture)(self.raw.host, texture) }
+    }
+}
+
+/// Announce a host texture to the compositor.
+pub fn texture_payload(buffer: &mut SignalBuffer, texture: u64) {
+    buffer.signal_type = SignalType::Texture as u32;
+    buffer.value = SignalValue { gpu_id: texture };
+    buffer.size = 0;
+    buffer.param = 0;
+}
+
+// --- PAYLOAD HELPERS ---
+// Payloads leave the plugin as raw pointers and come back through `free_buffer`,
+// so these keep allocation and release on the same (plugin) allocator with a
+// layout that can be reconstructed from the buffer alone.
+
+/// Fill `buffer` with an owned, NUL-terminated text payload.
+pub fn text_payload(buffer: &mut SignalBuffer, text: &str) {
+    let cstring = CString::new(text.replace('\0', "")).unwrap_or_default();
+    buffer.signal_type = SignalType::Text as u32;
+    buffer.value = SignalValue {
+        ptr: cstring.into_raw() as *mut _,
+    };
+    buffer.size = 0;
+}
+
+/// Fill `buffer` with owned interleaved audio.
+pub fn audio_payload(
+    buffer: &mut SignalBuffer,
+    samples: Vec<f32>,
+    sample_rate: u32,
+    channels: u16,
+) {
+    let samples = samples.into_boxed_slice();
+    buffer.signal_type = SignalType::Audio as u32;
+    buffer.size = samples.len() as u64;
+    buffer.param = ((sample_rate as u64) << 32) | (channels as u64);
+    buffer.value = SignalValue {
+        ptr: Box::into_raw(samples) as *mut f32 as *mut _,
+    };
+}
+
+/// Fill `buffer` with an owned byte payload (e.g. JSON) of the given type.
+pub fn bytes_payload(buffer: &mut SignalBuffer, signal_type: SignalType, bytes: Vec<u8>) {
+    let bytes = bytes.into_boxed_slice();
+    buffer.signal_type = signal_type as u32;
+    buffer.size = bytes.len() as u64;
+    buffer.value = SignalValue {
+        ptr: Box::into_raw(bytes) as *mut u8 as *mut _,
+    };
+}
+
+/// Free a payload built by one of the helpers above and null out the pointer.
+///
+/// # Safety
+/// `buffer` must have been filled by [`text_payload`], [`audio_payload`] or
+/// [`bytes_payload`] and not released already.
+pub unsafe fn free_payload(buffer: &mut SignalBuffer) {
+    let ptr = unsafe { buffer.value.ptr };
+    if ptr.is_null() {
+        return;
+    }
+    let size = buffer.size as usize;
+    match buffer.signal_type {
+        t if t == SignalType::Text as u32 => drop(unsafe { CString::from_raw(ptr as *mut c_char) }),
+        t if t == SignalType::Audio as u32 => {
+            let samples = std::ptr::slice_from_raw_parts_mut(ptr as *mut f32, size);
+            drop(unsafe { Box::from_raw(samples) });
+        }
+        t if t == SignalType::Astrology as u32 || t == SignalType::Blob as u32 => {
+            let bytes = std::ptr::slice_from_raw_parts_mut(ptr as *mut u8, size);
+            drop(unsafe { Box::from_raw(bytes) });
+        }
+        // GPU handles and inline values are not allocations.
+        _ => return,
+    }
+    buffer.value = SignalValue {
+        ptr: std::ptr::null_mut(),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payload_helpers_round_trip_through_free() {
+        let mut text = SignalBuffer::empty();
+        text_payload(&mut text, "hello");
+        let mut audio = SignalBuffer::empty();
+        audio_payload(&mut audio, vec![0.5; 64], 48_000, 2);
+        assert_eq!(audio.size, 64);
+        assert_eq!(audio.param >> 32, 48_000);
+        let mut json = SignalBuffer::empty();
+        bytes_payload(&mut json, SignalType::Astrology, b"{}".to_vec());
+
+        for buffer in [&mut text, &mut audio, &mut json] {
+            unsafe { free_payload(buffer) };
+            assert!(unsafe { buffer.value.ptr }.is_null());
+        }
+    }
 }
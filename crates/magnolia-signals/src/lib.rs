@@ -2,7 +2,9 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
+pub mod midi;
 pub mod ring_buffer;
+pub use midi::MidiMessage;
 use ring_buffer::RingBufferReceiver;
 
 // ============================================================================
@@ -28,6 +30,8 @@ pub enum DataType {
     Numeric,
     /// Control signals (shutdown, reload, etc.)
     Control,
+    /// MIDI messages from controllers and instruments
+    Midi,
     /// Accepts any data type (universal transforms)
     Any,
 }
@@ -172,10 +176,15 @@ pub enum Signal {
         sample_rate: u32,
         channels: u16,
     },
-    /// Shared audio data (Arc-wrapped) - one allocation, many readers
-    /// Use this for large audio buffers to avoid copying overhead
+    /// Shared audio (Arc-wrapped) - one allocation, many readers.
+    /// The router promotes `Audio` to this when it fans out to several sinks
     #[serde(skip)]
-    SharedAudio(Arc<Vec<f32>>), // Simplified from core::AudioData for now
+    SharedAudio {
+        sample_rate: u32,
+        channels: u16,
+        timestamp_us: u64,
+        data: Arc<Vec<f32>>,
+    },
     /// Real-time audio stream handle (ring buffer for minimal latency)
     /// Contains receiver end - SPSC: only ONE module can consume this!
     #[serde(skip)]
@@ -189,26 +198,30 @@ pub enum Signal {
         // Let's define AudioFrame here.
         receiver: RingBufferReceiver<f32>,
     },
-    /// Shared blob data (Arc-wrapped) - one allocation, many readers
+    /// Shared blob data (Arc-wrapped) - one allocation, many readers.
+    /// The router promotes `Blob` to this when it fans out to several sinks
     #[serde(skip)]
-    SharedBlob(Arc<Vec<u8>>),
+    SharedBlob {
+        mime_type: String,
+        bytes: Arc<Vec<u8>>,
+    },
     /// A control signal for the system (e.g., "Shutdown", "Reload")
     Control(ControlSignal),
     /// Computed/Processed Data (Source, Content)
     Computed { source: String, content: String },
-    /// Pointer to WGPU Context types (Device, Queue) - Unsafe!
-    /// DEPRECATED: Use HostGpuApi instead of passing raw pointers
-    #[serde(skip)]
-    GpuContext {
-        device: usize, // cast to *const wgpu::Device
-        queue: usize,  // cast to *const wgpu::Queue
-    },
     /// GPU Texture Handle (for Compositor)
     #[serde(skip)]
     Texture {
         handle: GpuTextureHandle,
         start_time: f64, // Optional timestamp
     },
+    /// One MIDI message: the status byte and its data bytes (two for most
+    /// channel messages, the whole payload for SysEx)
+    Midi {
+        timestamp_us: u64,
+        status: u8,
+        data: Vec<u8>,
+    },
     /// Empty signal, used for heartbeat or triggers
     Pulse,
 }
@@ -230,6 +243,112 @@ impl Signal {
             _ => OverflowPolicy::LossSensitive,
         }
     }
+
+    /// The samples of `Audio` or `SharedAudio`, for readers that don't care which
+    pub fn audio(&self) -> Option<AudioView<'_>> {
+        match self {
+            Signal::Audio {
+                sample_rate,
+                channels,
+                timestamp_us,
+                data,
+            } => Some(AudioView {
+                sample_rate: *sample_rate,
+                channels: *channels,
+                timestamp_us: *timestamp_us,
+                data,
+            }),
+            Signal::SharedAudio {
+                sample_rate,
+                channels,
+                timestamp_us,
+                data,
+            } => Some(AudioView {
+                sample_rate: *sample_rate,
+                channels: *channels,
+                timestamp_us: *timestamp_us,
+                data: data.as_slice(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// `SharedAudio` as `Audio`, for modules that change the samples. They
+    /// are only copied while another holder still has them; anything else
+    /// comes back as it was.
+    pub fn into_owned_audio(self) -> Signal {
+        match self {
+            Signal::SharedAudio {
+                sample_rate,
+                channels,
+                timestamp_us,
+                data,
+            } => Signal::Audio {
+                sample_rate,
+                channels,
+                timestamp_us,
+                data: Arc::try_unwrap(data).unwrap_or_else(|shared| shared.as_ref().clone()),
+            },
+            signal => signal,
+        }
+    }
+
+    /// The bytes of `Blob` or `SharedBlob`, for readers that don't care which
+    pub fn blob(&self) -> Option<BlobView<'_>> {
+        match self {
+            Signal::Blob { mime_type, bytes } => Some(BlobView { mime_type, bytes }),
+            Signal::SharedBlob { mime_type, bytes } => Some(BlobView {
+                mime_type,
+                bytes: bytes.as_slice(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// `Blob` as `SharedBlob`, so clones share one buffer
+    pub fn into_shared_blob(self) -> Signal {
+        match self {
+            Signal::Blob { mime_type, bytes } => Signal::SharedBlob {
+                mime_type,
+                bytes: Arc::new(bytes),
+            },
+            signal => signal,
+        }
+    }
+
+    /// `Audio` as `SharedAudio`, so clones share one buffer
+    pub fn into_shared_audio(self) -> Signal {
+        match self {
+            Signal::Audio {
+                sample_rate,
+                channels,
+                timestamp_us,
+                data,
+            } => Signal::SharedAudio {
+                sample_rate,
+                channels,
+                timestamp_us,
+                data: Arc::new(data),
+            },
+            signal => signal,
+        }
+    }
+}
+
+/// Borrowed bytes from [`Signal::blob`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlobView<'a> {
+    pub mime_type: &'a str,
+    pub bytes: &'a [u8],
+}
+
+/// Borrowed audio from [`Signal::audio`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AudioView<'a> {
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub timestamp_us: u64,
+    pub data: &'a [f32],
 }
 
 impl Clone for Signal {
@@ -269,24 +388,42 @@ impl Clone for Signal {
                 sample_rate: *sample_rate,
                 channels: *channels,
             },
-            Signal::SharedAudio(data) => Signal::SharedAudio(Arc::clone(data)),
+            Signal::SharedAudio {
+                sample_rate,
+                channels,
+                timestamp_us,
+                data,
+            } => Signal::SharedAudio {
+                sample_rate: *sample_rate,
+                channels: *channels,
+                timestamp_us: *timestamp_us,
+                data: Arc::clone(data),
+            },
             Signal::AudioStream { .. } => {
                 panic!("Signal::AudioStream cannot be cloned (SPSC receiver)");
             }
-            Signal::SharedBlob(data) => Signal::SharedBlob(Arc::clone(data)),
+            Signal::SharedBlob { mime_type, bytes } => Signal::SharedBlob {
+                mime_type: mime_type.clone(),
+                bytes: Arc::clone(bytes),
+            },
             Signal::Control(signal) => Signal::Control(signal.clone()),
             Signal::Computed { source, content } => Signal::Computed {
                 source: source.clone(),
                 content: content.clone(),
             },
-            Signal::GpuContext { device, queue } => Signal::GpuContext {
-                device: *device,
-                queue: *queue,
-            },
             Signal::Texture { handle, start_time } => Signal::Texture {
                 handle: *handle,
                 start_time: *start_time,
             },
+            Signal::Midi {
+                timestamp_us,
+                status,
+                data,
+            } => Signal::Midi {
+                timestamp_us: *timestamp_us,
+                status: *status,
+                data: data.clone(),
+            },
             Signal::Pulse => Signal::Pulse,
         }
     }
@@ -0,0 +1,197 @@
+//! Reading [`Signal::Midi`] messages
+//!
+//! The signal keeps the raw bytes so nothing is lost on the way through the
+//! router; modules acting on notes or controllers decode them here.
+
+use crate::Signal;
+
+/// A decoded MIDI message. Channels are 1-16, as printed on hardware.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MidiMessage {
+    NoteOff {
+        channel: u8,
+        note: u8,
+        velocity: u8,
+    },
+    /// Never with velocity 0; that is sent as a note off
+    NoteOn {
+        channel: u8,
+        note: u8,
+        velocity: u8,
+    },
+    PolyPressure {
+        channel: u8,
+        note: u8,
+        pressure: u8,
+    },
+    ControlChange {
+        channel: u8,
+        controller: u8,
+        value: u8,
+    },
+    ProgramChange {
+        channel: u8,
+        program: u8,
+    },
+    ChannelPressure {
+        channel: u8,
+        pressure: u8,
+    },
+    /// -8192..=8191, centre 0
+    PitchBend {
+        channel: u8,
+        value: i16,
+    },
+    /// SysEx, timing and the other channel-less messages
+    System {
+        status: u8,
+    },
+}
+
+impl MidiMessage {
+    /// `None` when `status` isn't a status byte or the data is short
+    pub fn parse(status: u8, data: &[u8]) -> Option<Self> {
+        if status < 0x80 {
+            return None;
+        }
+        if status >= 0xF0 {
+            return Some(Self::System { status });
+        }
+        let channel = (status & 0x0F) + 1;
+        let byte = |i: usize| data.get(i).map(|b| b & 0x7F);
+        Some(match status & 0xF0 {
+            0x80 => Self::NoteOff {
+                channel,
+                note: byte(0)?,
+                velocity: byte(1)?,
+            },
+            0x90 => match (byte(0)?, byte(1)?) {
+                (note, 0) => Self::NoteOff {
+                    channel,
+                    note,
+                    velocity: 0,
+                },
+                (note, velocity) => Self::NoteOn {
+                    channel,
+                    note,
+                    velocity,
+                },
+            },
+            0xA0 => Self::PolyPressure {
+                channel,
+                note: byte(0)?,
+                pressure: byte(1)?,
+            },
+            0xB0 => Self::ControlChange {
+                channel,
+                controller: byte(0)?,
+                value: byte(1)?,
+            },
+            0xC0 => Self::ProgramChange {
+                channel,
+                program: byte(0)?,
+            },
+            0xD0 => Self::ChannelPressure {
+                channel,
+                pressure: byte(0)?,
+            },
+            _ => Self::PitchBend {
+                channel,
+                value: ((byte(1)? as i16) << 7 | byte(0)? as i16) - 8192,
+            },
+        })
+    }
+
+    /// 1-16, `None` for system messages
+    pub fn channel(&self) -> Option<u8> {
+        match *self {
+            Self::NoteOff { channel, .. }
+            | Self::NoteOn { channel, .. }
+            | Self::PolyPressure { channel, .. }
+            | Self::ControlChange { channel, .. }
+            | Self::ProgramChange { channel, .. }
+            | Self::ChannelPressure { channel, .. }
+            | Self::PitchBend { channel, .. } => Some(channel),
+            Self::System { .. } => None,
+        }
+    }
+}
+
+/// Clock, start/stop and active sensing: sent constantly by some gear
+pub fn is_realtime(status: u8) -> bool {
+    status >= 0xF8
+}
+
+impl Signal {
+    /// A `Midi` signal from one message as it came off the wire; `None`
+    /// when it doesn't start with a status byte
+    pub fn midi_from_bytes(timestamp_us: u64, bytes: &[u8]) -> Option<Signal> {
+        let (&status, data) = bytes.split_first()?;
+        (status >= 0x80).then(|| Signal::Midi {
+            timestamp_us,
+            status,
+            data: data.to_vec(),
+        })
+    }
+
+    /// The message a `Midi` signal carries
+    pub fn midi(&self) -> Option<MidiMessage> {
+        match self {
+            Signal::Midi { status, data, .. } => MidiMessage::parse(*status, data),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_channel_messages() {
+        let midi = |bytes: &[u8]| Signal::midi_from_bytes(0, bytes).and_then(|s| s.midi());
+        assert_eq!(
+            midi(&[0x92, 60, 100]),
+            Some(MidiMessage::NoteOn {
+                channel: 3,
+                note: 60,
+                velocity: 100
+            })
+        );
+        assert_eq!(
+            midi(&[0x90, 60, 0]),
+            Some(MidiMessage::NoteOff {
+                channel: 1,
+                note: 60,
+                velocity: 0
+            })
+        );
+        assert_eq!(
+            midi(&[0xBF, 7, 127]),
+            Some(MidiMessage::ControlChange {
+                channel: 16,
+                controller: 7,
+                value: 127
+            })
+        );
+        assert_eq!(
+            midi(&[0xE0, 0x00, 0x40]),
+            Some(MidiMessage::PitchBend {
+                channel: 1,
+                value: 0
+            })
+        );
+        assert_eq!(
+            midi(&[0xE0, 0x7F, 0x7F]).unwrap(),
+            MidiMessage::PitchBend {
+                channel: 1,
+                value: 8191
+            }
+        );
+        assert_eq!(midi(&[0xF8]), Some(MidiMessage::System { status: 0xF8 }));
+        assert!(is_realtime(0xF8) && !is_realtime(0xF0));
+        assert_eq!(midi(&[0x90, 60]), None);
+        assert!(Signal::midi_from_bytes(0, &[60, 100]).is_none());
+        assert_eq!(midi(&[0xC4, 12]).unwrap().channel(), Some(5));
+    }
+}
@@ -4,6 +4,27 @@
 
 #[cfg(feature = "tile-rendering")]
 use nannou::prelude::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static HIGH_CONTRAST: AtomicBool = AtomicBool::new(false);
+
+/// Switch the high-contrast variant for everything drawn from now on
+pub fn set_high_contrast(on: bool) {
+    HIGH_CONTRAST.store(on, Ordering::Relaxed);
+}
+
+pub fn high_contrast() -> bool {
+    HIGH_CONTRAST.load(Ordering::Relaxed)
+}
+
+/// Brightness of grey text and lines, 0 to 1; full white in high contrast
+pub fn grey_level(level: f32) -> f32 {
+    if high_contrast() {
+        1.0
+    } else {
+        level
+    }
+}
 
 // =============================================================================
 // COLORS
@@ -21,7 +42,11 @@ pub fn dark_bg() -> (u8, u8, u8) {
 
 /// Muted stroke color for unselected elements
 pub fn muted_stroke() -> (u8, u8, u8) {
-    (60, 60, 70)
+    if high_contrast() {
+        (255, 255, 255)
+    } else {
+        (60, 60, 70)
+    }
 }
 
 /// Warning/alert color
@@ -55,7 +80,11 @@ pub fn selection_color(alpha: f32) -> LinSrgba {
 /// Get muted color with optional alpha  
 #[cfg(feature = "tile-rendering")]
 pub fn muted_color(alpha: f32) -> LinSrgba {
-    LinSrgba::new(0.25, 0.25, 0.28, alpha)
+    if high_contrast() {
+        LinSrgba::new(1.0, 1.0, 1.0, alpha)
+    } else {
+        LinSrgba::new(0.25, 0.25, 0.28, alpha)
+    }
 }
 
 /// Get the appropriate stroke weight for an element based on selection state
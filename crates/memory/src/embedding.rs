@@ -0,0 +1,129 @@
+//! Text embeddings for recall.
+//!
+//! The built-in [`HashingEmbedder`] needs no model files: words and word
+//! pairs are hashed into a fixed number of buckets, which is enough to find
+//! earlier exchanges that share vocabulary with the current one. It is not
+//! a language model and knows nothing of meaning, so "car" and "vehicle"
+//! don't match. Anything implementing [`Embedder`] (an ONNX sentence
+//! encoder, say) can replace it for recall by meaning.
+
+/// Maps text to a fixed-length vector; similar texts should have a high
+/// [`cosine`] similarity
+pub trait Embedder: Send + Sync {
+    fn dimensions(&self) -> usize;
+    fn embed(&self, text: &str) -> Vec<f32>;
+}
+
+pub const DEFAULT_DIMENSIONS: usize = 256;
+
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "did", "do", "for", "from", "had",
+    "has", "have", "i", "in", "is", "it", "its", "me", "my", "of", "on", "or", "so", "that", "the",
+    "this", "to", "was", "we", "were", "what", "with", "you", "your",
+];
+
+/// Feature-hashed bag of words and word pairs, L2 normalized
+#[derive(Debug, Clone)]
+pub struct HashingEmbedder {
+    dimensions: usize,
+}
+
+impl HashingEmbedder {
+    pub fn new(dimensions: usize) -> Self {
+        Self {
+            dimensions: dimensions.max(8),
+        }
+    }
+
+    fn add(&self, vector: &mut [f32], feature: &str, weight: f32) {
+        let hash = fnv1a(feature);
+        let index = (hash % self.dimensions as u64) as usize;
+        // A second hash bit for the sign keeps collisions from only adding up
+        let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+        vector[index] += sign * weight;
+    }
+}
+
+impl Default for HashingEmbedder {
+    fn default() -> Self {
+        Self::new(DEFAULT_DIMENSIONS)
+    }
+}
+
+impl Embedder for HashingEmbedder {
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    fn embed(&self, text: &str) -> Vec<f32> {
+        let words = terms(text);
+        let mut vector = vec![0.0; self.dimensions];
+        for word in &words {
+            self.add(&mut vector, word, 1.0);
+        }
+        for pair in words.windows(2) {
+            self.add(&mut vector, &format!("{} {}", pair[0], pair[1]), 0.5);
+        }
+        normalize(&mut vector);
+        vector
+    }
+}
+
+/// Lowercased content words, with a trailing plural `s` dropped
+fn terms(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .map(|word| word.trim_matches('\'').to_lowercase())
+        .filter(|word| !word.is_empty() && !STOPWORDS.contains(&word.as_str()))
+        .map(|word| match word.strip_suffix('s') {
+            Some(stem) if stem.len() > 2 && !stem.ends_with('s') => stem.to_string(),
+            _ => word,
+        })
+        .collect()
+}
+
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+}
+
+/// Cosine similarity; 0 for mismatched lengths or zero vectors
+pub fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norms =
+        a.iter().map(|v| v * v).sum::<f32>().sqrt() * b.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norms > 0.0 {
+        dot / norms
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn related_texts_score_higher() {
+        let embedder = HashingEmbedder::default();
+        let query = embedder.embed("What did the cards say about my journey?");
+        let related = embedder.embed("The tarot card for the journey was The Chariot");
+        let unrelated = embedder.embed("Turn the volume up to forty");
+        assert!(cosine(&query, &related) > cosine(&query, &unrelated));
+        assert!((cosine(&related, &related) - 1.0).abs() < 1e-5);
+
+        assert_eq!(embedder.embed("").len(), DEFAULT_DIMENSIONS);
+        assert_eq!(cosine(&embedder.embed("the and of"), &query), 0.0);
+        assert_eq!(cosine(&[1.0], &[1.0, 0.0]), 0.0);
+    }
+}
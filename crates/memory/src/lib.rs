@@ -0,0 +1,20 @@
+//! Conversation memory: a context store for conversational modules.
+//!
+//! Exchanges flowing through the `memory` processor are kept with an
+//! embedding each. User turns leave with the most similar earlier exchanges
+//! and the latest few prepended, so a stateless LLM or oracle behind it can
+//! answer follow-up questions.
+//!
+//! Recall is lexical only: embeddings come from [`HashingEmbedder`], which
+//! hashes words, so an earlier exchange is found when it shares wording
+//! with the prompt and a paraphrase in other words isn't. No sentence
+//! encoder model ships with the crate; one can be plugged in through
+//! [`Embedder`] and [`MemoryProcessor::with_store`].
+
+mod embedding;
+mod processor;
+mod store;
+
+pub use embedding::{cosine, Embedder, HashingEmbedder, DEFAULT_DIMENSIONS};
+pub use processor::{MemoryConfig, MemoryProcessor, CONTEXT_PORT, PROMPT_PORT};
+pub use store::{MemoryEntry, MemoryStore, Recollection, USER_ROLE};
@@ -0,0 +1,391 @@
+use async_trait::async_trait;
+use magnolia_core::{
+    ControlSignal, DataType, ModuleSchema, Port, PortDirection, Processor, Signal,
+};
+use serde::{Deserialize, Serialize};
+use speech_to_text::SttEvent;
+
+use crate::embedding::HashingEmbedder;
+use crate::store::{MemoryStore, Recollection, USER_ROLE};
+
+pub const PROMPT_PORT: &str = "prompt_out";
+pub const CONTEXT_PORT: &str = "context_out";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MemoryConfig {
+    /// Exchanges kept; the oldest are forgotten first
+    pub capacity: usize,
+    /// Earlier exchanges recalled into each prompt
+    pub recall_count: usize,
+    /// Similarity below which an exchange isn't recalled
+    pub min_score: f32,
+    /// Latest exchanges always included in a prompt, in order
+    pub recent_turns: usize,
+    /// Off passes user text through to `prompt_out` unchanged
+    pub inject_context: bool,
+}
+
+impl Default for MemoryConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 200,
+            recall_count: 3,
+            min_score: 0.15,
+            recent_turns: 4,
+            inject_context: true,
+        }
+    }
+}
+
+impl MemoryConfig {
+    pub fn schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "capacity": {
+                    "type": "integer",
+                    "title": "Exchanges Kept",
+                    "minimum": 1,
+                    "maximum": 10000,
+                    "default": 200
+                },
+                "recall_count": {
+                    "type": "integer",
+                    "title": "Recalled per Prompt",
+                    "minimum": 0,
+                    "maximum": 20,
+                    "default": 3
+                },
+                "min_score": {
+                    "type": "number",
+                    "title": "Minimum Similarity",
+                    "minimum": 0.0,
+                    "maximum": 1.0,
+                    "default": 0.15
+                },
+                "recent_turns": {
+                    "type": "integer",
+                    "title": "Recent Turns",
+                    "minimum": 0,
+                    "maximum": 20,
+                    "default": 4
+                },
+                "inject_context": {
+                    "type": "boolean",
+                    "title": "Inject Context",
+                    "default": true
+                }
+            }
+        })
+    }
+}
+
+/// Keeps the conversation going through it and prefixes each user turn with
+/// what is relevant from earlier.
+///
+/// - `Text` and final STT segments are user turns: stored, then re-emitted on
+///   `prompt_out` with recalled and recent exchanges prepended
+/// - `Computed` signals are replies (an LLM, cleromancy), stored under their
+///   source; JSON with a `text` field stores that field
+/// - `Intent` commands: `recall <query>` emits matches as JSON on
+///   `context_out`, `remember <text>` stores a note, `forget` clears
+pub struct MemoryProcessor {
+    id: String,
+    enabled: bool,
+    config: MemoryConfig,
+    store: MemoryStore,
+    last_port: &'static str,
+}
+
+impl MemoryProcessor {
+    pub fn new(id: &str, config: MemoryConfig) -> Self {
+        Self::with_store(
+            id,
+            MemoryStore::new(config.capacity, Box::new(HashingEmbedder::default())),
+            config,
+        )
+    }
+
+    /// For a store with a different [`Embedder`](crate::Embedder)
+    pub fn with_store(id: &str, store: MemoryStore, config: MemoryConfig) -> Self {
+        Self {
+            id: id.to_string(),
+            enabled: true,
+            config,
+            store,
+            last_port: PROMPT_PORT,
+        }
+    }
+
+    pub fn config(&self) -> &MemoryConfig {
+        &self.config
+    }
+
+    pub fn store(&self) -> &MemoryStore {
+        &self.store
+    }
+
+    pub fn set_config(&mut self, config: MemoryConfig) {
+        self.store.set_capacity(config.capacity);
+        self.config = config;
+    }
+
+    /// `text` preceded by recalled and recent exchanges; just `text` when
+    /// there is nothing to add
+    pub fn build_prompt(&self, text: &str) -> String {
+        let recent: Vec<_> = self.store.recent(self.config.recent_turns).collect();
+        let recalled: Vec<Recollection> = self
+            .store
+            .recall(text, self.config.recall_count, self.config.min_score)
+            .into_iter()
+            .filter(|r| !recent.iter().any(|e| e.id == r.id))
+            .collect();
+
+        let mut prompt = String::new();
+        if !recalled.is_empty() {
+            prompt.push_str("Relevant earlier conversation:\n");
+            for r in &recalled {
+                prompt.push_str(&format!("- {}: {}\n", r.role, r.text));
+            }
+            prompt.push('\n');
+        }
+        if !recent.is_empty() {
+            prompt.push_str("Recent conversation:\n");
+            for entry in &recent {
+                prompt.push_str(&format!("{}: {}\n", entry.role, entry.text));
+            }
+            prompt.push('\n');
+        }
+        if prompt.is_empty() {
+            return text.to_string();
+        }
+        prompt.push_str(&format!("{}: {}", USER_ROLE, text));
+        prompt
+    }
+
+    fn user_turn(&mut self, text: &str) -> Option<Signal> {
+        let text = text.trim();
+        if text.is_empty() {
+            return None;
+        }
+        let prompt = if self.config.inject_context {
+            self.build_prompt(text)
+        } else {
+            text.to_string()
+        };
+        self.store.push(USER_ROLE, text);
+        self.last_port = PROMPT_PORT;
+        Some(Signal::Text(prompt))
+    }
+
+    fn command(&mut self, action: &str, parameters: &[String]) -> Option<Signal> {
+        let argument = parameters.join(" ");
+        match action.to_ascii_lowercase().as_str() {
+            "recall" => {
+                let recalled = self.store.recall(
+                    &argument,
+                    self.config.recall_count.max(1),
+                    self.config.min_score,
+                );
+                self.last_port = CONTEXT_PORT;
+                Some(Signal::Computed {
+                    source: self.id.clone(),
+                    content: serde_json::to_string(&recalled).unwrap_or_default(),
+                })
+            }
+            "remember" => {
+                self.store.push("note", &argument);
+                None
+            }
+            "forget" => {
+                self.store.clear();
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Reply text in a `Computed` payload: the `text` field of a JSON object,
+/// otherwise the whole content
+fn reply_text(content: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(content)
+        .ok()
+        .and_then(|value| value.get("text")?.as_str().map(str::to_string))
+        .unwrap_or_else(|| content.to_string())
+}
+
+#[async_trait]
+impl Processor for MemoryProcessor {
+    fn name(&self) -> &str {
+        "Memory"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "Memory".to_string(),
+            description: "Remembers the conversation and adds relevant context to prompts"
+                .to_string(),
+            ports: vec![
+                Port {
+                    id: "exchange_in".to_string(),
+                    label: "Exchanges In".to_string(),
+                    data_type: DataType::Any,
+                    direction: PortDirection::Input,
+                },
+                Port {
+                    id: PROMPT_PORT.to_string(),
+                    label: "Prompt Out".to_string(),
+                    data_type: DataType::Text,
+                    direction: PortDirection::Output,
+                },
+                Port {
+                    id: CONTEXT_PORT.to_string(),
+                    label: "Recalled".to_string(),
+                    data_type: DataType::Text,
+                    direction: PortDirection::Output,
+                },
+            ],
+            settings_schema: Some(MemoryConfig::schema()),
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    async fn process(&mut self, signal: Signal) -> anyhow::Result<Option<Signal>> {
+        match signal {
+            Signal::Control(ControlSignal::Settings(settings)) => {
+                match serde_json::from_value::<MemoryConfig>(settings) {
+                    Ok(config) => self.set_config(config),
+                    Err(e) => log::warn!("Memory: invalid settings, keeping current: {}", e),
+                }
+                Ok(None)
+            }
+            Signal::Text(text) => Ok(self.user_turn(&text)),
+            Signal::Computed { source, content } => {
+                match serde_json::from_str::<SttEvent>(&content) {
+                    Ok(SttEvent::Final { text, .. }) => return Ok(self.user_turn(&text)),
+                    Ok(_) => return Ok(None),
+                    Err(_) => {}
+                }
+                self.store.push(&source, &reply_text(&content));
+                Ok(None)
+            }
+            Signal::Intent { action, parameters } => Ok(self.command(&action, &parameters)),
+            _ => Ok(None),
+        }
+    }
+
+    fn output_port(&self, _signal: &Signal) -> Option<&str> {
+        Some(self.last_port)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reply(source: &str, text: &str) -> Signal {
+        Signal::Computed {
+            source: source.to_string(),
+            content: serde_json::json!({ "text": text }).to_string(),
+        }
+    }
+
+    fn prompt(out: Option<Signal>) -> String {
+        match out {
+            Some(Signal::Text(text)) => text,
+            other => panic!("expected a prompt, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn injects_recalled_and_recent_context() {
+        let mut memory = MemoryProcessor::new(
+            "memory",
+            MemoryConfig {
+                recent_turns: 1,
+                ..MemoryConfig::default()
+            },
+        );
+        assert_eq!(
+            prompt(
+                memory
+                    .process(Signal::Text("my cat is called Juniper".into()))
+                    .await
+                    .unwrap()
+            ),
+            "my cat is called Juniper"
+        );
+        memory
+            .process(reply("oracle", "What a fine name."))
+            .await
+            .unwrap();
+        memory
+            .process(Signal::Text("set the volume to five".into()))
+            .await
+            .unwrap();
+
+        let out = memory
+            .process(Signal::Text("what is my cat called?".into()))
+            .await
+            .unwrap();
+        assert_eq!(memory.output_port(&Signal::Pulse), Some(PROMPT_PORT));
+        assert_eq!(
+            prompt(out),
+            "Relevant earlier conversation:\n- user: my cat is called Juniper\n\n\
+             Recent conversation:\nuser: set the volume to five\n\n\
+             user: what is my cat called?"
+        );
+        assert_eq!(memory.store().len(), 4);
+
+        let out = memory
+            .process(Signal::Intent {
+                action: "recall".into(),
+                parameters: vec!["Juniper".into()],
+            })
+            .await
+            .unwrap();
+        assert_eq!(memory.output_port(&Signal::Pulse), Some(CONTEXT_PORT));
+        let Some(Signal::Computed { content, .. }) = out else {
+            panic!("expected recalled context");
+        };
+        let recalled: Vec<serde_json::Value> = serde_json::from_str(&content).unwrap();
+        assert_eq!(recalled[0]["text"], "my cat is called Juniper");
+
+        // STT partials are ignored, finals are user turns
+        let partial = SttEvent::Partial {
+            session_id: "s".into(),
+            segment_id: 1,
+            text: "hel".into(),
+            audio_end_ms: 10,
+            sequence: 1,
+            language: None,
+            stable_len: None,
+        };
+        let partial = Signal::Computed {
+            source: "speech_to_text".into(),
+            content: serde_json::to_string(&partial).unwrap(),
+        };
+        assert!(memory.process(partial).await.unwrap().is_none());
+        assert_eq!(memory.store().len(), 4);
+
+        memory
+            .process(Signal::Intent {
+                action: "forget".into(),
+                parameters: vec![],
+            })
+            .await
+            .unwrap();
+        assert!(memory.store().is_empty());
+    }
+}
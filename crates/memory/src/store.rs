@@ -0,0 +1,146 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use crate::embedding::{cosine, Embedder};
+
+/// Role of exchanges that arrive as plain text
+pub const USER_ROLE: &str = "user";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryEntry {
+    /// Increases with every stored entry
+    pub id: u64,
+    /// `user`, or the source module of a reply
+    pub role: String,
+    pub text: String,
+    #[serde(skip)]
+    pub embedding: Vec<f32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recollection {
+    pub id: u64,
+    pub role: String,
+    pub text: String,
+    pub score: f32,
+}
+
+/// Most recent exchanges, oldest evicted first
+pub struct MemoryStore {
+    entries: VecDeque<MemoryEntry>,
+    capacity: usize,
+    next_id: u64,
+    embedder: Box<dyn Embedder>,
+}
+
+impl MemoryStore {
+    pub fn new(capacity: usize, embedder: Box<dyn Embedder>) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            capacity: capacity.max(1),
+            next_id: 0,
+            embedder,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        self.evict();
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn push(&mut self, role: &str, text: &str) -> Option<u64> {
+        let text = text.trim();
+        if text.is_empty() {
+            return None;
+        }
+        let id = self.next_id;
+        self.next_id += 1;
+        self.entries.push_back(MemoryEntry {
+            id,
+            role: role.to_string(),
+            text: text.to_string(),
+            embedding: self.embedder.embed(text),
+        });
+        self.evict();
+        Some(id)
+    }
+
+    fn evict(&mut self) {
+        while self.entries.len() > self.capacity {
+            self.entries.pop_front();
+        }
+    }
+
+    /// Last `count` entries, oldest first
+    pub fn recent(&self, count: usize) -> impl Iterator<Item = &MemoryEntry> {
+        self.entries
+            .iter()
+            .skip(self.entries.len().saturating_sub(count))
+    }
+
+    /// Up to `limit` entries scoring at least `min_score` against `query`,
+    /// best first; ties go to the newer entry
+    pub fn recall(&self, query: &str, limit: usize, min_score: f32) -> Vec<Recollection> {
+        let query = self.embedder.embed(query);
+        let mut scored: Vec<(f32, &MemoryEntry)> = self
+            .entries
+            .iter()
+            .map(|entry| (cosine(&query, &entry.embedding), entry))
+            .filter(|(score, _)| *score >= min_score && *score > 0.0)
+            .collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0).then(b.1.id.cmp(&a.1.id)));
+        scored
+            .into_iter()
+            .take(limit)
+            .map(|(score, entry)| Recollection {
+                id: entry.id,
+                role: entry.role.clone(),
+                text: entry.text.clone(),
+                score,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embedding::HashingEmbedder;
+
+    #[test]
+    fn recalls_relevant_entries_within_capacity() {
+        let mut store = MemoryStore::new(3, Box::new(HashingEmbedder::default()));
+        assert_eq!(store.push(USER_ROLE, "  "), None);
+        store.push(USER_ROLE, "my cat is called Juniper");
+        store.push("cleromancy", "Hexagram 11: Peace");
+        store.push(USER_ROLE, "draw a tarot card for my cat");
+        store.push("oracle", "The Star speaks of hope for Juniper");
+        assert_eq!(store.len(), 3);
+
+        let recalled = store.recall("how is Juniper the cat", 2, 0.1);
+        assert_eq!(recalled.len(), 2);
+        assert!(recalled
+            .iter()
+            .all(|r| r.text.contains("cat") || r.text.contains("Juniper")));
+        // The evicted first entry is gone
+        assert!(recalled.iter().all(|r| r.id > 0));
+        assert!(store.recall("volume", 5, 0.1).is_empty());
+
+        let recent: Vec<_> = store.recent(2).map(|e| e.role.as_str()).collect();
+        assert_eq!(recent, vec![USER_ROLE, "oracle"]);
+        store.set_capacity(1);
+        assert_eq!(store.recent(5).count(), 1);
+    }
+}
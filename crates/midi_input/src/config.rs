@@ -0,0 +1,85 @@
+use magnolia_core::midi::is_realtime;
+use magnolia_core::MidiMessage;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MidiInputConfig {
+    /// Input port name, or part of it; empty opens every port
+    pub port: String,
+    /// Only pass this channel (1-16); 0 passes all of them
+    pub channel: u8,
+    /// Also pass clock, start/stop and active sensing
+    pub realtime: bool,
+}
+
+impl MidiInputConfig {
+    pub fn schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "port": {
+                    "type": "string",
+                    "title": "Port (empty: all)",
+                    "default": ""
+                },
+                "channel": {
+                    "type": "integer",
+                    "title": "Channel (0: all)",
+                    "minimum": 0,
+                    "maximum": 16,
+                    "default": 0
+                },
+                "realtime": {
+                    "type": "boolean",
+                    "title": "Pass Clock & Sensing",
+                    "default": false
+                }
+            }
+        })
+    }
+
+    /// Whether the port called `name` is one to open
+    pub fn matches_port(&self, name: &str) -> bool {
+        let wanted = self.port.trim().to_lowercase();
+        wanted.is_empty() || name.to_lowercase().contains(&wanted)
+    }
+
+    /// Whether a message with these bytes gets through the filters
+    pub fn accepts(&self, status: u8, data: &[u8]) -> bool {
+        if is_realtime(status) {
+            return self.realtime;
+        }
+        if self.channel == 0 {
+            return true;
+        }
+        match MidiMessage::parse(status, data).and_then(|message| message.channel()) {
+            Some(channel) => channel == self.channel,
+            None => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters_ports_channels_and_clock() {
+        let config = MidiInputConfig {
+            port: "launch".into(),
+            channel: 2,
+            realtime: false,
+        };
+        assert!(config.matches_port("Launchpad Mini MIDI 1"));
+        assert!(!config.matches_port("Midi Through Port-0"));
+        assert!(MidiInputConfig::default().matches_port("anything"));
+
+        assert!(config.accepts(0x91, &[60, 100]));
+        assert!(!config.accepts(0x90, &[60, 100]));
+        assert!(!config.accepts(0xF8, &[]));
+        // SysEx has no channel and isn't filtered by it
+        assert!(config.accepts(0xF0, &[0x7E, 0xF7]));
+        assert!(MidiInputConfig::default().accepts(0x9F, &[1, 2]));
+    }
+}
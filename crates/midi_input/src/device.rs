@@ -0,0 +1,108 @@
+//! MIDI ports through `midir`.
+//!
+//! A scan thread opens every matching input port and keeps its connection
+//! alive; `midir` calls back on its own thread per message. Ports that go
+//! away are dropped and picked up again when they return.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use magnolia_core::SharedClock;
+use midir::{Ignore, MidiInput, MidiInputConnection};
+use tokio::sync::mpsc;
+
+pub const RESCAN_INTERVAL: Duration = Duration::from_secs(2);
+
+const CLIENT_NAME: &str = "magnolia";
+
+/// One message as it came off a port
+#[derive(Debug, Clone, PartialEq)]
+pub struct MidiEvent {
+    pub port: String,
+    pub timestamp_us: u64,
+    pub bytes: Vec<u8>,
+}
+
+/// Names of the input ports present now
+pub fn list_ports() -> Vec<String> {
+    let Ok(input) = MidiInput::new(CLIENT_NAME) else {
+        return Vec::new();
+    };
+    input
+        .ports()
+        .iter()
+        .filter_map(|port| input.port_name(port).ok())
+        .collect()
+}
+
+fn connect(
+    name: &str,
+    clock: SharedClock,
+    tx: mpsc::Sender<MidiEvent>,
+) -> Result<MidiInputConnection<()>, String> {
+    let mut input = MidiInput::new(CLIENT_NAME).map_err(|e| e.to_string())?;
+    input.ignore(Ignore::None);
+    let port = input
+        .ports()
+        .into_iter()
+        .find(|port| input.port_name(port).ok().as_deref() == Some(name))
+        .ok_or_else(|| "port went away".to_string())?;
+    let port_name = name.to_string();
+    input
+        .connect(
+            &port,
+            "magnolia-in",
+            move |_, bytes, _| {
+                // Stamped on the runtime clock like audio, not midir's own
+                let event = MidiEvent {
+                    port: port_name.clone(),
+                    timestamp_us: clock.now_us(),
+                    bytes: bytes.to_vec(),
+                };
+                if tx.try_send(event).is_err() {
+                    log::debug!("MIDI: queue full, dropped a message");
+                }
+            },
+            (),
+        )
+        .map_err(|e| e.to_string())
+}
+
+/// Background thread that opens the ports `wanted` accepts and forwards
+/// their messages until `tx` closes
+pub fn spawn_reader(
+    wanted: impl Fn(&str) -> bool + Send + 'static,
+    clock: SharedClock,
+    tx: mpsc::Sender<MidiEvent>,
+) -> std::io::Result<()> {
+    std::thread::Builder::new()
+        .name("midi-scan".to_string())
+        .spawn(move || {
+            let mut open: HashMap<String, MidiInputConnection<()>> = HashMap::new();
+            while !tx.is_closed() {
+                let ports = list_ports();
+                open.retain(|name, _| {
+                    let present = ports.contains(name);
+                    if !present {
+                        log::info!("MIDI port disconnected: {}", name);
+                    }
+                    present
+                });
+                for name in ports {
+                    if open.contains_key(&name) || !wanted(&name) {
+                        continue;
+                    }
+                    match connect(&name, clock.clone(), tx.clone()) {
+                        Ok(connection) => {
+                            log::info!("MIDI port connected: {}", name);
+                            open.insert(name, connection);
+                        }
+                        // Retried on the next scan
+                        Err(e) => log::debug!("MIDI: cannot open {}: {}", name, e),
+                    }
+                }
+                std::thread::sleep(RESCAN_INTERVAL);
+            }
+        })
+        .map(|_| ())
+}
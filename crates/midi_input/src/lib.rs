@@ -0,0 +1,14 @@
+//! MIDI input: hardware controllers and instruments as a Magnolia source.
+//!
+//! Every message leaves `midi_out` as a `Signal::Midi`, so a controller can
+//! be patched into DSP and tile modules the way audio is. Ports come from
+//! `midir` (ALSA on Linux, CoreMIDI on macOS, WinMM on Windows) and are
+//! rescanned periodically so devices can be plugged in later.
+
+mod config;
+mod device;
+mod source;
+
+pub use config::MidiInputConfig;
+pub use device::{list_ports, MidiEvent};
+pub use source::MidiInputSource;
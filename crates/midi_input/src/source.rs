@@ -0,0 +1,162 @@
+use async_trait::async_trait;
+use magnolia_core::{
+    system_clock, ControlSignal, DataType, ModuleSchema, Port, PortDirection, SharedClock, Signal,
+    Source,
+};
+use tokio::sync::mpsc;
+
+use crate::config::MidiInputConfig;
+use crate::device::{spawn_reader, MidiEvent, RESCAN_INTERVAL};
+
+const EVENT_QUEUE: usize = 1024;
+
+/// MIDI controllers as a source: `midi_out` carries every message that gets
+/// through the port and channel filters. Settings arrive on `settings_in`;
+/// changing the port reopens the devices.
+pub struct MidiInputSource {
+    id: String,
+    enabled: bool,
+    config: MidiInputConfig,
+    clock: SharedClock,
+    events: Option<mpsc::Receiver<MidiEvent>>,
+}
+
+impl MidiInputSource {
+    pub fn new(id: &str, config: MidiInputConfig) -> Self {
+        Self {
+            id: id.to_string(),
+            enabled: true,
+            config,
+            clock: system_clock(),
+            events: None,
+        }
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn config(&self) -> &MidiInputConfig {
+        &self.config
+    }
+
+    pub fn set_config(&mut self, config: MidiInputConfig) {
+        if config.port != self.config.port {
+            // Dropping the receiver closes the old connections
+            self.events = None;
+        }
+        self.config = config;
+    }
+
+    fn start(&mut self) {
+        let (tx, rx) = mpsc::channel(EVENT_QUEUE);
+        let config = self.config.clone();
+        let wanted = move |name: &str| config.matches_port(name);
+        match spawn_reader(wanted, self.clock.clone(), tx) {
+            Ok(()) => self.events = Some(rx),
+            Err(e) => log::error!("MIDI: failed to start port scan: {}", e),
+        }
+    }
+}
+
+#[async_trait]
+impl Source for MidiInputSource {
+    fn name(&self) -> &str {
+        "MIDI Input"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "MIDI Input".to_string(),
+            description: "Notes, controllers and other messages from MIDI devices".to_string(),
+            ports: vec![
+                Port {
+                    id: "midi_out".to_string(),
+                    label: "MIDI".to_string(),
+                    data_type: DataType::Midi,
+                    direction: PortDirection::Output,
+                },
+                Port {
+                    id: "settings_in".to_string(),
+                    label: "Settings".to_string(),
+                    data_type: DataType::Control,
+                    direction: PortDirection::Input,
+                },
+            ],
+            settings_schema: Some(MidiInputConfig::schema()),
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    async fn poll(&mut self) -> Option<Signal> {
+        loop {
+            let Some(events) = self.events.as_mut() else {
+                self.start();
+                if self.events.is_none() {
+                    tokio::time::sleep(RESCAN_INTERVAL).await;
+                }
+                continue;
+            };
+            let Some(event) = events.recv().await else {
+                // Scan thread gone; start over
+                self.events = None;
+                tokio::time::sleep(RESCAN_INTERVAL).await;
+                continue;
+            };
+            if !self.enabled {
+                continue;
+            }
+            let Some((&status, data)) = event.bytes.split_first() else {
+                continue;
+            };
+            if !self.config.accepts(status, data) {
+                continue;
+            }
+            if let Some(signal) = Signal::midi_from_bytes(event.timestamp_us, &event.bytes) {
+                return Some(signal);
+            }
+        }
+    }
+
+    fn handle_signal(&mut self, signal: Signal) {
+        if let Signal::Control(ControlSignal::Settings(settings)) = signal {
+            match serde_json::from_value::<MidiInputConfig>(settings) {
+                Ok(config) => self.set_config(config),
+                Err(e) => log::warn!("MIDI: invalid settings, keeping current: {}", e),
+            }
+        }
+    }
+
+    fn output_port(&self, _signal: &Signal) -> Option<&str> {
+        Some("midi_out")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_change_the_filters() {
+        let mut source = MidiInputSource::new("midi_input", MidiInputConfig::default());
+        let schema = source.schema();
+        assert_eq!(schema.ports[0].data_type, DataType::Midi);
+
+        source.handle_signal(Signal::Control(ControlSignal::Settings(
+            serde_json::json!({ "port": "keys", "channel": 10 }),
+        )));
+        assert_eq!(source.config().port, "keys");
+        assert!(source.config().accepts(0x99, &[36, 90]));
+        assert!(!source.config().accepts(0x90, &[36, 90]));
+    }
+}
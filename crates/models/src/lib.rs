@@ -0,0 +1,399 @@
+//! Model files: names to local paths, fetching what's missing
+//!
+//! A [`ModelSpec`] names a model, the directory it lives in and its files by
+//! role (`encoder`, `tokens`, ...), each optionally with the URL it comes
+//! from and its sha256. [`ModelStore::ensure`] downloads missing files to
+//! `<file>.part`, checks the hash and only then moves them into place, so a
+//! model file that exists is a complete one.
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+
+/// Gives up on a connection that stalls this long
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+/// Progress is reported every this many bytes
+const PROGRESS_STEP: u64 = 1024 * 1024;
+
+/// One file of a model
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ModelFile {
+    /// Relative to the model's directory
+    pub path: PathBuf,
+    /// Where a missing copy is downloaded from
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Hex sha256 a download (and [`ModelStore::verify`]) must match
+    #[serde(default)]
+    pub sha256: Option<String>,
+}
+
+/// A named model and its files by role
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ModelSpec {
+    pub name: String,
+    /// Relative to the store's root
+    pub dir: PathBuf,
+    pub files: BTreeMap<String, ModelFile>,
+}
+
+fn is_plain_relative(path: &Path) -> bool {
+    !path.as_os_str().is_empty() && path.components().all(|c| matches!(c, Component::Normal(_)))
+}
+
+impl ModelSpec {
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            bail!("model name is empty");
+        }
+        if self.files.is_empty() {
+            bail!("model {} lists no files", self.name);
+        }
+        for (role, file) in &self.files {
+            if !is_plain_relative(&file.path) {
+                bail!(
+                    "model {} {} path {} must stay inside the model directory",
+                    self.name,
+                    role,
+                    file.path.display()
+                );
+            }
+            if let Some(sha256) = &file.sha256 {
+                if sha256.len() != 64 || !sha256.chars().all(|c| c.is_ascii_hexdigit()) {
+                    bail!("model {} {} sha256 isn't 64 hex digits", self.name, role);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A model whose files are all on disk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedModel {
+    pub name: String,
+    pub dir: PathBuf,
+    pub files: BTreeMap<String, PathBuf>,
+}
+
+impl ResolvedModel {
+    pub fn file(&self, role: &str) -> Option<&Path> {
+        self.files.get(role).map(PathBuf::as_path)
+    }
+}
+
+/// Download progress
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModelEvent {
+    Downloading {
+        role: String,
+        downloaded: u64,
+        total: Option<u64>,
+    },
+    /// The file matched its hash and is in place
+    Installed { role: String, path: PathBuf },
+}
+
+/// The configured models, under one root directory
+#[derive(Debug, Clone, Default)]
+pub struct ModelStore {
+    root: PathBuf,
+    models: Vec<ModelSpec>,
+}
+
+impl ModelStore {
+    pub fn new(root: impl Into<PathBuf>, models: Vec<ModelSpec>) -> Self {
+        Self {
+            root: root.into(),
+            models,
+        }
+    }
+
+    pub fn spec(&self, name: &str) -> Option<&ModelSpec> {
+        self.models.iter().find(|model| model.name == name)
+    }
+
+    fn lookup(&self, name: &str) -> Result<&ModelSpec> {
+        self.spec(name)
+            .with_context(|| format!("no model named {} is configured", name))
+    }
+
+    fn path(&self, spec: &ModelSpec, file: &ModelFile) -> PathBuf {
+        self.root.join(&spec.dir).join(&file.path)
+    }
+
+    /// Roles whose file isn't on disk
+    pub fn missing(&self, name: &str) -> Result<Vec<String>> {
+        let spec = self.lookup(name)?;
+        Ok(spec
+            .files
+            .iter()
+            .filter(|(_, file)| !self.path(spec, file).is_file())
+            .map(|(role, _)| role.clone())
+            .collect())
+    }
+
+    /// Every missing file has a URL to fetch it from
+    pub fn can_download(&self, name: &str) -> bool {
+        let Some(spec) = self.spec(name) else {
+            return false;
+        };
+        spec.files
+            .values()
+            .all(|file| file.url.is_some() || self.path(spec, file).is_file())
+    }
+
+    /// Paths of a model that's all there, without hashing anything
+    pub fn resolve(&self, name: &str) -> Result<ResolvedModel> {
+        let spec = self.lookup(name)?;
+        let missing = self.missing(name)?;
+        if !missing.is_empty() {
+            bail!("model {} is missing {}", name, missing.join(", "));
+        }
+        Ok(ResolvedModel {
+            name: spec.name.clone(),
+            dir: self.root.join(&spec.dir),
+            files: spec
+                .files
+                .iter()
+                .map(|(role, file)| (role.clone(), self.path(spec, file)))
+                .collect(),
+        })
+    }
+
+    /// Check every file that has a sha256 against it
+    pub fn verify(&self, name: &str) -> Result<()> {
+        let spec = self.lookup(name)?;
+        for (role, file) in &spec.files {
+            let Some(expected) = &file.sha256 else {
+                continue;
+            };
+            let path = self.path(spec, file);
+            let mut reader =
+                std::fs::File::open(&path).with_context(|| format!("{}", path.display()))?;
+            let mut hasher = Sha256::new();
+            std::io::copy(&mut reader, &mut hasher)
+                .with_context(|| format!("{}", path.display()))?;
+            let actual = hex::encode(hasher.finalize());
+            if !actual.eq_ignore_ascii_case(expected) {
+                bail!(
+                    "model {} {} has sha256 {}, expected {}",
+                    name,
+                    role,
+                    actual,
+                    expected
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Download whatever is missing, then resolve. Blocks; run it off the
+    /// UI thread.
+    pub fn ensure(
+        &self,
+        name: &str,
+        mut on_event: impl FnMut(ModelEvent),
+    ) -> Result<ResolvedModel> {
+        let spec = self.lookup(name)?;
+        for role in self.missing(name)? {
+            let file = &spec.files[&role];
+            let url = file
+                .url
+                .as_deref()
+                .with_context(|| format!("model {} {} is missing and has no url", name, role))?;
+            let path = self.path(spec, file);
+            log::info!("Downloading model {} {} from {}", name, role, url);
+            // No overall timeout: models run to hundreds of megabytes
+            let agent = ureq::AgentBuilder::new()
+                .timeout_connect(REQUEST_TIMEOUT)
+                .timeout_read(REQUEST_TIMEOUT)
+                .build();
+            let response = agent
+                .get(url)
+                .call()
+                .with_context(|| format!("fetching {}", url))?;
+            let total = response
+                .header("Content-Length")
+                .and_then(|length| length.parse().ok());
+            install_from(
+                response.into_reader(),
+                total,
+                &path,
+                file.sha256.as_deref(),
+                |downloaded| {
+                    on_event(ModelEvent::Downloading {
+                        role: role.clone(),
+                        downloaded,
+                        total,
+                    })
+                },
+            )
+            .with_context(|| format!("downloading model {} {}", name, role))?;
+            on_event(ModelEvent::Installed {
+                role: role.clone(),
+                path,
+            });
+        }
+        self.resolve(name)
+    }
+}
+
+/// Stream `reader` to `<path>.part`, hashing as it goes, and move it to
+/// `path` only if the hash matches
+fn install_from(
+    mut reader: impl Read,
+    total: Option<u64>,
+    path: &Path,
+    sha256: Option<&str>,
+    mut on_progress: impl FnMut(u64),
+) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).with_context(|| format!("{}", parent.display()))?;
+    }
+    let mut part = path.as_os_str().to_owned();
+    part.push(".part");
+    let part = PathBuf::from(part);
+
+    let result = (|| {
+        let mut out = std::io::BufWriter::new(
+            std::fs::File::create(&part).with_context(|| format!("{}", part.display()))?,
+        );
+        let mut hasher = Sha256::new();
+        let mut buffer = vec![0u8; 64 * 1024];
+        let mut downloaded = 0u64;
+        let mut reported = 0u64;
+        loop {
+            let read = reader.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..read]);
+            out.write_all(&buffer[..read])?;
+            downloaded += read as u64;
+            if downloaded - reported >= PROGRESS_STEP {
+                reported = downloaded;
+                on_progress(downloaded);
+            }
+        }
+        out.flush()?;
+        on_progress(downloaded);
+
+        if let Some(total) = total {
+            if downloaded != total {
+                bail!("got {} of {} bytes", downloaded, total);
+            }
+        }
+        if let Some(expected) = sha256 {
+            let actual = hex::encode(hasher.finalize());
+            if !actual.eq_ignore_ascii_case(expected) {
+                bail!("sha256 is {}, expected {}", actual, expected);
+            }
+        }
+        Ok(())
+    })();
+
+    match result {
+        Ok(()) => std::fs::rename(&part, path).with_context(|| format!("{}", path.display())),
+        Err(e) => {
+            let _ = std::fs::remove_file(&part);
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("magnolia_models_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn spec(files: &[(&str, &str)]) -> ModelSpec {
+        ModelSpec {
+            name: "tiny".to_string(),
+            dir: PathBuf::from("tiny"),
+            files: files
+                .iter()
+                .map(|(role, path)| {
+                    (
+                        role.to_string(),
+                        ModelFile {
+                            path: PathBuf::from(path),
+                            url: None,
+                            sha256: None,
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn resolves_once_every_file_is_there() {
+        let root = scratch("resolve");
+        let store = ModelStore::new(
+            &root,
+            vec![spec(&[("encoder", "enc.onnx"), ("tokens", "tokens.txt")])],
+        );
+
+        assert_eq!(store.missing("tiny").unwrap(), vec!["encoder", "tokens"]);
+        assert!(!store.can_download("tiny"));
+        assert!(store.resolve("tiny").is_err());
+        assert!(store.resolve("other").is_err());
+
+        std::fs::create_dir_all(root.join("tiny")).unwrap();
+        std::fs::write(root.join("tiny/enc.onnx"), b"e").unwrap();
+        std::fs::write(root.join("tiny/tokens.txt"), b"t").unwrap();
+        let resolved = store.resolve("tiny").unwrap();
+        assert_eq!(
+            resolved.file("tokens"),
+            Some(root.join("tiny/tokens.txt").as_path())
+        );
+
+        std::fs::remove_dir_all(root).ok();
+    }
+
+    #[test]
+    fn downloads_land_only_when_the_hash_matches() {
+        let root = scratch("install");
+        let path = root.join("model/enc.onnx");
+        let body = b"weights".to_vec();
+        let good = hex::encode(Sha256::digest(&body));
+
+        let bad = "0".repeat(64);
+        assert!(install_from(&body[..], Some(7), &path, Some(&bad), |_| {}).is_err());
+        assert!(!path.exists());
+        assert!(!root.join("model/enc.onnx.part").exists());
+        assert!(install_from(&body[..], Some(8), &path, Some(&good), |_| {}).is_err());
+        assert!(!path.exists());
+
+        let mut progress = Vec::new();
+        install_from(&body[..], Some(7), &path, Some(&good), |n| progress.push(n)).unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), body);
+        assert_eq!(progress, vec![7]);
+
+        std::fs::remove_dir_all(root).ok();
+    }
+
+    #[test]
+    fn specs_keep_files_inside_their_directory() {
+        assert!(spec(&[("encoder", "enc.onnx")]).validate().is_ok());
+        assert!(spec(&[("encoder", "../enc.onnx")]).validate().is_err());
+        assert!(spec(&[("encoder", "/enc.onnx")]).validate().is_err());
+        assert!(spec(&[]).validate().is_err());
+
+        let mut hashed = spec(&[("encoder", "enc.onnx")]);
+        hashed.files.get_mut("encoder").unwrap().sha256 = Some("abc".to_string());
+        assert!(hashed.validate().is_err());
+    }
+}
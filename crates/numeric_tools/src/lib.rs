@@ -0,0 +1,13 @@
+//! Numeric tools: processors computing with numeric signals.
+//!
+//! Numbers travel as `Computed { source, content }` with the value as the
+//! content, as gamepad axes and meters send them; the source names the
+//! value, which is how these processors tell their inputs apart.
+
+mod math;
+mod stats;
+mod value;
+
+pub use math::{MathConfig, MathProcessor, MathVariable};
+pub use stats::{aggregate, Aggregates, StatsConfig, StatsProcessor};
+pub use value::numeric_value;
@@ -0,0 +1,312 @@
+use async_trait::async_trait;
+use magnolia_core::{
+    system_clock, ControlSignal, DataType, ModuleSchema, Port, PortDirection, Processor,
+    SharedClock, Signal,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::value::numeric_value;
+
+/// Variable for numbers from sources without one of their own
+const DEFAULT_VARIABLE: &str = "x";
+/// Variable holding the clock, in seconds
+const TIME_VARIABLE: &str = "t";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MathVariable {
+    pub name: String,
+    /// `Computed` source whose values it takes
+    pub source: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MathConfig {
+    /// `a * 0.5 + sin(b)`; a leading `out =` is allowed
+    pub expression: String,
+    /// Other sources go to `x`; `t` is the time in seconds
+    pub variables: Vec<MathVariable>,
+    /// Source of the results; empty uses the processor's id
+    pub output_source: String,
+}
+
+impl Default for MathConfig {
+    fn default() -> Self {
+        Self {
+            expression: DEFAULT_VARIABLE.to_string(),
+            variables: Vec::new(),
+            output_source: String::new(),
+        }
+    }
+}
+
+impl MathConfig {
+    pub fn schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "expression": { "type": "string", "title": "Expression", "default": "x" },
+                "variables": {
+                    "type": "array",
+                    "title": "Variables",
+                    "default": [],
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "name": { "type": "string", "title": "Name", "default": "" },
+                            "source": { "type": "string", "title": "Source", "default": "" }
+                        }
+                    }
+                },
+                "output_source": {
+                    "type": "string",
+                    "title": "Output Source (empty: module id)",
+                    "default": ""
+                }
+            }
+        })
+    }
+}
+
+/// `out = expr` or just `expr`
+fn parse(expression: &str) -> Result<meval::Expr, meval::Error> {
+    let expression = expression.trim();
+    let body = match expression.split_once('=') {
+        Some((lhs, rhs)) if lhs.trim().chars().all(|c| c.is_alphanumeric() || c == '_') => rhs,
+        _ => expression,
+    };
+    body.parse()
+}
+
+/// Evaluates an expression over the latest value of each variable, emitting
+/// the result whenever one of them changes. Variables not yet seen are 0.
+pub struct MathProcessor {
+    id: String,
+    enabled: bool,
+    config: MathConfig,
+    clock: SharedClock,
+    /// `None` while the expression doesn't parse
+    expr: Option<meval::Expr>,
+    values: HashMap<String, f64>,
+    /// An evaluation error was logged since the last settings change
+    warned: bool,
+}
+
+impl MathProcessor {
+    pub fn new(id: &str, config: MathConfig) -> Self {
+        let mut math = Self {
+            id: id.to_string(),
+            enabled: true,
+            config: MathConfig::default(),
+            clock: system_clock(),
+            expr: None,
+            values: HashMap::new(),
+            warned: false,
+        };
+        math.set_config(config);
+        math
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn config(&self) -> &MathConfig {
+        &self.config
+    }
+
+    /// Variables keep their values across changes
+    pub fn set_config(&mut self, config: MathConfig) {
+        self.expr = match parse(&config.expression) {
+            Ok(expr) => Some(expr),
+            Err(e) => {
+                log::warn!(
+                    "Math {}: can't read {:?}: {}",
+                    self.id,
+                    config.expression,
+                    e
+                );
+                None
+            }
+        };
+        self.warned = false;
+        self.config = config;
+    }
+
+    fn variable(&self, source: &str) -> String {
+        self.config
+            .variables
+            .iter()
+            .find(|v| v.source.trim() == source && !v.name.trim().is_empty())
+            .map(|v| v.name.trim().to_string())
+            .unwrap_or_else(|| DEFAULT_VARIABLE.to_string())
+    }
+
+    pub fn evaluate(&mut self) -> Option<f64> {
+        let expr = self.expr.as_ref()?;
+        let mut context = meval::Context::new();
+        context.var(DEFAULT_VARIABLE, 0.0);
+        for variable in &self.config.variables {
+            if !variable.name.trim().is_empty() {
+                context.var(variable.name.trim(), 0.0);
+            }
+        }
+        context.var(TIME_VARIABLE, self.clock.now_us() as f64 / 1e6);
+        for (name, value) in &self.values {
+            context.var(name.as_str(), *value);
+        }
+        match expr.eval_with_context(context) {
+            Ok(value) => value.is_finite().then_some(value),
+            Err(e) => {
+                if !std::mem::replace(&mut self.warned, true) {
+                    log::warn!("Math {}: {}", self.id, e);
+                }
+                None
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Processor for MathProcessor {
+    fn name(&self) -> &str {
+        "Math"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "Math".to_string(),
+            description: "Evaluates an expression over numeric inputs".to_string(),
+            ports: vec![
+                Port {
+                    id: "numeric_in".to_string(),
+                    label: "In".to_string(),
+                    data_type: DataType::Numeric,
+                    direction: PortDirection::Input,
+                },
+                Port {
+                    id: "numeric_out".to_string(),
+                    label: "Out".to_string(),
+                    data_type: DataType::Numeric,
+                    direction: PortDirection::Output,
+                },
+            ],
+            settings_schema: Some(MathConfig::schema()),
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    async fn process(&mut self, signal: Signal) -> anyhow::Result<Option<Signal>> {
+        if let Signal::Control(ControlSignal::Settings(settings)) = signal {
+            match serde_json::from_value::<MathConfig>(settings) {
+                Ok(config) => self.set_config(config),
+                Err(e) => log::warn!("Math: invalid settings, keeping current: {}", e),
+            }
+            return Ok(None);
+        }
+        let Some((source, value)) = numeric_value(&signal) else {
+            return Ok(None);
+        };
+        let variable = self.variable(source);
+        self.values.insert(variable, value);
+
+        let Some(result) = self.evaluate() else {
+            return Ok(None);
+        };
+        let source = match self.config.output_source.trim() {
+            "" => self.id.clone(),
+            source => source.to_string(),
+        };
+        Ok(Some(Signal::Computed {
+            source,
+            content: result.to_string(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn number(source: &str, value: f64) -> Signal {
+        Signal::Computed {
+            source: source.to_string(),
+            content: value.to_string(),
+        }
+    }
+
+    async fn result(math: &mut MathProcessor, signal: Signal) -> Option<f64> {
+        match math.process(signal).await.unwrap()? {
+            Signal::Computed { source, content } => {
+                assert_eq!(source, "math");
+                content.parse().ok()
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn binds_sources_to_variables() {
+        let config = MathConfig {
+            expression: "out = a * 0.5 + b".to_string(),
+            variables: vec![
+                MathVariable {
+                    name: "a".into(),
+                    source: "gamepad_axis_0".into(),
+                },
+                MathVariable {
+                    name: "b".into(),
+                    source: "level".into(),
+                },
+            ],
+            ..MathConfig::default()
+        };
+        let mut math = MathProcessor::new("math", config);
+
+        assert_eq!(
+            result(&mut math, number("gamepad_axis_0", 0.5)).await,
+            Some(0.25)
+        );
+        assert_eq!(result(&mut math, number("level", 2.0)).await, Some(2.25));
+        let json = Signal::Computed {
+            source: "level".into(),
+            content: r#"{"value": 1}"#.into(),
+        };
+        assert_eq!(result(&mut math, json).await, Some(1.25));
+        assert_eq!(result(&mut math, Signal::Text("3".into())).await, None);
+    }
+
+    #[tokio::test]
+    async fn unmapped_sources_are_x_and_bad_expressions_emit_nothing() {
+        let config = MathConfig {
+            expression: "sin(x) + 1".to_string(),
+            ..MathConfig::default()
+        };
+        let mut math = MathProcessor::new("math", config);
+        assert_eq!(result(&mut math, number("anything", 0.0)).await, Some(1.0));
+
+        math.set_config(MathConfig {
+            expression: "x +".to_string(),
+            ..MathConfig::default()
+        });
+        assert_eq!(result(&mut math, number("anything", 1.0)).await, None);
+
+        math.set_config(MathConfig {
+            expression: "x / 0".to_string(),
+            ..MathConfig::default()
+        });
+        assert_eq!(result(&mut math, number("anything", 1.0)).await, None);
+    }
+}
@@ -0,0 +1,349 @@
+use async_trait::async_trait;
+use magnolia_core::{
+    system_clock, ControlSignal, DataType, ModuleSchema, Port, PortDirection, Processor,
+    SharedClock, Signal,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::value::numeric_value;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatsConfig {
+    /// Values older than this are forgotten
+    pub window_secs: f32,
+    /// Only the newest this many are kept
+    pub max_samples: usize,
+    /// How often aggregates are emitted
+    pub interval_secs: f32,
+    /// Each adds a `pNN` entry
+    pub percentiles: Vec<f64>,
+    /// Only values from this source count; empty takes all
+    pub source: String,
+}
+
+impl Default for StatsConfig {
+    fn default() -> Self {
+        Self {
+            window_secs: 10.0,
+            max_samples: 10_000,
+            interval_secs: 1.0,
+            percentiles: vec![50.0, 90.0, 99.0],
+            source: String::new(),
+        }
+    }
+}
+
+impl StatsConfig {
+    pub fn schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "window_secs": {
+                    "type": "number",
+                    "title": "Window (s)",
+                    "minimum": 0.1,
+                    "maximum": 86400.0,
+                    "default": 10.0
+                },
+                "max_samples": {
+                    "type": "integer",
+                    "title": "Max Samples",
+                    "minimum": 1,
+                    "maximum": 1000000,
+                    "default": 10000
+                },
+                "interval_secs": {
+                    "type": "number",
+                    "title": "Emit Every (s)",
+                    "minimum": 0.05,
+                    "maximum": 3600.0,
+                    "default": 1.0
+                },
+                "percentiles": {
+                    "type": "array",
+                    "title": "Percentiles",
+                    "items": { "type": "number", "minimum": 0.0, "maximum": 100.0 },
+                    "default": [50.0, 90.0, 99.0]
+                },
+                "source": { "type": "string", "title": "Source (empty: all)", "default": "" }
+            }
+        })
+    }
+
+    fn window_us(&self) -> u64 {
+        (f64::from(self.window_secs.max(0.1)) * 1e6) as u64
+    }
+
+    fn interval_us(&self) -> u64 {
+        (f64::from(self.interval_secs.max(0.05)) * 1e6) as u64
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Aggregates {
+    pub count: usize,
+    pub mean: f64,
+    pub min: f64,
+    pub max: f64,
+    pub stddev: f64,
+    /// `p50`, `p90` ..
+    #[serde(flatten)]
+    pub percentiles: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Linear interpolation between the closest ranks of `sorted`
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = (p.clamp(0.0, 100.0) / 100.0) * (sorted.len() - 1) as f64;
+    let (low, high) = (rank.floor() as usize, rank.ceil() as usize);
+    sorted[low] + (sorted[high] - sorted[low]) * (rank - low as f64)
+}
+
+/// `p50`, `p99.9`
+fn percentile_key(p: f64) -> String {
+    format!("p{}", p)
+}
+
+/// Aggregates of `values`; `None` if empty
+pub fn aggregate(values: impl Iterator<Item = f64>, percentiles: &[f64]) -> Option<Aggregates> {
+    let mut sorted: Vec<f64> = values.collect();
+    if sorted.is_empty() {
+        return None;
+    }
+    sorted.sort_by(f64::total_cmp);
+    let count = sorted.len();
+    let mean = sorted.iter().sum::<f64>() / count as f64;
+    let variance = sorted.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / count as f64;
+    Some(Aggregates {
+        count,
+        mean,
+        min: sorted[0],
+        max: sorted[count - 1],
+        stddev: variance.sqrt(),
+        percentiles: percentiles
+            .iter()
+            .map(|&p| (percentile_key(p), percentile(&sorted, p).into()))
+            .collect(),
+    })
+}
+
+/// Keeps a rolling window of numeric values and emits their aggregates as
+/// JSON every interval, while the window has any
+pub struct StatsProcessor {
+    id: String,
+    enabled: bool,
+    config: StatsConfig,
+    clock: SharedClock,
+    /// Values with when they arrived, oldest first
+    samples: VecDeque<(u64, f64)>,
+    next_emit_us: Option<u64>,
+}
+
+impl StatsProcessor {
+    pub fn new(id: &str, config: StatsConfig) -> Self {
+        Self {
+            id: id.to_string(),
+            enabled: true,
+            config,
+            clock: system_clock(),
+            samples: VecDeque::new(),
+            next_emit_us: None,
+        }
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn config(&self) -> &StatsConfig {
+        &self.config
+    }
+
+    /// The window keeps what it has, trimmed to the new size on the next
+    /// value or emit
+    pub fn set_config(&mut self, config: StatsConfig) {
+        self.config = config;
+        self.next_emit_us = None;
+    }
+
+    fn evict(&mut self, now: u64) {
+        let window = self.config.window_us();
+        while self
+            .samples
+            .front()
+            .is_some_and(|&(at, _)| now.saturating_sub(at) > window)
+        {
+            self.samples.pop_front();
+        }
+        while self.samples.len() > self.config.max_samples.max(1) {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Aggregates of the current window
+    pub fn aggregates(&mut self) -> Option<Aggregates> {
+        self.evict(self.clock.now_us());
+        aggregate(
+            self.samples.iter().map(|&(_, v)| v),
+            &self.config.percentiles,
+        )
+    }
+}
+
+#[async_trait]
+impl Processor for StatsProcessor {
+    fn name(&self) -> &str {
+        "Statistics"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "Statistics".to_string(),
+            description: "Rolling mean, min, max and percentiles of numeric signals".to_string(),
+            ports: vec![
+                Port {
+                    id: "numeric_in".to_string(),
+                    label: "In".to_string(),
+                    data_type: DataType::Numeric,
+                    direction: PortDirection::Input,
+                },
+                Port {
+                    id: "stats_out".to_string(),
+                    label: "Aggregates".to_string(),
+                    data_type: DataType::Numeric,
+                    direction: PortDirection::Output,
+                },
+            ],
+            settings_schema: Some(StatsConfig::schema()),
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    async fn process(&mut self, signal: Signal) -> anyhow::Result<Option<Signal>> {
+        if let Signal::Control(ControlSignal::Settings(settings)) = signal {
+            match serde_json::from_value::<StatsConfig>(settings) {
+                Ok(config) => self.set_config(config),
+                Err(e) => log::warn!("Statistics: invalid settings, keeping current: {}", e),
+            }
+            return Ok(None);
+        }
+        let Some((source, value)) = numeric_value(&signal) else {
+            return Ok(None);
+        };
+        let wanted = self.config.source.trim();
+        if !wanted.is_empty() && wanted != source {
+            return Ok(None);
+        }
+        let now = self.clock.now_us();
+        self.samples.push_back((now, value));
+        self.evict(now);
+        self.next_emit_us
+            .get_or_insert(now + self.config.interval_us());
+        Ok(None)
+    }
+
+    async fn next_wake(&self) {
+        match self.next_emit_us {
+            Some(at) => {
+                let wait = at.saturating_sub(self.clock.now_us());
+                self.clock.sleep(Duration::from_micros(wait)).await;
+            }
+            None => std::future::pending().await,
+        }
+    }
+
+    async fn wake(&mut self) -> anyhow::Result<Option<Signal>> {
+        let now = self.clock.now_us();
+        match self.next_emit_us {
+            Some(at) if at <= now => {}
+            _ => return Ok(None),
+        }
+        let aggregates = self.aggregates();
+        // Idle once the window empties; the next value starts the schedule
+        self.next_emit_us = aggregates
+            .is_some()
+            .then(|| now + self.config.interval_us());
+        Ok(aggregates.map(|aggregates| Signal::Computed {
+            source: self.id.clone(),
+            content: serde_json::to_string(&aggregates).unwrap_or_default(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use magnolia_core::VirtualClock;
+
+    fn number(value: f64) -> Signal {
+        Signal::Computed {
+            source: "level".to_string(),
+            content: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn aggregates_with_interpolated_percentiles() {
+        let stats = aggregate([4.0, 1.0, 3.0, 2.0].into_iter(), &[50.0, 90.0]).unwrap();
+        assert_eq!(stats.count, 4);
+        assert_eq!(stats.mean, 2.5);
+        assert_eq!((stats.min, stats.max), (1.0, 4.0));
+        assert!((stats.stddev - 1.118).abs() < 1e-3);
+        assert_eq!(stats.percentiles["p50"], 2.5);
+        assert!((stats.percentiles["p90"].as_f64().unwrap() - 3.7).abs() < 1e-9);
+        assert_eq!(aggregate(std::iter::empty(), &[50.0]), None);
+    }
+
+    #[tokio::test]
+    async fn emits_every_interval_over_the_window() {
+        let clock = VirtualClock::manual();
+        let config = StatsConfig {
+            window_secs: 2.0,
+            interval_secs: 1.0,
+            percentiles: vec![50.0],
+            ..StatsConfig::default()
+        };
+        let mut stats = StatsProcessor::new("stats", config).with_clock(clock.clone());
+
+        for v in [1.0, 2.0, 3.0] {
+            assert!(stats.process(number(v)).await.unwrap().is_none());
+        }
+        assert!(stats.wake().await.unwrap().is_none());
+
+        clock.advance(Duration::from_secs(1));
+        let Some(Signal::Computed { source, content }) = stats.wake().await.unwrap() else {
+            panic!("expected aggregates");
+        };
+        assert_eq!(source, "stats");
+        let json: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(json["mean"], 2.0);
+        assert_eq!(json["p50"], 2.0);
+
+        stats.process(number(10.0)).await.unwrap();
+        clock.advance(Duration::from_millis(1500));
+        // The first three have left the window
+        let Some(Signal::Computed { content, .. }) = stats.wake().await.unwrap() else {
+            panic!("expected aggregates");
+        };
+        let json: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(json["count"], 1);
+        assert_eq!(json["max"], 10.0);
+
+        // Nothing left: no output and no more wakes
+        clock.advance(Duration::from_secs(5));
+        assert!(stats.wake().await.unwrap().is_none());
+        assert!(stats.next_emit_us.is_none());
+    }
+}
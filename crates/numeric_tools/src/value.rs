@@ -0,0 +1,18 @@
+use magnolia_core::Signal;
+
+/// The number a signal carries: `Computed` content that's a number, bare
+/// or as JSON `{"value": ..}`. `None` for anything else and for NaN or
+/// infinity.
+pub fn numeric_value(signal: &Signal) -> Option<(&str, f64)> {
+    let Signal::Computed { source, content } = signal else {
+        return None;
+    };
+    let content = content.trim();
+    let value = content.parse::<f64>().ok().or_else(|| {
+        serde_json::from_str::<serde_json::Value>(content)
+            .ok()?
+            .get("value")?
+            .as_f64()
+    })?;
+    value.is_finite().then_some((source.as_str(), value))
+}
@@ -0,0 +1,468 @@
+//! The connection thread and the sink/source pair sharing it.
+
+use anyhow::{anyhow, bail};
+use async_trait::async_trait;
+use magnolia_core::{
+    ControlSignal, DataType, ModuleSchema, Port, PortDirection, Signal, Sink, Source,
+    WorkerSettings,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::{mpsc as std_mpsc, Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+use crate::protocol::{self, ServerMessage};
+use crate::ws::{Message, WsClient};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+const POLL_TIMEOUT: Duration = Duration::from_millis(50);
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+const EVENT_QUEUE: usize = 256;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ObsConfig {
+    pub host: String,
+    pub port: u16,
+    /// Empty when OBS has authentication off
+    pub password: String,
+    pub connect: bool,
+}
+
+impl Default for ObsConfig {
+    fn default() -> Self {
+        Self {
+            host: "127.0.0.1".to_string(),
+            port: 4455,
+            password: String::new(),
+            connect: true,
+        }
+    }
+}
+
+impl ObsConfig {
+    pub fn schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "host": { "type": "string", "title": "Host", "default": "127.0.0.1" },
+                "port": {
+                    "type": "integer",
+                    "title": "Port",
+                    "minimum": 1,
+                    "maximum": 65535,
+                    "default": 4455
+                },
+                "password": { "type": "string", "title": "Password", "default": "" },
+                "connect": { "type": "boolean", "title": "Connect", "default": true }
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ObsStatus {
+    Disabled,
+    Connecting,
+    Connected,
+    /// Last connection error; retried with backoff
+    Failed(String),
+}
+
+struct Shared {
+    config: WorkerSettings<ObsConfig>,
+    status: Mutex<ObsStatus>,
+}
+
+impl Shared {
+    fn set_status(&self, status: ObsStatus) {
+        if let Ok(mut current) = self.status.lock() {
+            *current = status;
+        }
+    }
+
+    fn status(&self) -> ObsStatus {
+        self.status
+            .lock()
+            .map(|s| s.clone())
+            .unwrap_or(ObsStatus::Disabled)
+    }
+}
+
+/// Start the connection thread; `sink_id` takes commands and settings,
+/// `source_id` emits OBS events
+pub fn spawn(
+    sink_id: &str,
+    source_id: &str,
+    config: ObsConfig,
+) -> std::io::Result<(ObsSink, ObsSource)> {
+    let shared = Arc::new(Shared {
+        config: WorkerSettings::new(config),
+        status: Mutex::new(ObsStatus::Connecting),
+    });
+    let (command_tx, command_rx) = std_mpsc::channel();
+    let (event_tx, event_rx) = mpsc::channel(EVENT_QUEUE);
+    let thread_shared = shared.clone();
+    std::thread::Builder::new()
+        .name("obs-bridge".to_string())
+        .spawn(move || run(thread_shared, command_rx, event_tx))?;
+    Ok((
+        ObsSink::new(sink_id, command_tx, shared),
+        ObsSource::new(source_id, event_rx),
+    ))
+}
+
+type Command = (String, Value);
+
+fn run(shared: Arc<Shared>, commands: std_mpsc::Receiver<Command>, events: mpsc::Sender<Signal>) {
+    let mut backoff = Duration::from_secs(1);
+    while !events.is_closed() {
+        let (generation, config) = shared.config.current();
+        // Commands sent while offline are dropped rather than fired late
+        while commands.try_recv().is_ok() {}
+        if !config.connect {
+            shared.set_status(ObsStatus::Disabled);
+            shared.config.wait(generation, Duration::from_secs(1));
+            continue;
+        }
+
+        shared.set_status(ObsStatus::Connecting);
+        let result = session(&shared, &config, generation, &commands, &events);
+        let _ = events.blocking_send(Signal::Intent {
+            action: "obs_disconnected".to_string(),
+            parameters: Vec::new(),
+        });
+        match result {
+            Ok(connected) => {
+                shared.set_status(ObsStatus::Connecting);
+                if connected {
+                    backoff = Duration::from_secs(1);
+                }
+            }
+            Err(e) => {
+                if !matches!(shared.status(), ObsStatus::Failed(_)) {
+                    log::warn!("OBS bridge: {}:{}: {}", config.host, config.port, e);
+                }
+                shared.set_status(ObsStatus::Failed(e.to_string()));
+            }
+        }
+        shared.config.wait(generation, backoff);
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
+
+/// One connection, until it drops or the settings change. `Ok(true)` if it
+/// got as far as identifying.
+fn session(
+    shared: &Shared,
+    config: &ObsConfig,
+    generation: u64,
+    commands: &std_mpsc::Receiver<Command>,
+    events: &mpsc::Sender<Signal>,
+) -> anyhow::Result<bool> {
+    let mut ws = WsClient::connect(&config.host, config.port, CONNECT_TIMEOUT, POLL_TIMEOUT)?;
+
+    let deadline = Instant::now() + HANDSHAKE_TIMEOUT;
+    let mut identified = false;
+    while !identified {
+        if Instant::now() > deadline {
+            bail!("no handshake from OBS");
+        }
+        match ws.poll()? {
+            Some(Message::Text(text)) => match protocol::parse(&text) {
+                Some(ServerMessage::Hello { authentication }) => {
+                    if authentication.is_some() && config.password.is_empty() {
+                        bail!("OBS requires a password");
+                    }
+                    ws.send_text(&protocol::identify(
+                        authentication.as_ref(),
+                        &config.password,
+                    ))?;
+                }
+                Some(ServerMessage::Identified) => identified = true,
+                _ => {}
+            },
+            // OBS closes the socket on a wrong password
+            Some(Message::Close) => bail!("closed during handshake (wrong password?)"),
+            None => {}
+        }
+    }
+    log::info!("OBS bridge connected to {}:{}", config.host, config.port);
+    shared.set_status(ObsStatus::Connected);
+    if events
+        .blocking_send(Signal::Intent {
+            action: "obs_connected".to_string(),
+            parameters: Vec::new(),
+        })
+        .is_err()
+    {
+        return Ok(true);
+    }
+
+    let mut next_request = 0u64;
+    loop {
+        if shared.config.changed_since(generation) || events.is_closed() {
+            ws.close();
+            return Ok(true);
+        }
+        while let Ok((request_type, data)) = commands.try_recv() {
+            next_request += 1;
+            let request = protocol::request(&next_request.to_string(), &request_type, data);
+            ws.send_text(&request)?;
+        }
+        match ws.poll()? {
+            Some(Message::Text(text)) => match protocol::parse(&text) {
+                Some(ServerMessage::Event { event_type, data }) => {
+                    for signal in protocol::event_signals(&event_type, &data) {
+                        if events.blocking_send(signal).is_err() {
+                            return Ok(true);
+                        }
+                    }
+                }
+                Some(ServerMessage::RequestResponse {
+                    request_type,
+                    ok: false,
+                    comment,
+                    ..
+                }) => log::warn!(
+                    "OBS bridge: {} failed: {}",
+                    request_type,
+                    comment.unwrap_or_default()
+                ),
+                _ => {}
+            },
+            Some(Message::Close) => return Err(anyhow!("connection closed by OBS")),
+            None => {}
+        }
+    }
+}
+
+/// Sends OBS requests for the command intents described in
+/// [`protocol::intent_request`]; plain text is read as `action args..`
+pub struct ObsSink {
+    id: String,
+    enabled: bool,
+    commands: std_mpsc::Sender<Command>,
+    shared: Arc<Shared>,
+}
+
+impl ObsSink {
+    fn new(id: &str, commands: std_mpsc::Sender<Command>, shared: Arc<Shared>) -> Self {
+        Self {
+            id: id.to_string(),
+            enabled: true,
+            commands,
+            shared,
+        }
+    }
+
+    pub fn status(&self) -> ObsStatus {
+        self.shared.status()
+    }
+
+    pub fn set_config(&self, config: ObsConfig) {
+        self.shared.config.set(config);
+    }
+}
+
+#[async_trait]
+impl Sink for ObsSink {
+    fn name(&self) -> &str {
+        "OBS Control"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "OBS Control".to_string(),
+            description: "Switches OBS scenes and controls recording over obs-websocket"
+                .to_string(),
+            ports: vec![Port {
+                id: "commands_in".to_string(),
+                label: "Commands".to_string(),
+                data_type: DataType::Control,
+                direction: PortDirection::Input,
+            }],
+            settings_schema: Some(ObsConfig::schema()),
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    fn render_output(&self) -> Option<String> {
+        Some(match self.status() {
+            ObsStatus::Disabled => "OBS: disabled".to_string(),
+            ObsStatus::Connecting => "OBS: connecting".to_string(),
+            ObsStatus::Connected => "OBS: connected".to_string(),
+            ObsStatus::Failed(e) => format!("OBS: {}", e),
+        })
+    }
+
+    async fn consume(&self, signal: Signal) -> magnolia_core::Result<Option<Signal>> {
+        let (action, parameters) = match signal {
+            Signal::Control(ControlSignal::Settings(settings)) => {
+                match serde_json::from_value::<ObsConfig>(settings) {
+                    Ok(config) => self.set_config(config),
+                    Err(e) => log::warn!("OBS bridge: invalid settings, keeping current: {}", e),
+                }
+                return Ok(None);
+            }
+            _ if !self.enabled => return Ok(None),
+            Signal::Intent { action, parameters } => (action, parameters),
+            Signal::Text(text) => {
+                let mut words = text.split_whitespace().map(str::to_string);
+                let Some(action) = words.next() else {
+                    return Ok(None);
+                };
+                (action, words.collect())
+            }
+            _ => return Ok(None),
+        };
+        match protocol::intent_request(&action, &parameters) {
+            Some(request) => {
+                if self.status() != ObsStatus::Connected {
+                    log::debug!("OBS bridge: not connected, dropping {}", request.0);
+                }
+                let _ = self.commands.send(request);
+            }
+            None => log::debug!("OBS bridge: no request for {:?}", action),
+        }
+        Ok(None)
+    }
+}
+
+/// OBS events: each as JSON on `events_out`, plus connection changes and
+/// common events as intents on `intents_out`
+pub struct ObsSource {
+    id: String,
+    enabled: bool,
+    events: mpsc::Receiver<Signal>,
+}
+
+impl ObsSource {
+    fn new(id: &str, events: mpsc::Receiver<Signal>) -> Self {
+        Self {
+            id: id.to_string(),
+            enabled: true,
+            events,
+        }
+    }
+}
+
+#[async_trait]
+impl Source for ObsSource {
+    fn name(&self) -> &str {
+        "OBS Events"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "OBS Events".to_string(),
+            description: "Scene, recording and stream events from OBS".to_string(),
+            ports: vec![
+                Port {
+                    id: "events_out".to_string(),
+                    label: "Events".to_string(),
+                    data_type: DataType::Text,
+                    direction: PortDirection::Output,
+                },
+                Port {
+                    id: "intents_out".to_string(),
+                    label: "Intents".to_string(),
+                    data_type: DataType::Control,
+                    direction: PortDirection::Output,
+                },
+            ],
+            settings_schema: None,
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    async fn poll(&mut self) -> Option<Signal> {
+        loop {
+            let signal = self.events.recv().await?;
+            if self.enabled {
+                return Some(signal);
+            }
+        }
+    }
+
+    fn output_port(&self, signal: &Signal) -> Option<&str> {
+        match signal {
+            Signal::Intent { .. } => Some("intents_out"),
+            _ => Some("events_out"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn sink_queues_requests_and_applies_settings() {
+        let shared = Arc::new(Shared {
+            config: WorkerSettings::new(ObsConfig::default()),
+            status: Mutex::new(ObsStatus::Connected),
+        });
+        let (tx, rx) = std_mpsc::channel();
+        let sink = ObsSink::new("obs_control", tx, shared.clone());
+
+        sink.consume(Signal::Intent {
+            action: "scene".into(),
+            parameters: vec!["Intro".into()],
+        })
+        .await
+        .unwrap();
+        sink.consume(Signal::Text("record stop".into()))
+            .await
+            .unwrap();
+        sink.consume(Signal::Text("hello there".into()))
+            .await
+            .unwrap();
+        let queued: Vec<String> = rx.try_iter().map(|(request, _)| request).collect();
+        assert_eq!(queued, vec!["SetCurrentProgramScene", "StopRecord"]);
+
+        let settings = serde_json::json!({ "port": 4456, "password": "pw" });
+        sink.consume(Signal::Control(ControlSignal::Settings(settings)))
+            .await
+            .unwrap();
+        assert_eq!(shared.config.get().port, 4456);
+        assert_eq!(shared.config.get().host, "127.0.0.1");
+        assert_eq!(shared.config.generation(), 1);
+        assert_eq!(sink.render_output().as_deref(), Some("OBS: connected"));
+
+        let (event_tx, event_rx) = mpsc::channel(4);
+        let mut source = ObsSource::new("obs", event_rx);
+        for signal in protocol::event_signals(
+            "CurrentProgramSceneChanged",
+            &serde_json::json!({ "sceneName": "Intro" }),
+        ) {
+            event_tx.send(signal).await.unwrap();
+        }
+        let event = source.poll().await.unwrap();
+        assert_eq!(source.output_port(&event), Some("events_out"));
+        let intent = source.poll().await.unwrap();
+        assert_eq!(source.output_port(&intent), Some("intents_out"));
+        drop(event_tx);
+        assert!(source.poll().await.is_none());
+    }
+}
@@ -0,0 +1,13 @@
+//! OBS bridge: obs-websocket v5 in both directions.
+//!
+//! `OBS Control` (a sink) turns intents such as `scene Intro` or
+//! `record start` into OBS requests; `OBS Events` (a source) emits OBS
+//! events back into the patch bay, so overlays can follow scene changes and
+//! recording state. Both share one connection thread that reconnects with
+//! backoff while OBS isn't running.
+
+mod bridge;
+pub mod protocol;
+mod ws;
+
+pub use bridge::{spawn, ObsConfig, ObsSink, ObsSource, ObsStatus};
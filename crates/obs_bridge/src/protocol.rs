@@ -0,0 +1,283 @@
+//! obs-websocket v5 messages: the identify handshake, requests built from
+//! intents and events turned back into signals.
+
+use base64::Engine;
+use magnolia_core::Signal;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+pub const RPC_VERSION: u64 = 1;
+
+const OP_HELLO: u64 = 0;
+const OP_IDENTIFY: u64 = 1;
+const OP_IDENTIFIED: u64 = 2;
+const OP_EVENT: u64 = 5;
+const OP_REQUEST: u64 = 6;
+const OP_REQUEST_RESPONSE: u64 = 7;
+
+/// General, Config, Scenes, Inputs, Transitions, Filters, Outputs,
+/// SceneItems, MediaInputs, Vendors, Ui: everything but the high-volume
+/// meter and activity events
+pub const EVENT_SUBSCRIPTIONS: u64 = (1 << 11) - 1;
+
+/// Source name of the JSON events emitted on `events_out`
+pub const EVENT_SOURCE: &str = "obs";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ServerMessage {
+    Hello {
+        /// `(challenge, salt)` when OBS has a password set
+        authentication: Option<(String, String)>,
+    },
+    Identified,
+    Event {
+        event_type: String,
+        data: Value,
+    },
+    RequestResponse {
+        request_id: String,
+        request_type: String,
+        ok: bool,
+        comment: Option<String>,
+    },
+    Other,
+}
+
+pub fn parse(text: &str) -> Option<ServerMessage> {
+    let message: Value = serde_json::from_str(text).ok()?;
+    let op = message.get("op")?.as_u64()?;
+    let d = message.get("d")?;
+    let field =
+        |value: &Value, name: &str| value.get(name).and_then(Value::as_str).map(str::to_string);
+    Some(match op {
+        OP_HELLO => ServerMessage::Hello {
+            authentication: d
+                .get("authentication")
+                .and_then(|auth| Some((field(auth, "challenge")?, field(auth, "salt")?))),
+        },
+        OP_IDENTIFIED => ServerMessage::Identified,
+        OP_EVENT => ServerMessage::Event {
+            event_type: field(d, "eventType")?,
+            data: d.get("eventData").cloned().unwrap_or(Value::Null),
+        },
+        OP_REQUEST_RESPONSE => {
+            let status = d.get("requestStatus");
+            ServerMessage::RequestResponse {
+                request_id: field(d, "requestId").unwrap_or_default(),
+                request_type: field(d, "requestType").unwrap_or_default(),
+                ok: status
+                    .and_then(|s| s.get("result"))
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false),
+                comment: status.and_then(|s| field(s, "comment")),
+            }
+        }
+        _ => ServerMessage::Other,
+    })
+}
+
+/// `base64(sha256(base64(sha256(password + salt)) + challenge))`
+pub fn auth_response(password: &str, salt: &str, challenge: &str) -> String {
+    let b64 = base64::engine::general_purpose::STANDARD;
+    let secret = b64.encode(Sha256::digest(format!("{password}{salt}")));
+    b64.encode(Sha256::digest(format!("{secret}{challenge}")))
+}
+
+pub fn identify(authentication: Option<&(String, String)>, password: &str) -> String {
+    let mut d = json!({
+        "rpcVersion": RPC_VERSION,
+        "eventSubscriptions": EVENT_SUBSCRIPTIONS,
+    });
+    if let Some((challenge, salt)) = authentication {
+        d["authentication"] = auth_response(password, salt, challenge).into();
+    }
+    json!({ "op": OP_IDENTIFY, "d": d }).to_string()
+}
+
+pub fn request(request_id: &str, request_type: &str, data: Value) -> String {
+    let mut d = json!({ "requestType": request_type, "requestId": request_id });
+    if !data.is_null() {
+        d["requestData"] = data;
+    }
+    json!({ "op": OP_REQUEST, "d": d }).to_string()
+}
+
+/// OBS request for a command intent:
+///
+/// - `scene <name>` switches the program scene
+/// - `record|stream start|stop|toggle`, `record pause|resume`
+/// - `mute|unmute <input>`, `replay save`
+/// - `obs <RequestType> [json data]` sends any request as-is
+pub fn intent_request(action: &str, parameters: &[String]) -> Option<(String, Value)> {
+    let argument = parameters.join(" ");
+    let verb = parameters.first().map(|p| p.to_ascii_lowercase());
+    let toggle = |noun: &str| -> Option<(String, Value)> {
+        let request = match verb.as_deref().unwrap_or("toggle") {
+            "start" => format!("Start{noun}"),
+            "stop" => format!("Stop{noun}"),
+            "toggle" => format!("Toggle{noun}"),
+            "pause" if noun == "Record" => "PauseRecord".to_string(),
+            "resume" if noun == "Record" => "ResumeRecord".to_string(),
+            _ => return None,
+        };
+        Some((request, Value::Null))
+    };
+    match action.to_ascii_lowercase().as_str() {
+        "scene" | "obs_scene" if !argument.trim().is_empty() => Some((
+            "SetCurrentProgramScene".to_string(),
+            json!({ "sceneName": argument.trim() }),
+        )),
+        "record" | "recording" => toggle("Record"),
+        "stream" | "streaming" => toggle("Stream"),
+        "replay" if verb.as_deref() == Some("save") => {
+            Some(("SaveReplayBuffer".to_string(), Value::Null))
+        }
+        action @ ("mute" | "unmute") if !argument.trim().is_empty() => Some((
+            "SetInputMute".to_string(),
+            json!({ "inputName": argument.trim(), "inputMuted": action == "mute" }),
+        )),
+        "obs" => {
+            let (request_type, data) = match argument.trim().split_once(char::is_whitespace) {
+                Some((request_type, data)) => (request_type, serde_json::from_str(data).ok()?),
+                None => (argument.trim(), Value::Null),
+            };
+            (!request_type.is_empty()).then(|| (request_type.to_string(), data))
+        }
+        _ => None,
+    }
+}
+
+/// Signals for an OBS event: the event as JSON, and an intent for the ones
+/// patches commonly react to
+pub fn event_signals(event_type: &str, data: &Value) -> Vec<Signal> {
+    let mut signals = vec![Signal::Computed {
+        source: EVENT_SOURCE.to_string(),
+        content: json!({ "eventType": event_type, "eventData": data }).to_string(),
+    }];
+    let text = |name: &str| {
+        data.get(name)
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string()
+    };
+    let output_state = || {
+        // OBS_WEBSOCKET_OUTPUT_STARTED → "started"
+        text("outputState")
+            .rsplit('_')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase()
+    };
+    let intent = match event_type {
+        "CurrentProgramSceneChanged" => Some(("obs_scene_changed", vec![text("sceneName")])),
+        "RecordStateChanged" => Some(("obs_record", vec![output_state()])),
+        "StreamStateChanged" => Some(("obs_stream", vec![output_state()])),
+        "ReplayBufferSaved" => Some(("obs_replay_saved", vec![text("savedReplayPath")])),
+        "InputMuteStateChanged" => Some((
+            "obs_input_muted",
+            vec![
+                text("inputName"),
+                data.get("inputMuted")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false)
+                    .to_string(),
+            ],
+        )),
+        "ExitStarted" => Some(("obs_exiting", Vec::new())),
+        _ => None,
+    };
+    if let Some((action, parameters)) = intent {
+        signals.push(Signal::Intent {
+            action: action.to_string(),
+            parameters,
+        });
+    }
+    signals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(words: &[&str]) -> Vec<String> {
+        words.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn handshake_and_requests() {
+        let hello = r#"{"op":0,"d":{"obsWebSocketVersion":"5.1.0","rpcVersion":1,
+            "authentication":{"challenge":"c","salt":"s"}}}"#;
+        let Some(ServerMessage::Hello { authentication }) = parse(hello) else {
+            panic!("expected hello");
+        };
+        let identify: Value =
+            serde_json::from_str(&identify(authentication.as_ref(), "pw")).unwrap();
+        assert_eq!(identify["op"], 1);
+        assert_eq!(
+            identify["d"]["authentication"],
+            auth_response("pw", "s", "c")
+        );
+        assert_ne!(auth_response("pw", "s", "c"), auth_response("pw", "s", "d"));
+        assert!(
+            serde_json::from_str::<Value>(&self::identify(None, "")).unwrap()["d"]
+                .get("authentication")
+                .is_none()
+        );
+
+        assert_eq!(
+            intent_request("scene", &params(&["Be", "Right", "Back"])),
+            Some((
+                "SetCurrentProgramScene".into(),
+                json!({ "sceneName": "Be Right Back" })
+            ))
+        );
+        assert_eq!(
+            intent_request("record", &params(&["start"])).unwrap().0,
+            "StartRecord"
+        );
+        assert_eq!(intent_request("stream", &[]).unwrap().0, "ToggleStream");
+        assert_eq!(intent_request("stream", &params(&["pause"])), None);
+        assert_eq!(
+            intent_request(
+                "obs",
+                &params(&["SetStudioModeEnabled", r#"{"studioModeEnabled":true}"#])
+            ),
+            Some((
+                "SetStudioModeEnabled".into(),
+                json!({ "studioModeEnabled": true })
+            ))
+        );
+        assert_eq!(intent_request("scene", &[]), None);
+        assert_eq!(intent_request("sigil", &params(&["x"])), None);
+    }
+
+    #[test]
+    fn events_become_signals() {
+        let event = r#"{"op":5,"d":{"eventType":"RecordStateChanged","eventIntent":64,
+            "eventData":{"outputActive":true,"outputState":"OBS_WEBSOCKET_OUTPUT_STARTED"}}}"#;
+        let Some(ServerMessage::Event { event_type, data }) = parse(event) else {
+            panic!("expected event");
+        };
+        let signals = event_signals(&event_type, &data);
+        assert_eq!(signals.len(), 2);
+        assert!(matches!(&signals[0], Signal::Computed { source, .. } if source == EVENT_SOURCE));
+        assert!(matches!(
+            &signals[1],
+            Signal::Intent { action, parameters } if action == "obs_record" && parameters == &["started"]
+        ));
+        assert_eq!(event_signals("SceneListChanged", &Value::Null).len(), 1);
+
+        let response = r#"{"op":7,"d":{"requestType":"SetCurrentProgramScene","requestId":"3",
+            "requestStatus":{"result":false,"code":600,"comment":"No source was found"}}}"#;
+        assert_eq!(
+            parse(response),
+            Some(ServerMessage::RequestResponse {
+                request_id: "3".into(),
+                request_type: "SetCurrentProgramScene".into(),
+                ok: false,
+                comment: Some("No source was found".into()),
+            })
+        );
+        assert_eq!(parse("not json"), None);
+    }
+}
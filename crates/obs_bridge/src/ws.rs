@@ -0,0 +1,316 @@
+//! Minimal blocking WebSocket client (RFC 6455), enough for obs-websocket:
+//! text messages, ping/pong and close over plain TCP on a local network.
+
+use base64::Engine;
+use std::io::{self, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const OP_CONTINUATION: u8 = 0x0;
+const OP_TEXT: u8 = 0x1;
+const OP_BINARY: u8 = 0x2;
+const OP_CLOSE: u8 = 0x8;
+const OP_PING: u8 = 0x9;
+const OP_PONG: u8 = 0xA;
+
+/// Frames larger than this are treated as a protocol error
+const MAX_FRAME: usize = 16 << 20;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Message {
+    Text(String),
+    Close,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Frame {
+    pub fin: bool,
+    pub opcode: u8,
+    pub payload: Vec<u8>,
+}
+
+/// One frame from the front of `buffer` and the bytes it took, or `None`
+/// if more data is needed
+pub(crate) fn parse_frame(buffer: &[u8]) -> io::Result<Option<(Frame, usize)>> {
+    if buffer.len() < 2 {
+        return Ok(None);
+    }
+    let fin = buffer[0] & 0x80 != 0;
+    let opcode = buffer[0] & 0x0F;
+    let masked = buffer[1] & 0x80 != 0;
+    let (len, mut at) = match buffer[1] & 0x7F {
+        126 if buffer.len() >= 4 => (u16::from_be_bytes([buffer[2], buffer[3]]) as usize, 4),
+        127 if buffer.len() >= 10 => {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(&buffer[2..10]);
+            (u64::from_be_bytes(bytes) as usize, 10)
+        }
+        126 | 127 => return Ok(None),
+        len => (len as usize, 2),
+    };
+    if len > MAX_FRAME {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("frame of {} bytes", len),
+        ));
+    }
+    let mask = if masked {
+        if buffer.len() < at + 4 {
+            return Ok(None);
+        }
+        let mask = [buffer[at], buffer[at + 1], buffer[at + 2], buffer[at + 3]];
+        at += 4;
+        Some(mask)
+    } else {
+        None
+    };
+    if buffer.len() < at + len {
+        return Ok(None);
+    }
+    let mut payload = buffer[at..at + len].to_vec();
+    if let Some(mask) = mask {
+        apply_mask(&mut payload, mask);
+    }
+    Ok(Some((
+        Frame {
+            fin,
+            opcode,
+            payload,
+        },
+        at + len,
+    )))
+}
+
+/// Client frames are always masked
+pub(crate) fn encode_frame(opcode: u8, payload: &[u8], mask: [u8; 4]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(payload.len() + 14);
+    out.push(0x80 | opcode);
+    match payload.len() {
+        len @ 0..=125 => out.push(0x80 | len as u8),
+        len @ 126..=0xFFFF => {
+            out.push(0x80 | 126);
+            out.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            out.push(0x80 | 127);
+            out.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+    out.extend_from_slice(&mask);
+    let start = out.len();
+    out.extend_from_slice(payload);
+    apply_mask(&mut out[start..], mask);
+    out
+}
+
+fn apply_mask(data: &mut [u8], mask: [u8; 4]) {
+    for (i, byte) in data.iter_mut().enumerate() {
+        *byte ^= mask[i % 4];
+    }
+}
+
+/// xorshift seeded from the clock: masks and handshake keys only need to be
+/// unpredictable to intermediaries, not cryptographically random
+struct Nonce(u64);
+
+impl Nonce {
+    fn new() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0x9E37_79B9_7F4A_7C15);
+        Self(nanos | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn mask(&mut self) -> [u8; 4] {
+        let bytes = self.next().to_le_bytes();
+        [bytes[0], bytes[1], bytes[2], bytes[3]]
+    }
+}
+
+pub struct WsClient {
+    stream: TcpStream,
+    buffer: Vec<u8>,
+    /// Payload of a fragmented message still being received
+    partial: Option<Vec<u8>>,
+    nonce: Nonce,
+}
+
+impl WsClient {
+    /// Connect and upgrade; reads then block for at most `poll_timeout`
+    pub fn connect(
+        host: &str,
+        port: u16,
+        connect_timeout: Duration,
+        poll_timeout: Duration,
+    ) -> io::Result<Self> {
+        let addr = (host, port)
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "host not found"))?;
+        let mut stream = TcpStream::connect_timeout(&addr, connect_timeout)?;
+        stream.set_read_timeout(Some(connect_timeout))?;
+        stream.set_nodelay(true)?;
+
+        let mut nonce = Nonce::new();
+        let mut key = [0u8; 16];
+        key[..8].copy_from_slice(&nonce.next().to_le_bytes());
+        key[8..].copy_from_slice(&nonce.next().to_le_bytes());
+        let key = base64::engine::general_purpose::STANDARD.encode(key);
+        write!(
+            stream,
+            "GET / HTTP/1.1\r\nHost: {host}:{port}\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+             Sec-WebSocket-Key: {key}\r\nSec-WebSocket-Version: 13\r\n\
+             Sec-WebSocket-Protocol: obswebsocket.json\r\n\r\n"
+        )?;
+
+        // Response headers, plus whatever frames arrived right behind them
+        let mut buffer = Vec::new();
+        let mut chunk = [0u8; 1024];
+        let header_end = loop {
+            if let Some(end) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+                break end + 4;
+            }
+            if buffer.len() > 16 * 1024 {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "oversized handshake",
+                ));
+            }
+            let n = stream.read(&mut chunk)?;
+            if n == 0 {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            buffer.extend_from_slice(&chunk[..n]);
+        };
+        let status = String::from_utf8_lossy(&buffer[..header_end]);
+        let status_line = status.lines().next().unwrap_or_default();
+        if status_line.split_whitespace().nth(1) != Some("101") {
+            return Err(io::Error::new(
+                io::ErrorKind::ConnectionRefused,
+                format!("upgrade refused: {}", status_line),
+            ));
+        }
+        buffer.drain(..header_end);
+        stream.set_read_timeout(Some(poll_timeout))?;
+        Ok(Self {
+            stream,
+            buffer,
+            partial: None,
+            nonce,
+        })
+    }
+
+    pub fn send_text(&mut self, text: &str) -> io::Result<()> {
+        self.send(OP_TEXT, text.as_bytes())
+    }
+
+    fn send(&mut self, opcode: u8, payload: &[u8]) -> io::Result<()> {
+        let frame = encode_frame(opcode, payload, self.nonce.mask());
+        self.stream.write_all(&frame)
+    }
+
+    pub fn close(&mut self) {
+        let _ = self.send(OP_CLOSE, &[]);
+    }
+
+    /// Next complete message, or `None` if nothing arrived within the poll
+    /// timeout. Pings are answered here.
+    pub fn poll(&mut self) -> io::Result<Option<Message>> {
+        loop {
+            while let Some((frame, used)) = parse_frame(&self.buffer)? {
+                self.buffer.drain(..used);
+                if let Some(message) = self.on_frame(frame)? {
+                    return Ok(Some(message));
+                }
+            }
+            let mut chunk = [0u8; 8192];
+            match self.stream.read(&mut chunk) {
+                Ok(0) => return Ok(Some(Message::Close)),
+                Ok(n) => self.buffer.extend_from_slice(&chunk[..n]),
+                Err(e)
+                    if matches!(
+                        e.kind(),
+                        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                    ) =>
+                {
+                    return Ok(None)
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    fn on_frame(&mut self, frame: Frame) -> io::Result<Option<Message>> {
+        match frame.opcode {
+            OP_PING => {
+                self.send(OP_PONG, &frame.payload)?;
+                Ok(None)
+            }
+            OP_PONG => Ok(None),
+            OP_CLOSE => {
+                self.close();
+                Ok(Some(Message::Close))
+            }
+            OP_TEXT | OP_BINARY | OP_CONTINUATION => {
+                let mut payload = match (frame.opcode, self.partial.take()) {
+                    (OP_CONTINUATION, Some(mut partial)) => {
+                        partial.extend_from_slice(&frame.payload);
+                        partial
+                    }
+                    (OP_CONTINUATION, None) => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            "continuation without a message",
+                        ))
+                    }
+                    _ => frame.payload,
+                };
+                if !frame.fin {
+                    self.partial = Some(std::mem::take(&mut payload));
+                    return Ok(None);
+                }
+                // obs-websocket's JSON protocol only sends text
+                Ok(String::from_utf8(payload).ok().map(Message::Text))
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_round_trip_through_masking() {
+        let long = "x".repeat(300);
+        for payload in ["", "hello", long.as_str()] {
+            let encoded = encode_frame(OP_TEXT, payload.as_bytes(), [1, 2, 3, 4]);
+            let (frame, used) = parse_frame(&encoded).unwrap().unwrap();
+            assert_eq!(used, encoded.len());
+            assert!(frame.fin);
+            assert_eq!(frame.opcode, OP_TEXT);
+            assert_eq!(frame.payload, payload.as_bytes());
+            // Incomplete input waits for more
+            assert!(parse_frame(&encoded[..encoded.len() - 1])
+                .unwrap()
+                .is_none());
+        }
+
+        // Unmasked server frame: FIN + text, 2 bytes
+        let (frame, used) = parse_frame(&[0x81, 0x02, b'o', b'k', 0xFF])
+            .unwrap()
+            .unwrap();
+        assert_eq!((frame.payload.as_slice(), used), (&b"ok"[..], 4));
+        let oversized = [0x81, 127, 0xFF, 0, 0, 0, 0, 0, 0, 0];
+        assert!(parse_frame(&oversized).is_err());
+    }
+}
@@ -0,0 +1,17 @@
+//! Particle field for "something happened" visuals.
+//!
+//! [`ParticleSink`] turns Pulse, numeric `Computed` and Text signals into
+//! [`ParticleEvent`]s; the tile drains them each frame into a
+//! [`ParticleSystem`]. Pulses emit a burst, text emits one particle per
+//! character in a hue derived from the text, and numeric values drive the
+//! configured emitter parameter.
+
+mod sink;
+pub mod system;
+#[cfg(feature = "tile-rendering")]
+mod tile;
+
+pub use sink::{ParticleEvents, ParticleSink, MAX_QUEUED_EVENTS};
+pub use system::{NumericTarget, Particle, ParticleConfig, ParticleEvent, ParticleSystem};
+#[cfg(feature = "tile-rendering")]
+pub use tile::ParticleTile;
@@ -0,0 +1,164 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use magnolia_core::{DataType, ModuleSchema, Port, PortDirection, Signal, Sink};
+
+use crate::system::ParticleEvent;
+
+/// Events held for the tile between frames; the oldest are dropped beyond this
+pub const MAX_QUEUED_EVENTS: usize = 1024;
+
+/// Events from the sink, drained by the tile every frame
+#[derive(Debug, Default)]
+pub struct ParticleEvents {
+    queue: Mutex<VecDeque<ParticleEvent>>,
+}
+
+impl ParticleEvents {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    pub fn push(&self, event: ParticleEvent) {
+        if let Ok(mut queue) = self.queue.lock() {
+            if queue.len() >= MAX_QUEUED_EVENTS {
+                queue.pop_front();
+            }
+            queue.push_back(event);
+        }
+    }
+
+    pub fn drain(&self) -> Vec<ParticleEvent> {
+        self.queue
+            .lock()
+            .map(|mut queue| queue.drain(..).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Turns Pulse, numeric and text signals into [`ParticleEvent`]s
+pub struct ParticleSink {
+    id: String,
+    enabled: bool,
+    events: Arc<ParticleEvents>,
+}
+
+impl ParticleSink {
+    pub fn new(id: &str, events: Arc<ParticleEvents>) -> Self {
+        Self {
+            id: id.to_string(),
+            enabled: true,
+            events,
+        }
+    }
+
+    pub fn event_for(signal: Signal) -> Option<ParticleEvent> {
+        match signal {
+            Signal::Pulse => Some(ParticleEvent::Pulse),
+            Signal::Text(text) => Some(ParticleEvent::Text(text)),
+            Signal::Computed { source, content } => content
+                .trim()
+                .parse::<f32>()
+                .ok()
+                .map(|value| ParticleEvent::Numeric { source, value }),
+            _ => None,
+        }
+    }
+}
+
+#[async_trait]
+impl Sink for ParticleSink {
+    fn name(&self) -> &str {
+        "Particles"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "Particles".to_string(),
+            description: "Particle bursts and emission driven by incoming events".to_string(),
+            ports: vec![
+                Port {
+                    id: "trigger_in".to_string(),
+                    label: "Trigger".to_string(),
+                    data_type: DataType::Control,
+                    direction: PortDirection::Input,
+                },
+                Port {
+                    id: "value_in".to_string(),
+                    label: "Value".to_string(),
+                    data_type: DataType::Numeric,
+                    direction: PortDirection::Input,
+                },
+                Port {
+                    id: "text_in".to_string(),
+                    label: "Text".to_string(),
+                    data_type: DataType::Text,
+                    direction: PortDirection::Input,
+                },
+            ],
+            settings_schema: None,
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    async fn consume(&self, signal: Signal) -> anyhow::Result<Option<Signal>> {
+        if !self.enabled {
+            return Ok(None);
+        }
+        if let Some(event) = Self::event_for(signal) {
+            self.events.push(event);
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn queues_events_and_drops_the_oldest() {
+        let events = ParticleEvents::new();
+        let sink = ParticleSink::new("particles", events.clone());
+        sink.consume(Signal::Pulse).await.unwrap();
+        sink.consume(Signal::Computed {
+            source: "sysmon_cpu".to_string(),
+            content: " 0.75 ".to_string(),
+        })
+        .await
+        .unwrap();
+        sink.consume(Signal::Computed {
+            source: "weather".to_string(),
+            content: "{}".to_string(),
+        })
+        .await
+        .unwrap();
+        assert_eq!(
+            events.drain(),
+            vec![
+                ParticleEvent::Pulse,
+                ParticleEvent::Numeric {
+                    source: "sysmon_cpu".to_string(),
+                    value: 0.75
+                }
+            ]
+        );
+
+        for i in 0..MAX_QUEUED_EVENTS + 1 {
+            events.push(ParticleEvent::Text(i.to_string()));
+        }
+        let drained = events.drain();
+        assert_eq!(drained.len(), MAX_QUEUED_EVENTS);
+        assert_eq!(drained[0], ParticleEvent::Text("1".to_string()));
+    }
+}
@@ -0,0 +1,344 @@
+//! Particle simulation, independent of rendering.
+//!
+//! Positions live in a square centred on the origin (-1..1, y-up) that the
+//! tile scales to its rect. Particles take the emitter's hue at birth, so a
+//! colour change shows up as a new wave rather than a global recolour.
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+/// Longest step simulated at once; slower frames just run slower
+const MAX_STEP_SECS: f32 = 0.1;
+/// Particles further than this from the centre are dropped
+const BOUNDS: f32 = 1.5;
+/// Most particles a single Text signal emits
+const MAX_TEXT_BURST: u32 = 200;
+
+/// Something that happened upstream
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParticleEvent {
+    Pulse,
+    Numeric { source: String, value: f32 },
+    Text(String),
+}
+
+/// Emitter parameter that numeric signals drive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NumericTarget {
+    /// 0..2x the base rate
+    #[default]
+    Rate,
+    /// Full hue circle
+    Hue,
+    /// Full turn, 0 = right, counter-clockwise
+    Direction,
+    /// 0..2x the base speed
+    Speed,
+}
+
+impl NumericTarget {
+    pub const ALL: [NumericTarget; 4] = [
+        NumericTarget::Rate,
+        NumericTarget::Hue,
+        NumericTarget::Direction,
+        NumericTarget::Speed,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NumericTarget::Rate => "rate",
+            NumericTarget::Hue => "hue",
+            NumericTarget::Direction => "direction",
+            NumericTarget::Speed => "speed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ParticleConfig {
+    /// Particles per second with no numeric input
+    pub base_rate: f32,
+    /// Particles per Pulse
+    pub burst: u32,
+    pub max_particles: usize,
+    pub lifetime_secs: f32,
+    /// Initial speed, units per second
+    pub speed: f32,
+    /// Emission cone around the direction, degrees
+    pub spread_deg: f32,
+    pub direction_deg: f32,
+    /// Downward acceleration, units per second squared
+    pub gravity: f32,
+    /// Starting hue, 0..1
+    pub hue: f32,
+    pub numeric_target: NumericTarget,
+    /// Numeric input range mapped onto the target
+    pub numeric_min: f32,
+    pub numeric_max: f32,
+}
+
+impl Default for ParticleConfig {
+    fn default() -> Self {
+        Self {
+            base_rate: 20.0,
+            burst: 40,
+            max_particles: 2000,
+            lifetime_secs: 2.5,
+            speed: 0.6,
+            spread_deg: 360.0,
+            direction_deg: 90.0,
+            gravity: 0.0,
+            hue: 0.55,
+            numeric_target: NumericTarget::Rate,
+            numeric_min: 0.0,
+            numeric_max: 1.0,
+        }
+    }
+}
+
+impl ParticleConfig {
+    /// `value` mapped from the numeric range onto 0..1
+    pub fn normalize(&self, value: f32) -> f32 {
+        let span = self.numeric_max - self.numeric_min;
+        if span.abs() < f32::EPSILON || !value.is_finite() {
+            return 0.0;
+        }
+        ((value - self.numeric_min) / span).clamp(0.0, 1.0)
+    }
+
+    pub fn schema() -> serde_json::Value {
+        let targets: Vec<&str> = NumericTarget::ALL.iter().map(|t| t.as_str()).collect();
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "base_rate": { "type": "number", "title": "Base Rate (/s)", "minimum": 0.0, "maximum": 1000.0, "default": 20.0 },
+                "burst": { "type": "integer", "title": "Pulse Burst", "minimum": 0, "maximum": 1000, "default": 40 },
+                "max_particles": { "type": "integer", "title": "Max Particles", "minimum": 10, "maximum": 20000, "default": 2000 },
+                "lifetime_secs": { "type": "number", "title": "Lifetime (s)", "minimum": 0.1, "maximum": 30.0, "default": 2.5 },
+                "speed": { "type": "number", "title": "Speed", "minimum": 0.0, "maximum": 5.0, "default": 0.6 },
+                "spread_deg": { "type": "number", "title": "Spread (°)", "minimum": 0.0, "maximum": 360.0, "default": 360.0 },
+                "direction_deg": { "type": "number", "title": "Direction (°)", "minimum": 0.0, "maximum": 360.0, "default": 90.0 },
+                "gravity": { "type": "number", "title": "Gravity", "minimum": -5.0, "maximum": 5.0, "default": 0.0 },
+                "hue": { "type": "number", "title": "Hue", "minimum": 0.0, "maximum": 1.0, "default": 0.55 },
+                "numeric_target": { "type": "string", "title": "Numeric Controls", "enum": targets, "default": "rate" },
+                "numeric_min": { "type": "number", "title": "Numeric Min", "default": 0.0 },
+                "numeric_max": { "type": "number", "title": "Numeric Max", "default": 1.0 }
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Particle {
+    pub pos: (f32, f32),
+    pub vel: (f32, f32),
+    pub age: f32,
+    pub life: f32,
+    pub hue: f32,
+}
+
+impl Particle {
+    /// Remaining life, 1 at birth to 0 at death
+    pub fn fade(&self) -> f32 {
+        (1.0 - self.age / self.life.max(f32::EPSILON)).clamp(0.0, 1.0)
+    }
+}
+
+/// Stable hue for a piece of text (FNV-1a)
+pub fn text_hue(text: &str) -> f32 {
+    let hash = text.bytes().fold(0x811c_9dc5u32, |h, b| {
+        (h ^ b as u32).wrapping_mul(0x0100_0193)
+    });
+    (hash % 360) as f32 / 360.0
+}
+
+pub struct ParticleSystem {
+    config: ParticleConfig,
+    particles: Vec<Particle>,
+    rng: ChaCha8Rng,
+    rate_scale: f32,
+    speed_scale: f32,
+    hue: f32,
+    direction_deg: f32,
+    /// Burst particles waiting for the next step
+    pending: u32,
+    /// Fractional particles carried between steps
+    carry: f32,
+    /// Events seen, for the tile's counters
+    pub event_count: u64,
+}
+
+impl ParticleSystem {
+    pub fn new(config: ParticleConfig, seed: u64) -> Self {
+        Self {
+            hue: config.hue,
+            direction_deg: config.direction_deg,
+            config,
+            particles: Vec::new(),
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            rate_scale: 1.0,
+            speed_scale: 1.0,
+            pending: 0,
+            carry: 0.0,
+            event_count: 0,
+        }
+    }
+
+    pub fn config(&self) -> &ParticleConfig {
+        &self.config
+    }
+
+    /// Replace the configuration, resetting what numeric input had changed
+    pub fn set_config(&mut self, config: ParticleConfig) {
+        self.hue = config.hue;
+        self.direction_deg = config.direction_deg;
+        self.rate_scale = 1.0;
+        self.speed_scale = 1.0;
+        self.particles.truncate(config.max_particles);
+        self.config = config;
+    }
+
+    pub fn particles(&self) -> &[Particle] {
+        &self.particles
+    }
+
+    pub fn hue(&self) -> f32 {
+        self.hue
+    }
+
+    pub fn rate(&self) -> f32 {
+        self.config.base_rate * self.rate_scale
+    }
+
+    pub fn apply(&mut self, event: &ParticleEvent) {
+        self.event_count += 1;
+        match event {
+            ParticleEvent::Pulse => self.pending = self.pending.saturating_add(self.config.burst),
+            ParticleEvent::Numeric { value, .. } => {
+                let n = self.config.normalize(*value);
+                match self.config.numeric_target {
+                    NumericTarget::Rate => self.rate_scale = 2.0 * n,
+                    NumericTarget::Hue => self.hue = n,
+                    NumericTarget::Direction => self.direction_deg = 360.0 * n,
+                    NumericTarget::Speed => self.speed_scale = 2.0 * n,
+                }
+            }
+            ParticleEvent::Text(text) => {
+                self.hue = text_hue(text);
+                self.pending = self
+                    .pending
+                    .saturating_add((text.chars().count() as u32).clamp(1, MAX_TEXT_BURST));
+            }
+        }
+    }
+
+    /// Emit and integrate for `dt` seconds
+    pub fn step(&mut self, dt: f32) {
+        let dt = dt.clamp(0.0, MAX_STEP_SECS);
+
+        let emitted = self.rate() * dt + self.carry;
+        self.carry = emitted.fract();
+        let room = self
+            .config
+            .max_particles
+            .saturating_sub(self.particles.len());
+        let count = (emitted as usize + self.pending as usize).min(room);
+        self.pending = 0;
+        for _ in 0..count {
+            let particle = self.spawn();
+            self.particles.push(particle);
+        }
+
+        let gravity = self.config.gravity;
+        for p in &mut self.particles {
+            p.vel.1 -= gravity * dt;
+            p.pos.0 += p.vel.0 * dt;
+            p.pos.1 += p.vel.1 * dt;
+            p.age += dt;
+        }
+        self.particles
+            .retain(|p| p.age < p.life && p.pos.0.abs() < BOUNDS && p.pos.1.abs() < BOUNDS);
+    }
+
+    fn spawn(&mut self) -> Particle {
+        let half = self.config.spread_deg.clamp(0.0, 360.0) / 2.0;
+        let angle = (self.direction_deg + self.rng.gen_range(-half..=half)).to_radians();
+        let speed = self.config.speed * self.speed_scale * self.rng.gen_range(0.6..=1.0);
+        Particle {
+            pos: (0.0, 0.0),
+            vel: (angle.cos() * speed, angle.sin() * speed),
+            age: 0.0,
+            life: self.config.lifetime_secs.max(0.1) * self.rng.gen_range(0.7..=1.0),
+            hue: self.hue,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quiet() -> ParticleConfig {
+        ParticleConfig {
+            base_rate: 0.0,
+            ..ParticleConfig::default()
+        }
+    }
+
+    #[test]
+    fn pulses_and_text_emit_bursts_that_expire() {
+        let mut system = ParticleSystem::new(quiet(), 7);
+        system.apply(&ParticleEvent::Pulse);
+        system.step(0.016);
+        assert_eq!(system.particles().len(), 40);
+
+        system.apply(&ParticleEvent::Text("hello".to_string()));
+        system.step(0.016);
+        assert_eq!(system.particles().len(), 45);
+        assert_eq!(system.particles()[44].hue, text_hue("hello"));
+        assert_eq!(system.particles()[0].hue, 0.55);
+
+        for _ in 0..40 {
+            system.step(0.1);
+        }
+        assert!(system.particles().is_empty());
+    }
+
+    #[test]
+    fn numeric_signals_drive_the_configured_target() {
+        let mut system = ParticleSystem::new(
+            ParticleConfig {
+                numeric_max: 10.0,
+                ..ParticleConfig::default()
+            },
+            1,
+        );
+        system.apply(&ParticleEvent::Numeric {
+            source: "cpu".to_string(),
+            value: 10.0,
+        });
+        assert_eq!(system.rate(), 40.0);
+        system.step(0.1);
+        assert_eq!(system.particles().len(), 4);
+
+        system.set_config(ParticleConfig {
+            base_rate: 0.0,
+            burst: 1,
+            spread_deg: 0.0,
+            numeric_target: NumericTarget::Direction,
+            ..ParticleConfig::default()
+        });
+        system.apply(&ParticleEvent::Numeric {
+            source: "cpu".to_string(),
+            value: 0.5,
+        });
+        system.apply(&ParticleEvent::Pulse);
+        system.step(0.05);
+        // Half a turn: straight left
+        let p = system.particles().last().unwrap();
+        assert!(p.vel.0 < 0.0 && p.vel.1.abs() < 1e-4, "{:?}", p.vel);
+    }
+}
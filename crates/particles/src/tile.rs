@@ -0,0 +1,171 @@
+//! Particles Tile
+//!
+//! Monitor mode draws the particle field; the maximized view adds emitter
+//! state and event counts. Particles are simulated in `update`, so the
+//! field keeps moving while the tile is not maximized.
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use magnolia_core::{BindableAction, ModuleSeed, RenderContext, SharedSeed, TileRenderer};
+use magnolia_ui::{draw_text, FontId, TextAlignment};
+use nannou::prelude::*;
+
+use crate::sink::ParticleEvents;
+use crate::system::{ParticleConfig, ParticleEvent, ParticleSystem};
+
+/// Seed without a session seed source
+const DEFAULT_SEED: u64 = 0x5eed;
+
+pub struct ParticleTile {
+    id: String,
+    events: Arc<ParticleEvents>,
+    system: ParticleSystem,
+    seed: Option<ModuleSeed>,
+    last_update: Instant,
+}
+
+impl ParticleTile {
+    pub fn new(id: &str, events: Arc<ParticleEvents>) -> Self {
+        Self {
+            id: id.to_string(),
+            events,
+            system: ParticleSystem::new(ParticleConfig::default(), DEFAULT_SEED),
+            seed: None,
+            last_update: Instant::now(),
+        }
+    }
+
+    /// Seed the field from `seeds`, starting a fresh one whenever it changes
+    pub fn with_seeds(mut self, seeds: SharedSeed) -> Self {
+        self.seed = Some(ModuleSeed::new(seeds, &self.id));
+        self.restart();
+        self
+    }
+
+    fn restart(&mut self) {
+        let seed = self.seed.as_ref().map_or(DEFAULT_SEED, ModuleSeed::seed);
+        self.system = ParticleSystem::new(self.system.config().clone(), seed);
+    }
+
+    fn draw_field(&self, draw: &Draw, rect: Rect) {
+        draw.rect()
+            .xy(rect.xy())
+            .wh(rect.wh())
+            .color(srgba(0.01, 0.01, 0.03, 0.95));
+
+        let scale = rect.w().min(rect.h()) / 2.0;
+        let size = (scale / 60.0).clamp(1.5, 5.0);
+        for p in self.system.particles() {
+            let fade = p.fade();
+            draw.ellipse()
+                .x_y(rect.x() + p.pos.0 * scale, rect.y() + p.pos.1 * scale)
+                .radius(size * (0.5 + 0.5 * fade))
+                .color(hsla(p.hue, 0.8, 0.6, fade));
+        }
+    }
+}
+
+impl TileRenderer for ParticleTile {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn name(&self) -> &str {
+        "Particles"
+    }
+
+    fn update(&mut self) {
+        if self.seed.as_mut().and_then(ModuleSeed::changed).is_some() {
+            self.restart();
+        }
+        for event in self.events.drain() {
+            self.system.apply(&event);
+        }
+        let now = Instant::now();
+        self.system.step((now - self.last_update).as_secs_f32());
+        self.last_update = now;
+    }
+
+    fn render_monitor(&self, draw: &Draw, rect: Rect, _ctx: &RenderContext) {
+        self.draw_field(draw, rect);
+    }
+
+    fn render_controls(&self, draw: &Draw, rect: Rect, _ctx: &RenderContext) -> bool {
+        self.draw_field(draw, rect);
+
+        let config = self.system.config();
+        let lines = [
+            format!(
+                "{} / {} particles",
+                self.system.particles().len(),
+                config.max_particles
+            ),
+            format!(
+                "Rate {:.1}/s  Hue {:.2}",
+                self.system.rate(),
+                self.system.hue()
+            ),
+            format!("Numeric → {}", config.numeric_target.as_str()),
+            format!("{} events", self.system.event_count),
+        ];
+        let mut y = rect.top() - 24.0;
+        for line in &lines {
+            draw_text(
+                draw,
+                FontId::PlexMonoRegular,
+                line,
+                pt2(rect.left() + 140.0, y),
+                12.0,
+                srgba(0.8, 0.8, 0.8, 1.0),
+                TextAlignment::Center,
+            );
+            y -= 18.0;
+        }
+        false
+    }
+
+    fn settings_schema(&self) -> Option<serde_json::Value> {
+        Some(ParticleConfig::schema())
+    }
+
+    fn apply_settings(&mut self, settings: &serde_json::Value) {
+        match serde_json::from_value::<ParticleConfig>(settings.clone()) {
+            Ok(config) => {
+                if &config != self.system.config() {
+                    self.system.set_config(config);
+                }
+            }
+            Err(e) => log::warn!("Particles: invalid settings, keeping current: {}", e),
+        }
+    }
+
+    fn get_settings(&self) -> serde_json::Value {
+        serde_json::to_value(self.system.config()).unwrap_or_default()
+    }
+
+    fn bindable_actions(&self) -> Vec<BindableAction> {
+        vec![
+            BindableAction::new("burst", "Emit Burst", false),
+            BindableAction::new("clear", "Clear Particles", false),
+        ]
+    }
+
+    fn execute_action(&mut self, action: &str) -> bool {
+        match action {
+            "burst" => {
+                self.system.apply(&ParticleEvent::Pulse);
+                true
+            }
+            "clear" => {
+                self.restart();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn get_display_text(&self) -> Option<String> {
+        Some(format!("Particles: {}", self.system.particles().len()))
+    }
+}
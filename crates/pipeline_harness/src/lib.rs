@@ -0,0 +1,514 @@
+//! Golden-file harness for patch graphs.
+//!
+//! A fixture TOML describes modules, patches between them, recorded inputs to
+//! inject, and which output ports to capture. The harness drives the graph
+//! synchronously (no threads, no wall clock), routes through a real
+//! [`PatchBay`], and compares what each capture saw against a golden JSON file.
+//!
+//! ```toml
+//! [[modules]]
+//! id = "dsp"
+//! kind = "audio_dsp"
+//! settings = { gain = 0.5, agc_enabled = false }
+//!
+//! [[inputs]]
+//! module = "dsp"
+//! wav = "tone.wav"
+//! chunk_ms = 20
+//!
+//! [[captures]]
+//! name = "dsp_out"
+//! module = "dsp"
+//! port = "audio_out"
+//! golden = "dsp_out.golden.json"
+//! rms_tolerance = 1e-4
+//! ```
+//!
+//! Paths are relative to the fixture file. Set `MAGNOLIA_UPDATE_GOLDEN=1` to
+//! (re)write golden files from the current output instead of comparing.
+
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use magnolia_core::{
+    default_output_port, AudioView, ModuleSchema, PatchBay, Processor, Signal, Sink,
+};
+
+/// Environment variable that switches the harness from comparing to recording.
+pub const UPDATE_GOLDEN_ENV: &str = "MAGNOLIA_UPDATE_GOLDEN";
+
+/// Upper bound on deliveries per run so a cyclic fixture fails instead of spinning.
+const MAX_DELIVERIES: usize = 100_000;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Fixture {
+    pub modules: Vec<ModuleSpec>,
+    #[serde(default)]
+    pub patches: Vec<PatchSpec>,
+    #[serde(default)]
+    pub inputs: Vec<InputSpec>,
+    #[serde(default)]
+    pub captures: Vec<CaptureSpec>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModuleSpec {
+    pub id: String,
+    /// Factory key, e.g. `audio_dsp` or `devowelizer`.
+    pub kind: String,
+    #[serde(default)]
+    pub settings: toml::Table,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PatchSpec {
+    pub source: String,
+    pub source_port: String,
+    pub sink: String,
+    pub sink_port: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct InputSpec {
+    /// Module that receives the input directly.
+    pub module: String,
+    /// WAV file replayed as `Signal::Audio` chunks.
+    pub wav: Option<PathBuf>,
+    #[serde(default = "default_chunk_ms")]
+    pub chunk_ms: u32,
+    /// Lines delivered as `Signal::Text`.
+    #[serde(default)]
+    pub text: Vec<String>,
+    /// JSON file holding an array of serialized `Signal`s.
+    pub signals: Option<PathBuf>,
+}
+
+fn default_chunk_ms() -> u32 {
+    20
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CaptureSpec {
+    pub name: String,
+    pub module: String,
+    pub port: String,
+    pub golden: PathBuf,
+    #[serde(default = "default_rms_tolerance")]
+    pub rms_tolerance: f32,
+}
+
+fn default_rms_tolerance() -> f32 {
+    1e-4
+}
+
+/// What a capture records for each signal. Audio is summarized so golden files
+/// stay small and comparisons are tolerant of float noise.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CapturedSignal {
+    Audio {
+        sample_rate: u32,
+        channels: u16,
+        timestamp_us: u64,
+        frames: usize,
+        rms: f32,
+        peak: f32,
+    },
+    Text {
+        text: String,
+    },
+    Computed {
+        source: String,
+        content: String,
+    },
+    Other {
+        signal: serde_json::Value,
+    },
+}
+
+impl CapturedSignal {
+    pub fn from_signal(signal: &Signal) -> Self {
+        match signal {
+            // Fanned-out audio arrives shared; it's captured the same
+            Signal::Audio { .. } | Signal::SharedAudio { .. } => {
+                let AudioView {
+                    sample_rate,
+                    channels,
+                    timestamp_us,
+                    data,
+                } = signal.audio().expect("audio signal");
+                let rms = if data.is_empty() {
+                    0.0
+                } else {
+                    (data.iter().map(|s| s * s).sum::<f32>() / data.len() as f32).sqrt()
+                };
+                let peak = data.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+                CapturedSignal::Audio {
+                    sample_rate,
+                    channels,
+                    timestamp_us,
+                    frames: data.len() / channels.max(1) as usize,
+                    rms,
+                    peak,
+                }
+            }
+            Signal::Text(text) => CapturedSignal::Text { text: text.clone() },
+            Signal::Computed { source, content } => CapturedSignal::Computed {
+                source: source.clone(),
+                content: content.clone(),
+            },
+            other => CapturedSignal::Other {
+                signal: serde_json::to_value(other).unwrap_or(serde_json::Value::Null),
+            },
+        }
+    }
+
+    /// Describe how `self` differs from `golden`, or `None` if it is within tolerance.
+    fn mismatch(&self, golden: &CapturedSignal, rms_tolerance: f32) -> Option<String> {
+        match (self, golden) {
+            (
+                CapturedSignal::Audio {
+                    sample_rate,
+                    channels,
+                    frames,
+                    rms,
+                    peak,
+                    ..
+                },
+                CapturedSignal::Audio {
+                    sample_rate: golden_rate,
+                    channels: golden_channels,
+                    frames: golden_frames,
+                    rms: golden_rms,
+                    peak: golden_peak,
+                    ..
+                },
+            ) => {
+                if (sample_rate, channels, frames) != (golden_rate, golden_channels, golden_frames)
+                {
+                    Some(format!(
+                        "audio format {sample_rate} Hz/{channels} ch/{frames} frames, expected {golden_rate} Hz/{golden_channels} ch/{golden_frames} frames"
+                    ))
+                } else if (rms - golden_rms).abs() > rms_tolerance
+                    || (peak - golden_peak).abs() > rms_tolerance
+                {
+                    Some(format!(
+                        "audio rms {rms:.6} peak {peak:.6}, expected rms {golden_rms:.6} peak {golden_peak:.6} (tolerance {rms_tolerance})"
+                    ))
+                } else {
+                    None
+                }
+            }
+            (actual, golden) if actual == golden => None,
+            (actual, golden) => Some(format!("got {actual:?}, expected {golden:?}")),
+        }
+    }
+}
+
+/// A module the harness can drive. Sinks participate too because several
+/// existing "sinks" return derived signals from `consume`.
+pub enum HarnessNode {
+    Processor(Box<dyn Processor>),
+    Sink(Box<dyn Sink>),
+}
+
+impl HarnessNode {
+    fn schema(&self) -> ModuleSchema {
+        match self {
+            HarnessNode::Processor(processor) => processor.schema(),
+            HarnessNode::Sink(sink) => sink.schema(),
+        }
+    }
+
+    async fn deliver(&mut self, signal: Signal) -> anyhow::Result<Option<Signal>> {
+        match self {
+            HarnessNode::Processor(processor) => processor.process(signal).await,
+            HarnessNode::Sink(sink) => sink.consume(signal).await,
+        }
+    }
+}
+
+/// Builds a node from a fixture module entry.
+pub type NodeFactory = Box<dyn Fn(&ModuleSpec) -> anyhow::Result<HarnessNode>>;
+
+/// Result of running a fixture: signals seen by each capture, by capture name.
+pub type Captures = HashMap<String, Vec<CapturedSignal>>;
+
+pub struct Harness {
+    factories: HashMap<String, NodeFactory>,
+}
+
+impl Default for Harness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Harness {
+    /// Harness with factories for the in-tree DSP and text modules.
+    pub fn new() -> Self {
+        let mut harness = Self {
+            factories: HashMap::new(),
+        };
+        harness.register_factory("audio_dsp", |spec| {
+            let state = audio_dsp::AudioDspState::new();
+            let settings = &spec.settings;
+            if let Some(gain) = settings.get("gain").and_then(toml::Value::as_float) {
+                state.set_gain(gain as f32);
+            }
+            if let Some(enabled) = settings.get("agc_enabled").and_then(toml::Value::as_bool) {
+                state.set_agc_enabled(enabled);
+            }
+            if let Some(enabled) = settings
+                .get("lowpass_enabled")
+                .and_then(toml::Value::as_bool)
+            {
+                state.set_lowpass_enabled(enabled);
+            }
+            if let Some(hz) = settings.get("lowpass_hz").and_then(toml::Value::as_float) {
+                state.set_lowpass_hz(hz as f32);
+            }
+            if let Some(muted) = settings.get("is_muted").and_then(toml::Value::as_bool) {
+                state.set_muted(muted);
+            }
+            Ok(HarnessNode::Processor(Box::new(
+                audio_dsp::AudioDspProcessor::new(&spec.id, state),
+            )))
+        });
+        harness.register_factory("stereo_tools", |spec| {
+            let state = audio_dsp::StereoToolsState::new();
+            state.apply_settings(&settings_json(&spec.settings)?);
+            Ok(HarnessNode::Processor(Box::new(
+                audio_dsp::StereoToolsProcessor::new(&spec.id, state),
+            )))
+        });
+        harness.register_factory("devowelizer", |_| {
+            Ok(HarnessNode::Sink(Box::new(
+                text_tools::DevowelizerSink::new(None),
+            )))
+        });
+        harness.register_factory("word_count", |_| {
+            Ok(HarnessNode::Sink(Box::new(text_tools::WordCountSink::new(
+                None,
+            ))))
+        });
+        harness
+    }
+
+    pub fn register_factory<F>(&mut self, kind: &str, factory: F)
+    where
+        F: Fn(&ModuleSpec) -> anyhow::Result<HarnessNode> + 'static,
+    {
+        self.factories.insert(kind.to_string(), Box::new(factory));
+    }
+
+    /// Run a fixture and return what every capture recorded.
+    ///
+    /// `base_dir` resolves relative input paths.
+    pub fn run(&self, fixture: &Fixture, base_dir: &Path) -> anyhow::Result<Captures> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .context("failed to build harness runtime")?;
+        runtime.block_on(self.run_async(fixture, base_dir))
+    }
+
+    async fn run_async(&self, fixture: &Fixture, base_dir: &Path) -> anyhow::Result<Captures> {
+        let mut patch_bay = PatchBay::new();
+        let mut nodes = HashMap::new();
+        let mut output_ports = HashMap::new();
+        for spec in &fixture.modules {
+            let factory = self
+                .factories
+                .get(&spec.kind)
+                .with_context(|| format!("unknown module kind '{}'", spec.kind))?;
+            let node = factory(spec).with_context(|| format!("building module '{}'", spec.id))?;
+            // Fixture ids win over the ids modules hardcode in their schemas.
+            let mut schema = node.schema();
+            schema.id = spec.id.clone();
+            output_ports.insert(spec.id.clone(), default_output_port(&schema));
+            patch_bay.register_module(schema);
+            anyhow::ensure!(
+                nodes.insert(spec.id.clone(), node).is_none(),
+                "duplicate module id '{}'",
+                spec.id
+            );
+        }
+        for patch in &fixture.patches {
+            patch_bay
+                .connect(
+                    &patch.source,
+                    &patch.source_port,
+                    &patch.sink,
+                    &patch.sink_port,
+                )
+                .with_context(|| {
+                    format!(
+                        "connecting {}.{} -> {}.{}",
+                        patch.source, patch.source_port, patch.sink, patch.sink_port
+                    )
+                })?;
+        }
+
+        let mut captures: Captures = fixture
+            .captures
+            .iter()
+            .map(|capture| (capture.name.clone(), Vec::new()))
+            .collect();
+
+        let mut queue = VecDeque::new();
+        for input in &fixture.inputs {
+            anyhow::ensure!(
+                nodes.contains_key(&input.module),
+                "input targets unknown module '{}'",
+                input.module
+            );
+            for signal in load_input(input, base_dir)? {
+                queue.push_back((input.module.clone(), signal));
+            }
+        }
+
+        let mut deliveries = 0;
+        while let Some((module_id, signal)) = queue.pop_front() {
+            deliveries += 1;
+            anyhow::ensure!(
+                deliveries <= MAX_DELIVERIES,
+                "more than {MAX_DELIVERIES} deliveries; does the fixture contain a cycle?"
+            );
+            let node = nodes
+                .get_mut(&module_id)
+                .with_context(|| format!("unknown module '{module_id}'"))?;
+            let Some(output) = node
+                .deliver(signal)
+                .await
+                .with_context(|| format!("module '{module_id}' failed"))?
+            else {
+                continue;
+            };
+
+            let port = &output_ports[&module_id];
+            for capture in &fixture.captures {
+                if capture.module == module_id && &capture.port == port {
+                    if let Some(seen) = captures.get_mut(&capture.name) {
+                        seen.push(CapturedSignal::from_signal(&output));
+                    }
+                }
+            }
+            for patch in patch_bay.get_outgoing_patches(&module_id) {
+                if &patch.source_port == port && !patch_bay.is_module_disabled(&patch.sink_module) {
+                    queue.push_back((patch.sink_module.clone(), output.clone()));
+                }
+            }
+        }
+
+        Ok(captures)
+    }
+}
+
+fn settings_json(settings: &toml::Table) -> anyhow::Result<serde_json::Value> {
+    Ok(serde_json::to_value(settings)?)
+}
+
+fn load_input(input: &InputSpec, base_dir: &Path) -> anyhow::Result<Vec<Signal>> {
+    let mut signals = Vec::new();
+    if let Some(wav) = &input.wav {
+        let path = base_dir.join(wav);
+        let (_, _, chunks) = audio_replay::load_wav_audio_signals(&path, input.chunk_ms)
+            .with_context(|| format!("loading {}", path.display()))?;
+        signals.extend(chunks);
+    }
+    signals.extend(input.text.iter().cloned().map(Signal::Text));
+    if let Some(file) = &input.signals {
+        let path = base_dir.join(file);
+        let text = std::fs::read_to_string(&path)
+            .with_context(|| format!("reading {}", path.display()))?;
+        let recorded: Vec<Signal> =
+            serde_json::from_str(&text).with_context(|| format!("parsing {}", path.display()))?;
+        signals.extend(recorded);
+    }
+    Ok(signals)
+}
+
+/// Compare captures against their golden files, returning one line per mismatch.
+pub fn compare_golden(
+    fixture: &Fixture,
+    captures: &Captures,
+    base_dir: &Path,
+) -> anyhow::Result<Vec<String>> {
+    let mut mismatches = Vec::new();
+    for capture in &fixture.captures {
+        let path = base_dir.join(&capture.golden);
+        let text = std::fs::read_to_string(&path).with_context(|| {
+            format!(
+                "reading golden {} (run with {UPDATE_GOLDEN_ENV}=1 to create it)",
+                path.display()
+            )
+        })?;
+        let golden: Vec<CapturedSignal> = serde_json::from_str(&text)
+            .with_context(|| format!("parsing golden {}", path.display()))?;
+        let actual = captures
+            .get(&capture.name)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        if actual.len() != golden.len() {
+            mismatches.push(format!(
+                "{}: captured {} signals, golden has {}",
+                capture.name,
+                actual.len(),
+                golden.len()
+            ));
+        }
+        for (index, (actual, golden)) in actual.iter().zip(&golden).enumerate() {
+            if let Some(reason) = actual.mismatch(golden, capture.rms_tolerance) {
+                mismatches.push(format!("{}[{index}]: {reason}", capture.name));
+            }
+        }
+    }
+    Ok(mismatches)
+}
+
+/// Write captures out as the new golden files.
+pub fn write_golden(fixture: &Fixture, captures: &Captures, base_dir: &Path) -> anyhow::Result<()> {
+    for capture in &fixture.captures {
+        let path = base_dir.join(&capture.golden);
+        let seen = captures.get(&capture.name).cloned().unwrap_or_default();
+        let mut text = serde_json::to_string_pretty(&seen)?;
+        text.push('\n');
+        std::fs::write(&path, text).with_context(|| format!("writing {}", path.display()))?;
+    }
+    Ok(())
+}
+
+pub fn load_fixture(path: &Path) -> anyhow::Result<Fixture> {
+    let text =
+        std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    toml::from_str(&text).with_context(|| format!("parsing {}", path.display()))
+}
+
+/// Run the fixture at `path` with the default harness and check it against its
+/// golden files, or rewrite them when `MAGNOLIA_UPDATE_GOLDEN` is set.
+pub fn assert_golden(path: impl AsRef<Path>) -> anyhow::Result<()> {
+    assert_golden_with(&Harness::new(), path)
+}
+
+pub fn assert_golden_with(harness: &Harness, path: impl AsRef<Path>) -> anyhow::Result<()> {
+    let path = path.as_ref();
+    let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let fixture = load_fixture(path)?;
+    let captures = harness.run(&fixture, base_dir)?;
+
+    if std::env::var_os(UPDATE_GOLDEN_ENV).is_some() {
+        return write_golden(&fixture, &captures, base_dir);
+    }
+
+    let mismatches = compare_golden(&fixture, &captures, base_dir)?;
+    anyhow::ensure!(
+        mismatches.is_empty(),
+        "{} does not match its golden files:\n  {}",
+        path.display(),
+        mismatches.join("\n  ")
+    );
+    Ok(())
+}
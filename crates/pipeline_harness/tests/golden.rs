@@ -0,0 +1,34 @@
+use std::path::PathBuf;
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+#[test]
+fn dsp_gain_matches_golden() {
+    pipeline_harness::assert_golden(fixture("dsp_gain.toml")).unwrap();
+}
+
+#[test]
+fn text_chain_matches_golden() {
+    pipeline_harness::assert_golden(fixture("text_chain.toml")).unwrap();
+}
+
+#[test]
+fn changed_gain_is_reported_as_mismatch() {
+    let path = fixture("dsp_gain.toml");
+    let base_dir = path.parent().unwrap();
+    let mut fixture = pipeline_harness::load_fixture(&path).unwrap();
+    fixture.modules[0]
+        .settings
+        .insert("gain".to_string(), toml::Value::Float(0.25));
+
+    let captures = pipeline_harness::Harness::new()
+        .run(&fixture, base_dir)
+        .unwrap();
+    let mismatches = pipeline_harness::compare_golden(&fixture, &captures, base_dir).unwrap();
+    assert!(!mismatches.is_empty());
+    assert!(mismatches[0].contains("rms"));
+}
@@ -0,0 +1,299 @@
+//! Community plugin index
+//!
+//! An index is a JSON list of plugins ([`PluginIndex`]) published with a
+//! detached Ed25519 signature at the same URL plus `.sig`, checked against
+//! the keys in `~/.magnolia/trusted_keys.txt` like plugin libraries are.
+//! Each entry carries the signature of its library; [`install`] checks that
+//! and the ABI before anything is written to the plugin directory, and
+//! leaves the library's `.sig` next to it.
+
+use anyhow::{bail, Context, Result};
+use magnolia_core::PluginVerifier;
+pub use magnolia_plugin_abi::ABI_VERSION;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// Refuse downloads past this
+const MAX_DOWNLOAD_BYTES: u64 = 256 * 1024 * 1024;
+/// Versions installed from the index, in the plugin directory
+const INSTALLED_FILE: &str = "index-installed.json";
+
+/// One plugin in the index
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexEntry {
+    pub name: String,
+    pub version: String,
+    /// Plugin ABI the library was built against
+    pub abi: u32,
+    #[serde(default)]
+    pub description: String,
+    /// Where the library for this platform is downloaded from
+    pub url: String,
+    /// Hex Ed25519 signature of the library
+    pub signature: String,
+}
+
+impl IndexEntry {
+    /// Built against the ABI this build loads
+    pub fn is_compatible(&self) -> bool {
+        self.abi == ABI_VERSION
+    }
+
+    /// `libname.so`, `name.dll`, ... for this platform
+    pub fn file_name(&self) -> String {
+        format!(
+            "{}{}{}",
+            std::env::consts::DLL_PREFIX,
+            self.name,
+            std::env::consts::DLL_SUFFIX
+        )
+    }
+}
+
+/// `index.json`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginIndex {
+    pub plugins: Vec<IndexEntry>,
+}
+
+/// An entry against what's in the plugin directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EntryStatus {
+    Available,
+    Installed,
+    /// Installed at another version
+    UpdateAvailable {
+        installed: String,
+    },
+    /// Built for another ABI; can't be installed
+    Incompatible {
+        abi: u32,
+    },
+}
+
+/// Plugin versions installed from the index, by name
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Installed(pub BTreeMap<String, String>);
+
+impl Installed {
+    /// What `dir` records; nothing when it's never been installed to
+    pub fn load(dir: &Path) -> Self {
+        std::fs::read_to_string(dir.join(INSTALLED_FILE))
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, dir: &Path) -> Result<()> {
+        let path = dir.join(INSTALLED_FILE);
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("{}", path.display()))
+    }
+
+    pub fn status(&self, entry: &IndexEntry) -> EntryStatus {
+        if !entry.is_compatible() {
+            return EntryStatus::Incompatible { abi: entry.abi };
+        }
+        match self.0.get(&entry.name) {
+            None => EntryStatus::Available,
+            Some(version) if *version == entry.version => EntryStatus::Installed,
+            Some(version) => EntryStatus::UpdateAvailable {
+                installed: version.clone(),
+            },
+        }
+    }
+}
+
+/// Check `signature` over `index` and parse it
+pub fn parse_index(
+    index: &[u8],
+    signature: &[u8],
+    verifier: &PluginVerifier,
+) -> Result<PluginIndex> {
+    if !verifier.has_keys() {
+        bail!("no trusted keys; add the index publisher's key to ~/.magnolia/trusted_keys.txt");
+    }
+    if !verifier.verify_bytes(index, signature) {
+        bail!("index signature doesn't match any trusted key");
+    }
+    serde_json::from_slice(index).context("reading plugin index")
+}
+
+fn download(url: &str) -> Result<Vec<u8>> {
+    let response = ureq::get(url)
+        .timeout(REQUEST_TIMEOUT)
+        .call()
+        .with_context(|| format!("fetching {}", url))?;
+    let mut bytes = Vec::new();
+    response
+        .into_reader()
+        .take(MAX_DOWNLOAD_BYTES + 1)
+        .read_to_end(&mut bytes)
+        .with_context(|| format!("reading {}", url))?;
+    if bytes.len() as u64 > MAX_DOWNLOAD_BYTES {
+        bail!("{} is larger than {} bytes", url, MAX_DOWNLOAD_BYTES);
+    }
+    Ok(bytes)
+}
+
+/// Fetch and check the index at `url`
+pub fn fetch_index(url: &str, verifier: &PluginVerifier) -> Result<PluginIndex> {
+    let index = download(url)?;
+    let signature = download(&format!("{}.sig", url))?;
+    parse_index(&index, &signature, verifier)
+}
+
+/// Put a downloaded library in `dir` once its ABI and signature check out.
+/// The signature is written first and the library moved into place whole,
+/// so a hot-reload watcher never sees half of it.
+pub fn install_bytes(
+    entry: &IndexEntry,
+    library: &[u8],
+    dir: &Path,
+    verifier: &PluginVerifier,
+) -> Result<PathBuf> {
+    if !entry.is_compatible() {
+        bail!(
+            "{} {} is built for plugin ABI {}, this build loads {}",
+            entry.name,
+            entry.version,
+            entry.abi,
+            ABI_VERSION
+        );
+    }
+    // The name becomes a file name
+    if entry.name.is_empty()
+        || !entry
+            .name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        bail!("plugin name {:?} isn't usable as a file name", entry.name);
+    }
+    let signature = hex::decode(&entry.signature).context("reading the entry's signature")?;
+    if !verifier.verify_bytes(library, &signature) {
+        bail!(
+            "{} {} isn't signed by a trusted key",
+            entry.name,
+            entry.version
+        );
+    }
+
+    std::fs::create_dir_all(dir).with_context(|| format!("{}", dir.display()))?;
+    let path = dir.join(entry.file_name());
+    let mut sig_path = path.clone().into_os_string();
+    sig_path.push(".sig");
+    std::fs::write(&sig_path, &signature)?;
+    let mut part = path.clone().into_os_string();
+    part.push(".part");
+    std::fs::write(&part, library)?;
+    std::fs::rename(&part, &path).with_context(|| format!("{}", path.display()))?;
+
+    let mut installed = Installed::load(dir);
+    installed
+        .0
+        .insert(entry.name.clone(), entry.version.clone());
+    installed.save(dir)?;
+    log::info!(
+        "Installed plugin {} {} to {}",
+        entry.name,
+        entry.version,
+        path.display()
+    );
+    Ok(path)
+}
+
+/// Download `entry` and install or update it in `dir`
+pub fn install(entry: &IndexEntry, dir: &Path, verifier: &PluginVerifier) -> Result<PathBuf> {
+    if !entry.is_compatible() {
+        // Not worth the download
+        return install_bytes(entry, &[], dir, verifier);
+    }
+    let library = download(&entry.url)?;
+    install_bytes(entry, &library, dir, verifier)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    fn entry(key: &SigningKey, library: &[u8], version: &str, abi: u32) -> IndexEntry {
+        IndexEntry {
+            name: "hello".into(),
+            version: version.into(),
+            abi,
+            description: String::new(),
+            url: "https://plugins.example/hello".into(),
+            signature: hex::encode(key.sign(library).to_bytes()),
+        }
+    }
+
+    #[test]
+    fn index_must_be_signed_by_a_trusted_key() {
+        let publisher = SigningKey::from_bytes(&[7; 32]);
+        let stranger = SigningKey::from_bytes(&[9; 32]);
+        let verifier = PluginVerifier::with_keys(vec![publisher.verifying_key()]);
+        let index = PluginIndex {
+            plugins: vec![entry(&publisher, b"lib", "1.0.0", ABI_VERSION)],
+        };
+        let bytes = serde_json::to_vec(&index).unwrap();
+
+        let signature = publisher.sign(&bytes).to_bytes();
+        assert_eq!(parse_index(&bytes, &signature, &verifier).unwrap(), index);
+        let forged = stranger.sign(&bytes).to_bytes();
+        assert!(parse_index(&bytes, &forged, &verifier).is_err());
+        assert!(parse_index(&bytes, &signature, &PluginVerifier::with_keys(vec![])).is_err());
+    }
+
+    #[test]
+    fn installs_check_abi_and_signature_and_track_versions() {
+        let dir = std::env::temp_dir().join(format!("magnolia_index_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let publisher = SigningKey::from_bytes(&[7; 32]);
+        let verifier = PluginVerifier::with_keys(vec![publisher.verifying_key()]);
+
+        let v1 = entry(&publisher, b"hello v1", "1.0.0", ABI_VERSION);
+        assert_eq!(Installed::load(&dir).status(&v1), EntryStatus::Available);
+        assert!(install_bytes(&v1, b"tampered", &dir, &verifier).is_err());
+        let escaping = IndexEntry {
+            name: "../hello".into(),
+            ..v1.clone()
+        };
+        assert!(install_bytes(&escaping, b"hello v1", &dir, &verifier).is_err());
+        let path = install_bytes(&v1, b"hello v1", &dir, &verifier).unwrap();
+        assert_eq!(path, dir.join(v1.file_name()));
+        assert_eq!(std::fs::read(&path).unwrap(), b"hello v1");
+        let mut sig_path = path.into_os_string();
+        sig_path.push(".sig");
+        assert_eq!(std::fs::read(sig_path).unwrap().len(), 64);
+        assert_eq!(Installed::load(&dir).status(&v1), EntryStatus::Installed);
+
+        let v2 = entry(&publisher, b"hello v2", "1.1.0", ABI_VERSION);
+        assert_eq!(
+            Installed::load(&dir).status(&v2),
+            EntryStatus::UpdateAvailable {
+                installed: "1.0.0".into()
+            }
+        );
+
+        let old_abi = entry(&publisher, b"hello v0", "0.9.0", ABI_VERSION - 1);
+        assert_eq!(
+            Installed::load(&dir).status(&old_abi),
+            EntryStatus::Incompatible {
+                abi: ABI_VERSION - 1
+            }
+        );
+        assert!(install_bytes(&old_abi, b"hello v0", &dir, &verifier).is_err());
+        assert_eq!(
+            std::fs::read(dir.join(v1.file_name())).unwrap(),
+            b"hello v1"
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
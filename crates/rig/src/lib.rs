@@ -0,0 +1,491 @@
+//! Rigs: a whole setup in one file, to move between machines or share.
+//!
+//! A rig is a gzipped tar holding `rig.toml` (a [`RigManifest`]: the layout
+//! with its patches and tile settings, a manifest of the files those settings
+//! point at, and hashes of the plugins the layout uses) next to an `assets/`
+//! directory with those files; the same layout unpacked into a directory
+//! reads as well. [`import_rig`] checks that every module the rig uses
+//! exists here before anything is applied.
+
+use anyhow::{bail, Context, Result};
+use magnolia_core::layout_migration;
+use magnolia_core::LayoutConfig;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashMap};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// The rig format this build writes and reads
+pub const RIG_FORMAT: u32 = 1;
+/// File extension of rig archives
+pub const RIG_EXTENSION: &str = "rig";
+
+const MANIFEST: &str = "rig.toml";
+
+/// A file a tile setting points at
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RigAsset {
+    pub tile: String,
+    /// JSON pointer into the tile's settings config, e.g. `/path`
+    pub setting: String,
+    /// Path inside the archive
+    pub file: String,
+    pub sha256: String,
+}
+
+/// A plugin library the layout uses
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RigPlugin {
+    pub module: String,
+    /// Library file name
+    pub file: String,
+    pub sha256: String,
+}
+
+/// `rig.toml`
+#[derive(Debug, Clone, Serialize)]
+pub struct RigManifest {
+    pub format: u32,
+    pub name: String,
+    pub assets: Vec<RigAsset>,
+    pub plugins: Vec<RigPlugin>,
+    pub layout: LayoutConfig,
+}
+
+/// The manifest as read, before the layout is brought up to date
+#[derive(Deserialize)]
+struct RawManifest {
+    format: u32,
+    name: String,
+    #[serde(default)]
+    assets: Vec<RigAsset>,
+    #[serde(default)]
+    plugins: Vec<RigPlugin>,
+    layout: toml::Table,
+}
+
+/// A plugin the rig was made with that isn't here in the same build
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginMismatch {
+    pub module: String,
+    pub file: String,
+    /// Installed, but its hash differs
+    pub installed: bool,
+}
+
+/// A rig read and checked against this machine
+#[derive(Debug, Clone)]
+pub struct RigImport {
+    /// Asset settings point at the extracted files
+    pub manifest: RigManifest,
+    /// Modules the rig uses that aren't available here
+    pub missing_modules: Vec<String>,
+    pub plugin_mismatches: Vec<PluginMismatch>,
+}
+
+impl RigImport {
+    /// Every module the rig uses is available
+    pub fn is_complete(&self) -> bool {
+        self.missing_modules.is_empty()
+    }
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+/// Modules the layout's tiles and patches use
+pub fn referenced_modules(layout: &LayoutConfig) -> BTreeSet<String> {
+    let tiles = layout.tiles.iter().map(|tile| tile.module.clone());
+    let patches = layout
+        .patches
+        .iter()
+        .flat_map(|p| [p.source_module.clone(), p.sink_module.clone()]);
+    tiles.chain(patches).collect()
+}
+
+/// String settings under `value` that name existing files, with their JSON
+/// pointers
+fn file_settings(value: &serde_json::Value, pointer: &str, found: &mut Vec<(String, PathBuf)>) {
+    match value {
+        serde_json::Value::String(s) if !s.is_empty() && Path::new(s).is_file() => {
+            found.push((pointer.to_string(), PathBuf::from(s)));
+        }
+        serde_json::Value::Object(map) => {
+            for (key, value) in map {
+                let key = key.replace('~', "~0").replace('/', "~1");
+                file_settings(value, &format!("{}/{}", pointer, key), found);
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for (i, value) in items.iter().enumerate() {
+                file_settings(value, &format!("{}/{}", pointer, i), found);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn append(builder: &mut tar::Builder<impl std::io::Write>, path: &str, bytes: &[u8]) -> Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(bytes.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append_data(&mut header, path, bytes)?;
+    Ok(())
+}
+
+/// Write `layout` as a rig to `out`, with the files its settings point at
+/// and hashes of the `plugins` (module id, library path) it uses
+pub fn export_rig(
+    name: &str,
+    layout: &LayoutConfig,
+    plugins: &[(String, PathBuf)],
+    out: &Path,
+) -> Result<RigManifest> {
+    let mut assets = Vec::new();
+    let mut files = Vec::new();
+    for tile in &layout.tiles {
+        let mut found = Vec::new();
+        file_settings(&tile.settings.config, "", &mut found);
+        for (setting, path) in found {
+            let bytes = std::fs::read(&path).with_context(|| format!("{}", path.display()))?;
+            let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+            let file = format!("assets/{}-{}", assets.len(), file_name);
+            assets.push(RigAsset {
+                tile: tile.id.clone(),
+                setting,
+                file: file.clone(),
+                sha256: sha256_hex(&bytes),
+            });
+            files.push((file, bytes));
+        }
+    }
+
+    let used = referenced_modules(layout);
+    let mut rig_plugins = Vec::new();
+    for (module, path) in plugins.iter().filter(|(module, _)| used.contains(module)) {
+        let bytes = std::fs::read(path).with_context(|| format!("{}", path.display()))?;
+        rig_plugins.push(RigPlugin {
+            module: module.clone(),
+            file: path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned(),
+            sha256: sha256_hex(&bytes),
+        });
+    }
+
+    let manifest = RigManifest {
+        format: RIG_FORMAT,
+        name: name.to_string(),
+        assets,
+        plugins: rig_plugins,
+        layout: layout.clone(),
+    };
+    let toml = toml::to_string_pretty(&manifest).context("serializing rig manifest")?;
+
+    if let Some(dir) = out.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let file = std::fs::File::create(out).with_context(|| format!("{}", out.display()))?;
+    let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+        file,
+        flate2::Compression::default(),
+    ));
+    append(&mut builder, MANIFEST, toml.as_bytes())?;
+    for (path, bytes) in &files {
+        append(&mut builder, path, bytes)?;
+    }
+    builder.into_inner()?.finish()?;
+    Ok(manifest)
+}
+
+/// Parse `rig.toml`, upgrading the layout in it if it's from an older build
+pub fn parse_manifest(text: &str) -> Result<RigManifest> {
+    let raw: RawManifest = toml::from_str(text).context("reading rig manifest")?;
+    if raw.format > RIG_FORMAT {
+        bail!(
+            "rig format {} is newer than this build reads ({})",
+            raw.format,
+            RIG_FORMAT
+        );
+    }
+    let mut layout = raw.layout;
+    layout_migration::migrate(&mut layout)?;
+    Ok(RigManifest {
+        format: raw.format,
+        name: raw.name,
+        assets: raw.assets,
+        plugins: raw.plugins,
+        layout: LayoutConfig::deserialize(layout).context("reading rig layout")?,
+    })
+}
+
+/// A rig laid out as files rather than archived, as the examples in the
+/// source tree are: `rig.toml` with the assets at the paths it lists
+fn read_rig_dir(dir: &Path) -> Result<(RigManifest, HashMap<String, Vec<u8>>)> {
+    let manifest = dir.join(MANIFEST);
+    let text =
+        std::fs::read_to_string(&manifest).with_context(|| format!("{}", manifest.display()))?;
+    let manifest = parse_manifest(&text)?;
+    let mut files = HashMap::new();
+    for asset in &manifest.assets {
+        let path = dir.join(&asset.file);
+        let bytes = std::fs::read(&path).with_context(|| format!("{}", path.display()))?;
+        files.insert(asset.file.clone(), bytes);
+    }
+    Ok((manifest, files))
+}
+
+/// The manifest and the rig's other files by path; `archive` may also be
+/// an unpacked rig directory
+pub fn read_rig(archive: &Path) -> Result<(RigManifest, HashMap<String, Vec<u8>>)> {
+    if archive.is_dir() {
+        return read_rig_dir(archive);
+    }
+    let file = std::fs::File::open(archive).with_context(|| format!("{}", archive.display()))?;
+    let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(file));
+    let mut manifest = None;
+    let mut files = HashMap::new();
+    for entry in tar.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.to_string_lossy().into_owned();
+        let mut bytes = Vec::new();
+        entry.read_to_end(&mut bytes)?;
+        if path == MANIFEST {
+            manifest = Some(parse_manifest(&String::from_utf8_lossy(&bytes))?);
+        } else {
+            files.insert(path, bytes);
+        }
+    }
+    let manifest = manifest.with_context(|| format!("{}: no {}", archive.display(), MANIFEST))?;
+    Ok((manifest, files))
+}
+
+/// The rigs in `dir`, archives and unpacked directories alike, by name
+pub fn list_rigs(dir: &Path) -> Vec<PathBuf> {
+    let mut rigs: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| {
+                    path.extension().is_some_and(|ext| ext == RIG_EXTENSION)
+                        || path.join(MANIFEST).is_file()
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    rigs.sort();
+    rigs
+}
+
+/// Read a rig and check it against this machine: which modules it uses
+/// that `is_available` doesn't know, and which of its plugins differ from
+/// the `installed` ones (module id, library path). Assets are extracted
+/// under `assets_dir` and the settings pointed at them; nothing else is
+/// touched.
+pub fn import_rig(
+    archive: &Path,
+    assets_dir: &Path,
+    is_available: impl Fn(&str) -> bool,
+    installed: &[(String, PathBuf)],
+) -> Result<RigImport> {
+    let (mut manifest, files) = read_rig(archive)?;
+
+    let missing_modules = referenced_modules(&manifest.layout)
+        .into_iter()
+        .filter(|module| !is_available(module))
+        .collect();
+
+    let plugin_mismatches = manifest
+        .plugins
+        .iter()
+        .filter_map(|plugin| {
+            let local = installed
+                .iter()
+                .find(|(module, _)| *module == plugin.module)
+                .and_then(|(_, path)| std::fs::read(path).ok());
+            match local {
+                Some(bytes) if sha256_hex(&bytes) == plugin.sha256 => None,
+                local => Some(PluginMismatch {
+                    module: plugin.module.clone(),
+                    file: plugin.file.clone(),
+                    installed: local.is_some(),
+                }),
+            }
+        })
+        .collect();
+
+    let rig_dir = assets_dir.join(
+        manifest
+            .name
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect::<String>(),
+    );
+    for asset in &manifest.assets {
+        let bytes = files
+            .get(&asset.file)
+            .with_context(|| format!("rig is missing {}", asset.file))?;
+        if sha256_hex(bytes) != asset.sha256 {
+            bail!("{} doesn't match its hash", asset.file);
+        }
+        let file_name = Path::new(&asset.file)
+            .file_name()
+            .context("asset without a file name")?;
+        let target = rig_dir.join(file_name);
+        std::fs::create_dir_all(&rig_dir)?;
+        std::fs::write(&target, bytes).with_context(|| format!("{}", target.display()))?;
+        let setting = manifest
+            .layout
+            .tiles
+            .iter_mut()
+            .find(|tile| tile.id == asset.tile)
+            .and_then(|tile| tile.settings.config.pointer_mut(&asset.setting));
+        if let Some(setting) = setting {
+            *setting = serde_json::Value::String(target.to_string_lossy().into_owned());
+        }
+    }
+
+    Ok(RigImport {
+        manifest,
+        missing_modules,
+        plugin_mismatches,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use magnolia_core::{Patch, TileConfig, TileSettings};
+
+    fn tile(id: &str, module: &str, config: serde_json::Value) -> TileConfig {
+        TileConfig {
+            id: id.to_string(),
+            col: 0,
+            row: 0,
+            colspan: None,
+            rowspan: None,
+            module: module.to_string(),
+            enabled: true,
+            settings: TileSettings {
+                config,
+                ..TileSettings::default()
+            },
+            window: None,
+            z: 0,
+            overlay: None,
+        }
+    }
+
+    #[test]
+    fn round_trips_layout_assets_and_plugins() {
+        let dir = std::env::temp_dir().join(format!("magnolia_rig_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let wav = dir.join("take.wav");
+        std::fs::write(&wav, b"RIFF....WAVE").unwrap();
+        let plugin = dir.join("libhello.so");
+        std::fs::write(&plugin, b"\x7fELF hello").unwrap();
+
+        let (mut layout, _) =
+            magnolia_core::parse_layout("columns = [\"1fr\"]\nrows = [\"1fr\"]\ntiles = []")
+                .unwrap();
+        layout.tiles = vec![
+            tile(
+                "replay",
+                "audio_replay",
+                serde_json::json!({ "path": wav.to_string_lossy(), "loop": true }),
+            ),
+            tile("hello", "hello", serde_json::json!({ "greeting": "hi" })),
+        ];
+        layout.patches = vec![Patch {
+            id: "p1".into(),
+            source_module: "audio_replay".into(),
+            source_port: "audio_out".into(),
+            sink_module: "audio_output".into(),
+            sink_port: "audio_in".into(),
+            merge: None,
+            transform: None,
+        }];
+
+        let archive = dir.join("show.rig");
+        let plugins = vec![
+            ("hello".to_string(), plugin.clone()),
+            ("unused".to_string(), plugin.clone()),
+        ];
+        let manifest = export_rig("show", &layout, &plugins, &archive).unwrap();
+        assert_eq!(manifest.assets.len(), 1);
+        assert_eq!(manifest.assets[0].setting, "/path");
+        assert_eq!(manifest.plugins.len(), 1);
+
+        // Same plugin here, but no audio_output
+        let import = import_rig(
+            &archive,
+            &dir.join("assets"),
+            |module| module != "audio_output",
+            &plugins,
+        )
+        .unwrap();
+        assert!(!import.is_complete());
+        assert_eq!(import.missing_modules, ["audio_output"]);
+        assert!(import.plugin_mismatches.is_empty());
+        let replay = &import.manifest.layout.tiles[0];
+        let extracted = PathBuf::from(replay.settings.config["path"].as_str().unwrap());
+        assert_eq!(extracted, dir.join("assets/show/0-take.wav"));
+        assert_eq!(std::fs::read(&extracted).unwrap(), b"RIFF....WAVE");
+        assert_eq!(replay.settings.config["loop"], true);
+        assert_eq!(import.manifest.layout.patches.len(), 1);
+
+        // A different build of the plugin, then none at all
+        std::fs::write(&plugin, b"\x7fELF hello v2").unwrap();
+        let import = import_rig(&archive, &dir.join("assets"), |_| true, &plugins).unwrap();
+        assert!(import.is_complete());
+        assert_eq!(
+            import.plugin_mismatches,
+            [PluginMismatch {
+                module: "hello".into(),
+                file: "libhello.so".into(),
+                installed: true,
+            }]
+        );
+        let import = import_rig(&archive, &dir.join("assets"), |_| true, &[]).unwrap();
+        assert!(!import.plugin_mismatches[0].installed);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn shipped_examples_read() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../examples/rigs");
+        let examples = list_rigs(&dir);
+        assert_eq!(examples.len(), 3);
+        for example in examples {
+            let (manifest, _) = read_rig(&example).unwrap();
+            let dir_name = example.file_name().unwrap().to_string_lossy();
+            assert_eq!(manifest.name, dir_name);
+            assert!(!manifest.layout.patches.is_empty());
+        }
+    }
+
+    #[test]
+    fn newer_rigs_are_refused() {
+        let text = format!(
+            "format = {}\nname = \"x\"\n[layout]\ncolumns = []\nrows = []\ntiles = []\n",
+            RIG_FORMAT + 1
+        );
+        assert!(parse_manifest(&text).is_err());
+        let text =
+            "format = 1\nname = \"x\"\n[layout]\ncolumns = 2\nrows = [\"1fr\"]\ntiles = []\n";
+        assert_eq!(parse_manifest(text).unwrap().layout.columns.len(), 2);
+    }
+}
@@ -0,0 +1,32 @@
+// Default canvas: a ring that breathes with the audio level, spectrum bands
+// as spokes, and the Sun and Moon as dots on the ring.
+
+fn dot_at(p: vec2<f32>, degrees: f32, radius: f32, size: f32) -> f32 {
+    if (degrees < 0.0) {
+        return 0.0;
+    }
+    let a = radians(degrees);
+    let c = vec2<f32>(cos(a), sin(a)) * radius;
+    return smoothstep(size, size * 0.5, length(p - c));
+}
+
+fn shade(uv: vec2<f32>) -> vec4<f32> {
+    let aspect = u.frame.x / max(u.frame.y, 1.0);
+    let p = (uv - vec2<f32>(0.5, 0.5)) * vec2<f32>(aspect, 1.0) * 2.0;
+    let r = length(p);
+    let angle = atan2(p.y, p.x);
+    let t = u.frame.z;
+
+    let radius = 0.55 + u.audio.x * 0.35;
+    let ring = smoothstep(0.03, 0.0, abs(r - radius));
+
+    let sector = i32(floor((angle / 6.2831853 + 0.5) * 8.0)) % 8;
+    let spoke = band(sector) * smoothstep(radius, 0.0, r) * 0.6;
+
+    let sun = dot_at(p, planet(0), radius, 0.06);
+    let moon = dot_at(p, planet(1), radius, 0.045);
+
+    let hue = vec3<f32>(0.5 + 0.5 * sin(t * 0.3), 0.4 + 0.4 * u.audio.y, 0.9);
+    let color = hue * (ring + spoke) + vec3<f32>(1.0, 0.8, 0.3) * sun + vec3<f32>(0.8) * moon;
+    return vec4<f32>(color, 1.0);
+}
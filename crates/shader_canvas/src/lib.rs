@@ -0,0 +1,27 @@
+//! Audio-reactive shader canvas.
+//!
+//! Runs a user-supplied WGSL fragment shader (see [`shader`]) with a uniform
+//! block fed from patched signals: audio level and spectrum bands, planetary
+//! longitudes and numeric modulation. [`ShaderCanvasSink`] writes the shared
+//! [`CanvasInputs`]; the tile renders into a host texture each frame.
+//!
+//! This lives in-tree rather than as a plugin because plugins never get a
+//! `wgpu` device, only the host's draw-command canvas.
+
+#[cfg(feature = "gpu")]
+pub mod renderer;
+mod settings;
+pub mod shader;
+mod sink;
+#[cfg(feature = "tile-rendering")]
+mod tile;
+pub mod uniforms;
+
+#[cfg(feature = "gpu")]
+pub use renderer::ShaderRenderer;
+pub use settings::{CanvasConfig, MAX_RESOLUTION, MIN_RESOLUTION};
+pub use shader::{ShaderError, DEFAULT_SHADER};
+pub use sink::{AudioAnalyzer, ShaderCanvasSink, FFT_SIZE};
+#[cfg(feature = "tile-rendering")]
+pub use tile::ShaderCanvasTile;
+pub use uniforms::{CanvasInputs, ShaderUniforms, UniformInputs};
@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+
+use crate::uniforms::MOD_COUNT;
+
+pub const MIN_RESOLUTION: u32 = 64;
+pub const MAX_RESOLUTION: u32 = 2048;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CanvasConfig {
+    /// WGSL file defining `shade(uv)`; empty for the built-in shader
+    pub shader_path: String,
+    /// Edge length of the square render target, in pixels
+    pub resolution: u32,
+    /// Numeric sources pinned to `u.mods` slots, in order; unlisted sources
+    /// take the next free slot
+    pub mod_sources: Vec<String>,
+}
+
+impl Default for CanvasConfig {
+    fn default() -> Self {
+        Self {
+            shader_path: String::new(),
+            resolution: 512,
+            mod_sources: Vec::new(),
+        }
+    }
+}
+
+impl CanvasConfig {
+    pub fn resolution(&self) -> u32 {
+        self.resolution.clamp(MIN_RESOLUTION, MAX_RESOLUTION)
+    }
+
+    pub fn schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "shader_path": {
+                    "type": "string",
+                    "title": "Shader (WGSL)",
+                    "default": ""
+                },
+                "resolution": {
+                    "type": "integer",
+                    "title": "Resolution (px)",
+                    "minimum": MIN_RESOLUTION,
+                    "maximum": MAX_RESOLUTION,
+                    "default": 512
+                },
+                "mod_sources": {
+                    "type": "array",
+                    "title": "Modulation Sources",
+                    "items": { "type": "string" },
+                    "maxItems": MOD_COUNT,
+                    "default": []
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,134 @@
+//! WGSL handling: the prelude wrapped around user shaders, and validation.
+//!
+//! A canvas shader only has to define
+//!
+//! ```wgsl
+//! fn shade(uv: vec2<f32>) -> vec4<f32> { ... }
+//! ```
+//!
+//! with `uv` in 0..1 (origin bottom-left). The prelude declares the
+//! [`Uniforms`](PRELUDE) block as `u` and a full-screen triangle vertex stage;
+//! a fragment entry point calling `shade` is appended unless the source
+//! defines its own `fs_main`.
+
+use thiserror::Error;
+
+/// Declarations prepended to every canvas shader; matches
+/// [`ShaderUniforms`](crate::ShaderUniforms)
+pub const PRELUDE: &str = r#"
+struct Uniforms {
+    // width, height, seconds, frame
+    frame: vec4<f32>,
+    // rms, peak, unsmoothed rms, 0
+    audio: vec4<f32>,
+    // 8 spectrum bands, low to high, 0..1
+    bands: array<vec4<f32>, 2>,
+    // sun moon mercury venus | mars jupiter saturn uranus | neptune pluto node chiron
+    planets: array<vec4<f32>, 3>,
+    // numeric modulation inputs
+    mods: array<vec4<f32>, 2>,
+}
+
+@group(0) @binding(0)
+var<uniform> u: Uniforms;
+
+fn band(i: i32) -> f32 {
+    return u.bands[i / 4][i % 4];
+}
+
+fn planet(i: i32) -> f32 {
+    return u.planets[i / 4][i % 4];
+}
+
+fn modulation(i: i32) -> f32 {
+    return u.mods[i / 4][i % 4];
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+    let x = f32((index << 1u) & 2u) * 2.0 - 1.0;
+    let y = f32(index & 2u) * 2.0 - 1.0;
+    return vec4<f32>(x, y, 0.0, 1.0);
+}
+"#;
+
+const FRAGMENT: &str = r#"
+@fragment
+fn fs_main(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    let uv = vec2<f32>(position.x / u.frame.x, 1.0 - position.y / u.frame.y);
+    return shade(uv);
+}
+"#;
+
+/// Shader used when no file is configured
+pub const DEFAULT_SHADER: &str = include_str!("../shaders/pulse.wgsl");
+
+#[derive(Error, Debug)]
+pub enum ShaderError {
+    #[error("Could not read shader {path}: {source}")]
+    Io {
+        path: String,
+        source: std::io::Error,
+    },
+    #[error("WGSL parse error:\n{0}")]
+    Parse(String),
+    #[error("WGSL validation error:\n{0}")]
+    Validation(String),
+}
+
+/// Full module source for `user` (prelude, user code, fragment entry point)
+pub fn compose(user: &str) -> String {
+    let mut source = String::with_capacity(PRELUDE.len() + user.len() + FRAGMENT.len());
+    source.push_str(PRELUDE);
+    source.push_str(user);
+    if !user.contains("fn fs_main") {
+        source.push_str(FRAGMENT);
+    }
+    source
+}
+
+/// Compose and validate `user`, returning the full module source.
+///
+/// Errors carry naga's annotated diagnostics so they can be shown as-is in
+/// the tile; line numbers refer to the composed source.
+pub fn compile(user: &str) -> Result<String, ShaderError> {
+    let source = compose(user);
+    let module = naga::front::wgsl::parse_str(&source)
+        .map_err(|e| ShaderError::Parse(e.emit_to_string(&source)))?;
+    naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::empty(),
+    )
+    .validate(&module)
+    .map_err(|e| ShaderError::Validation(e.emit_to_string(&source)))?;
+    Ok(source)
+}
+
+/// Read and compile a shader file
+pub fn load(path: &std::path::Path) -> Result<String, ShaderError> {
+    let user = std::fs::read_to_string(path).map_err(|source| ShaderError::Io {
+        path: path.display().to_string(),
+        source,
+    })?;
+    compile(&user)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_shader_compiles() {
+        let source = compile(DEFAULT_SHADER).unwrap();
+        assert!(source.contains("fn fs_main"));
+    }
+
+    #[test]
+    fn reports_parse_and_type_errors() {
+        let err = compile("fn shade(uv: vec2<f32>) -> vec4<f32> { return uv }").unwrap_err();
+        assert!(matches!(err, ShaderError::Parse(_)), "{err}");
+
+        let err = compile("fn shade(uv: vec2<f32>) -> vec4<f32> { return uv; }").unwrap_err();
+        assert!(matches!(err, ShaderError::Validation(_)), "{err}");
+    }
+}
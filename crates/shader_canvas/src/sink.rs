@@ -0,0 +1,271 @@
+//! `shader_canvas` sink: turns patched signals into shader uniforms.
+//!
+//! - `audio_in`: level meters and [`BAND_COUNT`] log-spaced spectrum bands
+//!   from the last [`FFT_SIZE`] mono samples
+//! - `astro_in`: planetary longitudes ([`PLANETS`](crate::uniforms::PLANETS))
+//! - `mod_in`: numeric `Computed` signals, one `u.mods` slot per source
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use magnolia_core::{DataType, ModuleSchema, Port, PortDirection, Signal, Sink};
+use rustfft::num_complex::Complex;
+use rustfft::{Fft, FftPlanner};
+
+use crate::uniforms::{CanvasInputs, BAND_COUNT};
+
+pub const FFT_SIZE: usize = 1024;
+
+/// Lowest band edge, Hz; the top edge is Nyquist (capped at 16 kHz)
+const MIN_BAND_HZ: f32 = 40.0;
+const MAX_BAND_HZ: f32 = 16_000.0;
+/// Band magnitudes are mapped from this many dB below full scale to 0..1
+const BAND_RANGE_DB: f32 = 60.0;
+
+/// Mono history and FFT scratch for the spectrum bands
+pub struct AudioAnalyzer {
+    history: Vec<f32>,
+    window: Vec<f32>,
+    fft: Arc<dyn Fft<f32>>,
+    scratch: Vec<Complex<f32>>,
+}
+
+impl Default for AudioAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AudioAnalyzer {
+    pub fn new() -> Self {
+        let window = (0..FFT_SIZE)
+            .map(|i| {
+                let phase = std::f32::consts::TAU * i as f32 / (FFT_SIZE - 1) as f32;
+                0.5 - 0.5 * phase.cos()
+            })
+            .collect();
+        Self {
+            history: vec![0.0; FFT_SIZE],
+            window,
+            fft: FftPlanner::new().plan_fft_forward(FFT_SIZE),
+            scratch: vec![Complex::default(); FFT_SIZE],
+        }
+    }
+
+    /// RMS, peak and band levels of an interleaved block
+    pub fn analyze(
+        &mut self,
+        data: &[f32],
+        channels: u16,
+        sample_rate: u32,
+    ) -> (f32, f32, [f32; BAND_COUNT]) {
+        let channels = channels.max(1) as usize;
+        let mono: Vec<f32> = data
+            .chunks(channels)
+            .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+            .collect();
+        if mono.is_empty() {
+            return (0.0, 0.0, [0.0; BAND_COUNT]);
+        }
+        let rms = (mono.iter().map(|s| s * s).sum::<f32>() / mono.len() as f32).sqrt();
+        let peak = mono.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+
+        if mono.len() >= FFT_SIZE {
+            self.history.copy_from_slice(&mono[mono.len() - FFT_SIZE..]);
+        } else {
+            self.history.rotate_left(mono.len());
+            self.history[FFT_SIZE - mono.len()..].copy_from_slice(&mono);
+        }
+        (rms.min(1.0), peak.min(1.0), self.bands(sample_rate))
+    }
+
+    fn bands(&mut self, sample_rate: u32) -> [f32; BAND_COUNT] {
+        for (bin, (sample, w)) in self
+            .scratch
+            .iter_mut()
+            .zip(self.history.iter().zip(&self.window))
+        {
+            *bin = Complex::new(sample * w, 0.0);
+        }
+        self.fft.process(&mut self.scratch);
+
+        let hz_per_bin = sample_rate.max(1) as f32 / FFT_SIZE as f32;
+        let top = MAX_BAND_HZ
+            .min(sample_rate as f32 / 2.0)
+            .max(MIN_BAND_HZ * 2.0);
+        let ratio = (top / MIN_BAND_HZ).powf(1.0 / BAND_COUNT as f32);
+        // A full-scale sine through a Hann window peaks at N/4
+        let full_scale = FFT_SIZE as f32 / 4.0;
+
+        let mut bands = [0.0; BAND_COUNT];
+        for (i, band) in bands.iter_mut().enumerate() {
+            let lo = MIN_BAND_HZ * ratio.powi(i as i32);
+            let first = ((lo / hz_per_bin) as usize).max(1);
+            let last = (((lo * ratio) / hz_per_bin).ceil() as usize).clamp(first + 1, FFT_SIZE / 2);
+            let magnitude = self.scratch[first..last]
+                .iter()
+                .map(|c| c.norm())
+                .fold(0.0f32, f32::max)
+                / full_scale;
+            let db = 20.0 * magnitude.max(1e-6).log10();
+            *band = ((db + BAND_RANGE_DB) / BAND_RANGE_DB).clamp(0.0, 1.0);
+        }
+        bands
+    }
+}
+
+/// Feeds [`CanvasInputs`] from the patch bay
+pub struct ShaderCanvasSink {
+    id: String,
+    enabled: bool,
+    inputs: Arc<CanvasInputs>,
+    analyzer: Mutex<AudioAnalyzer>,
+}
+
+impl ShaderCanvasSink {
+    pub fn new(id: &str, inputs: Arc<CanvasInputs>) -> Self {
+        Self {
+            id: id.to_string(),
+            enabled: true,
+            inputs,
+            analyzer: Mutex::new(AudioAnalyzer::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl Sink for ShaderCanvasSink {
+    fn name(&self) -> &str {
+        "Shader Canvas"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "Shader Canvas".to_string(),
+            description: "WGSL fragment shader with uniforms from patched signals".to_string(),
+            ports: vec![
+                Port {
+                    id: "audio_in".to_string(),
+                    label: "Audio In".to_string(),
+                    data_type: DataType::Audio,
+                    direction: PortDirection::Input,
+                },
+                Port {
+                    id: "astro_in".to_string(),
+                    label: "Astrology".to_string(),
+                    data_type: DataType::Astrology,
+                    direction: PortDirection::Input,
+                },
+                Port {
+                    id: "mod_in".to_string(),
+                    label: "Modulation".to_string(),
+                    data_type: DataType::Numeric,
+                    direction: PortDirection::Input,
+                },
+            ],
+            settings_schema: None,
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    async fn consume(&self, signal: Signal) -> anyhow::Result<Option<Signal>> {
+        if !self.enabled {
+            return Ok(None);
+        }
+
+        match signal {
+            Signal::Audio { .. } | Signal::SharedAudio { .. } => {
+                let Some(audio) = signal.audio() else {
+                    return Ok(None);
+                };
+                let (rms, peak, bands) = match self.analyzer.lock() {
+                    Ok(mut analyzer) => {
+                        analyzer.analyze(audio.data, audio.channels, audio.sample_rate)
+                    }
+                    Err(_) => return Ok(None),
+                };
+                self.inputs
+                    .update(|inputs| inputs.apply_audio(rms, peak, &bands));
+            }
+            Signal::Astrology(data) => {
+                self.inputs.update(|inputs| {
+                    for (name, longitude) in &data.planetary_positions {
+                        inputs.apply_planet(name, *longitude);
+                    }
+                });
+            }
+            Signal::Computed { source, content } => {
+                // Only bare numbers; JSON payloads on other ports are ignored
+                if let Ok(value) = content.trim().parse::<f32>() {
+                    self.inputs
+                        .update(|inputs| inputs.apply_mod(&source, value));
+                }
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use magnolia_core::AstrologyData;
+
+    fn sine(hz: f32, sample_rate: u32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| (std::f32::consts::TAU * hz * i as f32 / sample_rate as f32).sin())
+            .collect()
+    }
+
+    #[test]
+    fn sine_lands_in_its_band() {
+        let mut analyzer = AudioAnalyzer::new();
+        let (rms, peak, bands) = analyzer.analyze(&sine(1000.0, 48_000, 2048), 1, 48_000);
+        assert!((rms - std::f32::consts::FRAC_1_SQRT_2).abs() < 0.01);
+        assert!(peak > 0.99);
+        let loudest = (0..BAND_COUNT)
+            .max_by(|&a, &b| bands[a].total_cmp(&bands[b]))
+            .unwrap();
+        // Edges grow by (16k / 40)^(1/8) from 40 Hz: 1 kHz is in band 4 (800..1690 Hz)
+        assert_eq!(loudest, 4);
+        assert!(bands[loudest] > 0.9);
+        assert!(bands[0] < 0.3);
+    }
+
+    #[tokio::test]
+    async fn routes_signals_into_inputs() {
+        let inputs = CanvasInputs::new();
+        let sink = ShaderCanvasSink::new("shader_canvas", inputs.clone());
+        sink.consume(Signal::Astrology(AstrologyData {
+            sun_sign: "Aries".to_string(),
+            moon_sign: "Leo".to_string(),
+            rising_sign: "Virgo".to_string(),
+            planetary_positions: vec![("sun".to_string(), 12.5), ("eris".to_string(), 3.0)],
+        }))
+        .await
+        .unwrap();
+        for content in ["21.5", "{\"temperature\": 21.5}"] {
+            sink.consume(Signal::Computed {
+                source: "weather_temperature".to_string(),
+                content: content.to_string(),
+            })
+            .await
+            .unwrap();
+        }
+
+        let snapshot = inputs.snapshot();
+        assert_eq!(snapshot.planets[0], 12.5);
+        assert_eq!(snapshot.mods[0], 21.5);
+        assert_eq!(snapshot.mod_sources().len(), 1);
+    }
+}
@@ -0,0 +1,296 @@
+//! Shader Canvas Tile
+//!
+//! Runs the configured WGSL shader into a square render target every frame
+//! and draws that texture. Monitor mode shows the canvas only; the maximized
+//! view adds the uniform values and the shader path. Compile errors keep the
+//! last good pipeline running and are reported through `get_error`.
+
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Instant, SystemTime};
+
+use magnolia_core::{BindableAction, GpuDeviceSource, RenderContext, TileError, TileRenderer};
+use magnolia_ui::{draw_text, FontId, TextAlignment};
+use nannou::prelude::*;
+use nannou::wgpu;
+
+use crate::renderer::ShaderRenderer;
+use crate::settings::CanvasConfig;
+use crate::shader::{ShaderError, DEFAULT_SHADER};
+use crate::uniforms::{CanvasInputs, PLANETS};
+
+const TARGET_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+pub struct ShaderCanvasTile {
+    id: String,
+    device: Arc<dyn GpuDeviceSource>,
+    inputs: Arc<CanvasInputs>,
+    config: CanvasConfig,
+    renderer: Option<ShaderRenderer>,
+    target: Option<(wgpu::Texture, wgpu::TextureView)>,
+    error: Option<String>,
+    /// Modification time of the loaded shader file, for live reload
+    loaded_mtime: Option<SystemTime>,
+    started: Instant,
+    frame: u64,
+}
+
+impl ShaderCanvasTile {
+    pub fn new(id: &str, device: Arc<dyn GpuDeviceSource>, inputs: Arc<CanvasInputs>) -> Self {
+        let mut tile = Self {
+            id: id.to_string(),
+            device,
+            inputs,
+            config: CanvasConfig::default(),
+            renderer: None,
+            target: None,
+            error: None,
+            loaded_mtime: None,
+            started: Instant::now(),
+            frame: 0,
+        };
+        tile.reload();
+        tile
+    }
+
+    fn shader_mtime(&self) -> Option<SystemTime> {
+        if self.config.shader_path.is_empty() {
+            return None;
+        }
+        std::fs::metadata(&self.config.shader_path)
+            .and_then(|m| m.modified())
+            .ok()
+    }
+
+    /// (Re)compile the configured shader; on failure the previous pipeline
+    /// stays in place
+    fn reload(&mut self) {
+        self.loaded_mtime = self.shader_mtime();
+        let user = if self.config.shader_path.is_empty() {
+            Ok(DEFAULT_SHADER.to_string())
+        } else {
+            let path = Path::new(&self.config.shader_path);
+            std::fs::read_to_string(path).map_err(|source| ShaderError::Io {
+                path: path.display().to_string(),
+                source,
+            })
+        };
+        match user.and_then(|user| ShaderRenderer::new(self.device.device(), TARGET_FORMAT, &user))
+        {
+            Ok(renderer) => {
+                self.renderer = Some(renderer);
+                self.error = None;
+                self.started = Instant::now();
+                self.frame = 0;
+            }
+            Err(e) => {
+                log::warn!("shader_canvas: {}", e);
+                self.error = Some(e.to_string());
+            }
+        }
+    }
+
+    fn ensure_target(&mut self) {
+        let size = self.config.resolution();
+        if matches!(&self.target, Some((texture, _)) if texture.size() == [size, size]) {
+            return;
+        }
+        let texture = wgpu::TextureBuilder::new()
+            .size([size, size])
+            .format(TARGET_FORMAT)
+            .usage(wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING)
+            .build(self.device.device());
+        let view = texture.view().build();
+        self.target = Some((texture, view));
+    }
+
+    fn canvas_rect(rect: Rect) -> Rect {
+        let side = rect.w().min(rect.h());
+        Rect::from_xy_wh(rect.xy(), vec2(side, side))
+    }
+
+    fn draw_canvas(&self, draw: &Draw, rect: Rect) {
+        draw.rect().xy(rect.xy()).wh(rect.wh()).color(BLACK);
+        if let (Some((_, view)), Some(_)) = (&self.target, &self.renderer) {
+            let canvas = Self::canvas_rect(rect);
+            draw.texture(view).xy(canvas.xy()).wh(canvas.wh());
+        }
+    }
+}
+
+impl TileRenderer for ShaderCanvasTile {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn name(&self) -> &str {
+        "Shader Canvas"
+    }
+
+    fn prefers_gpu(&self) -> bool {
+        true
+    }
+
+    fn update(&mut self) {
+        if self.loaded_mtime != self.shader_mtime() {
+            self.reload();
+        }
+        self.ensure_target();
+        let (Some(renderer), Some((_, view))) = (&self.renderer, &self.target) else {
+            return;
+        };
+        let size = self.config.resolution();
+        let uniforms = self.inputs.snapshot().uniforms(
+            size,
+            size,
+            self.started.elapsed().as_secs_f32(),
+            self.frame,
+        );
+        renderer.render(self.device.device(), self.device.queue(), view, &uniforms);
+        self.frame += 1;
+    }
+
+    fn render_monitor(&self, draw: &Draw, rect: Rect, _ctx: &RenderContext) {
+        self.draw_canvas(draw, rect);
+        if self.renderer.is_none() {
+            draw_text(
+                draw,
+                FontId::PlexSansBold,
+                "SHADER ERROR",
+                rect.xy(),
+                14.0,
+                srgba(1.0, 0.4, 0.4, 1.0),
+                TextAlignment::Center,
+            );
+        }
+    }
+
+    fn render_controls(&self, draw: &Draw, rect: Rect, _ctx: &RenderContext) -> bool {
+        draw.rect()
+            .xy(rect.xy())
+            .wh(rect.wh())
+            .color(srgba(0.02, 0.02, 0.04, 0.95));
+
+        let pad = 20.0;
+        let panel_w = (rect.w() * 0.35).clamp(200.0, 360.0);
+        let canvas = Rect::from_x_y_w_h(
+            rect.left() + (rect.w() - panel_w) / 2.0,
+            rect.y(),
+            rect.w() - panel_w - pad * 2.0,
+            rect.h() - pad * 2.0,
+        );
+        self.draw_canvas(draw, canvas);
+
+        let inputs = self.inputs.snapshot();
+        let shader = if self.config.shader_path.is_empty() {
+            "built-in".to_string()
+        } else {
+            self.config.shader_path.clone()
+        };
+        let mut lines = vec![
+            format!("Shader: {}", shader),
+            format!(
+                "Frame {}  {:.1}s",
+                self.frame,
+                self.started.elapsed().as_secs_f32()
+            ),
+            format!("RMS {:.2}  Peak {:.2}", inputs.rms, inputs.peak),
+            format!(
+                "Bands {}",
+                inputs
+                    .bands
+                    .iter()
+                    .map(|b| format!("{:.1}", b))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+        ];
+        lines.extend(
+            PLANETS
+                .iter()
+                .zip(inputs.planets)
+                .filter(|(_, lon)| *lon >= 0.0)
+                .map(|(name, lon)| format!("{:<10} {:6.2}°", name, lon)),
+        );
+        lines.extend(
+            inputs
+                .mod_sources()
+                .into_iter()
+                .map(|(slot, source)| format!("mod{} {} = {:.3}", slot, source, inputs.mods[slot])),
+        );
+        if let Some(error) = &self.error {
+            lines.push(String::new());
+            lines.extend(error.lines().take(12).map(str::to_string));
+        }
+
+        let x = rect.right() - panel_w / 2.0 - pad;
+        let mut y = rect.top() - pad;
+        for line in &lines {
+            draw_text(
+                draw,
+                FontId::PlexMonoRegular,
+                line,
+                pt2(x, y),
+                12.0,
+                srgba(0.8, 0.8, 0.8, 1.0),
+                TextAlignment::Center,
+            );
+            y -= 16.0;
+        }
+        false
+    }
+
+    fn get_error(&self) -> Option<TileError> {
+        let error = self.error.as_ref()?;
+        Some(TileError::new("Shader failed to compile").with_details(error))
+    }
+
+    fn clear_error(&mut self) {
+        self.error = None;
+    }
+
+    fn retry(&mut self) -> bool {
+        self.reload();
+        true
+    }
+
+    fn settings_schema(&self) -> Option<serde_json::Value> {
+        Some(CanvasConfig::schema())
+    }
+
+    fn apply_settings(&mut self, settings: &serde_json::Value) {
+        match serde_json::from_value::<CanvasConfig>(settings.clone()) {
+            Ok(config) if config != self.config => {
+                let reload = config.shader_path != self.config.shader_path;
+                if config.mod_sources != self.config.mod_sources {
+                    self.inputs
+                        .update(|inputs| inputs.set_mod_sources(&config.mod_sources));
+                }
+                self.config = config;
+                if reload {
+                    self.reload();
+                }
+            }
+            Ok(_) => {}
+            Err(e) => log::warn!("Shader canvas: invalid settings, keeping current: {}", e),
+        }
+    }
+
+    fn get_settings(&self) -> serde_json::Value {
+        serde_json::to_value(&self.config).unwrap_or_default()
+    }
+
+    fn bindable_actions(&self) -> Vec<BindableAction> {
+        vec![BindableAction::new("reload", "Reload Shader", false)]
+    }
+
+    fn execute_action(&mut self, action: &str) -> bool {
+        match action {
+            "reload" => {
+                self.reload();
+                true
+            }
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,234 @@
+//! The uniform block every canvas shader sees, and the patched inputs it is
+//! built from.
+//!
+//! The WGSL side is declared in [`crate::shader::PRELUDE`]; the layout here
+//! must match it field for field. Scalars are packed into `vec4`s so the block
+//! has no implicit padding under WGSL's uniform layout rules.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+pub const BAND_COUNT: usize = 8;
+pub const PLANET_COUNT: usize = 12;
+pub const MOD_COUNT: usize = 8;
+
+/// Bodies in `u.planets` order (longitudes in degrees, -1 when unknown)
+pub const PLANETS: [&str; PLANET_COUNT] = [
+    "sun",
+    "moon",
+    "mercury",
+    "venus",
+    "mars",
+    "jupiter",
+    "saturn",
+    "uranus",
+    "neptune",
+    "pluto",
+    "north_node",
+    "chiron",
+];
+
+/// Level meters fall by this factor per audio block, so visuals decay
+/// smoothly instead of flickering between buffers.
+const RELEASE: f32 = 0.85;
+
+/// Mirror of the WGSL `Uniforms` struct
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ShaderUniforms {
+    /// width, height, seconds since load, frame number
+    pub frame: [f32; 4],
+    /// rms, peak, rms before release smoothing, 0
+    pub audio: [f32; 4],
+    pub bands: [[f32; 4]; BAND_COUNT / 4],
+    pub planets: [[f32; 4]; PLANET_COUNT / 4],
+    pub mods: [[f32; 4]; MOD_COUNT / 4],
+}
+
+impl ShaderUniforms {
+    /// Size of the uniform buffer, in bytes
+    pub const SIZE: usize = std::mem::size_of::<Self>();
+
+    /// Native-endian bytes for `Queue::write_buffer`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::SIZE);
+        let vectors = std::iter::once(&self.frame)
+            .chain(std::iter::once(&self.audio))
+            .chain(&self.bands)
+            .chain(&self.planets)
+            .chain(&self.mods);
+        for value in vectors.flatten() {
+            bytes.extend_from_slice(&value.to_ne_bytes());
+        }
+        bytes
+    }
+}
+
+fn pack<const N: usize, const V: usize>(values: &[f32; N]) -> [[f32; 4]; V] {
+    let mut packed = [[0.0; 4]; V];
+    for (i, value) in values.iter().enumerate() {
+        packed[i / 4][i % 4] = *value;
+    }
+    packed
+}
+
+/// Latest patched values, written by the sink and read each frame by the tile
+#[derive(Debug, Clone)]
+pub struct UniformInputs {
+    pub rms: f32,
+    pub peak: f32,
+    pub raw_rms: f32,
+    pub bands: [f32; BAND_COUNT],
+    pub planets: [f32; PLANET_COUNT],
+    pub mods: [f32; MOD_COUNT],
+    /// Numeric source name -> `u.mods` slot
+    mod_slots: HashMap<String, usize>,
+}
+
+impl Default for UniformInputs {
+    fn default() -> Self {
+        Self {
+            rms: 0.0,
+            peak: 0.0,
+            raw_rms: 0.0,
+            bands: [0.0; BAND_COUNT],
+            planets: [-1.0; PLANET_COUNT],
+            mods: [0.0; MOD_COUNT],
+            mod_slots: HashMap::new(),
+        }
+    }
+}
+
+impl UniformInputs {
+    /// Fold in one audio block's levels and band energies (all 0..1)
+    pub fn apply_audio(&mut self, rms: f32, peak: f32, bands: &[f32; BAND_COUNT]) {
+        self.raw_rms = rms;
+        self.rms = rms.max(self.rms * RELEASE);
+        self.peak = peak.max(self.peak * RELEASE);
+        for (level, &band) in self.bands.iter_mut().zip(bands) {
+            *level = band.max(*level * RELEASE);
+        }
+    }
+
+    /// Store a longitude if `name` is one of [`PLANETS`]
+    pub fn apply_planet(&mut self, name: &str, longitude: f64) {
+        if let Some(slot) = PLANETS.iter().position(|p| p.eq_ignore_ascii_case(name)) {
+            self.planets[slot] = longitude.rem_euclid(360.0) as f32;
+        }
+    }
+
+    /// Store a numeric value; each source name keeps the slot it was first
+    /// given (or was pinned to), and values from sources beyond the last slot
+    /// are dropped
+    pub fn apply_mod(&mut self, source: &str, value: f32) -> Option<usize> {
+        let slot = match self.mod_slots.get(source) {
+            Some(&slot) => slot,
+            None if self.mod_slots.len() < MOD_COUNT => {
+                let slot = (0..MOD_COUNT).find(|s| !self.mod_slots.values().any(|v| v == s))?;
+                self.mod_slots.insert(source.to_string(), slot);
+                slot
+            }
+            None => return None,
+        };
+        self.mods[slot] = value;
+        Some(slot)
+    }
+
+    /// Pin numeric sources to slots in order (`u.mods` index = position)
+    pub fn set_mod_sources(&mut self, sources: &[String]) {
+        self.mod_slots = sources
+            .iter()
+            .take(MOD_COUNT)
+            .enumerate()
+            .map(|(slot, source)| (source.clone(), slot))
+            .collect();
+        self.mods = [0.0; MOD_COUNT];
+    }
+
+    /// Slot assignments ordered by slot
+    pub fn mod_sources(&self) -> Vec<(usize, String)> {
+        let mut slots: Vec<(usize, String)> = self
+            .mod_slots
+            .iter()
+            .map(|(source, &slot)| (slot, source.clone()))
+            .collect();
+        slots.sort();
+        slots
+    }
+
+    pub fn uniforms(&self, width: u32, height: u32, seconds: f32, frame: u64) -> ShaderUniforms {
+        ShaderUniforms {
+            frame: [width as f32, height as f32, seconds, frame as f32],
+            audio: [self.rms, self.peak, self.raw_rms, 0.0],
+            bands: pack(&self.bands),
+            planets: pack(&self.planets),
+            mods: pack(&self.mods),
+        }
+    }
+}
+
+/// [`UniformInputs`] shared between the sink and the tile
+#[derive(Debug, Default)]
+pub struct CanvasInputs {
+    inner: Mutex<UniformInputs>,
+}
+
+impl CanvasInputs {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    pub fn update<R>(&self, f: impl FnOnce(&mut UniformInputs) -> R) -> Option<R> {
+        self.inner.lock().ok().map(|mut inputs| f(&mut inputs))
+    }
+
+    pub fn snapshot(&self) -> UniformInputs {
+        self.inner
+            .lock()
+            .map(|inputs| inputs.clone())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uniforms_pack_inputs_in_declaration_order() {
+        let mut inputs = UniformInputs::default();
+        inputs.apply_planet("Moon", 370.0);
+        inputs.set_mod_sources(&["weather_temperature".to_string()]);
+        assert_eq!(inputs.apply_mod("sysmon_cpu", 0.5), Some(1));
+        assert_eq!(inputs.apply_mod("weather_temperature", 21.0), Some(0));
+
+        let uniforms = inputs.uniforms(640, 480, 2.5, 7);
+        assert_eq!(ShaderUniforms::SIZE, 4 * (4 + 4 + 8 + 12 + 8));
+        let bytes = uniforms.to_bytes();
+        assert_eq!(bytes.len(), ShaderUniforms::SIZE);
+        let floats: Vec<f32> = bytes
+            .chunks_exact(4)
+            .map(|b| f32::from_ne_bytes([b[0], b[1], b[2], b[3]]))
+            .collect();
+        assert_eq!(&floats[..4], &[640.0, 480.0, 2.5, 7.0]);
+        // planets start after frame, audio and two band vectors
+        assert_eq!(&floats[16..18], &[-1.0, 10.0]);
+        assert_eq!(&floats[28..30], &[21.0, 0.5]);
+    }
+
+    #[test]
+    fn levels_release_and_mod_slots_fill_up() {
+        let mut inputs = UniformInputs::default();
+        inputs.apply_audio(0.8, 1.0, &[0.5; BAND_COUNT]);
+        inputs.apply_audio(0.0, 0.0, &[0.0; BAND_COUNT]);
+        assert!((inputs.rms - 0.8 * RELEASE).abs() < 1e-6);
+        assert_eq!(inputs.raw_rms, 0.0);
+        assert!(inputs.bands.iter().all(|b| *b > 0.0 && *b < 0.5));
+
+        for i in 0..MOD_COUNT {
+            assert_eq!(inputs.apply_mod(&format!("m{i}"), 1.0), Some(i));
+        }
+        assert_eq!(inputs.apply_mod("overflow", 1.0), None);
+        assert_eq!(inputs.mod_sources()[2], (2, "m2".to_string()));
+    }
+}
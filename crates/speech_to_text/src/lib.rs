@@ -7,6 +7,7 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
+use std::path::PathBuf;
 use std::time::Duration;
 
 #[cfg(feature = "magnolia")]
@@ -15,7 +16,7 @@ mod processor;
 mod sherpa;
 
 #[cfg(feature = "magnolia")]
-pub use processor::{SttMetrics, SttMetricsSnapshot, SttProcessor};
+pub use processor::{BackendFactory, SttMetrics, SttMetricsSnapshot, SttProcessor};
 #[cfg(feature = "sherpa")]
 pub use sherpa::{LocalSherpaBackend, SherpaConfig};
 
@@ -53,6 +54,13 @@ pub enum SttEvent {
         text: String,
         audio_end_ms: u64,
         sequence: u64,
+        /// Language code of the recognizer, filled in by the processor
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        language: Option<String>,
+        /// Bytes of `text` the caption stabilizer has settled; the rest is
+        /// a tail that may still change
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        stable_len: Option<usize>,
     },
     Final {
         session_id: String,
@@ -61,6 +69,8 @@ pub enum SttEvent {
         start_ms: u64,
         end_ms: u64,
         sequence: u64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        language: Option<String>,
     },
     Status {
         status: SttStatus,
@@ -76,6 +86,31 @@ impl SttEvent {
     pub fn is_replaceable(&self) -> bool {
         matches!(self, Self::Partial { .. })
     }
+
+    /// Tag a transcript event with the language it was recognized in
+    pub fn set_language(&mut self, code: &str) {
+        if let Self::Partial { language, .. } | Self::Final { language, .. } = self {
+            *language = Some(code.to_string());
+        }
+    }
+
+    pub fn language(&self) -> Option<&str> {
+        match self {
+            Self::Partial { language, .. } | Self::Final { language, .. } => language.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// Model and vocabulary for one transcription language
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SttLanguage {
+    /// Directory holding the language's model files; the backend's own
+    /// model when unset
+    pub model_dir: Option<PathBuf>,
+    /// Word list biasing recognition towards the language's vocabulary
+    pub vocab: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -201,6 +236,8 @@ mod tests {
             text: "hello".into(),
             audio_end_ms: 200,
             sequence: 1,
+            language: None,
+            stable_len: None,
         });
         let mut events = Vec::new();
         backend.poll_events(&mut events).unwrap();
@@ -215,6 +252,8 @@ mod tests {
             text: format!("p{sequence}"),
             audio_end_ms: sequence,
             sequence,
+            language: None,
+            stable_len: None,
         }
     }
 
@@ -226,6 +265,7 @@ mod tests {
             start_ms: 0,
             end_ms: sequence,
             sequence,
+            language: None,
         }
     }
 
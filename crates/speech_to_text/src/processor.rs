@@ -1,6 +1,9 @@
-use super::{AudioChunk, SttBackend, SttEvent, SttEventQueue, SttQueueError};
+use super::{AudioChunk, SttBackend, SttEvent, SttEventQueue, SttLanguage, SttQueueError};
 use async_trait::async_trait;
-use magnolia_core::{DataType, ModuleSchema, Port, PortDirection, Processor, Signal};
+use magnolia_core::{
+    AudioView, ControlSignal, DataType, ModuleSchema, Port, PortDirection, Processor, Signal,
+};
+use std::collections::BTreeMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
@@ -34,16 +37,32 @@ impl SttMetrics {
     }
 }
 
+/// Builds the backend for a language code and its configured model files
+pub type BackendFactory =
+    Box<dyn Fn(&str, &SttLanguage) -> anyhow::Result<Box<dyn SttBackend>> + Send + Sync>;
+
 /// Magnolia adapter for a streaming STT backend.
 ///
 /// The processor receives ordinary routed audio buffers, performs the cheap
 /// downmix/resample step on its worker, and emits one serialized STT event at
 /// a time. Model inference never runs in the audio capture callback.
+///
+/// `silence_start` / `silence_end` intents (from the silence detector) close
+/// the current utterance and pause transcription until the sound returns.
+///
+/// Transcripts are tagged with the current language. A `set_language`
+/// intent (or the `language` setting) closes the utterance and swaps in a
+/// backend from the factory, using the model and vocabulary listed for that
+/// language under the `languages` setting.
 pub struct SttProcessor {
     id: String,
     enabled: bool,
     backend: Box<dyn SttBackend>,
+    factory: Option<BackendFactory>,
+    language: String,
+    languages: BTreeMap<String, SttLanguage>,
     started: bool,
+    paused: bool,
     events: SttEventQueue,
     metrics: Arc<SttMetrics>,
 }
@@ -54,22 +73,151 @@ impl SttProcessor {
             id: id.to_string(),
             enabled: true,
             backend,
+            factory: None,
+            language: "en".to_string(),
+            languages: BTreeMap::new(),
             started: false,
+            paused: false,
             events: SttEventQueue::new(64),
             metrics: Arc::new(SttMetrics::default()),
         }
     }
 
+    /// Language the starting backend recognizes
+    pub fn with_language(mut self, code: &str) -> Self {
+        self.language = code.to_string();
+        self
+    }
+
+    /// Allow switching languages, building each new backend with `factory`
+    pub fn with_backend_factory(mut self, factory: BackendFactory) -> Self {
+        self.factory = Some(factory);
+        self
+    }
+
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
     pub fn metrics(&self) -> Arc<SttMetrics> {
         self.metrics.clone()
     }
 
+    /// Finish the current utterance on the old backend and continue in
+    /// `code`, returning the old backend's last event
+    fn switch_language(&mut self, code: &str) -> anyhow::Result<Option<Signal>> {
+        let code = code.trim();
+        if code.is_empty() || code == self.language {
+            return Ok(None);
+        }
+        let Some(factory) = &self.factory else {
+            anyhow::bail!("no backend available for language {code}");
+        };
+        let language = self.languages.get(code).cloned().unwrap_or_default();
+        let backend = match factory(code, &language) {
+            Ok(backend) => backend,
+            Err(error) => {
+                self.metrics.backend_errors.fetch_add(1, Ordering::Relaxed);
+                return Err(error.context(format!("switching to language {code}")));
+            }
+        };
+
+        let mut signal = None;
+        if self.started {
+            // The old backend is going away either way; a failure here
+            // only loses its unfinished utterance (and is counted)
+            if self.backend.finish_utterance().is_ok() {
+                signal = self.poll_backend().ok().flatten();
+            } else {
+                self.metrics.backend_errors.fetch_add(1, Ordering::Relaxed);
+            }
+            self.backend.shutdown();
+        }
+        self.backend = backend;
+        self.language = code.to_string();
+        // The new backend starts with the next audio buffer
+        self.started = false;
+        Ok(signal)
+    }
+
+    fn apply_settings(&mut self, settings: &serde_json::Value) -> anyhow::Result<Option<Signal>> {
+        if let Some(languages) = settings.get("languages") {
+            self.languages = serde_json::from_value(languages.clone())?;
+        }
+        match settings.get("language").and_then(|v| v.as_str()) {
+            Some(code) => self.switch_language(code),
+            None => Ok(None),
+        }
+    }
+
     fn event_signal(event: SttEvent) -> anyhow::Result<Signal> {
         Ok(Signal::Computed {
             source: "speech_to_text".to_string(),
             content: serde_json::to_string(&event)?,
         })
     }
+
+    /// Pause on silence, closing the utterance so its final text comes out
+    fn handle_intent(
+        &mut self,
+        action: &str,
+        parameters: &[String],
+    ) -> anyhow::Result<Option<Signal>> {
+        match action {
+            "set_language" => match parameters.first() {
+                Some(code) => self.switch_language(code),
+                None => Ok(None),
+            },
+            "silence_start" if !self.paused => {
+                self.paused = true;
+                if !self.started {
+                    return Ok(None);
+                }
+                if let Err(error) = self.backend.finish_utterance() {
+                    self.metrics.backend_errors.fetch_add(1, Ordering::Relaxed);
+                    return Err(error);
+                }
+                self.poll_backend()
+            }
+            "silence_end" => {
+                self.paused = false;
+                Ok(None)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn poll_backend(&mut self) -> anyhow::Result<Option<Signal>> {
+        let mut polled = Vec::new();
+        if let Err(error) = self.backend.poll_events(&mut polled) {
+            self.metrics.backend_errors.fetch_add(1, Ordering::Relaxed);
+            return Err(error);
+        }
+        for mut event in polled {
+            event.set_language(&self.language);
+            let result = self.events.push(event);
+            if let Err(error) = result {
+                self.metrics.queue_overflows.fetch_add(1, Ordering::Relaxed);
+                return Err(match error {
+                    SttQueueError::FullLossSensitive => {
+                        anyhow::anyhow!("STT event queue full of loss-sensitive events")
+                    }
+                });
+            }
+        }
+        self.metrics
+            .dropped_partials
+            .store(self.events.dropped_partials(), Ordering::Relaxed);
+        let mut events = Vec::new();
+        self.events.drain_into(&mut events);
+        // Keep the newest event. The backend emits partials frequently, and
+        // the router/display treats them as replaceable state.
+        let signal = events.pop().map(Self::event_signal).transpose()?;
+        if signal.is_some() {
+            self.metrics.emitted_events.fetch_add(1, Ordering::Relaxed);
+        }
+        Ok(signal)
+    }
 }
 
 #[async_trait]
@@ -91,6 +239,18 @@ impl Processor for SttProcessor {
                     data_type: DataType::Audio,
                     direction: PortDirection::Input,
                 },
+                Port {
+                    id: "silence_in".to_string(),
+                    label: "Silence".to_string(),
+                    data_type: DataType::Control,
+                    direction: PortDirection::Input,
+                },
+                Port {
+                    id: "language_in".to_string(),
+                    label: "Language".to_string(),
+                    data_type: DataType::Control,
+                    direction: PortDirection::Input,
+                },
                 Port {
                     id: "text_out".to_string(),
                     label: "Text Events".to_string(),
@@ -98,7 +258,23 @@ impl Processor for SttProcessor {
                     direction: PortDirection::Output,
                 },
             ],
-            settings_schema: None,
+            settings_schema: Some(serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "language": { "type": "string", "default": self.language },
+                    "languages": {
+                        "type": "object",
+                        "additionalProperties": {
+                            "type": "object",
+                            "properties": {
+                                "model_dir": { "type": "string" },
+                                "vocab": { "type": "string" }
+                            }
+                        }
+                    }
+                }
+            })),
+            docs: None,
         }
     }
 
@@ -110,16 +286,26 @@ impl Processor for SttProcessor {
     }
 
     async fn process(&mut self, signal: Signal) -> anyhow::Result<Option<Signal>> {
-        let Signal::Audio {
+        match &signal {
+            Signal::Intent { action, parameters } => return self.handle_intent(action, parameters),
+            Signal::Control(ControlSignal::Settings(settings)) => {
+                return self.apply_settings(settings)
+            }
+            _ => {}
+        }
+        let Some(AudioView {
             sample_rate,
             channels,
             timestamp_us,
             data,
-        } = signal
+        }) = signal.audio()
         else {
             return Ok(None);
         };
         self.metrics.audio_chunks.fetch_add(1, Ordering::Relaxed);
+        if self.paused {
+            return Ok(None);
+        }
         if !self.started {
             if let Err(error) = self.backend.start(&self.id) {
                 self.metrics.backend_errors.fetch_add(1, Ordering::Relaxed);
@@ -127,39 +313,12 @@ impl Processor for SttProcessor {
             }
             self.started = true;
         }
-        let audio = normalize_audio(sample_rate, channels, &data, timestamp_us)?;
+        let audio = normalize_audio(sample_rate, channels, data, timestamp_us)?;
         if let Err(error) = self.backend.push_audio(audio) {
             self.metrics.backend_errors.fetch_add(1, Ordering::Relaxed);
             return Err(error);
         }
-        let mut polled = Vec::new();
-        if let Err(error) = self.backend.poll_events(&mut polled) {
-            self.metrics.backend_errors.fetch_add(1, Ordering::Relaxed);
-            return Err(error);
-        }
-        for event in polled {
-            let result = self.events.push(event);
-            if let Err(error) = result {
-                self.metrics.queue_overflows.fetch_add(1, Ordering::Relaxed);
-                return Err(match error {
-                    SttQueueError::FullLossSensitive => {
-                        anyhow::anyhow!("STT event queue full of loss-sensitive events")
-                    }
-                });
-            }
-        }
-        self.metrics
-            .dropped_partials
-            .store(self.events.dropped_partials(), Ordering::Relaxed);
-        let mut events = Vec::new();
-        self.events.drain_into(&mut events);
-        // Keep the newest event. The backend emits partials frequently, and
-        // the router/display treats them as replaceable state.
-        let signal = events.pop().map(Self::event_signal).transpose()?;
-        if signal.is_some() {
-            self.metrics.emitted_events.fetch_add(1, Ordering::Relaxed);
-        }
-        Ok(signal)
+        self.poll_backend()
     }
 }
 
@@ -201,7 +360,168 @@ fn normalize_audio(
 
 #[cfg(test)]
 mod tests {
-    use super::normalize_audio;
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Counts pushed buffers and finished utterances
+    #[derive(Default)]
+    struct CountingBackend {
+        counts: Arc<Mutex<(usize, usize)>>,
+    }
+
+    impl SttBackend for CountingBackend {
+        fn start(&mut self, _session_id: &str) -> anyhow::Result<()> {
+            Ok(())
+        }
+        fn push_audio(&mut self, _audio: AudioChunk) -> anyhow::Result<()> {
+            self.counts.lock().unwrap().0 += 1;
+            Ok(())
+        }
+        fn finish_utterance(&mut self) -> anyhow::Result<()> {
+            self.counts.lock().unwrap().1 += 1;
+            Ok(())
+        }
+        fn reset(&mut self) -> anyhow::Result<()> {
+            Ok(())
+        }
+        fn poll_events(&mut self, _output: &mut Vec<SttEvent>) -> anyhow::Result<()> {
+            Ok(())
+        }
+        fn shutdown(&mut self) {}
+    }
+
+    fn silence(action: &str) -> Signal {
+        Signal::Intent {
+            action: action.to_string(),
+            parameters: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn silence_closes_the_utterance_and_pauses() {
+        let backend = CountingBackend::default();
+        let counts = backend.counts.clone();
+        let mut processor = SttProcessor::new("stt", Box::new(backend));
+        let audio = || Signal::Audio {
+            sample_rate: 16_000,
+            channels: 1,
+            timestamp_us: 0,
+            data: vec![0.1; 160],
+        };
+
+        processor.process(audio()).await.unwrap();
+        processor.process(silence("silence_start")).await.unwrap();
+        processor.process(audio()).await.unwrap();
+        assert_eq!(*counts.lock().unwrap(), (1, 1));
+
+        processor.process(silence("silence_end")).await.unwrap();
+        processor.process(audio()).await.unwrap();
+        assert_eq!(*counts.lock().unwrap(), (2, 1));
+        assert_eq!(processor.metrics().snapshot().audio_chunks, 3);
+    }
+
+    /// Emits a partial for every pushed buffer
+    #[derive(Default)]
+    struct PartialBackend {
+        pending: Vec<SttEvent>,
+    }
+
+    impl SttBackend for PartialBackend {
+        fn start(&mut self, _session_id: &str) -> anyhow::Result<()> {
+            Ok(())
+        }
+        fn push_audio(&mut self, _audio: AudioChunk) -> anyhow::Result<()> {
+            self.pending.push(SttEvent::Partial {
+                session_id: "s".into(),
+                segment_id: 0,
+                text: "hallo".into(),
+                audio_end_ms: 10,
+                sequence: 1,
+                language: None,
+                stable_len: None,
+            });
+            Ok(())
+        }
+        fn finish_utterance(&mut self) -> anyhow::Result<()> {
+            Ok(())
+        }
+        fn reset(&mut self) -> anyhow::Result<()> {
+            Ok(())
+        }
+        fn poll_events(&mut self, output: &mut Vec<SttEvent>) -> anyhow::Result<()> {
+            output.append(&mut self.pending);
+            Ok(())
+        }
+        fn shutdown(&mut self) {}
+    }
+
+    #[tokio::test]
+    async fn switching_language_swaps_the_backend_and_tags_transcripts() {
+        let backend = CountingBackend::default();
+        let counts = backend.counts.clone();
+        let requested = Arc::new(Mutex::new(Vec::new()));
+        let factory_requested = requested.clone();
+        let mut processor = SttProcessor::new("stt", Box::new(backend))
+            .with_language("en")
+            .with_backend_factory(Box::new(move |code, language| {
+                factory_requested
+                    .lock()
+                    .unwrap()
+                    .push((code.to_string(), language.vocab.clone()));
+                Ok(Box::new(PartialBackend::default()) as Box<dyn SttBackend>)
+            }));
+        let audio = || Signal::Audio {
+            sample_rate: 16_000,
+            channels: 1,
+            timestamp_us: 0,
+            data: vec![0.1; 160],
+        };
+
+        processor
+            .process(Signal::Control(ControlSignal::Settings(
+                serde_json::json!({
+                    "languages": { "de": { "vocab": "vocab/de.txt" } }
+                }),
+            )))
+            .await
+            .unwrap();
+        processor.process(audio()).await.unwrap();
+        processor
+            .process(Signal::Intent {
+                action: "set_language".to_string(),
+                parameters: vec!["de".to_string()],
+            })
+            .await
+            .unwrap();
+        assert_eq!(processor.language(), "de");
+        // The English utterance was closed before the swap
+        assert_eq!(*counts.lock().unwrap(), (1, 1));
+        assert_eq!(
+            *requested.lock().unwrap(),
+            vec![("de".to_string(), Some("vocab/de.txt".into()))]
+        );
+
+        let Some(Signal::Computed { content, .. }) = processor.process(audio()).await.unwrap()
+        else {
+            panic!("expected a transcript event");
+        };
+        let event: SttEvent = serde_json::from_str(&content).unwrap();
+        assert_eq!(event.language(), Some("de"));
+    }
+
+    #[tokio::test]
+    async fn switching_without_a_factory_keeps_the_language() {
+        let mut processor =
+            SttProcessor::new("stt", Box::new(CountingBackend::default())).with_language("en");
+        let result = processor
+            .process(Signal::Intent {
+                action: "set_language".to_string(),
+                parameters: vec!["fr".to_string()],
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(processor.language(), "en");
+    }
 
     #[test]
     fn normalize_audio_downmixes_and_resamples() {
@@ -13,6 +13,8 @@ pub struct SherpaConfig {
     pub tokens: PathBuf,
     pub num_threads: i32,
     pub endpointing: bool,
+    /// Hotwords file biasing the decoder towards a vocabulary
+    pub hotwords: Option<PathBuf>,
 }
 
 pub struct LocalSherpaBackend {
@@ -56,6 +58,8 @@ impl LocalSherpaBackend {
             text: result.text,
             audio_end_ms: audio_end.as_millis() as u64,
             sequence: self.sequence,
+            language: None,
+            stable_len: None,
         });
     }
 }
@@ -70,6 +74,12 @@ impl SttBackend for LocalSherpaBackend {
         config.model_config.num_threads = self.config.num_threads;
         config.enable_endpoint = self.config.endpointing;
         config.decoding_method = Some("greedy_search".into());
+        if let Some(hotwords) = &self.config.hotwords {
+            // Hotwords only apply to beam search
+            config.decoding_method = Some("modified_beam_search".into());
+            config.hotwords_file = Some(hotwords.display().to_string());
+            config.hotwords_score = 1.5;
+        }
         let recognizer = OnlineRecognizer::create(&config)
             .ok_or_else(|| anyhow::anyhow!("failed to create Sherpa recognizer"))?;
         self.stream = Some(recognizer.create_stream());
@@ -124,6 +134,7 @@ impl SttBackend for LocalSherpaBackend {
             start_ms: self.segment_start.as_millis() as u64,
             end_ms: self.segment_end.as_millis() as u64,
             sequence: self.sequence,
+            language: None,
         });
         self.segment_id += 1;
         self.stream = Some(recognizer.create_stream());
@@ -0,0 +1,14 @@
+//! Switchboard: fans one intent stream out to several modules.
+//!
+//! Each route pairs a pattern with an output port, so a single voice-command
+//! source can drive a recorder from `record *` and kamea from `sigil *`
+//! without a script module in between. Patterns are matched against the
+//! intent action and its parameters joined by spaces.
+
+mod pattern;
+mod processor;
+
+pub use pattern::Pattern;
+pub use processor::{
+    command_text, RouteConfig, SwitchboardConfig, SwitchboardProcessor, MAX_ROUTES, UNMATCHED_PORT,
+};
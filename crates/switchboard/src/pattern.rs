@@ -0,0 +1,92 @@
+//! Route patterns: case-insensitive globs, or regexes for anything fancier.
+
+use regex::{Regex, RegexBuilder};
+
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    /// `*` matches any run of characters, `?` exactly one
+    Glob(Vec<char>),
+    Regex(Regex),
+}
+
+impl Pattern {
+    pub fn new(pattern: &str, regex: bool) -> Result<Self, regex::Error> {
+        if regex {
+            RegexBuilder::new(pattern)
+                .case_insensitive(true)
+                .build()
+                .map(Pattern::Regex)
+        } else {
+            Ok(Pattern::Glob(
+                pattern.trim().to_lowercase().chars().collect(),
+            ))
+        }
+    }
+
+    /// Globs must match the whole command, though a trailing ` *` also
+    /// matches the bare word (`record *` matches `record`); regexes match
+    /// anywhere unless anchored
+    pub fn matches(&self, command: &str) -> bool {
+        match self {
+            Pattern::Glob(glob) => {
+                let command: Vec<char> = command.trim().to_lowercase().chars().collect();
+                glob_matches(glob, &command)
+                    || glob
+                        .strip_suffix(&[' ', '*'])
+                        .is_some_and(|word| glob_matches(word, &command))
+            }
+            Pattern::Regex(regex) => regex.is_match(command),
+        }
+    }
+}
+
+/// Iterative wildcard match, backtracking only to the last `*`
+fn glob_matches(glob: &[char], text: &[char]) -> bool {
+    let (mut g, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match glob.get(g) {
+            Some('*') => {
+                star = Some((g, t));
+                g += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                g += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_g, star_t)) => {
+                    g = star_g + 1;
+                    t = star_t + 1;
+                    star = Some((star_g, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    glob[g..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn globs_match_whole_commands_case_insensitively() {
+        let record = Pattern::new("record *", false).unwrap();
+        assert!(record.matches("Record start"));
+        assert!(record.matches("record "));
+        assert!(record.matches("record"));
+        assert!(!record.matches("recording start"));
+        assert!(!record.matches("stop record now"));
+
+        let any = Pattern::new("*sigil*", false).unwrap();
+        assert!(any.matches("draw a sigil for me"));
+        assert!(Pattern::new("mute ?", false).unwrap().matches("mute 2"));
+
+        let regex = Pattern::new(r"^(play|pause)\b", true).unwrap();
+        assert!(regex.matches("PLAY next"));
+        assert!(!regex.matches("display"));
+        assert!(Pattern::new("(", true).is_err());
+    }
+}
@@ -0,0 +1,266 @@
+use async_trait::async_trait;
+use magnolia_core::{
+    ControlSignal, DataType, ModuleSchema, Port, PortDirection, Processor, Signal,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::pattern::Pattern;
+
+/// Output ports available to routes (`route_1` ..)
+pub const MAX_ROUTES: usize = 8;
+pub const UNMATCHED_PORT: &str = "unmatched";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RouteConfig {
+    /// Glob (`record *`) or, with `regex`, a regular expression
+    pub pattern: String,
+    pub regex: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SwitchboardConfig {
+    /// Checked in order; route `n` leaves from port `route_{n+1}`
+    pub routes: Vec<RouteConfig>,
+}
+
+impl SwitchboardConfig {
+    pub fn schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "routes": {
+                    "type": "array",
+                    "title": "Routes",
+                    "maxItems": MAX_ROUTES,
+                    "default": [],
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "pattern": { "type": "string", "title": "Pattern", "default": "" },
+                            "regex": { "type": "boolean", "title": "Regex", "default": false }
+                        }
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// Text a route pattern is matched against: the intent action followed by
+/// its parameters, space separated (`record` + `["start"]` → `record start`)
+pub fn command_text(signal: &Signal) -> Option<String> {
+    match signal {
+        Signal::Intent { action, parameters } => Some(
+            std::iter::once(action.as_str())
+                .chain(parameters.iter().map(String::as_str))
+                .collect::<Vec<_>>()
+                .join(" "),
+        ),
+        Signal::Text(text) => Some(text.clone()),
+        _ => None,
+    }
+}
+
+/// Routes each incoming intent to the output port of the first route whose
+/// pattern matches, or to `unmatched`. Signals pass through unchanged.
+pub struct SwitchboardProcessor {
+    id: String,
+    enabled: bool,
+    config: SwitchboardConfig,
+    /// Compiled `config.routes`; `None` where the regex didn't compile
+    patterns: Vec<Option<Pattern>>,
+    /// Port for the signal `process` last returned
+    last_port: String,
+}
+
+impl SwitchboardProcessor {
+    pub fn new(id: &str, config: SwitchboardConfig) -> Self {
+        let mut processor = Self {
+            id: id.to_string(),
+            enabled: true,
+            config: SwitchboardConfig::default(),
+            patterns: Vec::new(),
+            last_port: UNMATCHED_PORT.to_string(),
+        };
+        processor.set_config(config);
+        processor
+    }
+
+    pub fn config(&self) -> &SwitchboardConfig {
+        &self.config
+    }
+
+    pub fn set_config(&mut self, mut config: SwitchboardConfig) {
+        if config.routes.len() > MAX_ROUTES {
+            log::warn!(
+                "Switchboard {}: only the first {} routes are used",
+                self.id,
+                MAX_ROUTES
+            );
+            config.routes.truncate(MAX_ROUTES);
+        }
+        self.patterns = config
+            .routes
+            .iter()
+            .map(|route| {
+                if route.pattern.trim().is_empty() {
+                    return None;
+                }
+                Pattern::new(&route.pattern, route.regex)
+                    .map_err(|e| {
+                        log::warn!(
+                            "Switchboard {}: invalid pattern {:?}: {}",
+                            self.id,
+                            route.pattern,
+                            e
+                        )
+                    })
+                    .ok()
+            })
+            .collect();
+        self.config = config;
+    }
+
+    /// Output port for `command`
+    pub fn route(&self, command: &str) -> String {
+        self.patterns
+            .iter()
+            .position(|p| p.as_ref().is_some_and(|p| p.matches(command)))
+            .map(|index| format!("route_{}", index + 1))
+            .unwrap_or_else(|| UNMATCHED_PORT.to_string())
+    }
+}
+
+#[async_trait]
+impl Processor for SwitchboardProcessor {
+    fn name(&self) -> &str {
+        "Switchboard"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        let mut ports = vec![Port {
+            id: "intent_in".to_string(),
+            label: "Intents In".to_string(),
+            data_type: DataType::Control,
+            direction: PortDirection::Input,
+        }];
+        ports.extend((0..MAX_ROUTES).map(|index| {
+            let label = match self.config.routes.get(index) {
+                Some(route) if !route.pattern.is_empty() => route.pattern.clone(),
+                _ => format!("Route {}", index + 1),
+            };
+            Port {
+                id: format!("route_{}", index + 1),
+                label,
+                data_type: DataType::Control,
+                direction: PortDirection::Output,
+            }
+        }));
+        ports.push(Port {
+            id: UNMATCHED_PORT.to_string(),
+            label: "Unmatched".to_string(),
+            data_type: DataType::Control,
+            direction: PortDirection::Output,
+        });
+
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "Switchboard".to_string(),
+            description: "Routes intents to outputs by action pattern".to_string(),
+            ports,
+            settings_schema: Some(SwitchboardConfig::schema()),
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    async fn process(&mut self, signal: Signal) -> anyhow::Result<Option<Signal>> {
+        if let Signal::Control(ControlSignal::Settings(settings)) = &signal {
+            match serde_json::from_value::<SwitchboardConfig>(settings.clone()) {
+                Ok(config) => self.set_config(config),
+                Err(e) => log::warn!("Switchboard: invalid settings, keeping current: {}", e),
+            }
+            return Ok(None);
+        }
+        let Some(command) = command_text(&signal) else {
+            return Ok(None);
+        };
+        self.last_port = self.route(&command);
+        Ok(Some(signal))
+    }
+
+    fn output_port(&self, _signal: &Signal) -> Option<&str> {
+        Some(&self.last_port)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn intent(action: &str, parameters: &[&str]) -> Signal {
+        Signal::Intent {
+            action: action.to_string(),
+            parameters: parameters.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn routes_intents_to_the_first_matching_port() {
+        let route = |pattern: &str, regex: bool| RouteConfig {
+            pattern: pattern.to_string(),
+            regex,
+        };
+        let mut switchboard = SwitchboardProcessor::new(
+            "switchboard",
+            SwitchboardConfig {
+                routes: vec![
+                    route("record *", false),
+                    route("(", true),
+                    route("sigil *", false),
+                    route("*", false),
+                ],
+            },
+        );
+        let schema = switchboard.schema();
+        assert_eq!(schema.ports.len(), 1 + MAX_ROUTES + 1);
+        assert_eq!(schema.ports[1].label, "record *");
+
+        let out = switchboard
+            .process(intent("record", &["start"]))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(&out, Signal::Intent { action, .. } if action == "record"));
+        assert_eq!(switchboard.output_port(&out), Some("route_1"));
+
+        switchboard
+            .process(intent("sigil", &["love", "and", "light"]))
+            .await
+            .unwrap();
+        assert_eq!(switchboard.output_port(&Signal::Pulse), Some("route_3"));
+
+        switchboard.process(intent("stop", &[])).await.unwrap();
+        assert_eq!(switchboard.output_port(&Signal::Pulse), Some("route_4"));
+
+        // Settings replace the routes; nothing is emitted for them
+        let settings = serde_json::json!({ "routes": [{ "pattern": "^sig", "regex": true }] });
+        assert!(switchboard
+            .process(Signal::Control(ControlSignal::Settings(settings)))
+            .await
+            .unwrap()
+            .is_none());
+        assert_eq!(switchboard.route("Sigil now"), "route_1");
+        assert_eq!(switchboard.route("record start"), UNMATCHED_PORT);
+        assert!(switchboard.process(Signal::Pulse).await.unwrap().is_none());
+    }
+}
@@ -4,6 +4,8 @@
 
 mod save_file;
 mod sinks;
+pub mod ticker;
 
 pub use save_file::{OutputFormat, SaveFileSink};
 pub use sinks::{DevowelizerSink, WordCountSink};
+pub use ticker::{SharedTicker, TickerConfig, TickerFont, TickerSink, TickerState};
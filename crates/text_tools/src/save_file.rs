@@ -1,8 +1,13 @@
 use async_trait::async_trait;
-use magnolia_core::{DataType, ModuleSchema, Port, PortDirection, Result, Signal, Sink};
+use magnolia_core::session::marker_label;
+use magnolia_core::{
+    AudioView, BlobView, ControlSignal, DataType, ModuleSchema, Port, PortDirection, Result,
+    SessionState, Signal, SilenceConfig, SilenceDetector, SilenceEvent, Sink,
+};
 use std::fs::File;
-use std::io::Write;
-use std::path::PathBuf;
+use std::io::{Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 
 /// Output format for SaveFileSink
@@ -18,7 +23,12 @@ pub enum OutputFormat {
 /// A sink that saves incoming signals to files.
 /// - Text signals are saved as .txt files
 /// - Blob signals (images) are saved as .png or .bmp files
-/// - Audio signals are saved as .wav files
+/// - Audio signals are saved as .wav files, optionally one per stretch of
+///   sound with the silences between them left out, and `marker` intents
+///   arriving during a recording kept as cue points in it
+///
+/// With a session attached, relative paths land in the running session's
+/// folder and a recording is closed when a session starts or ends.
 pub struct SaveFileSink {
     enabled: bool,
     output_path: Arc<Mutex<PathBuf>>,
@@ -26,6 +36,80 @@ pub struct SaveFileSink {
     last_saved: Arc<Mutex<Option<String>>>,
     // Persistent writer to avoid re-opening/overwriting WAV headers for every chunk
     audio_writer: Arc<Mutex<Option<hound::WavWriter<std::io::BufWriter<File>>>>>,
+    // Set when recordings split at silence
+    silence: Arc<Mutex<Option<SilenceDetector>>>,
+    // WAV file the next recording opens, numbered from the output path
+    segment: Arc<Mutex<usize>>,
+    // File the open recording writes to
+    recording_path: Arc<Mutex<Option<PathBuf>>>,
+    session: Option<SessionState>,
+    // Session generation the open recording belongs to
+    session_generation: Arc<AtomicU64>,
+    // Frame and label of each marker in the open recording
+    cues: Arc<Mutex<Vec<(u32, String)>>>,
+}
+
+/// `path` for the first recording, `name_001.wav` and on for later ones
+fn segment_path(path: &Path, segment: usize) -> PathBuf {
+    if segment == 0 {
+        return path.to_path_buf();
+    }
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let name = match path.extension() {
+        Some(ext) => format!("{}_{:03}.{}", stem, segment, ext.to_string_lossy()),
+        None => format!("{}_{:03}", stem, segment),
+    };
+    path.with_file_name(name)
+}
+
+/// Append a `cue ` chunk and a `LIST`/`adtl` chunk labelling its points to a
+/// finished WAV file, for editors to show as markers
+fn append_cues(path: &Path, cues: &[(u32, String)]) -> std::io::Result<()> {
+    let mut cue = Vec::new();
+    cue.extend_from_slice(&(cues.len() as u32).to_le_bytes());
+    let mut labels = b"adtl".to_vec();
+    for (index, (frame, label)) in cues.iter().enumerate() {
+        let id = index as u32 + 1;
+        cue.extend_from_slice(&id.to_le_bytes());
+        cue.extend_from_slice(&frame.to_le_bytes());
+        cue.extend_from_slice(b"data");
+        cue.extend_from_slice(&0u32.to_le_bytes()); // chunk start
+        cue.extend_from_slice(&0u32.to_le_bytes()); // block start
+        cue.extend_from_slice(&frame.to_le_bytes());
+
+        let mut text = label.as_bytes().to_vec();
+        text.push(0);
+        labels.extend_from_slice(b"labl");
+        labels.extend_from_slice(&(4 + text.len() as u32).to_le_bytes());
+        labels.extend_from_slice(&id.to_le_bytes());
+        labels.extend_from_slice(&text);
+        if text.len() % 2 == 1 {
+            labels.push(0);
+        }
+    }
+
+    let mut file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(path)?;
+    let mut end = file.seek(SeekFrom::End(0))?;
+    // Chunks start on even offsets
+    if end % 2 == 1 {
+        file.write_all(&[0])?;
+        end += 1;
+    }
+    for (id, body) in [(b"cue ", &cue), (b"LIST", &labels)] {
+        file.write_all(id)?;
+        file.write_all(&(body.len() as u32).to_le_bytes())?;
+        file.write_all(body)?;
+        end += 8 + body.len() as u64;
+    }
+    file.seek(SeekFrom::Start(4))?;
+    file.write_all(&((end - 8) as u32).to_le_bytes())?;
+    Ok(())
 }
 
 impl SaveFileSink {
@@ -36,6 +120,29 @@ impl SaveFileSink {
             output_format: Arc::new(Mutex::new(OutputFormat::Text)),
             last_saved: Arc::new(Mutex::new(None)),
             audio_writer: Arc::new(Mutex::new(None)),
+            silence: Arc::new(Mutex::new(None)),
+            segment: Arc::new(Mutex::new(0)),
+            recording_path: Arc::new(Mutex::new(None)),
+            session: None,
+            session_generation: Arc::new(AtomicU64::new(0)),
+            cues: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Write relative paths into `session`'s folder while one runs
+    pub fn with_session(mut self, session: SessionState) -> Self {
+        self.session_generation
+            .store(session.generation(), Ordering::Release);
+        self.session = Some(session);
+        self
+    }
+
+    /// The output path, inside the running session's folder if relative
+    fn resolved_path(&self) -> PathBuf {
+        let path = self.output_path.lock().unwrap().clone();
+        match &self.session {
+            Some(session) => session.resolve(path),
+            None => path,
         }
     }
 
@@ -58,6 +165,51 @@ impl SaveFileSink {
     pub fn get_format(&self) -> OutputFormat {
         self.output_format.lock().unwrap().clone()
     }
+
+    /// Split WAV recordings at silence, or record continuously with `None`
+    pub fn set_split_on_silence(&self, config: Option<SilenceConfig>) {
+        let mut silence = self.silence.lock().unwrap();
+        match (silence.as_mut(), config) {
+            (Some(detector), Some(config)) => detector.set_config(config),
+            (_, config) => *silence = config.map(SilenceDetector::new),
+        }
+    }
+
+    fn apply_settings(&self, settings: &serde_json::Value) {
+        let current = self.silence.lock().unwrap().as_ref().map(|d| d.config());
+        let split = settings
+            .get("split_on_silence")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(current.is_some());
+        let config = split.then(|| {
+            let mut config = current.unwrap_or_default();
+            config.apply_settings(settings);
+            config
+        });
+        self.set_split_on_silence(config);
+    }
+
+    /// Close the current WAV file so its header is complete
+    fn finish_recording(&self) {
+        if let Some(writer) = self.audio_writer.lock().unwrap().take() {
+            let path = self.recording_path.lock().unwrap().take();
+            let cues = std::mem::take(&mut *self.cues.lock().unwrap());
+            match writer.finalize() {
+                Ok(()) => {
+                    *self.segment.lock().unwrap() += 1;
+                    if let Some(path) = path.as_ref().filter(|_| !cues.is_empty()) {
+                        if let Err(e) = append_cues(path, &cues) {
+                            log::error!("SaveFileSink: Failed to write markers: {}", e);
+                        }
+                    }
+                    let msg = format!("Saved recording to {:?}", path.unwrap_or_default());
+                    log::info!("SaveFileSink: {}", msg);
+                    *self.last_saved.lock().unwrap() = Some(msg);
+                }
+                Err(e) => log::error!("SaveFileSink: Failed to finalize WAV file: {}", e),
+            }
+        }
+    }
 }
 
 impl Default for SaveFileSink {
@@ -96,6 +248,12 @@ impl Sink for SaveFileSink {
                     data_type: DataType::Audio,
                     direction: PortDirection::Input,
                 },
+                Port {
+                    id: "control_in".to_string(),
+                    label: "Markers".to_string(),
+                    data_type: DataType::Control,
+                    direction: PortDirection::Input,
+                },
             ],
             settings_schema: Some(serde_json::json!({
                 "type": "object",
@@ -110,9 +268,28 @@ impl Sink for SaveFileSink {
                         "enum": ["text", "png", "bmp", "wav"],
                         "title": "Output Format",
                         "default": "text"
+                    },
+                    "split_on_silence": {
+                        "type": "boolean",
+                        "title": "Split WAV at Silence",
+                        "default": false
+                    },
+                    "silence_threshold_db": {
+                        "type": "number",
+                        "title": "Silence Threshold (dBFS)",
+                        "default": SilenceConfig::default().threshold_db,
+                        "minimum": -96.0,
+                        "maximum": 0.0
+                    },
+                    "silence_hold_ms": {
+                        "type": "number",
+                        "title": "Silence Hold (ms)",
+                        "default": SilenceConfig::default().hold_ms,
+                        "minimum": 0.0
                     }
                 }
             })),
+            docs: None,
         }
     }
 
@@ -132,8 +309,19 @@ impl Sink for SaveFileSink {
         if !self.enabled {
             return Ok(None);
         }
+        if let Signal::Control(ControlSignal::Settings(settings)) = &signal {
+            self.apply_settings(settings);
+            return Ok(None);
+        }
+        if let Some(label) = marker_label(&signal) {
+            match self.audio_writer.lock().unwrap().as_ref() {
+                Some(writer) => self.cues.lock().unwrap().push((writer.duration(), label)),
+                None => log::debug!("SaveFileSink: Not recording, marker {:?} dropped", label),
+            }
+            return Ok(None);
+        }
 
-        let path = self.output_path.lock().unwrap().clone();
+        let path = self.resolved_path();
         let format = self.output_format.lock().unwrap().clone();
 
         match signal {
@@ -155,40 +343,74 @@ impl Sink for SaveFileSink {
                     }
                 }
             }
-            Signal::Blob { bytes, mime_type } => match format {
-                OutputFormat::Png | OutputFormat::Bmp => match File::create(&path) {
-                    Ok(mut file) => {
-                        if let Err(e) = file.write_all(&bytes) {
-                            log::error!("SaveFileSink: Failed to write blob: {}", e);
-                        } else {
-                            let msg = format!(
-                                "Saved {} bytes ({}) to {:?}",
-                                bytes.len(),
-                                mime_type,
-                                path
-                            );
-                            log::info!("SaveFileSink: {}", msg);
-                            *self.last_saved.lock().unwrap() = Some(msg);
+            Signal::Blob { .. } | Signal::SharedBlob { .. } => {
+                let Some(BlobView { mime_type, bytes }) = signal.blob() else {
+                    return Ok(None);
+                };
+                match format {
+                    OutputFormat::Png | OutputFormat::Bmp => match File::create(&path) {
+                        Ok(mut file) => {
+                            if let Err(e) = file.write_all(bytes) {
+                                log::error!("SaveFileSink: Failed to write blob: {}", e);
+                            } else {
+                                let msg = format!(
+                                    "Saved {} bytes ({}) to {:?}",
+                                    bytes.len(),
+                                    mime_type,
+                                    path
+                                );
+                                log::info!("SaveFileSink: {}", msg);
+                                *self.last_saved.lock().unwrap() = Some(msg);
+                            }
                         }
+                        Err(e) => {
+                            log::error!("SaveFileSink: Failed to create file {:?}: {}", path, e);
+                        }
+                    },
+                    _ => {
+                        log::warn!(
+                            "SaveFileSink: Received Blob but format is {:?}, ignoring",
+                            format
+                        );
                     }
-                    Err(e) => {
-                        log::error!("SaveFileSink: Failed to create file {:?}: {}", path, e);
+                }
+            }
+
+            Signal::Audio { .. } | Signal::SharedAudio { .. } => {
+                let Some(AudioView {
+                    sample_rate,
+                    channels,
+                    data,
+                    ..
+                }) = signal.audio()
+                else {
+                    return Ok(None);
+                };
+
+                // A session boundary closes the file; numbering starts over
+                // in the new folder
+                if let Some(session) = &self.session {
+                    let generation = session.generation();
+                    if self.session_generation.swap(generation, Ordering::AcqRel) != generation {
+                        self.finish_recording();
+                        *self.segment.lock().unwrap() = 0;
+                    }
+                }
+
+                // Silence closes the file and nothing is written until the
+                // sound comes back, into the next one
+                let mut silence = self.silence.lock().unwrap();
+                if let Some(detector) = silence.as_mut() {
+                    if detector.process(sample_rate, channels, data) == Some(SilenceEvent::Start) {
+                        self.finish_recording();
+                    }
+                    if detector.is_silent() {
+                        return Ok(None);
                     }
-                },
-                _ => {
-                    log::warn!(
-                        "SaveFileSink: Received Blob but format is {:?}, ignoring",
-                        format
-                    );
                 }
-            },
-
-            Signal::Audio {
-                sample_rate,
-                channels,
-                timestamp_us: _,
-                data,
-            } => {
+                drop(silence);
+
+                let path = segment_path(&path, *self.segment.lock().unwrap());
                 let mut guard = self.audio_writer.lock().unwrap();
 
                 // Initialize writer if None or if we should check path changes (simplified here)
@@ -207,6 +429,7 @@ impl Sink for SaveFileSink {
                             match hound::WavWriter::new(buf_writer, spec) {
                                 Ok(writer) => {
                                     *guard = Some(writer);
+                                    *self.recording_path.lock().unwrap() = Some(path.clone());
                                     log::info!("SaveFileSink: Started WAV recording to {:?}", path);
                                 }
                                 Err(e) => {
@@ -220,7 +443,7 @@ impl Sink for SaveFileSink {
 
                 if let Some(writer) = guard.as_mut() {
                     for sample in data {
-                        if let Err(e) = writer.write_sample(sample) {
+                        if let Err(e) = writer.write_sample(*sample) {
                             log::error!("SaveFileSink: Error writing sample: {}", e);
                             break;
                         }
@@ -266,6 +489,105 @@ mod tests {
         let schema = sink.schema();
 
         assert_eq!(schema.id, "save_file");
-        assert_eq!(schema.ports.len(), 3); // text, blob, audio inputs
+        assert_eq!(schema.ports.len(), 4); // text, blob, audio and marker inputs
+    }
+
+    #[tokio::test]
+    async fn splits_recordings_at_silence() {
+        let dir = temp_dir().join("test_save_file_split");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("take.wav");
+        let sink = SaveFileSink::new(path.clone());
+        sink.set_format(OutputFormat::Wav);
+        sink.consume(Signal::Control(ControlSignal::Settings(
+            serde_json::json!({
+                "split_on_silence": true,
+                "silence_hold_ms": 200
+            }),
+        )))
+        .await
+        .unwrap();
+
+        // 100 ms buffers at 1 kHz
+        let audio = |value: f32| Signal::Audio {
+            sample_rate: 1_000,
+            channels: 1,
+            timestamp_us: 0,
+            data: vec![value; 100],
+        };
+        for value in [0.5, 0.0, 0.0, 0.0, 0.0, 0.5, 0.5] {
+            sink.consume(audio(value)).await.unwrap();
+        }
+        sink.finish_recording();
+
+        // The sound and the quiet until the hold ran out, then the sound after it
+        let frames = |path: &Path| hound::WavReader::open(path).unwrap().duration();
+        assert_eq!(frames(&path), 200);
+        assert_eq!(frames(&dir.join("take_001.wav")), 200);
+        assert!(!dir.join("take_002.wav").exists());
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[tokio::test]
+    async fn keeps_markers_as_cue_points() {
+        let path = temp_dir().join(format!("test_save_file_cues_{}.wav", std::process::id()));
+        let sink = SaveFileSink::new(path.clone());
+        sink.set_format(OutputFormat::Wav);
+        let audio = || Signal::Audio {
+            sample_rate: 1_000,
+            channels: 2,
+            timestamp_us: 0,
+            data: vec![0.5; 200],
+        };
+        sink.consume(audio()).await.unwrap();
+        sink.consume(magnolia_core::session::marker_signal("odd"))
+            .await
+            .unwrap();
+        sink.consume(audio()).await.unwrap();
+        sink.finish_recording();
+
+        let bytes = std::fs::read(&path).unwrap();
+        let riff_len = u32::from_le_bytes(bytes[4..8].try_into().unwrap()) as usize;
+        assert_eq!(riff_len + 8, bytes.len());
+        let cue = bytes.windows(4).position(|w| w == b"cue ").unwrap();
+        // One point, id 1, at frame 100
+        assert_eq!(&bytes[cue + 8..cue + 12], &1u32.to_le_bytes());
+        assert_eq!(&bytes[cue + 16..cue + 20], &100u32.to_le_bytes());
+        assert!(bytes.windows(4).any(|w| w == b"odd\0"));
+        assert_eq!(hound::WavReader::open(&path).unwrap().duration(), 200);
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[tokio::test]
+    async fn records_into_the_running_session() {
+        let root = temp_dir().join(format!("test_save_file_session_{}", std::process::id()));
+        let session = SessionState::new(magnolia_core::SessionConfig {
+            root: root.clone(),
+            auto_start: false,
+            seed: None,
+        });
+        let sink = SaveFileSink::new(PathBuf::from("take.wav")).with_session(session.clone());
+        sink.set_format(OutputFormat::Wav);
+        let audio = || Signal::Audio {
+            sample_rate: 1_000,
+            channels: 1,
+            timestamp_us: 0,
+            data: vec![0.5; 100],
+        };
+
+        let (_, first) = session.start(Some("one")).unwrap();
+        sink.consume(audio()).await.unwrap();
+        let (_, second) = session.start(Some("two")).unwrap();
+        sink.consume(audio()).await.unwrap();
+        sink.consume(audio()).await.unwrap();
+        sink.finish_recording();
+
+        let frames = |path: PathBuf| hound::WavReader::open(path).unwrap().duration();
+        assert_eq!(frames(first.dir.join("take.wav")), 100);
+        assert_eq!(frames(second.dir.join("take.wav")), 200);
+
+        std::fs::remove_dir_all(root).ok();
     }
 }
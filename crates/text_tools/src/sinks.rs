@@ -47,6 +47,7 @@ impl Sink for WordCountSink {
                 },
             ],
             settings_schema: None,
+            docs: None,
         }
     }
 
@@ -129,6 +130,7 @@ impl Sink for DevowelizerSink {
                 },
             ],
             settings_schema: None,
+            docs: None,
         }
     }
 
@@ -0,0 +1,289 @@
+//! Ticker: recent text lines for a scrolling marquee display.
+//!
+//! The sink appends incoming text to a shared [`TickerState`]; the ticker
+//! tile joins the history into one strip and scrolls it right to left.
+
+use async_trait::async_trait;
+use magnolia_core::{DataType, ModuleSchema, Port, PortDirection, Result, Signal, Sink};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+pub type SharedTicker = Arc<Mutex<TickerState>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TickerFont {
+    #[default]
+    Sans,
+    SansBold,
+    Mono,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TickerConfig {
+    /// Scroll speed, pixels per second
+    pub speed: f32,
+    pub font: TickerFont,
+    pub font_size: f32,
+    /// Lines kept in the strip, oldest first
+    pub history: usize,
+    pub separator: String,
+}
+
+impl Default for TickerConfig {
+    fn default() -> Self {
+        Self {
+            speed: 120.0,
+            font: TickerFont::Sans,
+            font_size: 32.0,
+            history: 10,
+            separator: "  •  ".to_string(),
+        }
+    }
+}
+
+impl TickerConfig {
+    pub fn schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "speed": {
+                    "type": "number",
+                    "title": "Speed (px/s)",
+                    "minimum": 0.0,
+                    "maximum": 2000.0,
+                    "default": 120.0
+                },
+                "font": {
+                    "type": "string",
+                    "title": "Font",
+                    "enum": ["sans", "sans_bold", "mono"],
+                    "default": "sans"
+                },
+                "font_size": {
+                    "type": "number",
+                    "title": "Font Size",
+                    "minimum": 8.0,
+                    "maximum": 200.0,
+                    "default": 32.0
+                },
+                "history": {
+                    "type": "integer",
+                    "title": "History (lines)",
+                    "minimum": 1,
+                    "maximum": 200,
+                    "default": 10
+                },
+                "separator": {
+                    "type": "string",
+                    "title": "Separator",
+                    "default": "  •  "
+                }
+            }
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct TickerState {
+    pub config: TickerConfig,
+    lines: VecDeque<String>,
+    /// Bumped on every change so the tile can re-measure the strip lazily
+    pub revision: u64,
+}
+
+impl TickerState {
+    pub fn new() -> SharedTicker {
+        Arc::new(Mutex::new(Self::default()))
+    }
+
+    pub fn push(&mut self, line: &str) {
+        let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+        if line.is_empty() {
+            return;
+        }
+        self.lines.push_back(line);
+        self.trim();
+        self.revision += 1;
+    }
+
+    pub fn set_config(&mut self, config: TickerConfig) {
+        self.config = config;
+        self.trim();
+        self.revision += 1;
+    }
+
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.revision += 1;
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = &str> {
+        self.lines.iter().map(String::as_str)
+    }
+
+    /// The whole history as one line
+    pub fn strip(&self) -> String {
+        self.lines
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(&self.config.separator)
+    }
+
+    fn trim(&mut self) {
+        let keep = self.config.history.max(1);
+        while self.lines.len() > keep {
+            self.lines.pop_front();
+        }
+    }
+}
+
+/// Left edge of a strip `strip_width` wide after scrolling `offset` pixels
+/// through a view `view_width` wide: it enters at the right edge, leaves at
+/// the left, then starts over
+pub fn marquee_x(offset: f64, strip_width: f32, view_width: f32) -> f32 {
+    let cycle = (strip_width + view_width).max(1.0) as f64;
+    view_width - offset.rem_euclid(cycle) as f32
+}
+
+/// Display text for a signal: plain text, a final STT transcript, or the
+/// `text` field of a JSON payload. STT partials and other JSON are skipped.
+pub fn ticker_text(signal: &Signal) -> Option<String> {
+    match signal {
+        Signal::Text(text) => Some(text.clone()),
+        Signal::Computed { content, .. } => {
+            let Ok(value) = serde_json::from_str::<serde_json::Value>(content) else {
+                return Some(content.clone());
+            };
+            let text = value
+                .get("Final")
+                .unwrap_or(&value)
+                .get("text")
+                .and_then(|t| t.as_str());
+            match (text, &value) {
+                (Some(text), _) => Some(text.to_string()),
+                (None, serde_json::Value::Number(n)) => Some(n.to_string()),
+                (None, serde_json::Value::String(s)) => Some(s.clone()),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+pub struct TickerSink {
+    id: String,
+    enabled: bool,
+    state: SharedTicker,
+}
+
+impl TickerSink {
+    pub fn new(id: &str, state: SharedTicker) -> Self {
+        Self {
+            id: id.to_string(),
+            enabled: true,
+            state,
+        }
+    }
+}
+
+#[async_trait]
+impl Sink for TickerSink {
+    fn name(&self) -> &str {
+        "ticker"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "Ticker".to_string(),
+            description: "Scrolls incoming text across a marquee tile".to_string(),
+            ports: vec![
+                Port {
+                    id: "text_in".to_string(),
+                    label: "Text Input".to_string(),
+                    data_type: DataType::Text,
+                    direction: PortDirection::Input,
+                },
+                Port {
+                    id: "computed_in".to_string(),
+                    label: "Computed Input".to_string(),
+                    data_type: DataType::Any,
+                    direction: PortDirection::Input,
+                },
+            ],
+            settings_schema: None,
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    fn render_output(&self) -> Option<String> {
+        self.state.lock().ok().map(|state| state.strip())
+    }
+
+    async fn consume(&self, signal: Signal) -> Result<Option<Signal>> {
+        if !self.enabled {
+            return Ok(None);
+        }
+        if let Some(text) = ticker_text(&signal) {
+            if let Ok(mut state) = self.state.lock() {
+                state.push(&text);
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn marquee_enters_right_and_wraps() {
+        assert_eq!(marquee_x(0.0, 300.0, 100.0), 100.0);
+        assert_eq!(marquee_x(250.0, 300.0, 100.0), -150.0);
+        // Fully past the left edge: back at the right
+        assert_eq!(marquee_x(400.0, 300.0, 100.0), 100.0);
+    }
+
+    #[test]
+    fn keeps_history_and_extracts_text() {
+        let mut state = TickerState::default();
+        state.set_config(TickerConfig {
+            history: 2,
+            separator: " | ".to_string(),
+            ..TickerConfig::default()
+        });
+        for line in ["one", "  two\n lines ", "", "three"] {
+            state.push(line);
+        }
+        assert_eq!(state.strip(), "two lines | three");
+
+        let stt = |content: &str| Signal::Computed {
+            source: "speech_to_text".to_string(),
+            content: content.to_string(),
+        };
+        assert_eq!(
+            ticker_text(&stt(r#"{"Final":{"text":"hello world","sequence":3}}"#)),
+            Some("hello world".to_string())
+        );
+        assert_eq!(ticker_text(&stt(r#"{"Partial":{"text":"hel"}}"#)), None);
+        assert_eq!(
+            ticker_text(&stt(r#"{"text":"reply"}"#)),
+            Some("reply".to_string())
+        );
+        assert_eq!(ticker_text(&stt("21.5")), Some("21.5".to_string()));
+        assert_eq!(ticker_text(&Signal::Pulse), None);
+    }
+}
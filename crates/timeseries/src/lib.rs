@@ -0,0 +1,17 @@
+//! Time-series export: numbers from routed signals as InfluxDB line
+//! protocol.
+//!
+//! `Computed` values (bare numbers or JSON objects, flattened), astrology
+//! longitudes and audio levels become points; sources map to measurements
+//! and JSON strings to tags through the sink's settings. A thread of its
+//! own posts them in batches to an InfluxDB or VictoriaMetrics `/write`
+//! endpoint and holds them through outages.
+
+mod line;
+mod mapping;
+mod sink;
+mod writer;
+
+pub use line::{FieldValue, Point};
+pub use sink::{TimeseriesConfig, TimeseriesSink};
+pub use writer::WriteStatus;
@@ -0,0 +1,111 @@
+//! Points and their InfluxDB line protocol encoding.
+
+use std::fmt::Write;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldValue {
+    /// Numbers are always floats, so a field never changes type between
+    /// points (Influx rejects that)
+    Float(f64),
+    Bool(bool),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Point {
+    pub measurement: String,
+    pub tags: Vec<(String, String)>,
+    pub fields: Vec<(String, FieldValue)>,
+    /// Nanoseconds since the Unix epoch, the protocol's default precision
+    pub timestamp_ns: i64,
+}
+
+/// Backslash before each of `special`; line breaks can't be escaped and
+/// become spaces
+fn escape(out: &mut String, value: &str, special: &[char]) {
+    for c in value.chars() {
+        match c {
+            '\n' | '\r' => out.push(' '),
+            c if special.contains(&c) => {
+                out.push('\\');
+                out.push(c);
+            }
+            c => out.push(c),
+        }
+    }
+}
+
+const MEASUREMENT: &[char] = &[',', ' ', '\\'];
+const KEY: &[char] = &[',', '=', ' ', '\\'];
+
+impl Point {
+    /// One line, without its newline. `None` without a finite field, which
+    /// the protocol requires.
+    pub fn to_line(&self) -> Option<String> {
+        let mut fields = self.fields.iter().filter(|(key, value)| {
+            !key.is_empty() && !matches!(value, FieldValue::Float(v) if !v.is_finite())
+        });
+        let first = fields.next()?;
+
+        let mut line = String::new();
+        escape(&mut line, &self.measurement, MEASUREMENT);
+        // Sorted tags are what Influx recommends for write performance
+        let mut tags: Vec<_> = self
+            .tags
+            .iter()
+            .filter(|(key, value)| !key.is_empty() && !value.is_empty())
+            .collect();
+        tags.sort();
+        for (key, value) in tags {
+            line.push(',');
+            escape(&mut line, key, KEY);
+            line.push('=');
+            escape(&mut line, value, KEY);
+        }
+        for (i, (key, value)) in std::iter::once(first).chain(fields).enumerate() {
+            line.push(if i == 0 { ' ' } else { ',' });
+            escape(&mut line, key, KEY);
+            line.push('=');
+            match value {
+                FieldValue::Float(v) => write!(line, "{}", v),
+                FieldValue::Bool(v) => write!(line, "{}", v),
+            }
+            .ok()?;
+        }
+        write!(line, " {}", self.timestamp_ns).ok()?;
+        Some(line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_and_skips_non_finite_fields() {
+        let point = Point {
+            measurement: "audio level".into(),
+            tags: vec![
+                ("source".into(), "mic,left".into()),
+                ("host".into(), "studio=1".into()),
+                ("empty".into(), String::new()),
+            ],
+            fields: vec![
+                ("rms db".into(), FieldValue::Float(-18.5)),
+                ("bad".into(), FieldValue::Float(f64::NAN)),
+                ("clipped".into(), FieldValue::Bool(false)),
+                ("peak".into(), FieldValue::Float(1.0)),
+            ],
+            timestamp_ns: 1_700_000_000_000_000_000,
+        };
+        assert_eq!(
+            point.to_line().unwrap(),
+            r"audio\ level,host=studio\=1,source=mic\,left rms\ db=-18.5,clipped=false,peak=1 1700000000000000000"
+        );
+
+        let empty = Point {
+            fields: vec![("x".into(), FieldValue::Float(f64::INFINITY))],
+            ..point
+        };
+        assert_eq!(empty.to_line(), None);
+    }
+}
@@ -0,0 +1,312 @@
+//! Which signals become points, and under which measurement and tags.
+
+use magnolia_core::Signal;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::line::{FieldValue, Point};
+
+/// JSON nested deeper than this isn't flattened further
+const MAX_DEPTH: usize = 4;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MeasurementMapping {
+    /// `Computed` source, `astrology` or `audio`
+    pub source: String,
+    pub measurement: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StaticTag {
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Mapping {
+    /// Measurement for sources without one of their own; empty uses the
+    /// source name
+    pub measurement: String,
+    pub measurements: Vec<MeasurementMapping>,
+    /// Added to every point (`host=studio`)
+    pub tags: Vec<StaticTag>,
+    /// JSON strings in `Computed` content become tags; otherwise they're
+    /// left out
+    pub string_tags: bool,
+    /// Only these sources are exported; empty exports all
+    pub sources: Vec<String>,
+}
+
+impl Default for Mapping {
+    fn default() -> Self {
+        Self {
+            measurement: String::new(),
+            measurements: Vec::new(),
+            tags: Vec::new(),
+            string_tags: true,
+            sources: Vec::new(),
+        }
+    }
+}
+
+impl Mapping {
+    fn exports(&self, source: &str) -> bool {
+        self.sources.is_empty() || self.sources.iter().any(|s| s.trim() == source)
+    }
+
+    fn measurement(&self, source: &str) -> String {
+        self.measurements
+            .iter()
+            .find(|m| m.source.trim() == source && !m.measurement.trim().is_empty())
+            .map(|m| m.measurement.trim())
+            .or_else(|| Some(self.measurement.trim()).filter(|m| !m.is_empty()))
+            .unwrap_or(source)
+            .to_string()
+    }
+
+    fn point(&self, source: &str, timestamp_ns: i64) -> Point {
+        Point {
+            measurement: self.measurement(source),
+            tags: self
+                .tags
+                .iter()
+                .map(|t| (t.key.trim().to_string(), t.value.trim().to_string()))
+                .collect(),
+            fields: Vec::new(),
+            timestamp_ns,
+        }
+    }
+
+    /// The point for a `Computed` or `Astrology` signal; `None` for other
+    /// signals, unexported sources, and content without a number in it.
+    /// Audio goes through [`AudioMeter`] instead.
+    pub fn map(&self, signal: &Signal, timestamp_ns: i64) -> Option<Point> {
+        let point = match signal {
+            Signal::Computed { source, content } => {
+                if !self.exports(source) {
+                    return None;
+                }
+                let mut point = self.point(source, timestamp_ns);
+                match serde_json::from_str::<Value>(content) {
+                    Ok(value) => flatten(&mut point, "", &value, self.string_tags, 0),
+                    // Plain text like `12.5 ms` isn't a number
+                    Err(_) => {
+                        let value = content.trim().parse::<f64>().ok()?;
+                        point
+                            .fields
+                            .push(("value".into(), FieldValue::Float(value)));
+                    }
+                }
+                point
+            }
+            Signal::Astrology(data) => {
+                if !self.exports("astrology") {
+                    return None;
+                }
+                let mut point = self.point("astrology", timestamp_ns);
+                point.tags.extend([
+                    ("sun_sign".to_string(), data.sun_sign.clone()),
+                    ("moon_sign".to_string(), data.moon_sign.clone()),
+                    ("rising_sign".to_string(), data.rising_sign.clone()),
+                ]);
+                point
+                    .fields
+                    .extend(data.planetary_positions.iter().map(|(planet, longitude)| {
+                        (planet.to_lowercase(), FieldValue::Float(*longitude))
+                    }));
+                point
+            }
+            _ => return None,
+        };
+        (!point.fields.is_empty()).then_some(point)
+    }
+}
+
+/// Objects and arrays become dotted keys (`levels.0.rms`); a bare value is
+/// the field `value`
+fn flatten(point: &mut Point, key: &str, value: &Value, string_tags: bool, depth: usize) {
+    let name = || {
+        if key.is_empty() {
+            "value".to_string()
+        } else {
+            key.to_string()
+        }
+    };
+    let child = |k: &str| {
+        if key.is_empty() {
+            k.to_string()
+        } else {
+            format!("{}.{}", key, k)
+        }
+    };
+    match value {
+        Value::Number(n) => {
+            if let Some(n) = n.as_f64() {
+                point.fields.push((name(), FieldValue::Float(n)));
+            }
+        }
+        Value::Bool(b) => point.fields.push((name(), FieldValue::Bool(*b))),
+        Value::String(s) if string_tags => point.tags.push((name(), s.clone())),
+        Value::Object(map) if depth < MAX_DEPTH => {
+            for (k, v) in map {
+                flatten(point, &child(k), v, string_tags, depth + 1);
+            }
+        }
+        Value::Array(items) if depth < MAX_DEPTH => {
+            for (i, v) in items.iter().enumerate() {
+                flatten(point, &child(&i.to_string()), v, string_tags, depth + 1);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Audio arrives many times a second; this folds it into one RMS/peak
+/// point per interval
+#[derive(Debug, Default)]
+pub struct AudioMeter {
+    sum_squares: f64,
+    samples: u64,
+    peak: f32,
+    started_ns: Option<i64>,
+}
+
+/// Silence reads as this rather than -inf, which Influx can't store
+const FLOOR_DB: f64 = -120.0;
+
+fn to_db(amplitude: f64) -> f64 {
+    if amplitude > 0.0 {
+        (20.0 * amplitude.log10()).max(FLOOR_DB)
+    } else {
+        FLOOR_DB
+    }
+}
+
+impl AudioMeter {
+    /// Add a block; returns the point for the interval it completes
+    pub fn add(
+        &mut self,
+        mapping: &Mapping,
+        data: &[f32],
+        timestamp_ns: i64,
+        interval_ns: i64,
+    ) -> Option<Point> {
+        if !mapping.exports("audio") {
+            return None;
+        }
+        let started = *self.started_ns.get_or_insert(timestamp_ns);
+        for &sample in data {
+            self.sum_squares += f64::from(sample) * f64::from(sample);
+            self.peak = self.peak.max(sample.abs());
+        }
+        self.samples += data.len() as u64;
+        if timestamp_ns - started < interval_ns || self.samples == 0 {
+            return None;
+        }
+
+        let rms = (self.sum_squares / self.samples as f64).sqrt();
+        let mut point = mapping.point("audio", timestamp_ns);
+        point.fields = vec![
+            ("rms_db".into(), FieldValue::Float(to_db(rms))),
+            (
+                "peak_db".into(),
+                FieldValue::Float(to_db(f64::from(self.peak))),
+            ),
+            ("clipped".into(), FieldValue::Bool(self.peak >= 1.0)),
+        ];
+        *self = Self::default();
+        Some(point)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use magnolia_core::AstrologyData;
+
+    fn computed(source: &str, content: &str) -> Signal {
+        Signal::Computed {
+            source: source.into(),
+            content: content.into(),
+        }
+    }
+
+    #[test]
+    fn maps_sources_to_measurements_and_strings_to_tags() {
+        let mapping = Mapping {
+            measurements: vec![MeasurementMapping {
+                source: "stt_latency".into(),
+                measurement: "stt".into(),
+            }],
+            tags: vec![StaticTag {
+                key: "host".into(),
+                value: "studio".into(),
+            }],
+            ..Mapping::default()
+        };
+
+        let point = mapping.map(&computed("stt_latency", "42.5"), 7).unwrap();
+        assert_eq!(point.to_line().unwrap(), "stt,host=studio value=42.5 7");
+
+        let point = mapping
+            .map(
+                &computed(
+                    "weather",
+                    r#"{"temp":12,"sky":"clear","wind":{"speed":3.5},"raining":false}"#,
+                ),
+                9,
+            )
+            .unwrap();
+        assert_eq!(
+            point.to_line().unwrap(),
+            "weather,host=studio,sky=clear raining=false,temp=12,wind.speed=3.5 9"
+        );
+
+        assert_eq!(mapping.map(&computed("stt_final", "hello there"), 1), None);
+        assert_eq!(mapping.map(&Signal::Text("3".into()), 1), None);
+
+        let only_audio = Mapping {
+            sources: vec!["audio".into()],
+            ..Mapping::default()
+        };
+        assert_eq!(only_audio.map(&computed("weather", "1"), 1), None);
+    }
+
+    #[test]
+    fn maps_astrology_longitudes() {
+        let data = AstrologyData {
+            sun_sign: "Libra".into(),
+            moon_sign: "Aries".into(),
+            rising_sign: "Leo".into(),
+            planetary_positions: vec![("Sun".into(), 201.25), ("Moon".into(), 14.5)],
+        };
+        let point = Mapping::default().map(&Signal::Astrology(data), 3).unwrap();
+        assert_eq!(
+            point.to_line().unwrap(),
+            "astrology,moon_sign=Aries,rising_sign=Leo,sun_sign=Libra sun=201.25,moon=14.5 3"
+        );
+    }
+
+    #[test]
+    fn meters_audio_per_interval() {
+        let mapping = Mapping::default();
+        let mut meter = AudioMeter::default();
+        assert_eq!(meter.add(&mapping, &[0.5, -0.5], 0, 100), None);
+        assert_eq!(meter.add(&mapping, &[0.0; 0], 50, 100), None);
+        let point = meter.add(&mapping, &[0.5, -0.5], 100, 100).unwrap();
+        assert_eq!(point.measurement, "audio");
+        match point.fields[..] {
+            [(_, FieldValue::Float(rms)), (_, FieldValue::Float(peak)), (_, FieldValue::Bool(false))] =>
+            {
+                assert!((rms - -6.0206).abs() < 1e-3);
+                assert!((peak - -6.0206).abs() < 1e-3);
+            }
+            ref fields => panic!("unexpected fields {:?}", fields),
+        }
+        // The next interval starts fresh
+        assert_eq!(meter.add(&mapping, &[1.0], 150, 100), None);
+    }
+}
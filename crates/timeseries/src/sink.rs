@@ -0,0 +1,540 @@
+//! The export thread and the sink feeding it.
+
+use async_trait::async_trait;
+use magnolia_core::{
+    ControlSignal, DataType, ModuleSchema, Port, PortDirection, Signal, Sink, WorkerSettings,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::line::Point;
+use crate::mapping::{AudioMeter, Mapping};
+use crate::writer::{HttpWriter, WriteError, WriteStatus, Writer};
+
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// Lines held while the server is slow or away; the oldest go first
+const QUEUE: usize = 50_000;
+const TICK: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimeseriesConfig {
+    /// `http://host:8086/write?db=magnolia` (InfluxDB 1.x, VictoriaMetrics)
+    /// or `http://host:8086/api/v2/write?org=..&bucket=..`; empty is off
+    pub url: String,
+    /// Sent as `Authorization: Token ..` (InfluxDB 2.x)
+    pub token: String,
+    /// Points per request
+    pub batch_size: usize,
+    /// Longest a point waits for a full batch
+    pub flush_interval_secs: f32,
+    /// One audio level point per this many seconds
+    pub audio_interval_secs: f32,
+    #[serde(flatten)]
+    pub mapping: Mapping,
+}
+
+impl Default for TimeseriesConfig {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            token: String::new(),
+            batch_size: 500,
+            flush_interval_secs: 10.0,
+            audio_interval_secs: 1.0,
+            mapping: Mapping::default(),
+        }
+    }
+}
+
+impl TimeseriesConfig {
+    pub fn schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "url": { "type": "string", "title": "Write URL", "default": "" },
+                "token": { "type": "string", "title": "Token", "default": "" },
+                "batch_size": {
+                    "type": "integer",
+                    "title": "Batch Size",
+                    "minimum": 1,
+                    "maximum": 5000,
+                    "default": 500
+                },
+                "flush_interval_secs": {
+                    "type": "number",
+                    "title": "Flush Interval (s)",
+                    "minimum": 0.1,
+                    "maximum": 300.0,
+                    "default": 10.0
+                },
+                "audio_interval_secs": {
+                    "type": "number",
+                    "title": "Audio Level Interval (s)",
+                    "minimum": 0.1,
+                    "maximum": 60.0,
+                    "default": 1.0
+                },
+                "measurement": {
+                    "type": "string",
+                    "title": "Measurement (empty: source name)",
+                    "default": ""
+                },
+                "measurements": {
+                    "type": "array",
+                    "title": "Measurement per Source",
+                    "default": [],
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "source": { "type": "string", "title": "Source", "default": "" },
+                            "measurement": { "type": "string", "title": "Measurement", "default": "" }
+                        }
+                    }
+                },
+                "tags": {
+                    "type": "array",
+                    "title": "Tags",
+                    "default": [],
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "key": { "type": "string", "title": "Key", "default": "" },
+                            "value": { "type": "string", "title": "Value", "default": "" }
+                        }
+                    }
+                },
+                "string_tags": {
+                    "type": "boolean",
+                    "title": "JSON Strings as Tags",
+                    "default": true
+                },
+                "sources": {
+                    "type": "array",
+                    "title": "Sources (empty: all)",
+                    "items": { "type": "string" },
+                    "default": []
+                }
+            }
+        })
+    }
+
+    fn enabled(&self) -> bool {
+        !self.url.trim().is_empty()
+    }
+
+    fn batch_size(&self) -> usize {
+        self.batch_size.clamp(1, QUEUE)
+    }
+
+    fn flush_interval(&self) -> Duration {
+        Duration::from_secs_f32(self.flush_interval_secs.clamp(0.1, 300.0))
+    }
+
+    fn audio_interval_ns(&self) -> i64 {
+        (f64::from(self.audio_interval_secs.clamp(0.1, 60.0)) * 1e9) as i64
+    }
+}
+
+fn now_ns() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos() as i64)
+}
+
+type Connector = Box<dyn Fn(&TimeseriesConfig) -> Box<dyn Writer> + Send>;
+
+fn connect(config: &TimeseriesConfig) -> Box<dyn Writer> {
+    Box::new(HttpWriter::new(&config.url, &config.token))
+}
+
+struct Shared {
+    config: WorkerSettings<TimeseriesConfig>,
+    status: Mutex<WriteStatus>,
+    written: AtomicU64,
+    dropped: AtomicU64,
+}
+
+impl Shared {
+    fn set_status(&self, status: WriteStatus) {
+        if let Ok(mut s) = self.status.lock() {
+            *s = status;
+        }
+    }
+
+    fn status(&self) -> WriteStatus {
+        self.status
+            .lock()
+            .map(|s| s.clone())
+            .unwrap_or(WriteStatus::Disabled)
+    }
+
+    fn drop_lines(&self, count: usize) {
+        if self.dropped.fetch_add(count as u64, Ordering::Relaxed) == 0 {
+            log::warn!("Time-series export: dropping points");
+        }
+    }
+}
+
+/// How a session ended without an error
+enum Ended {
+    SettingsChanged,
+    SinkDropped,
+}
+
+fn run(shared: Arc<Shared>, lines: mpsc::Receiver<String>, connect: Connector) {
+    let mut backoff = Duration::from_secs(1);
+    let mut pending = VecDeque::new();
+    loop {
+        let (generation, config) = shared.config.current();
+        if !config.enabled() {
+            shared.set_status(WriteStatus::Disabled);
+            pending.clear();
+            match lines.recv_timeout(TICK) {
+                Err(mpsc::RecvTimeoutError::Disconnected) => return,
+                _ => continue,
+            }
+        }
+
+        let mut writer = connect(&config);
+        match session(
+            &shared,
+            &config,
+            generation,
+            &lines,
+            &mut pending,
+            writer.as_mut(),
+        ) {
+            Ok(Ended::SinkDropped) => return,
+            Ok(Ended::SettingsChanged) => {
+                backoff = Duration::from_secs(1);
+                continue;
+            }
+            Err(e) => {
+                if !matches!(shared.status(), WriteStatus::Failed(_)) {
+                    log::warn!("Time-series export: {}", e);
+                }
+                shared.set_status(WriteStatus::Failed(e));
+            }
+        }
+        shared.config.wait(generation, backoff);
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
+
+fn enqueue(shared: &Shared, pending: &mut VecDeque<String>, line: String) {
+    if pending.len() >= QUEUE {
+        pending.pop_front();
+        shared.drop_lines(1);
+    }
+    pending.push_back(line);
+}
+
+/// Writes until a request fails, the settings change or the sink goes.
+/// Lines not yet written stay in `pending` for the next session.
+fn session(
+    shared: &Shared,
+    config: &TimeseriesConfig,
+    generation: u64,
+    lines: &mpsc::Receiver<String>,
+    pending: &mut VecDeque<String>,
+    writer: &mut dyn Writer,
+) -> Result<Ended, String> {
+    let batch_size = config.batch_size();
+    // Retry what's held right away rather than after another interval
+    let mut next_flush = Instant::now();
+    loop {
+        let wait = next_flush
+            .saturating_duration_since(Instant::now())
+            .min(TICK);
+        let dropped = match lines.recv_timeout(wait) {
+            Ok(line) => {
+                enqueue(shared, pending, line);
+                for line in lines.try_iter().take(batch_size) {
+                    enqueue(shared, pending, line);
+                }
+                false
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => false,
+            Err(mpsc::RecvTimeoutError::Disconnected) => true,
+        };
+        let changed = shared.config.changed_since(generation);
+
+        if pending.len() >= batch_size || Instant::now() >= next_flush || dropped || changed {
+            while !pending.is_empty() {
+                let count = pending.len().min(batch_size);
+                let body = pending
+                    .range(..count)
+                    .map(String::as_str)
+                    .collect::<Vec<_>>();
+                match writer.write(&body.join("\n")) {
+                    Ok(()) => {
+                        shared.written.fetch_add(count as u64, Ordering::Relaxed);
+                        shared.set_status(WriteStatus::Ready);
+                    }
+                    Err(WriteError::Rejected(e)) => {
+                        log::warn!("Time-series export: batch rejected: {}", e);
+                        shared.drop_lines(count);
+                    }
+                    Err(e) => return Err(e.message().to_string()),
+                }
+                pending.drain(..count);
+            }
+            next_flush = Instant::now() + config.flush_interval();
+        }
+        if dropped {
+            return Ok(Ended::SinkDropped);
+        }
+        if changed {
+            return Ok(Ended::SettingsChanged);
+        }
+    }
+}
+
+/// Exports numbers from every signal patched into it as InfluxDB line
+/// protocol
+pub struct TimeseriesSink {
+    id: String,
+    enabled: bool,
+    lines: mpsc::SyncSender<String>,
+    shared: Arc<Shared>,
+    meter: Mutex<AudioMeter>,
+}
+
+impl TimeseriesSink {
+    /// Start the export thread; it stays idle while no URL is set
+    pub fn spawn(id: &str, config: TimeseriesConfig) -> std::io::Result<Self> {
+        Self::with_connector(id, config, Box::new(connect))
+    }
+
+    fn with_connector(
+        id: &str,
+        config: TimeseriesConfig,
+        connect: Connector,
+    ) -> std::io::Result<Self> {
+        let status = if config.enabled() {
+            WriteStatus::Ready
+        } else {
+            WriteStatus::Disabled
+        };
+        let shared = Arc::new(Shared {
+            config: WorkerSettings::new(config),
+            status: Mutex::new(status),
+            written: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+        });
+        let (tx, rx) = mpsc::sync_channel(QUEUE);
+        let thread_shared = shared.clone();
+        std::thread::Builder::new()
+            .name("timeseries".to_string())
+            .spawn(move || run(thread_shared, rx, connect))?;
+        Ok(Self {
+            id: id.to_string(),
+            enabled: true,
+            lines: tx,
+            shared,
+            meter: Mutex::new(AudioMeter::default()),
+        })
+    }
+
+    pub fn status(&self) -> WriteStatus {
+        self.shared.status()
+    }
+
+    pub fn set_config(&self, config: TimeseriesConfig) {
+        if let Ok(mut meter) = self.meter.lock() {
+            *meter = AudioMeter::default();
+        }
+        self.shared.config.set(config);
+    }
+
+    fn point(&self, config: &TimeseriesConfig, signal: &Signal) -> Option<Point> {
+        let timestamp_ns = now_ns();
+        let samples = match signal {
+            Signal::Audio { .. } | Signal::SharedAudio { .. } => signal.audio()?.data,
+            signal => return config.mapping.map(signal, timestamp_ns),
+        };
+        self.meter.lock().ok()?.add(
+            &config.mapping,
+            samples,
+            timestamp_ns,
+            config.audio_interval_ns(),
+        )
+    }
+
+    fn export(&self, signal: &Signal) {
+        let config = self.shared.config.get();
+        if !config.enabled() {
+            return;
+        }
+        let Some(line) = self.point(&config, signal).and_then(|p| p.to_line()) else {
+            return;
+        };
+        if self.lines.try_send(line).is_err() {
+            self.shared.drop_lines(1);
+        }
+    }
+}
+
+#[async_trait]
+impl Sink for TimeseriesSink {
+    fn name(&self) -> &str {
+        "Time-Series Export"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "Time-Series Export".to_string(),
+            description: "Exports numeric signals to InfluxDB or VictoriaMetrics".to_string(),
+            ports: vec![Port {
+                id: "signals_in".to_string(),
+                label: "Signals".to_string(),
+                data_type: DataType::Any,
+                direction: PortDirection::Input,
+            }],
+            settings_schema: Some(TimeseriesConfig::schema()),
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    fn render_output(&self) -> Option<String> {
+        let written = self.shared.written.load(Ordering::Relaxed);
+        let dropped = self.shared.dropped.load(Ordering::Relaxed);
+        let mut line = match self.status() {
+            WriteStatus::Disabled => return Some("TSDB: off".to_string()),
+            WriteStatus::Ready => format!("TSDB: {} points", written),
+            WriteStatus::Failed(e) => format!("TSDB: {}", e),
+        };
+        if dropped > 0 {
+            line.push_str(&format!(" · {} dropped", dropped));
+        }
+        Some(line)
+    }
+
+    async fn consume(&self, signal: Signal) -> magnolia_core::Result<Option<Signal>> {
+        match signal {
+            Signal::Control(ControlSignal::Settings(settings)) => {
+                match serde_json::from_value::<TimeseriesConfig>(settings) {
+                    Ok(config) => self.set_config(config),
+                    Err(e) => {
+                        log::warn!(
+                            "Time-series export: invalid settings, keeping current: {}",
+                            e
+                        )
+                    }
+                }
+            }
+            _ if !self.enabled => {}
+            signal => self.export(&signal),
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fails if told to, rejects any batch containing `bad`, and records
+    /// the rest
+    struct FakeWriter {
+        bodies: Arc<Mutex<Vec<String>>>,
+        fail: bool,
+    }
+
+    impl Writer for FakeWriter {
+        fn write(&mut self, body: &str) -> Result<(), WriteError> {
+            if std::mem::take(&mut self.fail) {
+                return Err(WriteError::Failed("connection refused".into()));
+            }
+            if body.contains("bad") {
+                return Err(WriteError::Rejected("HTTP 400: bad line".into()));
+            }
+            self.bodies.lock().unwrap().push(body.to_string());
+            Ok(())
+        }
+    }
+
+    fn lines(bodies: &Mutex<Vec<String>>) -> Vec<String> {
+        bodies
+            .lock()
+            .unwrap()
+            .iter()
+            .flat_map(|body| body.lines().map(str::to_string).collect::<Vec<_>>())
+            .collect()
+    }
+
+    fn wait_for(bodies: &Mutex<Vec<String>>, count: usize) {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while lines(bodies).len() < count && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(lines(bodies).len(), count);
+    }
+
+    fn computed(source: &str, content: &str) -> Signal {
+        Signal::Computed {
+            source: source.into(),
+            content: content.into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn batches_points_and_retries_after_failures() {
+        let bodies = Arc::new(Mutex::new(Vec::new()));
+        let fake = bodies.clone();
+        let connected = AtomicU64::new(0);
+        let config = TimeseriesConfig {
+            url: "http://localhost:8086/write?db=magnolia".into(),
+            batch_size: 2,
+            flush_interval_secs: 300.0,
+            ..TimeseriesConfig::default()
+        };
+        let sink = TimeseriesSink::with_connector(
+            "timeseries",
+            config,
+            Box::new(move |_| {
+                Box::new(FakeWriter {
+                    bodies: fake.clone(),
+                    fail: connected.fetch_add(1, Ordering::Relaxed) == 0,
+                })
+            }),
+        )
+        .unwrap();
+
+        sink.consume(computed("stt_latency", "120")).await.unwrap();
+        sink.consume(Signal::Text("not a number".into()))
+            .await
+            .unwrap();
+        sink.consume(computed("level", r#"{"rms":-18}"#))
+            .await
+            .unwrap();
+        // The first attempt fails; the held batch goes out on the retry
+        wait_for(&bodies, 2);
+        assert_eq!(sink.status(), WriteStatus::Ready);
+
+        sink.consume(computed("bad", "1")).await.unwrap();
+        sink.consume(computed("bad", "2")).await.unwrap();
+        sink.consume(computed("cpu", "0.5")).await.unwrap();
+        // Dropping the sink flushes what's left
+        drop(sink);
+        wait_for(&bodies, 3);
+
+        let lines = lines(&bodies);
+        assert!(lines[0].starts_with("stt_latency value=120 "));
+        assert!(lines[1].starts_with("level rms=-18 "));
+        assert!(lines[2].starts_with("cpu value=0.5 "));
+    }
+}
@@ -0,0 +1,84 @@
+//! Posting batches of lines to a `/write` endpoint.
+
+use std::time::Duration;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WriteStatus {
+    /// No URL set
+    Disabled,
+    Ready,
+    /// Last error; retried with backoff, keeping queued points
+    Failed(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum WriteError {
+    /// The server won't take these lines; retrying can't help
+    Rejected(String),
+    /// Down, unreachable, or refusing us for now
+    Failed(String),
+}
+
+impl WriteError {
+    pub fn message(&self) -> &str {
+        match self {
+            WriteError::Rejected(message) | WriteError::Failed(message) => message,
+        }
+    }
+}
+
+/// Sends one batch of newline-separated lines
+pub(crate) trait Writer: Send {
+    fn write(&mut self, body: &str) -> Result<(), WriteError>;
+}
+
+/// InfluxDB 1.x/2.x and VictoriaMetrics all take line protocol as the
+/// body of a POST
+pub(crate) struct HttpWriter {
+    agent: ureq::Agent,
+    url: String,
+    token: String,
+}
+
+impl HttpWriter {
+    pub fn new(url: &str, token: &str) -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new().timeout(REQUEST_TIMEOUT).build(),
+            url: url.trim().to_string(),
+            token: token.trim().to_string(),
+        }
+    }
+}
+
+impl Writer for HttpWriter {
+    fn write(&mut self, body: &str) -> Result<(), WriteError> {
+        let mut request = self
+            .agent
+            .post(&self.url)
+            .set("Content-Type", "text/plain; charset=utf-8");
+        if !self.token.is_empty() {
+            request = request.set("Authorization", &format!("Token {}", self.token));
+        }
+        match request.send_string(body) {
+            Ok(_) => Ok(()),
+            Err(ureq::Error::Status(code, response)) => {
+                // Influx explains rejected lines in a JSON `message`
+                let message = response
+                    .into_string()
+                    .ok()
+                    .and_then(|body| serde_json::from_str::<serde_json::Value>(&body).ok())
+                    .and_then(|body| body["message"].as_str().map(str::to_string))
+                    .unwrap_or_else(|| "write rejected".to_string());
+                let message = format!("HTTP {}: {}", code, message);
+                // Bad syntax, too large, or a field changing type
+                Err(match code {
+                    400 | 413 | 422 => WriteError::Rejected(message),
+                    _ => WriteError::Failed(message),
+                })
+            }
+            Err(e) => Err(WriteError::Failed(e.to_string())),
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use magnolia_core::LibraryError;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::frame::VideoFrame;
+use crate::ndi::NdiSender;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackendKind {
+    /// NDI over the network; needs the NDI runtime installed
+    Ndi,
+    /// Spout (Windows, DirectX texture sharing)
+    Spout,
+    /// Syphon (macOS, IOSurface sharing)
+    Syphon,
+}
+
+impl BackendKind {
+    pub const ALL: [BackendKind; 3] = [BackendKind::Ndi, BackendKind::Spout, BackendKind::Syphon];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            BackendKind::Ndi => "NDI",
+            BackendKind::Spout => "Spout",
+            BackendKind::Syphon => "Syphon",
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ShareError {
+    #[error("{0}")]
+    Unsupported(String),
+    #[error("could not load {library}: {message}")]
+    Library { library: String, message: String },
+    #[error("{0}")]
+    Sender(String),
+}
+
+impl From<LibraryError> for ShareError {
+    fn from(e: LibraryError) -> Self {
+        Self::Library {
+            library: e.library,
+            message: e.message,
+        }
+    }
+}
+
+/// A published video source other applications can pick up
+pub trait ShareBackend: Send {
+    fn kind(&self) -> BackendKind;
+
+    fn send(&mut self, frame: &VideoFrame) -> Result<(), ShareError>;
+}
+
+/// Publish a source called `name` other applications will list
+pub fn open(kind: BackendKind, name: &str) -> Result<Box<dyn ShareBackend>, ShareError> {
+    match kind {
+        BackendKind::Ndi => Ok(Box::new(NdiSender::new(name)?)),
+        // Both share GPU textures in place and need their platform SDKs
+        // (Spout2 / Syphon.framework), which aren't bound yet; NDI works on
+        // every platform and OBS, Resolume and vMix all receive it
+        BackendKind::Spout if cfg!(windows) => Err(ShareError::Unsupported(
+            "Spout output isn't available in this build; use NDI".to_string(),
+        )),
+        BackendKind::Syphon if cfg!(target_os = "macos") => Err(ShareError::Unsupported(
+            "Syphon output isn't available in this build; use NDI".to_string(),
+        )),
+        BackendKind::Spout => Err(ShareError::Unsupported(
+            "Spout is Windows-only; use NDI".to_string(),
+        )),
+        BackendKind::Syphon => Err(ShareError::Unsupported(
+            "Syphon is macOS-only; use NDI".to_string(),
+        )),
+    }
+}
@@ -0,0 +1,83 @@
+/// Tightly packed 8-bit sRGBA pixels, top row first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VideoFrame {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+/// Region of a frame in pixels, origin at the top left
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PixelRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl VideoFrame {
+    /// `None` if `data` doesn't hold `width * height` pixels
+    pub fn new(width: u32, height: u32, data: Vec<u8>) -> Option<Self> {
+        (data.len() == width as usize * height as usize * 4).then_some(Self {
+            width,
+            height,
+            data,
+        })
+    }
+
+    /// The part of the frame inside `rect`, clipped to the frame; `None` if
+    /// nothing is left
+    pub fn crop(&self, rect: PixelRect) -> Option<VideoFrame> {
+        let x0 = rect.x.min(self.width);
+        let y0 = rect.y.min(self.height);
+        let x1 = rect.x.saturating_add(rect.width).min(self.width);
+        let y1 = rect.y.saturating_add(rect.height).min(self.height);
+        if x1 <= x0 || y1 <= y0 {
+            return None;
+        }
+        let row_bytes = (x1 - x0) as usize * 4;
+        let mut data = Vec::with_capacity(row_bytes * (y1 - y0) as usize);
+        for y in y0..y1 {
+            let start = (y as usize * self.width as usize + x0 as usize) * 4;
+            data.extend_from_slice(&self.data[start..start + row_bytes]);
+        }
+        Some(VideoFrame {
+            width: x1 - x0,
+            height: y1 - y0,
+            data,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crops_and_clips() {
+        // 3x2, each pixel's red channel is its index
+        let data: Vec<u8> = (0..6u8).flat_map(|i| [i, 0, 0, 255]).collect();
+        let frame = VideoFrame::new(3, 2, data).unwrap();
+        assert!(VideoFrame::new(3, 3, frame.data.clone()).is_none());
+
+        let crop = frame
+            .crop(PixelRect {
+                x: 1,
+                y: 0,
+                width: 5,
+                height: 5,
+            })
+            .unwrap();
+        assert_eq!((crop.width, crop.height), (2, 2));
+        let reds: Vec<u8> = crop.data.chunks(4).map(|p| p[0]).collect();
+        assert_eq!(reds, vec![1, 2, 4, 5]);
+
+        let outside = PixelRect {
+            x: 3,
+            y: 0,
+            width: 2,
+            height: 2,
+        };
+        assert!(frame.crop(outside).is_none());
+    }
+}
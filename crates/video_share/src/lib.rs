@@ -0,0 +1,18 @@
+//! Video share: publishes the compositor output, or one tile, as a video
+//! source other applications (OBS, Resolume, vMix) can ingest without
+//! screen capture.
+//!
+//! The daemon asks [`VideoShare::capture_target`] each frame, reads the
+//! frame back from the GPU when it says so and [`VideoShare::submit`]s it;
+//! a worker thread publishes it through the configured backend. NDI loads
+//! the NDI runtime at run time; Spout and Syphon are recognised but not
+//! bound yet.
+
+mod backend;
+mod frame;
+mod ndi;
+mod share;
+
+pub use backend::{BackendKind, ShareBackend, ShareError};
+pub use frame::{PixelRect, VideoFrame};
+pub use share::{CaptureTarget, ShareConfig, ShareStatus, VideoShare, VideoShareSink};
@@ -0,0 +1,161 @@
+//! NDI sender on the NDI runtime, loaded at run time so the daemon starts
+//! (and builds) without the SDK; only the handful of `NDIlib_*` calls a
+//! sender needs are bound.
+
+use libloading::Library;
+use magnolia_core::native_library::{load_library, symbol};
+use std::ffi::{c_char, c_int, c_void, CString};
+use std::path::PathBuf;
+
+use crate::backend::{BackendKind, ShareBackend, ShareError};
+use crate::frame::VideoFrame;
+
+#[repr(C)]
+struct SendCreate {
+    p_ndi_name: *const c_char,
+    p_groups: *const c_char,
+    clock_video: bool,
+    clock_audio: bool,
+}
+
+#[repr(C)]
+struct VideoFrameV2 {
+    xres: c_int,
+    yres: c_int,
+    four_cc: u32,
+    frame_rate_n: c_int,
+    frame_rate_d: c_int,
+    picture_aspect_ratio: f32,
+    frame_format_type: c_int,
+    timecode: i64,
+    p_data: *const u8,
+    line_stride_in_bytes: c_int,
+    p_metadata: *const c_char,
+    timestamp: i64,
+}
+
+const FOURCC_RGBA: u32 = u32::from_le_bytes(*b"RGBA");
+const FRAME_FORMAT_PROGRESSIVE: c_int = 1;
+/// Let NDI stamp frames itself
+const TIMECODE_SYNTHESIZE: i64 = i64::MAX;
+
+type InitializeFn = unsafe extern "C" fn() -> bool;
+type SendCreateFn = unsafe extern "C" fn(*const SendCreate) -> *mut c_void;
+type SendVideoFn = unsafe extern "C" fn(*mut c_void, *const VideoFrameV2);
+type SendDestroyFn = unsafe extern "C" fn(*mut c_void);
+
+pub struct NdiSender {
+    instance: *mut c_void,
+    send_video: SendVideoFn,
+    destroy: SendDestroyFn,
+    /// Keeps the function pointers above valid
+    _library: Library,
+}
+
+// The send instance is only used from the thread that owns the sender
+unsafe impl Send for NdiSender {}
+
+/// Where the runtime may be: the runtime installer's directory first, then
+/// the loader's search path
+fn library_candidates() -> Vec<PathBuf> {
+    let names: &[&str] = if cfg!(windows) {
+        &["Processing.NDI.Lib.x64.dll"]
+    } else if cfg!(target_os = "macos") {
+        &["libndi.dylib"]
+    } else {
+        &["libndi.so.6", "libndi.so.5", "libndi.so"]
+    };
+    let mut candidates = Vec::new();
+    for var in ["NDI_RUNTIME_DIR_V6", "NDI_RUNTIME_DIR_V5"] {
+        if let Some(dir) = std::env::var_os(var) {
+            candidates.extend(names.iter().map(|name| PathBuf::from(&dir).join(name)));
+        }
+    }
+    if cfg!(target_os = "macos") {
+        candidates.push(PathBuf::from("/usr/local/lib/libndi.dylib"));
+    }
+    candidates.extend(names.iter().map(PathBuf::from));
+    candidates
+}
+
+impl NdiSender {
+    pub fn new(name: &str) -> Result<Self, ShareError> {
+        let library = load_library("the NDI runtime", &library_candidates())?;
+        // Safety: the signatures match Processing.NDI.Lib.h (v5/v6)
+        let (initialize, create, send_video, destroy) = unsafe {
+            (
+                symbol::<InitializeFn>(&library, b"NDIlib_initialize\0")?,
+                symbol::<SendCreateFn>(&library, b"NDIlib_send_create\0")?,
+                symbol::<SendVideoFn>(&library, b"NDIlib_send_send_video_v2\0")?,
+                symbol::<SendDestroyFn>(&library, b"NDIlib_send_destroy\0")?,
+            )
+        };
+        // Safety: initialize has no preconditions and may be called repeatedly
+        if !unsafe { initialize() } {
+            return Err(ShareError::Unsupported(
+                "NDI doesn't support this CPU".to_string(),
+            ));
+        }
+        let name =
+            CString::new(name.replace('\0', "")).map_err(|e| ShareError::Sender(e.to_string()))?;
+        let settings = SendCreate {
+            p_ndi_name: name.as_ptr(),
+            p_groups: std::ptr::null(),
+            // Frames go out as the renderer produces them
+            clock_video: false,
+            clock_audio: false,
+        };
+        // Safety: settings and the name it points to outlive the call; NDI
+        // copies them
+        let instance = unsafe { create(&settings) };
+        if instance.is_null() {
+            return Err(ShareError::Sender(
+                "NDI couldn't create a sender".to_string(),
+            ));
+        }
+        Ok(Self {
+            instance,
+            send_video,
+            destroy,
+            _library: library,
+        })
+    }
+}
+
+impl ShareBackend for NdiSender {
+    fn kind(&self) -> BackendKind {
+        BackendKind::Ndi
+    }
+
+    fn send(&mut self, frame: &VideoFrame) -> Result<(), ShareError> {
+        if frame.data.len() != frame.width as usize * frame.height as usize * 4 {
+            return Err(ShareError::Sender("malformed frame".to_string()));
+        }
+        let video = VideoFrameV2 {
+            xres: frame.width as c_int,
+            yres: frame.height as c_int,
+            four_cc: FOURCC_RGBA,
+            // Nominal; unclocked senders are timed by arrival
+            frame_rate_n: 30_000,
+            frame_rate_d: 1_000,
+            picture_aspect_ratio: frame.width as f32 / frame.height.max(1) as f32,
+            frame_format_type: FRAME_FORMAT_PROGRESSIVE,
+            timecode: TIMECODE_SYNTHESIZE,
+            p_data: frame.data.as_ptr(),
+            line_stride_in_bytes: (frame.width * 4) as c_int,
+            p_metadata: std::ptr::null(),
+            timestamp: 0,
+        };
+        // Safety: the synchronous send has finished with the pixels when it
+        // returns, and `instance` is live until drop
+        unsafe { (self.send_video)(self.instance, &video) };
+        Ok(())
+    }
+}
+
+impl Drop for NdiSender {
+    fn drop(&mut self) {
+        // Safety: created in `new`, destroyed exactly once
+        unsafe { (self.destroy)(self.instance) };
+    }
+}
@@ -0,0 +1,443 @@
+use async_trait::async_trait;
+use magnolia_core::{
+    ControlSignal, DataType, ModuleSchema, Port, PortDirection, Signal, Sink, WorkerSettings,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+use crate::backend::{self, BackendKind, ShareBackend, ShareError};
+use crate::frame::VideoFrame;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShareConfig {
+    pub enabled: bool,
+    pub backend: BackendKind,
+    /// Source name receivers list
+    pub name: String,
+    /// Tile id to share; empty shares the whole compositor
+    pub tile: String,
+    pub max_fps: u32,
+}
+
+impl Default for ShareConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            backend: BackendKind::Ndi,
+            name: "Magnolia".to_string(),
+            tile: String::new(),
+            max_fps: 30,
+        }
+    }
+}
+
+impl ShareConfig {
+    pub fn schema() -> serde_json::Value {
+        let backends: Vec<_> = BackendKind::ALL
+            .iter()
+            .map(|kind| serde_json::to_value(kind).unwrap_or_default())
+            .collect();
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "enabled": { "type": "boolean", "title": "Sharing", "default": false },
+                "backend": {
+                    "type": "string",
+                    "title": "Backend",
+                    "enum": backends,
+                    "default": "ndi"
+                },
+                "name": { "type": "string", "title": "Source Name", "default": "Magnolia" },
+                "tile": {
+                    "type": "string",
+                    "title": "Tile (empty: whole screen)",
+                    "default": ""
+                },
+                "max_fps": {
+                    "type": "integer",
+                    "title": "Max FPS",
+                    "minimum": 1,
+                    "maximum": 120,
+                    "default": 30
+                }
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShareStatus {
+    Off,
+    Sharing { backend: BackendKind, name: String },
+    Failed(String),
+}
+
+/// What the renderer should read back for the next frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CaptureTarget {
+    Compositor,
+    Tile(String),
+}
+
+type Opener = Box<dyn Fn(BackendKind, &str) -> Result<Box<dyn ShareBackend>, ShareError> + Send>;
+
+struct Inner {
+    config: WorkerSettings<ShareConfig>,
+    status: Mutex<ShareStatus>,
+    /// Latest captured frame not yet sent; a slow backend drops frames
+    /// rather than queueing them
+    pending: Mutex<Option<VideoFrame>>,
+    wake: Condvar,
+    last_capture: Mutex<Option<Instant>>,
+    frames_sent: AtomicU64,
+}
+
+/// Handle shared by the renderer, which captures frames, and the sink,
+/// which owns the settings. Publishing runs on its own thread so a blocking
+/// backend never stalls a frame.
+#[derive(Clone)]
+pub struct VideoShare {
+    inner: Arc<Inner>,
+}
+
+impl VideoShare {
+    pub fn start(config: ShareConfig) -> std::io::Result<Self> {
+        Self::with_opener(config, Box::new(backend::open))
+    }
+
+    pub(crate) fn with_opener(config: ShareConfig, opener: Opener) -> std::io::Result<Self> {
+        let inner = Arc::new(Inner {
+            config: WorkerSettings::new(config),
+            status: Mutex::new(ShareStatus::Off),
+            pending: Mutex::new(None),
+            wake: Condvar::new(),
+            last_capture: Mutex::new(None),
+            frames_sent: AtomicU64::new(0),
+        });
+        let worker = Arc::downgrade(&inner);
+        std::thread::Builder::new()
+            .name("video-share".to_string())
+            .spawn(move || run(worker, opener))?;
+        Ok(Self { inner })
+    }
+
+    pub fn config(&self) -> ShareConfig {
+        self.inner.config.get()
+    }
+
+    pub fn set_config(&self, config: ShareConfig) {
+        self.inner.config.set(config);
+        // A new target or rate captures straight away
+        if let Ok(mut last) = self.inner.last_capture.lock() {
+            *last = None;
+        }
+        self.inner.wake.notify_all();
+    }
+
+    pub fn status(&self) -> ShareStatus {
+        self.inner
+            .status
+            .lock()
+            .map(|s| s.clone())
+            .unwrap_or(ShareStatus::Off)
+    }
+
+    pub fn frames_sent(&self) -> u64 {
+        self.inner.frames_sent.load(Ordering::Relaxed)
+    }
+
+    /// Whether to capture this frame, and of what. Call once per rendered
+    /// frame; it paces captures to `max_fps` and skips them while sharing is
+    /// off, failed, or the previous frame hasn't gone out yet.
+    pub fn capture_target(&self) -> Option<CaptureTarget> {
+        if !matches!(self.status(), ShareStatus::Sharing { .. }) {
+            return None;
+        }
+        if self.inner.pending.lock().ok()?.is_some() {
+            return None;
+        }
+        let config = self.config();
+        let interval = Duration::from_secs_f64(1.0 / config.max_fps.max(1) as f64);
+        let mut last = self.inner.last_capture.lock().ok()?;
+        let now = Instant::now();
+        if last.is_some_and(|last| now.duration_since(last) < interval) {
+            return None;
+        }
+        *last = Some(now);
+        Some(match config.tile.trim() {
+            "" => CaptureTarget::Compositor,
+            tile => CaptureTarget::Tile(tile.to_string()),
+        })
+    }
+
+    /// Hand a captured frame to the publishing thread
+    pub fn submit(&self, frame: VideoFrame) {
+        if let Ok(mut pending) = self.inner.pending.lock() {
+            *pending = Some(frame);
+        }
+        self.inner.wake.notify_all();
+    }
+}
+
+fn set_status(inner: &Inner, status: ShareStatus) {
+    if let Ok(mut current) = inner.status.lock() {
+        *current = status;
+    }
+}
+
+fn run(inner: Weak<Inner>, opener: Opener) {
+    let mut backend: Option<Box<dyn ShareBackend>> = None;
+    let mut opened_generation = None;
+    // Ends once every handle is gone
+    while let Some(inner) = inner.upgrade() {
+        let generation = inner.config.generation();
+        if opened_generation != Some(generation) {
+            opened_generation = Some(generation);
+            // Close the old source before publishing a new one
+            backend = None;
+            let config = inner.config.get();
+            if !config.enabled {
+                set_status(&inner, ShareStatus::Off);
+            } else {
+                match opener(config.backend, &config.name) {
+                    Ok(opened) => {
+                        log::info!(
+                            "Video share: publishing {} source {:?}",
+                            config.backend.label(),
+                            config.name
+                        );
+                        backend = Some(opened);
+                        set_status(
+                            &inner,
+                            ShareStatus::Sharing {
+                                backend: config.backend,
+                                name: config.name,
+                            },
+                        );
+                    }
+                    Err(e) => {
+                        log::warn!("Video share: {}", e);
+                        set_status(&inner, ShareStatus::Failed(e.to_string()));
+                    }
+                }
+            }
+        }
+
+        let frame = match inner.pending.lock() {
+            Ok(pending) => inner
+                .wake
+                .wait_timeout_while(pending, Duration::from_millis(250), |pending| {
+                    pending.is_none() && !inner.config.changed_since(generation)
+                })
+                .ok()
+                .and_then(|(mut pending, _)| pending.take()),
+            Err(_) => return,
+        };
+        let (Some(frame), Some(sender)) = (frame, backend.as_mut()) else {
+            continue;
+        };
+        match sender.send(&frame) {
+            Ok(()) => {
+                inner.frames_sent.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(e) => {
+                log::warn!("Video share: {}", e);
+                backend = None;
+                set_status(&inner, ShareStatus::Failed(e.to_string()));
+            }
+        }
+    }
+}
+
+/// Controls the video share: its settings tile, plus `share start|stop|toggle`
+/// intents (or the same as text) on `control_in`
+pub struct VideoShareSink {
+    id: String,
+    enabled: bool,
+    share: VideoShare,
+}
+
+impl VideoShareSink {
+    pub fn new(id: &str, share: VideoShare) -> Self {
+        Self {
+            id: id.to_string(),
+            enabled: true,
+            share,
+        }
+    }
+
+    fn command(&self, action: &str, parameters: &[String]) {
+        if !action.eq_ignore_ascii_case("share") {
+            return;
+        }
+        let mut config = self.share.config();
+        let enabled = match parameters.first().map(String::as_str) {
+            Some("start") | Some("on") => true,
+            Some("stop") | Some("off") => false,
+            Some("toggle") | None => !config.enabled,
+            Some(other) => {
+                log::debug!("Video share: unknown command {:?}", other);
+                return;
+            }
+        };
+        if enabled != config.enabled {
+            config.enabled = enabled;
+            self.share.set_config(config);
+        }
+    }
+}
+
+#[async_trait]
+impl Sink for VideoShareSink {
+    fn name(&self) -> &str {
+        "Video Share"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "Video Share".to_string(),
+            description: "Publishes the screen or one tile as an NDI source".to_string(),
+            ports: vec![Port {
+                id: "control_in".to_string(),
+                label: "Control".to_string(),
+                data_type: DataType::Control,
+                direction: PortDirection::Input,
+            }],
+            settings_schema: Some(ShareConfig::schema()),
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    fn render_output(&self) -> Option<String> {
+        Some(match self.share.status() {
+            ShareStatus::Off => "Video share: off".to_string(),
+            ShareStatus::Sharing { backend, name } => {
+                let config = self.share.config();
+                let what = match config.tile.trim() {
+                    "" => "screen",
+                    tile => tile,
+                };
+                format!(
+                    "{}: {} ({}) · {} frames",
+                    backend.label(),
+                    name,
+                    what,
+                    self.share.frames_sent()
+                )
+            }
+            ShareStatus::Failed(e) => format!("Video share: {}", e),
+        })
+    }
+
+    async fn consume(&self, signal: Signal) -> magnolia_core::Result<Option<Signal>> {
+        match signal {
+            Signal::Control(ControlSignal::Settings(settings)) => {
+                match serde_json::from_value::<ShareConfig>(settings) {
+                    Ok(config) => self.share.set_config(config),
+                    Err(e) => log::warn!("Video share: invalid settings, keeping current: {}", e),
+                }
+            }
+            _ if !self.enabled => {}
+            Signal::Intent { action, parameters } => self.command(&action, &parameters),
+            Signal::Text(text) => {
+                let words: Vec<String> = text.split_whitespace().map(str::to_string).collect();
+                if let Some((action, parameters)) = words.split_first() {
+                    self.command(action, parameters);
+                }
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FakeBackend(Arc<Mutex<Vec<(u32, u32)>>>);
+
+    impl ShareBackend for FakeBackend {
+        fn kind(&self) -> BackendKind {
+            BackendKind::Ndi
+        }
+
+        fn send(&mut self, frame: &VideoFrame) -> Result<(), ShareError> {
+            self.0.lock().unwrap().push((frame.width, frame.height));
+            Ok(())
+        }
+    }
+
+    fn wait_for(mut condition: impl FnMut() -> bool) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !condition() {
+            assert!(Instant::now() < deadline, "timed out");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[tokio::test]
+    async fn shares_paced_frames_while_enabled() {
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let backend_frames = sent.clone();
+        let opener: Opener = Box::new(move |kind, _name| match kind {
+            BackendKind::Ndi => Ok(Box::new(FakeBackend(backend_frames.clone()))),
+            _ => Err(ShareError::Unsupported("not here".into())),
+        });
+        let share = VideoShare::with_opener(ShareConfig::default(), opener).unwrap();
+        let sink = VideoShareSink::new("video_share", share.clone());
+        assert_eq!(share.capture_target(), None);
+
+        sink.consume(Signal::Intent {
+            action: "share".into(),
+            parameters: vec!["start".into()],
+        })
+        .await
+        .unwrap();
+        wait_for(|| matches!(share.status(), ShareStatus::Sharing { .. }));
+        assert_eq!(share.capture_target(), Some(CaptureTarget::Compositor));
+        // Paced to max_fps
+        assert_eq!(share.capture_target(), None);
+
+        share.submit(VideoFrame::new(2, 1, vec![0; 8]).unwrap());
+        wait_for(|| share.frames_sent() == 1);
+        assert_eq!(*sent.lock().unwrap(), vec![(2, 1)]);
+        assert!(sink.render_output().unwrap().contains("1 frames"));
+
+        let settings =
+            serde_json::json!({ "enabled": true, "backend": "syphon", "tile": "canvas" });
+        sink.consume(Signal::Control(ControlSignal::Settings(settings)))
+            .await
+            .unwrap();
+        wait_for(|| matches!(share.status(), ShareStatus::Failed(_)));
+        assert_eq!(share.capture_target(), None);
+
+        let settings = serde_json::json!({ "enabled": true, "tile": "canvas", "max_fps": 120 });
+        sink.consume(Signal::Control(ControlSignal::Settings(settings)))
+            .await
+            .unwrap();
+        wait_for(|| matches!(share.status(), ShareStatus::Sharing { .. }));
+        assert_eq!(
+            share.capture_target(),
+            Some(CaptureTarget::Tile("canvas".into()))
+        );
+
+        sink.consume(Signal::Text("share off".into()))
+            .await
+            .unwrap();
+        wait_for(|| share.status() == ShareStatus::Off);
+        assert!(!share.config().enabled);
+    }
+}
@@ -0,0 +1,16 @@
+//! Weather source: polls a weather provider for current conditions.
+//!
+//! Emits the conditions as JSON `Computed` signals on `conditions_out` and the
+//! temperature on the numeric `temperature_out` port.
+
+pub mod provider;
+mod settings;
+mod source;
+#[cfg(feature = "tile-rendering")]
+mod tile;
+
+pub use provider::{Conditions, Units, WeatherProvider};
+pub use settings::{WeatherConfig, WeatherSettings, MIN_POLL_INTERVAL_SECS};
+pub use source::{conditions_signals, WeatherSource, CONDITIONS_SOURCE, TEMPERATURE_SOURCE};
+#[cfg(feature = "tile-rendering")]
+pub use tile::WeatherTile;
@@ -0,0 +1,224 @@
+//! Weather providers: request building and response parsing.
+//!
+//! Only Open-Meteo is supported for now. It needs no API key and accepts a
+//! custom endpoint, so a self-hosted instance works too.
+
+use std::time::Duration;
+
+use anyhow::{anyhow, Context};
+use serde::{Deserialize, Serialize};
+
+use crate::settings::WeatherConfig;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+const OPEN_METEO_FIELDS: &str = "temperature_2m,apparent_temperature,relative_humidity_2m,\
+precipitation,cloud_cover,wind_speed_10m,wind_direction_10m,weather_code,is_day";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum WeatherProvider {
+    #[default]
+    OpenMeteo,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Units {
+    #[default]
+    Metric,
+    Imperial,
+}
+
+impl Units {
+    pub fn temperature_label(&self) -> &'static str {
+        match self {
+            Units::Metric => "°C",
+            Units::Imperial => "°F",
+        }
+    }
+
+    pub fn wind_label(&self) -> &'static str {
+        match self {
+            Units::Metric => "km/h",
+            Units::Imperial => "mph",
+        }
+    }
+
+    pub fn precipitation_label(&self) -> &'static str {
+        match self {
+            Units::Metric => "mm",
+            Units::Imperial => "in",
+        }
+    }
+}
+
+/// Current conditions, in the units the request asked for.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Conditions {
+    /// Provider timestamp of the observation (ISO 8601, local to the location)
+    pub observed_at: String,
+    pub temperature: f64,
+    pub apparent_temperature: f64,
+    /// Relative humidity, 0..100 %
+    pub humidity: f64,
+    pub precipitation: f64,
+    /// Cloud cover, 0..100 %
+    pub cloud_cover: f64,
+    pub wind_speed: f64,
+    /// Degrees clockwise from north the wind blows from
+    pub wind_direction: f64,
+    /// WMO weather interpretation code
+    pub weather_code: u32,
+    pub summary: String,
+    pub is_day: bool,
+    pub units: Units,
+}
+
+/// Short description of a WMO weather interpretation code.
+pub fn describe_weather_code(code: u32) -> &'static str {
+    match code {
+        0 => "Clear sky",
+        1 => "Mainly clear",
+        2 => "Partly cloudy",
+        3 => "Overcast",
+        45 | 48 => "Fog",
+        51 | 53 | 55 => "Drizzle",
+        56 | 57 => "Freezing drizzle",
+        61 | 63 | 65 => "Rain",
+        66 | 67 => "Freezing rain",
+        71 | 73 | 75 | 77 => "Snow",
+        80..=82 => "Rain showers",
+        85 | 86 => "Snow showers",
+        95 => "Thunderstorm",
+        96 | 99 => "Thunderstorm with hail",
+        _ => "Unknown",
+    }
+}
+
+/// Full request URL for the configured provider and location.
+pub fn request_url(config: &WeatherConfig) -> String {
+    match config.provider {
+        WeatherProvider::OpenMeteo => {
+            let mut url = format!(
+                "{}?latitude={:.4}&longitude={:.4}&current={}&timezone=auto",
+                config.endpoint.trim_end_matches('/'),
+                config.latitude,
+                config.longitude,
+                OPEN_METEO_FIELDS
+            );
+            if config.units == Units::Imperial {
+                url.push_str(
+                    "&temperature_unit=fahrenheit&wind_speed_unit=mph&precipitation_unit=inch",
+                );
+            }
+            url
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct OpenMeteoResponse {
+    current: OpenMeteoCurrent,
+}
+
+#[derive(Deserialize)]
+struct OpenMeteoCurrent {
+    time: String,
+    temperature_2m: f64,
+    apparent_temperature: f64,
+    relative_humidity_2m: f64,
+    precipitation: f64,
+    cloud_cover: f64,
+    wind_speed_10m: f64,
+    wind_direction_10m: f64,
+    weather_code: u32,
+    is_day: u8,
+}
+
+/// Parse an Open-Meteo `current` response body.
+pub fn parse_open_meteo(body: &str, units: Units) -> anyhow::Result<Conditions> {
+    let response: OpenMeteoResponse =
+        serde_json::from_str(body).context("Unexpected Open-Meteo response")?;
+    let current = response.current;
+    Ok(Conditions {
+        observed_at: current.time,
+        temperature: current.temperature_2m,
+        apparent_temperature: current.apparent_temperature,
+        humidity: current.relative_humidity_2m,
+        precipitation: current.precipitation,
+        cloud_cover: current.cloud_cover,
+        wind_speed: current.wind_speed_10m,
+        wind_direction: current.wind_direction_10m,
+        weather_code: current.weather_code,
+        summary: describe_weather_code(current.weather_code).to_string(),
+        is_day: current.is_day != 0,
+        units,
+    })
+}
+
+/// Fetch current conditions. Blocking; run it off the async executor.
+pub fn fetch(config: &WeatherConfig) -> anyhow::Result<Conditions> {
+    let url = request_url(config);
+    let response = ureq::get(&url)
+        .timeout(REQUEST_TIMEOUT)
+        .call()
+        .map_err(|e| anyhow!("Weather request failed: {}", e))?;
+    let body = response
+        .into_string()
+        .context("Failed to read weather response")?;
+    match config.provider {
+        WeatherProvider::OpenMeteo => parse_open_meteo(&body, config.units),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"{
+        "latitude": 51.5,
+        "longitude": 0.0,
+        "current_units": {"temperature_2m": "°C"},
+        "current": {
+            "time": "2024-03-20T12:00",
+            "interval": 900,
+            "temperature_2m": 11.4,
+            "apparent_temperature": 9.8,
+            "relative_humidity_2m": 71,
+            "precipitation": 0.2,
+            "cloud_cover": 88,
+            "wind_speed_10m": 14.3,
+            "wind_direction_10m": 225,
+            "weather_code": 61,
+            "is_day": 1
+        }
+    }"#;
+
+    #[test]
+    fn parses_open_meteo_current_block() {
+        let conditions = parse_open_meteo(SAMPLE, Units::Metric).unwrap();
+        assert_eq!(conditions.observed_at, "2024-03-20T12:00");
+        assert_eq!(conditions.temperature, 11.4);
+        assert_eq!(conditions.humidity, 71.0);
+        assert_eq!(conditions.summary, "Rain");
+        assert!(conditions.is_day);
+
+        assert!(parse_open_meteo(r#"{"error": true}"#, Units::Metric).is_err());
+    }
+
+    #[test]
+    fn request_url_carries_location_and_units() {
+        let mut config = WeatherConfig {
+            latitude: 40.7128,
+            longitude: -74.006,
+            endpoint: "https://example.test/v1/forecast/".to_string(),
+            ..WeatherConfig::default()
+        };
+        let url = request_url(&config);
+        assert!(url.starts_with("https://example.test/v1/forecast?latitude=40.7128"));
+        assert!(url.contains("&longitude=-74.0060"));
+        assert!(!url.contains("fahrenheit"));
+
+        config.units = Units::Imperial;
+        assert!(request_url(&config).contains("temperature_unit=fahrenheit"));
+    }
+}
@@ -0,0 +1,142 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use crate::provider::{Conditions, Units, WeatherProvider};
+
+/// Open-Meteo asks for no more than one request per minute per client.
+pub const MIN_POLL_INTERVAL_SECS: u64 = 60;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WeatherConfig {
+    pub provider: WeatherProvider,
+    pub endpoint: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub units: Units,
+    pub poll_interval_secs: u64,
+}
+
+impl Default for WeatherConfig {
+    fn default() -> Self {
+        Self {
+            provider: WeatherProvider::OpenMeteo,
+            endpoint: "https://api.open-meteo.com/v1/forecast".to_string(),
+            // Greenwich, matching the clock and astro defaults
+            latitude: 51.48,
+            longitude: 0.0,
+            units: Units::Metric,
+            poll_interval_secs: 900,
+        }
+    }
+}
+
+impl WeatherConfig {
+    pub fn poll_interval_secs(&self) -> u64 {
+        self.poll_interval_secs.max(MIN_POLL_INTERVAL_SECS)
+    }
+
+    pub fn schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "provider": {
+                    "type": "string",
+                    "title": "Provider",
+                    "enum": ["OpenMeteo"],
+                    "default": "OpenMeteo"
+                },
+                "endpoint": {
+                    "type": "string",
+                    "title": "Endpoint",
+                    "default": "https://api.open-meteo.com/v1/forecast"
+                },
+                "latitude": {
+                    "type": "number",
+                    "title": "Latitude",
+                    "minimum": -90.0,
+                    "maximum": 90.0,
+                    "default": 51.48
+                },
+                "longitude": {
+                    "type": "number",
+                    "title": "Longitude",
+                    "minimum": -180.0,
+                    "maximum": 180.0,
+                    "default": 0.0
+                },
+                "units": {
+                    "type": "string",
+                    "title": "Units",
+                    "enum": ["Metric", "Imperial"],
+                    "default": "Metric"
+                },
+                "poll_interval_secs": {
+                    "type": "integer",
+                    "title": "Poll Interval (s)",
+                    "minimum": MIN_POLL_INTERVAL_SECS,
+                    "maximum": 86400,
+                    "multipleOf": 60,
+                    "default": 900
+                }
+            }
+        })
+    }
+}
+
+/// State shared between the weather source and its tile.
+#[derive(Default)]
+pub struct WeatherSettings {
+    config: Mutex<WeatherConfig>,
+    pending: AtomicBool,
+    conditions: Mutex<Option<Conditions>>,
+    last_error: Mutex<Option<String>>,
+}
+
+impl WeatherSettings {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    pub fn config(&self) -> WeatherConfig {
+        self.config.lock().map(|c| c.clone()).unwrap_or_default()
+    }
+
+    /// Replace the config; the source refetches on its next poll.
+    pub fn set_config(&self, config: WeatherConfig) {
+        if let Ok(mut current) = self.config.lock() {
+            *current = config;
+        }
+        self.request_refresh();
+    }
+
+    pub fn request_refresh(&self) {
+        self.pending.store(true, Ordering::Relaxed);
+    }
+
+    pub fn take_pending(&self) -> bool {
+        self.pending.swap(false, Ordering::Relaxed)
+    }
+
+    pub fn set_conditions(&self, conditions: Conditions) {
+        if let Ok(mut current) = self.conditions.lock() {
+            *current = Some(conditions);
+        }
+    }
+
+    pub fn conditions(&self) -> Option<Conditions> {
+        self.conditions.lock().ok().and_then(|c| c.clone())
+    }
+
+    pub fn set_last_error(&self, err: Option<String>) {
+        if let Ok(mut e) = self.last_error.lock() {
+            *e = err;
+        }
+    }
+
+    pub fn last_error(&self) -> Option<String> {
+        self.last_error.lock().ok().and_then(|e| e.clone())
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::time::Instant;
+
+use crate::provider::{self, Conditions};
+use crate::WeatherSettings;
+use magnolia_core::{DataType, ModuleSchema, Port, PortDirection, Signal, Source};
+
+/// How often poll() wakes to check for refresh requests and config changes.
+const TICK: Duration = Duration::from_millis(500);
+/// Back-off after a failed request.
+const RETRY_DELAY: Duration = Duration::from_secs(60);
+
+pub const CONDITIONS_SOURCE: &str = "weather";
+pub const TEMPERATURE_SOURCE: &str = "weather_temperature";
+
+/// Signals emitted for one observation: the full conditions as JSON on
+/// `conditions_out`, and the bare temperature on `temperature_out`.
+pub fn conditions_signals(conditions: &Conditions) -> Vec<Signal> {
+    let mut signals = Vec::with_capacity(2);
+    match serde_json::to_string(conditions) {
+        Ok(content) => signals.push(Signal::Computed {
+            source: CONDITIONS_SOURCE.to_string(),
+            content,
+        }),
+        Err(e) => log::warn!("WeatherSource: failed to encode conditions: {}", e),
+    }
+    signals.push(Signal::Computed {
+        source: TEMPERATURE_SOURCE.to_string(),
+        content: format!("{:.1}", conditions.temperature),
+    });
+    signals
+}
+
+/// Polls the configured weather provider for current conditions.
+pub struct WeatherSource {
+    id: String,
+    enabled: bool,
+    settings: Arc<WeatherSettings>,
+    pending: VecDeque<Signal>,
+    next_fetch: Option<Instant>,
+}
+
+impl WeatherSource {
+    pub fn new(id: &str, settings: Arc<WeatherSettings>) -> Self {
+        Self {
+            id: id.to_string(),
+            enabled: true,
+            settings,
+            pending: VecDeque::new(),
+            next_fetch: None,
+        }
+    }
+
+    async fn refresh(&mut self) {
+        let config = self.settings.config();
+        let interval = Duration::from_secs(config.poll_interval_secs());
+        let result = tokio::task::spawn_blocking(move || provider::fetch(&config)).await;
+
+        let now = Instant::now();
+        match result {
+            Ok(Ok(conditions)) => {
+                self.pending.extend(conditions_signals(&conditions));
+                self.settings.set_conditions(conditions);
+                self.settings.set_last_error(None);
+                self.next_fetch = Some(now + interval);
+            }
+            Ok(Err(e)) => {
+                log::warn!("WeatherSource: {}", e);
+                self.settings.set_last_error(Some(e.to_string()));
+                self.next_fetch = Some(now + RETRY_DELAY.min(interval));
+            }
+            Err(e) => {
+                log::error!("WeatherSource: fetch task failed: {}", e);
+                self.settings.set_last_error(Some(e.to_string()));
+                self.next_fetch = Some(now + RETRY_DELAY);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Source for WeatherSource {
+    fn name(&self) -> &str {
+        "Weather"
+    }
+
+    fn schema(&self) -> ModuleSchema {
+        ModuleSchema {
+            id: self.id.clone(),
+            name: "Weather".to_string(),
+            description: "Current weather conditions from Open-Meteo".to_string(),
+            ports: vec![
+                Port {
+                    id: "conditions_out".to_string(),
+                    label: "Conditions".to_string(),
+                    data_type: DataType::Text,
+                    direction: PortDirection::Output,
+                },
+                Port {
+                    id: "temperature_out".to_string(),
+                    label: "Temperature".to_string(),
+                    data_type: DataType::Numeric,
+                    direction: PortDirection::Output,
+                },
+                Port {
+                    id: "refresh_in".to_string(),
+                    label: "Refresh".to_string(),
+                    data_type: DataType::Control,
+                    direction: PortDirection::Input,
+                },
+            ],
+            settings_schema: Some(crate::WeatherConfig::schema()),
+            docs: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    async fn poll(&mut self) -> Option<Signal> {
+        loop {
+            if let Some(signal) = self.pending.pop_front() {
+                return Some(signal);
+            }
+
+            let refresh = self.settings.take_pending();
+            let due = self.next_fetch.map_or(true, |next| Instant::now() >= next);
+            if self.enabled && (refresh || due) {
+                self.refresh().await;
+                continue;
+            }
+
+            tokio::time::sleep(TICK).await;
+        }
+    }
+
+    fn handle_signal(&mut self, signal: Signal) {
+        // Any trigger on refresh_in forces a fetch
+        match signal {
+            Signal::Pulse | Signal::Intent { .. } | Signal::Control(_) => {
+                self.settings.request_refresh()
+            }
+            _ => {}
+        }
+    }
+
+    fn output_port(&self, signal: &Signal) -> Option<&str> {
+        match signal {
+            Signal::Computed { source, .. } if source == TEMPERATURE_SOURCE => {
+                Some("temperature_out")
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::Units;
+
+    fn sample() -> Conditions {
+        Conditions {
+            observed_at: "2024-03-20T12:00".to_string(),
+            temperature: 11.44,
+            apparent_temperature: 9.8,
+            humidity: 71.0,
+            precipitation: 0.0,
+            cloud_cover: 20.0,
+            wind_speed: 8.0,
+            wind_direction: 180.0,
+            weather_code: 1,
+            summary: "Mainly clear".to_string(),
+            is_day: true,
+            units: Units::Metric,
+        }
+    }
+
+    #[test]
+    fn conditions_and_temperature_leave_from_their_own_ports() {
+        let source = WeatherSource::new("weather", WeatherSettings::new());
+        let signals = conditions_signals(&sample());
+        assert_eq!(signals.len(), 2);
+
+        match &signals[0] {
+            Signal::Computed {
+                source: name,
+                content,
+            } => {
+                assert_eq!(name, CONDITIONS_SOURCE);
+                let decoded: Conditions = serde_json::from_str(content).unwrap();
+                assert_eq!(decoded, sample());
+            }
+            other => panic!("unexpected signal {:?}", other),
+        }
+        assert_eq!(source.output_port(&signals[0]), None);
+
+        match &signals[1] {
+            Signal::Computed { content, .. } => assert_eq!(content, "11.4"),
+            other => panic!("unexpected signal {:?}", other),
+        }
+        assert_eq!(source.output_port(&signals[1]), Some("temperature_out"));
+    }
+
+    #[test]
+    fn refresh_input_requests_a_fetch() {
+        let settings = WeatherSettings::new();
+        let mut source = WeatherSource::new("weather", settings.clone());
+        source.handle_signal(Signal::Text("ignored".to_string()));
+        assert!(!settings.take_pending());
+        source.handle_signal(Signal::Pulse);
+        assert!(settings.take_pending());
+    }
+}
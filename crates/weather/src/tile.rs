@@ -0,0 +1,252 @@
+//! Weather Tile
+//!
+//! Monitor mode shows the current temperature and summary; the maximized
+//! view lists every field of the last observation.
+
+use std::sync::Arc;
+
+use magnolia_core::{BindableAction, RenderContext, TileError, TileRenderer};
+use magnolia_ui::{draw_text, FontId, TextAlignment};
+use nannou::prelude::*;
+
+use crate::{Conditions, WeatherConfig, WeatherSettings};
+
+pub struct WeatherTile {
+    id: String,
+    settings: Arc<WeatherSettings>,
+    conditions: Option<Conditions>,
+}
+
+impl WeatherTile {
+    pub fn new(id: &str, settings: Arc<WeatherSettings>) -> Self {
+        Self {
+            id: id.to_string(),
+            settings,
+            conditions: None,
+        }
+    }
+
+    fn temperature_text(conditions: &Conditions) -> String {
+        format!(
+            "{:.0}{}",
+            conditions.temperature,
+            conditions.units.temperature_label()
+        )
+    }
+}
+
+impl TileRenderer for WeatherTile {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn name(&self) -> &str {
+        "Weather"
+    }
+
+    fn update(&mut self) {
+        self.conditions = self.settings.conditions();
+    }
+
+    fn render_monitor(&self, draw: &Draw, rect: Rect, _ctx: &RenderContext) {
+        draw.rect()
+            .xy(rect.xy())
+            .wh(rect.wh())
+            .color(srgba(0.03, 0.04, 0.07, 0.95));
+
+        draw_text(
+            draw,
+            FontId::PlexSansBold,
+            "WEATHER",
+            pt2(rect.x(), rect.top() - 18.0),
+            12.0,
+            srgba(0.6, 0.8, 0.9, 1.0),
+            TextAlignment::Center,
+        );
+
+        let Some(conditions) = &self.conditions else {
+            draw_text(
+                draw,
+                FontId::PlexSansRegular,
+                "Waiting for data...",
+                rect.xy(),
+                12.0,
+                srgba(0.5, 0.5, 0.55, 1.0),
+                TextAlignment::Center,
+            );
+            return;
+        };
+
+        let font_size = (rect.h() * 0.3).min(rect.w() / 4.0).min(72.0);
+        draw_text(
+            draw,
+            FontId::PlexMonoRegular,
+            &Self::temperature_text(conditions),
+            pt2(rect.x(), rect.y() + font_size * 0.2),
+            font_size,
+            srgba(1.0, 0.85, 0.5, 1.0),
+            TextAlignment::Center,
+        );
+        draw_text(
+            draw,
+            FontId::PlexSansRegular,
+            &conditions.summary,
+            pt2(rect.x(), rect.y() - font_size * 0.6),
+            13.0,
+            srgba(0.8, 0.8, 0.85, 1.0),
+            TextAlignment::Center,
+        );
+        draw_text(
+            draw,
+            FontId::PlexSansRegular,
+            &format!(
+                "Feels {:.0}{}  Humidity {:.0}%  Wind {:.0} {}",
+                conditions.apparent_temperature,
+                conditions.units.temperature_label(),
+                conditions.humidity,
+                conditions.wind_speed,
+                conditions.units.wind_label()
+            ),
+            pt2(rect.x(), rect.bottom() + 16.0),
+            10.0,
+            srgba(0.55, 0.55, 0.65, 1.0),
+            TextAlignment::Center,
+        );
+    }
+
+    fn render_controls(&self, draw: &Draw, rect: Rect, _ctx: &RenderContext) -> bool {
+        draw.rect()
+            .xy(rect.xy())
+            .wh(rect.wh())
+            .color(srgba(0.02, 0.02, 0.05, 0.98));
+
+        draw_text(
+            draw,
+            FontId::PlexSansBold,
+            "WEATHER",
+            pt2(rect.x(), rect.top() - 30.0),
+            18.0,
+            srgba(0.0, 1.0, 1.0, 1.0),
+            TextAlignment::Center,
+        );
+        draw_text(
+            draw,
+            FontId::PlexSansRegular,
+            "[R] Refresh now",
+            pt2(rect.x(), rect.top() - 55.0),
+            12.0,
+            srgba(0.5, 0.5, 0.55, 1.0),
+            TextAlignment::Center,
+        );
+
+        let config = self.settings.config();
+        let mut lines = vec![format!(
+            "Location: {:.4}, {:.4}  every {} s",
+            config.latitude,
+            config.longitude,
+            config.poll_interval_secs()
+        )];
+        if let Some(c) = &self.conditions {
+            let units = c.units;
+            lines.extend([
+                format!("Observed: {}", c.observed_at),
+                format!("Conditions: {} (WMO {})", c.summary, c.weather_code),
+                format!("Temperature: {}", Self::temperature_text(c)),
+                format!(
+                    "Feels like: {:.1}{}",
+                    c.apparent_temperature,
+                    units.temperature_label()
+                ),
+                format!("Humidity: {:.0}%", c.humidity),
+                format!(
+                    "Precipitation: {:.1} {}",
+                    c.precipitation,
+                    units.precipitation_label()
+                ),
+                format!("Cloud cover: {:.0}%", c.cloud_cover),
+                format!(
+                    "Wind: {:.1} {} from {:.0}°",
+                    c.wind_speed,
+                    units.wind_label(),
+                    c.wind_direction
+                ),
+                format!("Daylight: {}", if c.is_day { "yes" } else { "no" }),
+            ]);
+        }
+
+        for (i, line) in lines.iter().enumerate() {
+            draw_text(
+                draw,
+                FontId::PlexMonoRegular,
+                line,
+                pt2(rect.left() + 20.0, rect.top() - 90.0 - i as f32 * 20.0),
+                12.0,
+                srgba(0.6, 0.7, 0.9, 1.0),
+                TextAlignment::Left,
+            );
+        }
+
+        false
+    }
+
+    fn handle_key(&mut self, key: nannou::prelude::Key, _ctrl: bool, _shift: bool) -> bool {
+        match key {
+            Key::R => {
+                self.settings.request_refresh();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn get_error(&self) -> Option<TileError> {
+        self.settings
+            .last_error()
+            .map(|e| TileError::new("Weather provider error").with_details(&e))
+    }
+
+    fn retry(&mut self) -> bool {
+        self.settings.set_last_error(None);
+        self.settings.request_refresh();
+        true
+    }
+
+    fn settings_schema(&self) -> Option<serde_json::Value> {
+        Some(WeatherConfig::schema())
+    }
+
+    fn apply_settings(&mut self, settings: &serde_json::Value) {
+        match serde_json::from_value::<WeatherConfig>(settings.clone()) {
+            Ok(config) => {
+                if config != self.settings.config() {
+                    self.settings.set_config(config);
+                }
+            }
+            Err(e) => log::warn!("Weather: invalid settings, keeping current: {}", e),
+        }
+    }
+
+    fn get_settings(&self) -> serde_json::Value {
+        serde_json::to_value(self.settings.config()).unwrap_or_default()
+    }
+
+    fn bindable_actions(&self) -> Vec<BindableAction> {
+        vec![BindableAction::new("refresh", "Refresh Weather", false)]
+    }
+
+    fn execute_action(&mut self, action: &str) -> bool {
+        match action {
+            "refresh" => {
+                self.settings.request_refresh();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn get_display_text(&self) -> Option<String> {
+        self.conditions
+            .as_ref()
+            .map(|c| format!("{} {}", Self::temperature_text(c), c.summary))
+    }
+}
@@ -0,0 +1,120 @@
+//! Read-only web dashboard served by the daemon.
+//!
+//! The daemon fills a [`Dashboard`] from the module host and patch bay a
+//! couple of times a second and hands it to a [`WebMonitor`], whose HTTP
+//! thread serves it to browsers on the network:
+//! - `/` — module list, patch graph, levels, recent transcript and screen
+//!   announcements, refreshing itself every few seconds
+//! - `/patches.svg` — the patch graph on its own
+//! - `/api/status` — everything as JSON
+
+mod page;
+mod server;
+
+pub use page::{render_html, render_patch_svg};
+pub use server::WebMonitor;
+
+use magnolia_core::{ModuleHost, ModuleState, PatchBay, RoutingMetricsSnapshot};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// One module as the dashboard shows it
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ModuleStatus {
+    pub id: String,
+    pub name: String,
+    /// `running`, `deferred`, `failed` ..; `idle` for modules with nothing
+    /// running behind them (tile-only entries)
+    pub state: String,
+    pub disabled: bool,
+    pub bypassed: bool,
+    pub muted: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PatchStatus {
+    pub id: String,
+    pub source_module: String,
+    pub source_port: String,
+    pub sink_module: String,
+    pub sink_port: String,
+    /// Recent messages per second through the patch
+    pub messages_per_sec: f64,
+}
+
+/// Everything the dashboard shows, as of one moment
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Dashboard {
+    /// By id
+    pub modules: Vec<ModuleStatus>,
+    pub patches: Vec<PatchStatus>,
+    /// Meter name to level, 0..1
+    pub levels: BTreeMap<String, f32>,
+    /// Recent transcript lines, newest last
+    pub transcript: Vec<String>,
+    /// What last changed on the daemon's screen, newest last
+    pub announcements: Vec<String>,
+    pub routing: RoutingMetricsSnapshot,
+    pub uptime_secs: u64,
+}
+
+fn state_label(state: ModuleState) -> &'static str {
+    match state {
+        ModuleState::Starting => "starting",
+        ModuleState::Running => "running",
+        ModuleState::Stopping => "stopping",
+        ModuleState::Stopped => "stopped",
+        ModuleState::Failed => "failed",
+    }
+}
+
+impl Dashboard {
+    /// Modules, patches and routing counters; levels, transcript,
+    /// announcements and uptime are left for the caller
+    pub fn collect(host: &ModuleHost, patch_bay: &PatchBay) -> Self {
+        let mut modules: Vec<ModuleStatus> = patch_bay
+            .get_modules()
+            .into_iter()
+            .map(|schema| {
+                let state = match host.module_state(&schema.id) {
+                    Some(state) => state_label(state),
+                    None if host.is_deferred(&schema.id) => "deferred",
+                    None => "idle",
+                };
+                ModuleStatus {
+                    id: schema.id.clone(),
+                    name: schema.name.clone(),
+                    state: state.to_string(),
+                    disabled: patch_bay.is_module_disabled(&schema.id),
+                    bypassed: patch_bay.is_module_bypassed(&schema.id),
+                    muted: patch_bay.is_module_muted(&schema.id),
+                }
+            })
+            .collect();
+        modules.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let metrics = host.routing_metrics();
+        let traffic = metrics.patch_traffic();
+        let patches = patch_bay
+            .get_patches()
+            .iter()
+            .map(|patch| PatchStatus {
+                id: patch.id.clone(),
+                source_module: patch.source_module.clone(),
+                source_port: patch.source_port.clone(),
+                sink_module: patch.sink_module.clone(),
+                sink_port: patch.sink_port.clone(),
+                messages_per_sec: traffic
+                    .get(&patch.id)
+                    .map_or(0.0, |traffic| traffic.messages_per_sec),
+            })
+            .collect();
+
+        Self {
+            modules,
+            patches,
+            routing: metrics.snapshot(),
+            ..Self::default()
+        }
+    }
+}
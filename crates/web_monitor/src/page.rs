@@ -0,0 +1,224 @@
+//! HTML and SVG rendering of a [`Dashboard`]
+
+use crate::Dashboard;
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+/// Seconds between the page's own reloads
+const REFRESH_SECS: u32 = 3;
+const GRAPH_SIZE: f64 = 600.0;
+const NODE_RADIUS: f64 = 6.0;
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// The patched modules on a circle with an arrow per patch, thicker the
+/// busier it is
+pub fn render_patch_svg(dashboard: &Dashboard) -> String {
+    let nodes: Vec<&str> = dashboard
+        .patches
+        .iter()
+        .flat_map(|p| [p.source_module.as_str(), p.sink_module.as_str()])
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let center = GRAPH_SIZE / 2.0;
+    // Leaves room for the labels outside the circle
+    let radius = GRAPH_SIZE * 0.32;
+    let position = |module: &str| {
+        let index = nodes.iter().position(|n| *n == module).unwrap_or(0);
+        let angle = std::f64::consts::TAU * index as f64 / nodes.len().max(1) as f64
+            - std::f64::consts::FRAC_PI_2;
+        (
+            center + radius * angle.cos(),
+            center + radius * angle.sin(),
+            angle,
+        )
+    };
+
+    let mut svg = String::new();
+    let _ = write!(
+        svg,
+        r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {size} {size}" font-family="sans-serif" font-size="12"><defs><marker id="arrow" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="6" markerHeight="6" orient="auto-start-reverse"><path d="M0,0 L10,5 L0,10 z" fill="#8ab"/></marker></defs>"##,
+        size = GRAPH_SIZE
+    );
+    if nodes.is_empty() {
+        let _ = write!(
+            svg,
+            r##"<text x="{center}" y="{center}" text-anchor="middle" fill="#888">No patches</text>"##
+        );
+    }
+    for patch in &dashboard.patches {
+        let (x1, y1, _) = position(&patch.source_module);
+        let (x2, y2, _) = position(&patch.sink_module);
+        // Stop at the edge of the sink's dot so the arrowhead shows
+        let (dx, dy) = (x2 - x1, y2 - y1);
+        let length = (dx * dx + dy * dy).sqrt().max(1.0);
+        let (x2, y2) = (
+            x2 - dx / length * NODE_RADIUS,
+            y2 - dy / length * NODE_RADIUS,
+        );
+        let width = 1.0 + patch.messages_per_sec.max(0.0).ln_1p().min(5.0);
+        let _ = write!(
+            svg,
+            r##"<line x1="{x1:.1}" y1="{y1:.1}" x2="{x2:.1}" y2="{y2:.1}" stroke="#8ab" stroke-width="{width:.1}" marker-end="url(#arrow)"><title>{} → {} ({:.1}/s)</title></line>"##,
+            escape(&format!("{}.{}", patch.source_module, patch.source_port)),
+            escape(&format!("{}.{}", patch.sink_module, patch.sink_port)),
+            patch.messages_per_sec,
+        );
+    }
+    for node in &nodes {
+        let (x, y, angle) = position(node);
+        let anchor = if angle.cos() < -0.1 {
+            "end"
+        } else if angle.cos() > 0.1 {
+            "start"
+        } else {
+            "middle"
+        };
+        let (lx, ly) = (x + angle.cos() * 12.0, y + angle.sin() * 12.0 + 4.0);
+        let _ = write!(
+            svg,
+            r##"<circle cx="{x:.1}" cy="{y:.1}" r="{NODE_RADIUS}" fill="#dde"/><text x="{lx:.1}" y="{ly:.1}" text-anchor="{anchor}" fill="#dde">{}</text>"##,
+            escape(node)
+        );
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+/// The whole dashboard page
+pub fn render_html(dashboard: &Dashboard) -> String {
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        r#"<!DOCTYPE html><html><head><meta charset="utf-8"><meta name="viewport" content="width=device-width, initial-scale=1"><meta http-equiv="refresh" content="{REFRESH_SECS}"><title>Magnolia</title><style>
+body {{ background: #111; color: #dde; font-family: sans-serif; margin: 1em; }}
+h2 {{ font-size: 1.1em; margin-top: 1.5em; }}
+table {{ border-collapse: collapse; width: 100%; }}
+td, th {{ text-align: left; padding: 0.2em 0.5em; border-bottom: 1px solid #333; }}
+.failed {{ color: #e66; }} .running {{ color: #6c6; }} .flag {{ color: #db6; }}
+meter {{ width: 60%; }} svg {{ max-width: 100%; height: auto; }}
+</style></head><body><h1>Magnolia</h1><p>Up {}s · {} signals routed, {} delivered, {} dropped</p>"#,
+        dashboard.uptime_secs,
+        dashboard.routing.received,
+        dashboard.routing.delivered,
+        dashboard.routing.invalid_dropped
+            + dashboard.routing.unroutable
+            + dashboard.routing.send_failures
+            + dashboard.routing.replaceable_drops,
+    );
+
+    html.push_str("<h2>Modules</h2><table><tr><th>Module</th><th>State</th><th></th></tr>");
+    for module in &dashboard.modules {
+        let flags: Vec<&str> = [
+            (module.disabled, "disabled"),
+            (module.bypassed, "bypassed"),
+            (module.muted, "muted"),
+        ]
+        .into_iter()
+        .filter_map(|(on, flag)| on.then_some(flag))
+        .collect();
+        let _ = write!(
+            html,
+            r#"<tr><td>{} <small>{}</small></td><td class="{state}">{state}</td><td class="flag">{}</td></tr>"#,
+            escape(&module.name),
+            escape(&module.id),
+            flags.join(", "),
+            state = escape(&module.state),
+        );
+    }
+    html.push_str("</table>");
+
+    html.push_str("<h2>Patches</h2>");
+    html.push_str(&render_patch_svg(dashboard));
+
+    if !dashboard.levels.is_empty() {
+        html.push_str("<h2>Levels</h2><table>");
+        for (name, level) in &dashboard.levels {
+            let _ = write!(
+                html,
+                r#"<tr><td>{}</td><td><meter min="0" max="1" value="{level:.3}"></meter> {level:.2}</td></tr>"#,
+                escape(name),
+            );
+        }
+        html.push_str("</table>");
+    }
+
+    html.push_str("<h2>Transcript</h2>");
+    if dashboard.transcript.is_empty() {
+        html.push_str("<p><small>Nothing yet</small></p>");
+    }
+    for line in &dashboard.transcript {
+        let _ = write!(html, "<p>{}</p>", escape(line));
+    }
+
+    // A live region, so a screen reader on the page reads what the daemon's
+    // own window can't tell it
+    if !dashboard.announcements.is_empty() {
+        html.push_str(r#"<h2>Announcements</h2><div role="log" aria-live="polite">"#);
+        for line in &dashboard.announcements {
+            let _ = write!(html, "<p>{}</p>", escape(line));
+        }
+        html.push_str("</div>");
+    }
+    html.push_str("</body></html>");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ModuleStatus, PatchStatus};
+
+    #[test]
+    fn renders_escaped_modules_patches_and_transcript() {
+        let dashboard = Dashboard {
+            modules: vec![ModuleStatus {
+                id: "stt".into(),
+                name: "Speech <STT>".into(),
+                state: "failed".into(),
+                muted: true,
+                ..ModuleStatus::default()
+            }],
+            patches: vec![PatchStatus {
+                id: "p1".into(),
+                source_module: "audio_input".into(),
+                source_port: "audio_out".into(),
+                sink_module: "stt".into(),
+                sink_port: "audio_in".into(),
+                messages_per_sec: 50.0,
+            }],
+            levels: [("audio_output".to_string(), 0.5)].into(),
+            transcript: vec!["hello & welcome".into()],
+            announcements: vec!["Add tile, clock".into()],
+            ..Dashboard::default()
+        };
+        let html = render_html(&dashboard);
+        assert!(html.contains("Speech &lt;STT&gt;"));
+        assert!(html.contains(r#"<td class="failed">failed</td>"#));
+        assert!(html.contains(">muted<"));
+        assert!(html.contains(r#"value="0.500""#));
+        assert!(html.contains("<p>hello &amp; welcome</p>"));
+        assert!(html.contains(r#"aria-live="polite"><p>Add tile, clock</p>"#));
+        assert!(!render_html(&Dashboard::default()).contains("aria-live"));
+
+        let svg = render_patch_svg(&dashboard);
+        assert_eq!(svg.matches("<line").count(), 1);
+        assert_eq!(svg.matches("<circle").count(), 2);
+        assert!(svg.contains("audio_input.audio_out → stt.audio_in"));
+        assert!(render_patch_svg(&Dashboard::default()).contains("No patches"));
+    }
+}
@@ -0,0 +1,391 @@
+//! Minimal blocking HTTP/1.1 server: GET only, one request per connection,
+//! optionally over TLS and behind `[access]` tokens
+
+use crate::{render_html, render_patch_svg, Dashboard};
+use magnolia_core::{AccessConfig, AccessError, Scope, TlsConfig};
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Requests with longer headers are refused
+const MAX_REQUEST: usize = 8 << 10;
+/// How long a client gets to send its request or take the response
+const IO_TIMEOUT: Duration = Duration::from_secs(2);
+/// How often the accept loop checks for shutdown
+const ACCEPT_POLL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl Response {
+    fn new(status: u16, content_type: &'static str, body: String) -> Self {
+        Self {
+            status,
+            content_type,
+            body,
+        }
+    }
+
+    fn text(status: u16, body: &str) -> Self {
+        Self::new(status, "text/plain", format!("{}\n", body))
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            401 => "Unauthorized",
+            403 => "Forbidden",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            _ => "Internal Server Error",
+        }
+    }
+
+    fn write_to(&self, stream: &mut impl Write) -> io::Result<()> {
+        write!(
+            stream,
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n",
+            self.status,
+            self.reason(),
+            self.content_type,
+            self.body.len()
+        )?;
+        if self.status == 401 {
+            stream.write_all(b"WWW-Authenticate: Bearer\r\n")?;
+        }
+        stream.write_all(b"\r\n")?;
+        stream.write_all(self.body.as_bytes())?;
+        stream.flush()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Request<'a> {
+    pub method: &'a str,
+    /// Without the query
+    pub path: &'a str,
+    /// From `Authorization: Bearer`, or else a `token` query parameter so a
+    /// bookmarked link works from a phone
+    pub token: Option<&'a str>,
+}
+
+pub(crate) fn parse_request(head: &str) -> Option<Request<'_>> {
+    let mut lines = head.lines();
+    let mut parts = lines.next()?.split_whitespace();
+    let method = parts.next()?;
+    let target = parts.next()?;
+    parts
+        .next()
+        .filter(|version| version.starts_with("HTTP/"))?;
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+    let header_token = lines
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("authorization"))
+        .and_then(|(_, value)| value.trim().strip_prefix("Bearer "))
+        .map(str::trim);
+    let query_token = query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("token="))
+        .filter(|token| !token.is_empty());
+    Some(Request {
+        method,
+        path,
+        token: header_token.or(query_token),
+    })
+}
+
+pub(crate) fn respond(request: &Request, dashboard: &Dashboard, access: &AccessConfig) -> Response {
+    // Everything served here is read-only
+    match access.authorize(request.token, Scope::Read) {
+        Ok(_) => {}
+        Err(e @ (AccessError::Missing | AccessError::Unknown)) => {
+            return Response::text(401, &e.to_string())
+        }
+        Err(e @ AccessError::Forbidden { .. }) => return Response::text(403, &e.to_string()),
+    }
+    if request.method != "GET" {
+        return Response::text(405, "read-only");
+    }
+    match request.path {
+        "/" | "/index.html" => {
+            Response::new(200, "text/html; charset=utf-8", render_html(dashboard))
+        }
+        "/patches.svg" => Response::new(200, "image/svg+xml", render_patch_svg(dashboard)),
+        "/api/status" => match serde_json::to_string(dashboard) {
+            Ok(json) => Response::new(200, "application/json", json),
+            Err(e) => Response::text(500, &e.to_string()),
+        },
+        _ => Response::text(404, "not found"),
+    }
+}
+
+/// Reads up to the end of the headers; the body of a GET is ignored
+fn read_head(stream: &mut impl Read) -> io::Result<String> {
+    let mut head = Vec::new();
+    let mut chunk = [0u8; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+        if head.len() > MAX_REQUEST {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "request too large",
+            ));
+        }
+        let n = stream.read(&mut chunk)?;
+        if n == 0 {
+            break;
+        }
+        head.extend_from_slice(&chunk[..n]);
+    }
+    Ok(String::from_utf8_lossy(&head).into_owned())
+}
+
+fn invalid(e: impl std::fmt::Display) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, e.to_string())
+}
+
+/// Server config from the PEM certificate chain and key in `tls`
+fn tls_config(tls: &TlsConfig) -> io::Result<Arc<rustls::ServerConfig>> {
+    use rustls::pki_types::pem::PemObject;
+    use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+
+    let certs = CertificateDer::pem_file_iter(&tls.cert)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| invalid(format!("{}: {}", tls.cert.display(), e)))?;
+    let key = PrivateKeyDer::from_pem_file(&tls.key)
+        .map_err(|e| invalid(format!("{}: {}", tls.key.display(), e)))?;
+    let config = rustls::ServerConfig::builder_with_provider(
+        rustls::crypto::ring::default_provider().into(),
+    )
+    .with_protocol_versions(&[&rustls::version::TLS12, &rustls::version::TLS13])
+    .map_err(invalid)?
+    .with_no_client_auth()
+    .with_single_cert(certs, key)
+    .map_err(invalid)?;
+    Ok(Arc::new(config))
+}
+
+struct Shared {
+    dashboard: Mutex<Dashboard>,
+    access: AccessConfig,
+    tls: Option<Arc<rustls::ServerConfig>>,
+}
+
+fn exchange(stream: &mut (impl Read + Write), shared: &Shared) -> io::Result<()> {
+    let head = read_head(stream)?;
+    let response = match parse_request(&head) {
+        Some(request) => match shared.dashboard.lock() {
+            Ok(dashboard) => respond(&request, &dashboard, &shared.access),
+            Err(_) => Response::text(500, "unavailable"),
+        },
+        None => Response::text(400, "bad request"),
+    };
+    response.write_to(stream)
+}
+
+fn serve(mut stream: TcpStream, shared: &Shared) -> io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+    match &shared.tls {
+        Some(config) => {
+            let connection = rustls::ServerConnection::new(config.clone()).map_err(invalid)?;
+            let mut stream = rustls::StreamOwned::new(connection, stream);
+            exchange(&mut stream, shared)?;
+            stream.conn.send_close_notify();
+            stream.flush()
+        }
+        None => exchange(&mut stream, shared),
+    }
+}
+
+/// The dashboard's HTTP thread; stops when dropped
+pub struct WebMonitor {
+    addr: SocketAddr,
+    shared: Arc<Shared>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl WebMonitor {
+    /// Listen on `bind` (`127.0.0.1:8420`; port 0 picks a free one), with
+    /// the tokens and TLS certificate from `access`
+    pub fn start(bind: &str, access: AccessConfig) -> io::Result<Self> {
+        let tls = access.tls.as_ref().map(tls_config).transpose()?;
+        let listener = TcpListener::bind(bind)?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+        if access.is_open() && !addr.ip().is_loopback() {
+            log::warn!(
+                "Web monitor on {} is open to anyone who can reach it; add [[access.tokens]]",
+                addr
+            );
+        }
+        let shared = Arc::new(Shared {
+            dashboard: Mutex::new(Dashboard::default()),
+            access,
+            tls,
+        });
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = {
+            let shared = shared.clone();
+            let stop = stop.clone();
+            thread::Builder::new()
+                .name("web-monitor".to_string())
+                .spawn(move || {
+                    while !stop.load(Ordering::Relaxed) {
+                        match listener.accept() {
+                            Ok((stream, peer)) => {
+                                if let Err(e) = serve(stream, &shared) {
+                                    log::debug!("Web monitor: {}: {}", peer, e);
+                                }
+                            }
+                            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                                thread::sleep(ACCEPT_POLL)
+                            }
+                            Err(e) => {
+                                log::warn!("Web monitor: accept failed: {}", e);
+                                thread::sleep(ACCEPT_POLL);
+                            }
+                        }
+                    }
+                })?
+        };
+        Ok(Self {
+            addr,
+            shared,
+            stop,
+            thread: Some(thread),
+        })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// `https` when serving TLS
+    pub fn url(&self) -> String {
+        let scheme = if self.shared.tls.is_some() {
+            "https"
+        } else {
+            "http"
+        };
+        format!("{}://{}", scheme, self.addr)
+    }
+
+    /// Replace what's served
+    pub fn publish(&self, dashboard: Dashboard) {
+        if let Ok(mut current) = self.shared.dashboard.lock() {
+            *current = dashboard;
+        }
+    }
+}
+
+impl Drop for WebMonitor {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use magnolia_core::ApiToken;
+
+    fn get(addr: SocketAddr, request: &str) -> String {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn parses_requests() {
+        let request =
+            parse_request("GET /api/status?x=1&token=abc HTTP/1.1\r\nHost: a\r\n\r\n").unwrap();
+        assert_eq!(
+            (request.method, request.path, request.token),
+            ("GET", "/api/status", Some("abc"))
+        );
+        let request = parse_request("GET / HTTP/1.1\r\nauthorization: Bearer xyz\r\n\r\n").unwrap();
+        assert_eq!(request.token, Some("xyz"));
+        assert_eq!(parse_request("GET /\r\n"), None);
+        assert_eq!(parse_request(""), None);
+
+        let open = AccessConfig::default();
+        let dashboard = Dashboard::default();
+        let post = parse_request("POST / HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!(respond(&post, &dashboard, &open).status, 405);
+        let missing = parse_request("GET /nope HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!(respond(&missing, &dashboard, &open).status, 404);
+    }
+
+    #[test]
+    fn serves_the_published_dashboard() {
+        let monitor = WebMonitor::start("127.0.0.1:0", AccessConfig::default()).unwrap();
+        assert!(monitor.url().starts_with("http://127.0.0.1:"));
+        monitor.publish(Dashboard {
+            uptime_secs: 42,
+            ..Dashboard::default()
+        });
+
+        let response = get(monitor.addr(), "GET /api/status HTTP/1.1\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("Content-Type: application/json"));
+        let body = response.split("\r\n\r\n").nth(1).unwrap();
+        let json: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(json["uptime_secs"], 42);
+
+        let response = get(monitor.addr(), "GET / HTTP/1.1\r\n\r\n");
+        assert!(response.contains("Up 42s"));
+        assert!(get(monitor.addr(), "garbage\r\n\r\n").starts_with("HTTP/1.1 400"));
+    }
+
+    #[test]
+    fn tokens_gate_every_page() {
+        let access = AccessConfig {
+            tokens: vec![ApiToken {
+                name: "phone".to_string(),
+                token: "s3cret".to_string(),
+                scope: Scope::Read,
+            }],
+            tls: None,
+        };
+        let monitor = WebMonitor::start("127.0.0.1:0", access).unwrap();
+        let response = get(monitor.addr(), "GET / HTTP/1.1\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 401"));
+        assert!(response.contains("WWW-Authenticate: Bearer"));
+        assert!(
+            get(monitor.addr(), "GET /?token=guess HTTP/1.1\r\n\r\n").starts_with("HTTP/1.1 401")
+        );
+        assert!(
+            get(monitor.addr(), "GET /?token=s3cret HTTP/1.1\r\n\r\n").starts_with("HTTP/1.1 200")
+        );
+        assert!(get(
+            monitor.addr(),
+            "GET /patches.svg HTTP/1.1\r\nAuthorization: Bearer s3cret\r\n\r\n"
+        )
+        .starts_with("HTTP/1.1 200"));
+
+        let missing_cert = AccessConfig {
+            tls: Some(TlsConfig {
+                cert: "/nonexistent/cert.pem".into(),
+                key: "/nonexistent/key.pem".into(),
+            }),
+            ..AccessConfig::default()
+        };
+        assert!(WebMonitor::start("127.0.0.1:0", missing_cert).is_err());
+    }
+}
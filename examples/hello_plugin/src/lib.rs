@@ -25,7 +25,7 @@ impl HelloPlugin {
 #[no_mangle]
 pub unsafe extern "C" fn magnolia_plugin_manifest() -> PluginManifest {
     PluginManifest {
-        abi_version: 4,
+        abi_version: ABI_VERSION,
         name: "Hello Plugin\0".as_ptr() as *const i8,
         version: "0.1.0\0".as_ptr() as *const i8,
         description: "Example plugin that demonstrates the plugin ABI\0".as_ptr() as *const i8,
@@ -50,6 +50,10 @@ static VTABLE: ModuleRuntimeVTable = ModuleRuntimeVTable {
     consume_signal: hello_consume_signal,
     apply_settings: hello_apply_settings,
     destroy: hello_destroy,
+    free_buffer: hello_free_buffer,
+    attach_gpu: hello_attach_gpu,
+    // Its settings have never changed shape
+    migrate_settings: None,
 };
 
 #[no_mangle]
@@ -57,6 +61,18 @@ pub unsafe extern "C" fn magnolia_plugin_get_vtable() -> *const ModuleRuntimeVTa
     &VTABLE as *const _
 }
 
+// Docs for the host's docs pane (optional)
+#[no_mangle]
+pub unsafe extern "C" fn magnolia_plugin_get_docs() -> *const i8 {
+    concat!(
+        r#"{"overview": "Counts its polls and says hello on every tenth one. "#,
+        r#"Patch its output into a ticker or captions tile to see the plugin ABI at work.", "#,
+        r#""ports": {"out": "Text: Hello from plugin! with the poll count"}}"#,
+        "\0"
+    )
+    .as_ptr() as *const i8
+}
+
 // VTable function implementations
 
 unsafe extern "C" fn hello_get_id(instance: *const c_void) -> *const i8 {
@@ -119,3 +135,19 @@ unsafe extern "C" fn hello_apply_settings(_instance: *mut c_void, _json: *const
 unsafe extern "C" fn hello_destroy(instance: *mut c_void) {
     let _ = Box::from_raw(instance as *mut HelloPlugin);
 }
+
+unsafe extern "C" fn hello_free_buffer(_instance: *mut c_void, buffer: *mut SignalBuffer) {
+    // Reclaim the CString leaked in hello_poll_signal
+    if buffer.is_null() || (*buffer).signal_type != SignalType::Text as u32 {
+        return;
+    }
+    let ptr = (*buffer).value.ptr;
+    if !ptr.is_null() {
+        drop(CString::from_raw(ptr as *mut i8));
+        (*buffer).value.ptr = std::ptr::null_mut();
+    }
+}
+
+unsafe extern "C" fn hello_attach_gpu(_instance: *mut c_void, _api: *const HostGpuApi) {
+    // Text-only plugin: no GPU resources needed
+}